@@ -0,0 +1,82 @@
+//! Benchmarks of the hottest endpoints against a seeded database
+//!
+//! ```bash
+//! DATABASE_URL=postgres://... cargo bench --features bench -- --save-baseline main
+//! DATABASE_URL=postgres://... cargo bench --features bench -- --baseline main
+//! ```
+//!
+//! The first run seeds the database (see `rust_server::bench`); the second
+//! compares against the saved baseline and flags regressions. Requests go
+//! through the full middleware stack of the in-process application, so
+//! blocking work on the request path shows up in the numbers.
+
+use actix_web::test;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rust_server::{
+    bench::{BenchFixture, BENCH_ADMIN_EMAIL, BENCH_ORGANIZATIONS, BENCH_PASSWORD},
+    domain::TokenManager,
+    server,
+};
+use serde_json::json;
+
+fn hot_endpoints(c: &mut Criterion) {
+    let runtime = actix_rt::Runtime::new().expect("Failed to start the runtime");
+    let fixture = runtime
+        .block_on(BenchFixture::seed())
+        .expect("Failed to seed the benchmark database");
+    let app = runtime.block_on(test::init_service(server::app(&fixture.config)));
+    let bearer = format!("Bearer {}", fixture.token);
+
+    let mut auth = c.benchmark_group("auth");
+    auth.bench_function("verify_token", |b| {
+        b.iter(|| TokenManager::validate_token(&fixture.token, &fixture.config).unwrap())
+    });
+    auth.bench_function("authenticated_request", |b| {
+        let uri = format!("/v1/organizations/{}", fixture.admin.org_id);
+        b.iter(|| {
+            let request = test::TestRequest::get()
+                .uri(&uri)
+                .insert_header(("Authorization", bearer.as_str()))
+                .to_request();
+            let response = runtime.block_on(test::call_service(&app, request));
+            assert!(response.status().is_success(), "GET {} answered {}", uri, response.status());
+        })
+    });
+    // Argon2 is slow on purpose, a handful of samples is plenty
+    auth.sample_size(10).bench_function("login", |b| {
+        b.iter(|| {
+            let request = test::TestRequest::post()
+                .uri("/v1/auth/login")
+                .set_json(json!({ "email": BENCH_ADMIN_EMAIL, "password": BENCH_PASSWORD }))
+                .to_request();
+            let response = runtime.block_on(test::call_service(&app, request));
+            assert!(response.status().is_success(), "login answered {}", response.status());
+        })
+    });
+    auth.finish();
+
+    let mut pagination = c.benchmark_group("organizations/list");
+    for per_page in [20, 100] {
+        // The handler reads `page` as a row offset
+        let last = BENCH_ORGANIZATIONS - per_page;
+        for (position, offset) in [("first", 0), ("middle", last / 2), ("last", last)] {
+            let uri = format!("/v1/organizations?page={}&per_page={}", offset, per_page);
+            pagination.bench_with_input(BenchmarkId::new(position, per_page), &uri, |b, uri| {
+                b.iter(|| {
+                    let request = test::TestRequest::get()
+                        .uri(uri)
+                        .insert_header(("Authorization", bearer.as_str()))
+                        .to_request();
+                    let response = runtime.block_on(test::call_service(&app, request));
+                    assert!(response.status().is_success(), "GET {} answered {}", uri, response.status());
+                })
+            });
+        }
+    }
+    pagination.finish();
+
+    // Telemetry ingest joins these once the ingest endpoint exists
+}
+
+criterion_group!(benches, hot_endpoints);
+criterion_main!(benches);
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Something a user did
+ */
+export type ActivityResponse = { id: string, 
+/**
+ * `block_signed_off`, `document_uploaded`, `import_uploaded`,
+ * `tender_created`, `bid_captured`, `parcel_awarded` or
+ * `certification_recorded`
+ */
+action: string, 
+/**
+ * What the action was taken on, e.g. `block` or `document`
+ */
+subject_type: string, subject_id: string, summary: string, data: Record<string, unknown> | null, created_at: string, };
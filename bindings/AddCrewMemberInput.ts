@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for adding a member to a crew
+ */
+export type AddCrewMemberInput = { user_id: string, };
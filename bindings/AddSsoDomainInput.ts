@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Role } from "./Role";
+
+/**
+ * Input for claiming an email domain for an organization's single sign-on
+ */
+export type AddSsoDomainInput = { 
+/**
+ * Email domain whose users are provisioned into the organization, e.g. `pinecorp.com`
+ */
+domain: string, 
+/**
+ * Role of provisioned users; Operator when omitted
+ */
+role: Role | null, };
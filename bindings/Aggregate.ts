@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Aggregation of a column over each group
+ */
+export type Aggregate = "count" | "sum" | "avg" | "min" | "max";
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockSignoffResponse } from "./BlockSignoffResponse";
+
+/**
+ * Where a block stands in the approval chain
+ */
+export type ApprovalStatusResponse = { block_id: string, 
+/**
+ * Whether every step is signed, so the block can be activated
+ */
+approved: boolean, 
+/**
+ * Step to be signed next
+ */
+next_step: string | null, missing_steps: Array<string>, 
+/**
+ * Hash of the package document signed so far
+ */
+document_hash: string | null, signoffs: Array<BlockSignoffResponse>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * A single archived record
+ *
+ * Dataset rows are flattened into a stable id, the timestamp used for
+ * retention and a JSON payload holding the remaining columns.
+ */
+export type ArchiveRecord = { id: string, recorded_at: string, payload: JsonValue, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArchiveRecord } from "./ArchiveRecord";
+import type { ArchiveResponse } from "./ArchiveResponse";
+
+/**
+ * Records read back from an archive
+ */
+export type ArchiveRecordsResponse = { archive: ArchiveResponse, records: Array<ArchiveRecord>, };
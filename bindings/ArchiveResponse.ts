@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Archive stub response
+ */
+export type ArchiveResponse = { id: string, dataset: string, record_count: number, byte_size: number, range_start: string, range_end: string, rehydrated_at: string | null, created_at: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to set who a user reports to
+ */
+export type AssignSupervisorInput = { 
+/**
+ * An active manager or admin of the organization, `null` for no one
+ */
+supervisor_id: string | null, };
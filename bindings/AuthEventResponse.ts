@@ -0,0 +1,37 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A login, token refresh, password change or logout
+ */
+export type AuthEventResponse = { id: string, 
+/**
+ * `login_succeeded`, `login_failed`, `token_refreshed`, `password_changed`,
+ * `email_changed`, `logged_out`, `passkey_added`, `passkey_removed`,
+ * `sessions_revoked`, `deactivated` or `reactivated`
+ */
+event: string, 
+/**
+ * How the user authenticated: `password`, `magic_link`, `passkey`, `password_reset` or
+ * `sso:<provider>`
+ */
+method: string | null, 
+/**
+ * Email a failed login was attempted with
+ */
+email: string | null, 
+/**
+ * Why the attempt failed
+ */
+reason: string | null, ip_address: string | null, user_agent: string | null, 
+/**
+ * ISO 3166 code of the country the client was in, when known
+ */
+country: string | null, 
+/**
+ * Network the client connected from, e.g. `AS13335`, when known
+ */
+asn: string | null, 
+/**
+ * Device the client logged in from, when it named one
+ */
+device_id: string | null, created_at: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UserResponse } from "./UserResponse";
+
+/**
+ * Authentication response payload
+ */
+export type AuthResponse = { access_token: string, refresh_token: string, user: UserResponse, };
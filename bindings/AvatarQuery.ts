@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters of a signed avatar link
+ */
+export type AvatarQuery = { 
+/**
+ * Upload the link was made for, to tell caches apart
+ */
+v: number | null, 
+/**
+ * Unix time the link stops working at
+ */
+expires: number, signature: string, };
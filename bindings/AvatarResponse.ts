@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Signed links to an avatar, which need no bearer token
+ */
+export type AvatarResponse = { 
+/**
+ * 64×64 PNG
+ */
+small_url: string, 
+/**
+ * 256×256 PNG
+ */
+large_url: string, 
+/**
+ * When the links stop working
+ */
+expires_at: string, };
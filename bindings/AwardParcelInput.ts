@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for awarding a parcel to one of its bids
+ */
+export type AwardParcelInput = { bid_id: string, };
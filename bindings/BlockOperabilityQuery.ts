@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for a block's operability
+ */
+export type BlockOperabilityQuery = { 
+/**
+ * Daily precipitation in millimetres, most recent day first, as
+ * comma separated totals
+ */
+precipitation_mm: string | null, };
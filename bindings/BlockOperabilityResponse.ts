@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OperabilityStatus } from "./OperabilityStatus";
+import type { SoilSensitivity } from "./SoilSensitivity";
+
+/**
+ * Whether ground-based operations may run on a block after recent rain
+ */
+export type BlockOperabilityResponse = { block_id: string, status: OperabilityStatus, sensitivity: SoilSensitivity, 
+/**
+ * Antecedent precipitation index, in millimetres
+ */
+antecedent_mm: number, 
+/**
+ * Index at which operations stop on the block's soil
+ */
+shutdown_mm: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A sign-off on a block package
+ */
+export type BlockSignoffResponse = { id: string, step: string, signer_id: string | null, 
+/**
+ * Name of the signer when they signed
+ */
+signer_name: string, document_hash: string, signed_at: string, };
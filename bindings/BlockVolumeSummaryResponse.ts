@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SpeciesVolumeResponse } from "./SpeciesVolumeResponse";
+
+/**
+ * A block's stand volumes rolled up
+ */
+export type BlockVolumeSummaryResponse = { block_id: string, stand_count: number, 
+/**
+ * Area of the block's stands, which may differ from the block's net
+ * harvest area
+ */
+area_ha: number, net_merchantable_volume_m3: number, 
+/**
+ * Net merchantable volume per hectare of stand area
+ */
+volume_per_ha: number, 
+/**
+ * The block's planned volume, for comparison
+ */
+planned_volume_m3: number, 
+/**
+ * Volume by species, largest first
+ */
+species: Array<SpeciesVolumeResponse>, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A GeoJSON polygon in WGS 84 longitude and latitude
+ *
+ * The first ring is the outer boundary and any others are holes in it.
+ * Each ring is closed, its last position repeating its first.
+ */
+export type Boundary = { "type": "Polygon", 
+/**
+ * Rings of `[longitude, latitude]` positions
+ */
+coordinates: Array<Array<[number, number]>>, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SaveStandInput } from "./SaveStandInput";
+
+/**
+ * Stands sent by an inventory system, matched to a block's stands by
+ * stand number
+ */
+export type BulkUpsertStandsInput = { stands: Array<SaveStandInput>, };
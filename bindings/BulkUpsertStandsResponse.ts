@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StandResponse } from "./StandResponse";
+
+/**
+ * Outcome of a bulk upsert
+ */
+export type BulkUpsertStandsResponse = { 
+/**
+ * Stands that were new to the block
+ */
+created: number, 
+/**
+ * Stands that replaced one with the same number
+ */
+updated: number, 
+/**
+ * The saved stands, by stand number
+ */
+stands: Array<StandResponse>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for capturing a sealed bid received for a parcel
+ */
+export type CaptureBidInput = { parcel_id: string, bidder_name: string, bidder_email: string | null, price_per_m3: number, 
+/**
+ * When the bid reached the seller, now when unset
+ */
+received_at: string | null, };
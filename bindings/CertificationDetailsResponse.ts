@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CertificationResponse } from "./CertificationResponse";
+import type { DocumentResponse } from "./DocumentResponse";
+
+/**
+ * A certification with the documents attached to it
+ */
+export type CertificationDetailsResponse = { certification: CertificationResponse, documents: Array<DocumentResponse>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Certification response
+ */
+export type CertificationResponse = { id: string, user_id: string, kind: string, 
+/**
+ * The name given, or else the kind, as shown to users
+ */
+title: string, name: string | null, certificate_number: string | null, issuer: string | null, issued_on: string, expires_on: string | null, 
+/**
+ * When supervisors were notified of the coming expiry
+ */
+expiry_notified_at: string | null, created_by: string | null, created_at: string, updated_at: string, };
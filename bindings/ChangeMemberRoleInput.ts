@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Role } from "./Role";
+
+/**
+ * Input for changing the role of a member
+ */
+export type ChangeMemberRoleInput = { role: Role, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A category of a block status checklist
+ */
+export type ChecklistItem = { category: string, 
+/**
+ * Whether a document of the category is on file
+ */
+present: boolean, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for starting a Checkout session
+ */
+export type CheckoutInput = { 
+/**
+ * Paid plan to subscribe to, e.g. `team`
+ */
+plan: string, };
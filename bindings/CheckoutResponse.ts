@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A Checkout session to send the customer to
+ */
+export type CheckoutResponse = { 
+/**
+ * Stripe Checkout session ID
+ */
+id: string, 
+/**
+ * Stripe-hosted payment page
+ */
+url: string, };
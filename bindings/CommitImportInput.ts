@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for committing an import
+ */
+export type CommitImportInput = { 
+/**
+ * Final mapping of headers to fields; the stored mapping is used when
+ * unset
+ */
+mapping: { [key in string]?: string } | null, };
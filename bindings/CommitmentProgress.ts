@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How deliveries on a contract compare with its commitment
+ */
+export type CommitmentProgress = { 
+/**
+ * Day the progress is measured on
+ */
+as_of: string, 
+/**
+ * Share of the period elapsed, from 0 to 1
+ */
+elapsed: number, delivered_m3: number, 
+/**
+ * Volume due by now if deliveries were spread evenly over the period
+ */
+expected_m3: number, 
+/**
+ * Volume delivered by the end of the period at the rate so far
+ */
+projected_m3: number, 
+/**
+ * Committed volume the projection falls short of
+ */
+shortfall_m3: number, 
+/**
+ * Whether the projection falls short by more than the tolerance
+ */
+at_risk: boolean, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for commitment progress
+ */
+export type CommitmentsQuery = { 
+/**
+ * Day to measure progress on, today when unset
+ */
+as_of: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Email change confirmation payload
+ */
+export type ConfirmEmailChangeRequest = { 
+/**
+ * Token from the link mailed to the new address
+ */
+token: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommitmentProgress } from "./CommitmentProgress";
+import type { SupplyContractResponse } from "./SupplyContractResponse";
+
+/**
+ * A supply contract with how deliveries compare with its commitment
+ */
+export type ContractCommitmentResponse = { contract: SupplyContractResponse, progress: CommitmentProgress, };
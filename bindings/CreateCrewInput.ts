@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for creating a crew
+ */
+export type CreateCrewInput = { 
+/**
+ * Unique within the organization
+ */
+name: string, 
+/**
+ * User leading the crew, who need not be a member
+ */
+supervisor_id: string | null, 
+/**
+ * Machine ids the crew works with unless a job says otherwise, as the
+ * machines report them in telemetry
+ */
+default_equipment: Array<string>, 
+/**
+ * Users working in the crew
+ */
+member_ids: Array<string>, };
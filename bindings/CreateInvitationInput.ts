@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Role } from "./Role";
+
+/**
+ * Input for inviting someone into the caller's organization
+ */
+export type CreateInvitationInput = { email: string, 
+/**
+ * Role the invitee joins with; only admins invite admins
+ */
+role: Role, 
+/**
+ * Days the invitation can be accepted for, 7 when unset, up to 30
+ */
+expires_in_days?: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for placing a legal hold
+ */
+export type CreateLegalHoldInput = { 
+/**
+ * Entity type of the held record, e.g. `users`
+ */
+entity_type: string, entity_id: string, reason: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for issuing a SCIM provisioning token
+ */
+export type CreateScimTokenInput = { 
+/**
+ * What the token is for, e.g. the identity provider using it
+ */
+name: string, };
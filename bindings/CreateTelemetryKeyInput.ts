@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for issuing a telemetry API key
+ */
+export type CreateTelemetryKeyInput = { 
+/**
+ * What the key is for, e.g. the machine or gateway using it
+ */
+name: string, };
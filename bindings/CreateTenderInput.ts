@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TenderParcelInput } from "./TenderParcelInput";
+
+/**
+ * Input for creating a tender as a draft
+ */
+export type CreateTenderInput = { title: string, description: string | null, 
+/**
+ * Bids must be received by this time
+ */
+bid_deadline: string, parcels: Array<TenderParcelInput>, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AuthenticatorSelection } from "./AuthenticatorSelection";
+import type { CredentialDescriptor } from "./CredentialDescriptor";
+import type { CredentialParameters } from "./CredentialParameters";
+import type { RelyingParty } from "./RelyingParty";
+import type { UserEntity } from "./UserEntity";
+
+/**
+ * Options for `navigator.credentials.create()`, binary values base64url
+ */
+export type CreationOptions = { challenge: string, rp: RelyingParty, user: UserEntity, pubKeyCredParams: Array<CredentialParameters>, 
+/**
+ * Milliseconds the user has to complete the registration
+ */
+timeout: bigint, attestation: string, authenticatorSelection: AuthenticatorSelection, 
+/**
+ * The user's passkeys, so an authenticator isn't registered twice
+ */
+excludeCredentials: Array<CredentialDescriptor>, };
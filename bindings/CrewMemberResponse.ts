@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A member of a crew
+ */
+export type CrewMemberResponse = { user_id: string, first_name: string, last_name: string, email: string, added_at: string, };
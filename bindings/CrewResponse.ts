@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CrewMemberResponse } from "./CrewMemberResponse";
+
+/**
+ * Crew response
+ */
+export type CrewResponse = { id: string, name: string, supervisor_id: string | null, default_equipment: Array<string>, 
+/**
+ * By last and first name
+ */
+members: Array<CrewMemberResponse>, created_at: string, updated_at: string, };
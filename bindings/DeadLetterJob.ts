@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * A job that failed on every attempt
+ */
+export type DeadLetterJob = { id: string, kind: string, payload: JsonValue, attempts: number, last_error: string | null, failed_at: string, 
+/**
+ * When the job was originally enqueued
+ */
+created_at: string, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CheckStatus } from "./CheckStatus";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Result of checking one dependency
+ */
+export type DependencyCheck = { status: CheckStatus, 
+/**
+ * Whether the service cannot serve traffic while this dependency is down
+ */
+critical: boolean, 
+/**
+ * Time the check took in milliseconds
+ */
+latency_ms: number, 
+/**
+ * Reason the dependency is down or degraded
+ */
+error?: string, 
+/**
+ * Additional check-specific information
+ */
+details?: JsonValue, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Device response payload
+ */
+export type DeviceResponse = { id: string, 
+/**
+ * ID the client generated for itself
+ */
+device_id: string, name: string | null, 
+/**
+ * Last login, token refresh or heartbeat from the device
+ */
+last_seen_at: string, created_at: string, 
+/**
+ * Version of the app, as of the last heartbeat
+ */
+app_version: string | null, 
+/**
+ * Battery charge in percent, as of the last heartbeat
+ */
+battery_level: number | null, last_heartbeat_at: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for a block's document checklist
+ */
+export type DocumentChecklistQuery = { block_id: string, 
+/**
+ * Block status to check against: `planned`, `approved`, `active` or
+ * `completed`
+ */
+status: string, };
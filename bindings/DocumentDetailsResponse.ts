@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DocumentResponse } from "./DocumentResponse";
+import type { DocumentVersionResponse } from "./DocumentVersionResponse";
+
+/**
+ * A document with its versions, latest first
+ */
+export type DocumentDetailsResponse = { document: DocumentResponse, versions: Array<DocumentVersionResponse>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Document response
+ */
+export type DocumentResponse = { id: string, subject_type: string, subject_id: string, category: string, title: string, current_version: number, 
+/**
+ * The document cannot be deleted before this
+ */
+retain_until: string | null, created_by: string | null, created_at: string, updated_at: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A version of a document
+ */
+export type DocumentVersionResponse = { version: number, filename: string, content_type: string, byte_size: number, 
+/**
+ * Version whose file this one restores
+ */
+restored_from: number | null, uploaded_by: string | null, created_at: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A file sent along with an email
+ */
+export type EmailAttachment = { filename: string, content_type: string, 
+/**
+ * File content, base64 encoded
+ */
+content: string, };
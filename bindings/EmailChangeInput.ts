@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to switch the caller to another email address
+ */
+export type EmailChangeInput = { 
+/**
+ * The new address, which the caller confirms from a link mailed to it
+ */
+email: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EmailAttachment } from "./EmailAttachment";
+
+/**
+ * A composed email, ready for delivery
+ */
+export type EmailMessage = { 
+/**
+ * Sender, `address` or `Name <address>`
+ */
+from: string, reply_to: string | null, to: string, subject: string, html: string, text: string, attachments: Array<EmailAttachment>, };
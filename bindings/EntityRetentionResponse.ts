@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Retention applied to one entity type
+ */
+export type EntityRetentionResponse = { entity_type: string, 
+/**
+ * Days soft-deleted records are kept, `null` when they are kept forever
+ */
+retention_days: number | null, 
+/**
+ * Number of records currently exempt from purging
+ */
+active_holds: number, };
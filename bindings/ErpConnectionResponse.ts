@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportColumn } from "./ExportColumn";
+
+/**
+ * ERP connection response
+ */
+export type ErpConnectionResponse = { id: string, name: string, connector: string, settings: Record<string, unknown>, mappings: { [key in string]?: Array<ExportColumn> }, schedule: string | null, next_run_at: string | null, enabled: boolean, created_by: string | null, created_at: string, updated_at: string, };
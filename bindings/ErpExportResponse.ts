@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One exported file and the status of its delivery
+ */
+export type ErpExportResponse = { id: string, connection_id: string, source: string, 
+/**
+ * `pending` until delivered; `failed` while retries are pending after a
+ * failed attempt
+ */
+status: string, 
+/**
+ * Records changed after this time, when set, up to `period_end` are
+ * included
+ */
+period_start: string | null, period_end: string, record_count: number, 
+/**
+ * Where the file was delivered
+ */
+remote_ref: string | null, attempts: number, 
+/**
+ * Why the last attempt failed
+ */
+error: string | null, delivered_at: string | null, created_at: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Something that can be exported to an ERP
+ */
+export type ErpSourceResponse = { name: string, 
+/**
+ * Fields of every record, in their default column order
+ */
+fields: Array<string>, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+export type ErrorResponse = { code: string, message: string, details: JsonValue | null, 
+/**
+ * Whether repeating the request later may succeed
+ */
+retryable: boolean, 
+/**
+ * Seconds to wait before retrying, mirrored in the `Retry-After` header
+ */
+retry_after?: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CertificationResponse } from "./CertificationResponse";
+
+/**
+ * A certification expiring soon, with its holder
+ */
+export type ExpiringCertificationResponse = { certification: CertificationResponse, first_name: string, last_name: string, email: string, 
+/**
+ * Days until it expires, negative once it has expired
+ */
+days_left: number, };
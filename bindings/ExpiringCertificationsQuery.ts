@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing expiring certifications
+ */
+export type ExpiringCertificationsQuery = { 
+/**
+ * Days ahead to look, 30 when unset
+ */
+days: number | null, 
+/**
+ * Only the certifications of users reporting directly to this user
+ */
+reports_to: string | null, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A column of an exported file
+ */
+export type ExportColumn = { 
+/**
+ * Heading the ERP expects
+ */
+header: string, 
+/**
+ * Field of the source the column is filled from
+ */
+field: string, };
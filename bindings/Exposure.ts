@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Topographic exposure to prevailing and storm winds
+ */
+export type Exposure = "sheltered" | "moderate" | "exposed" | "very_exposed";
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A change of one field of a record
+ */
+export type FieldChangeResponse = { field: string, 
+/**
+ * Value before the change, `null` when the field was empty or absent
+ */
+old_value: unknown, 
+/**
+ * Value after the change, `null` when the field was emptied or removed
+ */
+new_value: unknown, changed_by: string | null, 
+/**
+ * Name of the user who made the change, when still known
+ */
+changed_by_name: string | null, changed_at: string, };
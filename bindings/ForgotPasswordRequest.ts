@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Forgot-password request payload
+ */
+export type ForgotPasswordRequest = { email: string, 
+/**
+ * Solved CAPTCHA, when `auth.captcha` asks for one
+ */
+captcha_token?: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Boundary } from "./Boundary";
+import type { SoilSensitivity } from "./SoilSensitivity";
+
+/**
+ * Harvest block response
+ */
+export type HarvestBlockResponse = { id: string, license: string, block_number: string, area_ha: number, planned_volume_m3: number, status: string, boundary: Boundary, notes: string | null, soil_sensitivity: SoilSensitivity | null, created_by: string | null, created_at: string, updated_at: string, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SystemMetrics } from "./SystemMetrics";
+
+/**
+ * Response structure for health check endpoints
+ */
+export type HealthStatus = { 
+/**
+ * Current status of the service ("UP" or "DOWN")
+ */
+status: string, 
+/**
+ * Whether the database connection is healthy
+ */
+database: boolean, 
+/**
+ * Current version of the service
+ */
+version: string, 
+/**
+ * System metrics
+ */
+metrics?: SystemMetrics, };
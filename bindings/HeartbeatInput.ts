@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Heartbeat a field app sends while it runs
+ */
+export type HeartbeatInput = { 
+/**
+ * ID the app generated for itself, as sent on login
+ */
+device_id: string, 
+/**
+ * Names or renames the device
+ */
+device_name?: string, 
+/**
+ * e.g. `2.4.1`
+ */
+app_version?: string, 
+/**
+ * Battery charge in percent, 0 to 100
+ */
+battery_level?: number, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a cell is read into a field
+ *
+ * `number` accepts a decimal point or comma, `boolean` accepts
+ * `true`/`false`, `yes`/`no`, `y`/`n` and `1`/`0`, `date` is `YYYY-MM-DD`.
+ */
+export type ImportFieldKind = "text" | "number" | "integer" | "boolean" | "date";
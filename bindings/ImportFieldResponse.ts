@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportFieldKind } from "./ImportFieldKind";
+
+/**
+ * A field columns can be mapped to
+ */
+export type ImportFieldResponse = { name: string, kind: ImportFieldKind, required: boolean, 
+/**
+ * Other headers detected as this field
+ */
+aliases: Array<string>, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportPreviewRow } from "./ImportPreviewRow";
+
+/**
+ * How a file would be imported with a column mapping
+ */
+export type ImportPreview = { mapping: { [key in string]?: string }, 
+/**
+ * Rows of the whole file that would be imported
+ */
+valid_rows: number, 
+/**
+ * Rows of the whole file that would be rejected
+ */
+invalid_rows: number, 
+/**
+ * The first rows of the file
+ */
+rows: Array<ImportPreviewRow>, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One row of a preview
+ */
+export type ImportPreviewRow = { 
+/**
+ * Line of the row in the file, the header being line 1
+ */
+line: number, 
+/**
+ * The converted record, absent when the row is rejected
+ */
+record: Record<string, unknown> | null, 
+/**
+ * Why the row would be rejected
+ */
+errors: Array<string>, };
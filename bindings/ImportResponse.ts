@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Import response
+ */
+export type ImportResponse = { id: string, target: string, filename: string, headers: Array<string>, mapping: { [key in string]?: string }, 
+/**
+ * `uploaded` until committed, then `queued`, `running`, and
+ * `completed` or `failed`
+ */
+status: string, total_rows: number, imported_rows: number | null, failed_rows: number | null, 
+/**
+ * Whether rejected rows can be downloaded from
+ * `/v1/imports/{id}/errors.csv`
+ */
+has_error_report: boolean, error: string | null, created_by: string | null, created_at: string, updated_at: string, };
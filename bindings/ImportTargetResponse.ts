@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportFieldResponse } from "./ImportFieldResponse";
+
+/**
+ * Something files can be imported into
+ */
+export type ImportTargetResponse = { name: string, fields: Array<ImportFieldResponse>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Role } from "./Role";
+
+/**
+ * An invitation to join an organization
+ */
+export type InvitationResponse = { id: string, org_id: string, email: string, role: Role, invited_by: string | null, expires_at: string, accepted_at: string | null, created_at: string, };
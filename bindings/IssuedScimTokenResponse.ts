@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A newly issued SCIM provisioning token
+ */
+export type IssuedScimTokenResponse = { id: string, name: string, 
+/**
+ * The bearer token for the identity provider; it is not shown again
+ */
+token: string, 
+/**
+ * Base URL to configure in the identity provider, relative to the API's host
+ */
+base_url: string, created_at: string, };
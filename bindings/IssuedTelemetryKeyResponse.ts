@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A newly issued telemetry API key
+ */
+export type IssuedTelemetryKeyResponse = { id: string, name: string, 
+/**
+ * The key to send in the `X-Api-Key` header; it is not shown again
+ */
+key: string, created_at: string, };
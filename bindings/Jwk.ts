@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Public key of a signing key, as a JSON Web Key
+ */
+export type Jwk = { 
+/**
+ * `RSA` or `OKP` (Ed25519)
+ */
+kty: string, kid: string, alg: string, use: string, 
+/**
+ * RSA modulus, base64url
+ */
+n?: string, 
+/**
+ * RSA public exponent, base64url
+ */
+e?: string, 
+/**
+ * Curve of an `OKP` key, `Ed25519`
+ */
+crv?: string, 
+/**
+ * Ed25519 public key, base64url
+ */
+x?: string, };
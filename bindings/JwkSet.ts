@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Jwk } from "./Jwk";
+
+/**
+ * The published signing keys
+ */
+export type JwkSet = { keys: Array<Jwk>, };
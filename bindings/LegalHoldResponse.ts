@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Legal hold response
+ */
+export type LegalHoldResponse = { id: string, entity_type: string, entity_id: string, reason: string, placed_by: string | null, created_at: string, };
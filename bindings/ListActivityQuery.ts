@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing a user's activity
+ */
+export type ListActivityQuery = { 
+/**
+ * Only the activities of this day, in the caller's timezone
+ */
+date: string | null, page: number | null, per_page: number | null, };
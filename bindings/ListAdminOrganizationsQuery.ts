@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing organizations as an admin, which can
+ * include the soft-deleted ones
+ */
+export type ListAdminOrganizationsQuery = { 
+/**
+ * Text the name has to contain, ignoring case
+ */
+name_contains: string | null, 
+/**
+ * Only organizations created after this RFC 3339 timestamp or date
+ */
+created_after: string | null, 
+/**
+ * `name`, `created_at` or `updated_at`, with `-` for descending
+ */
+sort: string | null, 
+/**
+ * `true` to list soft-deleted organizations too, with their `deleted_at`
+ */
+include_deleted: boolean | null, page: number | null, per_page: number | null, };
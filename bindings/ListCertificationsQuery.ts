@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing certifications
+ */
+export type ListCertificationsQuery = { 
+/**
+ * Only the certifications of this user
+ */
+user_id: string | null, 
+/**
+ * Only the certifications of users reporting directly to this user
+ */
+reports_to: string | null, 
+/**
+ * Only certifications of this kind
+ */
+kind: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing crews
+ */
+export type ListCrewsQuery = { 
+/**
+ * Only the crews this user supervises or works in
+ */
+user_id: string | null, };
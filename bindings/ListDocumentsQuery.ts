@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing documents
+ */
+export type ListDocumentsQuery = { 
+/**
+ * `block`, `permit` or `certification`
+ */
+subject_type: string, subject_id: string, };
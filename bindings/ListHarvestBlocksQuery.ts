@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing harvest blocks
+ */
+export type ListHarvestBlocksQuery = { page: number | null, per_page: number | null, 
+/**
+ * Only the blocks of this license
+ */
+license: string | null, 
+/**
+ * Only blocks in this status
+ */
+status: string | null, 
+/**
+ * Only blocks crossing the box `min_lon,min_lat,max_lon,max_lat`
+ */
+bbox: string | null, 
+/**
+ * Only blocks with these tags, as comma separated tag ids
+ */
+tags: string | null, 
+/**
+ * `any` (the default) or `all` of the tags
+ */
+tag_match: string | null, };
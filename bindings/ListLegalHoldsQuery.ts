@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing legal holds
+ */
+export type ListLegalHoldsQuery = { entity_type: string | null, page: number | null, per_page: number | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing notifications
+ */
+export type ListNotificationsQuery = { 
+/**
+ * Only list unread notifications
+ */
+unread: boolean | null, page: number | null, per_page: number | null, };
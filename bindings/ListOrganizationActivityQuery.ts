@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing an organization's activity
+ */
+export type ListOrganizationActivityQuery = { 
+/**
+ * Comma-separated kinds to include, e.g. `user_joined,member_removed`
+ */
+kind: string | null, 
+/**
+ * Only what this user did
+ */
+actor_id: string | null, 
+/**
+ * Only what concerns this type of subject, e.g. `user` or `block`
+ */
+subject_type: string | null, 
+/**
+ * Only activity from this RFC 3339 timestamp or date on
+ */
+since: string | null, 
+/**
+ * Only activity before this RFC 3339 timestamp or date
+ */
+until: string | null, page: number | null, per_page: number | null, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing the users of an organization
+ */
+export type ListOrganizationUsersQuery = { 
+/**
+ * Words each matching a user's name or email
+ */
+query: string | null, 
+/**
+ * Comma-separated roles to include, e.g. `Manager,Operator`
+ */
+role: string | null, 
+/**
+ * `true` for active users only, `false` for deactivated ones only
+ */
+active: boolean | null, 
+/**
+ * `true` to list removed users instead of current ones
+ */
+removed: boolean | null, 
+/**
+ * `name`, `email`, `role`, `created_at` or `relevance`, with `-` for
+ * descending
+ */
+sort: string | null, page: number | null, per_page: number | null, };
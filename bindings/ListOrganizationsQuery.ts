@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing organizations
+ */
+export type ListOrganizationsQuery = { 
+/**
+ * Text the name has to contain, ignoring case
+ */
+name_contains: string | null, 
+/**
+ * Only organizations created after this RFC 3339 timestamp or date
+ */
+created_after: string | null, 
+/**
+ * `name`, `created_at` or `updated_at`, with `-` for descending
+ */
+sort: string | null, page: number | null, per_page: number | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A complete list, for collections too small to page through
+ *
+ * [`ApiResponse`] flattens its data into the body, which a bare list can't be.
+ */
+export type ListResponse<T> = { data: Array<T>, };
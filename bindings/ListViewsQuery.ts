@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing views
+ */
+export type ListViewsQuery = { 
+/**
+ * Only views of this resource
+ */
+resource: string | null, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MaintenanceSettings } from "./MaintenanceSettings";
+import type { RateLimitSettings } from "./RateLimitSettings";
+
+/**
+ * Settings of the `[live]` section
+ */
+export type LiveSettings = { 
+/**
+ * Log filter directives, `RUST_LOG` or the environment default when unset
+ */
+log_filter: string | null, rate_limit: RateLimitSettings, 
+/**
+ * Origins allowed to call the API from a browser, `*` for any
+ */
+cors_origins: Array<string>, maintenance: MaintenanceSettings, 
+/**
+ * Feature flags by name, unknown flags are off
+ */
+features: { [key in string]?: boolean }, 
+/**
+ * Seconds between checks of the config files for changes, 0 disables
+ */
+watch_interval_secs: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Login request payload
+ */
+export type LoginRequest = { email: string, password: string, 
+/**
+ * ID the client generated for itself, registering it as a device
+ */
+device_id?: string, 
+/**
+ * Name for the device, e.g. "Harvester tablet"
+ */
+device_name?: string, 
+/**
+ * Solved CAPTCHA, when `auth.captcha` asks for one
+ */
+captcha_token?: string, };
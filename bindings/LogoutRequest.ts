@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Logout request payload
+ */
+export type LogoutRequest = { 
+/**
+ * Refresh token to revoke along with the access token
+ */
+refresh_token: string, };
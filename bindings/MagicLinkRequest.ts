@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Magic link request payload
+ */
+export type MagicLinkRequest = { email: string, 
+/**
+ * Device the link is requested from, the only one it will work on
+ */
+device_id?: string, };
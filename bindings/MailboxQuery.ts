@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for reading the dev mailbox
+ */
+export type MailboxQuery = { 
+/**
+ * Only list mail sent to this address
+ */
+to: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReceivedEmail } from "./ReceivedEmail";
+
+/**
+ * Mail caught by the dev mailbox, newest first
+ */
+export type MailboxResponse = { messages: Array<ReceivedEmail>, };
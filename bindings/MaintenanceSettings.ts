@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Maintenance mode, rejecting API requests except health, auth and admin
+ */
+export type MaintenanceSettings = { enabled: boolean, 
+/**
+ * Message returned with the 503 response
+ */
+message: string, 
+/**
+ * Seconds clients are told to wait before retrying
+ */
+retry_after_secs: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of marking all notifications read
+ */
+export type MarkAllReadResponse = { 
+/**
+ * Notifications that were unread
+ */
+updated: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Represents a notification addressed to a user
+ *
+ * # Fields
+ *
+ * * `kind` - What triggered the notification (e.g. `import_failed`), for
+ *   icons and filtering in the UI
+ * * `link` - Page in the web UI the notification refers to
+ * * `data` - Structured details for the UI, e.g. the id of the affected stand
+ * * `read_at` - When the user read the notification, unread while unset
+ */
+export type Notification = { id: string, user_id: string, org_id: string | null, kind: string, title: string, body: string | null, link: string | null, data: JsonValue | null, read_at: string | null, created_at: string, };
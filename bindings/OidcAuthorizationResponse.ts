@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Single sign-on start response payload
+ */
+export type OidcAuthorizationResponse = { 
+/**
+ * The provider's sign-in page to send the user to
+ */
+authorization_url: string, };
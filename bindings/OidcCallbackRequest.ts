@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Single sign-on callback payload
+ */
+export type OidcCallbackRequest = { 
+/**
+ * Authorization code the provider redirected back with
+ */
+code: string, 
+/**
+ * State the provider redirected back with
+ */
+state: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Role } from "./Role";
+
+/**
+ * A device online in the field, with its user
+ */
+export type OnlineDeviceResponse = { user_id: string, first_name: string, last_name: string, role: Role, id: string, 
+/**
+ * ID the app generated for itself
+ */
+device_id: string, device_name: string | null, app_version: string | null, 
+/**
+ * Battery charge in percent, 0 to 100
+ */
+battery_level: number | null, last_heartbeat_at: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for listing the devices online
+ */
+export type OnlineQuery = { 
+/**
+ * Minutes since the last heartbeat a device counts as online, 5 when
+ * unset
+ */
+minutes: number | null, };
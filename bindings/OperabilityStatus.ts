@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Whether ground-based operations may run on a block
+ */
+export type OperabilityStatus = "open" | "caution" | "shutdown";
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Represents an organization in the system
+ * 
+ * This model serves as the core entity for managing organizations. It includes
+ * all necessary fields for tracking organization data and implements soft deletion
+ * for data retention.
+ * 
+ * # Fields
+ * 
+ * * `id` - Unique identifier for the organization
+ * * `name` - Organization's display name
+ * * `created_at` - Timestamp of when the organization was created
+ * * `updated_at` - Timestamp of the last update
+ * * `deleted_at` - Optional timestamp for soft deletion
+ * * `parent_id` - Organization this one is a division or area of, if any
+ * * `owner_id` - Admin owning the organization, if one was recorded
+ * * `archived_at` - When the organization was archived; archived
+ *   organizations take no writes
+ */
+export type Organization = { id: string, name: string, created_at: string, updated_at: string, deleted_at: string | null, parent_id: string | null, owner_id: string | null, archived_at: string | null, };
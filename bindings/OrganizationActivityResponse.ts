@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Something that happened in an organization
+ */
+export type OrganizationActivityResponse = { id: string, organization_id: string, 
+/**
+ * Who caused it; null when the system did or the user is gone
+ */
+actor_id: string | null, 
+/**
+ * `user_joined`, `member_role_changed`, `member_removed`,
+ * `block_signed_off`, `organization_archived` or
+ * `organization_unarchived`
+ */
+kind: string, 
+/**
+ * What it concerns, e.g. `user` or `block`
+ */
+subject_type: string, subject_id: string, summary: string, data: Record<string, unknown> | null, created_at: string, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An archival of an organization and its export bundle
+ */
+export type OrganizationArchiveResponse = { id: string, organization_id: string, requested_by: string | null, 
+/**
+ * `pending` while the export bundle is written, then `completed`, or
+ * `failed` while it is retried
+ */
+status: string, 
+/**
+ * Size of the gzipped export bundle
+ */
+byte_size: bigint | null, 
+/**
+ * Why writing the export bundle last failed
+ */
+error: string | null, created_at: string, completed_at: string | null, 
+/**
+ * When the organization was reopened
+ */
+unarchived_at: string | null, };
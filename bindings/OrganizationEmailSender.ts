@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Sender identity of an organization
+ *
+ * # Fields
+ *
+ * * `org_id` - Organization the sender belongs to
+ * * `from_address` - Address mail is sent from
+ * * `from_name` - Display name shown with the address
+ * * `reply_to` - Address replies go to, the sender address when unset
+ */
+export type OrganizationEmailSender = { org_id: string, from_address: string, from_name: string | null, reply_to: string | null, created_at: string, updated_at: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An organization in a hierarchy listing
+ */
+export type OrganizationNodeResponse = { id: string, name: string, parent_id: string | null, 
+/**
+ * Levels below the organization the listing starts at
+ */
+depth: number, created_at: string, updated_at: string, };
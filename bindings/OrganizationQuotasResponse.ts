@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Limits of an organization's plan
+ */
+export type OrganizationQuotasResponse = { org_id: string, 
+/**
+ * `null` for any number of users
+ */
+max_users: number | null, 
+/**
+ * `null` for any number of active harvest blocks
+ */
+max_active_blocks: number | null, 
+/**
+ * Days telemetry is kept
+ */
+telemetry_retention_days: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Organization response
+ */
+export type OrganizationResponse = { id: string, name: string, 
+/**
+ * Organization this one is a division or area of
+ */
+parent_id: string | null, 
+/**
+ * Admin owning the organization
+ */
+owner_id: string | null, 
+/**
+ * When the organization was archived; archived organizations are read-only
+ */
+archived_at: string | null, created_at: string, updated_at: string, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UnitSystem } from "./UnitSystem";
+
+/**
+ * How an organization works
+ */
+export type OrganizationSettingsResponse = { 
+/**
+ * Units members see unless their preferences say otherwise
+ */
+default_units: UnitSystem, 
+/**
+ * Month the fiscal year starts in, 1 for January
+ */
+fiscal_year_start_month: number, 
+/**
+ * Operational timezone, `UTC` or an offset from it such as `-08:00`
+ */
+timezone: string, 
+/**
+ * Safety forms every job needs
+ */
+required_safety_forms: Array<string>, };
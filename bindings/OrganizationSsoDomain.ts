@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Role } from "./Role";
+
+/**
+ * Email domain whose users join an organization on their first sign-on
+ *
+ * # Fields
+ *
+ * * `domain` - Lower-case domain, e.g. `pinecorp.com`; claimed by one organization
+ * * `org_id` - Organization new users join
+ * * `role` - Role new users get
+ */
+export type OrganizationSsoDomain = { domain: string, org_id: string, role: Role, created_at: string, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How much of its plan an organization uses
+ */
+export type OrganizationUsageResponse = { 
+/**
+ * Users of the organization, deactivated ones included
+ */
+users: number, 
+/**
+ * `null` for any number of users
+ */
+max_users: number | null, 
+/**
+ * `null` for any number of active harvest blocks
+ */
+max_active_blocks: number | null, 
+/**
+ * Days telemetry is kept
+ */
+telemetry_retention_days: number, };
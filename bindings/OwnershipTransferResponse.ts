@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A transfer of an organization's ownership
+ */
+export type OwnershipTransferResponse = { id: string, organization_id: string, from_user_id: string, to_user_id: string, 
+/**
+ * When the transfer lapses unless accepted
+ */
+expires_at: string, accepted_at: string | null, created_at: string, };
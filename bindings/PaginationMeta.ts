@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PaginationMeta = { current_page: number, per_page: number, total_items: number, total_pages: number, has_next_page: boolean, has_previous_page: boolean, 
+/**
+ * Whether `total_items` is an exact count or a planner estimate
+ */
+exact: boolean, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PasskeyAssertionResponse } from "./PasskeyAssertionResponse";
+
+/**
+ * What `navigator.credentials.get()` returned, as
+ * `PublicKeyCredential.toJSON()` encodes it
+ */
+export type PasskeyAssertion = { 
+/**
+ * Credential ID, base64url
+ */
+id: string, response: PasskeyAssertionResponse, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PasskeyAssertionResponse = { 
+/**
+ * Base64url
+ */
+clientDataJSON: string, 
+/**
+ * Base64url
+ */
+authenticatorData: string, 
+/**
+ * Base64url
+ */
+signature: string, 
+/**
+ * ID of the user the passkey was registered for, base64url
+ */
+userHandle?: string, };
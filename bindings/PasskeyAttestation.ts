@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PasskeyAttestationResponse } from "./PasskeyAttestationResponse";
+
+/**
+ * What `navigator.credentials.create()` returned, as
+ * `PublicKeyCredential.toJSON()` encodes it
+ */
+export type PasskeyAttestation = { 
+/**
+ * Credential ID, base64url
+ */
+id: string, response: PasskeyAttestationResponse, };
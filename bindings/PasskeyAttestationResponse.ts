@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PasskeyAttestationResponse = { 
+/**
+ * Base64url
+ */
+clientDataJSON: string, 
+/**
+ * Base64url
+ */
+attestationObject: string, };
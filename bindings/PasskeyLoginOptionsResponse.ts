@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RequestOptions } from "./RequestOptions";
+
+/**
+ * Passkey login options response payload
+ */
+export type PasskeyLoginOptionsResponse = { 
+/**
+ * Sent back with the signed challenge
+ */
+challenge_token: string, 
+/**
+ * The `publicKey` options for `navigator.credentials.get()`
+ */
+options: RequestOptions, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PasskeyAssertion } from "./PasskeyAssertion";
+
+/**
+ * Passkey login payload
+ */
+export type PasskeyLoginRequest = { 
+/**
+ * Token from the login options
+ */
+challenge_token: string, credential: PasskeyAssertion, 
+/**
+ * ID the client generated for itself, registering it as a device
+ */
+device_id?: string, 
+/**
+ * Name for the device, e.g. "Harvester tablet"
+ */
+device_name?: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CreationOptions } from "./CreationOptions";
+
+/**
+ * Passkey registration options response payload
+ */
+export type PasskeyRegistrationOptionsResponse = { 
+/**
+ * Sent back with the new passkey
+ */
+challenge_token: string, 
+/**
+ * The `publicKey` options for `navigator.credentials.create()`
+ */
+options: CreationOptions, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Passkey response payload
+ */
+export type PasskeyResponse = { id: string, name: string, 
+/**
+ * Last login with the passkey
+ */
+last_used_at: string | null, created_at: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An action routes can require
+ */
+export type Permission = "customers:read" | "customers:write" | "timber_sales:read" | "timber_sales:write" | "reports:read" | "reports:write" | "imports:read" | "imports:write" | "tags:read" | "tags:write" | "erp:read" | "erp:write" | "certifications:read" | "certifications:write" | "blocks:read" | "blocks:write" | "crews:read" | "crews:write";
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Permission } from "./Permission";
+import type { RolePermissionsResponse } from "./RolePermissionsResponse";
+
+/**
+ * Every permission and the roles holding them
+ */
+export type PermissionsResponse = { 
+/**
+ * Every permission routes can require
+ */
+permissions: Array<Permission>, roles: Array<RolePermissionsResponse>, };
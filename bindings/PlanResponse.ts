@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A plan of the catalog with the quotas it grants
+ */
+export type PlanResponse = { 
+/**
+ * e.g. `team`
+ */
+id: string, name: string, 
+/**
+ * `null` for any number of users
+ */
+max_users: number | null, 
+/**
+ * `null` for any number of active harvest blocks
+ */
+max_active_blocks: number | null, 
+/**
+ * Days telemetry is kept
+ */
+telemetry_retention_days: number, 
+/**
+ * Whether the plan includes premium features such as ERP exports
+ */
+premium: boolean, 
+/**
+ * Whether the plan can be bought, paid plans need a Stripe price
+ */
+available: boolean, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UnitSystem } from "./UnitSystem";
+
+/**
+ * How the caller wants measurements and times presented
+ */
+export type PreferencesResponse = { units: UnitSystem, 
+/**
+ * `UTC` or an offset from it, such as `-08:00`
+ */
+timezone: string, 
+/**
+ * `en` or `fr`
+ */
+locale: string, };
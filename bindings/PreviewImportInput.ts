@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for previewing an import
+ */
+export type PreviewImportInput = { 
+/**
+ * Field each header is imported into, replacing the stored mapping;
+ * the stored mapping is used when unset
+ */
+mapping: { [key in string]?: string } | null, 
+/**
+ * Rows returned, 20 when unset and at most 200
+ */
+limit: number | null, };
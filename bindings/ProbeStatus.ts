@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DependencyCheck } from "./DependencyCheck";
+
+/**
+ * Response of the liveness, readiness and startup probes
+ */
+export type ProbeStatus = { 
+/**
+ * "DOWN" when a critical dependency is down, "DEGRADED" when another
+ * dependency is down or any is slow, "UP" otherwise
+ */
+status: string, 
+/**
+ * Per-dependency results, keyed by dependency name
+ */
+checks: { [key in string]?: DependencyCheck }, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * A job waiting for, or in, execution
+ *
+ * # Fields
+ *
+ * * `kind` - Handler the job is dispatched to (e.g. `stand_import`)
+ * * `attempts` - Number of times the job has been claimed
+ * * `run_at` - Earliest time the job may run, pushed back after failures
+ * * `locked_by` - Worker processing the job
+ * * `locked_until` - End of the visibility timeout; once passed another
+ *   worker may claim the job again
+ */
+export type QueuedJob = { id: string, kind: string, payload: JsonValue, attempts: number, max_attempts: number, run_at: string, locked_by: string | null, locked_until: string | null, last_error: string | null, created_at: string, updated_at: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for a quick search
+ */
+export type QuickSearchQuery = { 
+/**
+ * Name or number searched for
+ */
+q: string, 
+/**
+ * Comma separated kinds searched, all of them when unset
+ */
+types: string | null, 
+/**
+ * Results per kind
+ */
+limit: number | null, };
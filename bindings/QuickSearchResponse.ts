@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SearchHit } from "./SearchHit";
+
+/**
+ * Quick search results, best first
+ */
+export type QuickSearchResponse = { query: string, results: Array<SearchHit>, };
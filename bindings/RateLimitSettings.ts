@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Requests allowed per client and window
+ */
+export type RateLimitSettings = { max_requests: number, window_secs: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EmailMessage } from "./EmailMessage";
+
+/**
+ * A message caught by the [`DevMailbox`]
+ */
+export type ReceivedEmail = { id: string, received_at: string, message: EmailMessage, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Magic link login payload
+ */
+export type RedeemMagicLinkRequest = { 
+/**
+ * Token from the magic link
+ */
+token: string, 
+/**
+ * Device the link is opened on
+ */
+device_id?: string, 
+/**
+ * Name for the device, e.g. "Harvester tablet"
+ */
+device_name?: string, };
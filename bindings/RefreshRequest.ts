@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Token refresh request payload
+ */
+export type RefreshRequest = { refresh_token: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PasskeyAttestation } from "./PasskeyAttestation";
+
+/**
+ * Passkey registration payload
+ */
+export type RegisterPasskeyRequest = { 
+/**
+ * Name for the passkey, e.g. "Office laptop"
+ */
+name: string, 
+/**
+ * Token from the registration options
+ */
+challenge_token: string, credential: PasskeyAttestation, };
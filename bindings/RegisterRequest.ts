@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Registration request payload
+ *
+ * Either `org_id` or `invite_token` is required.
+ */
+export type RegisterRequest = { first_name: string, last_name: string, email: string, phone_number: string, password: string, 
+/**
+ * Organization to join as an admin, when not invited
+ */
+org_id?: string, 
+/**
+ * Token from an invitation email; joins the organization with the
+ * invitation's role
+ */
+invite_token?: string, 
+/**
+ * Solved CAPTCHA, when `auth.captcha` asks for one
+ */
+captcha_token?: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LiveSettings } from "./LiveSettings";
+
+/**
+ * Outcome of a configuration reload
+ */
+export type ReloadConfigResponse = { 
+/**
+ * Keys of the `[live]` section whose values changed
+ */
+changed: Array<string>, settings: LiveSettings, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Aggregate } from "./Aggregate";
+
+/**
+ * A column of the report output
+ */
+export type ReportColumn = { field: string, aggregate: Aggregate | null, 
+/**
+ * Column heading, derived from the field and aggregate when unset
+ */
+label: string | null, };
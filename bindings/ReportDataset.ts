@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Data of an organization a report can read, rolled up from the
+ * organizations under it: its members (`users`, without credentials), the
+ * notifications sent to them (`notifications`) or the contracts its tenders
+ * awarded (`sale_contracts`)
+ */
+export type ReportDataset = "users" | "notifications" | "sale_contracts";
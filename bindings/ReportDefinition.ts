@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReportColumn } from "./ReportColumn";
+import type { ReportDataset } from "./ReportDataset";
+import type { ReportFilter } from "./ReportFilter";
+import type { ReportParameter } from "./ReportParameter";
+
+/**
+ * What a report reads and how it shapes the rows
+ *
+ * Without `group_by`, every matching row is listed with the selected
+ * columns. With it, rows are grouped by those fields and every other
+ * column must be aggregated.
+ */
+export type ReportDefinition = { dataset: ReportDataset, 
+/**
+ * Values supplied when the report runs, referenced by filters
+ */
+parameters: Array<ReportParameter>, filters: Array<ReportFilter>, group_by: Array<string>, columns: Array<ReportColumn>, };
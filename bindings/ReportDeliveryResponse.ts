@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReportFormat } from "./ReportFormat";
+
+/**
+ * One scheduled run of a report
+ */
+export type ReportDeliveryResponse = { id: string, report_id: string, recipients: Array<string>, format: ReportFormat, 
+/**
+ * `queued` once the mail is queued for every recipient, `failed` when
+ * the report could not be run
+ */
+status: string, row_count: number | null, 
+/**
+ * Whether the output was too large to attach and is downloaded from
+ * `/v1/reports/{id}/deliveries/{delivery_id}/file` instead
+ */
+linked: boolean, error: string | null, created_at: string, };
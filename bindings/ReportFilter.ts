@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FilterOp } from "./FilterOp";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * A condition rows must meet
+ *
+ * Compares `field` with either a fixed `value` or the run's `parameter`;
+ * exactly one of the two is set.
+ */
+export type ReportFilter = { field: string, op: FilterOp, value: JsonValue | null, parameter: string | null, };
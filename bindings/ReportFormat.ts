@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Format a report run is delivered in: the result in the usual response
+ * envelope (`json`), or as an attachment of comma-separated values with a
+ * heading row (`csv`) or a formatted Excel workbook (`xlsx`)
+ */
+export type ReportFormat = "json" | "csv" | "xlsx";
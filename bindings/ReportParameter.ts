@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * A value supplied when the report runs
+ */
+export type ReportParameter = { name: string, 
+/**
+ * Used when the run does not supply the parameter, which is then
+ * required when unset
+ */
+default: JsonValue | null, };
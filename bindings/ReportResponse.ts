@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReportDefinition } from "./ReportDefinition";
+
+/**
+ * Report response
+ */
+export type ReportResponse = { id: string, name: string, description: string | null, definition: ReportDefinition, created_by: string | null, created_at: string, updated_at: string, };
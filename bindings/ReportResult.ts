@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Output of a report run
+ */
+export type ReportResult = { 
+/**
+ * Column headings, in the order of each row's values
+ */
+columns: Array<string>, rows: Array<Array<JsonValue>>, 
+/**
+ * Whether the dataset had more rows than a run reads, so the newest
+ * rows only were reported
+ */
+truncated: boolean, generated_at: string, };
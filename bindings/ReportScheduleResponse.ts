@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+import type { ReportFormat } from "./ReportFormat";
+
+/**
+ * Report schedule response
+ */
+export type ReportScheduleResponse = { report_id: string, schedule: string, recipients: Array<string>, format: ReportFormat, parameters: { [key in string]?: JsonValue }, next_run_at: string, created_by: string | null, created_at: string, updated_at: string, };
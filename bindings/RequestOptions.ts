@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CredentialDescriptor } from "./CredentialDescriptor";
+
+/**
+ * Options for `navigator.credentials.get()`, binary values base64url
+ */
+export type RequestOptions = { challenge: string, 
+/**
+ * Milliseconds the user has to complete the login
+ */
+timeout: bigint, rpId: string, userVerification: string, 
+/**
+ * Empty, so the user picks any of their passkeys for the site
+ */
+allowCredentials: Array<CredentialDescriptor>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Password reset request payload
+ */
+export type ResetPasswordRequest = { 
+/**
+ * Token from the password reset link
+ */
+token: string, 
+/**
+ * The new password
+ */
+password: string, };
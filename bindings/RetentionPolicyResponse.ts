@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EntityRetentionResponse } from "./EntityRetentionResponse";
+
+/**
+ * Effective soft-delete retention policy
+ */
+export type RetentionPolicyResponse = { default_retention_days: number | null, entities: Array<EntityRetentionResponse>, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Permission } from "./Permission";
+import type { Role } from "./Role";
+
+/**
+ * Permissions of one role
+ */
+export type RolePermissionsResponse = { role: Role, permissions: Array<Permission>, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Permission } from "./Permission";
+
+/**
+ * A custom role of the organization
+ */
+export type RoleResponse = { id: string, name: string, description: string | null, permissions: Array<Permission>, 
+/**
+ * Users the role is assigned to, earliest first
+ */
+members: Array<string>, created_at: string, updated_at: string, };
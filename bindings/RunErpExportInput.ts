@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for exporting a source through a connection now
+ */
+export type RunErpExportInput = { source: string, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+import type { ReportFormat } from "./ReportFormat";
+
+/**
+ * Input for running a saved report
+ */
+export type RunReportInput = { 
+/**
+ * Output format, JSON when unset
+ */
+format: ReportFormat | null, 
+/**
+ * Values of the report's parameters, by name
+ */
+parameters: { [key in string]?: JsonValue }, };
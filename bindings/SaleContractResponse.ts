@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Sale contract response
+ */
+export type SaleContractResponse = { id: string, tender_id: string, parcel_id: string, bid_id: string, buyer_name: string, block_id: string | null, assortment: string, volume_m3: number, price_per_m3: number, created_by: string | null, created_at: string, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for recording or replacing a certification
+ */
+export type SaveCertificationInput = { 
+/**
+ * The member of the organization holding the certification
+ */
+user_id: string, 
+/**
+ * `faller`, `first_aid`, `dangerous_goods` or `other`
+ */
+kind: string, 
+/**
+ * Required for `other` certifications
+ */
+name: string | null, certificate_number: string | null, 
+/**
+ * Who issued the certification, e.g. a training provider
+ */
+issuer: string | null, issued_on: string, 
+/**
+ * Unset when the certification doesn't expire
+ */
+expires_on: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for creating or replacing a customer
+ */
+export type SaveCustomerInput = { name: string, contact_name: string | null, email: string | null, phone_number: string | null, address: string | null, notes: string | null, };
@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportColumn } from "./ExportColumn";
+
+/**
+ * Input for creating an ERP connection, also used to replace one
+ */
+export type SaveErpConnectionInput = { name: string, 
+/**
+ * `csv_download` or `csv_sftp`
+ */
+connector: string, 
+/**
+ * Connector settings; `csv_sftp` takes `host`, `port`, `username`,
+ * `directory` and the server's `host_key_sha256` fingerprint
+ */
+settings: Record<string, unknown>, 
+/**
+ * Columns exported per source; an empty list exports every field
+ */
+mappings: { [key in string]?: Array<ExportColumn> }, 
+/**
+ * Cron expression (`sec min hour day month weekday`, UTC) exports run
+ * on; exports run on request only when unset
+ */
+schedule: string | null, 
+/**
+ * Whether scheduled exports run, true when unset
+ */
+enabled: boolean | null, };
@@ -0,0 +1,33 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Boundary } from "./Boundary";
+import type { SoilSensitivity } from "./SoilSensitivity";
+
+/**
+ * Input for creating or replacing a harvest block
+ */
+export type SaveHarvestBlockInput = { 
+/**
+ * Harvesting license or cutting permit the block is cut under
+ */
+license: string, 
+/**
+ * Unique within the license
+ */
+block_number: string, 
+/**
+ * Net harvest area in hectares
+ */
+area_ha: number, 
+/**
+ * Volume planned to be cut
+ */
+planned_volume_m3: number, 
+/**
+ * `planned`, `approved`, `active` or `completed`; new blocks are
+ * planned and updates keep the status when unset
+ */
+status: string | null, boundary: Boundary, notes: string | null, 
+/**
+ * How easily the soil ruts, for the block's wet-weather operability
+ */
+soil_sensitivity: SoilSensitivity | null, };
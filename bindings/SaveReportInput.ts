@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReportDefinition } from "./ReportDefinition";
+
+/**
+ * Input for saving a report, also used to replace one
+ */
+export type SaveReportInput = { name: string, description: string | null, definition: ReportDefinition, };
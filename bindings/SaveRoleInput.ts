@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Permission } from "./Permission";
+
+/**
+ * Input for creating or replacing a custom role
+ */
+export type SaveRoleInput = { 
+/**
+ * Unique within the organization, ignoring case, and not the name of a
+ * built-in role
+ */
+name: string, description: string | null, 
+/**
+ * Permissions the role grants
+ */
+permissions: Array<Permission>, };
@@ -0,0 +1,33 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SpeciesShare } from "./SpeciesShare";
+import type { StandConditions } from "./StandConditions";
+
+/**
+ * Input for creating or replacing a stand
+ */
+export type SaveStandInput = { 
+/**
+ * Unique within the block
+ */
+stand_number: string, area_ha: number, 
+/**
+ * Species mix, leading species first; the percentages add up to 100
+ */
+species: Array<SpeciesShare>, 
+/**
+ * 20-year age class, 1 for up to 20 years to 9 for over 250
+ */
+age_class: number, 
+/**
+ * Height in metres the leading species reaches at breast height age 50
+ */
+site_index: number, net_merchantable_volume_m3: number, 
+/**
+ * Day the stand was last cruised
+ */
+inventoried_on: string | null, 
+/**
+ * Height, stocking, soil and exposure, for scoring the stand's
+ * windthrow risk
+ */
+windthrow_conditions: StandConditions | null, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for creating or replacing a supply contract
+ */
+export type SaveSupplyContractInput = { 
+/**
+ * Contract number, unique within the organization
+ */
+reference: string, 
+/**
+ * Product delivered, e.g. `spruce sawlog`
+ */
+product: string, 
+/**
+ * Volume committed over the period
+ */
+volume_m3: number, price_per_m3: number, 
+/**
+ * First delivery day
+ */
+starts_on: string, 
+/**
+ * Last delivery day
+ */
+ends_on: string, notes: string | null, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for creating or replacing a tag
+ */
+export type SaveTagInput = { 
+/**
+ * Unique within the organization, ignoring case
+ */
+name: string, 
+/**
+ * Hex color such as `#2e7d32`
+ */
+color: string, };
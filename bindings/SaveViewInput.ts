@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for saving a view or replacing one
+ */
+export type SaveViewInput = { 
+/**
+ * List the view applies to, e.g. `customers`; it cannot change once
+ * saved
+ */
+resource: string, name: string, 
+/**
+ * Query parameters the list is filtered with
+ */
+filters: Record<string, unknown>, 
+/**
+ * Fields sorted by, in order, with `-` for descending
+ */
+sort: Array<string>, 
+/**
+ * Columns shown, in order
+ */
+columns: Array<string>, 
+/**
+ * Whether the rest of the organization can use the view
+ */
+shared: boolean, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Saved view response
+ */
+export type SavedViewResponse = { id: string, resource: string, name: string, filters: Record<string, unknown>, sort: Array<string>, columns: Array<string>, shared: boolean, 
+/**
+ * User who saved the view
+ */
+user_id: string, 
+/**
+ * Whether the list of the resource opens with this view for the
+ * requesting user
+ */
+is_default: boolean, created_at: string, updated_at: string, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+import type { ReportFormat } from "./ReportFormat";
+
+/**
+ * Input for scheduling a report's delivery by email, replacing any
+ * existing schedule
+ */
+export type ScheduleReportInput = { 
+/**
+ * Cron expression (`sec min hour day month weekday`, UTC), e.g.
+ * `0 0 6 * * Mon` for Mondays at 06:00
+ */
+schedule: string, 
+/**
+ * Email addresses the report is sent to
+ */
+recipients: Array<string>, 
+/**
+ * Format of the delivered file, JSON when unset
+ */
+format: ReportFormat | null, 
+/**
+ * Values of the report's parameters, by name
+ */
+parameters: { [key in string]?: JsonValue }, };
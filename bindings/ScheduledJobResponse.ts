@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Schedule and last run of a recurring background job
+ */
+export type ScheduledJobResponse = { name: string, 
+/**
+ * Cron expression in effect, `off` when disabled
+ */
+schedule: string, 
+/**
+ * Expression set through the admin API, replacing the configured one
+ */
+schedule_override: string | null, next_run_at: string, 
+/**
+ * Whether an instance currently holds the job's lease
+ */
+running: boolean, 
+/**
+ * Instance running the job
+ */
+locked_by: string | null, last_started_at: string | null, last_finished_at: string | null, 
+/**
+ * `running`, `succeeded` or `failed`
+ */
+last_status: string | null, last_error: string | null, last_duration_ms: number | null, 
+/**
+ * Summary returned by the last run
+ */
+last_output: JsonValue | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A SCIM provisioning token, without the token itself
+ */
+export type ScimTokenResponse = { id: string, name: string, created_by: string | null, last_used_at: string | null, created_at: string, };
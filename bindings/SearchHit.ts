@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SearchKind } from "./SearchKind";
+
+/**
+ * A record matching a quick search
+ */
+export type SearchHit = { kind: SearchKind, id: string, 
+/**
+ * What the record is shown as
+ */
+title: string, 
+/**
+ * Secondary line, e.g. a user's email
+ */
+subtitle: string | null, 
+/**
+ * Record the hit is opened under, e.g. a stand's block
+ */
+parent_id: string | null, 
+/**
+ * Relevance; higher ranks first
+ */
+score: number, };
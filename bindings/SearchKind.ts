@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Kind of record a hit is
+ */
+export type SearchKind = "user" | "customer" | "supply_contract" | "tender" | "document" | "block" | "stand";
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * "This wasn't me" payload, from the link in a login alert
+ */
+export type SecureAccountRequest = { 
+/**
+ * Token from the link
+ */
+token: string, };
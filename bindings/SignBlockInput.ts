@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for signing off a block package
+ */
+export type SignBlockInput = { 
+/**
+ * `planner`, `professional_forester` or `operations_manager`
+ */
+step: string, 
+/**
+ * SHA-256 of the package document signed, in hex
+ */
+document_hash: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How deeply the soil lets trees root
+ */
+export type SoilRooting = "deep" | "moderate" | "poorly_drained" | "shallow";
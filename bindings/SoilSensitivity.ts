@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How easily a block's soil ruts under ground-based equipment
+ */
+export type SoilSensitivity = "low" | "moderate" | "high" | "very_high";
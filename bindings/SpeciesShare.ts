@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Share of a stand taken by one species
+ */
+export type SpeciesShare = { 
+/**
+ * Species name or inventory code, e.g. `spruce`
+ */
+species: string, 
+/**
+ * Percentage of the stand, the shares of a stand adding up to 100
+ */
+percent: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Net merchantable volume of one species across a block's stands
+ */
+export type SpeciesVolumeResponse = { species: string, volume_m3: number, 
+/**
+ * Percentage of the block's net merchantable volume
+ */
+percent: number, };
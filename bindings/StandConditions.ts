@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Exposure } from "./Exposure";
+import type { SoilRooting } from "./SoilRooting";
+
+/**
+ * What the risk of a stand is computed from, as recorded in its inventory
+ */
+export type StandConditions = { 
+/**
+ * Mean height of the dominant trees, in metres
+ */
+mean_height_m: number, 
+/**
+ * Stocking density, in stems per hectare
+ */
+stems_per_ha: number, soil: SoilRooting, exposure: Exposure, 
+/**
+ * Whether the stand borders an opening cut since it grew up, so its
+ * trees face wind they are not adapted to
+ */
+new_edge: boolean, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RiskClass } from "./RiskClass";
+import type { SpeciesShare } from "./SpeciesShare";
+import type { StandConditions } from "./StandConditions";
+
+/**
+ * Stand response
+ */
+export type StandResponse = { id: string, block_id: string, stand_number: string, area_ha: number, species: Array<SpeciesShare>, age_class: number, site_index: number, net_merchantable_volume_m3: number, inventoried_on: string | null, windthrow_conditions: StandConditions | null, 
+/**
+ * Windthrow risk from 0 to 100, null without conditions
+ */
+windthrow_score: number | null, windthrow_class: RiskClass | null, created_at: string, updated_at: string, };
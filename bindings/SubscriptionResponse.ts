@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An organization's plan and subscription
+ */
+export type SubscriptionResponse = { org_id: string, 
+/**
+ * Plan the organization gets, `free` unless its subscription is active
+ */
+plan: string, 
+/**
+ * Stripe status of the subscription, e.g. `active` or `past_due`;
+ * `null` when the organization never subscribed
+ */
+status: string | null, 
+/**
+ * Plan subscribed to, which may differ from `plan` while inactive
+ */
+subscribed_plan: string | null, 
+/**
+ * Whether premium features are available
+ */
+premium: boolean, current_period_end: string | null, cancel_at_period_end: boolean, };
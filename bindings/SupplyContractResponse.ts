@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Supply contract response
+ */
+export type SupplyContractResponse = { id: string, customer_id: string, reference: string, product: string, volume_m3: number, price_per_m3: number, starts_on: string, ends_on: string, notes: string | null, created_by: string | null, created_at: string, updated_at: string, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * System metrics for detailed health information
+ */
+export type SystemMetrics = { 
+/**
+ * CPU usage percentage
+ */
+cpu_usage: number, 
+/**
+ * Memory usage in bytes
+ */
+memory_used: number, 
+/**
+ * Total memory in bytes
+ */
+memory_total: number, 
+/**
+ * Memory usage percentage
+ */
+memory_usage_percentage: number, 
+/**
+ * Number of active database connections
+ */
+db_active_connections: number, 
+/**
+ * Maximum database connections
+ */
+db_max_connections: number, };
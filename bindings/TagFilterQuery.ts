@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Tag filter query parameters, taken by list endpoints of taggable
+ * subjects
+ */
+export type TagFilterQuery = { 
+/**
+ * Comma separated tag ids
+ */
+tags: string | null, 
+/**
+ * `any` (the default) or `all` of the tags
+ */
+tag_match: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Tag response
+ */
+export type TagResponse = { id: string, name: string, color: string, created_at: string, updated_at: string, };
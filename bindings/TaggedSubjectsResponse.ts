@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Subjects of a type kept by a tag filter
+ */
+export type TaggedSubjectsResponse = { subject_type: string, subject_ids: Array<string>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Something a tag is attached to
+ */
+export type TaggingResponse = { 
+/**
+ * `stand`, `block`, `equipment` or `work_order`
+ */
+subject_type: string, subject_id: string, tagged_by: string | null, created_at: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TelemetryPointInput } from "./TelemetryPointInput";
+
+/**
+ * Data points uploaded together, such as a day buffered while offline
+ */
+export type TelemetryBatchInput = { points: Array<TelemetryPointInput>, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome of a telemetry upload
+ */
+export type TelemetryBatchResponse = { 
+/**
+ * Points in the request
+ */
+received: number, 
+/**
+ * Points stored, replacing any stored for the same machine and time
+ */
+stored: number, 
+/**
+ * Points repeating an earlier one of the request, of which the last
+ * was kept
+ */
+duplicates: number, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A data point reported by a machine
+ */
+export type TelemetryPointInput = { 
+/**
+ * The machine's own identifier, as configured on its telematics unit
+ */
+machine_id: string, 
+/**
+ * When the machine took the reading
+ */
+timestamp: string, 
+/**
+ * Engine hour meter reading
+ */
+engine_hours: number | null, 
+/**
+ * Fuel used according to the machine's counter, in litres
+ */
+fuel_used_l: number | null, 
+/**
+ * WGS 84 latitude, given with the longitude
+ */
+latitude: number | null, 
+/**
+ * WGS 84 longitude, given with the latitude
+ */
+longitude: number | null, };
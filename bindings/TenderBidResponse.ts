@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A captured bid
+ */
+export type TenderBidResponse = { id: string, parcel_id: string, bidder_name: string, bidder_email: string | null, price_per_m3: number, 
+/**
+ * Whether the price reaches the parcel's reserve, so the bid can be
+ * awarded; unset while bids are sealed
+ */
+meets_reserve: boolean | null, received_at: string, captured_by: string | null, created_at: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TenderParcelResponse } from "./TenderParcelResponse";
+import type { TenderResponse } from "./TenderResponse";
+
+/**
+ * A tender with its parcels
+ */
+export type TenderDetailsResponse = { tender: TenderResponse, parcels: Array<TenderParcelResponse>, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A parcel offered in a new tender
+ */
+export type TenderParcelInput = { 
+/**
+ * Harvest block the timber comes from
+ */
+block_id: string | null, 
+/**
+ * Product offered, e.g. `spruce sawlog`
+ */
+assortment: string, volume_m3: number, 
+/**
+ * Lowest price per cubic metre that can be awarded
+ */
+reserve_price: number | null, description: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A parcel offered in a tender
+ */
+export type TenderParcelResponse = { id: string, block_id: string | null, assortment: string, volume_m3: number, reserve_price: number | null, description: string | null, 
+/**
+ * Bids received so far; their prices stay sealed until the deadline
+ */
+bid_count: number, awarded_bid_id: string | null, };
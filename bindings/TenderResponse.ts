@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Tender response
+ */
+export type TenderResponse = { id: string, title: string, description: string | null, 
+/**
+ * `draft`, `open`, `awarded` or `cancelled`
+ */
+status: string, bid_deadline: string, 
+/**
+ * Whether bids stay hidden because the deadline has not passed
+ */
+bids_sealed: boolean, created_by: string | null, created_at: string, updated_at: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for handing an organization to another of its members
+ */
+export type TransferOwnershipInput = { 
+/**
+ * Verified, active member of the organization to take it over
+ */
+new_owner_id: string, };
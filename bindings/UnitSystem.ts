@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Units measurements are presented in; they are always stored metric
+ */
+export type UnitSystem = "metric" | "imperial";
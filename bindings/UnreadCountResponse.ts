@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Number of unread notifications, for the notification badge
+ */
+export type UnreadCountResponse = { unread_count: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for replacing a crew's details; members are managed separately
+ */
+export type UpdateCrewInput = { 
+/**
+ * Unique within the organization
+ */
+name: string, 
+/**
+ * User leading the crew, who need not be a member
+ */
+supervisor_id: string | null, 
+/**
+ * Machine ids the crew works with unless a job says otherwise
+ */
+default_equipment: Array<string>, };
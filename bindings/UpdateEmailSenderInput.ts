@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for setting an organization's email sender
+ */
+export type UpdateEmailSenderInput = { 
+/**
+ * Address mail is sent from; its domain must be verified with the mail provider
+ */
+from_address: string, from_name: string | null, reply_to: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for overriding a job's schedule
+ */
+export type UpdateJobScheduleInput = { 
+/**
+ * Cron expression (`sec min hour day month weekday`) or `off`; `null`
+ * restores the configured schedule
+ */
+schedule: string | null, };
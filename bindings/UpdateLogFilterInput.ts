@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Input for replacing the log filter
+ */
+export type UpdateLogFilterInput = { 
+/**
+ * `EnvFilter` directives, e.g. `info,rust_server::domain::optimization=debug`
+ */
+filter: string, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UnitSystem } from "./UnitSystem";
+
+/**
+ * Changes to an organization's settings; fields left out keep their value
+ */
+export type UpdateOrganizationSettingsInput = { default_units?: UnitSystem, 
+/**
+ * 1 for January to 12 for December
+ */
+fiscal_year_start_month?: number, 
+/**
+ * `UTC` or an offset from it, such as `-08:00` or `+05:30`
+ */
+timezone?: string, 
+/**
+ * Replaces the required safety forms
+ */
+required_safety_forms?: Array<string>, };
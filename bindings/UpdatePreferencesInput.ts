@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UnitSystem } from "./UnitSystem";
+
+/**
+ * Changes to the caller's preferences; fields left out keep their value
+ */
+export type UpdatePreferencesInput = { units?: UnitSystem, 
+/**
+ * `UTC` or an offset from it, such as `-08:00` or `+05:30`
+ */
+timezone?: string, 
+/**
+ * `en` or `fr`
+ */
+locale?: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Changes the caller makes to their profile; fields left out keep their
+ * value
+ *
+ * Role and organization aren't the caller's to change, so naming them is
+ * rejected rather than ignored.
+ */
+export type UpdateProfileInput = { first_name?: string, last_name?: string, 
+/**
+ * Such as `+1 (250) 555-0100`; an empty string removes it
+ */
+phone_number?: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Limits of an organization's plan; limits left out or `null` are unset
+ */
+export type UpdateQuotasInput = { 
+/**
+ * At least 1; unset allows any number of users
+ */
+max_users?: number, 
+/**
+ * Unset allows any number of active harvest blocks
+ */
+max_active_blocks?: number, 
+/**
+ * 1 to 3650; unset keeps telemetry for the default 90 days
+ */
+telemetry_retention_days?: number, };
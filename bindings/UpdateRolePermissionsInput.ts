@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Permission } from "./Permission";
+
+/**
+ * Input for replacing the permissions of a role
+ */
+export type UpdateRolePermissionsInput = { permissions: Array<Permission>, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for uploading a new document
+ */
+export type UploadDocumentQuery = { 
+/**
+ * `block`, `permit` or `certification`
+ */
+subject_type: string, subject_id: string, 
+/**
+ * Kind of document, e.g. `harvest_permit`
+ */
+category: string, 
+/**
+ * Title shown for the document, the file name when unset
+ */
+title: string | null, filename: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters for uploading a file to import
+ */
+export type UploadImportQuery = { 
+/**
+ * Import target the rows are loaded into
+ */
+target: string, 
+/**
+ * Name of the uploaded file; its extension (`.csv` or `.xlsx`) picks
+ * how the body is read
+ */
+filename: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UserEntity = { 
+/**
+ * The user's ID, as the base64url of its 16 bytes
+ */
+id: string, name: string, displayName: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A problem with one field of an imported row
+ */
+export type UserImportError = { field: string, 
+/**
+ * e.g. `INVALID_FORMAT` or `DUPLICATE`
+ */
+code: string, message: string, };
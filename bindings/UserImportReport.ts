@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UserImportRow } from "./UserImportRow";
+
+/**
+ * What became of each row of a user import
+ */
+export type UserImportReport = { 
+/**
+ * Users created and invited
+ */
+created: number, 
+/**
+ * Rows rejected
+ */
+failed: number, rows: Array<UserImportRow>, };
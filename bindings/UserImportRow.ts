@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UserImportError } from "./UserImportError";
+
+/**
+ * One row of a user import
+ */
+export type UserImportRow = { 
+/**
+ * Line of the file, the header being line 1
+ */
+line: number, email: string, 
+/**
+ * The user created, `null` when the row was rejected
+ */
+user_id: string | null, 
+/**
+ * Why the row was rejected, empty when it was imported
+ */
+errors: Array<UserImportError>, };
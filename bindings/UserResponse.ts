@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvatarResponse } from "./AvatarResponse";
+import type { Role } from "./Role";
+
+/**
+ * User response payload
+ */
+export type UserResponse = { id: string, first_name: string, last_name: string, email: string, phone_number: string, role: Role, org_id: string, 
+/**
+ * `false` once an admin deactivated the user
+ */
+is_active: boolean, 
+/**
+ * The manager or admin the user reports to, `null` for no one
+ */
+supervisor_id: string | null, 
+/**
+ * When an admin anonymized the user, who left, `null` otherwise
+ */
+anonymized_at: string | null, 
+/**
+ * Signed links to the user's avatar, `null` without one
+ */
+avatar: AvatarResponse | null, };
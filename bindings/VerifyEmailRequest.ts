@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Email verification request payload
+ */
+export type VerifyEmailRequest = { 
+/**
+ * Token from the verification link
+ */
+token: string, };
@@ -0,0 +1,17 @@
+-- Stub records for data moved out of the hot database into cold storage
+CREATE TABLE "archives" (
+    "id" UUID NOT NULL,
+    "dataset" VARCHAR(100) NOT NULL,
+    "storage_key" VARCHAR(1024) NOT NULL,
+    "record_count" BIGINT NOT NULL,
+    "byte_size" BIGINT NOT NULL,
+    "range_start" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "range_end" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "rehydrated_at" TIMESTAMP WITH TIME ZONE NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "deleted_at" TIMESTAMP WITH TIME ZONE NULL
+);
+ALTER TABLE "archives" ADD PRIMARY KEY("id");
+ALTER TABLE "archives" ADD CONSTRAINT "archives_storage_key_unique" UNIQUE("storage_key");
+CREATE INDEX "archives_dataset_range_index" ON "archives"("dataset", "range_start");
@@ -0,0 +1,14 @@
+-- Records exempt from the purge job while a hold is active
+CREATE TABLE "legal_holds" (
+    "id" UUID NOT NULL,
+    "entity_type" VARCHAR(100) NOT NULL,
+    "entity_id" UUID NOT NULL,
+    "reason" TEXT NOT NULL,
+    "placed_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "deleted_at" TIMESTAMP WITH TIME ZONE NULL
+);
+ALTER TABLE "legal_holds" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "legal_holds_active_entity_unique" ON "legal_holds"("entity_type", "entity_id") WHERE "deleted_at" IS NULL;
+ALTER TABLE "legal_holds" ADD CONSTRAINT "legal_holds_placed_by_foreign" FOREIGN KEY("placed_by") REFERENCES "users"("id") ON DELETE SET NULL;
@@ -0,0 +1,17 @@
+-- Schedule, lease and last-run status of recurring background jobs
+CREATE TABLE "scheduled_jobs" (
+    "name" VARCHAR(100) NOT NULL,
+    "schedule" VARCHAR(255) NULL,
+    "next_run_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "locked_by" VARCHAR(255) NULL,
+    "locked_until" TIMESTAMP WITH TIME ZONE NULL,
+    "last_started_at" TIMESTAMP WITH TIME ZONE NULL,
+    "last_finished_at" TIMESTAMP WITH TIME ZONE NULL,
+    "last_status" VARCHAR(20) NULL,
+    "last_error" TEXT NULL,
+    "last_duration_ms" BIGINT NULL,
+    "last_output" JSONB NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "scheduled_jobs" ADD PRIMARY KEY("name");
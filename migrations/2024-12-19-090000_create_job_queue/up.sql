@@ -0,0 +1,29 @@
+-- Durable background job queue
+CREATE TABLE "queued_jobs" (
+    "id" UUID NOT NULL,
+    "kind" VARCHAR(100) NOT NULL,
+    "payload" JSONB NOT NULL,
+    "attempts" INTEGER NOT NULL DEFAULT 0,
+    "max_attempts" INTEGER NOT NULL,
+    "run_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "locked_by" VARCHAR(255) NULL,
+    "locked_until" TIMESTAMP WITH TIME ZONE NULL,
+    "last_error" TEXT NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "queued_jobs" ADD PRIMARY KEY("id");
+CREATE INDEX "queued_jobs_kind_run_at_index" ON "queued_jobs"("kind", "run_at");
+
+-- Jobs that failed on every attempt, kept until requeued or discarded
+CREATE TABLE "dead_letter_jobs" (
+    "id" UUID NOT NULL,
+    "kind" VARCHAR(100) NOT NULL,
+    "payload" JSONB NOT NULL,
+    "attempts" INTEGER NOT NULL,
+    "last_error" TEXT NULL,
+    "failed_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "dead_letter_jobs" ADD PRIMARY KEY("id");
+CREATE INDEX "dead_letter_jobs_kind_failed_at_index" ON "dead_letter_jobs"("kind", "failed_at");
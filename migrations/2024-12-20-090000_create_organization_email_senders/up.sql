@@ -0,0 +1,11 @@
+-- Sender identity used for mail sent on behalf of an organization
+CREATE TABLE "organization_email_senders" (
+    "org_id" UUID NOT NULL,
+    "from_address" VARCHAR(255) NOT NULL,
+    "from_name" VARCHAR(255) NULL,
+    "reply_to" VARCHAR(255) NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "organization_email_senders" ADD PRIMARY KEY("org_id");
+ALTER TABLE "organization_email_senders" ADD CONSTRAINT "organization_email_senders_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
@@ -0,0 +1,18 @@
+-- In-app notifications shown in a user's notification center
+CREATE TABLE "notifications" (
+    "id" UUID NOT NULL,
+    "user_id" UUID NOT NULL,
+    "org_id" UUID NULL,
+    "kind" VARCHAR(100) NOT NULL,
+    "title" VARCHAR(255) NOT NULL,
+    "body" TEXT NULL,
+    "link" VARCHAR(2048) NULL,
+    "data" JSONB NULL,
+    "read_at" TIMESTAMP WITH TIME ZONE NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "notifications" ADD PRIMARY KEY("id");
+CREATE INDEX "notifications_user_id_created_at_index" ON "notifications"("user_id", "created_at" DESC);
+CREATE INDEX "notifications_user_id_unread_index" ON "notifications"("user_id") WHERE "read_at" IS NULL;
+ALTER TABLE "notifications" ADD CONSTRAINT "notifications_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
+ALTER TABLE "notifications" ADD CONSTRAINT "notifications_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
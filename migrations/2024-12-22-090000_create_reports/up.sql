@@ -0,0 +1,16 @@
+-- Saved report definitions, run on demand against an organization's data
+CREATE TABLE "reports" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "name" VARCHAR(255) NOT NULL,
+    "description" TEXT NULL,
+    "definition" JSONB NOT NULL,
+    "created_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "deleted_at" TIMESTAMP WITH TIME ZONE NULL
+);
+ALTER TABLE "reports" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "reports_org_id_name_unique" ON "reports"("org_id", "name") WHERE "deleted_at" IS NULL;
+ALTER TABLE "reports" ADD CONSTRAINT "reports_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "reports" ADD CONSTRAINT "reports_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
@@ -0,0 +1,38 @@
+-- Cron schedules emailing a report to a list of recipients, one per report
+CREATE TABLE "report_schedules" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "report_id" UUID NOT NULL,
+    "schedule" VARCHAR(255) NOT NULL,
+    "recipients" TEXT[] NOT NULL,
+    "format" VARCHAR(16) NOT NULL,
+    "parameters" JSONB NOT NULL DEFAULT '{}',
+    "next_run_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "created_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "report_schedules" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "report_schedules_report_id_unique" ON "report_schedules"("report_id");
+CREATE INDEX "report_schedules_next_run_at_index" ON "report_schedules"("next_run_at");
+ALTER TABLE "report_schedules" ADD CONSTRAINT "report_schedules_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "report_schedules" ADD CONSTRAINT "report_schedules_report_id_foreign" FOREIGN KEY("report_id") REFERENCES "reports"("id") ON DELETE CASCADE;
+ALTER TABLE "report_schedules" ADD CONSTRAINT "report_schedules_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
+
+-- Every scheduled run of a report, whether or not the mail went out
+CREATE TABLE "report_deliveries" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "report_id" UUID NOT NULL,
+    "recipients" TEXT[] NOT NULL,
+    "format" VARCHAR(16) NOT NULL,
+    "status" VARCHAR(16) NOT NULL,
+    "row_count" INTEGER NULL,
+    "file_key" TEXT NULL,
+    "error" TEXT NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "report_deliveries" ADD PRIMARY KEY("id");
+CREATE INDEX "report_deliveries_report_id_created_at_index" ON "report_deliveries"("report_id", "created_at");
+ALTER TABLE "report_deliveries" ADD CONSTRAINT "report_deliveries_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "report_deliveries" ADD CONSTRAINT "report_deliveries_report_id_foreign" FOREIGN KEY("report_id") REFERENCES "reports"("id") ON DELETE CASCADE;
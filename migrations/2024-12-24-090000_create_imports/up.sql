@@ -0,0 +1,24 @@
+-- Guided spreadsheet imports: the uploaded file, its column mapping and the
+-- outcome of the committed import
+CREATE TABLE "imports" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "target" VARCHAR(100) NOT NULL,
+    "filename" VARCHAR(255) NOT NULL,
+    "file_key" TEXT NOT NULL,
+    "headers" TEXT[] NOT NULL,
+    "mapping" JSONB NOT NULL DEFAULT '{}',
+    "status" VARCHAR(16) NOT NULL,
+    "total_rows" INTEGER NOT NULL,
+    "imported_rows" INTEGER NULL,
+    "failed_rows" INTEGER NULL,
+    "error_key" TEXT NULL,
+    "error" TEXT NULL,
+    "created_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "imports" ADD PRIMARY KEY("id");
+CREATE INDEX "imports_org_id_created_at_index" ON "imports"("org_id", "created_at");
+ALTER TABLE "imports" ADD CONSTRAINT "imports_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "imports" ADD CONSTRAINT "imports_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS "erp_exports";
+DROP TABLE IF EXISTS "erp_connections";
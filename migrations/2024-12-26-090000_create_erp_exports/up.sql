@@ -0,0 +1,43 @@
+-- ERP export connections of an organization and the exports delivered
+-- through them
+CREATE TABLE "erp_connections" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "name" VARCHAR(255) NOT NULL,
+    "connector" VARCHAR(50) NOT NULL,
+    "settings" JSONB NOT NULL DEFAULT '{}',
+    "mappings" JSONB NOT NULL DEFAULT '{}',
+    "schedule" VARCHAR(255) NULL,
+    "next_run_at" TIMESTAMP WITH TIME ZONE NULL,
+    "enabled" BOOLEAN NOT NULL DEFAULT TRUE,
+    "created_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "erp_connections" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "erp_connections_org_id_name_unique" ON "erp_connections"("org_id", "name");
+CREATE INDEX "erp_connections_next_run_at_index" ON "erp_connections"("next_run_at") WHERE "enabled";
+ALTER TABLE "erp_connections" ADD CONSTRAINT "erp_connections_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "erp_connections" ADD CONSTRAINT "erp_connections_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
+
+CREATE TABLE "erp_exports" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "connection_id" UUID NOT NULL,
+    "source" VARCHAR(100) NOT NULL,
+    "status" VARCHAR(16) NOT NULL,
+    "period_start" TIMESTAMP WITH TIME ZONE NULL,
+    "period_end" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "record_count" INTEGER NOT NULL,
+    "file_key" TEXT NOT NULL,
+    "remote_ref" TEXT NULL,
+    "attempts" INTEGER NOT NULL DEFAULT 0,
+    "error" TEXT NULL,
+    "delivered_at" TIMESTAMP WITH TIME ZONE NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "erp_exports" ADD PRIMARY KEY("id");
+CREATE INDEX "erp_exports_connection_id_source_period_end_index" ON "erp_exports"("connection_id", "source", "period_end");
+ALTER TABLE "erp_exports" ADD CONSTRAINT "erp_exports_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "erp_exports" ADD CONSTRAINT "erp_exports_connection_id_foreign" FOREIGN KEY("connection_id") REFERENCES "erp_connections"("id") ON DELETE CASCADE;
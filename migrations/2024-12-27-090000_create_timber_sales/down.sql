@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS "sale_contracts";
+ALTER TABLE IF EXISTS "tender_parcels" DROP CONSTRAINT IF EXISTS "tender_parcels_awarded_bid_id_foreign";
+DROP TABLE IF EXISTS "tender_bids";
+DROP TABLE IF EXISTS "tender_parcels";
+DROP TABLE IF EXISTS "timber_tenders";
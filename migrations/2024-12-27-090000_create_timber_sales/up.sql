@@ -0,0 +1,75 @@
+-- Timber sale tenders with their offered parcels, the sealed bids received
+-- for them and the sale contracts awarded from those bids
+CREATE TABLE "timber_tenders" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "title" VARCHAR(255) NOT NULL,
+    "description" TEXT NULL,
+    "status" VARCHAR(16) NOT NULL,
+    "bid_deadline" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "created_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "timber_tenders" ADD PRIMARY KEY("id");
+CREATE INDEX "timber_tenders_org_id_created_at_index" ON "timber_tenders"("org_id", "created_at");
+ALTER TABLE "timber_tenders" ADD CONSTRAINT "timber_tenders_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "timber_tenders" ADD CONSTRAINT "timber_tenders_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
+
+-- "block_id" gets its foreign key once harvest blocks are stored
+CREATE TABLE "tender_parcels" (
+    "id" UUID NOT NULL,
+    "tender_id" UUID NOT NULL,
+    "position" INTEGER NOT NULL,
+    "block_id" UUID NULL,
+    "assortment" VARCHAR(100) NOT NULL,
+    "volume_m3" DOUBLE PRECISION NOT NULL,
+    "reserve_price" DOUBLE PRECISION NULL,
+    "description" TEXT NULL,
+    "awarded_bid_id" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "tender_parcels" ADD PRIMARY KEY("id");
+CREATE INDEX "tender_parcels_tender_id_position_index" ON "tender_parcels"("tender_id", "position");
+ALTER TABLE "tender_parcels" ADD CONSTRAINT "tender_parcels_tender_id_foreign" FOREIGN KEY("tender_id") REFERENCES "timber_tenders"("id") ON DELETE CASCADE;
+
+CREATE TABLE "tender_bids" (
+    "id" UUID NOT NULL,
+    "tender_id" UUID NOT NULL,
+    "parcel_id" UUID NOT NULL,
+    "bidder_name" VARCHAR(255) NOT NULL,
+    "bidder_email" VARCHAR(255) NULL,
+    "price_per_m3" DOUBLE PRECISION NOT NULL,
+    "received_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "captured_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "tender_bids" ADD PRIMARY KEY("id");
+CREATE INDEX "tender_bids_parcel_id_index" ON "tender_bids"("parcel_id");
+ALTER TABLE "tender_bids" ADD CONSTRAINT "tender_bids_tender_id_foreign" FOREIGN KEY("tender_id") REFERENCES "timber_tenders"("id") ON DELETE CASCADE;
+ALTER TABLE "tender_bids" ADD CONSTRAINT "tender_bids_parcel_id_foreign" FOREIGN KEY("parcel_id") REFERENCES "tender_parcels"("id") ON DELETE CASCADE;
+ALTER TABLE "tender_bids" ADD CONSTRAINT "tender_bids_captured_by_foreign" FOREIGN KEY("captured_by") REFERENCES "users"("id") ON DELETE SET NULL;
+ALTER TABLE "tender_parcels" ADD CONSTRAINT "tender_parcels_awarded_bid_id_foreign" FOREIGN KEY("awarded_bid_id") REFERENCES "tender_bids"("id");
+
+CREATE TABLE "sale_contracts" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "tender_id" UUID NOT NULL,
+    "parcel_id" UUID NOT NULL,
+    "bid_id" UUID NOT NULL,
+    "buyer_name" VARCHAR(255) NOT NULL,
+    "block_id" UUID NULL,
+    "assortment" VARCHAR(100) NOT NULL,
+    "volume_m3" DOUBLE PRECISION NOT NULL,
+    "price_per_m3" DOUBLE PRECISION NOT NULL,
+    "created_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "sale_contracts" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "sale_contracts_parcel_id_unique" ON "sale_contracts"("parcel_id");
+CREATE INDEX "sale_contracts_org_id_block_id_index" ON "sale_contracts"("org_id", "block_id");
+ALTER TABLE "sale_contracts" ADD CONSTRAINT "sale_contracts_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "sale_contracts" ADD CONSTRAINT "sale_contracts_tender_id_foreign" FOREIGN KEY("tender_id") REFERENCES "timber_tenders"("id") ON DELETE CASCADE;
+ALTER TABLE "sale_contracts" ADD CONSTRAINT "sale_contracts_parcel_id_foreign" FOREIGN KEY("parcel_id") REFERENCES "tender_parcels"("id") ON DELETE CASCADE;
+ALTER TABLE "sale_contracts" ADD CONSTRAINT "sale_contracts_bid_id_foreign" FOREIGN KEY("bid_id") REFERENCES "tender_bids"("id") ON DELETE CASCADE;
+ALTER TABLE "sale_contracts" ADD CONSTRAINT "sale_contracts_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
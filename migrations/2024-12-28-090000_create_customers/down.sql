@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS "supply_contracts";
+DROP TABLE IF EXISTS "customers";
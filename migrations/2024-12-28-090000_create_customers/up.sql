@@ -0,0 +1,41 @@
+-- Customers buying an organization's timber and their supply contracts
+CREATE TABLE "customers" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "name" VARCHAR(255) NOT NULL,
+    "contact_name" VARCHAR(255) NULL,
+    "email" VARCHAR(255) NULL,
+    "phone_number" VARCHAR(255) NULL,
+    "address" TEXT NULL,
+    "notes" TEXT NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "customers" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "customers_org_id_name_unique" ON "customers"("org_id", "name");
+ALTER TABLE "customers" ADD CONSTRAINT "customers_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+
+-- A customer cannot be deleted while it has contracts
+CREATE TABLE "supply_contracts" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "customer_id" UUID NOT NULL,
+    "reference" VARCHAR(100) NOT NULL,
+    "product" VARCHAR(100) NOT NULL,
+    "volume_m3" DOUBLE PRECISION NOT NULL,
+    "price_per_m3" DOUBLE PRECISION NOT NULL,
+    "starts_on" DATE NOT NULL,
+    "ends_on" DATE NOT NULL,
+    "notes" TEXT NULL,
+    "created_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT "supply_contracts_period_check" CHECK ("starts_on" <= "ends_on")
+);
+ALTER TABLE "supply_contracts" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "supply_contracts_org_id_reference_unique" ON "supply_contracts"("org_id", "reference");
+CREATE INDEX "supply_contracts_customer_id_index" ON "supply_contracts"("customer_id");
+CREATE INDEX "supply_contracts_org_id_ends_on_index" ON "supply_contracts"("org_id", "ends_on");
+ALTER TABLE "supply_contracts" ADD CONSTRAINT "supply_contracts_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "supply_contracts" ADD CONSTRAINT "supply_contracts_customer_id_foreign" FOREIGN KEY("customer_id") REFERENCES "customers"("id") ON DELETE RESTRICT;
+ALTER TABLE "supply_contracts" ADD CONSTRAINT "supply_contracts_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
@@ -0,0 +1,16 @@
+-- Sign-offs on harvest block packages, one per step of the approval chain
+CREATE TABLE "block_signoffs" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "block_id" UUID NOT NULL,
+    "step" VARCHAR(50) NOT NULL,
+    "signer_id" UUID NULL,
+    "signer_name" VARCHAR(255) NOT NULL,
+    "document_hash" VARCHAR(64) NOT NULL,
+    "signed_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "block_signoffs" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "block_signoffs_org_id_block_id_step_unique" ON "block_signoffs"("org_id", "block_id", "step");
+ALTER TABLE "block_signoffs" ADD CONSTRAINT "block_signoffs_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+-- The signer's name is kept as signed when the account goes away
+ALTER TABLE "block_signoffs" ADD CONSTRAINT "block_signoffs_signer_id_foreign" FOREIGN KEY("signer_id") REFERENCES "users"("id") ON DELETE SET NULL;
@@ -0,0 +1,36 @@
+-- Documents attached to harvest blocks and permits, kept as versions
+CREATE TABLE "documents" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "subject_type" VARCHAR(16) NOT NULL,
+    "subject_id" UUID NOT NULL,
+    "category" VARCHAR(100) NOT NULL,
+    "title" VARCHAR(255) NOT NULL,
+    "current_version" INTEGER NOT NULL,
+    "retain_until" TIMESTAMP WITH TIME ZONE NULL,
+    "created_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "documents" ADD PRIMARY KEY("id");
+CREATE INDEX "documents_org_id_subject_index" ON "documents"("org_id", "subject_type", "subject_id");
+ALTER TABLE "documents" ADD CONSTRAINT "documents_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "documents" ADD CONSTRAINT "documents_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
+
+-- Versions are never changed; restoring one adds a new version with its file
+CREATE TABLE "document_versions" (
+    "id" UUID NOT NULL,
+    "document_id" UUID NOT NULL,
+    "version" INTEGER NOT NULL,
+    "filename" VARCHAR(255) NOT NULL,
+    "content_type" VARCHAR(255) NOT NULL,
+    "byte_size" BIGINT NOT NULL,
+    "file_key" TEXT NOT NULL,
+    "restored_from" INTEGER NULL,
+    "uploaded_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "document_versions" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "document_versions_document_id_version_unique" ON "document_versions"("document_id", "version");
+ALTER TABLE "document_versions" ADD CONSTRAINT "document_versions_document_id_foreign" FOREIGN KEY("document_id") REFERENCES "documents"("id") ON DELETE CASCADE;
+ALTER TABLE "document_versions" ADD CONSTRAINT "document_versions_uploaded_by_foreign" FOREIGN KEY("uploaded_by") REFERENCES "users"("id") ON DELETE SET NULL;
@@ -0,0 +1,28 @@
+-- Organization-defined tags and what they are attached to
+CREATE TABLE "tags" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "name" VARCHAR(50) NOT NULL,
+    "color" VARCHAR(7) NOT NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "tags" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "tags_org_id_name_unique" ON "tags"("org_id", LOWER("name"));
+ALTER TABLE "tags" ADD CONSTRAINT "tags_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+
+-- Subjects are stands, blocks, equipment and work orders, told apart by
+-- subject_type, so subject_id has no foreign key
+CREATE TABLE "taggings" (
+    "tag_id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "subject_type" VARCHAR(16) NOT NULL,
+    "subject_id" UUID NOT NULL,
+    "tagged_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "taggings" ADD PRIMARY KEY("tag_id", "subject_type", "subject_id");
+CREATE INDEX "taggings_org_id_subject_index" ON "taggings"("org_id", "subject_type", "subject_id");
+ALTER TABLE "taggings" ADD CONSTRAINT "taggings_tag_id_foreign" FOREIGN KEY("tag_id") REFERENCES "tags"("id") ON DELETE CASCADE;
+ALTER TABLE "taggings" ADD CONSTRAINT "taggings_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "taggings" ADD CONSTRAINT "taggings_tagged_by_foreign" FOREIGN KEY("tagged_by") REFERENCES "users"("id") ON DELETE SET NULL;
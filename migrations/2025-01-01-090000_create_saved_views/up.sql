@@ -0,0 +1,32 @@
+-- Named filter, sort and column configurations of list endpoints, owned by
+-- a user and optionally shared with the organization
+CREATE TABLE "saved_views" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "user_id" UUID NOT NULL,
+    "resource" VARCHAR(50) NOT NULL,
+    "name" VARCHAR(100) NOT NULL,
+    "filters" JSONB NOT NULL DEFAULT '{}',
+    "sort" TEXT[] NOT NULL DEFAULT '{}',
+    "columns" TEXT[] NOT NULL DEFAULT '{}',
+    "shared" BOOLEAN NOT NULL DEFAULT FALSE,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "saved_views" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "saved_views_user_id_resource_name_unique" ON "saved_views"("user_id", "resource", "name");
+CREATE INDEX "saved_views_org_id_resource_index" ON "saved_views"("org_id", "resource");
+ALTER TABLE "saved_views" ADD CONSTRAINT "saved_views_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "saved_views" ADD CONSTRAINT "saved_views_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
+
+-- The view each user opens a resource's list with
+CREATE TABLE "saved_view_defaults" (
+    "user_id" UUID NOT NULL,
+    "resource" VARCHAR(50) NOT NULL,
+    "view_id" UUID NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "saved_view_defaults" ADD PRIMARY KEY("user_id", "resource");
+CREATE INDEX "saved_view_defaults_view_id_index" ON "saved_view_defaults"("view_id");
+ALTER TABLE "saved_view_defaults" ADD CONSTRAINT "saved_view_defaults_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
+ALTER TABLE "saved_view_defaults" ADD CONSTRAINT "saved_view_defaults_view_id_foreign" FOREIGN KEY("view_id") REFERENCES "saved_views"("id") ON DELETE CASCADE;
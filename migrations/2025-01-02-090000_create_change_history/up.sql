@@ -0,0 +1,16 @@
+-- Field-level changes of records, one row per changed field
+CREATE TABLE "change_history" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "resource" VARCHAR(50) NOT NULL,
+    "record_id" UUID NOT NULL,
+    "field" VARCHAR(100) NOT NULL,
+    "old_value" JSONB NULL,
+    "new_value" JSONB NULL,
+    "changed_by" UUID NULL,
+    "changed_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "change_history" ADD PRIMARY KEY("id");
+CREATE INDEX "change_history_record_index" ON "change_history"("org_id", "resource", "record_id", "changed_at");
+ALTER TABLE "change_history" ADD CONSTRAINT "change_history_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "change_history" ADD CONSTRAINT "change_history_changed_by_foreign" FOREIGN KEY("changed_by") REFERENCES "users"("id") ON DELETE SET NULL;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS "users_phone_number_unique";
+ALTER TABLE "users" ADD CONSTRAINT "users_phone_number_unique" UNIQUE("phone_number");
+DROP TABLE IF EXISTS "user_identities";
+DROP TABLE IF EXISTS "organization_sso_domains";
@@ -0,0 +1,27 @@
+-- Email domains whose users join an organization on their first single sign-on
+CREATE TABLE "organization_sso_domains" (
+    "domain" VARCHAR(255) NOT NULL,
+    "org_id" UUID NOT NULL,
+    "role" user_role NOT NULL DEFAULT 'Operator',
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "organization_sso_domains" ADD PRIMARY KEY("domain");
+ALTER TABLE "organization_sso_domains" ADD CONSTRAINT "organization_sso_domains_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+CREATE INDEX "organization_sso_domains_org_id_index" ON "organization_sso_domains"("org_id");
+
+-- Accounts at identity providers, linked to the users they sign in as
+CREATE TABLE "user_identities" (
+    "provider" VARCHAR(50) NOT NULL,
+    "subject" VARCHAR(255) NOT NULL,
+    "user_id" UUID NOT NULL,
+    "email" VARCHAR(255) NOT NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "last_login_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "user_identities" ADD PRIMARY KEY("provider", "subject");
+ALTER TABLE "user_identities" ADD CONSTRAINT "user_identities_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
+CREATE INDEX "user_identities_user_id_index" ON "user_identities"("user_id");
+
+-- Users provisioned through single sign-on have no phone number
+ALTER TABLE "users" DROP CONSTRAINT "users_phone_number_unique";
+CREATE UNIQUE INDEX "users_phone_number_unique" ON "users"("phone_number") WHERE "phone_number" <> '';
@@ -0,0 +1,22 @@
+-- Permissions granted to each role; admins hold every permission implicitly
+CREATE TABLE "role_permissions" (
+    "role" user_role NOT NULL,
+    "permission" VARCHAR(100) NOT NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+ALTER TABLE "role_permissions" ADD PRIMARY KEY("role", "permission");
+
+-- Managers keep the access the role-guarded routes gave them
+INSERT INTO "role_permissions" ("role", "permission") VALUES
+    ('Manager', 'customers:read'),
+    ('Manager', 'customers:write'),
+    ('Manager', 'timber_sales:read'),
+    ('Manager', 'timber_sales:write'),
+    ('Manager', 'reports:read'),
+    ('Manager', 'reports:write'),
+    ('Manager', 'imports:read'),
+    ('Manager', 'imports:write'),
+    ('Manager', 'tags:read'),
+    ('Manager', 'tags:write'),
+    ('Manager', 'erp:read'),
+    ('Manager', 'erp:write');
@@ -0,0 +1,16 @@
+-- Single-use links that log a user in without a password
+CREATE TABLE "magic_link_tokens" (
+    "id" UUID NOT NULL,
+    "token" VARCHAR(255) NOT NULL,
+    "user_id" UUID NOT NULL,
+    -- Device the link was requested from, the only one that may redeem it
+    "device_id" VARCHAR(255) NULL,
+    "expires_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "deleted_at" TIMESTAMP WITH TIME ZONE NULL
+);
+ALTER TABLE "magic_link_tokens" ADD PRIMARY KEY("id");
+ALTER TABLE "magic_link_tokens" ADD CONSTRAINT "magic_link_tokens_token_unique" UNIQUE("token");
+CREATE INDEX "magic_link_tokens_user_id_index" ON "magic_link_tokens"("user_id");
+ALTER TABLE "magic_link_tokens" ADD CONSTRAINT "magic_link_tokens_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
@@ -0,0 +1,18 @@
+-- Devices users have logged in from, by the ID the client generated
+CREATE TABLE "devices" (
+    "id" UUID NOT NULL,
+    "user_id" UUID NOT NULL,
+    "device_id" VARCHAR(255) NOT NULL,
+    -- Name the user gave the device, e.g. "Harvester tablet"
+    "name" VARCHAR(255) NULL,
+    "last_seen_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "deleted_at" TIMESTAMP WITH TIME ZONE NULL
+);
+ALTER TABLE "devices" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "devices_user_id_device_id_unique" ON "devices"("user_id", "device_id") WHERE "deleted_at" IS NULL;
+ALTER TABLE "devices" ADD CONSTRAINT "devices_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
+
+-- Device a refresh token was issued to, revoked along with it
+ALTER TABLE "refresh_tokens" ADD COLUMN "device_id" VARCHAR(255) NULL;
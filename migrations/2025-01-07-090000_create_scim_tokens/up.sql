@@ -0,0 +1,17 @@
+-- Long-lived tokens identity providers provision an organization's users with
+CREATE TABLE "scim_tokens" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "name" VARCHAR(100) NOT NULL,
+    -- SHA-256 of the token, which is only shown when it is created
+    "token_hash" VARCHAR(64) NOT NULL,
+    "created_by" UUID NULL,
+    "last_used_at" TIMESTAMP WITH TIME ZONE NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "revoked_at" TIMESTAMP WITH TIME ZONE NULL
+);
+ALTER TABLE "scim_tokens" ADD PRIMARY KEY("id");
+ALTER TABLE "scim_tokens" ADD CONSTRAINT "scim_tokens_token_hash_unique" UNIQUE("token_hash");
+CREATE INDEX "scim_tokens_org_id_index" ON "scim_tokens"("org_id");
+ALTER TABLE "scim_tokens" ADD CONSTRAINT "scim_tokens_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "scim_tokens" ADD CONSTRAINT "scim_tokens_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
@@ -0,0 +1,21 @@
+-- Logins, token refreshes, password changes and logouts, for users and
+-- admins to review
+CREATE TABLE "auth_events" (
+    "id" UUID NOT NULL,
+    -- NULL for failed logins with an email no user has
+    "user_id" UUID NULL,
+    -- e.g. login_succeeded, login_failed, token_refreshed, password_changed
+    "event" VARCHAR(50) NOT NULL,
+    -- How the user authenticated: password, magic_link or sso:<provider>
+    "method" VARCHAR(100) NULL,
+    -- Email a failed login was attempted with
+    "email" VARCHAR(255) NULL,
+    -- Why the attempt failed
+    "reason" VARCHAR(255) NULL,
+    "ip_address" VARCHAR(45) NULL,
+    "user_agent" VARCHAR(512) NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "auth_events" ADD PRIMARY KEY("id");
+CREATE INDEX "auth_events_user_id_created_at_index" ON "auth_events"("user_id", "created_at" DESC);
+ALTER TABLE "auth_events" ADD CONSTRAINT "auth_events_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
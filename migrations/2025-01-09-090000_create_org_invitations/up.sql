@@ -0,0 +1,19 @@
+-- Invitations to join an organization, accepted by registering with the
+-- signed token mailed to the invitee
+CREATE TABLE "org_invitations" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "email" VARCHAR(255) NOT NULL,
+    "role" user_role NOT NULL,
+    "invited_by" UUID NULL,
+    "expires_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- Set when the invitee registers, after which the token no longer works
+    "accepted_at" TIMESTAMP WITH TIME ZONE NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "deleted_at" TIMESTAMP WITH TIME ZONE NULL
+);
+ALTER TABLE "org_invitations" ADD PRIMARY KEY("id");
+CREATE INDEX "org_invitations_org_id_email_index" ON "org_invitations"("org_id", "email");
+ALTER TABLE "org_invitations" ADD CONSTRAINT "org_invitations_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "org_invitations" ADD CONSTRAINT "org_invitations_invited_by_foreign" FOREIGN KEY("invited_by") REFERENCES "users"("id") ON DELETE SET NULL;
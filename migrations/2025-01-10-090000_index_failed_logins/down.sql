@@ -0,0 +1 @@
+DROP INDEX IF EXISTS "auth_events_failed_logins_index";
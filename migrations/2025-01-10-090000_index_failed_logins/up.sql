@@ -0,0 +1,3 @@
+-- Failed logins are counted over a recent window to decide when logging in
+-- needs a CAPTCHA
+CREATE INDEX "auth_events_failed_logins_index" ON "auth_events"("created_at") WHERE "event" = 'login_failed';
@@ -0,0 +1,20 @@
+-- WebAuthn credentials users log in with instead of a password
+CREATE TABLE "passkeys" (
+    "id" UUID NOT NULL,
+    "user_id" UUID NOT NULL,
+    -- Credential ID the authenticator chose, base64url
+    "credential_id" VARCHAR(1024) NOT NULL,
+    -- COSE_Key the authenticator returned at registration
+    "public_key" BYTEA NOT NULL,
+    -- Signature counter of the last assertion, 0 if the authenticator keeps none
+    "sign_count" BIGINT NOT NULL,
+    -- Name the user gave the passkey, e.g. "Office laptop"
+    "name" VARCHAR(255) NOT NULL,
+    "last_used_at" TIMESTAMP WITH TIME ZONE NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "passkeys" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "passkeys_credential_id_unique" ON "passkeys"("credential_id");
+CREATE INDEX "passkeys_user_id_index" ON "passkeys"("user_id");
+ALTER TABLE "passkeys" ADD CONSTRAINT "passkeys_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
@@ -0,0 +1,29 @@
+-- Roles organizations define on top of the built-in ones, each granting
+-- its permissions to the users it is assigned to
+CREATE TABLE "roles" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "name" VARCHAR(100) NOT NULL,
+    "description" TEXT NULL,
+    -- Permission names, e.g. customers:write
+    "permissions" TEXT[] NOT NULL DEFAULT '{}',
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "roles" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "roles_org_id_name_unique" ON "roles"("org_id", LOWER("name"));
+ALTER TABLE "roles" ADD CONSTRAINT "roles_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+
+-- Users hold the permissions of their built-in role and of every custom
+-- role assigned to them
+CREATE TABLE "user_roles" (
+    "user_id" UUID NOT NULL,
+    "role_id" UUID NOT NULL,
+    "assigned_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "user_roles" ADD PRIMARY KEY("user_id", "role_id");
+CREATE INDEX "user_roles_role_id_index" ON "user_roles"("role_id");
+ALTER TABLE "user_roles" ADD CONSTRAINT "user_roles_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
+ALTER TABLE "user_roles" ADD CONSTRAINT "user_roles_role_id_foreign" FOREIGN KEY("role_id") REFERENCES "roles"("id") ON DELETE CASCADE;
+ALTER TABLE "user_roles" ADD CONSTRAINT "user_roles_assigned_by_foreign" FOREIGN KEY("assigned_by") REFERENCES "users"("id") ON DELETE SET NULL;
@@ -0,0 +1 @@
+ALTER TABLE "refresh_tokens" DROP COLUMN IF EXISTS "session_expires_at";
@@ -0,0 +1,5 @@
+-- When the session a refresh token renews ends, however often it is
+-- refreshed; sessions already open get the default 30 days
+ALTER TABLE "refresh_tokens" ADD COLUMN "session_expires_at" TIMESTAMP WITH TIME ZONE NULL;
+UPDATE "refresh_tokens" SET "session_expires_at" = "created_at" + INTERVAL '30 days';
+ALTER TABLE "refresh_tokens" ALTER COLUMN "session_expires_at" SET NOT NULL;
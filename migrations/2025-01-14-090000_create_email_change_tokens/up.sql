@@ -0,0 +1,16 @@
+-- Pending changes of a user's email address, confirmed from the new address
+CREATE TABLE "email_change_tokens" (
+    "id" UUID NOT NULL,
+    "token" VARCHAR(255) NOT NULL,
+    "user_id" UUID NOT NULL,
+    -- Address the user asked to switch to, theirs once the link is followed
+    "new_email" VARCHAR(255) NOT NULL,
+    "expires_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "deleted_at" TIMESTAMP WITH TIME ZONE NULL
+);
+ALTER TABLE "email_change_tokens" ADD PRIMARY KEY("id");
+ALTER TABLE "email_change_tokens" ADD CONSTRAINT "email_change_tokens_token_unique" UNIQUE("token");
+CREATE INDEX "email_change_tokens_user_id_index" ON "email_change_tokens"("user_id");
+ALTER TABLE "email_change_tokens" ADD CONSTRAINT "email_change_tokens_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS "auth_events_user_id_event_index";
+ALTER TABLE "auth_events" DROP COLUMN "device_id";
+ALTER TABLE "auth_events" DROP COLUMN "asn";
+ALTER TABLE "auth_events" DROP COLUMN "country";
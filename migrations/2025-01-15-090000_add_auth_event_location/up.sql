@@ -0,0 +1,6 @@
+-- Where and on what a login happened, so logins from somewhere new can be
+-- told apart from familiar ones
+ALTER TABLE "auth_events" ADD COLUMN "country" VARCHAR(2) NULL;
+ALTER TABLE "auth_events" ADD COLUMN "asn" VARCHAR(20) NULL;
+ALTER TABLE "auth_events" ADD COLUMN "device_id" VARCHAR(255) NULL;
+CREATE INDEX "auth_events_user_id_event_index" ON "auth_events"("user_id", "event");
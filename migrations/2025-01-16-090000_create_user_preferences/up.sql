@@ -0,0 +1,12 @@
+-- How each user wants measurements and times presented; users without a
+-- row get the defaults
+CREATE TABLE "user_preferences" (
+    "user_id" UUID NOT NULL,
+    "units" VARCHAR(16) NOT NULL DEFAULT 'metric',
+    "timezone" VARCHAR(16) NOT NULL DEFAULT 'UTC',
+    "locale" VARCHAR(16) NOT NULL DEFAULT 'en',
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "user_preferences" ADD PRIMARY KEY("user_id");
+ALTER TABLE "user_preferences" ADD CONSTRAINT "user_preferences_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
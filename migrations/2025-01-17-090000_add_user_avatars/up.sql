@@ -0,0 +1,3 @@
+-- When the user last uploaded an avatar; its sizes are kept in object
+-- storage under avatars/<user_id>/
+ALTER TABLE "users" ADD COLUMN "avatar_updated_at" TIMESTAMP WITH TIME ZONE NULL;
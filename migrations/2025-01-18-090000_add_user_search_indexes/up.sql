@@ -0,0 +1,6 @@
+-- Trigram indexes behind the organization user search, which matches
+-- names and emails by substring and by similarity
+CREATE EXTENSION IF NOT EXISTS "pg_trgm";
+CREATE INDEX "users_first_name_trgm_index" ON "users" USING GIN ("first_name" gin_trgm_ops);
+CREATE INDEX "users_last_name_trgm_index" ON "users" USING GIN ("last_name" gin_trgm_ops);
+CREATE INDEX "users_email_trgm_index" ON "users" USING GIN ("email" gin_trgm_ops);
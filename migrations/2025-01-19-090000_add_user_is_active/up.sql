@@ -0,0 +1,3 @@
+-- Deactivated users keep their records but can't sign in; unlike removed
+-- (soft deleted) users they are still listed as members
+ALTER TABLE "users" ADD COLUMN "is_active" BOOLEAN NOT NULL DEFAULT TRUE;
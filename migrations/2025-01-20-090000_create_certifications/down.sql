@@ -0,0 +1,2 @@
+DELETE FROM "role_permissions" WHERE "permission" IN ('certifications:read', 'certifications:write');
+DROP TABLE IF EXISTS "certifications";
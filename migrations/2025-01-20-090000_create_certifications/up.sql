@@ -0,0 +1,32 @@
+-- Certifications operators hold, such as a faller certification or first
+-- aid, with the day they expire
+CREATE TABLE "certifications" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "user_id" UUID NOT NULL,
+    -- faller, first_aid, dangerous_goods or other
+    "kind" VARCHAR(32) NOT NULL,
+    "name" VARCHAR(255) NULL,
+    "certificate_number" VARCHAR(100) NULL,
+    "issuer" VARCHAR(255) NULL,
+    "issued_on" DATE NOT NULL,
+    -- NULL when the certification doesn't expire
+    "expires_on" DATE NULL,
+    -- When supervisors were told of the coming expiry; cleared when
+    -- expires_on changes
+    "expiry_notified_at" TIMESTAMP WITH TIME ZONE NULL,
+    "created_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "certifications" ADD PRIMARY KEY("id");
+CREATE INDEX "certifications_org_id_user_id_index" ON "certifications"("org_id", "user_id");
+CREATE INDEX "certifications_expires_on_index" ON "certifications"("expires_on") WHERE "expires_on" IS NOT NULL;
+ALTER TABLE "certifications" ADD CONSTRAINT "certifications_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "certifications" ADD CONSTRAINT "certifications_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
+ALTER TABLE "certifications" ADD CONSTRAINT "certifications_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
+
+INSERT INTO "role_permissions" ("role", "permission") VALUES
+    ('Manager', 'certifications:read'),
+    ('Manager', 'certifications:write')
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,20 @@
+-- Significant actions taken by users, such as signing off a block or
+-- uploading a document, for supervisors to review
+CREATE TABLE "user_activities" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "user_id" UUID NOT NULL,
+    -- e.g. block_signed_off or document_uploaded
+    "action" VARCHAR(64) NOT NULL,
+    -- What the action was taken on, e.g. block or document; subjects are
+    -- of several tables, so subject_id has no foreign key
+    "subject_type" VARCHAR(32) NOT NULL,
+    "subject_id" UUID NOT NULL,
+    "summary" VARCHAR(255) NOT NULL,
+    "data" JSONB NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "user_activities" ADD PRIMARY KEY("id");
+CREATE INDEX "user_activities_user_id_created_at_index" ON "user_activities"("user_id", "created_at" DESC);
+ALTER TABLE "user_activities" ADD CONSTRAINT "user_activities_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "user_activities" ADD CONSTRAINT "user_activities_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
@@ -0,0 +1,4 @@
+-- Reporting lines: who each user reports to
+ALTER TABLE users ADD COLUMN supervisor_id UUID NULL REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_users_supervisor_id ON users(supervisor_id) WHERE supervisor_id IS NOT NULL;
@@ -0,0 +1,2 @@
+-- When a departed user's personal data was scrubbed
+ALTER TABLE users ADD COLUMN anonymized_at TIMESTAMPTZ NULL;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS "devices_last_heartbeat_at_index";
+ALTER TABLE "devices" DROP COLUMN IF EXISTS "last_heartbeat_at";
+ALTER TABLE "devices" DROP COLUMN IF EXISTS "battery_level";
+ALTER TABLE "devices" DROP COLUMN IF EXISTS "app_version";
@@ -0,0 +1,7 @@
+-- What a field device last reported in its heartbeat
+ALTER TABLE "devices" ADD COLUMN "app_version" VARCHAR(50) NULL;
+-- Percent, 0 to 100
+ALTER TABLE "devices" ADD COLUMN "battery_level" SMALLINT NULL CHECK ("battery_level" BETWEEN 0 AND 100);
+ALTER TABLE "devices" ADD COLUMN "last_heartbeat_at" TIMESTAMP WITH TIME ZONE NULL;
+
+CREATE INDEX "devices_last_heartbeat_at_index" ON "devices"("last_heartbeat_at") WHERE "deleted_at" IS NULL;
@@ -0,0 +1,4 @@
+-- Organization hierarchy: companies own divisions, divisions own operating areas
+ALTER TABLE organizations ADD COLUMN parent_id UUID NULL REFERENCES organizations(id) ON DELETE RESTRICT;
+
+CREATE INDEX idx_organizations_parent_id ON organizations(parent_id) WHERE parent_id IS NOT NULL;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS ownership_transfers;
+ALTER TABLE organizations DROP COLUMN IF EXISTS owner_id;
@@ -0,0 +1,18 @@
+-- The admin owning each organization, and transfers of ownership awaiting the new owner's confirmation
+ALTER TABLE organizations ADD COLUMN owner_id UUID NULL REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE TABLE ownership_transfers (
+    id UUID PRIMARY KEY,
+    org_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    from_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    to_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    accepted_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    deleted_at TIMESTAMPTZ NULL
+);
+
+-- At most one transfer of an organization is pending
+CREATE UNIQUE INDEX idx_ownership_transfers_pending ON ownership_transfers(org_id)
+    WHERE accepted_at IS NULL AND deleted_at IS NULL;
@@ -0,0 +1,9 @@
+-- Plan limits of an organization; NULL leaves a limit unset
+CREATE TABLE organization_quotas (
+    org_id UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    max_users INTEGER NULL CHECK (max_users >= 1),
+    max_active_blocks INTEGER NULL CHECK (max_active_blocks >= 0),
+    telemetry_retention_days INTEGER NULL CHECK (telemetry_retention_days >= 1),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,20 @@
+-- Stripe subscription of an organization; organizations without one are on
+-- the free plan
+CREATE TABLE subscriptions (
+    org_id UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    plan VARCHAR(32) NOT NULL,
+    status VARCHAR(32) NOT NULL,
+    stripe_customer_id VARCHAR(255) NULL,
+    stripe_subscription_id VARCHAR(255) NULL UNIQUE,
+    current_period_end TIMESTAMPTZ NULL,
+    cancel_at_period_end BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Webhook events already handled; Stripe delivers events at least once
+CREATE TABLE stripe_events (
+    id VARCHAR(255) PRIMARY KEY,
+    kind VARCHAR(100) NOT NULL,
+    processed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
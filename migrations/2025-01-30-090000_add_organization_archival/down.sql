@@ -0,0 +1,2 @@
+DROP TABLE organization_archives;
+ALTER TABLE organizations DROP COLUMN archived_at;
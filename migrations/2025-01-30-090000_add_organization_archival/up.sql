@@ -0,0 +1,18 @@
+-- Archived organizations keep their data but take no more writes
+ALTER TABLE organizations ADD COLUMN archived_at TIMESTAMPTZ NULL;
+
+-- Export bundles of archived organizations, one per archival
+CREATE TABLE organization_archives (
+    id UUID PRIMARY KEY,
+    org_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    requested_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    file_key VARCHAR(255) NULL,
+    byte_size BIGINT NULL,
+    error TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMPTZ NULL,
+    unarchived_at TIMESTAMPTZ NULL
+);
+
+CREATE INDEX idx_organization_archives_org ON organization_archives (org_id, created_at DESC);
@@ -0,0 +1,22 @@
+-- Organization-wide events, such as a user joining or a block signed off,
+-- for managers to follow operations in order
+CREATE TABLE "activities" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    -- Who caused the event; NULL when the system did or the user is gone
+    "actor_id" UUID NULL,
+    -- e.g. user_joined or block_signed_off
+    "kind" VARCHAR(64) NOT NULL,
+    -- What the event is about, e.g. user or block; subjects are of several
+    -- tables, so subject_id has no foreign key
+    "subject_type" VARCHAR(32) NOT NULL,
+    "subject_id" UUID NOT NULL,
+    "summary" VARCHAR(255) NOT NULL,
+    "data" JSONB NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "activities" ADD PRIMARY KEY("id");
+CREATE INDEX "activities_org_id_created_at_index" ON "activities"("org_id", "created_at" DESC, "id" DESC);
+CREATE INDEX "activities_org_id_kind_created_at_index" ON "activities"("org_id", "kind", "created_at" DESC);
+ALTER TABLE "activities" ADD CONSTRAINT "activities_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "activities" ADD CONSTRAINT "activities_actor_id_foreign" FOREIGN KEY("actor_id") REFERENCES "users"("id") ON DELETE SET NULL;
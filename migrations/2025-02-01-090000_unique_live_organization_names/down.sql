@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS "organizations_name_live_unique";
+ALTER TABLE "organizations" ADD CONSTRAINT "organizations_name_key" UNIQUE ("name");
@@ -0,0 +1,4 @@
+-- Names are unique among live organizations only, so a deleted
+-- organization's name can be reused and is checked again on restore
+ALTER TABLE "organizations" DROP CONSTRAINT "organizations_name_key";
+CREATE UNIQUE INDEX "organizations_name_live_unique" ON "organizations"("name") WHERE "deleted_at" IS NULL;
@@ -0,0 +1,4 @@
+DELETE FROM "role_permissions" WHERE "permission" IN ('blocks:read', 'blocks:write');
+ALTER TABLE "sale_contracts" DROP CONSTRAINT IF EXISTS "sale_contracts_block_id_foreign";
+ALTER TABLE "tender_parcels" DROP CONSTRAINT IF EXISTS "tender_parcels_block_id_foreign";
+DROP TABLE IF EXISTS "harvest_blocks";
@@ -0,0 +1,38 @@
+-- Harvest blocks, the areas cut under a license, with their boundary
+-- stored and indexed by PostGIS
+CREATE EXTENSION IF NOT EXISTS "postgis";
+
+CREATE TABLE "harvest_blocks" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    -- Harvesting license or cutting permit the block is cut under
+    "license" VARCHAR(100) NOT NULL,
+    "block_number" VARCHAR(50) NOT NULL,
+    "area_ha" DOUBLE PRECISION NOT NULL,
+    "planned_volume_m3" DOUBLE PRECISION NOT NULL,
+    -- planned, approved, active or completed
+    "status" VARCHAR(16) NOT NULL,
+    -- WGS 84 longitude and latitude
+    "boundary" geometry(Polygon, 4326) NOT NULL,
+    "notes" TEXT NULL,
+    "created_by" UUID NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT "harvest_blocks_boundary_valid" CHECK (ST_IsValid("boundary"))
+);
+ALTER TABLE "harvest_blocks" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "harvest_blocks_org_id_license_block_number_unique" ON "harvest_blocks"("org_id", "license", "block_number");
+CREATE INDEX "harvest_blocks_org_id_status_index" ON "harvest_blocks"("org_id", "status");
+CREATE INDEX "harvest_blocks_boundary_index" ON "harvest_blocks" USING GIST ("boundary");
+ALTER TABLE "harvest_blocks" ADD CONSTRAINT "harvest_blocks_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "harvest_blocks" ADD CONSTRAINT "harvest_blocks_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
+
+-- Parcels and sale contracts recorded before blocks were stored may name
+-- blocks that don't exist, so only new rows are checked
+ALTER TABLE "tender_parcels" ADD CONSTRAINT "tender_parcels_block_id_foreign" FOREIGN KEY("block_id") REFERENCES "harvest_blocks"("id") NOT VALID;
+ALTER TABLE "sale_contracts" ADD CONSTRAINT "sale_contracts_block_id_foreign" FOREIGN KEY("block_id") REFERENCES "harvest_blocks"("id") NOT VALID;
+
+INSERT INTO "role_permissions" ("role", "permission") VALUES
+    ('Manager', 'blocks:read'),
+    ('Manager', 'blocks:write')
+ON CONFLICT DO NOTHING;
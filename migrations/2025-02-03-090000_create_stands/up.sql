@@ -0,0 +1,26 @@
+-- Stands, the inventory units of a harvest block, as recorded by forest
+-- inventory systems
+CREATE TABLE "stands" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "block_id" UUID NOT NULL,
+    -- Unique within the block; inventory systems upsert by it
+    "stand_number" VARCHAR(50) NOT NULL,
+    "area_ha" DOUBLE PRECISION NOT NULL,
+    -- Species and their percentage of the stand, e.g.
+    -- [{"species": "spruce", "percent": 60}, {"species": "pine", "percent": 40}]
+    "species" JSONB NOT NULL,
+    -- 20-year age classes, 1 for up to 20 years to 9 for over 250
+    "age_class" SMALLINT NOT NULL,
+    -- Height in metres the leading species reaches at breast height age 50
+    "site_index" DOUBLE PRECISION NOT NULL,
+    "net_merchantable_volume_m3" DOUBLE PRECISION NOT NULL,
+    "inventoried_on" DATE NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "stands" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "stands_block_id_stand_number_unique" ON "stands"("block_id", "stand_number");
+CREATE INDEX "stands_org_id_index" ON "stands"("org_id");
+ALTER TABLE "stands" ADD CONSTRAINT "stands_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "stands" ADD CONSTRAINT "stands_block_id_foreign" FOREIGN KEY("block_id") REFERENCES "harvest_blocks"("id") ON DELETE CASCADE;
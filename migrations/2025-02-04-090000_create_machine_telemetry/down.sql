@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS "machine_telemetry";
+DROP TABLE IF EXISTS "telemetry_api_keys";
@@ -0,0 +1,36 @@
+-- Keys machines and their gateways upload telemetry with
+CREATE TABLE "telemetry_api_keys" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "name" VARCHAR(100) NOT NULL,
+    -- SHA-256 of the key, which is only shown when it is created
+    "key_hash" VARCHAR(64) NOT NULL,
+    "created_by" UUID NULL,
+    "last_used_at" TIMESTAMP WITH TIME ZONE NULL,
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "revoked_at" TIMESTAMP WITH TIME ZONE NULL
+);
+ALTER TABLE "telemetry_api_keys" ADD PRIMARY KEY("id");
+ALTER TABLE "telemetry_api_keys" ADD CONSTRAINT "telemetry_api_keys_key_hash_unique" UNIQUE("key_hash");
+CREATE INDEX "telemetry_api_keys_org_id_index" ON "telemetry_api_keys"("org_id");
+ALTER TABLE "telemetry_api_keys" ADD CONSTRAINT "telemetry_api_keys_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "telemetry_api_keys" ADD CONSTRAINT "telemetry_api_keys_created_by_foreign" FOREIGN KEY("created_by") REFERENCES "users"("id") ON DELETE SET NULL;
+
+-- Data points reported by machines, one per machine and time; a point
+-- sent again replaces the one stored
+CREATE TABLE "machine_telemetry" (
+    "org_id" UUID NOT NULL,
+    -- The machine's own identifier, as configured on its telematics unit
+    "machine_id" VARCHAR(100) NOT NULL,
+    "recorded_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "engine_hours" DOUBLE PRECISION NULL,
+    -- Fuel used according to the machine's counter, in litres
+    "fuel_used_l" DOUBLE PRECISION NULL,
+    -- WGS 84
+    "latitude" DOUBLE PRECISION NULL,
+    "longitude" DOUBLE PRECISION NULL,
+    "received_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "machine_telemetry" ADD PRIMARY KEY("org_id", "machine_id", "recorded_at");
+CREATE INDEX "machine_telemetry_org_id_recorded_at_index" ON "machine_telemetry"("org_id", "recorded_at");
+ALTER TABLE "machine_telemetry" ADD CONSTRAINT "machine_telemetry_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
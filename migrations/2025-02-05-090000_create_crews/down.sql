@@ -0,0 +1,3 @@
+DELETE FROM "role_permissions" WHERE "permission" IN ('crews:read', 'crews:write');
+DROP TABLE IF EXISTS "crew_members";
+DROP TABLE IF EXISTS "crews";
@@ -0,0 +1,35 @@
+-- Crews, the teams work is dispatched to, with their supervisor and the
+-- machines they work with unless a job says otherwise
+CREATE TABLE "crews" (
+    "id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "name" VARCHAR(100) NOT NULL,
+    "supervisor_id" UUID NULL,
+    -- Machine ids, as the machines report them in telemetry
+    "default_equipment" TEXT[] NOT NULL DEFAULT '{}',
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "crews" ADD PRIMARY KEY("id");
+CREATE UNIQUE INDEX "crews_org_id_name_unique" ON "crews"("org_id", "name");
+ALTER TABLE "crews" ADD CONSTRAINT "crews_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+ALTER TABLE "crews" ADD CONSTRAINT "crews_supervisor_id_foreign" FOREIGN KEY("supervisor_id") REFERENCES "users"("id") ON DELETE SET NULL;
+
+-- Users working in a crew; a user may be in several
+CREATE TABLE "crew_members" (
+    "crew_id" UUID NOT NULL,
+    "user_id" UUID NOT NULL,
+    "org_id" UUID NOT NULL,
+    "added_at" TIMESTAMP WITH TIME ZONE NOT NULL
+);
+ALTER TABLE "crew_members" ADD PRIMARY KEY("crew_id", "user_id");
+CREATE INDEX "crew_members_user_id_index" ON "crew_members"("user_id");
+CREATE INDEX "crew_members_org_id_index" ON "crew_members"("org_id");
+ALTER TABLE "crew_members" ADD CONSTRAINT "crew_members_crew_id_foreign" FOREIGN KEY("crew_id") REFERENCES "crews"("id") ON DELETE CASCADE;
+ALTER TABLE "crew_members" ADD CONSTRAINT "crew_members_user_id_foreign" FOREIGN KEY("user_id") REFERENCES "users"("id") ON DELETE CASCADE;
+ALTER TABLE "crew_members" ADD CONSTRAINT "crew_members_org_id_foreign" FOREIGN KEY("org_id") REFERENCES "organizations"("id") ON DELETE CASCADE;
+
+INSERT INTO "role_permissions" ("role", "permission") VALUES
+    ('Manager', 'crews:read'),
+    ('Manager', 'crews:write')
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,3 @@
+DELETE FROM "magic_link_tokens";
+ALTER TABLE "magic_link_tokens" RENAME CONSTRAINT "magic_link_tokens_token_hash_unique" TO "magic_link_tokens_token_unique";
+ALTER TABLE "magic_link_tokens" RENAME COLUMN "token_hash" TO "token";
@@ -0,0 +1,5 @@
+-- Links are kept as a hash of their token; links sent before can't be
+-- looked up any more, so they are dropped
+DELETE FROM "magic_link_tokens";
+ALTER TABLE "magic_link_tokens" RENAME COLUMN "token" TO "token_hash";
+ALTER TABLE "magic_link_tokens" RENAME CONSTRAINT "magic_link_tokens_token_unique" TO "magic_link_tokens_token_hash_unique";
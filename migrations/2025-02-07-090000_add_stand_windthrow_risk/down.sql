@@ -0,0 +1,3 @@
+ALTER TABLE "stands" DROP COLUMN IF EXISTS "windthrow_class";
+ALTER TABLE "stands" DROP COLUMN IF EXISTS "windthrow_score";
+ALTER TABLE "stands" DROP COLUMN IF EXISTS "windthrow_conditions";
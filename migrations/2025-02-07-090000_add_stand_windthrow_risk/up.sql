@@ -0,0 +1,5 @@
+-- What a stand's windthrow risk is scored from, as its inventory records
+-- it, and the risk scored when the stand was last saved
+ALTER TABLE "stands" ADD COLUMN "windthrow_conditions" JSONB NULL;
+ALTER TABLE "stands" ADD COLUMN "windthrow_score" DOUBLE PRECISION NULL;
+ALTER TABLE "stands" ADD COLUMN "windthrow_class" VARCHAR(20) NULL;
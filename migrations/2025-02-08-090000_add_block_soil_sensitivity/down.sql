@@ -0,0 +1 @@
+ALTER TABLE "harvest_blocks" DROP COLUMN IF EXISTS "soil_sensitivity";
@@ -0,0 +1,3 @@
+-- How easily a block's soil ruts under ground-based equipment, which with
+-- recent precipitation decides whether operations on it should pause
+ALTER TABLE "harvest_blocks" ADD COLUMN "soil_sensitivity" VARCHAR(20) NULL;
@@ -0,0 +1,7 @@
+DELETE FROM "email_verification_tokens";
+ALTER TABLE "email_verification_tokens" RENAME CONSTRAINT "email_verification_tokens_token_hash_unique" TO "email_verification_tokens_token_unique";
+ALTER TABLE "email_verification_tokens" RENAME COLUMN "token_hash" TO "token";
+
+DELETE FROM "password_reset_tokens";
+ALTER TABLE "password_reset_tokens" RENAME CONSTRAINT "password_reset_tokens_token_hash_unique" TO "password_reset_tokens_token_unique";
+ALTER TABLE "password_reset_tokens" RENAME COLUMN "token_hash" TO "token";
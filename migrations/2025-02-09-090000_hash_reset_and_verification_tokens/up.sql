@@ -0,0 +1,10 @@
+-- Password reset and email verification links are kept as a hash of their
+-- token, as magic links are; links sent before can't be looked up any
+-- more, so they are dropped
+DELETE FROM "password_reset_tokens";
+ALTER TABLE "password_reset_tokens" RENAME COLUMN "token" TO "token_hash";
+ALTER TABLE "password_reset_tokens" RENAME CONSTRAINT "password_reset_tokens_token_unique" TO "password_reset_tokens_token_hash_unique";
+
+DELETE FROM "email_verification_tokens";
+ALTER TABLE "email_verification_tokens" RENAME COLUMN "token" TO "token_hash";
+ALTER TABLE "email_verification_tokens" RENAME CONSTRAINT "email_verification_tokens_token_unique" TO "email_verification_tokens_token_hash_unique";
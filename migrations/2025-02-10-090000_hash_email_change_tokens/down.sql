@@ -0,0 +1,3 @@
+DELETE FROM "email_change_tokens";
+ALTER TABLE "email_change_tokens" RENAME CONSTRAINT "email_change_tokens_token_hash_unique" TO "email_change_tokens_token_unique";
+ALTER TABLE "email_change_tokens" RENAME COLUMN "token_hash" TO "token";
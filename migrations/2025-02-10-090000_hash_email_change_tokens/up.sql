@@ -0,0 +1,5 @@
+-- Email change links are kept as a hash of their token; links sent before
+-- can't be looked up any more, so they are dropped
+DELETE FROM "email_change_tokens";
+ALTER TABLE "email_change_tokens" RENAME COLUMN "token" TO "token_hash";
+ALTER TABLE "email_change_tokens" RENAME CONSTRAINT "email_change_tokens_token_unique" TO "email_change_tokens_token_hash_unique";
@@ -1,15 +1,21 @@
-use std::future::{ready, Ready};
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
 use actix_web::{
     dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
     Error, FromRequest, HttpMessage, HttpRequest, web,
 };
 use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
 use crate::{
-    domain::{auth::Claims, TokenManager},
+    db::get_connection,
+    domain::{auth::{Claims, RevocationList}, organization::ArchiveService, TokenManager},
     error::{ApiError, ErrorCode, ErrorContext},
     utils::Config,
 };
-use tracing::error;
+use tracing::{error, Span};
 
 /// Extractor for authenticated user claims
 pub struct AuthenticatedUser(pub Claims);
@@ -50,13 +56,23 @@ impl AuthenticatedUser {
 }
 
 /// JWT authentication middleware
+///
+/// Rejects tokens that are invalid, expired or revoked by logging out or by
+/// a change to the user's role or password. Tokens that passed are
+/// remembered briefly in the [`TokenCache`](crate::domain::auth::TokenCache).
+///
+/// Members of an archived organization keep reading, but their writes are
+/// rejected unless the scope opted out with [`Auth::allow_archived`].
 pub struct AuthMiddleware<S> {
-    service: S,
+    service: Rc<S>,
+    allow_archived: bool,
 }
 
 /// Authentication middleware factory
 #[derive(Clone)]
-pub struct Auth;
+pub struct Auth {
+    allow_archived: bool,
+}
 
 impl Default for Auth {
     fn default() -> Self {
@@ -66,10 +82,22 @@ impl Default for Auth {
 
 impl Auth {
     pub fn new() -> Self {
-        Auth
+        Auth { allow_archived: false }
+    }
+
+    /// Lets members of archived organizations write, for the endpoints
+    /// that reopen them
+    pub fn allow_archived(mut self) -> Self {
+        self.allow_archived = true;
+        self
     }
 }
 
+/// Whether a request only reads
+fn is_read(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
 impl<S, B> Transform<S, ServiceRequest> for Auth
 where
     S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
@@ -82,7 +110,7 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(AuthMiddleware { service }))
+        ready(Ok(AuthMiddleware { service: Rc::new(service), allow_archived: self.allow_archived }))
     }
 }
 
@@ -100,7 +128,8 @@ where
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let config = req.app_data::<web::Data<Config>>()
-            .expect("Config not found in app data");
+            .expect("Config not found in app data")
+            .clone();
             
         let auth_header = req.headers().get("Authorization");
         
@@ -137,19 +166,45 @@ where
             }
         };
 
-        match TokenManager::validate_token(&token, config) {
-            Ok(claims) => {
-                req.extensions_mut().insert(claims);
-                let fut = self.service.call(req);
-                Box::pin(async move {
-                    let res = fut.await?;
-                    Ok(res)
-                })
-            }
+        // Tokens verified moments ago skip verification and the revocation
+        // list, which revoking them clears
+        let cached = config.token_cache().get(&token);
+        let verified = cached.is_some();
+        let claims = match cached.map_or_else(|| TokenManager::validate_token(&token, &config), Ok) {
+            Ok(claims) => claims,
             Err(e) => {
                 error!("Token validation failed: {:?}", e);
-                Box::pin(ready(Err(e.into())))
+                return Box::pin(ready(Err(e.into())));
             }
-        }
+        };
+
+        let check_archived = !self.allow_archived && !is_read(req.method());
+        let service = self.service.clone();
+        Box::pin(async move {
+            if !verified {
+                if RevocationList::is_token_revoked(&config, &claims).await {
+                    return Err(ApiError::new(
+                        ErrorCode::Unauthorized,
+                        "Token revoked",
+                        ErrorContext::default(),
+                    ).into());
+                }
+                config.token_cache().insert(&token, &claims);
+            }
+
+            if check_archived {
+                let org_id = Uuid::parse_str(&claims.org_id)
+                    .map_err(|_| ApiError::unauthorized("Invalid organization claim"))?;
+                let mut conn = get_connection(config.pool())?;
+                ArchiveService::ensure_writable(&mut conn, org_id).await?;
+            }
+
+            // Attach the caller to the request span for structured logs
+            Span::current()
+                .record("org_id", claims.org_id.as_str())
+                .record("user_id", claims.sub.as_str());
+            req.extensions_mut().insert(claims);
+            service.call(req).await
+        })
     }
-} 
\ No newline at end of file
+}
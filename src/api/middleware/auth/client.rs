@@ -0,0 +1,36 @@
+use std::{
+    convert::Infallible,
+    future::{ready, Ready},
+};
+use actix_web::{dev::Payload, http::header, web, FromRequest, HttpRequest};
+use crate::{domain::auth::ClientInfo, utils::Config};
+
+/// Extracts the client's IP address and user agent for the audit log
+///
+/// The address is taken from `Forwarded` or `X-Forwarded-For` when a proxy
+/// set them, like the rate limiter does, and from the connection otherwise.
+/// The country and network come from the headers named in
+/// `auth.login_alerts`, when set.
+impl FromRequest for ClientInfo {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let text = |name: &str| {
+            if name.is_empty() {
+                return None;
+            }
+            req.headers().get(name).and_then(|value| value.to_str().ok())
+        };
+        let user_agent = text(header::USER_AGENT.as_str());
+        let client = ClientInfo::new(req.connection_info().realip_remote_addr(), user_agent);
+        let client = match req.app_data::<web::Data<Config>>() {
+            Some(config) => {
+                let alerts = &config.auth.login_alerts;
+                client.located(text(&alerts.country_header), text(&alerts.asn_header))
+            }
+            None => client,
+        };
+        ready(Ok(client))
+    }
+}
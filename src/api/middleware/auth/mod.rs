@@ -3,10 +3,28 @@
 //! This module provides middleware components for:
 //! - JWT-based authentication
 //! - Role-based authorization
+//! - Permission-based authorization
+//! - Subscription gating of premium features
 //! - User claims extraction
+//! - Tenant resolution for organization-scoped queries
+//! - Client address and user agent extraction for the audit log
+//! - SCIM provisioning token authentication
+//! - Telemetry API key authentication
 
+#[allow(clippy::module_inception)]
 mod auth;
+mod client;
+mod permission;
 mod role;
+mod scim;
+mod subscription;
+mod telemetry;
+mod tenant;
 
 pub use auth::{Auth, AuthenticatedUser};
-pub use role::{RequireAuth, RequireRole}; 
\ No newline at end of file
+pub use permission::RequirePermission;
+pub use role::{RequireAuth, RequireRole};
+pub use scim::ScimClient;
+pub use subscription::RequireSubscription;
+pub use telemetry::TelemetryClient;
+pub use tenant::Tenant; 
\ No newline at end of file
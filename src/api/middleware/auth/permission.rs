@@ -0,0 +1,132 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    web, Error, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+use crate::{
+    db::{get_connection, DbPool},
+    domain::auth::{Claims, Permission, PermissionService},
+    error::{ApiError, ErrorCode, ErrorContext},
+};
+use super::role::claimed_role;
+
+/// Middleware for requiring a permission of the caller's role or of one of
+/// their custom roles
+///
+/// Reads (GET and HEAD) need the read permission, every other method the
+/// write permission.
+#[derive(Clone)]
+pub struct RequirePermission {
+    read: Permission,
+    write: Permission,
+}
+
+impl RequirePermission {
+    /// Requires `permission` whatever the method
+    pub fn new(permission: Permission) -> Self {
+        Self {
+            read: permission,
+            write: permission,
+        }
+    }
+
+    /// Requires `read` for reads and `write` for everything else
+    pub fn read_write(read: Permission, write: Permission) -> Self {
+        Self { read, write }
+    }
+}
+
+pub struct PermissionMiddleware<S> {
+    service: Rc<S>,
+    read: Permission,
+    write: Permission,
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequirePermission
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = PermissionMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(PermissionMiddleware {
+            service: Rc::new(service),
+            read: self.read,
+            write: self.write,
+        }))
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for PermissionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let claims = match req.extensions().get::<Claims>().cloned() {
+            Some(claims) => claims,
+            None => {
+                return Box::pin(ready(Err(ApiError::new(
+                    ErrorCode::Unauthorized,
+                    "Missing authentication",
+                    ErrorContext::default(),
+                )
+                .into())));
+            }
+        };
+        let role = match claimed_role(&claims) {
+            Ok(role) => role,
+            Err(e) => return Box::pin(ready(Err(e.into()))),
+        };
+        let user = match Uuid::parse_str(&claims.sub) {
+            Ok(user) => user,
+            Err(_) => return Box::pin(ready(Err(ApiError::unauthorized("Invalid token subject").into()))),
+        };
+
+        let permission = match *req.method() {
+            Method::GET | Method::HEAD => self.read,
+            _ => self.write,
+        };
+        let pool = req.app_data::<web::Data<DbPool>>()
+            .expect("DbPool not found in app data")
+            .clone();
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let mut conn = get_connection(&pool)?;
+            if !PermissionService::allows_user(&mut conn, user, role, permission).await? {
+                return Err(ApiError::new(
+                    ErrorCode::Forbidden,
+                    "Insufficient permissions",
+                    ErrorContext::new().with_details(serde_json::json!({
+                        "permission": permission.as_str(),
+                    })),
+                )
+                .into());
+            }
+            // Release the connection before the handler takes its own
+            drop(conn);
+
+            service.call(req).await
+        })
+    }
+}
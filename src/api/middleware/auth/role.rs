@@ -1,27 +1,62 @@
-use std::future::{ready, Ready};
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage,
+    web, Error, HttpMessage,
 };
 use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
 use crate::{
-    db::models::auth::Role,
+    db::{get_connection, models::auth::Role, DbPool},
     error::{ApiError, ErrorCode, ErrorContext},
-    domain::auth::Claims,
+    domain::auth::{Claims, PermissionService},
 };
 use tracing::error;
 
-/// Middleware for requiring a specific role
+/// Middleware for requiring a role
+///
+/// A built-in role admits users with it or a role above it, in the order
+/// of [`Role`]. A set of built-in roles admits users with one of them,
+/// without regard to the order. A custom role, named as the caller's
+/// organization defined it, admits the users it is assigned to, resolved on
+/// each request, and admins.
+#[derive(Clone)]
+pub struct RequireRole(Requirement);
+
 #[derive(Clone)]
-pub struct RequireRole(pub Role);
+enum Requirement {
+    Builtin(Role),
+    Any(Rc<[Role]>),
+    Custom(Rc<str>),
+}
+
+impl RequireRole {
+    /// Requires `role` or a role above it
+    pub fn new(role: Role) -> Self {
+        Self(Requirement::Builtin(role))
+    }
+
+    /// Requires one of `roles`; roles above them aren't admitted unless
+    /// listed
+    pub fn any(roles: impl IntoIterator<Item = Role>) -> Self {
+        Self(Requirement::Any(roles.into_iter().collect()))
+    }
+
+    /// Requires the custom role named `name`, ignoring case
+    pub fn custom(name: &str) -> Self {
+        Self(Requirement::Custom(name.into()))
+    }
+}
 
 /// Middleware for requiring authentication
 #[derive(Clone)]
 pub struct RequireAuth;
 
 pub struct RoleMiddleware<S> {
-    service: S,
-    role: Role,
+    service: Rc<S>,
+    requirement: Requirement,
 }
 
 impl<S, B> Transform<S, ServiceRequest> for RequireRole
@@ -38,8 +73,8 @@ where
 
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(RoleMiddleware {
-            service,
-            role: self.0,
+            service: Rc::new(service),
+            requirement: self.0.clone(),
         }))
     }
 }
@@ -58,8 +93,8 @@ where
 
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(RoleMiddleware {
-            service,
-            role: Role::Operator,
+            service: Rc::new(service),
+            requirement: Requirement::Builtin(Role::Operator),
         }))
     }
 }
@@ -91,34 +126,20 @@ where
         };
 
         // Parse role from claims
-        let user_role = match claims.role.to_uppercase().as_str() {
-            "ADMIN" => Role::Admin,
-            "MANAGER" => Role::Manager,
-            "OPERATOR" => Role::Operator,
-            _ => {
-                error!("Invalid role in claims: {}", claims.role);
-                return Box::pin(ready(Err(ApiError::new(
-                    ErrorCode::Unauthorized,
-                    "Invalid role",
-                    ErrorContext::default(),
-                )
-                .into())));
-            }
+        let user_role = match claimed_role(&claims) {
+            Ok(role) => role,
+            Err(e) => return Box::pin(ready(Err(e.into()))),
         };
 
-        // Check if user has required role
-        match (user_role, self.role) {
-            (Role::Admin, _) => (),  // Admin can access everything
-            (Role::Manager, Role::Manager | Role::Operator) => (),  // Manager can access Manager and Operator routes
-            (Role::Operator, Role::Operator) => (),  // Operator can only access Operator routes
-            _ => {
-                return Box::pin(ready(Err(ApiError::new(
-                    ErrorCode::Forbidden,
-                    "Insufficient permissions",
-                    ErrorContext::default(),
-                )
-                .into())));
+        let admitted = match &self.requirement {
+            Requirement::Builtin(required) => user_role.is_at_least(*required),
+            Requirement::Any(roles) => roles.contains(&user_role),
+            Requirement::Custom(name) => {
+                return self.require_custom_role(req, claims, user_role, name.clone());
             }
+        };
+        if !admitted {
+            return Box::pin(ready(Err(insufficient_permissions().into())));
         }
 
         let fut = self.service.call(req);
@@ -127,4 +148,65 @@ where
             Ok(res)
         })
     }
-} 
\ No newline at end of file
+}
+
+impl<S, B> RoleMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    /// Calls the service if the caller is an admin or assigned the custom
+    /// role `name`
+    fn require_custom_role(
+        &self,
+        req: ServiceRequest,
+        claims: Claims,
+        user_role: Role,
+        name: Rc<str>,
+    ) -> LocalBoxFuture<'static, Result<ServiceResponse<B>, Error>> {
+        let pool = req.app_data::<web::Data<DbPool>>()
+            .expect("DbPool not found in app data")
+            .clone();
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            if user_role != Role::Admin {
+                let user = Uuid::parse_str(&claims.sub).map_err(|_| ApiError::unauthorized("Invalid token subject"))?;
+                let mut conn = get_connection(&pool)?;
+                if !PermissionService::policy(&mut conn).await?.has_custom_role(user, &name) {
+                    return Err(insufficient_permissions().into());
+                }
+                // Release the connection before the handler takes its own
+                drop(conn);
+            }
+
+            service.call(req).await
+        })
+    }
+}
+
+fn insufficient_permissions() -> ApiError {
+    ApiError::new(
+        ErrorCode::Forbidden,
+        "Insufficient permissions",
+        ErrorContext::default(),
+    )
+}
+
+/// The role in the caller's claims
+pub(super) fn claimed_role(claims: &Claims) -> Result<Role, ApiError> {
+    match claims.role.to_uppercase().as_str() {
+        "ADMIN" => Ok(Role::Admin),
+        "MANAGER" => Ok(Role::Manager),
+        "OPERATOR" => Ok(Role::Operator),
+        _ => {
+            error!("Invalid role in claims: {}", claims.role);
+            Err(ApiError::new(
+                ErrorCode::Unauthorized,
+                "Invalid role",
+                ErrorContext::default(),
+            ))
+        }
+    }
+}
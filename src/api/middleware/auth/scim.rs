@@ -0,0 +1,50 @@
+use actix_web::{dev::Payload, http::header, web, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+
+use crate::{
+    db::{get_connection, DbPool},
+    domain::scim::{ScimError, ScimService},
+};
+
+/// Extractor for the organization a SCIM request provisions, identified by
+/// the provisioning token it carries
+///
+/// SCIM clients don't hold user sessions, so this replaces the `Auth`
+/// middleware on the SCIM routes.
+pub struct ScimClient {
+    pub org_id: Uuid,
+    pub token_id: Uuid,
+}
+
+impl FromRequest for ScimClient {
+    type Error = ScimError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| token.trim().to_string());
+        let pool = req.app_data::<web::Data<DbPool>>().cloned();
+
+        Box::pin(async move {
+            let token = token.ok_or_else(|| ScimError::unauthorized("Missing provisioning token"))?;
+            let pool = pool.ok_or_else(|| ScimError::new(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                None,
+                "Database unavailable",
+            ))?;
+            let mut conn = get_connection(&pool)?;
+            let token = ScimService::authenticate(&mut conn, &token)
+                .await?
+                .ok_or_else(|| ScimError::unauthorized("Invalid or revoked provisioning token"))?;
+            Ok(ScimClient {
+                org_id: token.org_id,
+                token_id: token.id,
+            })
+        })
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+use crate::{
+    db::{get_connection, DbPool},
+    domain::{auth::Claims, billing::BillingService},
+    error::{ApiError, ErrorCode, ErrorContext},
+    utils::Config,
+};
+
+/// Middleware for premium features, which need the caller's organization to
+/// have an active subscription to a premium plan
+///
+/// Lets every request through while billing is disabled. Wrap it inside
+/// `Auth`, which provides the claims.
+#[derive(Clone, Default)]
+pub struct RequireSubscription;
+
+impl RequireSubscription {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+pub struct SubscriptionMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequireSubscription
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SubscriptionMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SubscriptionMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for SubscriptionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let config = req.app_data::<web::Data<Config>>()
+            .expect("Config not found in app data")
+            .clone();
+        let service = self.service.clone();
+        if !config.billing.enabled() {
+            return Box::pin(service.call(req));
+        }
+
+        let claims = match req.extensions().get::<Claims>().cloned() {
+            Some(claims) => claims,
+            None => {
+                return Box::pin(ready(Err(ApiError::new(
+                    ErrorCode::Unauthorized,
+                    "Missing authentication",
+                    ErrorContext::default(),
+                )
+                .into())));
+            }
+        };
+        let org_id = match Uuid::parse_str(&claims.org_id) {
+            Ok(org_id) => org_id,
+            Err(_) => return Box::pin(ready(Err(ApiError::unauthorized("Invalid organization claim").into()))),
+        };
+        let pool = req.app_data::<web::Data<DbPool>>()
+            .expect("DbPool not found in app data")
+            .clone();
+
+        Box::pin(async move {
+            let mut conn = get_connection(&pool)?;
+            if !BillingService::has_premium(&mut conn, &config, org_id).await? {
+                return Err(ApiError::new(
+                    ErrorCode::SubscriptionRequired,
+                    "This feature needs an active subscription to a premium plan",
+                    ErrorContext::new(),
+                )
+                .into());
+            }
+            // Release the connection before the handler takes its own
+            drop(conn);
+
+            service.call(req).await
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+
+use crate::{
+    db::{get_connection, repositories::TelemetryRepositoryImpl, DbPool},
+    domain::telemetry::{TelemetryService, API_KEY_HEADER},
+    error::ApiError,
+};
+
+/// Extractor for the organization a telemetry upload belongs to,
+/// identified by the API key it carries
+///
+/// Telematics units don't hold user sessions, so this replaces the `Auth`
+/// middleware on the upload route.
+pub struct TelemetryClient {
+    pub org_id: Uuid,
+    pub key_id: Uuid,
+}
+
+impl FromRequest for TelemetryClient {
+    type Error = ApiError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let key = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty());
+        let pool = req.app_data::<web::Data<DbPool>>()
+            .expect("DbPool not found in app data")
+            .clone();
+
+        Box::pin(async move {
+            let key = key.ok_or_else(|| ApiError::unauthorized("Missing telemetry API key"))?;
+            let mut conn = get_connection(&pool)?;
+            let key = TelemetryService::new(TelemetryRepositoryImpl)
+                .authenticate(&mut conn, &key)
+                .await?
+                .ok_or_else(|| ApiError::unauthorized("Invalid or revoked telemetry API key"))?;
+            Ok(TelemetryClient {
+                org_id: key.org_id,
+                key_id: key.id,
+            })
+        })
+    }
+}
@@ -0,0 +1,42 @@
+use std::future::{ready, Ready};
+use actix_web::{dev::Payload, FromRequest, HttpMessage, HttpRequest};
+use uuid::Uuid;
+use crate::{
+    domain::auth::Claims,
+    error::ApiError,
+};
+
+/// Extractor for the organization of the authenticated caller
+///
+/// The organization comes from the verified token, never from the request,
+/// so handlers pass it to the repositories, which scope every query by it
+/// (see [`TenantScoped`](crate::db::tenant::TenantScoped)). A guessed id of
+/// another organization's row then finds nothing. Wrap the routes in `Auth`,
+/// which provides the claims.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tenant(pub Uuid);
+
+impl Tenant {
+    /// The organization named by the token's claims
+    pub fn from_claims(claims: &Claims) -> Result<Self, ApiError> {
+        Uuid::parse_str(&claims.org_id)
+            .map(Tenant)
+            .map_err(|_| ApiError::unauthorized("Invalid token organization"))
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.0
+    }
+}
+
+impl FromRequest for Tenant {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(match req.extensions().get::<Claims>() {
+            Some(claims) => Tenant::from_claims(claims),
+            None => Err(ApiError::unauthorized("Missing authentication")),
+        })
+    }
+}
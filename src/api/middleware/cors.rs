@@ -0,0 +1,152 @@
+//! CORS middleware
+//!
+//! Lets browsers call the API from the origins in `live.cors_origins`, so
+//! the list changes on config reload. Preflight requests from an allowed
+//! origin are answered here; requests from other origins get no CORS
+//! headers and are blocked by the browser.
+
+use std::fmt;
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{self, HeaderMap, HeaderValue},
+        Method, StatusCode,
+    },
+    web, Error, HttpResponse, ResponseError,
+};
+
+use crate::utils::Config;
+
+const ALLOWED_METHODS: &str = "GET, POST, PUT, PATCH, DELETE, OPTIONS";
+const EXPOSED_HEADERS: &str = "x-request-id, retry-after";
+/// Seconds browsers may cache a preflight response
+const MAX_AGE_SECS: &str = "3600";
+
+/// Middleware adding CORS headers for allowed origins
+#[derive(Default, Clone)]
+pub struct Cors;
+
+impl Cors {
+    pub fn new() -> Self {
+        Cors
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Cors
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = CorsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CorsMiddleware { service }))
+    }
+}
+
+pub struct CorsMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for CorsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let origin = req
+            .headers()
+            .get(header::ORIGIN)
+            .filter(|origin| {
+                let allowed = req.app_data::<web::Data<Config>>().map(|config| config.live().get());
+                match (allowed, origin.to_str()) {
+                    (Some(live), Ok(origin)) => live.allows_origin(origin),
+                    _ => false,
+                }
+            })
+            .cloned();
+        let Some(origin) = origin else {
+            let fut = self.service.call(req);
+            return Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) });
+        };
+
+        if req.method() == Method::OPTIONS && req.headers().contains_key(header::ACCESS_CONTROL_REQUEST_METHOD) {
+            let mut preflight = HttpResponse::NoContent();
+            preflight
+                .insert_header((header::ACCESS_CONTROL_ALLOW_ORIGIN, origin))
+                .insert_header((header::ACCESS_CONTROL_ALLOW_METHODS, ALLOWED_METHODS))
+                .insert_header((header::ACCESS_CONTROL_MAX_AGE, MAX_AGE_SECS))
+                .insert_header((header::VARY, "Origin"));
+            if let Some(headers) = req.headers().get(header::ACCESS_CONTROL_REQUEST_HEADERS) {
+                preflight.insert_header((header::ACCESS_CONTROL_ALLOW_HEADERS, headers.clone()));
+            }
+            let response = req.into_response(preflight.finish()).map_into_right_body();
+            return Box::pin(async move { Ok(response) });
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            match fut.await {
+                Ok(mut res) => {
+                    add_headers(res.headers_mut(), origin);
+                    Ok(res.map_into_left_body())
+                }
+                // Errors from inner middleware (401, 429, ...) are rendered
+                // later, so they carry the headers along
+                Err(error) => Err(CorsError { error, origin }.into()),
+            }
+        })
+    }
+}
+
+fn add_headers(headers: &mut HeaderMap, origin: HeaderValue) {
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, HeaderValue::from_static(EXPOSED_HEADERS));
+    headers.append(header::VARY, HeaderValue::from_static("Origin"));
+}
+
+/// Error of an inner service, rendered with the CORS headers of the request
+struct CorsError {
+    error: Error,
+    origin: HeaderValue,
+}
+
+impl fmt::Debug for CorsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.error, f)
+    }
+}
+
+impl fmt::Display for CorsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl ResponseError for CorsError {
+    fn status_code(&self) -> StatusCode {
+        self.error.as_response_error().status_code()
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut res = self.error.error_response();
+        add_headers(res.headers_mut(), self.origin.clone());
+        res
+    }
+}
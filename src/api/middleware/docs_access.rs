@@ -0,0 +1,158 @@
+//! Access control of the API documentation
+//!
+//! Swagger UI and the OpenAPI document answer 404 unless enabled through
+//! `docs.swagger_ui` and `docs.openapi_json` (by default everywhere but
+//! production). When enabled, `docs.auth` decides who may read them: anyone,
+//! callers with the `docs.username` and `docs.password` basic auth
+//! credentials, or admins with a bearer token.
+
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{self, HeaderMap, HeaderValue},
+    web, Error, ResponseError,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use subtle::ConstantTimeEq;
+
+use crate::{
+    domain::TokenManager,
+    error::{ApiError, ErrorCode, ErrorContext},
+    utils::{Config, DocsAuth},
+};
+
+/// Challenge making browsers prompt for basic auth credentials
+const BASIC_CHALLENGE: &str = "Basic realm=\"API documentation\", charset=\"UTF-8\"";
+
+/// Middleware guarding Swagger UI and the OpenAPI document
+#[derive(Default, Clone)]
+pub struct DocsAccess;
+
+impl DocsAccess {
+    pub fn new() -> Self {
+        DocsAccess
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for DocsAccess
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = DocsAccessMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(DocsAccessMiddleware { service }))
+    }
+}
+
+pub struct DocsAccessMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for DocsAccessMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let Some(config) = req.app_data::<web::Data<Config>>().cloned() else {
+            return Box::pin(ready(Err(ApiError::not_found("Not found").into())));
+        };
+
+        let enabled = if req.path().ends_with("/openapi.json") {
+            config.docs.openapi_json_enabled(&config.environment)
+        } else {
+            config.docs.swagger_ui_enabled(&config.environment)
+        };
+        if !enabled {
+            return Box::pin(ready(Err(ApiError::not_found("Not found").into())));
+        }
+
+        match authorize(&config, req.headers()) {
+            Ok(()) => {
+                let fut = self.service.call(req);
+                Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) })
+            }
+            Err(error) if config.docs.auth == DocsAuth::Basic => {
+                let mut response = error.error_response();
+                response
+                    .headers_mut()
+                    .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static(BASIC_CHALLENGE));
+                let response = req.into_response(response).map_into_right_body();
+                Box::pin(ready(Ok(response)))
+            }
+            Err(error) => Box::pin(ready(Err(error.into()))),
+        }
+    }
+}
+
+/// Checks the request carries the credentials required by `docs.auth`
+fn authorize(config: &Config, headers: &HeaderMap) -> Result<(), ApiError> {
+    let authorization = headers.get(header::AUTHORIZATION).and_then(|value| value.to_str().ok());
+    match config.docs.auth {
+        DocsAuth::None => Ok(()),
+        DocsAuth::Basic => {
+            let username = config.docs.username.as_deref().unwrap_or_default();
+            let password = config.docs.password.as_deref().unwrap_or_default();
+            if authorization.is_some_and(|header| basic_matches(header, username, password)) {
+                Ok(())
+            } else {
+                Err(ApiError::unauthorized("Invalid documentation credentials"))
+            }
+        }
+        DocsAuth::Admin => {
+            let token = authorization
+                .and_then(|header| header.strip_prefix("Bearer "))
+                .ok_or_else(|| ApiError::unauthorized("Missing authorization header"))?;
+            let claims = TokenManager::validate_token(token, config)?;
+            if claims.role.eq_ignore_ascii_case("admin") {
+                Ok(())
+            } else {
+                Err(ApiError::new(ErrorCode::Forbidden, "Insufficient permissions", ErrorContext::default()))
+            }
+        }
+    }
+}
+
+/// Whether a basic auth `header` carries `username` and `password`,
+/// compared in constant time
+fn basic_matches(header: &str, username: &str, password: &str) -> bool {
+    let Some(decoded) = header
+        .strip_prefix("Basic ")
+        .and_then(|encoded| STANDARD.decode(encoded.trim()).ok())
+    else {
+        return false;
+    };
+    let expected = format!("{}:{}", username, password);
+    decoded.ct_eq(expected.as_bytes()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_matches() {
+        let header = format!("Basic {}", STANDARD.encode("docs:s3cret"));
+        assert!(basic_matches(&header, "docs", "s3cret"));
+        assert!(!basic_matches(&header, "docs", "other"));
+        assert!(!basic_matches("Basic not-base64!", "docs", "s3cret"));
+        assert!(!basic_matches("Bearer token", "docs", "s3cret"));
+    }
+}
@@ -0,0 +1,163 @@
+//! Error reporting middleware
+//!
+//! Runs every request on its own Sentry hub so breadcrumbs and panics are
+//! attributed to the request that produced them, and reports responses with
+//! a 5xx status as Sentry events tagged with the request id, the caller's
+//! user and organization and the `ErrorCode`. When Sentry is not configured
+//! the middleware only binds the hub and reports nothing.
+
+use std::{
+    future::{ready, Ready},
+    sync::Arc,
+};
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::StatusCode,
+    Error, HttpMessage, HttpRequest,
+};
+use futures_util::future::LocalBoxFuture;
+use sentry::{
+    protocol::{Event, Level, Request},
+    Hub, SentryFutureExt,
+};
+use uuid::Uuid;
+
+use crate::{domain::auth::Claims, error::ApiError};
+
+/// Middleware reporting server errors to Sentry
+#[derive(Default, Clone)]
+pub struct ErrorReporter;
+
+impl ErrorReporter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ErrorReporter
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = ErrorReporterMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ErrorReporterMiddleware { service }))
+    }
+}
+
+pub struct ErrorReporterMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for ErrorReporterMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let hub = Arc::new(Hub::new_from_top(Hub::main()));
+        let request = sentry_request(req.request());
+        hub.configure_scope(|scope| {
+            if let Some(request_id) = req.extensions().get::<Uuid>() {
+                scope.set_tag("request_id", request_id);
+            }
+            let route = req.match_pattern().unwrap_or_else(|| req.path().to_string());
+            scope.set_transaction(Some(&format!("{} {}", req.method(), route)));
+        });
+
+        let fut = Hub::run(hub.clone(), || self.service.call(req));
+        Box::pin(async move {
+            let result = fut.bind_hub(hub.clone()).await;
+            match &result {
+                Ok(res) if res.status().is_server_error() => {
+                    report(&hub, request, res.status(), res.response().error(), Some(res.request()));
+                }
+                Err(error) if error.as_response_error().status_code().is_server_error() => {
+                    report(&hub, request, error.as_response_error().status_code(), Some(error), None);
+                }
+                _ => {}
+            }
+            result
+        })
+    }
+}
+
+/// Builds the Sentry request context; PII is scrubbed in `before_send`
+fn sentry_request(req: &HttpRequest) -> Request {
+    let info = req.connection_info();
+    Request {
+        url: format!("{}://{}{}", info.scheme(), info.host(), req.uri()).parse().ok(),
+        method: Some(req.method().to_string()),
+        query_string: Some(req.query_string().to_string()).filter(|query| !query.is_empty()),
+        headers: req
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn report(
+    hub: &Hub,
+    request: Request,
+    status: StatusCode,
+    error: Option<&Error>,
+    http_request: Option<&HttpRequest>,
+) {
+    let api_error = error.and_then(|error| error.as_error::<ApiError>());
+    let message = match (api_error, error) {
+        (Some(api_error), _) => api_error.message.clone(),
+        (None, Some(error)) => error.to_string(),
+        (None, None) => status.canonical_reason().unwrap_or("Server error").to_string(),
+    };
+
+    let (request_id, claims) = http_request
+        .map(|req| {
+            let extensions = req.extensions();
+            (extensions.get::<Uuid>().copied(), extensions.get::<Claims>().cloned())
+        })
+        .unwrap_or_default();
+
+    hub.with_scope(
+        |scope| {
+            scope.set_tag("status_code", status.as_u16());
+            if let Some(api_error) = api_error {
+                scope.set_tag("error_code", api_error.code);
+            }
+            if let Some(request_id) = request_id {
+                scope.set_tag("request_id", request_id);
+            }
+            if let Some(claims) = claims {
+                scope.set_user(Some(sentry::User {
+                    id: Some(claims.sub),
+                    ..Default::default()
+                }));
+                scope.set_tag("org_id", claims.org_id);
+                scope.set_tag("role", claims.role);
+            }
+        },
+        || {
+            hub.capture_event(Event {
+                level: Level::Error,
+                message: Some(message),
+                request: Some(request),
+                ..Default::default()
+            })
+        },
+    );
+}
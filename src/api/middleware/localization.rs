@@ -0,0 +1,102 @@
+//! Error localization middleware
+//!
+//! Negotiates a locale from the `Accept-Language` header and rewrites
+//! validation error responses with messages from that locale's catalog.
+//! Successful responses and requests preferring English pass through
+//! untouched.
+
+use std::future::{ready, Ready};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header,
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+
+use crate::{error::ApiError, utils::i18n::Locale};
+
+/// Middleware translating error messages to the client's language
+#[derive(Default, Clone)]
+pub struct Localization;
+
+impl Localization {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Localization
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = LocalizationMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LocalizationMiddleware { service }))
+    }
+}
+
+pub struct LocalizationMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for LocalizationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let locale = req
+            .headers()
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(Locale::negotiate)
+            .unwrap_or_default();
+
+        if locale == Locale::default() {
+            let fut = self.service.call(req);
+            return Box::pin(async move { Ok(fut.await?.map_into_left_body()) });
+        }
+
+        // Errors raised by inner middleware carry no request of their own
+        let http_req = req.request().clone();
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            match fut.await {
+                Ok(res) => {
+                    let localized = res
+                        .response()
+                        .error()
+                        .and_then(|error| error.as_error::<ApiError>())
+                        .map(|error| error.localized_response(locale));
+                    Ok(match localized {
+                        Some(localized) => res.into_response(localized).map_into_right_body(),
+                        None => res.map_into_left_body(),
+                    })
+                }
+                Err(error) => match error.as_error::<ApiError>() {
+                    Some(api_error) => {
+                        let localized = api_error.localized_response(locale);
+                        Ok(ServiceResponse::new(http_req, localized).map_into_right_body())
+                    }
+                    None => Err(error),
+                },
+            }
+        })
+    }
+}
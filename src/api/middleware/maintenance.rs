@@ -0,0 +1,104 @@
+//! Maintenance mode middleware
+//!
+//! While `live.maintenance.enabled` is set, API requests are rejected with
+//! `503 Service Unavailable` and a `Retry-After` header. Health probes, auth
+//! and the admin API stay available, so operators can still sign in and
+//! switch maintenance mode off again.
+
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error,
+};
+
+use crate::error::{ApiError, ErrorCode, ErrorContext};
+use crate::utils::Config;
+
+/// Path prefixes served during maintenance
+const EXEMPT_PREFIXES: &[&str] = &["/v1/health", "/v1/auth", "/v1/admin"];
+
+/// Middleware rejecting requests while maintenance mode is on
+#[derive(Default, Clone)]
+pub struct Maintenance;
+
+impl Maintenance {
+    pub fn new() -> Self {
+        Maintenance
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Maintenance
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = MaintenanceMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(MaintenanceMiddleware { service }))
+    }
+}
+
+pub struct MaintenanceMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for MaintenanceMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let maintenance = req
+            .app_data::<web::Data<Config>>()
+            .map(|config| config.live().get().maintenance.clone())
+            .filter(|maintenance| maintenance.enabled);
+
+        match maintenance {
+            Some(maintenance) if !is_exempt(req.path()) => {
+                let error = ApiError::new(
+                    ErrorCode::ServiceUnavailable,
+                    maintenance.message,
+                    ErrorContext::new(),
+                )
+                .with_retry_after(maintenance.retry_after_secs);
+                Box::pin(async move { Err(error.into()) })
+            }
+            _ => Box::pin(self.service.call(req)),
+        }
+    }
+}
+
+fn is_exempt(path: &str) -> bool {
+    EXEMPT_PREFIXES.iter().any(|prefix| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_exempt() {
+        assert!(is_exempt("/v1/health/ready"));
+        assert!(is_exempt("/v1/admin"));
+        assert!(!is_exempt("/v1/administrators"));
+        assert!(!is_exempt("/v1/organizations"));
+    }
+}
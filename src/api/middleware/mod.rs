@@ -4,13 +4,23 @@
 //! cross-cutting concerns in the request processing pipeline.
 
 pub mod auth;
+pub mod cors;
+pub mod docs_access;
+pub mod error_reporter;
+pub mod localization;
+pub mod maintenance;
 pub mod rate_limit;
 pub mod request_id;
 pub mod security;
 pub mod validation;
 
 // Re-export commonly used middleware
-pub use auth::{Auth, AuthenticatedUser, RequireAuth, RequireRole};
+pub use auth::{Auth, AuthenticatedUser, RequireAuth, RequirePermission, RequireRole, RequireSubscription, ScimClient, TelemetryClient, Tenant};
+pub use cors::Cors;
+pub use docs_access::DocsAccess;
+pub use error_reporter::ErrorReporter;
+pub use localization::Localization;
+pub use maintenance::Maintenance;
 pub use rate_limit::RateLimit;
 pub use request_id::RequestId;
 pub use security::SecurityHeaders;
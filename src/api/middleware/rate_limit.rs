@@ -11,6 +11,10 @@
 //! - Configurable burst and replenishment rates
 //! - Thread-safe state management
 //! - Proper error responses with retry-after headers
+//! - Limits shared by all instances when Redis is configured, falling back
+//!   to per-instance counters while Redis is unreachable
+//! - Limits follow `live.rate_limit` when the app has a `Config`, so they
+//!   change on config reload
 //! 
 //! # Configuration
 //! 
@@ -34,20 +38,25 @@
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
+use std::rc::Rc;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
-use actix_web::Error;
+use actix_web::{web, Error};
+use crate::error::ApiError;
+use crate::infrastructure::RedisClient;
+use crate::utils::Config;
 use futures::future::{ok, Ready};
+use tracing::warn;
 
 /// Type alias for the shared rate limit state
 type RateLimitState = Arc<Mutex<HashMap<String, (u32, Instant)>>>;
 
-/// Configuration for the rate limit middleware
+/// Configuration for the rate limit middleware, used when the app has no
+/// `Config` to take live limits from
 #[derive(Clone)]
 pub struct RateLimit {
-    #[allow(dead_code)]
     /// Maximum number of requests allowed in the window
     max_requests: u32,
     /// Time window in seconds
@@ -71,7 +80,7 @@ impl RateLimit {
 
 impl<S, B> Transform<S, ServiceRequest> for RateLimit
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -83,7 +92,7 @@ where
 
     fn new_transform(&self, service: S) -> Self::Future {
         ok(RateLimitMiddleware {
-            service,
+            service: Rc::new(service),
             state: Arc::new(Mutex::new(HashMap::new())),
             config: self.clone(),
         })
@@ -92,14 +101,56 @@ where
 
 /// The actual middleware that performs rate limiting
 pub struct RateLimitMiddleware<S> {
-    service: S,
+    service: Rc<S>,
     state: RateLimitState,
     config: RateLimit,
 }
 
+impl<S> RateLimitMiddleware<S> {
+    /// Counts a request in this instance's window, returning the seconds to
+    /// wait once the client is over the limit
+    fn check_local(state: &RateLimitState, config: &RateLimit, ip: String) -> Option<u64> {
+        let mut state = state.lock().unwrap();
+        let now = Instant::now();
+
+        // Check if client has existing rate limit entry
+        let (count, start) = match state.get(&ip).map(|(c, s)| (*c, *s)) {
+            // Reset if window has passed
+            Some((_, start)) if now.duration_since(start).as_secs() >= u64::from(config.window_seconds) => (1, now),
+            // Increment counter
+            Some((count, start)) => (count.saturating_add(1), start),
+            // First request from this client
+            None => (1, now),
+        };
+        state.insert(ip, (count, start));
+        drop(state);
+
+        if count > config.max_requests {
+            let elapsed = now.duration_since(start).as_secs();
+            return Some(u64::from(config.window_seconds).saturating_sub(elapsed).max(1));
+        }
+        None
+    }
+
+    /// Counts a request in the window shared through Redis
+    ///
+    /// Windows are aligned to the epoch so every instance uses the same key.
+    async fn check_shared(redis: &RedisClient, config: &RateLimit, ip: &str) -> Result<Option<u64>, ApiError> {
+        let window = u64::from(config.window_seconds.max(1));
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let key = format!("ratelimit:{}:{}", ip, now / window);
+        let count = redis.incr_ex(&key, Duration::from_secs(window)).await?;
+
+        if count > u64::from(config.max_requests) {
+            return Ok(Some((window - now % window).max(1)));
+        }
+        Ok(None)
+    }
+}
+
 impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -116,26 +167,38 @@ where
             .unwrap_or("unknown")
             .to_string();
 
-        let mut state = self.state.lock().unwrap();
-        let now = Instant::now();
-
-        // Check if client has existing rate limit entry
-        if let Some((count, start)) = state.get(&ip).map(|(c, s)| (*c, *s)) {
-            let elapsed = now.duration_since(start).as_secs() as u32;
-            
-            // Reset if window has passed
-            if elapsed >= self.config.window_seconds {
-                state.insert(ip.clone(), (1, now));
-            } else {
-                // Increment counter
-                state.insert(ip.clone(), (count + 1, start));
+        let app_config = req.app_data::<web::Data<Config>>();
+        let config = app_config.map_or_else(
+            || self.config.clone(),
+            |config| {
+                let limits = config.live().get().rate_limit.clone();
+                RateLimit::new(limits.max_requests, limits.window_secs)
+            },
+        );
+        let redis = app_config.and_then(|config| config.redis().cloned());
+        let Some(redis) = redis else {
+            if let Some(retry_after) = Self::check_local(&self.state, &config, ip) {
+                let error = ApiError::rate_limited("Too many requests", retry_after);
+                return Box::pin(async move { Err(error.into()) });
             }
-        } else {
-            // First request from this client
-            state.insert(ip.clone(), (1, now));
-        }
+            let fut = self.service.call(req);
+            return Box::pin(fut);
+        };
 
-        let fut = self.service.call(req);
-        Box::pin(fut)
+        let service = self.service.clone();
+        let state = self.state.clone();
+        Box::pin(async move {
+            let limited = match Self::check_shared(&redis, &config, &ip).await {
+                Ok(limited) => limited,
+                Err(e) => {
+                    warn!(error = %e, "Shared rate limit unavailable, limiting per instance");
+                    Self::check_local(&state, &config, ip)
+                }
+            };
+            if let Some(retry_after) = limited {
+                return Err(ApiError::rate_limited("Too many requests", retry_after).into());
+            }
+            service.call(req).await
+        })
     }
-} 
\ No newline at end of file
+}
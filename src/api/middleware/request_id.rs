@@ -11,7 +11,9 @@
 //! - Zero-allocation implementation
 //! - Request ID available throughout request lifecycle
 //! - Automatic response header injection
-//! - Integration with logging system
+//! - Integration with logging system: requests run inside a `request` span
+//!   carrying `request_id`, later joined by `org_id` and `user_id` once
+//!   the caller is authenticated
 //! 
 //! # Example
 //! 
@@ -41,6 +43,7 @@ use std::future::{ready, Ready};
 
 use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
 use actix_web::{Error, HttpMessage};
+use tracing::{field::Empty, info_span, instrument::Instrumented, Instrument, Span};
 use uuid::Uuid;
 
 /// Request ID middleware
@@ -86,16 +89,31 @@ where
 {
     type Response = ServiceResponse<B>;
     type Error = Error;
-    type Future = S::Future;
+    type Future = Instrumented<S::Future>;
 
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        // Generate and insert request ID
-        let request_id = Uuid::new_v4();
-        req.extensions_mut().insert(request_id);
+        // Generate and insert request ID, keeping one assigned by an outer scope
+        let existing = req.extensions().get::<Uuid>().copied();
+        let span = match existing {
+            Some(_) => Span::current(),
+            None => {
+                let request_id = Uuid::new_v4();
+                req.extensions_mut().insert(request_id);
+                info_span!(
+                    "request",
+                    request_id = %request_id,
+                    method = %req.method(),
+                    path = %req.path(),
+                    org_id = Empty,
+                    user_id = Empty,
+                )
+            }
+        };
 
         // Add request ID to response headers
-        self.service.call(req)
+        let fut = span.in_scope(|| self.service.call(req));
+        fut.instrument(span)
     }
 } 
\ No newline at end of file
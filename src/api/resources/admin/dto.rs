@@ -0,0 +1,418 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    api::resources::organization::dto::ListOrganizationsQuery,
+    db::models::{auth::Role, Archive, LegalHold, OrganizationFilter, ScheduledJobState, ScimToken},
+    domain::{auth::Permission, organization::Quotas},
+    error::ApiError,
+    jobs::{archive::ArchiveRecord, scheduler::DISABLED},
+    utils::LiveSettings,
+};
+
+/// Query parameters for listing archives
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListArchivesQuery {
+    pub dataset: Option<String>,
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// Query parameters for listing queued and dead-letter jobs
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListQueueJobsQuery {
+    pub kind: Option<String>,
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// Archive stub response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ArchiveResponse {
+    pub id: Uuid,
+    pub dataset: String,
+    #[ts(type = "number")]
+    pub record_count: i64,
+    #[ts(type = "number")]
+    pub byte_size: i64,
+    pub range_start: DateTime<Utc>,
+    pub range_end: DateTime<Utc>,
+    pub rehydrated_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Records read back from an archive
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ArchiveRecordsResponse {
+    pub archive: ArchiveResponse,
+    pub records: Vec<ArchiveRecord>,
+}
+
+impl From<Archive> for ArchiveResponse {
+    fn from(archive: Archive) -> Self {
+        Self {
+            id: archive.id,
+            dataset: archive.dataset,
+            record_count: archive.record_count,
+            byte_size: archive.byte_size,
+            range_start: archive.range_start,
+            range_end: archive.range_end,
+            rehydrated_at: archive.rehydrated_at,
+            created_at: archive.created_at,
+        }
+    }
+}
+
+/// Input for placing a legal hold
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct CreateLegalHoldInput {
+    /// Entity type of the held record, e.g. `users`
+    #[validate(length(min = 1, max = 100))]
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    #[validate(length(min = 1, max = 2000))]
+    pub reason: String,
+}
+
+/// Query parameters for listing legal holds
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListLegalHoldsQuery {
+    pub entity_type: Option<String>,
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// Legal hold response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct LegalHoldResponse {
+    pub id: Uuid,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub reason: String,
+    pub placed_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Retention applied to one entity type
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct EntityRetentionResponse {
+    pub entity_type: String,
+    /// Days soft-deleted records are kept, `null` when they are kept forever
+    #[ts(type = "number | null")]
+    pub retention_days: Option<i64>,
+    /// Number of records currently exempt from purging
+    #[ts(type = "number")]
+    pub active_holds: i64,
+}
+
+/// Effective soft-delete retention policy
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct RetentionPolicyResponse {
+    #[ts(type = "number | null")]
+    pub default_retention_days: Option<i64>,
+    pub entities: Vec<EntityRetentionResponse>,
+}
+
+impl From<LegalHold> for LegalHoldResponse {
+    fn from(hold: LegalHold) -> Self {
+        Self {
+            id: hold.id,
+            entity_type: hold.entity_type,
+            entity_id: hold.entity_id,
+            reason: hold.reason,
+            placed_by: hold.placed_by,
+            created_at: hold.created_at,
+        }
+    }
+}
+
+/// Input for replacing the log filter
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct UpdateLogFilterInput {
+    /// `EnvFilter` directives, e.g. `info,rust_server::domain::optimization=debug`
+    #[validate(length(min = 1, max = 1000))]
+    pub filter: String,
+}
+
+/// Active log filter
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct LogFilterResponse {
+    pub filter: String,
+}
+
+/// Outcome of a configuration reload
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ReloadConfigResponse {
+    /// Keys of the `[live]` section whose values changed
+    pub changed: Vec<String>,
+    pub settings: LiveSettings,
+}
+
+/// Schedule and last run of a recurring background job
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ScheduledJobResponse {
+    pub name: String,
+    /// Cron expression in effect, `off` when disabled
+    pub schedule: String,
+    /// Expression set through the admin API, replacing the configured one
+    pub schedule_override: Option<String>,
+    pub next_run_at: DateTime<Utc>,
+    /// Whether an instance currently holds the job's lease
+    pub running: bool,
+    /// Instance running the job
+    pub locked_by: Option<String>,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    /// `running`, `succeeded` or `failed`
+    pub last_status: Option<String>,
+    pub last_error: Option<String>,
+    #[ts(type = "number | null")]
+    pub last_duration_ms: Option<i64>,
+    /// Summary returned by the last run
+    pub last_output: Option<serde_json::Value>,
+}
+
+impl ScheduledJobResponse {
+    /// Builds the response, `configured` being the job's schedule from config
+    pub fn new(state: ScheduledJobState, configured: Option<&str>) -> Self {
+        let running = state.is_running(Utc::now());
+        Self {
+            schedule: state
+                .schedule
+                .clone()
+                .or_else(|| configured.map(str::to_string))
+                .unwrap_or_else(|| DISABLED.to_string()),
+            name: state.name,
+            schedule_override: state.schedule,
+            next_run_at: state.next_run_at,
+            running,
+            locked_by: state.locked_by.filter(|_| running),
+            last_started_at: state.last_started_at,
+            last_finished_at: state.last_finished_at,
+            last_status: state.last_status,
+            last_error: state.last_error,
+            last_duration_ms: state.last_duration_ms,
+            last_output: state.last_output,
+        }
+    }
+}
+
+/// All recurring background jobs
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ScheduledJobsResponse {
+    pub jobs: Vec<ScheduledJobResponse>,
+}
+
+/// Input for overriding a job's schedule
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct UpdateJobScheduleInput {
+    /// Cron expression (`sec min hour day month weekday`) or `off`; `null`
+    /// restores the configured schedule
+    #[validate(length(min = 1, max = 255))]
+    pub schedule: Option<String>,
+}
+
+/// Input for setting an organization's email sender
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct UpdateEmailSenderInput {
+    /// Address mail is sent from; its domain must be verified with the mail provider
+    #[validate(email, length(max = 255))]
+    pub from_address: String,
+    #[validate(length(min = 1, max = 255))]
+    pub from_name: Option<String>,
+    #[validate(email, length(max = 255))]
+    pub reply_to: Option<String>,
+}
+
+/// Query parameters for listing organizations as an admin, which can
+/// include the soft-deleted ones
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListAdminOrganizationsQuery {
+    /// Text the name has to contain, ignoring case
+    pub name_contains: Option<String>,
+    /// Only organizations created after this RFC 3339 timestamp or date
+    pub created_after: Option<String>,
+    /// `name`, `created_at` or `updated_at`, with `-` for descending
+    pub sort: Option<String>,
+    /// `true` to list soft-deleted organizations too, with their `deleted_at`
+    pub include_deleted: Option<bool>,
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+impl ListAdminOrganizationsQuery {
+    /// The organizations asked for, filtered like the organization list
+    pub fn filter(&self) -> Result<OrganizationFilter, ApiError> {
+        let filter = ListOrganizationsQuery {
+            name_contains: self.name_contains.clone(),
+            created_after: self.created_after.clone(),
+            sort: self.sort.clone(),
+            page: self.page,
+            per_page: self.per_page,
+        }
+        .filter()?;
+        Ok(OrganizationFilter {
+            include_deleted: self.include_deleted.unwrap_or(false),
+            ..filter
+        })
+    }
+}
+
+/// Limits of an organization's plan; limits left out or `null` are unset
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct UpdateQuotasInput {
+    /// At least 1; unset allows any number of users
+    #[ts(optional)]
+    pub max_users: Option<u32>,
+    /// Unset allows any number of active harvest blocks
+    #[ts(optional)]
+    pub max_active_blocks: Option<u32>,
+    /// 1 to 3650; unset keeps telemetry for the default 90 days
+    #[ts(optional)]
+    pub telemetry_retention_days: Option<u32>,
+}
+
+impl From<UpdateQuotasInput> for Quotas {
+    fn from(input: UpdateQuotasInput) -> Self {
+        Self {
+            max_users: input.max_users,
+            max_active_blocks: input.max_active_blocks,
+            telemetry_retention_days: input.telemetry_retention_days,
+        }
+    }
+}
+
+/// Limits of an organization's plan
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OrganizationQuotasResponse {
+    pub org_id: Uuid,
+    /// `null` for any number of users
+    pub max_users: Option<u32>,
+    /// `null` for any number of active harvest blocks
+    pub max_active_blocks: Option<u32>,
+    /// Days telemetry is kept
+    pub telemetry_retention_days: u32,
+}
+
+impl OrganizationQuotasResponse {
+    pub fn new(org_id: Uuid, quotas: Quotas) -> Self {
+        Self {
+            org_id,
+            max_users: quotas.max_users,
+            max_active_blocks: quotas.max_active_blocks,
+            telemetry_retention_days: quotas.retention_days(),
+        }
+    }
+}
+
+/// Input for claiming an email domain for an organization's single sign-on
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct AddSsoDomainInput {
+    /// Email domain whose users are provisioned into the organization, e.g. `pinecorp.com`
+    #[validate(length(min = 3, max = 255))]
+    pub domain: String,
+    /// Role of provisioned users; Operator when omitted
+    pub role: Option<Role>,
+}
+
+/// Input for issuing a SCIM provisioning token
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct CreateScimTokenInput {
+    /// What the token is for, e.g. the identity provider using it
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+/// A SCIM provisioning token, without the token itself
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ScimTokenResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub created_by: Option<Uuid>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ScimToken> for ScimTokenResponse {
+    fn from(token: ScimToken) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            created_by: token.created_by,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+/// A newly issued SCIM provisioning token
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct IssuedScimTokenResponse {
+    pub id: Uuid,
+    pub name: String,
+    /// The bearer token for the identity provider; it is not shown again
+    pub token: String,
+    /// Base URL to configure in the identity provider, relative to the API's host
+    pub base_url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Permissions of one role
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct RolePermissionsResponse {
+    pub role: Role,
+    pub permissions: Vec<Permission>,
+}
+
+/// Every permission and the roles holding them
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct PermissionsResponse {
+    /// Every permission routes can require
+    pub permissions: Vec<Permission>,
+    pub roles: Vec<RolePermissionsResponse>,
+}
+
+/// Input for replacing the permissions of a role
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct UpdateRolePermissionsInput {
+    pub permissions: Vec<Permission>,
+}
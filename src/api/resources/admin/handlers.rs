@@ -0,0 +1,1785 @@
+//! Admin resource handlers
+//!
+//! This module contains the handlers for operator-facing maintenance
+//! endpoints. All routes require the admin role; those changing settings
+//! shared across organizations also require a platform admin.
+
+use crate::{
+    api::{
+        middleware::AuthenticatedUser,
+        utils::{ApiResponseBuilder, ErrorResponse},
+    },
+    db::{
+        get_connection,
+        models::Organization,
+        repositories::{OrganizationRepository, OrganizationRepositoryImpl, Repository},
+        DbPool,
+    },
+    error::{ApiError, ErrorCode, ErrorContext},
+    utils::Config,
+};
+use actix_web::{web, HttpResponse};
+use diesel::PgConnection;
+use uuid::Uuid;
+
+/// Whether `user` is listed in `auth.platform_admins`
+fn is_platform_admin(user: &AuthenticatedUser, config: &Config) -> bool {
+    Uuid::parse_str(user.user_id()).is_ok_and(|id| config.auth.platform_admins.contains(&id))
+}
+
+/// Refuses callers who are not platform admins
+fn require_platform_admin(user: &AuthenticatedUser, config: &Config) -> Result<(), ApiError> {
+    if is_platform_admin(user, config) {
+        return Ok(());
+    }
+    Err(ApiError::new(
+        ErrorCode::Forbidden,
+        "Platform admin required",
+        ErrorContext::new().with_details(serde_json::json!({ "code": "PLATFORM_ADMIN_REQUIRED" })),
+    ))
+}
+
+/// Finds an organization the caller administers: any for platform admins,
+/// otherwise the caller's own or one under it, answering 404 for the rest
+async fn administered_organization(
+    conn: &mut PgConnection,
+    user: &AuthenticatedUser,
+    config: &Config,
+    tenant: Uuid,
+    organization_id: Uuid,
+) -> Result<Organization, ApiError> {
+    if is_platform_admin(user, config) {
+        OrganizationRepositoryImpl.find_by_id(conn, organization_id).await
+    } else {
+        OrganizationRepositoryImpl.find_governed(conn, tenant, organization_id).await
+    }
+}
+
+pub mod archives {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::AuthenticatedUser,
+            resources::admin::dto::{ArchiveRecordsResponse, ArchiveResponse, ListArchivesQuery},
+            utils::{PaginatedResponse, PaginationParams},
+        },
+        db::repositories::{ArchiveRepository, ArchiveRepositoryImpl, Repository},
+        infrastructure::dependencies,
+        jobs::archive::Archiver,
+    };
+
+    /// Lists archive stubs, optionally filtered by dataset
+    ///
+    /// Archives hold the records of every organization, so only platform
+    /// admins reach them.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/archives",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "List of archives", body = PaginatedResponse<ArchiveResponse>),
+            (status = 401, description = "Unauthorized", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("dataset" = Option<String>, Query, description = "Only list archives of this dataset"),
+            ("page" = Option<i64>, Query, description = "Page number"),
+            ("per_page" = Option<i64>, Query, description = "Number of items per page")
+        )
+    )]
+    pub async fn list_archives(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        query: web::Query<ListArchivesQuery>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(10));
+        let repo = ArchiveRepositoryImpl;
+
+        let mut conn = get_connection(&pool)?;
+        let archives = match query.dataset.as_deref() {
+            Some(dataset) => repo.list_by_dataset(&mut conn, dataset, &pagination).await?,
+            None => repo.list(&mut conn, &pagination).await?,
+        };
+        let total = repo.count(&mut conn, query.dataset.as_deref()).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Archives retrieved successfully")
+                .with_data(PaginatedResponse::with_count(
+                    archives.into_iter().map(ArchiveResponse::from).collect(),
+                    total,
+                    &pagination
+                ))
+                .build()
+        ))
+    }
+
+    /// Retrieves a single archive stub
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/archives/{id}",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Archive found", body = ArchiveResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Archive not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Archive ID")
+        )
+    )]
+    pub async fn get_archive(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        archive_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let mut conn = get_connection(&pool)?;
+        let archive = ArchiveRepositoryImpl.find_by_id(&mut conn, *archive_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Archive retrieved successfully")
+                .with_data(ArchiveResponse::from(archive))
+                .build()
+        ))
+    }
+
+    /// Reads the records of an archive from cold storage
+    ///
+    /// The records are returned as stored and are not written back to the
+    /// hot database.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/archives/{id}/records",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Archived records", body = ArchiveRecordsResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Archive not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse),
+            (status = 503, description = "Object storage is down", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Archive ID")
+        )
+    )]
+    pub async fn get_archive_records(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        archive_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        config.dependencies().require(dependencies::STORAGE)?;
+        let archiver = Archiver::from_config(&config);
+
+        let mut conn = get_connection(&pool)?;
+        let archive = ArchiveRepositoryImpl.find_by_id(&mut conn, *archive_id).await?;
+        let records = archiver.read(&archive).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Archived records retrieved successfully")
+                .with_data(ArchiveRecordsResponse {
+                    archive: archive.into(),
+                    records,
+                })
+                .build()
+        ))
+    }
+
+    /// Restores the records of an archive into the hot database
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/admin/archives/{id}/rehydrate",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Archive rehydrated", body = ArchiveResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Archive not found", body = ErrorResponse),
+            (status = 422, description = "Dataset can no longer be rehydrated", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse),
+            (status = 503, description = "Object storage is down", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Archive ID")
+        )
+    )]
+    pub async fn rehydrate_archive(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        archive_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        config.dependencies().require(dependencies::STORAGE)?;
+        let archiver = Archiver::from_config(&config);
+
+        let mut conn = get_connection(&pool)?;
+        let archive = archiver.rehydrate(&mut conn, *archive_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Archive rehydrated successfully")
+                .with_data(ArchiveResponse::from(archive))
+                .build()
+        ))
+    }
+}
+
+pub mod legal_holds {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::AuthenticatedUser,
+            resources::admin::dto::{
+                CreateLegalHoldInput, EntityRetentionResponse, LegalHoldResponse, ListLegalHoldsQuery,
+                RetentionPolicyResponse,
+            },
+            utils::{PaginatedResponse, PaginationParams},
+        },
+        db::repositories::{LegalHoldRepository, LegalHoldRepositoryImpl},
+        domain::retention::{LegalHoldService, PURGE_TARGETS},
+        jobs::purge::Purger,
+    };
+
+    /// Lists active legal holds, optionally filtered by entity type
+    ///
+    /// Holds span organizations, so only platform admins manage them.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/legal-holds",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "List of legal holds", body = PaginatedResponse<LegalHoldResponse>),
+            (status = 401, description = "Unauthorized", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("entity_type" = Option<String>, Query, description = "Only list holds on this entity type"),
+            ("page" = Option<i64>, Query, description = "Page number"),
+            ("per_page" = Option<i64>, Query, description = "Number of items per page")
+        )
+    )]
+    pub async fn list_legal_holds(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        query: web::Query<ListLegalHoldsQuery>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let service = LegalHoldService::new(LegalHoldRepositoryImpl);
+        let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(10));
+
+        let mut conn = get_connection(&pool)?;
+        let holds = service.list(&mut conn, query.entity_type.as_deref(), &pagination).await?;
+        let total = service.count(&mut conn, query.entity_type.as_deref()).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Legal holds retrieved successfully")
+                .with_data(PaginatedResponse::with_count(
+                    holds.into_iter().map(LegalHoldResponse::from).collect(),
+                    total,
+                    &pagination
+                ))
+                .build()
+        ))
+    }
+
+    /// Places a legal hold on a record, exempting it from purging
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/admin/legal-holds",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        request_body = CreateLegalHoldInput,
+        responses(
+            (status = 201, description = "Legal hold placed", body = LegalHoldResponse),
+            (status = 400, description = "Bad request", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 409, description = "Record is already under legal hold", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        )
+    )]
+    pub async fn create_legal_hold(
+        pool: web::Data<DbPool>,
+        user: AuthenticatedUser,
+        config: web::Data<Config>,
+        input: web::Json<CreateLegalHoldInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let service = LegalHoldService::new(LegalHoldRepositoryImpl);
+        let placed_by = Uuid::parse_str(user.user_id()).ok();
+
+        let mut conn = get_connection(&pool)?;
+        let hold = service.place(&mut conn, input.into_inner(), placed_by).await?;
+
+        Ok(HttpResponse::Created().json(
+            ApiResponseBuilder::success()
+                .with_message("Legal hold placed successfully")
+                .with_data(LegalHoldResponse::from(hold))
+                .build()
+        ))
+    }
+
+    /// Releases a legal hold
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        delete,
+        path = "/v1/admin/legal-holds/{id}",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 204, description = "Legal hold released"),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Legal hold not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Legal hold ID")
+        )
+    )]
+    pub async fn release_legal_hold(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        hold_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let service = LegalHoldService::new(LegalHoldRepositoryImpl);
+
+        let mut conn = get_connection(&pool)?;
+        service.release(&mut conn, *hold_id).await?;
+
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    /// Returns the effective soft-delete retention per entity type
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/retention",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Retention policy", body = RetentionPolicyResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        )
+    )]
+    pub async fn get_retention_policy(
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+    ) -> Result<HttpResponse, ApiError> {
+        let purger = Purger::from_config(&config)?;
+        let policy = purger.policy();
+
+        let mut conn = get_connection(&pool)?;
+        let holds = LegalHoldRepositoryImpl.count_by_entity_type(&mut conn).await?;
+
+        let entities = PURGE_TARGETS
+            .iter()
+            .map(|target| EntityRetentionResponse {
+                entity_type: target.entity.to_string(),
+                retention_days: policy.retention_days(target.entity),
+                active_holds: holds
+                    .iter()
+                    .find(|(entity, _)| entity == target.entity)
+                    .map_or(0, |(_, count)| *count),
+            })
+            .collect();
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Retention policy retrieved successfully")
+                .with_data(RetentionPolicyResponse {
+                    default_retention_days: policy.default_days(),
+                    entities,
+                })
+                .build()
+        ))
+    }
+}
+
+pub mod logging {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::AuthenticatedUser,
+            resources::admin::dto::{LogFilterResponse, UpdateLogFilterInput},
+        },
+        error::ErrorContext,
+        infrastructure::cluster::{self, ClusterEvent},
+        utils::logging,
+    };
+    use tracing::{info, warn};
+    use validator::Validate as ValidatorValidate;
+
+    /// Returns the active log filter
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/log-filter",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Active log filter", body = LogFilterResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        )
+    )]
+    pub async fn get_log_filter() -> Result<HttpResponse, ApiError> {
+        let filter = logging::filter()?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Log filter retrieved successfully")
+                .with_data(LogFilterResponse { filter })
+                .build()
+        ))
+    }
+
+    /// Replaces the log filter without restarting the server
+    ///
+    /// The change reaches every instance sharing the Redis server and lasts
+    /// until the next restart or config reload. Only platform admins change
+    /// it, as it applies to every organization.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        put,
+        path = "/v1/admin/log-filter",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        request_body = UpdateLogFilterInput,
+        responses(
+            (status = 200, description = "Log filter updated", body = LogFilterResponse),
+            (status = 400, description = "Invalid filter directives", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        )
+    )]
+    pub async fn update_log_filter(
+        user: AuthenticatedUser,
+        config: web::Data<Config>,
+        input: web::Json<UpdateLogFilterInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        if let Err(e) = ValidatorValidate::validate(&input.0) {
+            return Err(ApiError::validation_with_context(
+                "Invalid input",
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(serde_json::json!(e))
+            ));
+        }
+
+        let filter = logging::set_filter(&input.filter)?;
+        info!(user_id = %user.user_id(), filter = %filter, "Log filter updated through admin API");
+        let event = ClusterEvent::SetLogFilter { filter: filter.clone() };
+        if let Err(e) = cluster::broadcast(config.redis(), event).await {
+            warn!(error = %e, "Failed to send the log filter to other instances");
+        }
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Log filter updated successfully")
+                .with_data(LogFilterResponse { filter })
+                .build()
+        ))
+    }
+}
+
+pub mod live_config {
+    use super::*;
+    use crate::{
+        api::{middleware::AuthenticatedUser, resources::admin::dto::ReloadConfigResponse},
+        infrastructure::cluster::{self, ClusterEvent},
+        utils::LiveSettings,
+    };
+    use tracing::{info, warn};
+
+    /// Returns the live settings in effect
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/config/live",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Live settings in effect", body = LiveSettings),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        )
+    )]
+    pub async fn get_live_config(config: web::Data<Config>) -> Result<HttpResponse, ApiError> {
+        let settings = LiveSettings::clone(&config.live().get());
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Live settings retrieved successfully")
+                .with_data(settings)
+                .build()
+        ))
+    }
+
+    /// Reads the configuration again and applies its `[live]` section
+    ///
+    /// Other sections are validated but only take effect on restart. An
+    /// invalid configuration is rejected and the current settings are kept.
+    /// Every instance sharing the Redis server reloads as well. Only platform
+    /// admins reload, as the settings apply to every organization.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/admin/config/reload",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Configuration reloaded", body = ReloadConfigResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 500, description = "Invalid configuration, current settings kept", body = ErrorResponse)
+        )
+    )]
+    pub async fn reload_config(
+        user: AuthenticatedUser,
+        config: web::Data<Config>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let changed = config.live().reload()?;
+        info!(user_id = %user.user_id(), changed = ?changed, "Live configuration reloaded through admin API");
+        if let Err(e) = cluster::broadcast(config.redis(), ClusterEvent::ReloadConfig).await {
+            warn!(error = %e, "Failed to ask other instances to reload");
+        }
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Configuration reloaded successfully")
+                .with_data(ReloadConfigResponse {
+                    changed,
+                    settings: LiveSettings::clone(&config.live().get()),
+                })
+                .build()
+        ))
+    }
+}
+
+pub mod scheduled_jobs {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::AuthenticatedUser,
+            resources::admin::dto::{ScheduledJobResponse, ScheduledJobsResponse, UpdateJobScheduleInput},
+        },
+        db::repositories::{ScheduledJobRepository, ScheduledJobRepositoryImpl},
+        error::ErrorContext,
+        jobs::scheduler::{next_run, parse_schedule},
+    };
+    use chrono::Utc;
+    use tracing::info;
+    use validator::Validate as ValidatorValidate;
+
+    fn configured<'a>(config: &'a Config, job: &str) -> Option<&'a str> {
+        config.scheduler.jobs.get(job).map(String::as_str)
+    }
+
+    /// Lists recurring jobs with their schedule and last run
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/jobs",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Scheduled jobs", body = ScheduledJobsResponse),
+            (status = 401, description = "Unauthorized", body = ErrorResponse),
+            (status = 403, description = "Forbidden", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        )
+    )]
+    pub async fn list_scheduled_jobs(
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let jobs = ScheduledJobRepositoryImpl.list(&mut conn).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Scheduled jobs retrieved successfully")
+                .with_data(ScheduledJobsResponse {
+                    jobs: jobs
+                        .into_iter()
+                        .map(|job| {
+                            let schedule = configured(&config, &job.name);
+                            ScheduledJobResponse::new(job, schedule)
+                        })
+                        .collect(),
+                })
+                .build()
+        ))
+    }
+
+    /// Returns the schedule and last run of one job
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/jobs/{name}",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        params(("name" = String, Path, description = "Job name, e.g. `purger`")),
+        responses(
+            (status = 200, description = "Scheduled job", body = ScheduledJobResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Job not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        )
+    )]
+    pub async fn get_scheduled_job(
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        name: web::Path<String>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let job = ScheduledJobRepositoryImpl.find_by_name(&mut conn, &name).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Scheduled job retrieved successfully")
+                .with_data(ScheduledJobResponse::new(job, configured(&config, &name)))
+                .build()
+        ))
+    }
+
+    /// Overrides the schedule of a job on all instances
+    ///
+    /// A `null` schedule removes the override and restores the configured
+    /// schedule. The override survives restarts. Only platform admins change
+    /// schedules, which apply to every organization.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        put,
+        path = "/v1/admin/jobs/{name}/schedule",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        params(("name" = String, Path, description = "Job name, e.g. `purger`")),
+        request_body = UpdateJobScheduleInput,
+        responses(
+            (status = 200, description = "Schedule updated", body = ScheduledJobResponse),
+            (status = 400, description = "Invalid cron expression", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Job not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        )
+    )]
+    pub async fn update_job_schedule(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        name: web::Path<String>,
+        input: web::Json<UpdateJobScheduleInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        if let Err(e) = ValidatorValidate::validate(&input.0) {
+            return Err(ApiError::validation_with_context(
+                "Invalid input",
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(serde_json::json!(e))
+            ));
+        }
+
+        let expression = input.schedule.as_deref().map(str::trim);
+        let schedule = match expression.or(configured(&config, &name)) {
+            Some(expression) => parse_schedule(expression)?,
+            None => None,
+        };
+
+        let repository = ScheduledJobRepositoryImpl;
+        let mut conn = get_connection(&pool)?;
+        repository.find_by_name(&mut conn, &name).await?;
+        let job = repository
+            .set_schedule(&mut conn, &name, expression, next_run(schedule.as_ref(), Utc::now()))
+            .await?;
+        info!(
+            user_id = %user.user_id(),
+            job = %name,
+            schedule = expression.unwrap_or("configured"),
+            next_run_at = %job.next_run_at,
+            "Job schedule updated through admin API"
+        );
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Job schedule updated successfully")
+                .with_data(ScheduledJobResponse::new(job, configured(&config, &name)))
+                .build()
+        ))
+    }
+}
+
+pub mod queue {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::AuthenticatedUser,
+            resources::admin::dto::ListQueueJobsQuery,
+            utils::{PaginatedResponse, PaginationParams},
+        },
+        db::{
+            models::{DeadLetterJob, QueuedJob},
+            repositories::{JobQueueRepository, JobQueueRepositoryImpl},
+        },
+    };
+    use tracing::info;
+
+    /// Lists jobs waiting in the queue or being processed, in run order
+    ///
+    /// The queue holds the jobs of every organization, with payloads such
+    /// as email addresses and tokens, so only platform admins reach it.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/queue/jobs",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Queued jobs", body = PaginatedResponse<QueuedJob>),
+            (status = 401, description = "Unauthorized", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("kind" = Option<String>, Query, description = "Only list jobs of this kind"),
+            ("page" = Option<i64>, Query, description = "Page number"),
+            ("per_page" = Option<i64>, Query, description = "Number of items per page")
+        )
+    )]
+    pub async fn list_queued_jobs(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        query: web::Query<ListQueueJobsQuery>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(10));
+        let repo = JobQueueRepositoryImpl;
+
+        let mut conn = get_connection(&pool)?;
+        let jobs = repo.list(&mut conn, query.kind.as_deref(), &pagination).await?;
+        let total = repo.count(&mut conn, query.kind.as_deref()).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Queued jobs retrieved successfully")
+                .with_data(PaginatedResponse::with_count(jobs, total, &pagination))
+                .build()
+        ))
+    }
+
+    /// Lists jobs that failed on every attempt, most recent first
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/queue/dead-letters",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Dead-letter jobs", body = PaginatedResponse<DeadLetterJob>),
+            (status = 401, description = "Unauthorized", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("kind" = Option<String>, Query, description = "Only list jobs of this kind"),
+            ("page" = Option<i64>, Query, description = "Page number"),
+            ("per_page" = Option<i64>, Query, description = "Number of items per page")
+        )
+    )]
+    pub async fn list_dead_letters(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        query: web::Query<ListQueueJobsQuery>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(10));
+        let repo = JobQueueRepositoryImpl;
+
+        let mut conn = get_connection(&pool)?;
+        let jobs = repo.list_dead_letters(&mut conn, query.kind.as_deref(), &pagination).await?;
+        let total = repo.count_dead_letters(&mut conn, query.kind.as_deref()).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Dead-letter jobs retrieved successfully")
+                .with_data(PaginatedResponse::with_count(jobs, total, &pagination))
+                .build()
+        ))
+    }
+
+    /// Retrieves a dead-letter job with its payload and last error
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/queue/dead-letters/{id}",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Dead-letter job found", body = DeadLetterJob),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Dead-letter job not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Job ID")
+        )
+    )]
+    pub async fn get_dead_letter(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        job_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let mut conn = get_connection(&pool)?;
+        let job = JobQueueRepositoryImpl.find_dead_letter(&mut conn, *job_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Dead-letter job retrieved successfully")
+                .with_data(job)
+                .build()
+        ))
+    }
+
+    /// Moves a dead-letter job back into the queue
+    ///
+    /// The job keeps its id and payload, runs as soon as a worker is free
+    /// and gets `queue.max_attempts` fresh attempts.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/admin/queue/dead-letters/{id}/requeue",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Job requeued", body = QueuedJob),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Dead-letter job not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Job ID")
+        )
+    )]
+    pub async fn requeue_dead_letter(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        job_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let mut conn = get_connection(&pool)?;
+        let job = JobQueueRepositoryImpl
+            .requeue(&mut conn, *job_id, config.queue.max_attempts)
+            .await?;
+        info!(
+            user_id = %user.user_id(),
+            job_id = %job.id,
+            kind = %job.kind,
+            "Dead-letter job requeued through admin API"
+        );
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Job requeued successfully")
+                .with_data(job)
+                .build()
+        ))
+    }
+
+    /// Deletes a dead-letter job without running it again
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        delete,
+        path = "/v1/admin/queue/dead-letters/{id}",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Job discarded", body = DeadLetterJob),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Dead-letter job not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Job ID")
+        )
+    )]
+    pub async fn discard_dead_letter(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        job_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let mut conn = get_connection(&pool)?;
+        let job = JobQueueRepositoryImpl.discard(&mut conn, *job_id).await?;
+        info!(
+            user_id = %user.user_id(),
+            job_id = %job.id,
+            kind = %job.kind,
+            "Dead-letter job discarded through admin API"
+        );
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Job discarded successfully")
+                .with_data(job)
+                .build()
+        ))
+    }
+}
+
+pub mod email_senders {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::{AuthenticatedUser, Tenant},
+            resources::admin::dto::UpdateEmailSenderInput,
+        },
+        db::{
+            models::OrganizationEmailSender,
+            repositories::{EmailSenderRepository, EmailSenderRepositoryImpl},
+        },
+        error::ErrorContext,
+    };
+    use chrono::Utc;
+    use tracing::info;
+    use validator::Validate as ValidatorValidate;
+
+    /// Returns the sender used for an organization's mail
+    ///
+    /// Admins manage the senders of their own organization and of those
+    /// under it; platform admins those of any.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/organizations/{id}/email-sender",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Email sender found", body = OrganizationEmailSender),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Organization uses the default sender", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn get_email_sender(
+        user: AuthenticatedUser,
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        administered_organization(&mut conn, &user, &config, tenant, *organization_id).await?;
+        let sender = EmailSenderRepositoryImpl
+            .find(&mut conn, *organization_id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::not_found(format!("Email sender of organization {} not found", organization_id))
+            })?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Email sender retrieved successfully")
+                .with_data(sender)
+                .build()
+        ))
+    }
+
+    /// Sets the sender used for an organization's mail
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        put,
+        path = "/v1/admin/organizations/{id}/email-sender",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        request_body = UpdateEmailSenderInput,
+        responses(
+            (status = 200, description = "Email sender saved", body = OrganizationEmailSender),
+            (status = 400, description = "Invalid input", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn update_email_sender(
+        user: AuthenticatedUser,
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+        input: web::Json<UpdateEmailSenderInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        if let Err(e) = ValidatorValidate::validate(&input.0) {
+            return Err(ApiError::validation_with_context(
+                "Invalid input",
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(serde_json::json!(e))
+            ));
+        }
+
+        let mut conn = get_connection(&pool)?;
+        administered_organization(&mut conn, &user, &config, tenant, *organization_id).await?;
+        let input = input.into_inner();
+        let now = Utc::now();
+        let sender = EmailSenderRepositoryImpl
+            .upsert(&mut conn, &OrganizationEmailSender {
+                org_id: *organization_id,
+                from_address: input.from_address,
+                from_name: input.from_name,
+                reply_to: input.reply_to,
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+        info!(
+            user_id = %user.user_id(),
+            org_id = %sender.org_id,
+            from_address = %sender.from_address,
+            "Organization email sender updated through admin API"
+        );
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Email sender saved successfully")
+                .with_data(sender)
+                .build()
+        ))
+    }
+
+    /// Removes an organization's sender, restoring the default
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        delete,
+        path = "/v1/admin/organizations/{id}/email-sender",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Email sender removed", body = OrganizationEmailSender),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Organization uses the default sender", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn delete_email_sender(
+        user: AuthenticatedUser,
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        administered_organization(&mut conn, &user, &config, tenant, *organization_id).await?;
+        let sender = EmailSenderRepositoryImpl.delete(&mut conn, *organization_id).await?;
+        info!(
+            user_id = %user.user_id(),
+            org_id = %sender.org_id,
+            "Organization email sender removed through admin API"
+        );
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Email sender removed successfully")
+                .with_data(sender)
+                .build()
+        ))
+    }
+}
+
+pub mod organizations {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::Tenant,
+            resources::admin::dto::ListAdminOrganizationsQuery,
+            utils::{PaginatedResponse, PaginationParams},
+        },
+        db::{models::Organization, repositories::OrganizationRepositoryImpl},
+        domain::organization::OrganizationService,
+    };
+
+    /// Lists the caller's organization and those under it like the
+    /// organization list, soft-deleted ones included when asked
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/organizations",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "List of organizations", body = PaginatedResponse<Organization>),
+            (status = 400, description = "Invalid search, timestamp or sort", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("name_contains" = Option<String>, Query, description = "Text the name has to contain, ignoring case"),
+            ("created_after" = Option<String>, Query, description = "Only organizations created after this RFC 3339 timestamp or date"),
+            ("sort" = Option<String>, Query, description = "`name`, `created_at` or `updated_at`, with `-` for descending; newest first by default"),
+            ("include_deleted" = Option<bool>, Query, description = "List soft-deleted organizations too"),
+            ("page" = Option<i64>, Query, description = "Number of items to skip"),
+            ("per_page" = Option<i64>, Query, description = "Number of items per page")
+        )
+    )]
+    pub async fn list_organizations(
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        query: web::Query<ListAdminOrganizationsQuery>,
+    ) -> Result<HttpResponse, ApiError> {
+        let filter = query.filter()?;
+        let pagination = PaginationParams {
+            page: (query.page.unwrap_or(0) / query.per_page.unwrap_or(10)) + 1,
+            per_page: query.per_page.unwrap_or(10),
+        };
+
+        let mut conn = get_connection(&pool)?;
+        let (organizations, total) = OrganizationService::new(OrganizationRepositoryImpl)
+            .list(&mut conn, tenant, &filter, &pagination)
+            .await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Organizations retrieved successfully")
+                .with_data(PaginatedResponse::with_count(organizations, total, &pagination))
+                .build()
+        ))
+    }
+}
+
+pub mod quotas {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::{AuthenticatedUser, Tenant},
+            resources::admin::dto::{OrganizationQuotasResponse, UpdateQuotasInput},
+        },
+        domain::organization::QuotaService,
+    };
+    use tracing::info;
+
+    /// Returns the limits of an organization's plan
+    ///
+    /// Admins see the plan of their own organization and of those under
+    /// it; platform admins see any.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/organizations/{id}/quotas",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Quotas of the organization", body = OrganizationQuotasResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn get_quotas(
+        user: AuthenticatedUser,
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let organization = administered_organization(&mut conn, &user, &config, tenant, *organization_id).await?;
+        let quotas = QuotaService::of(&mut conn, organization.id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Quotas retrieved successfully")
+                .with_data(OrganizationQuotasResponse::new(organization.id, quotas))
+                .build()
+        ))
+    }
+
+    /// Replaces the limits of an organization's plan
+    ///
+    /// Only platform admins change plans. Lowering a limit below current
+    /// usage keeps what exists and only stops more from being created.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        put,
+        path = "/v1/admin/organizations/{id}/quotas",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        request_body = UpdateQuotasInput,
+        responses(
+            (status = 200, description = "Quotas saved", body = OrganizationQuotasResponse),
+            (status = 400, description = "Limit out of range", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn update_quotas(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+        input: web::Json<UpdateQuotasInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let mut conn = get_connection(&pool)?;
+        let quotas = QuotaService::set(&mut conn, *organization_id, input.into_inner().into()).await?;
+        info!(
+            user_id = %user.user_id(),
+            org_id = %organization_id,
+            "Organization quotas updated through admin API"
+        );
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Quotas saved successfully")
+                .with_data(OrganizationQuotasResponse::new(*organization_id, quotas))
+                .build()
+        ))
+    }
+}
+
+pub mod sso_domains {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::{AuthenticatedUser, Tenant},
+            resources::admin::dto::AddSsoDomainInput,
+            utils::ListResponse,
+        },
+        db::{
+            models::{auth::Role, OrganizationSsoDomain},
+            repositories::{SsoRepository, SsoRepositoryImpl},
+        },
+        domain::auth::sso::normalize_domain,
+        error::ErrorContext,
+    };
+    use chrono::Utc;
+    use tracing::info;
+    use validator::Validate as ValidatorValidate;
+
+    /// Lists the email domains an organization provisions single sign-on users from
+    ///
+    /// Admins see the domains of their own organization and of those under
+    /// it; platform admins those of any.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/organizations/{id}/sso-domains",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "SSO domains of the organization", body = ListResponse<OrganizationSsoDomain>),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn list_sso_domains(
+        user: AuthenticatedUser,
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        administered_organization(&mut conn, &user, &config, tenant, *organization_id).await?;
+        let domains = SsoRepositoryImpl.list_domains(&mut conn, *organization_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("SSO domains retrieved successfully")
+                .with_data(ListResponse::new(domains))
+                .build()
+        ))
+    }
+
+    /// Claims an email domain, provisioning its single sign-on users into the organization
+    ///
+    /// Only platform admins claim domains, as a claim routes the sign-ons of
+    /// everyone with an address at the domain.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/admin/organizations/{id}/sso-domains",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        request_body = AddSsoDomainInput,
+        responses(
+            (status = 201, description = "SSO domain claimed", body = OrganizationSsoDomain),
+            (status = 400, description = "Invalid input", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 409, description = "Domain claimed by an organization", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn add_sso_domain(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+        input: web::Json<AddSsoDomainInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        if let Err(e) = ValidatorValidate::validate(&input.0) {
+            return Err(ApiError::validation_with_context(
+                "Invalid input",
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(serde_json::json!(e))
+            ));
+        }
+        let input = input.into_inner();
+        let domain = normalize_domain(&input.domain).ok_or_else(|| {
+            ApiError::validation(
+                "Invalid domain",
+                Some(serde_json::json!({ "field": "domain", "value": input.domain })),
+            )
+        })?;
+        let role = input.role.unwrap_or(Role::Operator);
+        if role == Role::Admin {
+            return Err(ApiError::validation(
+                "Single sign-on can't provision admins",
+                Some(serde_json::json!({ "field": "role" })),
+            ));
+        }
+
+        let mut conn = get_connection(&pool)?;
+        OrganizationRepositoryImpl.find_by_id(&mut conn, *organization_id).await?;
+        let claimed = SsoRepositoryImpl
+            .add_domain(&mut conn, &OrganizationSsoDomain {
+                domain,
+                org_id: *organization_id,
+                role,
+                created_at: Utc::now(),
+            })
+            .await?;
+        info!(
+            user_id = %user.user_id(),
+            org_id = %claimed.org_id,
+            domain = %claimed.domain,
+            "SSO domain claimed through admin API"
+        );
+
+        Ok(HttpResponse::Created().json(
+            ApiResponseBuilder::success()
+                .with_message("SSO domain claimed successfully")
+                .with_data(claimed)
+                .build()
+        ))
+    }
+
+    /// Releases an email domain; users already provisioned keep their accounts
+    ///
+    /// Admins release the domains of their own organization and of those
+    /// under it; platform admins those of any.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        delete,
+        path = "/v1/admin/organizations/{id}/sso-domains/{domain}",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "SSO domain released", body = OrganizationSsoDomain),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Domain not claimed by the organization", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID"),
+            ("domain" = String, Path, description = "Email domain")
+        )
+    )]
+    pub async fn remove_sso_domain(
+        user: AuthenticatedUser,
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        path: web::Path<(Uuid, String)>,
+    ) -> Result<HttpResponse, ApiError> {
+        let (organization_id, domain) = path.into_inner();
+        let mut conn = get_connection(&pool)?;
+        administered_organization(&mut conn, &user, &config, tenant, organization_id).await?;
+        let released = SsoRepositoryImpl
+            .remove_domain(&mut conn, organization_id, &domain.to_ascii_lowercase())
+            .await?;
+        info!(
+            user_id = %user.user_id(),
+            org_id = %released.org_id,
+            domain = %released.domain,
+            "SSO domain released through admin API"
+        );
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("SSO domain released successfully")
+                .with_data(released)
+                .build()
+        ))
+    }
+}
+
+pub mod scim_tokens {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::{AuthenticatedUser, Tenant},
+            resources::admin::dto::{CreateScimTokenInput, IssuedScimTokenResponse, ScimTokenResponse},
+            utils::ListResponse,
+        },
+        domain::scim::{ScimService, BASE_PATH},
+        error::ErrorContext,
+    };
+    use tracing::info;
+    use validator::Validate as ValidatorValidate;
+
+    /// Lists the SCIM provisioning tokens of an organization
+    ///
+    /// Admins manage the tokens of their own organization and of those
+    /// under it; platform admins those of any.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/organizations/{id}/scim-tokens",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Unrevoked SCIM tokens of the organization, newest first", body = ListResponse<ScimTokenResponse>),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn list_scim_tokens(
+        user: AuthenticatedUser,
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        administered_organization(&mut conn, &user, &config, tenant, *organization_id).await?;
+        let tokens = ScimService::list_tokens(&mut conn, *organization_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("SCIM tokens retrieved successfully")
+                .with_data(ListResponse::new(tokens.into_iter().map(ScimTokenResponse::from).collect()))
+                .build()
+        ))
+    }
+
+    /// Issues a token an identity provider provisions the organization's users with
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/admin/organizations/{id}/scim-tokens",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        request_body = CreateScimTokenInput,
+        responses(
+            (status = 201, description = "SCIM token issued; the token is only shown in this response", body = IssuedScimTokenResponse),
+            (status = 400, description = "Invalid input", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn create_scim_token(
+        user: AuthenticatedUser,
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+        input: web::Json<CreateScimTokenInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        if let Err(e) = ValidatorValidate::validate(&input.0) {
+            return Err(ApiError::validation_with_context(
+                "Invalid input",
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(serde_json::json!(e))
+            ));
+        }
+        let created_by = Uuid::parse_str(user.user_id())
+            .map_err(|_| ApiError::validation("Invalid user ID in token", None))?;
+
+        let mut conn = get_connection(&pool)?;
+        administered_organization(&mut conn, &user, &config, tenant, *organization_id).await?;
+        let (token, plaintext) = ScimService::issue_token(&mut conn, *organization_id, &input.name, created_by).await?;
+        info!(user_id = %created_by, org_id = %token.org_id, token_id = %token.id, "SCIM token issued through admin API");
+
+        Ok(HttpResponse::Created().json(
+            ApiResponseBuilder::success()
+                .with_message("SCIM token issued successfully")
+                .with_data(IssuedScimTokenResponse {
+                    id: token.id,
+                    name: token.name,
+                    token: plaintext,
+                    base_url: BASE_PATH.to_string(),
+                    created_at: token.created_at,
+                })
+                .build()
+        ))
+    }
+
+    /// Revokes a SCIM provisioning token
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        delete,
+        path = "/v1/admin/organizations/{id}/scim-tokens/{token_id}",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "SCIM token revoked", body = ScimTokenResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Organization or token not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID"),
+            ("token_id" = Uuid, Path, description = "SCIM token ID")
+        )
+    )]
+    pub async fn revoke_scim_token(
+        user: AuthenticatedUser,
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        path: web::Path<(Uuid, Uuid)>,
+    ) -> Result<HttpResponse, ApiError> {
+        let (organization_id, token_id) = path.into_inner();
+        let mut conn = get_connection(&pool)?;
+        administered_organization(&mut conn, &user, &config, tenant, organization_id).await?;
+        let token = ScimService::revoke_token(&mut conn, organization_id, token_id).await?;
+        info!(user_id = %user.user_id(), org_id = %token.org_id, token_id = %token.id, "SCIM token revoked through admin API");
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("SCIM token revoked successfully")
+                .with_data(ScimTokenResponse::from(token))
+                .build()
+        ))
+    }
+}
+
+pub mod permissions {
+    use super::*;
+    use crate::{
+        api::{
+            middleware::AuthenticatedUser,
+            resources::admin::dto::{PermissionsResponse, RolePermissionsResponse, UpdateRolePermissionsInput},
+        },
+        db::models::auth::Role,
+        domain::auth::{Permission, PermissionService},
+        infrastructure::cluster::{self, ClusterEvent},
+    };
+    use tracing::{info, warn};
+
+    /// Returns every permission and the roles holding them
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/admin/permissions",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        responses(
+            (status = 200, description = "Role permissions", body = PermissionsResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        )
+    )]
+    pub async fn list_permissions(pool: web::Data<DbPool>) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let policy = PermissionService::load(&mut conn).await?;
+        let roles = [Role::Admin, Role::Manager, Role::Operator]
+            .into_iter()
+            .map(|role| RolePermissionsResponse {
+                role,
+                permissions: policy.permissions(role),
+            })
+            .collect();
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Permissions retrieved successfully")
+                .with_data(PermissionsResponse {
+                    permissions: Permission::ALL.to_vec(),
+                    roles,
+                })
+                .build()
+        ))
+    }
+
+    /// Replaces the permissions of a role
+    ///
+    /// The built-in roles are shared by every organization, so only
+    /// platform admins change them. Admins hold every permission and can't
+    /// be changed.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        put,
+        path = "/v1/admin/roles/{role}/permissions",
+        security(("bearer_auth" = [])),
+        tag = "admin",
+        request_body = UpdateRolePermissionsInput,
+        responses(
+            (status = 200, description = "Role permissions replaced", body = RolePermissionsResponse),
+            (status = 400, description = "Unknown permission, or the admin role", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Platform admin required", body = ErrorResponse),
+            (status = 404, description = "Unknown role", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("role" = Role, Path, description = "Role")
+        )
+    )]
+    pub async fn update_role_permissions(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        role: web::Path<Role>,
+        input: web::Json<UpdateRolePermissionsInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        require_platform_admin(&user, &config)?;
+        let role = role.into_inner();
+        let mut conn = get_connection(&pool)?;
+        let permissions = PermissionService::set_role_permissions(&mut conn, role, &input.permissions).await?;
+        info!(
+            user_id = %user.user_id(),
+            role = ?role,
+            "Role permissions replaced through admin API"
+        );
+        if let Err(e) = cluster::broadcast(config.redis(), ClusterEvent::ReloadPermissions).await {
+            warn!(error = %e, "Failed to tell other instances about the permission change");
+        }
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Role permissions replaced successfully")
+                .with_data(RolePermissionsResponse { role, permissions })
+                .build()
+        ))
+    }
+}
@@ -0,0 +1,47 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequireRole},
+    db::models::auth::Role,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            .wrap(RequireRole::new(Role::Admin))
+            .wrap(Auth::new())
+            .route("/archives", web::get().to(crate::api::resources::admin::handlers::archives::list_archives))
+            .route("/archives/{id}", web::get().to(crate::api::resources::admin::handlers::archives::get_archive))
+            .route("/archives/{id}/records", web::get().to(crate::api::resources::admin::handlers::archives::get_archive_records))
+            .route("/archives/{id}/rehydrate", web::post().to(crate::api::resources::admin::handlers::archives::rehydrate_archive))
+            .route("/jobs", web::get().to(crate::api::resources::admin::handlers::scheduled_jobs::list_scheduled_jobs))
+            .route("/jobs/{name}", web::get().to(crate::api::resources::admin::handlers::scheduled_jobs::get_scheduled_job))
+            .route("/jobs/{name}/schedule", web::put().to(crate::api::resources::admin::handlers::scheduled_jobs::update_job_schedule))
+            .route("/queue/jobs", web::get().to(crate::api::resources::admin::handlers::queue::list_queued_jobs))
+            .route("/queue/dead-letters", web::get().to(crate::api::resources::admin::handlers::queue::list_dead_letters))
+            .route("/queue/dead-letters/{id}", web::get().to(crate::api::resources::admin::handlers::queue::get_dead_letter))
+            .route("/queue/dead-letters/{id}", web::delete().to(crate::api::resources::admin::handlers::queue::discard_dead_letter))
+            .route("/queue/dead-letters/{id}/requeue", web::post().to(crate::api::resources::admin::handlers::queue::requeue_dead_letter))
+            .route("/organizations", web::get().to(crate::api::resources::admin::handlers::organizations::list_organizations))
+            .route("/organizations/{id}/email-sender", web::get().to(crate::api::resources::admin::handlers::email_senders::get_email_sender))
+            .route("/organizations/{id}/email-sender", web::put().to(crate::api::resources::admin::handlers::email_senders::update_email_sender))
+            .route("/organizations/{id}/email-sender", web::delete().to(crate::api::resources::admin::handlers::email_senders::delete_email_sender))
+            .route("/organizations/{id}/quotas", web::get().to(crate::api::resources::admin::handlers::quotas::get_quotas))
+            .route("/organizations/{id}/quotas", web::put().to(crate::api::resources::admin::handlers::quotas::update_quotas))
+            .route("/organizations/{id}/sso-domains", web::get().to(crate::api::resources::admin::handlers::sso_domains::list_sso_domains))
+            .route("/organizations/{id}/sso-domains", web::post().to(crate::api::resources::admin::handlers::sso_domains::add_sso_domain))
+            .route("/organizations/{id}/sso-domains/{domain}", web::delete().to(crate::api::resources::admin::handlers::sso_domains::remove_sso_domain))
+            .route("/organizations/{id}/scim-tokens", web::get().to(crate::api::resources::admin::handlers::scim_tokens::list_scim_tokens))
+            .route("/organizations/{id}/scim-tokens", web::post().to(crate::api::resources::admin::handlers::scim_tokens::create_scim_token))
+            .route("/organizations/{id}/scim-tokens/{token_id}", web::delete().to(crate::api::resources::admin::handlers::scim_tokens::revoke_scim_token))
+            .route("/legal-holds", web::get().to(crate::api::resources::admin::handlers::legal_holds::list_legal_holds))
+            .route("/legal-holds", web::post().to(crate::api::resources::admin::handlers::legal_holds::create_legal_hold))
+            .route("/legal-holds/{id}", web::delete().to(crate::api::resources::admin::handlers::legal_holds::release_legal_hold))
+            .route("/retention", web::get().to(crate::api::resources::admin::handlers::legal_holds::get_retention_policy))
+            .route("/permissions", web::get().to(crate::api::resources::admin::handlers::permissions::list_permissions))
+            .route("/roles/{role}/permissions", web::put().to(crate::api::resources::admin::handlers::permissions::update_role_permissions))
+            .route("/log-filter", web::get().to(crate::api::resources::admin::handlers::logging::get_log_filter))
+            .route("/log-filter", web::put().to(crate::api::resources::admin::handlers::logging::update_log_filter))
+            .route("/config/live", web::get().to(crate::api::resources::admin::handlers::live_config::get_live_config))
+            .route("/config/reload", web::post().to(crate::api::resources::admin::handlers::live_config::reload_config))
+    );
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    db::models::{BlockSignoff, SignoffStep},
+    domain::approval::ApprovalStatus,
+    error::{ApiError, Result},
+};
+
+/// Input for signing off a block package
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SignBlockInput {
+    /// `planner`, `professional_forester` or `operations_manager`
+    pub step: String,
+    /// SHA-256 of the package document signed, in hex
+    pub document_hash: String,
+}
+
+impl SignBlockInput {
+    /// The step signed
+    pub fn step(&self) -> Result<SignoffStep> {
+        SignoffStep::parse(&self.step)
+            .ok_or_else(|| ApiError::validation(format!("Unknown sign-off step {}", self.step), None))
+    }
+}
+
+/// A sign-off on a block package
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct BlockSignoffResponse {
+    pub id: Uuid,
+    pub step: String,
+    pub signer_id: Option<Uuid>,
+    /// Name of the signer when they signed
+    pub signer_name: String,
+    pub document_hash: String,
+    pub signed_at: DateTime<Utc>,
+}
+
+impl From<BlockSignoff> for BlockSignoffResponse {
+    fn from(signoff: BlockSignoff) -> Self {
+        Self {
+            id: signoff.id,
+            step: signoff.step,
+            signer_id: signoff.signer_id,
+            signer_name: signoff.signer_name,
+            document_hash: signoff.document_hash,
+            signed_at: signoff.signed_at,
+        }
+    }
+}
+
+/// Where a block stands in the approval chain
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ApprovalStatusResponse {
+    pub block_id: Uuid,
+    /// Whether every step is signed, so the block can be activated
+    pub approved: bool,
+    /// Step to be signed next
+    pub next_step: Option<String>,
+    pub missing_steps: Vec<String>,
+    /// Hash of the package document signed so far
+    pub document_hash: Option<String>,
+    pub signoffs: Vec<BlockSignoffResponse>,
+}
+
+impl ApprovalStatusResponse {
+    pub fn new(block_id: Uuid, signoffs: Vec<BlockSignoff>, status: ApprovalStatus) -> Self {
+        Self {
+            block_id,
+            approved: status.approved(),
+            next_step: status.next_step.map(|step| step.as_str().to_string()),
+            missing_steps: status.missing_steps.iter().map(|step| step.as_str().to_string()).collect(),
+            document_hash: status.document_hash,
+            signoffs: signoffs.into_iter().map(BlockSignoffResponse::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Block approval resource handlers
+//!
+//! Every handler works on the sign-offs of a block of the authenticated
+//! user's organization. Any member can read them; who may sign or reset is
+//! decided by the signer's role.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::approval::dto::{ApprovalStatusResponse, BlockSignoffResponse, SignBlockInput},
+        utils::{ApiResponseBuilder, ErrorResponse},
+    },
+    db::{get_connection, repositories::SignoffRepositoryImpl, DbPool},
+    domain::approval::ApprovalService,
+    error::ApiError,
+};
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+
+fn service() -> ApprovalService<SignoffRepositoryImpl> {
+    ApprovalService::new(SignoffRepositoryImpl)
+}
+
+fn user_id(user: &AuthenticatedUser) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))
+}
+
+/// Retrieves the sign-offs of a block and where it stands in the chain
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/blocks/{block_id}/signoffs",
+    security(("bearer_auth" = [])),
+    tag = "approvals",
+    responses(
+        (status = 200, description = "Approval status", body = ApprovalStatusResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Path, description = "Harvest block ID")
+    )
+)]
+pub async fn get_block_signoffs(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    block_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let (signoffs, status) = service().status(&mut conn, org_id, *block_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Approval status retrieved successfully")
+            .with_data(ApprovalStatusResponse::new(*block_id, signoffs, status))
+            .build()
+    ))
+}
+
+/// Signs the next step of a block's approval chain as the current user
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/blocks/{block_id}/signoffs",
+    security(("bearer_auth" = [])),
+    tag = "approvals",
+    request_body = SignBlockInput,
+    responses(
+        (status = 201, description = "Block signed off", body = BlockSignoffResponse),
+        (status = 400, description = "Unknown step or invalid document hash", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "The step needs another role or another signer", body = ErrorResponse),
+        (status = 409, description = "Step out of order or already signed, or the package changed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Path, description = "Harvest block ID")
+    )
+)]
+pub async fn sign_block(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    block_id: web::Path<Uuid>,
+    input: web::Json<SignBlockInput>,
+) -> Result<HttpResponse, ApiError> {
+    let signer_id = user_id(&user)?;
+    let step = input.step()?;
+    let mut conn = get_connection(&pool)?;
+    let signoff = service()
+        .sign(&mut conn, org_id, *block_id, signer_id, step, &input.document_hash)
+        .await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Block signed off successfully")
+            .with_data(BlockSignoffResponse::from(signoff))
+            .build()
+    ))
+}
+
+/// Resets a block's sign-offs after its package is revised
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/blocks/{block_id}/signoffs",
+    security(("bearer_auth" = [])),
+    tag = "approvals",
+    responses(
+        (status = 204, description = "Sign-offs reset"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Path, description = "Harvest block ID")
+    )
+)]
+pub async fn reset_block_signoffs(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    block_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let reset_by = user_id(&user)?;
+    let mut conn = get_connection(&pool)?;
+    service().reset(&mut conn, org_id, *block_id, reset_by).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,13 @@
+use actix_web::web;
+use crate::api::middleware::auth::{Auth, RequireAuth};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/blocks/{block_id}/signoffs")
+            .wrap(RequireAuth)
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::approval::handlers::get_block_signoffs))
+            .route("", web::post().to(crate::api::resources::approval::handlers::sign_block))
+            .route("", web::delete().to(crate::api::resources::approval::handlers::reset_block_signoffs))
+    );
+}
@@ -1,34 +1,275 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use ts_rs::TS;
 use utoipa::ToSchema;
-use crate::db::models::auth::Role;
+use crate::{
+    db::models::auth::{Device, Passkey, Role, User},
+    domain::{
+        auth::webauthn::{CreationOptions, RequestOptions},
+        user::AvatarLinks,
+        AvatarService,
+    },
+    utils::Config,
+};
 
 /// Login request payload
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
+    /// ID the client generated for itself, registering it as a device
+    #[serde(default)]
+    #[ts(optional)]
+    pub device_id: Option<String>,
+    /// Name for the device, e.g. "Harvester tablet"
+    #[serde(default)]
+    #[ts(optional)]
+    pub device_name: Option<String>,
+    /// Solved CAPTCHA, when `auth.captcha` asks for one
+    #[serde(default)]
+    #[ts(optional)]
+    pub captcha_token: Option<String>,
 }
 
 /// Registration request payload
-#[derive(Debug, Deserialize, ToSchema)]
+///
+/// Either `org_id` or `invite_token` is required.
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
 pub struct RegisterRequest {
     pub first_name: String,
     pub last_name: String,
     pub email: String,
     pub phone_number: String,
     pub password: String,
-    pub org_id: Uuid,
+    /// Organization to join as an admin, when not invited
+    #[serde(default)]
+    #[ts(optional)]
+    pub org_id: Option<Uuid>,
+    /// Token from an invitation email; joins the organization with the
+    /// invitation's role
+    #[serde(default)]
+    #[ts(optional)]
+    pub invite_token: Option<String>,
+    /// Solved CAPTCHA, when `auth.captcha` asks for one
+    #[serde(default)]
+    #[ts(optional)]
+    pub captcha_token: Option<String>,
 }
 
 /// Token refresh request payload
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
 pub struct RefreshRequest {
     pub refresh_token: String,
 }
 
+/// Logout request payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct LogoutRequest {
+    /// Refresh token to revoke along with the access token
+    pub refresh_token: String,
+}
+
+/// Forgot-password request payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+    /// Solved CAPTCHA, when `auth.captcha` asks for one
+    #[serde(default)]
+    #[ts(optional)]
+    pub captcha_token: Option<String>,
+}
+
+/// Password reset request payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ResetPasswordRequest {
+    /// Token from the password reset link
+    pub token: String,
+    /// The new password
+    pub password: String,
+}
+
+/// Verification email request payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SendVerificationRequest {
+    pub email: String,
+}
+
+/// Email verification request payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct VerifyEmailRequest {
+    /// Token from the verification link
+    pub token: String,
+}
+
+/// Email change confirmation payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ConfirmEmailChangeRequest {
+    /// Token from the link mailed to the new address
+    pub token: String,
+}
+
+/// "This wasn't me" payload, from the link in a login alert
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SecureAccountRequest {
+    /// Token from the link
+    pub token: String,
+}
+
+/// Magic link request payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct MagicLinkRequest {
+    pub email: String,
+    /// Device the link is requested from, the only one it will work on
+    #[serde(default)]
+    #[ts(optional)]
+    pub device_id: Option<String>,
+}
+
+/// Magic link login payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct RedeemMagicLinkRequest {
+    /// Token from the magic link
+    pub token: String,
+    /// Device the link is opened on
+    #[serde(default)]
+    #[ts(optional)]
+    pub device_id: Option<String>,
+    /// Name for the device, e.g. "Harvester tablet"
+    #[serde(default)]
+    #[ts(optional)]
+    pub device_name: Option<String>,
+}
+
+/// Single sign-on callback payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OidcCallbackRequest {
+    /// Authorization code the provider redirected back with
+    pub code: String,
+    /// State the provider redirected back with
+    pub state: String,
+}
+
+/// Single sign-on start response payload
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OidcAuthorizationResponse {
+    /// The provider's sign-in page to send the user to
+    pub authorization_url: String,
+}
+
+/// Passkey registration payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct RegisterPasskeyRequest {
+    /// Name for the passkey, e.g. "Office laptop"
+    pub name: String,
+    /// Token from the registration options
+    pub challenge_token: String,
+    pub credential: PasskeyAttestation,
+}
+
+/// What `navigator.credentials.create()` returned, as
+/// `PublicKeyCredential.toJSON()` encodes it
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct PasskeyAttestation {
+    /// Credential ID, base64url
+    pub id: String,
+    pub response: PasskeyAttestationResponse,
+}
+
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PasskeyAttestationResponse {
+    /// Base64url
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    /// Base64url
+    pub attestation_object: String,
+}
+
+/// Passkey login payload
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct PasskeyLoginRequest {
+    /// Token from the login options
+    pub challenge_token: String,
+    pub credential: PasskeyAssertion,
+    /// ID the client generated for itself, registering it as a device
+    #[serde(default)]
+    #[ts(optional)]
+    pub device_id: Option<String>,
+    /// Name for the device, e.g. "Harvester tablet"
+    #[serde(default)]
+    #[ts(optional)]
+    pub device_name: Option<String>,
+}
+
+/// What `navigator.credentials.get()` returned, as
+/// `PublicKeyCredential.toJSON()` encodes it
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct PasskeyAssertion {
+    /// Credential ID, base64url
+    pub id: String,
+    pub response: PasskeyAssertionResponse,
+}
+
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PasskeyAssertionResponse {
+    /// Base64url
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    /// Base64url
+    pub authenticator_data: String,
+    /// Base64url
+    pub signature: String,
+    /// ID of the user the passkey was registered for, base64url
+    #[serde(default)]
+    #[ts(optional)]
+    pub user_handle: Option<String>,
+}
+
+/// Passkey registration options response payload
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct PasskeyRegistrationOptionsResponse {
+    /// Sent back with the new passkey
+    pub challenge_token: String,
+    /// The `publicKey` options for `navigator.credentials.create()`
+    pub options: CreationOptions,
+}
+
+/// Passkey login options response payload
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct PasskeyLoginOptionsResponse {
+    /// Sent back with the signed challenge
+    pub challenge_token: String,
+    /// The `publicKey` options for `navigator.credentials.get()`
+    pub options: RequestOptions,
+}
+
 /// Authentication response payload
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
 pub struct AuthResponse {
     pub access_token: String,
     pub refresh_token: String,
@@ -36,7 +277,8 @@ pub struct AuthResponse {
 }
 
 /// User response payload
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
 pub struct UserResponse {
     pub id: Uuid,
     pub first_name: String,
@@ -45,4 +287,108 @@ pub struct UserResponse {
     pub phone_number: String,
     pub role: Role,
     pub org_id: Uuid,
-} 
\ No newline at end of file
+    /// `false` once an admin deactivated the user
+    pub is_active: bool,
+    /// The manager or admin the user reports to, `null` for no one
+    pub supervisor_id: Option<Uuid>,
+    /// When an admin anonymized the user, who left, `null` otherwise
+    pub anonymized_at: Option<DateTime<Utc>>,
+    /// Signed links to the user's avatar, `null` without one
+    pub avatar: Option<AvatarResponse>,
+}
+
+impl UserResponse {
+    pub fn new(user: User, config: &Config) -> Self {
+        let avatar = AvatarService::links(config, &user).map(AvatarResponse::from);
+        Self {
+            id: user.id,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            email: user.email,
+            phone_number: user.phone_number,
+            role: user.role,
+            org_id: user.org_id,
+            is_active: user.is_active,
+            supervisor_id: user.supervisor_id,
+            anonymized_at: user.anonymized_at,
+            avatar,
+        }
+    }
+}
+
+/// Signed links to an avatar, which need no bearer token
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct AvatarResponse {
+    /// 64×64 PNG
+    pub small_url: String,
+    /// 256×256 PNG
+    pub large_url: String,
+    /// When the links stop working
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<AvatarLinks> for AvatarResponse {
+    fn from(links: AvatarLinks) -> Self {
+        Self {
+            small_url: links.small,
+            large_url: links.large,
+            expires_at: links.expires_at,
+        }
+    }
+}
+
+/// Device response payload
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct DeviceResponse {
+    pub id: Uuid,
+    /// ID the client generated for itself
+    pub device_id: String,
+    pub name: Option<String>,
+    /// Last login, token refresh or heartbeat from the device
+    pub last_seen_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// Version of the app, as of the last heartbeat
+    pub app_version: Option<String>,
+    /// Battery charge in percent, as of the last heartbeat
+    pub battery_level: Option<i16>,
+    pub last_heartbeat_at: Option<DateTime<Utc>>,
+}
+
+impl From<Device> for DeviceResponse {
+    fn from(device: Device) -> Self {
+        Self {
+            id: device.id,
+            device_id: device.device_id,
+            name: device.name,
+            last_seen_at: device.last_seen_at,
+            created_at: device.created_at,
+            app_version: device.app_version,
+            battery_level: device.battery_level,
+            last_heartbeat_at: device.last_heartbeat_at,
+        }
+    }
+}
+
+/// Passkey response payload
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct PasskeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    /// Last login with the passkey
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Passkey> for PasskeyResponse {
+    fn from(passkey: Passkey) -> Self {
+        Self {
+            id: passkey.id,
+            name: passkey.name,
+            last_used_at: passkey.last_used_at,
+            created_at: passkey.created_at,
+        }
+    }
+}
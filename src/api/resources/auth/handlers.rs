@@ -1,16 +1,30 @@
 //! Authentication handlers implementation
 //! 
 //! This module provides handlers for authentication-related endpoints including
-//! login, registration, token refresh, and password reset.
+//! login, registration, token refresh, password reset, and single sign-on.
 
-use actix_web::{web, HttpResponse};
+use actix_web::{http::header, web, HttpResponse};
 use serde_json::json;
 use crate::{
-    api::{utils::{ApiResponseBuilder, ErrorResponse}, resources::auth::{AuthResponse, LoginRequest, RegisterRequest, UserResponse}}, db::DbPool, domain::auth::AuthService, error::Result, utils::Config
+    api::{middleware::auth::AuthenticatedUser, utils::{ApiResponseBuilder, ErrorResponse, ListResponse}, resources::auth::{AuthResponse, LoginRequest, RegisterRequest, UserResponse}}, db::{models::auth::Role, repositories::auth::CreateUserParams, DbPool}, domain::{auth::{webauthn::{Assertion, Attestation}, AuthService, CaptchaGuard, ClientInfo, JwkSet}, invitation}, error::{ApiError, Result}, utils::Config
 };
+use uuid::Uuid;
 use tracing::info;
 
-use super::dto::RefreshRequest;
+use super::dto::{
+    DeviceResponse, ForgotPasswordRequest, LogoutRequest, MagicLinkRequest, OidcAuthorizationResponse, OidcCallbackRequest,
+    PasskeyLoginOptionsResponse, PasskeyLoginRequest, PasskeyRegistrationOptionsResponse, PasskeyResponse,
+    RedeemMagicLinkRequest, RefreshRequest, RegisterPasskeyRequest, ResetPasswordRequest, SendVerificationRequest,
+    VerifyEmailRequest, ConfirmEmailChangeRequest, SecureAccountRequest,
+};
+
+/// Seconds clients may cache the key set, bounding how long after a key is
+/// added before they accept tokens it signs
+pub const JWKS_MAX_AGE_SECS: u32 = 300;
+
+fn user_id(user: &AuthenticatedUser) -> Result<Uuid> {
+    Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))
+}
 
 /// Login handler
 /// 
@@ -21,20 +35,28 @@ use super::dto::RefreshRequest;
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 400, description = "Malformed request body, or a CAPTCHA is needed after repeated failures", body = ErrorResponse),
         (status = 401, description = "Invalid credentials", body = ErrorResponse),
-        (status = 500, description = "Internal server error", body = ErrorResponse)
+        (status = 403, description = "Email address not verified", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 502, description = "CAPTCHA verification unavailable", body = ErrorResponse)
     ),
     tag = "auth"
 )]
 pub async fn login(
     pool: web::Data<DbPool>,
     config: web::Data<Config>,
+    client: ClientInfo,
     req: web::Json<LoginRequest>,
 ) -> Result<HttpResponse> {
+    CaptchaGuard::require_for_login(&pool, &config, &req.email, req.captcha_token.as_deref(), &client).await?;
     let service_response = AuthService::login(
         &pool,
         &req.email,
         &req.password,
+        req.device_id.as_deref(),
+        req.device_name.as_deref(),
+        &client,
         &config,
     ).await?;
 
@@ -48,15 +70,7 @@ pub async fn login(
     let response = AuthResponse {
         access_token,
         refresh_token: refresh_token.token,
-        user: UserResponse {
-            id: user.id,
-            first_name: user.first_name,
-            last_name: user.last_name,
-            email: user.email,
-            phone_number: user.phone_number,
-            role: user.role,
-            org_id: user.org_id,
-        },
+        user: UserResponse::new(user, &config),
     };
 
     Ok(HttpResponse::Ok().json(
@@ -69,31 +83,54 @@ pub async fn login(
 
 /// Registration handler
 /// 
-/// Registers a new user
+/// Registers a new user into `org_id`, or the organization of the
+/// invitation `invite_token` is from. Uninvited users are mailed a link to
+/// verify their email.
 #[utoipa::path(
     post,
     path = "/v1/auth/register",
     request_body = RegisterRequest,
     responses(
         (status = 201, description = "Registration successful", body = UserResponse),
-        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 400, description = "Invalid input, invitation or CAPTCHA", body = ErrorResponse),
         (status = 409, description = "User already exists", body = ErrorResponse),
-        (status = 500, description = "Internal server error", body = ErrorResponse)
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 502, description = "CAPTCHA verification unavailable", body = ErrorResponse)
     ),
     tag = "auth"
 )]
 pub async fn register(
     pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
     req: web::Json<RegisterRequest>,
 ) -> Result<HttpResponse> {
+    CaptchaGuard::require(&config, req.captcha_token.as_deref(), &client).await?;
+    let invitation = req.invite_token.as_deref().map(|token| invitation::verify_token(token, &config)).transpose()?;
+    let (org_id, role) = match (&invitation, req.org_id) {
+        (Some(invitation), _) => (invitation.org_id, invitation.role),
+        (None, Some(org_id)) => (org_id, Role::Admin),
+        (None, None) => {
+            return Err(ApiError::validation(
+                "An org_id or an invite_token is required",
+                Some(json!({ "field": "org_id", "code": "REQUIRED" })),
+            ))
+        }
+    };
+
     let service_response = AuthService::register(
         &pool,
-        &req.first_name,
-        &req.last_name,
-        &req.email,
-        &req.phone_number,
-        &req.password,
-        req.org_id,
+        CreateUserParams {
+            first_name: &req.first_name,
+            last_name: &req.last_name,
+            email: &req.email,
+            phone_number: &req.phone_number,
+            password: &req.password,
+            org_id,
+            role,
+        },
+        invitation.as_ref(),
+        &config,
     ).await?;
 
     let user = service_response.data;
@@ -103,15 +140,7 @@ pub async fn register(
         "New user registered"
     );
 
-    let response = UserResponse {
-        id: user.id,
-        first_name: user.first_name,
-        last_name: user.last_name,
-        email: user.email,
-        phone_number: user.phone_number,
-        role: user.role,
-        org_id: user.org_id,
-    };
+    let response = UserResponse::new(user, &config);
 
     Ok(HttpResponse::Created().json(
         ApiResponseBuilder::success()
@@ -130,6 +159,7 @@ pub async fn register(
     request_body = RefreshRequest,
     responses(
         (status = 200, description = "Token refreshed", body = AuthResponse),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
         (status = 401, description = "Invalid refresh token", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -138,11 +168,13 @@ pub async fn register(
 pub async fn refresh(
     pool: web::Data<DbPool>,
     config: web::Data<Config>,
+    client: ClientInfo,
     req: web::Json<RefreshRequest>,
 ) -> Result<HttpResponse> {
     let service_response = AuthService::refresh_token(
         &pool,
         &req.refresh_token,
+        &client,
         &config,
     ).await?;
 
@@ -157,4 +189,704 @@ pub async fn refresh(
             }))
             .build()
     ))
-} 
\ No newline at end of file
+}
+
+/// Logout handler
+///
+/// Revokes the refresh token and the access token the request is made
+/// with. Unknown or already revoked refresh tokens are ignored.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/logout",
+    request_body = LogoutRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Logged out"),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn logout(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    req: web::Json<LogoutRequest>,
+) -> Result<HttpResponse> {
+    AuthService::logout(&pool, user.claims(), &req.refresh_token, &client, &config).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Device list handler
+///
+/// Lists the devices the caller has logged in from, most recently seen first
+#[utoipa::path(
+    get,
+    path = "/v1/auth/devices",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Devices", body = ListResponse<DeviceResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn list_devices(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse> {
+    let devices = AuthService::list_devices(&pool, user_id(&user)?).await?;
+    let devices = devices.into_iter().map(DeviceResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Devices retrieved successfully")
+            .with_data(devices)
+            .build()
+    ))
+}
+
+/// Device revocation handler
+///
+/// Forgets one of the caller's devices and revokes its refresh tokens
+#[utoipa::path(
+    delete,
+    path = "/v1/auth/devices/{id}",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Device ID")
+    ),
+    responses(
+        (status = 204, description = "Device revoked"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "Device not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn revoke_device(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    AuthService::revoke_device(&pool, user_id(&user)?, *id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Passkey registration options handler
+///
+/// Starts registering a passkey for the caller. The options are passed to
+/// `navigator.credentials.create()` and the result posted, with the
+/// challenge token, to `/v1/auth/passkeys`.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/passkeys/options",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Registration options", body = PasskeyRegistrationOptionsResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn passkey_registration_options(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse> {
+    let (challenge_token, options) = AuthService::passkey_registration_options(&pool, user_id(&user)?, &config).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Create the passkey with these options")
+            .with_data(PasskeyRegistrationOptionsResponse { challenge_token, options })
+            .build()
+    ))
+}
+
+/// Passkey registration handler
+///
+/// Stores the passkey the browser created from the registration options
+#[utoipa::path(
+    post,
+    path = "/v1/auth/passkeys",
+    security(("bearer_auth" = [])),
+    request_body = RegisterPasskeyRequest,
+    responses(
+        (status = 201, description = "Passkey registered", body = PasskeyResponse),
+        (status = 400, description = "Invalid name or credential, or an expired or used challenge", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 409, description = "Passkey already registered", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn register_passkey(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    req: web::Json<RegisterPasskeyRequest>,
+) -> Result<HttpResponse> {
+    let attestation = Attestation {
+        credential_id: &req.credential.id,
+        client_data_json: &req.credential.response.client_data_json,
+        attestation_object: &req.credential.response.attestation_object,
+    };
+    let passkey = AuthService::register_passkey(
+        &pool,
+        user_id(&user)?,
+        &req.name,
+        &req.challenge_token,
+        &attestation,
+        &client,
+        &config,
+    ).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Passkey registered")
+            .with_data(PasskeyResponse::from(passkey))
+            .build()
+    ))
+}
+
+/// Passkey list handler
+///
+/// Lists the caller's passkeys
+#[utoipa::path(
+    get,
+    path = "/v1/auth/passkeys",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Passkeys", body = ListResponse<PasskeyResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn list_passkeys(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse> {
+    let passkeys = AuthService::list_passkeys(&pool, user_id(&user)?).await?;
+    let passkeys = passkeys.into_iter().map(PasskeyResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Passkeys retrieved successfully")
+            .with_data(passkeys)
+            .build()
+    ))
+}
+
+/// Passkey removal handler
+///
+/// Removes one of the caller's passkeys, so it can no longer log in
+#[utoipa::path(
+    delete,
+    path = "/v1/auth/passkeys/{id}",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Passkey ID")
+    ),
+    responses(
+        (status = 204, description = "Passkey removed"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "Passkey not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn delete_passkey(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    client: ClientInfo,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    AuthService::delete_passkey(&pool, user_id(&user)?, *id, &client).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Passkey login options handler
+///
+/// Starts a login with a passkey. The options are passed to
+/// `navigator.credentials.get()` and the result posted, with the challenge
+/// token, to `/v1/auth/passkeys/login`.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/passkeys/login/options",
+    responses(
+        (status = 200, description = "Login options", body = PasskeyLoginOptionsResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn passkey_login_options(config: web::Data<Config>) -> Result<HttpResponse> {
+    let (challenge_token, options) = AuthService::passkey_login_options(&config)?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Sign the challenge with a passkey")
+            .with_data(PasskeyLoginOptionsResponse { challenge_token, options })
+            .build()
+    ))
+}
+
+/// Passkey login handler
+///
+/// Logs in the user whose passkey signed the challenge
+#[utoipa::path(
+    post,
+    path = "/v1/auth/passkeys/login",
+    request_body = PasskeyLoginRequest,
+    responses(
+        (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 400, description = "Malformed request body, or an expired or used challenge", body = ErrorResponse),
+        (status = 401, description = "Unknown passkey or invalid signature", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn passkey_login(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    req: web::Json<PasskeyLoginRequest>,
+) -> Result<HttpResponse> {
+    let assertion = Assertion {
+        credential_id: &req.credential.id,
+        client_data_json: &req.credential.response.client_data_json,
+        authenticator_data: &req.credential.response.authenticator_data,
+        signature: &req.credential.response.signature,
+        user_handle: req.credential.response.user_handle.as_deref(),
+    };
+    let service_response = AuthService::passkey_login(
+        &pool,
+        &req.challenge_token,
+        &assertion,
+        req.device_id.as_deref(),
+        req.device_name.as_deref(),
+        &client,
+        &config,
+    ).await?;
+
+    let (access_token, refresh_token, user) = service_response.data;
+
+    let response = AuthResponse {
+        access_token,
+        refresh_token: refresh_token.token,
+        user: UserResponse::new(user, &config),
+    };
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Login successful")
+            .with_data(response)
+            .build()
+    ))
+}
+
+/// Forgot-password handler
+/// 
+/// Mails a password reset link if the email belongs to a user. The response
+/// is the same either way.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/forgot-password",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (status = 202, description = "Reset link sent if the email is registered"),
+        (status = 400, description = "Malformed request body, or missing or unsolved CAPTCHA", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 502, description = "CAPTCHA verification unavailable", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn forgot_password(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    req: web::Json<ForgotPasswordRequest>,
+) -> Result<HttpResponse> {
+    CaptchaGuard::require(&config, req.captcha_token.as_deref(), &client).await?;
+    AuthService::forgot_password(
+        &pool,
+        &req.email,
+        &config,
+    ).await?;
+
+    Ok(HttpResponse::Accepted().json(
+        ApiResponseBuilder::success()
+            .with_message("If the email is registered, a password reset link has been sent")
+            .with_data(json!({}))
+            .build()
+    ))
+}
+
+/// Reset-password handler
+/// 
+/// Sets a new password using the token from a password reset link
+#[utoipa::path(
+    post,
+    path = "/v1/auth/reset-password",
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 200, description = "Password reset"),
+        (status = 400, description = "Invalid or expired token, or the password is too weak", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn reset_password(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    req: web::Json<ResetPasswordRequest>,
+) -> Result<HttpResponse> {
+    AuthService::reset_password(
+        &pool,
+        &req.token,
+        &req.password,
+        &client,
+        &config,
+    ).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Password reset successfully")
+            .with_data(json!({}))
+            .build()
+    ))
+}
+
+/// Verification email handler
+/// 
+/// Mails a new email verification link if the email belongs to a user who
+/// has not verified it yet. The response is the same either way.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/send-verification",
+    request_body = SendVerificationRequest,
+    responses(
+        (status = 202, description = "Verification link sent if the email needs verifying"),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn send_verification(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    req: web::Json<SendVerificationRequest>,
+) -> Result<HttpResponse> {
+    AuthService::send_verification(
+        &pool,
+        &req.email,
+        &config,
+    ).await?;
+
+    Ok(HttpResponse::Accepted().json(
+        ApiResponseBuilder::success()
+            .with_message("If the email needs verifying, a verification link has been sent")
+            .with_data(json!({}))
+            .build()
+    ))
+}
+
+/// Email verification handler
+/// 
+/// Confirms the user's email address using the token from a verification link
+#[utoipa::path(
+    post,
+    path = "/v1/auth/verify-email",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 200, description = "Email verified", body = UserResponse),
+        (status = 400, description = "Invalid or expired token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn verify_email(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    req: web::Json<VerifyEmailRequest>,
+) -> Result<HttpResponse> {
+    let user = AuthService::verify_email(
+        &pool,
+        &req.token,
+    ).await?;
+
+    let response = UserResponse::new(user, &config);
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Email verified")
+            .with_data(response)
+            .build()
+    ))
+}
+
+/// Email change confirmation handler
+/// 
+/// Switches the user to the address the token was mailed to, marking it
+/// verified
+#[utoipa::path(
+    post,
+    path = "/v1/auth/confirm-email-change",
+    request_body = ConfirmEmailChangeRequest,
+    responses(
+        (status = 200, description = "Email changed", body = UserResponse),
+        (status = 400, description = "Invalid or expired token", body = ErrorResponse),
+        (status = 409, description = "The new email was registered in the meantime", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn confirm_email_change(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    req: web::Json<ConfirmEmailChangeRequest>,
+) -> Result<HttpResponse> {
+    let user = AuthService::confirm_email_change(
+        &pool,
+        &req.token,
+        &client,
+    ).await?;
+
+    let response = UserResponse::new(user, &config);
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Email changed")
+            .with_data(response)
+            .build()
+    ))
+}
+
+/// "This wasn't me" handler
+/// 
+/// Signs the user a login alert was sent to out everywhere, using the token
+/// from the alert's link
+#[utoipa::path(
+    post,
+    path = "/v1/auth/secure-account",
+    request_body = SecureAccountRequest,
+    responses(
+        (status = 200, description = "Every session of the user revoked"),
+        (status = 400, description = "Invalid or expired token", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn secure_account(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    req: web::Json<SecureAccountRequest>,
+) -> Result<HttpResponse> {
+    AuthService::secure_account(
+        &pool,
+        &req.token,
+        &client,
+        &config,
+    ).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Signed out everywhere; reset your password to keep others out")
+            .with_data(json!({}))
+            .build()
+    ))
+}
+
+/// Magic link request handler
+/// 
+/// Mails a single-use login link if the email belongs to a user. The
+/// response is the same either way.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/magic-link",
+    request_body = MagicLinkRequest,
+    responses(
+        (status = 202, description = "Login link sent if the email is registered"),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn request_magic_link(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    req: web::Json<MagicLinkRequest>,
+) -> Result<HttpResponse> {
+    AuthService::request_magic_link(
+        &pool,
+        &req.email,
+        req.device_id.as_deref(),
+        &config,
+    ).await?;
+
+    Ok(HttpResponse::Accepted().json(
+        ApiResponseBuilder::success()
+            .with_message("If the email is registered, a login link has been sent")
+            .with_data(json!({}))
+            .build()
+    ))
+}
+
+/// Magic link login handler
+/// 
+/// Logs in with the token from a magic link
+#[utoipa::path(
+    post,
+    path = "/v1/auth/magic-link/redeem",
+    request_body = RedeemMagicLinkRequest,
+    responses(
+        (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 401, description = "Invalid, expired or used link, or opened on another device", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn redeem_magic_link(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    req: web::Json<RedeemMagicLinkRequest>,
+) -> Result<HttpResponse> {
+    let service_response = AuthService::redeem_magic_link(
+        &pool,
+        &req.token,
+        req.device_id.as_deref(),
+        req.device_name.as_deref(),
+        &client,
+        &config,
+    ).await?;
+
+    let (access_token, refresh_token, user) = service_response.data;
+
+    let response = AuthResponse {
+        access_token,
+        refresh_token: refresh_token.token,
+        user: UserResponse::new(user, &config),
+    };
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Login successful")
+            .with_data(response)
+            .build()
+    ))
+}
+
+/// Single sign-on start handler
+/// 
+/// Returns the provider's sign-in page. The provider redirects back to its
+/// configured redirect URL with a code and state for the callback.
+#[utoipa::path(
+    get,
+    path = "/v1/auth/oidc/{provider}/authorize",
+    params(
+        ("provider" = String, Path, description = "Provider name from the auth.oidc configuration")
+    ),
+    responses(
+        (status = 200, description = "Sign-in page of the provider", body = OidcAuthorizationResponse),
+        (status = 404, description = "Provider not configured", body = ErrorResponse),
+        (status = 502, description = "Provider unavailable", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn oidc_authorize(
+    config: web::Data<Config>,
+    provider: web::Path<String>,
+) -> Result<HttpResponse> {
+    let authorization_url = AuthService::oidc_authorize(&provider, &config).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Continue at the identity provider")
+            .with_data(OidcAuthorizationResponse { authorization_url })
+            .build()
+    ))
+}
+
+/// Single sign-on callback handler
+/// 
+/// Logs in with the code and state the provider redirected back with. The
+/// first sign-on links the account to the user with its email, or creates
+/// a user in the organization that claimed the email's domain.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/oidc/{provider}/callback",
+    params(
+        ("provider" = String, Path, description = "Provider name from the auth.oidc configuration")
+    ),
+    request_body = OidcCallbackRequest,
+    responses(
+        (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 400, description = "Invalid, expired or reused code or state", body = ErrorResponse),
+        (status = 401, description = "Invalid ID token", body = ErrorResponse),
+        (status = 403, description = "No user or organization accepts the account", body = ErrorResponse),
+        (status = 404, description = "Provider not configured", body = ErrorResponse),
+        (status = 502, description = "Provider unavailable", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn oidc_callback(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    provider: web::Path<String>,
+    client: ClientInfo,
+    req: web::Json<OidcCallbackRequest>,
+) -> Result<HttpResponse> {
+    let service_response = AuthService::oidc_login(
+        &pool,
+        &provider,
+        &req.code,
+        &req.state,
+        &client,
+        &config,
+    ).await?;
+
+    let (access_token, refresh_token, user) = service_response.data;
+
+    let response = AuthResponse {
+        access_token,
+        refresh_token: refresh_token.token,
+        user: UserResponse::new(user, &config),
+    };
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Login successful")
+            .with_data(response)
+            .build()
+    ))
+}
+
+/// Public keys access tokens are verified with
+///
+/// A JSON Web Key Set, not wrapped in the API envelope, so JWT libraries
+/// can read it directly. Keys signed with a shared secret are not listed.
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (status = 200, description = "The signing keys", body = JwkSet)
+    ),
+    tag = "auth"
+)]
+pub async fn jwks(config: web::Data<Config>) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, format!("public, max-age={}", JWKS_MAX_AGE_SECS)))
+        .json(config.signing_keys().jwks())
+}
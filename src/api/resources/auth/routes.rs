@@ -1,4 +1,5 @@
 use actix_web::web;
+use crate::api::middleware::auth::Auth;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -6,5 +7,48 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/login", web::post().to(crate::api::resources::auth::handlers::login))
             .route("/register", web::post().to(crate::api::resources::auth::handlers::register))
             .route("/refresh", web::post().to(crate::api::resources::auth::handlers::refresh))
+            .service(
+                web::resource("/logout")
+                    .wrap(Auth::new().allow_archived())
+                    .route(web::post().to(crate::api::resources::auth::handlers::logout))
+            )
+            .service(
+                web::resource("/devices")
+                    .wrap(Auth::new())
+                    .route(web::get().to(crate::api::resources::auth::handlers::list_devices))
+            )
+            .service(
+                web::resource("/devices/{id}")
+                    .wrap(Auth::new())
+                    .route(web::delete().to(crate::api::resources::auth::handlers::revoke_device))
+            )
+            .service(
+                web::resource("/passkeys")
+                    .wrap(Auth::new())
+                    .route(web::get().to(crate::api::resources::auth::handlers::list_passkeys))
+                    .route(web::post().to(crate::api::resources::auth::handlers::register_passkey))
+            )
+            .service(
+                web::resource("/passkeys/options")
+                    .wrap(Auth::new())
+                    .route(web::post().to(crate::api::resources::auth::handlers::passkey_registration_options))
+            )
+            .route("/passkeys/login/options", web::post().to(crate::api::resources::auth::handlers::passkey_login_options))
+            .route("/passkeys/login", web::post().to(crate::api::resources::auth::handlers::passkey_login))
+            .service(
+                web::resource("/passkeys/{id}")
+                    .wrap(Auth::new())
+                    .route(web::delete().to(crate::api::resources::auth::handlers::delete_passkey))
+            )
+            .route("/forgot-password", web::post().to(crate::api::resources::auth::handlers::forgot_password))
+            .route("/reset-password", web::post().to(crate::api::resources::auth::handlers::reset_password))
+            .route("/send-verification", web::post().to(crate::api::resources::auth::handlers::send_verification))
+            .route("/verify-email", web::post().to(crate::api::resources::auth::handlers::verify_email))
+            .route("/confirm-email-change", web::post().to(crate::api::resources::auth::handlers::confirm_email_change))
+            .route("/secure-account", web::post().to(crate::api::resources::auth::handlers::secure_account))
+            .route("/magic-link", web::post().to(crate::api::resources::auth::handlers::request_magic_link))
+            .route("/magic-link/redeem", web::post().to(crate::api::resources::auth::handlers::redeem_magic_link))
+            .route("/oidc/{provider}/authorize", web::get().to(crate::api::resources::auth::handlers::oidc_authorize))
+            .route("/oidc/{provider}/callback", web::post().to(crate::api::resources::auth::handlers::oidc_callback))
     );
 } 
\ No newline at end of file
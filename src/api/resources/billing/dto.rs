@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::billing::{CheckoutSession, Plan, SubscriptionStatus};
+
+/// A plan of the catalog with the quotas it grants
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct PlanResponse {
+    /// e.g. `team`
+    pub id: String,
+    pub name: String,
+    /// `null` for any number of users
+    pub max_users: Option<u32>,
+    /// `null` for any number of active harvest blocks
+    pub max_active_blocks: Option<u32>,
+    /// Days telemetry is kept
+    pub telemetry_retention_days: u32,
+    /// Whether the plan includes premium features such as ERP exports
+    pub premium: bool,
+    /// Whether the plan can be bought, paid plans need a Stripe price
+    pub available: bool,
+}
+
+impl PlanResponse {
+    pub fn new(plan: &Plan, available: bool) -> Self {
+        Self {
+            id: plan.id.to_string(),
+            name: plan.name.to_string(),
+            max_users: plan.quotas.max_users,
+            max_active_blocks: plan.quotas.max_active_blocks,
+            telemetry_retention_days: plan.quotas.retention_days(),
+            premium: plan.premium,
+            available,
+        }
+    }
+}
+
+/// Input for starting a Checkout session
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct CheckoutInput {
+    /// Paid plan to subscribe to, e.g. `team`
+    pub plan: String,
+}
+
+/// A Checkout session to send the customer to
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CheckoutResponse {
+    /// Stripe Checkout session ID
+    pub id: String,
+    /// Stripe-hosted payment page
+    pub url: String,
+}
+
+impl From<CheckoutSession> for CheckoutResponse {
+    fn from(session: CheckoutSession) -> Self {
+        Self {
+            id: session.id,
+            url: session.url,
+        }
+    }
+}
+
+/// An organization's plan and subscription
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SubscriptionResponse {
+    pub org_id: Uuid,
+    /// Plan the organization gets, `free` unless its subscription is active
+    pub plan: String,
+    /// Stripe status of the subscription, e.g. `active` or `past_due`;
+    /// `null` when the organization never subscribed
+    pub status: Option<String>,
+    /// Plan subscribed to, which may differ from `plan` while inactive
+    pub subscribed_plan: Option<String>,
+    /// Whether premium features are available
+    pub premium: bool,
+    pub current_period_end: Option<DateTime<Utc>>,
+    pub cancel_at_period_end: bool,
+}
+
+impl SubscriptionResponse {
+    pub fn new(org_id: Uuid, status: SubscriptionStatus) -> Self {
+        let subscription = status.subscription.as_ref();
+        Self {
+            org_id,
+            plan: status.plan.id.to_string(),
+            status: subscription.map(|subscription| subscription.status.clone()),
+            subscribed_plan: subscription.map(|subscription| subscription.plan.clone()),
+            premium: status.plan.premium,
+            current_period_end: subscription.and_then(|subscription| subscription.current_period_end),
+            cancel_at_period_end: subscription.is_some_and(|subscription| subscription.cancel_at_period_end),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Billing resource handlers
+//!
+//! Members see the plan catalog and their organization's subscription;
+//! admins start Checkout sessions. Stripe posts subscription lifecycle
+//! events to the webhook, authenticated by their signature rather than a
+//! token.
+
+use crate::{
+    api::{
+        middleware::AuthenticatedUser,
+        resources::billing::dto::{CheckoutInput, CheckoutResponse, PlanResponse, SubscriptionResponse},
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{
+        get_connection,
+        repositories::{auth::UserRepositoryImpl, Repository},
+        DbPool,
+    },
+    domain::billing::{BillingService, Plan},
+    error::ApiError,
+    utils::Config,
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+fn user_id(user: &AuthenticatedUser) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))
+}
+
+/// Lists the plans of the catalog, cheapest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/billing/plans",
+    security(("bearer_auth" = [])),
+    tag = "billing",
+    responses(
+        (status = 200, description = "Plans of the catalog", body = ListResponse<PlanResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse)
+    )
+)]
+pub async fn list_plans(config: web::Data<Config>) -> Result<HttpResponse, ApiError> {
+    let billing = &config.billing;
+    let available = |plan: &Plan| !plan.is_paid() || (billing.enabled() && billing.prices.contains_key(plan.id));
+    let plans = Plan::all()
+        .iter()
+        .map(|plan| PlanResponse::new(plan, available(plan)))
+        .collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Plans retrieved successfully")
+            .with_data(plans)
+            .build()
+    ))
+}
+
+/// Shows the plan and subscription of the caller's organization
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/billing/subscription",
+    security(("bearer_auth" = [])),
+    tag = "billing",
+    responses(
+        (status = 200, description = "Plan and subscription", body = SubscriptionResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn get_subscription(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let viewer = UserRepositoryImpl.find_by_id(&mut conn, user_id(&user)?).await?;
+    let status = BillingService::status(&mut conn, &viewer, viewer.org_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Subscription retrieved successfully")
+            .with_data(SubscriptionResponse::new(viewer.org_id, status))
+            .build()
+    ))
+}
+
+/// Starts a Stripe Checkout session subscribing the caller's organization to a paid plan
+///
+/// The customer pays on the returned Stripe page; the subscription becomes
+/// active once Stripe reports the payment through the webhook.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/billing/checkout",
+    security(("bearer_auth" = [])),
+    tag = "billing",
+    request_body = CheckoutInput,
+    responses(
+        (status = 201, description = "Checkout session created", body = CheckoutResponse),
+        (status = 400, description = "Unknown or free plan", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 409, description = "The organization already has an active subscription", body = ErrorResponse),
+        (status = 422, description = "Billing is not enabled", body = ErrorResponse),
+        (status = 502, description = "Stripe is unavailable", body = ErrorResponse)
+    )
+)]
+pub async fn create_checkout(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    input: web::Json<CheckoutInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let admin = UserRepositoryImpl.find_by_id(&mut conn, user_id(&user)?).await?;
+    let session = BillingService::checkout(&mut conn, &config, &admin, &input.plan).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Checkout session created")
+            .with_data(CheckoutResponse::from(session))
+            .build()
+    ))
+}
+
+/// Receives Stripe webhook events
+///
+/// Handles `checkout.session.completed` and the `customer.subscription.*`
+/// lifecycle events; other events are acknowledged and ignored.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/billing/webhook",
+    tag = "billing",
+    request_body(content = String, description = "Stripe event", content_type = "application/json"),
+    responses(
+        (status = 204, description = "Event applied or ignored"),
+        (status = 400, description = "Malformed event", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired Stripe-Signature", body = ErrorResponse),
+        (status = 422, description = "Billing is not enabled", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("Stripe-Signature" = String, Header, description = "Signature Stripe computed over the payload")
+    )
+)]
+pub async fn stripe_webhook(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    payload: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let signature = req.headers().get("Stripe-Signature").and_then(|value| value.to_str().ok());
+    let mut conn = get_connection(&pool)?;
+    BillingService::handle_webhook(&mut conn, &config, signature, &payload).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
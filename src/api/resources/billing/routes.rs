@@ -0,0 +1,29 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequireRole},
+    db::models::auth::Role,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/billing")
+            // Stripe authenticates with the payload's signature, not a token
+            .route("/webhook", web::post().to(crate::api::resources::billing::handlers::stripe_webhook))
+            .service(
+                web::resource("/plans")
+                    .wrap(Auth::new())
+                    .route(web::get().to(crate::api::resources::billing::handlers::list_plans))
+            )
+            .service(
+                web::resource("/subscription")
+                    .wrap(Auth::new())
+                    .route(web::get().to(crate::api::resources::billing::handlers::get_subscription))
+            )
+            .service(
+                web::resource("/checkout")
+                    .wrap(RequireRole::new(Role::Admin))
+                    .wrap(Auth::new())
+                    .route(web::post().to(crate::api::resources::billing::handlers::create_checkout))
+            )
+    );
+}
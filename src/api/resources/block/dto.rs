@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    db::models::{Boundary, HarvestBlock},
+    domain::operability::{Operability, OperabilityStatus, SoilSensitivity},
+};
+
+/// Input for creating or replacing a harvest block
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct SaveHarvestBlockInput {
+    /// Harvesting license or cutting permit the block is cut under
+    #[validate(length(min = 1, max = 100))]
+    pub license: String,
+    /// Unique within the license
+    #[validate(length(min = 1, max = 50))]
+    pub block_number: String,
+    /// Net harvest area in hectares
+    pub area_ha: f64,
+    /// Volume planned to be cut
+    pub planned_volume_m3: f64,
+    /// `planned`, `approved`, `active` or `completed`; new blocks are
+    /// planned and updates keep the status when unset
+    pub status: Option<String>,
+    pub boundary: Boundary,
+    #[validate(length(max = 4000))]
+    pub notes: Option<String>,
+    /// How easily the soil ruts, for the block's wet-weather operability
+    #[serde(default)]
+    pub soil_sensitivity: Option<SoilSensitivity>,
+}
+
+/// Query parameters for listing harvest blocks
+#[derive(Debug, Default, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListHarvestBlocksQuery {
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+    /// Only the blocks of this license
+    pub license: Option<String>,
+    /// Only blocks in this status
+    pub status: Option<String>,
+    /// Only blocks crossing the box `min_lon,min_lat,max_lon,max_lat`
+    pub bbox: Option<String>,
+    /// Only blocks with these tags, as comma separated tag ids
+    pub tags: Option<String>,
+    /// `any` (the default) or `all` of the tags
+    pub tag_match: Option<String>,
+}
+
+/// Harvest block response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct HarvestBlockResponse {
+    pub id: Uuid,
+    pub license: String,
+    pub block_number: String,
+    pub area_ha: f64,
+    pub planned_volume_m3: f64,
+    pub status: String,
+    pub boundary: Boundary,
+    pub notes: Option<String>,
+    pub soil_sensitivity: Option<SoilSensitivity>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<HarvestBlock> for HarvestBlockResponse {
+    fn from(block: HarvestBlock) -> Self {
+        let soil_sensitivity = block.soil_sensitivity();
+        Self {
+            id: block.id,
+            license: block.license,
+            block_number: block.block_number,
+            area_ha: block.area_ha,
+            planned_volume_m3: block.planned_volume_m3,
+            status: block.status,
+            boundary: block.boundary,
+            notes: block.notes,
+            soil_sensitivity,
+            created_by: block.created_by,
+            created_at: block.created_at,
+            updated_at: block.updated_at,
+        }
+    }
+}
+
+/// Query parameters for a block's operability
+#[derive(Debug, Default, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct BlockOperabilityQuery {
+    /// Daily precipitation in millimetres, most recent day first, as
+    /// comma separated totals
+    pub precipitation_mm: Option<String>,
+}
+
+/// Whether ground-based operations may run on a block after recent rain
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct BlockOperabilityResponse {
+    pub block_id: Uuid,
+    pub status: OperabilityStatus,
+    pub sensitivity: SoilSensitivity,
+    /// Antecedent precipitation index, in millimetres
+    pub antecedent_mm: f64,
+    /// Index at which operations stop on the block's soil
+    pub shutdown_mm: f64,
+}
+
+impl BlockOperabilityResponse {
+    pub fn new(block_id: Uuid, operability: Operability) -> Self {
+        Self {
+            block_id,
+            status: operability.status,
+            sensitivity: operability.sensitivity,
+            antecedent_mm: operability.antecedent_mm,
+            shutdown_mm: operability.shutdown_mm,
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Harvest block resource handlers
+//!
+//! Every handler works on the harvest blocks of the authenticated user's
+//! organization. Sign-offs are managed under `/v1/blocks/{id}/signoffs`
+//! and documents through the documents API with the `block` subject.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::block::dto::{
+            BlockOperabilityQuery, BlockOperabilityResponse, HarvestBlockResponse, ListHarvestBlocksQuery, SaveHarvestBlockInput,
+        },
+        utils::{ApiResponseBuilder, ErrorResponse, PaginatedResponse, PaginationParams},
+    },
+    db::{get_connection, repositories::HarvestBlockRepositoryImpl, DbPool},
+    domain::block::{HarvestBlockService, HarvestBlockValidator},
+    error::ApiError,
+    utils::Config,
+};
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+
+fn service() -> HarvestBlockService<HarvestBlockRepositoryImpl> {
+    HarvestBlockService::new(HarvestBlockRepositoryImpl)
+}
+
+/// Lists the organization's harvest blocks by license and block number
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/blocks",
+    security(("bearer_auth" = [])),
+    tag = "blocks",
+    responses(
+        (status = 200, description = "Harvest blocks", body = PaginatedResponse<HarvestBlockResponse>),
+        (status = 400, description = "Unknown status, malformed box or invalid tag filter", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page"),
+        ("license" = Option<String>, Query, description = "Only the blocks of this license"),
+        ("status" = Option<String>, Query, description = "`planned`, `approved`, `active` or `completed`"),
+        ("bbox" = Option<String>, Query, description = "Only blocks crossing the box `min_lon,min_lat,max_lon,max_lat`"),
+        ("tags" = Option<String>, Query, description = "Only blocks with these tags, comma separated tag IDs"),
+        ("tag_match" = Option<String>, Query, description = "`any` (default) or `all` of the tags")
+    )
+)]
+pub async fn list_blocks(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    query: web::Query<ListHarvestBlocksQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+    let mut conn = get_connection(&pool)?;
+    let (blocks, total) = service().list(&mut conn, org_id, &query, &pagination).await?;
+    let blocks = blocks.into_iter().map(HarvestBlockResponse::from).collect();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Harvest blocks retrieved successfully")
+            .with_data(PaginatedResponse::with_count(blocks, total, &pagination))
+            .build()
+    ))
+}
+
+/// Creates a harvest block
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/blocks",
+    security(("bearer_auth" = [])),
+    tag = "blocks",
+    request_body = SaveHarvestBlockInput,
+    responses(
+        (status = 201, description = "Harvest block created", body = HarvestBlockResponse),
+        (status = 400, description = "Invalid input or boundary", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required or active block quota used up", body = ErrorResponse),
+        (status = 409, description = "Block number taken under the license, or sign-off chain incomplete", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_block(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    input: web::Json<SaveHarvestBlockInput>,
+) -> Result<HttpResponse, ApiError> {
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let block = service().create(&mut conn, &config, org_id, created_by, input.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Harvest block created successfully")
+            .with_data(HarvestBlockResponse::from(block))
+            .build()
+    ))
+}
+
+/// Retrieves a harvest block
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/blocks/{id}",
+    security(("bearer_auth" = [])),
+    tag = "blocks",
+    responses(
+        (status = 200, description = "Harvest block", body = HarvestBlockResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 404, description = "Harvest block not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Harvest block ID")
+    )
+)]
+pub async fn get_block(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    block_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let block = service().get(&mut conn, org_id, *block_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Harvest block retrieved successfully")
+            .with_data(HarvestBlockResponse::from(block))
+            .build()
+    ))
+}
+
+/// Tells whether ground-based operations may run on a harvest block after
+/// recent rain
+///
+/// The block's soil sensitivity decides how much rain it takes; the daily
+/// totals come from the caller's weather data, most recent day first.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/blocks/{id}/operability",
+    security(("bearer_auth" = [])),
+    tag = "blocks",
+    responses(
+        (status = 200, description = "Operability of the block", body = BlockOperabilityResponse),
+        (status = 400, description = "Missing or invalid precipitation", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 404, description = "Harvest block not found", body = ErrorResponse),
+        (status = 409, description = "No soil sensitivity recorded for the block", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Harvest block ID"),
+        ("precipitation_mm" = String, Query, description = "Daily precipitation in millimetres, most recent day first, comma separated")
+    )
+)]
+pub async fn get_block_operability(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    block_id: web::Path<Uuid>,
+    query: web::Query<BlockOperabilityQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let daily_mm = HarvestBlockValidator::validate_precipitation(query.precipitation_mm.as_deref())?;
+    let mut conn = get_connection(&pool)?;
+    let operability = service().operability(&mut conn, org_id, *block_id, &daily_mm).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Harvest block operability retrieved successfully")
+            .with_data(BlockOperabilityResponse::new(*block_id, operability))
+            .build()
+    ))
+}
+
+/// Replaces the details of a harvest block, moving it to another status
+/// when one is given
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/blocks/{id}",
+    security(("bearer_auth" = [])),
+    tag = "blocks",
+    request_body = SaveHarvestBlockInput,
+    responses(
+        (status = 200, description = "Harvest block updated", body = HarvestBlockResponse),
+        (status = 400, description = "Invalid input or boundary", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required or active block quota used up", body = ErrorResponse),
+        (status = 404, description = "Harvest block not found", body = ErrorResponse),
+        (status = 409, description = "Block number taken under the license, or sign-off chain incomplete", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Harvest block ID")
+    )
+)]
+pub async fn update_block(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    block_id: web::Path<Uuid>,
+    input: web::Json<SaveHarvestBlockInput>,
+) -> Result<HttpResponse, ApiError> {
+    let changed_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let block = service()
+        .update(&mut conn, &config, org_id, *block_id, changed_by, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Harvest block updated successfully")
+            .with_data(HarvestBlockResponse::from(block))
+            .build()
+    ))
+}
+
+/// Deletes a harvest block no timber sale refers to
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/blocks/{id}",
+    security(("bearer_auth" = [])),
+    tag = "blocks",
+    responses(
+        (status = 204, description = "Harvest block deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 404, description = "Harvest block not found", body = ErrorResponse),
+        (status = 409, description = "Block offered or sold in a timber sale", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Harvest block ID")
+    )
+)]
+pub async fn delete_block(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    block_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    service().delete(&mut conn, org_id, *block_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,19 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequirePermission},
+    domain::auth::Permission,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/blocks")
+            .wrap(RequirePermission::read_write(Permission::BlocksRead, Permission::BlocksWrite))
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::block::handlers::list_blocks))
+            .route("", web::post().to(crate::api::resources::block::handlers::create_block))
+            .route("/{id}", web::get().to(crate::api::resources::block::handlers::get_block))
+            .route("/{id}/operability", web::get().to(crate::api::resources::block::handlers::get_block_operability))
+            .route("/{id}", web::put().to(crate::api::resources::block::handlers::update_block))
+            .route("/{id}", web::delete().to(crate::api::resources::block::handlers::delete_block))
+    );
+}
@@ -0,0 +1,139 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    api::resources::document::dto::DocumentResponse,
+    db::models::{auth::User, Certification, Document},
+};
+
+/// Input for recording or replacing a certification
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct SaveCertificationInput {
+    /// The member of the organization holding the certification
+    pub user_id: Uuid,
+    /// `faller`, `first_aid`, `dangerous_goods` or `other`
+    pub kind: String,
+    /// Required for `other` certifications
+    #[validate(length(max = 255))]
+    pub name: Option<String>,
+    #[validate(length(max = 100))]
+    pub certificate_number: Option<String>,
+    /// Who issued the certification, e.g. a training provider
+    #[validate(length(max = 255))]
+    pub issuer: Option<String>,
+    pub issued_on: NaiveDate,
+    /// Unset when the certification doesn't expire
+    pub expires_on: Option<NaiveDate>,
+}
+
+/// Query parameters for listing certifications
+#[derive(Debug, Default, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListCertificationsQuery {
+    /// Only the certifications of this user
+    pub user_id: Option<Uuid>,
+    /// Only the certifications of users reporting directly to this user
+    pub reports_to: Option<Uuid>,
+    /// Only certifications of this kind
+    pub kind: Option<String>,
+}
+
+/// Query parameters for listing expiring certifications
+#[derive(Debug, Default, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ExpiringCertificationsQuery {
+    /// Days ahead to look, 30 when unset
+    #[ts(type = "number | null")]
+    pub days: Option<i64>,
+    /// Only the certifications of users reporting directly to this user
+    pub reports_to: Option<Uuid>,
+}
+
+/// Certification response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CertificationResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    /// The name given, or else the kind, as shown to users
+    pub title: String,
+    pub name: Option<String>,
+    pub certificate_number: Option<String>,
+    pub issuer: Option<String>,
+    pub issued_on: NaiveDate,
+    pub expires_on: Option<NaiveDate>,
+    /// When supervisors were notified of the coming expiry
+    pub expiry_notified_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Certification> for CertificationResponse {
+    fn from(certification: Certification) -> Self {
+        Self {
+            id: certification.id,
+            title: certification.title(),
+            user_id: certification.user_id,
+            kind: certification.kind,
+            name: certification.name,
+            certificate_number: certification.certificate_number,
+            issuer: certification.issuer,
+            issued_on: certification.issued_on,
+            expires_on: certification.expires_on,
+            expiry_notified_at: certification.expiry_notified_at,
+            created_by: certification.created_by,
+            created_at: certification.created_at,
+            updated_at: certification.updated_at,
+        }
+    }
+}
+
+/// A certification with the documents attached to it
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CertificationDetailsResponse {
+    pub certification: CertificationResponse,
+    pub documents: Vec<DocumentResponse>,
+}
+
+impl CertificationDetailsResponse {
+    pub fn new(certification: Certification, documents: Vec<Document>) -> Self {
+        Self {
+            certification: CertificationResponse::from(certification),
+            documents: documents.into_iter().map(DocumentResponse::from).collect(),
+        }
+    }
+}
+
+/// A certification expiring soon, with its holder
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ExpiringCertificationResponse {
+    pub certification: CertificationResponse,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    /// Days until it expires, negative once it has expired
+    #[ts(type = "number")]
+    pub days_left: i64,
+}
+
+impl ExpiringCertificationResponse {
+    pub fn new(certification: Certification, holder: User, today: NaiveDate) -> Self {
+        let days_left = certification.expires_on.map(|expires_on| (expires_on - today).num_days()).unwrap_or_default();
+        Self {
+            certification: CertificationResponse::from(certification),
+            first_name: holder.first_name,
+            last_name: holder.last_name,
+            email: holder.email,
+            days_left,
+        }
+    }
+}
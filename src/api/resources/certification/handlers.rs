@@ -0,0 +1,270 @@
+//! Certification resource handlers
+//!
+//! Every handler works on the certifications held by members of the
+//! authenticated user's organization. Scanned certificates are uploaded
+//! through the documents API with the `certification` subject.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::certification::dto::{
+            CertificationDetailsResponse, CertificationResponse, ExpiringCertificationResponse,
+            ExpiringCertificationsQuery, ListCertificationsQuery, SaveCertificationInput,
+        },
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{
+        get_connection,
+        repositories::{CertificationRepositoryImpl, DocumentRepositoryImpl},
+        DbPool,
+    },
+    domain::{
+        certification::{CertificationService, EXPIRY_NOTICE_DAYS},
+        document::DocumentService,
+    },
+    error::ApiError,
+    utils::Config,
+};
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use uuid::Uuid;
+
+fn service(config: &Config) -> CertificationService<CertificationRepositoryImpl> {
+    CertificationService::new(
+        CertificationRepositoryImpl,
+        DocumentService::new(DocumentRepositoryImpl, config.storage().clone()),
+    )
+}
+
+/// Lists the organization's certifications, soonest expiry first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/certifications",
+    security(("bearer_auth" = [])),
+    tag = "certifications",
+    responses(
+        (status = 200, description = "Certifications", body = ListResponse<CertificationResponse>),
+        (status = 400, description = "Unknown kind", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Certifications permission required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("user_id" = Option<Uuid>, Query, description = "Only the certifications of this user"),
+        ("reports_to" = Option<Uuid>, Query, description = "Only the certifications of users reporting directly to this user"),
+        ("kind" = Option<String>, Query, description = "`faller`, `first_aid`, `dangerous_goods` or `other`")
+    )
+)]
+pub async fn list_certifications(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    query: web::Query<ListCertificationsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let certifications = service(&config)
+        .list(&mut conn, org_id, query.user_id, query.reports_to, query.kind.as_deref())
+        .await?;
+    let certifications = certifications.into_iter().map(CertificationResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Certifications retrieved successfully")
+            .with_data(certifications)
+            .build()
+    ))
+}
+
+/// Lists the certifications of the organization's active members expiring
+/// within `days`, expired ones included, soonest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/certifications/expiring",
+    security(("bearer_auth" = [])),
+    tag = "certifications",
+    responses(
+        (status = 200, description = "Expiring certifications", body = ListResponse<ExpiringCertificationResponse>),
+        (status = 400, description = "Days out of range", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Certifications permission required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("days" = Option<i64>, Query, description = "Days ahead to look, 30 when unset, up to 365"),
+        ("reports_to" = Option<Uuid>, Query, description = "Only the certifications of users reporting directly to this user")
+    )
+)]
+pub async fn list_expiring_certifications(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    query: web::Query<ExpiringCertificationsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let today = Utc::now().date_naive();
+    let mut conn = get_connection(&pool)?;
+    let expiring = service(&config)
+        .expiring(&mut conn, org_id, query.reports_to, today, query.days.unwrap_or(EXPIRY_NOTICE_DAYS))
+        .await?;
+    let expiring = expiring
+        .into_iter()
+        .map(|(certification, holder)| ExpiringCertificationResponse::new(certification, holder, today))
+        .collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Expiring certifications retrieved successfully")
+            .with_data(expiring)
+            .build()
+    ))
+}
+
+/// Records a certification held by a member of the organization
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/certifications",
+    security(("bearer_auth" = [])),
+    tag = "certifications",
+    request_body = SaveCertificationInput,
+    responses(
+        (status = 201, description = "Certification created", body = CertificationResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Certifications permission required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_certification(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    input: web::Json<SaveCertificationInput>,
+) -> Result<HttpResponse, ApiError> {
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let certification = service(&config)
+        .create(&mut conn, org_id, created_by, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Certification created successfully")
+            .with_data(CertificationResponse::from(certification))
+            .build()
+    ))
+}
+
+/// Retrieves a certification with its documents
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/certifications/{id}",
+    security(("bearer_auth" = [])),
+    tag = "certifications",
+    responses(
+        (status = 200, description = "Certification", body = CertificationDetailsResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Certifications permission required", body = ErrorResponse),
+        (status = 404, description = "Certification not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Certification ID")
+    )
+)]
+pub async fn get_certification(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    certification_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let (certification, documents) = service(&config).get(&mut conn, org_id, *certification_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Certification retrieved successfully")
+            .with_data(CertificationDetailsResponse::new(certification, documents))
+            .build()
+    ))
+}
+
+/// Replaces the details of a certification, such as when it is renewed
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/certifications/{id}",
+    security(("bearer_auth" = [])),
+    tag = "certifications",
+    request_body = SaveCertificationInput,
+    responses(
+        (status = 200, description = "Certification updated", body = CertificationResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Certifications permission required", body = ErrorResponse),
+        (status = 404, description = "Certification or user not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Certification ID")
+    )
+)]
+pub async fn update_certification(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    certification_id: web::Path<Uuid>,
+    input: web::Json<SaveCertificationInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let certification = service(&config)
+        .update(&mut conn, org_id, *certification_id, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Certification updated successfully")
+            .with_data(CertificationResponse::from(certification))
+            .build()
+    ))
+}
+
+/// Deletes a certification with its documents
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/certifications/{id}",
+    security(("bearer_auth" = [])),
+    tag = "certifications",
+    responses(
+        (status = 204, description = "Certification deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Certifications permission required", body = ErrorResponse),
+        (status = 404, description = "Certification not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Certification ID")
+    )
+)]
+pub async fn delete_certification(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    certification_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    service(&config).delete(&mut conn, org_id, *certification_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
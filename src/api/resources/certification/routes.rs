@@ -0,0 +1,20 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequirePermission},
+    domain::auth::Permission,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/certifications")
+            .wrap(RequirePermission::read_write(Permission::CertificationsRead, Permission::CertificationsWrite))
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::certification::handlers::list_certifications))
+            .route("", web::post().to(crate::api::resources::certification::handlers::create_certification))
+            // Registered before `/{id}` so `expiring` is not taken for an id
+            .route("/expiring", web::get().to(crate::api::resources::certification::handlers::list_expiring_certifications))
+            .route("/{id}", web::get().to(crate::api::resources::certification::handlers::get_certification))
+            .route("/{id}", web::put().to(crate::api::resources::certification::handlers::update_certification))
+            .route("/{id}", web::delete().to(crate::api::resources::certification::handlers::delete_certification))
+    );
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    db::models::{auth::User, CrewMember},
+    domain::crew::CrewRoster,
+};
+
+/// Input for creating a crew
+#[derive(Debug, Clone, Serialize, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct CreateCrewInput {
+    /// Unique within the organization
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    /// User leading the crew, who need not be a member
+    pub supervisor_id: Option<Uuid>,
+    /// Machine ids the crew works with unless a job says otherwise, as the
+    /// machines report them in telemetry
+    #[serde(default)]
+    #[validate(length(max = 50))]
+    pub default_equipment: Vec<String>,
+    /// Users working in the crew
+    #[serde(default)]
+    #[validate(length(max = 200))]
+    pub member_ids: Vec<Uuid>,
+}
+
+/// Input for replacing a crew's details; members are managed separately
+#[derive(Debug, Clone, Serialize, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct UpdateCrewInput {
+    /// Unique within the organization
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    /// User leading the crew, who need not be a member
+    pub supervisor_id: Option<Uuid>,
+    /// Machine ids the crew works with unless a job says otherwise
+    #[serde(default)]
+    #[validate(length(max = 50))]
+    pub default_equipment: Vec<String>,
+}
+
+/// Input for adding a member to a crew
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct AddCrewMemberInput {
+    pub user_id: Uuid,
+}
+
+/// Query parameters for listing crews
+#[derive(Debug, Default, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListCrewsQuery {
+    /// Only the crews this user supervises or works in
+    pub user_id: Option<Uuid>,
+}
+
+/// A member of a crew
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CrewMemberResponse {
+    pub user_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub added_at: DateTime<Utc>,
+}
+
+impl From<(CrewMember, User)> for CrewMemberResponse {
+    fn from((member, user): (CrewMember, User)) -> Self {
+        Self {
+            user_id: member.user_id,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            email: user.email,
+            added_at: member.added_at,
+        }
+    }
+}
+
+/// Crew response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CrewResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub supervisor_id: Option<Uuid>,
+    pub default_equipment: Vec<String>,
+    /// By last and first name
+    pub members: Vec<CrewMemberResponse>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<CrewRoster> for CrewResponse {
+    fn from(roster: CrewRoster) -> Self {
+        let crew = roster.crew;
+        Self {
+            id: crew.id,
+            name: crew.name,
+            supervisor_id: crew.supervisor_id,
+            default_equipment: crew.default_equipment,
+            members: roster.members.into_iter().map(CrewMemberResponse::from).collect(),
+            created_at: crew.created_at,
+            updated_at: crew.updated_at,
+        }
+    }
+}
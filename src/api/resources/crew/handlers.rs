@@ -0,0 +1,267 @@
+//! Crew resource handlers
+//!
+//! Every handler works on the crews of the authenticated user's
+//! organization.
+
+use crate::{
+    api::{
+        middleware::Tenant,
+        resources::crew::dto::{AddCrewMemberInput, CreateCrewInput, CrewResponse, ListCrewsQuery, UpdateCrewInput},
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{get_connection, repositories::CrewRepositoryImpl, DbPool},
+    domain::crew::CrewService,
+    error::ApiError,
+};
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+
+fn service() -> CrewService<CrewRepositoryImpl> {
+    CrewService::new(CrewRepositoryImpl)
+}
+
+/// Lists the organization's crews by name
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/crews",
+    security(("bearer_auth" = [])),
+    tag = "crews",
+    responses(
+        (status = 200, description = "Crews", body = ListResponse<CrewResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Crews permission required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("user_id" = Option<Uuid>, Query, description = "Only the crews this user supervises or works in")
+    )
+)]
+pub async fn list_crews(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    query: web::Query<ListCrewsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let crews = service().list(&mut conn, org_id, query.user_id).await?;
+    let crews = crews.into_iter().map(CrewResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Crews retrieved successfully")
+            .with_data(crews)
+            .build()
+    ))
+}
+
+/// Creates a crew with its first members
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/crews",
+    security(("bearer_auth" = [])),
+    tag = "crews",
+    request_body = CreateCrewInput,
+    responses(
+        (status = 201, description = "Crew created", body = CrewResponse),
+        (status = 400, description = "Invalid input or equipment", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Crews permission required", body = ErrorResponse),
+        (status = 404, description = "Supervisor or member not found", body = ErrorResponse),
+        (status = 409, description = "Crew name taken", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_crew(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    input: web::Json<CreateCrewInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let crew = service().create(&mut conn, org_id, input.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Crew created successfully")
+            .with_data(CrewResponse::from(crew))
+            .build()
+    ))
+}
+
+/// Retrieves a crew with its members
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/crews/{id}",
+    security(("bearer_auth" = [])),
+    tag = "crews",
+    responses(
+        (status = 200, description = "Crew", body = CrewResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Crews permission required", body = ErrorResponse),
+        (status = 404, description = "Crew not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Crew ID")
+    )
+)]
+pub async fn get_crew(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    crew_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let crew = service().get(&mut conn, org_id, *crew_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Crew retrieved successfully")
+            .with_data(CrewResponse::from(crew))
+            .build()
+    ))
+}
+
+/// Replaces a crew's name, supervisor and default equipment
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/crews/{id}",
+    security(("bearer_auth" = [])),
+    tag = "crews",
+    request_body = UpdateCrewInput,
+    responses(
+        (status = 200, description = "Crew updated", body = CrewResponse),
+        (status = 400, description = "Invalid input or equipment", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Crews permission required", body = ErrorResponse),
+        (status = 404, description = "Crew or supervisor not found", body = ErrorResponse),
+        (status = 409, description = "Crew name taken", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Crew ID")
+    )
+)]
+pub async fn update_crew(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    crew_id: web::Path<Uuid>,
+    input: web::Json<UpdateCrewInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let crew = service().update(&mut conn, org_id, *crew_id, input.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Crew updated successfully")
+            .with_data(CrewResponse::from(crew))
+            .build()
+    ))
+}
+
+/// Deletes a crew
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/crews/{id}",
+    security(("bearer_auth" = [])),
+    tag = "crews",
+    responses(
+        (status = 204, description = "Crew deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Crews permission required", body = ErrorResponse),
+        (status = 404, description = "Crew not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Crew ID")
+    )
+)]
+pub async fn delete_crew(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    crew_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    service().delete(&mut conn, org_id, *crew_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Adds a user of the organization to a crew
+///
+/// Adding a member again changes nothing.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/crews/{id}/members",
+    security(("bearer_auth" = [])),
+    tag = "crews",
+    request_body = AddCrewMemberInput,
+    responses(
+        (status = 200, description = "Member added", body = CrewResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Crews permission required", body = ErrorResponse),
+        (status = 404, description = "Crew or user not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Crew ID")
+    )
+)]
+pub async fn add_crew_member(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    crew_id: web::Path<Uuid>,
+    input: web::Json<AddCrewMemberInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let crew = service().add_member(&mut conn, org_id, *crew_id, input.user_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Crew member added successfully")
+            .with_data(CrewResponse::from(crew))
+            .build()
+    ))
+}
+
+/// Removes a member from a crew
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/crews/{id}/members/{user_id}",
+    security(("bearer_auth" = [])),
+    tag = "crews",
+    responses(
+        (status = 204, description = "Member removed"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Crews permission required", body = ErrorResponse),
+        (status = 404, description = "Crew not found, or the user is not a member", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Crew ID"),
+        ("user_id" = Uuid, Path, description = "Member's user ID")
+    )
+)]
+pub async fn remove_crew_member(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (crew_id, user_id) = path.into_inner();
+    let mut conn = get_connection(&pool)?;
+    service().remove_member(&mut conn, org_id, crew_id, user_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,20 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequirePermission},
+    domain::auth::Permission,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/crews")
+            .wrap(RequirePermission::read_write(Permission::CrewsRead, Permission::CrewsWrite))
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::crew::handlers::list_crews))
+            .route("", web::post().to(crate::api::resources::crew::handlers::create_crew))
+            .route("/{id}", web::get().to(crate::api::resources::crew::handlers::get_crew))
+            .route("/{id}", web::put().to(crate::api::resources::crew::handlers::update_crew))
+            .route("/{id}", web::delete().to(crate::api::resources::crew::handlers::delete_crew))
+            .route("/{id}/members", web::post().to(crate::api::resources::crew::handlers::add_crew_member))
+            .route("/{id}/members/{user_id}", web::delete().to(crate::api::resources::crew::handlers::remove_crew_member))
+    );
+}
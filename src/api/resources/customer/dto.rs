@@ -0,0 +1,153 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    db::models::{Customer, SupplyContract},
+    domain::customer::CommitmentProgress,
+};
+
+/// Input for creating or replacing a customer
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct SaveCustomerInput {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(length(max = 255))]
+    pub contact_name: Option<String>,
+    #[validate(email)]
+    pub email: Option<String>,
+    #[validate(length(max = 50))]
+    pub phone_number: Option<String>,
+    #[validate(length(max = 1000))]
+    pub address: Option<String>,
+    #[validate(length(max = 4000))]
+    pub notes: Option<String>,
+}
+
+/// Input for creating or replacing a supply contract
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct SaveSupplyContractInput {
+    /// Contract number, unique within the organization
+    #[validate(length(min = 1, max = 100))]
+    pub reference: String,
+    /// Product delivered, e.g. `spruce sawlog`
+    #[validate(length(min = 1, max = 100))]
+    pub product: String,
+    /// Volume committed over the period
+    pub volume_m3: f64,
+    pub price_per_m3: f64,
+    /// First delivery day
+    pub starts_on: NaiveDate,
+    /// Last delivery day
+    pub ends_on: NaiveDate,
+    #[validate(length(max = 4000))]
+    pub notes: Option<String>,
+}
+
+/// Query parameters for listing customers
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListCustomersQuery {
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// Query parameters for commitment progress
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CommitmentsQuery {
+    /// Day to measure progress on, today when unset
+    pub as_of: Option<NaiveDate>,
+}
+
+/// Customer response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CustomerResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub email: Option<String>,
+    pub phone_number: Option<String>,
+    pub address: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Customer> for CustomerResponse {
+    fn from(customer: Customer) -> Self {
+        Self {
+            id: customer.id,
+            name: customer.name,
+            contact_name: customer.contact_name,
+            email: customer.email,
+            phone_number: customer.phone_number,
+            address: customer.address,
+            notes: customer.notes,
+            created_at: customer.created_at,
+            updated_at: customer.updated_at,
+        }
+    }
+}
+
+/// Supply contract response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SupplyContractResponse {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub reference: String,
+    pub product: String,
+    pub volume_m3: f64,
+    pub price_per_m3: f64,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SupplyContract> for SupplyContractResponse {
+    fn from(contract: SupplyContract) -> Self {
+        Self {
+            id: contract.id,
+            customer_id: contract.customer_id,
+            reference: contract.reference,
+            product: contract.product,
+            volume_m3: contract.volume_m3,
+            price_per_m3: contract.price_per_m3,
+            starts_on: contract.starts_on,
+            ends_on: contract.ends_on,
+            notes: contract.notes,
+            created_by: contract.created_by,
+            created_at: contract.created_at,
+            updated_at: contract.updated_at,
+        }
+    }
+}
+
+/// A supply contract with how deliveries compare with its commitment
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ContractCommitmentResponse {
+    pub contract: SupplyContractResponse,
+    pub progress: CommitmentProgress,
+}
+
+impl From<(SupplyContract, CommitmentProgress)> for ContractCommitmentResponse {
+    fn from((contract, progress): (SupplyContract, CommitmentProgress)) -> Self {
+        Self {
+            contract: SupplyContractResponse::from(contract),
+            progress,
+        }
+    }
+}
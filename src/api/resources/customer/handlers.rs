@@ -0,0 +1,545 @@
+//! Customer and supply contract resource handlers
+//!
+//! Every handler works on the customers and supply contracts of the
+//! authenticated user's organization. Routes require the manager role.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::{
+            customer::dto::{
+                CommitmentsQuery, ContractCommitmentResponse, CustomerResponse, ListCustomersQuery, SaveCustomerInput,
+                SaveSupplyContractInput, SupplyContractResponse,
+            },
+            history::dto::FieldChangeResponse,
+        },
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse, PaginatedResponse, PaginationParams},
+    },
+    db::{get_connection, repositories::CustomerRepositoryImpl, DbPool},
+    domain::customer::CustomerService,
+    error::ApiError,
+};
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use uuid::Uuid;
+
+fn service() -> CustomerService<CustomerRepositoryImpl> {
+    CustomerService::new(CustomerRepositoryImpl)
+}
+
+/// Lists the organization's customers by name
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/customers",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    responses(
+        (status = 200, description = "Customers", body = PaginatedResponse<CustomerResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page")
+    )
+)]
+pub async fn list_customers(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    query: web::Query<ListCustomersQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+    let mut conn = get_connection(&pool)?;
+    let (customers, total) = service().list_customers(&mut conn, org_id, &pagination).await?;
+    let customers = customers.into_iter().map(CustomerResponse::from).collect();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Customers retrieved successfully")
+            .with_data(PaginatedResponse::with_count(customers, total, &pagination))
+            .build()
+    ))
+}
+
+/// Creates a customer
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/customers",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    request_body = SaveCustomerInput,
+    responses(
+        (status = 201, description = "Customer created", body = CustomerResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 409, description = "A customer with this name exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_customer(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    input: web::Json<SaveCustomerInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let customer = service().create_customer(&mut conn, org_id, input.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Customer created successfully")
+            .with_data(CustomerResponse::from(customer))
+            .build()
+    ))
+}
+
+/// Retrieves a customer
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/customers/{id}",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    responses(
+        (status = 200, description = "Customer", body = CustomerResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Customer not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Customer ID")
+    )
+)]
+pub async fn get_customer(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    customer_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let customer = service().get_customer(&mut conn, org_id, *customer_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Customer retrieved successfully")
+            .with_data(CustomerResponse::from(customer))
+            .build()
+    ))
+}
+
+/// Replaces a customer's details
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/customers/{id}",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    request_body = SaveCustomerInput,
+    responses(
+        (status = 200, description = "Customer updated", body = CustomerResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Customer not found", body = ErrorResponse),
+        (status = 409, description = "A customer with this name exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Customer ID")
+    )
+)]
+pub async fn update_customer(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    customer_id: web::Path<Uuid>,
+    input: web::Json<SaveCustomerInput>,
+) -> Result<HttpResponse, ApiError> {
+    let changed_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let customer = service()
+        .update_customer(&mut conn, org_id, *customer_id, changed_by, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Customer updated successfully")
+            .with_data(CustomerResponse::from(customer))
+            .build()
+    ))
+}
+
+/// Deletes a customer that has no supply contracts
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/customers/{id}",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    responses(
+        (status = 204, description = "Customer deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Customer not found", body = ErrorResponse),
+        (status = 409, description = "The customer has supply contracts", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Customer ID")
+    )
+)]
+pub async fn delete_customer(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    customer_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    service().delete_customer(&mut conn, org_id, *customer_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Lists the supply contracts of a customer, latest period first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/customers/{id}/contracts",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    responses(
+        (status = 200, description = "Supply contracts", body = ListResponse<SupplyContractResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Customer not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Customer ID")
+    )
+)]
+pub async fn list_supply_contracts(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    customer_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let contracts = service().list_contracts(&mut conn, org_id, *customer_id).await?;
+    let contracts = contracts.into_iter().map(SupplyContractResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Supply contracts retrieved successfully")
+            .with_data(contracts)
+            .build()
+    ))
+}
+
+/// Creates a supply contract for a customer
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/customers/{id}/contracts",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    request_body = SaveSupplyContractInput,
+    responses(
+        (status = 201, description = "Supply contract created", body = SupplyContractResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Customer not found", body = ErrorResponse),
+        (status = 409, description = "A contract with this reference exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Customer ID")
+    )
+)]
+pub async fn create_supply_contract(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    customer_id: web::Path<Uuid>,
+    input: web::Json<SaveSupplyContractInput>,
+) -> Result<HttpResponse, ApiError> {
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let contract = service()
+        .create_contract(&mut conn, org_id, *customer_id, created_by, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Supply contract created successfully")
+            .with_data(SupplyContractResponse::from(contract))
+            .build()
+    ))
+}
+
+/// Retrieves a supply contract
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/supply-contracts/{id}",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    responses(
+        (status = 200, description = "Supply contract", body = SupplyContractResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Supply contract not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Supply contract ID")
+    )
+)]
+pub async fn get_supply_contract(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    contract_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let contract = service().get_contract(&mut conn, org_id, *contract_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Supply contract retrieved successfully")
+            .with_data(SupplyContractResponse::from(contract))
+            .build()
+    ))
+}
+
+/// Replaces the terms of a supply contract
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/supply-contracts/{id}",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    request_body = SaveSupplyContractInput,
+    responses(
+        (status = 200, description = "Supply contract updated", body = SupplyContractResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Supply contract not found", body = ErrorResponse),
+        (status = 409, description = "A contract with this reference exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Supply contract ID")
+    )
+)]
+pub async fn update_supply_contract(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    contract_id: web::Path<Uuid>,
+    input: web::Json<SaveSupplyContractInput>,
+) -> Result<HttpResponse, ApiError> {
+    let changed_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let contract = service()
+        .update_contract(&mut conn, org_id, *contract_id, changed_by, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Supply contract updated successfully")
+            .with_data(SupplyContractResponse::from(contract))
+            .build()
+    ))
+}
+
+/// Deletes a supply contract
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/supply-contracts/{id}",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    responses(
+        (status = 204, description = "Supply contract deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Supply contract not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Supply contract ID")
+    )
+)]
+pub async fn delete_supply_contract(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    contract_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    service().delete_contract(&mut conn, org_id, *contract_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Compares deliveries with the commitments of every supply contract
+/// running on a day, flagging those projected to fall short
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/supply-contracts/commitments",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    responses(
+        (status = 200, description = "Commitment progress", body = ListResponse<ContractCommitmentResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("as_of" = Option<String>, Query, description = "Day to measure progress on (YYYY-MM-DD), today when unset")
+    )
+)]
+pub async fn list_commitments(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    query: web::Query<CommitmentsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let as_of = query.as_of.unwrap_or_else(|| Utc::now().date_naive());
+    let mut conn = get_connection(&pool)?;
+    let commitments = service().commitments(&mut conn, org_id, as_of).await?;
+    let commitments = commitments
+        .into_iter()
+        .map(ContractCommitmentResponse::from)
+        .collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Commitments retrieved successfully")
+            .with_data(commitments)
+            .build()
+    ))
+}
+
+/// Compares deliveries on a supply contract with its commitment
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/supply-contracts/{id}/commitment",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    responses(
+        (status = 200, description = "Commitment progress", body = ContractCommitmentResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Supply contract not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Supply contract ID"),
+        ("as_of" = Option<String>, Query, description = "Day to measure progress on (YYYY-MM-DD), today when unset")
+    )
+)]
+pub async fn get_contract_commitment(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    contract_id: web::Path<Uuid>,
+    query: web::Query<CommitmentsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let as_of = query.as_of.unwrap_or_else(|| Utc::now().date_naive());
+    let mut conn = get_connection(&pool)?;
+    let commitment = service().commitment(&mut conn, org_id, *contract_id, as_of).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Commitment retrieved successfully")
+            .with_data(ContractCommitmentResponse::from(commitment))
+            .build()
+    ))
+}
+
+/// Lists the changes made to a customer, oldest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/customers/{id}/history",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    responses(
+        (status = 200, description = "Change history", body = ListResponse<FieldChangeResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Customer not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Customer ID")
+    )
+)]
+pub async fn get_customer_history(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    customer_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let changes = service().customer_history(&mut conn, org_id, *customer_id).await?;
+    let changes = changes.into_iter().map(FieldChangeResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Change history retrieved successfully")
+            .with_data(changes)
+            .build()
+    ))
+}
+
+/// Lists the changes made to a supply contract, oldest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/supply-contracts/{id}/history",
+    security(("bearer_auth" = [])),
+    tag = "customers",
+    responses(
+        (status = 200, description = "Change history", body = ListResponse<FieldChangeResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Supply contract not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Supply contract ID")
+    )
+)]
+pub async fn get_supply_contract_history(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    contract_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let changes = service().contract_history(&mut conn, org_id, *contract_id).await?;
+    let changes = changes.into_iter().map(FieldChangeResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Change history retrieved successfully")
+            .with_data(changes)
+            .build()
+    ))
+}
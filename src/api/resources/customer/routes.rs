@@ -0,0 +1,33 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequirePermission},
+    domain::auth::Permission,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/customers")
+            .wrap(RequirePermission::read_write(Permission::CustomersRead, Permission::CustomersWrite))
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::customer::handlers::list_customers))
+            .route("", web::post().to(crate::api::resources::customer::handlers::create_customer))
+            .route("/{id}", web::get().to(crate::api::resources::customer::handlers::get_customer))
+            .route("/{id}", web::put().to(crate::api::resources::customer::handlers::update_customer))
+            .route("/{id}", web::delete().to(crate::api::resources::customer::handlers::delete_customer))
+            .route("/{id}/contracts", web::get().to(crate::api::resources::customer::handlers::list_supply_contracts))
+            .route("/{id}/contracts", web::post().to(crate::api::resources::customer::handlers::create_supply_contract))
+            .route("/{id}/history", web::get().to(crate::api::resources::customer::handlers::get_customer_history))
+    )
+    .service(
+        web::scope("/supply-contracts")
+            .wrap(RequirePermission::read_write(Permission::CustomersRead, Permission::CustomersWrite))
+            .wrap(Auth::new())
+            // Registered before `/{id}` so it is not taken for a contract id
+            .route("/commitments", web::get().to(crate::api::resources::customer::handlers::list_commitments))
+            .route("/{id}", web::get().to(crate::api::resources::customer::handlers::get_supply_contract))
+            .route("/{id}", web::put().to(crate::api::resources::customer::handlers::update_supply_contract))
+            .route("/{id}", web::delete().to(crate::api::resources::customer::handlers::delete_supply_contract))
+            .route("/{id}/commitment", web::get().to(crate::api::resources::customer::handlers::get_contract_commitment))
+            .route("/{id}/history", web::get().to(crate::api::resources::customer::handlers::get_supply_contract_history))
+    );
+}
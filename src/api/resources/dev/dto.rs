@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::infrastructure::email::ReceivedEmail;
+
+/// Query parameters for reading the dev mailbox
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct MailboxQuery {
+    /// Only list mail sent to this address
+    pub to: Option<String>,
+}
+
+/// Mail caught by the dev mailbox, newest first
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct MailboxResponse {
+    pub messages: Vec<ReceivedEmail>,
+}
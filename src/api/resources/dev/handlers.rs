@@ -0,0 +1,66 @@
+//! Development helper handlers
+
+use crate::{
+    api::{
+        resources::dev::dto::{MailboxQuery, MailboxResponse},
+        utils::{ApiResponseBuilder, ErrorResponse},
+    },
+    error::ApiError,
+    infrastructure::email::DevMailbox,
+    utils::Config,
+};
+use actix_web::{web, HttpResponse};
+
+fn mailbox(config: &Config) -> Result<&DevMailbox, ApiError> {
+    config
+        .mailer()
+        .mailbox()
+        .ok_or_else(|| ApiError::not_found("Dev mailbox is disabled, set email.transport = \"mailbox\""))
+}
+
+/// Lists mail caught by the dev mailbox
+///
+/// Only available with `email.transport = "mailbox"`, which is limited to
+/// the development environment.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/dev/mailbox",
+    tag = "dev",
+    params(("to" = Option<String>, Query, description = "Only list mail sent to this address")),
+    responses(
+        (status = 200, description = "Caught mail", body = MailboxResponse),
+        (status = 404, description = "Dev mailbox disabled", body = ErrorResponse)
+    )
+)]
+pub async fn list_mailbox(
+    config: web::Data<Config>,
+    query: web::Query<MailboxQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let messages = mailbox(&config)?.list(query.to.as_deref());
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Mailbox retrieved successfully")
+            .with_data(MailboxResponse { messages })
+            .build()
+    ))
+}
+
+/// Empties the dev mailbox
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/dev/mailbox",
+    tag = "dev",
+    responses(
+        (status = 204, description = "Mailbox emptied"),
+        (status = 404, description = "Dev mailbox disabled", body = ErrorResponse)
+    )
+)]
+pub async fn clear_mailbox(config: web::Data<Config>) -> Result<HttpResponse, ApiError> {
+    mailbox(&config)?.clear();
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,8 @@
+//! Development helpers
+//!
+//! Endpoints for local development and end-to-end tests. They answer 404
+//! unless the matching development feature is configured.
+
+pub mod dto;
+pub mod handlers;
+pub mod routes;
@@ -0,0 +1,9 @@
+use actix_web::web;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/dev")
+            .route("/mailbox", web::get().to(crate::api::resources::dev::handlers::list_mailbox))
+            .route("/mailbox", web::delete().to(crate::api::resources::dev::handlers::clear_mailbox))
+    );
+}
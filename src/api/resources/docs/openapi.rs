@@ -1,6 +1,9 @@
-use actix_web::web;
-use utoipa::OpenApi;
-use utoipa_swagger_ui::SwaggerUi;
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::domain::telemetry::API_KEY_HEADER;
 
 #[derive(OpenApi)]
 #[openapi(
@@ -8,45 +11,689 @@ use utoipa_swagger_ui::SwaggerUi;
         crate::api::resources::health::handlers::health_check,
         crate::api::resources::health::handlers::liveness,
         crate::api::resources::health::handlers::readiness,
+        crate::api::resources::health::handlers::startup,
         crate::api::resources::auth::handlers::login,
         crate::api::resources::auth::handlers::register,
         crate::api::resources::auth::handlers::refresh,
+        crate::api::resources::auth::handlers::logout,
+        crate::api::resources::auth::handlers::list_devices,
+        crate::api::resources::auth::handlers::revoke_device,
+        crate::api::resources::auth::handlers::passkey_registration_options,
+        crate::api::resources::auth::handlers::register_passkey,
+        crate::api::resources::auth::handlers::list_passkeys,
+        crate::api::resources::auth::handlers::delete_passkey,
+        crate::api::resources::auth::handlers::passkey_login_options,
+        crate::api::resources::auth::handlers::passkey_login,
+        crate::api::resources::auth::handlers::forgot_password,
+        crate::api::resources::auth::handlers::reset_password,
+        crate::api::resources::auth::handlers::send_verification,
+        crate::api::resources::auth::handlers::verify_email,
+        crate::api::resources::auth::handlers::confirm_email_change,
+        crate::api::resources::auth::handlers::secure_account,
+        crate::api::resources::auth::handlers::request_magic_link,
+        crate::api::resources::auth::handlers::redeem_magic_link,
+        crate::api::resources::auth::handlers::oidc_authorize,
+        crate::api::resources::auth::handlers::oidc_callback,
+        crate::api::resources::auth::handlers::jwks,
+        crate::api::resources::user::handlers::list_my_auth_events,
+        crate::api::resources::user::handlers::list_user_auth_events,
+        crate::api::resources::user::handlers::list_my_activity,
+        crate::api::resources::user::handlers::list_user_activity,
+        crate::api::resources::user::handlers::anonymize_user,
+        crate::api::resources::user::handlers::assign_supervisor,
+        crate::api::resources::user::handlers::list_my_reports,
+        crate::api::resources::user::handlers::list_user_reports,
+        crate::api::resources::user::handlers::deactivate_user,
+        crate::api::resources::user::handlers::reactivate_user,
+        crate::api::resources::user::handlers::get_me,
+        crate::api::resources::user::handlers::update_me,
+        crate::api::resources::user::handlers::upload_my_avatar,
+        crate::api::resources::user::handlers::get_avatar,
+        crate::api::resources::user::handlers::get_my_preferences,
+        crate::api::resources::user::handlers::update_my_preferences,
+        crate::api::resources::user::handlers::request_email_change,
+        crate::api::resources::invitation::handlers::create_invitation,
+        crate::api::resources::invitation::handlers::list_invitations,
+        crate::api::resources::invitation::handlers::resend_invitation,
+        crate::api::resources::invitation::handlers::revoke_invitation,
+        crate::api::resources::role::handlers::list_roles,
+        crate::api::resources::role::handlers::create_role,
+        crate::api::resources::role::handlers::get_role,
+        crate::api::resources::role::handlers::update_role,
+        crate::api::resources::role::handlers::delete_role,
+        crate::api::resources::role::handlers::assign_role,
+        crate::api::resources::role::handlers::unassign_role,
         crate::api::resources::organization::handlers::read::get_organization,
         crate::api::resources::organization::handlers::read::list_organizations,
+        crate::api::resources::organization::handlers::read::list_organization_users,
+        crate::api::resources::organization::handlers::read::get_organization_tree,
+        crate::api::resources::organization::handlers::read::get_organization_settings,
+        crate::api::resources::organization::handlers::read::get_organization_usage,
+        crate::api::resources::organization::handlers::read::get_organization_archive,
+        crate::api::resources::organization::handlers::read::list_organization_members,
+        crate::api::resources::organization::handlers::read::list_organization_activity,
+        crate::api::resources::organization::handlers::read::download_organization_export,
+        crate::api::resources::organization::handlers::update::update_organization_settings,
+        crate::api::resources::organization::handlers::update::transfer_ownership,
+        crate::api::resources::organization::handlers::update::accept_ownership_transfer,
+        crate::api::resources::organization::handlers::update::archive_organization,
+        crate::api::resources::organization::handlers::update::unarchive_organization,
+        crate::api::resources::organization::handlers::update::restore_organization,
+        crate::api::resources::organization::handlers::update::change_member_role,
+        crate::api::resources::organization::handlers::delete::remove_member,
+        crate::api::resources::organization::handlers::create::create_child_organization,
+        crate::api::resources::organization::handlers::create::import_organization_users,
         crate::api::resources::organization::handlers::create::create_organization,
         crate::api::resources::organization::handlers::update::update_organization,
-        crate::api::resources::organization::handlers::delete::delete_organization
+        crate::api::resources::organization::handlers::delete::delete_organization,
+        crate::api::resources::admin::handlers::archives::list_archives,
+        crate::api::resources::admin::handlers::archives::get_archive,
+        crate::api::resources::admin::handlers::archives::get_archive_records,
+        crate::api::resources::admin::handlers::archives::rehydrate_archive,
+        crate::api::resources::admin::handlers::legal_holds::list_legal_holds,
+        crate::api::resources::admin::handlers::legal_holds::create_legal_hold,
+        crate::api::resources::admin::handlers::legal_holds::release_legal_hold,
+        crate::api::resources::admin::handlers::legal_holds::get_retention_policy,
+        crate::api::resources::admin::handlers::logging::get_log_filter,
+        crate::api::resources::admin::handlers::logging::update_log_filter,
+        crate::api::resources::admin::handlers::live_config::get_live_config,
+        crate::api::resources::admin::handlers::live_config::reload_config,
+        crate::api::resources::admin::handlers::scheduled_jobs::list_scheduled_jobs,
+        crate::api::resources::admin::handlers::scheduled_jobs::get_scheduled_job,
+        crate::api::resources::admin::handlers::scheduled_jobs::update_job_schedule,
+        crate::api::resources::admin::handlers::queue::list_queued_jobs,
+        crate::api::resources::admin::handlers::queue::list_dead_letters,
+        crate::api::resources::admin::handlers::queue::get_dead_letter,
+        crate::api::resources::admin::handlers::queue::requeue_dead_letter,
+        crate::api::resources::admin::handlers::queue::discard_dead_letter,
+        crate::api::resources::admin::handlers::email_senders::get_email_sender,
+        crate::api::resources::admin::handlers::email_senders::update_email_sender,
+        crate::api::resources::admin::handlers::email_senders::delete_email_sender,
+        crate::api::resources::admin::handlers::organizations::list_organizations,
+        crate::api::resources::admin::handlers::quotas::get_quotas,
+        crate::api::resources::admin::handlers::quotas::update_quotas,
+        crate::api::resources::admin::handlers::sso_domains::list_sso_domains,
+        crate::api::resources::admin::handlers::sso_domains::add_sso_domain,
+        crate::api::resources::admin::handlers::sso_domains::remove_sso_domain,
+        crate::api::resources::admin::handlers::scim_tokens::list_scim_tokens,
+        crate::api::resources::admin::handlers::scim_tokens::create_scim_token,
+        crate::api::resources::admin::handlers::scim_tokens::revoke_scim_token,
+        crate::api::resources::admin::handlers::permissions::list_permissions,
+        crate::api::resources::admin::handlers::permissions::update_role_permissions,
+        crate::api::resources::dev::handlers::list_mailbox,
+        crate::api::resources::dev::handlers::clear_mailbox,
+        crate::api::resources::notification::handlers::list_notifications,
+        crate::api::resources::notification::handlers::unread_count,
+        crate::api::resources::notification::handlers::mark_read,
+        crate::api::resources::notification::handlers::mark_all_read,
+        crate::api::resources::report::handlers::list_reports,
+        crate::api::resources::report::handlers::create_report,
+        crate::api::resources::report::handlers::get_report,
+        crate::api::resources::report::handlers::update_report,
+        crate::api::resources::report::handlers::delete_report,
+        crate::api::resources::report::handlers::run_report,
+        crate::api::resources::report::handlers::schedule_report,
+        crate::api::resources::report::handlers::get_report_schedule,
+        crate::api::resources::report::handlers::delete_report_schedule,
+        crate::api::resources::report::handlers::list_report_deliveries,
+        crate::api::resources::report::handlers::download_report_delivery,
+        crate::api::resources::import::handlers::list_import_targets,
+        crate::api::resources::import::handlers::upload_import,
+        crate::api::resources::import::handlers::list_imports,
+        crate::api::resources::import::handlers::get_import,
+        crate::api::resources::import::handlers::preview_import,
+        crate::api::resources::import::handlers::commit_import,
+        crate::api::resources::import::handlers::download_import_errors,
+        crate::api::resources::erp::handlers::list_erp_sources,
+        crate::api::resources::erp::handlers::list_erp_connectors,
+        crate::api::resources::erp::handlers::list_erp_connections,
+        crate::api::resources::erp::handlers::create_erp_connection,
+        crate::api::resources::erp::handlers::get_erp_connection,
+        crate::api::resources::erp::handlers::update_erp_connection,
+        crate::api::resources::erp::handlers::delete_erp_connection,
+        crate::api::resources::erp::handlers::run_erp_export,
+        crate::api::resources::erp::handlers::list_erp_exports,
+        crate::api::resources::erp::handlers::download_erp_export,
+        crate::api::resources::billing::handlers::list_plans,
+        crate::api::resources::billing::handlers::get_subscription,
+        crate::api::resources::billing::handlers::create_checkout,
+        crate::api::resources::billing::handlers::stripe_webhook,
+        crate::api::resources::sales::handlers::list_tenders,
+        crate::api::resources::sales::handlers::create_tender,
+        crate::api::resources::sales::handlers::get_tender,
+        crate::api::resources::sales::handlers::open_tender,
+        crate::api::resources::sales::handlers::cancel_tender,
+        crate::api::resources::sales::handlers::capture_tender_bid,
+        crate::api::resources::sales::handlers::list_tender_bids,
+        crate::api::resources::sales::handlers::award_tender_parcel,
+        crate::api::resources::sales::handlers::list_sale_contracts,
+        crate::api::resources::sales::handlers::get_sale_contract,
+        crate::api::resources::customer::handlers::list_customers,
+        crate::api::resources::customer::handlers::create_customer,
+        crate::api::resources::customer::handlers::get_customer,
+        crate::api::resources::customer::handlers::update_customer,
+        crate::api::resources::customer::handlers::delete_customer,
+        crate::api::resources::customer::handlers::list_supply_contracts,
+        crate::api::resources::customer::handlers::create_supply_contract,
+        crate::api::resources::customer::handlers::get_supply_contract,
+        crate::api::resources::customer::handlers::update_supply_contract,
+        crate::api::resources::customer::handlers::delete_supply_contract,
+        crate::api::resources::customer::handlers::list_commitments,
+        crate::api::resources::customer::handlers::get_contract_commitment,
+        crate::api::resources::customer::handlers::get_customer_history,
+        crate::api::resources::customer::handlers::get_supply_contract_history,
+        crate::api::resources::approval::handlers::get_block_signoffs,
+        crate::api::resources::approval::handlers::sign_block,
+        crate::api::resources::approval::handlers::reset_block_signoffs,
+        crate::api::resources::document::handlers::list_documents,
+        crate::api::resources::document::handlers::upload_document,
+        crate::api::resources::document::handlers::get_document,
+        crate::api::resources::document::handlers::delete_document,
+        crate::api::resources::document::handlers::upload_document_version,
+        crate::api::resources::document::handlers::download_document_version,
+        crate::api::resources::document::handlers::restore_document_version,
+        crate::api::resources::document::handlers::get_document_checklist,
+        crate::api::resources::tag::handlers::list_tags,
+        crate::api::resources::tag::handlers::create_tag,
+        crate::api::resources::tag::handlers::get_tag,
+        crate::api::resources::tag::handlers::update_tag,
+        crate::api::resources::tag::handlers::delete_tag,
+        crate::api::resources::tag::handlers::list_taggings,
+        crate::api::resources::tag::handlers::attach_tag,
+        crate::api::resources::tag::handlers::detach_tag,
+        crate::api::resources::tag::handlers::list_subject_tags,
+        crate::api::resources::tag::handlers::list_tagged_subjects,
+        crate::api::resources::block::handlers::list_blocks,
+        crate::api::resources::block::handlers::create_block,
+        crate::api::resources::block::handlers::get_block,
+        crate::api::resources::block::handlers::get_block_operability,
+        crate::api::resources::block::handlers::update_block,
+        crate::api::resources::block::handlers::delete_block,
+        crate::api::resources::stand::handlers::list_stands,
+        crate::api::resources::stand::handlers::create_stand,
+        crate::api::resources::stand::handlers::upsert_stands,
+        crate::api::resources::stand::handlers::get_volume_summary,
+        crate::api::resources::stand::handlers::get_stand,
+        crate::api::resources::stand::handlers::update_stand,
+        crate::api::resources::stand::handlers::delete_stand,
+        crate::api::resources::telemetry::handlers::ingest_telemetry_batch,
+        crate::api::resources::telemetry::handlers::list_telemetry_keys,
+        crate::api::resources::telemetry::handlers::create_telemetry_key,
+        crate::api::resources::telemetry::handlers::revoke_telemetry_key,
+        crate::api::resources::crew::handlers::list_crews,
+        crate::api::resources::crew::handlers::create_crew,
+        crate::api::resources::crew::handlers::get_crew,
+        crate::api::resources::crew::handlers::update_crew,
+        crate::api::resources::crew::handlers::delete_crew,
+        crate::api::resources::crew::handlers::add_crew_member,
+        crate::api::resources::crew::handlers::remove_crew_member,
+        crate::api::resources::certification::handlers::list_certifications,
+        crate::api::resources::certification::handlers::list_expiring_certifications,
+        crate::api::resources::certification::handlers::create_certification,
+        crate::api::resources::certification::handlers::get_certification,
+        crate::api::resources::certification::handlers::update_certification,
+        crate::api::resources::certification::handlers::delete_certification,
+        crate::api::resources::presence::handlers::heartbeat,
+        crate::api::resources::presence::handlers::list_online,
+        crate::api::resources::view::handlers::list_views,
+        crate::api::resources::view::handlers::create_view,
+        crate::api::resources::view::handlers::get_view,
+        crate::api::resources::view::handlers::update_view,
+        crate::api::resources::view::handlers::delete_view,
+        crate::api::resources::view::handlers::set_default_view,
+        crate::api::resources::view::handlers::get_default_view,
+        crate::api::resources::view::handlers::clear_default_view,
+        crate::api::resources::search::handlers::quick_search,
+        crate::api::resources::scim::handlers::service_provider_config,
+        crate::api::resources::scim::handlers::list_users,
+        crate::api::resources::scim::handlers::create_user,
+        crate::api::resources::scim::handlers::get_user,
+        crate::api::resources::scim::handlers::replace_user,
+        crate::api::resources::scim::handlers::patch_user,
+        crate::api::resources::scim::handlers::delete_user,
+        crate::api::resources::scim::handlers::list_groups,
+        crate::api::resources::scim::handlers::get_group,
+        crate::api::resources::scim::handlers::patch_group
     ),
     components(
         schemas(
             crate::api::resources::auth::dto::LoginRequest,
             crate::api::resources::auth::dto::RegisterRequest,
             crate::api::resources::auth::dto::RefreshRequest,
+            crate::api::resources::auth::dto::LogoutRequest,
+            crate::api::resources::auth::dto::ForgotPasswordRequest,
+            crate::api::resources::auth::dto::ResetPasswordRequest,
+            crate::api::resources::auth::dto::SendVerificationRequest,
+            crate::api::resources::auth::dto::VerifyEmailRequest,
+            crate::api::resources::auth::dto::ConfirmEmailChangeRequest,
+            crate::api::resources::auth::dto::SecureAccountRequest,
+            crate::api::resources::auth::dto::MagicLinkRequest,
+            crate::api::resources::auth::dto::RedeemMagicLinkRequest,
+            crate::api::resources::auth::dto::OidcCallbackRequest,
+            crate::api::resources::auth::dto::OidcAuthorizationResponse,
             crate::api::resources::auth::dto::AuthResponse,
             crate::api::resources::auth::dto::UserResponse,
+            crate::api::resources::auth::dto::AvatarResponse,
+            crate::api::resources::auth::dto::DeviceResponse,
+            crate::api::resources::auth::dto::RegisterPasskeyRequest,
+            crate::api::resources::auth::dto::PasskeyAttestation,
+            crate::api::resources::auth::dto::PasskeyAttestationResponse,
+            crate::api::resources::auth::dto::PasskeyLoginRequest,
+            crate::api::resources::auth::dto::PasskeyAssertion,
+            crate::api::resources::auth::dto::PasskeyAssertionResponse,
+            crate::api::resources::auth::dto::PasskeyRegistrationOptionsResponse,
+            crate::api::resources::auth::dto::PasskeyLoginOptionsResponse,
+            crate::api::resources::auth::dto::PasskeyResponse,
+            crate::domain::auth::webauthn::CreationOptions,
+            crate::domain::auth::webauthn::RequestOptions,
+            crate::domain::auth::webauthn::RelyingParty,
+            crate::domain::auth::webauthn::UserEntity,
+            crate::domain::auth::webauthn::CredentialParameters,
+            crate::domain::auth::webauthn::AuthenticatorSelection,
+            crate::domain::auth::webauthn::CredentialDescriptor,
+            crate::api::resources::user::dto::AuthEventResponse,
+            crate::api::resources::user::dto::ActivityResponse,
+            crate::api::resources::user::dto::AssignSupervisorInput,
+            crate::api::resources::user::dto::UpdateProfileInput,
+            crate::api::resources::user::dto::PreferencesResponse,
+            crate::api::resources::user::dto::UpdatePreferencesInput,
+            crate::domain::user::UnitSystem,
+            crate::api::resources::user::dto::EmailChangeInput,
+            crate::api::resources::invitation::dto::CreateInvitationInput,
+            crate::api::resources::invitation::dto::InvitationResponse,
+            crate::api::resources::role::dto::SaveRoleInput,
+            crate::api::resources::role::dto::RoleResponse,
+            crate::domain::auth::Jwk,
+            crate::domain::auth::JwkSet,
             crate::api::resources::health::dto::HealthStatus,
             crate::api::resources::health::dto::SystemMetrics,
+            crate::api::resources::health::dto::ProbeStatus,
+            crate::api::resources::health::dto::DependencyCheck,
+            crate::api::resources::health::dto::CheckStatus,
             crate::api::resources::organization::dto::CreateOrganizationInput,
             crate::api::resources::organization::dto::UpdateOrganizationInput,
             crate::api::resources::organization::dto::OrganizationResponse,
+            crate::api::resources::organization::dto::OrganizationNodeResponse,
+            crate::api::resources::organization::dto::OrganizationSettingsResponse,
+            crate::api::resources::organization::dto::OrganizationUsageResponse,
+            crate::api::resources::organization::dto::UpdateOrganizationSettingsInput,
+            crate::api::resources::organization::dto::TransferOwnershipInput,
+            crate::api::resources::organization::dto::OwnershipTransferResponse,
+            crate::api::resources::organization::dto::OrganizationArchiveResponse,
+            crate::api::resources::organization::dto::ChangeMemberRoleInput,
+            crate::api::resources::organization::dto::OrganizationActivityResponse,
+            crate::api::resources::admin::dto::ArchiveResponse,
+            crate::api::resources::admin::dto::ArchiveRecordsResponse,
+            crate::jobs::archive::ArchiveRecord,
+            crate::api::resources::admin::dto::CreateLegalHoldInput,
+            crate::api::resources::admin::dto::LegalHoldResponse,
+            crate::api::resources::admin::dto::EntityRetentionResponse,
+            crate::api::resources::admin::dto::RetentionPolicyResponse,
+            crate::api::resources::admin::dto::UpdateLogFilterInput,
+            crate::api::resources::admin::dto::LogFilterResponse,
+            crate::api::resources::admin::dto::ReloadConfigResponse,
+            crate::utils::LiveSettings,
+            crate::utils::RateLimitSettings,
+            crate::utils::MaintenanceSettings,
+            crate::api::resources::admin::dto::ScheduledJobResponse,
+            crate::api::resources::admin::dto::ScheduledJobsResponse,
+            crate::api::resources::admin::dto::UpdateJobScheduleInput,
+            crate::db::models::QueuedJob,
+            crate::db::models::DeadLetterJob,
+            crate::api::resources::admin::dto::UpdateEmailSenderInput,
+            crate::db::models::OrganizationEmailSender,
+            crate::api::resources::admin::dto::UpdateQuotasInput,
+            crate::api::resources::admin::dto::OrganizationQuotasResponse,
+            crate::api::resources::admin::dto::AddSsoDomainInput,
+            crate::db::models::OrganizationSsoDomain,
+            crate::api::resources::admin::dto::CreateScimTokenInput,
+            crate::api::resources::admin::dto::ScimTokenResponse,
+            crate::api::resources::admin::dto::IssuedScimTokenResponse,
+            crate::domain::scim::ScimUser,
+            crate::domain::scim::ScimUserInput,
+            crate::domain::scim::ScimName,
+            crate::domain::scim::ScimValue,
+            crate::domain::scim::ScimMeta,
+            crate::domain::scim::ScimGroup,
+            crate::domain::scim::ScimMember,
+            crate::domain::scim::ScimPatchRequest,
+            crate::domain::scim::ScimPatchOperation,
+            crate::domain::scim::ScimListResponse<crate::domain::scim::ScimUser>,
+            crate::domain::scim::ScimListResponse<crate::domain::scim::ScimGroup>,
+            crate::domain::scim::ScimError,
+            crate::domain::auth::Permission,
+            crate::api::resources::admin::dto::RolePermissionsResponse,
+            crate::api::resources::admin::dto::PermissionsResponse,
+            crate::api::resources::admin::dto::UpdateRolePermissionsInput,
+            crate::api::resources::dev::dto::MailboxResponse,
+            crate::db::models::Notification,
+            crate::api::resources::notification::dto::UnreadCountResponse,
+            crate::api::resources::notification::dto::MarkAllReadResponse,
+            crate::api::resources::report::dto::SaveReportInput,
+            crate::api::resources::report::dto::RunReportInput,
+            crate::api::resources::report::dto::ReportResponse,
+            crate::api::resources::report::dto::ScheduleReportInput,
+            crate::api::resources::report::dto::ReportScheduleResponse,
+            crate::api::resources::report::dto::ReportDeliveryResponse,
+            crate::domain::report::ReportDefinition,
+            crate::domain::report::ReportDataset,
+            crate::domain::report::ReportParameter,
+            crate::domain::report::ReportFilter,
+            crate::domain::report::FilterOp,
+            crate::domain::report::ReportColumn,
+            crate::domain::report::Aggregate,
+            crate::domain::report::ReportFormat,
+            crate::domain::report::ReportResult,
+            crate::api::resources::import::dto::UploadImportQuery,
+            crate::api::resources::import::dto::PreviewImportInput,
+            crate::api::resources::import::dto::CommitImportInput,
+            crate::api::resources::import::dto::ImportFieldResponse,
+            crate::api::resources::import::dto::ImportTargetResponse,
+            crate::api::resources::import::dto::ImportResponse,
+            crate::domain::import::ImportFieldKind,
+            crate::domain::import::ImportPreview,
+            crate::domain::import::ImportPreviewRow,
+            crate::domain::user::UserImportReport,
+            crate::domain::user::UserImportRow,
+            crate::domain::user::UserImportError,
+            crate::api::resources::erp::dto::SaveErpConnectionInput,
+            crate::api::resources::erp::dto::RunErpExportInput,
+            crate::api::resources::erp::dto::ErpSourceResponse,
+            crate::api::resources::erp::dto::ErpConnectionResponse,
+            crate::api::resources::erp::dto::ErpExportResponse,
+            crate::api::resources::billing::dto::PlanResponse,
+            crate::api::resources::billing::dto::CheckoutInput,
+            crate::api::resources::billing::dto::CheckoutResponse,
+            crate::api::resources::billing::dto::SubscriptionResponse,
+            crate::api::resources::sales::dto::TenderParcelInput,
+            crate::api::resources::sales::dto::CreateTenderInput,
+            crate::api::resources::sales::dto::CaptureBidInput,
+            crate::api::resources::sales::dto::AwardParcelInput,
+            crate::api::resources::sales::dto::TenderResponse,
+            crate::api::resources::sales::dto::TenderParcelResponse,
+            crate::api::resources::sales::dto::TenderDetailsResponse,
+            crate::api::resources::sales::dto::TenderBidResponse,
+            crate::api::resources::sales::dto::SaleContractResponse,
+            crate::api::resources::customer::dto::SaveCustomerInput,
+            crate::api::resources::customer::dto::SaveSupplyContractInput,
+            crate::api::resources::customer::dto::CustomerResponse,
+            crate::api::resources::customer::dto::SupplyContractResponse,
+            crate::api::resources::customer::dto::ContractCommitmentResponse,
+            crate::domain::customer::CommitmentProgress,
+            crate::api::resources::approval::dto::SignBlockInput,
+            crate::api::resources::approval::dto::BlockSignoffResponse,
+            crate::api::resources::approval::dto::ApprovalStatusResponse,
+            crate::api::resources::document::dto::UploadDocumentQuery,
+            crate::api::resources::document::dto::UploadVersionQuery,
+            crate::api::resources::document::dto::ListDocumentsQuery,
+            crate::api::resources::document::dto::DocumentChecklistQuery,
+            crate::api::resources::document::dto::DocumentResponse,
+            crate::api::resources::document::dto::DocumentVersionResponse,
+            crate::api::resources::document::dto::DocumentDetailsResponse,
+            crate::domain::document::ChecklistItem,
+            crate::api::resources::tag::dto::SaveTagInput,
+            crate::api::resources::tag::dto::TagFilterQuery,
+            crate::api::resources::tag::dto::TagResponse,
+            crate::api::resources::tag::dto::TaggingResponse,
+            crate::api::resources::tag::dto::TaggedSubjectsResponse,
+            crate::db::models::Boundary,
+            crate::api::resources::block::dto::SaveHarvestBlockInput,
+            crate::api::resources::block::dto::ListHarvestBlocksQuery,
+            crate::api::resources::block::dto::HarvestBlockResponse,
+            crate::api::resources::block::dto::BlockOperabilityQuery,
+            crate::api::resources::block::dto::BlockOperabilityResponse,
+            crate::domain::operability::SoilSensitivity,
+            crate::domain::operability::OperabilityStatus,
+            crate::db::models::SpeciesShare,
+            crate::domain::windthrow::StandConditions,
+            crate::domain::windthrow::SoilRooting,
+            crate::domain::windthrow::Exposure,
+            crate::domain::windthrow::RiskClass,
+            crate::api::resources::stand::dto::SaveStandInput,
+            crate::api::resources::stand::dto::BulkUpsertStandsInput,
+            crate::api::resources::stand::dto::StandResponse,
+            crate::api::resources::stand::dto::BulkUpsertStandsResponse,
+            crate::api::resources::stand::dto::SpeciesVolumeResponse,
+            crate::api::resources::stand::dto::BlockVolumeSummaryResponse,
+            crate::api::resources::telemetry::dto::TelemetryPointInput,
+            crate::api::resources::telemetry::dto::TelemetryBatchInput,
+            crate::api::resources::telemetry::dto::TelemetryBatchResponse,
+            crate::api::resources::telemetry::dto::CreateTelemetryKeyInput,
+            crate::api::resources::telemetry::dto::TelemetryKeyResponse,
+            crate::api::resources::telemetry::dto::IssuedTelemetryKeyResponse,
+            crate::api::resources::crew::dto::CreateCrewInput,
+            crate::api::resources::crew::dto::UpdateCrewInput,
+            crate::api::resources::crew::dto::AddCrewMemberInput,
+            crate::api::resources::crew::dto::ListCrewsQuery,
+            crate::api::resources::crew::dto::CrewMemberResponse,
+            crate::api::resources::crew::dto::CrewResponse,
+            crate::api::resources::certification::dto::SaveCertificationInput,
+            crate::api::resources::certification::dto::ListCertificationsQuery,
+            crate::api::resources::certification::dto::ExpiringCertificationsQuery,
+            crate::api::resources::certification::dto::CertificationResponse,
+            crate::api::resources::certification::dto::CertificationDetailsResponse,
+            crate::api::resources::certification::dto::ExpiringCertificationResponse,
+            crate::api::resources::presence::dto::HeartbeatInput,
+            crate::api::resources::presence::dto::OnlineQuery,
+            crate::api::resources::presence::dto::OnlineDeviceResponse,
+            crate::api::resources::view::dto::SaveViewInput,
+            crate::api::resources::view::dto::ListViewsQuery,
+            crate::api::resources::view::dto::SavedViewResponse,
+            crate::api::resources::history::dto::FieldChangeResponse,
+            crate::api::resources::search::dto::QuickSearchQuery,
+            crate::api::resources::search::dto::QuickSearchResponse,
+            crate::domain::search::SearchHit,
+            crate::domain::search::SearchKind,
+            crate::domain::erp::ExportColumn,
+            crate::infrastructure::email::ReceivedEmail,
+            crate::infrastructure::email::EmailMessage,
+            crate::infrastructure::email::EmailAttachment,
             crate::api::utils::PaginationParams,
+            crate::api::utils::pagination::PaginationMeta,
+            crate::db::models::Organization,
             crate::api::utils::PaginatedResponse<crate::api::resources::organization::dto::OrganizationResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::organization::dto::OrganizationActivityResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::user::dto::AuthEventResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::user::dto::ActivityResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::admin::dto::ArchiveResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::admin::dto::LegalHoldResponse>,
+            crate::api::utils::PaginatedResponse<crate::db::models::QueuedJob>,
+            crate::api::utils::PaginatedResponse<crate::db::models::DeadLetterJob>,
+            crate::api::utils::PaginatedResponse<crate::db::models::Notification>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::report::dto::ReportResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::report::dto::ReportDeliveryResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::import::dto::ImportResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::erp::dto::ErpExportResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::sales::dto::TenderResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::sales::dto::SaleContractResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::customer::dto::CustomerResponse>,
+            crate::api::utils::PaginatedResponse<crate::api::resources::block::dto::HarvestBlockResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::import::dto::ImportTargetResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::auth::dto::UserResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::organization::dto::OrganizationNodeResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::invitation::dto::InvitationResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::erp::dto::ErpSourceResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::erp::dto::ErpConnectionResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::billing::dto::PlanResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::sales::dto::TenderBidResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::customer::dto::SupplyContractResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::customer::dto::ContractCommitmentResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::history::dto::FieldChangeResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::document::dto::DocumentResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::stand::dto::StandResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::telemetry::dto::TelemetryKeyResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::crew::dto::CrewResponse>,
+            crate::api::utils::ListResponse<crate::domain::document::ChecklistItem>,
+            crate::api::utils::ListResponse<crate::api::resources::role::dto::RoleResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::tag::dto::TagResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::tag::dto::TaggingResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::certification::dto::CertificationResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::certification::dto::ExpiringCertificationResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::presence::dto::OnlineDeviceResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::view::dto::SavedViewResponse>,
+            crate::api::utils::ListResponse<crate::db::models::OrganizationSsoDomain>,
+            crate::api::utils::ListResponse<crate::api::resources::auth::dto::DeviceResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::auth::dto::PasskeyResponse>,
+            crate::api::utils::ListResponse<crate::api::resources::admin::dto::ScimTokenResponse>,
             crate::api::utils::ApiResponse<crate::api::resources::organization::dto::OrganizationResponse>,
             crate::api::utils::ErrorResponse
         )
     ),
+    modifiers(&SecurityAddon),
     tags(
         (name = "health", description = "Health check endpoints"),
         (name = "auth", description = "Authentication endpoints"),
-        (name = "organizations", description = "Organization management endpoints")
+        (name = "users", description = "The current user and, for admins, any user"),
+        (name = "invitations", description = "Invitations to join the caller's organization"),
+        (name = "roles", description = "Custom roles of the caller's organization and who holds them"),
+        (name = "organizations", description = "Organization management endpoints"),
+        (name = "notifications", description = "Notification center of the current user"),
+        (name = "reports", description = "Saved reports over the organization's data and their scheduled delivery by email"),
+        (name = "imports", description = "Guided CSV and Excel imports with column mapping and row validation"),
+        (name = "erp", description = "Exports of invoices and delivered volumes to the organization's ERP"),
+        (name = "billing", description = "Plans and Stripe subscriptions"),
+        (name = "sales", description = "Timber sale tenders, sealed bids and sale contracts"),
+        (name = "customers", description = "Customers, their supply contracts and delivery commitments"),
+        (name = "approvals", description = "Sign-off chain approving harvest block packages"),
+        (name = "documents", description = "Versioned documents attached to blocks, permits and certifications, checklists and retention"),
+        (name = "tags", description = "Organization-defined tags on stands, blocks, equipment and work orders"),
+        (name = "blocks", description = "Harvest blocks with their boundaries and status"),
+        (name = "stands", description = "Stand inventory of harvest blocks and their volume roll-ups"),
+        (name = "telemetry", description = "Machine telemetry uploads and the API keys they are made with"),
+        (name = "crews", description = "Crews of an organization's users, their supervisor and default equipment"),
+        (name = "certifications", description = "Certifications held by operators and their coming expiries"),
+        (name = "presence", description = "Heartbeats from field devices and who is online"),
+        (name = "views", description = "Saved filter, sort and column configurations of list endpoints"),
+        (name = "search", description = "Quick search across record types"),
+        (name = "scim", description = "SCIM 2.0 provisioning of an organization's users by its identity provider"),
+        (name = "admin", description = "Administrative maintenance endpoints"),
+        (name = "dev", description = "Development helpers, disabled outside development")
     )
 )]
 pub struct ApiDoc;
 
-pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        SwaggerUi::new("/swagger-ui/{_:.*}")
-            .url("/api-docs/openapi.json", ApiDoc::openapi())
-            .config(utoipa_swagger_ui::Config::new(["/api-docs/openapi.json"]))
-    );
+/// Registers the schemes referenced by `security(("bearer_auth" = []))`,
+/// `security(("scim_token" = []))` and `security(("telemetry_key" = []))`
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some("Access token from `POST /v1/auth/login`"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "scim_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("Provisioning token from `POST /v1/admin/organizations/{id}/scim-tokens`"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "telemetry_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                API_KEY_HEADER,
+                "Telemetry API key from `POST /v1/telemetry/keys`",
+            ))),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::path::Path;
+    use utoipa::openapi::RefOr;
+
+    /// Routes registered in every `<resource>/routes.rs`, as (method, path)
+    ///
+    /// Read from the source, since actix-web cannot list its routes. Routes
+    /// are expected in the form used throughout `resources`: one
+    /// `web::scope(..)` per file with `.route(path, web::method()..)` calls.
+    fn mounted_routes() -> Vec<(String, String)> {
+        let scope = Regex::new(r#"web::scope\("([^"]*)"\)"#).unwrap();
+        let route = Regex::new(r#"\.route\(\s*"([^"]*)",\s*web::(\w+)\(\)"#).unwrap();
+        let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/api/resources");
+
+        let mut routes = Vec::new();
+        for entry in std::fs::read_dir(resources).unwrap() {
+            let dir = entry.unwrap().path();
+            // Swagger UI and the document itself are not part of the API
+            if dir.file_name().is_some_and(|name| name == "docs") {
+                continue;
+            }
+            let Ok(source) = std::fs::read_to_string(dir.join("routes.rs")) else {
+                continue;
+            };
+            // SCIM is mounted beside `/v1`, at the path identity providers expect
+            let version = if dir.file_name().is_some_and(|name| name == "scim") { "" } else { "/v1" };
+            let mut prefix = String::new();
+            for line in source.lines() {
+                if let Some(captures) = scope.captures(line) {
+                    prefix = captures[1].to_string();
+                }
+                // Routes added to the parent config sit outside the scope
+                let prefix = if line.contains("cfg.route(") { "" } else { prefix.as_str() };
+                for captures in route.captures_iter(line) {
+                    routes.push((captures[2].to_string(), format!("{}{}{}", version, prefix, &captures[1])));
+                }
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn test_every_mounted_route_is_documented() {
+        let openapi = ApiDoc::openapi();
+        let routes = mounted_routes();
+        assert!(routes.len() > 10, "no routes found, has the routes.rs layout changed?");
+
+        let missing: Vec<String> = routes
+            .iter()
+            .filter(|(method, path)| {
+                let item = openapi.paths.paths.get(path);
+                let operation = item.and_then(|item| match method.as_str() {
+                    "get" => item.get.as_ref(),
+                    "post" => item.post.as_ref(),
+                    "put" => item.put.as_ref(),
+                    "patch" => item.patch.as_ref(),
+                    "delete" => item.delete.as_ref(),
+                    _ => None,
+                });
+                operation.is_none()
+            })
+            .map(|(method, path)| format!("{} {}", method.to_uppercase(), path))
+            .collect();
+        assert!(missing.is_empty(), "routes missing from ApiDoc: {:?}", missing);
+    }
+
+    #[test]
+    fn test_error_responses_have_a_schema() {
+        let openapi = ApiDoc::openapi();
+        let mut missing = Vec::new();
+        for (path, item) in &openapi.paths.paths {
+            let operations = [&item.get, &item.post, &item.put, &item.patch, &item.delete];
+            for operation in operations.into_iter().flatten() {
+                for (status, response) in &operation.responses.responses {
+                    let RefOr::T(response) = response else {
+                        continue;
+                    };
+                    if !status.starts_with('2') && response.content.is_empty() {
+                        missing.push(format!("{} {}", path, status));
+                    }
+                }
+            }
+        }
+        assert!(missing.is_empty(), "error responses without a body: {:?}", missing);
+    }
 } 
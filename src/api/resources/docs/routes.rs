@@ -1,12 +1,31 @@
-use actix_web::web;
+use actix_web::{http::header, web, HttpResponse};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 use super::openapi::ApiDoc;
+use crate::api::middleware::DocsAccess;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
-        SwaggerUi::new("/swagger-ui/{_:.*}")
-            .url("/api-docs/openapi.json", ApiDoc::openapi())
-            .config(utoipa_swagger_ui::Config::new(["/api-docs/openapi.json"]))
+        web::scope("/docs")
+            .wrap(DocsAccess::new())
+            .route("/openapi.json", web::get().to(openapi_json))
+            // Trailing slashes are trimmed before routing, so the UI's own
+            // index redirect never matches
+            .route("/swagger-ui", web::get().to(swagger_ui_index))
+            .service(
+                SwaggerUi::new("/swagger-ui/{_:.*}")
+                    .config(utoipa_swagger_ui::Config::new(["/v1/docs/openapi.json"]))
+            )
     );
-}
\ No newline at end of file
+}
+
+/// The OpenAPI document, for tooling and CI
+async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
+
+async fn swagger_ui_index() -> HttpResponse {
+    HttpResponse::Found()
+        .insert_header((header::LOCATION, "/v1/docs/swagger-ui/index.html"))
+        .finish()
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    db::models::{Document, DocumentSubject, DocumentVersion},
+    domain::document::NewDocument,
+    error::{ApiError, Result},
+};
+
+fn subject(value: &str) -> Result<DocumentSubject> {
+    DocumentSubject::parse(value).ok_or_else(|| ApiError::validation(format!("Unknown document subject {}", value), None))
+}
+
+/// Query parameters for uploading a new document
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct UploadDocumentQuery {
+    /// `block`, `permit` or `certification`
+    pub subject_type: String,
+    pub subject_id: Uuid,
+    /// Kind of document, e.g. `harvest_permit`
+    pub category: String,
+    /// Title shown for the document, the file name when unset
+    pub title: Option<String>,
+    pub filename: String,
+}
+
+impl UploadDocumentQuery {
+    /// What the uploaded document is and what it is attached to
+    pub fn document(&self) -> Result<NewDocument> {
+        Ok(NewDocument {
+            subject: subject(&self.subject_type)?,
+            subject_id: self.subject_id,
+            category: self.category.clone(),
+            title: self.title.clone(),
+        })
+    }
+}
+
+/// Query parameters for uploading a new version of a document
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct UploadVersionQuery {
+    pub filename: String,
+}
+
+/// Query parameters for listing documents
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListDocumentsQuery {
+    /// `block`, `permit` or `certification`
+    pub subject_type: String,
+    pub subject_id: Uuid,
+}
+
+impl ListDocumentsQuery {
+    /// The subject documents are listed for
+    pub fn subject(&self) -> Result<DocumentSubject> {
+        subject(&self.subject_type)
+    }
+}
+
+/// Query parameters for a block's document checklist
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct DocumentChecklistQuery {
+    pub block_id: Uuid,
+    /// Block status to check against: `planned`, `approved`, `active` or
+    /// `completed`
+    pub status: String,
+}
+
+/// Document response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct DocumentResponse {
+    pub id: Uuid,
+    pub subject_type: String,
+    pub subject_id: Uuid,
+    pub category: String,
+    pub title: String,
+    pub current_version: i32,
+    /// The document cannot be deleted before this
+    pub retain_until: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Document> for DocumentResponse {
+    fn from(document: Document) -> Self {
+        Self {
+            id: document.id,
+            subject_type: document.subject_type,
+            subject_id: document.subject_id,
+            category: document.category,
+            title: document.title,
+            current_version: document.current_version,
+            retain_until: document.retain_until,
+            created_by: document.created_by,
+            created_at: document.created_at,
+            updated_at: document.updated_at,
+        }
+    }
+}
+
+/// A version of a document
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct DocumentVersionResponse {
+    pub version: i32,
+    pub filename: String,
+    pub content_type: String,
+    #[ts(type = "number")]
+    pub byte_size: i64,
+    /// Version whose file this one restores
+    pub restored_from: Option<i32>,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DocumentVersion> for DocumentVersionResponse {
+    fn from(version: DocumentVersion) -> Self {
+        Self {
+            version: version.version,
+            filename: version.filename,
+            content_type: version.content_type,
+            byte_size: version.byte_size,
+            restored_from: version.restored_from,
+            uploaded_by: version.uploaded_by,
+            created_at: version.created_at,
+        }
+    }
+}
+
+/// A document with its versions, latest first
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct DocumentDetailsResponse {
+    pub document: DocumentResponse,
+    pub versions: Vec<DocumentVersionResponse>,
+}
+
+impl DocumentDetailsResponse {
+    pub fn new(document: Document, versions: Vec<DocumentVersion>) -> Self {
+        Self {
+            document: DocumentResponse::from(document),
+            versions: versions.into_iter().map(DocumentVersionResponse::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Document resource handlers
+//!
+//! Every handler works on the documents of the authenticated user's
+//! organization. Uploads take the file itself as the body, with its media
+//! type in `Content-Type`.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::document::dto::{
+            DocumentChecklistQuery, DocumentDetailsResponse, DocumentResponse, ListDocumentsQuery, UploadDocumentQuery,
+            UploadVersionQuery,
+        },
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{get_connection, repositories::DocumentRepositoryImpl, DbPool},
+    domain::document::{ChecklistItem, DocumentService, DocumentUpload},
+    error::ApiError,
+    utils::Config,
+};
+use actix_web::{
+    http::header::{self, ContentDisposition},
+    web, HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+fn service(config: &Config) -> DocumentService<DocumentRepositoryImpl> {
+    DocumentService::new(DocumentRepositoryImpl, config.storage().clone())
+}
+
+fn upload(req: &HttpRequest, filename: &str, bytes: web::Bytes) -> DocumentUpload {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= 255)
+        .unwrap_or("application/octet-stream");
+    DocumentUpload {
+        filename: filename.to_string(),
+        content_type: content_type.to_string(),
+        bytes,
+    }
+}
+
+/// Lists the documents attached to a block or permit
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/documents",
+    security(("bearer_auth" = [])),
+    tag = "documents",
+    responses(
+        (status = 200, description = "Documents", body = ListResponse<DocumentResponse>),
+        (status = 400, description = "Unknown subject type", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("subject_type" = String, Query, description = "`block`, `permit` or `certification`"),
+        ("subject_id" = Uuid, Query, description = "Block, permit or certification ID")
+    )
+)]
+pub async fn list_documents(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    query: web::Query<ListDocumentsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let subject = query.subject()?;
+    let mut conn = get_connection(&pool)?;
+    let documents = service(&config).list(&mut conn, org_id, subject, query.subject_id).await?;
+    let documents = documents.into_iter().map(DocumentResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Documents retrieved successfully")
+            .with_data(documents)
+            .build()
+    ))
+}
+
+/// Attaches a new document to a block or permit
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/documents",
+    security(("bearer_auth" = [])),
+    tag = "documents",
+    request_body(content = Vec<u8>, content_type = "application/octet-stream", description = "The document file"),
+    responses(
+        (status = 201, description = "Document uploaded", body = DocumentDetailsResponse),
+        (status = 400, description = "Invalid subject, category or file", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 413, description = "File too large", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("subject_type" = String, Query, description = "`block`, `permit` or `certification`"),
+        ("subject_id" = Uuid, Query, description = "Block, permit or certification ID"),
+        ("category" = String, Query, description = "Kind of document, e.g. `harvest_permit`"),
+        ("title" = Option<String>, Query, description = "Title, the file name when unset"),
+        ("filename" = String, Query, description = "Name of the file")
+    )
+)]
+pub async fn upload_document(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    query: web::Query<UploadDocumentQuery>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+    let document = query.document()?;
+    let mut conn = get_connection(&pool)?;
+    let (document, version) = service(&config)
+        .upload(&mut conn, org_id, created_by, document, upload(&req, &query.filename, body))
+        .await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Document uploaded successfully")
+            .with_data(DocumentDetailsResponse::new(document, vec![version]))
+            .build()
+    ))
+}
+
+/// Retrieves a document with its version history
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/documents/{id}",
+    security(("bearer_auth" = [])),
+    tag = "documents",
+    responses(
+        (status = 200, description = "Document", body = DocumentDetailsResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "Document not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Document ID")
+    )
+)]
+pub async fn get_document(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    document_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let (document, versions) = service(&config).get(&mut conn, org_id, *document_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Document retrieved successfully")
+            .with_data(DocumentDetailsResponse::new(document, versions))
+            .build()
+    ))
+}
+
+/// Deletes a document with all its versions
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/documents/{id}",
+    security(("bearer_auth" = [])),
+    tag = "documents",
+    responses(
+        (status = 204, description = "Document deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "Document not found", body = ErrorResponse),
+        (status = 409, description = "The document is under regulatory retention", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Document ID")
+    )
+)]
+pub async fn delete_document(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    document_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    service(&config).delete(&mut conn, org_id, *document_id, Utc::now()).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Uploads a new version of a document
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/documents/{id}/versions",
+    security(("bearer_auth" = [])),
+    tag = "documents",
+    request_body(content = Vec<u8>, content_type = "application/octet-stream", description = "The document file"),
+    responses(
+        (status = 201, description = "Version uploaded", body = DocumentDetailsResponse),
+        (status = 400, description = "Invalid file", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "Document not found", body = ErrorResponse),
+        (status = 413, description = "File too large", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Document ID"),
+        ("filename" = String, Query, description = "Name of the file")
+    )
+)]
+pub async fn upload_document_version(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    document_id: web::Path<Uuid>,
+    query: web::Query<UploadVersionQuery>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let Tenant(org_id) = Tenant::from_claims(user.claims())?;
+    let uploaded_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let (document, version) = service(&config)
+        .add_version(&mut conn, org_id, *document_id, uploaded_by, upload(&req, &query.filename, body))
+        .await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Version uploaded successfully")
+            .with_data(DocumentDetailsResponse::new(document, vec![version]))
+            .build()
+    ))
+}
+
+/// Downloads the file of a version of a document
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/documents/{id}/versions/{version}/file",
+    security(("bearer_auth" = [])),
+    tag = "documents",
+    responses(
+        (status = 200, description = "The file, with the media type it was uploaded with", body = Vec<u8>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "Document or version not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Document ID"),
+        ("version" = i32, Path, description = "Version number")
+    )
+)]
+pub async fn download_document_version(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<(Uuid, i32)>,
+) -> Result<HttpResponse, ApiError> {
+    let (document_id, version) = path.into_inner();
+    let mut conn = get_connection(&pool)?;
+    let (version, file) = service(&config).file(&mut conn, org_id, document_id, version).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(version.content_type)
+        .insert_header(ContentDisposition::attachment(version.filename))
+        .body(file))
+}
+
+/// Restores an older version of a document as its latest
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/documents/{id}/versions/{version}/restore",
+    security(("bearer_auth" = [])),
+    tag = "documents",
+    responses(
+        (status = 201, description = "Version restored", body = DocumentDetailsResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "Document or version not found", body = ErrorResponse),
+        (status = 409, description = "The version is already the latest", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Document ID"),
+        ("version" = i32, Path, description = "Version number to restore")
+    )
+)]
+pub async fn restore_document_version(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<(Uuid, i32)>,
+) -> Result<HttpResponse, ApiError> {
+    let restored_by = Uuid::parse_str(user.user_id()).ok();
+    let (document_id, version) = path.into_inner();
+    let mut conn = get_connection(&pool)?;
+    let (document, version) = service(&config)
+        .restore(&mut conn, org_id, document_id, version, restored_by)
+        .await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Version restored successfully")
+            .with_data(DocumentDetailsResponse::new(document, vec![version]))
+            .build()
+    ))
+}
+
+/// Lists the document categories a block needs on file for a status and
+/// whether each is present
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/documents/checklist",
+    security(("bearer_auth" = [])),
+    tag = "documents",
+    responses(
+        (status = 200, description = "Document checklist", body = ListResponse<ChecklistItem>),
+        (status = 400, description = "Status without a checklist", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Query, description = "Harvest block ID"),
+        ("status" = String, Query, description = "`planned`, `approved`, `active` or `completed`")
+    )
+)]
+pub async fn get_document_checklist(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    query: web::Query<DocumentChecklistQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let checklist = service(&config).checklist(&mut conn, org_id, query.block_id, &query.status).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Document checklist retrieved successfully")
+            .with_data(ListResponse::new(checklist))
+            .build()
+    ))
+}
@@ -0,0 +1,22 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequireAuth},
+    domain::document::MAX_DOCUMENT_BYTES,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/documents")
+            .wrap(RequireAuth)
+            .wrap(Auth::new())
+            .app_data(web::PayloadConfig::new(MAX_DOCUMENT_BYTES))
+            .route("", web::get().to(crate::api::resources::document::handlers::list_documents))
+            .route("", web::post().to(crate::api::resources::document::handlers::upload_document))
+            .route("/checklist", web::get().to(crate::api::resources::document::handlers::get_document_checklist))
+            .route("/{id}", web::get().to(crate::api::resources::document::handlers::get_document))
+            .route("/{id}", web::delete().to(crate::api::resources::document::handlers::delete_document))
+            .route("/{id}/versions", web::post().to(crate::api::resources::document::handlers::upload_document_version))
+            .route("/{id}/versions/{version}/file", web::get().to(crate::api::resources::document::handlers::download_document_version))
+            .route("/{id}/versions/{version}/restore", web::post().to(crate::api::resources::document::handlers::restore_document_version))
+    );
+}
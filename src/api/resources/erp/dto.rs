@@ -0,0 +1,150 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    db::models::{ErpConnection, ErpExport},
+    domain::erp::{ExportMapping, ExportSource},
+};
+
+/// Input for creating an ERP connection, also used to replace one
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct SaveErpConnectionInput {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    /// `csv_download` or `csv_sftp`
+    pub connector: String,
+    /// Connector settings; `csv_sftp` takes `host`, `port`, `username`,
+    /// `directory` and the server's `host_key_sha256` fingerprint
+    #[serde(default)]
+    #[ts(type = "Record<string, unknown>")]
+    pub settings: serde_json::Value,
+    /// Columns exported per source; an empty list exports every field
+    pub mappings: ExportMapping,
+    /// Cron expression (`sec min hour day month weekday`, UTC) exports run
+    /// on; exports run on request only when unset
+    #[serde(default)]
+    #[validate(length(max = 255))]
+    pub schedule: Option<String>,
+    /// Whether scheduled exports run, true when unset
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+/// Input for exporting a source through a connection now
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct RunErpExportInput {
+    pub source: String,
+}
+
+/// Query parameters for listing exports
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListErpExportsQuery {
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// Something that can be exported to an ERP
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ErpSourceResponse {
+    pub name: String,
+    /// Fields of every record, in their default column order
+    pub fields: Vec<String>,
+}
+
+impl From<&dyn ExportSource> for ErpSourceResponse {
+    fn from(source: &dyn ExportSource) -> Self {
+        Self {
+            name: source.name().to_string(),
+            fields: source.fields().iter().map(|field| field.to_string()).collect(),
+        }
+    }
+}
+
+/// ERP connection response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ErpConnectionResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub connector: String,
+    #[ts(type = "Record<string, unknown>")]
+    pub settings: serde_json::Value,
+    pub mappings: ExportMapping,
+    pub schedule: Option<String>,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub enabled: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ErpConnection> for ErpConnectionResponse {
+    fn from(connection: ErpConnection) -> Self {
+        Self {
+            id: connection.id,
+            mappings: serde_json::from_value(connection.mappings).unwrap_or_default(),
+            name: connection.name,
+            connector: connection.connector,
+            settings: connection.settings,
+            schedule: connection.schedule,
+            next_run_at: connection.next_run_at,
+            enabled: connection.enabled,
+            created_by: connection.created_by,
+            created_at: connection.created_at,
+            updated_at: connection.updated_at,
+        }
+    }
+}
+
+/// One exported file and the status of its delivery
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ErpExportResponse {
+    pub id: Uuid,
+    pub connection_id: Uuid,
+    pub source: String,
+    /// `pending` until delivered; `failed` while retries are pending after a
+    /// failed attempt
+    pub status: String,
+    /// Records changed after this time, when set, up to `period_end` are
+    /// included
+    pub period_start: Option<DateTime<Utc>>,
+    pub period_end: DateTime<Utc>,
+    pub record_count: i32,
+    /// Where the file was delivered
+    pub remote_ref: Option<String>,
+    pub attempts: i32,
+    /// Why the last attempt failed
+    pub error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ErpExport> for ErpExportResponse {
+    fn from(export: ErpExport) -> Self {
+        Self {
+            id: export.id,
+            connection_id: export.connection_id,
+            source: export.source,
+            status: export.status,
+            period_start: export.period_start,
+            period_end: export.period_end,
+            record_count: export.record_count,
+            remote_ref: export.remote_ref,
+            attempts: export.attempts,
+            error: export.error,
+            delivered_at: export.delivered_at,
+            created_at: export.created_at,
+        }
+    }
+}
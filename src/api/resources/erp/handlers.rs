@@ -0,0 +1,385 @@
+//! ERP export resource handlers
+//!
+//! Every handler works on the ERP connections and exports of the
+//! authenticated user's organization. Routes require the manager role.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::erp::dto::{
+            ErpConnectionResponse, ErpExportResponse, ErpSourceResponse, ListErpExportsQuery, RunErpExportInput,
+            SaveErpConnectionInput,
+        },
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse, PaginatedResponse, PaginationParams},
+    },
+    db::{get_connection, repositories::ErpRepositoryImpl, DbPool},
+    domain::erp::{connectors, export_filename, ErpService},
+    error::ApiError,
+    utils::Config,
+};
+use actix_web::{http::header::ContentDisposition, web, HttpResponse};
+use uuid::Uuid;
+
+fn service(config: &Config) -> ErpService<ErpRepositoryImpl> {
+    ErpService::new(ErpRepositoryImpl, config.storage().clone(), connectors(&config.erp))
+}
+
+/// Lists the sources that can be exported and their fields
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/erp/sources",
+    security(("bearer_auth" = [])),
+    tag = "erp",
+    responses(
+        (status = 200, description = "Export sources", body = ListResponse<ErpSourceResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse)
+    )
+)]
+pub async fn list_erp_sources(_user: AuthenticatedUser, config: web::Data<Config>) -> Result<HttpResponse, ApiError> {
+    let service = service(&config);
+    let sources = service
+        .sources()
+        .iter()
+        .map(|source| ErpSourceResponse::from(source.as_ref()))
+        .collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Export sources retrieved successfully")
+            .with_data(sources)
+            .build()
+    ))
+}
+
+/// Lists the connectors ERP connections can use
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/erp/connectors",
+    security(("bearer_auth" = [])),
+    tag = "erp",
+    responses(
+        (status = 200, description = "Connector names", body = ListResponse<String>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse)
+    )
+)]
+pub async fn list_erp_connectors(_user: AuthenticatedUser, config: web::Data<Config>) -> Result<HttpResponse, ApiError> {
+    let service = service(&config);
+    let connectors = service.connectors().iter().map(|connector| connector.kind()).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Connectors retrieved successfully")
+            .with_data(connectors)
+            .build()
+    ))
+}
+
+/// Lists the organization's ERP connections by name
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/erp/connections",
+    security(("bearer_auth" = [])),
+    tag = "erp",
+    responses(
+        (status = 200, description = "ERP connections", body = ListResponse<ErpConnectionResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn list_erp_connections(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let connections = service(&config).list_connections(&mut conn, org_id).await?;
+    let connections = connections.into_iter().map(ErpConnectionResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("ERP connections retrieved successfully")
+            .with_data(connections)
+            .build()
+    ))
+}
+
+/// Creates an ERP connection
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/erp/connections",
+    security(("bearer_auth" = [])),
+    tag = "erp",
+    request_body = SaveErpConnectionInput,
+    responses(
+        (status = 201, description = "ERP connection created", body = ErpConnectionResponse),
+        (status = 400, description = "Invalid connector, settings, mapping or schedule", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 409, description = "A connection with this name already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_erp_connection(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    input: web::Json<SaveErpConnectionInput>,
+) -> Result<HttpResponse, ApiError> {
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let connection = service(&config)
+        .create_connection(&mut conn, org_id, created_by, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("ERP connection created successfully")
+            .with_data(ErpConnectionResponse::from(connection))
+            .build()
+    ))
+}
+
+/// Retrieves an ERP connection
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/erp/connections/{id}",
+    security(("bearer_auth" = [])),
+    tag = "erp",
+    responses(
+        (status = 200, description = "ERP connection", body = ErpConnectionResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "ERP connection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "ERP connection ID")
+    )
+)]
+pub async fn get_erp_connection(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    connection_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let connection = service(&config).get_connection(&mut conn, org_id, *connection_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("ERP connection retrieved successfully")
+            .with_data(ErpConnectionResponse::from(connection))
+            .build()
+    ))
+}
+
+/// Replaces an ERP connection; its export history is kept
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/erp/connections/{id}",
+    security(("bearer_auth" = [])),
+    tag = "erp",
+    request_body = SaveErpConnectionInput,
+    responses(
+        (status = 200, description = "ERP connection updated", body = ErpConnectionResponse),
+        (status = 400, description = "Invalid connector, settings, mapping or schedule", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "ERP connection not found", body = ErrorResponse),
+        (status = 409, description = "A connection with this name already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "ERP connection ID")
+    )
+)]
+pub async fn update_erp_connection(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    connection_id: web::Path<Uuid>,
+    input: web::Json<SaveErpConnectionInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let connection = service(&config)
+        .update_connection(&mut conn, org_id, *connection_id, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("ERP connection updated successfully")
+            .with_data(ErpConnectionResponse::from(connection))
+            .build()
+    ))
+}
+
+/// Deletes an ERP connection with its export history
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/erp/connections/{id}",
+    security(("bearer_auth" = [])),
+    tag = "erp",
+    responses(
+        (status = 204, description = "ERP connection deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "ERP connection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "ERP connection ID")
+    )
+)]
+pub async fn delete_erp_connection(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    connection_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    service(&config).delete_connection(&mut conn, org_id, *connection_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Exports a source through a connection now
+///
+/// The file covers the source's records since the connection's previous
+/// export of it and is delivered in the background; poll the connection's
+/// exports for its status.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/erp/connections/{id}/exports",
+    security(("bearer_auth" = [])),
+    tag = "erp",
+    request_body = RunErpExportInput,
+    responses(
+        (status = 202, description = "Export written and queued for delivery", body = ErpExportResponse),
+        (status = 400, description = "Source not mapped by the connection", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "ERP connection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "ERP connection ID")
+    )
+)]
+pub async fn run_erp_export(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    connection_id: web::Path<Uuid>,
+    input: web::Json<RunErpExportInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let export = service(&config)
+        .export(&mut conn, &config.queue, org_id, *connection_id, &input.source)
+        .await?;
+
+    Ok(HttpResponse::Accepted().json(
+        ApiResponseBuilder::success()
+            .with_message("Export queued successfully")
+            .with_data(ErpExportResponse::from(export))
+            .build()
+    ))
+}
+
+/// Lists the exports of a connection with their delivery status, newest
+/// first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/erp/connections/{id}/exports",
+    security(("bearer_auth" = [])),
+    tag = "erp",
+    responses(
+        (status = 200, description = "Exports", body = PaginatedResponse<ErpExportResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "ERP connection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "ERP connection ID"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page")
+    )
+)]
+pub async fn list_erp_exports(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    connection_id: web::Path<Uuid>,
+    query: web::Query<ListErpExportsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+    let mut conn = get_connection(&pool)?;
+    let (exports, total) = service(&config).exports(&mut conn, org_id, *connection_id, &pagination).await?;
+    let exports = exports.into_iter().map(ErpExportResponse::from).collect();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Exports retrieved successfully")
+            .with_data(PaginatedResponse::with_count(exports, total, &pagination))
+            .build()
+    ))
+}
+
+/// Downloads an exported file
+///
+/// This is how `csv_download` connections deliver; files of other
+/// connectors can be downloaded too.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/erp/exports/{id}/file",
+    security(("bearer_auth" = [])),
+    tag = "erp",
+    responses(
+        (status = 200, description = "Exported CSV", content_type = "text/csv", body = String),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Export not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Export ID")
+    )
+)]
+pub async fn download_erp_export(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    export_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let (export, file) = service(&config).export_file(&mut conn, org_id, *export_id).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(ContentDisposition::attachment(export_filename(&export)))
+        .body(file))
+}
@@ -0,0 +1,24 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequirePermission, RequireSubscription},
+    domain::auth::Permission,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/erp")
+            .wrap(RequireSubscription::new())
+            .wrap(RequirePermission::read_write(Permission::ErpRead, Permission::ErpWrite))
+            .wrap(Auth::new())
+            .route("/sources", web::get().to(crate::api::resources::erp::handlers::list_erp_sources))
+            .route("/connectors", web::get().to(crate::api::resources::erp::handlers::list_erp_connectors))
+            .route("/connections", web::get().to(crate::api::resources::erp::handlers::list_erp_connections))
+            .route("/connections", web::post().to(crate::api::resources::erp::handlers::create_erp_connection))
+            .route("/connections/{id}", web::get().to(crate::api::resources::erp::handlers::get_erp_connection))
+            .route("/connections/{id}", web::put().to(crate::api::resources::erp::handlers::update_erp_connection))
+            .route("/connections/{id}", web::delete().to(crate::api::resources::erp::handlers::delete_erp_connection))
+            .route("/connections/{id}/exports", web::get().to(crate::api::resources::erp::handlers::list_erp_exports))
+            .route("/connections/{id}/exports", web::post().to(crate::api::resources::erp::handlers::run_erp_export))
+            .route("/exports/{id}/file", web::get().to(crate::api::resources::erp::handlers::download_erp_export))
+    );
+}
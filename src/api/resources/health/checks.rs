@@ -0,0 +1,280 @@
+//! Dependency checks backing the readiness and startup probes
+//!
+//! Every check is bounded by [`CHECK_TIMEOUT`] so a hanging dependency
+//! reports DOWN instead of stalling the probe past the orchestrator's own
+//! timeout. A dependency answering slower than `health.degraded_latency_ms`
+//! reports DEGRADED.
+//!
+//! The database and migrations are critical: the service cannot serve
+//! traffic without them. Every other dependency only backs some features,
+//! which fail fast with `DEPENDENCY_UNAVAILABLE` while it is down (see
+//! [`crate::infrastructure::dependencies`]).
+
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use actix_web::{rt::task::JoinHandle, web};
+use diesel::prelude::*;
+use futures_util::future::join_all;
+use once_cell::sync::Lazy;
+use tracing::warn;
+
+use super::dto::{CheckStatus, DependencyCheck};
+use crate::{
+    db::{get_connection, migrations::pending_migrations, DbPool},
+    infrastructure::{dependencies, EventBus, Mailer, ObjectStorage, RedisClient},
+    jobs::shutdown::Shutdown,
+    utils::{Config, EmailTransport},
+};
+
+/// Details of a passing check, or the reason it failed
+type CheckResult = Result<Option<serde_json::Value>, String>;
+
+/// Upper bound for a single dependency check
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Client for HTTP dependency checks, redirects are not followed
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap_or_default()
+});
+
+/// Checks every dependency, recording each status in the config's
+/// [`DependencyMonitor`](crate::infrastructure::DependencyMonitor)
+pub async fn readiness_checks(config: &Config, pool: &DbPool) -> BTreeMap<String, DependencyCheck> {
+    let (database, migrations, redis, storage, email, event_bus, http) = futures_util::join!(
+        database(pool.clone()),
+        migrations(pool.clone()),
+        redis(config.redis().cloned()),
+        storage(config.storage().clone()),
+        email(config.mailer().clone(), config.email.transport()),
+        event_bus(config.event_bus().cloned()),
+        join_all(config.health.http_dependencies.iter().map(|(name, url)| async move {
+            (name.clone(), http(url.clone()).await)
+        })),
+    );
+
+    let mut checks = BTreeMap::from([
+        ("database".to_string(), database.critical()),
+        ("migrations".to_string(), migrations.critical()),
+        (dependencies::REDIS.to_string(), redis),
+        (dependencies::STORAGE.to_string(), storage),
+        (dependencies::EMAIL.to_string(), email),
+        (dependencies::EVENT_BUS.to_string(), event_bus),
+    ]);
+    checks.extend(http);
+
+    let degraded_after = Duration::from_millis(config.health.degraded_latency_ms);
+    for (name, check) in checks.iter_mut() {
+        grade(check, degraded_after);
+        config.dependencies().record(name, check.status);
+    }
+    checks
+}
+
+/// Runs the readiness checks every `health.check_interval_secs`, so
+/// features see a dependency go down without waiting for a probe
+pub fn spawn_monitor(config: Config, pool: DbPool, shutdown: Shutdown) -> Option<JoinHandle<()>> {
+    if config.health.check_interval_secs == 0 {
+        return None;
+    }
+    let interval = Duration::from_secs(config.health.check_interval_secs);
+    Some(actix_web::rt::spawn(async move {
+        let mut previous = BTreeMap::new();
+        loop {
+            let checks = readiness_checks(&config, &pool).await;
+            for (name, check) in &checks {
+                let changed = previous.get(name).is_some_and(|status| *status != check.status);
+                if changed || (previous.is_empty() && check.status == CheckStatus::Down) {
+                    warn!(dependency = %name, status = ?check.status, error = ?check.error, "Dependency status changed");
+                }
+            }
+            previous = checks.into_iter().map(|(name, check)| (name, check.status)).collect();
+
+            tokio::select! {
+                _ = actix_web::rt::time::sleep(interval) => {}
+                _ = shutdown.triggered() => break,
+            }
+        }
+    }))
+}
+
+/// Database reachable and answering queries
+pub async fn database(pool: DbPool) -> DependencyCheck {
+    timed(async move {
+        web::block(move || -> CheckResult {
+            let mut conn = get_connection(&pool).map_err(|e| e.message)?;
+            diesel::select(diesel::dsl::sql::<diesel::sql_types::Bool>("TRUE"))
+                .get_result::<bool>(&mut conn)
+                .map_err(|e| e.to_string())?;
+            let state = pool.state();
+            Ok(Some(serde_json::json!({
+                "connections": state.connections,
+                "idle_connections": state.idle_connections,
+                "max_connections": pool.max_size(),
+            })))
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    })
+    .await
+}
+
+/// All embedded migrations applied to the database
+pub async fn migrations(pool: DbPool) -> DependencyCheck {
+    timed(async move {
+        let pending = web::block(move || pending_migrations(&mut *get_connection(&pool)?))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.message)?;
+        if pending.is_empty() {
+            Ok(None)
+        } else {
+            Err(format!("{} pending migration(s): {}", pending.len(), pending.join(", ")))
+        }
+    })
+    .await
+}
+
+/// Redis answering `PING`, skipped when no Redis is configured
+pub async fn redis(redis: Option<RedisClient>) -> DependencyCheck {
+    let Some(redis) = redis else {
+        return skipped();
+    };
+    timed(async move { redis.ping().await.map(|_| None).map_err(|e| e.message) }).await
+}
+
+/// Object storage backend reachable
+pub async fn storage(storage: ObjectStorage) -> DependencyCheck {
+    timed(async move { storage.ping().await.map(|_| None).map_err(|e| e.message) }).await
+}
+
+/// Email provider reachable, skipped for transports that send nothing
+pub async fn email(mailer: Mailer, transport: EmailTransport) -> DependencyCheck {
+    if matches!(transport, EmailTransport::Log | EmailTransport::Mailbox) {
+        return skipped();
+    }
+    timed(async move { mailer.check().await.map(|_| None).map_err(|e| e.message) }).await
+}
+
+/// Event bus reachable, skipped when events are not published
+pub async fn event_bus(bus: Option<EventBus>) -> DependencyCheck {
+    let Some(bus) = bus else {
+        return skipped();
+    };
+    timed(async move { bus.check().await.map(|_| None).map_err(|e| e.message) }).await
+}
+
+/// HTTP service answering `GET url` without a server error
+pub async fn http(url: String) -> DependencyCheck {
+    timed(async move {
+        let response = HTTP_CLIENT.get(&url).send().await.map_err(|e| e.to_string())?;
+        let status = response.status();
+        if status.is_server_error() {
+            return Err(format!("Answered {}", status));
+        }
+        Ok(Some(serde_json::json!({ "status_code": status.as_u16() })))
+    })
+    .await
+}
+
+fn skipped() -> DependencyCheck {
+    DependencyCheck {
+        status: CheckStatus::Skipped,
+        critical: false,
+        latency_ms: 0,
+        error: None,
+        details: None,
+    }
+}
+
+/// Marks a check that passed slower than `degraded_after` as degraded
+fn grade(check: &mut DependencyCheck, degraded_after: Duration) {
+    if check.status == CheckStatus::Up && check.latency_ms > degraded_after.as_millis() as u64 {
+        check.status = CheckStatus::Degraded;
+        check.error = Some(format!("Answered in {}ms, slower than {}ms", check.latency_ms, degraded_after.as_millis()));
+    }
+}
+
+/// Runs a check under [`CHECK_TIMEOUT`], measuring its latency
+async fn timed<F>(check: F) -> DependencyCheck
+where
+    F: Future<Output = CheckResult>,
+{
+    let started = Instant::now();
+    let outcome = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(outcome) => outcome,
+        Err(_) => Err(format!("No answer within {}ms", CHECK_TIMEOUT.as_millis())),
+    };
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match outcome {
+        Ok(details) => DependencyCheck {
+            status: CheckStatus::Up,
+            critical: false,
+            latency_ms,
+            error: None,
+            details,
+        },
+        Err(error) => DependencyCheck {
+            status: CheckStatus::Down,
+            critical: false,
+            latency_ms,
+            error: Some(error),
+            details: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::resources::health::dto::ProbeStatus;
+
+    #[actix_web::test]
+    async fn test_timed_check() {
+        assert_eq!(timed(async { Ok(None) }).await.status, CheckStatus::Up);
+
+        let failed = timed(async { Err("boom".to_string()) }).await;
+        assert_eq!(failed.status, CheckStatus::Down);
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+
+        assert_eq!(redis(None).await.status, CheckStatus::Skipped);
+    }
+
+    #[test]
+    fn test_grade_slow_checks_as_degraded() {
+        let mut check = DependencyCheck {
+            status: CheckStatus::Up,
+            latency_ms: 1500,
+            ..skipped()
+        };
+        grade(&mut check, Duration::from_millis(1000));
+        assert_eq!(check.status, CheckStatus::Degraded);
+        assert!(check.error.is_some());
+
+        check.status = CheckStatus::Down;
+        grade(&mut check, Duration::from_millis(1000));
+        assert_eq!(check.status, CheckStatus::Down);
+    }
+
+    #[test]
+    fn test_only_critical_checks_fail_the_probe() {
+        let down = || DependencyCheck {
+            status: CheckStatus::Down,
+            ..skipped()
+        };
+        let status = ProbeStatus::from_checks(BTreeMap::from([("storage".to_string(), down())]));
+        assert_eq!(status.status, "DEGRADED");
+        assert!(status.passes());
+
+        let status = ProbeStatus::from_checks(BTreeMap::from([("database".to_string(), down().critical())]));
+        assert_eq!(status.status, "DOWN");
+        assert!(!status.passes());
+    }
+}
@@ -4,10 +4,13 @@
 //! including system metrics and health status information.
 
 use serde::Serialize;
+use std::collections::BTreeMap;
+use ts_rs::TS;
 use utoipa::ToSchema;
 
 /// Response structure for health check endpoints
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, ToSchema, TS)]
+#[ts(export)]
 pub struct HealthStatus {
     /// Current status of the service ("UP" or "DOWN")
     pub status: String,
@@ -17,17 +20,21 @@ pub struct HealthStatus {
     pub version: String,
     /// System metrics
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub metrics: Option<SystemMetrics>,
 }
 
 /// System metrics for detailed health information
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, ToSchema, TS)]
+#[ts(export)]
 pub struct SystemMetrics {
     /// CPU usage percentage
     pub cpu_usage: f32,
     /// Memory usage in bytes
+    #[ts(type = "number")]
     pub memory_used: u64,
     /// Total memory in bytes
+    #[ts(type = "number")]
     pub memory_total: u64,
     /// Memory usage percentage
     pub memory_usage_percentage: f32,
@@ -35,4 +42,68 @@ pub struct SystemMetrics {
     pub db_active_connections: u32,
     /// Maximum database connections
     pub db_max_connections: u32,
-} 
\ No newline at end of file
+} 
+pub use crate::infrastructure::dependencies::CheckStatus;
+
+/// Result of checking one dependency
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct DependencyCheck {
+    pub status: CheckStatus,
+    /// Whether the service cannot serve traffic while this dependency is down
+    pub critical: bool,
+    /// Time the check took in milliseconds
+    #[ts(type = "number")]
+    pub latency_ms: u64,
+    /// Reason the dependency is down or degraded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub error: Option<String>,
+    /// Additional check-specific information
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub details: Option<serde_json::Value>,
+}
+
+impl DependencyCheck {
+    /// Marks the dependency as needed to serve traffic
+    pub fn critical(mut self) -> Self {
+        self.critical = true;
+        self
+    }
+}
+
+/// Response of the liveness, readiness and startup probes
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ProbeStatus {
+    /// "DOWN" when a critical dependency is down, "DEGRADED" when another
+    /// dependency is down or any is slow, "UP" otherwise
+    pub status: String,
+    /// Per-dependency results, keyed by dependency name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub checks: BTreeMap<String, DependencyCheck>,
+}
+
+impl ProbeStatus {
+    /// Status of the service given the checks of its dependencies
+    pub fn from_checks(checks: BTreeMap<String, DependencyCheck>) -> Self {
+        let down = |check: &&DependencyCheck| check.status == CheckStatus::Down;
+        let status = if checks.values().filter(down).any(|check| check.critical) {
+            "DOWN"
+        } else if checks.values().any(|check| matches!(check.status, CheckStatus::Down | CheckStatus::Degraded)) {
+            "DEGRADED"
+        } else {
+            "UP"
+        };
+        Self {
+            status: status.to_string(),
+            checks,
+        }
+    }
+
+    /// Whether the probe passes: up, or degraded but still serving
+    pub fn passes(&self) -> bool {
+        self.status != "DOWN"
+    }
+}
@@ -1,132 +1,119 @@
 //! Health check handlers
 //! 
-//! This module provides handler functions for health monitoring endpoints.
-//! It includes system metrics, database connectivity checks, and resource
-//! utilization monitoring to ensure the service is operating optimally.
+//! This module provides handler functions for health monitoring endpoints:
+//! Kubernetes liveness, readiness and startup probes, and a detailed status
+//! with system metrics.
 
 use actix_web::{web, HttpResponse};
 use crate::{
-    api::{utils::{ApiResponseBuilder, ErrorResponse}, resources::health::dto::{HealthStatus, SystemMetrics}},
-    db::DbPool, error::Result
+    api::{utils::{ApiResponseBuilder, ErrorResponse}, resources::health::{checks, dto::{HealthStatus, ProbeStatus, SystemMetrics}}},
+    db::DbPool, error::Result, utils::Config,
 };
-use diesel::prelude::*;
+use std::{collections::BTreeMap, sync::atomic::{AtomicBool, Ordering}};
 use sysinfo::{System, SystemExt, CpuExt};
-use tracing::{info, error};
+use tracing::{info, warn};
 
-/// Quick health check that verifies the service process is running
-/// and has sufficient resources. Returns DOWN if memory usage
-/// exceeds 95%.
-/// 
-/// This endpoint is designed to be:
-/// - Fast: <10ms response time
-/// - Lightweight: Minimal resource usage
-/// - Independent: No external dependency checks
-/// 
-/// # Returns
-/// Returns a 200 OK response with basic health status
+/// Liveness probe reporting that the process is up and serving requests
+///
+/// Checks no dependencies: an unreachable database makes the service
+/// unready, but restarting the process would not fix it.
 #[utoipa::path(
     get,
     path = "/v1/health/live",
     responses(
-        (status = 200, description = "Service is alive", body = HealthStatus),
-        (status = 503, description = "Service is not alive", body = ErrorResponse)
+        (status = 200, description = "Service is alive", body = ProbeStatus)
     ),
     tag = "health"
 )]
 pub async fn liveness() -> Result<HttpResponse> {
-    let mut sys = System::new_all();
-    sys.refresh_all();
-
-    let memory_usage = (sys.used_memory() as f32 / sys.total_memory() as f32) * 100.0;
-    let status = if memory_usage < 95.0 { "UP" } else { "DOWN" };
-
     Ok(HttpResponse::Ok().json(
         ApiResponseBuilder::success()
             .with_message("Service is live")
-            .with_data(serde_json::json!({
-                "status": status,
-                "memory_usage_percentage": memory_usage,
-            }))
+            .with_data(ProbeStatus::from_checks(BTreeMap::new()))
             .build()
     ))
 }
 
-/// Deep health check that verifies all service dependencies are
-/// functioning correctly. This includes:
-/// - Database connectivity
-/// - Connection pool health
-/// - Resource availability
-/// 
+/// Readiness probe checking every dependency:
+/// - Database reachable, with connection pool usage (critical)
+/// - All migrations applied (critical)
+/// - Redis reachable (skipped when not configured)
+/// - Object storage reachable
+/// - Email provider reachable (skipped for the log and mailbox transports)
+/// - Every service in `health.http_dependencies`, e.g. the weather provider
+///
+/// Each dependency reports UP, DEGRADED (slower than
+/// `health.degraded_latency_ms`), DOWN or SKIPPED, with its check latency.
+///
 /// Status codes:
-/// - 200: Service is fully operational
-/// - 429: Service is degraded (high connection pool usage)
-/// - 503: Service is unavailable (database unreachable)
-/// 
-/// # Arguments
-/// * `pool` - Database connection pool to check
-/// 
-/// # Returns
-/// Returns appropriate HTTP status code based on service health
+/// - 200: No critical dependency is down; the status is DEGRADED when
+///   another dependency is down or slow
+/// - 503: A critical dependency is down
 #[utoipa::path(
     get,
     path = "/v1/health/ready",
     responses(
-        (status = 200, description = "Service is ready", body = HealthStatus),
-        (status = 503, description = "Service is not ready", body = ErrorResponse)
+        (status = 200, description = "Service is ready, possibly degraded", body = ProbeStatus),
+        (status = 503, description = "Service is not ready", body = ProbeStatus)
     ),
     tag = "health"
 )]
-pub async fn readiness(pool: web::Data<DbPool>) -> Result<HttpResponse> {
-    let db_status = match pool.get() {
-        Ok(mut conn) => {
-            match diesel::select(diesel::dsl::sql::<diesel::sql_types::Bool>("SELECT 1")).get_result::<bool>(&mut conn) {
-                Ok(_) => {
-                    info!("Database connection successful");
-                    true
-                },
-                Err(e) => {
-                    error!("Database query failed: {}", e);
-                    false
-                }
-            }
-        },
-        Err(e) => {
-            error!("Failed to get database connection: {}", e);
-            false
-        }
-    };
+pub async fn readiness(pool: web::Data<DbPool>, config: web::Data<Config>) -> Result<HttpResponse> {
+    let status = ProbeStatus::from_checks(checks::readiness_checks(&config, &pool).await);
+    if status.status != "UP" {
+        warn!(checks = %serde_json::to_string(&status.checks).unwrap_or_default(), "Readiness check not UP");
+    }
+    Ok(probe_response(status, "Readiness check"))
+}
 
-    let pool_state = pool.state();
-    let total_connections = pool_state.connections + pool_state.idle_connections;
-    let pool_status = if pool_state.connections as f32 / total_connections as f32 > 0.9 {
-        "DEGRADED"
-    } else if db_status { "UP" } else { "DOWN" };
+/// Startup probe passing once the database is reachable and migrated
+///
+/// Holds off liveness and readiness probes while a new instance waits for
+/// its database. Once passed, the probe keeps passing without re-checking.
+#[utoipa::path(
+    get,
+    path = "/v1/health/startup",
+    responses(
+        (status = 200, description = "Service has started", body = ProbeStatus),
+        (status = 503, description = "Service is still starting", body = ProbeStatus)
+    ),
+    tag = "health"
+)]
+pub async fn startup(pool: web::Data<DbPool>) -> Result<HttpResponse> {
+    if STARTED.load(Ordering::Relaxed) {
+        return Ok(probe_response(ProbeStatus::from_checks(BTreeMap::new()), "Startup check"));
+    }
 
-    let status_code = match pool_status {
-        "UP" => 200,
-        "DEGRADED" => 429,
-        _ => 503,
-    };
+    let (database, migrations) = futures_util::join!(
+        checks::database(pool.get_ref().clone()),
+        checks::migrations(pool.get_ref().clone()),
+    );
+    let status = ProbeStatus::from_checks(BTreeMap::from([
+        ("database".to_string(), database.critical()),
+        ("migrations".to_string(), migrations.critical()),
+    ]));
+    if status.passes() {
+        STARTED.store(true, Ordering::Relaxed);
+        info!("Startup checks passed");
+    }
+    Ok(probe_response(status, "Startup check"))
+}
 
-    Ok(HttpResponse::build(actix_web::http::StatusCode::from_u16(status_code).unwrap())
-        .json(ApiResponseBuilder::success()
-            .with_message("Readiness check")
-            .with_data(serde_json::json!({
-                "status": pool_status,
-                "checks": {
-                    "database": {
-                        "status": if db_status { "UP" } else { "DOWN" },
-                        "pool": {
-                            "active_connections": pool_state.connections,
-                            "idle_connections": pool_state.idle_connections,
-                            "total_connections": total_connections,
-                            "usage_percentage": (pool_state.connections as f32 / total_connections as f32) * 100.0
-                        }
-                    }
-                }
-            }))
+/// Whether the startup probe has passed once
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+fn probe_response(status: ProbeStatus, message: &str) -> HttpResponse {
+    let mut response = if status.passes() {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::ServiceUnavailable()
+    };
+    response.json(
+        ApiResponseBuilder::success()
+            .with_message(message)
+            .with_data(status)
             .build()
-        ))
+    )
 }
 
 /// Comprehensive health check endpoint that provides detailed system metrics
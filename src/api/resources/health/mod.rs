@@ -1,3 +1,4 @@
+pub mod checks;
 pub mod dto;
 pub mod handlers;
 pub mod routes;
\ No newline at end of file
@@ -6,5 +6,6 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("", web::get().to(crate::api::resources::health::handlers::health_check))
             .route("/live", web::get().to(crate::api::resources::health::handlers::liveness))
             .route("/ready", web::get().to(crate::api::resources::health::handlers::readiness))
+            .route("/startup", web::get().to(crate::api::resources::health::handlers::startup))
     );
 } 
\ No newline at end of file
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::db::models::FieldChange;
+
+/// A change of one field of a record
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct FieldChangeResponse {
+    pub field: String,
+    /// Value before the change, `null` when the field was empty or absent
+    #[ts(type = "unknown")]
+    pub old_value: Option<serde_json::Value>,
+    /// Value after the change, `null` when the field was emptied or removed
+    #[ts(type = "unknown")]
+    pub new_value: Option<serde_json::Value>,
+    pub changed_by: Option<Uuid>,
+    /// Name of the user who made the change, when still known
+    pub changed_by_name: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl From<(FieldChange, Option<String>)> for FieldChangeResponse {
+    fn from((change, changed_by_name): (FieldChange, Option<String>)) -> Self {
+        Self {
+            field: change.field,
+            old_value: change.old_value,
+            new_value: change.new_value,
+            changed_by: change.changed_by,
+            changed_by_name,
+            changed_at: change.changed_at,
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! Change history responses
+//!
+//! Histories are served under the resource they belong to, e.g.
+//! `/v1/supply-contracts/{id}/history`, by that resource's handlers.
+
+pub mod dto;
+
+pub use dto::FieldChangeResponse;
@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    db::models::Import,
+    domain::import::{ColumnMapping, ImportField, ImportFieldKind, ImportTarget},
+};
+
+/// Query parameters for uploading a file to import
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct UploadImportQuery {
+    /// Import target the rows are loaded into
+    pub target: String,
+    /// Name of the uploaded file; its extension (`.csv` or `.xlsx`) picks
+    /// how the body is read
+    pub filename: String,
+}
+
+/// Query parameters for listing imports
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListImportsQuery {
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// Input for previewing an import
+#[derive(Debug, Default, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct PreviewImportInput {
+    /// Field each header is imported into, replacing the stored mapping;
+    /// the stored mapping is used when unset
+    #[serde(default)]
+    pub mapping: Option<ColumnMapping>,
+    /// Rows returned, 20 when unset and at most 200
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub limit: Option<usize>,
+}
+
+/// Input for committing an import
+#[derive(Debug, Default, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CommitImportInput {
+    /// Final mapping of headers to fields; the stored mapping is used when
+    /// unset
+    #[serde(default)]
+    pub mapping: Option<ColumnMapping>,
+}
+
+/// A field columns can be mapped to
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ImportFieldResponse {
+    pub name: String,
+    pub kind: ImportFieldKind,
+    pub required: bool,
+    /// Other headers detected as this field
+    pub aliases: Vec<String>,
+}
+
+impl From<&ImportField> for ImportFieldResponse {
+    fn from(field: &ImportField) -> Self {
+        Self {
+            name: field.name.to_string(),
+            kind: field.kind,
+            required: field.required,
+            aliases: field.aliases.iter().map(|alias| alias.to_string()).collect(),
+        }
+    }
+}
+
+/// Something files can be imported into
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ImportTargetResponse {
+    pub name: String,
+    pub fields: Vec<ImportFieldResponse>,
+}
+
+impl From<&dyn ImportTarget> for ImportTargetResponse {
+    fn from(target: &dyn ImportTarget) -> Self {
+        Self {
+            name: target.name().to_string(),
+            fields: target.fields().iter().map(ImportFieldResponse::from).collect(),
+        }
+    }
+}
+
+/// Import response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ImportResponse {
+    pub id: Uuid,
+    pub target: String,
+    pub filename: String,
+    pub headers: Vec<String>,
+    pub mapping: ColumnMapping,
+    /// `uploaded` until committed, then `queued`, `running`, and
+    /// `completed` or `failed`
+    pub status: String,
+    pub total_rows: i32,
+    pub imported_rows: Option<i32>,
+    pub failed_rows: Option<i32>,
+    /// Whether rejected rows can be downloaded from
+    /// `/v1/imports/{id}/errors.csv`
+    pub has_error_report: bool,
+    pub error: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Import> for ImportResponse {
+    fn from(import: Import) -> Self {
+        Self {
+            id: import.id,
+            mapping: serde_json::from_value(import.mapping).unwrap_or_default(),
+            target: import.target,
+            filename: import.filename,
+            headers: import.headers,
+            status: import.status,
+            total_rows: import.total_rows,
+            imported_rows: import.imported_rows,
+            failed_rows: import.failed_rows,
+            has_error_report: import.error_key.is_some(),
+            error: import.error,
+            created_by: import.created_by,
+            created_at: import.created_at,
+            updated_at: import.updated_at,
+        }
+    }
+}
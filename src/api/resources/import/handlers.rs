@@ -0,0 +1,307 @@
+//! Import resource handlers
+//!
+//! Every handler works on the imports of the authenticated user's
+//! organization. Routes require the manager role.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::import::dto::{
+            CommitImportInput, ImportResponse, ImportTargetResponse, ListImportsQuery, PreviewImportInput,
+            UploadImportQuery,
+        },
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse, PaginatedResponse, PaginationParams},
+    },
+    db::{get_connection, repositories::ImportRepositoryImpl, DbPool},
+    domain::import::{ImportPreview, ImportService},
+    error::ApiError,
+    utils::Config,
+};
+use actix_web::{http::header::ContentDisposition, web, HttpResponse};
+use uuid::Uuid;
+
+fn service(config: &Config) -> ImportService<ImportRepositoryImpl> {
+    ImportService::new(ImportRepositoryImpl, config.storage().clone())
+}
+
+/// Lists the targets files can be imported into and their fields
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/imports/targets",
+    security(("bearer_auth" = [])),
+    tag = "imports",
+    responses(
+        (status = 200, description = "Import targets", body = ListResponse<ImportTargetResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse)
+    )
+)]
+pub async fn list_import_targets(_user: AuthenticatedUser, config: web::Data<Config>) -> Result<HttpResponse, ApiError> {
+    let service = service(&config);
+    let targets = service
+        .targets()
+        .iter()
+        .map(|target| ImportTargetResponse::from(target.as_ref()))
+        .collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Import targets retrieved successfully")
+            .with_data(targets)
+            .build()
+    ))
+}
+
+/// Uploads a CSV or Excel file to import
+///
+/// The body is the file itself. Its columns are matched against the
+/// target's fields to suggest a mapping; nothing is imported until the
+/// import is committed.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/imports",
+    security(("bearer_auth" = [])),
+    tag = "imports",
+    request_body(content = Vec<u8>, content_type = "application/octet-stream", description = "The CSV or .xlsx file"),
+    responses(
+        (status = 201, description = "File uploaded", body = ImportResponse),
+        (status = 400, description = "Unknown target, or unreadable or unsupported file", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 413, description = "File too large", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("target" = String, Query, description = "Import target"),
+        ("filename" = String, Query, description = "Name of the file, ending in .csv or .xlsx")
+    )
+)]
+pub async fn upload_import(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    query: web::Query<UploadImportQuery>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+
+    let mut conn = get_connection(&pool)?;
+    let import = service(&config)
+        .upload(&mut conn, org_id, created_by, &query.target, &query.filename, body)
+        .await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("File uploaded successfully")
+            .with_data(ImportResponse::from(import))
+            .build()
+    ))
+}
+
+/// Lists the organization's imports, newest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/imports",
+    security(("bearer_auth" = [])),
+    tag = "imports",
+    responses(
+        (status = 200, description = "List of imports", body = PaginatedResponse<ImportResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page")
+    )
+)]
+pub async fn list_imports(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    query: web::Query<ListImportsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+
+    let mut conn = get_connection(&pool)?;
+    let (imports, total) = service(&config).list(&mut conn, org_id, &pagination).await?;
+    let imports = imports.into_iter().map(ImportResponse::from).collect();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Imports retrieved successfully")
+            .with_data(PaginatedResponse::with_count(imports, total, &pagination))
+            .build()
+    ))
+}
+
+/// Retrieves an import and, once it ran, its outcome
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/imports/{id}",
+    security(("bearer_auth" = [])),
+    tag = "imports",
+    responses(
+        (status = 200, description = "Import", body = ImportResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Import not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Import ID")
+    )
+)]
+pub async fn get_import(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    import_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let import = service(&config).get(&mut conn, org_id, *import_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Import retrieved successfully")
+            .with_data(ImportResponse::from(import))
+            .build()
+    ))
+}
+
+/// Validates every row of an import with a column mapping
+///
+/// A mapping in the body replaces the stored one. Counts cover the whole
+/// file; the first rows are returned with their converted values or
+/// problems.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/imports/{id}/preview",
+    security(("bearer_auth" = [])),
+    tag = "imports",
+    request_body = PreviewImportInput,
+    responses(
+        (status = 200, description = "Import preview", body = ImportPreview),
+        (status = 400, description = "Invalid column mapping", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Import not found", body = ErrorResponse),
+        (status = 409, description = "The import was already committed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Import ID")
+    )
+)]
+pub async fn preview_import(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    import_id: web::Path<Uuid>,
+    input: Option<web::Json<PreviewImportInput>>,
+) -> Result<HttpResponse, ApiError> {
+    let input = input.map(web::Json::into_inner).unwrap_or_default();
+    let mut conn = get_connection(&pool)?;
+    let preview = service(&config)
+        .preview(&mut conn, org_id, *import_id, input.mapping, input.limit)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Import previewed successfully")
+            .with_data(preview)
+            .build()
+    ))
+}
+
+/// Commits an import; its rows are loaded in the background
+///
+/// Poll the import for its outcome. Rejected rows can then be downloaded
+/// as a CSV report.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/imports/{id}/commit",
+    security(("bearer_auth" = [])),
+    tag = "imports",
+    request_body = CommitImportInput,
+    responses(
+        (status = 202, description = "Import queued", body = ImportResponse),
+        (status = 400, description = "Invalid column mapping", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Import not found", body = ErrorResponse),
+        (status = 409, description = "The import was already committed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Import ID")
+    )
+)]
+pub async fn commit_import(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    import_id: web::Path<Uuid>,
+    input: Option<web::Json<CommitImportInput>>,
+) -> Result<HttpResponse, ApiError> {
+    let input = input.map(web::Json::into_inner).unwrap_or_default();
+    let mut conn = get_connection(&pool)?;
+    let import = service(&config)
+        .commit(&mut conn, &config.queue, org_id, *import_id, input.mapping)
+        .await?;
+
+    Ok(HttpResponse::Accepted().json(
+        ApiResponseBuilder::success()
+            .with_message("Import queued successfully")
+            .with_data(ImportResponse::from(import))
+            .build()
+    ))
+}
+
+/// Downloads the rows an import rejected, with their problems, as CSV
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/imports/{id}/errors.csv",
+    security(("bearer_auth" = [])),
+    tag = "imports",
+    responses(
+        (status = 200, description = "Rejected rows", content_type = "text/csv", body = String),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Import not found or no rows rejected", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Import ID")
+    )
+)]
+pub async fn download_import_errors(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    import_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let (import, report) = service(&config).error_report(&mut conn, org_id, *import_id).await?;
+    let stem = import.filename.rsplit_once('.').map_or(import.filename.as_str(), |(stem, _)| stem);
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(ContentDisposition::attachment(format!("{}-errors.csv", stem)))
+        .body(report))
+}
@@ -0,0 +1,21 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequirePermission},
+    domain::{auth::Permission, import::MAX_IMPORT_BYTES},
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/imports")
+            .wrap(RequirePermission::read_write(Permission::ImportsRead, Permission::ImportsWrite))
+            .wrap(Auth::new())
+            .app_data(web::PayloadConfig::new(MAX_IMPORT_BYTES))
+            .route("", web::get().to(crate::api::resources::import::handlers::list_imports))
+            .route("", web::post().to(crate::api::resources::import::handlers::upload_import))
+            .route("/targets", web::get().to(crate::api::resources::import::handlers::list_import_targets))
+            .route("/{id}", web::get().to(crate::api::resources::import::handlers::get_import))
+            .route("/{id}/preview", web::post().to(crate::api::resources::import::handlers::preview_import))
+            .route("/{id}/commit", web::post().to(crate::api::resources::import::handlers::commit_import))
+            .route("/{id}/errors.csv", web::get().to(crate::api::resources::import::handlers::download_import_errors))
+    );
+}
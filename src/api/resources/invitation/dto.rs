@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::db::models::{auth::Role, OrgInvitation};
+
+/// Input for inviting someone into the caller's organization
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CreateInvitationInput {
+    pub email: String,
+    /// Role the invitee joins with; only admins invite admins
+    pub role: Role,
+    /// Days the invitation can be accepted for, 7 when unset, up to 30
+    #[ts(optional, type = "number")]
+    pub expires_in_days: Option<i64>,
+}
+
+/// An invitation to join an organization
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct InvitationResponse {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub email: String,
+    pub role: Role,
+    pub invited_by: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<OrgInvitation> for InvitationResponse {
+    fn from(invitation: OrgInvitation) -> Self {
+        Self {
+            id: invitation.id,
+            org_id: invitation.org_id,
+            email: invitation.email,
+            role: invitation.role,
+            invited_by: invitation.invited_by,
+            expires_at: invitation.expires_at,
+            accepted_at: invitation.accepted_at,
+            created_at: invitation.created_at,
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Invitation resource handlers
+//!
+//! Invitations are into the authenticated user's organization. Routes
+//! require the manager role.
+
+use crate::{
+    api::{
+        middleware::AuthenticatedUser,
+        resources::invitation::dto::{CreateInvitationInput, InvitationResponse},
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{
+        get_connection,
+        models::auth::User,
+        repositories::{auth::UserRepositoryImpl, Repository},
+        DbPool,
+    },
+    domain::{invitation::INVITATION_DAYS, InvitationService},
+    error::ApiError,
+    utils::Config,
+};
+use actix_web::{web, HttpResponse};
+use diesel::PgConnection;
+use uuid::Uuid;
+
+async fn caller(conn: &mut PgConnection, user: &AuthenticatedUser) -> Result<User, ApiError> {
+    let user_id = Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))?;
+    UserRepositoryImpl.find_by_id(conn, user_id).await
+}
+
+/// Invites an email address into the caller's organization and mails it a
+/// link to register with
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/invitations",
+    security(("bearer_auth" = [])),
+    tag = "invitations",
+    request_body = CreateInvitationInput,
+    responses(
+        (status = 201, description = "Invitation sent", body = InvitationResponse),
+        (status = 400, description = "Invalid email or expiry, or the email is already in use", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required, or an admin invited by a manager", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_invitation(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    input: web::Json<CreateInvitationInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let inviter = caller(&mut conn, &user).await?;
+    let days = input.expires_in_days.unwrap_or(INVITATION_DAYS);
+    let invitation = InvitationService::invite(&mut conn, &inviter, &input.email, input.role, days, &config).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Invitation sent successfully")
+            .with_data(InvitationResponse::from(invitation))
+            .build()
+    ))
+}
+
+/// Lists the invitations of the caller's organization that are neither
+/// accepted nor revoked, newest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/invitations",
+    security(("bearer_auth" = [])),
+    tag = "invitations",
+    responses(
+        (status = 200, description = "Pending invitations", body = ListResponse<InvitationResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn list_invitations(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let viewer = caller(&mut conn, &user).await?;
+    let invitations = InvitationService::pending(&mut conn, &viewer).await?;
+    let invitations = invitations.into_iter().map(InvitationResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Invitations retrieved successfully")
+            .with_data(invitations)
+            .build()
+    ))
+}
+
+/// Mails a pending invitation again with a fresh link, renewing it for at
+/// least 7 days
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/invitations/{id}/resend",
+    security(("bearer_auth" = [])),
+    tag = "invitations",
+    responses(
+        (status = 200, description = "Invitation resent", body = InvitationResponse),
+        (status = 400, description = "The email is already in use", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required, or an admin invitation resent by a manager", body = ErrorResponse),
+        (status = 404, description = "No such pending invitation", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Invitation ID")
+    )
+)]
+pub async fn resend_invitation(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let sender = caller(&mut conn, &user).await?;
+    let invitation = InvitationService::resend(&mut conn, &sender, id.into_inner(), &config).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Invitation resent successfully")
+            .with_data(InvitationResponse::from(invitation))
+            .build()
+    ))
+}
+
+/// Revokes a pending invitation, so its link no longer works
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/invitations/{id}",
+    security(("bearer_auth" = [])),
+    tag = "invitations",
+    responses(
+        (status = 204, description = "Invitation revoked"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required, or an admin invitation revoked by a manager", body = ErrorResponse),
+        (status = 404, description = "No such pending invitation", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Invitation ID")
+    )
+)]
+pub async fn revoke_invitation(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let revoker = caller(&mut conn, &user).await?;
+    InvitationService::revoke(&mut conn, &revoker, id.into_inner()).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,17 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequireRole},
+    db::models::auth::Role,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/invitations")
+            .wrap(RequireRole::new(Role::Manager))
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::invitation::handlers::list_invitations))
+            .route("", web::post().to(crate::api::resources::invitation::handlers::create_invitation))
+            .route("/{id}", web::delete().to(crate::api::resources::invitation::handlers::revoke_invitation))
+            .route("/{id}/resend", web::post().to(crate::api::resources::invitation::handlers::resend_invitation))
+    );
+}
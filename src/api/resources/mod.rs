@@ -1,15 +1,51 @@
 use actix_web::web;
 
 use super::middleware;
-mod health;
+use crate::error::ApiError;
+pub mod health;
+pub mod admin;
+pub mod approval;
 pub mod auth;
+pub mod block;
+pub mod billing;
+pub mod certification;
+pub mod crew;
+pub mod customer;
+pub mod dev;
+pub mod document;
+pub mod erp;
+pub mod history;
+pub mod import;
+pub mod invitation;
+pub mod notification;
 pub mod organization;
+pub mod presence;
+pub mod report;
+pub mod role;
+pub mod sales;
+pub mod scim;
+pub mod search;
+pub mod stand;
+pub mod tag;
+pub mod telemetry;
+pub mod user;
+pub mod view;
 pub mod docs;
 
 /// Configures all application routes
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    // Malformed bodies and queries get the documented error body rather
+    // than actix-web's plain text
+    cfg.app_data(web::JsonConfig::default().error_handler(|err, _| {
+        ApiError::validation(format!("Invalid request body: {}", err), None).into()
+    }))
+    .app_data(web::QueryConfig::default().error_handler(|err, _| {
+        ApiError::validation(format!("Invalid query string: {}", err), None).into()
+    }));
     cfg.service(
         web::scope("")
+            .route("/.well-known/jwks.json", web::get().to(auth::handlers::jwks))
+            .configure(scim::routes::configure)
             .configure(configure_v1_routes)
     );
 }
@@ -29,7 +65,32 @@ fn configure_v1_routes(cfg: &mut web::ServiceConfig) {
             .wrap(RateLimit::new(100, 60)) // 100 requests per minute
             .configure(health::routes::configure)
             .configure(auth::routes::configure)
+            .configure(user::routes::configure)
+            .configure(invitation::routes::configure)
+            .configure(role::routes::configure)
             .configure(organization::routes::configure)
+            .configure(notification::routes::configure)
+            .configure(report::routes::configure)
+            .configure(import::routes::configure)
+            .configure(erp::routes::configure)
+            .configure(billing::routes::configure)
+            .configure(sales::routes::configure)
+            .configure(customer::routes::configure)
+            .configure(approval::routes::configure)
+            .configure(stand::routes::configure)
+            // After the sign-off and stand scopes, which would otherwise be
+            // taken for a block id
+            .configure(block::routes::configure)
+            .configure(document::routes::configure)
+            .configure(tag::routes::configure)
+            .configure(certification::routes::configure)
+            .configure(presence::routes::configure)
+            .configure(view::routes::configure)
+            .configure(search::routes::configure)
+            .configure(telemetry::routes::configure)
+            .configure(crew::routes::configure)
+            .configure(admin::routes::configure)
+            .configure(dev::routes::configure)
             .configure(docs::configure)  // Moved docs into resources
     );
 }
\ No newline at end of file
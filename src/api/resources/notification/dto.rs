@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// Query parameters for listing notifications
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListNotificationsQuery {
+    /// Only list unread notifications
+    pub unread: Option<bool>,
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// Number of unread notifications, for the notification badge
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct UnreadCountResponse {
+    #[ts(type = "number")]
+    pub unread_count: i64,
+}
+
+/// Result of marking all notifications read
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct MarkAllReadResponse {
+    /// Notifications that were unread
+    pub updated: usize,
+}
@@ -0,0 +1,164 @@
+//! Notification resource handlers
+//!
+//! Every handler works on the notifications of the authenticated user.
+
+use crate::{
+    api::{
+        middleware::AuthenticatedUser,
+        resources::notification::dto::{ListNotificationsQuery, MarkAllReadResponse, UnreadCountResponse},
+        utils::{ApiResponseBuilder, ErrorResponse, PaginatedResponse, PaginationParams},
+    },
+    db::{get_connection, models::Notification, repositories::NotificationRepositoryImpl, DbPool},
+    domain::notification::NotificationService,
+    error::ApiError,
+};
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+
+fn service() -> NotificationService<NotificationRepositoryImpl> {
+    NotificationService::new(NotificationRepositoryImpl)
+}
+
+fn recipient(user: &AuthenticatedUser) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))
+}
+
+/// Lists the user's notifications, newest first
+///
+/// The response metadata carries the user's `unread_count`.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/notifications",
+    security(("bearer_auth" = [])),
+    tag = "notifications",
+    responses(
+        (status = 200, description = "List of notifications", body = PaginatedResponse<Notification>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("unread" = Option<bool>, Query, description = "Only list unread notifications"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page")
+    )
+)]
+pub async fn list_notifications(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    query: web::Query<ListNotificationsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = recipient(&user)?;
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+    let service = service();
+
+    let mut conn = get_connection(&pool)?;
+    let (notifications, total) = service
+        .list(&mut conn, user_id, query.unread.unwrap_or(false), &pagination)
+        .await?;
+    let unread_count = service.unread_count(&mut conn, user_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Notifications retrieved successfully")
+            .with_data(PaginatedResponse::with_count(notifications, total, &pagination))
+            .with_metadata(serde_json::json!({ "unread_count": unread_count }))
+            .build()
+    ))
+}
+
+/// Returns the number of unread notifications
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/notifications/unread-count",
+    security(("bearer_auth" = [])),
+    tag = "notifications",
+    responses(
+        (status = 200, description = "Unread notifications", body = UnreadCountResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn unread_count(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = recipient(&user)?;
+    let mut conn = get_connection(&pool)?;
+    let unread_count = service().unread_count(&mut conn, user_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Unread count retrieved successfully")
+            .with_data(UnreadCountResponse { unread_count })
+            .build()
+    ))
+}
+
+/// Marks a notification read
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/notifications/{id}/read",
+    security(("bearer_auth" = [])),
+    tag = "notifications",
+    responses(
+        (status = 200, description = "Notification marked read", body = Notification),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Notification not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Notification ID")
+    )
+)]
+pub async fn mark_read(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    notification_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = recipient(&user)?;
+    let mut conn = get_connection(&pool)?;
+    let notification = service().mark_read(&mut conn, user_id, *notification_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Notification marked read")
+            .with_data(notification)
+            .build()
+    ))
+}
+
+/// Marks all of the user's notifications read
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/notifications/read-all",
+    security(("bearer_auth" = [])),
+    tag = "notifications",
+    responses(
+        (status = 200, description = "Notifications marked read", body = MarkAllReadResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn mark_all_read(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = recipient(&user)?;
+    let mut conn = get_connection(&pool)?;
+    let updated = service().mark_all_read(&mut conn, user_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Notifications marked read")
+            .with_data(MarkAllReadResponse { updated })
+            .build()
+    ))
+}
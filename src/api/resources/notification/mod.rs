@@ -0,0 +1,5 @@
+pub mod dto;
+pub mod handlers;
+pub mod routes;
+
+pub use dto::{ListNotificationsQuery, MarkAllReadResponse, UnreadCountResponse};
@@ -0,0 +1,14 @@
+use actix_web::web;
+use crate::api::middleware::auth::{Auth, RequireAuth};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/notifications")
+            .wrap(RequireAuth)
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::notification::handlers::list_notifications))
+            .route("/unread-count", web::get().to(crate::api::resources::notification::handlers::unread_count))
+            .route("/read-all", web::post().to(crate::api::resources::notification::handlers::mark_all_read))
+            .route("/{id}/read", web::post().to(crate::api::resources::notification::handlers::mark_read))
+    );
+}
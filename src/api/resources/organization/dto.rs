@@ -1,40 +1,494 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate as ValidatorValidate;
+use ts_rs::TS;
 use utoipa::ToSchema;
 
-use crate::db::models::Organization;
+use crate::{
+    db::models::{
+        auth::{Role, UserFilter, UserSort},
+        Activity, ActivityFilter, Organization, OrganizationArchive, OrganizationFilter, OrganizationSort,
+        OwnershipTransfer,
+    },
+    domain::{
+        activity::FeedKind,
+        organization::{OrgSettings, QuotaUsage, SettingsChanges},
+        user::UnitSystem,
+    },
+    error::{ApiError, ErrorContext},
+};
+
+/// Longest user search accepted
+const MAX_USER_QUERY_LENGTH: usize = 100;
+
+/// Longest organization name search accepted
+const MAX_ORGANIZATION_QUERY_LENGTH: usize = 100;
 
 /// Input for creating a new organization
-#[derive(Debug, Deserialize, ValidatorValidate, ToSchema)]
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
 pub struct CreateOrganizationInput {
     #[validate(length(min = 1, max = 255))]
     pub name: String,
 }
 
 /// Input for updating an organization
-#[derive(Debug, Deserialize, ValidatorValidate, ToSchema)]
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
 pub struct UpdateOrganizationInput {
     #[validate(length(min = 1, max = 255))]
     pub name: Option<String>,
 }
 
 /// Organization response
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
 pub struct OrganizationResponse {
     pub id: Uuid,
     pub name: String,
+    /// Organization this one is a division or area of
+    pub parent_id: Option<Uuid>,
+    /// Admin owning the organization
+    pub owner_id: Option<Uuid>,
+    /// When the organization was archived; archived organizations are read-only
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Input for handing an organization to another of its members
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct TransferOwnershipInput {
+    /// Verified, active member of the organization to take it over
+    pub new_owner_id: Uuid,
+}
+
+/// Input for changing the role of a member
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct ChangeMemberRoleInput {
+    pub role: Role,
+}
+
+/// A transfer of an organization's ownership
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OwnershipTransferResponse {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
+    /// When the transfer lapses unless accepted
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub accepted_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<OwnershipTransfer> for OwnershipTransferResponse {
+    fn from(transfer: OwnershipTransfer) -> Self {
+        Self {
+            id: transfer.id,
+            organization_id: transfer.org_id,
+            from_user_id: transfer.from_user_id,
+            to_user_id: transfer.to_user_id,
+            expires_at: transfer.expires_at,
+            accepted_at: transfer.accepted_at,
+            created_at: transfer.created_at,
+        }
+    }
+}
+
+/// An archival of an organization and its export bundle
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OrganizationArchiveResponse {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub requested_by: Option<Uuid>,
+    /// `pending` while the export bundle is written, then `completed`, or
+    /// `failed` while it is retried
+    pub status: String,
+    /// Size of the gzipped export bundle
+    pub byte_size: Option<i64>,
+    /// Why writing the export bundle last failed
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the organization was reopened
+    pub unarchived_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<OrganizationArchive> for OrganizationArchiveResponse {
+    fn from(archive: OrganizationArchive) -> Self {
+        Self {
+            id: archive.id,
+            organization_id: archive.org_id,
+            requested_by: archive.requested_by,
+            status: archive.status,
+            byte_size: archive.byte_size,
+            error: archive.error,
+            created_at: archive.created_at,
+            completed_at: archive.completed_at,
+            unarchived_at: archive.unarchived_at,
+        }
+    }
+}
+
+/// An organization in a hierarchy listing
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OrganizationNodeResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    /// Levels below the organization the listing starts at
+    pub depth: usize,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<(Organization, usize)> for OrganizationNodeResponse {
+    fn from((organization, depth): (Organization, usize)) -> Self {
+        Self {
+            id: organization.id,
+            name: organization.name,
+            parent_id: organization.parent_id,
+            depth,
+            created_at: organization.created_at,
+            updated_at: organization.updated_at,
+        }
+    }
+}
+
+/// How an organization works
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OrganizationSettingsResponse {
+    /// Units members see unless their preferences say otherwise
+    pub default_units: UnitSystem,
+    /// Month the fiscal year starts in, 1 for January
+    pub fiscal_year_start_month: u32,
+    /// Operational timezone, `UTC` or an offset from it such as `-08:00`
+    pub timezone: String,
+    /// Safety forms every job needs
+    pub required_safety_forms: Vec<String>,
+}
+
+impl From<OrgSettings> for OrganizationSettingsResponse {
+    fn from(settings: OrgSettings) -> Self {
+        Self {
+            default_units: settings.default_units,
+            fiscal_year_start_month: settings.fiscal_year_start_month,
+            timezone: settings.timezone,
+            required_safety_forms: settings.required_safety_forms,
+        }
+    }
+}
+
+/// How much of its plan an organization uses
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OrganizationUsageResponse {
+    /// Users of the organization, deactivated ones included
+    #[ts(type = "number")]
+    pub users: i64,
+    /// `null` for any number of users
+    pub max_users: Option<u32>,
+    /// `null` for any number of active harvest blocks
+    pub max_active_blocks: Option<u32>,
+    /// Days telemetry is kept
+    pub telemetry_retention_days: u32,
+}
+
+impl From<QuotaUsage> for OrganizationUsageResponse {
+    fn from(usage: QuotaUsage) -> Self {
+        Self {
+            users: usage.users,
+            max_users: usage.quotas.max_users,
+            max_active_blocks: usage.quotas.max_active_blocks,
+            telemetry_retention_days: usage.quotas.retention_days(),
+        }
+    }
+}
+
+/// Changes to an organization's settings; fields left out keep their value
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct UpdateOrganizationSettingsInput {
+    #[serde(default)]
+    #[ts(optional)]
+    pub default_units: Option<UnitSystem>,
+    /// 1 for January to 12 for December
+    #[serde(default)]
+    #[ts(optional)]
+    pub fiscal_year_start_month: Option<u32>,
+    /// `UTC` or an offset from it, such as `-08:00` or `+05:30`
+    #[serde(default)]
+    #[ts(optional)]
+    pub timezone: Option<String>,
+    /// Replaces the required safety forms
+    #[serde(default)]
+    #[ts(optional)]
+    pub required_safety_forms: Option<Vec<String>>,
+}
+
+impl From<UpdateOrganizationSettingsInput> for SettingsChanges {
+    fn from(input: UpdateOrganizationSettingsInput) -> Self {
+        Self {
+            default_units: input.default_units,
+            fiscal_year_start_month: input.fiscal_year_start_month,
+            timezone: input.timezone,
+            required_safety_forms: input.required_safety_forms,
+        }
+    }
+}
+
 /// Query parameters for listing organizations
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
 pub struct ListOrganizationsQuery {
+    /// Text the name has to contain, ignoring case
+    pub name_contains: Option<String>,
+    /// Only organizations created after this RFC 3339 timestamp or date
+    pub created_after: Option<String>,
+    /// `name`, `created_at` or `updated_at`, with `-` for descending
+    pub sort: Option<String>,
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+impl ListOrganizationsQuery {
+    /// The organizations asked for; newest first unless `sort` says
+    pub fn filter(&self) -> Result<OrganizationFilter, ApiError> {
+        let name_contains = self.name_contains.as_deref().map(str::trim).filter(|text| !text.is_empty());
+        if name_contains.is_some_and(|text| text.chars().count() > MAX_ORGANIZATION_QUERY_LENGTH) {
+            return Err(invalid(
+                "name_contains",
+                format!("Search must be at most {} characters", MAX_ORGANIZATION_QUERY_LENGTH),
+            ));
+        }
+
+        let created_after = instant("created_after", self.created_after.as_deref())?;
+
+        let sort = self.sort.as_deref().map(str::trim).unwrap_or_default();
+        let (sort, descending) = match sort.strip_prefix('-') {
+            Some(sort) => (sort, true),
+            None if sort.is_empty() => ("created_at", true),
+            None => (sort, false),
+        };
+        let sort = match sort {
+            "name" => OrganizationSort::Name,
+            "created_at" => OrganizationSort::CreatedAt,
+            "updated_at" => OrganizationSort::UpdatedAt,
+            other => return Err(invalid("sort", format!("Can't sort organizations by {}", other))),
+        };
+
+        Ok(OrganizationFilter {
+            name_contains: name_contains.map(str::to_string),
+            created_after,
+            sort,
+            descending,
+            include_deleted: false,
+        })
+    }
+}
+
+/// Query parameters for listing an organization's activity
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListOrganizationActivityQuery {
+    /// Comma-separated kinds to include, e.g. `user_joined,member_removed`
+    pub kind: Option<String>,
+    /// Only what this user did
+    pub actor_id: Option<Uuid>,
+    /// Only what concerns this type of subject, e.g. `user` or `block`
+    pub subject_type: Option<String>,
+    /// Only activity from this RFC 3339 timestamp or date on
+    pub since: Option<String>,
+    /// Only activity before this RFC 3339 timestamp or date
+    pub until: Option<String>,
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+impl ListOrganizationActivityQuery {
+    /// The activity asked for
+    pub fn filter(&self) -> Result<ActivityFilter, ApiError> {
+        let kinds = self
+            .kind
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|kind| !kind.is_empty())
+            .map(|kind| {
+                FeedKind::parse(kind)
+                    .map(|kind| kind.as_str().to_string())
+                    .ok_or_else(|| invalid("kind", format!("Unknown activity kind {}", kind)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let since = instant("since", self.since.as_deref())?;
+        let until = instant("until", self.until.as_deref())?;
+        if let (Some(since), Some(until)) = (since, until) {
+            if since >= until {
+                return Err(invalid("until", "until must be after since".to_string()));
+            }
+        }
+
+        Ok(ActivityFilter {
+            kinds,
+            actor_id: self.actor_id,
+            subject_type: self
+                .subject_type
+                .as_deref()
+                .map(str::trim)
+                .filter(|subject_type| !subject_type.is_empty())
+                .map(str::to_string),
+            since,
+            until,
+        })
+    }
+}
+
+/// Something that happened in an organization
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OrganizationActivityResponse {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    /// Who caused it; null when the system did or the user is gone
+    pub actor_id: Option<Uuid>,
+    /// `user_joined`, `member_role_changed`, `member_removed`,
+    /// `block_signed_off`, `organization_archived` or
+    /// `organization_unarchived`
+    pub kind: String,
+    /// What it concerns, e.g. `user` or `block`
+    pub subject_type: String,
+    pub subject_id: Uuid,
+    pub summary: String,
+    #[ts(type = "Record<string, unknown> | null")]
+    pub data: Option<serde_json::Value>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<Activity> for OrganizationActivityResponse {
+    fn from(activity: Activity) -> Self {
+        Self {
+            id: activity.id,
+            organization_id: activity.org_id,
+            actor_id: activity.actor_id,
+            kind: activity.kind,
+            subject_type: activity.subject_type,
+            subject_id: activity.subject_id,
+            summary: activity.summary,
+            data: activity.data,
+            created_at: activity.created_at,
+        }
+    }
+}
+
+/// Query parameters for listing the users of an organization
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListOrganizationUsersQuery {
+    /// Words each matching a user's name or email
+    pub query: Option<String>,
+    /// Comma-separated roles to include, e.g. `Manager,Operator`
+    pub role: Option<String>,
+    /// `true` for active users only, `false` for deactivated ones only
+    pub active: Option<bool>,
+    /// `true` to list removed users instead of current ones
+    pub removed: Option<bool>,
+    /// `name`, `email`, `role`, `created_at` or `relevance`, with `-` for
+    /// descending
+    pub sort: Option<String>,
+    #[ts(type = "number | null")]
     pub page: Option<i64>,
+    #[ts(type = "number | null")]
     pub per_page: Option<i64>,
 }
 
+fn invalid(field: &str, message: String) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(serde_json::json!({
+            "field": field,
+            "code": "INVALID_VALUE",
+        })),
+    )
+}
+
+/// `value` of `field` as an RFC 3339 timestamp, or a date meaning its
+/// midnight UTC
+fn instant(field: &str, value: Option<&str>) -> Result<Option<chrono::DateTime<chrono::Utc>>, ApiError> {
+    let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|value| value.with_timezone(&chrono::Utc))
+        .or_else(|_| {
+            chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|day| day.and_time(chrono::NaiveTime::MIN).and_utc())
+        })
+        .map(Some)
+        .map_err(|_| invalid(field, format!("{} is not an RFC 3339 timestamp or date", value)))
+}
+
+impl ListOrganizationUsersQuery {
+    /// The users asked for; sorted by relevance when searching and by name
+    /// otherwise, unless `sort` says
+    pub fn filter(&self) -> Result<UserFilter, ApiError> {
+        let query = self.query.as_deref().map(str::trim).filter(|query| !query.is_empty());
+        if query.is_some_and(|query| query.chars().count() > MAX_USER_QUERY_LENGTH) {
+            return Err(invalid("query", format!("Search must be at most {} characters", MAX_USER_QUERY_LENGTH)));
+        }
+
+        let roles = self
+            .role
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|role| !role.is_empty())
+            .map(|role| match role.to_uppercase().as_str() {
+                "ADMIN" => Ok(Role::Admin),
+                "MANAGER" => Ok(Role::Manager),
+                "OPERATOR" => Ok(Role::Operator),
+                _ => Err(invalid("role", format!("Unknown role {}", role))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let sort = self.sort.as_deref().map(str::trim).unwrap_or_default();
+        let descending = sort.starts_with('-');
+        let sort = match sort.trim_start_matches('-') {
+            "" if query.is_some() => UserSort::Relevance,
+            "" | "name" => UserSort::Name,
+            "email" => UserSort::Email,
+            "role" => UserSort::Role,
+            "created_at" => UserSort::CreatedAt,
+            "relevance" => UserSort::Relevance,
+            other => return Err(invalid("sort", format!("Can't sort users by {}", other))),
+        };
+
+        Ok(UserFilter {
+            query: query.map(str::to_string),
+            roles,
+            active: self.active,
+            deprovisioned: self.removed.unwrap_or(false),
+            sort,
+            descending,
+        })
+    }
+}
+
 impl From<CreateOrganizationInput> for Organization {
     fn from(input: CreateOrganizationInput) -> Self {
         Self {
@@ -43,6 +497,9 @@ impl From<CreateOrganizationInput> for Organization {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             deleted_at: None,
+            parent_id: None,
+            owner_id: None,
+            archived_at: None,
         }
     }
 }
@@ -55,6 +512,11 @@ impl From<(Uuid, UpdateOrganizationInput)> for Organization {
             created_at: chrono::Utc::now(), // Note: This should ideally preserve the original created_at
             updated_at: chrono::Utc::now(),
             deleted_at: None,
+            // Left out of the changeset, so updates keep the parent, owner
+            // and archival
+            parent_id: None,
+            owner_id: None,
+            archived_at: None,
         }
     }
 } 
\ No newline at end of file
@@ -4,15 +4,22 @@
 //! It follows RESTful principles and provides CRUD operations.
 
 use crate::{
-    api::utils::ApiResponseBuilder,
+    api::middleware::{AuthenticatedUser, Tenant},
+    api::utils::{ApiResponseBuilder, ErrorResponse},
     api::resources::organization::dto::{
         CreateOrganizationInput, OrganizationResponse, UpdateOrganizationInput,
     },
-    db::{get_connection, models::Organization, repositories::OrganizationRepositoryImpl, DbPool},
+    db::{
+        get_connection,
+        models::{auth::User, Organization},
+        repositories::{auth::UserRepositoryImpl, OrganizationRepositoryImpl, Repository},
+        DbPool,
+    },
     error::ApiError,
     domain::organization::OrganizationService,
 };
 use actix_web::{web, HttpResponse};
+use diesel::PgConnection;
 use uuid::Uuid;
 
 /// Handler context containing shared resources and dependencies
@@ -35,27 +42,65 @@ impl HandlerContext {
     }
 }
 
+/// The caller, as stored
+async fn caller(conn: &mut PgConnection, user: &AuthenticatedUser) -> Result<User, ApiError> {
+    let user_id = Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))?;
+    UserRepositoryImpl.find_by_id(conn, user_id).await
+}
+
+/// Organization of the caller
+fn caller_organization(user: &AuthenticatedUser) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(user.org_id()).map_err(|_| ApiError::unauthorized("Invalid token organization"))
+}
+
 pub mod read {
-    use crate::api::{utils::{ApiResponseBuilder, PaginatedResponse, PaginationParams}, resources::organization::dto::ListOrganizationsQuery};
+    use actix_web::http::header::ContentDisposition;
+
+    use crate::{
+        api::{
+            resources::{
+                auth::dto::UserResponse,
+                organization::dto::{
+                    ListOrganizationActivityQuery, ListOrganizationUsersQuery, ListOrganizationsQuery,
+                    OrganizationActivityResponse, OrganizationArchiveResponse, OrganizationNodeResponse,
+                    OrganizationSettingsResponse, OrganizationUsageResponse,
+                },
+            },
+            utils::{ApiResponseBuilder, ListResponse, PaginatedResponse, PaginationParams},
+        },
+        domain::{
+            activity::ActivityFeed,
+            organization::{bundle_filename, ArchiveService, MembershipService, OrganizationSettingsService, QuotaService},
+        },
+        error::{ErrorCode, ErrorContext},
+        utils::Config,
+    };
 
     use super::*;
 
     /// Retrieves a single organization by ID
+    ///
+    /// Callers see their own organization and those under it; any other
+    /// answers 404.
     /// 
     /// # OpenAPI Specification
     #[utoipa::path(
         get,
         path = "/v1/organizations/{id}",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
         responses(
             (status = 200, description = "Organization found", body = OrganizationResponse),
-            (status = 404, description = "Organization not found"),
-            (status = 500, description = "Internal server error")
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
         ),
         params(
             ("id" = Uuid, Path, description = "Organization ID")
         )
     )]
     pub async fn get_organization(
+        Tenant(tenant): Tenant,
         pool: web::Data<DbPool>,
         organization_id: web::Path<Uuid>,
     ) -> Result<HttpResponse, ApiError> {
@@ -63,7 +108,7 @@ pub mod read {
         let org_id = *organization_id;
 
         let mut conn = get_connection(&ctx.pool)?;
-        let organization = ctx.service.get(&mut conn, org_id).await?;
+        let organization = ctx.service.get(&mut conn, tenant, org_id).await?;
 
         Ok(HttpResponse::Ok().json(
             ApiResponseBuilder::success()
@@ -71,6 +116,9 @@ pub mod read {
                 .with_data(OrganizationResponse {
                     id: organization.id,
                     name: organization.name,
+                    parent_id: organization.parent_id,
+                    owner_id: organization.owner_id,
+                    archived_at: organization.archived_at,
                     created_at: organization.created_at,
                     updated_at: organization.updated_at,
                 })
@@ -78,49 +126,439 @@ pub mod read {
         ))
     }
 
-    /// Lists all organizations with pagination
+    /// Lists the caller's organization and those under it, filtered by
+    /// name and creation time and sorted
+    ///
+    /// The total counts the organizations matching the filters.
     /// 
     /// # OpenAPI Specification
     #[utoipa::path(
         get,
         path = "/v1/organizations",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
         responses(
             (status = 200, description = "List of organizations", body = PaginatedResponse<Organization>),
-            (status = 400, description = "Bad request"),
-            (status = 500, description = "Internal server error")
+            (status = 400, description = "Invalid search, timestamp or sort", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
         ),
         params(
-            ("limit" = Option<i64>, Query, description = "Number of items per page"),
-            ("offset" = Option<i64>, Query, description = "Number of items to skip")
+            ("name_contains" = Option<String>, Query, description = "Text the name has to contain, ignoring case"),
+            ("created_after" = Option<String>, Query, description = "Only organizations created after this RFC 3339 timestamp or date"),
+            ("sort" = Option<String>, Query, description = "`name`, `created_at` or `updated_at`, with `-` for descending; newest first by default"),
+            ("page" = Option<i64>, Query, description = "Number of items to skip"),
+            ("per_page" = Option<i64>, Query, description = "Number of items per page")
         )
     )]
     pub async fn list_organizations(
+        Tenant(tenant): Tenant,
         pool: web::Data<DbPool>,
         query: web::Query<ListOrganizationsQuery>,
     ) -> Result<HttpResponse, ApiError> {
         let ctx = HandlerContext::new(pool);
+        let filter = query.filter()?;
         let pagination = PaginationParams {
             page: (query.page.unwrap_or(0) / query.per_page.unwrap_or(10)) + 1,
             per_page: query.per_page.unwrap_or(10),
         };
 
         let mut conn = get_connection(&ctx.pool)?;
-        let organizations = ctx.service.list(&mut conn, &pagination).await?;
+        let (organizations, total) = ctx.service.list(&mut conn, tenant, &filter, &pagination).await?;
 
         Ok(HttpResponse::Ok().json(
             ApiResponseBuilder::success()
                 .with_message("Organizations retrieved successfully")
+                .with_data(PaginatedResponse::with_count(
+                    organizations,
+                    total,
+                    &pagination
+                ))
+                .build()
+        ))
+    }
+
+    /// Lists the users of an organization, searched, filtered and sorted
+    ///
+    /// Callers only see the users of their own organization and of the
+    /// organizations under it.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/organizations/{id}/users",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Users of the organization", body = PaginatedResponse<UserResponse>),
+            (status = 400, description = "Invalid search, role or sort", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Another organization", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID"),
+            ("query" = Option<String>, Query, description = "Words each matching a name or email, by substring or similarity"),
+            ("role" = Option<String>, Query, description = "Comma-separated roles, e.g. `Manager,Operator`"),
+            ("active" = Option<bool>, Query, description = "`true` lists active users only, `false` deactivated ones only"),
+            ("removed" = Option<bool>, Query, description = "`true` lists removed users instead of current ones"),
+            ("sort" = Option<String>, Query, description = "`name`, `email`, `role`, `created_at` or `relevance`, with `-` for descending; relevance when searching, name otherwise"),
+            ("page" = Option<i64>, Query, description = "Page number"),
+            ("per_page" = Option<i64>, Query, description = "Number of items per page, 20 by default")
+        )
+    )]
+    pub async fn list_organization_users(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+        query: web::Query<ListOrganizationUsersQuery>,
+    ) -> Result<HttpResponse, ApiError> {
+        let org_id = *organization_id;
+        let filter = query.filter()?;
+        let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+
+        let ctx = HandlerContext::new(pool);
+        let mut conn = get_connection(&ctx.pool)?;
+        if !ctx.service.governs(&mut conn, caller_organization(&user)?, org_id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Users of other organizations can't be listed",
+                ErrorContext::new(),
+            ));
+        }
+        let (users, total) = ctx.service.list_users(&mut conn, org_id, &filter, &pagination).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Users retrieved successfully")
+                .with_data(PaginatedResponse::new(
+                    users.into_iter().map(|user| UserResponse::new(user, &config)).collect(),
+                    total,
+                    &pagination
+                ))
+                .build()
+        ))
+    }
+
+    /// Lists an organization and the divisions and areas under it, parents
+    /// before their children
+    ///
+    /// Callers see the hierarchy from their own organization down.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/organizations/{id}/tree",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "The organization and those under it", body = ListResponse<OrganizationNodeResponse>),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn get_organization_tree(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let ctx = HandlerContext::new(pool);
+        let mut conn = get_connection(&ctx.pool)?;
+        let tree = ctx.service.tree(&mut conn, caller_organization(&user)?, *organization_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Organization tree retrieved successfully")
+                .with_data(tree.into_iter().map(OrganizationNodeResponse::from).collect::<ListResponse<_>>())
+                .build()
+        ))
+    }
+
+    /// Retrieves the settings of an organization
+    ///
+    /// Members read the settings of their organization and of those under
+    /// it; organizations that never saved settings get the defaults.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/organizations/{id}/settings",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Settings of the organization", body = OrganizationSettingsResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn get_organization_settings(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let viewer = caller(&mut conn, &user).await?;
+        let settings = OrganizationSettingsService::get(&mut conn, &viewer, *organization_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Organization settings retrieved successfully")
+                .with_data(OrganizationSettingsResponse::from(settings))
+                .build()
+        ))
+    }
+
+    /// Reports an organization's usage against the limits of its plan
+    ///
+    /// Members see the usage of their organization and of those under it.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/organizations/{id}/usage",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Usage and quotas", body = OrganizationUsageResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn get_organization_usage(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let viewer = caller(&mut conn, &user).await?;
+        let usage = QuotaService::usage(&mut conn, &viewer, *organization_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Usage retrieved successfully")
+                .with_data(OrganizationUsageResponse::from(usage))
+                .build()
+        ))
+    }
+
+    /// Lists the members of an organization, searched, filtered and sorted
+    ///
+    /// Managers and admins see the members of their own organization and
+    /// of the organizations under it.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/organizations/{id}/members",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Members of the organization", body = PaginatedResponse<UserResponse>),
+            (status = 400, description = "Invalid search, role or sort", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Manager role required, or not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID"),
+            ("query" = Option<String>, Query, description = "Words each matching a name or email, by substring or similarity"),
+            ("role" = Option<String>, Query, description = "Comma-separated roles, e.g. `Manager,Operator`"),
+            ("active" = Option<bool>, Query, description = "`true` lists active members only, `false` deactivated ones only"),
+            ("removed" = Option<bool>, Query, description = "`true` lists removed members instead of current ones"),
+            ("sort" = Option<String>, Query, description = "`name`, `email`, `role`, `created_at` or `relevance`, with `-` for descending; relevance when searching, name otherwise"),
+            ("page" = Option<i64>, Query, description = "Page number"),
+            ("per_page" = Option<i64>, Query, description = "Number of items per page, 20 by default")
+        )
+    )]
+    pub async fn list_organization_members(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+        query: web::Query<ListOrganizationUsersQuery>,
+    ) -> Result<HttpResponse, ApiError> {
+        let filter = query.filter()?;
+        let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+
+        let mut conn = get_connection(&pool)?;
+        let manager = caller(&mut conn, &user).await?;
+        let (members, total) = MembershipService::list(&mut conn, &manager, *organization_id, &filter, &pagination).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Members retrieved successfully")
                 .with_data(PaginatedResponse::new(
-                    organizations.clone(),
-                    organizations.len() as i64,
+                    members.into_iter().map(|member| UserResponse::new(member, &config)).collect(),
+                    total,
                     &pagination
                 ))
                 .build()
         ))
     }
+
+    /// Lists what happened in an organization, such as users joining and blocks signed off, newest first
+    ///
+    /// Managers and admins follow their own organization and the
+    /// organizations under it.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/organizations/{id}/activity",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Activity of the organization", body = PaginatedResponse<OrganizationActivityResponse>),
+            (status = 400, description = "Invalid kind or period", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Manager role required, or not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID"),
+            ("kind" = Option<String>, Query, description = "Comma-separated kinds, e.g. `user_joined,member_removed`"),
+            ("actor_id" = Option<Uuid>, Query, description = "Only what this user did"),
+            ("subject_type" = Option<String>, Query, description = "Only what concerns this type of subject, e.g. `user` or `block`"),
+            ("since" = Option<String>, Query, description = "Only activity from this RFC 3339 timestamp or date on"),
+            ("until" = Option<String>, Query, description = "Only activity before this RFC 3339 timestamp or date"),
+            ("page" = Option<i64>, Query, description = "Page number"),
+            ("per_page" = Option<i64>, Query, description = "Number of items per page, 20 by default")
+        )
+    )]
+    pub async fn list_organization_activity(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        organization_id: web::Path<Uuid>,
+        query: web::Query<ListOrganizationActivityQuery>,
+    ) -> Result<HttpResponse, ApiError> {
+        let filter = query.filter()?;
+        let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+
+        let mut conn = get_connection(&pool)?;
+        let manager = caller(&mut conn, &user).await?;
+        let (activities, total) = ActivityFeed::list(&mut conn, &manager, *organization_id, &filter, &pagination).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Activity retrieved successfully")
+                .with_data(PaginatedResponse::new(
+                    activities.into_iter().map(OrganizationActivityResponse::from).collect(),
+                    total,
+                    &pagination
+                ))
+                .build()
+        ))
+    }
+
+    /// Shows the most recent archival of an organization
+    ///
+    /// Admins see the archival of their organization and of those under
+    /// it, including whether its export bundle is written.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/organizations/{id}/archive",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Most recent archival", body = OrganizationArchiveResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required, or not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization not found or never archived", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn get_organization_archive(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let viewer = caller(&mut conn, &user).await?;
+        let archive = ArchiveService::latest(&mut conn, &viewer, *organization_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Organization archive retrieved successfully")
+                .with_data(OrganizationArchiveResponse::from(archive))
+                .build()
+        ))
+    }
+
+    /// Downloads the export bundle of an organization's most recent archival
+    ///
+    /// The bundle is gzipped JSON holding every row of the organization's
+    /// data, and of those under it, keyed by table.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        get,
+        path = "/v1/organizations/{id}/archive/export",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "The gzipped export bundle", body = Vec<u8>, content_type = "application/gzip"),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required, or not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization not found or never archived", body = ErrorResponse),
+            (status = 409, description = "The export bundle is still being written", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn download_organization_export(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let admin = caller(&mut conn, &user).await?;
+        let (archive, bundle) = ArchiveService::bundle(&mut conn, config.storage(), &admin, *organization_id).await?;
+
+        Ok(HttpResponse::Ok()
+            .content_type("application/gzip")
+            .insert_header(ContentDisposition::attachment(bundle_filename(&archive)))
+            .body(bundle))
+    }
 }
 
 pub mod create {
+    use actix_web::{http::header, HttpRequest};
+    use serde_json::json;
+
+    use crate::{
+        api::utils::multipart,
+        domain::user::{UserImportReport, UserImportService},
+        error::{ErrorCode, ErrorContext},
+        utils::Config,
+    };
+
     use super::*;
 
     /// Creates a new organization
@@ -129,12 +567,13 @@ pub mod create {
     #[utoipa::path(
         post,
         path = "/v1/organizations",
+        tag = "organizations",
         request_body = CreateOrganizationInput,
         responses(
             (status = 201, description = "Organization created", body = OrganizationResponse),
-            (status = 400, description = "Bad request"),
-            (status = 409, description = "Organization already exists"),
-            (status = 500, description = "Internal server error")
+            (status = 400, description = "Bad request", body = ErrorResponse),
+            (status = 409, description = "Organization already exists", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
         )
     )]
     pub async fn create_organization(
@@ -153,35 +592,197 @@ pub mod create {
                 .with_data(OrganizationResponse {
                     id: organization.id,
                     name: organization.name,
+                    parent_id: organization.parent_id,
+                    owner_id: organization.owner_id,
+                    archived_at: organization.archived_at,
                     created_at: organization.created_at,
                     updated_at: organization.updated_at,
                 })
                 .build()
         ))
     }
+
+    /// Creates a division or operating area under an organization
+    ///
+    /// The parent must be the caller's organization or one under it.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/organizations/{id}/children",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        request_body = CreateOrganizationInput,
+        responses(
+            (status = 201, description = "Organization created", body = OrganizationResponse),
+            (status = 400, description = "Invalid or taken name, or nested too deep", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required, or not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Parent organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Parent organization ID")
+        )
+    )]
+    pub async fn create_child_organization(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        organization_id: web::Path<Uuid>,
+        new_organization: web::Json<CreateOrganizationInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        let ctx = HandlerContext::new(pool);
+        let mut conn = get_connection(&ctx.pool)?;
+        let organization = ctx
+            .service
+            .create_child(&mut conn, caller_organization(&user)?, *organization_id, new_organization.into_inner())
+            .await?;
+
+        Ok(HttpResponse::Created().json(
+            ApiResponseBuilder::success()
+                .with_message("Organization created successfully")
+                .with_data(OrganizationResponse {
+                    id: organization.id,
+                    name: organization.name,
+                    parent_id: organization.parent_id,
+                    owner_id: organization.owner_id,
+                    archived_at: organization.archived_at,
+                    created_at: organization.created_at,
+                    updated_at: organization.updated_at,
+                })
+                .build()
+        ))
+    }
+
+    /// The CSV in a user import: the `file` part of a form, else its first
+    /// file, or the body itself
+    fn user_import_file(req: &HttpRequest, body: web::Bytes) -> Result<web::Bytes, ApiError> {
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if !content_type.trim_start().to_ascii_lowercase().starts_with("multipart/form-data") {
+            return Ok(body);
+        }
+
+        let parts = multipart::parse(content_type, &body)?;
+        parts
+            .iter()
+            .find(|part| part.name.as_deref() == Some("file"))
+            .or_else(|| parts.iter().find(|part| part.filename.is_some()))
+            .map(|part| part.body.clone())
+            .ok_or_else(|| {
+                ApiError::validation_with_context(
+                    "Form has no file",
+                    ErrorContext::new().with_details(json!({
+                        "field": "file",
+                        "code": "REQUIRED",
+                    })),
+                )
+            })
+    }
+
+    /// Creates users in an organization from a CSV file and mails each an
+    /// invitation to set their password
+    ///
+    /// The file needs `email`, `first_name` and `last_name` columns and may
+    /// have `phone_number` and `role` ones; role defaults to `Operator`.
+    /// Rows are imported or rejected one by one, and the report says which.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/organizations/{id}/users/import",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        request_body(content = String, content_type = "text/csv", description = "CSV of users, or a `multipart/form-data` form with it as `file`"),
+        responses(
+            (status = 200, description = "What became of each row", body = UserImportReport),
+            (status = 400, description = "Unreadable file, missing columns or too many rows", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Manager role required, or another organization", body = ErrorResponse),
+            (status = 413, description = "File too large", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn import_organization_users(
+        req: HttpRequest,
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+        body: web::Bytes,
+    ) -> Result<HttpResponse, ApiError> {
+        if Uuid::parse_str(user.org_id()).ok() != Some(*organization_id) {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Users can't be imported into other organizations",
+                ErrorContext::new(),
+            ));
+        }
+        let csv = user_import_file(&req, body)?;
+        let user_id = Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))?;
+
+        let mut conn = get_connection(&pool)?;
+        let inviter = UserRepositoryImpl.find_by_id(&mut conn, user_id).await?;
+        let report = UserImportService::import(&mut conn, &config, &inviter, &csv).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Users imported")
+                .with_data(report)
+                .build()
+        ))
+    }
 }
 
 pub mod update {
+    use crate::{
+        api::resources::{
+            auth::dto::UserResponse,
+            organization::dto::{
+                ChangeMemberRoleInput, OrganizationArchiveResponse, OrganizationSettingsResponse,
+                OwnershipTransferResponse, TransferOwnershipInput, UpdateOrganizationSettingsInput,
+            },
+        },
+        domain::{
+            auth::RevocationList,
+            organization::{ArchiveService, MembershipService, OrganizationSettingsService},
+        },
+        utils::Config,
+    };
+
     use super::*;
 
     /// Updates an existing organization
+    ///
+    /// Callers update their own organization and those under it; any
+    /// other answers 404.
     /// 
     /// # OpenAPI Specification
     #[utoipa::path(
         put,
         path = "/v1/organizations/{id}",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
         request_body = UpdateOrganizationInput,
         responses(
             (status = 200, description = "Organization updated", body = OrganizationResponse),
-            (status = 400, description = "Bad request"),
-            (status = 404, description = "Organization not found"),
-            (status = 500, description = "Internal server error")
+            (status = 400, description = "Bad request", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
         ),
         params(
             ("id" = Uuid, Path, description = "Organization ID")
         )
     )]
     pub async fn update_organization(
+        Tenant(tenant): Tenant,
         pool: web::Data<DbPool>,
         organization_id: web::Path<Uuid>,
         updated_organization: web::Json<UpdateOrganizationInput>,
@@ -191,7 +792,7 @@ pub mod update {
         let input = updated_organization.into_inner();
 
         let mut conn = get_connection(&ctx.pool)?;
-        let organization = ctx.service.update(&mut conn, org_id, input).await?;
+        let organization = ctx.service.update(&mut conn, tenant, org_id, input).await?;
 
         Ok(HttpResponse::Ok().json(
             ApiResponseBuilder::success()
@@ -199,33 +800,364 @@ pub mod update {
                 .with_data(OrganizationResponse {
                     id: organization.id,
                     name: organization.name,
+                    parent_id: organization.parent_id,
+                    owner_id: organization.owner_id,
+                    archived_at: organization.archived_at,
                     created_at: organization.created_at,
                     updated_at: organization.updated_at,
                 })
                 .build()
         ))
     }
+
+    /// Restores a soft-deleted organization
+    ///
+    /// Admins restore their own organization and those under it; any
+    /// other answers 404.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/organizations/{id}/restore",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Organization restored", body = OrganizationResponse),
+            (status = 400, description = "A live organization has the name", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 409, description = "Not deleted, or the organization above it is deleted", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn restore_organization(
+        Tenant(tenant): Tenant,
+        pool: web::Data<DbPool>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let ctx = HandlerContext::new(pool);
+
+        let mut conn = get_connection(&ctx.pool)?;
+        let organization = ctx.service.restore(&mut conn, tenant, *organization_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Organization restored successfully")
+                .with_data(OrganizationResponse {
+                    id: organization.id,
+                    name: organization.name,
+                    parent_id: organization.parent_id,
+                    owner_id: organization.owner_id,
+                    archived_at: organization.archived_at,
+                    created_at: organization.created_at,
+                    updated_at: organization.updated_at,
+                })
+                .build()
+        ))
+    }
+
+    /// Changes the settings of an organization
+    ///
+    /// Admins change the settings of their organization and of those under
+    /// it; fields left out keep their value.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        patch,
+        path = "/v1/organizations/{id}/settings",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        request_body = UpdateOrganizationSettingsInput,
+        responses(
+            (status = 200, description = "Settings updated", body = OrganizationSettingsResponse),
+            (status = 400, description = "Invalid month, timezone or safety forms", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required, or not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn update_organization_settings(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        organization_id: web::Path<Uuid>,
+        input: web::Json<UpdateOrganizationSettingsInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let admin = caller(&mut conn, &user).await?;
+        let settings =
+            OrganizationSettingsService::update(&mut conn, &admin, *organization_id, input.into_inner().into()).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Organization settings updated successfully")
+                .with_data(OrganizationSettingsResponse::from(settings))
+                .build()
+        ))
+    }
+
+    /// Offers an organization to another of its members
+    ///
+    /// Only the owner, or any admin while no owner is recorded, can start a
+    /// transfer. Nothing changes hands until the new owner accepts; starting
+    /// another transfer cancels the pending one.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/organizations/{id}/transfer-ownership",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        request_body = TransferOwnershipInput,
+        responses(
+            (status = 202, description = "Transfer awaiting the new owner's confirmation", body = OwnershipTransferResponse),
+            (status = 400, description = "New owner is the caller, not a member, deactivated or unverified", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required, or not the organization's owner", body = ErrorResponse),
+            (status = 404, description = "Organization or new owner not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn transfer_ownership(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        organization_id: web::Path<Uuid>,
+        input: web::Json<TransferOwnershipInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        let ctx = HandlerContext::new(pool);
+        let mut conn = get_connection(&ctx.pool)?;
+        let owner = caller(&mut conn, &user).await?;
+        let transfer = ctx
+            .service
+            .start_transfer(&mut conn, &owner, *organization_id, input.into_inner().new_owner_id)
+            .await?;
+
+        Ok(HttpResponse::Accepted().json(
+            ApiResponseBuilder::success()
+                .with_message("Ownership transfer awaits the new owner's confirmation")
+                .with_data(OwnershipTransferResponse::from(transfer))
+                .build()
+        ))
+    }
+
+    /// Accepts the transfer of an organization to the caller
+    ///
+    /// The caller becomes the owning admin and the previous owner a manager,
+    /// together; both sign in again to pick up their new role.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/organizations/{id}/transfer-ownership/accept",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Ownership transferred", body = OrganizationResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 404, description = "No pending transfer to the caller", body = ErrorResponse),
+            (status = 409, description = "Organization changed hands since the transfer was started", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn accept_ownership_transfer(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let ctx = HandlerContext::new(pool);
+        let mut conn = get_connection(&ctx.pool)?;
+        let new_owner = caller(&mut conn, &user).await?;
+        let (organization, transfer) = ctx.service.accept_transfer(&mut conn, &new_owner, *organization_id).await?;
+        // Access tokens carry the role, so those issued before the change go
+        RevocationList::revoke_user(&config, transfer.from_user_id).await;
+        RevocationList::revoke_user(&config, transfer.to_user_id).await;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Ownership transferred successfully")
+                .with_data(OrganizationResponse {
+                    id: organization.id,
+                    name: organization.name,
+                    parent_id: organization.parent_id,
+                    owner_id: organization.owner_id,
+                    archived_at: organization.archived_at,
+                    created_at: organization.created_at,
+                    updated_at: organization.updated_at,
+                })
+                .build()
+        ))
+    }
+
+    /// Changes the role of a member of an organization
+    ///
+    /// Only admins make members admins or change the role of admins. The
+    /// last active admin and the owner stay admins. The member signs in
+    /// again to pick up the new role.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        patch,
+        path = "/v1/organizations/{id}/members/{user_id}",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        request_body = ChangeMemberRoleInput,
+        responses(
+            (status = 200, description = "Role changed", body = UserResponse),
+            (status = 400, description = "Unknown role", body = ErrorResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Manager role required, admin role required for admins, or not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization or member not found", body = ErrorResponse),
+            (status = 409, description = "The member is the last admin or the owner", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID"),
+            ("user_id" = Uuid, Path, description = "Member's user ID")
+        )
+    )]
+    pub async fn change_member_role(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        path: web::Path<(Uuid, Uuid)>,
+        input: web::Json<ChangeMemberRoleInput>,
+    ) -> Result<HttpResponse, ApiError> {
+        let (org_id, user_id) = path.into_inner();
+        let mut conn = get_connection(&pool)?;
+        let manager = caller(&mut conn, &user).await?;
+        let member = MembershipService::change_role(&mut conn, &config, &manager, org_id, user_id, input.role).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Member role changed successfully")
+                .with_data(UserResponse::new(member, &config))
+                .build()
+        ))
+    }
+
+    /// Archives an organization with those under it
+    ///
+    /// Archived organizations keep their data and their members can still
+    /// sign in and read, but every write is rejected until the organization
+    /// is unarchived. An export bundle of all its data is written to object
+    /// storage in the background.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/organizations/{id}/archive",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 202, description = "Organization archived, export bundle queued", body = OrganizationArchiveResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required, or not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 409, description = "The organization is already archived", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn archive_organization(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let admin = caller(&mut conn, &user).await?;
+        let archive = ArchiveService::archive(&mut conn, &config.queue, &admin, *organization_id).await?;
+
+        Ok(HttpResponse::Accepted().json(
+            ApiResponseBuilder::success()
+                .with_message("Organization archived, the export bundle is being written")
+                .with_data(OrganizationArchiveResponse::from(archive))
+                .build()
+        ))
+    }
+
+    /// Reopens an archived organization with those archived along with it
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        post,
+        path = "/v1/organizations/{id}/unarchive",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 200, description = "Organization reopened", body = OrganizationArchiveResponse),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Admin role required, or not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 409, description = "Not archived, or archived with the organization above it", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID")
+        )
+    )]
+    pub async fn unarchive_organization(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        organization_id: web::Path<Uuid>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut conn = get_connection(&pool)?;
+        let admin = caller(&mut conn, &user).await?;
+        let archive = ArchiveService::unarchive(&mut conn, &admin, *organization_id).await?;
+
+        Ok(HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Organization unarchived successfully")
+                .with_data(OrganizationArchiveResponse::from(archive))
+                .build()
+        ))
+    }
 }
 
 pub mod delete {
+    use crate::{domain::organization::MembershipService, utils::Config};
+
     use super::*;
 
     /// Soft deletes an organization
+    ///
+    /// Callers delete their own organization and those under it; any
+    /// other answers 404.
     /// 
     /// # OpenAPI Specification
     #[utoipa::path(
         delete,
         path = "/v1/organizations/{id}",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
         responses(
             (status = 204, description = "Organization deleted"),
-            (status = 404, description = "Organization not found"),
-            (status = 500, description = "Internal server error")
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 404, description = "Organization not found", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
         ),
         params(
             ("id" = Uuid, Path, description = "Organization ID")
         )
     )]
     pub async fn delete_organization(
+        Tenant(tenant): Tenant,
         pool: web::Data<DbPool>,
         organization_id: web::Path<Uuid>,
     ) -> Result<HttpResponse, ApiError> {
@@ -233,7 +1165,45 @@ pub mod delete {
         let org_id = *organization_id;
 
         let mut conn = get_connection(&ctx.pool)?;
-        ctx.service.delete(&mut conn, org_id).await?;
+        ctx.service.delete(&mut conn, tenant, org_id).await?;
+
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    /// Removes a member from an organization
+    ///
+    /// The member's sessions end at once. Only admins remove admins; the
+    /// last active admin and the owner can't be removed.
+    ///
+    /// # OpenAPI Specification
+    #[utoipa::path(
+        delete,
+        path = "/v1/organizations/{id}/members/{user_id}",
+        tag = "organizations",
+        security(("bearer_auth" = [])),
+        responses(
+            (status = 204, description = "Member removed"),
+            (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+            (status = 403, description = "Manager role required, admin role required for admins, or not the caller's organization nor under it", body = ErrorResponse),
+            (status = 404, description = "Organization or member not found", body = ErrorResponse),
+            (status = 409, description = "The member is the last admin or the owner", body = ErrorResponse),
+            (status = 500, description = "Internal server error", body = ErrorResponse)
+        ),
+        params(
+            ("id" = Uuid, Path, description = "Organization ID"),
+            ("user_id" = Uuid, Path, description = "Member's user ID")
+        )
+    )]
+    pub async fn remove_member(
+        user: AuthenticatedUser,
+        pool: web::Data<DbPool>,
+        config: web::Data<Config>,
+        path: web::Path<(Uuid, Uuid)>,
+    ) -> Result<HttpResponse, ApiError> {
+        let (org_id, user_id) = path.into_inner();
+        let mut conn = get_connection(&pool)?;
+        let manager = caller(&mut conn, &user).await?;
+        MembershipService::remove(&mut conn, &config, &manager, org_id, user_id).await?;
 
         Ok(HttpResponse::NoContent().finish())
     }
@@ -1,8 +1,21 @@
 use actix_web::web;
-use crate::api::middleware::auth::{Auth, RequireAuth};
+use crate::{
+    api::middleware::auth::{Auth, RequireAuth, RequireRole},
+    db::models::auth::Role,
+    domain::user::MAX_USER_IMPORT_BYTES,
+};
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.route("/organizations", web::post().to(crate::api::resources::organization::handlers::create::create_organization))
+        // Outside the scope below, whose authentication rejects writes to
+        // archived organizations
+        .service(
+            web::resource("/organizations/{id}/unarchive")
+                .wrap(RequireRole::new(Role::Admin))
+                .wrap(RequireAuth)
+                .wrap(Auth::new().allow_archived())
+                .route(web::post().to(crate::api::resources::organization::handlers::update::unarchive_organization))
+        )
         .service(
             web::scope("/organizations")
                 .wrap(RequireAuth)
@@ -11,5 +24,59 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
                 .route("/{id}", web::get().to(crate::api::resources::organization::handlers::read::get_organization))
                 .route("/{id}", web::put().to(crate::api::resources::organization::handlers::update::update_organization))
                 .route("/{id}", web::delete().to(crate::api::resources::organization::handlers::delete::delete_organization))
+                .route("/{id}/settings", web::get().to(crate::api::resources::organization::handlers::read::get_organization_settings))
+                .route("/{id}/settings", web::patch().to(crate::api::resources::organization::handlers::update::update_organization_settings))
+                .route("/{id}/usage", web::get().to(crate::api::resources::organization::handlers::read::get_organization_usage))
+                .route("/{id}/tree", web::get().to(crate::api::resources::organization::handlers::read::get_organization_tree))
+                .service(
+                    web::resource("/{id}/activity")
+                        .wrap(RequireRole::new(Role::Manager))
+                        .route(web::get().to(crate::api::resources::organization::handlers::read::list_organization_activity))
+                )
+                .service(
+                    web::resource("/{id}/archive")
+                        .wrap(RequireRole::new(Role::Admin))
+                        .route(web::get().to(crate::api::resources::organization::handlers::read::get_organization_archive))
+                        .route(web::post().to(crate::api::resources::organization::handlers::update::archive_organization))
+                )
+                .service(
+                    web::resource("/{id}/archive/export")
+                        .wrap(RequireRole::new(Role::Admin))
+                        .route(web::get().to(crate::api::resources::organization::handlers::read::download_organization_export))
+                )
+                .service(
+                    web::resource("/{id}/restore")
+                        .wrap(RequireRole::new(Role::Admin))
+                        .route(web::post().to(crate::api::resources::organization::handlers::update::restore_organization))
+                )
+                .service(
+                    web::resource("/{id}/children")
+                        .wrap(RequireRole::new(Role::Admin))
+                        .route(web::post().to(crate::api::resources::organization::handlers::create::create_child_organization))
+                )
+                .service(
+                    web::resource("/{id}/transfer-ownership")
+                        .wrap(RequireRole::new(Role::Admin))
+                        .route(web::post().to(crate::api::resources::organization::handlers::update::transfer_ownership))
+                )
+                .route("/{id}/transfer-ownership/accept", web::post().to(crate::api::resources::organization::handlers::update::accept_ownership_transfer))
+                .service(
+                    web::resource("/{id}/members")
+                        .wrap(RequireRole::new(Role::Manager))
+                        .route(web::get().to(crate::api::resources::organization::handlers::read::list_organization_members))
+                )
+                .service(
+                    web::resource("/{id}/members/{user_id}")
+                        .wrap(RequireRole::new(Role::Manager))
+                        .route(web::patch().to(crate::api::resources::organization::handlers::update::change_member_role))
+                        .route(web::delete().to(crate::api::resources::organization::handlers::delete::remove_member))
+                )
+                .route("/{id}/users", web::get().to(crate::api::resources::organization::handlers::read::list_organization_users))
+                .service(
+                    web::resource("/{id}/users/import")
+                        .wrap(RequireRole::new(Role::Manager))
+                        .app_data(web::PayloadConfig::new(MAX_USER_IMPORT_BYTES))
+                        .route(web::post().to(crate::api::resources::organization::handlers::create::import_organization_users))
+                )
         );
 } 
\ No newline at end of file
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::db::models::auth::{Device, Role, User};
+
+/// Heartbeat a field app sends while it runs
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[serde(try_from = "Map<String, Value>")]
+#[ts(export)]
+pub struct HeartbeatInput {
+    /// ID the app generated for itself, as sent on login
+    pub device_id: String,
+    /// Names or renames the device
+    #[ts(optional)]
+    pub device_name: Option<String>,
+    /// e.g. `2.4.1`
+    #[ts(optional)]
+    pub app_version: Option<String>,
+    /// Battery charge in percent, 0 to 100
+    #[ts(optional)]
+    pub battery_level: Option<i16>,
+}
+
+/// The fields of a heartbeat, as sent
+#[derive(Deserialize)]
+struct HeartbeatFields {
+    device_id: String,
+    #[serde(default)]
+    device_name: Option<String>,
+    #[serde(default)]
+    app_version: Option<String>,
+    #[serde(default)]
+    battery_level: Option<i16>,
+}
+
+// Only an object is a heartbeat: serde would otherwise read an array of
+// strings as the fields in order
+impl TryFrom<Map<String, Value>> for HeartbeatInput {
+    type Error = serde_json::Error;
+
+    fn try_from(fields: Map<String, Value>) -> Result<Self, Self::Error> {
+        let fields: HeartbeatFields = serde_json::from_value(Value::Object(fields))?;
+        Ok(Self {
+            device_id: fields.device_id,
+            device_name: fields.device_name,
+            app_version: fields.app_version,
+            battery_level: fields.battery_level,
+        })
+    }
+}
+
+/// Query parameters for listing the devices online
+#[derive(Debug, Default, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OnlineQuery {
+    /// Minutes since the last heartbeat a device counts as online, 5 when
+    /// unset
+    #[ts(type = "number | null")]
+    pub minutes: Option<i64>,
+}
+
+/// A device online in the field, with its user
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct OnlineDeviceResponse {
+    pub user_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub role: Role,
+    pub id: Uuid,
+    /// ID the app generated for itself
+    pub device_id: String,
+    pub device_name: Option<String>,
+    pub app_version: Option<String>,
+    /// Battery charge in percent, 0 to 100
+    pub battery_level: Option<i16>,
+    pub last_heartbeat_at: Option<DateTime<Utc>>,
+}
+
+impl From<(Device, User)> for OnlineDeviceResponse {
+    fn from((device, user): (Device, User)) -> Self {
+        Self {
+            user_id: user.id,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            role: user.role,
+            id: device.id,
+            device_id: device.device_id,
+            device_name: device.name,
+            app_version: device.app_version,
+            battery_level: device.battery_level,
+            last_heartbeat_at: device.last_heartbeat_at,
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Presence resource handlers
+//!
+//! Field apps of any user send heartbeats; listing who is online is for
+//! managers and above, dispatching crews of the caller's organization.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::presence::dto::{HeartbeatInput, OnlineDeviceResponse, OnlineQuery},
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{get_connection, DbPool},
+    domain::presence::{PresenceService, ONLINE_MINUTES},
+    error::ApiError,
+};
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use uuid::Uuid;
+
+fn user_id(user: &AuthenticatedUser) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))
+}
+
+/// Records that the caller's device is online
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/presence/heartbeat",
+    security(("bearer_auth" = [])),
+    tag = "presence",
+    request_body = HeartbeatInput,
+    responses(
+        (status = 204, description = "Heartbeat recorded"),
+        (status = 400, description = "Invalid device, app version or battery level", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn heartbeat(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    input: web::Json<HeartbeatInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    PresenceService::heartbeat(
+        &mut conn,
+        user_id(&user)?,
+        &input.device_id,
+        input.device_name.as_deref(),
+        input.app_version.as_deref(),
+        input.battery_level,
+    )
+    .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Lists the devices of the organization online in the field, most recent heartbeat first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/presence",
+    security(("bearer_auth" = [])),
+    tag = "presence",
+    responses(
+        (status = 200, description = "Devices online", body = ListResponse<OnlineDeviceResponse>),
+        (status = 400, description = "Minutes out of range", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("minutes" = Option<i64>, Query, description = "Minutes since the last heartbeat a device counts as online, 5 when unset, up to 1440")
+    )
+)]
+pub async fn list_online(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    query: web::Query<OnlineQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let online = PresenceService::online(&mut conn, org_id, Utc::now(), query.minutes.unwrap_or(ONLINE_MINUTES)).await?;
+    let online = online.into_iter().map(OnlineDeviceResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Devices online retrieved successfully")
+            .with_data(online)
+            .build()
+    ))
+}
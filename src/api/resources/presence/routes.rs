@@ -0,0 +1,18 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequireRole},
+    db::models::auth::Role,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/presence")
+            .wrap(Auth::new())
+            .route("/heartbeat", web::post().to(crate::api::resources::presence::handlers::heartbeat))
+            .service(
+                web::resource("")
+                    .wrap(RequireRole::new(Role::Manager))
+                    .route(web::get().to(crate::api::resources::presence::handlers::list_online))
+            )
+    );
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    db::models::{Report, ReportDelivery, ReportSchedule},
+    domain::report::{ReportDefinition, ReportFormat},
+    error::{ApiError, Result},
+};
+
+/// Input for saving a report, also used to replace one
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct SaveReportInput {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    pub definition: ReportDefinition,
+}
+
+/// Input for running a saved report
+#[derive(Debug, Default, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct RunReportInput {
+    /// Output format, JSON when unset
+    #[serde(default)]
+    pub format: Option<ReportFormat>,
+    /// Values of the report's parameters, by name
+    #[serde(default)]
+    pub parameters: HashMap<String, serde_json::Value>,
+}
+
+/// Query parameters for listing reports
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListReportsQuery {
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// Report response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ReportResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub definition: ReportDefinition,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<Report> for ReportResponse {
+    type Error = ApiError;
+
+    fn try_from(report: Report) -> Result<Self> {
+        Ok(Self {
+            definition: ReportDefinition::from_stored(&report)?,
+            id: report.id,
+            name: report.name,
+            description: report.description,
+            created_by: report.created_by,
+            created_at: report.created_at,
+            updated_at: report.updated_at,
+        })
+    }
+}
+
+/// Input for scheduling a report's delivery by email, replacing any
+/// existing schedule
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct ScheduleReportInput {
+    /// Cron expression (`sec min hour day month weekday`, UTC), e.g.
+    /// `0 0 6 * * Mon` for Mondays at 06:00
+    #[validate(length(min = 1, max = 255))]
+    pub schedule: String,
+    /// Email addresses the report is sent to
+    #[validate(length(min = 1, max = 50))]
+    pub recipients: Vec<String>,
+    /// Format of the delivered file, JSON when unset
+    #[serde(default)]
+    pub format: Option<ReportFormat>,
+    /// Values of the report's parameters, by name
+    #[serde(default)]
+    pub parameters: HashMap<String, serde_json::Value>,
+}
+
+/// Report schedule response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ReportScheduleResponse {
+    pub report_id: Uuid,
+    pub schedule: String,
+    pub recipients: Vec<String>,
+    pub format: ReportFormat,
+    pub parameters: HashMap<String, serde_json::Value>,
+    pub next_run_at: DateTime<Utc>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ReportSchedule> for ReportScheduleResponse {
+    fn from(schedule: ReportSchedule) -> Self {
+        Self {
+            report_id: schedule.report_id,
+            format: ReportFormat::parse(&schedule.format).unwrap_or_default(),
+            parameters: serde_json::from_value(schedule.parameters).unwrap_or_default(),
+            schedule: schedule.schedule,
+            recipients: schedule.recipients,
+            next_run_at: schedule.next_run_at,
+            created_by: schedule.created_by,
+            created_at: schedule.created_at,
+            updated_at: schedule.updated_at,
+        }
+    }
+}
+
+/// One scheduled run of a report
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ReportDeliveryResponse {
+    pub id: Uuid,
+    pub report_id: Uuid,
+    pub recipients: Vec<String>,
+    pub format: ReportFormat,
+    /// `queued` once the mail is queued for every recipient, `failed` when
+    /// the report could not be run
+    pub status: String,
+    pub row_count: Option<i32>,
+    /// Whether the output was too large to attach and is downloaded from
+    /// `/v1/reports/{id}/deliveries/{delivery_id}/file` instead
+    pub linked: bool,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ReportDelivery> for ReportDeliveryResponse {
+    fn from(delivery: ReportDelivery) -> Self {
+        Self {
+            id: delivery.id,
+            report_id: delivery.report_id,
+            format: ReportFormat::parse(&delivery.format).unwrap_or_default(),
+            recipients: delivery.recipients,
+            status: delivery.status,
+            row_count: delivery.row_count,
+            linked: delivery.file_key.is_some(),
+            error: delivery.error,
+            created_at: delivery.created_at,
+        }
+    }
+}
@@ -0,0 +1,470 @@
+//! Report resource handlers
+//!
+//! Every handler works on the reports of the authenticated user's
+//! organization. Routes require the manager role.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::report::dto::{
+            ListReportsQuery, ReportDeliveryResponse, ReportResponse, ReportScheduleResponse, RunReportInput,
+            SaveReportInput, ScheduleReportInput,
+        },
+        utils::{ApiResponseBuilder, ErrorResponse, PaginatedResponse, PaginationParams},
+    },
+    db::{
+        get_connection,
+        repositories::{ReportRepositoryImpl, ReportScheduleRepositoryImpl},
+        DbPool,
+    },
+    domain::{
+        report::{ReportFormat, ReportResult, ReportScheduleService, ReportService},
+        PreferenceService,
+    },
+    error::ApiError,
+    utils::Config,
+};
+use actix_web::{http::header::ContentDisposition, web, HttpResponse};
+use uuid::Uuid;
+
+fn service() -> ReportService<ReportRepositoryImpl> {
+    ReportService::new(ReportRepositoryImpl)
+}
+
+fn schedules() -> ReportScheduleService<ReportRepositoryImpl, ReportScheduleRepositoryImpl> {
+    ReportScheduleService::new(ReportRepositoryImpl, ReportScheduleRepositoryImpl)
+}
+
+/// Lists the organization's reports by name
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/reports",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    responses(
+        (status = 200, description = "List of reports", body = PaginatedResponse<ReportResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page")
+    )
+)]
+pub async fn list_reports(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    query: web::Query<ListReportsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+
+    let mut conn = get_connection(&pool)?;
+    let (reports, total) = service().list(&mut conn, org_id, &pagination).await?;
+    let reports = reports
+        .into_iter()
+        .map(ReportResponse::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Reports retrieved successfully")
+            .with_data(PaginatedResponse::with_count(reports, total, &pagination))
+            .build()
+    ))
+}
+
+/// Saves a report definition
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/reports",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    request_body = SaveReportInput,
+    responses(
+        (status = 201, description = "Report saved", body = ReportResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 409, description = "A report with this name already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_report(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    input: web::Json<SaveReportInput>,
+) -> Result<HttpResponse, ApiError> {
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+
+    let mut conn = get_connection(&pool)?;
+    let report = service().create(&mut conn, org_id, created_by, input.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Report saved successfully")
+            .with_data(ReportResponse::try_from(report)?)
+            .build()
+    ))
+}
+
+/// Retrieves a report definition
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/reports/{id}",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    responses(
+        (status = 200, description = "Report found", body = ReportResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Report not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    )
+)]
+pub async fn get_report(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    report_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let report = service().get(&mut conn, org_id, *report_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Report retrieved successfully")
+            .with_data(ReportResponse::try_from(report)?)
+            .build()
+    ))
+}
+
+/// Replaces a report's name, description and definition
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/reports/{id}",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    request_body = SaveReportInput,
+    responses(
+        (status = 200, description = "Report updated", body = ReportResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Report not found", body = ErrorResponse),
+        (status = 409, description = "A report with this name already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    )
+)]
+pub async fn update_report(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    report_id: web::Path<Uuid>,
+    input: web::Json<SaveReportInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let report = service().update(&mut conn, org_id, *report_id, input.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Report updated successfully")
+            .with_data(ReportResponse::try_from(report)?)
+            .build()
+    ))
+}
+
+/// Deletes a report
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/reports/{id}",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    responses(
+        (status = 204, description = "Report deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Report not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    )
+)]
+pub async fn delete_report(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    report_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    service().delete(&mut conn, org_id, *report_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Runs a saved report
+///
+/// JSON results come in the usual envelope; CSV and Excel workbooks are sent
+/// as an attachment named after the report. Volumes, distances, timestamps
+/// and CSV separators follow the caller's preferences.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/reports/{id}/run",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    request_body = RunReportInput,
+    responses(
+        (status = 200, description = "Report output", content(
+            (ReportResult = "application/json"),
+            (String = "text/csv"),
+            (String = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        )),
+        (status = 400, description = "Bad request or missing parameter", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Report not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    )
+)]
+pub async fn run_report(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    report_id: web::Path<Uuid>,
+    input: web::Json<RunReportInput>,
+) -> Result<HttpResponse, ApiError> {
+    let input = input.into_inner();
+    let mut conn = get_connection(&pool)?;
+    let user_id = Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))?;
+    let preferences = PreferenceService::get(&mut conn, user_id).await?;
+    let (report, result) = service()
+        .run(&mut conn, org_id, *report_id, &input.parameters, &preferences)
+        .await?;
+
+    Ok(match input.format.unwrap_or_default() {
+        ReportFormat::Json => HttpResponse::Ok().json(
+            ApiResponseBuilder::success()
+                .with_message("Report ran successfully")
+                .with_data(result)
+                .build()
+        ),
+        format => HttpResponse::Ok()
+            .content_type(format.content_type())
+            .insert_header(ContentDisposition::attachment(format!("{}.{}", report.name, format.extension())))
+            .body(format.render(&result, preferences.locale)?),
+    })
+}
+
+/// Schedules a report's delivery by email, replacing its existing schedule
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/reports/{id}/schedule",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    request_body = ScheduleReportInput,
+    responses(
+        (status = 200, description = "Report scheduled", body = ReportScheduleResponse),
+        (status = 400, description = "Invalid cron expression or recipient", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Report not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    )
+)]
+pub async fn schedule_report(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    report_id: web::Path<Uuid>,
+    input: web::Json<ScheduleReportInput>,
+) -> Result<HttpResponse, ApiError> {
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let schedule = schedules().set(&mut conn, org_id, *report_id, created_by, input.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Report scheduled successfully")
+            .with_data(ReportScheduleResponse::from(schedule))
+            .build()
+    ))
+}
+
+/// Retrieves a report's delivery schedule
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/reports/{id}/schedule",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    responses(
+        (status = 200, description = "Report schedule", body = ReportScheduleResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Report not found or not scheduled", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    )
+)]
+pub async fn get_report_schedule(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    report_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let schedule = schedules().get(&mut conn, org_id, *report_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Report schedule retrieved successfully")
+            .with_data(ReportScheduleResponse::from(schedule))
+            .build()
+    ))
+}
+
+/// Stops delivering a report by email
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/reports/{id}/schedule",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    responses(
+        (status = 204, description = "Report schedule deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Report not found or not scheduled", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    )
+)]
+pub async fn delete_report_schedule(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    report_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    schedules().remove(&mut conn, org_id, *report_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Lists the scheduled deliveries of a report, newest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/reports/{id}/deliveries",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    responses(
+        (status = 200, description = "Delivery history", body = PaginatedResponse<ReportDeliveryResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Report not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Report ID"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page")
+    )
+)]
+pub async fn list_report_deliveries(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    report_id: web::Path<Uuid>,
+    query: web::Query<ListReportsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+
+    let mut conn = get_connection(&pool)?;
+    let (deliveries, total) = schedules().deliveries(&mut conn, org_id, *report_id, &pagination).await?;
+    let deliveries = deliveries.into_iter().map(ReportDeliveryResponse::from).collect();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Report deliveries retrieved successfully")
+            .with_data(PaginatedResponse::with_count(deliveries, total, &pagination))
+            .build()
+    ))
+}
+
+/// Downloads the output of a delivery that was too large to attach
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/reports/{id}/deliveries/{delivery_id}/file",
+    security(("bearer_auth" = [])),
+    tag = "reports",
+    responses(
+        (status = 200, description = "Delivered report output", content(
+            (ReportResult = "application/json"),
+            (String = "text/csv"),
+            (String = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        )),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Delivery not found or its output was attached", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Report ID"),
+        ("delivery_id" = Uuid, Path, description = "Delivery ID")
+    )
+)]
+pub async fn download_report_delivery(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (report_id, delivery_id) = path.into_inner();
+    let mut conn = get_connection(&pool)?;
+    let report = service().get(&mut conn, org_id, report_id).await?;
+    let delivery = schedules().delivery(&mut conn, org_id, report_id, delivery_id).await?;
+    drop(conn);
+
+    let Some(key) = &delivery.file_key else {
+        return Err(ApiError::not_found("The output of this delivery was attached to the email"));
+    };
+    let format = ReportFormat::parse(&delivery.format).unwrap_or_default();
+    let body = config.storage().get(key).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(ContentDisposition::attachment(format!("{}.{}", report.name, format.extension())))
+        .body(body))
+}
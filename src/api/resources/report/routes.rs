@@ -0,0 +1,27 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequirePermission},
+    domain::auth::Permission,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/reports")
+            .wrap(RequirePermission::read_write(Permission::ReportsRead, Permission::ReportsWrite))
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::report::handlers::list_reports))
+            .route("", web::post().to(crate::api::resources::report::handlers::create_report))
+            .route("/{id}", web::get().to(crate::api::resources::report::handlers::get_report))
+            .route("/{id}", web::put().to(crate::api::resources::report::handlers::update_report))
+            .route("/{id}", web::delete().to(crate::api::resources::report::handlers::delete_report))
+            .route("/{id}/run", web::post().to(crate::api::resources::report::handlers::run_report))
+            .route("/{id}/schedule", web::get().to(crate::api::resources::report::handlers::get_report_schedule))
+            .route("/{id}/schedule", web::put().to(crate::api::resources::report::handlers::schedule_report))
+            .route("/{id}/schedule", web::delete().to(crate::api::resources::report::handlers::delete_report_schedule))
+            .route("/{id}/deliveries", web::get().to(crate::api::resources::report::handlers::list_report_deliveries))
+            .route(
+                "/{id}/deliveries/{delivery_id}/file",
+                web::get().to(crate::api::resources::report::handlers::download_report_delivery),
+            )
+    );
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    db::models::{CustomRole, RoleAssignment},
+    domain::auth::{Permission, RoleDefinition},
+};
+
+/// Input for creating or replacing a custom role
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SaveRoleInput {
+    /// Unique within the organization, ignoring case, and not the name of a
+    /// built-in role
+    pub name: String,
+    pub description: Option<String>,
+    /// Permissions the role grants
+    pub permissions: Vec<Permission>,
+}
+
+impl SaveRoleInput {
+    pub fn definition(&self) -> RoleDefinition<'_> {
+        RoleDefinition {
+            name: &self.name,
+            description: self.description.as_deref(),
+            permissions: &self.permissions,
+        }
+    }
+}
+
+/// A custom role of the organization
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct RoleResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<Permission>,
+    /// Users the role is assigned to, earliest first
+    pub members: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RoleResponse {
+    /// The response for `role`, with its members among `assignments`
+    pub fn new(role: CustomRole, assignments: &[RoleAssignment]) -> Self {
+        Self {
+            id: role.id,
+            name: role.name,
+            description: role.description,
+            permissions: role.permissions.iter().filter_map(|name| Permission::parse(name)).collect(),
+            members: assignments
+                .iter()
+                .filter(|assignment| assignment.role_id == role.id)
+                .map(|assignment| assignment.user_id)
+                .collect(),
+            created_at: role.created_at,
+            updated_at: role.updated_at,
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! Custom role resource handlers
+//!
+//! Every handler works on the custom roles of the authenticated user's
+//! organization. Routes require the admin role. Changes that alter what
+//! users may do are broadcast, so other instances drop their cached policy.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::role::dto::{RoleResponse, SaveRoleInput},
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{get_connection, models::CustomRole, DbPool},
+    domain::auth::RoleService,
+    error::ApiError,
+    infrastructure::cluster::{self, ClusterEvent},
+    utils::Config,
+};
+use actix_web::{web, HttpResponse};
+use diesel::PgConnection;
+use tracing::warn;
+use uuid::Uuid;
+
+/// The response for one of the organization's roles, with its members
+async fn response(conn: &mut PgConnection, org_id: Uuid, role: CustomRole) -> Result<RoleResponse, ApiError> {
+    let assignments = RoleService::members(conn, org_id).await?;
+    Ok(RoleResponse::new(role, &assignments))
+}
+
+/// Tells the other instances that who may do what changed
+async fn broadcast(config: &Config) {
+    if let Err(e) = cluster::broadcast(config.redis(), ClusterEvent::ReloadPermissions).await {
+        warn!(error = %e, "Failed to tell other instances about the role change");
+    }
+}
+
+/// Lists the organization's custom roles by name
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/roles",
+    security(("bearer_auth" = [])),
+    tag = "roles",
+    responses(
+        (status = 200, description = "Custom roles", body = ListResponse<RoleResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn list_roles(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let roles = RoleService::list(&mut conn, org_id).await?;
+    let assignments = RoleService::members(&mut conn, org_id).await?;
+    let roles = roles
+        .into_iter()
+        .map(|role| RoleResponse::new(role, &assignments))
+        .collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Roles retrieved successfully")
+            .with_data(roles)
+            .build()
+    ))
+}
+
+/// Defines a custom role
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/roles",
+    security(("bearer_auth" = [])),
+    tag = "roles",
+    request_body = SaveRoleInput,
+    responses(
+        (status = 201, description = "Role created", body = RoleResponse),
+        (status = 400, description = "Invalid input, an unknown permission or a built-in role's name", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 409, description = "A role with this name exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_role(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    input: web::Json<SaveRoleInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let role = RoleService::create(&mut conn, org_id, &input.definition()).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Role created successfully")
+            .with_data(RoleResponse::new(role, &[]))
+            .build()
+    ))
+}
+
+/// Retrieves a custom role
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/roles/{id}",
+    security(("bearer_auth" = [])),
+    tag = "roles",
+    responses(
+        (status = 200, description = "Custom role", body = RoleResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Role not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Role ID")
+    )
+)]
+pub async fn get_role(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    role_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let role = RoleService::get(&mut conn, org_id, *role_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Role retrieved successfully")
+            .with_data(response(&mut conn, org_id, role).await?)
+            .build()
+    ))
+}
+
+/// Renames a custom role or replaces its permissions
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/roles/{id}",
+    security(("bearer_auth" = [])),
+    tag = "roles",
+    request_body = SaveRoleInput,
+    responses(
+        (status = 200, description = "Role updated", body = RoleResponse),
+        (status = 400, description = "Invalid input, an unknown permission or a built-in role's name", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Role not found", body = ErrorResponse),
+        (status = 409, description = "A role with this name exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Role ID")
+    )
+)]
+pub async fn update_role(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    role_id: web::Path<Uuid>,
+    input: web::Json<SaveRoleInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let role = RoleService::update(&mut conn, org_id, *role_id, &input.definition()).await?;
+    broadcast(&config).await;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Role updated successfully")
+            .with_data(response(&mut conn, org_id, role).await?)
+            .build()
+    ))
+}
+
+/// Deletes a custom role, taking it from everyone it was assigned to
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/roles/{id}",
+    security(("bearer_auth" = [])),
+    tag = "roles",
+    responses(
+        (status = 204, description = "Role deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Role not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Role ID")
+    )
+)]
+pub async fn delete_role(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    role_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    RoleService::delete(&mut conn, org_id, *role_id).await?;
+    broadcast(&config).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Assigns a custom role to a user of the organization
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/roles/{id}/members/{user_id}",
+    security(("bearer_auth" = [])),
+    tag = "roles",
+    responses(
+        (status = 200, description = "Role assigned", body = RoleResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Role or user not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Role ID"),
+        ("user_id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn assign_role(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let assigned_by = Uuid::parse_str(user.user_id()).ok();
+    let (role_id, user_id) = path.into_inner();
+    let mut conn = get_connection(&pool)?;
+    let role = RoleService::assign(&mut conn, org_id, role_id, user_id, assigned_by).await?;
+    broadcast(&config).await;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Role assigned successfully")
+            .with_data(response(&mut conn, org_id, role).await?)
+            .build()
+    ))
+}
+
+/// Takes a custom role from a user
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/roles/{id}/members/{user_id}",
+    security(("bearer_auth" = [])),
+    tag = "roles",
+    responses(
+        (status = 204, description = "Role unassigned"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Role not found, or not assigned to the user", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Role ID"),
+        ("user_id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn unassign_role(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (role_id, user_id) = path.into_inner();
+    let mut conn = get_connection(&pool)?;
+    RoleService::unassign(&mut conn, org_id, role_id, user_id).await?;
+    broadcast(&config).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,20 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequireRole},
+    db::models::auth::Role,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/roles")
+            .wrap(RequireRole::new(Role::Admin))
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::role::handlers::list_roles))
+            .route("", web::post().to(crate::api::resources::role::handlers::create_role))
+            .route("/{id}", web::get().to(crate::api::resources::role::handlers::get_role))
+            .route("/{id}", web::put().to(crate::api::resources::role::handlers::update_role))
+            .route("/{id}", web::delete().to(crate::api::resources::role::handlers::delete_role))
+            .route("/{id}/members/{user_id}", web::put().to(crate::api::resources::role::handlers::assign_role))
+            .route("/{id}/members/{user_id}", web::delete().to(crate::api::resources::role::handlers::unassign_role))
+    );
+}
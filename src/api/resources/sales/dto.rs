@@ -0,0 +1,255 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    db::models::{SaleContract, TenderBid, TenderParcel, TenderStatus, TimberTender},
+    domain::sales::TenderDetails,
+    error::{ApiError, Result},
+};
+
+/// A parcel offered in a new tender
+#[derive(Debug, Serialize, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct TenderParcelInput {
+    /// Harvest block the timber comes from
+    pub block_id: Option<Uuid>,
+    /// Product offered, e.g. `spruce sawlog`
+    #[validate(length(min = 1, max = 100))]
+    pub assortment: String,
+    pub volume_m3: f64,
+    /// Lowest price per cubic metre that can be awarded
+    pub reserve_price: Option<f64>,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+}
+
+/// Input for creating a tender as a draft
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct CreateTenderInput {
+    #[validate(length(min = 1, max = 255))]
+    pub title: String,
+    #[validate(length(max = 4000))]
+    pub description: Option<String>,
+    /// Bids must be received by this time
+    pub bid_deadline: DateTime<Utc>,
+    #[validate(length(min = 1, max = 200))]
+    pub parcels: Vec<TenderParcelInput>,
+}
+
+/// Input for capturing a sealed bid received for a parcel
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct CaptureBidInput {
+    pub parcel_id: Uuid,
+    #[validate(length(min = 1, max = 255))]
+    pub bidder_name: String,
+    #[validate(email)]
+    pub bidder_email: Option<String>,
+    pub price_per_m3: f64,
+    /// When the bid reached the seller, now when unset
+    pub received_at: Option<DateTime<Utc>>,
+}
+
+/// Input for awarding a parcel to one of its bids
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct AwardParcelInput {
+    pub bid_id: Uuid,
+}
+
+/// Query parameters for listing tenders
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListTendersQuery {
+    /// `draft`, `open`, `awarded` or `cancelled`
+    pub status: Option<String>,
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+impl ListTendersQuery {
+    /// The status filter, if any
+    pub fn status(&self) -> Result<Option<TenderStatus>> {
+        self.status
+            .as_deref()
+            .map(|status| {
+                [TenderStatus::Draft, TenderStatus::Open, TenderStatus::Awarded, TenderStatus::Cancelled]
+                    .into_iter()
+                    .find(|candidate| candidate.as_str() == status)
+                    .ok_or_else(|| ApiError::validation(format!("Unknown tender status {}", status), None))
+            })
+            .transpose()
+    }
+}
+
+/// Query parameters for listing sale contracts
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListSaleContractsQuery {
+    /// Only contracts for timber from this block
+    pub block_id: Option<Uuid>,
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// Tender response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TenderResponse {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    /// `draft`, `open`, `awarded` or `cancelled`
+    pub status: String,
+    pub bid_deadline: DateTime<Utc>,
+    /// Whether bids stay hidden because the deadline has not passed
+    pub bids_sealed: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TimberTender> for TenderResponse {
+    fn from(tender: TimberTender) -> Self {
+        Self {
+            id: tender.id,
+            bids_sealed: tender.bid_deadline > Utc::now(),
+            title: tender.title,
+            description: tender.description,
+            status: tender.status,
+            bid_deadline: tender.bid_deadline,
+            created_by: tender.created_by,
+            created_at: tender.created_at,
+            updated_at: tender.updated_at,
+        }
+    }
+}
+
+/// A parcel offered in a tender
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TenderParcelResponse {
+    pub id: Uuid,
+    pub block_id: Option<Uuid>,
+    pub assortment: String,
+    pub volume_m3: f64,
+    pub reserve_price: Option<f64>,
+    pub description: Option<String>,
+    /// Bids received so far; their prices stay sealed until the deadline
+    #[ts(type = "number")]
+    pub bid_count: i64,
+    pub awarded_bid_id: Option<Uuid>,
+}
+
+impl TenderParcelResponse {
+    fn new(parcel: TenderParcel, bid_count: i64) -> Self {
+        Self {
+            id: parcel.id,
+            block_id: parcel.block_id,
+            assortment: parcel.assortment,
+            volume_m3: parcel.volume_m3,
+            reserve_price: parcel.reserve_price,
+            description: parcel.description,
+            bid_count,
+            awarded_bid_id: parcel.awarded_bid_id,
+        }
+    }
+}
+
+/// A tender with its parcels
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TenderDetailsResponse {
+    pub tender: TenderResponse,
+    pub parcels: Vec<TenderParcelResponse>,
+}
+
+impl From<TenderDetails> for TenderDetailsResponse {
+    fn from(details: TenderDetails) -> Self {
+        Self {
+            tender: TenderResponse::from(details.tender),
+            parcels: details
+                .parcels
+                .into_iter()
+                .map(|(parcel, bid_count)| TenderParcelResponse::new(parcel, bid_count))
+                .collect(),
+        }
+    }
+}
+
+/// A captured bid
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TenderBidResponse {
+    pub id: Uuid,
+    pub parcel_id: Uuid,
+    pub bidder_name: String,
+    pub bidder_email: Option<String>,
+    pub price_per_m3: f64,
+    /// Whether the price reaches the parcel's reserve, so the bid can be
+    /// awarded; unset while bids are sealed
+    pub meets_reserve: Option<bool>,
+    pub received_at: DateTime<Utc>,
+    pub captured_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TenderBidResponse {
+    pub fn new(bid: TenderBid, meets_reserve: Option<bool>) -> Self {
+        Self {
+            id: bid.id,
+            parcel_id: bid.parcel_id,
+            bidder_name: bid.bidder_name,
+            bidder_email: bid.bidder_email,
+            price_per_m3: bid.price_per_m3,
+            meets_reserve,
+            received_at: bid.received_at,
+            captured_by: bid.captured_by,
+            created_at: bid.created_at,
+        }
+    }
+}
+
+/// Sale contract response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SaleContractResponse {
+    pub id: Uuid,
+    pub tender_id: Uuid,
+    pub parcel_id: Uuid,
+    pub bid_id: Uuid,
+    pub buyer_name: String,
+    pub block_id: Option<Uuid>,
+    pub assortment: String,
+    pub volume_m3: f64,
+    pub price_per_m3: f64,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<SaleContract> for SaleContractResponse {
+    fn from(contract: SaleContract) -> Self {
+        Self {
+            id: contract.id,
+            tender_id: contract.tender_id,
+            parcel_id: contract.parcel_id,
+            bid_id: contract.bid_id,
+            buyer_name: contract.buyer_name,
+            block_id: contract.block_id,
+            assortment: contract.assortment,
+            volume_m3: contract.volume_m3,
+            price_per_m3: contract.price_per_m3,
+            created_by: contract.created_by,
+            created_at: contract.created_at,
+        }
+    }
+}
@@ -0,0 +1,407 @@
+//! Timber sale resource handlers
+//!
+//! Every handler works on the tenders and sale contracts of the
+//! authenticated user's organization. Routes require the manager role.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::sales::dto::{
+            AwardParcelInput, CaptureBidInput, CreateTenderInput, ListSaleContractsQuery, ListTendersQuery,
+            SaleContractResponse, TenderBidResponse, TenderDetailsResponse, TenderResponse,
+        },
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse, PaginatedResponse, PaginationParams},
+    },
+    db::{get_connection, repositories::TimberSaleRepositoryImpl, DbPool},
+    domain::sales::TimberSaleService,
+    error::ApiError,
+};
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+
+fn service() -> TimberSaleService<TimberSaleRepositoryImpl> {
+    TimberSaleService::new(TimberSaleRepositoryImpl)
+}
+
+/// Lists the organization's tenders, newest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/sales/tenders",
+    security(("bearer_auth" = [])),
+    tag = "sales",
+    responses(
+        (status = 200, description = "Tenders", body = PaginatedResponse<TenderResponse>),
+        (status = 400, description = "Unknown status", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("status" = Option<String>, Query, description = "Only tenders with this status"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page")
+    )
+)]
+pub async fn list_tenders(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    query: web::Query<ListTendersQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let status = query.status()?;
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+    let mut conn = get_connection(&pool)?;
+    let (tenders, total) = service().list_tenders(&mut conn, org_id, status, &pagination).await?;
+    let tenders = tenders.into_iter().map(TenderResponse::from).collect();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Tenders retrieved successfully")
+            .with_data(PaginatedResponse::with_count(tenders, total, &pagination))
+            .build()
+    ))
+}
+
+/// Creates a draft tender with the parcels it offers
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/sales/tenders",
+    security(("bearer_auth" = [])),
+    tag = "sales",
+    request_body = CreateTenderInput,
+    responses(
+        (status = 201, description = "Tender created", body = TenderDetailsResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_tender(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    input: web::Json<CreateTenderInput>,
+) -> Result<HttpResponse, ApiError> {
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let details = service().create_tender(&mut conn, org_id, created_by, input.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Tender created successfully")
+            .with_data(TenderDetailsResponse::from(details))
+            .build()
+    ))
+}
+
+/// Retrieves a tender with its parcels and how many bids each has received
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/sales/tenders/{id}",
+    security(("bearer_auth" = [])),
+    tag = "sales",
+    responses(
+        (status = 200, description = "Tender", body = TenderDetailsResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tender not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tender ID")
+    )
+)]
+pub async fn get_tender(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    tender_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let details = service().get_tender(&mut conn, org_id, *tender_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Tender retrieved successfully")
+            .with_data(TenderDetailsResponse::from(details))
+            .build()
+    ))
+}
+
+/// Opens a draft tender for bids until its deadline
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/sales/tenders/{id}/open",
+    security(("bearer_auth" = [])),
+    tag = "sales",
+    responses(
+        (status = 200, description = "Tender opened", body = TenderResponse),
+        (status = 400, description = "The bid deadline has passed", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tender not found", body = ErrorResponse),
+        (status = 409, description = "Tender is not a draft", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tender ID")
+    )
+)]
+pub async fn open_tender(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    tender_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let tender = service().open_tender(&mut conn, org_id, *tender_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Tender opened successfully")
+            .with_data(TenderResponse::from(tender))
+            .build()
+    ))
+}
+
+/// Cancels a tender that is not awarded yet
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/sales/tenders/{id}/cancel",
+    security(("bearer_auth" = [])),
+    tag = "sales",
+    responses(
+        (status = 200, description = "Tender cancelled", body = TenderResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tender not found", body = ErrorResponse),
+        (status = 409, description = "Tender is already awarded or cancelled", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tender ID")
+    )
+)]
+pub async fn cancel_tender(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    tender_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let tender = service().cancel_tender(&mut conn, org_id, *tender_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Tender cancelled successfully")
+            .with_data(TenderResponse::from(tender))
+            .build()
+    ))
+}
+
+/// Captures a sealed bid received for a parcel of an open tender
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/sales/tenders/{id}/bids",
+    security(("bearer_auth" = [])),
+    tag = "sales",
+    request_body = CaptureBidInput,
+    responses(
+        (status = 201, description = "Bid captured", body = TenderBidResponse),
+        (status = 400, description = "Invalid bid or received after the deadline", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tender or parcel not found", body = ErrorResponse),
+        (status = 409, description = "Tender is not open", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tender ID")
+    )
+)]
+pub async fn capture_tender_bid(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    tender_id: web::Path<Uuid>,
+    input: web::Json<CaptureBidInput>,
+) -> Result<HttpResponse, ApiError> {
+    let captured_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let bid = service()
+        .capture_bid(&mut conn, org_id, *tender_id, captured_by, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Bid captured successfully")
+            .with_data(TenderBidResponse::new(bid, None))
+            .build()
+    ))
+}
+
+/// Lists the bids of a tender once its deadline has passed, by parcel and
+/// highest price first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/sales/tenders/{id}/bids",
+    security(("bearer_auth" = [])),
+    tag = "sales",
+    responses(
+        (status = 200, description = "Bids", body = ListResponse<TenderBidResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tender not found", body = ErrorResponse),
+        (status = 409, description = "Bids are sealed until the deadline", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tender ID")
+    )
+)]
+pub async fn list_tender_bids(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    tender_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let bids = service().bids(&mut conn, org_id, *tender_id).await?;
+    let bids = bids
+        .into_iter()
+        .map(|(bid, meets_reserve)| TenderBidResponse::new(bid, Some(meets_reserve)))
+        .collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Bids retrieved successfully")
+            .with_data(bids)
+            .build()
+    ))
+}
+
+/// Awards a parcel to one of its bids, creating the sale contract
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/sales/tenders/{id}/awards",
+    security(("bearer_auth" = [])),
+    tag = "sales",
+    request_body = AwardParcelInput,
+    responses(
+        (status = 201, description = "Parcel awarded", body = SaleContractResponse),
+        (status = 400, description = "Bid is below the reserve price", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tender or bid not found", body = ErrorResponse),
+        (status = 409, description = "Tender not open, bids still sealed or parcel already awarded", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tender ID")
+    )
+)]
+pub async fn award_tender_parcel(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    tender_id: web::Path<Uuid>,
+    input: web::Json<AwardParcelInput>,
+) -> Result<HttpResponse, ApiError> {
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let contract = service()
+        .award(&mut conn, org_id, *tender_id, created_by, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Parcel awarded successfully")
+            .with_data(SaleContractResponse::from(contract))
+            .build()
+    ))
+}
+
+/// Lists the organization's sale contracts, newest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/sales/contracts",
+    security(("bearer_auth" = [])),
+    tag = "sales",
+    responses(
+        (status = 200, description = "Sale contracts", body = PaginatedResponse<SaleContractResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Option<Uuid>, Query, description = "Only contracts for timber from this block"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page")
+    )
+)]
+pub async fn list_sale_contracts(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    query: web::Query<ListSaleContractsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+    let mut conn = get_connection(&pool)?;
+    let (contracts, total) = service().contracts(&mut conn, org_id, query.block_id, &pagination).await?;
+    let contracts = contracts.into_iter().map(SaleContractResponse::from).collect();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Sale contracts retrieved successfully")
+            .with_data(PaginatedResponse::with_count(contracts, total, &pagination))
+            .build()
+    ))
+}
+
+/// Retrieves a sale contract
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/sales/contracts/{id}",
+    security(("bearer_auth" = [])),
+    tag = "sales",
+    responses(
+        (status = 200, description = "Sale contract", body = SaleContractResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Sale contract not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Sale contract ID")
+    )
+)]
+pub async fn get_sale_contract(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    contract_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let contract = service().get_contract(&mut conn, org_id, *contract_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Sale contract retrieved successfully")
+            .with_data(SaleContractResponse::from(contract))
+            .build()
+    ))
+}
@@ -0,0 +1,8 @@
+pub mod dto;
+pub mod handlers;
+pub mod routes;
+
+pub use dto::{
+    AwardParcelInput, CaptureBidInput, CreateTenderInput, SaleContractResponse, TenderBidResponse, TenderDetailsResponse,
+    TenderParcelInput, TenderResponse,
+};
@@ -0,0 +1,23 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequirePermission},
+    domain::auth::Permission,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/sales")
+            .wrap(RequirePermission::read_write(Permission::TimberSalesRead, Permission::TimberSalesWrite))
+            .wrap(Auth::new())
+            .route("/tenders", web::get().to(crate::api::resources::sales::handlers::list_tenders))
+            .route("/tenders", web::post().to(crate::api::resources::sales::handlers::create_tender))
+            .route("/tenders/{id}", web::get().to(crate::api::resources::sales::handlers::get_tender))
+            .route("/tenders/{id}/open", web::post().to(crate::api::resources::sales::handlers::open_tender))
+            .route("/tenders/{id}/cancel", web::post().to(crate::api::resources::sales::handlers::cancel_tender))
+            .route("/tenders/{id}/bids", web::get().to(crate::api::resources::sales::handlers::list_tender_bids))
+            .route("/tenders/{id}/bids", web::post().to(crate::api::resources::sales::handlers::capture_tender_bid))
+            .route("/tenders/{id}/awards", web::post().to(crate::api::resources::sales::handlers::award_tender_parcel))
+            .route("/contracts", web::get().to(crate::api::resources::sales::handlers::list_sale_contracts))
+            .route("/contracts/{id}", web::get().to(crate::api::resources::sales::handlers::get_sale_contract))
+    );
+}
@@ -0,0 +1,23 @@
+use serde::Deserialize;
+
+/// Query parameters of SCIM list requests
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListQuery {
+    /// `attribute eq "value"`
+    pub filter: Option<String>,
+    /// 1-based index of the first resource
+    pub start_index: Option<i64>,
+    pub count: Option<i64>,
+    /// Comma-separated attributes to leave out; only `members` is honored
+    pub excluded_attributes: Option<String>,
+}
+
+impl ScimListQuery {
+    /// Whether `attribute` was excluded from the response
+    pub fn excludes(&self, attribute: &str) -> bool {
+        self.excluded_attributes
+            .as_deref()
+            .is_some_and(|excluded| excluded.split(',').any(|name| name.trim().eq_ignore_ascii_case(attribute)))
+    }
+}
@@ -0,0 +1,348 @@
+//! SCIM resource handlers
+//!
+//! Every handler works on the users of the organization the provisioning
+//! token was issued for. Responses and errors are SCIM messages rather than
+//! the API's envelope, as identity providers expect.
+
+use crate::{
+    api::{middleware::ScimClient, resources::scim::dto::ScimListQuery},
+    db::{get_connection, DbPool},
+    domain::{
+        auth::RevocationList,
+        scim::{
+            ScimError, ScimGroup, ScimListResponse, ScimPatchRequest, ScimService, ScimUser, ScimUserInput,
+            DEFAULT_COUNT, SCIM_CONTENT_TYPE,
+        },
+    },
+    utils::Config,
+};
+use actix_web::{http::header, web, HttpResponse};
+use serde::Serialize;
+use uuid::Uuid;
+
+fn scim_json(mut response: actix_web::HttpResponseBuilder, body: impl Serialize) -> HttpResponse {
+    response.content_type(SCIM_CONTENT_TYPE).json(body)
+}
+
+/// The id of a user, which SCIM answers with 404 when malformed
+fn user_id(id: &str) -> Result<Uuid, ScimError> {
+    Uuid::parse_str(id).map_err(|_| ScimError::not_found(format!("User {} not found", id)))
+}
+
+/// Describes the SCIM features supported
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/scim/v2/ServiceProviderConfig",
+    tag = "scim",
+    responses(
+        (status = 200, description = "Supported SCIM features", body = Object, content_type = "application/scim+json")
+    )
+)]
+pub async fn service_provider_config() -> HttpResponse {
+    scim_json(HttpResponse::Ok(), serde_json::json!({
+        "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig"],
+        "patch": { "supported": true },
+        "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+        "filter": { "supported": true, "maxResults": crate::domain::scim::MAX_COUNT },
+        "changePassword": { "supported": false },
+        "sort": { "supported": false },
+        "etag": { "supported": false },
+        "authenticationSchemes": [{
+            "type": "oauthbearertoken",
+            "name": "Provisioning token",
+            "description": "A token issued by an admin for the organization, sent as a bearer token",
+            "primary": true,
+        }],
+        "meta": { "resourceType": "ServiceProviderConfig", "location": "/scim/v2/ServiceProviderConfig" },
+    }))
+}
+
+/// Lists the organization's users, optionally filtered by `userName`
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Users",
+    security(("scim_token" = [])),
+    tag = "scim",
+    responses(
+        (status = 200, description = "Page of users", body = ScimListResponse<ScimUser>, content_type = "application/scim+json"),
+        (status = 400, description = "Unsupported filter", body = ScimError, content_type = "application/scim+json"),
+        (status = 401, description = "Missing or invalid provisioning token", body = ScimError, content_type = "application/scim+json"),
+        (status = 500, description = "Internal server error", body = ScimError, content_type = "application/scim+json")
+    ),
+    params(
+        ("filter" = Option<String>, Query, description = "`userName eq \"<email>\"` or `emails.value eq \"<email>\"`"),
+        ("startIndex" = Option<i64>, Query, description = "1-based index of the first user, 1 by default"),
+        ("count" = Option<i64>, Query, description = "Users per page, 100 by default and at most 200")
+    )
+)]
+pub async fn list_users(
+    client: ScimClient,
+    pool: web::Data<DbPool>,
+    query: web::Query<ScimListQuery>,
+) -> Result<HttpResponse, ScimError> {
+    let mut conn = get_connection(&pool)?;
+    let users = ScimService::list_users(
+        &mut conn,
+        client.org_id,
+        query.filter.as_deref(),
+        query.start_index.unwrap_or(1),
+        query.count.unwrap_or(DEFAULT_COUNT),
+    )
+    .await?;
+    Ok(scim_json(HttpResponse::Ok(), users))
+}
+
+/// Provisions a user, or restores the removed user with the same email
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/scim/v2/Users",
+    security(("scim_token" = [])),
+    tag = "scim",
+    request_body(content = ScimUserInput, content_type = "application/scim+json"),
+    responses(
+        (status = 201, description = "User provisioned", body = ScimUser, content_type = "application/scim+json"),
+        (status = 400, description = "Invalid user", body = ScimError, content_type = "application/scim+json"),
+        (status = 401, description = "Missing or invalid provisioning token", body = ScimError, content_type = "application/scim+json"),
+        (status = 403, description = "Admins are managed in the application", body = ScimError, content_type = "application/scim+json"),
+        (status = 409, description = "A user with this userName exists", body = ScimError, content_type = "application/scim+json"),
+        (status = 500, description = "Internal server error", body = ScimError, content_type = "application/scim+json")
+    )
+)]
+pub async fn create_user(
+    client: ScimClient,
+    pool: web::Data<DbPool>,
+    input: web::Json<ScimUserInput>,
+) -> Result<HttpResponse, ScimError> {
+    let mut conn = get_connection(&pool)?;
+    let user = ScimService::create_user(&mut conn, client.org_id, &input).await?;
+    let mut response = HttpResponse::Created();
+    response.insert_header((header::LOCATION, user.meta.location.clone()));
+    Ok(scim_json(response, user))
+}
+
+/// Retrieves a user
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Users/{id}",
+    security(("scim_token" = [])),
+    tag = "scim",
+    responses(
+        (status = 200, description = "User, inactive once deprovisioned", body = ScimUser, content_type = "application/scim+json"),
+        (status = 401, description = "Missing or invalid provisioning token", body = ScimError, content_type = "application/scim+json"),
+        (status = 404, description = "User not found", body = ScimError, content_type = "application/scim+json"),
+        (status = 500, description = "Internal server error", body = ScimError, content_type = "application/scim+json")
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn get_user(
+    client: ScimClient,
+    pool: web::Data<DbPool>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ScimError> {
+    let id = user_id(&id)?;
+    let mut conn = get_connection(&pool)?;
+    let user = ScimService::get_user(&mut conn, client.org_id, id).await?;
+    Ok(scim_json(HttpResponse::Ok(), user))
+}
+
+/// Replaces a user; `active: false` deprovisions them
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/scim/v2/Users/{id}",
+    security(("scim_token" = [])),
+    tag = "scim",
+    request_body(content = ScimUserInput, content_type = "application/scim+json"),
+    responses(
+        (status = 200, description = "User replaced", body = ScimUser, content_type = "application/scim+json"),
+        (status = 400, description = "Invalid user", body = ScimError, content_type = "application/scim+json"),
+        (status = 401, description = "Missing or invalid provisioning token", body = ScimError, content_type = "application/scim+json"),
+        (status = 403, description = "Admins are managed in the application", body = ScimError, content_type = "application/scim+json"),
+        (status = 404, description = "User not found", body = ScimError, content_type = "application/scim+json"),
+        (status = 409, description = "A user with this userName exists", body = ScimError, content_type = "application/scim+json"),
+        (status = 500, description = "Internal server error", body = ScimError, content_type = "application/scim+json")
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn replace_user(
+    client: ScimClient,
+    pool: web::Data<DbPool>,
+    id: web::Path<String>,
+    input: web::Json<ScimUserInput>,
+) -> Result<HttpResponse, ScimError> {
+    let id = user_id(&id)?;
+    let mut conn = get_connection(&pool)?;
+    let user = ScimService::replace_user(&mut conn, client.org_id, id, &input).await?;
+    Ok(scim_json(HttpResponse::Ok(), user))
+}
+
+/// Changes attributes of a user; `active: false` deprovisions them
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    patch,
+    path = "/scim/v2/Users/{id}",
+    security(("scim_token" = [])),
+    tag = "scim",
+    request_body(content = ScimPatchRequest, content_type = "application/scim+json"),
+    responses(
+        (status = 200, description = "User updated", body = ScimUser, content_type = "application/scim+json"),
+        (status = 400, description = "Invalid operation", body = ScimError, content_type = "application/scim+json"),
+        (status = 401, description = "Missing or invalid provisioning token", body = ScimError, content_type = "application/scim+json"),
+        (status = 403, description = "Admins are managed in the application", body = ScimError, content_type = "application/scim+json"),
+        (status = 404, description = "User not found", body = ScimError, content_type = "application/scim+json"),
+        (status = 409, description = "A user with this userName exists", body = ScimError, content_type = "application/scim+json"),
+        (status = 500, description = "Internal server error", body = ScimError, content_type = "application/scim+json")
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn patch_user(
+    client: ScimClient,
+    pool: web::Data<DbPool>,
+    id: web::Path<String>,
+    patch: web::Json<ScimPatchRequest>,
+) -> Result<HttpResponse, ScimError> {
+    let id = user_id(&id)?;
+    let mut conn = get_connection(&pool)?;
+    let user = ScimService::patch_user(&mut conn, client.org_id, id, &patch).await?;
+    Ok(scim_json(HttpResponse::Ok(), user))
+}
+
+/// Deprovisions a user, revoking their sessions
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/scim/v2/Users/{id}",
+    security(("scim_token" = [])),
+    tag = "scim",
+    responses(
+        (status = 204, description = "User deprovisioned"),
+        (status = 401, description = "Missing or invalid provisioning token", body = ScimError, content_type = "application/scim+json"),
+        (status = 403, description = "Admins are managed in the application", body = ScimError, content_type = "application/scim+json"),
+        (status = 404, description = "User not found", body = ScimError, content_type = "application/scim+json"),
+        (status = 500, description = "Internal server error", body = ScimError, content_type = "application/scim+json")
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn delete_user(
+    client: ScimClient,
+    pool: web::Data<DbPool>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ScimError> {
+    let id = user_id(&id)?;
+    let mut conn = get_connection(&pool)?;
+    ScimService::delete_user(&mut conn, client.org_id, id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Lists the groups, the `Manager` and `Operator` roles
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Groups",
+    security(("scim_token" = [])),
+    tag = "scim",
+    responses(
+        (status = 200, description = "Groups", body = ScimListResponse<ScimGroup>, content_type = "application/scim+json"),
+        (status = 400, description = "Unsupported filter", body = ScimError, content_type = "application/scim+json"),
+        (status = 401, description = "Missing or invalid provisioning token", body = ScimError, content_type = "application/scim+json"),
+        (status = 500, description = "Internal server error", body = ScimError, content_type = "application/scim+json")
+    ),
+    params(
+        ("filter" = Option<String>, Query, description = "`displayName eq \"<role>\"`"),
+        ("excludedAttributes" = Option<String>, Query, description = "`members` to leave the members out")
+    )
+)]
+pub async fn list_groups(
+    client: ScimClient,
+    pool: web::Data<DbPool>,
+    query: web::Query<ScimListQuery>,
+) -> Result<HttpResponse, ScimError> {
+    let mut conn = get_connection(&pool)?;
+    let groups = ScimService::list_groups(&mut conn, client.org_id, query.filter.as_deref(), !query.excludes("members")).await?;
+    Ok(scim_json(HttpResponse::Ok(), groups))
+}
+
+/// Retrieves a group and its members
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Groups/{id}",
+    security(("scim_token" = [])),
+    tag = "scim",
+    responses(
+        (status = 200, description = "Group", body = ScimGroup, content_type = "application/scim+json"),
+        (status = 401, description = "Missing or invalid provisioning token", body = ScimError, content_type = "application/scim+json"),
+        (status = 404, description = "Group not found", body = ScimError, content_type = "application/scim+json"),
+        (status = 500, description = "Internal server error", body = ScimError, content_type = "application/scim+json")
+    ),
+    params(
+        ("id" = String, Path, description = "Group ID, `Manager` or `Operator`"),
+        ("excludedAttributes" = Option<String>, Query, description = "`members` to leave the members out")
+    )
+)]
+pub async fn get_group(
+    client: ScimClient,
+    pool: web::Data<DbPool>,
+    id: web::Path<String>,
+    query: web::Query<ScimListQuery>,
+) -> Result<HttpResponse, ScimError> {
+    let mut conn = get_connection(&pool)?;
+    let group = ScimService::get_group(&mut conn, client.org_id, &id, !query.excludes("members")).await?;
+    Ok(scim_json(HttpResponse::Ok(), group))
+}
+
+/// Adds, removes or replaces the members of a group, changing their role
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    patch,
+    path = "/scim/v2/Groups/{id}",
+    security(("scim_token" = [])),
+    tag = "scim",
+    request_body(content = ScimPatchRequest, content_type = "application/scim+json"),
+    responses(
+        (status = 204, description = "Members changed"),
+        (status = 400, description = "Invalid operation", body = ScimError, content_type = "application/scim+json"),
+        (status = 401, description = "Missing or invalid provisioning token", body = ScimError, content_type = "application/scim+json"),
+        (status = 404, description = "Group not found", body = ScimError, content_type = "application/scim+json"),
+        (status = 500, description = "Internal server error", body = ScimError, content_type = "application/scim+json")
+    ),
+    params(
+        ("id" = String, Path, description = "Group ID, `Manager` or `Operator`")
+    )
+)]
+pub async fn patch_group(
+    client: ScimClient,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    id: web::Path<String>,
+    patch: web::Json<ScimPatchRequest>,
+) -> Result<HttpResponse, ScimError> {
+    let mut conn = get_connection(&pool)?;
+    // Access tokens carry the role, so those issued before the change go
+    for user_id in ScimService::patch_group(&mut conn, client.org_id, &id, &patch).await? {
+        RevocationList::revoke_user(&config, user_id).await;
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
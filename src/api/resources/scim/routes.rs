@@ -0,0 +1,32 @@
+use actix_web::web;
+use crate::{
+    api::middleware::{request_id::RequestId, security::SecurityHeaders},
+    domain::scim::ScimError,
+};
+
+/// Mounts SCIM beside `/v1`, at the path identity providers are configured
+/// with. Requests carry a provisioning token instead of a user session, so
+/// the `Auth` middleware is replaced by the `ScimClient` extractor.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/scim/v2")
+            .wrap(SecurityHeaders::new())
+            .wrap(RequestId::new())
+            .app_data(web::JsonConfig::default().error_handler(|err, _| {
+                ScimError::invalid_syntax(format!("Invalid request body: {}", err)).into()
+            }))
+            .app_data(web::QueryConfig::default().error_handler(|err, _| {
+                ScimError::invalid_value(format!("Invalid query string: {}", err)).into()
+            }))
+            .route("/ServiceProviderConfig", web::get().to(crate::api::resources::scim::handlers::service_provider_config))
+            .route("/Users", web::get().to(crate::api::resources::scim::handlers::list_users))
+            .route("/Users", web::post().to(crate::api::resources::scim::handlers::create_user))
+            .route("/Users/{id}", web::get().to(crate::api::resources::scim::handlers::get_user))
+            .route("/Users/{id}", web::put().to(crate::api::resources::scim::handlers::replace_user))
+            .route("/Users/{id}", web::patch().to(crate::api::resources::scim::handlers::patch_user))
+            .route("/Users/{id}", web::delete().to(crate::api::resources::scim::handlers::delete_user))
+            .route("/Groups", web::get().to(crate::api::resources::scim::handlers::list_groups))
+            .route("/Groups/{id}", web::get().to(crate::api::resources::scim::handlers::get_group))
+            .route("/Groups/{id}", web::patch().to(crate::api::resources::scim::handlers::patch_group))
+    );
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::search::{SearchHit, SearchKind},
+    error::{ApiError, Result},
+};
+
+/// Query parameters for a quick search
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct QuickSearchQuery {
+    /// Name or number searched for
+    pub q: String,
+    /// Comma separated kinds searched, all of them when unset
+    pub types: Option<String>,
+    /// Results per kind
+    pub limit: Option<usize>,
+}
+
+impl QuickSearchQuery {
+    /// The kinds asked for
+    pub fn kinds(&self) -> Result<Vec<SearchKind>> {
+        let Some(types) = self.types.as_deref().filter(|types| !types.trim().is_empty()) else {
+            return Ok(SearchKind::ALL.to_vec());
+        };
+        types
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                SearchKind::parse(value).ok_or_else(|| {
+                    ApiError::validation(
+                        format!("Unknown search type {}", value),
+                        Some(serde_json::json!({ "available": SearchKind::ALL.map(|kind| kind.as_str()) })),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Quick search results, best first
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct QuickSearchResponse {
+    pub query: String,
+    pub results: Vec<SearchHit>,
+}
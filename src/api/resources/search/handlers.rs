@@ -0,0 +1,67 @@
+//! Quick search resource handlers
+//!
+//! Searches the records of the authenticated user's organization.
+//! Customers, supply contracts, tenders, harvest blocks and stands are
+//! only searched for managers.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::search::dto::{QuickSearchQuery, QuickSearchResponse},
+        utils::{ApiResponseBuilder, ErrorResponse},
+    },
+    db::{get_connection, repositories::SearchRepositoryImpl, DbPool},
+    domain::search::{QuickSearchService, DEFAULT_PER_TYPE},
+    error::ApiError,
+};
+use actix_web::{web, HttpResponse};
+
+fn service() -> QuickSearchService<SearchRepositoryImpl> {
+    QuickSearchService::new(SearchRepositoryImpl)
+}
+
+/// Finds records by name or number across kinds, best first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/quick-search",
+    security(("bearer_auth" = [])),
+    tag = "search",
+    responses(
+        (status = 200, description = "Search results", body = QuickSearchResponse),
+        (status = 400, description = "Query too short or too long, unknown type or invalid limit", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("q" = String, Query, description = "Name or number searched for, 2 to 100 characters"),
+        ("types" = Option<String>, Query, description = "Comma separated kinds: `user`, `customer`, `supply_contract`, `tender`, `document`, `block`, `stand`"),
+        ("limit" = Option<usize>, Query, description = "Results per kind, 5 by default and at most 10")
+    )
+)]
+pub async fn quick_search(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    query: web::Query<QuickSearchQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let mut kinds = query.kinds()?;
+    if user.role().eq_ignore_ascii_case("operator") {
+        kinds.retain(|kind| !kind.manager_only());
+    }
+    let mut conn = get_connection(&pool)?;
+    let results = service()
+        .search(&mut conn, org_id, &query.q, &kinds, query.limit.unwrap_or(DEFAULT_PER_TYPE))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Search results retrieved successfully")
+            .with_data(QuickSearchResponse {
+                query: query.q.trim().to_string(),
+                results,
+            })
+            .build()
+    ))
+}
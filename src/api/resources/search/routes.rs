@@ -0,0 +1,11 @@
+use actix_web::web;
+use crate::api::middleware::auth::{Auth, RequireAuth};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/quick-search")
+            .wrap(RequireAuth)
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::search::handlers::quick_search))
+    );
+}
@@ -0,0 +1,156 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    db::models::{SpeciesShare, Stand},
+    domain::{
+        stand::{SpeciesVolume, StandVolumeSummary},
+        windthrow::{RiskClass, StandConditions},
+    },
+};
+
+/// Input for creating or replacing a stand
+#[derive(Debug, Clone, Serialize, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct SaveStandInput {
+    /// Unique within the block
+    #[validate(length(min = 1, max = 50))]
+    pub stand_number: String,
+    pub area_ha: f64,
+    /// Species mix, leading species first; the percentages add up to 100
+    #[validate(length(min = 1, max = 20))]
+    pub species: Vec<SpeciesShare>,
+    /// 20-year age class, 1 for up to 20 years to 9 for over 250
+    pub age_class: i16,
+    /// Height in metres the leading species reaches at breast height age 50
+    pub site_index: f64,
+    pub net_merchantable_volume_m3: f64,
+    /// Day the stand was last cruised
+    pub inventoried_on: Option<NaiveDate>,
+    /// Height, stocking, soil and exposure, for scoring the stand's
+    /// windthrow risk
+    #[serde(default)]
+    pub windthrow_conditions: Option<StandConditions>,
+}
+
+/// Stands sent by an inventory system, matched to a block's stands by
+/// stand number
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct BulkUpsertStandsInput {
+    #[validate(length(min = 1, max = 1000))]
+    pub stands: Vec<SaveStandInput>,
+}
+
+/// Stand response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct StandResponse {
+    pub id: Uuid,
+    pub block_id: Uuid,
+    pub stand_number: String,
+    pub area_ha: f64,
+    pub species: Vec<SpeciesShare>,
+    pub age_class: i16,
+    pub site_index: f64,
+    pub net_merchantable_volume_m3: f64,
+    pub inventoried_on: Option<NaiveDate>,
+    pub windthrow_conditions: Option<StandConditions>,
+    /// Windthrow risk from 0 to 100, null without conditions
+    pub windthrow_score: Option<f64>,
+    pub windthrow_class: Option<RiskClass>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Stand> for StandResponse {
+    fn from(stand: Stand) -> Self {
+        Self {
+            species: stand.species(),
+            windthrow_conditions: stand.windthrow_conditions(),
+            windthrow_class: stand.windthrow_class(),
+            id: stand.id,
+            block_id: stand.block_id,
+            stand_number: stand.stand_number,
+            area_ha: stand.area_ha,
+            age_class: stand.age_class,
+            site_index: stand.site_index,
+            net_merchantable_volume_m3: stand.net_merchantable_volume_m3,
+            inventoried_on: stand.inventoried_on,
+            windthrow_score: stand.windthrow_score,
+            created_at: stand.created_at,
+            updated_at: stand.updated_at,
+        }
+    }
+}
+
+/// Outcome of a bulk upsert
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct BulkUpsertStandsResponse {
+    /// Stands that were new to the block
+    #[ts(type = "number")]
+    pub created: i64,
+    /// Stands that replaced one with the same number
+    #[ts(type = "number")]
+    pub updated: i64,
+    /// The saved stands, by stand number
+    pub stands: Vec<StandResponse>,
+}
+
+/// Net merchantable volume of one species across a block's stands
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SpeciesVolumeResponse {
+    pub species: String,
+    pub volume_m3: f64,
+    /// Percentage of the block's net merchantable volume
+    pub percent: f64,
+}
+
+impl From<SpeciesVolume> for SpeciesVolumeResponse {
+    fn from(volume: SpeciesVolume) -> Self {
+        Self {
+            species: volume.species,
+            volume_m3: volume.volume_m3,
+            percent: volume.percent,
+        }
+    }
+}
+
+/// A block's stand volumes rolled up
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct BlockVolumeSummaryResponse {
+    pub block_id: Uuid,
+    #[ts(type = "number")]
+    pub stand_count: i64,
+    /// Area of the block's stands, which may differ from the block's net
+    /// harvest area
+    pub area_ha: f64,
+    pub net_merchantable_volume_m3: f64,
+    /// Net merchantable volume per hectare of stand area
+    pub volume_per_ha: f64,
+    /// The block's planned volume, for comparison
+    pub planned_volume_m3: f64,
+    /// Volume by species, largest first
+    pub species: Vec<SpeciesVolumeResponse>,
+}
+
+impl From<StandVolumeSummary> for BlockVolumeSummaryResponse {
+    fn from(summary: StandVolumeSummary) -> Self {
+        Self {
+            block_id: summary.block_id,
+            stand_count: summary.stand_count,
+            area_ha: summary.area_ha,
+            net_merchantable_volume_m3: summary.net_merchantable_volume_m3,
+            volume_per_ha: summary.volume_per_ha,
+            planned_volume_m3: summary.planned_volume_m3,
+            species: summary.species.into_iter().map(SpeciesVolumeResponse::from).collect(),
+        }
+    }
+}
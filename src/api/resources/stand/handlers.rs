@@ -0,0 +1,298 @@
+//! Stand resource handlers
+//!
+//! Every handler works on the stands of a harvest block of the
+//! authenticated user's organization.
+
+use crate::{
+    api::{
+        middleware::Tenant,
+        resources::stand::dto::{
+            BlockVolumeSummaryResponse, BulkUpsertStandsInput, BulkUpsertStandsResponse, SaveStandInput, StandResponse,
+        },
+        resources::tag::TagFilterQuery,
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{get_connection, repositories::StandRepositoryImpl, DbPool},
+    domain::stand::StandService,
+    error::ApiError,
+};
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+
+fn service() -> StandService<StandRepositoryImpl> {
+    StandService::new(StandRepositoryImpl)
+}
+
+/// Lists the stands of a block by stand number
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/blocks/{block_id}/stands",
+    security(("bearer_auth" = [])),
+    tag = "stands",
+    responses(
+        (status = 200, description = "Stands", body = ListResponse<StandResponse>),
+        (status = 400, description = "Invalid tag filter", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 404, description = "Harvest block not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Path, description = "Harvest block ID"),
+        ("tags" = Option<String>, Query, description = "Only stands with these tags, comma separated tag IDs"),
+        ("tag_match" = Option<String>, Query, description = "`any` (default) or `all` of the tags")
+    )
+)]
+pub async fn list_stands(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    block_id: web::Path<Uuid>,
+    query: web::Query<TagFilterQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let tags = query.filter()?;
+    let mut conn = get_connection(&pool)?;
+    let stands = service().list(&mut conn, org_id, *block_id, tags.as_ref()).await?;
+    let stands = stands.into_iter().map(StandResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Stands retrieved successfully")
+            .with_data(stands)
+            .build()
+    ))
+}
+
+/// Creates a stand in a block
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/blocks/{block_id}/stands",
+    security(("bearer_auth" = [])),
+    tag = "stands",
+    request_body = SaveStandInput,
+    responses(
+        (status = 201, description = "Stand created", body = StandResponse),
+        (status = 400, description = "Invalid input or species mix", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 404, description = "Harvest block not found", body = ErrorResponse),
+        (status = 409, description = "Stand number taken in the block", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Path, description = "Harvest block ID")
+    )
+)]
+pub async fn create_stand(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    block_id: web::Path<Uuid>,
+    input: web::Json<SaveStandInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let stand = service().create(&mut conn, org_id, *block_id, input.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Stand created successfully")
+            .with_data(StandResponse::from(stand))
+            .build()
+    ))
+}
+
+/// Creates or replaces a block's stands by stand number, as sent by an
+/// inventory system
+///
+/// Stands the block already has are replaced and the others created, all
+/// or none. Stands left out of the request are kept.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/blocks/{block_id}/stands/bulk",
+    security(("bearer_auth" = [])),
+    tag = "stands",
+    request_body = BulkUpsertStandsInput,
+    responses(
+        (status = 200, description = "Stands saved", body = BulkUpsertStandsResponse),
+        (status = 400, description = "Invalid stand, named by its position, or a stand number listed twice", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 404, description = "Harvest block not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Path, description = "Harvest block ID")
+    )
+)]
+pub async fn upsert_stands(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    block_id: web::Path<Uuid>,
+    input: web::Json<BulkUpsertStandsInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let (stands, created) = service().upsert(&mut conn, org_id, *block_id, input.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Stands saved successfully")
+            .with_data(BulkUpsertStandsResponse {
+                created,
+                updated: stands.len() as i64 - created,
+                stands: stands.into_iter().map(StandResponse::from).collect(),
+            })
+            .build()
+    ))
+}
+
+/// Rolls up the net merchantable volume of a block's stands, in total and
+/// by species
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/blocks/{block_id}/stands/summary",
+    security(("bearer_auth" = [])),
+    tag = "stands",
+    responses(
+        (status = 200, description = "Volume summary", body = BlockVolumeSummaryResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 404, description = "Harvest block not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Path, description = "Harvest block ID")
+    )
+)]
+pub async fn get_volume_summary(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    block_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let summary = service().summary(&mut conn, org_id, *block_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Volume summary retrieved successfully")
+            .with_data(BlockVolumeSummaryResponse::from(summary))
+            .build()
+    ))
+}
+
+/// Retrieves a stand
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/blocks/{block_id}/stands/{id}",
+    security(("bearer_auth" = [])),
+    tag = "stands",
+    responses(
+        (status = 200, description = "Stand", body = StandResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 404, description = "Stand not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Path, description = "Harvest block ID"),
+        ("id" = Uuid, Path, description = "Stand ID")
+    )
+)]
+pub async fn get_stand(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (block_id, stand_id) = path.into_inner();
+    let mut conn = get_connection(&pool)?;
+    let stand = service().get(&mut conn, org_id, block_id, stand_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Stand retrieved successfully")
+            .with_data(StandResponse::from(stand))
+            .build()
+    ))
+}
+
+/// Replaces the details of a stand
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/blocks/{block_id}/stands/{id}",
+    security(("bearer_auth" = [])),
+    tag = "stands",
+    request_body = SaveStandInput,
+    responses(
+        (status = 200, description = "Stand updated", body = StandResponse),
+        (status = 400, description = "Invalid input or species mix", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 404, description = "Stand not found", body = ErrorResponse),
+        (status = 409, description = "Stand number taken in the block", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Path, description = "Harvest block ID"),
+        ("id" = Uuid, Path, description = "Stand ID")
+    )
+)]
+pub async fn update_stand(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    input: web::Json<SaveStandInput>,
+) -> Result<HttpResponse, ApiError> {
+    let (block_id, stand_id) = path.into_inner();
+    let mut conn = get_connection(&pool)?;
+    let stand = service()
+        .update(&mut conn, org_id, block_id, stand_id, input.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Stand updated successfully")
+            .with_data(StandResponse::from(stand))
+            .build()
+    ))
+}
+
+/// Deletes a stand
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/blocks/{block_id}/stands/{id}",
+    security(("bearer_auth" = [])),
+    tag = "stands",
+    responses(
+        (status = 204, description = "Stand deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Blocks permission required", body = ErrorResponse),
+        (status = 404, description = "Stand not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("block_id" = Uuid, Path, description = "Harvest block ID"),
+        ("id" = Uuid, Path, description = "Stand ID")
+    )
+)]
+pub async fn delete_stand(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (block_id, stand_id) = path.into_inner();
+    let mut conn = get_connection(&pool)?;
+    service().delete(&mut conn, org_id, block_id, stand_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
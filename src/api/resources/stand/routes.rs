@@ -0,0 +1,20 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequirePermission},
+    domain::auth::Permission,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/blocks/{block_id}/stands")
+            .wrap(RequirePermission::read_write(Permission::BlocksRead, Permission::BlocksWrite))
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::stand::handlers::list_stands))
+            .route("", web::post().to(crate::api::resources::stand::handlers::create_stand))
+            .route("/bulk", web::post().to(crate::api::resources::stand::handlers::upsert_stands))
+            .route("/summary", web::get().to(crate::api::resources::stand::handlers::get_volume_summary))
+            .route("/{id}", web::get().to(crate::api::resources::stand::handlers::get_stand))
+            .route("/{id}", web::put().to(crate::api::resources::stand::handlers::update_stand))
+            .route("/{id}", web::delete().to(crate::api::resources::stand::handlers::delete_stand))
+    );
+}
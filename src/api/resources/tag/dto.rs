@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    db::models::{Tag, TagSubject, Tagging},
+    domain::tag::TagFilter,
+    error::{ApiError, Result},
+};
+
+/// The subject type named by a path segment
+pub fn subject(value: &str) -> Result<TagSubject> {
+    TagSubject::parse(value).ok_or_else(|| {
+        ApiError::validation(
+            format!("Unknown tag subject {}", value),
+            Some(serde_json::json!({ "available": TagSubject::ALL.map(|subject| subject.as_str()) })),
+        )
+    })
+}
+
+/// Input for creating or replacing a tag
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct SaveTagInput {
+    /// Unique within the organization, ignoring case
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+    /// Hex color such as `#2e7d32`
+    pub color: String,
+}
+
+/// Tag filter query parameters, taken by list endpoints of taggable
+/// subjects
+#[derive(Debug, Default, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TagFilterQuery {
+    /// Comma separated tag ids
+    pub tags: Option<String>,
+    /// `any` (the default) or `all` of the tags
+    pub tag_match: Option<String>,
+}
+
+impl TagFilterQuery {
+    /// The filter asked for, `None` when no tags are given
+    pub fn filter(&self) -> Result<Option<TagFilter>> {
+        TagFilter::parse(self.tags.as_deref(), self.tag_match.as_deref())
+    }
+}
+
+/// Tag response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TagResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub color: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(tag: Tag) -> Self {
+        Self {
+            id: tag.id,
+            name: tag.name,
+            color: tag.color,
+            created_at: tag.created_at,
+            updated_at: tag.updated_at,
+        }
+    }
+}
+
+/// Something a tag is attached to
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TaggingResponse {
+    /// `stand`, `block`, `equipment` or `work_order`
+    pub subject_type: String,
+    pub subject_id: Uuid,
+    pub tagged_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Tagging> for TaggingResponse {
+    fn from(tagging: Tagging) -> Self {
+        Self {
+            subject_type: tagging.subject_type,
+            subject_id: tagging.subject_id,
+            tagged_by: tagging.tagged_by,
+            created_at: tagging.created_at,
+        }
+    }
+}
+
+/// Subjects of a type kept by a tag filter
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TaggedSubjectsResponse {
+    pub subject_type: String,
+    pub subject_ids: Vec<Uuid>,
+}
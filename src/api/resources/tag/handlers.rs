@@ -0,0 +1,398 @@
+//! Tag resource handlers
+//!
+//! Every handler works on the tags of the authenticated user's
+//! organization. Routes require the manager role.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::tag::dto::{subject, SaveTagInput, TagFilterQuery, TagResponse, TaggedSubjectsResponse, TaggingResponse},
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{get_connection, repositories::TagRepositoryImpl, DbPool},
+    domain::tag::TagService,
+    error::ApiError,
+};
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+
+fn service() -> TagService<TagRepositoryImpl> {
+    TagService::new(TagRepositoryImpl)
+}
+
+/// Lists the organization's tags by name
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/tags",
+    security(("bearer_auth" = [])),
+    tag = "tags",
+    responses(
+        (status = 200, description = "Tags", body = ListResponse<TagResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn list_tags(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let tags = service().list(&mut conn, org_id).await?;
+    let tags = tags.into_iter().map(TagResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Tags retrieved successfully")
+            .with_data(tags)
+            .build()
+    ))
+}
+
+/// Creates a tag
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/tags",
+    security(("bearer_auth" = [])),
+    tag = "tags",
+    request_body = SaveTagInput,
+    responses(
+        (status = 201, description = "Tag created", body = TagResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 409, description = "A tag with this name exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_tag(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    input: web::Json<SaveTagInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let tag = service().create(&mut conn, org_id, input.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Tag created successfully")
+            .with_data(TagResponse::from(tag))
+            .build()
+    ))
+}
+
+/// Retrieves a tag
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/tags/{id}",
+    security(("bearer_auth" = [])),
+    tag = "tags",
+    responses(
+        (status = 200, description = "Tag", body = TagResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tag ID")
+    )
+)]
+pub async fn get_tag(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    tag_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let tag = service().get(&mut conn, org_id, *tag_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Tag retrieved successfully")
+            .with_data(TagResponse::from(tag))
+            .build()
+    ))
+}
+
+/// Renames or recolors a tag
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/tags/{id}",
+    security(("bearer_auth" = [])),
+    tag = "tags",
+    request_body = SaveTagInput,
+    responses(
+        (status = 200, description = "Tag updated", body = TagResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 409, description = "A tag with this name exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tag ID")
+    )
+)]
+pub async fn update_tag(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    tag_id: web::Path<Uuid>,
+    input: web::Json<SaveTagInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let tag = service().update(&mut conn, org_id, *tag_id, input.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Tag updated successfully")
+            .with_data(TagResponse::from(tag))
+            .build()
+    ))
+}
+
+/// Deletes a tag, detaching it from everything
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/tags/{id}",
+    security(("bearer_auth" = [])),
+    tag = "tags",
+    responses(
+        (status = 204, description = "Tag deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tag ID")
+    )
+)]
+pub async fn delete_tag(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    tag_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    service().delete(&mut conn, org_id, *tag_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Lists what a tag is attached to, latest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/tags/{id}/subjects",
+    security(("bearer_auth" = [])),
+    tag = "tags",
+    responses(
+        (status = 200, description = "Tagged subjects", body = ListResponse<TaggingResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tag ID")
+    )
+)]
+pub async fn list_taggings(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    tag_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let taggings = service().taggings(&mut conn, org_id, *tag_id).await?;
+    let taggings = taggings.into_iter().map(TaggingResponse::from).collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Tagged subjects retrieved successfully")
+            .with_data(taggings)
+            .build()
+    ))
+}
+
+/// Attaches a tag to a stand, block, piece of equipment or work order
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/tags/{id}/subjects/{subject_type}/{subject_id}",
+    security(("bearer_auth" = [])),
+    tag = "tags",
+    responses(
+        (status = 200, description = "Tag attached", body = TagResponse),
+        (status = 400, description = "Unknown subject type", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tag ID"),
+        ("subject_type" = String, Path, description = "`stand`, `block`, `equipment` or `work_order`"),
+        ("subject_id" = Uuid, Path, description = "ID of the tagged subject")
+    )
+)]
+pub async fn attach_tag(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let tagged_by = Uuid::parse_str(user.user_id()).ok();
+    let (tag_id, subject_type, subject_id) = path.into_inner();
+    let subject = subject(&subject_type)?;
+    let mut conn = get_connection(&pool)?;
+    let tag = service().attach(&mut conn, org_id, tag_id, subject, subject_id, tagged_by).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Tag attached successfully")
+            .with_data(TagResponse::from(tag))
+            .build()
+    ))
+}
+
+/// Detaches a tag from a stand, block, piece of equipment or work order
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/tags/{id}/subjects/{subject_type}/{subject_id}",
+    security(("bearer_auth" = [])),
+    tag = "tags",
+    responses(
+        (status = 204, description = "Tag detached"),
+        (status = 400, description = "Unknown subject type", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "The tag is not attached", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Tag ID"),
+        ("subject_type" = String, Path, description = "`stand`, `block`, `equipment` or `work_order`"),
+        ("subject_id" = Uuid, Path, description = "ID of the tagged subject")
+    )
+)]
+pub async fn detach_tag(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (tag_id, subject_type, subject_id) = path.into_inner();
+    let subject = subject(&subject_type)?;
+    let mut conn = get_connection(&pool)?;
+    service().detach(&mut conn, org_id, tag_id, subject, subject_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Lists the tags of a stand, block, piece of equipment or work order
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/tags/subjects/{subject_type}/{subject_id}",
+    security(("bearer_auth" = [])),
+    tag = "tags",
+    responses(
+        (status = 200, description = "Tags of the subject", body = ListResponse<TagResponse>),
+        (status = 400, description = "Unknown subject type", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("subject_type" = String, Path, description = "`stand`, `block`, `equipment` or `work_order`"),
+        ("subject_id" = Uuid, Path, description = "ID of the subject")
+    )
+)]
+pub async fn list_subject_tags(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    path: web::Path<(String, Uuid)>,
+) -> Result<HttpResponse, ApiError> {
+    let (subject_type, subject_id) = path.into_inner();
+    let subject = subject(&subject_type)?;
+    let mut conn = get_connection(&pool)?;
+    let mut tags = service().subject_tags(&mut conn, org_id, subject, &[subject_id]).await?;
+    let tags = tags
+        .remove(&subject_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(TagResponse::from)
+        .collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Subject tags retrieved successfully")
+            .with_data(tags)
+            .build()
+    ))
+}
+
+/// Lists the subjects of a type kept by a tag filter
+///
+/// Takes the same `tags` and `tag_match` parameters as the list endpoints
+/// of taggable subjects.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/tags/subjects/{subject_type}",
+    security(("bearer_auth" = [])),
+    tag = "tags",
+    responses(
+        (status = 200, description = "Matching subjects", body = TaggedSubjectsResponse),
+        (status = 400, description = "Unknown subject type or invalid filter", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("subject_type" = String, Path, description = "`stand`, `block`, `equipment` or `work_order`"),
+        ("tags" = String, Query, description = "Comma separated tag IDs"),
+        ("tag_match" = Option<String>, Query, description = "`any` (default) or `all` of the tags")
+    )
+)]
+pub async fn list_tagged_subjects(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    subject_type: web::Path<String>,
+    query: web::Query<TagFilterQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let subject = subject(&subject_type)?;
+    let filter = query
+        .filter()?
+        .ok_or_else(|| ApiError::validation("At least one tag is required", None))?;
+    let mut conn = get_connection(&pool)?;
+    let subject_ids = service()
+        .filter(&mut conn, org_id, subject, Some(&filter))
+        .await?
+        .unwrap_or_default();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Tagged subjects retrieved successfully")
+            .with_data(TaggedSubjectsResponse {
+                subject_type: subject.as_str().to_string(),
+                subject_ids,
+            })
+            .build()
+    ))
+}
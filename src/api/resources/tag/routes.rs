@@ -0,0 +1,24 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequirePermission},
+    domain::auth::Permission,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/tags")
+            .wrap(RequirePermission::read_write(Permission::TagsRead, Permission::TagsWrite))
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::tag::handlers::list_tags))
+            .route("", web::post().to(crate::api::resources::tag::handlers::create_tag))
+            // Registered before `/{id}` so `subjects` is not taken for a tag id
+            .route("/subjects/{subject_type}", web::get().to(crate::api::resources::tag::handlers::list_tagged_subjects))
+            .route("/subjects/{subject_type}/{subject_id}", web::get().to(crate::api::resources::tag::handlers::list_subject_tags))
+            .route("/{id}", web::get().to(crate::api::resources::tag::handlers::get_tag))
+            .route("/{id}", web::put().to(crate::api::resources::tag::handlers::update_tag))
+            .route("/{id}", web::delete().to(crate::api::resources::tag::handlers::delete_tag))
+            .route("/{id}/subjects", web::get().to(crate::api::resources::tag::handlers::list_taggings))
+            .route("/{id}/subjects/{subject_type}/{subject_id}", web::put().to(crate::api::resources::tag::handlers::attach_tag))
+            .route("/{id}/subjects/{subject_type}/{subject_id}", web::delete().to(crate::api::resources::tag::handlers::detach_tag))
+    );
+}
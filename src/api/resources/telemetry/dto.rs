@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{db::models::TelemetryApiKey, domain::telemetry::IngestOutcome};
+
+/// A data point reported by a machine
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TelemetryPointInput {
+    /// The machine's own identifier, as configured on its telematics unit
+    pub machine_id: String,
+    /// When the machine took the reading
+    pub timestamp: DateTime<Utc>,
+    /// Engine hour meter reading
+    pub engine_hours: Option<f64>,
+    /// Fuel used according to the machine's counter, in litres
+    pub fuel_used_l: Option<f64>,
+    /// WGS 84 latitude, given with the longitude
+    pub latitude: Option<f64>,
+    /// WGS 84 longitude, given with the latitude
+    pub longitude: Option<f64>,
+}
+
+/// Data points uploaded together, such as a day buffered while offline
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TelemetryBatchInput {
+    pub points: Vec<TelemetryPointInput>,
+}
+
+/// Outcome of a telemetry upload
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TelemetryBatchResponse {
+    /// Points in the request
+    #[ts(type = "number")]
+    pub received: i64,
+    /// Points stored, replacing any stored for the same machine and time
+    #[ts(type = "number")]
+    pub stored: i64,
+    /// Points repeating an earlier one of the request, of which the last
+    /// was kept
+    #[ts(type = "number")]
+    pub duplicates: i64,
+}
+
+impl From<IngestOutcome> for TelemetryBatchResponse {
+    fn from(outcome: IngestOutcome) -> Self {
+        Self {
+            received: outcome.received as i64,
+            stored: outcome.stored as i64,
+            duplicates: outcome.duplicates as i64,
+        }
+    }
+}
+
+/// Input for issuing a telemetry API key
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct CreateTelemetryKeyInput {
+    /// What the key is for, e.g. the machine or gateway using it
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+/// A telemetry API key, without the key itself
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct TelemetryKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub created_by: Option<Uuid>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<TelemetryApiKey> for TelemetryKeyResponse {
+    fn from(key: TelemetryApiKey) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            created_by: key.created_by,
+            last_used_at: key.last_used_at,
+            created_at: key.created_at,
+        }
+    }
+}
+
+/// A newly issued telemetry API key
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct IssuedTelemetryKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    /// The key to send in the `X-Api-Key` header; it is not shown again
+    pub key: String,
+    pub created_at: DateTime<Utc>,
+}
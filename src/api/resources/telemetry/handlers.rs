@@ -0,0 +1,171 @@
+//! Telemetry resource handlers
+//!
+//! Machines upload data points with a telemetry API key; the organization's
+//! admins issue and revoke the keys.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, TelemetryClient, Tenant},
+        resources::telemetry::dto::{
+            CreateTelemetryKeyInput, IssuedTelemetryKeyResponse, TelemetryBatchInput, TelemetryBatchResponse,
+            TelemetryKeyResponse,
+        },
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{get_connection, repositories::TelemetryRepositoryImpl, DbPool},
+    domain::telemetry::TelemetryService,
+    error::{ApiError, ErrorContext},
+};
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+fn service() -> TelemetryService<TelemetryRepositoryImpl> {
+    TelemetryService::new(TelemetryRepositoryImpl)
+}
+
+/// Uploads machine data points, such as a day buffered while offline
+///
+/// A point replaces any stored for the same machine and time, and of the
+/// points of the request repeating a machine and time the last is kept.
+/// One invalid point stores none of them. Archived organizations take no
+/// uploads.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/telemetry/batch",
+    security(("telemetry_key" = [])),
+    tag = "telemetry",
+    request_body = TelemetryBatchInput,
+    responses(
+        (status = 200, description = "Points stored", body = TelemetryBatchResponse),
+        (status = 400, description = "Invalid point, named by its position, or too many points or bytes", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or revoked API key", body = ErrorResponse),
+        (status = 403, description = "The organization is archived", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn ingest_telemetry_batch(
+    client: TelemetryClient,
+    pool: web::Data<DbPool>,
+    input: web::Json<TelemetryBatchInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let outcome = service().ingest(&mut conn, client.org_id, input.into_inner().points).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Telemetry stored successfully")
+            .with_data(TelemetryBatchResponse::from(outcome))
+            .build()
+    ))
+}
+
+/// Lists the organization's telemetry API keys
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/telemetry/keys",
+    security(("bearer_auth" = [])),
+    tag = "telemetry",
+    responses(
+        (status = 200, description = "Unrevoked telemetry API keys, newest first", body = ListResponse<TelemetryKeyResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn list_telemetry_keys(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let keys = service().list_keys(&mut conn, org_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Telemetry API keys retrieved successfully")
+            .with_data(keys.into_iter().map(TelemetryKeyResponse::from).collect::<ListResponse<_>>())
+            .build()
+    ))
+}
+
+/// Issues an API key machines upload telemetry with
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/telemetry/keys",
+    security(("bearer_auth" = [])),
+    tag = "telemetry",
+    request_body = CreateTelemetryKeyInput,
+    responses(
+        (status = 201, description = "Telemetry API key issued; the key is only shown in this response", body = IssuedTelemetryKeyResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_telemetry_key(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    input: web::Json<CreateTelemetryKeyInput>,
+) -> Result<HttpResponse, ApiError> {
+    if let Err(e) = ValidatorValidate::validate(&input.0) {
+        return Err(ApiError::validation_with_context(
+            "Invalid input",
+            ErrorContext::new()
+                .with_message_key("INVALID_INPUT")
+                .with_details(serde_json::json!(e))
+        ));
+    }
+    let created_by = Uuid::parse_str(user.user_id()).ok();
+    let mut conn = get_connection(&pool)?;
+    let (key, plaintext) = service().issue_key(&mut conn, org_id, &input.name, created_by).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("Telemetry API key issued successfully")
+            .with_data(IssuedTelemetryKeyResponse {
+                id: key.id,
+                name: key.name,
+                key: plaintext,
+                created_at: key.created_at,
+            })
+            .build()
+    ))
+}
+
+/// Revokes a telemetry API key, after which its uploads are refused
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/telemetry/keys/{id}",
+    security(("bearer_auth" = [])),
+    tag = "telemetry",
+    responses(
+        (status = 204, description = "Telemetry API key revoked"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Telemetry API key not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Telemetry API key ID")
+    )
+)]
+pub async fn revoke_telemetry_key(
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    key_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    service().revoke_key(&mut conn, org_id, *key_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
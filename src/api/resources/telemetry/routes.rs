@@ -0,0 +1,30 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequireRole},
+    db::models::auth::Role,
+    error::ApiError,
+};
+
+/// Largest upload body, enough for a day of points every second
+pub const MAX_BATCH_BYTES: usize = 32 * 1024 * 1024;
+
+/// Mounts key management for admins and the upload route, which carries a
+/// telemetry API key instead of a user session: the `TelemetryClient`
+/// extractor replaces the `Auth` middleware there
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/telemetry/keys")
+            .wrap(RequireRole::new(Role::Admin))
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::telemetry::handlers::list_telemetry_keys))
+            .route("", web::post().to(crate::api::resources::telemetry::handlers::create_telemetry_key))
+            .route("/{id}", web::delete().to(crate::api::resources::telemetry::handlers::revoke_telemetry_key))
+    );
+    cfg.service(
+        web::scope("/telemetry")
+            .app_data(web::JsonConfig::default().limit(MAX_BATCH_BYTES).error_handler(|err, _| {
+                ApiError::validation(format!("Invalid request body: {}", err), None).into()
+            }))
+            .route("/batch", web::post().to(crate::api::resources::telemetry::handlers::ingest_telemetry_batch))
+    );
+}
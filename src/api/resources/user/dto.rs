@@ -0,0 +1,221 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    db::models::{
+        auth::{AuthEvent, ProfileChanges},
+        UserActivity,
+    },
+    domain::user::{timezone_name, PreferenceChanges, Preferences, UnitSystem},
+};
+
+/// Changes the caller makes to their profile; fields left out keep their
+/// value
+///
+/// Role and organization aren't the caller's to change, so naming them is
+/// rejected rather than ignored.
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct UpdateProfileInput {
+    #[serde(default)]
+    #[ts(optional)]
+    pub first_name: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub last_name: Option<String>,
+    /// Such as `+1 (250) 555-0100`; an empty string removes it
+    #[serde(default)]
+    #[ts(optional)]
+    pub phone_number: Option<String>,
+}
+
+impl From<UpdateProfileInput> for ProfileChanges {
+    fn from(input: UpdateProfileInput) -> Self {
+        Self {
+            first_name: input.first_name,
+            last_name: input.last_name,
+            phone_number: input.phone_number,
+        }
+    }
+}
+
+/// How the caller wants measurements and times presented
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct PreferencesResponse {
+    pub units: UnitSystem,
+    /// `UTC` or an offset from it, such as `-08:00`
+    pub timezone: String,
+    /// `en` or `fr`
+    pub locale: String,
+}
+
+impl From<Preferences> for PreferencesResponse {
+    fn from(preferences: Preferences) -> Self {
+        Self {
+            units: preferences.units,
+            timezone: timezone_name(preferences.timezone),
+            locale: preferences.locale.code().to_string(),
+        }
+    }
+}
+
+/// Changes to the caller's preferences; fields left out keep their value
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct UpdatePreferencesInput {
+    #[serde(default)]
+    #[ts(optional)]
+    pub units: Option<UnitSystem>,
+    /// `UTC` or an offset from it, such as `-08:00` or `+05:30`
+    #[serde(default)]
+    #[ts(optional)]
+    pub timezone: Option<String>,
+    /// `en` or `fr`
+    #[serde(default)]
+    #[ts(optional)]
+    pub locale: Option<String>,
+}
+
+impl From<UpdatePreferencesInput> for PreferenceChanges {
+    fn from(input: UpdatePreferencesInput) -> Self {
+        Self {
+            units: input.units,
+            timezone: input.timezone,
+            locale: input.locale,
+        }
+    }
+}
+
+/// Request to switch the caller to another email address
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct EmailChangeInput {
+    /// The new address, which the caller confirms from a link mailed to it
+    pub email: String,
+}
+
+/// Request to set who a user reports to
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct AssignSupervisorInput {
+    /// An active manager or admin of the organization, `null` for no one
+    pub supervisor_id: Option<Uuid>,
+}
+
+/// Query parameters for listing authentication events
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListAuthEventsQuery {
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// A login, token refresh, password change or logout
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct AuthEventResponse {
+    pub id: Uuid,
+    /// `login_succeeded`, `login_failed`, `token_refreshed`, `password_changed`,
+    /// `email_changed`, `logged_out`, `passkey_added`, `passkey_removed`,
+    /// `sessions_revoked`, `deactivated` or `reactivated`
+    pub event: String,
+    /// How the user authenticated: `password`, `magic_link`, `passkey`, `password_reset` or
+    /// `sso:<provider>`
+    pub method: Option<String>,
+    /// Email a failed login was attempted with
+    pub email: Option<String>,
+    /// Why the attempt failed
+    pub reason: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// ISO 3166 code of the country the client was in, when known
+    pub country: Option<String>,
+    /// Network the client connected from, e.g. `AS13335`, when known
+    pub asn: Option<String>,
+    /// Device the client logged in from, when it named one
+    pub device_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuthEvent> for AuthEventResponse {
+    fn from(event: AuthEvent) -> Self {
+        Self {
+            id: event.id,
+            event: event.event,
+            method: event.method,
+            email: event.email,
+            reason: event.reason,
+            ip_address: event.ip_address,
+            user_agent: event.user_agent,
+            country: event.country,
+            asn: event.asn,
+            device_id: event.device_id,
+            created_at: event.created_at,
+        }
+    }
+}
+
+/// Query parameters of a signed avatar link
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct AvatarQuery {
+    /// Upload the link was made for, to tell caches apart
+    #[ts(type = "number | null")]
+    pub v: Option<i64>,
+    /// Unix time the link stops working at
+    #[ts(type = "number")]
+    pub expires: i64,
+    pub signature: String,
+}
+
+/// Query parameters for listing a user's activity
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListActivityQuery {
+    /// Only the activities of this day, in the caller's timezone
+    pub date: Option<NaiveDate>,
+    #[ts(type = "number | null")]
+    pub page: Option<i64>,
+    #[ts(type = "number | null")]
+    pub per_page: Option<i64>,
+}
+
+/// Something a user did
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ActivityResponse {
+    pub id: Uuid,
+    /// `block_signed_off`, `document_uploaded`, `import_uploaded`,
+    /// `tender_created`, `bid_captured`, `parcel_awarded` or
+    /// `certification_recorded`
+    pub action: String,
+    /// What the action was taken on, e.g. `block` or `document`
+    pub subject_type: String,
+    pub subject_id: Uuid,
+    pub summary: String,
+    #[ts(type = "Record<string, unknown> | null")]
+    pub data: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<UserActivity> for ActivityResponse {
+    fn from(activity: UserActivity) -> Self {
+        Self {
+            id: activity.id,
+            action: activity.action,
+            subject_type: activity.subject_type,
+            subject_id: activity.subject_id,
+            summary: activity.summary,
+            data: activity.data,
+            created_at: activity.created_at,
+        }
+    }
+}
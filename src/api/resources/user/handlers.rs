@@ -0,0 +1,699 @@
+//! User resource handlers
+//!
+//! `/me` routes work on the authenticated user; `/users` routes work on any
+//! user and require the admin role, except a user's activity, which
+//! managers review too.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::{
+            auth::dto::UserResponse,
+            user::dto::{
+                ActivityResponse, AssignSupervisorInput, AuthEventResponse, AvatarQuery, EmailChangeInput, ListActivityQuery,
+                ListAuthEventsQuery, PreferencesResponse, UpdatePreferencesInput, UpdateProfileInput,
+            },
+        },
+        utils::{multipart, ApiResponseBuilder, ErrorResponse, ListResponse, PaginatedResponse, PaginationParams},
+    },
+    db::{
+        get_connection,
+        repositories::{auth::UserRepositoryImpl, OrganizationRepository, OrganizationRepositoryImpl, Repository},
+        DbPool,
+    },
+    domain::{
+        auth::ClientInfo, ActivationService, ActivityService, AnonymizationService, AuthService, AvatarService, PreferenceService,
+        ProfileService, SupervisorService,
+    },
+    error::{ApiError, ErrorContext},
+    utils::Config,
+};
+use actix_web::{
+    http::header::{self, CacheControl, CacheDirective},
+    web, HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use serde_json::json;
+use uuid::Uuid;
+
+fn user_id(user: &AuthenticatedUser) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))
+}
+
+async fn auth_events(pool: &DbPool, user_id: Uuid, query: &ListAuthEventsQuery) -> Result<HttpResponse, ApiError> {
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+    let (events, total) = AuthService::list_auth_events(pool, user_id, &pagination).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Authentication events retrieved successfully")
+            .with_data(PaginatedResponse::new(
+                events.into_iter().map(AuthEventResponse::from).collect(),
+                total,
+                &pagination
+            ))
+            .build()
+    ))
+}
+
+async fn activity(pool: &DbPool, viewer_id: Uuid, user_id: Uuid, query: &ListActivityQuery) -> Result<HttpResponse, ApiError> {
+    let pagination = PaginationParams::new(query.page.unwrap_or(1), query.per_page.unwrap_or(20));
+    let mut conn = get_connection(pool)?;
+    let viewer = UserRepositoryImpl.find_by_id(&mut conn, viewer_id).await?;
+    let (activities, total) = ActivityService::list(&mut conn, &viewer, user_id, query.date, &pagination).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Activity retrieved successfully")
+            .with_data(PaginatedResponse::new(
+                activities.into_iter().map(ActivityResponse::from).collect(),
+                total,
+                &pagination
+            ))
+            .build()
+    ))
+}
+
+/// Retrieves the caller's profile
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/me",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "The caller's profile", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn get_me(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let user = ProfileService::get(&mut conn, user_id(&user)?).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Profile retrieved successfully")
+            .with_data(UserResponse::new(user, &config))
+            .build()
+    ))
+}
+
+/// Changes the caller's name or phone number
+///
+/// Role, organization and email can't be changed here; naming them is a
+/// bad request.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    patch,
+    path = "/v1/me",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    request_body = UpdateProfileInput,
+    responses(
+        (status = 200, description = "Profile updated", body = UserResponse),
+        (status = 400, description = "Invalid name or phone number, or a field that can't be changed", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "Phone number already in use", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn update_me(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    input: web::Json<UpdateProfileInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let user = ProfileService::update(&mut conn, user_id(&user)?, input.into_inner().into()).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Profile updated successfully")
+            .with_data(UserResponse::new(user, &config))
+            .build()
+    ))
+}
+
+/// The picture in an avatar upload: the `avatar` part of a form, else its
+/// first file, or the body itself when it is sent as `image/png`
+fn avatar_upload(req: &HttpRequest, body: web::Bytes) -> Result<web::Bytes, ApiError> {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if content_type.trim().eq_ignore_ascii_case("image/png") {
+        return Ok(body);
+    }
+
+    let parts = multipart::parse(content_type, &body)?;
+    parts
+        .iter()
+        .find(|part| part.name.as_deref() == Some("avatar"))
+        .or_else(|| parts.iter().find(|part| part.filename.is_some()))
+        .map(|part| part.body.clone())
+        .ok_or_else(|| {
+            ApiError::validation_with_context(
+                "Form has no avatar file",
+                ErrorContext::new().with_details(json!({
+                    "field": "avatar",
+                    "code": "REQUIRED",
+                })),
+            )
+        })
+}
+
+/// Replaces the caller's avatar
+///
+/// Takes a PNG as the `avatar` field of a `multipart/form-data` form, or
+/// as the body itself. It is cropped to a centred square and stored at
+/// 64 and 256 pixels.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/me/avatar",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    request_body(content = Vec<u8>, content_type = "multipart/form-data", description = "Form with a PNG `avatar` file"),
+    responses(
+        (status = 200, description = "Avatar replaced", body = UserResponse),
+        (status = 400, description = "Missing, malformed or oversized PNG", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 413, description = "Upload too large", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn upload_my_avatar(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let png = avatar_upload(&req, body)?;
+    let mut conn = get_connection(&pool)?;
+    let user = AvatarService::upload(&mut conn, &config, user_id(&user)?, png).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Avatar updated successfully")
+            .with_data(UserResponse::new(user, &config))
+            .build()
+    ))
+}
+
+/// Serves an avatar through a signed link from a user's `avatar` links
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/avatars/{user_id}/{size}",
+    tag = "users",
+    responses(
+        (status = 200, description = "The avatar", content_type = "image/png", body = Vec<u8>),
+        (status = 404, description = "No such avatar, or an expired or tampered link", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("user_id" = Uuid, Path, description = "User ID"),
+        ("size" = u32, Path, description = "Edge length in pixels, 64 or 256"),
+        ("v" = Option<i64>, Query, description = "Upload the link was made for"),
+        ("expires" = i64, Query, description = "Unix time the link stops working at"),
+        ("signature" = String, Query, description = "Signature of the link")
+    )
+)]
+pub async fn get_avatar(
+    config: web::Data<Config>,
+    path: web::Path<(Uuid, u32)>,
+    query: web::Query<AvatarQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let (user_id, size) = path.into_inner();
+    let png = AvatarService::file(&config, user_id, size, query.expires, &query.signature).await?;
+    let max_age = (query.expires - Utc::now().timestamp()).max(0) as u32;
+
+    Ok(HttpResponse::Ok()
+        .content_type("image/png")
+        .insert_header(CacheControl(vec![CacheDirective::Private, CacheDirective::MaxAge(max_age)]))
+        .body(png))
+}
+
+/// Retrieves the caller's preferences, the defaults when they never saved
+/// any
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/me/preferences",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "The caller's preferences", body = PreferencesResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn get_my_preferences(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let preferences = PreferenceService::get(&mut conn, user_id(&user)?).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Preferences retrieved successfully")
+            .with_data(PreferencesResponse::from(preferences))
+            .build()
+    ))
+}
+
+/// Changes the caller's units, timezone or locale
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    patch,
+    path = "/v1/me/preferences",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    request_body = UpdatePreferencesInput,
+    responses(
+        (status = 200, description = "Preferences updated", body = PreferencesResponse),
+        (status = 400, description = "Unknown units, an invalid timezone or an unsupported locale", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn update_my_preferences(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    input: web::Json<UpdatePreferencesInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let preferences = PreferenceService::update(&mut conn, user_id(&user)?, input.into_inner().into()).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Preferences updated successfully")
+            .with_data(PreferencesResponse::from(preferences))
+            .build()
+    ))
+}
+
+/// Lists the caller's logins, failed logins, token refreshes, password changes and logouts, newest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/me/auth-events",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "Authentication events", body = PaginatedResponse<AuthEventResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page, 20 by default")
+    )
+)]
+pub async fn list_my_auth_events(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    query: web::Query<ListAuthEventsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    auth_events(&pool, user_id(&user)?, &query).await
+}
+
+/// Lists a user's logins, failed logins, token refreshes, password changes and logouts, newest first
+///
+/// Admins see the events of users of their own organization and of those
+/// under it; other users are not found.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/users/{id}/auth-events",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "Authentication events", body = PaginatedResponse<AuthEventResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page, 20 by default")
+    )
+)]
+pub async fn list_user_auth_events(
+    Tenant(tenant): Tenant,
+    pool: web::Data<DbPool>,
+    id: web::Path<Uuid>,
+    query: web::Query<ListAuthEventsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    {
+        let mut conn = get_connection(&pool)?;
+        let user = UserRepositoryImpl.find_by_id(&mut conn, *id).await?;
+        OrganizationRepositoryImpl
+            .find_governed(&mut conn, tenant, user.org_id)
+            .await
+            .map_err(|_| ApiError::not_found(format!("User with id {} not found", id)))?;
+    }
+    auth_events(&pool, *id, &query).await
+}
+
+/// Lists what the caller did, such as blocks signed off and documents uploaded, newest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/me/activity",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "Activity", body = PaginatedResponse<ActivityResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("date" = Option<String>, Query, description = "Only this day, as YYYY-MM-DD in the caller's timezone"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page, 20 by default")
+    )
+)]
+pub async fn list_my_activity(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    query: web::Query<ListActivityQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let caller = user_id(&user)?;
+    activity(&pool, caller, caller, &query).await
+}
+
+/// Lists what a user of the caller's organization did, such as blocks signed off and documents uploaded, newest first
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/users/{id}/activity",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "Activity", body = PaginatedResponse<ActivityResponse>),
+        (status = 400, description = "Invalid date", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Manager role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID"),
+        ("date" = Option<String>, Query, description = "Only this day, as YYYY-MM-DD in the caller's timezone"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Number of items per page, 20 by default")
+    )
+)]
+pub async fn list_user_activity(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    id: web::Path<Uuid>,
+    query: web::Query<ListActivityQuery>,
+) -> Result<HttpResponse, ApiError> {
+    activity(&pool, user_id(&user)?, *id, &query).await
+}
+
+/// Deactivates a user of the caller's organization
+///
+/// They keep their records but can't authenticate: their sessions end,
+/// their access tokens are rejected at once and they can't log in until
+/// reactivated.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/users/{id}/deactivate",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "User deactivated", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required, or the caller themselves", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn deactivate_user(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let admin = UserRepositoryImpl.find_by_id(&mut conn, user_id(&user)?).await?;
+    let user = ActivationService::deactivate(&mut conn, &config, &admin, *id, &client).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("User deactivated")
+            .with_data(UserResponse::new(user, &config))
+            .build()
+    ))
+}
+
+/// Lets a deactivated user of the caller's organization log in again
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/users/{id}/reactivate",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "User reactivated", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn reactivate_user(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let admin = UserRepositoryImpl.find_by_id(&mut conn, user_id(&user)?).await?;
+    let user = ActivationService::reactivate(&mut conn, &admin, *id, &client).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("User reactivated")
+            .with_data(UserResponse::new(user, &config))
+            .build()
+    ))
+}
+
+/// Irreversibly anonymizes a deactivated or removed user of the caller's organization
+///
+/// Their name and email become placeholders and the rest of their personal
+/// data is erased; what they produced stays attributed to the placeholder.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/users/{id}/anonymize",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "User anonymized", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required, or the caller themselves", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "User still active", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn anonymize_user(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    client: ClientInfo,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let admin = UserRepositoryImpl.find_by_id(&mut conn, user_id(&user)?).await?;
+    let user = AnonymizationService::anonymize(&mut conn, &config, &admin, *id, &client).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("User anonymized")
+            .with_data(UserResponse::new(user, &config))
+            .build()
+    ))
+}
+
+/// Sets who a user of the caller's organization reports to
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/users/{id}/supervisor",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    request_body = AssignSupervisorInput,
+    responses(
+        (status = 200, description = "Supervisor assigned", body = UserResponse),
+        (status = 400, description = "Not an active manager or admin, or a reporting loop", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn assign_supervisor(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    id: web::Path<Uuid>,
+    input: web::Json<AssignSupervisorInput>,
+) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(&pool)?;
+    let admin = UserRepositoryImpl.find_by_id(&mut conn, user_id(&user)?).await?;
+    let user = SupervisorService::assign(&mut conn, &admin, *id, input.supervisor_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Supervisor assigned")
+            .with_data(UserResponse::new(user, &config))
+            .build()
+    ))
+}
+
+async fn reports(pool: &DbPool, config: &Config, viewer_id: Uuid, supervisor_id: Uuid) -> Result<HttpResponse, ApiError> {
+    let mut conn = get_connection(pool)?;
+    let viewer = UserRepositoryImpl.find_by_id(&mut conn, viewer_id).await?;
+    let reports = SupervisorService::reports(&mut conn, &viewer, supervisor_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Direct reports retrieved successfully")
+            .with_data(reports.into_iter().map(|user| UserResponse::new(user, config)).collect::<ListResponse<_>>())
+            .build()
+    ))
+}
+
+/// Lists the users reporting directly to the caller, by name
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/me/reports",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "Direct reports", body = ListResponse<UserResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn list_my_reports(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let caller = user_id(&user)?;
+    reports(&pool, &config, caller, caller).await
+}
+
+/// Lists the users reporting directly to a user of the caller's organization, by name
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/users/{id}/reports",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    responses(
+        (status = 200, description = "Direct reports", body = ListResponse<UserResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub async fn list_user_reports(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    reports(&pool, &config, user_id(&user)?, *id).await
+}
+
+/// Asks to switch the caller to another email address
+///
+/// A confirmation link is mailed to the new address and a notice to the
+/// current one, which stays in use until the link is followed.
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/me/email-change",
+    security(("bearer_auth" = [])),
+    tag = "users",
+    request_body = EmailChangeInput,
+    responses(
+        (status = 202, description = "Confirmation link sent to the new address"),
+        (status = 400, description = "Invalid email, or the current one", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 409, description = "Email already registered", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn request_email_change(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    input: web::Json<EmailChangeInput>,
+) -> Result<HttpResponse, ApiError> {
+    AuthService::request_email_change(&pool, user_id(&user)?, &input.email, &config).await?;
+
+    Ok(HttpResponse::Accepted().json(
+        ApiResponseBuilder::success()
+            .with_message("A confirmation link has been sent to the new email")
+            .with_data(json!({}))
+            .build()
+    ))
+}
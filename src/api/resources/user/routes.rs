@@ -0,0 +1,49 @@
+use actix_web::web;
+use crate::{
+    api::middleware::auth::{Auth, RequireRole},
+    db::models::auth::Role,
+    domain::user::MAX_AVATAR_BYTES,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/me")
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::user::handlers::get_me))
+            .route("", web::patch().to(crate::api::resources::user::handlers::update_me))
+            .service(
+                web::resource("/avatar")
+                    .app_data(web::PayloadConfig::new(MAX_AVATAR_BYTES))
+                    .route(web::put().to(crate::api::resources::user::handlers::upload_my_avatar))
+            )
+            .route("/preferences", web::get().to(crate::api::resources::user::handlers::get_my_preferences))
+            .route("/preferences", web::patch().to(crate::api::resources::user::handlers::update_my_preferences))
+            .route("/auth-events", web::get().to(crate::api::resources::user::handlers::list_my_auth_events))
+            .route("/activity", web::get().to(crate::api::resources::user::handlers::list_my_activity))
+            .route("/reports", web::get().to(crate::api::resources::user::handlers::list_my_reports))
+            .route("/email-change", web::post().to(crate::api::resources::user::handlers::request_email_change))
+    );
+    cfg.service(
+        web::scope("/avatars")
+            .route("/{user_id}/{size}", web::get().to(crate::api::resources::user::handlers::get_avatar))
+    );
+    // Registered before the `/users` scope, which would take the path and
+    // require the admin role
+    cfg.service(
+        web::resource("/users/{id}/activity")
+            .wrap(RequireRole::new(Role::Manager))
+            .wrap(Auth::new())
+            .route(web::get().to(crate::api::resources::user::handlers::list_user_activity))
+    );
+    cfg.service(
+        web::scope("/users")
+            .wrap(RequireRole::new(Role::Admin))
+            .wrap(Auth::new())
+            .route("/{id}/auth-events", web::get().to(crate::api::resources::user::handlers::list_user_auth_events))
+            .route("/{id}/deactivate", web::post().to(crate::api::resources::user::handlers::deactivate_user))
+            .route("/{id}/reactivate", web::post().to(crate::api::resources::user::handlers::reactivate_user))
+            .route("/{id}/anonymize", web::post().to(crate::api::resources::user::handlers::anonymize_user))
+            .route("/{id}/supervisor", web::put().to(crate::api::resources::user::handlers::assign_supervisor))
+            .route("/{id}/reports", web::get().to(crate::api::resources::user::handlers::list_user_reports))
+    );
+}
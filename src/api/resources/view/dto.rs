@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::db::models::SavedView;
+
+/// Input for saving a view or replacing one
+#[derive(Debug, Deserialize, ValidatorValidate, ToSchema, TS)]
+#[ts(export)]
+pub struct SaveViewInput {
+    /// List the view applies to, e.g. `customers`; it cannot change once
+    /// saved
+    pub resource: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    /// Query parameters the list is filtered with
+    #[serde(default)]
+    #[ts(type = "Record<string, unknown>")]
+    pub filters: serde_json::Value,
+    /// Fields sorted by, in order, with `-` for descending
+    #[serde(default)]
+    pub sort: Vec<String>,
+    /// Columns shown, in order
+    #[serde(default)]
+    pub columns: Vec<String>,
+    /// Whether the rest of the organization can use the view
+    #[serde(default)]
+    pub shared: bool,
+}
+
+/// Query parameters for listing views
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListViewsQuery {
+    /// Only views of this resource
+    pub resource: Option<String>,
+}
+
+/// Saved view response
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SavedViewResponse {
+    pub id: Uuid,
+    pub resource: String,
+    pub name: String,
+    #[ts(type = "Record<string, unknown>")]
+    pub filters: serde_json::Value,
+    pub sort: Vec<String>,
+    pub columns: Vec<String>,
+    pub shared: bool,
+    /// User who saved the view
+    pub user_id: Uuid,
+    /// Whether the list of the resource opens with this view for the
+    /// requesting user
+    pub is_default: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SavedViewResponse {
+    pub fn new(view: SavedView, is_default: bool) -> Self {
+        Self {
+            id: view.id,
+            resource: view.resource,
+            name: view.name,
+            filters: view.filters,
+            sort: view.sort,
+            columns: view.columns,
+            shared: view.shared,
+            user_id: view.user_id,
+            is_default,
+            created_at: view.created_at,
+            updated_at: view.updated_at,
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Saved view resource handlers
+//!
+//! Every handler works on the views the authenticated user saved or that
+//! are shared within their organization; only a view's owner can change
+//! it.
+
+use crate::{
+    api::{
+        middleware::{AuthenticatedUser, Tenant},
+        resources::view::dto::{ListViewsQuery, SaveViewInput, SavedViewResponse},
+        utils::{ApiResponseBuilder, ErrorResponse, ListResponse},
+    },
+    db::{get_connection, repositories::SavedViewRepositoryImpl, DbPool},
+    domain::view::SavedViewService,
+    error::ApiError,
+};
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+
+fn service() -> SavedViewService<SavedViewRepositoryImpl> {
+    SavedViewService::new(SavedViewRepositoryImpl)
+}
+
+fn user_id(user: &AuthenticatedUser) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(user.user_id()).map_err(|_| ApiError::unauthorized("Invalid token subject"))
+}
+
+/// Lists the user's views and those shared with them, by resource and name
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/views",
+    security(("bearer_auth" = [])),
+    tag = "views",
+    responses(
+        (status = 200, description = "Views", body = ListResponse<SavedViewResponse>),
+        (status = 400, description = "Invalid resource", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("resource" = Option<String>, Query, description = "Only views of this resource")
+    )
+)]
+pub async fn list_views(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    query: web::Query<ListViewsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user_id(&user)?;
+    let mut conn = get_connection(&pool)?;
+    let views = service().list(&mut conn, org_id, user_id, query.resource.as_deref()).await?;
+    let views = views
+        .into_iter()
+        .map(|(view, is_default)| SavedViewResponse::new(view, is_default))
+        .collect::<ListResponse<_>>();
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Views retrieved successfully")
+            .with_data(views)
+            .build()
+    ))
+}
+
+/// Saves a view
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    post,
+    path = "/v1/views",
+    security(("bearer_auth" = [])),
+    tag = "views",
+    request_body = SaveViewInput,
+    responses(
+        (status = 201, description = "View saved", body = SavedViewResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 409, description = "The user has a view of the resource with this name", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_view(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    input: web::Json<SaveViewInput>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user_id(&user)?;
+    let mut conn = get_connection(&pool)?;
+    let view = service().create(&mut conn, org_id, user_id, input.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(
+        ApiResponseBuilder::success()
+            .with_message("View saved successfully")
+            .with_data(SavedViewResponse::new(view, false))
+            .build()
+    ))
+}
+
+/// Retrieves a view
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/views/{id}",
+    security(("bearer_auth" = [])),
+    tag = "views",
+    responses(
+        (status = 200, description = "View", body = SavedViewResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "View not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "View ID")
+    )
+)]
+pub async fn get_view(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    view_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user_id(&user)?;
+    let mut conn = get_connection(&pool)?;
+    let (view, is_default) = service().get(&mut conn, org_id, user_id, *view_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("View retrieved successfully")
+            .with_data(SavedViewResponse::new(view, is_default))
+            .build()
+    ))
+}
+
+/// Replaces the configuration of one of the user's views
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/views/{id}",
+    security(("bearer_auth" = [])),
+    tag = "views",
+    request_body = SaveViewInput,
+    responses(
+        (status = 200, description = "View updated", body = SavedViewResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "The view was saved by another user", body = ErrorResponse),
+        (status = 404, description = "View not found", body = ErrorResponse),
+        (status = 409, description = "The user has a view of the resource with this name", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "View ID")
+    )
+)]
+pub async fn update_view(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    view_id: web::Path<Uuid>,
+    input: web::Json<SaveViewInput>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user_id(&user)?;
+    let mut conn = get_connection(&pool)?;
+    let service = service();
+    let view = service.update(&mut conn, org_id, user_id, *view_id, input.into_inner()).await?;
+    let (view, is_default) = service.get(&mut conn, org_id, user_id, view.id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("View updated successfully")
+            .with_data(SavedViewResponse::new(view, is_default))
+            .build()
+    ))
+}
+
+/// Deletes one of the user's views
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/views/{id}",
+    security(("bearer_auth" = [])),
+    tag = "views",
+    responses(
+        (status = 204, description = "View deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "The view was saved by another user", body = ErrorResponse),
+        (status = 404, description = "View not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "View ID")
+    )
+)]
+pub async fn delete_view(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    view_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user_id(&user)?;
+    let mut conn = get_connection(&pool)?;
+    service().delete(&mut conn, org_id, user_id, *view_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Makes a view the one the user opens its resource's list with
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    put,
+    path = "/v1/views/{id}/default",
+    security(("bearer_auth" = [])),
+    tag = "views",
+    responses(
+        (status = 200, description = "Default view set", body = SavedViewResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "View not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "View ID")
+    )
+)]
+pub async fn set_default_view(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    view_id: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user_id(&user)?;
+    let mut conn = get_connection(&pool)?;
+    let view = service().set_default(&mut conn, org_id, user_id, *view_id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Default view set successfully")
+            .with_data(SavedViewResponse::new(view, true))
+            .build()
+    ))
+}
+
+/// Retrieves the view the user opens a resource's list with
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    get,
+    path = "/v1/views/defaults/{resource}",
+    security(("bearer_auth" = [])),
+    tag = "views",
+    responses(
+        (status = 200, description = "Default view", body = SavedViewResponse),
+        (status = 400, description = "Invalid resource", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "No default view", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("resource" = String, Path, description = "Resource, e.g. `customers`")
+    )
+)]
+pub async fn get_default_view(
+    user: AuthenticatedUser,
+    Tenant(org_id): Tenant,
+    pool: web::Data<DbPool>,
+    resource: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user_id(&user)?;
+    let mut conn = get_connection(&pool)?;
+    let view = service()
+        .default_view(&mut conn, org_id, user_id, &resource)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("No default {} view", resource)))?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponseBuilder::success()
+            .with_message("Default view retrieved successfully")
+            .with_data(SavedViewResponse::new(view, true))
+            .build()
+    ))
+}
+
+/// Makes the user's list of a resource open without a view again
+///
+/// # OpenAPI Specification
+#[utoipa::path(
+    delete,
+    path = "/v1/views/defaults/{resource}",
+    security(("bearer_auth" = [])),
+    tag = "views",
+    responses(
+        (status = 204, description = "Default view cleared"),
+        (status = 400, description = "Invalid resource", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "No default view", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    params(
+        ("resource" = String, Path, description = "Resource, e.g. `customers`")
+    )
+)]
+pub async fn clear_default_view(
+    user: AuthenticatedUser,
+    pool: web::Data<DbPool>,
+    resource: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = user_id(&user)?;
+    let mut conn = get_connection(&pool)?;
+    service().clear_default(&mut conn, user_id, &resource).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
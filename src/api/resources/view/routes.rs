@@ -0,0 +1,19 @@
+use actix_web::web;
+use crate::api::middleware::auth::{Auth, RequireAuth};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/views")
+            .wrap(RequireAuth)
+            .wrap(Auth::new())
+            .route("", web::get().to(crate::api::resources::view::handlers::list_views))
+            .route("", web::post().to(crate::api::resources::view::handlers::create_view))
+            // Registered before `/{id}` so `defaults` is not taken for a view id
+            .route("/defaults/{resource}", web::get().to(crate::api::resources::view::handlers::get_default_view))
+            .route("/defaults/{resource}", web::delete().to(crate::api::resources::view::handlers::clear_default_view))
+            .route("/{id}", web::get().to(crate::api::resources::view::handlers::get_view))
+            .route("/{id}", web::put().to(crate::api::resources::view::handlers::update_view))
+            .route("/{id}", web::delete().to(crate::api::resources::view::handlers::delete_view))
+            .route("/{id}/default", web::put().to(crate::api::resources::view::handlers::set_default_view))
+    );
+}
@@ -1,6 +1,7 @@
+pub mod multipart;
 pub mod pagination;
 pub mod responses;
 
 // Re-export commonly used types
 pub use pagination::{PaginationParams, PaginatedResponse};
-pub use responses::{ApiResponse, ApiResponseBuilder, ErrorResponse}; 
\ No newline at end of file
+pub use responses::{ApiResponse, ApiResponseBuilder, ErrorResponse, ListResponse}; 
\ No newline at end of file
@@ -0,0 +1,135 @@
+//! `multipart/form-data` request bodies
+//!
+//! Browsers send file inputs this way. Bodies are small and already
+//! buffered by the time a handler sees them, so parts are sliced out of
+//! them rather than streamed.
+
+use actix_web::web::Bytes;
+use serde_json::json;
+
+use crate::error::{ApiError, ErrorContext, Result};
+
+/// One part of a `multipart/form-data` body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormPart {
+    /// Form field the part was sent as
+    pub name: Option<String>,
+    /// File name, for file inputs
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub body: Bytes,
+}
+
+fn malformed(message: &str) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": "body",
+            "code": "INVALID_MULTIPART",
+        })),
+    )
+}
+
+/// The boundary a `multipart/form-data` content type names
+fn boundary(content_type: &str) -> Option<&str> {
+    let mut params = content_type.split(';');
+    if !params.next()?.trim().eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    params
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, value)| value.trim().trim_matches('"'))
+        .filter(|boundary| !boundary.is_empty() && boundary.len() <= 70)
+}
+
+/// A parameter of a `Content-Disposition` header, e.g. `name="avatar"`
+fn disposition_param(disposition: &str, key: &str) -> Option<String> {
+    disposition
+        .split(';')
+        .skip(1)
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(key))
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Splits a `multipart/form-data` body into its parts
+pub fn parse(content_type: &str, body: &Bytes) -> Result<Vec<FormPart>> {
+    let boundary = boundary(content_type)
+        .ok_or_else(|| malformed("Content-Type must be multipart/form-data with a boundary"))?;
+    let delimiter = format!("--{}", boundary);
+    let start = find(body, delimiter.as_bytes()).ok_or_else(|| malformed("Multipart body has no parts"))?;
+    let next_delimiter = format!("\r\n--{}", boundary);
+
+    let mut parts = Vec::new();
+    let mut offset = start + delimiter.len();
+    loop {
+        let rest = &body[offset..];
+        if rest.starts_with(b"--") {
+            return Ok(parts);
+        }
+        let rest = rest.strip_prefix(b"\r\n").ok_or_else(|| malformed("Malformed multipart delimiter"))?;
+        let headers_end = find(rest, b"\r\n\r\n").ok_or_else(|| malformed("Multipart part has no headers"))?;
+        let headers = std::str::from_utf8(&rest[..headers_end]).map_err(|_| malformed("Multipart headers must be UTF-8"))?;
+        let content = &rest[headers_end + 4..];
+        let length = find(content, next_delimiter.as_bytes()).ok_or_else(|| malformed("Multipart body is not terminated"))?;
+
+        let mut part = FormPart {
+            name: None,
+            filename: None,
+            content_type: None,
+            body: Bytes::new(),
+        };
+        for header in headers.split("\r\n") {
+            let Some((name, value)) = header.split_once(':') else {
+                continue;
+            };
+            if name.trim().eq_ignore_ascii_case("content-disposition") {
+                part.name = disposition_param(value, "name");
+                part.filename = disposition_param(value, "filename");
+            } else if name.trim().eq_ignore_ascii_case("content-type") {
+                part.content_type = Some(value.trim().to_string());
+            }
+        }
+        let content_start = body.len() - content.len();
+        part.body = body.slice(content_start..content_start + length);
+        parts.push(part);
+
+        offset = content_start + length + next_delimiter.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let body = Bytes::from_static(
+            b"preamble\r\n--XyZ\r\nContent-Disposition: form-data; name=\"note\"\r\n\r\nhello\r\n\
+              --XyZ\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"me.png\"\r\n\
+              Content-Type: image/png\r\n\r\n\x89PNG\r\n--X\r\n--XyZ--\r\n",
+        );
+        let parts = parse("multipart/form-data; boundary=\"XyZ\"", &body).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].name.as_deref(), Some("note"));
+        assert_eq!(parts[0].filename, None);
+        assert_eq!(&parts[0].body[..], b"hello");
+        assert_eq!(parts[1].name.as_deref(), Some("avatar"));
+        assert_eq!(parts[1].filename.as_deref(), Some("me.png"));
+        assert_eq!(parts[1].content_type.as_deref(), Some("image/png"));
+        assert_eq!(&parts[1].body[..], b"\x89PNG\r\n--X");
+    }
+
+    #[test]
+    fn test_rejects_other_bodies() {
+        let body = Bytes::from_static(b"--XyZ\r\n\r\nunterminated");
+        assert!(parse("image/png", &body).is_err());
+        assert!(parse("multipart/form-data", &body).is_err());
+        assert!(parse("multipart/form-data; boundary=XyZ", &body).is_err());
+    }
+}
@@ -1,9 +1,15 @@
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+use crate::db::count::RowCount;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
 pub struct PaginationParams {
+    #[ts(type = "number")]
     pub page: i64,
+    #[ts(type = "number")]
     pub per_page: i64,
 }
 
@@ -33,34 +39,52 @@ impl Default for PaginationParams {
     }
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
 pub struct PaginatedResponse<T> {
     pub data: Vec<T>,
     pub meta: PaginationMeta,
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
 pub struct PaginationMeta {
+    #[ts(type = "number")]
     pub current_page: i64,
+    #[ts(type = "number")]
     pub per_page: i64,
+    #[ts(type = "number")]
     pub total_items: i64,
+    #[ts(type = "number")]
     pub total_pages: i64,
     pub has_next_page: bool,
     pub has_previous_page: bool,
+    /// Whether `total_items` is an exact count or a planner estimate
+    pub exact: bool,
 }
 
 impl<T> PaginatedResponse<T> {
     pub fn new(data: Vec<T>, total: i64, pagination: &PaginationParams) -> Self {
-        let total_pages = (total as f64 / pagination.per_page as f64).ceil() as i64;
+        Self::with_count(data, RowCount::exact(total), pagination)
+    }
+
+    /// Creates a paginated response from a possibly estimated row count
+    ///
+    /// When the count is an estimate, `has_next_page` also turns true for a
+    /// full page so clients keep paging past an underestimated total.
+    pub fn with_count(data: Vec<T>, count: RowCount, pagination: &PaginationParams) -> Self {
+        let total_pages = (count.total as f64 / pagination.per_page as f64).ceil() as i64;
+        let full_page = data.len() as i64 >= pagination.per_page;
         Self {
             data,
             meta: PaginationMeta {
                 current_page: pagination.page,
                 per_page: pagination.per_page,
-                total_items: total,
+                total_items: count.total,
                 total_pages,
-                has_next_page: pagination.page < total_pages,
+                has_next_page: pagination.page < total_pages || (!count.exact && full_page),
                 has_previous_page: pagination.page > 1,
+                exact: count.exact,
             },
         }
     }
@@ -1,8 +1,10 @@
 use serde::Serialize;
 use std::fmt;
+use ts_rs::TS;
 use utoipa::ToSchema;
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, ToSchema, TS)]
+#[ts(export)]
 pub struct ApiResponse<T> {
     #[serde(flatten)]
     pub data: T,
@@ -10,6 +12,27 @@ pub struct ApiResponse<T> {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// A complete list, for collections too small to page through
+///
+/// [`ApiResponse`] flattens its data into the body, which a bare list can't be.
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ListResponse<T> {
+    pub data: Vec<T>,
+}
+
+impl<T> ListResponse<T> {
+    pub fn new(data: Vec<T>) -> Self {
+        Self { data }
+    }
+}
+
+impl<T> FromIterator<T> for ListResponse<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
 pub struct ApiResponseBuilder<T> {
     status: u16,
     message: String,
@@ -56,11 +79,18 @@ impl<T> ApiResponseBuilder<T> {
     }
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
 pub struct ErrorResponse {
     pub code: String,
     pub message: String,
     pub details: Option<serde_json::Value>,
+    /// Whether repeating the request later may succeed
+    pub retryable: bool,
+    /// Seconds to wait before retrying, mirrored in the `Retry-After` header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional, type = "number")]
+    pub retry_after: Option<u64>,
 }
 
 impl ErrorResponse {
@@ -69,6 +99,8 @@ impl ErrorResponse {
             code: code.to_string(),
             message: message.to_string(),
             details,
+            retryable: false,
+            retry_after: None,
         }
     }
 }
@@ -0,0 +1,116 @@
+//! Seeded fixtures for the benchmarks in `benches/`
+//!
+//! Compiled with the `bench` feature only. The benchmarks run against the
+//! database of `DATABASE_URL`, which [`BenchFixture::seed`] tops up to a
+//! fixed number of organizations and a benchmark admin, so repeated runs
+//! measure the same data and their numbers can be compared.
+
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{
+            auth::{Role, User},
+            Organization,
+        },
+        repositories::{Repository, UserRepository, UserRepositoryImpl},
+        schema::organizations,
+    },
+    domain::TokenManager,
+    error::{ApiError, DatabaseError, Result},
+    utils::Config,
+};
+
+/// Organizations listed by the pagination benchmarks
+pub const BENCH_ORGANIZATIONS: i64 = 5_000;
+pub const BENCH_ADMIN_EMAIL: &str = "bench-admin@example.com";
+pub const BENCH_PASSWORD: &str = "bench_password";
+
+const BENCH_ORG_NAME_PREFIX: &str = "Bench Organization";
+const INSERT_BATCH_SIZE: usize = 1_000;
+
+/// Configuration, seeded data and credentials shared by the benchmarks
+pub struct BenchFixture {
+    pub config: Config,
+    pub admin: User,
+    /// Access token of the benchmark admin
+    pub token: String,
+}
+
+impl BenchFixture {
+    /// Loads the configuration and seeds whatever data is missing
+    ///
+    /// The rate limit is lifted, as every iteration would otherwise count
+    /// against the same client.
+    pub async fn seed() -> Result<Self> {
+        std::env::set_var("LIVE__RATE_LIMIT__MAX_REQUESTS", u32::MAX.to_string());
+        let config = Config::load().map_err(|e| ApiError::configuration_error(e.to_string()))?;
+        let mut conn = config
+            .pool()
+            .get()
+            .map_err(|e| ApiError::database_error(format!("Failed to get a connection: {}", e), None))?;
+
+        let seeded = organizations::table
+            .filter(organizations::name.like(format!("{}%", BENCH_ORG_NAME_PREFIX)))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .map_err(|e| ApiError::from(DatabaseError::from(e)))?;
+        let missing = (BENCH_ORGANIZATIONS - seeded).max(0) as usize;
+        let now = Utc::now();
+        let new_organizations: Vec<Organization> = (0..missing)
+            .map(|_| Organization {
+                id: Uuid::new_v4(),
+                name: format!("{} {}", BENCH_ORG_NAME_PREFIX, Uuid::new_v4()),
+                created_at: now,
+                updated_at: now,
+                deleted_at: None,
+                parent_id: None,
+                owner_id: None,
+                archived_at: None,
+            })
+            .collect();
+        for batch in new_organizations.chunks(INSERT_BATCH_SIZE) {
+            diesel::insert_into(organizations::table)
+                .values(batch)
+                .execute(&mut conn)
+                .map_err(|e| ApiError::from(DatabaseError::from(e)))?;
+        }
+
+        let users = UserRepositoryImpl;
+        let admin = match users.find_by_email(&mut conn, BENCH_ADMIN_EMAIL).await? {
+            Some(admin) => admin,
+            None => {
+                let org_id = organizations::table
+                    .select(organizations::id)
+                    .filter(organizations::name.like(format!("{}%", BENCH_ORG_NAME_PREFIX)))
+                    .first::<Uuid>(&mut conn)
+                    .map_err(|e| ApiError::from(DatabaseError::from(e)))?;
+                users
+                    .create(&mut conn, &User {
+                        id: Uuid::new_v4(),
+                        first_name: "Bench".to_string(),
+                        last_name: "Admin".to_string(),
+                        email: BENCH_ADMIN_EMAIL.to_string(),
+                        phone_number: "+10000000000".to_string(),
+                        password: User::hash_password(BENCH_PASSWORD)?,
+                        org_id,
+                        role: Role::Admin,
+                        email_verified: true,
+                        avatar_updated_at: None,
+                        is_active: true,
+                        supervisor_id: None,
+                        anonymized_at: None,
+                        created_at: now,
+                        updated_at: now,
+                        deleted_at: None,
+                    })
+                    .await?
+            }
+        };
+
+        let token = TokenManager::generate_token(&admin, &config)?;
+        Ok(Self { config, admin, token })
+    }
+}
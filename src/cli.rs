@@ -0,0 +1,175 @@
+//! Command line interface
+//!
+//! Without a subcommand the binary starts the server. Subcommands run a
+//! single task and exit, most of them without loading the configuration or
+//! touching the database.
+
+use std::{fs, io::Write, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use utoipa::OpenApi;
+
+use crate::{
+    api::resources::docs::openapi::ApiDoc,
+    db::{
+        anonymize,
+        seed::{self, SeedProfile},
+    },
+    utils::Config,
+};
+
+#[derive(Debug, Parser)]
+#[command(name = crate::NAME, version = crate::VERSION, about = "Forestry optimizer API server")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the API server (the default)
+    Serve,
+    /// Write the OpenAPI document without starting the server
+    ExportOpenapi {
+        /// Output file, standard output when omitted or `-`
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
+    /// Fill the database with a reproducible dataset, skipping rows that
+    /// already exist
+    Seed {
+        #[arg(long, value_enum, default_value_t = SeedProfile::Demo)]
+        profile: SeedProfile,
+    },
+    /// Replace personal data in a restored production copy with
+    /// deterministic fakes
+    Anonymize {
+        /// Confirm that the configured database is a copy that may be
+        /// rewritten
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+/// The OpenAPI document served at `/v1/docs/openapi.json`, pretty-printed
+pub fn openapi_json() -> std::io::Result<String> {
+    ApiDoc::openapi().to_pretty_json().map_err(std::io::Error::other)
+}
+
+/// Writes the OpenAPI document to `out`, or standard output
+pub fn export_openapi(out: Option<&PathBuf>) -> std::io::Result<()> {
+    let mut json = openapi_json()?;
+    json.push('\n');
+    match out.filter(|path| path.as_os_str() != "-") {
+        Some(path) => {
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, json)
+        }
+        None => std::io::stdout().lock().write_all(json.as_bytes()),
+    }
+}
+
+/// Seeds the database of `config` with `profile`
+///
+/// Refuses to run in production, where made-up users with a published
+/// password must never appear.
+pub async fn seed(config: &Config, profile: SeedProfile) -> std::io::Result<()> {
+    if config.environment.is_production() {
+        return Err(std::io::Error::other("refusing to seed a production database"));
+    }
+    let mut conn = config.pool().get().map_err(std::io::Error::other)?;
+    let report = seed::seed(&mut conn, profile)
+        .await
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!(
+        "Seeded organization {} with {} users and {} notifications ({} rows inserted), password {:?}",
+        report.organization_id,
+        report.users,
+        report.notifications,
+        report.inserted,
+        seed::DEMO_PASSWORD,
+    );
+    Ok(())
+}
+
+/// Anonymizes the database of `config`
+///
+/// Runs only with `yes`, as the rewrite cannot be undone, and never in
+/// production: copies are restored into a development or staging database.
+pub async fn anonymize(config: &Config, yes: bool) -> std::io::Result<()> {
+    if config.environment.is_production() {
+        return Err(std::io::Error::other("refusing to anonymize a production database"));
+    }
+    if !yes {
+        return Err(std::io::Error::other(
+            "anonymize rewrites every user, organization and token in the configured database; pass --yes to proceed",
+        ));
+    }
+    let mut conn = config.pool().get().map_err(std::io::Error::other)?;
+    let report = anonymize::anonymize(&mut conn)
+        .await
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!(
+        "Anonymized {} organizations, {} users, {} identities, {} email senders, {} customers, {} tender bids, {} report schedules, {} notifications, {} legal holds and {} history entries; deleted {} tokens and jobs; password {:?}",
+        report.organizations,
+        report.users,
+        report.identities,
+        report.email_senders,
+        report.customers,
+        report.tender_bids,
+        report.report_schedules,
+        report.notifications,
+        report.legal_holds,
+        report.change_history,
+        report.deleted,
+        anonymize::ANONYMIZED_PASSWORD,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_export_openapi() {
+        let cli = Cli::try_parse_from(["rust_server", "export-openapi", "--out", "build/openapi.json"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::ExportOpenapi { out: Some(ref path) }) if path == &PathBuf::from("build/openapi.json")));
+
+        let cli = Cli::try_parse_from(["rust_server"]).unwrap();
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn test_parse_seed() {
+        let cli = Cli::try_parse_from(["rust_server", "seed", "--profile", "demo"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Seed { profile: SeedProfile::Demo })));
+
+        let cli = Cli::try_parse_from(["rust_server", "seed"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Seed { profile: SeedProfile::Demo })));
+
+        assert!(Cli::try_parse_from(["rust_server", "seed", "--profile", "production"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_anonymize() {
+        let cli = Cli::try_parse_from(["rust_server", "anonymize", "--yes"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Anonymize { yes: true })));
+
+        let cli = Cli::try_parse_from(["rust_server", "anonymize"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Anonymize { yes: false })));
+    }
+
+    #[test]
+    fn test_export_openapi_writes_document() {
+        let path = std::env::temp_dir().join(format!("openapi-{}", uuid::Uuid::new_v4())).join("openapi.json");
+        export_openapi(Some(&path)).unwrap();
+
+        let document: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(document["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(document["paths"]["/v1/organizations"].is_object());
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+}
@@ -0,0 +1,290 @@
+//! Anonymization of restored production copies and of departed users
+//!
+//! `rust_server anonymize` rewrites personal data in the configured database
+//! so support and developers can debug against production-shaped data. Ids
+//! are kept, so every foreign key still points where it did; names, emails
+//! and phone numbers are replaced with fakes derived from the row id, which
+//! makes the result the same however often, or on whichever copy, it runs.
+//!
+//! Customers' contacts, bidders and report recipients are faked the same
+//! way, and the values kept in change history are cleared, as they may be
+//! any of these. Credentials and anything that could reach a real person
+//! go entirely: passwords become [`ANONYMIZED_PASSWORD`], outstanding
+//! tokens are deleted, and so are queued and dead-lettered jobs, whose
+//! payloads carry addresses.
+//!
+//! [`anonymize_user`] scrubs one user in production, for good, when they
+//! ask to be forgotten. Their id, and so the records they produced, stay.
+
+use chrono::Utc;
+use diesel::prelude::*;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::auth::User,
+        schema::{
+            auth_events, change_history, customers, dead_letter_jobs, devices, email_change_tokens, email_verification_tokens,
+            legal_holds, magic_link_tokens, notifications, org_invitations, organization_email_senders, organizations, passkeys,
+            password_reset_tokens, queued_jobs, refresh_tokens, report_schedules, sale_contracts, scim_tokens, telemetry_api_keys, tender_bids,
+            user_identities, user_preferences, users,
+        },
+        seed::{FIRST_NAMES, LAST_NAMES},
+    },
+    error::{ApiError, DatabaseError, Result},
+};
+
+/// Password of every user once anonymized
+pub const ANONYMIZED_PASSWORD: &str = "anonymized-password";
+
+const ANONYMIZED_DOMAIN: &str = "anonymized.invalid";
+
+/// Name an anonymized user is shown under
+pub const FORGOTTEN_FIRST_NAME: &str = "Former";
+pub const FORGOTTEN_LAST_NAME: &str = "member";
+
+/// Rows rewritten or deleted by [`anonymize`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnonymizeReport {
+    pub organizations: usize,
+    pub users: usize,
+    /// Identities of users at single sign-on providers
+    pub identities: usize,
+    pub email_senders: usize,
+    pub customers: usize,
+    pub tender_bids: usize,
+    pub report_schedules: usize,
+    pub notifications: usize,
+    pub legal_holds: usize,
+    pub change_history: usize,
+    /// Tokens and jobs deleted
+    pub deleted: usize,
+}
+
+/// Fake personal data of one user, derived from its id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeUser {
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub phone_number: String,
+}
+
+impl FakeUser {
+    pub fn for_id(id: Uuid) -> Self {
+        let mut rng = rng_for(id);
+        let first_name = FIRST_NAMES.choose(&mut rng).unwrap().to_string();
+        let last_name = LAST_NAMES.choose(&mut rng).unwrap().to_string();
+        Self {
+            // The id keeps addresses unique, as the column requires
+            email: format!("user-{}@{}", id.simple(), ANONYMIZED_DOMAIN),
+            phone_number: format!("+1555{:07}", rng.gen_range(0..10_000_000)),
+            first_name,
+            last_name,
+        }
+    }
+}
+
+/// Fake display name of an organization, derived from its id
+pub fn fake_organization_name(id: Uuid) -> String {
+    let mut rng = rng_for(id);
+    format!("{} Forest Products {}", LAST_NAMES.choose(&mut rng).unwrap(), &id.simple().to_string()[..6])
+}
+
+fn rng_for(id: Uuid) -> ChaCha8Rng {
+    let mut seed = [0u8; 32];
+    seed[..16].copy_from_slice(id.as_bytes());
+    seed[16..].copy_from_slice(id.as_bytes());
+    ChaCha8Rng::from_seed(seed)
+}
+
+/// Rewrites personal data in the database in one transaction
+pub async fn anonymize(conn: &mut PgConnection) -> Result<AnonymizeReport> {
+    let password = User::hash_password(ANONYMIZED_PASSWORD)?;
+    conn.transaction(|conn| run(conn, &password))
+        .map_err(|e| ApiError::from(DatabaseError::from(e)))
+}
+
+fn run(conn: &mut PgConnection, password: &str) -> QueryResult<AnonymizeReport> {
+    let mut report = AnonymizeReport::default();
+
+    for id in organizations::table.select(organizations::id).load::<Uuid>(conn)? {
+        report.organizations += diesel::update(organizations::table.find(id))
+            .set(organizations::name.eq(fake_organization_name(id)))
+            .execute(conn)?;
+    }
+
+    for id in users::table.select(users::id).load::<Uuid>(conn)? {
+        let fake = FakeUser::for_id(id);
+        report.users += diesel::update(users::table.find(id))
+            .set((
+                users::first_name.eq(fake.first_name),
+                users::last_name.eq(fake.last_name),
+                users::email.eq(fake.email),
+                users::phone_number.eq(fake.phone_number),
+                users::password.eq(password),
+            ))
+            .execute(conn)?;
+    }
+
+    // Identities carry the address the provider knows the user by
+    let identities = user_identities::table
+        .select((user_identities::provider, user_identities::subject, user_identities::user_id))
+        .load::<(String, String, Uuid)>(conn)?;
+    for (provider, subject, user_id) in identities {
+        report.identities += diesel::update(user_identities::table.find((provider, subject)))
+            .set(user_identities::email.eq(FakeUser::for_id(user_id).email))
+            .execute(conn)?;
+    }
+
+    let senders = organization_email_senders::table
+        .select(organization_email_senders::org_id)
+        .load::<Uuid>(conn)?;
+    for org_id in senders {
+        let address = format!("org-{}@{}", org_id.simple(), ANONYMIZED_DOMAIN);
+        report.email_senders += diesel::update(organization_email_senders::table.find(org_id))
+            .set((
+                organization_email_senders::from_address.eq(&address),
+                organization_email_senders::from_name.eq(fake_organization_name(org_id)),
+                organization_email_senders::reply_to.eq(None::<String>),
+            ))
+            .execute(conn)?;
+    }
+
+    // Customers keep their business name; who to reach there is faked
+    let contacts = customers::table
+        .select((customers::id, customers::contact_name, customers::email, customers::phone_number))
+        .load::<(Uuid, Option<String>, Option<String>, Option<String>)>(conn)?;
+    for (id, contact_name, email, phone_number) in contacts {
+        let fake = FakeUser::for_id(id);
+        report.customers += diesel::update(customers::table.find(id))
+            .set((
+                customers::contact_name.eq(contact_name.map(|_| format!("{} {}", fake.first_name, fake.last_name))),
+                customers::email.eq(email.map(|_| format!("customer-{}@{}", id.simple(), ANONYMIZED_DOMAIN))),
+                customers::phone_number.eq(phone_number.map(|_| fake.phone_number)),
+                customers::address.eq(None::<String>),
+                customers::notes.eq(None::<String>),
+            ))
+            .execute(conn)?;
+    }
+
+    let bids = tender_bids::table
+        .select((tender_bids::id, tender_bids::bidder_email))
+        .load::<(Uuid, Option<String>)>(conn)?;
+    for (id, email) in bids {
+        report.tender_bids += diesel::update(tender_bids::table.find(id))
+            .set((
+                tender_bids::bidder_name.eq(fake_organization_name(id)),
+                tender_bids::bidder_email.eq(email.map(|_| format!("bidder-{}@{}", id.simple(), ANONYMIZED_DOMAIN))),
+            ))
+            .execute(conn)?;
+        // The contract awarded from a bid names its bidder as the buyer
+        diesel::update(sale_contracts::table.filter(sale_contracts::bid_id.eq(id)))
+            .set(sale_contracts::buyer_name.eq(fake_organization_name(id)))
+            .execute(conn)?;
+    }
+
+    let schedules = report_schedules::table
+        .select((report_schedules::id, report_schedules::recipients))
+        .load::<(Uuid, Vec<String>)>(conn)?;
+    for (id, recipients) in schedules {
+        let recipients: Vec<String> = (1..=recipients.len())
+            .map(|n| format!("recipient-{}-{}@{}", n, id.simple(), ANONYMIZED_DOMAIN))
+            .collect();
+        report.report_schedules += diesel::update(report_schedules::table.find(id))
+            .set(report_schedules::recipients.eq(recipients))
+            .execute(conn)?;
+    }
+
+    // Bodies and data are free text written by users; titles are generated
+    report.notifications = diesel::update(notifications::table)
+        .set((notifications::body.eq(None::<String>), notifications::data.eq(None::<serde_json::Value>)))
+        .execute(conn)?;
+    report.legal_holds = diesel::update(legal_holds::table)
+        .set(legal_holds::reason.eq("Anonymized"))
+        .execute(conn)?;
+    // Which field changed, when and by whom stays; the values may be names or addresses
+    report.change_history = diesel::update(change_history::table)
+        .set((
+            change_history::old_value.eq(None::<serde_json::Value>),
+            change_history::new_value.eq(None::<serde_json::Value>),
+        ))
+        .execute(conn)?;
+
+    report.deleted += diesel::delete(refresh_tokens::table).execute(conn)?;
+    report.deleted += diesel::delete(password_reset_tokens::table).execute(conn)?;
+    report.deleted += diesel::delete(email_verification_tokens::table).execute(conn)?;
+    report.deleted += diesel::delete(email_change_tokens::table).execute(conn)?;
+    report.deleted += diesel::delete(magic_link_tokens::table).execute(conn)?;
+    report.deleted += diesel::delete(devices::table).execute(conn)?;
+    report.deleted += diesel::delete(auth_events::table).execute(conn)?;
+    report.deleted += diesel::delete(scim_tokens::table).execute(conn)?;
+    report.deleted += diesel::delete(telemetry_api_keys::table).execute(conn)?;
+    report.deleted += diesel::delete(org_invitations::table).execute(conn)?;
+    report.deleted += diesel::delete(queued_jobs::table).execute(conn)?;
+    report.deleted += diesel::delete(dead_letter_jobs::table).execute(conn)?;
+
+    Ok(report)
+}
+
+/// Replaces the name and email of `user_id` with placeholders and erases
+/// everything else that identifies them, in one transaction
+///
+/// Their credentials, sessions, devices, passkeys, linked sign-ins,
+/// preferences, notifications and invitations are deleted, and the
+/// addresses and devices their auth events were recorded from are cleared.
+/// Removed users are anonymized too. `password` is the hash of a password
+/// nobody knows.
+pub async fn anonymize_user(conn: &mut PgConnection, user_id: Uuid, password: &str) -> Result<User> {
+    conn.transaction(|conn| forget(conn, user_id, password))
+        .map_err(|e| ApiError::from(DatabaseError::from(e)))
+}
+
+fn forget(conn: &mut PgConnection, user_id: Uuid, password: &str) -> QueryResult<User> {
+    let email: String = users::table.find(user_id).select(users::email).first(conn)?;
+    let now = Utc::now();
+    let user = diesel::update(users::table.find(user_id))
+        .set((
+            users::first_name.eq(FORGOTTEN_FIRST_NAME),
+            users::last_name.eq(FORGOTTEN_LAST_NAME),
+            users::email.eq(format!("forgotten-{}@{}", user_id.simple(), ANONYMIZED_DOMAIN)),
+            users::phone_number.eq(""),
+            users::password.eq(password),
+            users::avatar_updated_at.eq(None::<chrono::DateTime<Utc>>),
+            users::is_active.eq(false),
+            users::anonymized_at.eq(now),
+            users::updated_at.eq(now),
+        ))
+        .returning(User::as_select())
+        .get_result(conn)?;
+
+    diesel::delete(refresh_tokens::table.filter(refresh_tokens::user_id.eq(user_id))).execute(conn)?;
+    diesel::delete(password_reset_tokens::table.filter(password_reset_tokens::user_id.eq(user_id))).execute(conn)?;
+    diesel::delete(email_verification_tokens::table.filter(email_verification_tokens::user_id.eq(user_id))).execute(conn)?;
+    diesel::delete(email_change_tokens::table.filter(email_change_tokens::user_id.eq(user_id))).execute(conn)?;
+    diesel::delete(magic_link_tokens::table.filter(magic_link_tokens::user_id.eq(user_id))).execute(conn)?;
+    diesel::delete(devices::table.filter(devices::user_id.eq(user_id))).execute(conn)?;
+    diesel::delete(passkeys::table.filter(passkeys::user_id.eq(user_id))).execute(conn)?;
+    diesel::delete(user_identities::table.filter(user_identities::user_id.eq(user_id))).execute(conn)?;
+    diesel::delete(user_preferences::table.filter(user_preferences::user_id.eq(user_id))).execute(conn)?;
+    diesel::delete(notifications::table.filter(notifications::user_id.eq(user_id))).execute(conn)?;
+    diesel::delete(org_invitations::table.filter(org_invitations::email.eq(&email))).execute(conn)?;
+
+    // Failed logins may name the address without knowing the user
+    diesel::update(
+        auth_events::table.filter(auth_events::user_id.eq(user_id).or(auth_events::email.eq(&email))),
+    )
+    .set((
+        auth_events::email.eq(None::<String>),
+        auth_events::ip_address.eq(None::<String>),
+        auth_events::user_agent.eq(None::<String>),
+        auth_events::country.eq(None::<String>),
+        auth_events::asn.eq(None::<String>),
+        auth_events::device_id.eq(None::<String>),
+    ))
+    .execute(conn)?;
+
+    Ok(user)
+}
@@ -5,6 +5,7 @@
 //! creation, and error handling.
 
 use crate::error::{ApiError, ErrorCode, ErrorContext, Result};
+use diesel::connection::{AnsiTransactionManager, TransactionManager};
 use diesel::r2d2::{self, ConnectionManager};
 use diesel::PgConnection;
 use tracing::{error, debug};
@@ -65,7 +66,7 @@ impl Database {
     pub fn create_pool(database_url: &str) -> std::io::Result<DbPool> {
         create_connection_pool(database_url, DbConfig::default()).map_err(|e| {
             error!("Failed to create database pool: {}", e);
-            std::io::Error::new(std::io::ErrorKind::Other, e)
+            std::io::Error::other(e)
         })
     }
 }
@@ -120,7 +121,7 @@ pub fn get_connection(
     pool.get().map_err(|e| {
         error!("Failed to get DB connection: {}", e);
         ApiError::new(
-            ErrorCode::DatabaseError,
+            ErrorCode::ConnectionPoolError,
             "Failed to get database connection from pool",
             ErrorContext::new().with_details(serde_json::json!({
                 "error": e.to_string()
@@ -129,6 +130,38 @@ pub fn get_connection(
     })
 }
 
+/// Opens a transaction, or a savepoint inside one, that spans `await`s
+///
+/// `conn.transaction` takes a synchronous closure, so services whose
+/// writes go through async repositories open the transaction here and
+/// close it with [`finish_transaction`].
+pub fn begin_transaction(conn: &mut PgConnection) -> Result<()> {
+    AnsiTransactionManager::begin_transaction(conn).map_err(|e| {
+        error!(error = %e, "Failed to begin a transaction");
+        ApiError::database_error("Failed to begin a transaction", None)
+    })
+}
+
+/// Commits the transaction opened by [`begin_transaction`] when `result`
+/// is a success and rolls it back otherwise, passing `result` on
+pub fn finish_transaction<T>(conn: &mut PgConnection, result: Result<T>) -> Result<T> {
+    match result {
+        Ok(value) => {
+            AnsiTransactionManager::commit_transaction(conn).map_err(|e| {
+                error!(error = %e, "Failed to commit a transaction");
+                ApiError::database_error("Failed to commit a transaction", None)
+            })?;
+            Ok(value)
+        }
+        Err(err) => {
+            if let Err(e) = AnsiTransactionManager::rollback_transaction(conn) {
+                error!(error = %e, "Failed to roll back a transaction");
+            }
+            Err(err)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
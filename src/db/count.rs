@@ -0,0 +1,150 @@
+//! Row counting for paginated list endpoints
+//!
+//! An exact `COUNT(*)` has to visit every matching row, which becomes the
+//! slowest part of a list request once a table grows into the millions.
+//! This module provides planner-based estimates (from `pg_class.reltuples`
+//! or `EXPLAIN`) and a helper that only falls back to an exact count while
+//! the estimate stays below a threshold.
+
+use diesel::{
+    pg::Pg,
+    prelude::*,
+    query_builder::{AstPass, Query, QueryFragment, QueryId},
+    sql_types::{BigInt, Text},
+};
+use tracing::error;
+
+use crate::error::{ApiError, Result};
+
+/// Estimated row count above which list endpoints stop counting exactly
+pub const DEFAULT_ESTIMATE_THRESHOLD: i64 = 100_000;
+
+/// Total number of rows matched by a list query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowCount {
+    /// Number of matching rows
+    pub total: i64,
+    /// Whether `total` is an exact count or a planner estimate
+    pub exact: bool,
+}
+
+impl RowCount {
+    /// Creates an exact row count
+    pub fn exact(total: i64) -> Self {
+        Self { total, exact: true }
+    }
+
+    /// Creates a row count taken from a planner estimate
+    pub fn estimated(total: i64) -> Self {
+        Self {
+            total: total.max(0),
+            exact: false,
+        }
+    }
+}
+
+/// Wraps a query in `EXPLAIN (FORMAT JSON)`
+#[derive(Debug, Clone, Copy)]
+pub struct Explain<Q>(pub Q);
+
+impl<Q> QueryId for Explain<Q> {
+    type QueryId = ();
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl<Q: QueryFragment<Pg>> QueryFragment<Pg> for Explain<Q> {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+        out.push_sql("EXPLAIN (FORMAT JSON) ");
+        self.0.walk_ast(out.reborrow())
+    }
+}
+
+impl<Q> Query for Explain<Q> {
+    type SqlType = Text;
+}
+
+impl<Q> RunQueryDsl<PgConnection> for Explain<Q> {}
+
+#[derive(QueryableByName)]
+struct Reltuples {
+    #[diesel(sql_type = BigInt)]
+    estimate: i64,
+}
+
+/// Returns the planner's row estimate for a whole table
+///
+/// Reads `pg_class.reltuples`, which is maintained by `VACUUM` and `ANALYZE`.
+/// Returns `None` when the table has never been analyzed.
+pub fn table_estimate(conn: &mut PgConnection, table: &str) -> Result<Option<i64>> {
+    diesel::sql_query(
+        "SELECT COALESCE(reltuples, -1)::bigint AS estimate FROM pg_class WHERE oid = to_regclass($1)",
+    )
+    .bind::<Text, _>(table)
+    .get_result::<Reltuples>(conn)
+    .optional()
+    .map(|row| row.map(|r| r.estimate).filter(|estimate| *estimate >= 0))
+    .map_err(|e| {
+        error!(table = %table, error = %e, "Failed to read table row estimate");
+        ApiError::database_error("Failed to estimate row count", None)
+    })
+}
+
+/// Returns the planner's row estimate for an arbitrary query
+pub fn query_estimate<Q>(conn: &mut PgConnection, query: Q) -> Result<i64>
+where
+    Q: QueryFragment<Pg>,
+{
+    let plan: String = Explain(query).get_result(conn).map_err(|e| {
+        error!(error = %e, "Failed to explain query for row estimate");
+        ApiError::database_error("Failed to estimate row count", None)
+    })?;
+
+    parse_plan_rows(&plan)
+        .ok_or_else(|| ApiError::database_error("Failed to parse query plan", None))
+}
+
+/// Counts rows exactly while the estimate for `query` stays below `threshold`,
+/// otherwise returns the estimate itself
+pub fn count_with_estimate<Q, C>(
+    conn: &mut PgConnection,
+    query: Q,
+    threshold: i64,
+    exact: C,
+) -> Result<RowCount>
+where
+    Q: QueryFragment<Pg>,
+    C: FnOnce(&mut PgConnection) -> QueryResult<i64>,
+{
+    let estimate = query_estimate(conn, query)?;
+    if estimate >= threshold {
+        return Ok(RowCount::estimated(estimate));
+    }
+
+    exact(conn).map(RowCount::exact).map_err(|e| {
+        error!(error = %e, "Failed to count rows");
+        ApiError::database_error("Failed to count rows", None)
+    })
+}
+
+/// Extracts the top-level "Plan Rows" value from `EXPLAIN (FORMAT JSON)` output
+fn parse_plan_rows(plan: &str) -> Option<i64> {
+    let plan: serde_json::Value = serde_json::from_str(plan).ok()?;
+    plan.get(0)?
+        .get("Plan")?
+        .get("Plan Rows")?
+        .as_f64()
+        .map(|rows| rows.round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plan_rows() {
+        let plan = r#"[{"Plan": {"Node Type": "Seq Scan", "Plan Rows": 1234, "Plan Width": 64}}]"#;
+        assert_eq!(parse_plan_rows(plan), Some(1234));
+        assert_eq!(parse_plan_rows("[]"), None);
+        assert_eq!(parse_plan_rows("not json"), None);
+    }
+}
@@ -0,0 +1,104 @@
+//! Walks of the organization hierarchy
+//!
+//! Organizations form a forest through `parent_id`: a company owns its
+//! divisions, which own their operating areas. The walks go one level per
+//! query and stop at removed organizations, so a removed division hides the
+//! areas under it.
+
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::db::{models::Organization, schema::organizations};
+
+/// Most levels below a top-level organization; deeper organizations can't
+/// be created, and walks stop there
+pub const MAX_HIERARCHY_DEPTH: usize = 8;
+
+/// `root` and the live organizations under it, each with its depth below
+/// `root`, parents before their children and siblings by name
+pub fn subtree(conn: &mut PgConnection, root: &Organization) -> QueryResult<Vec<(Organization, usize)>> {
+    let mut tree = vec![(root.clone(), 0)];
+    let mut level = vec![root.id];
+    for depth in 1..=MAX_HIERARCHY_DEPTH {
+        if level.is_empty() {
+            break;
+        }
+        let children: Vec<Organization> = organizations::table
+            .filter(organizations::parent_id.eq_any(&level))
+            .filter(organizations::deleted_at.is_null())
+            .order_by(organizations::name.asc())
+            .select(Organization::as_select())
+            .load(conn)?;
+        level = children.iter().map(|child| child.id).collect();
+        for child in children {
+            // Right after its parent, or after the siblings placed before it
+            let parent = tree.iter().rposition(|(organization, _)| Some(organization.id) == child.parent_id);
+            let mut at = parent.map_or(tree.len(), |parent| parent + 1);
+            while at < tree.len() && tree[at].1 >= depth {
+                at += 1;
+            }
+            tree.insert(at, (child, depth));
+        }
+    }
+    Ok(tree)
+}
+
+/// Ids of `root` and the live organizations under it
+pub fn subtree_ids(conn: &mut PgConnection, root: Uuid) -> QueryResult<Vec<Uuid>> {
+    walk_down(conn, root, false)
+}
+
+/// Ids of `root` and every organization under it, removed ones and those
+/// under them included
+pub fn subtree_ids_with_removed(conn: &mut PgConnection, root: Uuid) -> QueryResult<Vec<Uuid>> {
+    walk_down(conn, root, true)
+}
+
+fn walk_down(conn: &mut PgConnection, root: Uuid, removed: bool) -> QueryResult<Vec<Uuid>> {
+    let mut ids = vec![root];
+    let mut level = vec![root];
+    for _ in 0..MAX_HIERARCHY_DEPTH {
+        if level.is_empty() {
+            break;
+        }
+        let mut query = organizations::table
+            .filter(organizations::parent_id.eq_any(&level))
+            .select(organizations::id)
+            .into_boxed();
+        if !removed {
+            query = query.filter(organizations::deleted_at.is_null());
+        }
+        level = query.load(conn)?;
+        ids.extend(&level);
+    }
+    Ok(ids)
+}
+
+/// Ids of the live organizations above `organization`, its parent first
+pub fn ancestor_ids(conn: &mut PgConnection, organization: Uuid) -> QueryResult<Vec<Uuid>> {
+    let mut ancestors = Vec::new();
+    let mut current = organization;
+    for _ in 0..=MAX_HIERARCHY_DEPTH {
+        let parent: Option<Option<Uuid>> = organizations::table
+            .find(current)
+            .filter(organizations::deleted_at.is_null())
+            .select(organizations::parent_id)
+            .first(conn)
+            .optional()?;
+        match parent {
+            Some(Some(parent)) => {
+                ancestors.push(parent);
+                current = parent;
+            }
+            Some(None) => break,
+            // Removed, and so is everything above it as far as the walk goes
+            None => {
+                if current != organization {
+                    ancestors.pop();
+                }
+                break;
+            }
+        }
+    }
+    Ok(ancestors)
+}
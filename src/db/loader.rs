@@ -0,0 +1,27 @@
+//! Stitching batch-loaded relations
+//!
+//! Related records are loaded with one query per relation, through
+//! repository methods taking all the parent ids at once, rather than one
+//! query per parent row. The results are then grouped by parent in memory.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let members = group_by(repository.members(conn, org_id, &crew_ids).await?, |(member, _)| member.crew_id);
+//! ```
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+/// Groups child records by their parent key
+pub fn group_by<M, K>(items: Vec<M>, key: K) -> HashMap<Uuid, Vec<M>>
+where
+    K: Fn(&M) -> Uuid,
+{
+    let mut groups: HashMap<Uuid, Vec<M>> = HashMap::new();
+    for item in items {
+        groups.entry(key(&item)).or_default().push(item);
+    }
+    groups
+}
@@ -0,0 +1,19 @@
+//! Schema migrations embedded at compile time
+//!
+//! Migrations are applied by the deployment (`diesel migration run`); the
+//! server only checks that none are pending before reporting itself ready.
+
+use diesel::PgConnection;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+
+use crate::error::{ApiError, Result};
+
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+
+/// Versions of migrations that have not been applied to the database
+pub fn pending_migrations(conn: &mut PgConnection) -> Result<Vec<String>> {
+    let pending = conn
+        .pending_migrations(MIGRATIONS)
+        .map_err(|e| ApiError::database_error(format!("Failed to read applied migrations: {}", e), None))?;
+    Ok(pending.iter().map(|m| m.name().version().to_string()).collect())
+}
@@ -1,6 +1,15 @@
+pub mod anonymize;
 pub mod connection;
+pub mod count;
+pub mod hierarchy;
+pub mod loader;
+pub mod migrations;
 pub mod models;
 pub mod repositories;
 pub mod schema;
+pub mod seed;
+pub mod spatial;
+pub mod tenant;
+pub mod trigram;
 
 pub use connection::*;
\ No newline at end of file
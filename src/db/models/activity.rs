@@ -0,0 +1,72 @@
+//! User activity model
+//!
+//! Significant actions users take, such as signing off a block or
+//! uploading a document, are recorded as activities so supervisors can
+//! review what a crew member did. Events concerning the whole organization,
+//! such as a user joining, are recorded as organization activities so
+//! managers can follow its operations.
+
+use crate::db::schema::{activities, user_activities};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Represents an action taken by a user
+///
+/// # Fields
+///
+/// * `action` - What the user did, e.g. `block_signed_off`
+/// * `subject_type` / `subject_id` - What they did it to, e.g. a `block`
+/// * `summary` - The action as shown to supervisors, e.g. `Signed off the
+///   forester step of a block`
+/// * `data` - Structured details for the UI
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = user_activities)]
+pub struct UserActivity {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub user_id: Uuid,
+    pub action: String,
+    pub subject_type: String,
+    pub subject_id: Uuid,
+    pub summary: String,
+    pub data: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Represents an event in an organization
+///
+/// # Fields
+///
+/// * `actor_id` - Who caused the event, if a user did
+/// * `kind` - What happened, e.g. `user_joined`
+/// * `subject_type` / `subject_id` - What it happened to, e.g. a `user`
+/// * `summary` - The event as shown to managers, e.g. `Jane Doe joined`
+/// * `data` - Structured details for the UI
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = activities)]
+pub struct Activity {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub kind: String,
+    pub subject_type: String,
+    pub subject_id: Uuid,
+    pub summary: String,
+    pub data: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Which organization activities to list
+#[derive(Debug, Clone, Default)]
+pub struct ActivityFilter {
+    /// Only these kinds; every kind when empty
+    pub kinds: Vec<String>,
+    pub actor_id: Option<Uuid>,
+    pub subject_type: Option<String>,
+    /// Only activities from this instant on
+    pub since: Option<DateTime<Utc>>,
+    /// Only activities before this instant
+    pub until: Option<DateTime<Utc>>,
+}
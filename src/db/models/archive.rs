@@ -0,0 +1,62 @@
+//! Archive model
+//!
+//! An archive is the stub left behind in the hot database after a batch of
+//! records has been exported to cold storage. It records where the batch
+//! lives and which time range it covers so it can be found and rehydrated.
+
+use super::Timestamps;
+use crate::db::schema::archives;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Represents a batch of records moved to cold storage
+///
+/// # Fields
+///
+/// * `id` - Unique identifier for the archive
+/// * `dataset` - Name of the archived dataset (e.g. `telemetry`)
+/// * `storage_key` - Object storage key of the Parquet file
+/// * `record_count` - Number of records in the file
+/// * `byte_size` - Size of the file in bytes
+/// * `range_start` - Timestamp of the oldest archived record
+/// * `range_end` - Timestamp of the newest archived record
+/// * `rehydrated_at` - When the records were restored to the hot database
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = archives)]
+pub struct Archive {
+    pub id: Uuid,
+    pub dataset: String,
+    pub storage_key: String,
+    pub record_count: i64,
+    pub byte_size: i64,
+    pub range_start: DateTime<Utc>,
+    pub range_end: DateTime<Utc>,
+    pub rehydrated_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Archive {
+    /// Returns true once the archived records have been restored
+    pub fn is_rehydrated(&self) -> bool {
+        self.rehydrated_at.is_some()
+    }
+}
+
+impl Timestamps for Archive {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+}
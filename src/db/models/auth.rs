@@ -5,23 +5,32 @@
 //! verification tokens.
 
 use crate::{
-    db::schema::{refresh_tokens, password_reset_tokens, email_verification_tokens, users},
+    db::schema::{refresh_tokens, password_reset_tokens, email_verification_tokens, email_change_tokens, magic_link_tokens, devices, passkeys, auth_events, users},
     error::{Result, ApiError, ErrorCode, ErrorContext},
-    db::models::Timestamps
+    db::models::Timestamps,
+    utils::{PasswordAlgorithm, PasswordHashConfig},
 };
 use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use tracing::error;
+use ts_rs::TS;
 use utoipa::ToSchema;
 use uuid::Uuid;
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2
+    Algorithm, Argon2, Params, Version,
 };
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+/// Settings new password hashes are made with, `auth.password_hash` once
+/// the configuration is loaded
+static PASSWORD_HASH: Lazy<RwLock<PasswordHashConfig>> = Lazy::new(Default::default);
 
 /// User roles in the system
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, diesel_derive_enum::DbEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema, TS, diesel_derive_enum::DbEnum)]
+#[ts(export)]
 #[ExistingTypePath = "crate::db::schema::sql_types::UserRole"]
 #[serde(rename_all = "PascalCase")]
 pub enum Role {
@@ -33,6 +42,36 @@ pub enum Role {
     Operator,
 }
 
+impl Role {
+    /// Place in the hierarchy, where each role has the access of those
+    /// below it
+    fn rank(self) -> u8 {
+        match self {
+            Role::Operator => 0,
+            Role::Manager => 1,
+            Role::Admin => 2,
+        }
+    }
+
+    /// Whether this role grants at least the access of `required`
+    pub fn is_at_least(self, required: Role) -> bool {
+        self >= required
+    }
+}
+
+/// Roles compare by the hierarchy, `Operator < Manager < Admin`
+impl Ord for Role {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for Role {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// Represents a user in the system with auth-specific fields
 #[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, ToSchema)]
 #[diesel(table_name = users)]
@@ -49,14 +88,89 @@ pub struct User {
     pub deleted_at: Option<DateTime<Utc>>,
     pub role: Role,
     pub email_verified: bool,
+    /// When the user last uploaded an avatar, `None` without one
+    pub avatar_updated_at: Option<DateTime<Utc>>,
+    /// Deactivated users keep their records but can't authenticate
+    pub is_active: bool,
+    /// The manager or admin the user reports to, `None` for no one
+    pub supervisor_id: Option<Uuid>,
+    /// When the user's personal data was scrubbed, for good
+    pub anonymized_at: Option<DateTime<Utc>>,
+}
+
+/// Changes a user makes to their own profile; fields left `None` keep their
+/// value
+#[derive(Debug, Clone, Default, AsChangeset)]
+#[diesel(table_name = users)]
+pub struct ProfileChanges {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub phone_number: Option<String>,
+}
+
+impl ProfileChanges {
+    pub fn is_empty(&self) -> bool {
+        self.first_name.is_none() && self.last_name.is_none() && self.phone_number.is_none()
+    }
+}
+
+/// What users of an organization are listed by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UserSort {
+    /// First then last name
+    #[default]
+    Name,
+    Email,
+    /// Admins first
+    Role,
+    CreatedAt,
+    /// Closest match to the search first, by name
+    Relevance,
+}
+
+/// Which users of an organization to list, and in what order
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    /// Words that must each match a user's name or email, by substring or
+    /// trigram similarity
+    pub query: Option<String>,
+    /// Roles to include, all of them when empty
+    pub roles: Vec<Role>,
+    /// Only active users, or only deactivated ones; both when `None`
+    pub active: Option<bool>,
+    /// List deprovisioned users instead of current ones
+    pub deprovisioned: bool,
+    pub sort: UserSort,
+    pub descending: bool,
 }
 
 impl User {
-    /// Hash a password using Argon2
+    /// Makes new password hashes with `config`
+    pub fn configure_password_hashing(config: &PasswordHashConfig) {
+        *PASSWORD_HASH.write() = config.clone();
+    }
+
+    /// The configured algorithm and parameters
+    fn password_settings() -> Result<(Algorithm, Params)> {
+        let config = PASSWORD_HASH.read().clone();
+        let algorithm = match config.algorithm {
+            PasswordAlgorithm::Argon2id => Algorithm::Argon2id,
+            PasswordAlgorithm::Argon2i => Algorithm::Argon2i,
+            PasswordAlgorithm::Argon2d => Algorithm::Argon2d,
+        };
+        let params = Params::new(config.memory_kib, config.iterations, config.parallelism, None).map_err(|e| {
+            error!("Invalid password hash parameters: {}", e);
+            ApiError::new(ErrorCode::InternalError, "Failed to hash password", ErrorContext::default())
+        })?;
+        Ok((algorithm, params))
+    }
+
+    /// Hash a password with the configured algorithm and parameters
     pub fn hash_password(password: &str) -> Result<String> {
         let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        
+        let (algorithm, params) = Self::password_settings()?;
+        let argon2 = Argon2::new(algorithm, Version::default(), params);
+
         argon2
             .hash_password(password.as_bytes(), &salt)
             .map(|hash| hash.to_string())
@@ -85,6 +199,18 @@ impl User {
             .verify_password(password.as_bytes(), &parsed_hash)
             .is_ok())
     }
+
+    /// Whether `hash` was made with other settings than the configured ones
+    /// and should be replaced
+    pub fn needs_rehash(hash: &str) -> bool {
+        let (Ok(hash), Ok((algorithm, params))) = (PasswordHash::new(hash), Self::password_settings()) else {
+            return false;
+        };
+        let costs = |params: &Params| (params.m_cost(), params.t_cost(), params.p_cost());
+        Algorithm::try_from(hash.algorithm).ok() != Some(algorithm)
+            || hash.version.and_then(|version| Version::try_from(version).ok()) != Some(Version::default())
+            || Params::try_from(&hash).ok().as_ref().map(costs) != Some(costs(&params))
+    }
 }
 
 /// Represents a refresh token for JWT authentication
@@ -98,6 +224,10 @@ pub struct RefreshToken {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
+    /// Client-generated ID of the device the token was issued to
+    pub device_id: Option<String>,
+    /// End of the session the token renews, however often it is refreshed
+    pub session_expires_at: DateTime<Utc>,
 }
 
 /// Represents a password reset token
@@ -105,7 +235,8 @@ pub struct RefreshToken {
 #[diesel(table_name = password_reset_tokens)]
 pub struct PasswordResetToken {
     pub id: Uuid,
-    pub token: String,
+    /// Hash of the token in the link, which only the email holds
+    pub token_hash: String,
     pub user_id: Uuid,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
@@ -118,14 +249,114 @@ pub struct PasswordResetToken {
 #[diesel(table_name = email_verification_tokens)]
 pub struct EmailVerificationToken {
     pub id: Uuid,
-    pub token: String,
+    /// Hash of the token in the link, which only the email holds
+    pub token_hash: String,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Represents a pending change of a user's email address, confirmed from
+/// the new address
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = email_change_tokens)]
+pub struct EmailChangeToken {
+    pub id: Uuid,
+    /// Hash of the token in the link, which only the email holds
+    pub token_hash: String,
     pub user_id: Uuid,
+    pub new_email: String,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
 }
 
+/// Represents a magic link token, logging its user in without a password
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = magic_link_tokens)]
+pub struct MagicLinkToken {
+    pub id: Uuid,
+    /// Hash of the token in the link, which only the email holds
+    pub token_hash: String,
+    pub user_id: Uuid,
+    /// Device the link was requested from, the only one that may redeem it
+    pub device_id: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Represents a device a user has logged in from
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = devices)]
+pub struct Device {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// ID the client generated for itself, unique per user
+    pub device_id: String,
+    pub name: Option<String>,
+    /// Last login, token refresh or heartbeat from the device
+    pub last_seen_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Version of the app, as of the last heartbeat
+    pub app_version: Option<String>,
+    /// Battery charge in percent, as of the last heartbeat
+    pub battery_level: Option<i16>,
+    /// Last heartbeat from the device; only field apps send them
+    pub last_heartbeat_at: Option<DateTime<Utc>>,
+}
+
+/// A WebAuthn credential a user logs in with instead of a password
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = passkeys)]
+pub struct Passkey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Credential ID the authenticator chose, base64url
+    pub credential_id: String,
+    /// COSE_Key the authenticator returned at registration
+    pub public_key: Vec<u8>,
+    /// Signature counter of the last assertion, 0 if the authenticator
+    /// keeps none
+    pub sign_count: i64,
+    pub name: String,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A recorded login, token refresh, password change or logout
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = auth_events)]
+pub struct AuthEvent {
+    pub id: Uuid,
+    /// `None` for failed logins with an email no user has
+    pub user_id: Option<Uuid>,
+    pub event: String,
+    /// How the user authenticated: `password`, `magic_link`, `passkey` or
+    /// `sso:<provider>`
+    pub method: Option<String>,
+    /// Email a failed login was attempted with
+    pub email: Option<String>,
+    /// Why the attempt failed
+    pub reason: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// ISO 3166 code of the country the client was in, when known
+    pub country: Option<String>,
+    /// Autonomous system (network) the client connected from, when known
+    pub asn: Option<String>,
+    /// Device the client logged in from, when it named one
+    pub device_id: Option<String>,
+}
+
 impl Timestamps for User {
     fn created_at(&self) -> DateTime<Utc> {
         self.created_at
@@ -180,4 +411,46 @@ impl Timestamps for EmailVerificationToken {
     fn deleted_at(&self) -> Option<DateTime<Utc>> {
         self.deleted_at
     }
-} 
\ No newline at end of file
+}
+
+impl Timestamps for EmailChangeToken {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+}
+
+impl Timestamps for MagicLinkToken {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+}
+
+impl Timestamps for Device {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+}
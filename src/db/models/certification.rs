@@ -0,0 +1,100 @@
+//! Certification models
+//!
+//! Operators hold certifications such as a faller certification, first aid
+//! or dangerous goods handling, each issued on a day and most expiring on
+//! another. Scanned certificates are attached as documents with the
+//! `certification` subject, and supervisors are notified ahead of an
+//! expiry.
+
+use crate::db::schema::certifications;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Kind of certification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificationKind {
+    Faller,
+    FirstAid,
+    DangerousGoods,
+    /// Any other certification, named by the certification's `name`
+    Other,
+}
+
+impl CertificationKind {
+    pub const ALL: [CertificationKind; 4] = [
+        CertificationKind::Faller,
+        CertificationKind::FirstAid,
+        CertificationKind::DangerousGoods,
+        CertificationKind::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CertificationKind::Faller => "faller",
+            CertificationKind::FirstAid => "first_aid",
+            CertificationKind::DangerousGoods => "dangerous_goods",
+            CertificationKind::Other => "other",
+        }
+    }
+
+    /// The kind stored as `value`, if any
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    /// How the kind reads in notifications
+    pub fn label(&self) -> &'static str {
+        match self {
+            CertificationKind::Faller => "Faller certification",
+            CertificationKind::FirstAid => "First aid",
+            CertificationKind::DangerousGoods => "Dangerous goods",
+            CertificationKind::Other => "Certification",
+        }
+    }
+}
+
+impl fmt::Display for CertificationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Represents a certification held by a user
+///
+/// # Fields
+///
+/// * `kind` - See [`CertificationKind`]
+/// * `name` - Required for `other` certifications, optional otherwise
+/// * `expires_on` - `None` when the certification doesn't expire
+/// * `expiry_notified_at` - When supervisors were told of the coming
+///   expiry; cleared when `expires_on` changes
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = certifications)]
+pub struct Certification {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub name: Option<String>,
+    pub certificate_number: Option<String>,
+    pub issuer: Option<String>,
+    pub issued_on: NaiveDate,
+    pub expires_on: Option<NaiveDate>,
+    pub expiry_notified_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Certification {
+    /// What the certification is called: its name, or else its kind
+    pub fn title(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            CertificationKind::parse(&self.kind).map(|kind| kind.label()).unwrap_or(&self.kind).to_string()
+        })
+    }
+}
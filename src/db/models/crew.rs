@@ -0,0 +1,41 @@
+//! Crew models
+//!
+//! A crew is a team of an organization's users that work is dispatched to,
+//! so dispatch, timecards and production records can name a crew rather
+//! than each of its members.
+
+use crate::db::schema::{crew_members, crews};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Represents a crew
+///
+/// # Fields
+///
+/// * `name` - Unique within the organization
+/// * `supervisor_id` - User leading the crew, who need not be a member
+/// * `default_equipment` - Machine ids the crew works with unless a job says
+///   otherwise, as the machines report them in telemetry
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crews)]
+pub struct Crew {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub name: String,
+    pub supervisor_id: Option<Uuid>,
+    pub default_equipment: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A user's membership of a crew
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = crew_members)]
+pub struct CrewMember {
+    pub crew_id: Uuid,
+    pub user_id: Uuid,
+    pub org_id: Uuid,
+    pub added_at: DateTime<Utc>,
+}
@@ -0,0 +1,53 @@
+//! Customer models
+//!
+//! Customers are the mills and buyers an organization supplies. A supply
+//! contract commits to delivering a volume of one product to a customer
+//! over a period at an agreed price.
+
+use crate::db::schema::{customers, supply_contracts};
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Represents a customer of an organization
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = customers)]
+pub struct Customer {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub email: Option<String>,
+    pub phone_number: Option<String>,
+    pub address: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A commitment to supply a customer
+///
+/// # Fields
+///
+/// * `reference` - Contract number, unique within the organization
+/// * `product` - Product delivered, e.g. `spruce sawlog`
+/// * `volume_m3` - Volume committed over the period
+/// * `starts_on` / `ends_on` - Delivery period, both days included
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = supply_contracts)]
+pub struct SupplyContract {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub customer_id: Uuid,
+    pub reference: String,
+    pub product: String,
+    pub volume_m3: f64,
+    pub price_per_m3: f64,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
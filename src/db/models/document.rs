@@ -0,0 +1,94 @@
+//! Document models
+//!
+//! Documents such as harvest plans, permits and assessments are attached
+//! to a harvest block, a permit or an operator's certification. Every
+//! upload of a document adds a version; older versions stay available and
+//! can be restored, which adds their file back as a new version.
+
+use crate::db::schema::{document_versions, documents};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// What a document is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentSubject {
+    Block,
+    Permit,
+    Certification,
+}
+
+impl DocumentSubject {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentSubject::Block => "block",
+            DocumentSubject::Permit => "permit",
+            DocumentSubject::Certification => "certification",
+        }
+    }
+
+    /// The subject stored as `value`, if any
+    pub fn parse(value: &str) -> Option<Self> {
+        [DocumentSubject::Block, DocumentSubject::Permit, DocumentSubject::Certification]
+            .into_iter()
+            .find(|subject| subject.as_str() == value)
+    }
+}
+
+impl fmt::Display for DocumentSubject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Represents a versioned document
+///
+/// # Fields
+///
+/// * `subject_type` / `subject_id` - Block, permit or certification the
+///   document is attached to, see [`DocumentSubject`]
+/// * `category` - Kind of document, e.g. `harvest_permit`; checklists and
+///   retention rules go by it
+/// * `current_version` - Number of the latest version
+/// * `retain_until` - Regulatory documents cannot be deleted before this
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = documents)]
+pub struct Document {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub subject_type: String,
+    pub subject_id: Uuid,
+    pub category: String,
+    pub title: String,
+    pub current_version: i32,
+    pub retain_until: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A version of a document
+///
+/// # Fields
+///
+/// * `version` - Numbered from 1 per document
+/// * `file_key` - Object storage key of the file, shared with the version
+///   it was restored from
+/// * `restored_from` - Version whose file this one restores
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = document_versions)]
+pub struct DocumentVersion {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub version: i32,
+    pub filename: String,
+    pub content_type: String,
+    pub byte_size: i64,
+    pub file_key: String,
+    pub restored_from: Option<i32>,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
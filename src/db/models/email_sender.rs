@@ -0,0 +1,43 @@
+//! Organization email sender model
+//!
+//! Mail sent on behalf of an organization (invitations, alerts) uses the
+//! organization's sender when one is configured, and `email.from` otherwise.
+//! The sender domain must be verified with the configured mail provider.
+
+use crate::db::schema::organization_email_senders;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Sender identity of an organization
+///
+/// # Fields
+///
+/// * `org_id` - Organization the sender belongs to
+/// * `from_address` - Address mail is sent from
+/// * `from_name` - Display name shown with the address
+/// * `reply_to` - Address replies go to, the sender address when unset
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+#[diesel(table_name = organization_email_senders, primary_key(org_id))]
+pub struct OrganizationEmailSender {
+    pub org_id: Uuid,
+    pub from_address: String,
+    pub from_name: Option<String>,
+    pub reply_to: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OrganizationEmailSender {
+    /// Sender in `Name <address>` form
+    pub fn mailbox(&self) -> String {
+        match &self.from_name {
+            Some(name) => format!("{} <{}>", name, self.from_address),
+            None => self.from_address.clone(),
+        }
+    }
+}
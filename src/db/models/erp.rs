@@ -0,0 +1,98 @@
+//! ERP export models
+//!
+//! An ERP connection says how an organization's data reaches its ERP: the
+//! connector that delivers files, the connector's settings and, per export
+//! source, the columns of the exported CSV. Each export is one file of a
+//! source's records for a period, with the status of its delivery.
+
+use crate::db::schema::{erp_connections, erp_exports};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Represents a connection to an organization's ERP
+///
+/// # Fields
+///
+/// * `connector` - Kind of connector delivering the files, e.g. `csv_sftp`
+/// * `settings` - Connector-specific settings, e.g. the SFTP host
+/// * `mappings` - Columns exported per source, as JSON
+/// * `schedule` - Cron expression exports run on, manual only when unset
+/// * `next_run_at` - When the next scheduled export runs
+/// * `enabled` - Whether scheduled exports run
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = erp_connections)]
+pub struct ErpConnection {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub name: String,
+    pub connector: String,
+    pub settings: serde_json::Value,
+    pub mappings: serde_json::Value,
+    pub schedule: Option<String>,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub enabled: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Delivery status of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErpExportStatus {
+    /// Written and waiting for its connector
+    Pending,
+    Delivered,
+    /// The last delivery attempt failed; the export queue retries it
+    Failed,
+}
+
+impl ErpExportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErpExportStatus::Pending => "pending",
+            ErpExportStatus::Delivered => "delivered",
+            ErpExportStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for ErpExportStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One exported file
+///
+/// # Fields
+///
+/// * `source` - Export source the records come from
+/// * `period_start` / `period_end` - Records changed after the start, when
+///   set, up to the end are included
+/// * `file_key` - Object storage key of the written file
+/// * `remote_ref` - Where the connector delivered the file, e.g. its path
+/// * `attempts` - Delivery attempts so far
+/// * `error` - Why the last attempt failed
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = erp_exports)]
+pub struct ErpExport {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub connection_id: Uuid,
+    pub source: String,
+    pub status: String,
+    pub period_start: Option<DateTime<Utc>>,
+    pub period_end: DateTime<Utc>,
+    pub record_count: i32,
+    pub file_key: String,
+    pub remote_ref: Option<String>,
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,162 @@
+//! Harvest block models
+//!
+//! A harvest block is an area cut under a harvesting license, the unit that
+//! sign-offs, documents, timber sale parcels and, later, work orders and
+//! production are tied to. Its boundary is a PostGIS polygon, read and
+//! written as GeoJSON.
+
+use crate::{
+    db::{schema::harvest_blocks, spatial::st_asgeojson},
+    domain::operability::SoilSensitivity,
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Lifecycle of a harvest block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockStatus {
+    /// Laid out; the package is being prepared and signed off
+    Planned,
+    /// Every step of the sign-off chain is signed
+    Approved,
+    /// Being harvested; counts against the plan's active block quota
+    Active,
+    Completed,
+}
+
+impl BlockStatus {
+    pub const ALL: [BlockStatus; 4] = [
+        BlockStatus::Planned,
+        BlockStatus::Approved,
+        BlockStatus::Active,
+        BlockStatus::Completed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockStatus::Planned => "planned",
+            BlockStatus::Approved => "approved",
+            BlockStatus::Active => "active",
+            BlockStatus::Completed => "completed",
+        }
+    }
+
+    /// The status stored as `value`, if any
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|status| status.as_str() == value)
+    }
+
+    /// Whether a block needs a complete sign-off chain to have this status
+    pub fn requires_approval(&self) -> bool {
+        *self != BlockStatus::Planned
+    }
+}
+
+impl fmt::Display for BlockStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A GeoJSON polygon in WGS 84 longitude and latitude
+///
+/// The first ring is the outer boundary and any others are holes in it.
+/// Each ring is closed, its last position repeating its first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, TS)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum Boundary {
+    Polygon {
+        /// Rings of `[longitude, latitude]` positions
+        #[schema(value_type = Vec<Vec<Vec<f64>>>)]
+        coordinates: Vec<Vec<[f64; 2]>>,
+    },
+}
+
+impl Boundary {
+    /// The rings of the polygon, outer boundary first
+    pub fn rings(&self) -> &[Vec<[f64; 2]>] {
+        match self {
+            Boundary::Polygon { coordinates } => coordinates,
+        }
+    }
+
+    /// The boundary as GeoJSON text
+    pub fn to_geojson(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl TryFrom<String> for Boundary {
+    type Error = serde_json::Error;
+
+    /// Reads the GeoJSON PostGIS returns for a boundary
+    fn try_from(geojson: String) -> Result<Self, Self::Error> {
+        serde_json::from_str(&geojson)
+    }
+}
+
+/// Represents a harvest block
+///
+/// # Fields
+///
+/// * `license` - Harvesting license or cutting permit the block is cut under
+/// * `block_number` - Unique within the license
+/// * `area_ha` - Net harvest area in hectares
+/// * `planned_volume_m3` - Volume planned to be cut
+/// * `status` - See [`BlockStatus`]
+/// * `boundary` - See [`Boundary`]; read through `ST_AsGeoJSON`
+/// * `soil_sensitivity` - [`SoilSensitivity`] in snake case, if recorded
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Serialize, Deserialize)]
+#[diesel(table_name = harvest_blocks)]
+pub struct HarvestBlock {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub license: String,
+    pub block_number: String,
+    pub area_ha: f64,
+    pub planned_volume_m3: f64,
+    pub status: String,
+    #[diesel(select_expression = st_asgeojson(harvest_blocks::boundary))]
+    #[diesel(select_expression_type = st_asgeojson<harvest_blocks::boundary>)]
+    #[diesel(deserialize_as = String)]
+    pub boundary: Boundary,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub soil_sensitivity: Option<String>,
+}
+
+impl HarvestBlock {
+    /// How easily the block's soil ruts, if recorded
+    pub fn soil_sensitivity(&self) -> Option<SoilSensitivity> {
+        serde_json::from_value(serde_json::Value::String(self.soil_sensitivity.clone()?)).ok()
+    }
+}
+
+/// A rectangle in WGS 84 longitude and latitude
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min_lon: f64,
+    pub min_lat: f64,
+    pub max_lon: f64,
+    pub max_lat: f64,
+}
+
+/// Conditions on the harvest blocks listed; unset ones match every block
+#[derive(Debug, Clone, Default)]
+pub struct HarvestBlockFilter {
+    pub license: Option<String>,
+    pub status: Option<BlockStatus>,
+    /// Blocks whose boundary crosses or lies within the box
+    pub bbox: Option<BoundingBox>,
+    /// Only these blocks, e.g. those a tag filter keeps
+    pub ids: Option<Vec<Uuid>>,
+}
@@ -0,0 +1,33 @@
+//! Change history models
+//!
+//! Every update of a tracked record stores one row per field it changed,
+//! with the old and the new value, who made the change and when.
+
+use crate::db::schema::change_history;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A change of one field of a record
+///
+/// # Fields
+///
+/// * `resource` / `record_id` - Record changed, e.g. a `supply_contract`
+/// * `old_value` / `new_value` - Values as JSON, `None` when the field was
+///   absent
+/// * `changed_by` - User who made the change, `None` for the system or a
+///   deleted user
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = change_history)]
+pub struct FieldChange {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub resource: String,
+    pub record_id: Uuid,
+    pub field: String,
+    pub old_value: Option<serde_json::Value>,
+    pub new_value: Option<serde_json::Value>,
+    pub changed_by: Option<Uuid>,
+    pub changed_at: DateTime<Utc>,
+}
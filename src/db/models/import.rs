@@ -0,0 +1,77 @@
+//! Import model
+//!
+//! An import is a spreadsheet uploaded to be loaded into one of the import
+//! targets (see `domain::import::ImportTarget`). It keeps the uploaded file,
+//! the mapping of its columns to the target's fields and, once committed,
+//! the outcome of the background job that loads it.
+
+use crate::db::schema::imports;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Progress of an import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    /// Uploaded and waiting for its mapping to be committed
+    Uploaded,
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl ImportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportStatus::Uploaded => "uploaded",
+            ImportStatus::Queued => "queued",
+            ImportStatus::Running => "running",
+            ImportStatus::Completed => "completed",
+            ImportStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for ImportStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Represents an uploaded spreadsheet and its import
+///
+/// # Fields
+///
+/// * `target` - Name of the import target rows are loaded into
+/// * `file_key` - Object storage key of the uploaded file
+/// * `headers` - Column headers read from the file
+/// * `mapping` - Field of the target each mapped header fills, as JSON
+/// * `status` - See [`ImportStatus`]
+/// * `total_rows` - Data rows in the file, without the header row
+/// * `imported_rows` / `failed_rows` - Outcome of the committed import
+/// * `error_key` - Object storage key of the CSV report of rejected rows
+/// * `error` - Why the import failed as a whole
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = imports)]
+pub struct Import {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub target: String,
+    pub filename: String,
+    pub file_key: String,
+    pub headers: Vec<String>,
+    pub mapping: serde_json::Value,
+    pub status: String,
+    pub total_rows: i32,
+    pub imported_rows: Option<i32>,
+    pub failed_rows: Option<i32>,
+    pub error_key: Option<String>,
+    pub error: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,30 @@
+//! Organization invitation models
+//!
+//! Managers invite people into their organization by email. The invitee
+//! registers with the signed token mailed to them and joins with the
+//! invitation's role.
+
+use crate::db::{models::auth::Role, schema::org_invitations};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// An invitation to join an organization
+///
+/// Pending until it is accepted, revoked (`deleted_at`) or expires.
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = org_invitations)]
+pub struct OrgInvitation {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    /// Address the invitation was sent to, the only one that may accept it
+    pub email: String,
+    /// Role the invitee joins with
+    pub role: Role,
+    pub invited_by: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,48 @@
+//! Legal hold model
+//!
+//! A legal hold exempts a single record from the purge job, regardless of
+//! how long ago it was soft deleted. Releasing a hold soft deletes it.
+
+use super::Timestamps;
+use crate::db::schema::legal_holds;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Represents a legal hold on a record
+///
+/// # Fields
+///
+/// * `id` - Unique identifier for the hold
+/// * `entity_type` - Table of the held record (e.g. `users`)
+/// * `entity_id` - Identifier of the held record
+/// * `reason` - Why the record must be preserved
+/// * `placed_by` - User who placed the hold, if still present
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = legal_holds)]
+pub struct LegalHold {
+    pub id: Uuid,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub reason: String,
+    pub placed_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Timestamps for LegalHold {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+}
@@ -81,7 +81,73 @@ pub trait BaseModel: Sized + Timestamps {
 }
 
 // Re-export other model modules
+pub mod activity;
+pub mod archive;
 pub mod auth;
+pub mod certification;
+pub mod crew;
+pub mod customer;
+pub mod document;
+pub mod email_sender;
+pub mod erp;
+pub mod harvest_block;
+pub mod history;
+pub mod import;
+pub mod invitation;
+pub mod legal_hold;
+pub mod notification;
 pub mod organization;
+pub mod organization_archive;
+pub mod organization_settings;
+pub mod ownership_transfer;
+pub mod permission;
+pub mod preference;
+pub mod queued_job;
+pub mod quota;
+pub mod report;
+pub mod role;
+pub mod saved_view;
+pub mod scheduled_job;
+pub mod scim;
+pub mod signoff;
+pub mod sso;
+pub mod stand;
+pub mod subscription;
+pub mod tag;
+pub mod telemetry;
+pub mod timber_sale;
 
-pub use organization::Organization;
+pub use activity::{Activity, ActivityFilter, UserActivity};
+pub use archive::Archive;
+pub use certification::{Certification, CertificationKind};
+pub use crew::{Crew, CrewMember};
+pub use customer::{Customer, SupplyContract};
+pub use document::{Document, DocumentSubject, DocumentVersion};
+pub use email_sender::OrganizationEmailSender;
+pub use erp::{ErpConnection, ErpExport, ErpExportStatus};
+pub use harvest_block::{BlockStatus, Boundary, BoundingBox, HarvestBlock, HarvestBlockFilter};
+pub use history::FieldChange;
+pub use import::{Import, ImportStatus};
+pub use invitation::OrgInvitation;
+pub use legal_hold::LegalHold;
+pub use notification::Notification;
+pub use organization::{Organization, OrganizationFilter, OrganizationSort};
+pub use organization_archive::{OrganizationArchive, OrganizationArchiveStatus};
+pub use organization_settings::OrganizationSettings;
+pub use ownership_transfer::OwnershipTransfer;
+pub use permission::RolePermission;
+pub use preference::UserPreferences;
+pub use queued_job::{DeadLetterJob, QueuedJob};
+pub use quota::OrganizationQuota;
+pub use report::{Report, ReportDelivery, ReportDeliveryStatus, ReportSchedule};
+pub use role::{CustomRole, RoleAssignment};
+pub use saved_view::{SavedView, SavedViewDefault};
+pub use scheduled_job::{JobRunOutcome, JobRunStatus, ScheduledJobState};
+pub use scim::ScimToken;
+pub use signoff::{BlockSignoff, SignoffStep};
+pub use sso::{OrganizationSsoDomain, UserIdentity};
+pub use stand::{SpeciesShare, Stand};
+pub use subscription::Subscription;
+pub use tag::{Tag, TagSubject, Tagging};
+pub use telemetry::{TelemetryApiKey, TelemetryPoint};
+pub use timber_sale::{SaleContract, TenderBid, TenderParcel, TenderStatus, TimberTender};
@@ -0,0 +1,79 @@
+//! Notification model
+//!
+//! Notifications are created for a single user, typically by the
+//! notification rules, and shown in the notification center until read.
+
+use crate::db::schema::notifications;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Represents a notification addressed to a user
+///
+/// # Fields
+///
+/// * `kind` - What triggered the notification (e.g. `import_failed`), for
+///   icons and filtering in the UI
+/// * `link` - Page in the web UI the notification refers to
+/// * `data` - Structured details for the UI, e.g. the id of the affected stand
+/// * `read_at` - When the user read the notification, unread while unset
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+#[diesel(table_name = notifications)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub org_id: Option<Uuid>,
+    pub kind: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub link: Option<String>,
+    pub data: Option<serde_json::Value>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+    /// Creates an unread notification
+    pub fn new(user_id: Uuid, kind: &str, title: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            org_id: None,
+            kind: kind.to_string(),
+            title: title.into(),
+            body: None,
+            link: None,
+            data: None,
+            read_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn with_org(mut self, org_id: Uuid) -> Self {
+        self.org_id = Some(org_id);
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn with_link(mut self, link: impl Into<String>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn is_read(&self) -> bool {
+        self.read_at.is_some()
+    }
+}
@@ -10,6 +10,7 @@ use chrono::{DateTime, Utc};
 use diesel::{pg::Pg, prelude::*};
 use serde::{Deserialize, Serialize};
 use tracing::{error, warn};
+use ts_rs::TS;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -26,6 +27,10 @@ use uuid::Uuid;
 /// * `created_at` - Timestamp of when the organization was created
 /// * `updated_at` - Timestamp of the last update
 /// * `deleted_at` - Optional timestamp for soft deletion
+/// * `parent_id` - Organization this one is a division or area of, if any
+/// * `owner_id` - Admin owning the organization, if one was recorded
+/// * `archived_at` - When the organization was archived; archived
+///   organizations take no writes
 #[derive(
     Debug,
     Default,
@@ -37,8 +42,9 @@ use uuid::Uuid;
     AsChangeset,
     Serialize,
     Deserialize,
-    ToSchema,
+    ToSchema, TS,
 )]
+#[ts(export)]
 #[diesel(table_name = organizations)]
 pub struct Organization {
     pub id: Uuid,
@@ -46,6 +52,9 @@ pub struct Organization {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
+    pub parent_id: Option<Uuid>,
+    pub owner_id: Option<Uuid>,
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 impl Timestamps for Organization {
@@ -129,3 +138,38 @@ impl BaseModel for Organization {
         Box::new(organizations::deleted_at.is_null())
     }
 }
+
+/// What organizations are listed by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrganizationSort {
+    Name,
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// Which organizations to list, and in what order
+#[derive(Debug, Clone)]
+pub struct OrganizationFilter {
+    /// Text the name has to contain, ignoring case
+    pub name_contains: Option<String>,
+    /// Only organizations created after this instant
+    pub created_after: Option<DateTime<Utc>>,
+    pub sort: OrganizationSort,
+    pub descending: bool,
+    /// Lists soft-deleted organizations too
+    pub include_deleted: bool,
+}
+
+impl Default for OrganizationFilter {
+    /// Every live organization, newest first
+    fn default() -> Self {
+        Self {
+            name_contains: None,
+            created_after: None,
+            sort: OrganizationSort::CreatedAt,
+            descending: true,
+            include_deleted: false,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Organization archive model
+//!
+//! Archiving an organization freezes it and writes an export bundle of all
+//! its data to object storage; each archival is one record, which tracks
+//! the bundle and, once the organization is reopened, when that happened.
+
+use crate::db::schema::organization_archives;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// State of an archival's export bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrganizationArchiveStatus {
+    /// Waiting for the export job
+    Pending,
+    /// The bundle is in object storage
+    Completed,
+    /// The export job gave up; the organization stays archived
+    Failed,
+}
+
+impl OrganizationArchiveStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrganizationArchiveStatus::Pending => "pending",
+            OrganizationArchiveStatus::Completed => "completed",
+            OrganizationArchiveStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for OrganizationArchiveStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One archival of an organization
+///
+/// # Fields
+///
+/// * `requested_by` - Admin who archived the organization
+/// * `status` - State of the export bundle, see [`OrganizationArchiveStatus`]
+/// * `file_key` - Object storage key of the bundle once written
+/// * `byte_size` - Size of the bundle in bytes
+/// * `error` - Why writing the bundle failed
+/// * `unarchived_at` - When the organization was reopened
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = organization_archives)]
+pub struct OrganizationArchive {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub requested_by: Option<Uuid>,
+    pub status: String,
+    pub file_key: Option<String>,
+    pub byte_size: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub unarchived_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,29 @@
+//! Organization settings model
+//!
+//! An organization's settings are stored as a JSON document so settings can
+//! be added without migrations. The domain reads and writes them through a
+//! typed struct that validates every value; organizations that never saved
+//! settings have no row and get the defaults.
+
+use crate::db::schema::organization_settings;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Represents an organization's saved settings
+///
+/// # Fields
+///
+/// * `org_id` - Organization the settings belong to
+/// * `settings` - The settings document
+/// * `updated_by` - Admin who last changed them
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = organization_settings)]
+pub struct OrganizationSettings {
+    pub org_id: Uuid,
+    pub settings: serde_json::Value,
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
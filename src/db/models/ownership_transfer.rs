@@ -0,0 +1,29 @@
+//! Organization ownership transfer models
+//!
+//! The owning admin hands an organization to another member, who has to
+//! confirm before anything changes hands.
+
+use crate::db::schema::ownership_transfers;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// A transfer of an organization's ownership
+///
+/// Pending until the new owner accepts it, it is cancelled (`deleted_at`)
+/// or it expires. An organization has at most one pending transfer.
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = ownership_transfers)]
+pub struct OwnershipTransfer {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    /// Owner who started the transfer
+    pub from_user_id: Uuid,
+    /// Member taking over, the only one who may accept
+    pub to_user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,17 @@
+use crate::db::{models::auth::Role, schema::role_permissions};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+/// A permission granted to every user with a role
+///
+/// # Fields
+///
+/// * `role` - Role holding the permission
+/// * `permission` - Permission name, e.g. `customers:write`
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = role_permissions, primary_key(role, permission))]
+pub struct RolePermission {
+    pub role: Role,
+    pub permission: String,
+    pub created_at: DateTime<Utc>,
+}
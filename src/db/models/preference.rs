@@ -0,0 +1,29 @@
+//! User preference models
+//!
+//! How a user wants measurements and times presented: in metric or imperial
+//! units, at a UTC offset and in one of the supported locales. Users who
+//! never saved preferences have no row and get the defaults.
+
+use crate::db::schema::user_preferences;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Represents a user's saved preferences
+///
+/// # Fields
+///
+/// * `units` - `metric` or `imperial`
+/// * `timezone` - `UTC` or an offset from it, such as `-08:00`
+/// * `locale` - Code of a supported locale, such as `fr`
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = user_preferences)]
+pub struct UserPreferences {
+    pub user_id: Uuid,
+    pub units: String,
+    pub timezone: String,
+    pub locale: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
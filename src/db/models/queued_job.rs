@@ -0,0 +1,95 @@
+//! Job queue models
+//!
+//! A queued job is a unit of background work (an import, an export, a
+//! webhook delivery, ...) identified by its `kind` and described by a JSON
+//! payload. Jobs that fail on every attempt move to the dead-letter table
+//! with their last error until an admin requeues them.
+
+use crate::db::schema::{dead_letter_jobs, queued_jobs};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A job waiting for, or in, execution
+///
+/// # Fields
+///
+/// * `kind` - Handler the job is dispatched to (e.g. `stand_import`)
+/// * `attempts` - Number of times the job has been claimed
+/// * `run_at` - Earliest time the job may run, pushed back after failures
+/// * `locked_by` - Worker processing the job
+/// * `locked_until` - End of the visibility timeout; once passed another
+///   worker may claim the job again
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+#[diesel(table_name = queued_jobs)]
+pub struct QueuedJob {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub locked_by: Option<String>,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl QueuedJob {
+    /// Creates a job that may run immediately
+    pub fn new(kind: &str, payload: serde_json::Value, max_attempts: i32) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            kind: kind.to_string(),
+            payload,
+            attempts: 0,
+            max_attempts,
+            run_at: now,
+            locked_by: None,
+            locked_until: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Returns true once no attempts are left
+    pub fn is_exhausted(&self) -> bool {
+        self.attempts >= self.max_attempts
+    }
+}
+
+/// A job that failed on every attempt
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+#[diesel(table_name = dead_letter_jobs)]
+pub struct DeadLetterJob {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub failed_at: DateTime<Utc>,
+    /// When the job was originally enqueued
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<QueuedJob> for DeadLetterJob {
+    fn from(job: QueuedJob) -> Self {
+        Self {
+            id: job.id,
+            kind: job.kind,
+            payload: job.payload,
+            attempts: job.attempts,
+            last_error: job.last_error,
+            failed_at: Utc::now(),
+            created_at: job.created_at,
+        }
+    }
+}
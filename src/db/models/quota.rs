@@ -0,0 +1,24 @@
+//! Organization quota model
+//!
+//! Plan limits of an organization: how many users it may have, how many
+//! harvest blocks it may keep active and how long its telemetry is kept.
+
+use crate::db::schema::organization_quotas;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// Limits of an organization's plan
+///
+/// A limit left `None` is unset: counts are unlimited and telemetry is kept
+/// for the default retention.
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = organization_quotas, primary_key(org_id))]
+pub struct OrganizationQuota {
+    pub org_id: Uuid,
+    pub max_users: Option<i32>,
+    pub max_active_blocks: Option<i32>,
+    pub telemetry_retention_days: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
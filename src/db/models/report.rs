@@ -0,0 +1,125 @@
+//! Report model
+//!
+//! A report is a saved, parameterized query over one of an organization's
+//! datasets. The definition is stored as JSON and interpreted by the report
+//! domain (see `domain::report::ReportDefinition`). A report can have a
+//! schedule emailing it to a list of recipients, and every scheduled run is
+//! kept as a delivery.
+
+use super::Timestamps;
+use crate::db::schema::{report_deliveries, report_schedules, reports};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Represents a saved report definition
+///
+/// # Fields
+///
+/// * `org_id` - Organization whose data the report reads
+/// * `name` - Display name, unique within the organization
+/// * `definition` - Dataset, filters, grouping and columns as JSON
+/// * `created_by` - User who saved the report, if still present
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = reports)]
+pub struct Report {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub definition: serde_json::Value,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Timestamps for Report {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+}
+
+/// Cron schedule emailing a report to its recipients
+///
+/// # Fields
+///
+/// * `schedule` - Cron expression (`sec min hour day month weekday`, UTC)
+/// * `recipients` - Email addresses the report is sent to
+/// * `format` - `json` or `csv`, see `domain::report::ReportFormat`
+/// * `parameters` - Parameter values the report runs with
+/// * `next_run_at` - When the report is delivered next
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = report_schedules)]
+pub struct ReportSchedule {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub report_id: Uuid,
+    pub schedule: String,
+    pub recipients: Vec<String>,
+    pub format: String,
+    pub parameters: serde_json::Value,
+    pub next_run_at: DateTime<Utc>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcome of a scheduled report run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportDeliveryStatus {
+    /// The report ran and the mail was queued for every recipient
+    Queued,
+    /// The report could not be run or rendered; nothing was sent
+    Failed,
+}
+
+impl ReportDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportDeliveryStatus::Queued => "queued",
+            ReportDeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for ReportDeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One scheduled run of a report
+///
+/// # Fields
+///
+/// * `status` - `queued` or `failed`, see [`ReportDeliveryStatus`]
+/// * `row_count` - Rows in the delivered output
+/// * `file_key` - Object storage key of output too large to attach, which
+///   the mail links to instead
+/// * `error` - Why the run failed
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = report_deliveries)]
+pub struct ReportDelivery {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub report_id: Uuid,
+    pub recipients: Vec<String>,
+    pub format: String,
+    pub status: String,
+    pub row_count: Option<i32>,
+    pub file_key: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
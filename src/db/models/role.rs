@@ -0,0 +1,38 @@
+//! Custom role models
+//!
+//! Organizations define roles of their own, such as "Dispatcher" or
+//! "Mechanic", each a set of permissions. Users keep their built-in role
+//! and also hold the permissions of every custom role assigned to them.
+
+use crate::db::schema::{roles, user_roles};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// Represents a role defined by an organization
+///
+/// # Fields
+///
+/// * `name` - Unique within the organization, ignoring case
+/// * `permissions` - Permission names, e.g. `customers:write`
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = roles)]
+pub struct CustomRole {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A custom role assigned to a user
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = user_roles)]
+pub struct RoleAssignment {
+    pub user_id: Uuid,
+    pub role_id: Uuid,
+    pub assigned_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,49 @@
+//! Saved view models
+//!
+//! A saved view is a named configuration of a list endpoint: its filters,
+//! sort order and visible columns. Views belong to the user who saved them
+//! and can be shared with the organization. Each user can pick a view,
+//! their own or a shared one, that a resource's list opens with.
+
+use crate::db::schema::{saved_view_defaults, saved_views};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Represents a saved view of a list endpoint
+///
+/// # Fields
+///
+/// * `user_id` - User who saved the view; only they can change it
+/// * `resource` - List the view applies to, e.g. `customers`
+/// * `filters` - Query parameters the list is filtered with, as an object
+/// * `sort` - Fields sorted by, in order, with `-` for descending
+/// * `columns` - Columns shown, in order
+/// * `shared` - Whether the rest of the organization can use the view
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = saved_views)]
+pub struct SavedView {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub user_id: Uuid,
+    pub resource: String,
+    pub name: String,
+    pub filters: serde_json::Value,
+    pub sort: Vec<String>,
+    #[diesel(column_name = view_columns)]
+    pub columns: Vec<String>,
+    pub shared: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The view a user opens a resource's list with
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = saved_view_defaults)]
+pub struct SavedViewDefault {
+    pub user_id: Uuid,
+    pub resource: String,
+    pub view_id: Uuid,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,84 @@
+//! Scheduled job model
+//!
+//! One row per recurring background job, shared by all server instances.
+//! The row carries the lease that lets a single instance run each
+//! occurrence, an optional schedule overriding the configured one, and the
+//! outcome of the most recent run.
+
+use crate::db::schema::scheduled_jobs;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+
+/// Outcome of the most recent run of a scheduled job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobRunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobRunStatus::Running => "running",
+            JobRunStatus::Succeeded => "succeeded",
+            JobRunStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for JobRunStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result of one run of a scheduled job
+#[derive(Debug, Clone)]
+pub struct JobRunOutcome {
+    pub status: JobRunStatus,
+    /// Summary returned by the job, e.g. the number of purged records
+    pub output: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+}
+
+/// State of a recurring background job
+///
+/// # Fields
+///
+/// * `name` - Name the job is registered under (e.g. `purger`)
+/// * `schedule` - Cron expression overriding the configured schedule
+/// * `next_run_at` - When the job is due next
+/// * `locked_by` - Instance currently running the job
+/// * `locked_until` - Expiry of that instance's lease
+/// * `last_status` - `running`, `succeeded` or `failed`
+/// * `last_output` - Summary returned by the last successful run
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize, ToSchema)]
+#[diesel(table_name = scheduled_jobs, primary_key(name))]
+pub struct ScheduledJobState {
+    pub name: String,
+    pub schedule: Option<String>,
+    pub next_run_at: DateTime<Utc>,
+    pub locked_by: Option<String>,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    pub last_status: Option<String>,
+    pub last_error: Option<String>,
+    pub last_duration_ms: Option<i64>,
+    pub last_output: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ScheduledJobState {
+    /// Returns true while an instance holds an unexpired lease
+    pub fn is_running(&self, now: DateTime<Utc>) -> bool {
+        self.locked_until.is_some_and(|until| until > now)
+    }
+}
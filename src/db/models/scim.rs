@@ -0,0 +1,29 @@
+//! SCIM provisioning models
+//!
+//! Identity providers create, update and deprovision an organization's
+//! users over SCIM 2.0, authenticating with a long-lived token an admin
+//! issued for the organization.
+
+use crate::db::schema::scim_tokens;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// A provisioning token of an organization
+///
+/// Only the SHA-256 of the token is stored; the token itself is shown once,
+/// when it is issued.
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = scim_tokens)]
+pub struct ScimToken {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    /// What the token is for, e.g. the identity provider using it
+    pub name: String,
+    /// Hex SHA-256 of the token
+    pub token_hash: String,
+    pub created_by: Option<Uuid>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,71 @@
+//! Block sign-off models
+//!
+//! A harvest block package is approved by a chain of sign-offs: the
+//! planner who prepared it, a professional forester and an operations
+//! manager, in that order. Each sign-off records who signed, when, and the
+//! hash of the package document they signed.
+
+use crate::db::schema::block_signoffs;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Steps of the approval chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignoffStep {
+    Planner,
+    ProfessionalForester,
+    OperationsManager,
+}
+
+impl SignoffStep {
+    /// Every step, in the order they are signed
+    pub const ALL: [SignoffStep; 3] = [
+        SignoffStep::Planner,
+        SignoffStep::ProfessionalForester,
+        SignoffStep::OperationsManager,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignoffStep::Planner => "planner",
+            SignoffStep::ProfessionalForester => "professional_forester",
+            SignoffStep::OperationsManager => "operations_manager",
+        }
+    }
+
+    /// The step stored as `value`, if any
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|step| step.as_str() == value)
+    }
+}
+
+impl fmt::Display for SignoffStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A sign-off on a harvest block package
+///
+/// # Fields
+///
+/// * `block_id` - Harvest block the package is for
+/// * `step` - See [`SignoffStep`]
+/// * `signer_name` - Name of the signer when they signed
+/// * `document_hash` - SHA-256 of the package document, in hex
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = block_signoffs)]
+pub struct BlockSignoff {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub block_id: Uuid,
+    pub step: String,
+    pub signer_id: Option<Uuid>,
+    pub signer_name: String,
+    pub document_hash: String,
+    pub signed_at: DateTime<Utc>,
+}
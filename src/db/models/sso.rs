@@ -0,0 +1,49 @@
+//! Single sign-on models
+//!
+//! Users sign in through OpenID Connect providers configured in
+//! `auth.oidc`. An identity links a provider account to a user; users
+//! without one are provisioned into the organization that claimed the
+//! domain of their email address.
+
+use crate::db::{
+    models::auth::Role,
+    schema::{organization_sso_domains, user_identities},
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Email domain whose users join an organization on their first sign-on
+///
+/// # Fields
+///
+/// * `domain` - Lower-case domain, e.g. `pinecorp.com`; claimed by one organization
+/// * `org_id` - Organization new users join
+/// * `role` - Role new users get
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+#[diesel(table_name = organization_sso_domains, primary_key(domain))]
+pub struct OrganizationSsoDomain {
+    pub domain: String,
+    pub org_id: Uuid,
+    pub role: Role,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A provider account linked to a user
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = user_identities, primary_key(provider, subject))]
+pub struct UserIdentity {
+    /// Name of the provider in `auth.oidc`
+    pub provider: String,
+    /// The provider's id of the account, the `sub` claim
+    pub subject: String,
+    pub user_id: Uuid,
+    /// Email the provider reported when the account was linked
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: DateTime<Utc>,
+}
@@ -0,0 +1,80 @@
+//! Stand models
+//!
+//! A stand is an inventory unit of a harvest block: an area of fairly even
+//! species mix, age and site. Inventory systems number stands within their
+//! block and upsert them by that number.
+
+use crate::{
+    db::schema::stands,
+    domain::windthrow::{RiskClass, StandConditions},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Share of a stand taken by one species
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SpeciesShare {
+    /// Species name or inventory code, e.g. `spruce`
+    pub species: String,
+    /// Percentage of the stand, the shares of a stand adding up to 100
+    pub percent: f64,
+}
+
+/// Represents a stand of a harvest block
+///
+/// # Fields
+///
+/// * `stand_number` - Unique within the block
+/// * `species` - [`SpeciesShare`]s as a JSON array, leading species first
+/// * `age_class` - 20-year age class, 1 for up to 20 years to 9 for over 250
+/// * `site_index` - Height in metres the leading species reaches at breast
+///   height age 50
+/// * `net_merchantable_volume_m3` - Volume left after deductions for decay,
+///   waste and breakage
+/// * `inventoried_on` - Day the stand was last cruised, if known
+/// * `windthrow_conditions` - [`StandConditions`] as JSON, if the inventory
+///   records them
+/// * `windthrow_score` - Windthrow risk from 0 to 100, scored from the
+///   conditions when the stand was saved
+/// * `windthrow_class` - [`RiskClass`] of the score, in snake case
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = stands)]
+pub struct Stand {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub block_id: Uuid,
+    pub stand_number: String,
+    pub area_ha: f64,
+    pub species: serde_json::Value,
+    pub age_class: i16,
+    pub site_index: f64,
+    pub net_merchantable_volume_m3: f64,
+    pub inventoried_on: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub windthrow_conditions: Option<serde_json::Value>,
+    pub windthrow_score: Option<f64>,
+    pub windthrow_class: Option<String>,
+}
+
+impl Stand {
+    /// The species mix, empty if the stored JSON can't be read
+    pub fn species(&self) -> Vec<SpeciesShare> {
+        serde_json::from_value(self.species.clone()).unwrap_or_default()
+    }
+
+    /// What the windthrow risk is scored from, if recorded and readable
+    pub fn windthrow_conditions(&self) -> Option<StandConditions> {
+        serde_json::from_value(self.windthrow_conditions.clone()?).ok()
+    }
+
+    /// Class of the windthrow risk, if scored
+    pub fn windthrow_class(&self) -> Option<RiskClass> {
+        serde_json::from_value(serde_json::Value::String(self.windthrow_class.clone()?)).ok()
+    }
+}
@@ -0,0 +1,42 @@
+//! Subscription model
+//!
+//! The Stripe subscription an organization pays for its plan with, kept in
+//! step with Stripe through webhook events.
+
+use crate::db::schema::subscriptions;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// Statuses that keep premium features available; `past_due` gives the
+/// customer the dunning period to fix their payment method
+const ACCESS_STATUSES: &[&str] = &["active", "trialing", "past_due"];
+
+/// Represents an organization's subscription
+///
+/// # Fields
+///
+/// * `plan` - Plan id from the catalog, e.g. `team`
+/// * `status` - Stripe subscription status, e.g. `active` or `canceled`
+/// * `current_period_end` - When the paid period ends
+/// * `cancel_at_period_end` - Whether the subscription ends with the period
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = subscriptions, primary_key(org_id))]
+pub struct Subscription {
+    pub org_id: Uuid,
+    pub plan: String,
+    pub status: String,
+    pub stripe_customer_id: Option<String>,
+    pub stripe_subscription_id: Option<String>,
+    pub current_period_end: Option<DateTime<Utc>>,
+    pub cancel_at_period_end: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Subscription {
+    /// Whether the subscription's status gives access to premium features
+    pub fn is_active(&self) -> bool {
+        ACCESS_STATUSES.contains(&self.status.as_str())
+    }
+}
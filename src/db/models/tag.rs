@@ -0,0 +1,75 @@
+//! Tag models
+//!
+//! Tags are defined by an organization, each with a name and a color, and
+//! attached to its stands, blocks, equipment and work orders. List
+//! endpoints of those can be filtered by tag.
+
+use crate::db::schema::{taggings, tags};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// What a tag can be attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagSubject {
+    Stand,
+    Block,
+    Equipment,
+    WorkOrder,
+}
+
+impl TagSubject {
+    pub const ALL: [TagSubject; 4] = [TagSubject::Stand, TagSubject::Block, TagSubject::Equipment, TagSubject::WorkOrder];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TagSubject::Stand => "stand",
+            TagSubject::Block => "block",
+            TagSubject::Equipment => "equipment",
+            TagSubject::WorkOrder => "work_order",
+        }
+    }
+
+    /// The subject stored as `value`, if any
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|subject| subject.as_str() == value)
+    }
+}
+
+impl fmt::Display for TagSubject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Represents a tag of an organization
+///
+/// # Fields
+///
+/// * `name` - Unique within the organization, ignoring case
+/// * `color` - Hex color such as `#2e7d32`
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = tags)]
+pub struct Tag {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub name: String,
+    pub color: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A tag attached to a subject, see [`TagSubject`]
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = taggings)]
+pub struct Tagging {
+    pub tag_id: Uuid,
+    pub org_id: Uuid,
+    pub subject_type: String,
+    pub subject_id: Uuid,
+    pub tagged_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,55 @@
+//! Machine telemetry models
+//!
+//! Harvesters, forwarders and other machines report engine hours, fuel use
+//! and position through their telematics units, uploading with an API key
+//! an admin issued for the organization. A unit that was offline uploads
+//! what it buffered once it's back in coverage, so a point may arrive more
+//! than once; points are kept one per machine and time.
+
+use crate::db::schema::{machine_telemetry, telemetry_api_keys};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// An API key telemetry is uploaded with
+///
+/// Only the SHA-256 of the key is stored; the key itself is shown once,
+/// when it is issued.
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable)]
+#[diesel(table_name = telemetry_api_keys)]
+pub struct TelemetryApiKey {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    /// What the key is for, e.g. the machine or gateway using it
+    pub name: String,
+    /// Hex SHA-256 of the key
+    pub key_hash: String,
+    pub created_by: Option<Uuid>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Represents a telemetry data point of a machine
+///
+/// # Fields
+///
+/// * `machine_id` - The machine's own identifier, as configured on its
+///   telematics unit
+/// * `recorded_at` - When the machine took the reading
+/// * `engine_hours` - Engine hour meter reading
+/// * `fuel_used_l` - Fuel used according to the machine's counter, in litres
+/// * `latitude`, `longitude` - WGS 84 position, both or neither
+/// * `received_at` - When the point was last uploaded
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable, Insertable)]
+#[diesel(table_name = machine_telemetry)]
+pub struct TelemetryPoint {
+    pub org_id: Uuid,
+    pub machine_id: String,
+    pub recorded_at: DateTime<Utc>,
+    pub engine_hours: Option<f64>,
+    pub fuel_used_l: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub received_at: DateTime<Utc>,
+}
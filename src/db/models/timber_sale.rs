@@ -0,0 +1,130 @@
+//! Timber sale models
+//!
+//! A tender offers parcels of timber, each an assortment and volume from a
+//! harvest block, and takes sealed bids for them until its deadline. Each
+//! parcel is awarded to one bid, which becomes a sale contract that
+//! deliveries are tracked against.
+
+use crate::db::schema::{sale_contracts, tender_bids, tender_parcels, timber_tenders};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Lifecycle of a tender
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TenderStatus {
+    /// Being prepared; parcels can still change and no bids are taken
+    Draft,
+    /// Published; bids are taken until the deadline, then parcels are
+    /// awarded
+    Open,
+    /// Every parcel is awarded
+    Awarded,
+    Cancelled,
+}
+
+impl TenderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TenderStatus::Draft => "draft",
+            TenderStatus::Open => "open",
+            TenderStatus::Awarded => "awarded",
+            TenderStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for TenderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Represents a timber sale tender or sealed-bid auction
+///
+/// # Fields
+///
+/// * `status` - See [`TenderStatus`]
+/// * `bid_deadline` - Bids must be received by this time; they stay sealed
+///   until it passes
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = timber_tenders)]
+pub struct TimberTender {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    pub bid_deadline: DateTime<Utc>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A parcel of timber offered in a tender
+///
+/// # Fields
+///
+/// * `position` - Order the parcel is listed in
+/// * `block_id` - Harvest block the timber comes from
+/// * `assortment` - Product offered, e.g. `spruce sawlog`
+/// * `reserve_price` - Lowest price per cubic metre that can be awarded
+/// * `awarded_bid_id` - Bid the parcel was awarded to
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = tender_parcels)]
+pub struct TenderParcel {
+    pub id: Uuid,
+    pub tender_id: Uuid,
+    pub position: i32,
+    pub block_id: Option<Uuid>,
+    pub assortment: String,
+    pub volume_m3: f64,
+    pub reserve_price: Option<f64>,
+    pub description: Option<String>,
+    pub awarded_bid_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A sealed bid for a parcel
+///
+/// # Fields
+///
+/// * `received_at` - When the bid reached the seller
+/// * `captured_by` - Who recorded the bid
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = tender_bids)]
+pub struct TenderBid {
+    pub id: Uuid,
+    pub tender_id: Uuid,
+    pub parcel_id: Uuid,
+    pub bidder_name: String,
+    pub bidder_email: Option<String>,
+    pub price_per_m3: f64,
+    pub received_at: DateTime<Utc>,
+    pub captured_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A sale contract awarded from a bid
+///
+/// The buyer, block, assortment, volume and price are copied from the bid
+/// and its parcel when awarded.
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = sale_contracts)]
+pub struct SaleContract {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub tender_id: Uuid,
+    pub parcel_id: Uuid,
+    pub bid_id: Uuid,
+    pub buyer_name: String,
+    pub block_id: Option<Uuid>,
+    pub assortment: String,
+    pub volume_m3: f64,
+    pub price_per_m3: f64,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
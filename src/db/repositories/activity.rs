@@ -0,0 +1,159 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        models::{Activity, ActivityFilter, UserActivity},
+        schema::{activities, user_activities},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of the actions users take and of the events of organizations
+#[async_trait]
+pub trait ActivityRepository: Send + Sync + 'static {
+    /// Stores an activity
+    async fn record(&self, conn: &mut PgConnection, activity: &UserActivity) -> Result<()>;
+
+    /// Page through a user's activities in an organization, newest first,
+    /// only those from `from` until `until` when given, with the total count
+    async fn list_for_user(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        user_id: Uuid,
+        period: Option<(DateTime<Utc>, DateTime<Utc>)>,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<UserActivity>, i64)>;
+
+    /// Stores an organization activity
+    async fn record_event(&self, conn: &mut PgConnection, activity: &Activity) -> Result<()>;
+
+    /// Page through the activities of organization `org_id` matching
+    /// `filter`, newest first, with the total count
+    async fn feed(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        filter: &ActivityFilter,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<Activity>, i64)>;
+}
+
+/// Concrete implementation of the activity repository
+pub struct ActivityRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl ActivityRepository for ActivityRepositoryImpl {
+    async fn record(&self, conn: &mut PgConnection, activity: &UserActivity) -> Result<()> {
+        // In a savepoint, so a failure leaves the caller's transaction usable
+        conn.transaction(|conn| {
+            diesel::insert_into(user_activities::table)
+                .values(activity)
+                .execute(conn)
+        })
+        .map(|_| ())
+        .map_err(|e| database_error("record activity", e))
+    }
+
+    async fn list_for_user(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        user_id: Uuid,
+        period: Option<(DateTime<Utc>, DateTime<Utc>)>,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<UserActivity>, i64)> {
+        let query = || {
+            let mut query = user_activities::table
+                .scoped(organization)
+                .filter(user_activities::user_id.eq(user_id))
+                .into_boxed();
+            if let Some((from, until)) = period {
+                query = query
+                    .filter(user_activities::created_at.ge(from))
+                    .filter(user_activities::created_at.lt(until));
+            }
+            query
+        };
+
+        let total = query()
+            .count()
+            .get_result(conn)
+            .map_err(|e| database_error("count activities", e))?;
+        let activities = query()
+            .order_by((user_activities::created_at.desc(), user_activities::id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list activities", e))?;
+        Ok((activities, total))
+    }
+
+    async fn record_event(&self, conn: &mut PgConnection, activity: &Activity) -> Result<()> {
+        // In a savepoint, so a failure leaves the caller's transaction usable
+        conn.transaction(|conn| {
+            diesel::insert_into(activities::table)
+                .values(activity)
+                .execute(conn)
+        })
+        .map(|_| ())
+        .map_err(|e| database_error("record organization activity", e))
+    }
+
+    async fn feed(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        filter: &ActivityFilter,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<Activity>, i64)> {
+        let query = || {
+            let mut query = activities::table
+                .scoped(org_id)
+                .into_boxed();
+            if !filter.kinds.is_empty() {
+                query = query.filter(activities::kind.eq_any(&filter.kinds));
+            }
+            if let Some(actor_id) = filter.actor_id {
+                query = query.filter(activities::actor_id.eq(actor_id));
+            }
+            if let Some(subject_type) = &filter.subject_type {
+                query = query.filter(activities::subject_type.eq(subject_type));
+            }
+            if let Some(since) = filter.since {
+                query = query.filter(activities::created_at.ge(since));
+            }
+            if let Some(until) = filter.until {
+                query = query.filter(activities::created_at.lt(until));
+            }
+            query
+        };
+
+        let total = query()
+            .count()
+            .get_result(conn)
+            .map_err(|e| database_error("count organization activities", e))?;
+        let activities = query()
+            .order_by((activities::created_at.desc(), activities::id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list organization activities", e))?;
+        Ok((activities, total))
+    }
+}
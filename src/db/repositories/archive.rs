@@ -0,0 +1,226 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        count::RowCount,
+        models::Archive,
+        repositories::Repository,
+        schema::archives::dsl::*,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+/// Archive-specific repository operations
+#[async_trait]
+pub trait ArchiveRepository: Repository<Archive> {
+    /// Lists archives of a single dataset, newest range first
+    async fn list_by_dataset(
+        &self,
+        conn: &mut PgConnection,
+        dataset_name: &str,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<Archive>>;
+
+    /// Counts live archives, optionally restricted to one dataset
+    async fn count(&self, conn: &mut PgConnection, dataset_name: Option<&str>) -> Result<RowCount>;
+
+    /// Returns the time ranges of archives of a dataset rehydrated after `since`
+    ///
+    /// The archiver skips these ranges so freshly restored records are not
+    /// moved straight back to cold storage.
+    async fn rehydrated_ranges(
+        &self,
+        conn: &mut PgConnection,
+        dataset_name: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>>;
+}
+
+/// Concrete implementation of the archive repository
+pub struct ArchiveRepositoryImpl;
+
+#[async_trait]
+impl Repository<Archive> for ArchiveRepositoryImpl {
+    async fn find_by_id(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<Archive> {
+        archives
+            .find(search_id)
+            .filter(deleted_at.is_null())
+            .first(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    warn!(
+                        error_code = %ErrorCode::NotFound,
+                        archive_id = %search_id,
+                        "Archive not found"
+                    );
+                    ApiError::not_found(format!("Archive with id {} not found", search_id))
+                }
+                _ => {
+                    error!(
+                        error_code = %ErrorCode::DatabaseError,
+                        archive_id = %search_id,
+                        error = %e,
+                        "Database error occurred while finding archive"
+                    );
+                    ApiError::database_error("Failed to find archive", None)
+                }
+            })
+    }
+
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<Archive>> {
+        archives
+            .filter(id.eq_any(ids))
+            .filter(deleted_at.is_null())
+            .load(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Database error occurred while batch loading archives"
+                );
+                ApiError::database_error("Failed to find archives", None)
+            })
+    }
+
+    async fn create(&self, conn: &mut PgConnection, archive: &Archive) -> Result<Archive> {
+        diesel::insert_into(archives)
+            .values(archive)
+            .get_result(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Failed to create archive"
+                );
+                ApiError::database_error("Failed to create archive", None)
+            })
+    }
+
+    async fn update(&self, conn: &mut PgConnection, search_id: Uuid, archive: &Archive) -> Result<Archive> {
+        diesel::update(archives.find(search_id))
+            .set(archive)
+            .get_result(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    ApiError::not_found(format!("Archive with id {} not found", search_id))
+                }
+                _ => {
+                    error!(
+                        error_code = %ErrorCode::DatabaseError,
+                        error = %e,
+                        "Failed to update archive"
+                    );
+                    ApiError::database_error("Failed to update archive", None)
+                }
+            })
+    }
+
+    async fn soft_delete(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<Archive> {
+        diesel::update(archives.find(search_id))
+            .set(deleted_at.eq(Some(Utc::now())))
+            .get_result(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    ApiError::not_found(format!("Archive with id {} not found", search_id))
+                }
+                _ => {
+                    error!(
+                        error_code = %ErrorCode::DatabaseError,
+                        error = %e,
+                        "Failed to delete archive"
+                    );
+                    ApiError::database_error("Failed to delete archive", None)
+                }
+            })
+    }
+
+    async fn list(&self, conn: &mut PgConnection, pagination: &PaginationParams) -> Result<Vec<Archive>> {
+        archives
+            .filter(deleted_at.is_null())
+            .order_by((range_start.desc(), id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Database error occurred while listing archives"
+                );
+                ApiError::database_error("Failed to list archives", None)
+            })
+    }
+}
+
+#[async_trait]
+impl ArchiveRepository for ArchiveRepositoryImpl {
+    async fn list_by_dataset(
+        &self,
+        conn: &mut PgConnection,
+        dataset_name: &str,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<Archive>> {
+        archives
+            .filter(dataset.eq(dataset_name))
+            .filter(deleted_at.is_null())
+            .order_by((range_start.desc(), id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    dataset = %dataset_name,
+                    error = %e,
+                    "Database error occurred while listing archives"
+                );
+                ApiError::database_error("Failed to list archives", None)
+            })
+    }
+
+    async fn count(&self, conn: &mut PgConnection, dataset_name: Option<&str>) -> Result<RowCount> {
+        let mut query = archives.filter(deleted_at.is_null()).into_boxed();
+        if let Some(dataset_name) = dataset_name {
+            query = query.filter(dataset.eq(dataset_name));
+        }
+
+        query
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Failed to count archives"
+                );
+                ApiError::database_error("Failed to count archives", None)
+            })
+    }
+
+    async fn rehydrated_ranges(
+        &self,
+        conn: &mut PgConnection,
+        dataset_name: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        archives
+            .filter(dataset.eq(dataset_name))
+            .filter(rehydrated_at.gt(since))
+            .select((range_start, range_end))
+            .load(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    dataset = %dataset_name,
+                    error = %e,
+                    "Failed to load rehydrated archive ranges"
+                );
+                ApiError::database_error("Failed to load rehydrated archives", None)
+            })
+    }
+}
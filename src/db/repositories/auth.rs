@@ -6,14 +6,17 @@
 use crate::{
     api::utils::PaginationParams,
     db::{
-        models::auth::{User, RefreshToken, PasswordResetToken, EmailVerificationToken, Role},
-        schema::{users, refresh_tokens},
+        models::auth::{User, ProfileChanges, UserFilter, UserSort, RefreshToken, PasswordResetToken, EmailVerificationToken, EmailChangeToken, MagicLinkToken, Device, Passkey, AuthEvent, Role},
+        schema::{users, refresh_tokens, password_reset_tokens, email_verification_tokens, email_change_tokens, magic_link_tokens, devices, passkeys, auth_events},
+        tenant::TenantScoped,
         repositories::Repository,
+        trigram::{word_similar_to, word_similarity},
     },
-    error::{Result, ApiError, ErrorCode},
+    error::{Result, ApiError, ErrorCode, ErrorContext},
+    utils::SessionConfig,
 };
 use async_trait::async_trait;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use diesel::prelude::*;
 use tracing::{error, warn, info};
 use uuid::Uuid;
@@ -26,6 +29,7 @@ pub struct CreateUserParams<'a> {
     pub phone_number: &'a str,
     pub password: &'a str,
     pub org_id: Uuid,
+    pub role: Role,
 }
 
 /// User-specific repository operations
@@ -46,6 +50,57 @@ pub trait UserRepository: Repository<User> {
         conn: &mut PgConnection,
         params: CreateUserParams<'_>,
     ) -> Result<User>;
+
+    /// Replace a user's password with the hash of `password`
+    async fn update_password(&self, conn: &mut PgConnection, user_id: Uuid, password: &str) -> Result<User>;
+
+    /// Mark a user's email address as verified
+    async fn mark_email_verified(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<User>;
+
+    /// Switch a user to a confirmed email address, marking it verified
+    async fn change_email(&self, conn: &mut PgConnection, user_id: Uuid, email: &str) -> Result<User>;
+
+    /// Update a user's name and phone number
+    async fn update_profile(&self, conn: &mut PgConnection, user_id: Uuid, changes: &ProfileChanges) -> Result<User>;
+
+    /// Record when a user last uploaded an avatar
+    async fn set_avatar_updated_at(&self, conn: &mut PgConnection, user_id: Uuid, at: DateTime<Utc>) -> Result<User>;
+
+    /// Deactivate or reactivate a user
+    async fn set_active(&self, conn: &mut PgConnection, user_id: Uuid, active: bool) -> Result<User>;
+
+    /// Find a user, removed or not
+    async fn find_with_removed(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<User>;
+
+    /// Make a user report to `supervisor_id`, or to no one
+    async fn set_supervisor(&self, conn: &mut PgConnection, user_id: Uuid, supervisor_id: Option<Uuid>) -> Result<User>;
+
+    /// The users reporting directly to `supervisor_id`, by name
+    async fn find_reports(&self, conn: &mut PgConnection, supervisor_id: Uuid) -> Result<Vec<User>>;
+
+    /// Active members of the organizations with the manager role or above
+    async fn find_supervisors(&self, conn: &mut PgConnection, org_ids: &[Uuid]) -> Result<Vec<User>>;
+
+    /// The emails of `emails` some user, removed or not, already has
+    async fn find_taken_emails(&self, conn: &mut PgConnection, emails: &[String]) -> Result<Vec<String>>;
+
+    /// The phone numbers of `phone_numbers` some user already has
+    async fn find_taken_phone_numbers(&self, conn: &mut PgConnection, phone_numbers: &[String]) -> Result<Vec<String>>;
+
+    /// Page through the users of an organization matching `filter`, with
+    /// the total count
+    async fn search(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        filter: &UserFilter,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<User>, i64)>;
+}
+
+/// An `ILIKE` pattern matching `word` anywhere
+pub(crate) fn contains_pattern(word: &str) -> String {
+    format!("%{}%", word.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"))
 }
 
 /// Concrete implementation of the user repository
@@ -82,6 +137,18 @@ impl Repository<User> for UserRepositoryImpl {
             })
     }
 
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<User>> {
+        users::table
+            .filter(users::id.eq_any(ids))
+            .filter(users::deleted_at.is_null())
+            .select(User::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to batch load users: {}", e);
+                ApiError::database_error("Failed to find users", None)
+            })
+    }
+
     async fn create(&self, conn: &mut PgConnection, model: &User) -> Result<User> {
         diesel::insert_into(users::table)
             .values(model)
@@ -204,8 +271,12 @@ impl UserRepository for UserRepositoryImpl {
             phone_number: params.phone_number.to_string(),
             password: hashed_password,
             org_id: params.org_id,
-            role: Role::Admin,
+            role: params.role,
             email_verified: false,
+            avatar_updated_at: None,
+            is_active: true,
+            supervisor_id: None,
+            anonymized_at: None,
             created_at: now,
             updated_at: now,
             deleted_at: None,
@@ -213,19 +284,337 @@ impl UserRepository for UserRepositoryImpl {
 
         self.create(conn, &user).await
     }
+
+    async fn update_password(&self, conn: &mut PgConnection, user_id: Uuid, password: &str) -> Result<User> {
+        let hashed_password = User::hash_password(password)?;
+
+        diesel::update(users::table)
+            .filter(users::id.eq(user_id))
+            .filter(users::deleted_at.is_null())
+            .set((users::password.eq(hashed_password), users::updated_at.eq(Utc::now())))
+            .returning(User::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to update password: {}", e);
+                ApiError::database_error("Failed to update password", None)
+            })?
+            .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", user_id)))
+    }
+
+    async fn mark_email_verified(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<User> {
+        diesel::update(users::table)
+            .filter(users::id.eq(user_id))
+            .filter(users::deleted_at.is_null())
+            .set((users::email_verified.eq(true), users::updated_at.eq(Utc::now())))
+            .returning(User::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to mark email verified: {}", e);
+                ApiError::database_error("Failed to mark email verified", None)
+            })?
+            .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", user_id)))
+    }
+
+    async fn change_email(&self, conn: &mut PgConnection, user_id: Uuid, email: &str) -> Result<User> {
+        diesel::update(users::table)
+            .filter(users::id.eq(user_id))
+            .filter(users::deleted_at.is_null())
+            .set((
+                users::email.eq(email),
+                users::email_verified.eq(true),
+                users::updated_at.eq(Utc::now()),
+            ))
+            .returning(User::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                    ApiError::new(
+                        ErrorCode::Conflict,
+                        "Email already registered",
+                        ErrorContext::new().with_details(serde_json::json!({ "field": "email" })),
+                    )
+                }
+                e => {
+                    error!("Failed to change email: {}", e);
+                    ApiError::database_error("Failed to change email", None)
+                }
+            })?
+            .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", user_id)))
+    }
+
+    async fn update_profile(&self, conn: &mut PgConnection, user_id: Uuid, changes: &ProfileChanges) -> Result<User> {
+        diesel::update(users::table)
+            .filter(users::id.eq(user_id))
+            .filter(users::deleted_at.is_null())
+            .set((changes, users::updated_at.eq(Utc::now())))
+            .returning(User::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                    ApiError::new(
+                        ErrorCode::Conflict,
+                        "Phone number already in use",
+                        ErrorContext::new().with_details(serde_json::json!({ "field": "phone_number" })),
+                    )
+                }
+                e => {
+                    error!("Failed to update profile: {}", e);
+                    ApiError::database_error("Failed to update profile", None)
+                }
+            })?
+            .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", user_id)))
+    }
+
+    async fn set_avatar_updated_at(&self, conn: &mut PgConnection, user_id: Uuid, at: DateTime<Utc>) -> Result<User> {
+        diesel::update(users::table)
+            .filter(users::id.eq(user_id))
+            .filter(users::deleted_at.is_null())
+            .set((users::avatar_updated_at.eq(at), users::updated_at.eq(Utc::now())))
+            .returning(User::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to record avatar upload: {}", e);
+                ApiError::database_error("Failed to record avatar upload", None)
+            })?
+            .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", user_id)))
+    }
+
+    async fn set_active(&self, conn: &mut PgConnection, user_id: Uuid, active: bool) -> Result<User> {
+        diesel::update(users::table)
+            .filter(users::id.eq(user_id))
+            .filter(users::deleted_at.is_null())
+            .set((users::is_active.eq(active), users::updated_at.eq(Utc::now())))
+            .returning(User::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to change whether user is active: {}", e);
+                ApiError::database_error("Failed to change whether user is active", None)
+            })?
+            .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", user_id)))
+    }
+
+    async fn find_with_removed(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<User> {
+        users::table
+            .find(user_id)
+            .select(User::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to find user: {}", e);
+                ApiError::database_error("Failed to find user", None)
+            })?
+            .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", user_id)))
+    }
+
+    async fn set_supervisor(&self, conn: &mut PgConnection, user_id: Uuid, supervisor_id: Option<Uuid>) -> Result<User> {
+        diesel::update(users::table)
+            .filter(users::id.eq(user_id))
+            .filter(users::deleted_at.is_null())
+            .set((users::supervisor_id.eq(supervisor_id), users::updated_at.eq(Utc::now())))
+            .returning(User::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to change user's supervisor: {}", e);
+                ApiError::database_error("Failed to change user's supervisor", None)
+            })?
+            .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", user_id)))
+    }
+
+    async fn find_reports(&self, conn: &mut PgConnection, supervisor_id: Uuid) -> Result<Vec<User>> {
+        users::table
+            .filter(users::supervisor_id.eq(supervisor_id))
+            .filter(users::deleted_at.is_null())
+            .order_by((users::first_name.asc(), users::last_name.asc(), users::id.asc()))
+            .select(User::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to find direct reports: {}", e);
+                ApiError::database_error("Failed to find direct reports", None)
+            })
+    }
+
+    async fn find_supervisors(&self, conn: &mut PgConnection, org_ids: &[Uuid]) -> Result<Vec<User>> {
+        users::table
+            .filter(users::org_id.eq_any(org_ids))
+            .filter(users::role.eq_any([Role::Manager, Role::Admin]))
+            .filter(users::is_active.eq(true))
+            .filter(users::deleted_at.is_null())
+            .order_by(users::id.asc())
+            .select(User::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to find supervisors: {}", e);
+                ApiError::database_error("Failed to find supervisors", None)
+            })
+    }
+
+    async fn find_taken_emails(&self, conn: &mut PgConnection, emails: &[String]) -> Result<Vec<String>> {
+        users::table
+            .filter(users::email.eq_any(emails))
+            .select(users::email)
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to look up emails: {}", e);
+                ApiError::database_error("Failed to look up emails", None)
+            })
+    }
+
+    async fn find_taken_phone_numbers(&self, conn: &mut PgConnection, phone_numbers: &[String]) -> Result<Vec<String>> {
+        users::table
+            .filter(users::phone_number.eq_any(phone_numbers))
+            .select(users::phone_number)
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to look up phone numbers: {}", e);
+                ApiError::database_error("Failed to look up phone numbers", None)
+            })
+    }
+
+    async fn search(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        filter: &UserFilter,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<User>, i64)> {
+        let search = filter.query.as_deref().map(str::trim).filter(|search| !search.is_empty());
+        let filtered = || {
+            let mut query = users::table
+                .scoped(organization)
+                .into_boxed();
+            query = if filter.deprovisioned {
+                query.filter(users::deleted_at.is_not_null())
+            } else {
+                query.filter(users::deleted_at.is_null())
+            };
+            if let Some(active) = filter.active {
+                query = query.filter(users::is_active.eq(active));
+            }
+            if !filter.roles.is_empty() {
+                query = query.filter(users::role.eq_any(filter.roles.clone()));
+            }
+            // Every word has to match a name or the email, so "jane smi"
+            // finds Jane Smith; the trigram operator forgives typos
+            for word in search.unwrap_or_default().split_whitespace() {
+                let pattern = contains_pattern(word);
+                query = query.filter(
+                    users::first_name
+                        .ilike(pattern.clone())
+                        .or(users::last_name.ilike(pattern.clone()))
+                        .or(users::email.ilike(pattern))
+                        .or(word_similar_to(word, users::first_name))
+                        .or(word_similar_to(word, users::last_name))
+                        .or(word_similar_to(word, users::email)),
+                );
+            }
+            query
+        };
+
+        let total = filtered()
+            .count()
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to count users: {}", e);
+                ApiError::database_error("Failed to count users", None)
+            })?;
+
+        let full_name = users::first_name.concat(" ").concat(users::last_name);
+        let mut query = filtered();
+        query = match (filter.sort, search, filter.descending) {
+            (UserSort::Relevance, Some(search), false) => query
+                .order_by(word_similarity(search.to_string(), full_name).desc())
+                .then_order_by(word_similarity(search.to_string(), users::email).desc()),
+            (UserSort::Relevance, Some(search), true) => query
+                .order_by(word_similarity(search.to_string(), full_name).asc())
+                .then_order_by(word_similarity(search.to_string(), users::email).asc()),
+            (UserSort::Name | UserSort::Relevance, _, false) => {
+                query.order_by((users::first_name.asc(), users::last_name.asc()))
+            }
+            (UserSort::Name | UserSort::Relevance, _, true) => {
+                query.order_by((users::first_name.desc(), users::last_name.desc()))
+            }
+            (UserSort::Email, _, false) => query.order_by(users::email.asc()),
+            (UserSort::Email, _, true) => query.order_by(users::email.desc()),
+            (UserSort::Role, _, false) => query.order_by(users::role.asc()),
+            (UserSort::Role, _, true) => query.order_by(users::role.desc()),
+            (UserSort::CreatedAt, _, false) => query.order_by(users::created_at.asc()),
+            (UserSort::CreatedAt, _, true) => query.order_by(users::created_at.desc()),
+        };
+
+        let users = query
+            .then_order_by((users::first_name.asc(), users::last_name.asc(), users::id.asc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .select(User::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to search users: {}", e);
+                ApiError::database_error("Failed to search users", None)
+            })?;
+        Ok((users, total))
+    }
+}
+
+/// How long the session a login starts lasts
+#[derive(Debug, Clone, Copy)]
+pub struct SessionLifetime {
+    /// Without being refreshed
+    pub idle: Duration,
+    /// At most, however often it is refreshed
+    pub max: Duration,
+}
+
+impl From<&SessionConfig> for SessionLifetime {
+    fn from(config: &SessionConfig) -> Self {
+        Self {
+            idle: config.idle_timeout(),
+            max: config.max_lifetime(),
+        }
+    }
+}
+
+impl Default for SessionLifetime {
+    fn default() -> Self {
+        Self::from(&SessionConfig::default())
+    }
 }
 
 /// Refresh token repository operations
 #[async_trait]
 pub trait RefreshTokenRepository: Repository<RefreshToken> {
-    /// Create a new refresh token for a user
-    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<RefreshToken>;
+    /// Create a new refresh token for a user, starting a session
+    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid, lifetime: SessionLifetime) -> Result<RefreshToken>;
+
+    /// Create a new refresh token for a user, issued to `device_id` if
+    /// given, starting a session
+    async fn create_for_device(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        device_id: Option<&str>,
+        lifetime: SessionLifetime,
+    ) -> Result<RefreshToken>;
+
+    /// Replace `token` with a new one for the same device and session,
+    /// valid for `idle` unless the session ends first
+    async fn renew(&self, conn: &mut PgConnection, token: &RefreshToken, idle: Duration) -> Result<RefreshToken>;
     
     /// Find a refresh token by its token string
     async fn find_by_token(&self, conn: &mut PgConnection, token: &str) -> Result<Option<RefreshToken>>;
     
     /// Revoke all refresh tokens for a user
     async fn revoke_all_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<()>;
+
+    /// Revoke the user's refresh tokens issued to `device_id`, or those
+    /// issued to no device for `None`
+    async fn revoke_for_device(&self, conn: &mut PgConnection, user_id: Uuid, device_id: Option<&str>) -> Result<()>;
 }
 
 /// Concrete implementation of the refresh token repository
@@ -245,6 +634,18 @@ impl Repository<RefreshToken> for RefreshTokenRepositoryImpl {
             })
     }
 
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<RefreshToken>> {
+        refresh_tokens::table
+            .filter(refresh_tokens::id.eq_any(ids))
+            .filter(refresh_tokens::deleted_at.is_null())
+            .select(RefreshToken::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to batch load refresh tokens: {}", e);
+                ApiError::database_error("Failed to find refresh tokens", None)
+            })
+    }
+
     async fn create(&self, conn: &mut PgConnection, model: &RefreshToken) -> Result<RefreshToken> {
         diesel::insert_into(refresh_tokens::table)
             .values(model)
@@ -297,7 +698,17 @@ impl Repository<RefreshToken> for RefreshTokenRepositoryImpl {
 
 #[async_trait]
 impl RefreshTokenRepository for RefreshTokenRepositoryImpl {
-    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<RefreshToken> {
+    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid, lifetime: SessionLifetime) -> Result<RefreshToken> {
+        self.create_for_device(conn, user_id, None, lifetime).await
+    }
+
+    async fn create_for_device(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        device_id: Option<&str>,
+        lifetime: SessionLifetime,
+    ) -> Result<RefreshToken> {
         let now = Utc::now();
         let token = Uuid::new_v4().to_string();
 
@@ -305,10 +716,32 @@ impl RefreshTokenRepository for RefreshTokenRepositoryImpl {
             id: Uuid::new_v4(),
             token,
             user_id,
-            expires_at: now + Duration::days(7),
+            expires_at: now + lifetime.idle.min(lifetime.max),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+            device_id: device_id.map(str::to_string),
+            session_expires_at: now + lifetime.max,
+        };
+
+        self.create(conn, &refresh_token).await
+    }
+
+    async fn renew(&self, conn: &mut PgConnection, token: &RefreshToken, idle: Duration) -> Result<RefreshToken> {
+        let device_id = token.device_id.as_deref();
+        self.revoke_for_device(conn, token.user_id, device_id).await?;
+
+        let now = Utc::now();
+        let refresh_token = RefreshToken {
+            id: Uuid::new_v4(),
+            token: Uuid::new_v4().to_string(),
+            user_id: token.user_id,
+            expires_at: (now + idle).min(token.session_expires_at),
             created_at: now,
             updated_at: now,
             deleted_at: None,
+            device_id: token.device_id.clone(),
+            session_expires_at: token.session_expires_at,
         };
 
         self.create(conn, &refresh_token).await
@@ -338,28 +771,1180 @@ impl RefreshTokenRepository for RefreshTokenRepositoryImpl {
             })?;
         Ok(())
     }
+
+    async fn revoke_for_device(&self, conn: &mut PgConnection, user_id: Uuid, device_id: Option<&str>) -> Result<()> {
+        let mut query = diesel::update(refresh_tokens::table)
+            .filter(refresh_tokens::user_id.eq(user_id))
+            .filter(refresh_tokens::deleted_at.is_null())
+            .into_boxed();
+        query = match device_id {
+            Some(device_id) => query.filter(refresh_tokens::device_id.eq(device_id)),
+            None => query.filter(refresh_tokens::device_id.is_null()),
+        };
+        query
+            .set(refresh_tokens::deleted_at.eq(Some(Utc::now())))
+            .execute(conn)
+            .map_err(|e| {
+                error!("Failed to revoke refresh tokens: {}", e);
+                ApiError::database_error("Failed to revoke refresh tokens", None)
+            })?;
+        Ok(())
+    }
 }
 
-/// TODO: Implement
+/// Minutes a password reset token stays valid
+pub const PASSWORD_RESET_TOKEN_MINUTES: i64 = 30;
+
 /// Password reset token repository operations
-#[allow(unused)]
 #[async_trait]
 pub trait PasswordResetTokenRepository: Repository<PasswordResetToken> {
-    /// Create a new password reset token for a user
-    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<PasswordResetToken>;
-    
-    /// Find a password reset token by its token string
-    async fn find_by_token(&self, conn: &mut PgConnection, token: &str) -> Result<Option<PasswordResetToken>>;
+    /// Create a new password reset token for a user, stored as `token_hash`
+    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid, token_hash: &str) -> Result<PasswordResetToken>;
+
+    /// Create a password reset token valid for `valid_for` rather than the
+    /// usual half hour, such as one mailed to a newly imported user
+    async fn create_with_lifetime(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        token_hash: &str,
+        valid_for: Duration,
+    ) -> Result<PasswordResetToken>;
+
+    /// Find a password reset token by the hash of its token
+    async fn find_by_hash(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<PasswordResetToken>>;
+
+    /// Use up the token with `token_hash` if it is neither used nor
+    /// expired, `None` otherwise
+    async fn consume(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<PasswordResetToken>>;
+
+    /// Revoke all unused password reset tokens for a user
+    async fn revoke_all_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<()>;
 }
 
-/// TODO: Implement
-/// Email verification token repository operations
-#[allow(unused)]
+/// Concrete implementation of the password reset token repository
+pub struct PasswordResetTokenRepositoryImpl;
+
 #[async_trait]
-pub trait EmailVerificationTokenRepository: Repository<EmailVerificationToken> {
-    /// Create a new email verification token for a user
-    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<EmailVerificationToken>;
-    
-    /// Find an email verification token by its token string
-    async fn find_by_token(&self, conn: &mut PgConnection, token: &str) -> Result<Option<EmailVerificationToken>>;
-} 
\ No newline at end of file
+impl Repository<PasswordResetToken> for PasswordResetTokenRepositoryImpl {
+    async fn find_by_id(&self, conn: &mut PgConnection, id: Uuid) -> Result<PasswordResetToken> {
+        password_reset_tokens::table
+            .filter(password_reset_tokens::id.eq(id))
+            .filter(password_reset_tokens::deleted_at.is_null())
+            .select(PasswordResetToken::as_select())
+            .first(conn)
+            .map_err(|e| {
+                error!("Failed to find password reset token: {}", e);
+                ApiError::not_found(format!("Password reset token with id {} not found", id))
+            })
+    }
+
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<PasswordResetToken>> {
+        password_reset_tokens::table
+            .filter(password_reset_tokens::id.eq_any(ids))
+            .filter(password_reset_tokens::deleted_at.is_null())
+            .select(PasswordResetToken::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to batch load password reset tokens: {}", e);
+                ApiError::database_error("Failed to find password reset tokens", None)
+            })
+    }
+
+    async fn create(&self, conn: &mut PgConnection, model: &PasswordResetToken) -> Result<PasswordResetToken> {
+        diesel::insert_into(password_reset_tokens::table)
+            .values(model)
+            .returning(PasswordResetToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to create password reset token: {}", e);
+                ApiError::database_error("Failed to create password reset token", None)
+            })
+    }
+
+    async fn update(&self, conn: &mut PgConnection, id: Uuid, model: &PasswordResetToken) -> Result<PasswordResetToken> {
+        diesel::update(password_reset_tokens::table)
+            .filter(password_reset_tokens::id.eq(id))
+            .set(model)
+            .returning(PasswordResetToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to update password reset token: {}", e);
+                ApiError::database_error("Failed to update password reset token", None)
+            })
+    }
+
+    async fn soft_delete(&self, conn: &mut PgConnection, id: Uuid) -> Result<PasswordResetToken> {
+        let now = Utc::now();
+        diesel::update(password_reset_tokens::table)
+            .filter(password_reset_tokens::id.eq(id))
+            .set(password_reset_tokens::deleted_at.eq(Some(now)))
+            .returning(PasswordResetToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to soft delete password reset token: {}", e);
+                ApiError::database_error("Failed to soft delete password reset token", None)
+            })
+    }
+
+    async fn list(&self, conn: &mut PgConnection, pagination: &PaginationParams) -> Result<Vec<PasswordResetToken>> {
+        password_reset_tokens::table
+            .filter(password_reset_tokens::deleted_at.is_null())
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .select(PasswordResetToken::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to list password reset tokens: {}", e);
+                ApiError::database_error("Failed to list password reset tokens", None)
+            })
+    }
+}
+
+#[async_trait]
+impl PasswordResetTokenRepository for PasswordResetTokenRepositoryImpl {
+    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid, token_hash: &str) -> Result<PasswordResetToken> {
+        self.create_with_lifetime(conn, user_id, token_hash, Duration::minutes(PASSWORD_RESET_TOKEN_MINUTES)).await
+    }
+
+    async fn create_with_lifetime(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        token_hash: &str,
+        valid_for: Duration,
+    ) -> Result<PasswordResetToken> {
+        let now = Utc::now();
+
+        let reset_token = PasswordResetToken {
+            id: Uuid::new_v4(),
+            token_hash: token_hash.to_string(),
+            user_id,
+            expires_at: now + valid_for,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+
+        self.create(conn, &reset_token).await
+    }
+
+    async fn find_by_hash(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<PasswordResetToken>> {
+        password_reset_tokens::table
+            .filter(password_reset_tokens::token_hash.eq(token_hash))
+            .filter(password_reset_tokens::deleted_at.is_null())
+            .select(PasswordResetToken::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to find password reset token: {}", e);
+                ApiError::database_error("Failed to find password reset token by hash", None)
+            })
+    }
+
+    async fn consume(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<PasswordResetToken>> {
+        let now = Utc::now();
+        // A single conditional update, so a token can't be used twice by
+        // concurrent requests
+        diesel::update(password_reset_tokens::table)
+            .filter(password_reset_tokens::token_hash.eq(token_hash))
+            .filter(password_reset_tokens::deleted_at.is_null())
+            .filter(password_reset_tokens::expires_at.gt(now))
+            .set((password_reset_tokens::deleted_at.eq(Some(now)), password_reset_tokens::updated_at.eq(now)))
+            .returning(PasswordResetToken::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to consume password reset token: {}", e);
+                ApiError::database_error("Failed to consume password reset token", None)
+            })
+    }
+
+    async fn revoke_all_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<()> {
+        diesel::update(password_reset_tokens::table)
+            .filter(password_reset_tokens::user_id.eq(user_id))
+            .filter(password_reset_tokens::deleted_at.is_null())
+            .set(password_reset_tokens::deleted_at.eq(Some(Utc::now())))
+            .execute(conn)
+            .map_err(|e| {
+                error!("Failed to revoke password reset tokens: {}", e);
+                ApiError::database_error("Failed to revoke password reset tokens", None)
+            })?;
+        Ok(())
+    }
+}
+
+/// Hours an email verification token stays valid
+pub const EMAIL_VERIFICATION_TOKEN_HOURS: i64 = 24;
+
+/// Email verification token repository operations
+#[async_trait]
+pub trait EmailVerificationTokenRepository: Repository<EmailVerificationToken> {
+    /// Create a new email verification token for a user, stored as
+    /// `token_hash`
+    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid, token_hash: &str) -> Result<EmailVerificationToken>;
+
+    /// Find an email verification token by the hash of its token
+    async fn find_by_hash(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<EmailVerificationToken>>;
+
+    /// Use up the token with `token_hash` if it is neither used nor
+    /// expired, `None` otherwise
+    async fn consume(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<EmailVerificationToken>>;
+
+    /// Revoke all unused email verification tokens for a user
+    async fn revoke_all_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<()>;
+}
+
+/// Concrete implementation of the email verification token repository
+pub struct EmailVerificationTokenRepositoryImpl;
+
+#[async_trait]
+impl Repository<EmailVerificationToken> for EmailVerificationTokenRepositoryImpl {
+    async fn find_by_id(&self, conn: &mut PgConnection, id: Uuid) -> Result<EmailVerificationToken> {
+        email_verification_tokens::table
+            .filter(email_verification_tokens::id.eq(id))
+            .filter(email_verification_tokens::deleted_at.is_null())
+            .select(EmailVerificationToken::as_select())
+            .first(conn)
+            .map_err(|e| {
+                error!("Failed to find email verification token: {}", e);
+                ApiError::not_found(format!("Email verification token with id {} not found", id))
+            })
+    }
+
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<EmailVerificationToken>> {
+        email_verification_tokens::table
+            .filter(email_verification_tokens::id.eq_any(ids))
+            .filter(email_verification_tokens::deleted_at.is_null())
+            .select(EmailVerificationToken::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to batch load email verification tokens: {}", e);
+                ApiError::database_error("Failed to find email verification tokens", None)
+            })
+    }
+
+    async fn create(&self, conn: &mut PgConnection, model: &EmailVerificationToken) -> Result<EmailVerificationToken> {
+        diesel::insert_into(email_verification_tokens::table)
+            .values(model)
+            .returning(EmailVerificationToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to create email verification token: {}", e);
+                ApiError::database_error("Failed to create email verification token", None)
+            })
+    }
+
+    async fn update(&self, conn: &mut PgConnection, id: Uuid, model: &EmailVerificationToken) -> Result<EmailVerificationToken> {
+        diesel::update(email_verification_tokens::table)
+            .filter(email_verification_tokens::id.eq(id))
+            .set(model)
+            .returning(EmailVerificationToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to update email verification token: {}", e);
+                ApiError::database_error("Failed to update email verification token", None)
+            })
+    }
+
+    async fn soft_delete(&self, conn: &mut PgConnection, id: Uuid) -> Result<EmailVerificationToken> {
+        let now = Utc::now();
+        diesel::update(email_verification_tokens::table)
+            .filter(email_verification_tokens::id.eq(id))
+            .set(email_verification_tokens::deleted_at.eq(Some(now)))
+            .returning(EmailVerificationToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to soft delete email verification token: {}", e);
+                ApiError::database_error("Failed to soft delete email verification token", None)
+            })
+    }
+
+    async fn list(&self, conn: &mut PgConnection, pagination: &PaginationParams) -> Result<Vec<EmailVerificationToken>> {
+        email_verification_tokens::table
+            .filter(email_verification_tokens::deleted_at.is_null())
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .select(EmailVerificationToken::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to list email verification tokens: {}", e);
+                ApiError::database_error("Failed to list email verification tokens", None)
+            })
+    }
+}
+
+#[async_trait]
+impl EmailVerificationTokenRepository for EmailVerificationTokenRepositoryImpl {
+    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid, token_hash: &str) -> Result<EmailVerificationToken> {
+        let now = Utc::now();
+
+        let verification_token = EmailVerificationToken {
+            id: Uuid::new_v4(),
+            token_hash: token_hash.to_string(),
+            user_id,
+            expires_at: now + Duration::hours(EMAIL_VERIFICATION_TOKEN_HOURS),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+
+        self.create(conn, &verification_token).await
+    }
+
+    async fn find_by_hash(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<EmailVerificationToken>> {
+        email_verification_tokens::table
+            .filter(email_verification_tokens::token_hash.eq(token_hash))
+            .filter(email_verification_tokens::deleted_at.is_null())
+            .select(EmailVerificationToken::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to find email verification token: {}", e);
+                ApiError::database_error("Failed to find email verification token by hash", None)
+            })
+    }
+
+    async fn consume(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<EmailVerificationToken>> {
+        let now = Utc::now();
+        // A single conditional update, so a token can't be used twice by
+        // concurrent requests
+        diesel::update(email_verification_tokens::table)
+            .filter(email_verification_tokens::token_hash.eq(token_hash))
+            .filter(email_verification_tokens::deleted_at.is_null())
+            .filter(email_verification_tokens::expires_at.gt(now))
+            .set((email_verification_tokens::deleted_at.eq(Some(now)), email_verification_tokens::updated_at.eq(now)))
+            .returning(EmailVerificationToken::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to consume email verification token: {}", e);
+                ApiError::database_error("Failed to consume email verification token", None)
+            })
+    }
+
+    async fn revoke_all_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<()> {
+        diesel::update(email_verification_tokens::table)
+            .filter(email_verification_tokens::user_id.eq(user_id))
+            .filter(email_verification_tokens::deleted_at.is_null())
+            .set(email_verification_tokens::deleted_at.eq(Some(Utc::now())))
+            .execute(conn)
+            .map_err(|e| {
+                error!("Failed to revoke email verification tokens: {}", e);
+                ApiError::database_error("Failed to revoke email verification tokens", None)
+            })?;
+        Ok(())
+    }
+}
+
+/// Hours an email change link stays valid
+pub const EMAIL_CHANGE_TOKEN_HOURS: i64 = 24;
+
+/// Email change token repository operations
+#[async_trait]
+pub trait EmailChangeTokenRepository: Repository<EmailChangeToken> {
+    /// Create a token switching a user to `new_email`, stored as
+    /// `token_hash`
+    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid, token_hash: &str, new_email: &str) -> Result<EmailChangeToken>;
+
+    /// Use up the token with `token_hash` if it is neither used nor
+    /// expired, `None` otherwise
+    async fn consume(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<EmailChangeToken>>;
+
+    /// Revoke all pending email changes for a user
+    async fn revoke_all_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<()>;
+}
+
+/// Concrete implementation of the email change token repository
+pub struct EmailChangeTokenRepositoryImpl;
+
+#[async_trait]
+impl Repository<EmailChangeToken> for EmailChangeTokenRepositoryImpl {
+    async fn find_by_id(&self, conn: &mut PgConnection, id: Uuid) -> Result<EmailChangeToken> {
+        email_change_tokens::table
+            .filter(email_change_tokens::id.eq(id))
+            .filter(email_change_tokens::deleted_at.is_null())
+            .select(EmailChangeToken::as_select())
+            .first(conn)
+            .map_err(|e| {
+                error!("Failed to find email change token: {}", e);
+                ApiError::not_found(format!("Email change token with id {} not found", id))
+            })
+    }
+
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<EmailChangeToken>> {
+        email_change_tokens::table
+            .filter(email_change_tokens::id.eq_any(ids))
+            .filter(email_change_tokens::deleted_at.is_null())
+            .select(EmailChangeToken::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to batch load email change tokens: {}", e);
+                ApiError::database_error("Failed to find email change tokens", None)
+            })
+    }
+
+    async fn create(&self, conn: &mut PgConnection, model: &EmailChangeToken) -> Result<EmailChangeToken> {
+        diesel::insert_into(email_change_tokens::table)
+            .values(model)
+            .returning(EmailChangeToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to create email change token: {}", e);
+                ApiError::database_error("Failed to create email change token", None)
+            })
+    }
+
+    async fn update(&self, conn: &mut PgConnection, id: Uuid, model: &EmailChangeToken) -> Result<EmailChangeToken> {
+        diesel::update(email_change_tokens::table)
+            .filter(email_change_tokens::id.eq(id))
+            .set(model)
+            .returning(EmailChangeToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to update email change token: {}", e);
+                ApiError::database_error("Failed to update email change token", None)
+            })
+    }
+
+    async fn soft_delete(&self, conn: &mut PgConnection, id: Uuid) -> Result<EmailChangeToken> {
+        let now = Utc::now();
+        diesel::update(email_change_tokens::table)
+            .filter(email_change_tokens::id.eq(id))
+            .set(email_change_tokens::deleted_at.eq(Some(now)))
+            .returning(EmailChangeToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to soft delete email change token: {}", e);
+                ApiError::database_error("Failed to soft delete email change token", None)
+            })
+    }
+
+    async fn list(&self, conn: &mut PgConnection, pagination: &PaginationParams) -> Result<Vec<EmailChangeToken>> {
+        email_change_tokens::table
+            .filter(email_change_tokens::deleted_at.is_null())
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .select(EmailChangeToken::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to list email change tokens: {}", e);
+                ApiError::database_error("Failed to list email change tokens", None)
+            })
+    }
+}
+
+#[async_trait]
+impl EmailChangeTokenRepository for EmailChangeTokenRepositoryImpl {
+    async fn create_for_user(&self, conn: &mut PgConnection, user_id: Uuid, token_hash: &str, new_email: &str) -> Result<EmailChangeToken> {
+        let now = Utc::now();
+
+        let email_change_token = EmailChangeToken {
+            id: Uuid::new_v4(),
+            token_hash: token_hash.to_string(),
+            user_id,
+            new_email: new_email.to_string(),
+            expires_at: now + Duration::hours(EMAIL_CHANGE_TOKEN_HOURS),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+
+        self.create(conn, &email_change_token).await
+    }
+
+    async fn consume(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<EmailChangeToken>> {
+        let now = Utc::now();
+        // A single conditional update, so a token can't be used twice by
+        // concurrent requests
+        diesel::update(email_change_tokens::table)
+            .filter(email_change_tokens::token_hash.eq(token_hash))
+            .filter(email_change_tokens::deleted_at.is_null())
+            .filter(email_change_tokens::expires_at.gt(now))
+            .set((email_change_tokens::deleted_at.eq(Some(now)), email_change_tokens::updated_at.eq(now)))
+            .returning(EmailChangeToken::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to consume email change token: {}", e);
+                ApiError::database_error("Failed to consume email change token", None)
+            })
+    }
+
+    async fn revoke_all_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<()> {
+        diesel::update(email_change_tokens::table)
+            .filter(email_change_tokens::user_id.eq(user_id))
+            .filter(email_change_tokens::deleted_at.is_null())
+            .set(email_change_tokens::deleted_at.eq(Some(Utc::now())))
+            .execute(conn)
+            .map_err(|e| {
+                error!("Failed to revoke email change tokens: {}", e);
+                ApiError::database_error("Failed to revoke email change tokens", None)
+            })?;
+        Ok(())
+    }
+}
+
+/// Magic link token repository operations
+#[async_trait]
+pub trait MagicLinkTokenRepository: Repository<MagicLinkToken> {
+    /// Create a token for a user valid for `valid_for`, bound to `device_id`
+    /// if given, stored as `token_hash`
+    async fn create_for_user(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        token_hash: &str,
+        device_id: Option<&str>,
+        valid_for: Duration,
+    ) -> Result<MagicLinkToken>;
+
+    /// Use up the token with `token_hash` if it is neither used nor expired
+    /// and is either unbound or bound to `device_id`, `None` otherwise
+    async fn consume(&self, conn: &mut PgConnection, token_hash: &str, device_id: Option<&str>) -> Result<Option<MagicLinkToken>>;
+
+    /// Revoke all unused magic link tokens for a user
+    async fn revoke_all_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<()>;
+}
+
+/// Concrete implementation of the magic link token repository
+pub struct MagicLinkTokenRepositoryImpl;
+
+#[async_trait]
+impl Repository<MagicLinkToken> for MagicLinkTokenRepositoryImpl {
+    async fn find_by_id(&self, conn: &mut PgConnection, id: Uuid) -> Result<MagicLinkToken> {
+        magic_link_tokens::table
+            .filter(magic_link_tokens::id.eq(id))
+            .filter(magic_link_tokens::deleted_at.is_null())
+            .select(MagicLinkToken::as_select())
+            .first(conn)
+            .map_err(|e| {
+                error!("Failed to find magic link token: {}", e);
+                ApiError::not_found(format!("Magic link token with id {} not found", id))
+            })
+    }
+
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<MagicLinkToken>> {
+        magic_link_tokens::table
+            .filter(magic_link_tokens::id.eq_any(ids))
+            .filter(magic_link_tokens::deleted_at.is_null())
+            .select(MagicLinkToken::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to batch load magic link tokens: {}", e);
+                ApiError::database_error("Failed to find magic link tokens", None)
+            })
+    }
+
+    async fn create(&self, conn: &mut PgConnection, model: &MagicLinkToken) -> Result<MagicLinkToken> {
+        diesel::insert_into(magic_link_tokens::table)
+            .values(model)
+            .returning(MagicLinkToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to create magic link token: {}", e);
+                ApiError::database_error("Failed to create magic link token", None)
+            })
+    }
+
+    async fn update(&self, conn: &mut PgConnection, id: Uuid, model: &MagicLinkToken) -> Result<MagicLinkToken> {
+        diesel::update(magic_link_tokens::table)
+            .filter(magic_link_tokens::id.eq(id))
+            .set(model)
+            .returning(MagicLinkToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to update magic link token: {}", e);
+                ApiError::database_error("Failed to update magic link token", None)
+            })
+    }
+
+    async fn soft_delete(&self, conn: &mut PgConnection, id: Uuid) -> Result<MagicLinkToken> {
+        let now = Utc::now();
+        diesel::update(magic_link_tokens::table)
+            .filter(magic_link_tokens::id.eq(id))
+            .set(magic_link_tokens::deleted_at.eq(Some(now)))
+            .returning(MagicLinkToken::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to soft delete magic link token: {}", e);
+                ApiError::database_error("Failed to soft delete magic link token", None)
+            })
+    }
+
+    async fn list(&self, conn: &mut PgConnection, pagination: &PaginationParams) -> Result<Vec<MagicLinkToken>> {
+        magic_link_tokens::table
+            .filter(magic_link_tokens::deleted_at.is_null())
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .select(MagicLinkToken::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to list magic link tokens: {}", e);
+                ApiError::database_error("Failed to list magic link tokens", None)
+            })
+    }
+}
+
+#[async_trait]
+impl MagicLinkTokenRepository for MagicLinkTokenRepositoryImpl {
+    async fn create_for_user(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        token_hash: &str,
+        device_id: Option<&str>,
+        valid_for: Duration,
+    ) -> Result<MagicLinkToken> {
+        let now = Utc::now();
+
+        let magic_link_token = MagicLinkToken {
+            id: Uuid::new_v4(),
+            token_hash: token_hash.to_string(),
+            user_id,
+            device_id: device_id.map(str::to_string),
+            expires_at: now + valid_for,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+
+        self.create(conn, &magic_link_token).await
+    }
+
+    async fn consume(&self, conn: &mut PgConnection, token_hash: &str, device_id: Option<&str>) -> Result<Option<MagicLinkToken>> {
+        let now = Utc::now();
+        let mut query = diesel::update(magic_link_tokens::table)
+            .filter(magic_link_tokens::token_hash.eq(token_hash))
+            .filter(magic_link_tokens::deleted_at.is_null())
+            .filter(magic_link_tokens::expires_at.gt(now))
+            .into_boxed();
+        query = match device_id {
+            Some(device_id) => query.filter(
+                magic_link_tokens::device_id.is_null().or(magic_link_tokens::device_id.eq(device_id)),
+            ),
+            None => query.filter(magic_link_tokens::device_id.is_null()),
+        };
+        // A single conditional update, so a token can't be used twice by
+        // concurrent requests
+        query
+            .set((magic_link_tokens::deleted_at.eq(Some(now)), magic_link_tokens::updated_at.eq(now)))
+            .returning(MagicLinkToken::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to consume magic link token: {}", e);
+                ApiError::database_error("Failed to consume magic link token", None)
+            })
+    }
+
+    async fn revoke_all_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<()> {
+        diesel::update(magic_link_tokens::table)
+            .filter(magic_link_tokens::user_id.eq(user_id))
+            .filter(magic_link_tokens::deleted_at.is_null())
+            .set(magic_link_tokens::deleted_at.eq(Some(Utc::now())))
+            .execute(conn)
+            .map_err(|e| {
+                error!("Failed to revoke magic link tokens: {}", e);
+                ApiError::database_error("Failed to revoke magic link tokens", None)
+            })?;
+        Ok(())
+    }
+}
+
+/// Device repository operations
+#[async_trait]
+pub trait DeviceRepository: Repository<Device> {
+    /// Record a login from `device_id`, creating the device on its first
+    /// login and renaming it when `name` is given
+    async fn register(&self, conn: &mut PgConnection, user_id: Uuid, device_id: &str, name: Option<&str>) -> Result<Device>;
+
+    /// Record that `device_id` is still in use
+    async fn touch(&self, conn: &mut PgConnection, user_id: Uuid, device_id: &str) -> Result<()>;
+
+    /// Record a heartbeat from `device_id`, registering the device on its
+    /// first one
+    async fn heartbeat(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        device_id: &str,
+        name: Option<&str>,
+        app_version: Option<&str>,
+        battery_level: Option<i16>,
+    ) -> Result<Device>;
+
+    /// Devices of the organization's active members with a heartbeat since
+    /// `since`, with their user, most recent heartbeat first
+    async fn online(&self, conn: &mut PgConnection, organization: Uuid, since: DateTime<Utc>) -> Result<Vec<(Device, User)>>;
+
+    /// The user's devices, most recently seen first
+    async fn list_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<Vec<Device>>;
+
+    /// The user's device with `id`, `None` if it is someone else's
+    async fn find_for_user(&self, conn: &mut PgConnection, user_id: Uuid, id: Uuid) -> Result<Option<Device>>;
+}
+
+/// Concrete implementation of the device repository
+pub struct DeviceRepositoryImpl;
+
+#[async_trait]
+impl Repository<Device> for DeviceRepositoryImpl {
+    async fn find_by_id(&self, conn: &mut PgConnection, id: Uuid) -> Result<Device> {
+        devices::table
+            .filter(devices::id.eq(id))
+            .filter(devices::deleted_at.is_null())
+            .select(Device::as_select())
+            .first(conn)
+            .map_err(|e| {
+                error!("Failed to find device: {}", e);
+                ApiError::not_found(format!("Device with id {} not found", id))
+            })
+    }
+
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<Device>> {
+        devices::table
+            .filter(devices::id.eq_any(ids))
+            .filter(devices::deleted_at.is_null())
+            .select(Device::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to batch load devices: {}", e);
+                ApiError::database_error("Failed to find devices", None)
+            })
+    }
+
+    async fn create(&self, conn: &mut PgConnection, model: &Device) -> Result<Device> {
+        diesel::insert_into(devices::table)
+            .values(model)
+            .returning(Device::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to create device: {}", e);
+                ApiError::database_error("Failed to create device", None)
+            })
+    }
+
+    async fn update(&self, conn: &mut PgConnection, id: Uuid, model: &Device) -> Result<Device> {
+        diesel::update(devices::table)
+            .filter(devices::id.eq(id))
+            .set(model)
+            .returning(Device::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to update device: {}", e);
+                ApiError::database_error("Failed to update device", None)
+            })
+    }
+
+    async fn soft_delete(&self, conn: &mut PgConnection, id: Uuid) -> Result<Device> {
+        let now = Utc::now();
+        diesel::update(devices::table)
+            .filter(devices::id.eq(id))
+            .set(devices::deleted_at.eq(Some(now)))
+            .returning(Device::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to soft delete device: {}", e);
+                ApiError::database_error("Failed to soft delete device", None)
+            })
+    }
+
+    async fn list(&self, conn: &mut PgConnection, pagination: &PaginationParams) -> Result<Vec<Device>> {
+        devices::table
+            .filter(devices::deleted_at.is_null())
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .select(Device::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to list devices: {}", e);
+                ApiError::database_error("Failed to list devices", None)
+            })
+    }
+}
+
+#[async_trait]
+impl DeviceRepository for DeviceRepositoryImpl {
+    async fn register(&self, conn: &mut PgConnection, user_id: Uuid, device_id: &str, name: Option<&str>) -> Result<Device> {
+        let now = Utc::now();
+        let existing = devices::table
+            .filter(devices::user_id.eq(user_id))
+            .filter(devices::device_id.eq(device_id))
+            .filter(devices::deleted_at.is_null())
+            .select(Device::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to find device: {}", e);
+                ApiError::database_error("Failed to find device", None)
+            })?;
+
+        match existing {
+            Some(mut device) => {
+                if let Some(name) = name {
+                    device.name = Some(name.to_string());
+                }
+                device.last_seen_at = now;
+                device.updated_at = now;
+                self.update(conn, device.id, &device).await
+            }
+            None => {
+                let device = Device {
+                    id: Uuid::new_v4(),
+                    user_id,
+                    device_id: device_id.to_string(),
+                    name: name.map(str::to_string),
+                    last_seen_at: now,
+                    created_at: now,
+                    updated_at: now,
+                    deleted_at: None,
+                    app_version: None,
+                    battery_level: None,
+                    last_heartbeat_at: None,
+                };
+                self.create(conn, &device).await
+            }
+        }
+    }
+
+    async fn touch(&self, conn: &mut PgConnection, user_id: Uuid, device_id: &str) -> Result<()> {
+        diesel::update(devices::table)
+            .filter(devices::user_id.eq(user_id))
+            .filter(devices::device_id.eq(device_id))
+            .filter(devices::deleted_at.is_null())
+            .set(devices::last_seen_at.eq(Utc::now()))
+            .execute(conn)
+            .map_err(|e| {
+                error!("Failed to touch device: {}", e);
+                ApiError::database_error("Failed to update device", None)
+            })?;
+        Ok(())
+    }
+
+    async fn heartbeat(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        device_id: &str,
+        name: Option<&str>,
+        app_version: Option<&str>,
+        battery_level: Option<i16>,
+    ) -> Result<Device> {
+        let device = self.register(conn, user_id, device_id, name).await?;
+        diesel::update(devices::table.find(device.id))
+            .set((
+                devices::app_version.eq(app_version),
+                devices::battery_level.eq(battery_level),
+                devices::last_heartbeat_at.eq(device.last_seen_at),
+            ))
+            .returning(Device::as_select())
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to record heartbeat: {}", e);
+                ApiError::database_error("Failed to record heartbeat", None)
+            })
+    }
+
+    async fn online(&self, conn: &mut PgConnection, organization: Uuid, since: DateTime<Utc>) -> Result<Vec<(Device, User)>> {
+        devices::table
+            .inner_join(users::table)
+            .filter(users::org_id.eq(organization))
+            .filter(users::is_active.eq(true))
+            .filter(users::deleted_at.is_null())
+            .filter(devices::deleted_at.is_null())
+            .filter(devices::last_heartbeat_at.ge(since))
+            .order_by((devices::last_heartbeat_at.desc(), devices::id.asc()))
+            .select((Device::as_select(), User::as_select()))
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to list online devices: {}", e);
+                ApiError::database_error("Failed to list online devices", None)
+            })
+    }
+
+    async fn list_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<Vec<Device>> {
+        devices::table
+            .filter(devices::user_id.eq(user_id))
+            .filter(devices::deleted_at.is_null())
+            .order(devices::last_seen_at.desc())
+            .select(Device::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to list devices: {}", e);
+                ApiError::database_error("Failed to list devices", None)
+            })
+    }
+
+    async fn find_for_user(&self, conn: &mut PgConnection, user_id: Uuid, id: Uuid) -> Result<Option<Device>> {
+        devices::table
+            .filter(devices::id.eq(id))
+            .filter(devices::user_id.eq(user_id))
+            .filter(devices::deleted_at.is_null())
+            .select(Device::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to find device: {}", e);
+                ApiError::database_error("Failed to find device", None)
+            })
+    }
+}
+
+/// Passkey repository operations
+#[async_trait]
+pub trait PasskeyRepository: Send + Sync + 'static {
+    /// Store a newly registered passkey
+    async fn create(&self, conn: &mut PgConnection, passkey: &Passkey) -> Result<Passkey>;
+
+    /// The passkey with the base64url credential ID `credential_id`
+    async fn find_by_credential_id(&self, conn: &mut PgConnection, credential_id: &str) -> Result<Option<Passkey>>;
+
+    /// The user's passkeys, oldest first
+    async fn list_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<Vec<Passkey>>;
+
+    /// Record a login with the passkey and the signature counter it sent
+    async fn record_use(&self, conn: &mut PgConnection, id: Uuid, sign_count: i64) -> Result<()>;
+
+    /// Remove one of the user's passkeys, `false` if the user has no such
+    /// passkey
+    async fn delete_for_user(&self, conn: &mut PgConnection, user_id: Uuid, id: Uuid) -> Result<bool>;
+}
+
+/// Concrete implementation of the passkey repository
+pub struct PasskeyRepositoryImpl;
+
+#[async_trait]
+impl PasskeyRepository for PasskeyRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, passkey: &Passkey) -> Result<Passkey> {
+        diesel::insert_into(passkeys::table)
+            .values(passkey)
+            .returning(Passkey::as_select())
+            .get_result(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                    ApiError::new(ErrorCode::Conflict, "This passkey is already registered", ErrorContext::new())
+                }
+                e => {
+                    error!("Failed to create passkey: {}", e);
+                    ApiError::database_error("Failed to create passkey", None)
+                }
+            })
+    }
+
+    async fn find_by_credential_id(&self, conn: &mut PgConnection, credential_id: &str) -> Result<Option<Passkey>> {
+        passkeys::table
+            .filter(passkeys::credential_id.eq(credential_id))
+            .select(Passkey::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| {
+                error!("Failed to find passkey: {}", e);
+                ApiError::database_error("Failed to find passkey", None)
+            })
+    }
+
+    async fn list_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<Vec<Passkey>> {
+        passkeys::table
+            .filter(passkeys::user_id.eq(user_id))
+            .order((passkeys::created_at.asc(), passkeys::id.asc()))
+            .select(Passkey::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to list passkeys: {}", e);
+                ApiError::database_error("Failed to list passkeys", None)
+            })
+    }
+
+    async fn record_use(&self, conn: &mut PgConnection, id: Uuid, sign_count: i64) -> Result<()> {
+        let now = Utc::now();
+        diesel::update(passkeys::table.find(id))
+            .set((
+                passkeys::sign_count.eq(sign_count),
+                passkeys::last_used_at.eq(now),
+                passkeys::updated_at.eq(now),
+            ))
+            .execute(conn)
+            .map_err(|e| {
+                error!("Failed to record passkey use: {}", e);
+                ApiError::database_error("Failed to update passkey", None)
+            })?;
+        Ok(())
+    }
+
+    async fn delete_for_user(&self, conn: &mut PgConnection, user_id: Uuid, id: Uuid) -> Result<bool> {
+        diesel::delete(passkeys::table)
+            .filter(passkeys::id.eq(id))
+            .filter(passkeys::user_id.eq(user_id))
+            .execute(conn)
+            .map(|deleted| deleted > 0)
+            .map_err(|e| {
+                error!("Failed to delete passkey: {}", e);
+                ApiError::database_error("Failed to delete passkey", None)
+            })
+    }
+}
+
+/// Where a login comes from
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoginOrigin<'a> {
+    pub country: Option<&'a str>,
+    pub asn: Option<&'a str>,
+    /// Device the client named, which identifies it better than its user
+    /// agent
+    pub device_id: Option<&'a str>,
+    pub user_agent: Option<&'a str>,
+}
+
+/// Whether a user logged in before, and from the same place and device
+///
+/// A location or device that isn't known counts as seen, since it can't be
+/// told apart from the earlier ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoginHistory {
+    pub any: bool,
+    pub location: bool,
+    pub device: bool,
+}
+
+/// Authentication event repository operations
+///
+/// Events are only ever added; they go when their user is purged.
+#[async_trait]
+pub trait AuthEventRepository: Send + Sync + 'static {
+    /// Store an event
+    async fn record(&self, conn: &mut PgConnection, event: &AuthEvent) -> Result<AuthEvent>;
+
+    /// A page of the user's events, newest first
+    async fn list_for_user(&self, conn: &mut PgConnection, user_id: Uuid, pagination: &PaginationParams) -> Result<Vec<AuthEvent>>;
+
+    /// Number of events of the user
+    async fn count_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<i64>;
+
+    /// Number of `event` events with `email`, or from `ip_address`, since
+    /// `since`
+    async fn count_recent(
+        &self,
+        conn: &mut PgConnection,
+        event: &str,
+        email: &str,
+        ip_address: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> Result<i64>;
+
+    /// What the user's earlier successful logins have in common with one
+    /// from `origin`
+    async fn login_history(&self, conn: &mut PgConnection, user_id: Uuid, origin: &LoginOrigin<'_>) -> Result<LoginHistory>;
+}
+
+/// Concrete implementation of the authentication event repository
+pub struct AuthEventRepositoryImpl;
+
+#[async_trait]
+impl AuthEventRepository for AuthEventRepositoryImpl {
+    async fn record(&self, conn: &mut PgConnection, event: &AuthEvent) -> Result<AuthEvent> {
+        // In a savepoint, so a failure doesn't abort the caller's transaction
+        conn.transaction(|conn| {
+            diesel::insert_into(auth_events::table)
+                .values(event)
+                .returning(AuthEvent::as_select())
+                .get_result(conn)
+        })
+        .map_err(|e| {
+            error!("Failed to record auth event: {}", e);
+            ApiError::database_error("Failed to record auth event", None)
+        })
+    }
+
+    async fn list_for_user(&self, conn: &mut PgConnection, user_id: Uuid, pagination: &PaginationParams) -> Result<Vec<AuthEvent>> {
+        auth_events::table
+            .filter(auth_events::user_id.eq(user_id))
+            .order((auth_events::created_at.desc(), auth_events::id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .select(AuthEvent::as_select())
+            .load(conn)
+            .map_err(|e| {
+                error!("Failed to list auth events: {}", e);
+                ApiError::database_error("Failed to list auth events", None)
+            })
+    }
+
+    async fn count_for_user(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<i64> {
+        auth_events::table
+            .filter(auth_events::user_id.eq(user_id))
+            .count()
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to count auth events: {}", e);
+                ApiError::database_error("Failed to count auth events", None)
+            })
+    }
+
+    async fn count_recent(
+        &self,
+        conn: &mut PgConnection,
+        event: &str,
+        email: &str,
+        ip_address: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> Result<i64> {
+        let mut query = auth_events::table
+            .filter(auth_events::event.eq(event))
+            .filter(auth_events::created_at.gt(since))
+            .into_boxed();
+        query = match ip_address {
+            Some(ip_address) => query.filter(auth_events::email.eq(email).or(auth_events::ip_address.eq(ip_address))),
+            None => query.filter(auth_events::email.eq(email)),
+        };
+        query
+            .count()
+            .get_result(conn)
+            .map_err(|e| {
+                error!("Failed to count recent auth events: {}", e);
+                ApiError::database_error("Failed to count recent auth events", None)
+            })
+    }
+
+    async fn login_history(&self, conn: &mut PgConnection, user_id: Uuid, origin: &LoginOrigin<'_>) -> Result<LoginHistory> {
+        let logins = || {
+            auth_events::table
+                .filter(auth_events::user_id.eq(user_id))
+                .filter(auth_events::event.eq("login_succeeded"))
+                .into_boxed()
+        };
+        let seen = |conn: &mut PgConnection, query: auth_events::BoxedQuery<'_, diesel::pg::Pg>| {
+            diesel::select(diesel::dsl::exists(query))
+                .get_result::<bool>(conn)
+                .map_err(|e| {
+                    error!("Failed to look up earlier logins: {}", e);
+                    ApiError::database_error("Failed to look up earlier logins", None)
+                })
+        };
+
+        let any = seen(conn, logins())?;
+        if !any {
+            return Ok(LoginHistory { any, location: false, device: false });
+        }
+
+        let location = match (origin.country, origin.asn) {
+            (None, None) => true,
+            (country, asn) => {
+                let mut query = logins();
+                if let Some(country) = country {
+                    query = query.filter(auth_events::country.eq(country));
+                }
+                if let Some(asn) = asn {
+                    query = query.filter(auth_events::asn.eq(asn));
+                }
+                seen(conn, query)?
+            }
+        };
+        let device = match (origin.device_id, origin.user_agent) {
+            (Some(device_id), _) => seen(conn, logins().filter(auth_events::device_id.eq(device_id)))?,
+            (None, Some(user_agent)) => seen(conn, logins().filter(auth_events::user_agent.eq(user_agent)))?,
+            (None, None) => true,
+        };
+        Ok(LoginHistory { any, location, device })
+    }
+}
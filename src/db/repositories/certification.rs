@@ -0,0 +1,227 @@
+use crate::{
+    db::{
+        models::{auth::User, Certification, CertificationKind},
+        schema::{certifications, users},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of the certifications users hold
+///
+/// Every read and write made for a request is scoped to an organization;
+/// the expiry notifier reads across organizations.
+#[async_trait]
+pub trait CertificationRepository: Send + Sync + 'static {
+    /// Stores a new certification
+    async fn create(&self, conn: &mut PgConnection, certification: &Certification) -> Result<Certification>;
+
+    /// Finds one of an organization's certifications
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, certification_id: Uuid) -> Result<Certification>;
+
+    /// Lists an organization's certifications, of `user_id`, of the users
+    /// reporting directly to `reports_to` and of `kind` when given, soonest
+    /// expiry first
+    async fn list(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        user_id: Option<Uuid>,
+        reports_to: Option<Uuid>,
+        kind: Option<CertificationKind>,
+    ) -> Result<Vec<Certification>>;
+
+    /// Replaces the details of a certification
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, certification: &Certification) -> Result<Certification>;
+
+    /// Deletes a certification
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, certification_id: Uuid) -> Result<()>;
+
+    /// Certifications of the organization's active members, reporting
+    /// directly to `reports_to` when given, expiring on or before `until`,
+    /// expired ones included, with their holder, soonest expiry first
+    async fn expiring(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        reports_to: Option<Uuid>,
+        until: NaiveDate,
+    ) -> Result<Vec<(Certification, User)>>;
+
+    /// Certifications of active users, in any organization, expiring from
+    /// `from` through `until` whose supervisors haven't been notified yet,
+    /// with their holder
+    async fn due_for_notice(
+        &self,
+        conn: &mut PgConnection,
+        from: NaiveDate,
+        until: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<(Certification, User)>>;
+
+    /// Records that supervisors were notified of the coming expiry of
+    /// `certification_ids`
+    async fn mark_notified(&self, conn: &mut PgConnection, certification_ids: &[Uuid], at: DateTime<Utc>) -> Result<()>;
+}
+
+/// Concrete implementation of the certification repository
+pub struct CertificationRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn not_found(certification_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Certification with id {} not found", certification_id))
+}
+
+#[async_trait]
+impl CertificationRepository for CertificationRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, certification: &Certification) -> Result<Certification> {
+        diesel::insert_into(certifications::table)
+            .values(certification)
+            .get_result(conn)
+            .map_err(|e| database_error("create certification", e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, certification_id: Uuid) -> Result<Certification> {
+        certifications::table
+            .scoped(organization)
+            .filter(certifications::id.eq(certification_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find certification", e))?
+            .ok_or_else(|| not_found(certification_id))
+    }
+
+    async fn list(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        user_id: Option<Uuid>,
+        reports_to: Option<Uuid>,
+        kind: Option<CertificationKind>,
+    ) -> Result<Vec<Certification>> {
+        let mut query = certifications::table
+            .scoped(organization)
+            .into_boxed();
+        if let Some(user_id) = user_id {
+            query = query.filter(certifications::user_id.eq(user_id));
+        }
+        if let Some(supervisor_id) = reports_to {
+            query = query.filter(
+                certifications::user_id.eq_any(users::table.filter(users::supervisor_id.eq(supervisor_id)).select(users::id)),
+            );
+        }
+        if let Some(kind) = kind {
+            query = query.filter(certifications::kind.eq(kind.as_str()));
+        }
+        query
+            .order_by((
+                certifications::expires_on.asc().nulls_last(),
+                certifications::issued_on.desc(),
+                certifications::id.asc(),
+            ))
+            .load(conn)
+            .map_err(|e| database_error("list certifications", e))
+    }
+
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, certification: &Certification) -> Result<Certification> {
+        diesel::update(
+            certifications::table
+                .scoped(organization)
+                .filter(certifications::id.eq(certification.id)),
+        )
+        .set((
+            certifications::kind.eq(&certification.kind),
+            certifications::name.eq(&certification.name),
+            certifications::certificate_number.eq(&certification.certificate_number),
+            certifications::issuer.eq(&certification.issuer),
+            certifications::issued_on.eq(certification.issued_on),
+            certifications::expires_on.eq(certification.expires_on),
+            certifications::expiry_notified_at.eq(certification.expiry_notified_at),
+            certifications::updated_at.eq(Utc::now()),
+        ))
+        .get_result(conn)
+        .optional()
+        .map_err(|e| database_error("update certification", e))?
+        .ok_or_else(|| not_found(certification.id))
+    }
+
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, certification_id: Uuid) -> Result<()> {
+        let deleted = diesel::delete(
+            certifications::table
+                .scoped(organization)
+                .filter(certifications::id.eq(certification_id)),
+        )
+        .execute(conn)
+        .map_err(|e| database_error("delete certification", e))?;
+        if deleted == 0 {
+            return Err(not_found(certification_id));
+        }
+        Ok(())
+    }
+
+    async fn expiring(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        reports_to: Option<Uuid>,
+        until: NaiveDate,
+    ) -> Result<Vec<(Certification, User)>> {
+        let mut query = certifications::table
+            .scoped(organization)
+            .inner_join(users::table)
+            .filter(certifications::expires_on.le(until))
+            .filter(users::is_active.eq(true))
+            .filter(users::deleted_at.is_null())
+            .into_boxed();
+        if let Some(supervisor_id) = reports_to {
+            query = query.filter(users::supervisor_id.eq(supervisor_id));
+        }
+        query
+            .order_by((certifications::expires_on.asc(), users::last_name.asc(), certifications::id.asc()))
+            .select((Certification::as_select(), User::as_select()))
+            .load(conn)
+            .map_err(|e| database_error("list expiring certifications", e))
+    }
+
+    async fn due_for_notice(
+        &self,
+        conn: &mut PgConnection,
+        from: NaiveDate,
+        until: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<(Certification, User)>> {
+        certifications::table
+            .inner_join(users::table)
+            .filter(certifications::expires_on.between(from, until))
+            .filter(certifications::expiry_notified_at.is_null())
+            .filter(users::is_active.eq(true))
+            .filter(users::deleted_at.is_null())
+            .order_by((certifications::expires_on.asc(), certifications::id.asc()))
+            .limit(limit)
+            .select((Certification::as_select(), User::as_select()))
+            .load(conn)
+            .map_err(|e| database_error("find certifications due for notice", e))
+    }
+
+    async fn mark_notified(&self, conn: &mut PgConnection, certification_ids: &[Uuid], at: DateTime<Utc>) -> Result<()> {
+        diesel::update(certifications::table.filter(certifications::id.eq_any(certification_ids)))
+            .set(certifications::expiry_notified_at.eq(at))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("mark certifications notified", e))
+    }
+}
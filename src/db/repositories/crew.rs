@@ -0,0 +1,181 @@
+use crate::{
+    db::{
+        models::{auth::User, Crew, CrewMember},
+        schema::{crew_members, crews, users},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{
+    prelude::*,
+    result::{DatabaseErrorKind, Error as DieselError},
+};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of crews and their members
+///
+/// Every read and write is scoped to an organization; callers check that
+/// the users they add belong to it.
+#[async_trait]
+pub trait CrewRepository: Send + Sync + 'static {
+    /// Stores a new crew with its first members
+    async fn create(&self, conn: &mut PgConnection, crew: &Crew, members: &[CrewMember]) -> Result<Crew>;
+
+    /// Finds one of an organization's crews
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, crew_id: Uuid) -> Result<Crew>;
+
+    /// Lists an organization's crews by name, only those `user_id`
+    /// supervises or works in if given
+    async fn list(&self, conn: &mut PgConnection, organization: Uuid, user_id: Option<Uuid>) -> Result<Vec<Crew>>;
+
+    /// Replaces the details of a crew, keeping its members
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, crew: &Crew) -> Result<Crew>;
+
+    /// Deletes a crew with its memberships
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, crew_id: Uuid) -> Result<()>;
+
+    /// The memberships of crews with their users, leaving out deleted
+    /// users, by last and first name
+    async fn members(&self, conn: &mut PgConnection, organization: Uuid, crew_ids: &[Uuid]) -> Result<Vec<(CrewMember, User)>>;
+
+    /// Adds a member, returning false if they already were one
+    async fn add_member(&self, conn: &mut PgConnection, member: &CrewMember) -> Result<bool>;
+
+    /// Removes a member from a crew
+    async fn remove_member(&self, conn: &mut PgConnection, organization: Uuid, crew_id: Uuid, user_id: Uuid) -> Result<()>;
+}
+
+/// Concrete implementation of the crew repository
+pub struct CrewRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn not_found(crew_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Crew with id {} not found", crew_id))
+}
+
+/// Maps a crew write error, reporting a name taken in the organization as
+/// a conflict
+fn write_error(action: &str, crew: &Crew, e: DieselError) -> ApiError {
+    match e {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => ApiError::new(
+            ErrorCode::Conflict,
+            "A crew with this name already exists",
+            ErrorContext::new().with_details(serde_json::json!({ "name": crew.name })),
+        ),
+        DieselError::NotFound => not_found(crew.id),
+        e => database_error(action, e),
+    }
+}
+
+#[async_trait]
+impl CrewRepository for CrewRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, crew: &Crew, members: &[CrewMember]) -> Result<Crew> {
+        conn.transaction(|conn| {
+            let created = diesel::insert_into(crews::table)
+                .values(crew)
+                .get_result(conn)?;
+            diesel::insert_into(crew_members::table)
+                .values(members)
+                .execute(conn)?;
+            Ok(created)
+        })
+        .map_err(|e| write_error("create crew", crew, e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, crew_id: Uuid) -> Result<Crew> {
+        crews::table
+            .scoped(organization)
+            .filter(crews::id.eq(crew_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find crew", e))?
+            .ok_or_else(|| not_found(crew_id))
+    }
+
+    async fn list(&self, conn: &mut PgConnection, organization: Uuid, user_id: Option<Uuid>) -> Result<Vec<Crew>> {
+        let mut query = crews::table.scoped(organization).into_boxed();
+        if let Some(user_id) = user_id {
+            let member_of = crew_members::table
+                .scoped(organization)
+                .filter(crew_members::user_id.eq(user_id))
+                .select(crew_members::crew_id);
+            query = query.filter(crews::supervisor_id.eq(user_id).or(crews::id.eq_any(member_of)));
+        }
+        query
+            .order_by((crews::name.asc(), crews::id.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list crews", e))
+    }
+
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, crew: &Crew) -> Result<Crew> {
+        conn.transaction(|conn| {
+            diesel::update(crews::table.scoped(organization).filter(crews::id.eq(crew.id)))
+                .set((
+                    crews::name.eq(&crew.name),
+                    crews::supervisor_id.eq(crew.supervisor_id),
+                    crews::default_equipment.eq(&crew.default_equipment),
+                    crews::updated_at.eq(Utc::now()),
+                ))
+                .get_result(conn)
+        })
+        .map_err(|e| write_error("update crew", crew, e))
+    }
+
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, crew_id: Uuid) -> Result<()> {
+        let deleted = diesel::delete(crews::table.scoped(organization).filter(crews::id.eq(crew_id)))
+            .execute(conn)
+            .map_err(|e| database_error("delete crew", e))?;
+        if deleted == 0 {
+            return Err(not_found(crew_id));
+        }
+        Ok(())
+    }
+
+    async fn members(&self, conn: &mut PgConnection, organization: Uuid, crew_ids: &[Uuid]) -> Result<Vec<(CrewMember, User)>> {
+        crew_members::table
+            .scoped(organization)
+            .filter(crew_members::crew_id.eq_any(crew_ids))
+            .inner_join(users::table)
+            .filter(users::deleted_at.is_null())
+            .order_by((users::last_name.asc(), users::first_name.asc(), users::id.asc()))
+            .select((CrewMember::as_select(), User::as_select()))
+            .load(conn)
+            .map_err(|e| database_error("list crew members", e))
+    }
+
+    async fn add_member(&self, conn: &mut PgConnection, member: &CrewMember) -> Result<bool> {
+        let added = diesel::insert_into(crew_members::table)
+            .values(member)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .map_err(|e| database_error("add crew member", e))?;
+        Ok(added > 0)
+    }
+
+    async fn remove_member(&self, conn: &mut PgConnection, organization: Uuid, crew_id: Uuid, user_id: Uuid) -> Result<()> {
+        let removed = diesel::delete(
+            crew_members::table
+                .scoped(organization)
+                .filter(crew_members::crew_id.eq(crew_id))
+                .filter(crew_members::user_id.eq(user_id)),
+        )
+        .execute(conn)
+        .map_err(|e| database_error("remove crew member", e))?;
+        if removed == 0 {
+            return Err(ApiError::not_found(format!("User {} is not a member of crew {}", user_id, crew_id)));
+        }
+        Ok(())
+    }
+}
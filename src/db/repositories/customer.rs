@@ -0,0 +1,282 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        count::RowCount,
+        models::{Customer, SupplyContract},
+        schema::{customers, supply_contracts},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use diesel::{
+    prelude::*,
+    result::{DatabaseErrorKind, Error as DieselError},
+};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of customers and their supply contracts
+///
+/// Every read and write is scoped to an organization.
+#[async_trait]
+pub trait CustomerRepository: Send + Sync + 'static {
+    /// Stores a new customer
+    async fn create_customer(&self, conn: &mut PgConnection, customer: &Customer) -> Result<Customer>;
+
+    /// Finds one of an organization's customers
+    async fn find_customer(&self, conn: &mut PgConnection, organization: Uuid, customer_id: Uuid) -> Result<Customer>;
+
+    /// Lists an organization's customers by name
+    async fn list_customers(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<Customer>>;
+
+    /// Counts an organization's customers
+    async fn count_customers(&self, conn: &mut PgConnection, organization: Uuid) -> Result<RowCount>;
+
+    /// Replaces the details of a customer
+    async fn update_customer(&self, conn: &mut PgConnection, organization: Uuid, customer: &Customer) -> Result<Customer>;
+
+    /// Deletes a customer without contracts
+    async fn delete_customer(&self, conn: &mut PgConnection, organization: Uuid, customer_id: Uuid) -> Result<()>;
+
+    /// Stores a new supply contract
+    async fn create_contract(&self, conn: &mut PgConnection, contract: &SupplyContract) -> Result<SupplyContract>;
+
+    /// Finds one of an organization's supply contracts
+    async fn find_contract(&self, conn: &mut PgConnection, organization: Uuid, contract_id: Uuid) -> Result<SupplyContract>;
+
+    /// Lists the supply contracts of a customer, latest period first
+    async fn list_contracts(&self, conn: &mut PgConnection, organization: Uuid, customer_id: Uuid) -> Result<Vec<SupplyContract>>;
+
+    /// Supply contracts whose period includes `date`, by customer and
+    /// reference
+    async fn active_contracts(&self, conn: &mut PgConnection, organization: Uuid, date: NaiveDate) -> Result<Vec<SupplyContract>>;
+
+    /// Replaces the terms of a supply contract
+    async fn update_contract(&self, conn: &mut PgConnection, organization: Uuid, contract: &SupplyContract) -> Result<SupplyContract>;
+
+    /// Deletes a supply contract
+    async fn delete_contract(&self, conn: &mut PgConnection, organization: Uuid, contract_id: Uuid) -> Result<()>;
+}
+
+/// Concrete implementation of the customer repository
+pub struct CustomerRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn customer_not_found(customer_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Customer with id {} not found", customer_id))
+}
+
+fn contract_not_found(contract_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Supply contract with id {} not found", contract_id))
+}
+
+fn conflict(message: &str, details: serde_json::Value) -> ApiError {
+    ApiError::new(ErrorCode::Conflict, message, ErrorContext::new().with_details(details))
+}
+
+/// Maps a customer write error, reporting a name taken by another customer
+/// as a conflict
+fn customer_write_error(action: &str, customer: &Customer, e: DieselError) -> ApiError {
+    match e {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => conflict(
+            "A customer with this name already exists",
+            serde_json::json!({ "name": customer.name }),
+        ),
+        DieselError::NotFound => customer_not_found(customer.id),
+        e => database_error(action, e),
+    }
+}
+
+/// Maps a contract write error, reporting a reference taken by another
+/// contract as a conflict
+fn contract_write_error(action: &str, contract: &SupplyContract, e: DieselError) -> ApiError {
+    match e {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => conflict(
+            "A supply contract with this reference already exists",
+            serde_json::json!({ "reference": contract.reference }),
+        ),
+        DieselError::NotFound => contract_not_found(contract.id),
+        e => database_error(action, e),
+    }
+}
+
+#[async_trait]
+impl CustomerRepository for CustomerRepositoryImpl {
+    async fn create_customer(&self, conn: &mut PgConnection, customer: &Customer) -> Result<Customer> {
+        // In a savepoint, so a taken name leaves the caller's transaction usable
+        conn.transaction(|conn| {
+            diesel::insert_into(customers::table)
+                .values(customer)
+                .get_result(conn)
+        })
+        .map_err(|e| customer_write_error("create customer", customer, e))
+    }
+
+    async fn find_customer(&self, conn: &mut PgConnection, organization: Uuid, customer_id: Uuid) -> Result<Customer> {
+        customers::table
+            .scoped(organization)
+            .filter(customers::id.eq(customer_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find customer", e))?
+            .ok_or_else(|| customer_not_found(customer_id))
+    }
+
+    async fn list_customers(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<Customer>> {
+        customers::table
+            .scoped(organization)
+            .order_by((customers::name.asc(), customers::id.asc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list customers", e))
+    }
+
+    async fn count_customers(&self, conn: &mut PgConnection, organization: Uuid) -> Result<RowCount> {
+        customers::table
+            .scoped(organization)
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count customers", e))
+    }
+
+    async fn update_customer(&self, conn: &mut PgConnection, organization: Uuid, customer: &Customer) -> Result<Customer> {
+        conn.transaction(|conn| {
+            diesel::update(
+                customers::table
+                    .scoped(organization)
+                    .filter(customers::id.eq(customer.id)),
+            )
+            .set((
+                customers::name.eq(&customer.name),
+                customers::contact_name.eq(&customer.contact_name),
+                customers::email.eq(&customer.email),
+                customers::phone_number.eq(&customer.phone_number),
+                customers::address.eq(&customer.address),
+                customers::notes.eq(&customer.notes),
+                customers::updated_at.eq(Utc::now()),
+            ))
+            .get_result(conn)
+        })
+        .map_err(|e| customer_write_error("update customer", customer, e))
+    }
+
+    async fn delete_customer(&self, conn: &mut PgConnection, organization: Uuid, customer_id: Uuid) -> Result<()> {
+        let deleted = conn.transaction(|conn| {
+            diesel::delete(
+                customers::table
+                    .scoped(organization)
+                    .filter(customers::id.eq(customer_id)),
+            )
+            .execute(conn)
+        })
+        .map_err(|e| match e {
+            DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => conflict(
+                "The customer has supply contracts; delete them first",
+                serde_json::json!({ "customer_id": customer_id }),
+            ),
+            e => database_error("delete customer", e),
+        })?;
+        if deleted == 0 {
+            return Err(customer_not_found(customer_id));
+        }
+        Ok(())
+    }
+
+    async fn create_contract(&self, conn: &mut PgConnection, contract: &SupplyContract) -> Result<SupplyContract> {
+        // In a savepoint, so a taken reference leaves the caller's transaction usable
+        conn.transaction(|conn| {
+            diesel::insert_into(supply_contracts::table)
+                .values(contract)
+                .get_result(conn)
+        })
+        .map_err(|e| contract_write_error("create supply contract", contract, e))
+    }
+
+    async fn find_contract(&self, conn: &mut PgConnection, organization: Uuid, contract_id: Uuid) -> Result<SupplyContract> {
+        supply_contracts::table
+            .scoped(organization)
+            .filter(supply_contracts::id.eq(contract_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find supply contract", e))?
+            .ok_or_else(|| contract_not_found(contract_id))
+    }
+
+    async fn list_contracts(&self, conn: &mut PgConnection, organization: Uuid, customer_id: Uuid) -> Result<Vec<SupplyContract>> {
+        supply_contracts::table
+            .scoped(organization)
+            .filter(supply_contracts::customer_id.eq(customer_id))
+            .order_by((supply_contracts::starts_on.desc(), supply_contracts::reference.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list supply contracts", e))
+    }
+
+    async fn active_contracts(&self, conn: &mut PgConnection, organization: Uuid, date: NaiveDate) -> Result<Vec<SupplyContract>> {
+        supply_contracts::table
+            .scoped(organization)
+            .filter(supply_contracts::starts_on.le(date))
+            .filter(supply_contracts::ends_on.ge(date))
+            .order_by((supply_contracts::customer_id.asc(), supply_contracts::reference.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list active supply contracts", e))
+    }
+
+    async fn update_contract(&self, conn: &mut PgConnection, organization: Uuid, contract: &SupplyContract) -> Result<SupplyContract> {
+        conn.transaction(|conn| {
+            diesel::update(
+                supply_contracts::table
+                    .scoped(organization)
+                    .filter(supply_contracts::id.eq(contract.id)),
+            )
+            .set((
+                supply_contracts::reference.eq(&contract.reference),
+                supply_contracts::product.eq(&contract.product),
+                supply_contracts::volume_m3.eq(contract.volume_m3),
+                supply_contracts::price_per_m3.eq(contract.price_per_m3),
+                supply_contracts::starts_on.eq(contract.starts_on),
+                supply_contracts::ends_on.eq(contract.ends_on),
+                supply_contracts::notes.eq(&contract.notes),
+                supply_contracts::updated_at.eq(Utc::now()),
+            ))
+            .get_result(conn)
+        })
+        .map_err(|e| contract_write_error("update supply contract", contract, e))
+    }
+
+    async fn delete_contract(&self, conn: &mut PgConnection, organization: Uuid, contract_id: Uuid) -> Result<()> {
+        let deleted = diesel::delete(
+            supply_contracts::table
+                .scoped(organization)
+                .filter(supply_contracts::id.eq(contract_id)),
+        )
+        .execute(conn)
+        .map_err(|e| database_error("delete supply contract", e))?;
+        if deleted == 0 {
+            return Err(contract_not_found(contract_id));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    db::{
+        models::{Document, DocumentSubject, DocumentVersion},
+        schema::{document_versions, documents},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of documents and their versions
+///
+/// Documents are scoped to an organization; their versions are reached
+/// through a document that was.
+#[async_trait]
+pub trait DocumentRepository: Send + Sync + 'static {
+    /// Stores a new document with its first version
+    async fn create(
+        &self,
+        conn: &mut PgConnection,
+        document: &Document,
+        version: &DocumentVersion,
+    ) -> Result<(Document, DocumentVersion)>;
+
+    /// Finds one of an organization's documents
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, document_id: Uuid) -> Result<Document>;
+
+    /// Lists the documents attached to a block or permit, by category and
+    /// title
+    async fn list(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        subject: DocumentSubject,
+        subject_id: Uuid,
+    ) -> Result<Vec<Document>>;
+
+    /// Categories of the documents attached to a block or permit
+    async fn categories(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        subject: DocumentSubject,
+        subject_id: Uuid,
+    ) -> Result<Vec<String>>;
+
+    /// Lists the versions of a document, latest first
+    async fn versions(&self, conn: &mut PgConnection, document_id: Uuid) -> Result<Vec<DocumentVersion>>;
+
+    /// Finds a version of a document
+    async fn find_version(&self, conn: &mut PgConnection, document_id: Uuid, version: i32) -> Result<DocumentVersion>;
+
+    /// Adds a version after the latest one, numbering it, and extends the
+    /// document's retention to `retain_until`
+    async fn add_version(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        version: &DocumentVersion,
+        retain_until: Option<DateTime<Utc>>,
+    ) -> Result<(Document, DocumentVersion)>;
+
+    /// Deletes a document and its versions, returning the storage keys of
+    /// their files
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, document_id: Uuid) -> Result<Vec<String>>;
+}
+
+/// Concrete implementation of the document repository
+pub struct DocumentRepositoryImpl;
+
+fn database_error(action: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn not_found(document_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Document with id {} not found", document_id))
+}
+
+#[async_trait]
+impl DocumentRepository for DocumentRepositoryImpl {
+    async fn create(
+        &self,
+        conn: &mut PgConnection,
+        document: &Document,
+        version: &DocumentVersion,
+    ) -> Result<(Document, DocumentVersion)> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let document = diesel::insert_into(documents::table).values(document).get_result(conn)?;
+            let version = diesel::insert_into(document_versions::table).values(version).get_result(conn)?;
+            Ok((document, version))
+        })
+        .map_err(|e| database_error("create document", e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, document_id: Uuid) -> Result<Document> {
+        documents::table
+            .scoped(organization)
+            .filter(documents::id.eq(document_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find document", e))?
+            .ok_or_else(|| not_found(document_id))
+    }
+
+    async fn list(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        subject: DocumentSubject,
+        subject_id: Uuid,
+    ) -> Result<Vec<Document>> {
+        documents::table
+            .scoped(organization)
+            .filter(documents::subject_type.eq(subject.as_str()))
+            .filter(documents::subject_id.eq(subject_id))
+            .order_by((documents::category.asc(), documents::title.asc(), documents::id.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list documents", e))
+    }
+
+    async fn categories(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        subject: DocumentSubject,
+        subject_id: Uuid,
+    ) -> Result<Vec<String>> {
+        documents::table
+            .scoped(organization)
+            .filter(documents::subject_type.eq(subject.as_str()))
+            .filter(documents::subject_id.eq(subject_id))
+            .select(documents::category)
+            .distinct()
+            .load(conn)
+            .map_err(|e| database_error("list document categories", e))
+    }
+
+    async fn versions(&self, conn: &mut PgConnection, document_id: Uuid) -> Result<Vec<DocumentVersion>> {
+        document_versions::table
+            .filter(document_versions::document_id.eq(document_id))
+            .order_by(document_versions::version.desc())
+            .load(conn)
+            .map_err(|e| database_error("list document versions", e))
+    }
+
+    async fn find_version(&self, conn: &mut PgConnection, document_id: Uuid, version: i32) -> Result<DocumentVersion> {
+        document_versions::table
+            .filter(document_versions::document_id.eq(document_id))
+            .filter(document_versions::version.eq(version))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find document version", e))?
+            .ok_or_else(|| ApiError::not_found(format!("Version {} of document {} not found", version, document_id)))
+    }
+
+    async fn add_version(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        version: &DocumentVersion,
+        retain_until: Option<DateTime<Utc>>,
+    ) -> Result<(Document, DocumentVersion)> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            // Locked so concurrent uploads get consecutive numbers
+            let document: Option<Document> = documents::table
+                .scoped(organization)
+                .filter(documents::id.eq(version.document_id))
+                .for_update()
+                .first(conn)
+                .optional()?;
+            let Some(document) = document else {
+                return Ok(None);
+            };
+
+            let number = document.current_version + 1;
+            let version = diesel::insert_into(document_versions::table)
+                .values(&DocumentVersion { version: number, ..version.clone() })
+                .get_result(conn)?;
+            let document = diesel::update(documents::table.find(document.id))
+                .set((
+                    documents::current_version.eq(number),
+                    documents::retain_until.eq(retain_until.max(document.retain_until)),
+                    documents::updated_at.eq(Utc::now()),
+                ))
+                .get_result(conn)?;
+            Ok(Some((document, version)))
+        })
+        .map_err(|e| database_error("add document version", e))?
+        .ok_or_else(|| not_found(version.document_id))
+    }
+
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, document_id: Uuid) -> Result<Vec<String>> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let keys: Vec<String> = document_versions::table
+                .inner_join(documents::table)
+                .filter(documents::id.eq(document_id))
+                .filter(documents::org_id.eq(organization))
+                .select(document_versions::file_key)
+                .distinct()
+                .load(conn)?;
+            let deleted = diesel::delete(
+                documents::table
+                    .scoped(organization)
+                    .filter(documents::id.eq(document_id)),
+            )
+            .execute(conn)?;
+            Ok((deleted > 0).then_some(keys))
+        })
+        .map_err(|e| database_error("delete document", e))?
+        .ok_or_else(|| not_found(document_id))
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    db::{models::OrganizationEmailSender, schema::organization_email_senders::dsl::*},
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of per-organization email senders
+///
+/// Each organization has at most one sender, keyed by organization id.
+#[async_trait]
+pub trait EmailSenderRepository: Send + Sync + 'static {
+    /// Finds the sender of an organization, `None` when it uses the default
+    async fn find(&self, conn: &mut PgConnection, organization_id: Uuid) -> Result<Option<OrganizationEmailSender>>;
+
+    /// Creates or replaces the sender of an organization
+    async fn upsert(&self, conn: &mut PgConnection, sender: &OrganizationEmailSender) -> Result<OrganizationEmailSender>;
+
+    /// Removes the sender of an organization, restoring the default
+    async fn delete(&self, conn: &mut PgConnection, organization_id: Uuid) -> Result<OrganizationEmailSender>;
+}
+
+/// Concrete implementation of the email sender repository
+pub struct EmailSenderRepositoryImpl;
+
+fn database_error(action: &str, organization_id: Uuid, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        org_id = %organization_id,
+        error = %e,
+        "Failed to {} email sender",
+        action
+    );
+    ApiError::database_error(format!("Failed to {} email sender", action), None)
+}
+
+#[async_trait]
+impl EmailSenderRepository for EmailSenderRepositoryImpl {
+    async fn find(&self, conn: &mut PgConnection, organization_id: Uuid) -> Result<Option<OrganizationEmailSender>> {
+        organization_email_senders
+            .find(organization_id)
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find", organization_id, e))
+    }
+
+    async fn upsert(&self, conn: &mut PgConnection, sender: &OrganizationEmailSender) -> Result<OrganizationEmailSender> {
+        diesel::insert_into(organization_email_senders)
+            .values(sender)
+            .on_conflict(org_id)
+            .do_update()
+            .set((
+                from_address.eq(&sender.from_address),
+                from_name.eq(&sender.from_name),
+                reply_to.eq(&sender.reply_to),
+                updated_at.eq(Utc::now()),
+            ))
+            .get_result(conn)
+            .map_err(|e| database_error("save", sender.org_id, e))
+    }
+
+    async fn delete(&self, conn: &mut PgConnection, organization_id: Uuid) -> Result<OrganizationEmailSender> {
+        diesel::delete(organization_email_senders.find(organization_id))
+            .get_result(conn)
+            .optional()
+            .map_err(|e| database_error("delete", organization_id, e))?
+            .ok_or_else(|| {
+                ApiError::not_found(format!("Email sender of organization {} not found", organization_id))
+            })
+    }
+}
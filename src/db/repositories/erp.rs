@@ -0,0 +1,299 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        count::RowCount,
+        models::{ErpConnection, ErpExport, ErpExportStatus},
+        schema::{erp_connections, erp_exports},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of ERP connections and their exports
+///
+/// Reads and updates made for requests are scoped to an organization; the
+/// export jobs look records up by id alone.
+#[async_trait]
+pub trait ErpRepository: Send + Sync + 'static {
+    /// Stores a new connection
+    async fn create_connection(&self, conn: &mut PgConnection, connection: &ErpConnection) -> Result<ErpConnection>;
+
+    /// Finds one of an organization's connections
+    async fn find_connection(&self, conn: &mut PgConnection, organization: Uuid, connection_id: Uuid) -> Result<ErpConnection>;
+
+    /// Finds a connection of any organization
+    async fn find_connection_by_id(&self, conn: &mut PgConnection, connection_id: Uuid) -> Result<ErpConnection>;
+
+    /// Lists an organization's connections by name
+    async fn list_connections(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<ErpConnection>>;
+
+    /// Replaces the name, connector, settings, mappings and schedule of a
+    /// connection
+    async fn update_connection(&self, conn: &mut PgConnection, organization: Uuid, connection: &ErpConnection) -> Result<ErpConnection>;
+
+    /// Deletes one of an organization's connections with its exports
+    async fn delete_connection(&self, conn: &mut PgConnection, organization: Uuid, connection_id: Uuid) -> Result<()>;
+
+    /// Enabled connections whose scheduled export is due, oldest first
+    async fn due_connections(&self, conn: &mut PgConnection, now: DateTime<Utc>, limit: i64) -> Result<Vec<ErpConnection>>;
+
+    /// Moves a connection to its next scheduled export
+    async fn set_next_run(&self, conn: &mut PgConnection, connection_id: Uuid, next_run_at: Option<DateTime<Utc>>) -> Result<()>;
+
+    /// Records a written export
+    async fn create_export(&self, conn: &mut PgConnection, export: &ErpExport) -> Result<ErpExport>;
+
+    /// Finds one of an organization's exports
+    async fn find_export(&self, conn: &mut PgConnection, organization: Uuid, export_id: Uuid) -> Result<ErpExport>;
+
+    /// Finds an export of any organization
+    async fn find_export_by_id(&self, conn: &mut PgConnection, export_id: Uuid) -> Result<ErpExport>;
+
+    /// The export of a source through a connection covering the latest
+    /// period, if any
+    async fn latest_export(&self, conn: &mut PgConnection, connection_id: Uuid, source: &str) -> Result<Option<ErpExport>>;
+
+    /// Lists the exports of a connection, newest first
+    async fn list_exports(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        connection_id: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<ErpExport>>;
+
+    /// Counts the exports of a connection
+    async fn count_exports(&self, conn: &mut PgConnection, organization: Uuid, connection_id: Uuid) -> Result<RowCount>;
+
+    /// Records a successful delivery
+    async fn mark_delivered(&self, conn: &mut PgConnection, export_id: Uuid, remote_ref: &str) -> Result<ErpExport>;
+
+    /// Records a failed delivery attempt
+    async fn mark_failed(&self, conn: &mut PgConnection, export_id: Uuid, error: &str) -> Result<ErpExport>;
+}
+
+/// Concrete implementation of the ERP repository
+pub struct ErpRepositoryImpl;
+
+fn database_error(action: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn connection_not_found(connection_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("ERP connection with id {} not found", connection_id))
+}
+
+fn export_not_found(export_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("ERP export with id {} not found", export_id))
+}
+
+/// Maps a write error, reporting a name taken by another connection as a
+/// conflict
+fn write_error(action: &str, connection: &ErpConnection, e: diesel::result::Error) -> ApiError {
+    match e {
+        diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+            ApiError::new(
+                ErrorCode::Conflict,
+                "An ERP connection with this name already exists",
+                ErrorContext::new().with_details(serde_json::json!({ "name": connection.name })),
+            )
+        }
+        diesel::result::Error::NotFound => connection_not_found(connection.id),
+        e => database_error(action, e),
+    }
+}
+
+#[async_trait]
+impl ErpRepository for ErpRepositoryImpl {
+    async fn create_connection(&self, conn: &mut PgConnection, connection: &ErpConnection) -> Result<ErpConnection> {
+        // In a savepoint, so a taken name leaves the caller's transaction usable
+        conn.transaction(|conn| diesel::insert_into(erp_connections::table).values(connection).get_result(conn))
+            .map_err(|e| write_error("create ERP connection", connection, e))
+    }
+
+    async fn find_connection(&self, conn: &mut PgConnection, organization: Uuid, connection_id: Uuid) -> Result<ErpConnection> {
+        erp_connections::table
+            .scoped(organization)
+            .filter(erp_connections::id.eq(connection_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find ERP connection", e))?
+            .ok_or_else(|| connection_not_found(connection_id))
+    }
+
+    async fn find_connection_by_id(&self, conn: &mut PgConnection, connection_id: Uuid) -> Result<ErpConnection> {
+        erp_connections::table
+            .find(connection_id)
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find ERP connection", e))?
+            .ok_or_else(|| connection_not_found(connection_id))
+    }
+
+    async fn list_connections(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<ErpConnection>> {
+        erp_connections::table
+            .scoped(organization)
+            .order_by((erp_connections::name.asc(), erp_connections::id.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list ERP connections", e))
+    }
+
+    async fn update_connection(&self, conn: &mut PgConnection, organization: Uuid, connection: &ErpConnection) -> Result<ErpConnection> {
+        conn.transaction(|conn| {
+            diesel::update(
+                erp_connections::table
+                    .scoped(organization)
+                    .filter(erp_connections::id.eq(connection.id)),
+            )
+            .set((
+                erp_connections::name.eq(&connection.name),
+                erp_connections::connector.eq(&connection.connector),
+                erp_connections::settings.eq(&connection.settings),
+                erp_connections::mappings.eq(&connection.mappings),
+                erp_connections::schedule.eq(&connection.schedule),
+                erp_connections::next_run_at.eq(connection.next_run_at),
+                erp_connections::enabled.eq(connection.enabled),
+                erp_connections::updated_at.eq(Utc::now()),
+            ))
+            .get_result(conn)
+        })
+        .map_err(|e| write_error("update ERP connection", connection, e))
+    }
+
+    async fn delete_connection(&self, conn: &mut PgConnection, organization: Uuid, connection_id: Uuid) -> Result<()> {
+        let deleted = diesel::delete(
+            erp_connections::table
+                .scoped(organization)
+                .filter(erp_connections::id.eq(connection_id)),
+        )
+        .execute(conn)
+        .map_err(|e| database_error("delete ERP connection", e))?;
+        if deleted == 0 {
+            return Err(connection_not_found(connection_id));
+        }
+        Ok(())
+    }
+
+    async fn due_connections(&self, conn: &mut PgConnection, now: DateTime<Utc>, limit: i64) -> Result<Vec<ErpConnection>> {
+        erp_connections::table
+            .filter(erp_connections::enabled.eq(true))
+            .filter(erp_connections::next_run_at.le(now))
+            .order_by((erp_connections::next_run_at.asc(), erp_connections::id.asc()))
+            .limit(limit)
+            .load(conn)
+            .map_err(|e| database_error("find due ERP connections", e))
+    }
+
+    async fn set_next_run(&self, conn: &mut PgConnection, connection_id: Uuid, next_run_at: Option<DateTime<Utc>>) -> Result<()> {
+        diesel::update(erp_connections::table.find(connection_id))
+            .set(erp_connections::next_run_at.eq(next_run_at))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("reschedule ERP connection", e))
+    }
+
+    async fn create_export(&self, conn: &mut PgConnection, export: &ErpExport) -> Result<ErpExport> {
+        diesel::insert_into(erp_exports::table)
+            .values(export)
+            .get_result(conn)
+            .map_err(|e| database_error("record ERP export", e))
+    }
+
+    async fn find_export(&self, conn: &mut PgConnection, organization: Uuid, export_id: Uuid) -> Result<ErpExport> {
+        erp_exports::table
+            .scoped(organization)
+            .filter(erp_exports::id.eq(export_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find ERP export", e))?
+            .ok_or_else(|| export_not_found(export_id))
+    }
+
+    async fn find_export_by_id(&self, conn: &mut PgConnection, export_id: Uuid) -> Result<ErpExport> {
+        erp_exports::table
+            .find(export_id)
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find ERP export", e))?
+            .ok_or_else(|| export_not_found(export_id))
+    }
+
+    async fn latest_export(&self, conn: &mut PgConnection, connection_id: Uuid, source: &str) -> Result<Option<ErpExport>> {
+        erp_exports::table
+            .filter(erp_exports::connection_id.eq(connection_id))
+            .filter(erp_exports::source.eq(source))
+            .order_by(erp_exports::period_end.desc())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find latest ERP export", e))
+    }
+
+    async fn list_exports(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        connection_id: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<ErpExport>> {
+        erp_exports::table
+            .scoped(organization)
+            .filter(erp_exports::connection_id.eq(connection_id))
+            .order_by((erp_exports::created_at.desc(), erp_exports::id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list ERP exports", e))
+    }
+
+    async fn count_exports(&self, conn: &mut PgConnection, organization: Uuid, connection_id: Uuid) -> Result<RowCount> {
+        erp_exports::table
+            .scoped(organization)
+            .filter(erp_exports::connection_id.eq(connection_id))
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count ERP exports", e))
+    }
+
+    async fn mark_delivered(&self, conn: &mut PgConnection, export_id: Uuid, remote_ref: &str) -> Result<ErpExport> {
+        let now = Utc::now();
+        diesel::update(erp_exports::table.find(export_id))
+            .set((
+                erp_exports::status.eq(ErpExportStatus::Delivered.as_str()),
+                erp_exports::remote_ref.eq(remote_ref),
+                erp_exports::attempts.eq(erp_exports::attempts + 1),
+                erp_exports::error.eq(None::<String>),
+                erp_exports::delivered_at.eq(now),
+                erp_exports::updated_at.eq(now),
+            ))
+            .get_result(conn)
+            .optional()
+            .map_err(|e| database_error("record ERP export delivery", e))?
+            .ok_or_else(|| export_not_found(export_id))
+    }
+
+    async fn mark_failed(&self, conn: &mut PgConnection, export_id: Uuid, error: &str) -> Result<ErpExport> {
+        diesel::update(erp_exports::table.find(export_id))
+            .set((
+                erp_exports::status.eq(ErpExportStatus::Failed.as_str()),
+                erp_exports::attempts.eq(erp_exports::attempts + 1),
+                erp_exports::error.eq(error),
+                erp_exports::updated_at.eq(Utc::now()),
+            ))
+            .get_result(conn)
+            .optional()
+            .map_err(|e| database_error("record ERP export failure", e))?
+            .ok_or_else(|| export_not_found(export_id))
+    }
+}
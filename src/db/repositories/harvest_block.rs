@@ -0,0 +1,232 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        count::RowCount,
+        models::{BlockStatus, HarvestBlock, HarvestBlockFilter},
+        schema::harvest_blocks,
+        spatial::{envelope, geometry_from_geojson, st_intersects},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{
+    pg::Pg,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as DieselError},
+};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of harvest blocks
+///
+/// Every read and write is scoped to an organization. Boundaries are
+/// passed and returned as GeoJSON text.
+#[async_trait]
+pub trait HarvestBlockRepository: Send + Sync + 'static {
+    /// Stores a new block
+    async fn create(&self, conn: &mut PgConnection, block: &HarvestBlock) -> Result<HarvestBlock>;
+
+    /// Finds one of an organization's blocks
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid) -> Result<HarvestBlock>;
+
+    /// Lists an organization's blocks matching `filter`, by license and
+    /// block number
+    async fn list(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        filter: &HarvestBlockFilter,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<HarvestBlock>>;
+
+    /// Counts an organization's blocks matching `filter`
+    async fn count(&self, conn: &mut PgConnection, organization: Uuid, filter: &HarvestBlockFilter) -> Result<RowCount>;
+
+    /// Counts an organization's blocks in `status`
+    async fn count_in_status(&self, conn: &mut PgConnection, organization: Uuid, status: BlockStatus) -> Result<i64>;
+
+    /// Replaces the details of a block
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, block: &HarvestBlock) -> Result<HarvestBlock>;
+
+    /// Deletes a block no timber sale refers to
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid) -> Result<()>;
+}
+
+/// Concrete implementation of the harvest block repository
+pub struct HarvestBlockRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn not_found(block_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Harvest block with id {} not found", block_id))
+}
+
+fn conflict(message: &str, details: serde_json::Value) -> ApiError {
+    ApiError::new(ErrorCode::Conflict, message, ErrorContext::new().with_details(details))
+}
+
+/// Maps a block write error, reporting a block number taken under the
+/// license as a conflict and a boundary PostGIS rejects as invalid input
+fn write_error(action: &str, block: &HarvestBlock, e: DieselError) -> ApiError {
+    match e {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => conflict(
+            "A block with this number already exists under the license",
+            serde_json::json!({ "license": block.license, "block_number": block.block_number }),
+        ),
+        DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, _) => ApiError::validation(
+            "The boundary is not a valid polygon; its rings must not cross themselves or each other",
+            Some(serde_json::json!({ "field": "boundary" })),
+        ),
+        DieselError::NotFound => not_found(block.id),
+        e => database_error(action, e),
+    }
+}
+
+/// An organization's blocks matching `filter`, unordered
+fn filtered(organization: Uuid, filter: &HarvestBlockFilter) -> harvest_blocks::BoxedQuery<'_, Pg> {
+    let mut query = harvest_blocks::table.scoped(organization).into_boxed();
+    if let Some(ids) = filter.ids.as_deref() {
+        query = query.filter(harvest_blocks::id.eq_any(ids));
+    }
+    if let Some(license) = filter.license.as_deref() {
+        query = query.filter(harvest_blocks::license.eq(license));
+    }
+    if let Some(status) = filter.status {
+        query = query.filter(harvest_blocks::status.eq(status.as_str()));
+    }
+    if let Some(bbox) = filter.bbox {
+        query = query.filter(st_intersects(
+            harvest_blocks::boundary,
+            envelope((bbox.min_lon, bbox.min_lat), (bbox.max_lon, bbox.max_lat)),
+        ));
+    }
+    query
+}
+
+#[async_trait]
+impl HarvestBlockRepository for HarvestBlockRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, block: &HarvestBlock) -> Result<HarvestBlock> {
+        // In a savepoint, so a taken number leaves the caller's transaction usable
+        conn.transaction(|conn| {
+            diesel::insert_into(harvest_blocks::table)
+                .values((
+                    harvest_blocks::id.eq(block.id),
+                    harvest_blocks::org_id.eq(block.org_id),
+                    harvest_blocks::license.eq(&block.license),
+                    harvest_blocks::block_number.eq(&block.block_number),
+                    harvest_blocks::area_ha.eq(block.area_ha),
+                    harvest_blocks::planned_volume_m3.eq(block.planned_volume_m3),
+                    harvest_blocks::status.eq(&block.status),
+                    harvest_blocks::boundary.eq(geometry_from_geojson(&block.boundary.to_geojson())),
+                    harvest_blocks::notes.eq(&block.notes),
+                    harvest_blocks::created_by.eq(block.created_by),
+                    harvest_blocks::created_at.eq(block.created_at),
+                    harvest_blocks::updated_at.eq(block.updated_at),
+                    harvest_blocks::soil_sensitivity.eq(&block.soil_sensitivity),
+                ))
+                .returning(HarvestBlock::as_returning())
+                .get_result(conn)
+        })
+        .map_err(|e| write_error("create harvest block", block, e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid) -> Result<HarvestBlock> {
+        harvest_blocks::table
+            .scoped(organization)
+            .filter(harvest_blocks::id.eq(block_id))
+            .select(HarvestBlock::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find harvest block", e))?
+            .ok_or_else(|| not_found(block_id))
+    }
+
+    async fn list(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        filter: &HarvestBlockFilter,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<HarvestBlock>> {
+        filtered(organization, filter)
+            .select(HarvestBlock::as_select())
+            .order_by((harvest_blocks::license.asc(), harvest_blocks::block_number.asc(), harvest_blocks::id.asc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list harvest blocks", e))
+    }
+
+    async fn count(&self, conn: &mut PgConnection, organization: Uuid, filter: &HarvestBlockFilter) -> Result<RowCount> {
+        filtered(organization, filter)
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count harvest blocks", e))
+    }
+
+    async fn count_in_status(&self, conn: &mut PgConnection, organization: Uuid, status: BlockStatus) -> Result<i64> {
+        harvest_blocks::table
+            .scoped(organization)
+            .filter(harvest_blocks::status.eq(status.as_str()))
+            .count()
+            .get_result(conn)
+            .map_err(|e| database_error("count harvest blocks", e))
+    }
+
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, block: &HarvestBlock) -> Result<HarvestBlock> {
+        conn.transaction(|conn| {
+            diesel::update(
+                harvest_blocks::table
+                    .scoped(organization)
+                    .filter(harvest_blocks::id.eq(block.id)),
+            )
+            .set((
+                harvest_blocks::license.eq(&block.license),
+                harvest_blocks::block_number.eq(&block.block_number),
+                harvest_blocks::area_ha.eq(block.area_ha),
+                harvest_blocks::planned_volume_m3.eq(block.planned_volume_m3),
+                harvest_blocks::status.eq(&block.status),
+                harvest_blocks::boundary.eq(geometry_from_geojson(&block.boundary.to_geojson())),
+                harvest_blocks::notes.eq(&block.notes),
+                harvest_blocks::soil_sensitivity.eq(&block.soil_sensitivity),
+                harvest_blocks::updated_at.eq(Utc::now()),
+            ))
+            .returning(HarvestBlock::as_returning())
+            .get_result(conn)
+        })
+        .map_err(|e| write_error("update harvest block", block, e))
+    }
+
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid) -> Result<()> {
+        let deleted = conn.transaction(|conn| {
+            diesel::delete(
+                harvest_blocks::table
+                    .scoped(organization)
+                    .filter(harvest_blocks::id.eq(block_id)),
+            )
+            .execute(conn)
+        })
+        .map_err(|e| match e {
+            DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => conflict(
+                "The block is offered or sold in a timber sale",
+                serde_json::json!({ "block_id": block_id }),
+            ),
+            e => database_error("delete harvest block", e),
+        })?;
+        if deleted == 0 {
+            return Err(not_found(block_id));
+        }
+        Ok(())
+    }
+}
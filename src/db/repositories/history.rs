@@ -0,0 +1,79 @@
+use crate::{
+    db::{
+        models::FieldChange,
+        schema::{change_history, users},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of field-level change history
+#[async_trait]
+pub trait HistoryRepository: Send + Sync + 'static {
+    /// Stores the changes of an update
+    async fn record(&self, conn: &mut PgConnection, changes: &[FieldChange]) -> Result<()>;
+
+    /// The changes of a record, oldest first, each with the name of the
+    /// user who made it when known
+    async fn list(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        resource: &str,
+        record_id: Uuid,
+    ) -> Result<Vec<(FieldChange, Option<String>)>>;
+}
+
+/// Concrete implementation of the history repository
+pub struct HistoryRepositoryImpl;
+
+fn database_error(action: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl HistoryRepository for HistoryRepositoryImpl {
+    async fn record(&self, conn: &mut PgConnection, changes: &[FieldChange]) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        diesel::insert_into(change_history::table)
+            .values(changes)
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("record change history", e))
+    }
+
+    async fn list(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        resource: &str,
+        record_id: Uuid,
+    ) -> Result<Vec<(FieldChange, Option<String>)>> {
+        change_history::table
+            .scoped(organization)
+            .left_join(users::table)
+            .filter(change_history::resource.eq(resource))
+            .filter(change_history::record_id.eq(record_id))
+            .order_by((change_history::changed_at.asc(), change_history::field.asc()))
+            .select((FieldChange::as_select(), (users::first_name, users::last_name).nullable()))
+            .load::<(FieldChange, Option<(String, String)>)>(conn)
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|(change, user)| (change, user.map(|(first_name, last_name)| format!("{} {}", first_name, last_name))))
+                    .collect()
+            })
+            .map_err(|e| database_error("list change history", e))
+    }
+}
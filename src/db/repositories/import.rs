@@ -0,0 +1,217 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        count::RowCount,
+        models::{Import, ImportStatus},
+        schema::imports::dsl::*,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// Outcome of a committed import, recorded when its job ends
+#[derive(Debug, Clone)]
+pub struct ImportOutcome {
+    pub status: ImportStatus,
+    pub imported_rows: Option<i32>,
+    pub failed_rows: Option<i32>,
+    pub error_key: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Persistence of spreadsheet imports
+///
+/// Reads and updates made for requests are scoped to an organization; the
+/// import job looks imports up by id alone.
+#[async_trait]
+pub trait ImportRepository: Send + Sync + 'static {
+    /// Stores a new import
+    async fn create(&self, conn: &mut PgConnection, import: &Import) -> Result<Import>;
+
+    /// Finds one of an organization's imports
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, import_id: Uuid) -> Result<Import>;
+
+    /// Finds an import of any organization
+    async fn find_by_id(&self, conn: &mut PgConnection, import_id: Uuid) -> Result<Import>;
+
+    /// Lists an organization's imports, newest first
+    async fn list_for_org(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<Import>>;
+
+    /// Counts an organization's imports
+    async fn count_for_org(&self, conn: &mut PgConnection, organization: Uuid) -> Result<RowCount>;
+
+    /// Replaces the column mapping of an import that is not committed yet
+    async fn set_mapping(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        import_id: Uuid,
+        column_mapping: &serde_json::Value,
+    ) -> Result<Option<Import>>;
+
+    /// Commits an uploaded import with its final mapping, `None` when it
+    /// was already committed
+    async fn queue(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        import_id: Uuid,
+        column_mapping: &serde_json::Value,
+    ) -> Result<Option<Import>>;
+
+    /// Marks a queued import as running, `false` when it already ended
+    async fn start(&self, conn: &mut PgConnection, import_id: Uuid) -> Result<bool>;
+
+    /// Records the outcome of an import
+    async fn finish(&self, conn: &mut PgConnection, import_id: Uuid, outcome: ImportOutcome) -> Result<Import>;
+}
+
+/// Concrete implementation of the import repository
+pub struct ImportRepositoryImpl;
+
+fn database_error(action: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn not_found(import_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Import with id {} not found", import_id))
+}
+
+#[async_trait]
+impl ImportRepository for ImportRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, import: &Import) -> Result<Import> {
+        diesel::insert_into(imports)
+            .values(import)
+            .get_result(conn)
+            .map_err(|e| database_error("create import", e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, import_id: Uuid) -> Result<Import> {
+        imports
+            .find(import_id)
+            .filter(org_id.eq(organization))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find import", e))?
+            .ok_or_else(|| not_found(import_id))
+    }
+
+    async fn find_by_id(&self, conn: &mut PgConnection, import_id: Uuid) -> Result<Import> {
+        imports
+            .find(import_id)
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find import", e))?
+            .ok_or_else(|| not_found(import_id))
+    }
+
+    async fn list_for_org(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<Import>> {
+        imports
+            .filter(org_id.eq(organization))
+            .order_by((created_at.desc(), id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list imports", e))
+    }
+
+    async fn count_for_org(&self, conn: &mut PgConnection, organization: Uuid) -> Result<RowCount> {
+        imports
+            .filter(org_id.eq(organization))
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count imports", e))
+    }
+
+    async fn set_mapping(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        import_id: Uuid,
+        column_mapping: &serde_json::Value,
+    ) -> Result<Option<Import>> {
+        diesel::update(
+            imports
+                .find(import_id)
+                .filter(org_id.eq(organization))
+                .filter(status.eq(ImportStatus::Uploaded.as_str())),
+        )
+        .set((mapping.eq(column_mapping), updated_at.eq(Utc::now())))
+        .get_result(conn)
+        .optional()
+        .map_err(|e| database_error("update import mapping", e))
+    }
+
+    async fn queue(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        import_id: Uuid,
+        column_mapping: &serde_json::Value,
+    ) -> Result<Option<Import>> {
+        diesel::update(
+            imports
+                .find(import_id)
+                .filter(org_id.eq(organization))
+                .filter(status.eq(ImportStatus::Uploaded.as_str())),
+        )
+        .set((
+            mapping.eq(column_mapping),
+            status.eq(ImportStatus::Queued.as_str()),
+            updated_at.eq(Utc::now()),
+        ))
+        .get_result(conn)
+        .optional()
+        .map_err(|e| database_error("commit import", e))
+    }
+
+    async fn start(&self, conn: &mut PgConnection, import_id: Uuid) -> Result<bool> {
+        // A running import is taken over when its job is retried
+        diesel::update(
+            imports
+                .find(import_id)
+                .filter(status.eq_any([ImportStatus::Queued.as_str(), ImportStatus::Running.as_str()])),
+        )
+        .set((status.eq(ImportStatus::Running.as_str()), updated_at.eq(Utc::now())))
+        .execute(conn)
+        .map(|updated| updated > 0)
+        .map_err(|e| database_error("start import", e))
+    }
+
+    async fn finish(&self, conn: &mut PgConnection, import_id: Uuid, outcome: ImportOutcome) -> Result<Import> {
+        diesel::update(imports.find(import_id))
+            .set((
+                status.eq(outcome.status.as_str()),
+                imported_rows.eq(outcome.imported_rows),
+                failed_rows.eq(outcome.failed_rows),
+                error_key.eq(outcome.error_key),
+                error.eq(outcome.error),
+                updated_at.eq(Utc::now()),
+            ))
+            .get_result(conn)
+            .optional()
+            .map_err(|e| database_error("record import outcome", e))?
+            .ok_or_else(|| not_found(import_id))
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{
+    db::{models::OrgInvitation, schema::org_invitations, tenant::TenantScoped},
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of organization invitations
+#[async_trait]
+pub trait InvitationRepository: Send + Sync + 'static {
+    /// Stores a new invitation
+    async fn create(&self, conn: &mut PgConnection, invitation: &OrgInvitation) -> Result<OrgInvitation>;
+
+    /// Revokes the pending invitations of `email` to an organization,
+    /// returning how many there were
+    async fn revoke_pending(&self, conn: &mut PgConnection, organization: Uuid, email: &str) -> Result<usize>;
+
+    /// Marks the invitation with `id` accepted if it is still pending,
+    /// `None` if it was accepted, revoked or has expired
+    async fn accept(&self, conn: &mut PgConnection, id: Uuid) -> Result<Option<OrgInvitation>>;
+
+    /// The invitations to an organization neither accepted nor revoked,
+    /// expired ones included, newest first
+    async fn list_pending(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<OrgInvitation>>;
+
+    /// The invitation with `id` to an organization if it is neither
+    /// accepted nor revoked
+    async fn find_pending(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<Option<OrgInvitation>>;
+
+    /// Moves the expiry of the pending invitation with `id`, `None` if it
+    /// was accepted or revoked meanwhile
+    async fn extend(&self, conn: &mut PgConnection, id: Uuid, expires_at: DateTime<Utc>) -> Result<Option<OrgInvitation>>;
+
+    /// Revokes the pending invitation with `id` to an organization, `None`
+    /// if there is no such invitation
+    async fn revoke(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<Option<OrgInvitation>>;
+}
+
+/// Concrete implementation of the invitation repository
+pub struct InvitationRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl InvitationRepository for InvitationRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, invitation: &OrgInvitation) -> Result<OrgInvitation> {
+        diesel::insert_into(org_invitations::table)
+            .values(invitation)
+            .returning(OrgInvitation::as_select())
+            .get_result(conn)
+            .map_err(|e| database_error("create invitation", e))
+    }
+
+    async fn revoke_pending(&self, conn: &mut PgConnection, organization: Uuid, email: &str) -> Result<usize> {
+        let now = Utc::now();
+        diesel::update(org_invitations::table.scoped(organization))
+            .filter(org_invitations::email.eq(email))
+            .filter(org_invitations::accepted_at.is_null())
+            .filter(org_invitations::deleted_at.is_null())
+            .set((org_invitations::deleted_at.eq(Some(now)), org_invitations::updated_at.eq(now)))
+            .execute(conn)
+            .map_err(|e| database_error("revoke invitations", e))
+    }
+
+    async fn accept(&self, conn: &mut PgConnection, id: Uuid) -> Result<Option<OrgInvitation>> {
+        let now = Utc::now();
+        // A single conditional update, so an invitation can't be accepted
+        // twice by concurrent requests
+        diesel::update(org_invitations::table)
+            .filter(org_invitations::id.eq(id))
+            .filter(org_invitations::accepted_at.is_null())
+            .filter(org_invitations::deleted_at.is_null())
+            .filter(org_invitations::expires_at.gt(now))
+            .set((org_invitations::accepted_at.eq(Some(now)), org_invitations::updated_at.eq(now)))
+            .returning(OrgInvitation::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| database_error("accept invitation", e))
+    }
+
+    async fn list_pending(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<OrgInvitation>> {
+        org_invitations::table
+            .scoped(organization)
+            .filter(org_invitations::accepted_at.is_null())
+            .filter(org_invitations::deleted_at.is_null())
+            .order_by(org_invitations::created_at.desc())
+            .select(OrgInvitation::as_select())
+            .load(conn)
+            .map_err(|e| database_error("list invitations", e))
+    }
+
+    async fn find_pending(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<Option<OrgInvitation>> {
+        org_invitations::table
+            .scoped(organization)
+            .filter(org_invitations::id.eq(id))
+            .filter(org_invitations::accepted_at.is_null())
+            .filter(org_invitations::deleted_at.is_null())
+            .select(OrgInvitation::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find invitation", e))
+    }
+
+    async fn extend(&self, conn: &mut PgConnection, id: Uuid, expires_at: DateTime<Utc>) -> Result<Option<OrgInvitation>> {
+        diesel::update(org_invitations::table)
+            .filter(org_invitations::id.eq(id))
+            .filter(org_invitations::accepted_at.is_null())
+            .filter(org_invitations::deleted_at.is_null())
+            .set((org_invitations::expires_at.eq(expires_at), org_invitations::updated_at.eq(Utc::now())))
+            .returning(OrgInvitation::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| database_error("extend invitation", e))
+    }
+
+    async fn revoke(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<Option<OrgInvitation>> {
+        let now = Utc::now();
+        diesel::update(org_invitations::table.scoped(organization))
+            .filter(org_invitations::id.eq(id))
+            .filter(org_invitations::accepted_at.is_null())
+            .filter(org_invitations::deleted_at.is_null())
+            .set((org_invitations::deleted_at.eq(Some(now)), org_invitations::updated_at.eq(now)))
+            .returning(OrgInvitation::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| database_error("revoke invitation", e))
+    }
+}
@@ -0,0 +1,308 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        count::RowCount,
+        models::{DeadLetterJob, QueuedJob},
+        schema::{dead_letter_jobs, queued_jobs},
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of the durable job queue and its dead letters
+///
+/// Claiming uses `FOR UPDATE SKIP LOCKED`, so any number of workers can
+/// poll the queue concurrently without handing the same job to two of them.
+#[async_trait]
+pub trait JobQueueRepository: Send + Sync + 'static {
+    /// Adds a job to the queue
+    async fn enqueue(&self, conn: &mut PgConnection, job: &QueuedJob) -> Result<QueuedJob>;
+
+    /// Claims the due job of one of `kinds` that has waited longest
+    ///
+    /// The job stays invisible to other workers until `visible_at` and its
+    /// attempt counter is incremented. Returns `None` if no job is due.
+    async fn claim(
+        &self,
+        conn: &mut PgConnection,
+        kinds: &[&str],
+        worker: &str,
+        visible_at: DateTime<Utc>,
+    ) -> Result<Option<QueuedJob>>;
+
+    /// Pushes back the visibility timeout of a job held by `worker`,
+    /// returning false if the worker no longer holds it
+    async fn extend(&self, conn: &mut PgConnection, job_id: Uuid, worker: &str, visible_at: DateTime<Utc>) -> Result<bool>;
+
+    /// Removes a successfully processed job
+    async fn complete(&self, conn: &mut PgConnection, job_id: Uuid, worker: &str) -> Result<()>;
+
+    /// Releases a failed job for another attempt at `retry_at`
+    async fn retry(
+        &self,
+        conn: &mut PgConnection,
+        job_id: Uuid,
+        worker: &str,
+        error_message: &str,
+        retry_at: DateTime<Utc>,
+    ) -> Result<()>;
+
+    /// Moves a job that failed its last attempt to the dead-letter table
+    async fn dead_letter(&self, conn: &mut PgConnection, job: QueuedJob, worker: &str, error_message: &str) -> Result<DeadLetterJob>;
+
+    /// Lists queued jobs, optionally of one kind, in run order
+    async fn list(&self, conn: &mut PgConnection, job_kind: Option<&str>, pagination: &PaginationParams) -> Result<Vec<QueuedJob>>;
+
+    /// Counts queued jobs, optionally of one kind
+    async fn count(&self, conn: &mut PgConnection, job_kind: Option<&str>) -> Result<RowCount>;
+
+    /// Lists dead letters, optionally of one kind, most recent failure first
+    async fn list_dead_letters(
+        &self,
+        conn: &mut PgConnection,
+        job_kind: Option<&str>,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<DeadLetterJob>>;
+
+    /// Counts dead letters, optionally of one kind
+    async fn count_dead_letters(&self, conn: &mut PgConnection, job_kind: Option<&str>) -> Result<RowCount>;
+
+    /// Finds a dead letter by id
+    async fn find_dead_letter(&self, conn: &mut PgConnection, job_id: Uuid) -> Result<DeadLetterJob>;
+
+    /// Moves a dead letter back into the queue with a fresh set of attempts
+    async fn requeue(&self, conn: &mut PgConnection, job_id: Uuid, max_attempts: i32) -> Result<QueuedJob>;
+
+    /// Deletes a dead letter for good
+    async fn discard(&self, conn: &mut PgConnection, job_id: Uuid) -> Result<DeadLetterJob>;
+}
+
+/// Concrete implementation of the job queue repository
+pub struct JobQueueRepositoryImpl;
+
+fn database_error(action: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn dead_letter_not_found(job_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Dead letter job with id {} not found", job_id))
+}
+
+#[async_trait]
+impl JobQueueRepository for JobQueueRepositoryImpl {
+    async fn enqueue(&self, conn: &mut PgConnection, job: &QueuedJob) -> Result<QueuedJob> {
+        diesel::insert_into(queued_jobs::table)
+            .values(job)
+            .get_result(conn)
+            .map_err(|e| database_error("enqueue job", e))
+    }
+
+    async fn claim(
+        &self,
+        conn: &mut PgConnection,
+        kinds: &[&str],
+        worker: &str,
+        visible_at: DateTime<Utc>,
+    ) -> Result<Option<QueuedJob>> {
+        use crate::db::schema::queued_jobs::dsl::*;
+
+        conn.transaction(|conn| {
+            let now = Utc::now();
+            let Some(job_id) = queued_jobs
+                .select(id)
+                .filter(kind.eq_any(kinds))
+                .filter(run_at.le(now))
+                .filter(locked_until.is_null().or(locked_until.lt(now)))
+                .order_by(run_at.asc())
+                .limit(1)
+                .for_update()
+                .skip_locked()
+                .first::<Uuid>(conn)
+                .optional()?
+            else {
+                return Ok(None);
+            };
+
+            diesel::update(queued_jobs.find(job_id))
+                .set((
+                    attempts.eq(attempts + 1),
+                    locked_by.eq(worker),
+                    locked_until.eq(visible_at),
+                    updated_at.eq(now),
+                ))
+                .get_result(conn)
+                .map(Some)
+        })
+        .map_err(|e| database_error("claim job", e))
+    }
+
+    async fn extend(&self, conn: &mut PgConnection, job_id: Uuid, worker: &str, visible_at: DateTime<Utc>) -> Result<bool> {
+        use crate::db::schema::queued_jobs::dsl::*;
+
+        diesel::update(queued_jobs.find(job_id).filter(locked_by.eq(worker)))
+            .set(locked_until.eq(visible_at))
+            .execute(conn)
+            .map(|updated| updated > 0)
+            .map_err(|e| database_error("extend job visibility timeout", e))
+    }
+
+    async fn complete(&self, conn: &mut PgConnection, job_id: Uuid, worker: &str) -> Result<()> {
+        use crate::db::schema::queued_jobs::dsl::*;
+
+        diesel::delete(queued_jobs.find(job_id).filter(locked_by.eq(worker)))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("complete job", e))
+    }
+
+    async fn retry(
+        &self,
+        conn: &mut PgConnection,
+        job_id: Uuid,
+        worker: &str,
+        error_message: &str,
+        retry_at: DateTime<Utc>,
+    ) -> Result<()> {
+        use crate::db::schema::queued_jobs::dsl::*;
+
+        diesel::update(queued_jobs.find(job_id).filter(locked_by.eq(worker)))
+            .set((
+                run_at.eq(retry_at),
+                locked_by.eq(None::<String>),
+                locked_until.eq(None::<DateTime<Utc>>),
+                last_error.eq(error_message),
+                updated_at.eq(Utc::now()),
+            ))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("schedule job retry", e))
+    }
+
+    async fn dead_letter(&self, conn: &mut PgConnection, job: QueuedJob, worker: &str, error_message: &str) -> Result<DeadLetterJob> {
+        conn.transaction(|conn| {
+            diesel::delete(
+                queued_jobs::table
+                    .find(job.id)
+                    .filter(queued_jobs::locked_by.eq(worker)),
+            )
+            .execute(conn)?;
+
+            let dead_letter = DeadLetterJob {
+                last_error: Some(error_message.to_string()),
+                ..DeadLetterJob::from(job)
+            };
+            diesel::insert_into(dead_letter_jobs::table)
+                .values(&dead_letter)
+                .get_result(conn)
+        })
+        .map_err(|e| database_error("move job to dead letters", e))
+    }
+
+    async fn list(&self, conn: &mut PgConnection, job_kind: Option<&str>, pagination: &PaginationParams) -> Result<Vec<QueuedJob>> {
+        let mut query = queued_jobs::table.into_boxed();
+        if let Some(job_kind) = job_kind {
+            query = query.filter(queued_jobs::kind.eq(job_kind));
+        }
+        query
+            .order_by((queued_jobs::run_at.asc(), queued_jobs::id.asc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list queued jobs", e))
+    }
+
+    async fn count(&self, conn: &mut PgConnection, job_kind: Option<&str>) -> Result<RowCount> {
+        let mut query = queued_jobs::table.into_boxed();
+        if let Some(job_kind) = job_kind {
+            query = query.filter(queued_jobs::kind.eq(job_kind));
+        }
+        query
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count queued jobs", e))
+    }
+
+    async fn list_dead_letters(
+        &self,
+        conn: &mut PgConnection,
+        job_kind: Option<&str>,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<DeadLetterJob>> {
+        let mut query = dead_letter_jobs::table.into_boxed();
+        if let Some(job_kind) = job_kind {
+            query = query.filter(dead_letter_jobs::kind.eq(job_kind));
+        }
+        query
+            .order_by((dead_letter_jobs::failed_at.desc(), dead_letter_jobs::id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list dead letter jobs", e))
+    }
+
+    async fn count_dead_letters(&self, conn: &mut PgConnection, job_kind: Option<&str>) -> Result<RowCount> {
+        let mut query = dead_letter_jobs::table.into_boxed();
+        if let Some(job_kind) = job_kind {
+            query = query.filter(dead_letter_jobs::kind.eq(job_kind));
+        }
+        query
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count dead letter jobs", e))
+    }
+
+    async fn find_dead_letter(&self, conn: &mut PgConnection, job_id: Uuid) -> Result<DeadLetterJob> {
+        dead_letter_jobs::table
+            .find(job_id)
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find dead letter job", e))?
+            .ok_or_else(|| dead_letter_not_found(job_id))
+    }
+
+    async fn requeue(&self, conn: &mut PgConnection, job_id: Uuid, max_attempts: i32) -> Result<QueuedJob> {
+        let requeued = conn
+            .transaction(|conn| {
+                let Some(dead_letter) = diesel::delete(dead_letter_jobs::table.find(job_id))
+                    .get_result::<DeadLetterJob>(conn)
+                    .optional()?
+                else {
+                    return Ok(None);
+                };
+
+                let job = QueuedJob {
+                    id: dead_letter.id,
+                    last_error: dead_letter.last_error,
+                    created_at: dead_letter.created_at,
+                    ..QueuedJob::new(&dead_letter.kind, dead_letter.payload, max_attempts)
+                };
+                diesel::insert_into(queued_jobs::table)
+                    .values(&job)
+                    .get_result::<QueuedJob>(conn)
+                    .map(Some)
+            })
+            .map_err(|e| database_error("requeue dead letter job", e))?;
+
+        requeued.ok_or_else(|| dead_letter_not_found(job_id))
+    }
+
+    async fn discard(&self, conn: &mut PgConnection, job_id: Uuid) -> Result<DeadLetterJob> {
+        diesel::delete(dead_letter_jobs::table.find(job_id))
+            .get_result(conn)
+            .optional()
+            .map_err(|e| database_error("discard dead letter job", e))?
+            .ok_or_else(|| dead_letter_not_found(job_id))
+    }
+}
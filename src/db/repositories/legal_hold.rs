@@ -0,0 +1,254 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        count::RowCount,
+        models::LegalHold,
+        repositories::Repository,
+        schema::legal_holds::dsl::*,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::prelude::*;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+/// Legal hold specific repository operations
+#[async_trait]
+pub trait LegalHoldRepository: Repository<LegalHold> {
+    /// Finds the active hold on a record, if any
+    async fn find_active(
+        &self,
+        conn: &mut PgConnection,
+        entity: &str,
+        record_id: Uuid,
+    ) -> Result<Option<LegalHold>>;
+
+    /// Lists active holds on records of one entity type
+    async fn list_by_entity_type(
+        &self,
+        conn: &mut PgConnection,
+        entity: &str,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<LegalHold>>;
+
+    /// Counts active holds, optionally restricted to one entity type
+    async fn count(&self, conn: &mut PgConnection, entity: Option<&str>) -> Result<RowCount>;
+
+    /// Counts active holds per entity type
+    async fn count_by_entity_type(&self, conn: &mut PgConnection) -> Result<Vec<(String, i64)>>;
+}
+
+/// Concrete implementation of the legal hold repository
+pub struct LegalHoldRepositoryImpl;
+
+#[async_trait]
+impl Repository<LegalHold> for LegalHoldRepositoryImpl {
+    async fn find_by_id(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<LegalHold> {
+        legal_holds
+            .find(search_id)
+            .filter(deleted_at.is_null())
+            .first(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    warn!(
+                        error_code = %ErrorCode::NotFound,
+                        legal_hold_id = %search_id,
+                        "Legal hold not found"
+                    );
+                    ApiError::not_found(format!("Legal hold with id {} not found", search_id))
+                }
+                _ => {
+                    error!(
+                        error_code = %ErrorCode::DatabaseError,
+                        legal_hold_id = %search_id,
+                        error = %e,
+                        "Database error occurred while finding legal hold"
+                    );
+                    ApiError::database_error("Failed to find legal hold", None)
+                }
+            })
+    }
+
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<LegalHold>> {
+        legal_holds
+            .filter(id.eq_any(ids))
+            .filter(deleted_at.is_null())
+            .load(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Database error occurred while batch loading legal holds"
+                );
+                ApiError::database_error("Failed to find legal holds", None)
+            })
+    }
+
+    async fn create(&self, conn: &mut PgConnection, hold: &LegalHold) -> Result<LegalHold> {
+        diesel::insert_into(legal_holds)
+            .values(hold)
+            .get_result(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                    ApiError::new(
+                        ErrorCode::Conflict,
+                        "Record is already under legal hold",
+                        ErrorContext::new().with_details(serde_json::json!({
+                            "entity_type": hold.entity_type,
+                            "entity_id": hold.entity_id
+                        }))
+                    )
+                }
+                _ => {
+                    error!(
+                        error_code = %ErrorCode::DatabaseError,
+                        error = %e,
+                        "Failed to create legal hold"
+                    );
+                    ApiError::database_error("Failed to create legal hold", None)
+                }
+            })
+    }
+
+    async fn update(&self, conn: &mut PgConnection, search_id: Uuid, hold: &LegalHold) -> Result<LegalHold> {
+        diesel::update(legal_holds.find(search_id))
+            .set(hold)
+            .get_result(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    ApiError::not_found(format!("Legal hold with id {} not found", search_id))
+                }
+                _ => {
+                    error!(
+                        error_code = %ErrorCode::DatabaseError,
+                        error = %e,
+                        "Failed to update legal hold"
+                    );
+                    ApiError::database_error("Failed to update legal hold", None)
+                }
+            })
+    }
+
+    async fn soft_delete(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<LegalHold> {
+        diesel::update(legal_holds.find(search_id).filter(deleted_at.is_null()))
+            .set(deleted_at.eq(Some(Utc::now())))
+            .get_result(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    ApiError::not_found(format!("Legal hold with id {} not found", search_id))
+                }
+                _ => {
+                    error!(
+                        error_code = %ErrorCode::DatabaseError,
+                        error = %e,
+                        "Failed to release legal hold"
+                    );
+                    ApiError::database_error("Failed to release legal hold", None)
+                }
+            })
+    }
+
+    async fn list(&self, conn: &mut PgConnection, pagination: &PaginationParams) -> Result<Vec<LegalHold>> {
+        legal_holds
+            .filter(deleted_at.is_null())
+            .order_by((created_at.desc(), id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Database error occurred while listing legal holds"
+                );
+                ApiError::database_error("Failed to list legal holds", None)
+            })
+    }
+}
+
+#[async_trait]
+impl LegalHoldRepository for LegalHoldRepositoryImpl {
+    async fn find_active(
+        &self,
+        conn: &mut PgConnection,
+        entity: &str,
+        record_id: Uuid,
+    ) -> Result<Option<LegalHold>> {
+        legal_holds
+            .filter(entity_type.eq(entity))
+            .filter(entity_id.eq(record_id))
+            .filter(deleted_at.is_null())
+            .first(conn)
+            .optional()
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Failed to find legal hold for record"
+                );
+                ApiError::database_error("Failed to find legal hold", None)
+            })
+    }
+
+    async fn list_by_entity_type(
+        &self,
+        conn: &mut PgConnection,
+        entity: &str,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<LegalHold>> {
+        legal_holds
+            .filter(entity_type.eq(entity))
+            .filter(deleted_at.is_null())
+            .order_by((created_at.desc(), id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    entity_type = %entity,
+                    error = %e,
+                    "Database error occurred while listing legal holds"
+                );
+                ApiError::database_error("Failed to list legal holds", None)
+            })
+    }
+
+    async fn count(&self, conn: &mut PgConnection, entity: Option<&str>) -> Result<RowCount> {
+        let mut query = legal_holds.filter(deleted_at.is_null()).into_boxed();
+        if let Some(entity) = entity {
+            query = query.filter(entity_type.eq(entity));
+        }
+
+        query
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Failed to count legal holds"
+                );
+                ApiError::database_error("Failed to count legal holds", None)
+            })
+    }
+
+    async fn count_by_entity_type(&self, conn: &mut PgConnection) -> Result<Vec<(String, i64)>> {
+        legal_holds
+            .filter(deleted_at.is_null())
+            .group_by(entity_type)
+            .select((entity_type, diesel::dsl::count_star()))
+            .load(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Failed to count legal holds per entity type"
+                );
+                ApiError::database_error("Failed to count legal holds", None)
+            })
+    }
+}
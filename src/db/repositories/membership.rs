@@ -0,0 +1,97 @@
+use crate::{
+    db::{
+        models::auth::{Role, User},
+        schema::users,
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of the memberships of organizations
+///
+/// Both changes keep at least one active admin in the organization: its
+/// admins are locked while the change is checked, so two admins demoting
+/// each other at once can't leave it without one.
+#[async_trait]
+pub trait MembershipRepository: Send + Sync + 'static {
+    /// Gives member `user_id` of `organization` the role `role`; `None` if
+    /// they are its last active admin and `role` isn't admin
+    async fn change_role(&self, conn: &mut PgConnection, organization: Uuid, user_id: Uuid, role: Role) -> Result<Option<User>>;
+
+    /// Removes member `user_id` from `organization`; `None` if they are its
+    /// last active admin
+    async fn remove(&self, conn: &mut PgConnection, organization: Uuid, user_id: Uuid) -> Result<Option<User>>;
+}
+
+/// Concrete implementation of the membership repository
+pub struct MembershipRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    match e {
+        DieselError::NotFound => ApiError::not_found("Member not found"),
+        e => {
+            error!(
+                error_code = %ErrorCode::DatabaseError,
+                error = %e,
+                "Failed to {}",
+                action
+            );
+            ApiError::database_error(format!("Failed to {}", action), None)
+        }
+    }
+}
+
+/// Whether `user_id` is the only active admin of `organization`, locking
+/// its admins until the transaction ends
+fn is_last_admin(conn: &mut PgConnection, organization: Uuid, user_id: Uuid) -> QueryResult<bool> {
+    let admins: Vec<Uuid> = users::table
+        .scoped(organization)
+        .filter(users::role.eq(Role::Admin))
+        .filter(users::is_active.eq(true))
+        .filter(users::deleted_at.is_null())
+        .select(users::id)
+        .for_update()
+        .load(conn)?;
+    Ok(admins == [user_id])
+}
+
+#[async_trait]
+impl MembershipRepository for MembershipRepositoryImpl {
+    async fn change_role(&self, conn: &mut PgConnection, organization: Uuid, user_id: Uuid, role: Role) -> Result<Option<User>> {
+        conn.transaction::<_, DieselError, _>(|conn| {
+            if role != Role::Admin && is_last_admin(conn, organization, user_id)? {
+                return Ok(None);
+            }
+            diesel::update(users::table.scoped(organization))
+                .filter(users::id.eq(user_id))
+                .filter(users::deleted_at.is_null())
+                .set((users::role.eq(role), users::updated_at.eq(Utc::now())))
+                .returning(User::as_select())
+                .get_result(conn)
+                .map(Some)
+        })
+        .map_err(|e| database_error("change member role", e))
+    }
+
+    async fn remove(&self, conn: &mut PgConnection, organization: Uuid, user_id: Uuid) -> Result<Option<User>> {
+        conn.transaction::<_, DieselError, _>(|conn| {
+            if is_last_admin(conn, organization, user_id)? {
+                return Ok(None);
+            }
+            let now = Utc::now();
+            diesel::update(users::table.scoped(organization))
+                .filter(users::id.eq(user_id))
+                .filter(users::deleted_at.is_null())
+                .set((users::deleted_at.eq(Some(now)), users::updated_at.eq(now)))
+                .returning(User::as_select())
+                .get_result(conn)
+                .map(Some)
+        })
+        .map_err(|e| database_error("remove member", e))
+    }
+}
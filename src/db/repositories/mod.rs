@@ -6,7 +6,43 @@ use async_trait::async_trait;
 use diesel::PgConnection;
 use uuid::Uuid;
 
+pub mod activity;
+pub mod archive;
+pub mod certification;
+pub mod crew;
+pub mod customer;
+pub mod document;
+pub mod email_sender;
+pub mod erp;
+pub mod harvest_block;
+pub mod history;
+pub mod import;
+pub mod invitation;
+pub mod job_queue;
+pub mod legal_hold;
+pub mod membership;
+pub mod notification;
 pub mod organization;
+pub mod organization_archive;
+pub mod organization_settings;
+pub mod ownership_transfer;
+pub mod permission;
+pub mod preference;
+pub mod quota;
+pub mod report;
+pub mod report_schedule;
+pub mod role;
+pub mod saved_view;
+pub mod scheduled_job;
+pub mod scim;
+pub mod search;
+pub mod signoff;
+pub mod sso;
+pub mod stand;
+pub mod subscription;
+pub mod tag;
+pub mod telemetry;
+pub mod timber_sale;
 pub mod auth;
 
 /// Base repository trait for database operations
@@ -23,6 +59,12 @@ pub trait Repository<M>: Send + Sync + 'static {
     /// Finds a model by its unique identifier
     async fn find_by_id(&self, conn: &mut PgConnection, id: Uuid) -> Result<M>;
 
+    /// Finds all models matching the given identifiers in a single query
+    ///
+    /// Missing identifiers are skipped rather than reported as errors, so the
+    /// result may contain fewer models than requested.
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<M>>;
+
     /// Creates a new model in the database
     async fn create(&self, conn: &mut PgConnection, model: &M) -> Result<M>;
 
@@ -36,12 +78,58 @@ pub trait Repository<M>: Send + Sync + 'static {
     async fn list(&self, conn: &mut PgConnection, pagination: &PaginationParams) -> Result<Vec<M>>;
 }
 
+pub use activity::{ActivityRepository, ActivityRepositoryImpl};
+pub use archive::{ArchiveRepository, ArchiveRepositoryImpl};
+pub use certification::{CertificationRepository, CertificationRepositoryImpl};
+pub use crew::{CrewRepository, CrewRepositoryImpl};
+pub use customer::{CustomerRepository, CustomerRepositoryImpl};
+pub use document::{DocumentRepository, DocumentRepositoryImpl};
+pub use email_sender::{EmailSenderRepository, EmailSenderRepositoryImpl};
+pub use erp::{ErpRepository, ErpRepositoryImpl};
+pub use harvest_block::{HarvestBlockRepository, HarvestBlockRepositoryImpl};
+pub use history::{HistoryRepository, HistoryRepositoryImpl};
+pub use import::{ImportOutcome, ImportRepository, ImportRepositoryImpl};
+pub use invitation::{InvitationRepository, InvitationRepositoryImpl};
+pub use job_queue::{JobQueueRepository, JobQueueRepositoryImpl};
+pub use legal_hold::{LegalHoldRepository, LegalHoldRepositoryImpl};
+pub use membership::{MembershipRepository, MembershipRepositoryImpl};
+pub use notification::{NotificationRepository, NotificationRepositoryImpl};
 pub use organization::{OrganizationRepository, OrganizationRepositoryImpl};
+pub use organization_archive::{OrganizationArchiveRepository, OrganizationArchiveRepositoryImpl};
+pub use organization_settings::{OrganizationSettingsRepository, OrganizationSettingsRepositoryImpl};
+pub use ownership_transfer::{OwnershipTransferRepository, OwnershipTransferRepositoryImpl};
+pub use permission::{PermissionRepository, PermissionRepositoryImpl};
+pub use preference::{PreferenceRepository, PreferenceRepositoryImpl};
+pub use quota::{QuotaRepository, QuotaRepositoryImpl};
+pub use report::{ReportRepository, ReportRepositoryImpl};
+pub use report_schedule::{ReportScheduleRepository, ReportScheduleRepositoryImpl};
+pub use role::{RoleRepository, RoleRepositoryImpl};
+pub use saved_view::{SavedViewRepository, SavedViewRepositoryImpl};
+pub use scheduled_job::{ScheduledJobRepository, ScheduledJobRepositoryImpl};
+pub use scim::{ScimRepository, ScimRepositoryImpl};
+pub use search::{SearchRepository, SearchRepositoryImpl, SearchRow};
+pub use signoff::{SignoffRepository, SignoffRepositoryImpl};
+pub use sso::{SsoRepository, SsoRepositoryImpl};
+pub use stand::{upsert_stands, StandRepository, StandRepositoryImpl};
+pub use subscription::{SubscriptionRepository, SubscriptionRepositoryImpl};
+pub use tag::{TagRepository, TagRepositoryImpl};
+pub use telemetry::{TelemetryRepository, TelemetryRepositoryImpl};
+pub use timber_sale::{TimberSaleRepository, TimberSaleRepositoryImpl};
 pub use auth::{
     UserRepository,
     UserRepositoryImpl,
     RefreshTokenRepository,
     RefreshTokenRepositoryImpl,
     PasswordResetTokenRepository,
+    PasswordResetTokenRepositoryImpl,
     EmailVerificationTokenRepository,
+    EmailVerificationTokenRepositoryImpl,
+    EmailChangeTokenRepository,
+    EmailChangeTokenRepositoryImpl,
+    DeviceRepository,
+    DeviceRepositoryImpl,
+    AuthEventRepository,
+    AuthEventRepositoryImpl,
+    LoginHistory,
+    LoginOrigin,
 };
@@ -0,0 +1,116 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{count::RowCount, models::Notification, schema::notifications::dsl::*},
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of in-app notifications
+///
+/// All reads and updates are scoped to the recipient, so one user can never
+/// see or change another user's notifications.
+#[async_trait]
+pub trait NotificationRepository: Send + Sync + 'static {
+    /// Stores new notifications
+    async fn create_many(&self, conn: &mut PgConnection, notifications: &[Notification]) -> Result<Vec<Notification>>;
+
+    /// Lists a user's notifications, newest first
+    async fn list_for_user(
+        &self,
+        conn: &mut PgConnection,
+        recipient: Uuid,
+        unread_only: bool,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<Notification>>;
+
+    /// Counts a user's notifications
+    async fn count_for_user(&self, conn: &mut PgConnection, recipient: Uuid, unread_only: bool) -> Result<RowCount>;
+
+    /// Marks one of a user's notifications read, keeping an earlier read time
+    async fn mark_read(&self, conn: &mut PgConnection, recipient: Uuid, notification_id: Uuid) -> Result<Notification>;
+
+    /// Marks all of a user's notifications created up to `until` read,
+    /// returning how many were unread
+    async fn mark_all_read(&self, conn: &mut PgConnection, recipient: Uuid, until: DateTime<Utc>) -> Result<usize>;
+}
+
+/// Concrete implementation of the notification repository
+pub struct NotificationRepositoryImpl;
+
+fn database_error(action: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl NotificationRepository for NotificationRepositoryImpl {
+    async fn create_many(&self, conn: &mut PgConnection, new: &[Notification]) -> Result<Vec<Notification>> {
+        diesel::insert_into(notifications)
+            .values(new)
+            .get_results(conn)
+            .map_err(|e| database_error("create notifications", e))
+    }
+
+    async fn list_for_user(
+        &self,
+        conn: &mut PgConnection,
+        recipient: Uuid,
+        unread_only: bool,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<Notification>> {
+        let mut query = notifications.filter(user_id.eq(recipient)).into_boxed();
+        if unread_only {
+            query = query.filter(read_at.is_null());
+        }
+        query
+            .order_by((created_at.desc(), id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list notifications", e))
+    }
+
+    async fn count_for_user(&self, conn: &mut PgConnection, recipient: Uuid, unread_only: bool) -> Result<RowCount> {
+        let mut query = notifications.filter(user_id.eq(recipient)).into_boxed();
+        if unread_only {
+            query = query.filter(read_at.is_null());
+        }
+        query
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count notifications", e))
+    }
+
+    async fn mark_read(&self, conn: &mut PgConnection, recipient: Uuid, notification_id: Uuid) -> Result<Notification> {
+        diesel::update(notifications.find(notification_id).filter(user_id.eq(recipient)))
+            .set(read_at.eq(diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::Timestamptz>>(
+                "COALESCE(read_at, NOW())",
+            )))
+            .get_result(conn)
+            .optional()
+            .map_err(|e| database_error("mark notification read", e))?
+            .ok_or_else(|| ApiError::not_found(format!("Notification with id {} not found", notification_id)))
+    }
+
+    async fn mark_all_read(&self, conn: &mut PgConnection, recipient: Uuid, until: DateTime<Utc>) -> Result<usize> {
+        diesel::update(
+            notifications
+                .filter(user_id.eq(recipient))
+                .filter(read_at.is_null())
+                .filter(created_at.le(until)),
+        )
+        .set(read_at.eq(Utc::now()))
+        .execute(conn)
+        .map_err(|e| database_error("mark notifications read", e))
+    }
+}
@@ -1,15 +1,17 @@
 use crate::{
     api::utils::PaginationParams,
     db::{
-        models::Organization,
-        repositories::Repository,
-        schema::organizations::dsl::*,
+        count::{self, RowCount, DEFAULT_ESTIMATE_THRESHOLD},
+        hierarchy,
+        models::{Organization, OrganizationFilter, OrganizationSort},
+        repositories::{auth::contains_pattern, Repository},
+        schema::organizations::{self, dsl::*},
     },
     error::{ApiError, ErrorCode, ErrorContext, Result},
 };
 use async_trait::async_trait;
-use chrono::Utc;
-use diesel::prelude::*;
+use chrono::{DateTime, Utc};
+use diesel::{pg::Pg, prelude::*};
 use tracing::{error, warn};
 use uuid::Uuid;
 
@@ -31,11 +33,80 @@ pub trait OrganizationRepository: Repository<Organization> {
     /// 
     /// Returns Some(Organization) if found, None if not found
     async fn find_by_name(&self, conn: &mut PgConnection, name: &str) -> Result<Option<Organization>>;
+
+    /// Counts live organizations
+    /// 
+    /// Falls back to a planner estimate once the table grows beyond
+    /// `DEFAULT_ESTIMATE_THRESHOLD` rows.
+    async fn count(&self, conn: &mut PgConnection) -> Result<RowCount>;
+
+    /// Finds a live organization `tenant` governs, being `tenant` itself or
+    /// one somewhere under it
+    ///
+    /// Organizations of other tenants answer 404, like missing ones.
+    async fn find_governed(&self, conn: &mut PgConnection, tenant: Uuid, search_id: Uuid) -> Result<Organization>;
+
+    /// Pages through the organizations `tenant` governs matching `filter`,
+    /// live ones unless it includes deleted ones, with their count
+    ///
+    /// The count falls back to a planner estimate like [`Self::count`].
+    async fn search(
+        &self,
+        conn: &mut PgConnection,
+        tenant: Uuid,
+        filter: &OrganizationFilter,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<Organization>, RowCount)>;
+
+    /// Ids of the live organizations above an organization, its parent
+    /// first
+    async fn ancestor_ids(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<Vec<Uuid>>;
+
+    /// Ids of `root` and every organization under it, removed ones
+    /// included
+    async fn subtree_ids_with_removed(&self, conn: &mut PgConnection, root: Uuid) -> Result<Vec<Uuid>>;
+
+    /// `root` and the live organizations under it, with their depth below
+    /// `root`, parents before their children
+    async fn subtree(&self, conn: &mut PgConnection, root: &Organization) -> Result<Vec<(Organization, usize)>>;
+
+    /// Whether live organizations sit directly under an organization
+    async fn has_children(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<bool>;
+
+    /// Finds a soft-deleted organization by its id
+    async fn find_deleted(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<Option<Organization>>;
+
+    /// Clears the deletion of a soft-deleted organization
+    async fn restore(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<Organization>;
 }
 
 /// Concrete implementation of the organization repository
 pub struct OrganizationRepositoryImpl;
 
+/// Organizations among `governed` matching `filter`, unordered
+fn filtered<'a>(governed: &'a [Uuid], filter: &'a OrganizationFilter) -> organizations::BoxedQuery<'a, Pg> {
+    let mut query = organizations.filter(id.eq_any(governed)).into_boxed();
+    if !filter.include_deleted {
+        query = query.filter(deleted_at.is_null());
+    }
+    if let Some(text) = filter.name_contains.as_deref() {
+        query = query.filter(name.ilike(contains_pattern(text)));
+    }
+    if let Some(after) = filter.created_after {
+        query = query.filter(created_at.gt(after));
+    }
+    query
+}
+
+fn hierarchy_error(e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Database error occurred while walking the organization hierarchy"
+    );
+    ApiError::database_error("Failed to walk the organization hierarchy", None)
+}
+
 #[async_trait]
 impl Repository<Organization> for OrganizationRepositoryImpl {
     async fn find_by_id(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<Organization> {
@@ -66,6 +137,23 @@ impl Repository<Organization> for OrganizationRepositoryImpl {
             })
     }
 
+    async fn find_by_ids(&self, conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<Organization>> {
+        organizations
+            .filter(id.eq_any(ids))
+            .filter(deleted_at.is_null())
+            .load(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Database error occurred while batch loading organizations"
+                );
+                ApiError::database_error("Failed to find organizations", Some(serde_json::json!({
+                    "error": e.to_string()
+                })))
+            })
+    }
+
     async fn create(&self, conn: &mut PgConnection, org: &Organization) -> Result<Organization> {
         diesel::insert_into(organizations)
             .values(org)
@@ -177,4 +265,130 @@ impl OrganizationRepository for OrganizationRepositoryImpl {
                 ApiError::database_error("Failed to find organization by name", None)
             })
     }
-} 
\ No newline at end of file
+
+    async fn count(&self, conn: &mut PgConnection) -> Result<RowCount> {
+        count::count_with_estimate(
+            conn,
+            organizations.filter(deleted_at.is_null()),
+            DEFAULT_ESTIMATE_THRESHOLD,
+            |conn| organizations.filter(deleted_at.is_null()).count().get_result(conn),
+        )
+    }
+
+    async fn find_governed(&self, conn: &mut PgConnection, tenant: Uuid, search_id: Uuid) -> Result<Organization> {
+        let organization = self.find_by_id(conn, search_id).await?;
+        if organization.id != tenant && !self.ancestor_ids(conn, organization.id).await?.contains(&tenant) {
+            return Err(ApiError::not_found(format!("Organization with id {} not found", search_id)));
+        }
+        Ok(organization)
+    }
+
+    async fn search(
+        &self,
+        conn: &mut PgConnection,
+        tenant: Uuid,
+        filter: &OrganizationFilter,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<Organization>, RowCount)> {
+        let governed = if filter.include_deleted {
+            hierarchy::subtree_ids_with_removed(conn, tenant)
+        } else {
+            hierarchy::subtree_ids(conn, tenant)
+        }
+        .map_err(hierarchy_error)?;
+        let total = count::count_with_estimate(
+            conn,
+            filtered(&governed, filter),
+            DEFAULT_ESTIMATE_THRESHOLD,
+            |conn| filtered(&governed, filter).count().get_result(conn),
+        )?;
+
+        let query = match (filter.sort, filter.descending) {
+            (OrganizationSort::Name, false) => filtered(&governed, filter).order_by(name.asc()),
+            (OrganizationSort::Name, true) => filtered(&governed, filter).order_by(name.desc()),
+            (OrganizationSort::CreatedAt, false) => filtered(&governed, filter).order_by(created_at.asc()),
+            (OrganizationSort::CreatedAt, true) => filtered(&governed, filter).order_by(created_at.desc()),
+            (OrganizationSort::UpdatedAt, false) => filtered(&governed, filter).order_by(updated_at.asc()),
+            (OrganizationSort::UpdatedAt, true) => filtered(&governed, filter).order_by(updated_at.desc()),
+        };
+        let found = query
+            .then_order_by(id.asc())
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    error = %e,
+                    "Database error occurred while searching organizations"
+                );
+                ApiError::database_error("Failed to list organizations", None)
+            })?;
+        Ok((found, total))
+    }
+
+    async fn ancestor_ids(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<Vec<Uuid>> {
+        hierarchy::ancestor_ids(conn, search_id).map_err(hierarchy_error)
+    }
+
+    async fn subtree_ids_with_removed(&self, conn: &mut PgConnection, root: Uuid) -> Result<Vec<Uuid>> {
+        hierarchy::subtree_ids_with_removed(conn, root).map_err(hierarchy_error)
+    }
+
+    async fn subtree(&self, conn: &mut PgConnection, root: &Organization) -> Result<Vec<(Organization, usize)>> {
+        hierarchy::subtree(conn, root).map_err(hierarchy_error)
+    }
+
+    async fn has_children(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<bool> {
+        diesel::select(diesel::dsl::exists(
+            organizations.filter(parent_id.eq(search_id)).filter(deleted_at.is_null()),
+        ))
+        .get_result(conn)
+        .map_err(hierarchy_error)
+    }
+
+    async fn find_deleted(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<Option<Organization>> {
+        organizations
+            .find(search_id)
+            .filter(deleted_at.is_not_null())
+            .first(conn)
+            .optional()
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    organization_id = %search_id,
+                    error = %e,
+                    "Database error occurred while finding deleted organization"
+                );
+                ApiError::database_error("Failed to find organization", None)
+            })
+    }
+
+    async fn restore(&self, conn: &mut PgConnection, search_id: Uuid) -> Result<Organization> {
+        diesel::update(organizations.find(search_id).filter(deleted_at.is_not_null()))
+            .set((deleted_at.eq(None::<DateTime<Utc>>), updated_at.eq(Utc::now())))
+            .get_result(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    ApiError::not_found(format!("Deleted organization with id {} not found", search_id))
+                }
+                diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                    ApiError::validation_with_context(
+                        "Organization name already exists",
+                        ErrorContext::new().with_details(serde_json::json!({
+                            "field": "name",
+                            "code": "DUPLICATE"
+                        }))
+                    )
+                }
+                _ => {
+                    error!(
+                        error_code = %ErrorCode::DatabaseError,
+                        error = %e,
+                        "Failed to restore organization"
+                    );
+                    ApiError::database_error("Failed to restore organization", None)
+                }
+            })
+    }
+}
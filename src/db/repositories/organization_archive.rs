@@ -0,0 +1,170 @@
+use crate::{
+    db::{
+        models::{OrganizationArchive, OrganizationArchiveStatus},
+        schema::{organization_archives, organizations},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of organization archivals and of the freeze they put on
+/// organizations
+#[async_trait]
+pub trait OrganizationArchiveRepository: Send + Sync + 'static {
+    /// Freezes an organization and the organizations under it and records
+    /// the archival, `None` when the organization is already archived
+    async fn archive(&self, conn: &mut PgConnection, archive: &OrganizationArchive, below: &[Uuid]) -> Result<Option<OrganizationArchive>>;
+
+    /// Lifts the freeze of an organization's archival, from it and from the
+    /// organizations under it that were frozen with it; `None` when the
+    /// organization isn't archived
+    async fn unarchive(&self, conn: &mut PgConnection, organization: Uuid, below: &[Uuid]) -> Result<Option<OrganizationArchive>>;
+
+    /// Whether an organization is archived, on its own or with one above it
+    async fn is_archived(&self, conn: &mut PgConnection, organization: Uuid) -> Result<bool>;
+
+    /// The most recent archival of an organization
+    async fn latest(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Option<OrganizationArchive>>;
+
+    /// An archival by id
+    async fn find(&self, conn: &mut PgConnection, archive_id: Uuid) -> Result<Option<OrganizationArchive>>;
+
+    /// Records the written export bundle of an archival
+    async fn complete(&self, conn: &mut PgConnection, archive_id: Uuid, file_key: &str, byte_size: i64) -> Result<()>;
+
+    /// Records why the export bundle of an archival couldn't be written
+    async fn fail(&self, conn: &mut PgConnection, archive_id: Uuid, error: &str) -> Result<()>;
+}
+
+/// Concrete implementation of the organization archive repository
+pub struct OrganizationArchiveRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl OrganizationArchiveRepository for OrganizationArchiveRepositoryImpl {
+    async fn archive(&self, conn: &mut PgConnection, archive: &OrganizationArchive, below: &[Uuid]) -> Result<Option<OrganizationArchive>> {
+        conn.transaction::<_, DieselError, _>(|conn| {
+            let frozen = diesel::update(
+                organizations::table
+                    .find(archive.org_id)
+                    .filter(organizations::archived_at.is_null()),
+            )
+            .set(organizations::archived_at.eq(archive.created_at))
+            .execute(conn)?;
+            if frozen == 0 {
+                return Ok(None);
+            }
+            // Organizations archived on their own before keep that archival
+            diesel::update(
+                organizations::table
+                    .filter(organizations::id.eq_any(below))
+                    .filter(organizations::archived_at.is_null()),
+            )
+            .set(organizations::archived_at.eq(archive.created_at))
+            .execute(conn)?;
+            diesel::insert_into(organization_archives::table)
+                .values(archive)
+                .returning(OrganizationArchive::as_select())
+                .get_result(conn)
+                .map(Some)
+        })
+        .map_err(|e| database_error("archive organization", e))
+    }
+
+    async fn unarchive(&self, conn: &mut PgConnection, organization: Uuid, below: &[Uuid]) -> Result<Option<OrganizationArchive>> {
+        conn.transaction::<_, DieselError, _>(|conn| {
+            let archived_at: Option<DateTime<Utc>> = organizations::table
+                .find(organization)
+                .select(organizations::archived_at)
+                .first(conn)?;
+            let Some(archived_at) = archived_at else {
+                return Ok(None);
+            };
+            diesel::update(
+                organizations::table
+                    .filter(organizations::id.eq(organization).or(organizations::id.eq_any(below)))
+                    .filter(organizations::archived_at.eq(archived_at)),
+            )
+            .set(organizations::archived_at.eq(None::<DateTime<Utc>>))
+            .execute(conn)?;
+            diesel::update(
+                organization_archives::table
+                    .scoped(organization)
+                    .filter(organization_archives::unarchived_at.is_null()),
+            )
+            .set(organization_archives::unarchived_at.eq(Utc::now()))
+            .returning(OrganizationArchive::as_select())
+            .get_result(conn)
+            .optional()
+        })
+        .map_err(|e| database_error("unarchive organization", e))
+    }
+
+    async fn is_archived(&self, conn: &mut PgConnection, organization: Uuid) -> Result<bool> {
+        organizations::table
+            .find(organization)
+            .select(organizations::archived_at.is_not_null())
+            .first(conn)
+            .optional()
+            .map(|archived| archived.unwrap_or(false))
+            .map_err(|e| database_error("check organization archival", e))
+    }
+
+    async fn latest(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Option<OrganizationArchive>> {
+        organization_archives::table
+            .scoped(organization)
+            .order_by(organization_archives::created_at.desc())
+            .select(OrganizationArchive::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find organization archive", e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, archive_id: Uuid) -> Result<Option<OrganizationArchive>> {
+        organization_archives::table
+            .find(archive_id)
+            .select(OrganizationArchive::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find organization archive", e))
+    }
+
+    async fn complete(&self, conn: &mut PgConnection, archive_id: Uuid, file_key: &str, byte_size: i64) -> Result<()> {
+        diesel::update(organization_archives::table.find(archive_id))
+            .set((
+                organization_archives::status.eq(OrganizationArchiveStatus::Completed.as_str()),
+                organization_archives::file_key.eq(file_key),
+                organization_archives::byte_size.eq(byte_size),
+                organization_archives::error.eq(None::<String>),
+                organization_archives::completed_at.eq(Utc::now()),
+            ))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("record organization export", e))
+    }
+
+    async fn fail(&self, conn: &mut PgConnection, archive_id: Uuid, error: &str) -> Result<()> {
+        diesel::update(organization_archives::table.find(archive_id))
+            .set((
+                organization_archives::status.eq(OrganizationArchiveStatus::Failed.as_str()),
+                organization_archives::error.eq(error),
+            ))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("record organization export failure", e))
+    }
+}
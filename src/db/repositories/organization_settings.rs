@@ -0,0 +1,58 @@
+use crate::{
+    db::{models::OrganizationSettings, schema::organization_settings},
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of organizations' settings
+#[async_trait]
+pub trait OrganizationSettingsRepository: Send + Sync + 'static {
+    /// An organization's saved settings, `None` when it never saved any
+    async fn find(&self, conn: &mut PgConnection, org_id: Uuid) -> Result<Option<OrganizationSettings>>;
+
+    /// Saves an organization's settings, replacing those saved before
+    async fn save(&self, conn: &mut PgConnection, settings: &OrganizationSettings) -> Result<OrganizationSettings>;
+}
+
+/// Concrete implementation of the organization settings repository
+pub struct OrganizationSettingsRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl OrganizationSettingsRepository for OrganizationSettingsRepositoryImpl {
+    async fn find(&self, conn: &mut PgConnection, org_id: Uuid) -> Result<Option<OrganizationSettings>> {
+        organization_settings::table
+            .find(org_id)
+            .select(OrganizationSettings::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find organization settings", e))
+    }
+
+    async fn save(&self, conn: &mut PgConnection, settings: &OrganizationSettings) -> Result<OrganizationSettings> {
+        diesel::insert_into(organization_settings::table)
+            .values(settings)
+            .on_conflict(organization_settings::org_id)
+            .do_update()
+            .set((
+                organization_settings::settings.eq(&settings.settings),
+                organization_settings::updated_by.eq(settings.updated_by),
+                organization_settings::updated_at.eq(settings.updated_at),
+            ))
+            .returning(OrganizationSettings::as_select())
+            .get_result(conn)
+            .map_err(|e| database_error("save organization settings", e))
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    db::{
+        models::{auth::Role, OwnershipTransfer},
+        schema::{organizations, ownership_transfers, users},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of organization ownership transfers
+#[async_trait]
+pub trait OwnershipTransferRepository: Send + Sync + 'static {
+    /// Stores a new transfer, cancelling the one pending for the same
+    /// organization
+    async fn create(&self, conn: &mut PgConnection, transfer: &OwnershipTransfer) -> Result<OwnershipTransfer>;
+
+    /// The transfer of an organization neither accepted nor cancelled,
+    /// expired ones included
+    async fn find_pending(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Option<OwnershipTransfer>>;
+
+    /// Accepts the transfer with `id` if it is still pending and unexpired,
+    /// making its recipient the owning admin and demoting the previous
+    /// owner to manager, all or nothing; `None` if it can't be accepted
+    async fn accept(&self, conn: &mut PgConnection, id: Uuid) -> Result<Option<OwnershipTransfer>>;
+}
+
+/// Concrete implementation of the ownership transfer repository
+pub struct OwnershipTransferRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl OwnershipTransferRepository for OwnershipTransferRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, transfer: &OwnershipTransfer) -> Result<OwnershipTransfer> {
+        conn.transaction::<_, DieselError, _>(|conn| {
+            let now = Utc::now();
+            diesel::update(ownership_transfers::table.scoped(transfer.org_id))
+                .filter(ownership_transfers::accepted_at.is_null())
+                .filter(ownership_transfers::deleted_at.is_null())
+                .set((ownership_transfers::deleted_at.eq(Some(now)), ownership_transfers::updated_at.eq(now)))
+                .execute(conn)?;
+            diesel::insert_into(ownership_transfers::table)
+                .values(transfer)
+                .returning(OwnershipTransfer::as_select())
+                .get_result(conn)
+        })
+        .map_err(|e| database_error("create ownership transfer", e))
+    }
+
+    async fn find_pending(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Option<OwnershipTransfer>> {
+        ownership_transfers::table
+            .scoped(organization)
+            .filter(ownership_transfers::accepted_at.is_null())
+            .filter(ownership_transfers::deleted_at.is_null())
+            .select(OwnershipTransfer::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find ownership transfer", e))
+    }
+
+    async fn accept(&self, conn: &mut PgConnection, id: Uuid) -> Result<Option<OwnershipTransfer>> {
+        conn.transaction::<_, DieselError, _>(|conn| {
+            let now = Utc::now();
+            // A conditional update, so a transfer can't be accepted twice by
+            // concurrent requests
+            let Some(transfer) = diesel::update(ownership_transfers::table)
+                .filter(ownership_transfers::id.eq(id))
+                .filter(ownership_transfers::accepted_at.is_null())
+                .filter(ownership_transfers::deleted_at.is_null())
+                .filter(ownership_transfers::expires_at.gt(now))
+                .set((ownership_transfers::accepted_at.eq(Some(now)), ownership_transfers::updated_at.eq(now)))
+                .returning(OwnershipTransfer::as_select())
+                .get_result(conn)
+                .optional()?
+            else {
+                return Ok(None);
+            };
+            diesel::update(organizations::table.find(transfer.org_id))
+                .set((organizations::owner_id.eq(Some(transfer.to_user_id)), organizations::updated_at.eq(now)))
+                .execute(conn)?;
+            diesel::update(users::table.find(transfer.to_user_id))
+                .set((users::role.eq(Role::Admin), users::updated_at.eq(now)))
+                .execute(conn)?;
+            diesel::update(users::table.find(transfer.from_user_id))
+                .set((users::role.eq(Role::Manager), users::updated_at.eq(now)))
+                .execute(conn)?;
+            Ok(Some(transfer))
+        })
+        .map_err(|e| database_error("accept ownership transfer", e))
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    db::{
+        models::{auth::Role, RolePermission},
+        schema::role_permissions,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+
+/// Persistence of the permissions granted to roles
+#[async_trait]
+pub trait PermissionRepository: Send + Sync + 'static {
+    /// Lists every grant, by role and permission
+    async fn list(&self, conn: &mut PgConnection) -> Result<Vec<RolePermission>>;
+
+    /// Replaces the permissions of a role, returning its new grants
+    async fn replace_for_role(&self, conn: &mut PgConnection, role: Role, permissions: &[String]) -> Result<Vec<RolePermission>>;
+}
+
+/// Concrete implementation of the permission repository
+pub struct PermissionRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl PermissionRepository for PermissionRepositoryImpl {
+    async fn list(&self, conn: &mut PgConnection) -> Result<Vec<RolePermission>> {
+        role_permissions::table
+            .order_by((role_permissions::role.asc(), role_permissions::permission.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list role permissions", e))
+    }
+
+    async fn replace_for_role(&self, conn: &mut PgConnection, role: Role, permissions: &[String]) -> Result<Vec<RolePermission>> {
+        let now = Utc::now();
+        let grants: Vec<RolePermission> = permissions
+            .iter()
+            .map(|permission| RolePermission {
+                role,
+                permission: permission.clone(),
+                created_at: now,
+            })
+            .collect();
+
+        conn.transaction(|conn| {
+            diesel::delete(role_permissions::table.filter(role_permissions::role.eq(role))).execute(conn)?;
+            if !grants.is_empty() {
+                diesel::insert_into(role_permissions::table)
+                    .values(&grants)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+            }
+            role_permissions::table
+                .filter(role_permissions::role.eq(role))
+                .order_by(role_permissions::permission.asc())
+                .load(conn)
+        })
+        .map_err(|e| database_error("replace role permissions", e))
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    db::{models::UserPreferences, schema::user_preferences},
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of users' preferences
+#[async_trait]
+pub trait PreferenceRepository: Send + Sync + 'static {
+    /// A user's saved preferences, `None` when they never saved any
+    async fn find(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<Option<UserPreferences>>;
+
+    /// Saves a user's preferences, replacing those saved before
+    async fn save(&self, conn: &mut PgConnection, preferences: &UserPreferences) -> Result<UserPreferences>;
+}
+
+/// Concrete implementation of the preference repository
+pub struct PreferenceRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl PreferenceRepository for PreferenceRepositoryImpl {
+    async fn find(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<Option<UserPreferences>> {
+        user_preferences::table
+            .find(user_id)
+            .select(UserPreferences::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find preferences", e))
+    }
+
+    async fn save(&self, conn: &mut PgConnection, preferences: &UserPreferences) -> Result<UserPreferences> {
+        diesel::insert_into(user_preferences::table)
+            .values(preferences)
+            .on_conflict(user_preferences::user_id)
+            .do_update()
+            .set((
+                user_preferences::units.eq(&preferences.units),
+                user_preferences::timezone.eq(&preferences.timezone),
+                user_preferences::locale.eq(&preferences.locale),
+                user_preferences::updated_at.eq(preferences.updated_at),
+            ))
+            .returning(UserPreferences::as_select())
+            .get_result(conn)
+            .map_err(|e| database_error("save preferences", e))
+    }
+}
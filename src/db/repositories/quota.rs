@@ -0,0 +1,76 @@
+use crate::{
+    db::{
+        models::OrganizationQuota,
+        schema::{organization_quotas, users},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of organization quotas and the usage they limit
+#[async_trait]
+pub trait QuotaRepository: Send + Sync + 'static {
+    /// The quotas of an organization, `None` when none were set
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Option<OrganizationQuota>>;
+
+    /// Creates or replaces the quotas of an organization
+    async fn save(&self, conn: &mut PgConnection, quota: &OrganizationQuota) -> Result<OrganizationQuota>;
+
+    /// How many users an organization has, deactivated ones included and
+    /// removed ones not
+    async fn count_users(&self, conn: &mut PgConnection, organization: Uuid) -> Result<i64>;
+}
+
+/// Concrete implementation of the quota repository
+pub struct QuotaRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl QuotaRepository for QuotaRepositoryImpl {
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Option<OrganizationQuota>> {
+        organization_quotas::table
+            .find(organization)
+            .select(OrganizationQuota::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find quotas", e))
+    }
+
+    async fn save(&self, conn: &mut PgConnection, quota: &OrganizationQuota) -> Result<OrganizationQuota> {
+        diesel::insert_into(organization_quotas::table)
+            .values(quota)
+            .on_conflict(organization_quotas::org_id)
+            .do_update()
+            .set((
+                organization_quotas::max_users.eq(quota.max_users),
+                organization_quotas::max_active_blocks.eq(quota.max_active_blocks),
+                organization_quotas::telemetry_retention_days.eq(quota.telemetry_retention_days),
+                organization_quotas::updated_at.eq(quota.updated_at),
+            ))
+            .returning(OrganizationQuota::as_select())
+            .get_result(conn)
+            .map_err(|e| database_error("save quotas", e))
+    }
+
+    async fn count_users(&self, conn: &mut PgConnection, organization: Uuid) -> Result<i64> {
+        users::table
+            .scoped(organization)
+            .filter(users::deleted_at.is_null())
+            .count()
+            .get_result(conn)
+            .map_err(|e| database_error("count users", e))
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{count::RowCount, models::Report, schema::reports::dsl::*},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of saved report definitions
+///
+/// All reads and updates are scoped to an organization, so one organization
+/// can never see or change another organization's reports.
+#[async_trait]
+pub trait ReportRepository: Send + Sync + 'static {
+    /// Stores a new report
+    async fn create(&self, conn: &mut PgConnection, report: &Report) -> Result<Report>;
+
+    /// Finds one of an organization's reports
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, report_id: Uuid) -> Result<Report>;
+
+    /// Lists an organization's reports by name
+    async fn list_for_org(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<Report>>;
+
+    /// Counts an organization's reports
+    async fn count_for_org(&self, conn: &mut PgConnection, organization: Uuid) -> Result<RowCount>;
+
+    /// Replaces the name, description and definition of a report
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, report: &Report) -> Result<Report>;
+
+    /// Soft deletes one of an organization's reports
+    async fn soft_delete(&self, conn: &mut PgConnection, organization: Uuid, report_id: Uuid) -> Result<Report>;
+}
+
+/// Concrete implementation of the report repository
+pub struct ReportRepositoryImpl;
+
+fn database_error(action: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn not_found(report_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Report with id {} not found", report_id))
+}
+
+/// Maps a write error, reporting a name taken by another report as a conflict
+fn write_error(action: &str, report: &Report, e: diesel::result::Error) -> ApiError {
+    match e {
+        diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+            ApiError::new(
+                ErrorCode::Conflict,
+                "A report with this name already exists",
+                ErrorContext::new().with_details(serde_json::json!({ "name": report.name })),
+            )
+        }
+        diesel::result::Error::NotFound => not_found(report.id),
+        e => database_error(action, e),
+    }
+}
+
+#[async_trait]
+impl ReportRepository for ReportRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, report: &Report) -> Result<Report> {
+        diesel::insert_into(reports)
+            .values(report)
+            .get_result(conn)
+            .map_err(|e| write_error("create report", report, e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, report_id: Uuid) -> Result<Report> {
+        reports
+            .find(report_id)
+            .filter(org_id.eq(organization))
+            .filter(deleted_at.is_null())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find report", e))?
+            .ok_or_else(|| not_found(report_id))
+    }
+
+    async fn list_for_org(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<Report>> {
+        reports
+            .filter(org_id.eq(organization))
+            .filter(deleted_at.is_null())
+            .order_by((name.asc(), id.asc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list reports", e))
+    }
+
+    async fn count_for_org(&self, conn: &mut PgConnection, organization: Uuid) -> Result<RowCount> {
+        reports
+            .filter(org_id.eq(organization))
+            .filter(deleted_at.is_null())
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count reports", e))
+    }
+
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, report: &Report) -> Result<Report> {
+        diesel::update(
+            reports
+                .find(report.id)
+                .filter(org_id.eq(organization))
+                .filter(deleted_at.is_null()),
+        )
+        .set((
+            name.eq(&report.name),
+            description.eq(&report.description),
+            definition.eq(&report.definition),
+            updated_at.eq(Utc::now()),
+        ))
+        .get_result(conn)
+        .map_err(|e| write_error("update report", report, e))
+    }
+
+    async fn soft_delete(&self, conn: &mut PgConnection, organization: Uuid, report_id: Uuid) -> Result<Report> {
+        diesel::update(
+            reports
+                .find(report_id)
+                .filter(org_id.eq(organization))
+                .filter(deleted_at.is_null()),
+        )
+        .set(deleted_at.eq(Some(Utc::now())))
+        .get_result(conn)
+        .optional()
+        .map_err(|e| database_error("delete report", e))?
+        .ok_or_else(|| not_found(report_id))
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        count::RowCount,
+        models::{ReportDelivery, ReportSchedule},
+        schema::{report_deliveries, report_schedules, reports},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of report schedules and their delivery history
+///
+/// Reads are scoped to an organization, except for [`due`], which the
+/// delivery job uses across all organizations.
+///
+/// [`due`]: ReportScheduleRepository::due
+#[async_trait]
+pub trait ReportScheduleRepository: Send + Sync + 'static {
+    /// Stores the schedule of a report, replacing the existing one
+    async fn upsert(&self, conn: &mut PgConnection, schedule: &ReportSchedule) -> Result<ReportSchedule>;
+
+    /// Finds the schedule of one of an organization's reports
+    async fn find_for_report(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        report_id: Uuid,
+    ) -> Result<Option<ReportSchedule>>;
+
+    /// Removes the schedule of a report, returning whether it had one
+    async fn delete_for_report(&self, conn: &mut PgConnection, organization: Uuid, report_id: Uuid) -> Result<bool>;
+
+    /// Schedules due at `now` whose report still exists, oldest first
+    async fn due(&self, conn: &mut PgConnection, now: DateTime<Utc>, limit: i64) -> Result<Vec<ReportSchedule>>;
+
+    /// Moves a schedule to its next run
+    async fn set_next_run(&self, conn: &mut PgConnection, schedule_id: Uuid, next_run_at: DateTime<Utc>) -> Result<()>;
+
+    /// Records a scheduled run
+    async fn create_delivery(&self, conn: &mut PgConnection, delivery: &ReportDelivery) -> Result<ReportDelivery>;
+
+    /// Lists the deliveries of a report, newest first
+    async fn list_deliveries(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        report_id: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<ReportDelivery>>;
+
+    /// Counts the deliveries of a report
+    async fn count_deliveries(&self, conn: &mut PgConnection, organization: Uuid, report_id: Uuid) -> Result<RowCount>;
+
+    /// Finds one delivery of a report
+    async fn find_delivery(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        report_id: Uuid,
+        delivery_id: Uuid,
+    ) -> Result<ReportDelivery>;
+}
+
+/// Concrete implementation of the report schedule repository
+pub struct ReportScheduleRepositoryImpl;
+
+fn database_error(action: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl ReportScheduleRepository for ReportScheduleRepositoryImpl {
+    async fn upsert(&self, conn: &mut PgConnection, schedule: &ReportSchedule) -> Result<ReportSchedule> {
+        diesel::insert_into(report_schedules::table)
+            .values(schedule)
+            .on_conflict(report_schedules::report_id)
+            .do_update()
+            .set((
+                report_schedules::schedule.eq(&schedule.schedule),
+                report_schedules::recipients.eq(&schedule.recipients),
+                report_schedules::format.eq(&schedule.format),
+                report_schedules::parameters.eq(&schedule.parameters),
+                report_schedules::next_run_at.eq(schedule.next_run_at),
+                report_schedules::updated_at.eq(schedule.updated_at),
+            ))
+            .get_result(conn)
+            .map_err(|e| database_error("save report schedule", e))
+    }
+
+    async fn find_for_report(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        report_id: Uuid,
+    ) -> Result<Option<ReportSchedule>> {
+        report_schedules::table
+            .scoped(organization)
+            .filter(report_schedules::report_id.eq(report_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find report schedule", e))
+    }
+
+    async fn delete_for_report(&self, conn: &mut PgConnection, organization: Uuid, report_id: Uuid) -> Result<bool> {
+        diesel::delete(
+            report_schedules::table
+                .scoped(organization)
+                .filter(report_schedules::report_id.eq(report_id)),
+        )
+        .execute(conn)
+        .map(|deleted| deleted > 0)
+        .map_err(|e| database_error("delete report schedule", e))
+    }
+
+    async fn due(&self, conn: &mut PgConnection, now: DateTime<Utc>, limit: i64) -> Result<Vec<ReportSchedule>> {
+        report_schedules::table
+            .inner_join(reports::table)
+            .filter(report_schedules::next_run_at.le(now))
+            .filter(reports::deleted_at.is_null())
+            .order_by((report_schedules::next_run_at.asc(), report_schedules::id.asc()))
+            .limit(limit)
+            .select(ReportSchedule::as_select())
+            .load(conn)
+            .map_err(|e| database_error("find due report schedules", e))
+    }
+
+    async fn set_next_run(&self, conn: &mut PgConnection, schedule_id: Uuid, next_run_at: DateTime<Utc>) -> Result<()> {
+        diesel::update(report_schedules::table.find(schedule_id))
+            .set(report_schedules::next_run_at.eq(next_run_at))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("reschedule report", e))
+    }
+
+    async fn create_delivery(&self, conn: &mut PgConnection, delivery: &ReportDelivery) -> Result<ReportDelivery> {
+        diesel::insert_into(report_deliveries::table)
+            .values(delivery)
+            .get_result(conn)
+            .map_err(|e| database_error("record report delivery", e))
+    }
+
+    async fn list_deliveries(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        report_id: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<ReportDelivery>> {
+        report_deliveries::table
+            .scoped(organization)
+            .filter(report_deliveries::report_id.eq(report_id))
+            .order_by((report_deliveries::created_at.desc(), report_deliveries::id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list report deliveries", e))
+    }
+
+    async fn count_deliveries(&self, conn: &mut PgConnection, organization: Uuid, report_id: Uuid) -> Result<RowCount> {
+        report_deliveries::table
+            .scoped(organization)
+            .filter(report_deliveries::report_id.eq(report_id))
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count report deliveries", e))
+    }
+
+    async fn find_delivery(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        report_id: Uuid,
+        delivery_id: Uuid,
+    ) -> Result<ReportDelivery> {
+        report_deliveries::table
+            .scoped(organization)
+            .filter(report_deliveries::id.eq(delivery_id))
+            .filter(report_deliveries::report_id.eq(report_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find report delivery", e))?
+            .ok_or_else(|| ApiError::not_found(format!("Report delivery with id {} not found", delivery_id)))
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{
+    db::{
+        models::{CustomRole, RoleAssignment},
+        schema::{roles, user_roles},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{
+    prelude::*,
+    result::{DatabaseErrorKind, Error as DieselError},
+};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of custom roles and their assignments
+///
+/// Reads and writes of roles are scoped to an organization.
+#[async_trait]
+pub trait RoleRepository: Send + Sync + 'static {
+    /// Stores a new role
+    async fn create(&self, conn: &mut PgConnection, role: &CustomRole) -> Result<CustomRole>;
+
+    /// Finds one of an organization's roles
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, role_id: Uuid) -> Result<CustomRole>;
+
+    /// Lists an organization's roles by name
+    async fn list(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<CustomRole>>;
+
+    /// Replaces the name, description and permissions of a role
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, role: &CustomRole) -> Result<CustomRole>;
+
+    /// Deletes a role, unassigning it from everyone
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, role_id: Uuid) -> Result<()>;
+
+    /// Assigns a role, `false` when it was already assigned
+    async fn assign(&self, conn: &mut PgConnection, assignment: &RoleAssignment) -> Result<bool>;
+
+    /// Unassigns a role, `false` when it was not assigned
+    async fn unassign(&self, conn: &mut PgConnection, role_id: Uuid, user_id: Uuid) -> Result<bool>;
+
+    /// The assignments of an organization's roles, by role and when they
+    /// were made
+    async fn assignments(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<RoleAssignment>>;
+
+    /// Every user holding a custom role, with the role
+    async fn members(&self, conn: &mut PgConnection) -> Result<Vec<(Uuid, CustomRole)>>;
+}
+
+/// Concrete implementation of the role repository
+pub struct RoleRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn not_found(role_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Role with id {} not found", role_id))
+}
+
+/// Maps a role write error, reporting a name taken by another role as a
+/// conflict
+fn write_error(action: &str, role: &CustomRole, e: DieselError) -> ApiError {
+    match e {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => ApiError::new(
+            ErrorCode::Conflict,
+            "A role with this name already exists",
+            ErrorContext::new().with_details(serde_json::json!({ "name": role.name })),
+        ),
+        DieselError::NotFound => not_found(role.id),
+        e => database_error(action, e),
+    }
+}
+
+#[async_trait]
+impl RoleRepository for RoleRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, role: &CustomRole) -> Result<CustomRole> {
+        // In a savepoint, so a taken name leaves the caller's transaction usable
+        conn.transaction(|conn| {
+            diesel::insert_into(roles::table)
+                .values(role)
+                .get_result(conn)
+        })
+        .map_err(|e| write_error("create role", role, e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, role_id: Uuid) -> Result<CustomRole> {
+        roles::table
+            .scoped(organization)
+            .filter(roles::id.eq(role_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find role", e))?
+            .ok_or_else(|| not_found(role_id))
+    }
+
+    async fn list(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<CustomRole>> {
+        roles::table
+            .scoped(organization)
+            .order_by((roles::name.asc(), roles::id.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list roles", e))
+    }
+
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, role: &CustomRole) -> Result<CustomRole> {
+        conn.transaction(|conn| {
+            diesel::update(roles::table.scoped(organization).filter(roles::id.eq(role.id)))
+                .set((
+                    roles::name.eq(&role.name),
+                    roles::description.eq(&role.description),
+                    roles::permissions.eq(&role.permissions),
+                    roles::updated_at.eq(Utc::now()),
+                ))
+                .get_result(conn)
+        })
+        .map_err(|e| write_error("update role", role, e))
+    }
+
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, role_id: Uuid) -> Result<()> {
+        let deleted = diesel::delete(roles::table.scoped(organization).filter(roles::id.eq(role_id)))
+            .execute(conn)
+            .map_err(|e| database_error("delete role", e))?;
+        if deleted == 0 {
+            return Err(not_found(role_id));
+        }
+        Ok(())
+    }
+
+    async fn assign(&self, conn: &mut PgConnection, assignment: &RoleAssignment) -> Result<bool> {
+        diesel::insert_into(user_roles::table)
+            .values(assignment)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .map(|inserted| inserted > 0)
+            .map_err(|e| database_error("assign role", e))
+    }
+
+    async fn unassign(&self, conn: &mut PgConnection, role_id: Uuid, user_id: Uuid) -> Result<bool> {
+        diesel::delete(user_roles::table.find((user_id, role_id)))
+            .execute(conn)
+            .map(|deleted| deleted > 0)
+            .map_err(|e| database_error("unassign role", e))
+    }
+
+    async fn assignments(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<RoleAssignment>> {
+        user_roles::table
+            .inner_join(roles::table)
+            .filter(roles::org_id.eq(organization))
+            .order_by((user_roles::role_id.asc(), user_roles::created_at.asc(), user_roles::user_id.asc()))
+            .select(RoleAssignment::as_select())
+            .load(conn)
+            .map_err(|e| database_error("list role assignments", e))
+    }
+
+    async fn members(&self, conn: &mut PgConnection) -> Result<Vec<(Uuid, CustomRole)>> {
+        user_roles::table
+            .inner_join(roles::table)
+            .select((user_roles::user_id, CustomRole::as_select()))
+            .load(conn)
+            .map_err(|e| database_error("list role members", e))
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    db::{
+        models::{SavedView, SavedViewDefault},
+        schema::{saved_view_defaults, saved_views},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{
+    prelude::*,
+    result::{DatabaseErrorKind, Error as DieselError},
+};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of saved views and users' default views
+///
+/// Views are read and written within an organization; which of them a user
+/// may see or change is decided by the service.
+#[async_trait]
+pub trait SavedViewRepository: Send + Sync + 'static {
+    /// Stores a new view
+    async fn create(&self, conn: &mut PgConnection, view: &SavedView) -> Result<SavedView>;
+
+    /// Finds one of an organization's views
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, view_id: Uuid) -> Result<SavedView>;
+
+    /// Lists the views a user owns or that are shared with them, by
+    /// resource and name, optionally of one resource
+    async fn visible(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        user_id: Uuid,
+        resource: Option<&str>,
+    ) -> Result<Vec<SavedView>>;
+
+    /// Replaces a view's configuration; when it stops being shared, other
+    /// users stop opening lists with it
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, view: &SavedView) -> Result<SavedView>;
+
+    /// Deletes a view
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, view_id: Uuid) -> Result<()>;
+
+    /// Sets the view a user opens a resource's list with
+    async fn set_default(&self, conn: &mut PgConnection, default: &SavedViewDefault) -> Result<()>;
+
+    /// Forgets a user's default view of a resource, `false` when there was
+    /// none
+    async fn clear_default(&self, conn: &mut PgConnection, user_id: Uuid, resource: &str) -> Result<bool>;
+
+    /// A user's default views, optionally of one resource
+    async fn defaults(&self, conn: &mut PgConnection, user_id: Uuid, resource: Option<&str>) -> Result<Vec<SavedViewDefault>>;
+}
+
+/// Concrete implementation of the saved view repository
+pub struct SavedViewRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn not_found(view_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("View with id {} not found", view_id))
+}
+
+/// Maps a view write error, reporting a name the user already saved a view
+/// of the resource under as a conflict
+fn write_error(action: &str, view: &SavedView, e: DieselError) -> ApiError {
+    match e {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => ApiError::new(
+            ErrorCode::Conflict,
+            "You already have a view with this name",
+            ErrorContext::new().with_details(serde_json::json!({ "resource": view.resource, "name": view.name })),
+        ),
+        DieselError::NotFound => not_found(view.id),
+        e => database_error(action, e),
+    }
+}
+
+#[async_trait]
+impl SavedViewRepository for SavedViewRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, view: &SavedView) -> Result<SavedView> {
+        // In a savepoint, so a taken name leaves the caller's transaction usable
+        conn.transaction(|conn| {
+            diesel::insert_into(saved_views::table)
+                .values(view)
+                .get_result(conn)
+        })
+        .map_err(|e| write_error("create view", view, e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, view_id: Uuid) -> Result<SavedView> {
+        saved_views::table
+            .scoped(organization)
+            .filter(saved_views::id.eq(view_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find view", e))?
+            .ok_or_else(|| not_found(view_id))
+    }
+
+    async fn visible(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        user_id: Uuid,
+        resource: Option<&str>,
+    ) -> Result<Vec<SavedView>> {
+        let mut query = saved_views::table
+            .scoped(organization)
+            .filter(saved_views::user_id.eq(user_id).or(saved_views::shared.eq(true)))
+            .into_boxed();
+        if let Some(resource) = resource {
+            query = query.filter(saved_views::resource.eq(resource));
+        }
+        query
+            .order_by((saved_views::resource.asc(), saved_views::name.asc(), saved_views::id.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list views", e))
+    }
+
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, view: &SavedView) -> Result<SavedView> {
+        conn.transaction::<_, DieselError, _>(|conn| {
+            let updated: SavedView = diesel::update(
+                saved_views::table
+                    .scoped(organization)
+                    .filter(saved_views::id.eq(view.id)),
+            )
+            .set((
+                saved_views::name.eq(&view.name),
+                saved_views::filters.eq(&view.filters),
+                saved_views::sort.eq(&view.sort),
+                saved_views::view_columns.eq(&view.columns),
+                saved_views::shared.eq(view.shared),
+                saved_views::updated_at.eq(Utc::now()),
+            ))
+            .get_result(conn)?;
+
+            if !updated.shared {
+                diesel::delete(
+                    saved_view_defaults::table
+                        .filter(saved_view_defaults::view_id.eq(updated.id))
+                        .filter(saved_view_defaults::user_id.ne(updated.user_id)),
+                )
+                .execute(conn)?;
+            }
+            Ok(updated)
+        })
+        .map_err(|e| write_error("update view", view, e))
+    }
+
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, view_id: Uuid) -> Result<()> {
+        let deleted = diesel::delete(
+            saved_views::table
+                .scoped(organization)
+                .filter(saved_views::id.eq(view_id)),
+        )
+        .execute(conn)
+        .map_err(|e| database_error("delete view", e))?;
+        if deleted == 0 {
+            return Err(not_found(view_id));
+        }
+        Ok(())
+    }
+
+    async fn set_default(&self, conn: &mut PgConnection, default: &SavedViewDefault) -> Result<()> {
+        diesel::insert_into(saved_view_defaults::table)
+            .values(default)
+            .on_conflict((saved_view_defaults::user_id, saved_view_defaults::resource))
+            .do_update()
+            .set((
+                saved_view_defaults::view_id.eq(default.view_id),
+                saved_view_defaults::updated_at.eq(default.updated_at),
+            ))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("set default view", e))
+    }
+
+    async fn clear_default(&self, conn: &mut PgConnection, user_id: Uuid, resource: &str) -> Result<bool> {
+        diesel::delete(
+            saved_view_defaults::table
+                .filter(saved_view_defaults::user_id.eq(user_id))
+                .filter(saved_view_defaults::resource.eq(resource)),
+        )
+        .execute(conn)
+        .map(|deleted| deleted > 0)
+        .map_err(|e| database_error("clear default view", e))
+    }
+
+    async fn defaults(&self, conn: &mut PgConnection, user_id: Uuid, resource: Option<&str>) -> Result<Vec<SavedViewDefault>> {
+        let mut query = saved_view_defaults::table
+            .filter(saved_view_defaults::user_id.eq(user_id))
+            .into_boxed();
+        if let Some(resource) = resource {
+            query = query.filter(saved_view_defaults::resource.eq(resource));
+        }
+        query
+            .order_by(saved_view_defaults::resource.asc())
+            .load(conn)
+            .map_err(|e| database_error("list default views", e))
+    }
+}
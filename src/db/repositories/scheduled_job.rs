@@ -0,0 +1,210 @@
+use crate::{
+    db::{
+        models::{JobRunOutcome, JobRunStatus, ScheduledJobState},
+        schema::scheduled_jobs::dsl::*,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use tracing::error;
+
+/// Persistence of scheduled job leases and run status
+///
+/// Scheduled jobs are identified by name rather than id and are never
+/// deleted, so this does not build on [`super::Repository`].
+#[async_trait]
+pub trait ScheduledJobRepository: Send + Sync + 'static {
+    /// Lists all jobs ordered by name
+    async fn list(&self, conn: &mut PgConnection) -> Result<Vec<ScheduledJobState>>;
+
+    /// Finds a job by name
+    async fn find_by_name(&self, conn: &mut PgConnection, job: &str) -> Result<ScheduledJobState>;
+
+    /// Finds a job by name, returning `None` if it has never been registered
+    async fn find_optional(&self, conn: &mut PgConnection, job: &str) -> Result<Option<ScheduledJobState>>;
+
+    /// Creates the row of a job, or moves an existing job's next run
+    /// forward to `next_run` if that is earlier
+    async fn register(&self, conn: &mut PgConnection, job: &str, next_run: DateTime<Utc>) -> Result<ScheduledJobState>;
+
+    /// Takes the lease of a due job that no other instance holds
+    ///
+    /// Returns `None` if the job is not due or is leased by another instance.
+    async fn claim(
+        &self,
+        conn: &mut PgConnection,
+        job: &str,
+        instance: &str,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Option<ScheduledJobState>>;
+
+    /// Extends the lease held by `instance`, returning false if it was lost
+    async fn renew(&self, conn: &mut PgConnection, job: &str, instance: &str, lease_until: DateTime<Utc>) -> Result<bool>;
+
+    /// Records the outcome of a run and releases the lease
+    async fn complete(
+        &self,
+        conn: &mut PgConnection,
+        job: &str,
+        instance: &str,
+        outcome: &JobRunOutcome,
+        next_run: DateTime<Utc>,
+    ) -> Result<ScheduledJobState>;
+
+    /// Sets or clears the schedule override and the resulting next run
+    async fn set_schedule(
+        &self,
+        conn: &mut PgConnection,
+        job: &str,
+        expression: Option<&str>,
+        next_run: DateTime<Utc>,
+    ) -> Result<ScheduledJobState>;
+}
+
+/// Concrete implementation of the scheduled job repository
+pub struct ScheduledJobRepositoryImpl;
+
+fn database_error(action: &str, job: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        job = %job,
+        error = %e,
+        "Failed to {} scheduled job",
+        action
+    );
+    ApiError::database_error(format!("Failed to {} scheduled job", action), None)
+}
+
+fn not_found(job: &str) -> ApiError {
+    ApiError::not_found(format!("Scheduled job {} not found", job))
+}
+
+#[async_trait]
+impl ScheduledJobRepository for ScheduledJobRepositoryImpl {
+    async fn list(&self, conn: &mut PgConnection) -> Result<Vec<ScheduledJobState>> {
+        scheduled_jobs
+            .order_by(name.asc())
+            .load(conn)
+            .map_err(|e| database_error("list", "*", e))
+    }
+
+    async fn find_by_name(&self, conn: &mut PgConnection, job: &str) -> Result<ScheduledJobState> {
+        self.find_optional(conn, job).await?.ok_or_else(|| not_found(job))
+    }
+
+    async fn find_optional(&self, conn: &mut PgConnection, job: &str) -> Result<Option<ScheduledJobState>> {
+        scheduled_jobs
+            .find(job)
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find", job, e))
+    }
+
+    async fn register(&self, conn: &mut PgConnection, job: &str, next_run: DateTime<Utc>) -> Result<ScheduledJobState> {
+        let now = Utc::now();
+        diesel::insert_into(scheduled_jobs)
+            .values((
+                name.eq(job),
+                next_run_at.eq(next_run),
+                created_at.eq(now),
+                updated_at.eq(now),
+            ))
+            .on_conflict(name)
+            .do_update()
+            .set(next_run_at.eq(diesel::dsl::sql::<diesel::sql_types::Timestamptz>(
+                "LEAST(scheduled_jobs.next_run_at, excluded.next_run_at)",
+            )))
+            .get_result(conn)
+            .map_err(|e| database_error("register", job, e))
+    }
+
+    async fn claim(
+        &self,
+        conn: &mut PgConnection,
+        job: &str,
+        instance: &str,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Option<ScheduledJobState>> {
+        let now = Utc::now();
+        diesel::update(
+            scheduled_jobs
+                .find(job)
+                .filter(next_run_at.le(now))
+                .filter(locked_until.is_null().or(locked_until.lt(now))),
+        )
+        .set((
+            locked_by.eq(instance),
+            locked_until.eq(lease_until),
+            last_started_at.eq(now),
+            last_status.eq(JobRunStatus::Running.as_str()),
+            updated_at.eq(now),
+        ))
+        .get_result(conn)
+        .optional()
+        .map_err(|e| database_error("claim", job, e))
+    }
+
+    async fn renew(&self, conn: &mut PgConnection, job: &str, instance: &str, lease_until: DateTime<Utc>) -> Result<bool> {
+        diesel::update(scheduled_jobs.find(job).filter(locked_by.eq(instance)))
+            .set(locked_until.eq(lease_until))
+            .execute(conn)
+            .map(|updated| updated > 0)
+            .map_err(|e| database_error("renew", job, e))
+    }
+
+    async fn complete(
+        &self,
+        conn: &mut PgConnection,
+        job: &str,
+        instance: &str,
+        outcome: &JobRunOutcome,
+        next_run: DateTime<Utc>,
+    ) -> Result<ScheduledJobState> {
+        let now = Utc::now();
+        diesel::update(scheduled_jobs.find(job).filter(locked_by.eq(instance)))
+            .set((
+                locked_by.eq(None::<String>),
+                locked_until.eq(None::<DateTime<Utc>>),
+                last_finished_at.eq(now),
+                last_status.eq(outcome.status.as_str()),
+                last_error.eq(&outcome.error),
+                last_duration_ms.eq(outcome.duration_ms),
+                last_output.eq(&outcome.output),
+                next_run_at.eq(next_run),
+                updated_at.eq(now),
+            ))
+            .get_result(conn)
+            .map_err(|e| match e {
+                // The lease expired and another instance took over the job
+                diesel::result::Error::NotFound => ApiError::new(
+                    ErrorCode::LockConflict,
+                    format!("Lease of scheduled job {} was lost", job),
+                    Default::default(),
+                ),
+                e => database_error("complete", job, e),
+            })
+    }
+
+    async fn set_schedule(
+        &self,
+        conn: &mut PgConnection,
+        job: &str,
+        expression: Option<&str>,
+        next_run: DateTime<Utc>,
+    ) -> Result<ScheduledJobState> {
+        diesel::update(scheduled_jobs.find(job))
+            .set((
+                schedule.eq(expression),
+                next_run_at.eq(next_run),
+                updated_at.eq(Utc::now()),
+            ))
+            .get_result(conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => not_found(job),
+                e => database_error("update", job, e),
+            })
+    }
+}
+
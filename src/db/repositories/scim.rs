@@ -0,0 +1,219 @@
+use crate::{
+    db::{
+        models::{
+            auth::{Role, User},
+            ScimToken,
+        },
+        schema::{scim_tokens, users},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of provisioning tokens and the users SCIM clients manage
+#[async_trait]
+pub trait ScimRepository: Send + Sync + 'static {
+    /// Stores a new token
+    async fn create_token(&self, conn: &mut PgConnection, token: &ScimToken) -> Result<ScimToken>;
+
+    /// Finds the unrevoked token with the hash `token_hash`
+    async fn find_token(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<ScimToken>>;
+
+    /// Records a request made with a token
+    async fn touch_token(&self, conn: &mut PgConnection, id: Uuid) -> Result<()>;
+
+    /// Lists the unrevoked tokens of an organization, newest first
+    async fn list_tokens(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<ScimToken>>;
+
+    /// Revokes a token of an organization
+    async fn revoke_token(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<ScimToken>;
+
+    /// Pages through the users of an organization by email, only the one
+    /// with `email` if given, with the total count
+    async fn list_users(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        email: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<User>, i64)>;
+
+    /// Finds a user of an organization, including removed users, which
+    /// SCIM shows as inactive
+    async fn find_user(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<Option<User>>;
+
+    /// Finds the user with `email` in any organization, including removed
+    /// users, since emails stay taken until a user is anonymized
+    async fn find_user_by_email(&self, conn: &mut PgConnection, email: &str) -> Result<Option<User>>;
+
+    /// Restores a removed user of an organization
+    async fn restore_user(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<User>;
+
+    /// Lists the users of an organization with `role`, by email
+    async fn list_users_with_role(&self, conn: &mut PgConnection, organization: Uuid, role: Role) -> Result<Vec<User>>;
+
+    /// Gives `role` to the users in `ids` that belong to the organization
+    /// and are not admins, returning those whose role changed
+    async fn set_role(&self, conn: &mut PgConnection, organization: Uuid, ids: &[Uuid], role: Role) -> Result<Vec<Uuid>>;
+}
+
+/// Concrete implementation of the SCIM repository
+pub struct ScimRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl ScimRepository for ScimRepositoryImpl {
+    async fn create_token(&self, conn: &mut PgConnection, token: &ScimToken) -> Result<ScimToken> {
+        diesel::insert_into(scim_tokens::table)
+            .values(token)
+            .returning(ScimToken::as_select())
+            .get_result(conn)
+            .map_err(|e| database_error("create SCIM token", e))
+    }
+
+    async fn find_token(&self, conn: &mut PgConnection, token_hash: &str) -> Result<Option<ScimToken>> {
+        scim_tokens::table
+            .filter(scim_tokens::token_hash.eq(token_hash))
+            .filter(scim_tokens::revoked_at.is_null())
+            .select(ScimToken::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find SCIM token", e))
+    }
+
+    async fn touch_token(&self, conn: &mut PgConnection, id: Uuid) -> Result<()> {
+        diesel::update(scim_tokens::table.find(id))
+            .set(scim_tokens::last_used_at.eq(Utc::now()))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("record SCIM token use", e))
+    }
+
+    async fn list_tokens(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<ScimToken>> {
+        scim_tokens::table
+            .scoped(organization)
+            .filter(scim_tokens::revoked_at.is_null())
+            .order_by(scim_tokens::created_at.desc())
+            .select(ScimToken::as_select())
+            .load(conn)
+            .map_err(|e| database_error("list SCIM tokens", e))
+    }
+
+    async fn revoke_token(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<ScimToken> {
+        diesel::update(
+            scim_tokens::table
+                .scoped(organization)
+                .filter(scim_tokens::id.eq(id))
+                .filter(scim_tokens::revoked_at.is_null()),
+        )
+        .set(scim_tokens::revoked_at.eq(Utc::now()))
+        .returning(ScimToken::as_select())
+        .get_result(conn)
+        .optional()
+        .map_err(|e| database_error("revoke SCIM token", e))?
+        .ok_or_else(|| ApiError::not_found(format!("SCIM token {} not found", id)))
+    }
+
+    async fn list_users(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        email: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<User>, i64)> {
+        let filtered = || {
+            let mut query = users::table
+                .scoped(organization)
+                .filter(users::deleted_at.is_null())
+                .into_boxed();
+            if let Some(email) = email {
+                query = query.filter(users::email.ilike(email.replace('%', "\\%").replace('_', "\\_")));
+            }
+            query
+        };
+
+        let total = filtered()
+            .count()
+            .get_result(conn)
+            .map_err(|e| database_error("count users", e))?;
+        let users = filtered()
+            .order_by((users::email.asc(), users::id.asc()))
+            .offset(offset)
+            .limit(limit)
+            .select(User::as_select())
+            .load(conn)
+            .map_err(|e| database_error("list users", e))?;
+        Ok((users, total))
+    }
+
+    async fn find_user(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<Option<User>> {
+        users::table
+            .scoped(organization)
+            .filter(users::id.eq(id))
+            .select(User::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find user", e))
+    }
+
+    async fn find_user_by_email(&self, conn: &mut PgConnection, email: &str) -> Result<Option<User>> {
+        users::table
+            .filter(users::email.eq(email))
+            .select(User::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find user", e))
+    }
+
+    async fn restore_user(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<User> {
+        diesel::update(users::table.scoped(organization).filter(users::id.eq(id)))
+            .set((users::deleted_at.eq(None::<DateTime<Utc>>), users::updated_at.eq(Utc::now())))
+            .returning(User::as_select())
+            .get_result(conn)
+            .optional()
+            .map_err(|e| database_error("restore user", e))?
+            .ok_or_else(|| ApiError::not_found(format!("User {} not found", id)))
+    }
+
+    async fn list_users_with_role(&self, conn: &mut PgConnection, organization: Uuid, role: Role) -> Result<Vec<User>> {
+        users::table
+            .scoped(organization)
+            .filter(users::role.eq(role))
+            .filter(users::deleted_at.is_null())
+            .order_by(users::email.asc())
+            .select(User::as_select())
+            .load(conn)
+            .map_err(|e| database_error("list users", e))
+    }
+
+    async fn set_role(&self, conn: &mut PgConnection, organization: Uuid, ids: &[Uuid], role: Role) -> Result<Vec<Uuid>> {
+        diesel::update(
+            users::table
+                .scoped(organization)
+                .filter(users::id.eq_any(ids))
+                .filter(users::role.ne(Role::Admin))
+                .filter(users::role.ne(role))
+                .filter(users::deleted_at.is_null()),
+        )
+        .set((users::role.eq(role), users::updated_at.eq(Utc::now())))
+        .returning(users::id)
+        .get_results(conn)
+        .map_err(|e| database_error("change user roles", e))
+    }
+}
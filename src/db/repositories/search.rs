@@ -0,0 +1,160 @@
+use crate::{
+    db::{
+        schema::{customers, documents, harvest_blocks, stands, supply_contracts, timber_tenders, users},
+        tenant::TenantScoped,
+    },
+    domain::search::SearchKind,
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// A record matching a search: its id, what it is shown as, a secondary
+/// line and the record it is opened under, if any
+pub type SearchRow = (Uuid, String, Option<String>, Option<Uuid>);
+
+/// Lookups behind the quick search
+///
+/// Every lookup is scoped to an organization.
+#[async_trait]
+pub trait SearchRepository: Send + Sync + 'static {
+    /// Records of `kind` with a searched field matching the `ILIKE`
+    /// `pattern`, at most `limit` of them
+    async fn search(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        kind: SearchKind,
+        pattern: &str,
+        limit: i64,
+    ) -> Result<Vec<SearchRow>>;
+}
+
+/// Concrete implementation of the search repository
+pub struct SearchRepositoryImpl;
+
+fn database_error(action: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+/// Rows of a kind opened by its id alone
+fn top_level(rows: Vec<(Uuid, String, Option<String>)>) -> Vec<SearchRow> {
+    rows.into_iter().map(|(id, title, subtitle)| (id, title, subtitle, None)).collect()
+}
+
+#[async_trait]
+impl SearchRepository for SearchRepositoryImpl {
+    async fn search(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        kind: SearchKind,
+        pattern: &str,
+        limit: i64,
+    ) -> Result<Vec<SearchRow>> {
+        let rows = match kind {
+            SearchKind::User => users::table
+                .scoped(organization)
+                .filter(users::deleted_at.is_null())
+                .filter(
+                    users::first_name
+                        .concat(" ")
+                        .concat(users::last_name)
+                        .ilike(pattern)
+                        .or(users::email.ilike(pattern)),
+                )
+                .order_by((users::first_name.asc(), users::last_name.asc()))
+                .limit(limit)
+                .select((users::id, users::first_name, users::last_name, users::email))
+                .load::<(Uuid, String, String, String)>(conn)
+                .map(|rows| {
+                    rows.into_iter()
+                        .map(|(id, first_name, last_name, email)| (id, format!("{} {}", first_name, last_name), Some(email), None))
+                        .collect()
+                }),
+            SearchKind::Customer => customers::table
+                .scoped(organization)
+                .filter(customers::name.ilike(pattern).or(customers::contact_name.ilike(pattern)))
+                .order_by(customers::name.asc())
+                .limit(limit)
+                .select((customers::id, customers::name, customers::contact_name))
+                .load(conn)
+                .map(top_level),
+            SearchKind::SupplyContract => supply_contracts::table
+                .scoped(organization)
+                .filter(supply_contracts::reference.ilike(pattern).or(supply_contracts::product.ilike(pattern)))
+                .order_by(supply_contracts::reference.asc())
+                .limit(limit)
+                .select((supply_contracts::id, supply_contracts::reference, supply_contracts::product.nullable()))
+                .load(conn)
+                .map(top_level),
+            SearchKind::Tender => timber_tenders::table
+                .scoped(organization)
+                .filter(timber_tenders::title.ilike(pattern))
+                .order_by(timber_tenders::bid_deadline.desc())
+                .limit(limit)
+                .select((timber_tenders::id, timber_tenders::title, timber_tenders::status.nullable()))
+                .load(conn)
+                .map(top_level),
+            SearchKind::Document => documents::table
+                .scoped(organization)
+                .filter(documents::title.ilike(pattern).or(documents::category.ilike(pattern)))
+                .order_by(documents::updated_at.desc())
+                .limit(limit)
+                .select((documents::id, documents::title, documents::category.nullable()))
+                .load(conn)
+                .map(top_level),
+            SearchKind::Block => harvest_blocks::table
+                .scoped(organization)
+                .filter(
+                    harvest_blocks::license
+                        .concat(" ")
+                        .concat(harvest_blocks::block_number)
+                        .ilike(pattern)
+                        .or(harvest_blocks::block_number.ilike(pattern)),
+                )
+                .order_by((harvest_blocks::license.asc(), harvest_blocks::block_number.asc()))
+                .limit(limit)
+                .select((harvest_blocks::id, harvest_blocks::license, harvest_blocks::block_number, harvest_blocks::status))
+                .load::<(Uuid, String, String, String)>(conn)
+                .map(|rows| {
+                    rows.into_iter()
+                        .map(|(id, license, block_number, status)| (id, format!("{} {}", license, block_number), Some(status), None))
+                        .collect()
+                }),
+            // Under their block, which is what they are known by
+            SearchKind::Stand => stands::table
+                .inner_join(harvest_blocks::table)
+                .filter(stands::org_id.eq(organization))
+                .filter(
+                    harvest_blocks::license
+                        .concat(" ")
+                        .concat(harvest_blocks::block_number)
+                        .concat(" ")
+                        .concat(stands::stand_number)
+                        .ilike(pattern)
+                        .or(stands::stand_number.ilike(pattern)),
+                )
+                .order_by((harvest_blocks::license.asc(), harvest_blocks::block_number.asc(), stands::stand_number.asc()))
+                .limit(limit)
+                .select((stands::id, stands::block_id, harvest_blocks::license, harvest_blocks::block_number, stands::stand_number))
+                .load::<(Uuid, Uuid, String, String, String)>(conn)
+                .map(|rows| {
+                    rows.into_iter()
+                        .map(|(id, block_id, license, block_number, stand_number)| {
+                            (id, format!("{} {} {}", license, block_number, stand_number), None, Some(block_id))
+                        })
+                        .collect()
+                }),
+        };
+        rows.map_err(|e| database_error(&format!("search {}s", kind.as_str()), e))
+    }
+}
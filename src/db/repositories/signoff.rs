@@ -0,0 +1,94 @@
+use crate::{
+    db::{
+        models::{auth::User, BlockSignoff},
+        schema::{block_signoffs, users},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use diesel::{
+    prelude::*,
+    result::{DatabaseErrorKind, Error as DieselError},
+};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of block sign-offs
+///
+/// Every read and write is scoped to an organization.
+#[async_trait]
+pub trait SignoffRepository: Send + Sync + 'static {
+    /// Finds an active user of an organization who signs or resets sign-offs
+    async fn find_signer(&self, conn: &mut PgConnection, organization: Uuid, user_id: Uuid) -> Result<User>;
+
+    /// Lists the sign-offs of a block, in the order they were given
+    async fn list(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid) -> Result<Vec<BlockSignoff>>;
+
+    /// Stores a sign-off, failing when its step is already signed
+    async fn create(&self, conn: &mut PgConnection, signoff: &BlockSignoff) -> Result<BlockSignoff>;
+
+    /// Removes every sign-off of a block, returning how many there were
+    async fn clear(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid) -> Result<usize>;
+}
+
+/// Concrete implementation of the sign-off repository
+pub struct SignoffRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl SignoffRepository for SignoffRepositoryImpl {
+    async fn find_signer(&self, conn: &mut PgConnection, organization: Uuid, user_id: Uuid) -> Result<User> {
+        users::table
+            .scoped(organization)
+            .filter(users::id.eq(user_id))
+            .filter(users::deleted_at.is_null())
+            .select(User::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find signer", e))?
+            .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", user_id)))
+    }
+
+    async fn list(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid) -> Result<Vec<BlockSignoff>> {
+        block_signoffs::table
+            .scoped(organization)
+            .filter(block_signoffs::block_id.eq(block_id))
+            .order_by((block_signoffs::signed_at.asc(), block_signoffs::id.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list block sign-offs", e))
+    }
+
+    async fn create(&self, conn: &mut PgConnection, signoff: &BlockSignoff) -> Result<BlockSignoff> {
+        diesel::insert_into(block_signoffs::table)
+            .values(signoff)
+            .get_result(conn)
+            .map_err(|e| match e {
+                DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => ApiError::new(
+                    ErrorCode::Conflict,
+                    "This step is already signed",
+                    ErrorContext::new().with_details(serde_json::json!({ "step": signoff.step })),
+                ),
+                e => database_error("record block sign-off", e),
+            })
+    }
+
+    async fn clear(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid) -> Result<usize> {
+        diesel::delete(
+            block_signoffs::table
+                .scoped(organization)
+                .filter(block_signoffs::block_id.eq(block_id)),
+        )
+        .execute(conn)
+        .map_err(|e| database_error("reset block sign-offs", e))
+    }
+}
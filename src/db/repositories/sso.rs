@@ -0,0 +1,133 @@
+use crate::{
+    db::{
+        models::{OrganizationSsoDomain, UserIdentity},
+        schema::{organization_sso_domains, user_identities},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{
+    prelude::*,
+    result::{DatabaseErrorKind, Error as DieselError},
+};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of single sign-on domains and linked identities
+#[async_trait]
+pub trait SsoRepository: Send + Sync + 'static {
+    /// Finds the identity a provider account is linked through
+    async fn find_identity(&self, conn: &mut PgConnection, provider: &str, subject: &str) -> Result<Option<UserIdentity>>;
+
+    /// Links a provider account to a user
+    async fn link_identity(&self, conn: &mut PgConnection, identity: &UserIdentity) -> Result<UserIdentity>;
+
+    /// Records a sign-on through an identity
+    async fn touch_identity(&self, conn: &mut PgConnection, provider: &str, subject: &str) -> Result<()>;
+
+    /// Finds the organization that claimed an email domain
+    async fn find_domain(&self, conn: &mut PgConnection, domain: &str) -> Result<Option<OrganizationSsoDomain>>;
+
+    /// Lists the domains an organization claimed, by name
+    async fn list_domains(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<OrganizationSsoDomain>>;
+
+    /// Claims a domain for an organization, a conflict if it is claimed
+    async fn add_domain(&self, conn: &mut PgConnection, domain: &OrganizationSsoDomain) -> Result<OrganizationSsoDomain>;
+
+    /// Releases a domain an organization claimed
+    async fn remove_domain(&self, conn: &mut PgConnection, organization: Uuid, domain: &str) -> Result<OrganizationSsoDomain>;
+}
+
+/// Concrete implementation of the single sign-on repository
+pub struct SsoRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn domain_not_found(domain: &str) -> ApiError {
+    ApiError::not_found(format!("SSO domain {} not found", domain))
+}
+
+#[async_trait]
+impl SsoRepository for SsoRepositoryImpl {
+    async fn find_identity(&self, conn: &mut PgConnection, provider: &str, subject: &str) -> Result<Option<UserIdentity>> {
+        user_identities::table
+            .find((provider, subject))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find identity", e))
+    }
+
+    async fn link_identity(&self, conn: &mut PgConnection, identity: &UserIdentity) -> Result<UserIdentity> {
+        diesel::insert_into(user_identities::table)
+            .values(identity)
+            .get_result(conn)
+            .map_err(|e| match e {
+                DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => ApiError::new(
+                    ErrorCode::Conflict,
+                    "This account is already linked",
+                    ErrorContext::new().with_details(serde_json::json!({ "provider": identity.provider })),
+                ),
+                e => database_error("link identity", e),
+            })
+    }
+
+    async fn touch_identity(&self, conn: &mut PgConnection, provider: &str, subject: &str) -> Result<()> {
+        diesel::update(user_identities::table.find((provider, subject)))
+            .set(user_identities::last_login_at.eq(Utc::now()))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("record sign-on", e))
+    }
+
+    async fn find_domain(&self, conn: &mut PgConnection, domain: &str) -> Result<Option<OrganizationSsoDomain>> {
+        organization_sso_domains::table
+            .find(domain)
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find SSO domain", e))
+    }
+
+    async fn list_domains(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<OrganizationSsoDomain>> {
+        organization_sso_domains::table
+            .scoped(organization)
+            .order_by(organization_sso_domains::domain.asc())
+            .load(conn)
+            .map_err(|e| database_error("list SSO domains", e))
+    }
+
+    async fn add_domain(&self, conn: &mut PgConnection, domain: &OrganizationSsoDomain) -> Result<OrganizationSsoDomain> {
+        diesel::insert_into(organization_sso_domains::table)
+            .values(domain)
+            .get_result(conn)
+            .map_err(|e| match e {
+                DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => ApiError::new(
+                    ErrorCode::Conflict,
+                    "The domain is already claimed by an organization",
+                    ErrorContext::new().with_details(serde_json::json!({ "domain": domain.domain })),
+                ),
+                e => database_error("add SSO domain", e),
+            })
+    }
+
+    async fn remove_domain(&self, conn: &mut PgConnection, organization: Uuid, domain: &str) -> Result<OrganizationSsoDomain> {
+        diesel::delete(
+            organization_sso_domains::table
+                .scoped(organization)
+                .filter(organization_sso_domains::domain.eq(domain)),
+        )
+        .get_result(conn)
+        .optional()
+        .map_err(|e| database_error("remove SSO domain", e))?
+        .ok_or_else(|| domain_not_found(domain))
+    }
+}
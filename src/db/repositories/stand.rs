@@ -0,0 +1,187 @@
+use crate::{
+    db::{models::Stand, schema::stands, tenant::TenantScoped},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{
+    prelude::*,
+    result::{DatabaseErrorKind, Error as DieselError},
+    upsert::excluded,
+};
+use std::collections::HashSet;
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of the stands of harvest blocks
+///
+/// Every read and write is scoped to an organization; callers check that
+/// the block belongs to it.
+#[async_trait]
+pub trait StandRepository: Send + Sync + 'static {
+    /// Stores a new stand
+    async fn create(&self, conn: &mut PgConnection, stand: &Stand) -> Result<Stand>;
+
+    /// Finds a stand of one of an organization's blocks
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid, stand_id: Uuid) -> Result<Stand>;
+
+    /// Lists the stands of a block by stand number
+    async fn list(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid) -> Result<Vec<Stand>>;
+
+    /// Replaces the details of a stand
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, stand: &Stand) -> Result<Stand>;
+
+    /// Creates or replaces stands of one block by stand number, all or
+    /// none, returning them with how many were new
+    async fn upsert(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid, stands: &[Stand]) -> Result<(Vec<Stand>, usize)>;
+
+    /// Deletes a stand
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid, stand_id: Uuid) -> Result<()>;
+}
+
+/// Concrete implementation of the stand repository
+pub struct StandRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn not_found(stand_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Stand with id {} not found", stand_id))
+}
+
+/// Maps a stand write error, reporting a stand number taken in the block
+/// as a conflict
+fn write_error(action: &str, stand: &Stand, e: DieselError) -> ApiError {
+    match e {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => ApiError::new(
+            ErrorCode::Conflict,
+            "A stand with this number already exists in the block",
+            ErrorContext::new().with_details(serde_json::json!({ "stand_number": stand.stand_number })),
+        ),
+        DieselError::NotFound => not_found(stand.id),
+        e => database_error(action, e),
+    }
+}
+
+/// Inserts `stands`, replacing those whose number their block already has
+///
+/// Synchronous, for callers inside a `conn.transaction` such as imports.
+pub fn upsert_stands(conn: &mut PgConnection, stands: &[Stand]) -> QueryResult<Vec<Stand>> {
+    // A stand keeps its id and creation time when replaced
+    diesel::insert_into(stands::table)
+        .values(stands)
+        .on_conflict((stands::block_id, stands::stand_number))
+        .do_update()
+        .set((
+            stands::area_ha.eq(excluded(stands::area_ha)),
+            stands::species.eq(excluded(stands::species)),
+            stands::age_class.eq(excluded(stands::age_class)),
+            stands::site_index.eq(excluded(stands::site_index)),
+            stands::net_merchantable_volume_m3.eq(excluded(stands::net_merchantable_volume_m3)),
+            stands::inventoried_on.eq(excluded(stands::inventoried_on)),
+            stands::windthrow_conditions.eq(excluded(stands::windthrow_conditions)),
+            stands::windthrow_score.eq(excluded(stands::windthrow_score)),
+            stands::windthrow_class.eq(excluded(stands::windthrow_class)),
+            stands::updated_at.eq(excluded(stands::updated_at)),
+        ))
+        .get_results(conn)
+}
+
+#[async_trait]
+impl StandRepository for StandRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, stand: &Stand) -> Result<Stand> {
+        // In a savepoint, so a taken number leaves the caller's transaction usable
+        conn.transaction(|conn| {
+            diesel::insert_into(stands::table)
+                .values(stand)
+                .get_result(conn)
+        })
+        .map_err(|e| write_error("create stand", stand, e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid, stand_id: Uuid) -> Result<Stand> {
+        stands::table
+            .scoped(organization)
+            .filter(stands::block_id.eq(block_id))
+            .filter(stands::id.eq(stand_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find stand", e))?
+            .ok_or_else(|| not_found(stand_id))
+    }
+
+    async fn list(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid) -> Result<Vec<Stand>> {
+        stands::table
+            .scoped(organization)
+            .filter(stands::block_id.eq(block_id))
+            .order_by((stands::stand_number.asc(), stands::id.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list stands", e))
+    }
+
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, stand: &Stand) -> Result<Stand> {
+        conn.transaction(|conn| {
+            diesel::update(
+                stands::table
+                    .scoped(organization)
+                    .filter(stands::block_id.eq(stand.block_id))
+                    .filter(stands::id.eq(stand.id)),
+            )
+            .set((
+                stands::stand_number.eq(&stand.stand_number),
+                stands::area_ha.eq(stand.area_ha),
+                stands::species.eq(&stand.species),
+                stands::age_class.eq(stand.age_class),
+                stands::site_index.eq(stand.site_index),
+                stands::net_merchantable_volume_m3.eq(stand.net_merchantable_volume_m3),
+                stands::inventoried_on.eq(stand.inventoried_on),
+                stands::windthrow_conditions.eq(&stand.windthrow_conditions),
+                stands::windthrow_score.eq(stand.windthrow_score),
+                stands::windthrow_class.eq(&stand.windthrow_class),
+                stands::updated_at.eq(Utc::now()),
+            ))
+            .get_result(conn)
+        })
+        .map_err(|e| write_error("update stand", stand, e))
+    }
+
+    async fn upsert(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid, stands: &[Stand]) -> Result<(Vec<Stand>, usize)> {
+        let numbers: Vec<&str> = stands.iter().map(|stand| stand.stand_number.as_str()).collect();
+        conn.transaction(|conn| {
+            let existing: HashSet<String> = stands::table
+                .scoped(organization)
+                .filter(stands::block_id.eq(block_id))
+                .filter(stands::stand_number.eq_any(&numbers))
+                .select(stands::stand_number)
+                .load::<String>(conn)?
+                .into_iter()
+                .collect();
+            let mut saved = upsert_stands(conn, stands)?;
+            saved.sort_by(|a, b| a.stand_number.cmp(&b.stand_number));
+            Ok((saved, stands.len() - existing.len()))
+        })
+        .map_err(|e| database_error("save stands", e))
+    }
+
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, block_id: Uuid, stand_id: Uuid) -> Result<()> {
+        let deleted = diesel::delete(
+            stands::table
+                .scoped(organization)
+                .filter(stands::block_id.eq(block_id))
+                .filter(stands::id.eq(stand_id)),
+        )
+        .execute(conn)
+        .map_err(|e| database_error("delete stand", e))?;
+        if deleted == 0 {
+            return Err(not_found(stand_id));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    db::{
+        models::Subscription,
+        schema::{stripe_events, subscriptions},
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{prelude::*, result::Error as DieselError};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of subscriptions and of the Stripe events applied to them
+#[async_trait]
+pub trait SubscriptionRepository: Send + Sync + 'static {
+    /// The subscription of an organization, `None` when it never subscribed
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Option<Subscription>>;
+
+    /// The subscription with a Stripe subscription id
+    async fn find_by_stripe_id(&self, conn: &mut PgConnection, stripe_subscription_id: &str) -> Result<Option<Subscription>>;
+
+    /// Creates or replaces the subscription of an organization
+    async fn save(&self, conn: &mut PgConnection, subscription: &Subscription) -> Result<Subscription>;
+
+    /// Whether the Stripe event `id` was already applied
+    async fn event_processed(&self, conn: &mut PgConnection, id: &str) -> Result<bool>;
+
+    /// Records the Stripe event `id` as applied
+    async fn record_event(&self, conn: &mut PgConnection, id: &str, kind: &str) -> Result<()>;
+}
+
+/// Concrete implementation of the subscription repository
+pub struct SubscriptionRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl SubscriptionRepository for SubscriptionRepositoryImpl {
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Option<Subscription>> {
+        subscriptions::table
+            .find(organization)
+            .select(Subscription::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find subscription", e))
+    }
+
+    async fn find_by_stripe_id(&self, conn: &mut PgConnection, stripe_subscription_id: &str) -> Result<Option<Subscription>> {
+        subscriptions::table
+            .filter(subscriptions::stripe_subscription_id.eq(stripe_subscription_id))
+            .select(Subscription::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find subscription", e))
+    }
+
+    async fn save(&self, conn: &mut PgConnection, subscription: &Subscription) -> Result<Subscription> {
+        diesel::insert_into(subscriptions::table)
+            .values(subscription)
+            .on_conflict(subscriptions::org_id)
+            .do_update()
+            .set((
+                subscriptions::plan.eq(&subscription.plan),
+                subscriptions::status.eq(&subscription.status),
+                subscriptions::stripe_customer_id.eq(&subscription.stripe_customer_id),
+                subscriptions::stripe_subscription_id.eq(&subscription.stripe_subscription_id),
+                subscriptions::current_period_end.eq(subscription.current_period_end),
+                subscriptions::cancel_at_period_end.eq(subscription.cancel_at_period_end),
+                subscriptions::updated_at.eq(subscription.updated_at),
+            ))
+            .returning(Subscription::as_select())
+            .get_result(conn)
+            .map_err(|e| database_error("save subscription", e))
+    }
+
+    async fn event_processed(&self, conn: &mut PgConnection, id: &str) -> Result<bool> {
+        diesel::select(diesel::dsl::exists(stripe_events::table.find(id)))
+            .get_result(conn)
+            .map_err(|e| database_error("look up Stripe event", e))
+    }
+
+    async fn record_event(&self, conn: &mut PgConnection, id: &str, kind: &str) -> Result<()> {
+        diesel::insert_into(stripe_events::table)
+            .values((
+                stripe_events::id.eq(id),
+                stripe_events::kind.eq(kind),
+                stripe_events::processed_at.eq(Utc::now()),
+            ))
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("record Stripe event", e))
+    }
+}
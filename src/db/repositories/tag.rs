@@ -0,0 +1,240 @@
+use crate::{
+    db::{
+        models::{Tag, TagSubject, Tagging},
+        schema::{taggings, tags},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{
+    dsl::count,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as DieselError},
+};
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of tags and their taggings
+///
+/// Every read and write is scoped to an organization.
+#[async_trait]
+pub trait TagRepository: Send + Sync + 'static {
+    /// Stores a new tag
+    async fn create(&self, conn: &mut PgConnection, tag: &Tag) -> Result<Tag>;
+
+    /// Finds one of an organization's tags
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, tag_id: Uuid) -> Result<Tag>;
+
+    /// Lists an organization's tags by name
+    async fn list(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<Tag>>;
+
+    /// Replaces the name and color of a tag
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, tag: &Tag) -> Result<Tag>;
+
+    /// Deletes a tag, detaching it from everything
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, tag_id: Uuid) -> Result<()>;
+
+    /// Attaches a tag, `false` when it was already attached
+    async fn attach(&self, conn: &mut PgConnection, tagging: &Tagging) -> Result<bool>;
+
+    /// Detaches a tag, `false` when it was not attached
+    async fn detach(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        tag_id: Uuid,
+        subject: TagSubject,
+        subject_id: Uuid,
+    ) -> Result<bool>;
+
+    /// What a tag is attached to, latest first
+    async fn taggings(&self, conn: &mut PgConnection, organization: Uuid, tag_id: Uuid) -> Result<Vec<Tagging>>;
+
+    /// The tags attached to each of `subject_ids`, by subject and tag name
+    async fn subject_tags(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        subject: TagSubject,
+        subject_ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, Tag)>>;
+
+    /// Subjects tagged with all of `tag_ids` when `match_all`, otherwise
+    /// with any of them
+    async fn tagged(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        subject: TagSubject,
+        tag_ids: &[Uuid],
+        match_all: bool,
+    ) -> Result<Vec<Uuid>>;
+}
+
+/// Concrete implementation of the tag repository
+pub struct TagRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn not_found(tag_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Tag with id {} not found", tag_id))
+}
+
+/// Maps a tag write error, reporting a name taken by another tag as a
+/// conflict
+fn write_error(action: &str, tag: &Tag, e: DieselError) -> ApiError {
+    match e {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => ApiError::new(
+            ErrorCode::Conflict,
+            "A tag with this name already exists",
+            ErrorContext::new().with_details(serde_json::json!({ "name": tag.name })),
+        ),
+        DieselError::NotFound => not_found(tag.id),
+        e => database_error(action, e),
+    }
+}
+
+#[async_trait]
+impl TagRepository for TagRepositoryImpl {
+    async fn create(&self, conn: &mut PgConnection, tag: &Tag) -> Result<Tag> {
+        // In a savepoint, so a taken name leaves the caller's transaction usable
+        conn.transaction(|conn| {
+            diesel::insert_into(tags::table)
+                .values(tag)
+                .get_result(conn)
+        })
+        .map_err(|e| write_error("create tag", tag, e))
+    }
+
+    async fn find(&self, conn: &mut PgConnection, organization: Uuid, tag_id: Uuid) -> Result<Tag> {
+        tags::table
+            .scoped(organization)
+            .filter(tags::id.eq(tag_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find tag", e))?
+            .ok_or_else(|| not_found(tag_id))
+    }
+
+    async fn list(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<Tag>> {
+        tags::table
+            .scoped(organization)
+            .order_by((tags::name.asc(), tags::id.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list tags", e))
+    }
+
+    async fn update(&self, conn: &mut PgConnection, organization: Uuid, tag: &Tag) -> Result<Tag> {
+        conn.transaction(|conn| {
+            diesel::update(tags::table.scoped(organization).filter(tags::id.eq(tag.id)))
+                .set((
+                    tags::name.eq(&tag.name),
+                    tags::color.eq(&tag.color),
+                    tags::updated_at.eq(Utc::now()),
+                ))
+                .get_result(conn)
+        })
+        .map_err(|e| write_error("update tag", tag, e))
+    }
+
+    async fn delete(&self, conn: &mut PgConnection, organization: Uuid, tag_id: Uuid) -> Result<()> {
+        let deleted = diesel::delete(tags::table.scoped(organization).filter(tags::id.eq(tag_id)))
+            .execute(conn)
+            .map_err(|e| database_error("delete tag", e))?;
+        if deleted == 0 {
+            return Err(not_found(tag_id));
+        }
+        Ok(())
+    }
+
+    async fn attach(&self, conn: &mut PgConnection, tagging: &Tagging) -> Result<bool> {
+        diesel::insert_into(taggings::table)
+            .values(tagging)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .map(|inserted| inserted > 0)
+            .map_err(|e| database_error("attach tag", e))
+    }
+
+    async fn detach(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        tag_id: Uuid,
+        subject: TagSubject,
+        subject_id: Uuid,
+    ) -> Result<bool> {
+        diesel::delete(
+            taggings::table
+                .scoped(organization)
+                .filter(taggings::tag_id.eq(tag_id))
+                .filter(taggings::subject_type.eq(subject.as_str()))
+                .filter(taggings::subject_id.eq(subject_id)),
+        )
+        .execute(conn)
+        .map(|deleted| deleted > 0)
+        .map_err(|e| database_error("detach tag", e))
+    }
+
+    async fn taggings(&self, conn: &mut PgConnection, organization: Uuid, tag_id: Uuid) -> Result<Vec<Tagging>> {
+        taggings::table
+            .scoped(organization)
+            .filter(taggings::tag_id.eq(tag_id))
+            .order_by((taggings::created_at.desc(), taggings::subject_id.asc()))
+            .load(conn)
+            .map_err(|e| database_error("list taggings", e))
+    }
+
+    async fn subject_tags(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        subject: TagSubject,
+        subject_ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, Tag)>> {
+        taggings::table
+            .scoped(organization)
+            .inner_join(tags::table)
+            .filter(taggings::subject_type.eq(subject.as_str()))
+            .filter(taggings::subject_id.eq_any(subject_ids))
+            .order_by((taggings::subject_id.asc(), tags::name.asc()))
+            .select((taggings::subject_id, Tag::as_select()))
+            .load(conn)
+            .map_err(|e| database_error("list subject tags", e))
+    }
+
+    async fn tagged(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        subject: TagSubject,
+        tag_ids: &[Uuid],
+        match_all: bool,
+    ) -> Result<Vec<Uuid>> {
+        let query = taggings::table
+            .scoped(organization)
+            .filter(taggings::subject_type.eq(subject.as_str()))
+            .filter(taggings::tag_id.eq_any(tag_ids))
+            .group_by(taggings::subject_id)
+            .select(taggings::subject_id)
+            .order_by(taggings::subject_id.asc());
+        // A subject has each tag at most once, so counting its matching
+        // taggings tells whether it has all of them
+        let result = if match_all {
+            query.having(count(taggings::tag_id).eq(tag_ids.len() as i64)).load(conn)
+        } else {
+            query.load(conn)
+        };
+        result.map_err(|e| database_error("filter subjects by tag", e))
+    }
+}
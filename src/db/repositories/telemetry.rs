@@ -0,0 +1,161 @@
+use crate::{
+    db::{
+        models::{TelemetryApiKey, TelemetryPoint},
+        schema::{machine_telemetry, telemetry_api_keys},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, result::Error as DieselError, upsert::excluded};
+use tracing::error;
+use uuid::Uuid;
+
+/// Most points written by one statement, keeping its bind parameters well
+/// under Postgres' limit of 65535
+pub const WRITE_BATCH_SIZE: usize = 5000;
+
+/// Persistence of telemetry API keys and the points machines upload
+#[async_trait]
+pub trait TelemetryRepository: Send + Sync + 'static {
+    /// Stores a new key
+    async fn create_key(&self, conn: &mut PgConnection, key: &TelemetryApiKey) -> Result<TelemetryApiKey>;
+
+    /// Finds the unrevoked key with the hash `key_hash`
+    async fn find_key(&self, conn: &mut PgConnection, key_hash: &str) -> Result<Option<TelemetryApiKey>>;
+
+    /// Records an upload made with a key
+    async fn touch_key(&self, conn: &mut PgConnection, id: Uuid) -> Result<()>;
+
+    /// Lists the unrevoked keys of an organization, newest first
+    async fn list_keys(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<TelemetryApiKey>>;
+
+    /// Revokes a key of an organization
+    async fn revoke_key(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<TelemetryApiKey>;
+
+    /// Stores points, replacing those stored for the same machine and time,
+    /// in statements of at most [`WRITE_BATCH_SIZE`] points, all or none
+    ///
+    /// `points` must not repeat a machine and time.
+    async fn upsert_points(&self, conn: &mut PgConnection, points: &[TelemetryPoint]) -> Result<usize>;
+
+    /// Lists the points a machine recorded from `from` until before `to`,
+    /// oldest first
+    async fn list_points(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        machine_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TelemetryPoint>>;
+}
+
+/// Concrete implementation of the telemetry repository
+pub struct TelemetryRepositoryImpl;
+
+fn database_error(action: &str, e: DieselError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+#[async_trait]
+impl TelemetryRepository for TelemetryRepositoryImpl {
+    async fn create_key(&self, conn: &mut PgConnection, key: &TelemetryApiKey) -> Result<TelemetryApiKey> {
+        diesel::insert_into(telemetry_api_keys::table)
+            .values(key)
+            .returning(TelemetryApiKey::as_select())
+            .get_result(conn)
+            .map_err(|e| database_error("create telemetry API key", e))
+    }
+
+    async fn find_key(&self, conn: &mut PgConnection, key_hash: &str) -> Result<Option<TelemetryApiKey>> {
+        telemetry_api_keys::table
+            .filter(telemetry_api_keys::key_hash.eq(key_hash))
+            .filter(telemetry_api_keys::revoked_at.is_null())
+            .select(TelemetryApiKey::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find telemetry API key", e))
+    }
+
+    async fn touch_key(&self, conn: &mut PgConnection, id: Uuid) -> Result<()> {
+        diesel::update(telemetry_api_keys::table.find(id))
+            .set(telemetry_api_keys::last_used_at.eq(Utc::now()))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(|e| database_error("record telemetry API key use", e))
+    }
+
+    async fn list_keys(&self, conn: &mut PgConnection, organization: Uuid) -> Result<Vec<TelemetryApiKey>> {
+        telemetry_api_keys::table
+            .scoped(organization)
+            .filter(telemetry_api_keys::revoked_at.is_null())
+            .order_by(telemetry_api_keys::created_at.desc())
+            .select(TelemetryApiKey::as_select())
+            .load(conn)
+            .map_err(|e| database_error("list telemetry API keys", e))
+    }
+
+    async fn revoke_key(&self, conn: &mut PgConnection, organization: Uuid, id: Uuid) -> Result<TelemetryApiKey> {
+        diesel::update(
+            telemetry_api_keys::table
+                .scoped(organization)
+                .filter(telemetry_api_keys::id.eq(id))
+                .filter(telemetry_api_keys::revoked_at.is_null()),
+        )
+        .set(telemetry_api_keys::revoked_at.eq(Utc::now()))
+        .returning(TelemetryApiKey::as_select())
+        .get_result(conn)
+        .optional()
+        .map_err(|e| database_error("revoke telemetry API key", e))?
+        .ok_or_else(|| ApiError::not_found(format!("Telemetry API key {} not found", id)))
+    }
+
+    async fn upsert_points(&self, conn: &mut PgConnection, points: &[TelemetryPoint]) -> Result<usize> {
+        conn.transaction(|conn| {
+            let mut written = 0;
+            for batch in points.chunks(WRITE_BATCH_SIZE) {
+                written += diesel::insert_into(machine_telemetry::table)
+                    .values(batch)
+                    .on_conflict((machine_telemetry::org_id, machine_telemetry::machine_id, machine_telemetry::recorded_at))
+                    .do_update()
+                    .set((
+                        machine_telemetry::engine_hours.eq(excluded(machine_telemetry::engine_hours)),
+                        machine_telemetry::fuel_used_l.eq(excluded(machine_telemetry::fuel_used_l)),
+                        machine_telemetry::latitude.eq(excluded(machine_telemetry::latitude)),
+                        machine_telemetry::longitude.eq(excluded(machine_telemetry::longitude)),
+                        machine_telemetry::received_at.eq(excluded(machine_telemetry::received_at)),
+                    ))
+                    .execute(conn)?;
+            }
+            Ok(written)
+        })
+        .map_err(|e| database_error("store telemetry", e))
+    }
+
+    async fn list_points(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        machine_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TelemetryPoint>> {
+        machine_telemetry::table
+            .scoped(organization)
+            .filter(machine_telemetry::machine_id.eq(machine_id))
+            .filter(machine_telemetry::recorded_at.ge(from))
+            .filter(machine_telemetry::recorded_at.lt(to))
+            .order_by(machine_telemetry::recorded_at.asc())
+            .select(TelemetryPoint::as_select())
+            .load(conn)
+            .map_err(|e| database_error("list telemetry", e))
+    }
+}
@@ -0,0 +1,312 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        count::RowCount,
+        models::{SaleContract, TenderBid, TenderParcel, TenderStatus, TimberTender},
+        schema::{sale_contracts, tender_bids, tender_parcels, timber_tenders},
+        tenant::TenantScoped,
+    },
+    error::{ApiError, ErrorCode, Result},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::prelude::*;
+use tracing::error;
+use uuid::Uuid;
+
+/// Persistence of timber sale tenders, their parcels and bids, and the
+/// resulting sale contracts
+///
+/// Tenders and contracts are scoped to an organization; parcels and bids
+/// are reached through their tender.
+#[async_trait]
+pub trait TimberSaleRepository: Send + Sync + 'static {
+    /// Stores a new tender with its parcels
+    async fn create_tender(
+        &self,
+        conn: &mut PgConnection,
+        tender: &TimberTender,
+        parcels: &[TenderParcel],
+    ) -> Result<(TimberTender, Vec<TenderParcel>)>;
+
+    /// Finds one of an organization's tenders
+    async fn find_tender(&self, conn: &mut PgConnection, organization: Uuid, tender_id: Uuid) -> Result<TimberTender>;
+
+    /// Lists an organization's tenders, newest first, optionally only those
+    /// with a status
+    async fn list_tenders(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        status: Option<TenderStatus>,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<TimberTender>>;
+
+    /// Counts an organization's tenders, optionally only those with a status
+    async fn count_tenders(&self, conn: &mut PgConnection, organization: Uuid, status: Option<TenderStatus>) -> Result<RowCount>;
+
+    /// Moves a tender to a status if it is in one of `from`, `None` when it
+    /// is not
+    async fn set_status(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        tender_id: Uuid,
+        from: &[TenderStatus],
+        to: TenderStatus,
+    ) -> Result<Option<TimberTender>>;
+
+    /// The parcels of a tender in the order they were offered
+    async fn parcels(&self, conn: &mut PgConnection, tender_id: Uuid) -> Result<Vec<TenderParcel>>;
+
+    /// Number of bids per parcel of a tender, for parcels with bids
+    async fn bid_counts(&self, conn: &mut PgConnection, tender_id: Uuid) -> Result<Vec<(Uuid, i64)>>;
+
+    /// Stores a captured bid
+    async fn create_bid(&self, conn: &mut PgConnection, bid: &TenderBid) -> Result<TenderBid>;
+
+    /// Finds a bid of a tender
+    async fn find_bid(&self, conn: &mut PgConnection, tender_id: Uuid, bid_id: Uuid) -> Result<TenderBid>;
+
+    /// The bids of a tender by parcel, highest price first
+    async fn list_bids(&self, conn: &mut PgConnection, tender_id: Uuid) -> Result<Vec<TenderBid>>;
+
+    /// Awards a parcel to the contract's bid and stores the contract, `None`
+    /// when the parcel was already awarded
+    async fn award(&self, conn: &mut PgConnection, contract: &SaleContract) -> Result<Option<SaleContract>>;
+
+    /// Finds one of an organization's sale contracts
+    async fn find_contract(&self, conn: &mut PgConnection, organization: Uuid, contract_id: Uuid) -> Result<SaleContract>;
+
+    /// Lists an organization's sale contracts, newest first, optionally only
+    /// those of a block
+    async fn list_contracts(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        block_id: Option<Uuid>,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<SaleContract>>;
+
+    /// Counts an organization's sale contracts, optionally only those of a
+    /// block
+    async fn count_contracts(&self, conn: &mut PgConnection, organization: Uuid, block_id: Option<Uuid>) -> Result<RowCount>;
+}
+
+/// Concrete implementation of the timber sale repository
+pub struct TimberSaleRepositoryImpl;
+
+fn database_error(action: &str, e: diesel::result::Error) -> ApiError {
+    error!(
+        error_code = %ErrorCode::DatabaseError,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::database_error(format!("Failed to {}", action), None)
+}
+
+fn tender_not_found(tender_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Tender with id {} not found", tender_id))
+}
+
+fn bid_not_found(bid_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Bid with id {} not found", bid_id))
+}
+
+fn contract_not_found(contract_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Sale contract with id {} not found", contract_id))
+}
+
+#[async_trait]
+impl TimberSaleRepository for TimberSaleRepositoryImpl {
+    async fn create_tender(
+        &self,
+        conn: &mut PgConnection,
+        tender: &TimberTender,
+        parcels: &[TenderParcel],
+    ) -> Result<(TimberTender, Vec<TenderParcel>)> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let tender = diesel::insert_into(timber_tenders::table).values(tender).get_result(conn)?;
+            let parcels = diesel::insert_into(tender_parcels::table).values(parcels).get_results(conn)?;
+            Ok((tender, parcels))
+        })
+        .map_err(|e| database_error("create tender", e))
+    }
+
+    async fn find_tender(&self, conn: &mut PgConnection, organization: Uuid, tender_id: Uuid) -> Result<TimberTender> {
+        timber_tenders::table
+            .scoped(organization)
+            .filter(timber_tenders::id.eq(tender_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find tender", e))?
+            .ok_or_else(|| tender_not_found(tender_id))
+    }
+
+    async fn list_tenders(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        status: Option<TenderStatus>,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<TimberTender>> {
+        let mut query = timber_tenders::table
+            .scoped(organization)
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(timber_tenders::status.eq(status.as_str()));
+        }
+        query
+            .order_by((timber_tenders::created_at.desc(), timber_tenders::id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list tenders", e))
+    }
+
+    async fn count_tenders(&self, conn: &mut PgConnection, organization: Uuid, status: Option<TenderStatus>) -> Result<RowCount> {
+        let mut query = timber_tenders::table
+            .scoped(organization)
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(timber_tenders::status.eq(status.as_str()));
+        }
+        query
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count tenders", e))
+    }
+
+    async fn set_status(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        tender_id: Uuid,
+        from: &[TenderStatus],
+        to: TenderStatus,
+    ) -> Result<Option<TimberTender>> {
+        diesel::update(
+            timber_tenders::table
+                .scoped(organization)
+                .filter(timber_tenders::id.eq(tender_id))
+                .filter(timber_tenders::status.eq_any(from.iter().map(TenderStatus::as_str))),
+        )
+        .set((timber_tenders::status.eq(to.as_str()), timber_tenders::updated_at.eq(Utc::now())))
+        .get_result(conn)
+        .optional()
+        .map_err(|e| database_error("update tender status", e))
+    }
+
+    async fn parcels(&self, conn: &mut PgConnection, tender_id: Uuid) -> Result<Vec<TenderParcel>> {
+        tender_parcels::table
+            .filter(tender_parcels::tender_id.eq(tender_id))
+            .order_by(tender_parcels::position.asc())
+            .load(conn)
+            .map_err(|e| database_error("list tender parcels", e))
+    }
+
+    async fn bid_counts(&self, conn: &mut PgConnection, tender_id: Uuid) -> Result<Vec<(Uuid, i64)>> {
+        tender_bids::table
+            .filter(tender_bids::tender_id.eq(tender_id))
+            .group_by(tender_bids::parcel_id)
+            .select((tender_bids::parcel_id, diesel::dsl::count_star()))
+            .load(conn)
+            .map_err(|e| database_error("count bids", e))
+    }
+
+    async fn create_bid(&self, conn: &mut PgConnection, bid: &TenderBid) -> Result<TenderBid> {
+        diesel::insert_into(tender_bids::table)
+            .values(bid)
+            .get_result(conn)
+            .map_err(|e| database_error("capture bid", e))
+    }
+
+    async fn find_bid(&self, conn: &mut PgConnection, tender_id: Uuid, bid_id: Uuid) -> Result<TenderBid> {
+        tender_bids::table
+            .find(bid_id)
+            .filter(tender_bids::tender_id.eq(tender_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find bid", e))?
+            .ok_or_else(|| bid_not_found(bid_id))
+    }
+
+    async fn list_bids(&self, conn: &mut PgConnection, tender_id: Uuid) -> Result<Vec<TenderBid>> {
+        tender_bids::table
+            .filter(tender_bids::tender_id.eq(tender_id))
+            .order_by((
+                tender_bids::parcel_id.asc(),
+                tender_bids::price_per_m3.desc(),
+                tender_bids::received_at.asc(),
+            ))
+            .load(conn)
+            .map_err(|e| database_error("list bids", e))
+    }
+
+    async fn award(&self, conn: &mut PgConnection, contract: &SaleContract) -> Result<Option<SaleContract>> {
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let awarded = diesel::update(
+                tender_parcels::table
+                    .find(contract.parcel_id)
+                    .filter(tender_parcels::awarded_bid_id.is_null()),
+            )
+            .set(tender_parcels::awarded_bid_id.eq(contract.bid_id))
+            .execute(conn)?;
+            if awarded == 0 {
+                return Ok(None);
+            }
+            diesel::insert_into(sale_contracts::table)
+                .values(contract)
+                .get_result(conn)
+                .map(Some)
+        })
+        .map_err(|e| database_error("award parcel", e))
+    }
+
+    async fn find_contract(&self, conn: &mut PgConnection, organization: Uuid, contract_id: Uuid) -> Result<SaleContract> {
+        sale_contracts::table
+            .scoped(organization)
+            .filter(sale_contracts::id.eq(contract_id))
+            .first(conn)
+            .optional()
+            .map_err(|e| database_error("find sale contract", e))?
+            .ok_or_else(|| contract_not_found(contract_id))
+    }
+
+    async fn list_contracts(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        block_id: Option<Uuid>,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<SaleContract>> {
+        let mut query = sale_contracts::table
+            .scoped(organization)
+            .into_boxed();
+        if let Some(block_id) = block_id {
+            query = query.filter(sale_contracts::block_id.eq(block_id));
+        }
+        query
+            .order_by((sale_contracts::created_at.desc(), sale_contracts::id.desc()))
+            .offset(pagination.get_offset())
+            .limit(pagination.get_limit())
+            .load(conn)
+            .map_err(|e| database_error("list sale contracts", e))
+    }
+
+    async fn count_contracts(&self, conn: &mut PgConnection, organization: Uuid, block_id: Option<Uuid>) -> Result<RowCount> {
+        let mut query = sale_contracts::table
+            .scoped(organization)
+            .into_boxed();
+        if let Some(block_id) = block_id {
+            query = query.filter(sale_contracts::block_id.eq(block_id));
+        }
+        query
+            .count()
+            .get_result(conn)
+            .map(RowCount::exact)
+            .map_err(|e| database_error("count sale contracts", e))
+    }
+}
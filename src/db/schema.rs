@@ -1,18 +1,289 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "geometry"))]
+    pub struct Geometry;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "user_role"))]
     pub struct UserRole;
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+
+    activities (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        actor_id -> Nullable<Uuid>,
+        #[max_length = 64]
+        kind -> Varchar,
+        #[max_length = 32]
+        subject_type -> Varchar,
+        subject_id -> Uuid,
+        #[max_length = 255]
+        summary -> Varchar,
+        data -> Nullable<Jsonb>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    archives (id) {
+        id -> Uuid,
+        #[max_length = 100]
+        dataset -> Varchar,
+        #[max_length = 1024]
+        storage_key -> Varchar,
+        record_count -> Int8,
+        byte_size -> Int8,
+        range_start -> Timestamptz,
+        range_end -> Timestamptz,
+        rehydrated_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    auth_events (id) {
+        id -> Uuid,
+        user_id -> Nullable<Uuid>,
+        #[max_length = 50]
+        event -> Varchar,
+        #[max_length = 100]
+        method -> Nullable<Varchar>,
+        #[max_length = 255]
+        email -> Nullable<Varchar>,
+        #[max_length = 255]
+        reason -> Nullable<Varchar>,
+        #[max_length = 45]
+        ip_address -> Nullable<Varchar>,
+        #[max_length = 512]
+        user_agent -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+        #[max_length = 2]
+        country -> Nullable<Varchar>,
+        #[max_length = 20]
+        asn -> Nullable<Varchar>,
+        #[max_length = 255]
+        device_id -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    block_signoffs (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        block_id -> Uuid,
+        #[max_length = 50]
+        step -> Varchar,
+        signer_id -> Nullable<Uuid>,
+        #[max_length = 255]
+        signer_name -> Varchar,
+        #[max_length = 64]
+        document_hash -> Varchar,
+        signed_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    certifications (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 32]
+        kind -> Varchar,
+        #[max_length = 255]
+        name -> Nullable<Varchar>,
+        #[max_length = 100]
+        certificate_number -> Nullable<Varchar>,
+        #[max_length = 255]
+        issuer -> Nullable<Varchar>,
+        issued_on -> Date,
+        expires_on -> Nullable<Date>,
+        expiry_notified_at -> Nullable<Timestamptz>,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    change_history (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 50]
+        resource -> Varchar,
+        record_id -> Uuid,
+        #[max_length = 100]
+        field -> Varchar,
+        old_value -> Nullable<Jsonb>,
+        new_value -> Nullable<Jsonb>,
+        changed_by -> Nullable<Uuid>,
+        changed_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    crew_members (crew_id, user_id) {
+        crew_id -> Uuid,
+        user_id -> Uuid,
+        org_id -> Uuid,
+        added_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    crews (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        supervisor_id -> Nullable<Uuid>,
+        default_equipment -> Array<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    customers (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        #[max_length = 255]
+        contact_name -> Nullable<Varchar>,
+        #[max_length = 255]
+        email -> Nullable<Varchar>,
+        #[max_length = 255]
+        phone_number -> Nullable<Varchar>,
+        address -> Nullable<Text>,
+        notes -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    dead_letter_jobs (id) {
+        id -> Uuid,
+        #[max_length = 100]
+        kind -> Varchar,
+        payload -> Jsonb,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        failed_at -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    devices (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 255]
+        device_id -> Varchar,
+        #[max_length = 255]
+        name -> Nullable<Varchar>,
+        last_seen_at -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+        #[max_length = 50]
+        app_version -> Nullable<Varchar>,
+        battery_level -> Nullable<Int2>,
+        last_heartbeat_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    document_versions (id) {
+        id -> Uuid,
+        document_id -> Uuid,
+        version -> Int4,
+        #[max_length = 255]
+        filename -> Varchar,
+        #[max_length = 255]
+        content_type -> Varchar,
+        byte_size -> Int8,
+        file_key -> Text,
+        restored_from -> Nullable<Int4>,
+        uploaded_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    documents (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 16]
+        subject_type -> Varchar,
+        subject_id -> Uuid,
+        #[max_length = 100]
+        category -> Varchar,
+        #[max_length = 255]
+        title -> Varchar,
+        current_version -> Int4,
+        retain_until -> Nullable<Timestamptz>,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    email_change_tokens (id) {
+        id -> Uuid,
+        #[max_length = 255]
+        token_hash -> Varchar,
+        user_id -> Uuid,
+        #[max_length = 255]
+        new_email -> Varchar,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
 
     email_verification_tokens (id) {
         id -> Uuid,
         #[max_length = 255]
-        token -> Varchar,
+        token_hash -> Varchar,
         user_id -> Uuid,
         expires_at -> Timestamptz,
         created_at -> Timestamptz,
@@ -24,10 +295,109 @@ diesel::table! {
 diesel::table! {
     use diesel::sql_types::*;
 
-    organizations (id) {
+    erp_connections (id) {
         id -> Uuid,
+        org_id -> Uuid,
         #[max_length = 255]
         name -> Varchar,
+        #[max_length = 50]
+        connector -> Varchar,
+        settings -> Jsonb,
+        mappings -> Jsonb,
+        #[max_length = 255]
+        schedule -> Nullable<Varchar>,
+        next_run_at -> Nullable<Timestamptz>,
+        enabled -> Bool,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    erp_exports (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        connection_id -> Uuid,
+        #[max_length = 100]
+        source -> Varchar,
+        #[max_length = 16]
+        status -> Varchar,
+        period_start -> Nullable<Timestamptz>,
+        period_end -> Timestamptz,
+        record_count -> Int4,
+        file_key -> Text,
+        remote_ref -> Nullable<Text>,
+        attempts -> Int4,
+        error -> Nullable<Text>,
+        delivered_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Geometry;
+
+    harvest_blocks (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 100]
+        license -> Varchar,
+        #[max_length = 50]
+        block_number -> Varchar,
+        area_ha -> Float8,
+        planned_volume_m3 -> Float8,
+        #[max_length = 16]
+        status -> Varchar,
+        boundary -> Geometry,
+        notes -> Nullable<Text>,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,        #[max_length = 20]
+        soil_sensitivity -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    imports (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 100]
+        target -> Varchar,
+        #[max_length = 255]
+        filename -> Varchar,
+        file_key -> Text,
+        headers -> Array<Text>,
+        mapping -> Jsonb,
+        #[max_length = 16]
+        status -> Varchar,
+        total_rows -> Int4,
+        imported_rows -> Nullable<Int4>,
+        failed_rows -> Nullable<Int4>,
+        error_key -> Nullable<Text>,
+        error -> Nullable<Text>,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    legal_holds (id) {
+        id -> Uuid,
+        #[max_length = 100]
+        entity_type -> Varchar,
+        entity_id -> Uuid,
+        reason -> Text,
+        placed_by -> Nullable<Uuid>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         deleted_at -> Nullable<Timestamptz>,
@@ -37,11 +407,29 @@ diesel::table! {
 diesel::table! {
     use diesel::sql_types::*;
 
-    password_reset_tokens (id) {
+    machine_telemetry (org_id, machine_id, recorded_at) {
+        org_id -> Uuid,
+        #[max_length = 100]
+        machine_id -> Varchar,
+        recorded_at -> Timestamptz,
+        engine_hours -> Nullable<Float8>,
+        fuel_used_l -> Nullable<Float8>,
+        latitude -> Nullable<Float8>,
+        longitude -> Nullable<Float8>,
+        received_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    magic_link_tokens (id) {
         id -> Uuid,
         #[max_length = 255]
-        token -> Varchar,
+        token_hash -> Varchar,
         user_id -> Uuid,
+        #[max_length = 255]
+        device_id -> Nullable<Varchar>,
         expires_at -> Timestamptz,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
@@ -52,18 +440,636 @@ diesel::table! {
 diesel::table! {
     use diesel::sql_types::*;
 
-    refresh_tokens (id) {
+    notifications (id) {
         id -> Uuid,
-        #[max_length = 255]
-        token -> Varchar,
         user_id -> Uuid,
+        org_id -> Nullable<Uuid>,
+        #[max_length = 100]
+        kind -> Varchar,
+        #[max_length = 255]
+        title -> Varchar,
+        body -> Nullable<Text>,
+        #[max_length = 2048]
+        link -> Nullable<Varchar>,
+        data -> Nullable<Jsonb>,
+        read_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::UserRole;
+
+    org_invitations (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 255]
+        email -> Varchar,
+        role -> UserRole,
+        invited_by -> Nullable<Uuid>,
         expires_at -> Timestamptz,
+        accepted_at -> Nullable<Timestamptz>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         deleted_at -> Nullable<Timestamptz>,
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+
+    organization_archives (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        requested_by -> Nullable<Uuid>,
+        #[max_length = 16]
+        status -> Varchar,
+        #[max_length = 255]
+        file_key -> Nullable<Varchar>,
+        byte_size -> Nullable<Int8>,
+        error -> Nullable<Text>,
+        created_at -> Timestamptz,
+        completed_at -> Nullable<Timestamptz>,
+        unarchived_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    organization_email_senders (org_id) {
+        org_id -> Uuid,
+        #[max_length = 255]
+        from_address -> Varchar,
+        #[max_length = 255]
+        from_name -> Nullable<Varchar>,
+        #[max_length = 255]
+        reply_to -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    organization_quotas (org_id) {
+        org_id -> Uuid,
+        max_users -> Nullable<Int4>,
+        max_active_blocks -> Nullable<Int4>,
+        telemetry_retention_days -> Nullable<Int4>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    organization_settings (org_id) {
+        org_id -> Uuid,
+        settings -> Jsonb,
+        updated_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::UserRole;
+
+    organization_sso_domains (domain) {
+        #[max_length = 255]
+        domain -> Varchar,
+        org_id -> Uuid,
+        role -> UserRole,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    organizations (id) {
+        id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+        parent_id -> Nullable<Uuid>,
+        owner_id -> Nullable<Uuid>,
+        archived_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    ownership_transfers (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        from_user_id -> Uuid,
+        to_user_id -> Uuid,
+        expires_at -> Timestamptz,
+        accepted_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    passkeys (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 1024]
+        credential_id -> Varchar,
+        public_key -> Bytea,
+        sign_count -> Int8,
+        #[max_length = 255]
+        name -> Varchar,
+        last_used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    password_reset_tokens (id) {
+        id -> Uuid,
+        #[max_length = 255]
+        token_hash -> Varchar,
+        user_id -> Uuid,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    queued_jobs (id) {
+        id -> Uuid,
+        #[max_length = 100]
+        kind -> Varchar,
+        payload -> Jsonb,
+        attempts -> Int4,
+        max_attempts -> Int4,
+        run_at -> Timestamptz,
+        #[max_length = 255]
+        locked_by -> Nullable<Varchar>,
+        locked_until -> Nullable<Timestamptz>,
+        last_error -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    refresh_tokens (id) {
+        id -> Uuid,
+        #[max_length = 255]
+        token -> Varchar,
+        user_id -> Uuid,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+        #[max_length = 255]
+        device_id -> Nullable<Varchar>,
+        session_expires_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    report_deliveries (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        report_id -> Uuid,
+        recipients -> Array<Text>,
+        #[max_length = 16]
+        format -> Varchar,
+        #[max_length = 16]
+        status -> Varchar,
+        row_count -> Nullable<Int4>,
+        file_key -> Nullable<Text>,
+        error -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    report_schedules (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        report_id -> Uuid,
+        #[max_length = 255]
+        schedule -> Varchar,
+        recipients -> Array<Text>,
+        #[max_length = 16]
+        format -> Varchar,
+        parameters -> Jsonb,
+        next_run_at -> Timestamptz,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    reports (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        description -> Nullable<Text>,
+        definition -> Jsonb,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::UserRole;
+
+    role_permissions (role, permission) {
+        role -> UserRole,
+        #[max_length = 100]
+        permission -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    roles (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        description -> Nullable<Text>,
+        permissions -> Array<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    sale_contracts (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        tender_id -> Uuid,
+        parcel_id -> Uuid,
+        bid_id -> Uuid,
+        #[max_length = 255]
+        buyer_name -> Varchar,
+        block_id -> Nullable<Uuid>,
+        #[max_length = 100]
+        assortment -> Varchar,
+        volume_m3 -> Float8,
+        price_per_m3 -> Float8,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    saved_view_defaults (user_id, resource) {
+        user_id -> Uuid,
+        #[max_length = 50]
+        resource -> Varchar,
+        view_id -> Uuid,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    saved_views (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 50]
+        resource -> Varchar,
+        #[max_length = 100]
+        name -> Varchar,
+        filters -> Jsonb,
+        sort -> Array<Text>,
+        #[sql_name = "columns"]
+        view_columns -> Array<Text>,
+        shared -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    scheduled_jobs (name) {
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 255]
+        schedule -> Nullable<Varchar>,
+        next_run_at -> Timestamptz,
+        #[max_length = 255]
+        locked_by -> Nullable<Varchar>,
+        locked_until -> Nullable<Timestamptz>,
+        last_started_at -> Nullable<Timestamptz>,
+        last_finished_at -> Nullable<Timestamptz>,
+        #[max_length = 20]
+        last_status -> Nullable<Varchar>,
+        last_error -> Nullable<Text>,
+        last_duration_ms -> Nullable<Int8>,
+        last_output -> Nullable<Jsonb>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    scim_tokens (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        created_by -> Nullable<Uuid>,
+        last_used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    stands (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        block_id -> Uuid,
+        #[max_length = 50]
+        stand_number -> Varchar,
+        area_ha -> Float8,
+        species -> Jsonb,
+        age_class -> Int2,
+        site_index -> Float8,
+        net_merchantable_volume_m3 -> Float8,
+        inventoried_on -> Nullable<Date>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        windthrow_conditions -> Nullable<Jsonb>,
+        windthrow_score -> Nullable<Float8>,
+        #[max_length = 20]
+        windthrow_class -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    stripe_events (id) {
+        #[max_length = 255]
+        id -> Varchar,
+        #[max_length = 100]
+        kind -> Varchar,
+        processed_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    subscriptions (org_id) {
+        org_id -> Uuid,
+        #[max_length = 32]
+        plan -> Varchar,
+        #[max_length = 32]
+        status -> Varchar,
+        #[max_length = 255]
+        stripe_customer_id -> Nullable<Varchar>,
+        #[max_length = 255]
+        stripe_subscription_id -> Nullable<Varchar>,
+        current_period_end -> Nullable<Timestamptz>,
+        cancel_at_period_end -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    supply_contracts (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        customer_id -> Uuid,
+        #[max_length = 100]
+        reference -> Varchar,
+        #[max_length = 100]
+        product -> Varchar,
+        volume_m3 -> Float8,
+        price_per_m3 -> Float8,
+        starts_on -> Date,
+        ends_on -> Date,
+        notes -> Nullable<Text>,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    taggings (tag_id, subject_type, subject_id) {
+        tag_id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 16]
+        subject_type -> Varchar,
+        subject_id -> Uuid,
+        tagged_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    tags (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 50]
+        name -> Varchar,
+        #[max_length = 7]
+        color -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    telemetry_api_keys (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 64]
+        key_hash -> Varchar,
+        created_by -> Nullable<Uuid>,
+        last_used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    tender_bids (id) {
+        id -> Uuid,
+        tender_id -> Uuid,
+        parcel_id -> Uuid,
+        #[max_length = 255]
+        bidder_name -> Varchar,
+        #[max_length = 255]
+        bidder_email -> Nullable<Varchar>,
+        price_per_m3 -> Float8,
+        received_at -> Timestamptz,
+        captured_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    tender_parcels (id) {
+        id -> Uuid,
+        tender_id -> Uuid,
+        position -> Int4,
+        block_id -> Nullable<Uuid>,
+        #[max_length = 100]
+        assortment -> Varchar,
+        volume_m3 -> Float8,
+        reserve_price -> Nullable<Float8>,
+        description -> Nullable<Text>,
+        awarded_bid_id -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    timber_tenders (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        #[max_length = 255]
+        title -> Varchar,
+        description -> Nullable<Text>,
+        #[max_length = 16]
+        status -> Varchar,
+        bid_deadline -> Timestamptz,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    user_activities (id) {
+        id -> Uuid,
+        org_id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 64]
+        action -> Varchar,
+        #[max_length = 32]
+        subject_type -> Varchar,
+        subject_id -> Uuid,
+        #[max_length = 255]
+        summary -> Varchar,
+        data -> Nullable<Jsonb>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    user_identities (provider, subject) {
+        #[max_length = 50]
+        provider -> Varchar,
+        #[max_length = 255]
+        subject -> Varchar,
+        user_id -> Uuid,
+        #[max_length = 255]
+        email -> Varchar,
+        created_at -> Timestamptz,
+        last_login_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    user_preferences (user_id) {
+        user_id -> Uuid,
+        #[max_length = 16]
+        units -> Varchar,
+        #[max_length = 16]
+        timezone -> Varchar,
+        #[max_length = 16]
+        locale -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    user_roles (user_id, role_id) {
+        user_id -> Uuid,
+        role_id -> Uuid,
+        assigned_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::UserRole;
@@ -86,18 +1092,166 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         deleted_at -> Nullable<Timestamptz>,
+        avatar_updated_at -> Nullable<Timestamptz>,
+        is_active -> Bool,
+        supervisor_id -> Nullable<Uuid>,
+        anonymized_at -> Nullable<Timestamptz>,
     }
 }
 
+diesel::joinable!(activities -> organizations (org_id));
+diesel::joinable!(activities -> users (actor_id));
+diesel::joinable!(auth_events -> users (user_id));
+diesel::joinable!(block_signoffs -> organizations (org_id));
+diesel::joinable!(block_signoffs -> users (signer_id));
+diesel::joinable!(certifications -> organizations (org_id));
+diesel::joinable!(certifications -> users (user_id));
+diesel::joinable!(change_history -> organizations (org_id));
+diesel::joinable!(change_history -> users (changed_by));
+diesel::joinable!(crew_members -> crews (crew_id));
+diesel::joinable!(crew_members -> organizations (org_id));
+diesel::joinable!(crew_members -> users (user_id));
+diesel::joinable!(crews -> organizations (org_id));
+diesel::joinable!(crews -> users (supervisor_id));
+diesel::joinable!(customers -> organizations (org_id));
+diesel::joinable!(devices -> users (user_id));
+diesel::joinable!(document_versions -> documents (document_id));
+diesel::joinable!(document_versions -> users (uploaded_by));
+diesel::joinable!(documents -> organizations (org_id));
+diesel::joinable!(documents -> users (created_by));
+diesel::joinable!(email_change_tokens -> users (user_id));
 diesel::joinable!(email_verification_tokens -> users (user_id));
+diesel::joinable!(erp_connections -> organizations (org_id));
+diesel::joinable!(erp_connections -> users (created_by));
+diesel::joinable!(erp_exports -> erp_connections (connection_id));
+diesel::joinable!(erp_exports -> organizations (org_id));
+diesel::joinable!(harvest_blocks -> organizations (org_id));
+diesel::joinable!(harvest_blocks -> users (created_by));
+diesel::joinable!(imports -> organizations (org_id));
+diesel::joinable!(imports -> users (created_by));
+diesel::joinable!(legal_holds -> users (placed_by));
+diesel::joinable!(machine_telemetry -> organizations (org_id));
+diesel::joinable!(magic_link_tokens -> users (user_id));
+diesel::joinable!(notifications -> organizations (org_id));
+diesel::joinable!(notifications -> users (user_id));
+diesel::joinable!(org_invitations -> organizations (org_id));
+diesel::joinable!(org_invitations -> users (invited_by));
+diesel::joinable!(organization_archives -> organizations (org_id));
+diesel::joinable!(organization_archives -> users (requested_by));
+diesel::joinable!(organization_email_senders -> organizations (org_id));
+diesel::joinable!(organization_quotas -> organizations (org_id));
+diesel::joinable!(organization_settings -> organizations (org_id));
+diesel::joinable!(organization_settings -> users (updated_by));
+diesel::joinable!(organization_sso_domains -> organizations (org_id));
+diesel::joinable!(ownership_transfers -> organizations (org_id));
+diesel::joinable!(passkeys -> users (user_id));
 diesel::joinable!(password_reset_tokens -> users (user_id));
 diesel::joinable!(refresh_tokens -> users (user_id));
+diesel::joinable!(report_deliveries -> organizations (org_id));
+diesel::joinable!(report_deliveries -> reports (report_id));
+diesel::joinable!(report_schedules -> organizations (org_id));
+diesel::joinable!(report_schedules -> reports (report_id));
+diesel::joinable!(report_schedules -> users (created_by));
+diesel::joinable!(reports -> organizations (org_id));
+diesel::joinable!(reports -> users (created_by));
+diesel::joinable!(roles -> organizations (org_id));
+diesel::joinable!(sale_contracts -> harvest_blocks (block_id));
+diesel::joinable!(sale_contracts -> organizations (org_id));
+diesel::joinable!(sale_contracts -> tender_bids (bid_id));
+diesel::joinable!(sale_contracts -> tender_parcels (parcel_id));
+diesel::joinable!(sale_contracts -> timber_tenders (tender_id));
+diesel::joinable!(sale_contracts -> users (created_by));
+diesel::joinable!(saved_view_defaults -> saved_views (view_id));
+diesel::joinable!(saved_view_defaults -> users (user_id));
+diesel::joinable!(saved_views -> organizations (org_id));
+diesel::joinable!(saved_views -> users (user_id));
+diesel::joinable!(scim_tokens -> organizations (org_id));
+diesel::joinable!(scim_tokens -> users (created_by));
+diesel::joinable!(stands -> harvest_blocks (block_id));
+diesel::joinable!(stands -> organizations (org_id));
+diesel::joinable!(subscriptions -> organizations (org_id));
+diesel::joinable!(supply_contracts -> customers (customer_id));
+diesel::joinable!(supply_contracts -> organizations (org_id));
+diesel::joinable!(supply_contracts -> users (created_by));
+diesel::joinable!(taggings -> organizations (org_id));
+diesel::joinable!(taggings -> tags (tag_id));
+diesel::joinable!(taggings -> users (tagged_by));
+diesel::joinable!(tags -> organizations (org_id));
+diesel::joinable!(telemetry_api_keys -> organizations (org_id));
+diesel::joinable!(telemetry_api_keys -> users (created_by));
+diesel::joinable!(tender_bids -> timber_tenders (tender_id));
+diesel::joinable!(tender_bids -> users (captured_by));
+diesel::joinable!(tender_parcels -> harvest_blocks (block_id));
+diesel::joinable!(tender_parcels -> timber_tenders (tender_id));
+diesel::joinable!(timber_tenders -> organizations (org_id));
+diesel::joinable!(timber_tenders -> users (created_by));
+diesel::joinable!(user_activities -> organizations (org_id));
+diesel::joinable!(user_activities -> users (user_id));
+diesel::joinable!(user_identities -> users (user_id));
+diesel::joinable!(user_preferences -> users (user_id));
+diesel::joinable!(user_roles -> roles (role_id));
+diesel::joinable!(user_roles -> users (user_id));
 diesel::joinable!(users -> organizations (org_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    activities,
+    archives,
+    auth_events,
+    block_signoffs,
+    certifications,
+    change_history,
+    crew_members,
+    crews,
+    customers,
+    dead_letter_jobs,
+    devices,
+    document_versions,
+    documents,
+    email_change_tokens,
     email_verification_tokens,
+    erp_connections,
+    erp_exports,
+    harvest_blocks,
+    imports,
+    legal_holds,
+    machine_telemetry,
+    magic_link_tokens,
+    notifications,
+    org_invitations,
+    organization_archives,
+    organization_email_senders,
+    organization_quotas,
+    organization_settings,
+    organization_sso_domains,
     organizations,
+    ownership_transfers,
+    passkeys,
     password_reset_tokens,
+    queued_jobs,
     refresh_tokens,
+    report_deliveries,
+    report_schedules,
+    reports,
+    role_permissions,
+    roles,
+    sale_contracts,
+    saved_view_defaults,
+    saved_views,
+    scheduled_jobs,
+    scim_tokens,
+    stands,
+    stripe_events,
+    subscriptions,
+    supply_contracts,
+    taggings,
+    tags,
+    telemetry_api_keys,
+    tender_bids,
+    tender_parcels,
+    timber_tenders,
+    user_activities,
+    user_identities,
+    user_preferences,
+    user_roles,
     users,
 );
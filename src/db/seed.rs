@@ -0,0 +1,331 @@
+//! Reproducible datasets for demos and UI development
+//!
+//! `rust_server seed --profile demo` fills the configured database with a
+//! realistic organization. Every id, name and timestamp comes from a fixed
+//! RNG seed and a fixed date, so two runs, or two machines, show the same
+//! data; rows that already exist are left alone, so seeding again is a
+//! no-op. Only password hashes differ, as Argon2 salts are random.
+//!
+//! Harvest blocks are laid out across the year in every status, each with
+//! a dozen or so inventoried stands. Crews, equipment, production and
+//! optimization runs are not seeded yet: crews show up as groups of
+//! operators and the year of operations as their notifications.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use diesel::prelude::*;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use uuid::{Builder, Uuid};
+
+use crate::{
+    db::{
+        models::{
+            auth::{Role, User},
+            BlockStatus, Boundary, HarvestBlock, Notification, Organization, SpeciesShare, Stand,
+        },
+        schema::{harvest_blocks, notifications, organizations, stands, users},
+        spatial::geometry_from_geojson,
+    },
+    domain::operability::SoilSensitivity,
+    error::{ApiError, DatabaseError, Result},
+};
+
+/// Password of every demo user
+pub const DEMO_PASSWORD: &str = "demo-password";
+
+const DEMO_SEED: u64 = 0x5EED_F0E5_7000;
+const DEMO_CREWS: usize = 4;
+const DEMO_CREW_SIZE: usize = 4;
+const DEMO_BLOCKS: usize = 40;
+const DEMO_LICENSES: &[&str] = &["FL-A18157", "FL-A20934", "CP-1142"];
+const DEMO_STANDS: usize = 500;
+const SPECIES: &[&str] = &["spruce", "pine", "birch", "aspen"];
+
+pub(crate) const FIRST_NAMES: &[&str] = &[
+    "Aino", "Birgit", "Carlos", "Dana", "Erik", "Fatima", "Gustav", "Hanna", "Ilkka", "Jonas",
+    "Kaisa", "Lars", "Maja", "Nils", "Oona", "Pekka", "Riikka", "Sami", "Tove", "Ulla",
+];
+pub(crate) const LAST_NAMES: &[&str] = &[
+    "Andersson", "Berg", "Halvorsen", "Koskinen", "Lindqvist", "Mäkinen", "Nyberg", "Ohlsson",
+    "Rantanen", "Sandberg", "Virtanen", "Wikström",
+];
+const NOTIFICATION_KINDS: &[(&str, &str)] = &[
+    ("block_assigned", "New block assigned to your crew"),
+    ("load_delivered", "Load delivered to the mill"),
+    ("scale_ticket_disputed", "Scale ticket needs review"),
+    ("run_completed", "Harvest optimization finished"),
+];
+
+/// Dataset written by [`seed`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SeedProfile {
+    /// One organization with managers, crews of operators, harvest blocks
+    /// with their stands and a year of notifications
+    Demo,
+}
+
+/// Rows in the dataset, whether inserted now or by an earlier run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedReport {
+    pub organization_id: Uuid,
+    pub users: usize,
+    pub notifications: usize,
+    pub blocks: usize,
+    pub stands: usize,
+    /// Rows actually inserted by this run
+    pub inserted: usize,
+}
+
+/// Writes `profile` to the database in one transaction
+pub async fn seed(conn: &mut PgConnection, profile: SeedProfile) -> Result<SeedReport> {
+    match profile {
+        SeedProfile::Demo => {
+            let dataset = demo_dataset()?;
+            conn.transaction(|conn| dataset.insert(conn))
+                .map_err(|e| ApiError::from(DatabaseError::from(e)))
+        }
+    }
+}
+
+struct Dataset {
+    organization: Organization,
+    users: Vec<User>,
+    notifications: Vec<Notification>,
+    blocks: Vec<HarvestBlock>,
+    stands: Vec<Stand>,
+}
+
+impl Dataset {
+    fn insert(&self, conn: &mut PgConnection) -> QueryResult<SeedReport> {
+        let mut inserted = diesel::insert_into(organizations::table)
+            .values(&self.organization)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        inserted += diesel::insert_into(users::table)
+            .values(&self.users)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        inserted += diesel::insert_into(notifications::table)
+            .values(&self.notifications)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        for block in &self.blocks {
+            inserted += diesel::insert_into(harvest_blocks::table)
+                .values((
+                    harvest_blocks::id.eq(block.id),
+                    harvest_blocks::org_id.eq(block.org_id),
+                    harvest_blocks::license.eq(&block.license),
+                    harvest_blocks::block_number.eq(&block.block_number),
+                    harvest_blocks::area_ha.eq(block.area_ha),
+                    harvest_blocks::planned_volume_m3.eq(block.planned_volume_m3),
+                    harvest_blocks::status.eq(&block.status),
+                    harvest_blocks::boundary.eq(geometry_from_geojson(&block.boundary.to_geojson())),
+                    harvest_blocks::notes.eq(&block.notes),
+                    harvest_blocks::created_by.eq(block.created_by),
+                    harvest_blocks::created_at.eq(block.created_at),
+                    harvest_blocks::updated_at.eq(block.updated_at),
+                    harvest_blocks::soil_sensitivity.eq(&block.soil_sensitivity),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+        }
+        inserted += diesel::insert_into(stands::table)
+            .values(&self.stands)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        Ok(SeedReport {
+            organization_id: self.organization.id,
+            users: self.users.len(),
+            notifications: self.notifications.len(),
+            blocks: self.blocks.len(),
+            stands: self.stands.len(),
+            inserted,
+        })
+    }
+}
+
+/// The demo organization as of 2026-01-01, with a year of history before it
+fn demo_dataset() -> Result<Dataset> {
+    let mut rng = ChaCha8Rng::seed_from_u64(DEMO_SEED);
+    let now: DateTime<Utc> = Utc.with_ymd_and_hms(2026, 1, 1, 6, 0, 0).unwrap();
+    let year_ago = now - Duration::days(365);
+    let password = User::hash_password(DEMO_PASSWORD)?;
+
+    let organization = Organization {
+        id: next_id(&mut rng),
+        name: "Northwood Timber Co. (demo)".to_string(),
+        created_at: year_ago,
+        updated_at: year_ago,
+        deleted_at: None,
+        parent_id: None,
+        owner_id: None,
+        archived_at: None,
+    };
+
+    let mut people = vec![
+        (Role::Admin, "admin".to_string()),
+        (Role::Manager, "manager.harvest".to_string()),
+        (Role::Manager, "manager.logistics".to_string()),
+    ];
+    for crew in 1..=DEMO_CREWS {
+        for member in 1..=DEMO_CREW_SIZE {
+            people.push((Role::Operator, format!("crew{}.operator{}", crew, member)));
+        }
+    }
+    let users: Vec<User> = people
+        .into_iter()
+        .enumerate()
+        .map(|(index, (role, handle))| User {
+            id: next_id(&mut rng),
+            first_name: FIRST_NAMES.choose(&mut rng).unwrap().to_string(),
+            last_name: LAST_NAMES.choose(&mut rng).unwrap().to_string(),
+            email: format!("{}@demo.forestry-optimizer.local", handle),
+            phone_number: format!("+1555010{:04}", index),
+            password: password.clone(),
+            org_id: organization.id,
+            role,
+            email_verified: true,
+            avatar_updated_at: None,
+            is_active: true,
+            supervisor_id: None,
+            anonymized_at: None,
+            created_at: year_ago,
+            updated_at: year_ago,
+            deleted_at: None,
+        })
+        .collect();
+
+    let mut notifications = Vec::new();
+    for user in users.iter().filter(|user| user.role != Role::Admin) {
+        for _ in 0..rng.gen_range(6..=12) {
+            let (kind, title) = *NOTIFICATION_KINDS.choose(&mut rng).unwrap();
+            let created_at = year_ago + Duration::minutes(rng.gen_range(0..365 * 24 * 60));
+            // Older notifications have mostly been read
+            let read_at = (created_at < now - Duration::days(14) && rng.gen_bool(0.9))
+                .then(|| created_at + Duration::hours(rng.gen_range(1..48)));
+            notifications.push(Notification {
+                id: next_id(&mut rng),
+                user_id: user.id,
+                org_id: Some(organization.id),
+                kind: kind.to_string(),
+                title: title.to_string(),
+                body: None,
+                link: None,
+                data: None,
+                read_at,
+                created_at,
+            });
+        }
+    }
+
+    let planner = users.iter().find(|user| user.role == Role::Manager).map(|user| user.id);
+    let sensitivities = [SoilSensitivity::Low, SoilSensitivity::Moderate, SoilSensitivity::High, SoilSensitivity::VeryHigh];
+    let blocks: Vec<HarvestBlock> = (0..DEMO_BLOCKS)
+        .map(|index| {
+            let license = DEMO_LICENSES[index % DEMO_LICENSES.len()];
+            // Older blocks are further along
+            let status = match index * 4 / DEMO_BLOCKS {
+                0 => BlockStatus::Completed,
+                1 => BlockStatus::Active,
+                2 => BlockStatus::Approved,
+                _ => BlockStatus::Planned,
+            };
+            let (lon, lat) = (24.0 + rng.gen_range(0.0..2.0), 61.5 + rng.gen_range(0.0..1.0));
+            let size = rng.gen_range(0.004..0.012);
+            let area_ha: f64 = rng.gen_range(40..400) as f64 / 10.0;
+            let created_at = year_ago + Duration::days((index * 365 / DEMO_BLOCKS) as i64);
+            HarvestBlock {
+                id: next_id(&mut rng),
+                org_id: organization.id,
+                license: license.to_string(),
+                block_number: (index / DEMO_LICENSES.len() + 1).to_string(),
+                area_ha,
+                planned_volume_m3: (area_ha * rng.gen_range(150.0..320.0)).round(),
+                status: status.to_string(),
+                boundary: Boundary::Polygon {
+                    coordinates: vec![vec![[lon, lat], [lon + size, lat], [lon + size, lat + size], [lon, lat + size], [lon, lat]]],
+                },
+                notes: None,
+                created_by: planner,
+                created_at,
+                updated_at: created_at,
+                soil_sensitivity: serde_json::to_value(sensitivities.choose(&mut rng))
+                    .ok()
+                    .and_then(|sensitivity| sensitivity.as_str().map(str::to_string)),
+            }
+        })
+        .collect();
+
+    // Dealt out over the blocks in turn, each inventoried when it was laid out
+    let stands = (0..DEMO_STANDS)
+        .map(|index| {
+            let block = &blocks[index % blocks.len()];
+            let leading = rng.gen_range(5..=10) * 10;
+            let mut species = SPECIES.choose_multiple(&mut rng, 2);
+            let mut mix = vec![SpeciesShare { species: species.next().unwrap().to_string(), percent: leading as f64 }];
+            if leading < 100 {
+                mix.push(SpeciesShare { species: species.next().unwrap().to_string(), percent: (100 - leading) as f64 });
+            }
+            let area_ha: f64 = rng.gen_range(5..60) as f64 / 10.0;
+            Stand {
+                id: next_id(&mut rng),
+                org_id: organization.id,
+                block_id: block.id,
+                stand_number: (index / blocks.len() + 1).to_string(),
+                area_ha,
+                species: serde_json::to_value(mix).unwrap_or_default(),
+                age_class: rng.gen_range(3..=8),
+                site_index: rng.gen_range(120..=300) as f64 / 10.0,
+                net_merchantable_volume_m3: (area_ha * rng.gen_range(120.0..340.0)).round(),
+                inventoried_on: Some(block.created_at.date_naive()),
+                created_at: block.created_at,
+                updated_at: block.created_at,
+                windthrow_conditions: None,
+                windthrow_score: None,
+                windthrow_class: None,
+            }
+        })
+        .collect();
+
+    Ok(Dataset { organization, users, notifications, blocks, stands })
+}
+
+fn next_id(rng: &mut ChaCha8Rng) -> Uuid {
+    Builder::from_random_bytes(rng.gen()).into_uuid()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demo_dataset_is_deterministic() {
+        let first = demo_dataset().unwrap();
+        let second = demo_dataset().unwrap();
+        assert_eq!(first.organization.id, second.organization.id);
+        assert_eq!(
+            first.users.iter().map(|u| (u.id, &u.email, &u.first_name)).collect::<Vec<_>>(),
+            second.users.iter().map(|u| (u.id, &u.email, &u.first_name)).collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            first.notifications.iter().map(|n| (n.id, n.created_at, n.read_at)).collect::<Vec<_>>(),
+            second.notifications.iter().map(|n| (n.id, n.created_at, n.read_at)).collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            first.blocks.iter().map(|b| (b.id, &b.block_number, &b.boundary)).collect::<Vec<_>>(),
+            second.blocks.iter().map(|b| (b.id, &b.block_number, &b.boundary)).collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            first.stands.iter().map(|s| (s.id, s.block_id, &s.stand_number, &s.species)).collect::<Vec<_>>(),
+            second.stands.iter().map(|s| (s.id, s.block_id, &s.stand_number, &s.species)).collect::<Vec<_>>(),
+        );
+        assert_eq!(first.blocks.len(), DEMO_BLOCKS);
+        assert_eq!(first.stands.len(), DEMO_STANDS);
+        assert!(first.blocks.iter().all(|b| first.stands.iter().any(|s| s.block_id == b.id)));
+        assert!(first.stands.iter().all(|s| s.species().iter().map(|share| share.percent).sum::<f64>() == 100.0));
+        assert!(BlockStatus::ALL.iter().all(|status| first.blocks.iter().any(|b| b.status == status.as_str())));
+        assert_eq!(first.users.len(), 3 + DEMO_CREWS * DEMO_CREW_SIZE);
+        assert!(User::verify_password(DEMO_PASSWORD, &first.users[0].password).unwrap());
+    }
+}
@@ -0,0 +1,62 @@
+//! PostGIS functions
+//!
+//! The extension is installed by the migration creating harvest blocks.
+//! Geometries cross the API as GeoJSON text, converted by [`st_asgeojson`]
+//! on reads and [`geometry_from_geojson`] on writes, so no Rust type maps
+//! the geometry column. Matching with [`st_intersects`] can use the GIST
+//! index on the compared column.
+
+use diesel::{
+    define_sql_function,
+    dsl::AsExprOf,
+    expression::AsExpression,
+    sql_types::{Double, Integer, Text},
+};
+
+use crate::db::schema::sql_types::Geometry;
+
+/// Spatial reference of stored geometries, WGS 84 longitude and latitude
+pub const WGS84: i32 = 4326;
+
+define_sql_function! {
+    /// `geom` as a GeoJSON geometry
+    fn st_asgeojson(geom: Geometry) -> Text;
+}
+
+define_sql_function! {
+    /// The geometry described by a GeoJSON geometry
+    fn st_geomfromgeojson(geojson: Text) -> Geometry;
+}
+
+define_sql_function! {
+    /// `geom` with its spatial reference set to `srid`
+    fn st_setsrid(geom: Geometry, srid: Integer) -> Geometry;
+}
+
+define_sql_function! {
+    /// The rectangle from (`xmin`, `ymin`) to (`xmax`, `ymax`)
+    fn st_makeenvelope(xmin: Double, ymin: Double, xmax: Double, ymax: Double, srid: Integer) -> Geometry;
+}
+
+define_sql_function! {
+    /// Whether `a` and `b` share any point
+    fn st_intersects(a: Geometry, b: Geometry) -> Bool;
+}
+
+/// The WGS 84 geometry described by `geojson`
+pub fn geometry_from_geojson(geojson: &str) -> st_setsrid<st_geomfromgeojson<AsExprOf<String, Text>>, AsExprOf<i32, Integer>> {
+    st_setsrid(
+        st_geomfromgeojson(AsExpression::<Text>::as_expression(geojson.to_string())),
+        AsExpression::<Integer>::as_expression(WGS84),
+    )
+}
+
+type Coordinate = AsExprOf<f64, Double>;
+
+/// The WGS 84 rectangle between two corners
+pub fn envelope(
+    min: (f64, f64),
+    max: (f64, f64),
+) -> st_makeenvelope<Coordinate, Coordinate, Coordinate, Coordinate, AsExprOf<i32, Integer>> {
+    st_makeenvelope(min.0, min.1, max.0, max.1, WGS84)
+}
@@ -0,0 +1,85 @@
+//! Tenant scoping of queries
+//!
+//! Rows of the tables listed in [`TENANT_TABLES`] belong to one
+//! organization. Repositories read and write them through
+//! [`TenantScoped::scoped`], which filters by the organization's `org_id`
+//! before any other condition, so a guessed id of another organization's
+//! row finds nothing. Tables gaining a non-null `org_id` column are added to
+//! the list; the schema test fails until they are.
+
+use diesel::{
+    dsl::{Eq, Filter},
+    prelude::*,
+    query_dsl::methods::FilterDsl,
+    sql_types::Uuid as SqlUuid,
+    Table,
+};
+use uuid::Uuid;
+
+use crate::db::schema;
+
+/// A table whose rows belong to one organization
+pub trait TenantScoped: Table + Sized {
+    /// The column holding the organization of a row
+    type OrgId: Column<Table = Self> + Expression<SqlType = SqlUuid> + Default;
+
+    /// The rows of organization `org_id`
+    fn scoped(self, org_id: Uuid) -> Filter<Self, Eq<Self::OrgId, Uuid>>
+    where
+        Self: FilterDsl<Eq<Self::OrgId, Uuid>>,
+    {
+        self.filter(Self::OrgId::default().eq(org_id))
+    }
+}
+
+macro_rules! tenant_tables {
+    ($($table:ident),* $(,)?) => {
+        $(
+            impl TenantScoped for schema::$table::table {
+                type OrgId = schema::$table::org_id;
+            }
+        )*
+
+        /// Names of the tables scoped to an organization
+        pub const TENANT_TABLES: &[&str] = &[$(stringify!($table)),*];
+    };
+}
+
+tenant_tables!(
+    activities,
+    block_signoffs,
+    certifications,
+    change_history,
+    crew_members,
+    crews,
+    customers,
+    documents,
+    erp_connections,
+    erp_exports,
+    harvest_blocks,
+    imports,
+    machine_telemetry,
+    org_invitations,
+    organization_archives,
+    organization_email_senders,
+    organization_quotas,
+    organization_settings,
+    organization_sso_domains,
+    ownership_transfers,
+    report_deliveries,
+    report_schedules,
+    reports,
+    roles,
+    sale_contracts,
+    saved_views,
+    scim_tokens,
+    stands,
+    subscriptions,
+    supply_contracts,
+    taggings,
+    tags,
+    telemetry_api_keys,
+    timber_tenders,
+    user_activities,
+    users,
+);
@@ -0,0 +1,28 @@
+//! `pg_trgm` functions and operators
+//!
+//! The extension is installed by the migration adding the user search
+//! indexes. Matching with [`word_similar_to`] can use a GIN `gin_trgm_ops`
+//! index on the searched column; [`word_similarity`] can't, so it is only
+//! used to rank rows already matched.
+
+use diesel::{
+    define_sql_function,
+    dsl::AsExprOf,
+    expression::AsExpression,
+    pg::Pg,
+    sql_types::Text,
+};
+
+define_sql_function! {
+    /// How closely `query` matches the most similar run of words in
+    /// `text`, from 0 to 1
+    fn word_similarity(query: Text, text: Text) -> Float4;
+}
+
+diesel::infix_operator!(WordSimilarTo, " <% ", backend: Pg);
+
+/// Whether `text` has a run of words similar to `query`, by
+/// `pg_trgm.word_similarity_threshold` (0.6 by default)
+pub fn word_similar_to<T>(query: &str, text: T) -> WordSimilarTo<AsExprOf<String, Text>, T> {
+    WordSimilarTo::new(AsExpression::<Text>::as_expression(query.to_string()), text)
+}
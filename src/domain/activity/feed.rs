@@ -0,0 +1,143 @@
+use chrono::Utc;
+use diesel::PgConnection;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        models::{auth::User, Activity, ActivityFilter},
+        repositories::{ActivityRepository, ActivityRepositoryImpl, OrganizationRepositoryImpl, Repository},
+    },
+    domain::organization::OrganizationService,
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+
+/// Longest summary stored, in characters
+const MAX_SUMMARY_CHARS: usize = 255;
+
+/// What happened in an organization
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedKind {
+    UserJoined,
+    MemberRoleChanged,
+    MemberRemoved,
+    BlockSignedOff,
+    OrganizationArchived,
+    OrganizationUnarchived,
+}
+
+impl FeedKind {
+    pub const ALL: [FeedKind; 6] = [
+        Self::UserJoined,
+        Self::MemberRoleChanged,
+        Self::MemberRemoved,
+        Self::BlockSignedOff,
+        Self::OrganizationArchived,
+        Self::OrganizationUnarchived,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UserJoined => "user_joined",
+            Self::MemberRoleChanged => "member_role_changed",
+            Self::MemberRemoved => "member_removed",
+            Self::BlockSignedOff => "block_signed_off",
+            Self::OrganizationArchived => "organization_archived",
+            Self::OrganizationUnarchived => "organization_unarchived",
+        }
+    }
+
+    /// The kind named `name`, as in [`FeedKind::as_str`]
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == name)
+    }
+}
+
+/// An organization event about to be recorded
+#[derive(Debug, Clone)]
+pub struct FeedEvent {
+    kind: FeedKind,
+    subject_type: &'static str,
+    subject_id: Uuid,
+    summary: String,
+    data: Option<serde_json::Value>,
+}
+
+impl FeedEvent {
+    /// `kind` concerning the `subject_type` with `subject_id`, e.g. a `user`,
+    /// shown to managers as `summary`
+    pub fn new(kind: FeedKind, subject_type: &'static str, subject_id: Uuid, summary: impl Into<String>) -> Self {
+        Self {
+            kind,
+            subject_type,
+            subject_id,
+            summary: summary.into().chars().take(MAX_SUMMARY_CHARS).collect(),
+            data: None,
+        }
+    }
+
+    /// `user` joining their organization through `source`, e.g. `invitation`
+    pub fn user_joined(user: &User, source: &str) -> Self {
+        Self::new(
+            FeedKind::UserJoined,
+            "user",
+            user.id,
+            format!("{} {} joined as {:?}", user.first_name, user.last_name, user.role),
+        )
+        .with_data(serde_json::json!({ "source": source, "role": user.role }))
+    }
+
+    /// Structured details for the UI
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+/// Records and reads what happens in organizations
+pub struct ActivityFeed;
+
+impl ActivityFeed {
+    /// Records `event` in `org_id`, caused by `actor_id` if a user caused
+    /// it, logging rather than returning a failure
+    pub async fn record(conn: &mut PgConnection, org_id: Uuid, actor_id: Option<Uuid>, event: FeedEvent) {
+        let activity = Activity {
+            id: Uuid::new_v4(),
+            org_id,
+            actor_id,
+            kind: event.kind.as_str().to_string(),
+            subject_type: event.subject_type.to_string(),
+            subject_id: event.subject_id,
+            summary: event.summary,
+            data: event.data,
+            created_at: Utc::now(),
+        };
+        if let Err(e) = ActivityRepositoryImpl.record_event(conn, &activity).await {
+            warn!(kind = %activity.kind, org_id = %org_id, error = %e.message, "Organization activity not recorded");
+        }
+    }
+
+    /// A page of what happened in `org_id` matching `filter`, newest first,
+    /// with the total count
+    ///
+    /// Managers follow their own organization and those under it.
+    pub async fn list(
+        conn: &mut PgConnection,
+        viewer: &User,
+        org_id: Uuid,
+        filter: &ActivityFilter,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<Activity>, i64)> {
+        let organizations = OrganizationService::new(OrganizationRepositoryImpl);
+        organizations.repository().find_by_id(conn, org_id).await?;
+        if !organizations.governs(conn, viewer.org_id, org_id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Activity of other organizations can't be viewed",
+                ErrorContext::new(),
+            ));
+        }
+        ActivityRepositoryImpl.feed(conn, org_id, filter, pagination).await
+    }
+}
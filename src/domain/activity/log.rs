@@ -0,0 +1,98 @@
+use chrono::Utc;
+use diesel::PgConnection;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::db::{
+    models::UserActivity,
+    repositories::{ActivityRepository, ActivityRepositoryImpl},
+};
+
+/// Longest summary stored, in characters
+const MAX_SUMMARY_CHARS: usize = 255;
+
+/// What a user did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityKind {
+    BlockSignedOff,
+    BlockStatusChanged,
+    DocumentUploaded,
+    ImportUploaded,
+    TenderCreated,
+    BidCaptured,
+    ParcelAwarded,
+    CertificationRecorded,
+}
+
+impl ActivityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BlockSignedOff => "block_signed_off",
+            Self::BlockStatusChanged => "block_status_changed",
+            Self::DocumentUploaded => "document_uploaded",
+            Self::ImportUploaded => "import_uploaded",
+            Self::TenderCreated => "tender_created",
+            Self::BidCaptured => "bid_captured",
+            Self::ParcelAwarded => "parcel_awarded",
+            Self::CertificationRecorded => "certification_recorded",
+        }
+    }
+}
+
+/// An activity about to be recorded
+#[derive(Debug, Clone)]
+pub struct NewActivity {
+    kind: ActivityKind,
+    subject_type: &'static str,
+    subject_id: Uuid,
+    summary: String,
+    data: Option<serde_json::Value>,
+}
+
+impl NewActivity {
+    /// `kind` taken on the `subject_type` with `subject_id`, e.g. a `block`,
+    /// shown to supervisors as `summary`
+    pub fn new(kind: ActivityKind, subject_type: &'static str, subject_id: Uuid, summary: impl Into<String>) -> Self {
+        Self {
+            kind,
+            subject_type,
+            subject_id,
+            summary: summary.into().chars().take(MAX_SUMMARY_CHARS).collect(),
+            data: None,
+        }
+    }
+
+    /// Structured details for the UI
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+/// Records what users do
+pub struct ActivityLog;
+
+impl ActivityLog {
+    /// Records `activity` by `user_id` in `org_id`, logging rather than
+    /// returning a failure
+    ///
+    /// Nothing is recorded without a user, as for actions taken by the
+    /// system.
+    pub async fn record(conn: &mut PgConnection, org_id: Uuid, user_id: Option<Uuid>, activity: NewActivity) {
+        let Some(user_id) = user_id else { return };
+        let record = UserActivity {
+            id: Uuid::new_v4(),
+            org_id,
+            user_id,
+            action: activity.kind.as_str().to_string(),
+            subject_type: activity.subject_type.to_string(),
+            subject_id: activity.subject_id,
+            summary: activity.summary,
+            data: activity.data,
+            created_at: Utc::now(),
+        };
+        if let Err(e) = ActivityRepositoryImpl.record(conn, &record).await {
+            warn!(action = %record.action, user_id = %user_id, error = %e.message, "Activity not recorded");
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! User activity history
+//!
+//! Significant actions, such as signing off a block, uploading a document,
+//! starting an import, capturing a bid or recording a certification, are
+//! recorded against the user who took them by the services that carry them
+//! out, through [`ActivityLog`]. Supervisors page through a crew member's
+//! activities, optionally those of one day, with [`ActivityService`].
+//! Recording is best effort: when it fails, the failure is logged and the
+//! action stands.
+//!
+//! Events concerning a whole organization, such as a user joining, a
+//! member's role changing or a block being signed off, also go to its feed
+//! through [`ActivityFeed`], which managers page through and filter.
+
+mod feed;
+mod log;
+mod service;
+
+pub use feed::{ActivityFeed, FeedEvent, FeedKind};
+pub use log::{ActivityKind, ActivityLog, NewActivity};
+pub use service::ActivityService;
@@ -0,0 +1,52 @@
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use diesel::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        models::{auth::User, UserActivity},
+        repositories::{auth::UserRepositoryImpl, ActivityRepository, ActivityRepositoryImpl, Repository},
+    },
+    domain::user::PreferenceService,
+    error::{ApiError, Result},
+};
+
+/// Reads the activity history of an organization's users
+pub struct ActivityService;
+
+impl ActivityService {
+    /// A page of what `user_id` did, newest first, with the total count
+    ///
+    /// With `day`, only that day's activities are listed, the day running
+    /// midnight to midnight in the timezone `viewer` prefers. Users of other
+    /// organizations aren't found.
+    pub async fn list(
+        conn: &mut PgConnection,
+        viewer: &User,
+        user_id: Uuid,
+        day: Option<NaiveDate>,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<UserActivity>, i64)> {
+        let user = UserRepositoryImpl.find_by_id(conn, user_id).await?;
+        if user.org_id != viewer.org_id {
+            return Err(ApiError::not_found(format!("User with id {} not found", user_id)));
+        }
+
+        let period = match day {
+            Some(day) => {
+                let timezone = PreferenceService::get(conn, viewer.id).await?.timezone;
+                let from = timezone
+                    .from_local_datetime(&day.and_time(Default::default()))
+                    .single()
+                    .ok_or_else(|| ApiError::validation(format!("Invalid day {}", day), None))?
+                    .with_timezone(&Utc);
+                Some((from, from + Duration::days(1)))
+            }
+            None => None,
+        };
+        ActivityRepositoryImpl
+            .list_for_user(conn, viewer.org_id, user.id, period, pagination)
+            .await
+    }
+}
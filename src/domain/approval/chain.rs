@@ -0,0 +1,100 @@
+use crate::db::models::{auth::Role, BlockSignoff, SignoffStep};
+
+/// Lowest role that may sign a step
+pub fn required_role(step: SignoffStep) -> Role {
+    match step {
+        SignoffStep::Planner => Role::Operator,
+        SignoffStep::ProfessionalForester | SignoffStep::OperationsManager => Role::Manager,
+    }
+}
+
+/// A package document hash in canonical form, `None` unless it is a hex
+/// SHA-256 digest
+pub fn document_hash(value: &str) -> Option<String> {
+    let value = value.trim();
+    (value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())).then(|| value.to_ascii_lowercase())
+}
+
+/// Where a block stands in the approval chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalStatus {
+    /// Step to be signed next, `None` once the chain is complete
+    pub next_step: Option<SignoffStep>,
+    /// Steps not signed yet, in order
+    pub missing_steps: Vec<SignoffStep>,
+    /// Hash of the document signed so far
+    pub document_hash: Option<String>,
+}
+
+impl ApprovalStatus {
+    pub fn new(signoffs: &[BlockSignoff]) -> Self {
+        let missing_steps: Vec<SignoffStep> = SignoffStep::ALL
+            .into_iter()
+            .filter(|step| !signoffs.iter().any(|signoff| signoff.step == step.as_str()))
+            .collect();
+        Self {
+            next_step: missing_steps.first().copied(),
+            missing_steps,
+            document_hash: signoffs.first().map(|signoff| signoff.document_hash.clone()),
+        }
+    }
+
+    /// Whether every step is signed, so the block can be activated
+    pub fn approved(&self) -> bool {
+        self.missing_steps.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn signoff(step: SignoffStep) -> BlockSignoff {
+        BlockSignoff {
+            id: Uuid::new_v4(),
+            org_id: Uuid::new_v4(),
+            block_id: Uuid::new_v4(),
+            step: step.as_str().to_string(),
+            signer_id: None,
+            signer_name: "Anna Berg".to_string(),
+            document_hash: "ab".repeat(32),
+            signed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_status_follows_the_chain() {
+        let status = ApprovalStatus::new(&[]);
+        assert_eq!(status.next_step, Some(SignoffStep::Planner));
+        assert_eq!(status.missing_steps.len(), 3);
+        assert_eq!(status.document_hash, None);
+
+        let status = ApprovalStatus::new(&[signoff(SignoffStep::Planner), signoff(SignoffStep::ProfessionalForester)]);
+        assert_eq!(status.next_step, Some(SignoffStep::OperationsManager));
+        assert_eq!(status.document_hash, Some("ab".repeat(32)));
+        assert!(!status.approved());
+
+        let signoffs: Vec<_> = SignoffStep::ALL.into_iter().map(signoff).collect();
+        let status = ApprovalStatus::new(&signoffs);
+        assert_eq!(status.next_step, None);
+        assert!(status.approved());
+    }
+
+    #[test]
+    fn test_roles() {
+        assert!(Role::Operator.is_at_least(required_role(SignoffStep::Planner)));
+        assert!(!Role::Operator.is_at_least(required_role(SignoffStep::ProfessionalForester)));
+        assert!(Role::Manager.is_at_least(required_role(SignoffStep::OperationsManager)));
+        assert!(Role::Admin.is_at_least(required_role(SignoffStep::OperationsManager)));
+    }
+
+    #[test]
+    fn test_document_hash() {
+        assert_eq!(document_hash(&format!(" {} ", "AB".repeat(32))), Some("ab".repeat(32)));
+        assert_eq!(document_hash("ab"), None);
+        assert_eq!(document_hash(&"zz".repeat(32)), None);
+    }
+}
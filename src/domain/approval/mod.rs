@@ -0,0 +1,15 @@
+//! Harvest block approval
+//!
+//! A block package is approved by a chain of electronic sign-offs: the
+//! planner who prepared it, then a professional forester, then an
+//! operations manager. Each step needs a signer with the step's role who
+//! has not signed an earlier step, and every signer signs the same package
+//! document, identified by its hash. A block cannot be activated until the
+//! chain is complete; when the package is revised the sign-offs are reset
+//! and the chain starts over.
+
+mod chain;
+mod service;
+
+pub use chain::{document_hash, required_role, ApprovalStatus};
+pub use service::ApprovalService;
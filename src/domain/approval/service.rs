@@ -0,0 +1,153 @@
+use chrono::Utc;
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+
+use super::chain::{document_hash, required_role, ApprovalStatus};
+use crate::{
+    db::{
+        models::{auth::Role, BlockSignoff, SignoffStep},
+        repositories::SignoffRepository,
+    },
+    domain::activity::{ActivityFeed, ActivityKind, ActivityLog, FeedEvent, FeedKind, NewActivity},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+
+/// Service for the sign-off chain of harvest block packages
+pub struct ApprovalService<R: SignoffRepository + Send + Sync> {
+    repository: R,
+}
+
+fn conflict(message: impl Into<String>, details: serde_json::Value) -> ApiError {
+    ApiError::new(ErrorCode::Conflict, message, ErrorContext::new().with_details(details))
+}
+
+fn forbidden(message: impl Into<String>) -> ApiError {
+    ApiError::new(ErrorCode::Forbidden, message, ErrorContext::new())
+}
+
+impl<R: SignoffRepository + Send + Sync> ApprovalService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// The sign-offs of a block and where it stands in the chain
+    pub async fn status(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        block_id: Uuid,
+    ) -> Result<(Vec<BlockSignoff>, ApprovalStatus)> {
+        let signoffs = self.repository.list(conn, org_id, block_id).await?;
+        let status = ApprovalStatus::new(&signoffs);
+        Ok((signoffs, status))
+    }
+
+    /// Signs the next step of a block's chain
+    ///
+    /// The signer needs the step's role and must not have signed an earlier
+    /// step, and the document must be the one signed so far.
+    pub async fn sign(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        block_id: Uuid,
+        signer_id: Uuid,
+        step: SignoffStep,
+        hash: &str,
+    ) -> Result<BlockSignoff> {
+        let hash = document_hash(hash)
+            .ok_or_else(|| ApiError::validation("The document hash must be a hex SHA-256 digest", None))?;
+        let signer = self.repository.find_signer(conn, org_id, signer_id).await?;
+        let signoffs = self.repository.list(conn, org_id, block_id).await?;
+        let status = ApprovalStatus::new(&signoffs);
+
+        match status.next_step {
+            Some(next) if next == step => {}
+            Some(next) if next < step => {
+                return Err(conflict(
+                    format!("The {} step must be signed first", next),
+                    json!({ "next_step": next.as_str() }),
+                ))
+            }
+            _ => return Err(conflict("This step is already signed", json!({ "step": step.as_str() }))),
+        }
+        if !signer.role.is_at_least(required_role(step)) {
+            return Err(forbidden(format!("The {} step needs the {:?} role", step, required_role(step))));
+        }
+        if signoffs.iter().any(|signoff| signoff.signer_id == Some(signer.id)) {
+            return Err(forbidden("Each step must be signed by a different person"));
+        }
+        if let Some(signed) = status.document_hash.filter(|signed| *signed != hash) {
+            return Err(conflict(
+                "The package changed since it was first signed; reset the sign-offs to start over",
+                json!({ "document_hash": signed }),
+            ));
+        }
+
+        let signoff = self
+            .repository
+            .create(conn, &BlockSignoff {
+                id: Uuid::new_v4(),
+                org_id,
+                block_id,
+                step: step.as_str().to_string(),
+                signer_id: Some(signer.id),
+                signer_name: format!("{} {}", signer.first_name, signer.last_name),
+                document_hash: hash,
+                signed_at: Utc::now(),
+            })
+            .await?;
+        info!(block_id = %block_id, org_id = %org_id, signer_id = %signer.id, "Block signed off at the {} step", step);
+        ActivityLog::record(
+            conn,
+            org_id,
+            Some(signer.id),
+            NewActivity::new(ActivityKind::BlockSignedOff, "block", block_id, format!("Signed off the {} step of a block", step))
+                .with_data(json!({ "step": step.as_str() })),
+        )
+        .await;
+        ActivityFeed::record(
+            conn,
+            org_id,
+            Some(signer.id),
+            FeedEvent::new(
+                FeedKind::BlockSignedOff,
+                "block",
+                block_id,
+                format!("{} signed off the {} step of a block", signoff.signer_name, step),
+            )
+            .with_data(json!({ "step": step.as_str() })),
+        )
+        .await;
+        Ok(signoff)
+    }
+
+    /// Removes a block's sign-offs after its package is revised, so the
+    /// chain starts over
+    pub async fn reset(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid, user_id: Uuid) -> Result<()> {
+        let user = self.repository.find_signer(conn, org_id, user_id).await?;
+        if !user.role.is_at_least(Role::Manager) {
+            return Err(forbidden("Resetting sign-offs needs the Manager role"));
+        }
+        let removed = self.repository.clear(conn, org_id, block_id).await?;
+        info!(block_id = %block_id, org_id = %org_id, user_id = %user.id, "Reset {} block sign-offs", removed);
+        Ok(())
+    }
+
+    /// Fails unless every step of a block's chain is signed
+    ///
+    /// Block activation calls this before changing the block's status.
+    pub async fn ensure_approved(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid) -> Result<()> {
+        let (_, status) = self.status(conn, org_id, block_id).await?;
+        if status.approved() {
+            return Ok(());
+        }
+        let missing: Vec<&str> = status.missing_steps.iter().map(SignoffStep::as_str).collect();
+        Err(conflict(
+            format!("Block {} cannot be activated before it is signed off", block_id),
+            json!({ "missing_steps": missing }),
+        ))
+    }
+}
@@ -0,0 +1,215 @@
+//! Authentication audit log
+//!
+//! Logins, failed logins, token refreshes, password and email changes,
+//! logouts, passkeys added or removed and accounts deactivated or
+//! reactivated are recorded with the IP address, user agent, country and
+//! network of the client, so users can spot sign-ins they don't recognize
+//! and admins can investigate an account. Failed logins are recorded against
+//! the user with the email tried, if there is one. Recording is best
+//! effort: when it fails, the failure is logged and the request goes ahead.
+
+use chrono::Utc;
+use diesel::PgConnection;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::db::{
+    models::auth::AuthEvent,
+    repositories::auth::{AuthEventRepository, AuthEventRepositoryImpl},
+};
+
+/// Longest user agent stored, in characters
+const MAX_USER_AGENT_CHARS: usize = 512;
+
+/// What happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthEventKind {
+    LoginSucceeded,
+    LoginFailed,
+    TokenRefreshed,
+    PasswordChanged,
+    EmailChanged,
+    LoggedOut,
+    PasskeyAdded,
+    PasskeyRemoved,
+    SessionsRevoked,
+    Deactivated,
+    Reactivated,
+    Anonymized,
+}
+
+impl AuthEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LoginSucceeded => "login_succeeded",
+            Self::LoginFailed => "login_failed",
+            Self::TokenRefreshed => "token_refreshed",
+            Self::PasswordChanged => "password_changed",
+            Self::EmailChanged => "email_changed",
+            Self::LoggedOut => "logged_out",
+            Self::PasskeyAdded => "passkey_added",
+            Self::PasskeyRemoved => "passkey_removed",
+            Self::SessionsRevoked => "sessions_revoked",
+            Self::Deactivated => "deactivated",
+            Self::Reactivated => "reactivated",
+            Self::Anonymized => "anonymized",
+        }
+    }
+}
+
+/// How a user authenticated
+pub mod method {
+    pub const PASSWORD: &str = "password";
+    pub const MAGIC_LINK: &str = "magic_link";
+    pub const PASSKEY: &str = "passkey";
+    pub const PASSWORD_RESET: &str = "password_reset";
+
+    /// Single sign-on through `provider`
+    pub fn sso(provider: &str) -> String {
+        format!("sso:{}", provider)
+    }
+}
+
+/// The client a request came from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// ISO 3166 code of the country the client is in, upper case
+    pub country: Option<String>,
+    /// Autonomous system the client connects from, e.g. `AS13335`
+    pub asn: Option<String>,
+}
+
+impl ClientInfo {
+    pub fn new(ip_address: Option<&str>, user_agent: Option<&str>) -> Self {
+        Self {
+            ip_address: ip_address.map(|ip| ip.chars().take(45).collect()),
+            user_agent: user_agent.map(|agent| agent.chars().take(MAX_USER_AGENT_CHARS).collect()),
+            country: None,
+            asn: None,
+        }
+    }
+
+    /// The client, in `country` and on the network `asn` as a proxy reported
+    /// them
+    ///
+    /// Values that aren't a country code or an AS number are dropped, as are
+    /// the codes proxies use for unknown countries and Tor.
+    pub fn located(mut self, country: Option<&str>, asn: Option<&str>) -> Self {
+        self.country = country
+            .map(|country| country.trim().to_ascii_uppercase())
+            .filter(|country| country.len() == 2 && country.bytes().all(|b| b.is_ascii_uppercase()))
+            .filter(|country| !matches!(country.as_str(), "XX" | "T1" | "ZZ"));
+        self.asn = asn
+            .map(|asn| asn.trim())
+            .map(|asn| asn.strip_prefix("AS").or_else(|| asn.strip_prefix("as")).unwrap_or(asn))
+            .filter(|number| (1..=10).contains(&number.len()) && number.bytes().all(|b| b.is_ascii_digit()))
+            .map(|number| format!("AS{}", number));
+        self
+    }
+}
+
+/// An event about to be recorded
+#[derive(Debug, Clone)]
+pub struct NewAuthEvent {
+    kind: AuthEventKind,
+    user_id: Option<Uuid>,
+    method: Option<String>,
+    email: Option<String>,
+    reason: Option<String>,
+    device_id: Option<String>,
+}
+
+impl NewAuthEvent {
+    pub fn new(kind: AuthEventKind, user_id: Option<Uuid>) -> Self {
+        Self {
+            kind,
+            user_id,
+            method: None,
+            email: None,
+            reason: None,
+            device_id: None,
+        }
+    }
+
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        self.method = Some(method.into());
+        self
+    }
+
+    /// The email a failed login was attempted with
+    pub fn email(mut self, email: &str) -> Self {
+        self.email = Some(email.trim().to_lowercase().chars().take(255).collect());
+        self
+    }
+
+    /// Why the attempt failed
+    pub fn reason(mut self, reason: &str) -> Self {
+        self.reason = Some(reason.chars().take(255).collect());
+        self
+    }
+
+    /// The device the client named, if any
+    pub fn device(mut self, device_id: Option<&str>) -> Self {
+        self.device_id = device_id.map(|device_id| device_id.chars().take(255).collect());
+        self
+    }
+}
+
+/// Records authentication events
+pub struct AuthAudit;
+
+impl AuthAudit {
+    /// Records `event` from `client`, logging rather than returning a failure
+    pub async fn record(conn: &mut PgConnection, client: &ClientInfo, event: NewAuthEvent) {
+        let kind = event.kind;
+        let record = AuthEvent {
+            id: Uuid::new_v4(),
+            user_id: event.user_id,
+            event: kind.as_str().to_string(),
+            method: event.method,
+            email: event.email,
+            reason: event.reason,
+            ip_address: client.ip_address.clone(),
+            user_agent: client.user_agent.clone(),
+            created_at: Utc::now(),
+            country: client.country.clone(),
+            asn: client.asn.clone(),
+            device_id: event.device_id,
+        };
+        if let Err(e) = AuthEventRepositoryImpl.record(conn, &record).await {
+            warn!(event = kind.as_str(), user_id = ?record.user_id, error = %e, "Auth event not recorded");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_info_is_truncated_to_the_columns() {
+        let client = ClientInfo::new(Some("203.0.113.7"), Some(&"x".repeat(2000)));
+        assert_eq!(client.ip_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(client.user_agent.map(|agent| agent.len()), Some(MAX_USER_AGENT_CHARS));
+        assert_eq!(ClientInfo::new(None, None), ClientInfo::default());
+    }
+
+    #[test]
+    fn test_client_location_is_normalized() {
+        let located = |country, asn| ClientInfo::default().located(country, asn);
+
+        let client = located(Some(" ca "), Some("13335"));
+        assert_eq!(client.country.as_deref(), Some("CA"));
+        assert_eq!(client.asn.as_deref(), Some("AS13335"));
+        assert_eq!(located(None, Some("AS64496")).asn.as_deref(), Some("AS64496"));
+
+        for country in ["XX", "T1", "CAN", "C1", ""] {
+            assert_eq!(located(Some(country), None).country, None, "{}", country);
+        }
+        for asn in ["AS", "ASN1", "12345678901", "-1"] {
+            assert_eq!(located(None, Some(asn)).asn, None, "{}", asn);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! CAPTCHA challenges on public auth endpoints
+//!
+//! With `auth.captcha.provider` set, registering and asking for a password
+//! reset always need a solved challenge, sent as `captcha_token`. Logging
+//! in needs one once an email, or an IP address, has failed to log in
+//! `login_after_failures` times within `failure_window_minutes`. Errors
+//! name the `captcha_token` field, so clients know to show the widget.
+
+use chrono::{Duration, Utc};
+use tracing::info;
+
+use super::audit::{AuthEventKind, ClientInfo};
+use crate::{
+    db::{
+        connection,
+        repositories::auth::{AuthEventRepository, AuthEventRepositoryImpl},
+        DbPool,
+    },
+    error::{ApiError, ErrorContext, Result},
+    infrastructure::captcha,
+    utils::Config,
+};
+
+/// Decides when a challenge is needed and checks it was solved
+pub struct CaptchaGuard;
+
+impl CaptchaGuard {
+    /// Requires `token` to be a solved challenge, when challenges are on
+    pub async fn require(config: &Config, token: Option<&str>, client: &ClientInfo) -> Result<()> {
+        let settings = &config.auth.captcha;
+        if !settings.enabled() {
+            return Ok(());
+        }
+        let Some(token) = token.filter(|token| !token.is_empty()) else {
+            return Err(captcha_error("A CAPTCHA is required", "REQUIRED"));
+        };
+        if !captcha::verify(settings, token, client.ip_address.as_deref()).await? {
+            return Err(captcha_error("The CAPTCHA was not solved", "INVALID"));
+        }
+        Ok(())
+    }
+
+    /// Requires a solved challenge to log in as `email` once it, or the
+    /// client's IP address, has failed to log in too often
+    pub async fn require_for_login(
+        pool: &DbPool,
+        config: &Config,
+        email: &str,
+        token: Option<&str>,
+        client: &ClientInfo,
+    ) -> Result<()> {
+        let settings = &config.auth.captcha;
+        if !settings.enabled() {
+            return Ok(());
+        }
+        if settings.login_after_failures > 0 {
+            let mut conn = connection::get_connection(pool)?;
+            let since = Utc::now() - Duration::minutes(settings.failure_window_minutes);
+            let failures = AuthEventRepositoryImpl
+                .count_recent(
+                    &mut conn,
+                    AuthEventKind::LoginFailed.as_str(),
+                    &email.trim().to_lowercase(),
+                    client.ip_address.as_deref(),
+                    since,
+                )
+                .await?;
+            if failures < settings.login_after_failures {
+                return Ok(());
+            }
+            info!(failures, "Login needs a CAPTCHA after repeated failures");
+        }
+        Self::require(config, token, client).await
+    }
+}
+
+fn captcha_error(message: &str, code: &str) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(serde_json::json!({
+            "field": "captcha_token",
+            "code": code,
+        })),
+    )
+}
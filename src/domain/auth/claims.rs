@@ -7,4 +7,8 @@ pub struct Claims {
     pub role: String,
     pub iat: i64,
     pub exp: i64,
+    /// Id of the token, so it can be revoked; absent from tokens issued
+    /// before logout existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,243 @@
+//! Access token signing keys
+//!
+//! Access tokens are signed with the key `auth.signing_key` names and carry
+//! its id in the `kid` header. Every key in `auth.jwt_keys` verifies the
+//! tokens it signed, so keys are rotated without signing everyone out: a
+//! new key is added, then made the signing key, and the old one is removed
+//! once the last token it signed has expired. Tokens without a `kid`,
+//! signed before keys were configured, are verified with `jwt_secret`.
+//!
+//! The public halves of `RS256` and `EdDSA` keys are published as a JSON
+//! Web Key Set, so other services can verify tokens without a secret.
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use ring::{
+    rsa::PublicKeyComponents,
+    signature::{Ed25519KeyPair, KeyPair, RsaKeyPair},
+};
+use rustls::pki_types::PrivateKeyDer;
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::utils::{AuthConfig, JwtAlgorithm, JwtKeyConfig};
+
+/// Public key of a signing key, as a JSON Web Key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct Jwk {
+    /// `RSA` or `OKP` (Ed25519)
+    pub kty: String,
+    pub kid: String,
+    pub alg: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    /// RSA modulus, base64url
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub n: Option<String>,
+    /// RSA public exponent, base64url
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub e: Option<String>,
+    /// Curve of an `OKP` key, `Ed25519`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub crv: Option<String>,
+    /// Ed25519 public key, base64url
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub x: Option<String>,
+}
+
+/// The published signing keys
+#[derive(Debug, Clone, Default, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+/// A key from `auth.jwt_keys`
+#[derive(Clone)]
+pub struct SigningKey {
+    id: String,
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    /// `None` for shared secrets, which are never published
+    public: Option<Jwk>,
+}
+
+impl SigningKey {
+    /// Loads the key configured as `id`, describing what is wrong with it
+    /// otherwise
+    pub fn from_config(id: &str, config: &JwtKeyConfig) -> Result<Self, String> {
+        match config.algorithm {
+            JwtAlgorithm::Hs256 => {
+                let secret = config.secret.as_deref().ok_or("an HS256 key needs a secret")?;
+                Ok(Self {
+                    id: id.to_string(),
+                    algorithm: Algorithm::HS256,
+                    encoding: EncodingKey::from_secret(secret.as_bytes()),
+                    decoding: DecodingKey::from_secret(secret.as_bytes()),
+                    public: None,
+                })
+            }
+            JwtAlgorithm::Rs256 => {
+                let pem = config.private_key.as_deref().ok_or("an RS256 key needs a private_key")?;
+                let key_pair = match private_key_der(pem)? {
+                    PrivateKeyDer::Pkcs1(der) => RsaKeyPair::from_der(der.secret_pkcs1_der()),
+                    PrivateKeyDer::Pkcs8(der) => RsaKeyPair::from_pkcs8(der.secret_pkcs8_der()),
+                    _ => return Err("private_key is not an RSA key".to_string()),
+                }
+                .map_err(|e| format!("private_key is not a usable RSA key: {}", e))?;
+                let components = PublicKeyComponents::<Vec<u8>>::from(key_pair.public());
+                let (n, e) = (URL_SAFE_NO_PAD.encode(components.n), URL_SAFE_NO_PAD.encode(components.e));
+                Ok(Self {
+                    id: id.to_string(),
+                    algorithm: Algorithm::RS256,
+                    encoding: EncodingKey::from_rsa_pem(pem.as_bytes()).map_err(|e| format!("private_key: {}", e))?,
+                    decoding: DecodingKey::from_rsa_components(&n, &e).map_err(|e| format!("private_key: {}", e))?,
+                    public: Some(Jwk {
+                        kty: "RSA".to_string(),
+                        n: Some(n),
+                        e: Some(e),
+                        ..Jwk::new(id, "RS256")
+                    }),
+                })
+            }
+            JwtAlgorithm::EdDsa => {
+                let pem = config.private_key.as_deref().ok_or("an EdDSA key needs a private_key")?;
+                let PrivateKeyDer::Pkcs8(der) = private_key_der(pem)? else {
+                    return Err("private_key must be a PKCS#8 Ed25519 key".to_string());
+                };
+                let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der.secret_pkcs8_der())
+                    .map_err(|e| format!("private_key is not a usable Ed25519 key: {}", e))?;
+                let x = URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref());
+                Ok(Self {
+                    id: id.to_string(),
+                    algorithm: Algorithm::EdDSA,
+                    encoding: EncodingKey::from_ed_der(der.secret_pkcs8_der()),
+                    decoding: DecodingKey::from_ed_components(&x).map_err(|e| format!("private_key: {}", e))?,
+                    public: Some(Jwk {
+                        kty: "OKP".to_string(),
+                        crv: Some("Ed25519".to_string()),
+                        x: Some(x),
+                        ..Jwk::new(id, "EdDSA")
+                    }),
+                })
+            }
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Header of the tokens the key signs
+    pub fn header(&self) -> Header {
+        Header {
+            kid: Some(self.id.clone()),
+            ..Header::new(self.algorithm)
+        }
+    }
+
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding
+    }
+
+    pub fn decoding_key(&self) -> &DecodingKey {
+        &self.decoding
+    }
+
+    /// Validation accepting only the key's algorithm
+    pub fn validation(&self) -> Validation {
+        Validation::new(self.algorithm)
+    }
+
+    /// The public key, `None` for a shared secret
+    pub fn jwk(&self) -> Option<&Jwk> {
+        self.public.as_ref()
+    }
+}
+
+impl Jwk {
+    fn new(kid: &str, alg: &str) -> Self {
+        Self {
+            kty: String::new(),
+            kid: kid.to_string(),
+            alg: alg.to_string(),
+            key_use: "sig".to_string(),
+            n: None,
+            e: None,
+            crv: None,
+            x: None,
+        }
+    }
+}
+
+/// The keys of `auth.jwt_keys`, shared by clones of the configuration
+#[derive(Clone, Default)]
+pub struct SigningKeys {
+    keys: Arc<BTreeMap<String, SigningKey>>,
+    signing: Option<String>,
+}
+
+impl SigningKeys {
+    /// Loads every configured key, or names the first that can't be loaded
+    /// and what is wrong with it
+    pub fn from_config(config: &AuthConfig) -> Result<Self, (String, String)> {
+        let keys = config
+            .jwt_keys
+            .iter()
+            .map(|(id, key)| {
+                SigningKey::from_config(id, key)
+                    .map(|key| (id.clone(), key))
+                    .map_err(|message| (format!("auth.jwt_keys.{}", id), message))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        if let Some(id) = config.signing_key.as_ref().filter(|id| !keys.contains_key(*id)) {
+            return Err(("auth.signing_key".to_string(), format!("'{}' is not in auth.jwt_keys", id)));
+        }
+        Ok(Self {
+            keys: Arc::new(keys),
+            signing: config.signing_key.clone(),
+        })
+    }
+
+    /// The key new tokens are signed with, `None` to sign with `jwt_secret`
+    pub fn signing(&self) -> Option<&SigningKey> {
+        self.signing.as_deref().and_then(|id| self.keys.get(id))
+    }
+
+    /// The key with id `kid`, `None` once it has been removed
+    pub fn get(&self, kid: &str) -> Option<&SigningKey> {
+        self.keys.get(kid)
+    }
+
+    /// The public keys, for `/.well-known/jwks.json`
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self.keys.values().filter_map(|key| key.jwk().cloned()).collect(),
+        }
+    }
+}
+
+impl fmt::Debug for SigningKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKeys")
+            .field("keys", &self.keys.keys().collect::<Vec<_>>())
+            .field("signing", &self.signing)
+            .finish()
+    }
+}
+
+/// The private key in `pem`
+fn private_key_der(pem: &str) -> Result<PrivateKeyDer<'static>, String> {
+    rustls_pemfile::private_key(&mut pem.as_bytes())
+        .map_err(|e| format!("private_key is not valid PEM: {}", e))?
+        .ok_or_else(|| "private_key holds no private key".to_string())
+}
@@ -0,0 +1,160 @@
+//! Alerts about logins from somewhere new
+//!
+//! Each successful login is compared with the user's earlier ones. When none
+//! of them came from the same country and network, or from the same device,
+//! the user is mailed an alert and finds it in their notification center.
+//! Both link to `{email.app_url}/secure-account?token=...`; the token is
+//! signed with `jwt_secret`, and redeeming it signs the user out everywhere.
+//! A user's first login raises no alert. Alerting is best effort, like the
+//! audit log: a failure is logged and the login goes ahead.
+
+use chrono::{Duration, Utc};
+use diesel::PgConnection;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::audit::ClientInfo;
+use crate::{
+    db::{
+        models::{auth::User, Notification},
+        repositories::{AuthEventRepository, AuthEventRepositoryImpl, LoginOrigin, NotificationRepositoryImpl},
+    },
+    domain::NotificationService,
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::email::LoginAlertEmail,
+    jobs::email,
+    utils::Config,
+};
+
+/// Kind of the notifications raised for new logins
+pub const LOGIN_ALERT_KIND: &str = "login_alert";
+
+/// Audience of "this wasn't me" tokens, so they can't pass for other tokens
+const SECURE_ACCOUNT_AUDIENCE: &str = "secure-account";
+
+/// What a "this wasn't me" token vouches for
+#[derive(Debug, Serialize, Deserialize)]
+struct SecureAccountClaims {
+    /// Id of the user to sign out
+    sub: Uuid,
+    aud: String,
+    exp: i64,
+}
+
+/// Alerts users to logins from new locations and devices
+pub struct LoginAlerts;
+
+/// How a location reads in an alert
+fn describe_location(client: &ClientInfo) -> String {
+    match (&client.country, &client.asn) {
+        (Some(country), Some(asn)) => format!("{} (network {})", country, asn),
+        (Some(country), None) => country.clone(),
+        (None, Some(asn)) => format!("network {}", asn),
+        (None, None) => "an unknown location".to_string(),
+    }
+}
+
+impl LoginAlerts {
+    /// Alerts `user` when their login from `client` on `device_id` comes
+    /// from a location or device none of their earlier logins did
+    ///
+    /// To be called before the login is recorded, so it isn't compared with
+    /// itself.
+    pub async fn check(conn: &mut PgConnection, user: &User, client: &ClientInfo, device_id: Option<&str>, config: &Config) {
+        if !config.auth.login_alerts.enabled {
+            return;
+        }
+        if let Err(e) = Self::alert_if_new(conn, user, client, device_id, config).await {
+            warn!(user_id = %user.id, error = %e, "Login alert not sent");
+        }
+    }
+
+    async fn alert_if_new(
+        conn: &mut PgConnection,
+        user: &User,
+        client: &ClientInfo,
+        device_id: Option<&str>,
+        config: &Config,
+    ) -> Result<()> {
+        let origin = LoginOrigin {
+            country: client.country.as_deref(),
+            asn: client.asn.as_deref(),
+            device_id,
+            user_agent: client.user_agent.as_deref(),
+        };
+        let history = AuthEventRepositoryImpl.login_history(conn, user.id, &origin).await?;
+        if !history.any || (history.location && history.device) {
+            return Ok(());
+        }
+
+        let link_days = config.auth.login_alerts.link_days;
+        let secure_url = format!(
+            "{}/secure-account?token={}",
+            config.email.app_url.trim_end_matches('/'),
+            Self::issue_token(user.id, config)?,
+        );
+        let location = describe_location(client);
+        let device = client
+            .user_agent
+            .clone()
+            .or_else(|| device_id.map(str::to_string))
+            .unwrap_or_else(|| "an unknown device".to_string());
+
+        let data = LoginAlertEmail {
+            name: user.first_name.clone(),
+            signed_in_at: Utc::now(),
+            location: location.clone(),
+            device: device.clone(),
+            ip_address: client.ip_address.clone(),
+            secure_url: secure_url.clone(),
+            expires_in_days: link_days,
+        };
+        let message = config.mailer().compose(conn, Some(user.org_id), &user.email, &data).await?;
+        email::enqueue(conn, &config.queue, &message).await?;
+
+        let notification = Notification::new(user.id, LOGIN_ALERT_KIND, "New sign-in to your account")
+            .with_org(user.org_id)
+            .with_body(format!("Signed in from {} on {}. If this wasn't you, sign out everywhere.", location, device))
+            .with_link(secure_url)
+            .with_data(json!({
+                "new_location": !history.location,
+                "new_device": !history.device,
+                "country": client.country,
+                "asn": client.asn,
+                "ip_address": client.ip_address,
+                "device_id": device_id,
+            }));
+        NotificationService::new(NotificationRepositoryImpl).notify(conn, &[notification]).await?;
+
+        info!(
+            user_id = %user.id,
+            new_location = !history.location,
+            new_device = !history.device,
+            "Login alert sent"
+        );
+        Ok(())
+    }
+
+    /// The token of the "this wasn't me" link sent to `user_id`
+    pub fn issue_token(user_id: Uuid, config: &Config) -> Result<String> {
+        let claims = SecureAccountClaims {
+            sub: user_id,
+            aud: SECURE_ACCOUNT_AUDIENCE.to_string(),
+            exp: (Utc::now() + Duration::days(config.auth.login_alerts.link_days)).timestamp(),
+        };
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(config.jwt_secret.as_bytes()))
+            .map_err(|e| ApiError::new(ErrorCode::InternalError, format!("Failed to sign login alert link: {}", e), ErrorContext::new()))
+    }
+
+    /// The user an unexpired "this wasn't me" token was sent to
+    pub fn verify_token(token: &str, config: &Config) -> Result<Uuid> {
+        let mut validation = Validation::default();
+        validation.set_audience(&[SECURE_ACCOUNT_AUDIENCE]);
+        decode::<SecureAccountClaims>(token, &DecodingKey::from_secret(config.jwt_secret.as_bytes()), &validation)
+            .map(|data| data.claims.sub)
+            .map_err(|_| ApiError::validation("Invalid or expired link", None))
+    }
+}
@@ -1,9 +1,28 @@
+pub mod audit;
+mod captcha;
 mod claims;
+mod keys;
+mod login_alerts;
+mod permissions;
+mod revocation;
+mod roles;
 mod service;
+pub mod sso;
+mod token_cache;
 mod tokens;
 mod validation;
+pub mod webauthn;
 
+pub use audit::{AuthAudit, AuthEventKind, ClientInfo, NewAuthEvent};
+pub use captcha::CaptchaGuard;
 pub use claims::Claims;
+pub use keys::{Jwk, JwkSet, SigningKey, SigningKeys};
+pub use login_alerts::{LoginAlerts, LOGIN_ALERT_KIND};
+pub use permissions::{Permission, PermissionService, Policy};
+pub use revocation::RevocationList;
+pub use roles::{RoleDefinition, RoleService, MAX_ROLE_DESCRIPTION_LENGTH, MAX_ROLE_NAME_LENGTH};
 pub use service::AuthService;
+pub(crate) use service::link_token;
+pub use token_cache::TokenCache;
 pub use tokens::TokenManager;
 pub use validation::AuthValidator;
\ No newline at end of file
@@ -0,0 +1,309 @@
+//! Permissions and the role policy
+//!
+//! Routes require permissions such as `customers:write` rather than a role.
+//! Which role holds which permission is stored in `role_permissions`, seeded
+//! with defaults by the migration and editable through the admin API. Admins
+//! hold every permission without grants. Users also hold the permissions of
+//! the custom roles their organization assigned them, see
+//! [`RoleService`](super::RoleService).
+//!
+//! The policy is read on every guarded request, so it is cached per process
+//! for [`POLICY_TTL`]. Changes apply immediately on the instance that made
+//! them; the API broadcasts them so the others drop their copy too, and the
+//! TTL bounds how long a missed broadcast leaves one stale.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use diesel::PgConnection;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::auth::Role,
+        repositories::{PermissionRepository, PermissionRepositoryImpl, RoleRepository, RoleRepositoryImpl},
+    },
+    error::{ApiError, Result},
+};
+
+/// Time a loaded policy is reused
+pub const POLICY_TTL: Duration = Duration::from_secs(30);
+
+/// The loaded policy and when it was read
+type CachedPolicy = Option<(Instant, Arc<Policy>)>;
+
+static POLICY: Lazy<RwLock<CachedPolicy>> = Lazy::new(Default::default);
+
+/// An action routes can require
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub enum Permission {
+    #[serde(rename = "customers:read")]
+    CustomersRead,
+    #[serde(rename = "customers:write")]
+    CustomersWrite,
+    #[serde(rename = "timber_sales:read")]
+    TimberSalesRead,
+    #[serde(rename = "timber_sales:write")]
+    TimberSalesWrite,
+    #[serde(rename = "reports:read")]
+    ReportsRead,
+    #[serde(rename = "reports:write")]
+    ReportsWrite,
+    #[serde(rename = "imports:read")]
+    ImportsRead,
+    #[serde(rename = "imports:write")]
+    ImportsWrite,
+    #[serde(rename = "tags:read")]
+    TagsRead,
+    #[serde(rename = "tags:write")]
+    TagsWrite,
+    #[serde(rename = "erp:read")]
+    ErpRead,
+    #[serde(rename = "erp:write")]
+    ErpWrite,
+    #[serde(rename = "certifications:read")]
+    CertificationsRead,
+    #[serde(rename = "certifications:write")]
+    CertificationsWrite,
+    #[serde(rename = "blocks:read")]
+    BlocksRead,
+    #[serde(rename = "blocks:write")]
+    BlocksWrite,
+    #[serde(rename = "crews:read")]
+    CrewsRead,
+    #[serde(rename = "crews:write")]
+    CrewsWrite,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 18] = [
+        Self::CustomersRead,
+        Self::CustomersWrite,
+        Self::TimberSalesRead,
+        Self::TimberSalesWrite,
+        Self::ReportsRead,
+        Self::ReportsWrite,
+        Self::ImportsRead,
+        Self::ImportsWrite,
+        Self::TagsRead,
+        Self::TagsWrite,
+        Self::ErpRead,
+        Self::ErpWrite,
+        Self::CertificationsRead,
+        Self::CertificationsWrite,
+        Self::BlocksRead,
+        Self::BlocksWrite,
+        Self::CrewsRead,
+        Self::CrewsWrite,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CustomersRead => "customers:read",
+            Self::CustomersWrite => "customers:write",
+            Self::TimberSalesRead => "timber_sales:read",
+            Self::TimberSalesWrite => "timber_sales:write",
+            Self::ReportsRead => "reports:read",
+            Self::ReportsWrite => "reports:write",
+            Self::ImportsRead => "imports:read",
+            Self::ImportsWrite => "imports:write",
+            Self::TagsRead => "tags:read",
+            Self::TagsWrite => "tags:write",
+            Self::ErpRead => "erp:read",
+            Self::ErpWrite => "erp:write",
+            Self::CertificationsRead => "certifications:read",
+            Self::CertificationsWrite => "certifications:write",
+            Self::BlocksRead => "blocks:read",
+            Self::BlocksWrite => "blocks:write",
+            Self::CrewsRead => "crews:read",
+            Self::CrewsWrite => "crews:write",
+        }
+    }
+
+    /// The permission stored as `value`, if any
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|permission| permission.as_str() == value)
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Permissions held by each role, and the custom roles of each user
+#[derive(Debug, Clone, Default)]
+pub struct Policy {
+    grants: HashMap<Role, HashSet<Permission>>,
+    members: HashMap<Uuid, Vec<AssignedRole>>,
+}
+
+/// A custom role as the policy keeps it
+#[derive(Debug, Clone)]
+struct AssignedRole {
+    name: String,
+    permissions: HashSet<Permission>,
+}
+
+impl Policy {
+    /// Whether users with `role` may perform `permission`
+    pub fn allows(&self, role: Role, permission: Permission) -> bool {
+        role == Role::Admin || self.grants.get(&role).is_some_and(|grants| grants.contains(&permission))
+    }
+
+    /// Whether `user`, whose built-in role is `role`, may perform
+    /// `permission` through it or one of their custom roles
+    pub fn allows_user(&self, user: Uuid, role: Role, permission: Permission) -> bool {
+        self.allows(role, permission) || self.custom_roles(user).any(|assigned| assigned.permissions.contains(&permission))
+    }
+
+    /// Whether `user` is assigned the custom role named `name`, ignoring case
+    pub fn has_custom_role(&self, user: Uuid, name: &str) -> bool {
+        self.custom_roles(user).any(|assigned| assigned.name.eq_ignore_ascii_case(name))
+    }
+
+    fn custom_roles(&self, user: Uuid) -> impl Iterator<Item = &AssignedRole> {
+        self.members.get(&user).into_iter().flatten()
+    }
+
+    /// The permissions of `role`, in catalogue order
+    pub fn permissions(&self, role: Role) -> Vec<Permission> {
+        Permission::ALL.into_iter().filter(|permission| self.allows(role, *permission)).collect()
+    }
+}
+
+/// Reads and changes the role policy
+pub struct PermissionService;
+
+impl PermissionService {
+    /// The current policy, from the cache while it is fresh
+    pub async fn policy(conn: &mut PgConnection) -> Result<Arc<Policy>> {
+        let cached = POLICY
+            .read()
+            .as_ref()
+            .filter(|(loaded_at, _)| loaded_at.elapsed() < POLICY_TTL)
+            .map(|(_, policy)| policy.clone());
+        if let Some(policy) = cached {
+            return Ok(policy);
+        }
+        let policy = Arc::new(Self::load(conn).await?);
+        *POLICY.write() = Some((Instant::now(), policy.clone()));
+        Ok(policy)
+    }
+
+    /// Reads the policy from the database, bypassing the cache
+    pub async fn load(conn: &mut PgConnection) -> Result<Policy> {
+        let mut policy = Policy::default();
+        for grant in PermissionRepositoryImpl.list(conn).await? {
+            match Permission::parse(&grant.permission) {
+                Some(permission) => {
+                    policy.grants.entry(grant.role).or_default().insert(permission);
+                }
+                // Grants of permissions this build no longer knows
+                None => warn!(role = ?grant.role, permission = %grant.permission, "Ignoring unknown permission"),
+            }
+        }
+        for (user, role) in RoleRepositoryImpl.members(conn).await? {
+            let permissions = role.permissions.iter().filter_map(|name| Permission::parse(name)).collect();
+            policy.members.entry(user).or_default().push(AssignedRole {
+                name: role.name,
+                permissions,
+            });
+        }
+        Ok(policy)
+    }
+
+    /// Drops the cached policy so the next check reads the database
+    pub fn invalidate() {
+        *POLICY.write() = None;
+    }
+
+    /// Whether users with `role` may perform `permission`
+    pub async fn allows(conn: &mut PgConnection, role: Role, permission: Permission) -> Result<bool> {
+        Ok(Self::policy(conn).await?.allows(role, permission))
+    }
+
+    /// Whether `user`, whose built-in role is `role`, may perform
+    /// `permission`, counting their custom roles
+    pub async fn allows_user(conn: &mut PgConnection, user: Uuid, role: Role, permission: Permission) -> Result<bool> {
+        Ok(Self::policy(conn).await?.allows_user(user, role, permission))
+    }
+
+    /// Replaces the permissions of `role`, returning what it now holds
+    pub async fn set_role_permissions(
+        conn: &mut PgConnection,
+        role: Role,
+        permissions: &[Permission],
+    ) -> Result<Vec<Permission>> {
+        if role == Role::Admin {
+            return Err(ApiError::validation(
+                "Admins hold every permission",
+                Some(serde_json::json!({ "field": "role" })),
+            ));
+        }
+
+        let names: Vec<String> = permissions.iter().map(|permission| permission.as_str().to_string()).collect();
+        PermissionRepositoryImpl.replace_for_role(conn, role, &names).await?;
+        Self::invalidate();
+
+        let policy = Self::load(conn).await?;
+        info!(role = ?role, permissions = ?names, "Role permissions replaced");
+        Ok(policy.permissions(role))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_names_round_trip() {
+        for permission in Permission::ALL {
+            assert_eq!(Permission::parse(permission.as_str()), Some(permission));
+            assert_eq!(serde_json::to_value(permission).unwrap(), permission.as_str());
+        }
+        assert_eq!(Permission::parse("blocks:close"), None);
+    }
+
+    #[test]
+    fn test_admins_hold_every_permission() {
+        let mut policy = Policy::default();
+        policy.grants.entry(Role::Manager).or_default().insert(Permission::TagsRead);
+
+        assert!(policy.allows(Role::Admin, Permission::ErpWrite));
+        assert!(policy.allows(Role::Manager, Permission::TagsRead));
+        assert!(!policy.allows(Role::Manager, Permission::TagsWrite));
+        assert!(!policy.allows(Role::Operator, Permission::TagsRead));
+        assert_eq!(policy.permissions(Role::Admin).len(), Permission::ALL.len());
+    }
+
+    #[test]
+    fn test_custom_roles_add_to_the_built_in_role() {
+        let (dispatcher, mechanic) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut policy = Policy::default();
+        policy.grants.entry(Role::Operator).or_default().insert(Permission::TagsRead);
+        policy.members.entry(dispatcher).or_default().push(AssignedRole {
+            name: "Dispatcher".to_string(),
+            permissions: HashSet::from([Permission::ReportsRead]),
+        });
+
+        assert!(policy.allows_user(dispatcher, Role::Operator, Permission::ReportsRead));
+        assert!(policy.allows_user(dispatcher, Role::Operator, Permission::TagsRead));
+        assert!(!policy.allows_user(dispatcher, Role::Operator, Permission::ReportsWrite));
+        assert!(!policy.allows_user(mechanic, Role::Operator, Permission::ReportsRead));
+        assert!(policy.has_custom_role(dispatcher, "dispatcher"));
+        assert!(!policy.has_custom_role(mechanic, "Dispatcher"));
+    }
+}
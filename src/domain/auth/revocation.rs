@@ -0,0 +1,141 @@
+//! Revoked access tokens
+//!
+//! Access tokens are checked without the database, so logging out records
+//! the token's `jti` until the token would have expired anyway. The list is
+//! kept in Redis so every instance honors it. Without Redis, or while it is
+//! unreachable, each instance only knows the tokens revoked through it.
+//!
+//! Changing a user's role or password revokes every access token issued to
+//! them until then, by recording when it happened for as long as those
+//! tokens live. Revoked tokens are also dropped from the
+//! [`TokenCache`](super::TokenCache) of every instance.
+
+use std::{collections::HashMap, time::Duration};
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::Claims;
+use crate::{
+    infrastructure::cluster::{self, ClusterEvent},
+    utils::Config,
+};
+
+/// Tokens revoked through this instance, by `jti`, with their expiry
+static REVOKED: Lazy<Mutex<HashMap<String, i64>>> = Lazy::new(Default::default);
+
+/// Users whose tokens were revoked through this instance, with when
+static REVOKED_USERS: Lazy<Mutex<HashMap<String, i64>>> = Lazy::new(Default::default);
+
+fn key(jti: &str) -> String {
+    format!("revoked:{}", jti)
+}
+
+fn user_key(user_id: &str) -> String {
+    format!("revoked-user:{}", user_id)
+}
+
+/// Tells the other instances to drop tokens from their cache
+async fn broadcast(config: &Config, event: ClusterEvent) {
+    if let Err(e) = cluster::broadcast(config.redis(), event).await {
+        warn!(error = %e, "Revoked token may stay cached on other instances until the cache expires");
+    }
+}
+
+/// The access tokens logged out before they expired
+pub struct RevocationList;
+
+impl RevocationList {
+    /// Revokes the token `jti` until `exp`, its expiry in seconds since the
+    /// epoch
+    pub async fn revoke(config: &Config, jti: &str, exp: i64) {
+        let now = Utc::now().timestamp();
+        if exp <= now {
+            return;
+        }
+        {
+            let mut revoked = REVOKED.lock();
+            revoked.retain(|_, expires| *expires > now);
+            revoked.insert(jti.to_string(), exp);
+        }
+
+        if let Some(redis) = config.redis() {
+            let ttl = Duration::from_secs((exp - now) as u64);
+            if let Err(e) = redis.set_ex(&key(jti), 1, ttl).await {
+                warn!(error = %e, "Revoked token not shared, other instances accept it until it expires");
+            }
+        }
+        config.token_cache().forget(jti);
+        broadcast(config, ClusterEvent::ForgetToken { jti: jti.to_string() }).await;
+    }
+
+    /// Revokes every access token issued to the user `user_id` so far
+    ///
+    /// Token issue times are in whole seconds, so a token issued within the
+    /// same second is still accepted.
+    pub async fn revoke_user(config: &Config, user_id: Uuid) {
+        let user_id = user_id.to_string();
+        let now = Utc::now().timestamp();
+        let lifetime = config.auth.session.access_token_lifetime().num_seconds();
+        {
+            let mut revoked = REVOKED_USERS.lock();
+            revoked.retain(|_, at| *at + lifetime > now);
+            revoked.insert(user_id.clone(), now);
+        }
+
+        if let Some(redis) = config.redis() {
+            let ttl = Duration::from_secs(lifetime as u64);
+            if let Err(e) = redis.set_ex(&user_key(&user_id), now, ttl).await {
+                warn!(error = %e, user_id = %user_id, "Revoked tokens not shared, other instances accept them until they expire");
+            }
+        }
+        config.token_cache().forget_user(&user_id);
+        broadcast(config, ClusterEvent::ForgetUserTokens { user_id }).await;
+    }
+
+    /// Whether the token `jti` has been revoked
+    pub async fn is_revoked(config: &Config, jti: &str) -> bool {
+        let now = Utc::now().timestamp();
+        if REVOKED.lock().get(jti).is_some_and(|expires| *expires > now) {
+            return true;
+        }
+
+        let Some(redis) = config.redis() else {
+            return false;
+        };
+        match redis.get::<u8>(&key(jti)).await {
+            Ok(revoked) => revoked.is_some(),
+            Err(e) => {
+                warn!(error = %e, "Revocation list unavailable, checking this instance only");
+                false
+            }
+        }
+    }
+
+    /// Whether the access token with `claims` was revoked, by logging out or
+    /// along with the other tokens of its user
+    pub async fn is_token_revoked(config: &Config, claims: &Claims) -> bool {
+        if let Some(jti) = &claims.jti {
+            if Self::is_revoked(config, jti).await {
+                return true;
+            }
+        }
+
+        if REVOKED_USERS.lock().get(&claims.sub).is_some_and(|at| claims.iat < *at) {
+            return true;
+        }
+        let Some(redis) = config.redis() else {
+            return false;
+        };
+        match redis.get::<i64>(&user_key(&claims.sub)).await {
+            Ok(revoked_at) => revoked_at.is_some_and(|at| claims.iat < at),
+            Err(e) => {
+                warn!(error = %e, "Revocation list unavailable, checking this instance only");
+                false
+            }
+        }
+    }
+}
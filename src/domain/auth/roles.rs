@@ -0,0 +1,175 @@
+//! Custom roles
+//!
+//! Besides the built-in admin, manager and operator roles, an organization
+//! defines roles of its own, such as "Dispatcher" or "Mechanic", each a set
+//! of permissions, and assigns them to its users. A user holds the
+//! permissions of their built-in role and of every custom role they are
+//! assigned. Routes guarded by a permission resolve custom roles on each
+//! request through the cached [`Policy`](super::Policy), and routes can
+//! require a custom role by name with `RequireRole::custom`.
+
+use chrono::Utc;
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+
+use super::{Permission, PermissionService};
+use crate::{
+    db::{
+        models::{CustomRole, RoleAssignment},
+        repositories::{auth::UserRepositoryImpl, Repository, RoleRepository, RoleRepositoryImpl},
+    },
+    error::{ApiError, ErrorContext, Result},
+};
+
+pub const MAX_ROLE_NAME_LENGTH: usize = 100;
+pub const MAX_ROLE_DESCRIPTION_LENGTH: usize = 1000;
+
+/// Names custom roles can't take, so they aren't mistaken for built-in ones
+const RESERVED_NAMES: [&str; 3] = ["admin", "manager", "operator"];
+
+/// What a custom role is made of
+#[derive(Debug, Clone)]
+pub struct RoleDefinition<'a> {
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+    pub permissions: &'a [Permission],
+}
+
+/// Defines an organization's custom roles and assigns them
+pub struct RoleService;
+
+fn invalid(field: &str, code: &str, message: &str) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": field,
+            "code": code,
+        })),
+    )
+}
+
+impl RoleService {
+    /// The role `definition` describes, checked and normalized
+    fn role(org_id: Uuid, definition: &RoleDefinition<'_>) -> Result<CustomRole> {
+        let name = definition.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_ROLE_NAME_LENGTH {
+            return Err(invalid("name", "INVALID_LENGTH", "Invalid role name"));
+        }
+        if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(name)) {
+            return Err(invalid("name", "RESERVED", "The name of a built-in role can't be reused"));
+        }
+        let description = definition.description.map(str::trim).filter(|description| !description.is_empty());
+        if description.is_some_and(|description| description.chars().count() > MAX_ROLE_DESCRIPTION_LENGTH) {
+            return Err(invalid("description", "INVALID_LENGTH", "Invalid role description"));
+        }
+
+        let now = Utc::now();
+        Ok(CustomRole {
+            id: Uuid::new_v4(),
+            org_id,
+            name: name.to_string(),
+            description: description.map(str::to_string),
+            // In catalogue order, without repeats
+            permissions: Permission::ALL
+                .into_iter()
+                .filter(|permission| definition.permissions.contains(permission))
+                .map(|permission| permission.as_str().to_string())
+                .collect(),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Defines a role
+    pub async fn create(conn: &mut PgConnection, org_id: Uuid, definition: &RoleDefinition<'_>) -> Result<CustomRole> {
+        let role = RoleRepositoryImpl.create(conn, &Self::role(org_id, definition)?).await?;
+        info!(role_id = %role.id, org_id = %org_id, permissions = ?role.permissions, "Created role '{}'", role.name);
+        Ok(role)
+    }
+
+    /// Replaces the name, description and permissions of a role
+    pub async fn update(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        role_id: Uuid,
+        definition: &RoleDefinition<'_>,
+    ) -> Result<CustomRole> {
+        let existing = RoleRepositoryImpl.find(conn, org_id, role_id).await?;
+        let role = CustomRole {
+            id: existing.id,
+            created_at: existing.created_at,
+            ..Self::role(org_id, definition)?
+        };
+        let role = RoleRepositoryImpl.update(conn, org_id, &role).await?;
+        PermissionService::invalidate();
+        info!(role_id = %role.id, org_id = %org_id, permissions = ?role.permissions, "Updated role '{}'", role.name);
+        Ok(role)
+    }
+
+    /// Deletes a role, taking its permissions from everyone it was assigned to
+    pub async fn delete(conn: &mut PgConnection, org_id: Uuid, role_id: Uuid) -> Result<()> {
+        RoleRepositoryImpl.delete(conn, org_id, role_id).await?;
+        PermissionService::invalidate();
+        info!(role_id = %role_id, org_id = %org_id, "Deleted role");
+        Ok(())
+    }
+
+    /// Gets a role
+    pub async fn get(conn: &mut PgConnection, org_id: Uuid, role_id: Uuid) -> Result<CustomRole> {
+        RoleRepositoryImpl.find(conn, org_id, role_id).await
+    }
+
+    /// Lists the organization's roles by name
+    pub async fn list(conn: &mut PgConnection, org_id: Uuid) -> Result<Vec<CustomRole>> {
+        RoleRepositoryImpl.list(conn, org_id).await
+    }
+
+    /// The users each of the organization's roles is assigned to, earliest
+    /// first
+    pub async fn members(conn: &mut PgConnection, org_id: Uuid) -> Result<Vec<RoleAssignment>> {
+        RoleRepositoryImpl.assignments(conn, org_id).await
+    }
+
+    /// Assigns a role to one of the organization's users; assigning it
+    /// again changes nothing
+    pub async fn assign(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        role_id: Uuid,
+        user_id: Uuid,
+        assigned_by: Option<Uuid>,
+    ) -> Result<CustomRole> {
+        let role = RoleRepositoryImpl.find(conn, org_id, role_id).await?;
+        let user = UserRepositoryImpl.find_by_id(conn, user_id).await?;
+        if user.org_id != org_id {
+            return Err(ApiError::not_found(format!("User with id {} not found", user_id)));
+        }
+
+        let assigned = RoleRepositoryImpl
+            .assign(conn, &RoleAssignment {
+                user_id,
+                role_id: role.id,
+                assigned_by,
+                created_at: Utc::now(),
+            })
+            .await?;
+        if assigned {
+            PermissionService::invalidate();
+            info!(role_id = %role.id, org_id = %org_id, user_id = %user_id, "Assigned role '{}'", role.name);
+        }
+        Ok(role)
+    }
+
+    /// Takes a role from a user
+    pub async fn unassign(conn: &mut PgConnection, org_id: Uuid, role_id: Uuid, user_id: Uuid) -> Result<()> {
+        let role = RoleRepositoryImpl.find(conn, org_id, role_id).await?;
+        if !RoleRepositoryImpl.unassign(conn, role.id, user_id).await? {
+            return Err(ApiError::not_found(format!("Role {} is not assigned to user {}", role_id, user_id)));
+        }
+        PermissionService::invalidate();
+        info!(role_id = %role.id, org_id = %org_id, user_id = %user_id, "Unassigned role '{}'", role.name);
+        Ok(())
+    }
+}
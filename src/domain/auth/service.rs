@@ -1,27 +1,62 @@
 use crate::{
     db::{
-        models::auth::{User, RefreshToken},
-        repositories::auth::{UserRepositoryImpl, RefreshTokenRepositoryImpl, CreateUserParams, UserRepository, RefreshTokenRepository},
+        models::auth::{User, RefreshToken, Device, Passkey, AuthEvent},
+        repositories::auth::{
+            UserRepositoryImpl, RefreshTokenRepositoryImpl, PasswordResetTokenRepositoryImpl,
+            EmailVerificationTokenRepositoryImpl, EmailChangeTokenRepositoryImpl, MagicLinkTokenRepositoryImpl, DeviceRepositoryImpl, PasskeyRepositoryImpl,
+            AuthEventRepositoryImpl, CreateUserParams, SessionLifetime, UserRepository, RefreshTokenRepository, PasswordResetTokenRepository,
+            EmailVerificationTokenRepository, EmailChangeTokenRepository, MagicLinkTokenRepository, DeviceRepository, PasskeyRepository, AuthEventRepository,
+            PASSWORD_RESET_TOKEN_MINUTES, EMAIL_VERIFICATION_TOKEN_HOURS, EMAIL_CHANGE_TOKEN_HOURS,
+        },
         repositories::Repository,
         DbPool, connection,
     },
-    error::{Result, ApiError},
-    utils::Config,
-    api::utils::{ApiResponse, ApiResponseBuilder},
+    error::{Result, ApiError, ErrorCode, ErrorContext},
+    infrastructure::{
+        email::{EmailChangeEmail, EmailChangeNoticeEmail, EmailVerificationEmail, MagicLinkEmail, PasswordResetEmail},
+        oidc::OidcProvider,
+    },
+    jobs::email,
+    utils::{crypto::hash_token, Config},
+    api::utils::{ApiResponse, ApiResponseBuilder, PaginationParams},
+    domain::{
+        activity::{ActivityFeed, FeedEvent},
+        invitation::{InvitationClaims, InvitationService},
+        organization::QuotaService,
+    },
 };
 use super::{
+    audit::{method, AuthAudit, AuthEventKind, ClientInfo, NewAuthEvent},
+    claims::Claims,
+    login_alerts::LoginAlerts,
+    revocation::RevocationList,
+    sso,
     tokens::TokenManager,
     validation::AuthValidator,
+    webauthn::{self, Assertion, Attestation, CreationOptions, RequestOptions},
 };
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use diesel::PgConnection;
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
 use uuid::Uuid;
-use chrono::Utc;
+
+/// A new token for a link mailed to a user; only its hash is stored
+pub(crate) fn link_token() -> String {
+    URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+}
 
 /// Authentication service for user management and authentication
 pub struct AuthService;
 
 impl AuthService {
     /// Refresh an access token using a refresh token
-    pub async fn refresh_token(pool: &DbPool, refresh_token: &str, config: &Config) -> Result<ApiResponse<(String, RefreshToken)>> {
+    pub async fn refresh_token(
+        pool: &DbPool,
+        refresh_token: &str,
+        client: &ClientInfo,
+        config: &Config,
+    ) -> Result<ApiResponse<(String, RefreshToken)>> {
         let mut conn = connection::get_connection(pool)?;
 
         let repo = RefreshTokenRepositoryImpl;
@@ -30,23 +65,31 @@ impl AuthService {
             .await?
             .ok_or_else(|| ApiError::unauthorized("Invalid refresh token"))?;
 
-        // Check if token is expired
-        if token.expires_at < Utc::now() {
+        // Sessions end when idle for too long, measured from the last
+        // refresh with the current settings, or at their latest end
+        let now = Utc::now();
+        let session = &config.auth.session;
+        if token.expires_at <= now || token.created_at + session.idle_timeout() <= now {
             return Err(ApiError::unauthorized("Refresh token expired"));
         }
 
         let user_repo = UserRepositoryImpl;
         // Find the user
         let user = user_repo.find_by_id(&mut conn, token.user_id).await?;
+        if !user.is_active {
+            return Err(ApiError::unauthorized("Account deactivated"));
+        }
 
         // Generate new access token
         let access_token = TokenManager::generate_token(&user, config)?;
 
-        // Create new refresh token
-        let new_refresh_token = repo.create_for_user(&mut conn, user.id).await?;
-
-        // Revoke old refresh token
-        repo.revoke_all_for_user(&mut conn, user.id).await?;
+        // Renew the refresh token, sliding the session's idle timeout and
+        // leaving the sessions of other devices
+        let new_refresh_token = repo.renew(&mut conn, &token, session.idle_timeout()).await?;
+        if let Some(device_id) = token.device_id.as_deref() {
+            DeviceRepositoryImpl.touch(&mut conn, user.id, device_id).await?;
+        }
+        AuthAudit::record(&mut conn, client, NewAuthEvent::new(AuthEventKind::TokenRefreshed, Some(user.id))).await;
 
         Ok(ApiResponseBuilder::success()
             .with_message("Token refreshed successfully")
@@ -54,26 +97,103 @@ impl AuthService {
             .build())
     }
 
+    /// Logs the caller out
+    ///
+    /// Revokes `refresh_token` if it belongs to the caller, and the access
+    /// token they called with until it expires.
+    pub async fn logout(
+        pool: &DbPool,
+        claims: &Claims,
+        refresh_token: &str,
+        client: &ClientInfo,
+        config: &Config,
+    ) -> Result<()> {
+        let mut conn = connection::get_connection(pool)?;
+
+        let repo = RefreshTokenRepositoryImpl;
+        if let Some(token) = repo.find_by_token(&mut conn, refresh_token).await? {
+            if token.user_id.to_string() == claims.sub {
+                repo.soft_delete(&mut conn, token.id).await?;
+            }
+        }
+        if let Some(jti) = &claims.jti {
+            RevocationList::revoke(config, jti, claims.exp).await;
+        }
+        let user_id = Uuid::parse_str(&claims.sub).ok();
+        AuthAudit::record(&mut conn, client, NewAuthEvent::new(AuthEventKind::LoggedOut, user_id)).await;
+
+        info!(user_id = %claims.sub, "User logged out");
+        Ok(())
+    }
+
     /// Login a user and generate tokens
+    ///
+    /// With a `device_id` the device is registered, or renamed to
+    /// `device_name`, and the refresh token is issued to it.
     pub async fn login(
         pool: &DbPool,
         email: &str,
         password: &str,
+        device_id: Option<&str>,
+        device_name: Option<&str>,
+        client: &ClientInfo,
         config: &Config,
     ) -> Result<ApiResponse<(String, RefreshToken, User)>> {
+        if let Some(device_id) = device_id {
+            AuthValidator::validate_device(device_id, device_name)?;
+        }
+
         let mut conn = connection::get_connection(pool)?;
 
         let user_repo = UserRepositoryImpl;
-        let refresh_repo = RefreshTokenRepositoryImpl;
 
         // Validate credentials and get user
-        let user = AuthValidator::validate_login(&mut conn, &user_repo, email, password).await?;
+        let user = match AuthValidator::validate_login(&mut conn, &user_repo, email, password).await {
+            Ok(user) => user,
+            Err(e) => {
+                // Shown to the user the email belongs to, if any
+                let user_id = user_repo.find_by_email(&mut conn, email).await.ok().flatten().map(|user| user.id);
+                let event = NewAuthEvent::new(AuthEventKind::LoginFailed, user_id)
+                    .method(method::PASSWORD)
+                    .email(email)
+                    .reason(&e.message);
+                AuthAudit::record(&mut conn, client, event).await;
+                return Err(e);
+            }
+        };
+        Self::ensure_active(&mut conn, &user, method::PASSWORD, client).await?;
+
+        // Upgrade hashes made with older settings while the password is at hand
+        if User::needs_rehash(&user.password) {
+            match user_repo.update_password(&mut conn, user.id, password).await {
+                Ok(_) => info!(user_id = %user.id, "Password rehashed with the configured settings"),
+                Err(e) => warn!(user_id = %user.id, error = %e, "Failed to rehash password"),
+            }
+        }
+
+        if config.auth.require_verified_email && !user.email_verified {
+            let event = NewAuthEvent::new(AuthEventKind::LoginFailed, Some(user.id))
+                .method(method::PASSWORD)
+                .email(email)
+                .reason("Email address not verified");
+            AuthAudit::record(&mut conn, client, event).await;
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Email address not verified",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "email",
+                    "code": "NOT_VERIFIED",
+                })),
+            ));
+        }
 
         // Generate access token
         let access_token = TokenManager::generate_token(&user, config)?;
 
         // Generate refresh token
-        let refresh_token = refresh_repo.create_for_user(&mut conn, user.id).await?;
+        let refresh_token = Self::issue_refresh_token(&mut conn, user.id, device_id, device_name, config).await?;
+
+        Self::record_login(&mut conn, &user, method::PASSWORD, device_id, client, config).await;
 
         Ok(ApiResponseBuilder::success()
             .with_message("Login successful")
@@ -82,37 +202,650 @@ impl AuthService {
     }
 
     /// Register a new user
+    ///
+    /// With an `invitation` the user joins its organization with its role
+    /// and the invitation is used up. It was mailed to the user, so their
+    /// address counts as verified.
     pub async fn register(
         pool: &DbPool,
-        first_name: &str,
-        last_name: &str,
-        email: &str,
-        phone_number: &str,
-        password: &str,
-        org_id: Uuid,
+        params: CreateUserParams<'_>,
+        invitation: Option<&InvitationClaims>,
+        config: &Config,
     ) -> Result<ApiResponse<User>> {
         let mut conn = connection::get_connection(pool)?;
 
         let user_repo = UserRepositoryImpl;
 
-        let params = CreateUserParams {
-            first_name,
-            last_name,
-            email,
-            phone_number,
-            password,
-            org_id,
-        };
-
         // Validate registration input
         AuthValidator::validate_registration(&mut conn, &user_repo, &params).await?;
-        
-        // Create user
-        let user = user_repo.create_with_password(&mut conn, params).await?;
+        QuotaService::check_users(&mut conn, invitation.map_or(params.org_id, |invitation| invitation.org_id), 1).await?;
+
+        let Some(invitation) = invitation else {
+            let user = user_repo.create_with_password(&mut conn, params).await?;
+            Self::send_verification_email(&mut conn, &user, config).await?;
+            ActivityFeed::record(&mut conn, user.org_id, Some(user.id), FeedEvent::user_joined(&user, "registration")).await;
+
+            return Ok(ApiResponseBuilder::success()
+                .with_message("User registered successfully")
+                .with_data(user)
+                .build());
+        };
+
+        let invitation = InvitationService::accept(&mut conn, invitation, params.email).await?;
+        let user = user_repo
+            .create_with_password(&mut conn, CreateUserParams {
+                org_id: invitation.org_id,
+                role: invitation.role,
+                ..params
+            })
+            .await?;
+        let user = user_repo.mark_email_verified(&mut conn, user.id).await?;
+        info!(user_id = %user.id, invitation_id = %invitation.id, "Invited user registered");
+        ActivityFeed::record(&mut conn, user.org_id, invitation.invited_by, FeedEvent::user_joined(&user, "invitation")).await;
 
         Ok(ApiResponseBuilder::success()
             .with_message("User registered successfully")
             .with_data(user)
             .build())
     }
-}
\ No newline at end of file
+
+    /// Mail a single-use password reset link to the user with `email`
+    ///
+    /// Succeeds whether or not a user has that email, so the response does
+    /// not reveal which addresses are registered. Requesting another link
+    /// revokes the previous ones.
+    pub async fn forgot_password(pool: &DbPool, email: &str, config: &Config) -> Result<()> {
+        let mut conn = connection::get_connection(pool)?;
+
+        let user_repo = UserRepositoryImpl;
+        let Some(user) = user_repo.find_by_email(&mut conn, email).await? else {
+            info!("Password reset requested for an unknown email");
+            return Ok(());
+        };
+
+        let reset_repo = PasswordResetTokenRepositoryImpl;
+        reset_repo.revoke_all_for_user(&mut conn, user.id).await?;
+        let token = link_token();
+        reset_repo.create_for_user(&mut conn, user.id, &hash_token(&token)).await?;
+
+        let data = PasswordResetEmail {
+            name: user.first_name.clone(),
+            reset_url: format!("{}/reset-password?token={}", config.email.app_url.trim_end_matches('/'), token),
+            expires_in_minutes: PASSWORD_RESET_TOKEN_MINUTES,
+        };
+        let message = config.mailer().compose(&mut conn, Some(user.org_id), &user.email, &data).await?;
+        email::enqueue(&mut conn, &config.queue, &message).await?;
+
+        info!(user_id = %user.id, "Password reset link sent");
+        Ok(())
+    }
+
+    /// Set a new password with a token from a password reset link
+    ///
+    /// The token is used up and the user's refresh and access tokens are
+    /// revoked, so other sessions have to log in again.
+    pub async fn reset_password(
+        pool: &DbPool,
+        token: &str,
+        new_password: &str,
+        client: &ClientInfo,
+        config: &Config,
+    ) -> Result<()> {
+        AuthValidator::validate_password(new_password)?;
+
+        let mut conn = connection::get_connection(pool)?;
+
+        let reset_repo = PasswordResetTokenRepositoryImpl;
+        let token = reset_repo.consume(&mut conn, &hash_token(token))
+            .await?
+            .ok_or_else(|| ApiError::validation("Invalid or expired password reset token", None))?;
+
+        let user_repo = UserRepositoryImpl;
+        let mut user = user_repo.update_password(&mut conn, token.user_id, new_password).await?;
+        // The link was mailed to the user, so following it proves the
+        // address, as it does for imported users setting their first password
+        if !user.email_verified {
+            user = user_repo.mark_email_verified(&mut conn, user.id).await?;
+        }
+
+        reset_repo.revoke_all_for_user(&mut conn, user.id).await?;
+        RefreshTokenRepositoryImpl.revoke_all_for_user(&mut conn, user.id).await?;
+        RevocationList::revoke_user(config, user.id).await;
+
+        let event = NewAuthEvent::new(AuthEventKind::PasswordChanged, Some(user.id)).method(method::PASSWORD_RESET);
+        AuthAudit::record(&mut conn, client, event).await;
+
+        info!(user_id = %user.id, "Password reset");
+        Ok(())
+    }
+
+    /// Mail a new email verification link to the user with `email`
+    ///
+    /// Like [`Self::forgot_password`] this succeeds whether or not a user
+    /// has that email, and does nothing for addresses already verified.
+    pub async fn send_verification(pool: &DbPool, email: &str, config: &Config) -> Result<()> {
+        let mut conn = connection::get_connection(pool)?;
+
+        let user_repo = UserRepositoryImpl;
+        match user_repo.find_by_email(&mut conn, email).await? {
+            Some(user) if !user.email_verified => Self::send_verification_email(&mut conn, &user, config).await,
+            _ => {
+                info!("Email verification requested for an unknown or verified email");
+                Ok(())
+            }
+        }
+    }
+
+    /// Mark the user's email verified with a token from a verification link
+    pub async fn verify_email(pool: &DbPool, token: &str) -> Result<User> {
+        let mut conn = connection::get_connection(pool)?;
+
+        let verification_repo = EmailVerificationTokenRepositoryImpl;
+        let token = verification_repo.consume(&mut conn, &hash_token(token))
+            .await?
+            .ok_or_else(|| ApiError::validation("Invalid or expired email verification token", None))?;
+
+        let user_repo = UserRepositoryImpl;
+        let user = user_repo.mark_email_verified(&mut conn, token.user_id).await?;
+        verification_repo.revoke_all_for_user(&mut conn, user.id).await?;
+
+        info!(user_id = %user.id, "Email verified");
+        Ok(user)
+    }
+
+    /// Ask to switch `user_id` to `new_email`
+    ///
+    /// A confirmation link goes to the new address and a notice to the
+    /// current one, which stays in use until the link is followed. Asking
+    /// again revokes the links sent before.
+    pub async fn request_email_change(pool: &DbPool, user_id: Uuid, new_email: &str, config: &Config) -> Result<()> {
+        let new_email = new_email.trim();
+        AuthValidator::validate_email(new_email)?;
+
+        let mut conn = connection::get_connection(pool)?;
+
+        let user_repo = UserRepositoryImpl;
+        let user = user_repo.find_by_id(&mut conn, user_id).await?;
+        if user.email.eq_ignore_ascii_case(new_email) {
+            return Err(ApiError::validation_with_context(
+                "The new email is the current one",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "email",
+                    "code": "UNCHANGED",
+                })),
+            ));
+        }
+        if user_repo.find_by_email(&mut conn, new_email).await?.is_some() {
+            return Err(ApiError::new(
+                ErrorCode::Conflict,
+                "Email already registered",
+                ErrorContext::new().with_details(serde_json::json!({ "field": "email" })),
+            ));
+        }
+
+        let change_repo = EmailChangeTokenRepositoryImpl;
+        change_repo.revoke_all_for_user(&mut conn, user.id).await?;
+        let token = link_token();
+        change_repo.create_for_user(&mut conn, user.id, &hash_token(&token), new_email).await?;
+
+        let data = EmailChangeEmail {
+            name: user.first_name.clone(),
+            new_email: new_email.to_string(),
+            confirm_url: format!("{}/confirm-email-change?token={}", config.email.app_url.trim_end_matches('/'), token),
+            expires_in_hours: EMAIL_CHANGE_TOKEN_HOURS,
+        };
+        let message = config.mailer().compose(&mut conn, Some(user.org_id), new_email, &data).await?;
+        email::enqueue(&mut conn, &config.queue, &message).await?;
+
+        let notice = EmailChangeNoticeEmail {
+            name: user.first_name.clone(),
+            new_email: new_email.to_string(),
+        };
+        let message = config.mailer().compose(&mut conn, Some(user.org_id), &user.email, &notice).await?;
+        email::enqueue(&mut conn, &config.queue, &message).await?;
+
+        info!(user_id = %user.id, "Email change confirmation sent");
+        Ok(())
+    }
+
+    /// Switch the user to the new address with a token from an email change
+    /// link
+    ///
+    /// Following the link proves the user owns the address, so it is marked
+    /// verified. Other pending changes and verification links are revoked.
+    pub async fn confirm_email_change(pool: &DbPool, token: &str, client: &ClientInfo) -> Result<User> {
+        let mut conn = connection::get_connection(pool)?;
+
+        let change_repo = EmailChangeTokenRepositoryImpl;
+        let token = change_repo.consume(&mut conn, &hash_token(token))
+            .await?
+            .ok_or_else(|| ApiError::validation("Invalid or expired email change token", None))?;
+
+        let user_repo = UserRepositoryImpl;
+        let user = user_repo.change_email(&mut conn, token.user_id, &token.new_email).await?;
+        change_repo.revoke_all_for_user(&mut conn, user.id).await?;
+        EmailVerificationTokenRepositoryImpl.revoke_all_for_user(&mut conn, user.id).await?;
+
+        let event = NewAuthEvent::new(AuthEventKind::EmailChanged, Some(user.id));
+        AuthAudit::record(&mut conn, client, event).await;
+
+        info!(user_id = %user.id, "Email changed");
+        Ok(user)
+    }
+
+    /// Sign the user out everywhere with the token from the "this wasn't
+    /// me" link of a login alert
+    ///
+    /// Their refresh tokens, pending magic links and the access tokens
+    /// issued to them so far are revoked, so whoever logged in has to log in
+    /// again; the password is left for the user to reset.
+    pub async fn secure_account(pool: &DbPool, token: &str, client: &ClientInfo, config: &Config) -> Result<()> {
+        let user_id = LoginAlerts::verify_token(token, config)?;
+
+        let mut conn = connection::get_connection(pool)?;
+
+        let user = UserRepositoryImpl.find_by_id(&mut conn, user_id).await?;
+        RefreshTokenRepositoryImpl.revoke_all_for_user(&mut conn, user.id).await?;
+        MagicLinkTokenRepositoryImpl.revoke_all_for_user(&mut conn, user.id).await?;
+        RevocationList::revoke_user(config, user.id).await;
+
+        let event = NewAuthEvent::new(AuthEventKind::SessionsRevoked, Some(user.id));
+        AuthAudit::record(&mut conn, client, event).await;
+
+        info!(user_id = %user.id, "Signed out everywhere from a login alert");
+        Ok(())
+    }
+
+    /// Mail a single-use login link to the user with `email`
+    ///
+    /// Like [`Self::forgot_password`] this succeeds whether or not a user
+    /// has that email, and revokes the links sent before. With
+    /// `auth.magic_link.bind_device` the link only works on `device_id`, the
+    /// device it was requested from.
+    pub async fn request_magic_link(pool: &DbPool, email: &str, device_id: Option<&str>, config: &Config) -> Result<()> {
+        if let Some(device_id) = device_id {
+            AuthValidator::validate_device(device_id, None)?;
+        }
+
+        let mut conn = connection::get_connection(pool)?;
+
+        let user_repo = UserRepositoryImpl;
+        let Some(user) = user_repo.find_by_email(&mut conn, email).await? else {
+            info!("Magic link requested for an unknown email");
+            return Ok(());
+        };
+
+        let settings = &config.auth.magic_link;
+        let device_id = device_id.filter(|_| settings.bind_device);
+        let link_repo = MagicLinkTokenRepositoryImpl;
+        link_repo.revoke_all_for_user(&mut conn, user.id).await?;
+        // Only the email holds the token; the link is stored by its hash
+        let token = link_token();
+        link_repo
+            .create_for_user(&mut conn, user.id, &hash_token(&token), device_id, Duration::minutes(settings.expiry_minutes))
+            .await?;
+
+        let data = MagicLinkEmail {
+            name: user.first_name.clone(),
+            login_url: format!("{}/magic-link?token={}", config.email.app_url.trim_end_matches('/'), token),
+            expires_in_minutes: settings.expiry_minutes,
+        };
+        let message = config.mailer().compose(&mut conn, Some(user.org_id), &user.email, &data).await?;
+        email::enqueue(&mut conn, &config.queue, &message).await?;
+
+        info!(user_id = %user.id, bound = device_id.is_some(), "Magic link sent");
+        Ok(())
+    }
+
+    /// Log in with a token from a magic link, sent from `device_id`
+    ///
+    /// Opening the link proves the user owns the address, so it is marked
+    /// verified. Like [`Self::login`], the device is registered.
+    pub async fn redeem_magic_link(
+        pool: &DbPool,
+        token: &str,
+        device_id: Option<&str>,
+        device_name: Option<&str>,
+        client: &ClientInfo,
+        config: &Config,
+    ) -> Result<ApiResponse<(String, RefreshToken, User)>> {
+        if let Some(device_id) = device_id {
+            AuthValidator::validate_device(device_id, device_name)?;
+        }
+
+        let mut conn = connection::get_connection(pool)?;
+
+        let link_repo = MagicLinkTokenRepositoryImpl;
+        let Some(token) = link_repo.consume(&mut conn, &hash_token(token), device_id).await? else {
+            let event = NewAuthEvent::new(AuthEventKind::LoginFailed, None)
+                .method(method::MAGIC_LINK)
+                .reason("Invalid or expired magic link");
+            AuthAudit::record(&mut conn, client, event).await;
+            return Err(ApiError::unauthorized("Invalid or expired magic link"));
+        };
+
+        let user_repo = UserRepositoryImpl;
+        let mut user = user_repo.find_by_id(&mut conn, token.user_id).await?;
+        Self::ensure_active(&mut conn, &user, method::MAGIC_LINK, client).await?;
+        if !user.email_verified {
+            user = user_repo.mark_email_verified(&mut conn, user.id).await?;
+        }
+
+        let access_token = TokenManager::generate_token(&user, config)?;
+        let refresh_token = Self::issue_refresh_token(&mut conn, user.id, device_id, device_name, config).await?;
+
+        Self::record_login(&mut conn, &user, method::MAGIC_LINK, device_id, client, config).await;
+
+        info!(user_id = %user.id, "Logged in with a magic link");
+        Ok(ApiResponseBuilder::success()
+            .with_message("Login successful")
+            .with_data((access_token, refresh_token, user))
+            .build())
+    }
+
+    /// The devices `user_id` has logged in from, most recently seen first
+    pub async fn list_devices(pool: &DbPool, user_id: Uuid) -> Result<Vec<Device>> {
+        let mut conn = connection::get_connection(pool)?;
+        DeviceRepositoryImpl.list_for_user(&mut conn, user_id).await
+    }
+
+    /// Forget one of the user's devices and revoke the refresh tokens
+    /// issued to it
+    ///
+    /// Access tokens already issued to the device stay valid until they
+    /// expire.
+    pub async fn revoke_device(pool: &DbPool, user_id: Uuid, id: Uuid) -> Result<()> {
+        let mut conn = connection::get_connection(pool)?;
+
+        let device_repo = DeviceRepositoryImpl;
+        let device = device_repo.find_for_user(&mut conn, user_id, id)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Device with id {} not found", id)))?;
+
+        device_repo.soft_delete(&mut conn, device.id).await?;
+        RefreshTokenRepositoryImpl.revoke_for_device(&mut conn, user_id, Some(&device.device_id)).await?;
+
+        info!(user_id = %user_id, device_id = %device.id, "Device revoked");
+        Ok(())
+    }
+
+    /// A page of the authentication events of `user_id`, newest first, and
+    /// how many there are
+    pub async fn list_auth_events(
+        pool: &DbPool,
+        user_id: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<AuthEvent>, i64)> {
+        let mut conn = connection::get_connection(pool)?;
+        let repo = AuthEventRepositoryImpl;
+        let events = repo.list_for_user(&mut conn, user_id, pagination).await?;
+        let total = repo.count_for_user(&mut conn, user_id).await?;
+        Ok((events, total))
+    }
+
+    /// Options to register a passkey for `user_id`, and the token the
+    /// registration is finished with
+    pub async fn passkey_registration_options(
+        pool: &DbPool,
+        user_id: Uuid,
+        config: &Config,
+    ) -> Result<(String, CreationOptions)> {
+        let mut conn = connection::get_connection(pool)?;
+        let user = UserRepositoryImpl.find_by_id(&mut conn, user_id).await?;
+        let registered = PasskeyRepositoryImpl.list_for_user(&mut conn, user_id).await?;
+        webauthn::creation_options(&user, &registered, config)
+    }
+
+    /// Register the passkey `attestation` created for `user_id`, answering
+    /// the challenge of `token`
+    pub async fn register_passkey(
+        pool: &DbPool,
+        user_id: Uuid,
+        name: &str,
+        token: &str,
+        attestation: &Attestation<'_>,
+        client: &ClientInfo,
+        config: &Config,
+    ) -> Result<Passkey> {
+        AuthValidator::validate_passkey_name(name)?;
+        let credential = webauthn::verify_registration(token, user_id, attestation, config).await?;
+
+        let mut conn = connection::get_connection(pool)?;
+        let now = Utc::now();
+        let passkey = PasskeyRepositoryImpl
+            .create(&mut conn, &Passkey {
+                id: Uuid::new_v4(),
+                user_id,
+                credential_id: credential.credential_id,
+                public_key: credential.public_key,
+                sign_count: i64::from(credential.sign_count),
+                name: name.trim().to_string(),
+                last_used_at: None,
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+
+        let event = NewAuthEvent::new(AuthEventKind::PasskeyAdded, Some(user_id)).method(method::PASSKEY);
+        AuthAudit::record(&mut conn, client, event).await;
+
+        info!(user_id = %user_id, passkey_id = %passkey.id, "Passkey registered");
+        Ok(passkey)
+    }
+
+    /// Options to log in with a passkey, and the token the login is
+    /// finished with
+    pub fn passkey_login_options(config: &Config) -> Result<(String, RequestOptions)> {
+        webauthn::request_options(config)
+    }
+
+    /// Log in with the passkey `assertion` was signed with, answering the
+    /// challenge of `token`
+    ///
+    /// Like [`Self::login`], the device is registered.
+    pub async fn passkey_login(
+        pool: &DbPool,
+        token: &str,
+        assertion: &Assertion<'_>,
+        device_id: Option<&str>,
+        device_name: Option<&str>,
+        client: &ClientInfo,
+        config: &Config,
+    ) -> Result<ApiResponse<(String, RefreshToken, User)>> {
+        if let Some(device_id) = device_id {
+            AuthValidator::validate_device(device_id, device_name)?;
+        }
+
+        let mut conn = connection::get_connection(pool)?;
+
+        let passkey_repo = PasskeyRepositoryImpl;
+        let Some(passkey) = passkey_repo.find_by_credential_id(&mut conn, assertion.credential_id).await? else {
+            let e = ApiError::unauthorized("Invalid passkey: it is not registered");
+            return Err(Self::passkey_login_failed(&mut conn, client, None, e).await);
+        };
+        let sign_count = match webauthn::verify_assertion(token, &passkey, assertion, config).await {
+            Ok(sign_count) => sign_count,
+            Err(e) => return Err(Self::passkey_login_failed(&mut conn, client, Some(passkey.user_id), e).await),
+        };
+        passkey_repo.record_use(&mut conn, passkey.id, i64::from(sign_count)).await?;
+
+        let user = UserRepositoryImpl.find_by_id(&mut conn, passkey.user_id).await?;
+        Self::ensure_active(&mut conn, &user, method::PASSKEY, client).await?;
+        let access_token = TokenManager::generate_token(&user, config)?;
+        let refresh_token = Self::issue_refresh_token(&mut conn, user.id, device_id, device_name, config).await?;
+
+        Self::record_login(&mut conn, &user, method::PASSKEY, device_id, client, config).await;
+
+        info!(user_id = %user.id, passkey_id = %passkey.id, "Logged in with a passkey");
+        Ok(ApiResponseBuilder::success()
+            .with_message("Login successful")
+            .with_data((access_token, refresh_token, user))
+            .build())
+    }
+
+    /// The passkeys of `user_id`, oldest first
+    pub async fn list_passkeys(pool: &DbPool, user_id: Uuid) -> Result<Vec<Passkey>> {
+        let mut conn = connection::get_connection(pool)?;
+        PasskeyRepositoryImpl.list_for_user(&mut conn, user_id).await
+    }
+
+    /// Remove one of the user's passkeys, so it can no longer log in
+    pub async fn delete_passkey(pool: &DbPool, user_id: Uuid, id: Uuid, client: &ClientInfo) -> Result<()> {
+        let mut conn = connection::get_connection(pool)?;
+        if !PasskeyRepositoryImpl.delete_for_user(&mut conn, user_id, id).await? {
+            return Err(ApiError::not_found(format!("Passkey with id {} not found", id)));
+        }
+
+        let event = NewAuthEvent::new(AuthEventKind::PasskeyRemoved, Some(user_id)).method(method::PASSKEY);
+        AuthAudit::record(&mut conn, client, event).await;
+
+        info!(user_id = %user_id, passkey_id = %id, "Passkey removed");
+        Ok(())
+    }
+
+    /// Start a single sign-on with `provider`, returning the URL of its sign-in page
+    pub async fn oidc_authorize(provider: &str, config: &Config) -> Result<String> {
+        let oidc = Self::oidc_provider(provider, config)?;
+        let (state, nonce) = sso::issue_state(provider, config)?;
+        oidc.authorization_url(&state, &nonce).await
+    }
+
+    /// Finish a single sign-on with the code and state the provider
+    /// redirected back with, logging in the user the account belongs to
+    pub async fn oidc_login(
+        pool: &DbPool,
+        provider: &str,
+        code: &str,
+        state: &str,
+        client: &ClientInfo,
+        config: &Config,
+    ) -> Result<ApiResponse<(String, RefreshToken, User)>> {
+        let oidc = Self::oidc_provider(provider, config)?;
+        let nonce = sso::verify_state(state, provider, config)?;
+        let claims = oidc.exchange(code, &nonce).await?;
+
+        let mut conn = connection::get_connection(pool)?;
+        let user = match sso::resolve_user(&mut conn, provider, oidc.trust_email(), &claims, config).await {
+            Ok(user) => user,
+            Err(e) => {
+                let mut event = NewAuthEvent::new(AuthEventKind::LoginFailed, None)
+                    .method(method::sso(provider))
+                    .reason(&e.message);
+                if let Some(email) = claims.email() {
+                    event = event.email(email);
+                }
+                AuthAudit::record(&mut conn, client, event).await;
+                return Err(e);
+            }
+        };
+        Self::ensure_active(&mut conn, &user, &method::sso(provider), client).await?;
+
+        let access_token = TokenManager::generate_token(&user, config)?;
+        let refresh_repo = RefreshTokenRepositoryImpl;
+        let refresh_token = refresh_repo.create_for_user(&mut conn, user.id, SessionLifetime::from(&config.auth.session)).await?;
+
+        Self::record_login(&mut conn, &user, &method::sso(provider), None, client, config).await;
+
+        info!(user_id = %user.id, provider = %provider, "Logged in through single sign-on");
+        Ok(ApiResponseBuilder::success()
+            .with_message("Login successful")
+            .with_data((access_token, refresh_token, user))
+            .build())
+    }
+
+    /// The configured provider called `provider`
+    fn oidc_provider<'a>(provider: &'a str, config: &'a Config) -> Result<OidcProvider<'a>> {
+        config.auth.oidc
+            .get(provider)
+            .map(|provider_config| OidcProvider::new(provider, provider_config))
+            .ok_or_else(|| ApiError::not_found(format!("SSO provider {} not found", provider)))
+    }
+
+    /// Record a failed passkey login by `user_id`, if known, passing on
+    /// the error
+    async fn passkey_login_failed(
+        conn: &mut PgConnection,
+        client: &ClientInfo,
+        user_id: Option<Uuid>,
+        e: ApiError,
+    ) -> ApiError {
+        let event = NewAuthEvent::new(AuthEventKind::LoginFailed, user_id)
+            .method(method::PASSKEY)
+            .reason(&e.message);
+        AuthAudit::record(conn, client, event).await;
+        e
+    }
+
+    /// Refuse a login by `method` for a deactivated user, recording the
+    /// attempt
+    async fn ensure_active(conn: &mut PgConnection, user: &User, method: &str, client: &ClientInfo) -> Result<()> {
+        if user.is_active {
+            return Ok(());
+        }
+        let event = NewAuthEvent::new(AuthEventKind::LoginFailed, Some(user.id))
+            .method(method)
+            .email(&user.email)
+            .reason("Account deactivated");
+        AuthAudit::record(conn, client, event).await;
+        Err(ApiError::new(
+            ErrorCode::Forbidden,
+            "Account deactivated",
+            ErrorContext::new().with_details(serde_json::json!({
+                "field": "email",
+                "code": "DEACTIVATED",
+            })),
+        ))
+    }
+
+    /// Record a successful login by `method`, first alerting the user if it
+    /// comes from somewhere new
+    async fn record_login(
+        conn: &mut PgConnection,
+        user: &User,
+        method: &str,
+        device_id: Option<&str>,
+        client: &ClientInfo,
+        config: &Config,
+    ) {
+        LoginAlerts::check(conn, user, client, device_id, config).await;
+        let event = NewAuthEvent::new(AuthEventKind::LoginSucceeded, Some(user.id))
+            .method(method)
+            .device(device_id);
+        AuthAudit::record(conn, client, event).await;
+    }
+
+    /// Issue a refresh token starting a session, to `device_id` if given,
+    /// registering the device first
+    async fn issue_refresh_token(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        device_id: Option<&str>,
+        device_name: Option<&str>,
+        config: &Config,
+    ) -> Result<RefreshToken> {
+        if let Some(device_id) = device_id {
+            DeviceRepositoryImpl.register(conn, user_id, device_id, device_name).await?;
+        }
+        let lifetime = SessionLifetime::from(&config.auth.session);
+        RefreshTokenRepositoryImpl.create_for_device(conn, user_id, device_id, lifetime).await
+    }
+
+    /// Queue a verification link to `user`, revoking earlier links
+    async fn send_verification_email(conn: &mut PgConnection, user: &User, config: &Config) -> Result<()> {
+        let verification_repo = EmailVerificationTokenRepositoryImpl;
+        verification_repo.revoke_all_for_user(conn, user.id).await?;
+        let token = link_token();
+        verification_repo.create_for_user(conn, user.id, &hash_token(&token)).await?;
+
+        let data = EmailVerificationEmail {
+            name: user.first_name.clone(),
+            verify_url: format!("{}/verify-email?token={}", config.email.app_url.trim_end_matches('/'), token),
+            expires_in_hours: EMAIL_VERIFICATION_TOKEN_HOURS,
+        };
+        let message = config.mailer().compose(conn, Some(user.org_id), &user.email, &data).await?;
+        email::enqueue(conn, &config.queue, &message).await?;
+
+        info!(user_id = %user.id, "Email verification link sent");
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+//! Single sign-on through OpenID Connect
+//!
+//! The web app asks for a provider's authorization URL, sends the user
+//! there and posts back the code the provider returns. The `state` handed
+//! to the provider is a short-lived token signed with `jwt_secret`, so no
+//! flow is kept on the server; it carries the nonce the ID token must echo.
+//!
+//! A provider account signs in as the user it is linked to. An unlinked
+//! account is linked to the user with its email, if any, and otherwise a
+//! user is provisioned into the organization that claimed its email domain.
+//! A user whose email was never verified may have been registered by
+//! someone else, so their password, sessions and passkeys are dropped
+//! before linking.
+
+use chrono::{Duration, Utc};
+use diesel::PgConnection;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{auth::User, UserIdentity},
+        repositories::{
+            auth::{
+                EmailChangeTokenRepository, EmailChangeTokenRepositoryImpl, MagicLinkTokenRepository,
+                MagicLinkTokenRepositoryImpl, PasskeyRepository, PasskeyRepositoryImpl, RefreshTokenRepository,
+                RefreshTokenRepositoryImpl, UserRepository, UserRepositoryImpl,
+            },
+            Repository, SsoRepository, SsoRepositoryImpl,
+        },
+    },
+    domain::{
+        activity::{ActivityFeed, FeedEvent},
+        organization::QuotaService,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::oidc::IdTokenClaims,
+    utils::Config,
+};
+
+use super::revocation::RevocationList;
+
+/// Seconds a user has to complete a sign-on
+const STATE_TTL_SECS: i64 = 10 * 60;
+
+/// Audience of state tokens, so they can't pass for access tokens
+const STATE_AUDIENCE: &str = "sso-state";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SsoState {
+    provider: String,
+    nonce: String,
+    aud: String,
+    exp: i64,
+}
+
+/// A signed `state` for a sign-on with `provider`, and its nonce
+pub fn issue_state(provider: &str, config: &Config) -> Result<(String, String)> {
+    let nonce = Uuid::new_v4().to_string();
+    let state = SsoState {
+        provider: provider.to_string(),
+        nonce: nonce.clone(),
+        aud: STATE_AUDIENCE.to_string(),
+        exp: (Utc::now() + Duration::seconds(STATE_TTL_SECS)).timestamp(),
+    };
+    let token = encode(&Header::default(), &state, &EncodingKey::from_secret(config.jwt_secret.as_bytes()))
+        .map_err(|e| ApiError::new(ErrorCode::InternalError, format!("Failed to sign SSO state: {}", e), ErrorContext::new()))?;
+    Ok((token, nonce))
+}
+
+/// The nonce of a `state` issued for `provider`
+pub fn verify_state(token: &str, provider: &str, config: &Config) -> Result<String> {
+    let mut validation = Validation::default();
+    validation.set_audience(&[STATE_AUDIENCE]);
+    let state = decode::<SsoState>(token, &DecodingKey::from_secret(config.jwt_secret.as_bytes()), &validation)
+        .map_err(|_| ApiError::validation("The sign-in expired or was tampered with, please try again", None))?
+        .claims;
+    if state.provider != provider {
+        return Err(ApiError::validation("The sign-in was started with another provider", None));
+    }
+    Ok(state.nonce)
+}
+
+/// A claimable email domain in canonical form, `None` if `domain` isn't one
+pub fn normalize_domain(domain: &str) -> Option<String> {
+    let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    let labels_valid = domain.split('.').all(|label| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    (domain.contains('.') && domain.len() <= 255 && labels_valid).then_some(domain)
+}
+
+/// The user a verified provider account signs in as, linking or
+/// provisioning one on the first sign-on
+pub async fn resolve_user(
+    conn: &mut PgConnection,
+    provider: &str,
+    trust_email: bool,
+    claims: &IdTokenClaims,
+    config: &Config,
+) -> Result<User> {
+    let sso_repo = SsoRepositoryImpl;
+    let user_repo = UserRepositoryImpl;
+
+    if let Some(identity) = sso_repo.find_identity(conn, provider, &claims.sub).await? {
+        let user = user_repo.find_by_id(conn, identity.user_id).await.map_err(|e| match e.code {
+            ErrorCode::NotFound => forbidden("The account this sign-in is linked to was removed", None),
+            _ => e,
+        })?;
+        sso_repo.touch_identity(conn, provider, &claims.sub).await?;
+        return Ok(user);
+    }
+
+    let email = claims
+        .email()
+        .ok_or_else(|| forbidden("The identity provider did not share an email address", None))?;
+    if !claims.email_verified.unwrap_or(trust_email) {
+        return Err(forbidden("The identity provider has not verified the email address", Some(email)));
+    }
+
+    let user = match user_repo.find_by_email(conn, email).await? {
+        Some(user) if user.email_verified => user,
+        Some(user) => claim_unverified(conn, &user, config).await?,
+        None => provision(conn, email, claims).await?,
+    };
+
+    let now = Utc::now();
+    sso_repo
+        .link_identity(conn, &UserIdentity {
+            provider: provider.to_string(),
+            subject: claims.sub.clone(),
+            user_id: user.id,
+            email: email.to_string(),
+            created_at: now,
+            last_login_at: now,
+        })
+        .await?;
+    info!(user_id = %user.id, provider = %provider, "Linked identity provider account");
+    Ok(user)
+}
+
+/// Hands a user whose email was never verified to the owner of the
+/// address
+///
+/// Whoever registered the account may not own the address, so nothing
+/// they could log in with survives: the password is replaced with one
+/// nobody knows, and sessions, links, pending email changes and passkeys
+/// are revoked.
+async fn claim_unverified(conn: &mut PgConnection, user: &User, config: &Config) -> Result<User> {
+    let user_repo = UserRepositoryImpl;
+    user_repo.update_password(conn, user.id, &Uuid::new_v4().to_string()).await?;
+    RefreshTokenRepositoryImpl.revoke_all_for_user(conn, user.id).await?;
+    MagicLinkTokenRepositoryImpl.revoke_all_for_user(conn, user.id).await?;
+    EmailChangeTokenRepositoryImpl.revoke_all_for_user(conn, user.id).await?;
+    let passkey_repo = PasskeyRepositoryImpl;
+    for passkey in passkey_repo.list_for_user(conn, user.id).await? {
+        passkey_repo.delete_for_user(conn, user.id, passkey.id).await?;
+    }
+    RevocationList::revoke_user(config, user.id).await;
+
+    info!(user_id = %user.id, "Reset an unverified account before linking it to an identity provider");
+    user_repo.mark_email_verified(conn, user.id).await
+}
+
+/// Creates a user for `email` in the organization that claimed its domain
+async fn provision(conn: &mut PgConnection, email: &str, claims: &IdTokenClaims) -> Result<User> {
+    let domain = email.rsplit_once('@').map(|(_, domain)| domain.to_ascii_lowercase()).unwrap_or_default();
+    let claimed = SsoRepositoryImpl
+        .find_domain(conn, &domain)
+        .await?
+        .ok_or_else(|| forbidden("No organization accepts sign-ins from this email domain", Some(email)))?;
+
+    QuotaService::check_users(conn, claimed.org_id, 1).await?;
+
+    let (first_name, last_name) = names(claims, email);
+    let now = Utc::now();
+    let user = UserRepositoryImpl
+        .create(conn, &User {
+            id: Uuid::new_v4(),
+            first_name,
+            last_name,
+            email: email.to_string(),
+            phone_number: String::new(),
+            // Unknown to anyone; a password can be set through a reset
+            password: User::hash_password(&Uuid::new_v4().to_string())?,
+            org_id: claimed.org_id,
+            role: claimed.role,
+            email_verified: true,
+            avatar_updated_at: None,
+            is_active: true,
+            supervisor_id: None,
+            anonymized_at: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        })
+        .await?;
+    info!(user_id = %user.id, org_id = %user.org_id, domain = %domain, "Provisioned user through single sign-on");
+    ActivityFeed::record(conn, user.org_id, None, FeedEvent::user_joined(&user, "sso")).await;
+    Ok(user)
+}
+
+/// First and last name from the claims, the email's local part otherwise
+fn names(claims: &IdTokenClaims, email: &str) -> (String, String) {
+    if let (Some(first), Some(last)) = (&claims.given_name, &claims.family_name) {
+        return (first.clone(), last.clone());
+    }
+    let full = claims
+        .name
+        .clone()
+        .unwrap_or_else(|| email.split('@').next().unwrap_or_default().to_string());
+    match full.trim().split_once(' ') {
+        Some((first, last)) => (first.to_string(), last.trim().to_string()),
+        None => (full.trim().to_string(), String::new()),
+    }
+}
+
+fn forbidden(message: &str, email: Option<&str>) -> ApiError {
+    let context = match email {
+        Some(email) => ErrorContext::new().with_details(serde_json::json!({ "email": email })),
+        None => ErrorContext::new(),
+    };
+    ApiError::new(ErrorCode::Forbidden, message, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_domain() {
+        assert_eq!(normalize_domain(" PineCorp.com. "), Some("pinecorp.com".to_string()));
+        assert_eq!(normalize_domain("forest-ops.co.uk"), Some("forest-ops.co.uk".to_string()));
+        assert_eq!(normalize_domain("localhost"), None);
+        assert_eq!(normalize_domain("anna@pinecorp.com"), None);
+        assert_eq!(normalize_domain("-pine.com"), None);
+        assert_eq!(normalize_domain("pine..com"), None);
+    }
+
+    #[test]
+    fn test_names() {
+        let claims = IdTokenClaims {
+            given_name: Some("Anna".into()),
+            family_name: Some("Berg".into()),
+            name: Some("Berg, Anna".into()),
+            ..Default::default()
+        };
+        assert_eq!(names(&claims, "anna@pinecorp.com"), ("Anna".into(), "Berg".into()));
+
+        let claims = IdTokenClaims { name: Some("Anna Maria Berg".into()), ..Default::default() };
+        assert_eq!(names(&claims, "anna@pinecorp.com"), ("Anna".into(), "Maria Berg".into()));
+
+        assert_eq!(names(&IdTokenClaims::default(), "aberg@pinecorp.com"), ("aberg".into(), String::new()));
+    }
+}
@@ -0,0 +1,195 @@
+//! Verified access tokens
+//!
+//! Clients send the same access token with every request until it expires,
+//! and checking it means verifying its signature and asking the revocation
+//! list in Redis whether it was logged out. The outcome is remembered by the
+//! token's `jti` for `auth.token_cache_ttl_secs`, so repeated requests skip
+//! both. Logging out, and changing a user's role or password, drop the
+//! remembered tokens: on this instance at once, on the others through a
+//! cluster event.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use moka::sync::Cache;
+use ring::digest::{digest, SHA256};
+use serde::Deserialize;
+use subtle::ConstantTimeEq;
+use tracing::warn;
+
+use super::Claims;
+use crate::utils::AuthConfig;
+
+/// Most tokens remembered at once; the least recently used go first
+const MAX_TOKENS: u64 = 100_000;
+
+/// A token that passed verification, with its claims
+struct Verified {
+    fingerprint: Vec<u8>,
+    claims: Claims,
+}
+
+/// Access tokens verified recently, shared by clones of the configuration
+#[derive(Clone, Default)]
+pub struct TokenCache {
+    /// `None` when caching is disabled
+    tokens: Option<Cache<String, Arc<Verified>>>,
+}
+
+fn fingerprint(token: &str) -> Vec<u8> {
+    digest(&SHA256, token.as_bytes()).as_ref().to_vec()
+}
+
+/// The `jti` of `token`, read without checking its signature
+fn unverified_jti(token: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Payload {
+        jti: Option<String>,
+    }
+
+    let payload = token.split('.').nth(1)?;
+    let payload = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    serde_json::from_slice::<Payload>(&payload).ok()?.jti
+}
+
+impl TokenCache {
+    /// Remembers tokens for `ttl`, or not at all when it is zero
+    pub fn new(ttl: Duration) -> Self {
+        let tokens = (!ttl.is_zero()).then(|| {
+            Cache::builder()
+                .max_capacity(MAX_TOKENS)
+                .time_to_live(ttl)
+                .support_invalidation_closures()
+                .build()
+        });
+        Self { tokens }
+    }
+
+    pub fn from_config(auth: &AuthConfig) -> Self {
+        Self::new(Duration::from_secs(auth.token_cache_ttl_secs))
+    }
+
+    /// The claims of `token` if it was verified within the TTL and hasn't
+    /// expired since
+    pub fn get(&self, token: &str) -> Option<Claims> {
+        let tokens = self.tokens.as_ref()?;
+        let jti = unverified_jti(token)?;
+        let verified = tokens.get(&jti)?;
+        // The jti was read from an unverified token, so only the very token
+        // that was verified may match
+        if !bool::from(verified.fingerprint.ct_eq(&fingerprint(token))) {
+            return None;
+        }
+        if verified.claims.exp <= Utc::now().timestamp() {
+            tokens.invalidate(&jti);
+            return None;
+        }
+        Some(verified.claims.clone())
+    }
+
+    /// Remembers that `token` was verified; tokens without a `jti` aren't
+    /// remembered, since they can't be dropped on logout
+    pub fn insert(&self, token: &str, claims: &Claims) {
+        let (Some(tokens), Some(jti)) = (self.tokens.as_ref(), claims.jti.as_ref()) else {
+            return;
+        };
+        tokens.insert(jti.clone(), Arc::new(Verified {
+            fingerprint: fingerprint(token),
+            claims: claims.clone(),
+        }));
+    }
+
+    /// Forgets the token `jti`
+    pub fn forget(&self, jti: &str) {
+        if let Some(tokens) = &self.tokens {
+            tokens.invalidate(jti);
+        }
+    }
+
+    /// Forgets every token issued to the user `user_id`
+    pub fn forget_user(&self, user_id: &str) {
+        let Some(tokens) = &self.tokens else {
+            return;
+        };
+        let user_id = user_id.to_string();
+        if let Err(e) = tokens.invalidate_entries_if(move |_, verified| verified.claims.sub == user_id) {
+            // Only possible without invalidation closures, which are always
+            // enabled; drop everything rather than keep the user's tokens
+            warn!(error = %e, "Failed to forget a user's tokens, forgetting all");
+            tokens.invalidate_all();
+        }
+    }
+}
+
+impl fmt::Debug for TokenCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenCache")
+            .field("enabled", &self.tokens.is_some())
+            .field("entries", &self.tokens.as_ref().map(Cache::entry_count))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(jti: &str, signature: &str) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::json!({ "jti": jti }).to_string());
+        format!("header.{}.{}", payload, signature)
+    }
+
+    fn claims(sub: &str, jti: &str, exp: i64) -> Claims {
+        Claims {
+            sub: sub.to_string(),
+            org_id: "org".to_string(),
+            role: "OPERATOR".to_string(),
+            iat: 0,
+            exp,
+            jti: Some(jti.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_remembers_only_the_verified_token() {
+        let cache = TokenCache::new(Duration::from_secs(30));
+        let exp = Utc::now().timestamp() + 60;
+        cache.insert(&token("a", "signed"), &claims("alice", "a", exp));
+
+        assert_eq!(cache.get(&token("a", "signed")).map(|claims| claims.sub), Some("alice".to_string()));
+        // Same jti, different signature
+        assert!(cache.get(&token("a", "forged")).is_none());
+        assert!(cache.get(&token("b", "signed")).is_none());
+        assert!(cache.get("not a token").is_none());
+
+        // Expired since it was verified
+        cache.insert(&token("c", "signed"), &claims("alice", "c", Utc::now().timestamp() - 1));
+        assert!(cache.get(&token("c", "signed")).is_none());
+    }
+
+    #[test]
+    fn test_forgets_tokens() {
+        let cache = TokenCache::new(Duration::from_secs(30));
+        let exp = Utc::now().timestamp() + 60;
+        for (sub, jti) in [("alice", "a1"), ("alice", "a2"), ("bob", "b1")] {
+            cache.insert(&token(jti, "signed"), &claims(sub, jti, exp));
+        }
+
+        cache.forget("a1");
+        assert!(cache.get(&token("a1", "signed")).is_none());
+        assert!(cache.get(&token("a2", "signed")).is_some());
+
+        cache.forget_user("alice");
+        assert!(cache.get(&token("a2", "signed")).is_none());
+        assert!(cache.get(&token("b1", "signed")).is_some());
+    }
+
+    #[test]
+    fn test_zero_ttl_disables_the_cache() {
+        let cache = TokenCache::new(Duration::ZERO);
+        let exp = Utc::now().timestamp() + 60;
+        cache.insert(&token("a", "signed"), &claims("alice", "a", exp));
+        assert!(cache.get(&token("a", "signed")).is_none());
+    }
+}
@@ -3,16 +3,15 @@ use crate::{
     error::{Result, ApiError, ErrorCode, ErrorContext},
     utils::Config,
 };
-use chrono::{Duration, Utc};
+use chrono::Utc;
 use jsonwebtoken::{
-    decode, encode, DecodingKey, EncodingKey, Header, Validation,
+    decode, decode_header, encode, DecodingKey, EncodingKey, Header, Validation,
     errors::ErrorKind as JwtErrorKind,
 };
 use tracing::error;
+use uuid::Uuid;
 use super::claims::Claims;
 
-const JWT_EXPIRATION: i64 = 60 * 60; // 1 hour in seconds
-
 /// Token management functionality
 pub struct TokenManager;
 
@@ -20,7 +19,7 @@ impl TokenManager {
     /// Generate a new JWT token for a user
     pub fn generate_token(user: &User, config: &Config) -> Result<String> {
         let now = Utc::now();
-        let exp = now + Duration::seconds(JWT_EXPIRATION);
+        let exp = now + config.auth.session.access_token_lifetime();
 
         let claims = Claims {
             sub: user.id.to_string(),
@@ -28,14 +27,19 @@ impl TokenManager {
             role: format!("{:?}", user.role).to_uppercase(),
             iat: now.timestamp(),
             exp: exp.timestamp(),
+            jti: Some(Uuid::new_v4().to_string()),
         };
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
-        )
-        .map_err(|e| {
+        // Signed with jwt_secret and no kid until a signing key is configured
+        let encoded = match config.signing_keys().signing() {
+            Some(key) => encode(&key.header(), &claims, key.encoding_key()),
+            None => encode(
+                &Header::default(),
+                &claims,
+                &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+            ),
+        };
+        encoded.map_err(|e| {
             error!("Failed to generate JWT token: {}", e);
             ApiError::new(
                 ErrorCode::InternalError,
@@ -46,14 +50,26 @@ impl TokenManager {
     }
 
     /// Validate a JWT token and return the claims
+    ///
+    /// Tokens are verified with the key their `kid` names, or `jwt_secret`
+    /// when they carry none. A `kid` that is no longer configured is
+    /// rejected.
     pub fn validate_token(token: &str, config: &Config) -> Result<Claims> {
-        let validation = Validation::default();
+        let invalid = || ApiError::new(ErrorCode::Unauthorized, "Invalid token", ErrorContext::default());
+        let header = decode_header(token).map_err(|_| invalid())?;
+        let decoded = match header.kid {
+            Some(kid) => {
+                let key = config.signing_keys().get(&kid).ok_or_else(invalid)?;
+                decode::<Claims>(token, key.decoding_key(), &key.validation())
+            }
+            None => decode::<Claims>(
+                token,
+                &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
+                &Validation::default(),
+            ),
+        };
 
-        match decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
-            &validation,
-        ) {
+        match decoded {
             Ok(token_data) => Ok(token_data.claims),
             Err(e) => {
                 match e.kind() {
@@ -13,8 +13,22 @@ lazy_static! {
     static ref EMAIL_REGEX: Regex = Regex::new(
         r"^[a-zA-Z0-9.!#$%&'*+/=?^_`{|}~-]+@[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?(?:\.[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?)*$"
     ).unwrap();
+    /// An optional `+`, then digits with spaces, dashes, dots or parentheses
+    static ref PHONE_REGEX: Regex = Regex::new(r"^\+?[0-9][0-9 ().-]*[0-9]$").unwrap();
 }
 
+/// Longest first or last name stored
+const MAX_NAME_LENGTH: usize = 255;
+
+/// Fewest and most digits of a phone number, as E.164 allows
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 7..=15;
+
+/// Longest device ID or device name stored
+const MAX_DEVICE_FIELD_LENGTH: usize = 255;
+
+/// Longest name a passkey can be given
+const MAX_PASSKEY_NAME_LENGTH: usize = 255;
+
 pub struct AuthValidator;
 
 impl AuthValidator {
@@ -49,60 +63,149 @@ impl AuthValidator {
         Ok(user)
     }
 
-    /// Validates registration input
-    pub async fn validate_registration<'a, R: UserRepository + Send + Sync>(
-        conn: &'a mut PgConnection,
-        repo: &'a R,
-        params: &'a CreateUserParams<'a>,
-    ) -> Result<()> {
-        // Validate organization exists
-        let org_repo = OrganizationRepositoryImpl;
-        if org_repo.find_by_id(conn, params.org_id).await.is_err() {
+    /// Validates the strength of a new password
+    pub fn validate_password(password: &str) -> Result<()> {
+        // Validate password length (minimum 8 characters)
+        if password.len() < 8 {
             return Err(ApiError::validation_with_context(
-                "Organization not found",
+                "Password too short",
                 ErrorContext::new().with_details(serde_json::json!({
-                    "field": "org_id",
-                    "code": "NOT_FOUND",
-                    "value": params.org_id
+                    "field": "password",
+                    "code": "TOO_SHORT",
+                    "min_length": 8
                 }))
             ));
         }
 
-        // Validate email format
-        if !EMAIL_REGEX.is_match(params.email) {
+        // Validate password contains numbers
+        if !password.chars().any(|c| c.is_numeric()) {
+            return Err(ApiError::validation_with_context(
+                "Password must contain at least one number",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "password",
+                    "code": "MISSING_NUMBER"
+                }))
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Validates the format of an email address
+    pub fn validate_email(email: &str) -> Result<()> {
+        if !EMAIL_REGEX.is_match(email) {
             return Err(ApiError::validation_with_context(
                 "Invalid email format",
                 ErrorContext::new().with_details(serde_json::json!({
                     "field": "email",
                     "code": "INVALID_FORMAT",
-                    "value": params.email
+                    "value": email
                 }))
             ));
         }
 
-        // Validate password length (minimum 8 characters)
-        if params.password.len() < 8 {
+        Ok(())
+    }
+
+    /// Validates the ID a client generated for itself and the name it
+    /// gives the device
+    pub fn validate_device(device_id: &str, name: Option<&str>) -> Result<()> {
+        if device_id.trim().is_empty() || device_id.len() > MAX_DEVICE_FIELD_LENGTH {
             return Err(ApiError::validation_with_context(
-                "Password too short",
+                "Invalid device ID",
                 ErrorContext::new().with_details(serde_json::json!({
-                    "field": "password",
-                    "code": "TOO_SHORT",
-                    "min_length": 8
+                    "field": "device_id",
+                    "code": "INVALID_LENGTH",
+                    "max_length": MAX_DEVICE_FIELD_LENGTH
                 }))
             ));
         }
 
-        // Validate password contains numbers
-        if !params.password.chars().any(|c| c.is_numeric()) {
+        if name.is_some_and(|name| name.len() > MAX_DEVICE_FIELD_LENGTH) {
             return Err(ApiError::validation_with_context(
-                "Password must contain at least one number",
+                "Device name too long",
                 ErrorContext::new().with_details(serde_json::json!({
-                    "field": "password",
-                    "code": "MISSING_NUMBER"
+                    "field": "device_name",
+                    "code": "TOO_LONG",
+                    "max_length": MAX_DEVICE_FIELD_LENGTH
+                }))
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Validates a user's first or last name, given as `field`
+    pub fn validate_name(field: &str, name: &str) -> Result<()> {
+        if name.trim().is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(ApiError::validation_with_context(
+                "Invalid name",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": field,
+                    "code": "INVALID_LENGTH",
+                    "max_length": MAX_NAME_LENGTH
+                }))
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Validates a phone number, such as `+1 (250) 555-0100`
+    pub fn validate_phone_number(phone_number: &str) -> Result<()> {
+        let digits = phone_number.chars().filter(char::is_ascii_digit).count();
+        if !PHONE_REGEX.is_match(phone_number) || !PHONE_DIGITS.contains(&digits) {
+            return Err(ApiError::validation_with_context(
+                "Invalid phone number",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "phone_number",
+                    "code": "INVALID_FORMAT",
                 }))
             ));
         }
 
+        Ok(())
+    }
+
+    /// Validates the name a user gives a passkey
+    pub fn validate_passkey_name(name: &str) -> Result<()> {
+        if name.trim().is_empty() || name.len() > MAX_PASSKEY_NAME_LENGTH {
+            return Err(ApiError::validation_with_context(
+                "Invalid passkey name",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "name",
+                    "code": "INVALID_LENGTH",
+                    "max_length": MAX_PASSKEY_NAME_LENGTH
+                }))
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Validates registration input
+    pub async fn validate_registration<'a, R: UserRepository + Send + Sync>(
+        conn: &'a mut PgConnection,
+        repo: &'a R,
+        params: &'a CreateUserParams<'a>,
+    ) -> Result<()> {
+        // Validate organization exists
+        let org_repo = OrganizationRepositoryImpl;
+        if org_repo.find_by_id(conn, params.org_id).await.is_err() {
+            return Err(ApiError::validation_with_context(
+                "Organization not found",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "org_id",
+                    "code": "NOT_FOUND",
+                    "value": params.org_id
+                }))
+            ));
+        }
+
+        Self::validate_email(params.email)?;
+
+        Self::validate_password(params.password)?;
+
         // Check if user already exists
         if repo.find_by_email(conn, params.email).await?.is_some() {
             return Err(ApiError::validation_with_context(
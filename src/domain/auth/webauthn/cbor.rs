@@ -0,0 +1,151 @@
+//! Just enough CBOR (RFC 8949) to read what authenticators send
+//!
+//! Authenticators encode attestation objects and public keys in the CTAP2
+//! canonical form, so indefinite lengths and floats are refused rather
+//! than decoded.
+
+/// Deepest nesting decoded; authenticator data never comes close
+const MAX_DEPTH: usize = 16;
+
+/// A decoded data item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Integer(i128),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    Bool(bool),
+    Null,
+}
+
+impl Value {
+    /// The value of the map entry with the text key `key`
+    pub fn field(&self, key: &str) -> Option<&Value> {
+        self.entry(|k| matches!(k, Value::Text(text) if text == key))
+    }
+
+    /// The value of the map entry with the integer key `key`
+    pub fn label(&self, key: i128) -> Option<&Value> {
+        self.entry(|k| *k == Value::Integer(key))
+    }
+
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Value::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Value::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    fn entry(&self, matches: impl Fn(&Value) -> bool) -> Option<&Value> {
+        match self {
+            Value::Map(entries) => entries.iter().find(|(key, _)| matches(key)).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+/// The data item at the start of `bytes`, and how many bytes it took
+pub fn decode(bytes: &[u8]) -> Result<(Value, usize), String> {
+    let mut decoder = Decoder { bytes, position: 0 };
+    let value = decoder.item(0)?;
+    Ok((value, decoder.position))
+}
+
+/// The data item `bytes` holds, refusing trailing bytes
+pub fn decode_all(bytes: &[u8]) -> Result<Value, String> {
+    match decode(bytes)? {
+        (value, used) if used == bytes.len() => Ok(value),
+        _ => Err("trailing bytes after the CBOR item".to_string()),
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Decoder<'_> {
+    fn item(&mut self, depth: usize) -> Result<Value, String> {
+        if depth > MAX_DEPTH {
+            return Err("CBOR nested too deeply".to_string());
+        }
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        if major == 7 {
+            return match info {
+                20 => Ok(Value::Bool(false)),
+                21 => Ok(Value::Bool(true)),
+                22 => Ok(Value::Null),
+                _ => Err(format!("unsupported CBOR simple value {}", info)),
+            };
+        }
+        let argument = self.argument(info)?;
+        match major {
+            0 => Ok(Value::Integer(argument as i128)),
+            1 => Ok(Value::Integer(-1 - argument as i128)),
+            2 => Ok(Value::Bytes(self.take(self.length(argument)?)?.to_vec())),
+            3 => {
+                let text = self.take(self.length(argument)?)?;
+                String::from_utf8(text.to_vec())
+                    .map(Value::Text)
+                    .map_err(|_| "CBOR text is not UTF-8".to_string())
+            }
+            4 => (0..self.length(argument)?)
+                .map(|_| self.item(depth + 1))
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+            5 => (0..self.length(argument)?)
+                .map(|_| Ok((self.item(depth + 1)?, self.item(depth + 1)?)))
+                .collect::<Result<_, String>>()
+                .map(Value::Map),
+            // A tag only qualifies the item that follows it
+            6 => self.item(depth + 1),
+            _ => unreachable!("the major type has three bits"),
+        }
+    }
+
+    /// The argument of an item, from its additional information
+    fn argument(&mut self, info: u8) -> Result<u64, String> {
+        let size = match info {
+            0..=23 => return Ok(info as u64),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => return Err("indefinite-length CBOR is not supported".to_string()),
+        };
+        Ok(self.take(size)?.iter().fold(0, |value, byte| (value << 8) | *byte as u64))
+    }
+
+    /// A count of bytes or items, refusing counts longer than the input
+    /// before anything is allocated for them
+    fn length(&self, argument: u64) -> Result<usize, String> {
+        usize::try_from(argument)
+            .ok()
+            .filter(|length| *length <= self.bytes.len() - self.position)
+            .ok_or_else(|| "CBOR length exceeds the input".to_string())
+    }
+
+    fn take(&mut self, count: usize) -> Result<&[u8], String> {
+        let end = self.position.checked_add(count).filter(|end| *end <= self.bytes.len());
+        let end = end.ok_or_else(|| "CBOR ends early".to_string())?;
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+}
@@ -0,0 +1,80 @@
+//! Credential public keys, as COSE_Key (RFC 9053) maps
+//!
+//! Passkeys use ES256 almost everywhere; Ed25519 and RS256 cover the
+//! authenticators that don't.
+
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+
+use super::cbor::{self, Value};
+
+/// COSE algorithms passkeys are accepted with, in order of preference
+pub const ALGORITHMS: [i64; 3] = [ES256, EDDSA, RS256];
+
+const ES256: i64 = -7;
+const EDDSA: i64 = -8;
+const RS256: i64 = -257;
+
+// Key type, algorithm and curve labels and values
+const KTY: i128 = 1;
+const ALG: i128 = 3;
+const CRV: i128 = -1;
+const KTY_OKP: i128 = 1;
+const KTY_EC2: i128 = 2;
+const KTY_RSA: i128 = 3;
+const CRV_P256: i128 = 1;
+const CRV_ED25519: i128 = 6;
+
+/// A credential public key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicKey {
+    /// ECDSA on P-256 with SHA-256, as an uncompressed point
+    Es256(Vec<u8>),
+    Ed25519(Vec<u8>),
+    /// RSASSA-PKCS1-v1_5 with SHA-256, as modulus and exponent
+    Rs256 { n: Vec<u8>, e: Vec<u8> },
+}
+
+impl PublicKey {
+    /// The key `bytes` encode, describing what is wrong with it otherwise
+    pub fn from_cose(bytes: &[u8]) -> Result<Self, String> {
+        Self::from_value(&cbor::decode_all(bytes)?)
+    }
+
+    pub fn from_value(key: &Value) -> Result<Self, String> {
+        let int = |label| key.label(label).and_then(Value::as_integer);
+        let bytes = |label| key.label(label).and_then(Value::as_bytes);
+        let algorithm = int(ALG).ok_or("the public key names no algorithm")?;
+
+        match (int(KTY), algorithm as i64) {
+            (Some(KTY_EC2), ES256) if int(CRV) == Some(CRV_P256) => {
+                let (x, y) = bytes(-2).zip(bytes(-3)).ok_or("the P-256 key lacks coordinates")?;
+                if x.len() != 32 || y.len() != 32 {
+                    return Err("the P-256 key coordinates are not 32 bytes".to_string());
+                }
+                Ok(Self::Es256([&[0x04], x, y].concat()))
+            }
+            (Some(KTY_OKP), EDDSA) if int(CRV) == Some(CRV_ED25519) => {
+                let x = bytes(-2).filter(|x| x.len() == 32).ok_or("the Ed25519 key is not 32 bytes")?;
+                Ok(Self::Ed25519(x.to_vec()))
+            }
+            (Some(KTY_RSA), RS256) => {
+                let (n, e) = bytes(-1).zip(bytes(-2)).ok_or("the RSA key lacks its modulus or exponent")?;
+                Ok(Self::Rs256 { n: n.to_vec(), e: e.to_vec() })
+            }
+            _ => Err(format!("COSE algorithm {} is not supported", algorithm)),
+        }
+    }
+
+    /// Whether `signature` is the key's signature of `message`
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            Self::Es256(point) => UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point)
+                .verify(message, signature)
+                .is_ok(),
+            Self::Ed25519(x) => UnparsedPublicKey::new(&signature::ED25519, x).verify(message, signature).is_ok(),
+            Self::Rs256 { n, e } => RsaPublicKeyComponents { n, e }
+                .verify(&signature::RSA_PKCS1_2048_8192_SHA256, message, signature)
+                .is_ok(),
+        }
+    }
+}
@@ -0,0 +1,420 @@
+//! Passkeys (WebAuthn)
+//!
+//! A passkey is a key pair an authenticator, such as a laptop, phone or
+//! security key, keeps for this site. Registering one stores its public key;
+//! logging in signs a challenge with the private key. The web app asks for
+//! options, hands them to `navigator.credentials` and posts back what the
+//! browser returns. Like single sign-on state, the challenge travels in a
+//! short-lived token signed with `jwt_secret` rather than being kept on the
+//! server, and each challenge is accepted once.
+//!
+//! Attestation is not requested: which make of authenticator holds a
+//! passkey doesn't matter, only that it signs with the registered key. User
+//! verification, a PIN or biometric, is required, so a passkey stands in
+//! for a password rather than being a second factor.
+
+mod cbor;
+mod cose;
+
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rand::RngCore;
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::revocation::RevocationList;
+use crate::{
+    db::models::auth::{Passkey, User},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::Config,
+};
+use cose::PublicKey;
+
+/// Seconds the user has to complete a ceremony
+const CHALLENGE_TTL_SECS: i64 = 5 * 60;
+
+/// Audiences of challenge tokens, so they can't pass for access tokens or
+/// for each other
+const REGISTRATION_AUDIENCE: &str = "webauthn-registration";
+const LOGIN_AUDIENCE: &str = "webauthn-login";
+
+// Authenticator data flags
+const USER_PRESENT: u8 = 0x01;
+const USER_VERIFIED: u8 = 0x04;
+const ATTESTED_CREDENTIAL: u8 = 0x40;
+
+/// Base64url as browsers send it, with or without padding
+const BASE64URL: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ChallengeClaims {
+    challenge: String,
+    /// User registering a passkey, `None` for logins
+    sub: Option<Uuid>,
+    aud: String,
+    exp: i64,
+}
+
+/// Options for `navigator.credentials.create()`, binary values base64url
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CreationOptions {
+    pub challenge: String,
+    pub rp: RelyingParty,
+    pub user: UserEntity,
+    pub pub_key_cred_params: Vec<CredentialParameters>,
+    /// Milliseconds the user has to complete the registration
+    pub timeout: i64,
+    pub attestation: String,
+    pub authenticator_selection: AuthenticatorSelection,
+    /// The user's passkeys, so an authenticator isn't registered twice
+    pub exclude_credentials: Vec<CredentialDescriptor>,
+}
+
+/// Options for `navigator.credentials.get()`, binary values base64url
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RequestOptions {
+    pub challenge: String,
+    /// Milliseconds the user has to complete the login
+    pub timeout: i64,
+    pub rp_id: String,
+    pub user_verification: String,
+    /// Empty, so the user picks any of their passkeys for the site
+    pub allow_credentials: Vec<CredentialDescriptor>,
+}
+
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct RelyingParty {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct UserEntity {
+    /// The user's ID, as the base64url of its 16 bytes
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CredentialParameters {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// COSE algorithm
+    pub alg: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct AuthenticatorSelection {
+    pub resident_key: String,
+    pub require_resident_key: bool,
+    pub user_verification: String,
+}
+
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CredentialDescriptor {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+}
+
+/// What `navigator.credentials.create()` returned, binary values base64url
+pub struct Attestation<'a> {
+    pub credential_id: &'a str,
+    pub client_data_json: &'a str,
+    pub attestation_object: &'a str,
+}
+
+/// What `navigator.credentials.get()` returned, binary values base64url
+pub struct Assertion<'a> {
+    pub credential_id: &'a str,
+    pub client_data_json: &'a str,
+    pub authenticator_data: &'a str,
+    pub signature: &'a str,
+    pub user_handle: Option<&'a str>,
+}
+
+/// A credential created by a registration
+#[derive(Debug, Clone)]
+pub struct NewCredential {
+    /// Base64url, as clients name it
+    pub credential_id: String,
+    /// COSE_Key
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+}
+
+#[derive(Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+    #[serde(default, rename = "crossOrigin")]
+    cross_origin: bool,
+}
+
+/// The parts of authenticator data checked
+struct AuthenticatorData<'a> {
+    sign_count: u32,
+    /// Credential ID and COSE_Key, present on registration
+    credential: Option<(&'a [u8], &'a [u8])>,
+}
+
+/// Options to register a passkey for `user`, and the token of their
+/// challenge
+pub fn creation_options(user: &User, registered: &[Passkey], config: &Config) -> Result<(String, CreationOptions)> {
+    let webauthn = &config.auth.webauthn;
+    let (token, challenge) = issue_challenge(Some(user.id), REGISTRATION_AUDIENCE, config)?;
+    let options = CreationOptions {
+        challenge,
+        rp: RelyingParty {
+            id: webauthn.rp_id.clone(),
+            name: webauthn.rp_name.clone(),
+        },
+        user: UserEntity {
+            id: BASE64URL.encode(user.id.as_bytes()),
+            name: user.email.clone(),
+            display_name: format!("{} {}", user.first_name, user.last_name).trim().to_string(),
+        },
+        pub_key_cred_params: cose::ALGORITHMS
+            .iter()
+            .map(|alg| CredentialParameters {
+                kind: "public-key".to_string(),
+                alg: *alg,
+            })
+            .collect(),
+        timeout: CHALLENGE_TTL_SECS * 1000,
+        attestation: "none".to_string(),
+        authenticator_selection: AuthenticatorSelection {
+            resident_key: "required".to_string(),
+            require_resident_key: true,
+            user_verification: "required".to_string(),
+        },
+        exclude_credentials: registered
+            .iter()
+            .map(|passkey| CredentialDescriptor {
+                kind: "public-key".to_string(),
+                id: passkey.credential_id.clone(),
+            })
+            .collect(),
+    };
+    Ok((token, options))
+}
+
+/// Options to log in with a passkey, and the token of their challenge
+pub fn request_options(config: &Config) -> Result<(String, RequestOptions)> {
+    let (token, challenge) = issue_challenge(None, LOGIN_AUDIENCE, config)?;
+    let options = RequestOptions {
+        challenge,
+        timeout: CHALLENGE_TTL_SECS * 1000,
+        rp_id: config.auth.webauthn.rp_id.clone(),
+        user_verification: "required".to_string(),
+        allow_credentials: Vec::new(),
+    };
+    Ok((token, options))
+}
+
+/// The credential `attestation` registers for `user_id`, answering the
+/// challenge of `token`
+pub async fn verify_registration(
+    token: &str,
+    user_id: Uuid,
+    attestation: &Attestation<'_>,
+    config: &Config,
+) -> Result<NewCredential> {
+    let claims = redeem_challenge(token, REGISTRATION_AUDIENCE, config).await?;
+    if claims.sub != Some(user_id) {
+        return Err(ApiError::validation("The passkey registration was started by another user", None));
+    }
+    check_attestation(&claims.challenge, attestation, config).map_err(|reason| {
+        ApiError::validation_with_context(
+            format!("The passkey could not be registered: {}", reason),
+            ErrorContext::new().with_details(json!({
+                "field": "credential",
+                "code": "INVALID",
+            })),
+        )
+    })
+}
+
+/// The signature counter `assertion` leaves `passkey` at, answering the
+/// challenge of `token`
+pub async fn verify_assertion(
+    token: &str,
+    passkey: &Passkey,
+    assertion: &Assertion<'_>,
+    config: &Config,
+) -> Result<u32> {
+    let claims = redeem_challenge(token, LOGIN_AUDIENCE, config).await?;
+    check_assertion(&claims.challenge, passkey, assertion, config)
+        .map_err(|reason| ApiError::unauthorized(format!("Invalid passkey: {}", reason)))
+}
+
+fn check_attestation(challenge: &str, attestation: &Attestation<'_>, config: &Config) -> std::result::Result<NewCredential, String> {
+    let client_data = base64url(attestation.client_data_json, "clientDataJSON")?;
+    check_client_data(&client_data, "webauthn.create", challenge, config)?;
+
+    // Any format is accepted; its statement is not checked, see above
+    let object = cbor::decode_all(&base64url(attestation.attestation_object, "attestationObject")?)?;
+    object.field("fmt").and_then(cbor::Value::as_text).ok_or("the attestation object has no format")?;
+    let auth_data = object
+        .field("authData")
+        .and_then(cbor::Value::as_bytes)
+        .ok_or("the attestation object has no authenticator data")?;
+
+    let data = authenticator_data(auth_data, config)?;
+    let (credential_id, public_key) = data.credential.ok_or("the authenticator returned no credential")?;
+    if base64url(attestation.credential_id, "id")? != credential_id {
+        return Err("the credential ID does not match the authenticator data".to_string());
+    }
+    PublicKey::from_cose(public_key)?;
+
+    Ok(NewCredential {
+        credential_id: BASE64URL.encode(credential_id),
+        public_key: public_key.to_vec(),
+        sign_count: data.sign_count,
+    })
+}
+
+fn check_assertion(
+    challenge: &str,
+    passkey: &Passkey,
+    assertion: &Assertion<'_>,
+    config: &Config,
+) -> std::result::Result<u32, String> {
+    let client_data = base64url(assertion.client_data_json, "clientDataJSON")?;
+    check_client_data(&client_data, "webauthn.get", challenge, config)?;
+
+    if let Some(user_handle) = assertion.user_handle.filter(|handle| !handle.is_empty()) {
+        if base64url(user_handle, "userHandle")? != passkey.user_id.as_bytes() {
+            return Err("the passkey belongs to another user".to_string());
+        }
+    }
+
+    let auth_data = base64url(assertion.authenticator_data, "authenticatorData")?;
+    let data = authenticator_data(&auth_data, config)?;
+
+    let signed = [auth_data.as_slice(), digest(&SHA256, &client_data).as_ref()].concat();
+    let signature = base64url(assertion.signature, "signature")?;
+    if !PublicKey::from_cose(&passkey.public_key)?.verify(&signed, &signature) {
+        return Err("the signature does not verify".to_string());
+    }
+
+    // Authenticators that keep a counter increase it on every use; one that
+    // goes backwards means the key was copied
+    if (data.sign_count != 0 || passkey.sign_count != 0) && i64::from(data.sign_count) <= passkey.sign_count {
+        return Err("the signature counter went backwards, the passkey may have been cloned".to_string());
+    }
+    Ok(data.sign_count)
+}
+
+/// Checks the browser's record of the ceremony: its kind, the challenge it
+/// answered and the page it ran on
+fn check_client_data(bytes: &[u8], kind: &str, challenge: &str, config: &Config) -> std::result::Result<(), String> {
+    let client_data: ClientData =
+        serde_json::from_slice(bytes).map_err(|_| "clientDataJSON is not valid client data".to_string())?;
+    if client_data.kind != kind {
+        return Err(format!("expected a {} ceremony, got {}", kind, client_data.kind));
+    }
+    if client_data.challenge.trim_end_matches('=') != challenge {
+        return Err("the challenge does not match".to_string());
+    }
+    if !config.auth.webauthn.origins.iter().any(|origin| origin.trim_end_matches('/') == client_data.origin) {
+        return Err(format!("origin {} is not allowed", client_data.origin));
+    }
+    if client_data.cross_origin {
+        return Err("cross-origin ceremonies are not allowed".to_string());
+    }
+    Ok(())
+}
+
+/// Parses authenticator data, checking it is for this relying party and
+/// that the user was present and verified
+fn authenticator_data<'a>(bytes: &'a [u8], config: &Config) -> std::result::Result<AuthenticatorData<'a>, String> {
+    if bytes.len() < 37 {
+        return Err("the authenticator data is truncated".to_string());
+    }
+    if bytes[..32] != *digest(&SHA256, config.auth.webauthn.rp_id.as_bytes()).as_ref() {
+        return Err("the passkey is for another site".to_string());
+    }
+    let flags = bytes[32];
+    if flags & USER_PRESENT == 0 || flags & USER_VERIFIED == 0 {
+        return Err("the authenticator did not verify the user".to_string());
+    }
+    let sign_count = u32::from_be_bytes([bytes[33], bytes[34], bytes[35], bytes[36]]);
+
+    let credential = if flags & ATTESTED_CREDENTIAL != 0 {
+        // AAGUID (16 bytes), then the length of the credential ID (2 bytes)
+        let rest = bytes.get(37 + 16..).filter(|rest| rest.len() >= 2).ok_or("the credential data is truncated")?;
+        let id_len = u16::from_be_bytes([rest[0], rest[1]]) as usize;
+        let id = rest.get(2..2 + id_len).ok_or("the credential ID is truncated")?;
+        let key = &rest[2 + id_len..];
+        let (_, key_len) = cbor::decode(key)?;
+        Some((id, &key[..key_len]))
+    } else {
+        None
+    };
+
+    Ok(AuthenticatorData { sign_count, credential })
+}
+
+/// A challenge for a ceremony, and a token carrying it signed
+fn issue_challenge(user_id: Option<Uuid>, audience: &str, config: &Config) -> Result<(String, String)> {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let challenge = BASE64URL.encode(bytes);
+    let claims = ChallengeClaims {
+        challenge: challenge.clone(),
+        sub: user_id,
+        aud: audience.to_string(),
+        exp: (Utc::now() + Duration::seconds(CHALLENGE_TTL_SECS)).timestamp(),
+    };
+    let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(config.jwt_secret.as_bytes()))
+        .map_err(|e| ApiError::new(ErrorCode::InternalError, format!("Failed to sign passkey challenge: {}", e), ErrorContext::new()))?;
+    Ok((token, challenge))
+}
+
+/// The claims of a challenge token issued for `audience`, which can't be
+/// used again
+async fn redeem_challenge(token: &str, audience: &str, config: &Config) -> Result<ChallengeClaims> {
+    let mut validation = Validation::default();
+    validation.set_audience(&[audience]);
+    let claims = decode::<ChallengeClaims>(token, &DecodingKey::from_secret(config.jwt_secret.as_bytes()), &validation)
+        .map_err(|_| ApiError::validation("The passkey request expired or was tampered with, please try again", None))?
+        .claims;
+    if RevocationList::is_revoked(config, &claims.challenge).await {
+        return Err(ApiError::validation("The passkey request was already used, please try again", None));
+    }
+    RevocationList::revoke(config, &claims.challenge, claims.exp).await;
+    Ok(claims)
+}
+
+fn base64url(value: &str, name: &str) -> std::result::Result<Vec<u8>, String> {
+    BASE64URL.decode(value).map_err(|_| format!("{} is not base64url", name))
+}
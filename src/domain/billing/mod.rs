@@ -0,0 +1,17 @@
+//! Subscription billing
+//!
+//! Organizations pay for a plan from the catalog through Stripe: an admin
+//! starts a Checkout session for a paid plan, and Stripe's webhook events
+//! keep the organization's subscription in step with what the customer
+//! pays for. A plan sets the organization's quotas when it starts or ends,
+//! and premium features stay available while the subscription is active.
+//! Billing is off, with every feature available, until a Stripe key is
+//! configured.
+
+mod plan;
+mod service;
+mod webhook;
+
+pub use plan::{Plan, FREE_PLAN};
+pub use service::{BillingService, CheckoutSession, SubscriptionStatus};
+pub use webhook::{verify_signature, SIGNATURE_TOLERANCE_SECS};
@@ -0,0 +1,73 @@
+use crate::domain::organization::Quotas;
+
+/// Id of the plan organizations without a paid subscription are on
+pub const FREE_PLAN: &str = "free";
+
+/// A plan of the catalog
+///
+/// The prices of paid plans live in Stripe; `billing.prices` maps each
+/// paid plan to its Stripe price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Plan {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Quotas the organization gets when the plan starts
+    pub quotas: Quotas,
+    /// Whether the plan includes premium features (ERP exports)
+    pub premium: bool,
+}
+
+const PLANS: &[Plan] = &[
+    Plan {
+        id: FREE_PLAN,
+        name: "Free",
+        quotas: Quotas {
+            max_users: Some(5),
+            max_active_blocks: Some(10),
+            telemetry_retention_days: Some(30),
+        },
+        premium: false,
+    },
+    Plan {
+        id: "team",
+        name: "Team",
+        quotas: Quotas {
+            max_users: Some(50),
+            max_active_blocks: Some(200),
+            telemetry_retention_days: Some(365),
+        },
+        premium: true,
+    },
+    Plan {
+        id: "enterprise",
+        name: "Enterprise",
+        quotas: Quotas {
+            max_users: None,
+            max_active_blocks: None,
+            telemetry_retention_days: Some(3650),
+        },
+        premium: true,
+    },
+];
+
+impl Plan {
+    /// The catalog, cheapest plan first
+    pub fn all() -> &'static [Plan] {
+        PLANS
+    }
+
+    /// The plan with id `id`
+    pub fn find(id: &str) -> Option<&'static Plan> {
+        PLANS.iter().find(|plan| plan.id == id)
+    }
+
+    /// The plan organizations fall back to
+    pub fn free() -> &'static Plan {
+        &PLANS[0]
+    }
+
+    /// Whether the plan is paid for through Stripe
+    pub fn is_paid(&self) -> bool {
+        self.id != FREE_PLAN
+    }
+}
@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+
+use chrono::{TimeZone, Utc};
+use diesel::PgConnection;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{auth::User, Subscription},
+        repositories::{organization::OrganizationRepository, OrganizationRepositoryImpl, Repository, SubscriptionRepository, SubscriptionRepositoryImpl},
+    },
+    domain::{
+        billing::{plan::Plan, webhook::verify_signature},
+        organization::{OrganizationService, QuotaService},
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::stripe::{self, CheckoutRequest},
+    utils::Config,
+};
+
+pub use crate::infrastructure::stripe::CheckoutSession;
+
+/// Where an organization stands with its plan
+#[derive(Debug, Clone)]
+pub struct SubscriptionStatus {
+    /// The plan the organization gets, free unless its subscription is active
+    pub plan: &'static Plan,
+    /// The stored subscription, `None` when the organization never subscribed
+    pub subscription: Option<Subscription>,
+}
+
+/// A webhook event, with the object it is about left for its kind to parse
+#[derive(Debug, Deserialize)]
+struct Event {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    data: EventData,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventData {
+    object: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckoutSessionObject {
+    client_reference_id: Option<String>,
+    customer: Option<String>,
+    subscription: Option<String>,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionObject {
+    id: String,
+    customer: Option<String>,
+    status: String,
+    current_period_end: Option<i64>,
+    #[serde(default)]
+    cancel_at_period_end: bool,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+    #[serde(default)]
+    items: SubscriptionItems,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SubscriptionItems {
+    data: Vec<SubscriptionItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionItem {
+    price: Price,
+    /// Where newer API versions put the period
+    current_period_end: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Price {
+    id: String,
+}
+
+/// The plan a subscription gives its organization
+fn effective_plan(subscription: Option<&Subscription>) -> &'static Plan {
+    subscription
+        .filter(|subscription| subscription.is_active())
+        .and_then(|subscription| Plan::find(&subscription.plan))
+        .unwrap_or_else(Plan::free)
+}
+
+fn billing_disabled() -> ApiError {
+    ApiError::new(
+        ErrorCode::UnprocessableEntity,
+        "Billing is not enabled",
+        ErrorContext::new().with_details(json!({ "code": "BILLING_DISABLED" })),
+    )
+}
+
+fn invalid_plan(message: impl Into<String>, code: &str) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": "plan",
+            "code": code,
+        })),
+    )
+}
+
+/// Sells plans through Stripe and keeps subscriptions in step with it
+pub struct BillingService;
+
+impl BillingService {
+    /// The plan and subscription of `org_id` for `viewer`, who must belong
+    /// to it or to an organization above it
+    pub async fn status(conn: &mut PgConnection, viewer: &User, org_id: Uuid) -> Result<SubscriptionStatus> {
+        let organizations = OrganizationService::new(OrganizationRepositoryImpl);
+        let organization = organizations.repository().find_by_id(conn, org_id).await?;
+        if !organizations.governs(conn, viewer.org_id, organization.id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Subscriptions of other organizations can't be accessed",
+                ErrorContext::new(),
+            ));
+        }
+        let subscription = SubscriptionRepositoryImpl.find(conn, organization.id).await?;
+        Ok(SubscriptionStatus {
+            plan: effective_plan(subscription.as_ref()),
+            subscription,
+        })
+    }
+
+    /// Starts a Checkout session subscribing `admin`'s organization to
+    /// `plan_id`
+    pub async fn checkout(conn: &mut PgConnection, config: &Config, admin: &User, plan_id: &str) -> Result<CheckoutSession> {
+        let billing = &config.billing;
+        if !billing.enabled() {
+            return Err(billing_disabled());
+        }
+        let plan = Plan::find(plan_id).ok_or_else(|| invalid_plan(format!("Unknown plan {}", plan_id), "UNKNOWN_PLAN"))?;
+        if !plan.is_paid() {
+            return Err(invalid_plan("The free plan needs no subscription", "NOT_PAID"));
+        }
+        let price = billing
+            .prices
+            .get(plan.id)
+            .ok_or_else(|| ApiError::configuration_error(format!("billing.prices.{} is not set", plan.id)))?;
+
+        let subscription = SubscriptionRepositoryImpl.find(conn, admin.org_id).await?;
+        if subscription.as_ref().is_some_and(|subscription| subscription.is_active()) {
+            return Err(ApiError::new(
+                ErrorCode::Conflict,
+                "The organization already has an active subscription",
+                ErrorContext::new().with_details(json!({ "code": "ALREADY_SUBSCRIBED" })),
+            ));
+        }
+
+        let app_url = config.email.app_url.trim_end_matches('/');
+        let success_url = format!("{}/billing?checkout=success", app_url);
+        let cancel_url = format!("{}/billing?checkout=canceled", app_url);
+        stripe::create_checkout_session(billing, &CheckoutRequest {
+            org_id: admin.org_id,
+            plan: plan.id,
+            price,
+            customer: subscription.as_ref().and_then(|subscription| subscription.stripe_customer_id.as_deref()),
+            customer_email: Some(&admin.email),
+            success_url: &success_url,
+            cancel_url: &cancel_url,
+        })
+        .await
+    }
+
+    /// Whether `org_id` may use premium features
+    ///
+    /// Always true while billing is disabled. Otherwise the organization, or
+    /// one above it, needs an active subscription to a premium plan.
+    pub async fn has_premium(conn: &mut PgConnection, config: &Config, org_id: Uuid) -> Result<bool> {
+        if !config.billing.enabled() {
+            return Ok(true);
+        }
+        let mut organizations = vec![org_id];
+        organizations.extend(OrganizationRepositoryImpl.ancestor_ids(conn, org_id).await?);
+        for organization in organizations {
+            let subscription = SubscriptionRepositoryImpl.find(conn, organization).await?;
+            if effective_plan(subscription.as_ref()).premium {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Applies a webhook delivery after checking its `Stripe-Signature`
+    ///
+    /// Events are applied once each; Stripe retries deliveries that fail, so
+    /// events this service can't act on are acknowledged and skipped.
+    pub async fn handle_webhook(conn: &mut PgConnection, config: &Config, signature: Option<&str>, payload: &[u8]) -> Result<()> {
+        let billing = &config.billing;
+        let Some(secret) = billing.stripe_webhook_secret.as_deref().filter(|_| billing.enabled()) else {
+            return Err(billing_disabled());
+        };
+        let signature = signature.ok_or_else(|| ApiError::unauthorized("Missing Stripe signature"))?;
+        verify_signature(secret, signature, payload)?;
+
+        let event: Event = serde_json::from_slice(payload)
+            .map_err(|e| ApiError::validation(format!("Malformed Stripe event: {}", e), None))?;
+        if SubscriptionRepositoryImpl.event_processed(conn, &event.id).await? {
+            info!(event_id = %event.id, kind = %event.kind, "Stripe event already applied");
+            return Ok(());
+        }
+
+        match event.kind.as_str() {
+            "checkout.session.completed" => {
+                let session: CheckoutSessionObject = Self::object(&event)?;
+                Self::checkout_completed(conn, session).await?;
+            }
+            "customer.subscription.created" | "customer.subscription.updated" | "customer.subscription.deleted" => {
+                let subscription: SubscriptionObject = Self::object(&event)?;
+                Self::subscription_changed(conn, config, subscription).await?;
+            }
+            _ => info!(event_id = %event.id, kind = %event.kind, "Stripe event ignored"),
+        }
+        SubscriptionRepositoryImpl.record_event(conn, &event.id, &event.kind).await
+    }
+
+    fn object<T: for<'de> Deserialize<'de>>(event: &Event) -> Result<T> {
+        T::deserialize(&event.data.object)
+            .map_err(|e| ApiError::validation(format!("Malformed {} event: {}", event.kind, e), None))
+    }
+
+    /// Links the organization that paid to its Stripe customer and
+    /// subscription; the subscription's own events set its status
+    async fn checkout_completed(conn: &mut PgConnection, session: CheckoutSessionObject) -> Result<()> {
+        let org_id = session
+            .client_reference_id
+            .as_deref()
+            .or(session.metadata.get("org_id").map(String::as_str))
+            .and_then(|id| Uuid::parse_str(id).ok());
+        let Some(org_id) = org_id else {
+            warn!(subscription = ?session.subscription, "Checkout session without an organization");
+            return Ok(());
+        };
+        if OrganizationRepositoryImpl.find_by_ids(conn, &[org_id]).await?.is_empty() {
+            warn!(%org_id, "Checkout session of an unknown organization");
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let existing = SubscriptionRepositoryImpl.find(conn, org_id).await?;
+        let subscription = match existing {
+            // The subscription's events arrived first
+            Some(existing) if existing.stripe_subscription_id.is_some() && existing.stripe_subscription_id == session.subscription => {
+                return Ok(());
+            }
+            Some(existing) => Subscription {
+                plan: session.metadata.get("plan").cloned().unwrap_or(existing.plan),
+                status: "incomplete".to_string(),
+                stripe_customer_id: session.customer.or(existing.stripe_customer_id),
+                stripe_subscription_id: session.subscription,
+                current_period_end: None,
+                cancel_at_period_end: false,
+                updated_at: now,
+                ..existing
+            },
+            None => Subscription {
+                org_id,
+                plan: session.metadata.get("plan").cloned().unwrap_or_else(|| Plan::free().id.to_string()),
+                status: "incomplete".to_string(),
+                stripe_customer_id: session.customer,
+                stripe_subscription_id: session.subscription,
+                current_period_end: None,
+                cancel_at_period_end: false,
+                created_at: now,
+                updated_at: now,
+            },
+        };
+        SubscriptionRepositoryImpl.save(conn, &subscription).await?;
+        info!(%org_id, plan = %subscription.plan, "Checkout completed");
+        Ok(())
+    }
+
+    /// Stores a subscription's new state and moves its organization to the
+    /// quotas of the plan it now gets
+    async fn subscription_changed(conn: &mut PgConnection, config: &Config, object: SubscriptionObject) -> Result<()> {
+        let existing = match SubscriptionRepositoryImpl.find_by_stripe_id(conn, &object.id).await? {
+            Some(linked) => linked,
+            None => {
+                let org_id = object.metadata.get("org_id").and_then(|id| Uuid::parse_str(id).ok());
+                let known = match org_id {
+                    Some(org_id) => !OrganizationRepositoryImpl.find_by_ids(conn, &[org_id]).await?.is_empty(),
+                    None => false,
+                };
+                let (Some(org_id), true) = (org_id, known) else {
+                    warn!(subscription = %object.id, "Subscription of an unknown organization");
+                    return Ok(());
+                };
+                match SubscriptionRepositoryImpl.find(conn, org_id).await? {
+                    // The organization moved on to another subscription
+                    Some(current) if current.is_active() => {
+                        info!(%org_id, subscription = %object.id, "Event of a replaced subscription ignored");
+                        return Ok(());
+                    }
+                    Some(current) => current,
+                    None => {
+                        let now = Utc::now();
+                        Subscription {
+                            org_id,
+                            plan: Plan::free().id.to_string(),
+                            status: "incomplete".to_string(),
+                            stripe_customer_id: None,
+                            stripe_subscription_id: None,
+                            current_period_end: None,
+                            cancel_at_period_end: false,
+                            created_at: now,
+                            updated_at: now,
+                        }
+                    }
+                }
+            }
+        };
+        let item = object.items.data.first();
+        let plan = item
+            .and_then(|item| config.billing.prices.iter().find(|(_, price)| **price == item.price.id))
+            .map(|(plan, _)| plan.as_str())
+            .or(object.metadata.get("plan").map(String::as_str))
+            .and_then(Plan::find);
+        let Some(plan) = plan else {
+            warn!(org_id = %existing.org_id, subscription = %object.id, "Subscription to an unknown plan");
+            return Ok(());
+        };
+        let period_end = object
+            .current_period_end
+            .or(item.and_then(|item| item.current_period_end))
+            .and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single());
+
+        let before = effective_plan(Some(&existing));
+        let subscription = SubscriptionRepositoryImpl
+            .save(conn, &Subscription {
+                plan: plan.id.to_string(),
+                status: object.status,
+                stripe_customer_id: object.customer.or(existing.stripe_customer_id.clone()),
+                stripe_subscription_id: Some(object.id),
+                current_period_end: period_end,
+                cancel_at_period_end: object.cancel_at_period_end,
+                updated_at: Utc::now(),
+                ..existing
+            })
+            .await?;
+        let after = effective_plan(Some(&subscription));
+        info!(
+            org_id = %subscription.org_id,
+            plan = %subscription.plan,
+            status = %subscription.status,
+            "Subscription updated"
+        );
+        if after != before {
+            QuotaService::set(conn, subscription.org_id, after.quotas).await?;
+            info!(org_id = %subscription.org_id, from = before.id, to = after.id, "Organization moved to another plan");
+        }
+        Ok(())
+    }
+}
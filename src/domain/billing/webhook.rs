@@ -0,0 +1,48 @@
+use chrono::Utc;
+use ring::hmac;
+use subtle::ConstantTimeEq;
+
+use crate::error::{ApiError, Result};
+
+/// Seconds a webhook delivery may be old, so a captured one can't be replayed
+pub const SIGNATURE_TOLERANCE_SECS: i64 = 300;
+
+/// Checks the `Stripe-Signature` header of a webhook delivery
+///
+/// The header holds the time of signing and one or more signatures,
+/// `t=1700000000,v1=5257a8...`; a `v1` signature is the hex HMAC-SHA256 of
+/// `{t}.{payload}` keyed with the endpoint's signing secret.
+pub fn verify_signature(secret: &str, header: &str, payload: &[u8]) -> Result<()> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.push(value),
+            _ => {}
+        }
+    }
+    let (Some(timestamp), false) = (timestamp, signatures.is_empty()) else {
+        return Err(ApiError::unauthorized("Malformed Stripe signature"));
+    };
+    if (Utc::now().timestamp() - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+        return Err(ApiError::unauthorized("Stripe signature has expired"));
+    }
+
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let mut signed = format!("{}.", timestamp).into_bytes();
+    signed.extend_from_slice(payload);
+    let expected: String = hmac::sign(&key, &signed)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    if signatures
+        .iter()
+        .any(|signature| bool::from(signature.as_bytes().ct_eq(expected.as_bytes())))
+    {
+        Ok(())
+    } else {
+        Err(ApiError::unauthorized("Invalid Stripe signature"))
+    }
+}
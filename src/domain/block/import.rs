@@ -0,0 +1,96 @@
+use diesel::{prelude::*, PgConnection, QueryResult};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{service::block, validation::HarvestBlockValidator};
+use crate::{
+    api::resources::block::dto::SaveHarvestBlockInput,
+    db::{
+        models::{BlockStatus, Boundary},
+        schema::harvest_blocks,
+        spatial::geometry_from_geojson,
+    },
+    domain::import::{ImportField, ImportFieldKind, ImportRecord, ImportTarget},
+};
+
+const FIELDS: &[ImportField] = &[
+    ImportField { name: "license", kind: ImportFieldKind::Text, required: true, aliases: &["licence", "permit"] },
+    ImportField { name: "block_number", kind: ImportFieldKind::Text, required: true, aliases: &["block", "block_no"] },
+    ImportField { name: "area_ha", kind: ImportFieldKind::Number, required: true, aliases: &["area", "ha"] },
+    ImportField { name: "planned_volume_m3", kind: ImportFieldKind::Number, required: true, aliases: &["volume", "volume_m3"] },
+    ImportField { name: "boundary", kind: ImportFieldKind::Text, required: true, aliases: &["geojson", "geometry"] },
+    ImportField { name: "notes", kind: ImportFieldKind::Text, required: false, aliases: &[] },
+];
+
+/// Imports harvest blocks, their boundaries given as GeoJSON polygons
+///
+/// Blocks are created `planned`. A row whose block number is already taken
+/// under the license is left out, so re-importing a file only adds the
+/// blocks that are new.
+pub struct BlockImportTarget;
+
+fn text(record: &ImportRecord, field: &str) -> String {
+    record.get(field).and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+fn number(record: &ImportRecord, field: &str) -> f64 {
+    record.get(field).and_then(Value::as_f64).unwrap_or_default()
+}
+
+/// Reads a record as block input, or the problem with it
+fn input(record: &ImportRecord) -> Result<SaveHarvestBlockInput, String> {
+    let boundary = Boundary::try_from(text(record, "boundary"))
+        .map_err(|_| "boundary: must be a GeoJSON polygon".to_string())?;
+    let input = SaveHarvestBlockInput {
+        license: text(record, "license"),
+        block_number: text(record, "block_number"),
+        area_ha: number(record, "area_ha"),
+        planned_volume_m3: number(record, "planned_volume_m3"),
+        status: None,
+        boundary,
+        notes: record.get("notes").and_then(Value::as_str).map(str::to_string),
+        soil_sensitivity: None,
+    };
+    HarvestBlockValidator::validate_save(&input).map_err(|e| e.message)?;
+    Ok(input)
+}
+
+impl ImportTarget for BlockImportTarget {
+    fn name(&self) -> &'static str {
+        "blocks"
+    }
+
+    fn fields(&self) -> &'static [ImportField] {
+        FIELDS
+    }
+
+    fn check(&self, record: &ImportRecord) -> Vec<String> {
+        input(record).err().into_iter().collect()
+    }
+
+    fn insert(&self, conn: &mut PgConnection, org_id: Uuid, records: &[ImportRecord]) -> QueryResult<usize> {
+        let mut inserted = 0;
+        // Records reaching here passed `check`
+        for input in records.iter().filter_map(|record| input(record).ok()) {
+            let block = block(org_id, None, BlockStatus::Planned, input);
+            inserted += diesel::insert_into(harvest_blocks::table)
+                .values((
+                    harvest_blocks::id.eq(block.id),
+                    harvest_blocks::org_id.eq(block.org_id),
+                    harvest_blocks::license.eq(&block.license),
+                    harvest_blocks::block_number.eq(&block.block_number),
+                    harvest_blocks::area_ha.eq(block.area_ha),
+                    harvest_blocks::planned_volume_m3.eq(block.planned_volume_m3),
+                    harvest_blocks::status.eq(&block.status),
+                    harvest_blocks::boundary.eq(geometry_from_geojson(&block.boundary.to_geojson())),
+                    harvest_blocks::notes.eq(&block.notes),
+                    harvest_blocks::soil_sensitivity.eq(&block.soil_sensitivity),
+                    harvest_blocks::created_at.eq(block.created_at),
+                    harvest_blocks::updated_at.eq(block.updated_at),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+        }
+        Ok(inserted)
+    }
+}
@@ -0,0 +1,17 @@
+//! Harvest blocks
+//!
+//! A block is laid out `planned`, then moves through `approved`, `active`
+//! and `completed`. Leaving `planned` needs the block's sign-off chain to
+//! be complete, and an organization can't have more blocks `active` at
+//! once than its plan's `max_active_blocks` quota. Boundaries are GeoJSON
+//! polygons checked for shape here and for validity by PostGIS. A block
+//! with its soil sensitivity recorded can be checked for wet-weather
+//! operability against recent precipitation.
+
+mod import;
+mod service;
+mod validation;
+
+pub use import::BlockImportTarget;
+pub use service::HarvestBlockService;
+pub use validation::{HarvestBlockValidator, MAX_BOUNDARY_POSITIONS, MAX_BOUNDARY_RINGS, MAX_PRECIPITATION_DAYS};
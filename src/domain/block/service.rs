@@ -0,0 +1,233 @@
+use chrono::Utc;
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+
+use super::validation::HarvestBlockValidator;
+use crate::{
+    api::{
+        resources::block::dto::{ListHarvestBlocksQuery, SaveHarvestBlockInput},
+        utils::PaginationParams,
+    },
+    db::{
+        begin_transaction,
+        count::RowCount,
+        finish_transaction,
+        models::{BlockStatus, HarvestBlock, TagSubject},
+        repositories::{HarvestBlockRepository, SignoffRepositoryImpl, TagRepositoryImpl},
+    },
+    domain::{
+        activity::{ActivityKind, ActivityLog, NewActivity},
+        approval::ApprovalService,
+        operability::{self, Operability},
+        organization::QuotaService,
+        tag::{TagFilter, TagService},
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    jobs::events::{self, DomainEvent, BLOCK_APPROVED},
+    utils::Config,
+};
+
+/// Service for harvest blocks and the status they move through
+pub struct HarvestBlockService<R: HarvestBlockRepository + Send + Sync> {
+    repository: R,
+    approvals: ApprovalService<SignoffRepositoryImpl>,
+}
+
+/// Trims a text field, dropping it when blank
+fn optional(value: Option<String>) -> Option<String> {
+    value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// Builds a block from its input
+pub(super) fn block(org_id: Uuid, created_by: Option<Uuid>, status: BlockStatus, input: SaveHarvestBlockInput) -> HarvestBlock {
+    let now = Utc::now();
+    HarvestBlock {
+        id: Uuid::new_v4(),
+        org_id,
+        license: input.license.trim().to_string(),
+        block_number: input.block_number.trim().to_string(),
+        area_ha: input.area_ha,
+        planned_volume_m3: input.planned_volume_m3,
+        status: status.to_string(),
+        boundary: input.boundary,
+        notes: optional(input.notes),
+        created_by,
+        created_at: now,
+        updated_at: now,
+        soil_sensitivity: input
+            .soil_sensitivity
+            .and_then(|sensitivity| serde_json::to_value(sensitivity).ok())
+            .and_then(|sensitivity| sensitivity.as_str().map(str::to_string)),
+    }
+}
+
+impl<R: HarvestBlockRepository + Send + Sync> HarvestBlockService<R> {
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            approvals: ApprovalService::new(SignoffRepositoryImpl),
+        }
+    }
+
+    /// Fails unless block `block_id` may enter `status`: past `planned` it
+    /// needs a complete sign-off chain, and activating it needs room in the
+    /// organization's active block quota
+    async fn check_status(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid, status: BlockStatus) -> Result<()> {
+        if status.requires_approval() {
+            self.approvals.ensure_approved(conn, org_id, block_id).await?;
+        }
+        if status == BlockStatus::Active {
+            if let Some(limit) = QuotaService::of(conn, org_id).await?.max_active_blocks {
+                let active = self.repository.count_in_status(conn, org_id, BlockStatus::Active).await?;
+                if active >= i64::from(limit) {
+                    return Err(ApiError::quota_exceeded("active_blocks", i64::from(limit), active));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Records a [`BLOCK_APPROVED`] event for `block` when it moved into
+    /// `approved`, inside the transaction that wrote it
+    fn record_approval(conn: &mut PgConnection, config: &Config, block: &HarvestBlock, previous: Option<&str>, approved_by: Option<Uuid>) -> Result<()> {
+        let approved = BlockStatus::Approved.as_str();
+        if block.status != approved || previous == Some(approved) {
+            return Ok(());
+        }
+        let event = DomainEvent::new(
+            BLOCK_APPROVED,
+            json!({
+                "block_id": block.id,
+                "license": block.license,
+                "block_number": block.block_number,
+                "approved_by": approved_by,
+            }),
+        )?
+        .with_organization(block.org_id);
+        events::record(conn, config, &event)
+    }
+
+    /// Creates a block, planned unless the input says otherwise
+    pub async fn create(
+        &self,
+        conn: &mut PgConnection,
+        config: &Config,
+        org_id: Uuid,
+        created_by: Option<Uuid>,
+        input: SaveHarvestBlockInput,
+    ) -> Result<HarvestBlock> {
+        let status = HarvestBlockValidator::validate_save(&input)?.unwrap_or(BlockStatus::Planned);
+        let block = block(org_id, created_by, status, input);
+        self.check_status(conn, org_id, block.id, status).await?;
+        begin_transaction(conn)?;
+        let result = async {
+            let block = self.repository.create(conn, &block).await?;
+            Self::record_approval(conn, config, &block, None, created_by)?;
+            Ok(block)
+        }
+        .await;
+        let block = finish_transaction(conn, result)?;
+        info!(block_id = %block.id, org_id = %org_id, "Created harvest block {} {}", block.license, block.block_number);
+        Ok(block)
+    }
+
+    /// Replaces a block's details, recording a change of status
+    pub async fn update(
+        &self,
+        conn: &mut PgConnection,
+        config: &Config,
+        org_id: Uuid,
+        block_id: Uuid,
+        changed_by: Option<Uuid>,
+        input: SaveHarvestBlockInput,
+    ) -> Result<HarvestBlock> {
+        let requested = HarvestBlockValidator::validate_save(&input)?;
+        let existing = self.repository.find(conn, org_id, block_id).await?;
+        let status = match requested {
+            Some(status) => status,
+            None => BlockStatus::parse(&existing.status).unwrap_or(BlockStatus::Planned),
+        };
+        if status.as_str() != existing.status {
+            self.check_status(conn, org_id, existing.id, status).await?;
+        }
+        let block = HarvestBlock {
+            id: existing.id,
+            created_by: existing.created_by,
+            created_at: existing.created_at,
+            ..block(org_id, None, status, input)
+        };
+        begin_transaction(conn)?;
+        let result = async {
+            let block = self.repository.update(conn, org_id, &block).await?;
+            Self::record_approval(conn, config, &block, Some(&existing.status), changed_by)?;
+            Ok(block)
+        }
+        .await;
+        let block = finish_transaction(conn, result)?;
+
+        if block.status != existing.status {
+            info!(block_id = %block.id, org_id = %org_id, "Harvest block moved from {} to {}", existing.status, block.status);
+            ActivityLog::record(
+                conn,
+                org_id,
+                changed_by,
+                NewActivity::new(
+                    ActivityKind::BlockStatusChanged,
+                    "block",
+                    block.id,
+                    format!("Moved block {} {} to {}", block.license, block.block_number, block.status),
+                )
+                .with_data(json!({ "from": existing.status, "to": block.status })),
+            )
+            .await;
+        }
+        Ok(block)
+    }
+
+    /// Gets a block
+    pub async fn get(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid) -> Result<HarvestBlock> {
+        self.repository.find(conn, org_id, block_id).await
+    }
+
+    /// Lists an organization's blocks matching `query`, by license and
+    /// block number
+    pub async fn list(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        query: &ListHarvestBlocksQuery,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<HarvestBlock>, RowCount)> {
+        let mut filter = HarvestBlockValidator::validate_filter(query)?;
+        let tags = TagFilter::parse(query.tags.as_deref(), query.tag_match.as_deref())?;
+        filter.ids = TagService::new(TagRepositoryImpl)
+            .filter(conn, org_id, TagSubject::Block, tags.as_ref())
+            .await?;
+        let blocks = self.repository.list(conn, org_id, &filter, pagination).await?;
+        let total = self.repository.count(conn, org_id, &filter).await?;
+        Ok((blocks, total))
+    }
+
+    /// Whether ground-based operations may run on a block after the
+    /// given daily precipitation, most recent day first
+    pub async fn operability(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid, daily_mm: &[f64]) -> Result<Operability> {
+        let block = self.repository.find(conn, org_id, block_id).await?;
+        let sensitivity = block.soil_sensitivity().ok_or_else(|| {
+            ApiError::new(
+                ErrorCode::Conflict,
+                format!("Harvest block {} {} has no soil sensitivity recorded", block.license, block.block_number),
+                ErrorContext::new().with_details(json!({ "field": "soil_sensitivity", "code": "NOT_RECORDED" })),
+            )
+        })?;
+        Ok(operability::assess(sensitivity, daily_mm))
+    }
+
+    /// Deletes a block no timber sale refers to
+    pub async fn delete(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid) -> Result<()> {
+        self.repository.delete(conn, org_id, block_id).await?;
+        info!(block_id = %block_id, org_id = %org_id, "Deleted harvest block");
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+use serde_json::json;
+
+use crate::{
+    api::resources::block::dto::{ListHarvestBlocksQuery, SaveHarvestBlockInput},
+    db::models::{BlockStatus, Boundary, BoundingBox, HarvestBlockFilter},
+    error::{ApiError, ErrorContext, Result},
+};
+use validator::Validate as ValidatorValidate;
+
+/// Most rings a boundary may have, its outer ring included
+pub const MAX_BOUNDARY_RINGS: usize = 100;
+
+/// Most positions a boundary may have across its rings
+pub const MAX_BOUNDARY_POSITIONS: usize = 10_000;
+
+/// Most days of precipitation an operability check takes
+pub const MAX_PRECIPITATION_DAYS: usize = 31;
+
+fn invalid(field: &str, code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": field,
+            "code": code,
+        })),
+    )
+}
+
+fn status(value: &str) -> Result<BlockStatus> {
+    BlockStatus::parse(value).ok_or_else(|| {
+        ApiError::validation(
+            format!("Unknown block status {}", value),
+            Some(json!({ "field": "status", "available": BlockStatus::ALL })),
+        )
+    })
+}
+
+pub struct HarvestBlockValidator;
+
+impl HarvestBlockValidator {
+    /// Validates input for creating or replacing a block, returning the
+    /// status it asks for
+    pub fn validate_save(input: &SaveHarvestBlockInput) -> Result<Option<BlockStatus>> {
+        ValidatorValidate::validate(input).map_err(|e| {
+            ApiError::validation_with_context(
+                "Invalid input",
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(json!(e))
+            )
+        })?;
+        if !(input.area_ha.is_finite() && input.area_ha > 0.0) {
+            return Err(invalid("area_ha", "OUT_OF_RANGE", "The block area must be positive"));
+        }
+        if !(input.planned_volume_m3.is_finite() && input.planned_volume_m3 >= 0.0) {
+            return Err(invalid("planned_volume_m3", "OUT_OF_RANGE", "The planned volume must not be negative"));
+        }
+        Self::validate_boundary(&input.boundary)?;
+        input.status.as_deref().map(status).transpose()
+    }
+
+    /// Validates a boundary's shape: closed rings of enough positions, all
+    /// on the globe
+    ///
+    /// Rings crossing themselves or each other are left to PostGIS, which
+    /// rejects them when the block is stored.
+    pub fn validate_boundary(boundary: &Boundary) -> Result<()> {
+        let rings = boundary.rings();
+        if rings.is_empty() {
+            return Err(invalid("boundary", "EMPTY", "The boundary needs an outer ring"));
+        }
+        if rings.len() > MAX_BOUNDARY_RINGS {
+            return Err(invalid(
+                "boundary",
+                "TOO_LARGE",
+                format!("The boundary can have at most {} rings", MAX_BOUNDARY_RINGS),
+            ));
+        }
+        if rings.iter().map(Vec::len).sum::<usize>() > MAX_BOUNDARY_POSITIONS {
+            return Err(invalid(
+                "boundary",
+                "TOO_LARGE",
+                format!("The boundary can have at most {} positions", MAX_BOUNDARY_POSITIONS),
+            ));
+        }
+        for ring in rings {
+            if ring.len() < 4 {
+                return Err(invalid("boundary", "RING_TOO_SHORT", "Each ring needs at least four positions"));
+            }
+            if ring.first() != ring.last() {
+                return Err(invalid("boundary", "RING_NOT_CLOSED", "Each ring must end at the position it starts from"));
+            }
+            let on_globe = |[lon, lat]: &[f64; 2]| (-180.0..=180.0).contains(lon) && (-90.0..=90.0).contains(lat);
+            if !ring.iter().all(on_globe) {
+                return Err(invalid(
+                    "boundary",
+                    "OUT_OF_RANGE",
+                    "Positions must be a longitude from -180 to 180 and a latitude from -90 to 90",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the conditions of a block list
+    pub fn validate_filter(query: &ListHarvestBlocksQuery) -> Result<HarvestBlockFilter> {
+        Ok(HarvestBlockFilter {
+            license: query.license.as_deref().map(str::trim).filter(|license| !license.is_empty()).map(str::to_string),
+            status: query.status.as_deref().map(status).transpose()?,
+            bbox: query.bbox.as_deref().map(Self::bounding_box).transpose()?,
+            ids: None,
+        })
+    }
+
+    /// Reads daily precipitation totals given as comma separated
+    /// millimetres, most recent day first
+    pub fn validate_precipitation(value: Option<&str>) -> Result<Vec<f64>> {
+        let bad = || {
+            invalid(
+                "precipitation_mm",
+                "INVALID_PRECIPITATION",
+                format!("Give up to {} daily totals in millimetres, none negative", MAX_PRECIPITATION_DAYS),
+            )
+        };
+        let days = value
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|day| !day.is_empty())
+            .map(|day| day.parse::<f64>().ok().filter(|mm| mm.is_finite() && *mm >= 0.0))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(bad)?;
+        if days.is_empty() || days.len() > MAX_PRECIPITATION_DAYS {
+            return Err(bad());
+        }
+        Ok(days)
+    }
+
+    /// Reads a box given as `min_lon,min_lat,max_lon,max_lat`
+    fn bounding_box(value: &str) -> Result<BoundingBox> {
+        let bad = || invalid("bbox", "INVALID_BBOX", "The box must be min_lon,min_lat,max_lon,max_lat");
+        let values = value
+            .split(',')
+            .map(|part| part.trim().parse::<f64>().ok().filter(|value| value.is_finite()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(bad)?;
+        let [min_lon, min_lat, max_lon, max_lat] = values[..] else {
+            return Err(bad());
+        };
+        if min_lon > max_lon || min_lat > max_lat {
+            return Err(bad());
+        }
+        Ok(BoundingBox { min_lon, min_lat, max_lon, max_lat })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: f64) -> Vec<[f64; 2]> {
+        vec![[25.0, 61.0], [25.0 + size, 61.0], [25.0 + size, 61.0 + size], [25.0, 61.0 + size], [25.0, 61.0]]
+    }
+
+    #[test]
+    fn test_boundary_rings_are_closed_and_on_the_globe() {
+        let valid = Boundary::Polygon { coordinates: vec![square(0.01)] };
+        assert!(HarvestBlockValidator::validate_boundary(&valid).is_ok());
+
+        let mut open = square(0.01);
+        open.pop();
+        open.push([25.0, 61.001]);
+        let cases = [
+            Boundary::Polygon { coordinates: vec![] },
+            Boundary::Polygon { coordinates: vec![open] },
+            Boundary::Polygon { coordinates: vec![vec![[25.0, 61.0], [25.1, 61.0], [25.0, 61.0]]] },
+            Boundary::Polygon { coordinates: vec![vec![[190.0, 61.0], [25.1, 61.0], [25.1, 61.1], [190.0, 61.0]]] },
+        ];
+        for boundary in cases {
+            assert!(HarvestBlockValidator::validate_boundary(&boundary).is_err(), "{:?}", boundary);
+        }
+    }
+
+    #[test]
+    fn test_bounding_box_is_read_in_order() {
+        let bbox = HarvestBlockValidator::bounding_box("24.5, 60.5,25.5,61.5").unwrap();
+        assert_eq!(bbox, BoundingBox { min_lon: 24.5, min_lat: 60.5, max_lon: 25.5, max_lat: 61.5 });
+        assert!(HarvestBlockValidator::bounding_box("25.5,60.5,24.5,61.5").is_err());
+        assert!(HarvestBlockValidator::bounding_box("24.5,60.5,25.5").is_err());
+        assert!(HarvestBlockValidator::bounding_box("a,b,c,d").is_err());
+    }
+
+    #[test]
+    fn test_precipitation_is_daily_millimetres() {
+        assert_eq!(HarvestBlockValidator::validate_precipitation(Some("12, 0,4.5")).unwrap(), vec![12.0, 0.0, 4.5]);
+        assert!(HarvestBlockValidator::validate_precipitation(None).is_err());
+        assert!(HarvestBlockValidator::validate_precipitation(Some("12,-1")).is_err());
+        assert!(HarvestBlockValidator::validate_precipitation(Some("12,lots")).is_err());
+        assert!(HarvestBlockValidator::validate_precipitation(Some(&vec!["1"; MAX_PRECIPITATION_DAYS + 1].join(","))).is_err());
+    }
+}
@@ -0,0 +1,11 @@
+//! Operator certifications
+//!
+//! Users hold certifications such as a faller certification, first aid or
+//! dangerous goods handling, with scanned certificates attached as
+//! documents. The organization lists the certifications expiring soon, and
+//! the `certification_expiry` scheduled job notifies its supervisors
+//! [`EXPIRY_NOTICE_DAYS`] ahead of each expiry, once.
+
+mod service;
+
+pub use service::{CertificationService, EXPIRY_NOTICE_DAYS, EXPIRY_NOTIFICATION_KIND, MAX_EXPIRING_DAYS};
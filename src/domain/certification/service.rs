@@ -0,0 +1,321 @@
+use chrono::{Duration, NaiveDate, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    api::resources::certification::dto::SaveCertificationInput,
+    db::{
+        loader::group_by,
+        models::{auth::User, Certification, CertificationKind, Document, DocumentSubject, Notification},
+        repositories::{
+            auth::{UserRepository, UserRepositoryImpl},
+            CertificationRepository, DocumentRepositoryImpl, NotificationRepositoryImpl, Repository,
+        },
+    },
+    domain::{
+        activity::{ActivityKind, ActivityLog, NewActivity},
+        document::DocumentService,
+        notification::NotificationService,
+    },
+    error::{ApiError, ErrorContext, Result},
+};
+
+/// Days ahead of an expiry that supervisors are notified
+pub const EXPIRY_NOTICE_DAYS: i64 = 30;
+
+/// Furthest ahead the expiring certifications can be listed
+pub const MAX_EXPIRING_DAYS: i64 = 365;
+
+/// Notification kind of a coming expiry
+pub const EXPIRY_NOTIFICATION_KIND: &str = "certification_expiring";
+
+/// Certifications notified per batch
+const BATCH_SIZE: i64 = 100;
+
+/// Service for the certifications of an organization's users
+pub struct CertificationService<R: CertificationRepository + Send + Sync> {
+    repository: R,
+    documents: DocumentService<DocumentRepositoryImpl>,
+}
+
+fn invalid_input(e: validator::ValidationErrors) -> ApiError {
+    ApiError::validation_with_context(
+        "Invalid input",
+        ErrorContext::new()
+            .with_message_key("INVALID_INPUT")
+            .with_details(json!(e))
+    )
+}
+
+fn invalid_field(field: &str, code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": field,
+            "code": code,
+        })),
+    )
+}
+
+/// `value` trimmed, `None` when blank
+fn optional(value: Option<String>) -> Option<String> {
+    value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+impl<R: CertificationRepository + Send + Sync> CertificationService<R> {
+    pub fn new(repository: R, documents: DocumentService<DocumentRepositoryImpl>) -> Self {
+        Self { repository, documents }
+    }
+
+    /// Checks `input` and makes a certification of it
+    async fn certification(conn: &mut PgConnection, org_id: Uuid, input: SaveCertificationInput) -> Result<Certification> {
+        ValidatorValidate::validate(&input).map_err(invalid_input)?;
+        let kind = CertificationKind::parse(input.kind.trim()).ok_or_else(|| {
+            ApiError::validation(
+                format!("Unknown certification kind {}", input.kind),
+                Some(json!({ "available": CertificationKind::ALL.map(|kind| kind.as_str()) })),
+            )
+        })?;
+        let name = optional(input.name);
+        if kind == CertificationKind::Other && name.is_none() {
+            return Err(invalid_field("name", "REQUIRED", "Other certifications need a name"));
+        }
+        if input.expires_on.is_some_and(|expires_on| expires_on < input.issued_on) {
+            return Err(invalid_field("expires_on", "BEFORE_ISSUED", "A certification cannot expire before it is issued"));
+        }
+        let holder = UserRepositoryImpl.find_by_id(conn, input.user_id).await?;
+        if holder.org_id != org_id || holder.deleted_at.is_some() {
+            return Err(ApiError::not_found(format!("User with id {} not found", input.user_id)));
+        }
+
+        let now = Utc::now();
+        Ok(Certification {
+            id: Uuid::new_v4(),
+            org_id,
+            user_id: holder.id,
+            kind: kind.as_str().to_string(),
+            name,
+            certificate_number: optional(input.certificate_number),
+            issuer: optional(input.issuer),
+            issued_on: input.issued_on,
+            expires_on: input.expires_on,
+            expiry_notified_at: None,
+            created_by: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Records a certification held by a member of the organization
+    pub async fn create(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        created_by: Option<Uuid>,
+        input: SaveCertificationInput,
+    ) -> Result<Certification> {
+        let certification = Certification {
+            created_by,
+            ..Self::certification(conn, org_id, input).await?
+        };
+        let certification = self.repository.create(conn, &certification).await?;
+        info!(
+            certification_id = %certification.id,
+            org_id = %org_id,
+            user_id = %certification.user_id,
+            "Created {} certification",
+            certification.kind
+        );
+        ActivityLog::record(
+            conn,
+            org_id,
+            created_by,
+            NewActivity::new(
+                ActivityKind::CertificationRecorded,
+                "certification",
+                certification.id,
+                format!("Recorded {}", certification.title()),
+            )
+            .with_data(json!({ "user_id": certification.user_id })),
+        )
+        .await;
+        Ok(certification)
+    }
+
+    /// Replaces the details of a certification
+    ///
+    /// A renewed certification, one whose expiry changed, is notified
+    /// again ahead of its new expiry.
+    pub async fn update(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        certification_id: Uuid,
+        input: SaveCertificationInput,
+    ) -> Result<Certification> {
+        let existing = self.repository.find(conn, org_id, certification_id).await?;
+        let certification = Self::certification(conn, org_id, input).await?;
+        let expiry_notified_at = existing.expiry_notified_at.filter(|_| existing.expires_on == certification.expires_on);
+        let certification = Certification {
+            id: existing.id,
+            expiry_notified_at,
+            created_by: existing.created_by,
+            created_at: existing.created_at,
+            ..certification
+        };
+        self.repository.update(conn, org_id, &certification).await
+    }
+
+    /// Deletes a certification with its documents
+    pub async fn delete(&self, conn: &mut PgConnection, org_id: Uuid, certification_id: Uuid) -> Result<()> {
+        let certification = self.repository.find(conn, org_id, certification_id).await?;
+        let now = Utc::now();
+        for document in self.attachments(conn, &certification).await? {
+            self.documents.delete(conn, org_id, document.id, now).await?;
+        }
+        self.repository.delete(conn, org_id, certification.id).await?;
+        info!(certification_id = %certification.id, org_id = %org_id, "Deleted certification");
+        Ok(())
+    }
+
+    /// A certification with the documents attached to it
+    pub async fn get(&self, conn: &mut PgConnection, org_id: Uuid, certification_id: Uuid) -> Result<(Certification, Vec<Document>)> {
+        let certification = self.repository.find(conn, org_id, certification_id).await?;
+        let documents = self.attachments(conn, &certification).await?;
+        Ok((certification, documents))
+    }
+
+    async fn attachments(&self, conn: &mut PgConnection, certification: &Certification) -> Result<Vec<Document>> {
+        self.documents
+            .list(conn, certification.org_id, DocumentSubject::Certification, certification.id)
+            .await
+    }
+
+    /// Lists the organization's certifications, of one user or kind when
+    /// given, soonest expiry first
+    pub async fn list(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        user_id: Option<Uuid>,
+        reports_to: Option<Uuid>,
+        kind: Option<&str>,
+    ) -> Result<Vec<Certification>> {
+        let kind = kind
+            .map(|kind| {
+                CertificationKind::parse(kind.trim()).ok_or_else(|| {
+                    ApiError::validation(
+                        format!("Unknown certification kind {}", kind),
+                        Some(json!({ "available": CertificationKind::ALL.map(|kind| kind.as_str()) })),
+                    )
+                })
+            })
+            .transpose()?;
+        self.repository.list(conn, org_id, user_id, reports_to, kind).await
+    }
+
+    /// Certifications of the organization's active members expiring within
+    /// `days` of `today`, expired ones included, with their holders
+    pub async fn expiring(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        reports_to: Option<Uuid>,
+        today: NaiveDate,
+        days: i64,
+    ) -> Result<Vec<(Certification, User)>> {
+        if !(0..=MAX_EXPIRING_DAYS).contains(&days) {
+            return Err(invalid_field(
+                "days",
+                "OUT_OF_RANGE",
+                format!("Days must be from 0 to {}", MAX_EXPIRING_DAYS),
+            ));
+        }
+        self.repository
+            .expiring(conn, org_id, reports_to, today + Duration::days(days))
+            .await
+    }
+
+    /// Notifies the supervisors of the holders of certifications expiring
+    /// within [`EXPIRY_NOTICE_DAYS`] of `today`, returning how many
+    /// certifications they were notified of
+    ///
+    /// Each certification is notified once; those already expired are
+    /// left alone. The holder's own supervisor is notified when they have
+    /// an active one; otherwise all of the organization's active managers
+    /// and admins, other than the holder, are.
+    pub async fn notify_expiring(&self, conn: &mut PgConnection, today: NaiveDate) -> Result<usize> {
+        let until = today + Duration::days(EXPIRY_NOTICE_DAYS);
+        let notifications = NotificationService::new(NotificationRepositoryImpl);
+        let mut notified = 0;
+        loop {
+            let due = self.repository.due_for_notice(conn, today, until, BATCH_SIZE).await?;
+            if due.is_empty() {
+                break;
+            }
+
+            let mut org_ids: Vec<Uuid> = due.iter().map(|(certification, _)| certification.org_id).collect();
+            org_ids.sort();
+            org_ids.dedup();
+            let supervisors = group_by(UserRepositoryImpl.find_supervisors(conn, &org_ids).await?, |supervisor| supervisor.org_id);
+
+            let mut batch = Vec::new();
+            for (certification, holder) in &due {
+                let Some(expires_on) = certification.expires_on else { continue };
+                let title = format!(
+                    "{} {}'s {} expires on {}",
+                    holder.first_name,
+                    holder.last_name,
+                    certification.title(),
+                    expires_on
+                );
+                let recipients = supervisors.get(&certification.org_id).map(Vec::as_slice).unwrap_or_default();
+                let recipients: Vec<&User> = match recipients.iter().find(|supervisor| Some(supervisor.id) == holder.supervisor_id) {
+                    Some(supervisor) => vec![supervisor],
+                    None => recipients.iter().filter(|supervisor| supervisor.id != holder.id).collect(),
+                };
+                if recipients.is_empty() {
+                    warn!(
+                        certification_id = %certification.id,
+                        org_id = %certification.org_id,
+                        "No supervisor to notify of an expiring certification"
+                    );
+                }
+                let body = match (expires_on - today).num_days() {
+                    0 => "Expires today.".to_string(),
+                    1 => "Expires tomorrow.".to_string(),
+                    days => format!("Expires in {} days.", days),
+                };
+                for supervisor in recipients {
+                    batch.push(
+                        Notification::new(supervisor.id, EXPIRY_NOTIFICATION_KIND, title.clone())
+                            .with_org(certification.org_id)
+                            .with_body(body.clone())
+                            .with_data(json!({
+                                "certification_id": certification.id,
+                                "user_id": holder.id,
+                                "kind": certification.kind,
+                                "expires_on": expires_on,
+                            })),
+                    );
+                }
+            }
+            notifications.notify(conn, &batch).await?;
+
+            let ids: Vec<Uuid> = due.iter().map(|(certification, _)| certification.id).collect();
+            self.repository.mark_notified(conn, &ids, Utc::now()).await?;
+            notified += ids.len();
+            if (due.len() as i64) < BATCH_SIZE {
+                break;
+            }
+        }
+
+        if notified > 0 {
+            info!(certifications = notified, "Notified supervisors of expiring certifications");
+        }
+        Ok(notified)
+    }
+}
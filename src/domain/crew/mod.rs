@@ -0,0 +1,12 @@
+//! Crews
+//!
+//! A crew groups an organization's users under a supervisor, with the
+//! machines it works with by default, so that dispatch, timecards and
+//! production records can refer to the crew. The supervisor need not be a
+//! member, and a user may work in several crews.
+
+mod service;
+mod validation;
+
+pub use service::{CrewRoster, CrewService};
+pub use validation::{CrewValidator, MAX_EQUIPMENT_ID_LENGTH};
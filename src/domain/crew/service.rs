@@ -0,0 +1,158 @@
+use chrono::Utc;
+use diesel::PgConnection;
+use std::collections::HashSet;
+use tracing::info;
+use uuid::Uuid;
+
+use super::validation::CrewValidator;
+use crate::{
+    api::resources::crew::dto::{CreateCrewInput, UpdateCrewInput},
+    db::{
+        loader::group_by,
+        models::{auth::User, Crew, CrewMember},
+        repositories::{CrewRepository, Repository, UserRepositoryImpl},
+    },
+    error::{ApiError, Result},
+};
+
+/// A crew with its members
+#[derive(Debug, Clone)]
+pub struct CrewRoster {
+    pub crew: Crew,
+    /// By last and first name
+    pub members: Vec<(CrewMember, User)>,
+}
+
+/// Service for crews and their members
+pub struct CrewService<R: CrewRepository + Send + Sync> {
+    repository: R,
+}
+
+/// Machine ids trimmed, in the order given
+fn equipment(machine_ids: Vec<String>) -> Vec<String> {
+    machine_ids.into_iter().map(|machine_id| machine_id.trim().to_string()).collect()
+}
+
+/// Checks that every user of `ids` is a live user of the organization
+async fn check_users(conn: &mut PgConnection, org_id: Uuid, ids: &[Uuid]) -> Result<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let found: HashSet<Uuid> = UserRepositoryImpl
+        .find_by_ids(conn, ids)
+        .await?
+        .into_iter()
+        .filter(|user| user.org_id == org_id)
+        .map(|user| user.id)
+        .collect();
+    match ids.iter().find(|id| !found.contains(id)) {
+        Some(missing) => Err(ApiError::not_found(format!("User with id {} not found", missing))),
+        None => Ok(()),
+    }
+}
+
+impl<R: CrewRepository + Send + Sync> CrewService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    async fn roster(&self, conn: &mut PgConnection, org_id: Uuid, crew: Crew) -> Result<CrewRoster> {
+        let members = self.repository.members(conn, org_id, &[crew.id]).await?;
+        Ok(CrewRoster { crew, members })
+    }
+
+    /// Creates a crew with its first members
+    pub async fn create(&self, conn: &mut PgConnection, org_id: Uuid, input: CreateCrewInput) -> Result<CrewRoster> {
+        CrewValidator::validate_create(&input)?;
+        let mut seen = HashSet::new();
+        let member_ids: Vec<Uuid> = input.member_ids.into_iter().filter(|id| seen.insert(*id)).collect();
+        let mut users = member_ids.clone();
+        users.extend(input.supervisor_id);
+        check_users(conn, org_id, &users).await?;
+
+        let now = Utc::now();
+        let crew = Crew {
+            id: Uuid::new_v4(),
+            org_id,
+            name: input.name.trim().to_string(),
+            supervisor_id: input.supervisor_id,
+            default_equipment: equipment(input.default_equipment),
+            created_at: now,
+            updated_at: now,
+        };
+        let members: Vec<CrewMember> = member_ids
+            .into_iter()
+            .map(|user_id| CrewMember { crew_id: crew.id, user_id, org_id, added_at: now })
+            .collect();
+        let crew = self.repository.create(conn, &crew, &members).await?;
+        info!(crew_id = %crew.id, org_id = %org_id, members = members.len(), "Created crew {}", crew.name);
+        self.roster(conn, org_id, crew).await
+    }
+
+    /// Lists the organization's crews by name, only those `user_id`
+    /// supervises or works in if given
+    pub async fn list(&self, conn: &mut PgConnection, org_id: Uuid, user_id: Option<Uuid>) -> Result<Vec<CrewRoster>> {
+        let crews = self.repository.list(conn, org_id, user_id).await?;
+        let ids: Vec<Uuid> = crews.iter().map(|crew| crew.id).collect();
+        let mut members = group_by(self.repository.members(conn, org_id, &ids).await?, |(member, _)| member.crew_id);
+        Ok(crews
+            .into_iter()
+            .map(|crew| CrewRoster {
+                members: members.remove(&crew.id).unwrap_or_default(),
+                crew,
+            })
+            .collect())
+    }
+
+    /// Gets a crew with its members
+    pub async fn get(&self, conn: &mut PgConnection, org_id: Uuid, crew_id: Uuid) -> Result<CrewRoster> {
+        let crew = self.repository.find(conn, org_id, crew_id).await?;
+        self.roster(conn, org_id, crew).await
+    }
+
+    /// Replaces a crew's name, supervisor and default equipment
+    pub async fn update(&self, conn: &mut PgConnection, org_id: Uuid, crew_id: Uuid, input: UpdateCrewInput) -> Result<CrewRoster> {
+        CrewValidator::validate_update(&input)?;
+        let existing = self.repository.find(conn, org_id, crew_id).await?;
+        if let Some(supervisor_id) = input.supervisor_id {
+            check_users(conn, org_id, &[supervisor_id]).await?;
+        }
+        let crew = Crew {
+            name: input.name.trim().to_string(),
+            supervisor_id: input.supervisor_id,
+            default_equipment: equipment(input.default_equipment),
+            ..existing
+        };
+        let crew = self.repository.update(conn, org_id, &crew).await?;
+        self.roster(conn, org_id, crew).await
+    }
+
+    /// Deletes a crew
+    pub async fn delete(&self, conn: &mut PgConnection, org_id: Uuid, crew_id: Uuid) -> Result<()> {
+        self.repository.delete(conn, org_id, crew_id).await?;
+        info!(crew_id = %crew_id, org_id = %org_id, "Deleted crew");
+        Ok(())
+    }
+
+    /// Adds a user to a crew; adding a member again changes nothing
+    pub async fn add_member(&self, conn: &mut PgConnection, org_id: Uuid, crew_id: Uuid, user_id: Uuid) -> Result<CrewRoster> {
+        let crew = self.repository.find(conn, org_id, crew_id).await?;
+        check_users(conn, org_id, &[user_id]).await?;
+        let added = self
+            .repository
+            .add_member(conn, &CrewMember { crew_id, user_id, org_id, added_at: Utc::now() })
+            .await?;
+        if added {
+            info!(crew_id = %crew_id, user_id = %user_id, "Added crew member");
+        }
+        self.roster(conn, org_id, crew).await
+    }
+
+    /// Removes a user from a crew
+    pub async fn remove_member(&self, conn: &mut PgConnection, org_id: Uuid, crew_id: Uuid, user_id: Uuid) -> Result<()> {
+        self.repository.find(conn, org_id, crew_id).await?;
+        self.repository.remove_member(conn, org_id, crew_id, user_id).await?;
+        info!(crew_id = %crew_id, user_id = %user_id, "Removed crew member");
+        Ok(())
+    }
+}
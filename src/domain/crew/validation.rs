@@ -0,0 +1,94 @@
+use serde_json::json;
+use std::collections::HashSet;
+
+use crate::{
+    api::resources::crew::dto::{CreateCrewInput, UpdateCrewInput},
+    error::{ApiError, ErrorContext, Result},
+};
+use validator::Validate as ValidatorValidate;
+
+/// Longest machine id, matching those machines report in telemetry
+pub const MAX_EQUIPMENT_ID_LENGTH: usize = 100;
+
+fn invalid(field: String, code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": field,
+            "code": code,
+        })),
+    )
+}
+
+fn invalid_input(e: validator::ValidationErrors) -> ApiError {
+    ApiError::validation_with_context(
+        "Invalid input",
+        ErrorContext::new()
+            .with_message_key("INVALID_INPUT")
+            .with_details(json!(e))
+    )
+}
+
+pub struct CrewValidator;
+
+impl CrewValidator {
+    /// Validates input for creating a crew
+    pub fn validate_create(input: &CreateCrewInput) -> Result<()> {
+        ValidatorValidate::validate(input).map_err(invalid_input)?;
+        Self::validate_details(&input.name, &input.default_equipment)
+    }
+
+    /// Validates input for replacing a crew's details
+    pub fn validate_update(input: &UpdateCrewInput) -> Result<()> {
+        ValidatorValidate::validate(input).map_err(invalid_input)?;
+        Self::validate_details(&input.name, &input.default_equipment)
+    }
+
+    fn validate_details(name: &str, equipment: &[String]) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(invalid("name".to_string(), "REQUIRED", "The crew needs a name"));
+        }
+        let mut seen = HashSet::new();
+        for (index, machine_id) in equipment.iter().enumerate() {
+            let field = format!("default_equipment[{}]", index);
+            let machine_id = machine_id.trim();
+            if machine_id.is_empty() || machine_id.len() > MAX_EQUIPMENT_ID_LENGTH {
+                return Err(invalid(
+                    field,
+                    "INVALID_LENGTH",
+                    format!("A machine id must be 1 to {} characters", MAX_EQUIPMENT_ID_LENGTH),
+                ));
+            }
+            if !seen.insert(machine_id) {
+                return Err(invalid(field, "DUPLICATE", format!("Machine {} is listed more than once", machine_id)));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(name: &str, equipment: &[&str]) -> UpdateCrewInput {
+        UpdateCrewInput {
+            name: name.to_string(),
+            supervisor_id: None,
+            default_equipment: equipment.iter().map(|machine_id| machine_id.to_string()).collect(),
+        }
+    }
+
+    fn field(error: ApiError) -> serde_json::Value {
+        error.context.details.unwrap()["field"].clone()
+    }
+
+    #[test]
+    fn test_equipment_is_named_by_position() {
+        assert!(CrewValidator::validate_update(&input("Crew 1", &["HRV-2", "FWD-7"])).is_ok());
+        assert!(CrewValidator::validate_update(&input("Crew 1", &[])).is_ok());
+        assert_eq!(field(CrewValidator::validate_update(&input(" ", &[])).unwrap_err()), "name");
+        assert_eq!(field(CrewValidator::validate_update(&input("Crew 1", &["HRV-2", " "])).unwrap_err()), "default_equipment[1]");
+        assert_eq!(field(CrewValidator::validate_update(&input("Crew 1", &["HRV-2", " HRV-2"])).unwrap_err()), "default_equipment[1]");
+    }
+}
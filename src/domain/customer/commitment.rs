@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use diesel::{PgConnection, QueryResult};
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::db::models::SupplyContract;
+
+/// Share of a commitment a projection may fall short by before the
+/// contract is flagged
+pub const SHORTFALL_TOLERANCE: f64 = 0.05;
+
+/// Volumes delivered to customers, as recorded on scale tickets
+pub trait DeliveryLedger: Send + Sync {
+    /// Cubic metres of a product delivered to a customer between two days,
+    /// both included
+    fn delivered(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        customer_id: Uuid,
+        product: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> QueryResult<f64>;
+}
+
+/// Ledger without deliveries, used until scale tickets are recorded
+pub struct NoDeliveries;
+
+impl DeliveryLedger for NoDeliveries {
+    fn delivered(
+        &self,
+        _conn: &mut PgConnection,
+        _org_id: Uuid,
+        _customer_id: Uuid,
+        _product: &str,
+        _from: NaiveDate,
+        _to: NaiveDate,
+    ) -> QueryResult<f64> {
+        Ok(0.0)
+    }
+}
+
+/// Ledger deliveries are compared against commitments with
+pub fn ledger() -> Arc<dyn DeliveryLedger> {
+    Arc::new(NoDeliveries)
+}
+
+/// How deliveries on a contract compare with its commitment
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct CommitmentProgress {
+    /// Day the progress is measured on
+    pub as_of: NaiveDate,
+    /// Share of the period elapsed, from 0 to 1
+    pub elapsed: f64,
+    pub delivered_m3: f64,
+    /// Volume due by now if deliveries were spread evenly over the period
+    pub expected_m3: f64,
+    /// Volume delivered by the end of the period at the rate so far
+    pub projected_m3: f64,
+    /// Committed volume the projection falls short of
+    pub shortfall_m3: f64,
+    /// Whether the projection falls short by more than the tolerance
+    pub at_risk: bool,
+}
+
+/// Compares the volume delivered on a contract up to `as_of` with its
+/// commitment
+pub fn progress(contract: &SupplyContract, delivered_m3: f64, as_of: NaiveDate) -> CommitmentProgress {
+    let total_days = (contract.ends_on - contract.starts_on).num_days() + 1;
+    let elapsed_days = ((as_of - contract.starts_on).num_days() + 1).clamp(0, total_days);
+    let elapsed = elapsed_days as f64 / total_days as f64;
+    // Nothing can be projected before the period starts
+    let projected_m3 = if elapsed_days == 0 {
+        delivered_m3.max(contract.volume_m3)
+    } else {
+        delivered_m3 / elapsed_days as f64 * total_days as f64
+    };
+    let shortfall_m3 = (contract.volume_m3 - projected_m3).max(0.0);
+
+    CommitmentProgress {
+        as_of,
+        elapsed,
+        delivered_m3,
+        expected_m3: contract.volume_m3 * elapsed,
+        projected_m3,
+        shortfall_m3,
+        at_risk: shortfall_m3 > contract.volume_m3 * SHORTFALL_TOLERANCE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn contract(volume_m3: f64) -> SupplyContract {
+        SupplyContract {
+            id: Uuid::new_v4(),
+            org_id: Uuid::new_v4(),
+            customer_id: Uuid::new_v4(),
+            reference: "SC-1".into(),
+            product: "spruce sawlog".into(),
+            volume_m3,
+            price_per_m3: 62.0,
+            starts_on: date(1, 1),
+            ends_on: date(1, 10),
+            notes: None,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_progress_projects_the_rate_so_far() {
+        let on_track = progress(&contract(1000.0), 400.0, date(1, 4));
+        assert_eq!(on_track.elapsed, 0.4);
+        assert_eq!(on_track.expected_m3, 400.0);
+        assert_eq!((on_track.projected_m3, on_track.shortfall_m3, on_track.at_risk), (1000.0, 0.0, false));
+
+        let behind = progress(&contract(1000.0), 200.0, date(1, 4));
+        assert_eq!((behind.projected_m3, behind.shortfall_m3, behind.at_risk), (500.0, 500.0, true));
+
+        // Within the tolerance
+        let close = progress(&contract(1000.0), 380.0, date(1, 4));
+        assert_eq!(close.shortfall_m3, 50.0);
+        assert!(!close.at_risk);
+    }
+
+    #[test]
+    fn test_progress_outside_the_period() {
+        let early = progress(&contract(1000.0), 0.0, NaiveDate::from_ymd_opt(2024, 12, 31).unwrap());
+        assert_eq!((early.elapsed, early.projected_m3, early.at_risk), (0.0, 1000.0, false));
+
+        let ended = progress(&contract(1000.0), 900.0, date(2, 1));
+        assert_eq!((ended.elapsed, ended.shortfall_m3, ended.at_risk), (1.0, 100.0, true));
+    }
+}
@@ -0,0 +1,13 @@
+//! Customers and supply contracts
+//!
+//! Each supply contract commits to delivering a volume of a product to a
+//! customer over a period. Deliveries are compared against the commitment
+//! as the period runs: the volume expected by now if deliveries were
+//! spread evenly, and the volume projected by the end at the rate so far,
+//! flagging contracts heading for a shortfall.
+
+mod commitment;
+mod service;
+
+pub use commitment::{ledger, progress, CommitmentProgress, DeliveryLedger, NoDeliveries, SHORTFALL_TOLERANCE};
+pub use service::CustomerService;
@@ -0,0 +1,277 @@
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use super::commitment::{ledger, progress, CommitmentProgress, DeliveryLedger};
+use crate::{
+    api::{
+        resources::customer::dto::{SaveCustomerInput, SaveSupplyContractInput},
+        utils::PaginationParams,
+    },
+    db::{
+        count::RowCount,
+        models::{Customer, FieldChange, SupplyContract},
+        repositories::{CustomerRepository, HistoryRepositoryImpl},
+    },
+    domain::history::{HistoryService, Tracked},
+    error::{ApiError, ErrorContext, Result},
+};
+
+/// Service for customers, their supply contracts and how deliveries
+/// compare with them
+pub struct CustomerService<R: CustomerRepository + Send + Sync> {
+    repository: R,
+    ledger: Arc<dyn DeliveryLedger>,
+    history: HistoryService<HistoryRepositoryImpl>,
+}
+
+fn invalid_input(e: validator::ValidationErrors) -> ApiError {
+    ApiError::validation_with_context(
+        "Invalid input",
+        ErrorContext::new()
+            .with_message_key("INVALID_INPUT")
+            .with_details(json!(e))
+    )
+}
+
+/// Trims a text field, dropping it when blank
+fn optional(value: Option<String>) -> Option<String> {
+    value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+impl<R: CustomerRepository + Send + Sync> CustomerService<R> {
+    pub fn new(repository: R) -> Self {
+        Self::with_ledger(repository, ledger())
+    }
+
+    /// Creates a service comparing commitments with the given deliveries
+    pub fn with_ledger(repository: R, ledger: Arc<dyn DeliveryLedger>) -> Self {
+        Self {
+            repository,
+            ledger,
+            history: HistoryService::new(HistoryRepositoryImpl),
+        }
+    }
+
+    fn customer(org_id: Uuid, input: SaveCustomerInput) -> Result<Customer> {
+        ValidatorValidate::validate(&input).map_err(invalid_input)?;
+        let now = Utc::now();
+        Ok(Customer {
+            id: Uuid::new_v4(),
+            org_id,
+            name: input.name.trim().to_string(),
+            contact_name: optional(input.contact_name),
+            email: optional(input.email),
+            phone_number: optional(input.phone_number),
+            address: optional(input.address),
+            notes: optional(input.notes),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    fn contract(customer: &Customer, created_by: Option<Uuid>, input: SaveSupplyContractInput) -> Result<SupplyContract> {
+        ValidatorValidate::validate(&input).map_err(invalid_input)?;
+        if !(input.volume_m3.is_finite() && input.volume_m3 > 0.0) {
+            return Err(ApiError::validation("The committed volume must be positive", None));
+        }
+        if !(input.price_per_m3.is_finite() && input.price_per_m3 >= 0.0) {
+            return Err(ApiError::validation("The price must not be negative", None));
+        }
+        if input.ends_on < input.starts_on {
+            return Err(ApiError::validation("The contract cannot end before it starts", None));
+        }
+        let now = Utc::now();
+        Ok(SupplyContract {
+            id: Uuid::new_v4(),
+            org_id: customer.org_id,
+            customer_id: customer.id,
+            reference: input.reference.trim().to_string(),
+            product: input.product.trim().to_string(),
+            volume_m3: input.volume_m3,
+            price_per_m3: input.price_per_m3,
+            starts_on: input.starts_on,
+            ends_on: input.ends_on,
+            notes: optional(input.notes),
+            created_by,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Creates a customer
+    pub async fn create_customer(&self, conn: &mut PgConnection, org_id: Uuid, input: SaveCustomerInput) -> Result<Customer> {
+        let customer = Self::customer(org_id, input)?;
+        let customer = self.repository.create_customer(conn, &customer).await?;
+        info!(customer_id = %customer.id, org_id = %org_id, "Created customer '{}'", customer.name);
+        Ok(customer)
+    }
+
+    /// Replaces a customer's details, recording the fields changed
+    pub async fn update_customer(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        customer_id: Uuid,
+        changed_by: Option<Uuid>,
+        input: SaveCustomerInput,
+    ) -> Result<Customer> {
+        let existing = self.repository.find_customer(conn, org_id, customer_id).await?;
+        let customer = Customer {
+            id: existing.id,
+            created_at: existing.created_at,
+            ..Self::customer(org_id, input)?
+        };
+        let customer = self.repository.update_customer(conn, org_id, &customer).await?;
+        self.history.record(conn, org_id, &existing, &customer, changed_by).await?;
+        Ok(customer)
+    }
+
+    /// The changes made to a customer, oldest first
+    pub async fn customer_history(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        customer_id: Uuid,
+    ) -> Result<Vec<(FieldChange, Option<String>)>> {
+        let customer = self.repository.find_customer(conn, org_id, customer_id).await?;
+        self.history.list(conn, org_id, Customer::RESOURCE, customer.id).await
+    }
+
+    /// Deletes a customer that has no supply contracts
+    pub async fn delete_customer(&self, conn: &mut PgConnection, org_id: Uuid, customer_id: Uuid) -> Result<()> {
+        self.repository.delete_customer(conn, org_id, customer_id).await?;
+        info!(customer_id = %customer_id, org_id = %org_id, "Deleted customer");
+        Ok(())
+    }
+
+    /// Gets a customer
+    pub async fn get_customer(&self, conn: &mut PgConnection, org_id: Uuid, customer_id: Uuid) -> Result<Customer> {
+        self.repository.find_customer(conn, org_id, customer_id).await
+    }
+
+    /// Lists an organization's customers by name
+    pub async fn list_customers(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<Customer>, RowCount)> {
+        let customers = self.repository.list_customers(conn, org_id, pagination).await?;
+        let total = self.repository.count_customers(conn, org_id).await?;
+        Ok((customers, total))
+    }
+
+    /// Creates a supply contract for a customer
+    pub async fn create_contract(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        customer_id: Uuid,
+        created_by: Option<Uuid>,
+        input: SaveSupplyContractInput,
+    ) -> Result<SupplyContract> {
+        let customer = self.repository.find_customer(conn, org_id, customer_id).await?;
+        let contract = Self::contract(&customer, created_by, input)?;
+        let contract = self.repository.create_contract(conn, &contract).await?;
+        info!(contract_id = %contract.id, customer_id = %customer.id, org_id = %org_id, "Created supply contract {}", contract.reference);
+        Ok(contract)
+    }
+
+    /// Replaces the terms of a supply contract
+    pub async fn update_contract(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        contract_id: Uuid,
+        changed_by: Option<Uuid>,
+        input: SaveSupplyContractInput,
+    ) -> Result<SupplyContract> {
+        let existing = self.repository.find_contract(conn, org_id, contract_id).await?;
+        let customer = self.repository.find_customer(conn, org_id, existing.customer_id).await?;
+        let contract = SupplyContract {
+            id: existing.id,
+            created_at: existing.created_at,
+            ..Self::contract(&customer, existing.created_by, input)?
+        };
+        let contract = self.repository.update_contract(conn, org_id, &contract).await?;
+        self.history.record(conn, org_id, &existing, &contract, changed_by).await?;
+        Ok(contract)
+    }
+
+    /// The changes made to a supply contract, oldest first
+    pub async fn contract_history(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        contract_id: Uuid,
+    ) -> Result<Vec<(FieldChange, Option<String>)>> {
+        let contract = self.repository.find_contract(conn, org_id, contract_id).await?;
+        self.history.list(conn, org_id, SupplyContract::RESOURCE, contract.id).await
+    }
+
+    /// Deletes a supply contract
+    pub async fn delete_contract(&self, conn: &mut PgConnection, org_id: Uuid, contract_id: Uuid) -> Result<()> {
+        self.repository.delete_contract(conn, org_id, contract_id).await?;
+        info!(contract_id = %contract_id, org_id = %org_id, "Deleted supply contract");
+        Ok(())
+    }
+
+    /// Gets a supply contract
+    pub async fn get_contract(&self, conn: &mut PgConnection, org_id: Uuid, contract_id: Uuid) -> Result<SupplyContract> {
+        self.repository.find_contract(conn, org_id, contract_id).await
+    }
+
+    /// Lists the supply contracts of a customer, latest period first
+    pub async fn list_contracts(&self, conn: &mut PgConnection, org_id: Uuid, customer_id: Uuid) -> Result<Vec<SupplyContract>> {
+        let customer = self.repository.find_customer(conn, org_id, customer_id).await?;
+        self.repository.list_contracts(conn, org_id, customer.id).await
+    }
+
+    fn measure(&self, conn: &mut PgConnection, contract: &SupplyContract, as_of: NaiveDate) -> Result<CommitmentProgress> {
+        let until = as_of.min(contract.ends_on);
+        let delivered = if until < contract.starts_on {
+            0.0
+        } else {
+            self.ledger
+                .delivered(conn, contract.org_id, contract.customer_id, &contract.product, contract.starts_on, until)
+                .map_err(|e| ApiError::database_error(format!("Failed to read deliveries: {}", e), None))?
+        };
+        Ok(progress(contract, delivered, as_of))
+    }
+
+    /// How deliveries on a contract compare with its commitment on a day
+    pub async fn commitment(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        contract_id: Uuid,
+        as_of: NaiveDate,
+    ) -> Result<(SupplyContract, CommitmentProgress)> {
+        let contract = self.repository.find_contract(conn, org_id, contract_id).await?;
+        let progress = self.measure(conn, &contract, as_of)?;
+        Ok((contract, progress))
+    }
+
+    /// How deliveries compare with the commitments of every contract
+    /// running on a day
+    pub async fn commitments(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        as_of: NaiveDate,
+    ) -> Result<Vec<(SupplyContract, CommitmentProgress)>> {
+        let contracts = self.repository.active_contracts(conn, org_id, as_of).await?;
+        let mut commitments = Vec::with_capacity(contracts.len());
+        for contract in contracts {
+            let progress = self.measure(conn, &contract, as_of)?;
+            commitments.push((contract, progress));
+        }
+        Ok(commitments)
+    }
+}
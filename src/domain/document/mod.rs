@@ -0,0 +1,14 @@
+//! Versioned documents
+//!
+//! Harvest plans, permits, assessments and other documents are attached to
+//! blocks and permits and kept as versions: uploading a document again adds
+//! a version, and restoring an older version adds its file back as the
+//! latest. Each block status requires a checklist of document categories
+//! to be on file, and regulatory categories are retained for a number of
+//! years after their latest version, during which they cannot be deleted.
+
+mod rules;
+mod service;
+
+pub use rules::{checklist, required_categories, retain_until, ChecklistItem, BLOCK_STATUSES, MAX_DOCUMENT_BYTES};
+pub use service::{DocumentService, DocumentUpload, NewDocument};
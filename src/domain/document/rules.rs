@@ -0,0 +1,94 @@
+use chrono::{DateTime, Months, Utc};
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// Largest document file accepted
+pub const MAX_DOCUMENT_BYTES: usize = 25 * 1024 * 1024;
+
+/// Block statuses with a document checklist, in lifecycle order
+pub const BLOCK_STATUSES: [&str; 4] = ["planned", "approved", "active", "completed"];
+
+/// Categories a block needs on file by the status it enters; every status
+/// also needs those of the statuses before it
+const CHECKLISTS: [(&str, &[&str]); 4] = [
+    ("planned", &["harvest_plan"]),
+    ("approved", &["harvest_permit", "environmental_assessment"]),
+    ("active", &["safety_plan"]),
+    ("completed", &["completion_report"]),
+];
+
+/// Regulatory categories and how many years they are retained after their
+/// latest version
+const RETENTION_YEARS: [(&str, u32); 4] = [
+    ("harvest_permit", 10),
+    ("environmental_assessment", 10),
+    ("completion_report", 10),
+    ("safety_plan", 5),
+];
+
+/// A category of a block status checklist
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ChecklistItem {
+    pub category: String,
+    /// Whether a document of the category is on file
+    pub present: bool,
+}
+
+/// Categories required on file for a block in `status`, `None` for a
+/// status without a checklist
+pub fn required_categories(status: &str) -> Option<Vec<&'static str>> {
+    let position = BLOCK_STATUSES.iter().position(|candidate| *candidate == status)?;
+    Some(CHECKLISTS[..=position].iter().flat_map(|(_, categories)| categories.iter().copied()).collect())
+}
+
+/// The checklist of a block in `status` given the categories on file
+pub fn checklist(status: &str, present: &[String]) -> Option<Vec<ChecklistItem>> {
+    let items = required_categories(status)?
+        .into_iter()
+        .map(|category| ChecklistItem {
+            category: category.to_string(),
+            present: present.iter().any(|present| present == category),
+        })
+        .collect();
+    Some(items)
+}
+
+/// Until when a document of `category` versioned at `versioned_at` must be
+/// kept, `None` for categories without a retention rule
+pub fn retain_until(category: &str, versioned_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let (_, years) = RETENTION_YEARS.iter().find(|(regulated, _)| *regulated == category)?;
+    versioned_at.checked_add_months(Months::new(years * 12))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_checklists_accumulate() {
+        assert_eq!(required_categories("planned"), Some(vec!["harvest_plan"]));
+        assert_eq!(
+            required_categories("active"),
+            Some(vec!["harvest_plan", "harvest_permit", "environmental_assessment", "safety_plan"])
+        );
+        assert_eq!(required_categories("archived"), None);
+
+        let items = checklist("approved", &["harvest_permit".to_string(), "photo".to_string()]).unwrap();
+        let present: Vec<_> = items.iter().map(|item| (item.category.as_str(), item.present)).collect();
+        assert_eq!(
+            present,
+            vec![("harvest_plan", false), ("harvest_permit", true), ("environmental_assessment", false)]
+        );
+    }
+
+    #[test]
+    fn test_retention() {
+        let at = Utc.with_ymd_and_hms(2024, 2, 29, 12, 0, 0).unwrap();
+        assert_eq!(retain_until("harvest_permit", at), Some(Utc.with_ymd_and_hms(2034, 2, 28, 12, 0, 0).unwrap()));
+        assert_eq!(retain_until("photo", at), None);
+    }
+}
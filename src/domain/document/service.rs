@@ -0,0 +1,276 @@
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::rules::{checklist, retain_until, ChecklistItem, BLOCK_STATUSES, MAX_DOCUMENT_BYTES};
+use crate::{
+    db::{
+        models::{Document, DocumentSubject, DocumentVersion},
+        repositories::DocumentRepository,
+    },
+    domain::activity::{ActivityKind, ActivityLog, NewActivity},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::ObjectStorage,
+};
+
+/// An uploaded document file
+#[derive(Debug, Clone)]
+pub struct DocumentUpload {
+    pub filename: String,
+    pub content_type: String,
+    pub bytes: Bytes,
+}
+
+/// What a new document is and what it is attached to
+#[derive(Debug, Clone)]
+pub struct NewDocument {
+    pub subject: DocumentSubject,
+    pub subject_id: Uuid,
+    /// Kind of document, e.g. `harvest_permit`
+    pub category: String,
+    /// Title shown for the document, the file name when unset
+    pub title: Option<String>,
+}
+
+/// Object storage key of a version's file
+fn file_key(org_id: Uuid, document_id: Uuid, version_id: Uuid) -> String {
+    format!("documents/{}/{}/{}", org_id, document_id, version_id)
+}
+
+/// Service for versioned documents and block document checklists
+pub struct DocumentService<R: DocumentRepository + Send + Sync> {
+    repository: R,
+    storage: ObjectStorage,
+}
+
+impl<R: DocumentRepository + Send + Sync> DocumentService<R> {
+    pub fn new(repository: R, storage: ObjectStorage) -> Self {
+        Self { repository, storage }
+    }
+
+    /// Checks an upload and stores its file as a new version of a document
+    async fn store(&self, org_id: Uuid, document_id: Uuid, uploaded_by: Option<Uuid>, upload: DocumentUpload) -> Result<DocumentVersion> {
+        let filename = upload.filename.trim();
+        if filename.is_empty() || filename.len() > 255 {
+            return Err(ApiError::validation("A file name of up to 255 characters is required", None));
+        }
+        if upload.bytes.is_empty() {
+            return Err(ApiError::validation("The file is empty", None));
+        }
+        if upload.bytes.len() > MAX_DOCUMENT_BYTES {
+            return Err(ApiError::validation(
+                format!("A file may be at most {} MiB", MAX_DOCUMENT_BYTES / (1024 * 1024)),
+                None,
+            ));
+        }
+
+        let id = Uuid::new_v4();
+        let key = file_key(org_id, document_id, id);
+        let byte_size = upload.bytes.len() as i64;
+        self.storage.put(&key, upload.bytes).await?;
+        Ok(DocumentVersion {
+            id,
+            document_id,
+            version: 1,
+            filename: filename.to_string(),
+            content_type: upload.content_type,
+            byte_size,
+            file_key: key,
+            restored_from: None,
+            uploaded_by,
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Attaches a new document to a block, permit or certification
+    pub async fn upload(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        created_by: Option<Uuid>,
+        new: NewDocument,
+        upload: DocumentUpload,
+    ) -> Result<(Document, DocumentVersion)> {
+        let category = new.category.trim().to_lowercase();
+        if category.is_empty() || category.len() > 100 {
+            return Err(ApiError::validation("A category of up to 100 characters is required", None));
+        }
+        let title = new
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .unwrap_or(upload.filename.trim())
+            .to_string();
+        if title.len() > 255 {
+            return Err(ApiError::validation("A title may be at most 255 characters", None));
+        }
+
+        let id = Uuid::new_v4();
+        let version = self.store(org_id, id, created_by, upload).await?;
+        let document = Document {
+            id,
+            org_id,
+            subject_type: new.subject.as_str().to_string(),
+            subject_id: new.subject_id,
+            retain_until: retain_until(&category, version.created_at),
+            category,
+            title,
+            current_version: version.version,
+            created_by,
+            created_at: version.created_at,
+            updated_at: version.created_at,
+        };
+        let (document, version) = self.repository.create(conn, &document, &version).await?;
+        info!(document_id = %document.id, org_id = %org_id, "Uploaded {} document '{}'", document.category, document.title);
+        ActivityLog::record(
+            conn,
+            org_id,
+            created_by,
+            NewActivity::new(
+                ActivityKind::DocumentUploaded,
+                "document",
+                document.id,
+                format!("Uploaded {} to a {}", document.title, document.subject_type),
+            )
+            .with_data(json!({
+                "subject_type": document.subject_type,
+                "subject_id": document.subject_id,
+                "category": document.category,
+            })),
+        )
+        .await;
+        Ok((document, version))
+    }
+
+    /// Uploads a new version of a document
+    pub async fn add_version(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        document_id: Uuid,
+        uploaded_by: Option<Uuid>,
+        upload: DocumentUpload,
+    ) -> Result<(Document, DocumentVersion)> {
+        let document = self.repository.find(conn, org_id, document_id).await?;
+        let version = self.store(org_id, document.id, uploaded_by, upload).await?;
+        let retain = retain_until(&document.category, version.created_at);
+        let (document, version) = self.repository.add_version(conn, org_id, &version, retain).await?;
+        info!(document_id = %document.id, org_id = %org_id, "Uploaded version {} of document '{}'", version.version, document.title);
+        Ok((document, version))
+    }
+
+    /// Restores an older version by adding its file back as the latest
+    pub async fn restore(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        document_id: Uuid,
+        version: i32,
+        restored_by: Option<Uuid>,
+    ) -> Result<(Document, DocumentVersion)> {
+        let document = self.repository.find(conn, org_id, document_id).await?;
+        let restored = self.repository.find_version(conn, document.id, version).await?;
+        if restored.version == document.current_version {
+            return Err(ApiError::new(
+                ErrorCode::Conflict,
+                format!("Version {} is already the latest", version),
+                ErrorContext::new(),
+            ));
+        }
+
+        let now = Utc::now();
+        let (document, version) = self
+            .repository
+            .add_version(
+                conn,
+                org_id,
+                &DocumentVersion {
+                    id: Uuid::new_v4(),
+                    restored_from: Some(restored.version),
+                    uploaded_by: restored_by,
+                    created_at: now,
+                    ..restored
+                },
+                retain_until(&document.category, now),
+            )
+            .await?;
+        info!(document_id = %document.id, org_id = %org_id, "Restored version {} of document '{}'", version.restored_from.unwrap_or_default(), document.title);
+        Ok((document, version))
+    }
+
+    /// A document with its versions, latest first
+    pub async fn get(&self, conn: &mut PgConnection, org_id: Uuid, document_id: Uuid) -> Result<(Document, Vec<DocumentVersion>)> {
+        let document = self.repository.find(conn, org_id, document_id).await?;
+        let versions = self.repository.versions(conn, document.id).await?;
+        Ok((document, versions))
+    }
+
+    /// Lists the documents attached to a block, permit or certification
+    pub async fn list(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        subject: DocumentSubject,
+        subject_id: Uuid,
+    ) -> Result<Vec<Document>> {
+        self.repository.list(conn, org_id, subject, subject_id).await
+    }
+
+    /// The file of a version of a document
+    pub async fn file(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        document_id: Uuid,
+        version: i32,
+    ) -> Result<(DocumentVersion, Bytes)> {
+        let document = self.repository.find(conn, org_id, document_id).await?;
+        let version = self.repository.find_version(conn, document.id, version).await?;
+        let bytes = self.storage.get(&version.file_key).await?;
+        Ok((version, bytes))
+    }
+
+    /// Deletes a document with all its versions, unless it is still under
+    /// regulatory retention
+    pub async fn delete(&self, conn: &mut PgConnection, org_id: Uuid, document_id: Uuid, now: DateTime<Utc>) -> Result<()> {
+        let document = self.repository.find(conn, org_id, document_id).await?;
+        if let Some(retain_until) = document.retain_until.filter(|retain_until| *retain_until > now) {
+            return Err(ApiError::new(
+                ErrorCode::Conflict,
+                format!("{} documents are retained until {}", document.category, retain_until.date_naive()),
+                ErrorContext::new().with_details(json!({ "retain_until": retain_until })),
+            ));
+        }
+
+        let keys = self.repository.delete(conn, org_id, document.id).await?;
+        for key in keys {
+            // The rows are gone, so a file left behind is only wasted space
+            if let Err(e) = self.storage.delete(&key).await {
+                warn!(document_id = %document.id, error = %e, "Failed to delete document file {}", key);
+            }
+        }
+        info!(document_id = %document.id, org_id = %org_id, "Deleted document '{}'", document.title);
+        Ok(())
+    }
+
+    /// The document checklist of a block in `status`
+    pub async fn checklist(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        block_id: Uuid,
+        status: &str,
+    ) -> Result<Vec<ChecklistItem>> {
+        let present = self.repository.categories(conn, org_id, DocumentSubject::Block, block_id).await?;
+        checklist(status, &present).ok_or_else(|| {
+            ApiError::validation(
+                format!("No document checklist for block status {}", status),
+                Some(json!({ "available": BLOCK_STATUSES })),
+            )
+        })
+    }
+}
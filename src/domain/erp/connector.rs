@@ -0,0 +1,159 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::sftp::{self, SftpTarget},
+    utils::ErpConfig,
+};
+
+/// A written export handed to a connector
+#[derive(Debug, Clone)]
+pub struct ExportFile {
+    pub export_id: Uuid,
+    pub filename: String,
+    pub bytes: Bytes,
+}
+
+/// Delivers exported files to an ERP
+///
+/// Deliveries are retried by the job queue, so delivering the same file
+/// twice must be harmless; connectors overwrite rather than append.
+#[async_trait]
+pub trait ExportConnector: Send + Sync {
+    /// Stable connector name, stored with connections
+    fn kind(&self) -> &'static str;
+
+    /// Checks a connection's settings before they are saved
+    fn check_settings(&self, settings: &Value) -> Result<()>;
+
+    /// Delivers a file, returning where it was put
+    async fn deliver(&self, settings: &Value, file: &ExportFile) -> Result<String>;
+}
+
+fn invalid_settings(connector: &str, reason: impl std::fmt::Display) -> ApiError {
+    ApiError::validation_with_context(
+        format!("Invalid {} settings: {}", connector, reason),
+        ErrorContext::new().with_details(json!({
+            "field": "settings",
+            "code": "INVALID_SETTINGS",
+            "reason": reason.to_string()
+        })),
+    )
+}
+
+/// Keeps files for the ERP to download from `/v1/erp/exports/{id}/file`
+pub struct DownloadConnector;
+
+#[async_trait]
+impl ExportConnector for DownloadConnector {
+    fn kind(&self) -> &'static str {
+        "csv_download"
+    }
+
+    fn check_settings(&self, settings: &Value) -> Result<()> {
+        match settings {
+            Value::Null => Ok(()),
+            Value::Object(settings) if settings.is_empty() => Ok(()),
+            _ => Err(invalid_settings(self.kind(), "this connector takes no settings")),
+        }
+    }
+
+    async fn deliver(&self, _settings: &Value, file: &ExportFile) -> Result<String> {
+        Ok(format!("/v1/erp/exports/{}/file", file.export_id))
+    }
+}
+
+/// Settings of an SFTP connection
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SftpSettings {
+    host: String,
+    #[serde(default = "default_sftp_port")]
+    port: u16,
+    username: String,
+    /// Directory files are written to, the login directory when unset
+    #[serde(default)]
+    directory: String,
+    /// `SHA256:` fingerprint of the server's host key
+    host_key_sha256: String,
+}
+
+fn default_sftp_port() -> u16 {
+    22
+}
+
+/// Uploads files to the ERP's SFTP server with the deployment's key (see
+/// `erp.sftp_private_key_path`)
+pub struct SftpConnector {
+    private_key: Option<PathBuf>,
+    timeout: Duration,
+}
+
+impl SftpConnector {
+    pub fn new(config: &ErpConfig) -> Self {
+        Self {
+            private_key: config.sftp_private_key_path.as_ref().map(PathBuf::from),
+            timeout: Duration::from_secs(config.timeout_secs.max(1)),
+        }
+    }
+
+    fn settings(&self, settings: &Value) -> Result<SftpSettings> {
+        let settings: SftpSettings =
+            serde_json::from_value(settings.clone()).map_err(|e| invalid_settings(self.kind(), e))?;
+        if settings.host.trim().is_empty() || settings.username.trim().is_empty() {
+            return Err(invalid_settings(self.kind(), "host and username are required"));
+        }
+        if settings.host_key_sha256.trim().is_empty() {
+            return Err(invalid_settings(self.kind(), "host_key_sha256 is required"));
+        }
+        Ok(settings)
+    }
+}
+
+#[async_trait]
+impl ExportConnector for SftpConnector {
+    fn kind(&self) -> &'static str {
+        "csv_sftp"
+    }
+
+    fn check_settings(&self, settings: &Value) -> Result<()> {
+        self.settings(settings).map(|_| ())
+    }
+
+    async fn deliver(&self, settings: &Value, file: &ExportFile) -> Result<String> {
+        let settings = self.settings(settings)?;
+        let private_key = self.private_key.clone().ok_or_else(|| {
+            ApiError::configuration_error("erp.sftp_private_key_path is not set")
+        })?;
+        let remote_path = match settings.directory.trim_end_matches('/') {
+            "" => file.filename.clone(),
+            directory => format!("{}/{}", directory, file.filename),
+        };
+        let location = format!("sftp://{}:{}/{}", settings.host, settings.port, remote_path.trim_start_matches('/'));
+        let target = SftpTarget {
+            host: settings.host,
+            port: settings.port,
+            username: settings.username,
+            host_key_sha256: settings.host_key_sha256,
+        };
+        let timeout = self.timeout;
+        let bytes = file.bytes.clone();
+        tokio::task::spawn_blocking(move || sftp::upload(&target, &private_key, timeout, &remote_path, &bytes))
+            .await
+            .map_err(|e| {
+                ApiError::new(ErrorCode::InternalError, format!("SFTP upload panicked: {}", e), ErrorContext::new())
+            })??;
+        Ok(location)
+    }
+}
+
+/// Connectors available for ERP connections
+pub fn connectors(config: &ErpConfig) -> Vec<Arc<dyn ExportConnector>> {
+    vec![Arc::new(DownloadConnector), Arc::new(SftpConnector::new(config))]
+}
@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use super::source::{ExportRecord, ExportSource};
+use crate::error::{ApiError, ErrorCode, ErrorContext, Result};
+
+/// A column of an exported file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ExportColumn {
+    /// Heading the ERP expects
+    pub header: String,
+    /// Field of the source the column is filled from
+    pub field: String,
+}
+
+/// Columns exported per source; a connection exports the sources it maps,
+/// and an empty list exports every field under its own name
+pub type ExportMapping = BTreeMap<String, Vec<ExportColumn>>;
+
+fn invalid(problems: Vec<String>) -> ApiError {
+    ApiError::validation_with_context(
+        "Invalid export mapping",
+        ErrorContext::new().with_details(json!({
+            "field": "mappings",
+            "code": "INVALID_MAPPING",
+            "problems": problems
+        })),
+    )
+}
+
+/// Checks that every mapped source and field exists and that no source has
+/// an empty or repeated heading
+pub fn check_mapping(mapping: &ExportMapping, sources: &[std::sync::Arc<dyn ExportSource>]) -> Result<()> {
+    let mut problems = Vec::new();
+    if mapping.is_empty() {
+        problems.push("Map at least one source".to_string());
+    }
+    for (source_name, columns) in mapping {
+        let Some(source) = sources.iter().find(|source| source.name() == source_name) else {
+            problems.push(format!("'{}' is not an export source", source_name));
+            continue;
+        };
+        for (index, column) in columns.iter().enumerate() {
+            if column.header.trim().is_empty() {
+                problems.push(format!("{}: column {} has no heading", source_name, index + 1));
+            } else if columns[..index].iter().any(|other| other.header == column.header) {
+                problems.push(format!("{}: heading '{}' is used twice", source_name, column.header));
+            }
+            if !source.fields().contains(&column.field.as_str()) {
+                problems.push(format!("{}: '{}' is not a field", source_name, column.field));
+            }
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid(problems))
+    }
+}
+
+/// Columns exported for a source, every field when none are mapped
+pub fn columns(mapping: &[ExportColumn], source: &dyn ExportSource) -> Vec<ExportColumn> {
+    if !mapping.is_empty() {
+        return mapping.to_vec();
+    }
+    source
+        .fields()
+        .iter()
+        .map(|field| ExportColumn { header: field.to_string(), field: field.to_string() })
+        .collect()
+}
+
+/// One CSV cell; text starting like a formula is prefixed with `'`
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) if text.starts_with(['=', '+', '-', '@']) => format!("'{}", text),
+        Some(Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+/// Writes records as CSV with a heading row
+pub fn to_csv(columns: &[ExportColumn], records: &[ExportRecord]) -> Result<Vec<u8>> {
+    let failed = |e: csv::Error| {
+        ApiError::new(ErrorCode::InternalError, format!("Failed to write export: {}", e), ErrorContext::new())
+    };
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(Vec::new());
+    writer
+        .write_record(columns.iter().map(|column| column.header.as_str()))
+        .map_err(failed)?;
+    for record in records {
+        writer
+            .write_record(columns.iter().map(|column| cell(record.get(&column.field))))
+            .map_err(failed)?;
+    }
+    writer.into_inner().map_err(|e| {
+        ApiError::new(ErrorCode::InternalError, format!("Failed to write export: {}", e.error()), ErrorContext::new())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use diesel::{PgConnection, QueryResult};
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    struct Invoices;
+
+    impl ExportSource for Invoices {
+        fn name(&self) -> &'static str {
+            "invoices"
+        }
+
+        fn fields(&self) -> &'static [&'static str] {
+            &["number", "contractor", "amount"]
+        }
+
+        fn records(&self, _: &mut PgConnection, _: Uuid, _: Option<DateTime<Utc>>, _: DateTime<Utc>) -> QueryResult<Vec<ExportRecord>> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn column(header: &str, field: &str) -> ExportColumn {
+        ExportColumn { header: header.into(), field: field.into() }
+    }
+
+    #[test]
+    fn test_check_mapping() {
+        let sources: Vec<Arc<dyn ExportSource>> = vec![Arc::new(Invoices)];
+        assert!(check_mapping(&ExportMapping::from([("invoices".into(), vec![])]), &sources).is_ok());
+        assert!(check_mapping(&ExportMapping::new(), &sources).is_err());
+        assert!(check_mapping(&ExportMapping::from([("loads".into(), vec![])]), &sources).is_err());
+        assert!(check_mapping(&ExportMapping::from([("invoices".into(), vec![column("No", "id")])]), &sources).is_err());
+        assert!(check_mapping(
+            &ExportMapping::from([("invoices".into(), vec![column("No", "number"), column("No", "amount")])]),
+            &sources
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_to_csv_uses_mapped_headings() {
+        let record = json!({ "number": "INV-1", "contractor": "=Logging, Inc", "amount": 1250.5 });
+        let records = vec![record.as_object().unwrap().clone()];
+
+        let mapped = to_csv(&[column("Amount", "amount"), column("Vendor", "contractor")], &records).unwrap();
+        assert_eq!(String::from_utf8(mapped).unwrap(), "Amount,Vendor\r\n1250.5,\"'=Logging, Inc\"\r\n");
+
+        let all = to_csv(&columns(&[], &Invoices), &records).unwrap();
+        assert_eq!(String::from_utf8(all).unwrap(), "number,contractor,amount\r\nINV-1,\"'=Logging, Inc\",1250.5\r\n");
+    }
+}
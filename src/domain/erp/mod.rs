@@ -0,0 +1,19 @@
+//! ERP exports
+//!
+//! An organization connects its ERP by choosing a connector (a CSV file
+//! kept for download, or uploaded to its SFTP server) and mapping the
+//! fields of each export source (approved contractor invoices, delivered
+//! volume summaries) to the columns its ERP expects. Exports run on request
+//! or on the connection's cron schedule; each one is a CSV file of the
+//! source's records since the previous export, delivered through the job
+//! queue so failures are retried and every attempt is tracked.
+
+mod connector;
+mod mapping;
+mod service;
+mod source;
+
+pub use connector::{connectors, DownloadConnector, ExportConnector, ExportFile, SftpConnector};
+pub use mapping::{check_mapping, columns, to_csv, ExportColumn, ExportMapping};
+pub use service::{export_filename, export_key, ErpService, ERP_EXPORT_JOB};
+pub use source::{sources, ExportRecord, ExportSource};
@@ -0,0 +1,344 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use super::{
+    connector::ExportConnector,
+    mapping::{check_mapping, columns, to_csv, ExportMapping},
+    source::{sources, ExportSource},
+};
+use crate::{
+    api::{resources::erp::dto::SaveErpConnectionInput, utils::PaginationParams},
+    db::{
+        count::RowCount,
+        models::{ErpConnection, ErpExport, ErpExportStatus},
+        repositories::ErpRepository,
+    },
+    error::{ApiError, ErrorContext, Result},
+    infrastructure::ObjectStorage,
+    jobs::{
+        queue,
+        scheduler::{next_run, parse_schedule},
+    },
+    utils::QueueConfig,
+};
+
+/// Kind of the queue job that delivers an export
+pub const ERP_EXPORT_JOB: &str = "erp_export";
+
+/// Due connections exported per scheduled run
+const BATCH_SIZE: i64 = 100;
+
+/// Object storage key of a written export
+pub fn export_key(export: &ErpExport) -> String {
+    format!("erp/{}/{}/{}.csv", export.org_id, export.connection_id, export.id)
+}
+
+/// Name of an export's file at the ERP, e.g.
+/// `contractor_invoices-20250601T060000Z.csv`
+pub fn export_filename(export: &ErpExport) -> String {
+    format!("{}-{}.csv", export.source, export.period_end.format("%Y%m%dT%H%M%SZ"))
+}
+
+/// Service for an organization's ERP connections and their exports
+///
+/// Exports are written here and delivered by
+/// [`crate::jobs::erp_export::ErpExportDelivery`].
+pub struct ErpService<R: ErpRepository + Send + Sync> {
+    repository: R,
+    storage: ObjectStorage,
+    connectors: Vec<Arc<dyn ExportConnector>>,
+    sources: Vec<Arc<dyn ExportSource>>,
+}
+
+impl<R: ErpRepository + Send + Sync> ErpService<R> {
+    pub fn new(repository: R, storage: ObjectStorage, connectors: Vec<Arc<dyn ExportConnector>>) -> Self {
+        Self::with_sources(repository, storage, connectors, sources())
+    }
+
+    /// Creates a service exporting the given sources
+    pub fn with_sources(
+        repository: R,
+        storage: ObjectStorage,
+        connectors: Vec<Arc<dyn ExportConnector>>,
+        sources: Vec<Arc<dyn ExportSource>>,
+    ) -> Self {
+        Self {
+            repository,
+            storage,
+            connectors,
+            sources,
+        }
+    }
+
+    /// Sources that can be exported
+    pub fn sources(&self) -> &[Arc<dyn ExportSource>] {
+        &self.sources
+    }
+
+    /// Connectors connections can use
+    pub fn connectors(&self) -> &[Arc<dyn ExportConnector>] {
+        &self.connectors
+    }
+
+    fn connector(&self, kind: &str) -> Result<&Arc<dyn ExportConnector>> {
+        self.connectors.iter().find(|connector| connector.kind() == kind).ok_or_else(|| {
+            ApiError::validation_with_context(
+                format!("Unknown connector {}", kind),
+                ErrorContext::new().with_details(json!({
+                    "field": "connector",
+                    "code": "UNKNOWN_CONNECTOR",
+                    "available": self.connectors.iter().map(|connector| connector.kind()).collect::<Vec<_>>()
+                })),
+            )
+        })
+    }
+
+    fn source(&self, name: &str) -> Result<&Arc<dyn ExportSource>> {
+        self.sources
+            .iter()
+            .find(|source| source.name() == name)
+            .ok_or_else(|| ApiError::validation(format!("Unknown export source {}", name), None))
+    }
+
+    fn mapping(connection: &ErpConnection) -> ExportMapping {
+        serde_json::from_value(connection.mappings.clone()).unwrap_or_default()
+    }
+
+    /// Validates input into a new connection
+    fn connection(&self, org_id: Uuid, created_by: Option<Uuid>, input: SaveErpConnectionInput) -> Result<ErpConnection> {
+        if let Err(e) = ValidatorValidate::validate(&input) {
+            return Err(ApiError::validation_with_context(
+                "Invalid input",
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(json!(e))
+            ));
+        }
+        self.connector(&input.connector)?.check_settings(&input.settings)?;
+        check_mapping(&input.mappings, &self.sources)?;
+        let schedule = match input.schedule.as_deref().map(str::trim).filter(|expression| !expression.is_empty()) {
+            None => None,
+            Some(expression) => match parse_schedule(expression)? {
+                Some(cron) => Some((expression.to_string(), cron)),
+                None => {
+                    return Err(ApiError::validation(
+                        "Leave the schedule unset to export on request only",
+                        None,
+                    ))
+                }
+            },
+        };
+        let now = Utc::now();
+        Ok(ErpConnection {
+            id: Uuid::new_v4(),
+            org_id,
+            name: input.name.trim().to_string(),
+            connector: input.connector,
+            settings: input.settings,
+            mappings: json!(input.mappings),
+            next_run_at: schedule.as_ref().map(|(_, cron)| next_run(Some(cron), now)),
+            schedule: schedule.map(|(expression, _)| expression),
+            enabled: input.enabled.unwrap_or(true),
+            created_by,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Creates a connection
+    pub async fn create_connection(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        created_by: Option<Uuid>,
+        input: SaveErpConnectionInput,
+    ) -> Result<ErpConnection> {
+        let connection = self.connection(org_id, created_by, input)?;
+        let connection = self.repository.create_connection(conn, &connection).await?;
+        info!(connection_id = %connection.id, org_id = %org_id, connector = %connection.connector, "Created ERP connection '{}'", connection.name);
+        Ok(connection)
+    }
+
+    /// Replaces a connection's settings; its export history is kept
+    pub async fn update_connection(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        connection_id: Uuid,
+        input: SaveErpConnectionInput,
+    ) -> Result<ErpConnection> {
+        let existing = self.repository.find_connection(conn, org_id, connection_id).await?;
+        let connection = ErpConnection {
+            id: existing.id,
+            created_by: existing.created_by,
+            created_at: existing.created_at,
+            ..self.connection(org_id, existing.created_by, input)?
+        };
+        self.repository.update_connection(conn, org_id, &connection).await
+    }
+
+    /// Deletes a connection with its export history
+    pub async fn delete_connection(&self, conn: &mut PgConnection, org_id: Uuid, connection_id: Uuid) -> Result<()> {
+        self.repository.delete_connection(conn, org_id, connection_id).await?;
+        info!(connection_id = %connection_id, org_id = %org_id, "Deleted ERP connection");
+        Ok(())
+    }
+
+    /// Gets a connection
+    pub async fn get_connection(&self, conn: &mut PgConnection, org_id: Uuid, connection_id: Uuid) -> Result<ErpConnection> {
+        self.repository.find_connection(conn, org_id, connection_id).await
+    }
+
+    /// Lists an organization's connections by name
+    pub async fn list_connections(&self, conn: &mut PgConnection, org_id: Uuid) -> Result<Vec<ErpConnection>> {
+        self.repository.list_connections(conn, org_id).await
+    }
+
+    /// Exports a source through a connection now
+    pub async fn export(
+        &self,
+        conn: &mut PgConnection,
+        queue_config: &QueueConfig,
+        org_id: Uuid,
+        connection_id: Uuid,
+        source: &str,
+    ) -> Result<ErpExport> {
+        let connection = self.repository.find_connection(conn, org_id, connection_id).await?;
+        if !Self::mapping(&connection).contains_key(source) {
+            return Err(ApiError::validation(
+                format!("Connection '{}' does not export {}", connection.name, source),
+                None,
+            ));
+        }
+        self.write(conn, queue_config, &connection, source, Utc::now(), true)
+            .await?
+            .ok_or_else(|| ApiError::validation(format!("Unknown export source {}", source), None))
+    }
+
+    /// Writes the records of a source since the connection's previous
+    /// export of it and queues the file's delivery
+    ///
+    /// `None` when there are no new records and `always` is false.
+    async fn write(
+        &self,
+        conn: &mut PgConnection,
+        queue_config: &QueueConfig,
+        connection: &ErpConnection,
+        source_name: &str,
+        until: DateTime<Utc>,
+        always: bool,
+    ) -> Result<Option<ErpExport>> {
+        let source = self.source(source_name)?;
+        let mapping = Self::mapping(connection);
+        let columns = columns(mapping.get(source_name).map(Vec::as_slice).unwrap_or_default(), source.as_ref());
+        let after = self
+            .repository
+            .latest_export(conn, connection.id, source_name)
+            .await?
+            .map(|export| export.period_end);
+        let records = source
+            .records(conn, connection.org_id, after, until)
+            .map_err(|e| ApiError::database_error(format!("Failed to read {}: {}", source_name, e), None))?;
+        if records.is_empty() && !always {
+            return Ok(None);
+        }
+
+        let now = Utc::now();
+        let mut export = ErpExport {
+            id: Uuid::new_v4(),
+            org_id: connection.org_id,
+            connection_id: connection.id,
+            source: source_name.to_string(),
+            status: ErpExportStatus::Pending.to_string(),
+            period_start: after,
+            period_end: until,
+            record_count: records.len() as i32,
+            file_key: String::new(),
+            remote_ref: None,
+            attempts: 0,
+            error: None,
+            delivered_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        export.file_key = export_key(&export);
+        self.storage.put(&export.file_key, Bytes::from(to_csv(&columns, &records)?)).await?;
+        let export = self.repository.create_export(conn, &export).await?;
+        queue::enqueue(conn, queue_config, ERP_EXPORT_JOB, json!({ "export_id": export.id })).await?;
+        info!(
+            export_id = %export.id,
+            connection_id = %connection.id,
+            org_id = %connection.org_id,
+            source = %source_name,
+            records = export.record_count,
+            "Wrote ERP export"
+        );
+        Ok(Some(export))
+    }
+
+    /// Runs the scheduled exports that are due, skipping sources without
+    /// new records
+    ///
+    /// A connection moves to its next run before it is exported, so one
+    /// that keeps failing is retried on its schedule rather than on every
+    /// pass.
+    pub async fn export_due(&self, conn: &mut PgConnection, queue_config: &QueueConfig) -> Result<Vec<ErpExport>> {
+        let now = Utc::now();
+        let mut exports = Vec::new();
+        for connection in self.repository.due_connections(conn, now, BATCH_SIZE).await? {
+            let cron = connection
+                .schedule
+                .as_deref()
+                .map(parse_schedule)
+                .transpose()
+                .unwrap_or_else(|e| {
+                    warn!(connection_id = %connection.id, error = %e, "Invalid ERP export schedule");
+                    None
+                })
+                .flatten();
+            self.repository
+                .set_next_run(conn, connection.id, cron.as_ref().map(|cron| next_run(Some(cron), now)))
+                .await?;
+            for source in Self::mapping(&connection).keys() {
+                match self.write(conn, queue_config, &connection, source, now, false).await {
+                    Ok(export) => exports.extend(export),
+                    Err(e) => warn!(
+                        connection_id = %connection.id,
+                        source = %source,
+                        error = %e.message,
+                        "Scheduled ERP export failed"
+                    ),
+                }
+            }
+        }
+        Ok(exports)
+    }
+
+    /// Lists the exports of a connection, newest first
+    pub async fn exports(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        connection_id: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<ErpExport>, RowCount)> {
+        let connection = self.repository.find_connection(conn, org_id, connection_id).await?;
+        let exports = self.repository.list_exports(conn, org_id, connection.id, pagination).await?;
+        let total = self.repository.count_exports(conn, org_id, connection.id).await?;
+        Ok((exports, total))
+    }
+
+    /// Gets an export and its file
+    pub async fn export_file(&self, conn: &mut PgConnection, org_id: Uuid, export_id: Uuid) -> Result<(ErpExport, Bytes)> {
+        let export = self.repository.find_export(conn, org_id, export_id).await?;
+        let file = self.storage.get(&export.file_key).await?;
+        Ok((export, file))
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use diesel::{PgConnection, QueryResult};
+use uuid::Uuid;
+
+/// An exported record, keyed by field name
+pub type ExportRecord = serde_json::Map<String, serde_json::Value>;
+
+/// Data an organization can export to its ERP, e.g. approved contractor
+/// invoices
+///
+/// Exports are incremental: each covers the records that became ready for
+/// export (an invoice approved, a load delivered) after the previous
+/// export of the same source through the same connection.
+pub trait ExportSource: Send + Sync {
+    /// Stable source name, used in connection mappings and stored with
+    /// exports
+    fn name(&self) -> &'static str;
+
+    /// Fields of every record, in their default column order
+    fn fields(&self) -> &'static [&'static str];
+
+    /// Records of an organization that became ready after `after`, when
+    /// set, up to and including `until`
+    fn records(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+    ) -> QueryResult<Vec<ExportRecord>>;
+}
+
+/// Sources available for ERP exports
+pub fn sources() -> Vec<Arc<dyn ExportSource>> {
+    Vec::new()
+}
@@ -0,0 +1,82 @@
+use serde_json::Value;
+
+/// Fields never recorded as changes: identity, ownership and bookkeeping
+pub const IGNORED_FIELDS: [&str; 5] = ["id", "org_id", "created_by", "created_at", "updated_at"];
+
+/// A field whose value differs between two versions of a record
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub field: String,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+}
+
+/// The fields that differ between two versions of a record serialized as
+/// JSON objects, by field name
+///
+/// Anything but an object is compared as a whole under the field `value`.
+pub fn diff(before: &Value, after: &Value) -> Vec<FieldDiff> {
+    let (Value::Object(before), Value::Object(after)) = (before, after) else {
+        return if before == after {
+            Vec::new()
+        } else {
+            vec![FieldDiff {
+                field: "value".to_string(),
+                old_value: Some(before.clone()),
+                new_value: Some(after.clone()),
+            }]
+        };
+    };
+
+    let mut fields = before.keys().chain(after.keys()).collect::<Vec<_>>();
+    fields.sort();
+    fields.dedup();
+    fields
+        .into_iter()
+        .filter(|field| !IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|field| {
+            let old_value = before.get(field);
+            let new_value = after.get(field);
+            (old_value != new_value).then(|| FieldDiff {
+                field: field.clone(),
+                old_value: old_value.cloned(),
+                new_value: new_value.cloned(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_reports_changed_fields() {
+        let before = json!({ "id": 1, "reference": "SC-1", "volume_m3": 1000.0, "notes": null, "updated_at": "a" });
+        let after = json!({ "id": 1, "reference": "SC-1", "volume_m3": 1200.0, "notes": "Extended", "updated_at": "b" });
+
+        let changes = diff(&before, &after);
+        assert_eq!(
+            changes,
+            vec![
+                FieldDiff { field: "notes".into(), old_value: Some(Value::Null), new_value: Some(json!("Extended")) },
+                FieldDiff { field: "volume_m3".into(), old_value: Some(json!(1000.0)), new_value: Some(json!(1200.0)) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_of_equal_records_is_empty() {
+        let record = json!({ "name": "North Mill" });
+        assert!(diff(&record, &record).is_empty());
+    }
+
+    #[test]
+    fn test_diff_of_added_and_removed_fields() {
+        let changes = diff(&json!({ "a": 1 }), &json!({ "b": 2 }));
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].new_value, None);
+        assert_eq!(changes[1].old_value, None);
+    }
+}
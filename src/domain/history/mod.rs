@@ -0,0 +1,41 @@
+//! Change history
+//!
+//! Updates of tracked records are compared field by field with the record
+//! they replace, and each changed field is stored with its old and new
+//! value, who changed it and when. A record's history settles disputes
+//! about who changed what, e.g. a contract's committed volume.
+
+mod diff;
+mod service;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::models::{Customer, SupplyContract};
+
+pub use diff::{diff, FieldDiff, IGNORED_FIELDS};
+pub use service::HistoryService;
+
+/// A record whose changes are kept
+pub trait Tracked: Serialize + Send + Sync {
+    /// Name the record's history is kept under
+    const RESOURCE: &'static str;
+
+    fn record_id(&self) -> Uuid;
+}
+
+impl Tracked for Customer {
+    const RESOURCE: &'static str = "customer";
+
+    fn record_id(&self) -> Uuid {
+        self.id
+    }
+}
+
+impl Tracked for SupplyContract {
+    const RESOURCE: &'static str = "supply_contract";
+
+    fn record_id(&self) -> Uuid {
+        self.id
+    }
+}
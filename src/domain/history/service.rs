@@ -0,0 +1,66 @@
+use chrono::Utc;
+use diesel::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+
+use super::{diff::diff, Tracked};
+use crate::{
+    db::{models::FieldChange, repositories::HistoryRepository},
+    error::Result,
+};
+
+/// Service for the field-level change history of records
+pub struct HistoryService<R: HistoryRepository + Send + Sync> {
+    repository: R,
+}
+
+impl<R: HistoryRepository + Send + Sync> HistoryService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// Records the fields an update of a record changed, returning how many
+    pub async fn record<T: Tracked>(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        before: &T,
+        after: &T,
+        changed_by: Option<Uuid>,
+    ) -> Result<usize> {
+        let before_json = serde_json::to_value(before).unwrap_or_default();
+        let after_json = serde_json::to_value(after).unwrap_or_default();
+        let changed_at = Utc::now();
+        let changes = diff(&before_json, &after_json)
+            .into_iter()
+            .map(|diff| FieldChange {
+                id: Uuid::new_v4(),
+                org_id,
+                resource: T::RESOURCE.to_string(),
+                record_id: after.record_id(),
+                field: diff.field,
+                old_value: diff.old_value,
+                new_value: diff.new_value,
+                changed_by,
+                changed_at,
+            })
+            .collect::<Vec<_>>();
+        self.repository.record(conn, &changes).await?;
+        if !changes.is_empty() {
+            info!(record_id = %after.record_id(), org_id = %org_id, "Recorded {} changed fields of {}", changes.len(), T::RESOURCE);
+        }
+        Ok(changes.len())
+    }
+
+    /// The changes of a record of `resource`, oldest first, with who made
+    /// them
+    pub async fn list(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        resource: &str,
+        record_id: Uuid,
+    ) -> Result<Vec<(FieldChange, Option<String>)>> {
+        self.repository.list(conn, org_id, resource, record_id).await
+    }
+}
@@ -0,0 +1,210 @@
+use std::io::Cursor;
+
+use calamine::{open_workbook_from_rs, Data, DataType, Reader, Xlsx};
+use serde_json::json;
+
+use crate::error::{ApiError, ErrorCode, ErrorContext, Result};
+
+/// Largest file accepted for import
+pub const MAX_IMPORT_BYTES: usize = 20 * 1024 * 1024;
+
+/// Most data rows a file may have
+pub const MAX_IMPORT_ROWS: usize = 50_000;
+
+/// Kind of an uploaded file, from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFileFormat {
+    /// Comma, semicolon or tab separated, detected from the header row
+    Csv,
+    Xlsx,
+}
+
+impl ImportFileFormat {
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let (_, extension) = filename.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" | "txt" => Some(Self::Csv),
+            "xlsx" => Some(Self::Xlsx),
+            _ => None,
+        }
+    }
+}
+
+fn invalid_file(message: impl Into<String>, reason: impl std::fmt::Display) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": "file",
+            "code": "INVALID_FILE",
+            "reason": reason.to_string()
+        })),
+    )
+}
+
+/// A spreadsheet read into its header row and data rows of text cells
+///
+/// Blank rows are skipped and short rows padded to the header width, so
+/// every row has a cell per header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportFile {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl ImportFile {
+    /// Reads an uploaded file, picking the format from `filename`
+    pub fn parse(filename: &str, bytes: &[u8]) -> Result<Self> {
+        let format = ImportFileFormat::from_filename(filename)
+            .ok_or_else(|| invalid_file("Unsupported file type, upload a .csv or .xlsx file", filename))?;
+        let mut rows = match format {
+            ImportFileFormat::Csv => read_csv(bytes)?,
+            ImportFileFormat::Xlsx => read_xlsx(bytes)?,
+        }
+        .into_iter()
+        .filter(|row| row.iter().any(|cell| !cell.is_empty()));
+
+        let headers: Vec<String> = rows
+            .next()
+            .ok_or_else(|| invalid_file("The file is empty", "no header row"))?
+            .into_iter()
+            .enumerate()
+            .map(|(index, header)| if header.is_empty() { format!("Column {}", index + 1) } else { header })
+            .collect();
+        if let Some(duplicate) = headers.iter().enumerate().find_map(|(index, header)| {
+            headers[..index].iter().any(|other| other.eq_ignore_ascii_case(header)).then_some(header)
+        }) {
+            return Err(invalid_file("Column headers must be unique", duplicate));
+        }
+
+        let rows: Vec<Vec<String>> = rows
+            .map(|mut row| {
+                row.resize(headers.len(), String::new());
+                row
+            })
+            .collect();
+        if rows.len() > MAX_IMPORT_ROWS {
+            return Err(invalid_file(
+                format!("A file may have at most {} rows", MAX_IMPORT_ROWS),
+                rows.len(),
+            ));
+        }
+        Ok(Self { headers, rows })
+    }
+}
+
+/// Separator used most often in the first line
+fn detect_delimiter(bytes: &[u8]) -> u8 {
+    let line = bytes.split(|byte| *byte == b'\n').next().unwrap_or_default();
+    [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|delimiter| line.iter().filter(|byte| *byte == delimiter).count())
+        .unwrap_or(b',')
+}
+
+fn read_csv(bytes: &[u8]) -> Result<Vec<Vec<String>>> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(detect_delimiter(bytes))
+        .from_reader(bytes);
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|e| invalid_file("The CSV file could not be read", e))?;
+            Ok(record.iter().map(|cell| cell.trim().to_string()).collect())
+        })
+        .collect()
+}
+
+/// Text of a worksheet cell; dates are written `YYYY-MM-DD`
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(text) => text.trim().to_string(),
+        Data::Float(number) if number.fract() == 0.0 && number.abs() < 1e15 => (*number as i64).to_string(),
+        Data::DateTime(_) | Data::DateTimeIso(_) => match cell.as_datetime() {
+            Some(datetime) if datetime.time() == chrono::NaiveTime::MIN => datetime.format("%Y-%m-%d").to_string(),
+            Some(datetime) => datetime.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            None => cell.to_string(),
+        },
+        cell => cell.to_string(),
+    }
+}
+
+/// Rows of the first worksheet
+fn read_xlsx(bytes: &[u8]) -> Result<Vec<Vec<String>>> {
+    let mut workbook: Xlsx<_> = open_workbook_from_rs(Cursor::new(bytes))
+        .map_err(|e| invalid_file("The Excel file could not be read", e))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| invalid_file("The Excel file has no worksheet", "no worksheet"))?
+        .map_err(|e| invalid_file("The Excel file could not be read", e))?;
+    Ok(range.rows().map(|row| row.iter().map(cell_text).collect()).collect())
+}
+
+/// A data row rejected by an import
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedRow {
+    /// Line of the row in the file, the header being line 1
+    pub line: usize,
+    pub errors: Vec<String>,
+    pub cells: Vec<String>,
+}
+
+/// Writes rejected rows as CSV: their line, the problems found and the
+/// original cells
+///
+/// Cells starting like a formula are prefixed with `'`, so spreadsheets
+/// opening the report show them rather than evaluate them.
+pub fn error_report(headers: &[String], rejected: &[RejectedRow]) -> Result<Vec<u8>> {
+    let defuse = |cell: &str| {
+        if cell.starts_with(['=', '+', '-', '@']) {
+            format!("'{}", cell)
+        } else {
+            cell.to_string()
+        }
+    };
+    let failed = |e: csv::Error| {
+        ApiError::new(ErrorCode::InternalError, format!("Failed to write the error report: {}", e), ErrorContext::new())
+    };
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(["line", "errors"].into_iter().map(String::from).chain(headers.iter().map(|header| defuse(header))))
+        .map_err(failed)?;
+    for row in rejected {
+        writer
+            .write_record([row.line.to_string(), row.errors.join("; ")].into_iter().chain(row.cells.iter().map(|cell| defuse(cell))))
+            .map_err(failed)?;
+    }
+    writer.into_inner().map_err(|e| {
+        ApiError::new(ErrorCode::InternalError, format!("Failed to write the error report: {}", e.error()), ErrorContext::new())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_detects_delimiter_and_pads_rows() {
+        let file = ImportFile::parse("stands.csv", "\u{feff}Stand;Area (ha);\n\nS-1;12,5;x\nS-2\n".as_bytes()).unwrap();
+        assert_eq!(file.headers, vec!["Stand", "Area (ha)", "Column 3"]);
+        assert_eq!(file.rows, vec![vec!["S-1", "12,5", "x"], vec!["S-2", "", ""]]);
+
+        assert!(ImportFile::parse("stands.pdf", b"Stand").is_err());
+        assert!(ImportFile::parse("stands.csv", b"Stand,stand\n1,2").is_err());
+        assert!(ImportFile::parse("stands.csv", b"").is_err());
+    }
+
+    #[test]
+    fn test_error_report_neutralizes_formulas() {
+        let report = error_report(
+            &["Stand".into(), "Area".into()],
+            &[RejectedRow { line: 3, errors: vec!["Area is required".into()], cells: vec!["=HYPERLINK()".into(), String::new()] }],
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(report).unwrap(), "line,errors,Stand,Area\n3,Area is required,'=HYPERLINK(),\n");
+    }
+}
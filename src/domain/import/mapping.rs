@@ -0,0 +1,253 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+
+use super::target::{ImportField, ImportFieldKind, ImportRecord, ImportTarget};
+use crate::error::{ApiError, ErrorContext, Result};
+
+/// Fields the columns of a file are imported into, by header; columns
+/// without a field are ignored
+pub type ColumnMapping = BTreeMap<String, String>;
+
+/// Lowercase letters and digits of a header or field name, so `Area (ha)`
+/// matches `area_ha`
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Maps each header whose name matches a field, or one of its aliases, to
+/// that field; a field is mapped from its first matching header only
+pub fn detect(headers: &[String], fields: &[ImportField]) -> ColumnMapping {
+    let mut mapping = ColumnMapping::new();
+    for header in headers {
+        let normalized = normalize(header);
+        let field = fields.iter().find(|field| {
+            !mapping.values().any(|mapped| mapped == field.name)
+                && std::iter::once(field.name)
+                    .chain(field.aliases.iter().copied())
+                    .any(|name| normalize(name) == normalized)
+        });
+        if let Some(field) = field {
+            mapping.insert(header.clone(), field.name.to_string());
+        }
+    }
+    mapping
+}
+
+/// Reads one cell as a field of `kind`
+fn convert_cell(kind: ImportFieldKind, text: &str) -> std::result::Result<Value, String> {
+    match kind {
+        ImportFieldKind::Text => Ok(Value::String(text.to_string())),
+        ImportFieldKind::Number => text
+            .replace(',', ".")
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("'{}' is not a number", text)),
+        ImportFieldKind::Integer => text
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| format!("'{}' is not a whole number", text)),
+        ImportFieldKind::Boolean => match text.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "y" | "1" => Ok(Value::Bool(true)),
+            "false" | "no" | "n" | "0" => Ok(Value::Bool(false)),
+            _ => Err(format!("'{}' is not yes or no", text)),
+        },
+        ImportFieldKind::Date => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .map(|date| Value::String(date.to_string()))
+            .map_err(|_| format!("'{}' is not a date (YYYY-MM-DD)", text)),
+    }
+}
+
+/// Converts the rows of a file into records of a target through a column
+/// mapping
+pub struct RowConverter {
+    target: Arc<dyn ImportTarget>,
+    /// Column index and header of each mapped field
+    columns: Vec<(usize, String, ImportField)>,
+}
+
+impl RowConverter {
+    /// Checks a mapping against the file's headers and the target's fields
+    ///
+    /// Every header and field must exist, no field may be mapped twice and
+    /// every required field must be mapped.
+    pub fn new(target: Arc<dyn ImportTarget>, headers: &[String], mapping: &ColumnMapping) -> Result<Self> {
+        let mut problems = Vec::new();
+        let mut columns: Vec<(usize, String, ImportField)> = Vec::new();
+        for (header, field_name) in mapping {
+            let Some(index) = headers.iter().position(|candidate| candidate == header) else {
+                problems.push(format!("The file has no column '{}'", header));
+                continue;
+            };
+            let Some(field) = target.fields().iter().find(|field| field.name == field_name) else {
+                problems.push(format!("'{}' is not a field of {}", field_name, target.name()));
+                continue;
+            };
+            if columns.iter().any(|(_, _, mapped)| mapped.name == field.name) {
+                problems.push(format!("'{}' is mapped from more than one column", field.name));
+                continue;
+            }
+            columns.push((index, header.clone(), *field));
+        }
+        problems.extend(
+            target
+                .fields()
+                .iter()
+                .filter(|field| field.required && !columns.iter().any(|(_, _, mapped)| mapped.name == field.name))
+                .map(|field| format!("Required field '{}' is not mapped", field.name)),
+        );
+
+        if !problems.is_empty() {
+            return Err(ApiError::validation_with_context(
+                "Invalid column mapping",
+                ErrorContext::new().with_details(json!({
+                    "field": "mapping",
+                    "code": "INVALID_MAPPING",
+                    "problems": problems
+                })),
+            ));
+        }
+        columns.sort_by_key(|(index, _, _)| *index);
+        Ok(Self { target, columns })
+    }
+
+    /// Converts one row, or lists everything wrong with it
+    ///
+    /// Empty cells of optional fields are left out of the record.
+    pub fn convert(&self, row: &[String]) -> std::result::Result<ImportRecord, Vec<String>> {
+        let mut record = ImportRecord::new();
+        let mut errors = Vec::new();
+        for (index, header, field) in &self.columns {
+            let text = row.get(*index).map(String::as_str).unwrap_or_default();
+            if text.is_empty() {
+                if field.required {
+                    errors.push(format!("{}: a value is required", header));
+                }
+                continue;
+            }
+            match convert_cell(field.kind, text) {
+                Ok(value) => {
+                    record.insert(field.name.to_string(), value);
+                }
+                Err(e) => errors.push(format!("{}: {}", header, e)),
+            }
+        }
+        if errors.is_empty() {
+            errors = self.target.check(&record);
+        }
+        if errors.is_empty() {
+            Ok(record)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::{PgConnection, QueryResult};
+    use uuid::Uuid;
+
+    const FIELDS: &[ImportField] = &[
+        ImportField { name: "name", kind: ImportFieldKind::Text, required: true, aliases: &["stand"] },
+        ImportField { name: "area_ha", kind: ImportFieldKind::Number, required: true, aliases: &["ha", "area"] },
+        ImportField { name: "certified", kind: ImportFieldKind::Boolean, required: false, aliases: &[] },
+        ImportField { name: "planted_on", kind: ImportFieldKind::Date, required: false, aliases: &[] },
+    ];
+
+    struct Stands;
+
+    impl ImportTarget for Stands {
+        fn name(&self) -> &'static str {
+            "stands"
+        }
+
+        fn fields(&self) -> &'static [ImportField] {
+            FIELDS
+        }
+
+        fn check(&self, record: &ImportRecord) -> Vec<String> {
+            match record.get("area_ha").and_then(Value::as_f64) {
+                Some(area) if area <= 0.0 => vec!["area_ha: must be positive".to_string()],
+                _ => Vec::new(),
+            }
+        }
+
+        fn insert(&self, _conn: &mut PgConnection, _org_id: Uuid, records: &[ImportRecord]) -> QueryResult<usize> {
+            Ok(records.len())
+        }
+    }
+
+    fn headers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_detect_matches_names_and_aliases() {
+        let mapping = detect(&headers(&["Stand", "Area (ha)", "HA", "Notes", "Planted On"]), FIELDS);
+        assert_eq!(
+            mapping,
+            ColumnMapping::from([
+                ("Stand".into(), "name".into()),
+                ("Area (ha)".into(), "area_ha".into()),
+                ("Planted On".into(), "planted_on".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_converter_rejects_invalid_mappings() {
+        let headers = headers(&["Stand", "Area", "Size"]);
+        let mapping = ColumnMapping::from([("Stand".into(), "name".into()), ("Missing".into(), "area_ha".into())]);
+        assert!(RowConverter::new(Arc::new(Stands), &headers, &mapping).is_err());
+
+        let mapping = ColumnMapping::from([
+            ("Stand".into(), "name".into()),
+            ("Area".into(), "area_ha".into()),
+            ("Size".into(), "area_ha".into()),
+        ]);
+        assert!(RowConverter::new(Arc::new(Stands), &headers, &mapping).is_err());
+
+        let mapping = ColumnMapping::from([("Stand".into(), "name".into())]);
+        assert!(RowConverter::new(Arc::new(Stands), &headers, &mapping).is_err());
+    }
+
+    #[test]
+    fn test_convert_reads_kinds_and_collects_errors() {
+        let columns = headers(&["Stand", "Area", "Certified", "Planted"]);
+        let converter = RowConverter::new(Arc::new(Stands), &columns, &detect(&columns, FIELDS)).unwrap();
+        let mut mapping = detect(&columns, FIELDS);
+        mapping.insert("Planted".into(), "planted_on".into());
+        let full = RowConverter::new(Arc::new(Stands), &columns, &mapping).unwrap();
+
+        let record = full.convert(&headers(&["S-1", "12,5", "yes", "2001-04-30"])).unwrap();
+        assert_eq!(Value::Object(record), json!({
+            "name": "S-1",
+            "area_ha": 12.5,
+            "certified": true,
+            "planted_on": "2001-04-30"
+        }));
+        assert_eq!(Value::Object(converter.convert(&headers(&["S-2", "3", "", ""])).unwrap()), json!({
+            "name": "S-2",
+            "area_ha": 3.0
+        }));
+
+        assert_eq!(
+            full.convert(&headers(&["", "lots", "maybe", "30.04.2001"])).unwrap_err(),
+            vec![
+                "Stand: a value is required",
+                "Area: 'lots' is not a number",
+                "Certified: 'maybe' is not yes or no",
+                "Planted: '30.04.2001' is not a date (YYYY-MM-DD)",
+            ]
+        );
+        assert_eq!(full.convert(&headers(&["S-3", "-1", "", ""])).unwrap_err(), vec!["area_ha: must be positive"]);
+    }
+}
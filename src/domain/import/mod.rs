@@ -0,0 +1,21 @@
+//! Spreadsheet imports
+//!
+//! Imports load rows of a CSV or Excel file into an import target. A file is
+//! uploaded first; its headers are matched against the target's fields to
+//! suggest a column mapping, which the client can change while previewing
+//! how each row would be converted and validated. Committing the import
+//! queues a background job that loads the valid rows in one transaction and
+//! stores the rejected ones, with their problems, as a CSV report.
+
+mod file;
+mod mapping;
+mod service;
+mod target;
+
+pub use file::{error_report, ImportFile, ImportFileFormat, RejectedRow, MAX_IMPORT_BYTES, MAX_IMPORT_ROWS};
+pub use mapping::{detect, ColumnMapping, RowConverter};
+pub use service::{
+    error_key, source_key, ImportPreview, ImportPreviewRow, ImportService, DEFAULT_PREVIEW_ROWS, IMPORT_JOB,
+    MAX_PREVIEW_ROWS,
+};
+pub use target::{targets, ImportField, ImportFieldKind, ImportRecord, ImportTarget};
@@ -0,0 +1,318 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::Utc;
+use diesel::PgConnection;
+use serde::Serialize;
+use serde_json::json;
+use tracing::info;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{
+    file::{ImportFile, ImportFileFormat, MAX_IMPORT_BYTES},
+    mapping::{detect, ColumnMapping, RowConverter},
+    target::{targets, ImportRecord, ImportTarget},
+};
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        count::RowCount,
+        models::{Import, ImportStatus},
+        repositories::ImportRepository,
+    },
+    domain::activity::{ActivityKind, ActivityLog, NewActivity},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::ObjectStorage,
+    jobs::queue,
+    utils::QueueConfig,
+};
+
+/// Kind of the queue job that loads a committed import
+pub const IMPORT_JOB: &str = "import";
+
+/// Rows shown by a preview unless asked otherwise
+pub const DEFAULT_PREVIEW_ROWS: usize = 20;
+
+/// Most rows a preview shows
+pub const MAX_PREVIEW_ROWS: usize = 200;
+
+/// Object storage key of an uploaded file
+pub fn source_key(org_id: Uuid, import_id: Uuid, format: ImportFileFormat) -> String {
+    let extension = match format {
+        ImportFileFormat::Csv => "csv",
+        ImportFileFormat::Xlsx => "xlsx",
+    };
+    format!("imports/{}/{}.{}", org_id, import_id, extension)
+}
+
+/// Object storage key of the report of rows an import rejected
+pub fn error_key(org_id: Uuid, import_id: Uuid) -> String {
+    format!("imports/{}/{}-errors.csv", org_id, import_id)
+}
+
+/// One row of a preview
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ImportPreviewRow {
+    /// Line of the row in the file, the header being line 1
+    pub line: usize,
+    /// The converted record, absent when the row is rejected
+    #[schema(value_type = Option<Object>)]
+    #[ts(type = "Record<string, unknown> | null")]
+    pub record: Option<ImportRecord>,
+    /// Why the row would be rejected
+    pub errors: Vec<String>,
+}
+
+/// How a file would be imported with a column mapping
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ImportPreview {
+    pub mapping: ColumnMapping,
+    /// Rows of the whole file that would be imported
+    pub valid_rows: usize,
+    /// Rows of the whole file that would be rejected
+    pub invalid_rows: usize,
+    /// The first rows of the file
+    pub rows: Vec<ImportPreviewRow>,
+}
+
+/// Service for uploading, previewing and committing spreadsheet imports
+///
+/// Committed imports are loaded by [`crate::jobs::import::ImportProcessor`].
+pub struct ImportService<R: ImportRepository + Send + Sync> {
+    repository: R,
+    storage: ObjectStorage,
+    targets: Vec<Arc<dyn ImportTarget>>,
+}
+
+impl<R: ImportRepository + Send + Sync> ImportService<R> {
+    pub fn new(repository: R, storage: ObjectStorage) -> Self {
+        Self::with_targets(repository, storage, targets())
+    }
+
+    /// Creates a service importing into the given targets
+    pub fn with_targets(repository: R, storage: ObjectStorage, targets: Vec<Arc<dyn ImportTarget>>) -> Self {
+        Self {
+            repository,
+            storage,
+            targets,
+        }
+    }
+
+    /// Targets files can be imported into
+    pub fn targets(&self) -> &[Arc<dyn ImportTarget>] {
+        &self.targets
+    }
+
+    fn target(&self, name: &str) -> Result<Arc<dyn ImportTarget>> {
+        self.targets
+            .iter()
+            .find(|target| target.name() == name)
+            .cloned()
+            .ok_or_else(|| {
+                ApiError::validation_with_context(
+                    format!("Unknown import target {}", name),
+                    ErrorContext::new().with_details(json!({
+                        "field": "target",
+                        "code": "UNKNOWN_TARGET",
+                        "available": self.targets.iter().map(|target| target.name()).collect::<Vec<_>>()
+                    })),
+                )
+            })
+    }
+
+    fn conflict(import: &Import) -> ApiError {
+        ApiError::new(
+            ErrorCode::Conflict,
+            format!("Import {} is {}; only uploaded imports can be changed", import.id, import.status),
+            ErrorContext::new(),
+        )
+    }
+
+    fn stored_mapping(import: &Import) -> ColumnMapping {
+        serde_json::from_value(import.mapping.clone()).unwrap_or_default()
+    }
+
+    async fn read_file(&self, import: &Import) -> Result<ImportFile> {
+        let bytes = self.storage.get(&import.file_key).await?;
+        ImportFile::parse(&import.filename, &bytes)
+    }
+
+    /// Stores an uploaded file with the column mapping detected from its
+    /// headers
+    pub async fn upload(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        created_by: Option<Uuid>,
+        target: &str,
+        filename: &str,
+        bytes: Bytes,
+    ) -> Result<Import> {
+        let target = self.target(target)?;
+        if bytes.len() > MAX_IMPORT_BYTES {
+            return Err(ApiError::validation(
+                format!("A file may be at most {} MiB", MAX_IMPORT_BYTES / (1024 * 1024)),
+                None,
+            ));
+        }
+        let file = ImportFile::parse(filename, &bytes)?;
+        let format = ImportFileFormat::from_filename(filename).unwrap_or(ImportFileFormat::Csv);
+        let mapping = detect(&file.headers, target.fields());
+
+        let id = Uuid::new_v4();
+        let file_key = source_key(org_id, id, format);
+        self.storage.put(&file_key, bytes).await?;
+
+        let now = Utc::now();
+        let import = self
+            .repository
+            .create(conn, &Import {
+                id,
+                org_id,
+                target: target.name().to_string(),
+                filename: filename.to_string(),
+                file_key,
+                headers: file.headers,
+                mapping: json!(mapping),
+                status: ImportStatus::Uploaded.to_string(),
+                total_rows: file.rows.len() as i32,
+                imported_rows: None,
+                failed_rows: None,
+                error_key: None,
+                error: None,
+                created_by,
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+        info!(
+            import_id = %import.id,
+            org_id = %org_id,
+            target = %import.target,
+            rows = import.total_rows,
+            "Uploaded import '{}'", import.filename
+        );
+        ActivityLog::record(
+            conn,
+            org_id,
+            created_by,
+            NewActivity::new(
+                ActivityKind::ImportUploaded,
+                "import",
+                import.id,
+                format!("Uploaded {} to import {} rows", import.filename, import.total_rows),
+            )
+            .with_data(json!({ "target": import.target })),
+        )
+        .await;
+        Ok(import)
+    }
+
+    /// Gets an import
+    pub async fn get(&self, conn: &mut PgConnection, org_id: Uuid, import_id: Uuid) -> Result<Import> {
+        self.repository.find(conn, org_id, import_id).await
+    }
+
+    /// Lists an organization's imports, newest first
+    pub async fn list(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<Import>, RowCount)> {
+        let imports = self.repository.list_for_org(conn, org_id, pagination).await?;
+        let total = self.repository.count_for_org(conn, org_id).await?;
+        Ok((imports, total))
+    }
+
+    /// Validates every row of an import with a mapping
+    ///
+    /// A given mapping replaces the stored one, which only an uploaded
+    /// import allows; without one the stored mapping is used. Counts cover the whole
+    /// file; only the first `limit` rows are returned.
+    pub async fn preview(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        import_id: Uuid,
+        mapping: Option<ColumnMapping>,
+        limit: Option<usize>,
+    ) -> Result<ImportPreview> {
+        let import = self.repository.find(conn, org_id, import_id).await?;
+        let target = self.target(&import.target)?;
+        let changed = mapping.is_some();
+        let mapping = mapping.unwrap_or_else(|| Self::stored_mapping(&import));
+        let converter = RowConverter::new(target, &import.headers, &mapping)?;
+        let file = self.read_file(&import).await?;
+        if changed {
+            self.repository
+                .set_mapping(conn, org_id, import.id, &json!(mapping))
+                .await?
+                .ok_or_else(|| Self::conflict(&import))?;
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_PREVIEW_ROWS).min(MAX_PREVIEW_ROWS);
+        let mut preview = ImportPreview {
+            mapping,
+            valid_rows: 0,
+            invalid_rows: 0,
+            rows: Vec::new(),
+        };
+        for (index, row) in file.rows.iter().enumerate() {
+            let (record, errors) = match converter.convert(row) {
+                Ok(record) => {
+                    preview.valid_rows += 1;
+                    (Some(record), Vec::new())
+                }
+                Err(errors) => {
+                    preview.invalid_rows += 1;
+                    (None, errors)
+                }
+            };
+            if preview.rows.len() < limit {
+                preview.rows.push(ImportPreviewRow { line: index + 2, record, errors });
+            }
+        }
+        Ok(preview)
+    }
+
+    /// Queues an uploaded import to be loaded, with its final mapping
+    ///
+    /// Without a mapping the stored one is used.
+    pub async fn commit(
+        &self,
+        conn: &mut PgConnection,
+        queue_config: &QueueConfig,
+        org_id: Uuid,
+        import_id: Uuid,
+        mapping: Option<ColumnMapping>,
+    ) -> Result<Import> {
+        let import = self.repository.find(conn, org_id, import_id).await?;
+        let target = self.target(&import.target)?;
+        let mapping = mapping.unwrap_or_else(|| Self::stored_mapping(&import));
+        RowConverter::new(target, &import.headers, &mapping)?;
+
+        let queued = self
+            .repository
+            .queue(conn, org_id, import.id, &json!(mapping))
+            .await?
+            .ok_or_else(|| Self::conflict(&import))?;
+        queue::enqueue(conn, queue_config, IMPORT_JOB, json!({ "import_id": queued.id })).await?;
+        info!(import_id = %queued.id, org_id = %org_id, "Committed import '{}'", queued.filename);
+        Ok(queued)
+    }
+
+    /// Gets the report of rows an import rejected, as CSV
+    pub async fn error_report(&self, conn: &mut PgConnection, org_id: Uuid, import_id: Uuid) -> Result<(Import, Bytes)> {
+        let import = self.repository.find(conn, org_id, import_id).await?;
+        let Some(key) = &import.error_key else {
+            return Err(ApiError::not_found(format!("Import {} has no error report", import_id)));
+        };
+        let report = self.storage.get(key).await?;
+        Ok((import, report))
+    }
+}
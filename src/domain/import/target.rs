@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use diesel::{PgConnection, QueryResult};
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::{block::BlockImportTarget, stand::StandImportTarget};
+
+/// A converted row, keyed by field name; optional fields left empty are absent
+pub type ImportRecord = serde_json::Map<String, serde_json::Value>;
+
+/// How a cell is read into a field
+///
+/// `number` accepts a decimal point or comma, `boolean` accepts
+/// `true`/`false`, `yes`/`no`, `y`/`n` and `1`/`0`, `date` is `YYYY-MM-DD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ImportFieldKind {
+    Text,
+    Number,
+    Integer,
+    Boolean,
+    Date,
+}
+
+/// A field of an import target a column can be mapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportField {
+    pub name: &'static str,
+    pub kind: ImportFieldKind,
+    pub required: bool,
+    /// Other headers the column is recognized by, e.g. `ha` for `area_ha`
+    pub aliases: &'static [&'static str],
+}
+
+/// Something rows of a spreadsheet can be imported into
+///
+/// Cells are converted to the kinds of [`fields`] before [`check`] sees a
+/// record. [`insert`] runs inside the transaction that loads a committed
+/// import, so it is synchronous and an error rolls the whole import back.
+///
+/// [`fields`]: ImportTarget::fields
+/// [`check`]: ImportTarget::check
+/// [`insert`]: ImportTarget::insert
+pub trait ImportTarget: Send + Sync {
+    /// Stable target name, used in requests and stored with imports
+    fn name(&self) -> &'static str;
+
+    /// Fields columns can be mapped to
+    fn fields(&self) -> &'static [ImportField];
+
+    /// Problems with a converted record beyond its field kinds, e.g. a
+    /// value out of range; the record is rejected when any are returned
+    fn check(&self, _record: &ImportRecord) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Inserts records for an organization, returning how many were written
+    fn insert(&self, conn: &mut PgConnection, org_id: Uuid, records: &[ImportRecord]) -> QueryResult<usize>;
+}
+
+/// Targets available for imports
+pub fn targets() -> Vec<Arc<dyn ImportTarget>> {
+    vec![Arc::new(BlockImportTarget), Arc::new(StandImportTarget)]
+}
@@ -0,0 +1,14 @@
+//! Organization invitations
+//!
+//! A manager invites an email address into their organization with a
+//! role. The invitee is mailed a link carrying a token signed with
+//! `jwt_secret`, and registers with it instead of an `org_id`, joining the
+//! organization with the invitation's role. The token names the stored
+//! invitation, so it works once and can be revoked, explicitly or by
+//! inviting the address again.
+
+mod service;
+mod token;
+
+pub use service::{InvitationService, INVITATION_DAYS, MAX_INVITATION_DAYS};
+pub use token::{issue_token, verify_token, InvitationClaims};
@@ -0,0 +1,205 @@
+use chrono::{Duration, Utc};
+use diesel::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+
+use super::token::{invalid_invitation, issue_token, InvitationClaims};
+use crate::{
+    db::{
+        models::{
+            auth::{Role, User},
+            OrgInvitation,
+        },
+        repositories::{
+            auth::{UserRepository, UserRepositoryImpl},
+            InvitationRepository, InvitationRepositoryImpl, OrganizationRepositoryImpl, Repository,
+        },
+    },
+    domain::{auth::AuthValidator, organization::QuotaService},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::email::InvitationEmail,
+    jobs::email,
+    utils::Config,
+};
+
+/// Days an invitation can be accepted for unless the inviter says otherwise
+pub const INVITATION_DAYS: i64 = 7;
+
+/// Most days an invitation can be accepted for
+pub const MAX_INVITATION_DAYS: i64 = 30;
+
+/// Invites people into organizations and accepts their invitations
+pub struct InvitationService;
+
+impl InvitationService {
+    /// Invites `email` into the organization of `inviter` with `role` for
+    /// `days`, and mails them the link to register with
+    ///
+    /// Only admins invite admins. Inviting an address again revokes the
+    /// invitations it was sent before.
+    pub async fn invite(
+        conn: &mut PgConnection,
+        inviter: &User,
+        email: &str,
+        role: Role,
+        days: i64,
+        config: &Config,
+    ) -> Result<OrgInvitation> {
+        let email = email.trim().to_lowercase();
+        AuthValidator::validate_email(&email)?;
+        if !(1..=MAX_INVITATION_DAYS).contains(&days) {
+            return Err(ApiError::validation_with_context(
+                format!("Invitations expire after 1 to {} days", MAX_INVITATION_DAYS),
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "expires_in_days",
+                    "code": "OUT_OF_RANGE",
+                })),
+            ));
+        }
+        Self::check_role(inviter, role)?;
+        Self::check_unused(conn, &email).await?;
+        QuotaService::check_users(conn, inviter.org_id, 1).await?;
+
+        let repo = InvitationRepositoryImpl;
+        repo.revoke_pending(conn, inviter.org_id, &email).await?;
+        let now = Utc::now();
+        let invitation = repo
+            .create(conn, &OrgInvitation {
+                id: Uuid::new_v4(),
+                org_id: inviter.org_id,
+                email,
+                role,
+                invited_by: Some(inviter.id),
+                expires_at: now + Duration::days(days),
+                accepted_at: None,
+                created_at: now,
+                updated_at: now,
+                deleted_at: None,
+            })
+            .await?;
+        Self::mail(conn, &invitation, inviter, config).await?;
+
+        info!(invitation_id = %invitation.id, org_id = %invitation.org_id, role = ?invitation.role, "Invitation sent");
+        Ok(invitation)
+    }
+
+    /// The invitations of the organization of `viewer` neither accepted nor
+    /// revoked, expired ones included so they can be resent
+    pub async fn pending(conn: &mut PgConnection, viewer: &User) -> Result<Vec<OrgInvitation>> {
+        InvitationRepositoryImpl.list_pending(conn, viewer.org_id).await
+    }
+
+    /// Mails the pending invitation `id` again, on behalf of `sender`
+    ///
+    /// The invitation is renewed for at least [`INVITATION_DAYS`], so
+    /// expired invitations can be resent too.
+    pub async fn resend(conn: &mut PgConnection, sender: &User, id: Uuid, config: &Config) -> Result<OrgInvitation> {
+        let invitation = Self::find_pending(conn, sender, id).await?;
+        Self::check_role(sender, invitation.role)?;
+        Self::check_unused(conn, &invitation.email).await?;
+
+        let expires_at = invitation.expires_at.max(Utc::now() + Duration::days(INVITATION_DAYS));
+        let invitation = InvitationRepositoryImpl
+            .extend(conn, invitation.id, expires_at)
+            .await?
+            .ok_or_else(|| not_found(id))?;
+        Self::mail(conn, &invitation, sender, config).await?;
+
+        info!(invitation_id = %invitation.id, resent_by = %sender.id, "Invitation resent");
+        Ok(invitation)
+    }
+
+    /// Revokes the pending invitation `id`, so its link no longer works
+    pub async fn revoke(conn: &mut PgConnection, revoker: &User, id: Uuid) -> Result<OrgInvitation> {
+        let invitation = Self::find_pending(conn, revoker, id).await?;
+        Self::check_role(revoker, invitation.role)?;
+        let invitation = InvitationRepositoryImpl
+            .revoke(conn, revoker.org_id, invitation.id)
+            .await?
+            .ok_or_else(|| not_found(id))?;
+
+        info!(invitation_id = %invitation.id, revoked_by = %revoker.id, "Invitation revoked");
+        Ok(invitation)
+    }
+
+    /// Uses up the invitation `claims` vouch for, on behalf of `email`
+    ///
+    /// Fails if the invitation was sent to another address, or was accepted,
+    /// revoked or has expired since the token was issued.
+    pub async fn accept(conn: &mut PgConnection, claims: &InvitationClaims, email: &str) -> Result<OrgInvitation> {
+        if !email.trim().eq_ignore_ascii_case(&claims.email) {
+            return Err(ApiError::validation_with_context(
+                "The invitation was sent to another email address",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "email",
+                    "code": "INVITATION_MISMATCH",
+                })),
+            ));
+        }
+        let invitation = InvitationRepositoryImpl
+            .accept(conn, claims.sub)
+            .await?
+            .ok_or_else(invalid_invitation)?;
+
+        info!(invitation_id = %invitation.id, org_id = %invitation.org_id, "Invitation accepted");
+        Ok(invitation)
+    }
+
+    /// The pending invitation `id` to the organization of `viewer`
+    async fn find_pending(conn: &mut PgConnection, viewer: &User, id: Uuid) -> Result<OrgInvitation> {
+        InvitationRepositoryImpl
+            .find_pending(conn, viewer.org_id, id)
+            .await?
+            .ok_or_else(|| not_found(id))
+    }
+
+    /// Fails unless `user` may invite with `role`; only admins invite admins
+    fn check_role(user: &User, role: Role) -> Result<()> {
+        if role == Role::Admin && user.role != Role::Admin {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Only admins can invite admins",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "role",
+                    "code": "FORBIDDEN",
+                })),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fails when a user already has `email`
+    async fn check_unused(conn: &mut PgConnection, email: &str) -> Result<()> {
+        if UserRepositoryImpl.find_by_email(conn, email).await?.is_some() {
+            return Err(ApiError::validation_with_context(
+                "Email already in use",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "email",
+                    "code": "DUPLICATE",
+                    "value": email
+                }))
+            ));
+        }
+        Ok(())
+    }
+
+    /// Queues the email carrying a freshly signed token for `invitation`,
+    /// sent in the name of `sender`
+    async fn mail(conn: &mut PgConnection, invitation: &OrgInvitation, sender: &User, config: &Config) -> Result<()> {
+        let organization = OrganizationRepositoryImpl.find_by_id(conn, invitation.org_id).await?;
+        let token = issue_token(invitation, config)?;
+        let data = InvitationEmail {
+            organization: organization.name,
+            inviter: format!("{} {}", sender.first_name, sender.last_name),
+            accept_url: format!("{}/register?invite={}", config.email.app_url.trim_end_matches('/'), token),
+            expires_at: invitation.expires_at,
+        };
+        let message = config.mailer().compose(conn, Some(invitation.org_id), &invitation.email, &data).await?;
+        email::enqueue(conn, &config.queue, &message).await?;
+        Ok(())
+    }
+}
+
+fn not_found(id: Uuid) -> ApiError {
+    ApiError::not_found(format!("Invitation with id {} not found", id))
+}
@@ -0,0 +1,60 @@
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    db::models::{auth::Role, OrgInvitation},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::Config,
+};
+
+/// Audience of invitation tokens, so they can't pass for access tokens
+const INVITATION_AUDIENCE: &str = "invitation";
+
+/// What an invitation token vouches for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvitationClaims {
+    /// Id of the invitation
+    pub sub: Uuid,
+    pub org_id: Uuid,
+    pub role: Role,
+    pub email: String,
+    aud: String,
+    exp: i64,
+}
+
+/// The signed token mailed with `invitation`
+pub fn issue_token(invitation: &OrgInvitation, config: &Config) -> Result<String> {
+    let claims = InvitationClaims {
+        sub: invitation.id,
+        org_id: invitation.org_id,
+        role: invitation.role,
+        email: invitation.email.clone(),
+        aud: INVITATION_AUDIENCE.to_string(),
+        exp: invitation.expires_at.timestamp(),
+    };
+    encode(&Header::default(), &claims, &EncodingKey::from_secret(config.jwt_secret.as_bytes()))
+        .map_err(|e| ApiError::new(ErrorCode::InternalError, format!("Failed to sign invitation: {}", e), ErrorContext::new()))
+}
+
+/// The claims of an unexpired invitation token
+///
+/// Whether the invitation is still pending is up to the caller to check.
+pub fn verify_token(token: &str, config: &Config) -> Result<InvitationClaims> {
+    let mut validation = Validation::default();
+    validation.set_audience(&[INVITATION_AUDIENCE]);
+    decode::<InvitationClaims>(token, &DecodingKey::from_secret(config.jwt_secret.as_bytes()), &validation)
+        .map(|data| data.claims)
+        .map_err(|_| invalid_invitation())
+}
+
+/// The error for tokens that are forged, expired, used or revoked
+pub(super) fn invalid_invitation() -> ApiError {
+    ApiError::validation_with_context(
+        "Invalid or expired invitation",
+        ErrorContext::new().with_details(serde_json::json!({
+            "field": "invite_token",
+            "code": "INVALID",
+        })),
+    )
+}
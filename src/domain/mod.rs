@@ -1,6 +1,58 @@
+pub mod activity;
+pub mod approval;
 pub mod auth;
+pub mod billing;
+pub mod block;
+pub mod certification;
+pub mod crew;
+pub mod customer;
+pub mod document;
+pub mod erp;
+pub mod history;
+pub mod import;
+pub mod invitation;
+pub mod notification;
+pub mod operability;
 pub mod organization;
+pub mod presence;
+pub mod report;
+pub mod retention;
+pub mod roads;
+pub mod sales;
+pub mod scim;
+pub mod search;
+pub mod stand;
+pub mod tag;
+pub mod telemetry;
+pub mod tracking;
+pub mod user;
+pub mod view;
+pub mod windthrow;
 
 // Re-export commonly used types
+pub use activity::{ActivityLog, ActivityService};
+pub use approval::ApprovalService;
+pub use block::HarvestBlockService;
 pub use auth::{AuthService, TokenManager};
-pub use organization::OrganizationService;
\ No newline at end of file
+pub use billing::BillingService;
+pub use certification::CertificationService;
+pub use crew::CrewService;
+pub use customer::CustomerService;
+pub use document::DocumentService;
+pub use erp::ErpService;
+pub use history::HistoryService;
+pub use import::ImportService;
+pub use invitation::InvitationService;
+pub use notification::NotificationService;
+pub use organization::OrganizationService;
+pub use presence::PresenceService;
+pub use report::ReportService;
+pub use retention::{LegalHoldService, RetentionPolicy};
+pub use sales::TimberSaleService;
+pub use scim::ScimService;
+pub use search::QuickSearchService;
+pub use stand::StandService;
+pub use tag::TagService;
+pub use telemetry::TelemetryService;
+pub use user::{ActivationService, AnonymizationService, AvatarService, PreferenceService, Preferences, ProfileService, SupervisorService};
+pub use view::SavedViewService;
\ No newline at end of file
@@ -0,0 +1,70 @@
+use crate::{
+    api::utils::PaginationParams,
+    db::{count::RowCount, models::Notification, repositories::NotificationRepository},
+    error::Result,
+};
+use chrono::Utc;
+use diesel::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+
+/// Service for creating and reading in-app notifications
+///
+/// [`NotificationService::notify`] is the entry point for anything that
+/// raises notifications, such as the notification rules.
+pub struct NotificationService<R: NotificationRepository + Send + Sync> {
+    repository: R,
+}
+
+impl<R: NotificationRepository + Send + Sync> NotificationService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// Delivers notifications to their recipients' notification centers
+    pub async fn notify(&self, conn: &mut PgConnection, notifications: &[Notification]) -> Result<Vec<Notification>> {
+        if notifications.is_empty() {
+            return Ok(Vec::new());
+        }
+        let created = self.repository.create_many(conn, notifications).await?;
+        for notification in &created {
+            info!(
+                notification_id = %notification.id,
+                user_id = %notification.user_id,
+                kind = %notification.kind,
+                "Notification created"
+            );
+        }
+        Ok(created)
+    }
+
+    /// Lists a user's notifications, newest first
+    pub async fn list(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        unread_only: bool,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<Notification>, RowCount)> {
+        let notifications = self.repository.list_for_user(conn, user_id, unread_only, pagination).await?;
+        let total = self.repository.count_for_user(conn, user_id, unread_only).await?;
+        Ok((notifications, total))
+    }
+
+    /// Number of notifications the user has not read
+    pub async fn unread_count(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<i64> {
+        Ok(self.repository.count_for_user(conn, user_id, true).await?.total)
+    }
+
+    /// Marks one of the user's notifications read
+    pub async fn mark_read(&self, conn: &mut PgConnection, user_id: Uuid, notification_id: Uuid) -> Result<Notification> {
+        self.repository.mark_read(conn, user_id, notification_id).await
+    }
+
+    /// Marks all of the user's notifications read
+    ///
+    /// Notifications arriving while the request runs stay unread.
+    pub async fn mark_all_read(&self, conn: &mut PgConnection, user_id: Uuid) -> Result<usize> {
+        self.repository.mark_all_read(conn, user_id, Utc::now()).await
+    }
+}
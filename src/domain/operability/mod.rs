@@ -0,0 +1,10 @@
+//! Wet-weather operability
+//!
+//! Ground-based harvesting on wet soil ruts and compacts it, so operations
+//! pause on sensitive ground after heavy rain. A block's operability
+//! follows from its soil sensitivity and an antecedent precipitation
+//! index, which weighs each recent day's rain by how long ago it fell.
+
+mod rutting;
+
+pub use rutting::{antecedent_index, assess, Operability, OperabilityStatus, SoilSensitivity, DECAY, LOOKBACK_DAYS};
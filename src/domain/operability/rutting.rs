@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// Days of precipitation the antecedent index looks back over
+pub const LOOKBACK_DAYS: usize = 7;
+
+/// Share of a day's rain still counted one day later
+pub const DECAY: f64 = 0.85;
+
+/// How easily a block's soil ruts under ground-based equipment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SoilSensitivity {
+    /// Coarse, well-drained soils such as gravels and sands
+    Low,
+    /// Loams
+    Moderate,
+    /// Fine-textured silts and clays
+    High,
+    /// Organic and permanently wet soils
+    VeryHigh,
+}
+
+impl SoilSensitivity {
+    /// Antecedent index, in millimetres, at which operations need care and
+    /// at which they stop
+    fn thresholds(self) -> (f64, f64) {
+        match self {
+            Self::Low => (40.0, 70.0),
+            Self::Moderate => (25.0, 45.0),
+            Self::High => (15.0, 30.0),
+            Self::VeryHigh => (8.0, 15.0),
+        }
+    }
+}
+
+/// Whether ground-based operations may run on a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum OperabilityStatus {
+    /// Normal operations
+    Open,
+    /// Operations may continue on corduroyed trails, with low ground
+    /// pressure equipment or on the block's drier parts
+    Caution,
+    /// Ground-based operations should pause
+    Shutdown,
+}
+
+/// Operability of a block with the figures it was assessed from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Operability {
+    pub status: OperabilityStatus,
+    pub sensitivity: SoilSensitivity,
+    /// Antecedent precipitation index, in millimetres
+    pub antecedent_mm: f64,
+    /// Index at which operations stop on this soil
+    pub shutdown_mm: f64,
+}
+
+/// Antecedent precipitation index of daily totals in millimetres, most
+/// recent day first
+///
+/// Days beyond [`LOOKBACK_DAYS`] are ignored, as are missing (negative or
+/// non-finite) readings.
+pub fn antecedent_index(daily_mm: &[f64]) -> f64 {
+    daily_mm
+        .iter()
+        .take(LOOKBACK_DAYS)
+        .zip(std::iter::successors(Some(1.0), |weight| Some(weight * DECAY)))
+        .filter(|(mm, _)| mm.is_finite() && **mm > 0.0)
+        .map(|(mm, weight)| mm * weight)
+        .sum()
+}
+
+/// Assesses a block from its soil sensitivity and recent daily
+/// precipitation, most recent day first
+pub fn assess(sensitivity: SoilSensitivity, daily_mm: &[f64]) -> Operability {
+    let antecedent_mm = (antecedent_index(daily_mm) * 10.0).round() / 10.0;
+    let (caution_mm, shutdown_mm) = sensitivity.thresholds();
+    let status = if antecedent_mm >= shutdown_mm {
+        OperabilityStatus::Shutdown
+    } else if antecedent_mm >= caution_mm {
+        OperabilityStatus::Caution
+    } else {
+        OperabilityStatus::Open
+    };
+
+    Operability {
+        status,
+        sensitivity,
+        antecedent_mm,
+        shutdown_mm,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_antecedent_index_decays() {
+        assert_eq!(antecedent_index(&[]), 0.0);
+        assert!((antecedent_index(&[10.0, 10.0]) - 18.5).abs() < 1e-9);
+        assert!((antecedent_index(&[0.0, f64::NAN, -1.0, 10.0]) - 6.141_25).abs() < 1e-9);
+
+        // Only the last week counts
+        let mut days = vec![0.0; LOOKBACK_DAYS];
+        days.push(100.0);
+        assert_eq!(antecedent_index(&days), 0.0);
+    }
+
+    #[test]
+    fn test_assess_by_sensitivity() {
+        let rain = [12.0, 8.0, 4.0];
+        let assessments = [
+            SoilSensitivity::Low,
+            SoilSensitivity::Moderate,
+            SoilSensitivity::High,
+            SoilSensitivity::VeryHigh,
+        ]
+        .map(|sensitivity| assess(sensitivity, &rain).status);
+        assert_eq!(assessments, [
+            OperabilityStatus::Open,
+            OperabilityStatus::Open,
+            OperabilityStatus::Caution,
+            OperabilityStatus::Shutdown,
+        ]);
+
+        let soaked = assess(SoilSensitivity::Moderate, &[30.0, 20.0]);
+        assert_eq!(soaked.antecedent_mm, 47.0);
+        assert_eq!(soaked.status, OperabilityStatus::Shutdown);
+    }
+}
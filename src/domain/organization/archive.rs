@@ -0,0 +1,362 @@
+use std::io::Write;
+
+use bytes::Bytes;
+use chrono::Utc;
+use diesel::{
+    prelude::*,
+    sql_types::{Array, Text, Uuid as SqlUuid},
+};
+use flate2::{write::GzEncoder, Compression};
+use serde_json::{json, Map, Value};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{auth::User, Organization, OrganizationArchive, OrganizationArchiveStatus},
+        repositories::{
+            organization::OrganizationRepository, OrganizationArchiveRepository, OrganizationArchiveRepositoryImpl,
+            OrganizationRepositoryImpl, Repository,
+        },
+    },
+    domain::{
+        activity::{ActivityFeed, FeedEvent, FeedKind},
+        organization::OrganizationService,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::ObjectStorage,
+    jobs::queue,
+    utils::QueueConfig,
+};
+
+/// Kind of the queue job that writes an archived organization's export bundle
+pub const ORGANIZATION_EXPORT_JOB: &str = "organization_export";
+
+/// Version of the export bundle layout, bumped when it changes incompatibly
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// A table in the export bundle
+///
+/// `scope` selects the rows of the exported organizations, bound to `$1`;
+/// `omit` lists columns left out, such as password hashes.
+#[derive(Debug, Clone, Copy)]
+pub struct ExportTable {
+    pub name: &'static str,
+    pub scope: &'static str,
+    pub omit: &'static [&'static str],
+}
+
+const fn owned(name: &'static str) -> ExportTable {
+    ExportTable { name, scope: "org_id = ANY($1)", omit: &[] }
+}
+
+/// Tables of an organization's data, in the order they appear in the bundle
+///
+/// Credentials (login tokens, passkeys, SCIM tokens) are left out: they are
+/// of no use outside this deployment.
+pub const EXPORT_TABLES: &[ExportTable] = &[
+    ExportTable { name: "organizations", scope: "id = ANY($1)", omit: &[] },
+    owned("organization_settings"),
+    owned("organization_quotas"),
+    owned("organization_sso_domains"),
+    owned("organization_email_senders"),
+    owned("subscriptions"),
+    owned("roles"),
+    ExportTable { name: "users", scope: "org_id = ANY($1)", omit: &["password"] },
+    ExportTable { name: "user_roles", scope: "user_id IN (SELECT id FROM users WHERE org_id = ANY($1))", omit: &[] },
+    ExportTable { name: "user_preferences", scope: "user_id IN (SELECT id FROM users WHERE org_id = ANY($1))", omit: &[] },
+    owned("user_activities"),
+    owned("org_invitations"),
+    owned("ownership_transfers"),
+    owned("notifications"),
+    owned("customers"),
+    owned("supply_contracts"),
+    owned("harvest_blocks"),
+    owned("stands"),
+    owned("machine_telemetry"),
+    owned("crews"),
+    owned("crew_members"),
+    owned("timber_tenders"),
+    ExportTable { name: "tender_parcels", scope: "tender_id IN (SELECT id FROM timber_tenders WHERE org_id = ANY($1))", omit: &[] },
+    ExportTable { name: "tender_bids", scope: "tender_id IN (SELECT id FROM timber_tenders WHERE org_id = ANY($1))", omit: &[] },
+    owned("sale_contracts"),
+    owned("block_signoffs"),
+    owned("certifications"),
+    owned("documents"),
+    ExportTable { name: "document_versions", scope: "document_id IN (SELECT id FROM documents WHERE org_id = ANY($1))", omit: &[] },
+    owned("tags"),
+    owned("taggings"),
+    owned("saved_views"),
+    owned("reports"),
+    owned("report_schedules"),
+    owned("report_deliveries"),
+    owned("imports"),
+    owned("erp_connections"),
+    owned("erp_exports"),
+    owned("change_history"),
+];
+
+/// Object storage key of an archival's export bundle
+pub fn bundle_key(archive: &OrganizationArchive) -> String {
+    format!("organizations/{}/archives/{}.json.gz", archive.org_id, archive.id)
+}
+
+/// Name the export bundle is downloaded as, e.g.
+/// `organization-3f2c...-20250601T060000Z.json.gz`
+pub fn bundle_filename(archive: &OrganizationArchive) -> String {
+    format!("organization-{}-{}.json.gz", archive.org_id, archive.created_at.format("%Y%m%dT%H%M%SZ"))
+}
+
+#[derive(QueryableByName)]
+struct ExportedRows {
+    #[diesel(sql_type = Text)]
+    rows: String,
+}
+
+/// The rows of `table` for `organizations`, as a JSON array
+fn export_rows(conn: &mut PgConnection, table: &ExportTable, organizations: &[Uuid]) -> Result<Value> {
+    let omit: String = table.omit.iter().map(|column| format!(" - '{}'", column)).collect();
+    let query = format!(
+        "SELECT COALESCE(jsonb_agg(to_jsonb(t){}), '[]'::jsonb)::text AS rows FROM {} t WHERE {}",
+        omit, table.name, table.scope
+    );
+    let exported: ExportedRows = diesel::sql_query(query)
+        .bind::<Array<SqlUuid>, _>(organizations)
+        .get_result(conn)
+        .map_err(|e| ApiError::database_error(format!("Failed to export {}: {}", table.name, e), None))?;
+    serde_json::from_str(&exported.rows)
+        .map_err(|e| ApiError::database_error(format!("Failed to export {}: {}", table.name, e), None))
+}
+
+fn archive_conflict(message: &str, code: &str) -> ApiError {
+    ApiError::new(
+        ErrorCode::Conflict,
+        message,
+        ErrorContext::new().with_details(json!({ "code": code })),
+    )
+}
+
+/// Archives organizations whose contract ended and reopens them
+///
+/// An archived organization, and every organization under it, keeps its
+/// data but takes no more writes; its members can still sign in and read.
+/// Archiving also writes an export bundle of all the organization's data
+/// (gzipped JSON, one array of rows per table) to object storage through
+/// the job queue.
+pub struct ArchiveService;
+
+impl ArchiveService {
+    /// Fails while `org_id` is archived, for writes made on its behalf
+    pub async fn ensure_writable(conn: &mut PgConnection, org_id: Uuid) -> Result<()> {
+        if OrganizationArchiveRepositoryImpl.is_archived(conn, org_id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "The organization is archived and read-only",
+                ErrorContext::new().with_details(json!({ "code": "ORGANIZATION_ARCHIVED" })),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fails unless `admin`'s organization governs `org_id`
+    async fn governed(conn: &mut PgConnection, admin: &User, org_id: Uuid) -> Result<Organization> {
+        let organizations = OrganizationService::new(OrganizationRepositoryImpl);
+        let organization = organizations.repository().find_by_id(conn, org_id).await?;
+        if !organizations.governs(conn, admin.org_id, organization.id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Archives of other organizations can't be accessed",
+                ErrorContext::new(),
+            ));
+        }
+        Ok(organization)
+    }
+
+    /// Archives `org_id` with the organizations under it and queues its
+    /// export bundle
+    pub async fn archive(conn: &mut PgConnection, queue_config: &QueueConfig, admin: &User, org_id: Uuid) -> Result<OrganizationArchive> {
+        let organization = Self::governed(conn, admin, org_id).await?;
+        let below: Vec<Uuid> = OrganizationRepositoryImpl
+            .subtree(conn, &organization)
+            .await?
+            .into_iter()
+            .skip(1)
+            .map(|(organization, _)| organization.id)
+            .collect();
+
+        let archive = OrganizationArchiveRepositoryImpl
+            .archive(conn, &OrganizationArchive {
+                id: Uuid::new_v4(),
+                org_id: organization.id,
+                requested_by: Some(admin.id),
+                status: OrganizationArchiveStatus::Pending.as_str().to_string(),
+                file_key: None,
+                byte_size: None,
+                error: None,
+                created_at: Utc::now(),
+                completed_at: None,
+                unarchived_at: None,
+            }, &below)
+            .await?
+            .ok_or_else(|| archive_conflict("The organization is already archived", "ALREADY_ARCHIVED"))?;
+        queue::enqueue(conn, queue_config, ORGANIZATION_EXPORT_JOB, json!({ "archive_id": archive.id })).await?;
+
+        info!(
+            org_id = %organization.id,
+            archive_id = %archive.id,
+            archived_by = %admin.id,
+            organizations_below = below.len(),
+            "Organization archived"
+        );
+        ActivityFeed::record(
+            conn,
+            organization.id,
+            Some(admin.id),
+            FeedEvent::new(
+                FeedKind::OrganizationArchived,
+                "organization",
+                organization.id,
+                format!("{} {} archived {}", admin.first_name, admin.last_name, organization.name),
+            )
+            .with_data(json!({ "archive_id": archive.id, "organizations_below": below.len() })),
+        )
+        .await;
+        Ok(archive)
+    }
+
+    /// Reopens `org_id` and the organizations archived with it
+    ///
+    /// An organization archived together with one above it is reopened with
+    /// that one.
+    pub async fn unarchive(conn: &mut PgConnection, admin: &User, org_id: Uuid) -> Result<OrganizationArchive> {
+        let organization = Self::governed(conn, admin, org_id).await?;
+        if organization.archived_at.is_none() {
+            return Err(archive_conflict("The organization isn't archived", "NOT_ARCHIVED"));
+        }
+        if let Some(parent_id) = organization.parent_id {
+            let parent = OrganizationRepositoryImpl.find_by_id(conn, parent_id).await?;
+            if parent.archived_at.is_some() && parent.archived_at == organization.archived_at {
+                return Err(archive_conflict(
+                    "The organization was archived with the one above it, reopen that one instead",
+                    "ARCHIVED_WITH_PARENT",
+                ));
+            }
+        }
+        let below: Vec<Uuid> = OrganizationRepositoryImpl
+            .subtree(conn, &organization)
+            .await?
+            .into_iter()
+            .skip(1)
+            .map(|(organization, _)| organization.id)
+            .collect();
+
+        let archive = OrganizationArchiveRepositoryImpl
+            .unarchive(conn, organization.id, &below)
+            .await?
+            .ok_or_else(|| archive_conflict("The organization isn't archived", "NOT_ARCHIVED"))?;
+        info!(org_id = %organization.id, archive_id = %archive.id, unarchived_by = %admin.id, "Organization unarchived");
+        ActivityFeed::record(
+            conn,
+            organization.id,
+            Some(admin.id),
+            FeedEvent::new(
+                FeedKind::OrganizationUnarchived,
+                "organization",
+                organization.id,
+                format!("{} {} reopened {}", admin.first_name, admin.last_name, organization.name),
+            )
+            .with_data(json!({ "archive_id": archive.id })),
+        )
+        .await;
+        Ok(archive)
+    }
+
+    /// The most recent archival of `org_id`, for a member of it or of an
+    /// organization above it
+    pub async fn latest(conn: &mut PgConnection, viewer: &User, org_id: Uuid) -> Result<OrganizationArchive> {
+        let organization = Self::governed(conn, viewer, org_id).await?;
+        OrganizationArchiveRepositoryImpl
+            .latest(conn, organization.id)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Organization {} was never archived", org_id)))
+    }
+
+    /// The export bundle of `org_id`'s most recent archival
+    pub async fn bundle(conn: &mut PgConnection, storage: &ObjectStorage, admin: &User, org_id: Uuid) -> Result<(OrganizationArchive, Bytes)> {
+        let archive = Self::latest(conn, admin, org_id).await?;
+        let Some(key) = archive.file_key.as_deref() else {
+            return Err(archive_conflict("The export bundle is still being written", "EXPORT_PENDING"));
+        };
+        let bundle = storage.get(key).await?;
+        Ok((archive, bundle))
+    }
+
+    /// Writes the export bundle of an archival, `None` when it was already
+    /// written
+    ///
+    /// Failures are recorded on the archival and returned, so the job is
+    /// retried.
+    pub async fn write_bundle(conn: &mut PgConnection, storage: &ObjectStorage, archive_id: Uuid) -> Result<Option<OrganizationArchive>> {
+        let repository = OrganizationArchiveRepositoryImpl;
+        let archive = repository
+            .find(conn, archive_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Organization archive {} not found", archive_id)))?;
+        if archive.status == OrganizationArchiveStatus::Completed.as_str() {
+            return Ok(None);
+        }
+
+        let written = async {
+            let bundle = Self::export(conn, &archive).await?;
+            let key = bundle_key(&archive);
+            let byte_size = bundle.len() as i64;
+            storage.put(&key, bundle).await?;
+            repository.complete(conn, archive.id, &key, byte_size).await?;
+            Ok::<_, ApiError>((key, byte_size))
+        }
+        .await;
+        match written {
+            Ok((key, byte_size)) => {
+                info!(org_id = %archive.org_id, archive_id = %archive.id, key = %key, byte_size, "Organization export bundle written");
+                repository.find(conn, archive.id).await
+            }
+            Err(e) => {
+                warn!(org_id = %archive.org_id, archive_id = %archive.id, error = %e.message, "Organization export failed");
+                repository.fail(conn, archive.id, &e.message).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// The gzipped export bundle of an archival's organization and those
+    /// under it
+    async fn export(conn: &mut PgConnection, archive: &OrganizationArchive) -> Result<Bytes> {
+        let organization = OrganizationRepositoryImpl.find_by_id(conn, archive.org_id).await?;
+        let organizations: Vec<Uuid> = OrganizationRepositoryImpl
+            .subtree(conn, &organization)
+            .await?
+            .into_iter()
+            .map(|(organization, _)| organization.id)
+            .collect();
+
+        let mut tables = Map::new();
+        for table in EXPORT_TABLES {
+            tables.insert(table.name.to_string(), export_rows(conn, table, &organizations)?);
+        }
+        let document = json!({
+            "format_version": EXPORT_FORMAT_VERSION,
+            "organization_id": organization.id,
+            "organization_name": organization.name,
+            "archived_at": organization.archived_at,
+            "exported_at": Utc::now(),
+            "tables": tables,
+        });
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&serde_json::to_vec(&document).unwrap_or_default())
+            .and_then(|_| encoder.finish())
+            .map(Bytes::from)
+            .map_err(|e| ApiError::database_error(format!("Failed to compress the export bundle: {}", e), None))
+    }
+}
@@ -0,0 +1,163 @@
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        models::auth::{Role, User, UserFilter},
+        repositories::{
+            auth::{RefreshTokenRepository, RefreshTokenRepositoryImpl, UserRepository, UserRepositoryImpl},
+            MembershipRepository, MembershipRepositoryImpl, OrganizationRepositoryImpl, Repository,
+        },
+    },
+    domain::{
+        activity::{ActivityFeed, FeedEvent, FeedKind},
+        auth::RevocationList,
+        organization::OrganizationService,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::Config,
+};
+
+/// Manages who belongs to an organization and with what role
+///
+/// Managers and admins manage the members of their organization and of
+/// those under it, but only admins promote members to admin or change and
+/// remove admins. Every organization keeps an active admin, and its owner
+/// stays an admin until they hand the organization over.
+pub struct MembershipService;
+
+fn membership_conflict(message: &str, code: &str) -> ApiError {
+    ApiError::new(
+        ErrorCode::Conflict,
+        message,
+        ErrorContext::new().with_details(json!({ "code": code })),
+    )
+}
+
+impl MembershipService {
+    /// Pages through the members of `org_id` matching `filter`, with their
+    /// count
+    pub async fn list(
+        conn: &mut PgConnection,
+        viewer: &User,
+        org_id: Uuid,
+        filter: &UserFilter,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<User>, i64)> {
+        Self::governed(conn, viewer, org_id).await?;
+        UserRepositoryImpl.search(conn, org_id, filter, pagination).await
+    }
+
+    /// Gives member `user_id` of `org_id` the role `role`
+    ///
+    /// Their access tokens carry the role, so they sign in again.
+    pub async fn change_role(
+        conn: &mut PgConnection,
+        config: &Config,
+        manager: &User,
+        org_id: Uuid,
+        user_id: Uuid,
+        role: Role,
+    ) -> Result<User> {
+        let member = Self::find_member(conn, manager, org_id, user_id).await?;
+        let previous = member.role;
+        if role == Role::Admin && manager.role != Role::Admin {
+            return Err(ApiError::new(ErrorCode::Forbidden, "Only admins make members admins", ErrorContext::new()));
+        }
+        if member.role == role {
+            return Ok(member);
+        }
+        if role != Role::Admin {
+            Self::check_not_owner(conn, &member, "The owner stays an admin, transfer the ownership first").await?;
+        }
+
+        let member = MembershipRepositoryImpl
+            .change_role(conn, org_id, member.id, role)
+            .await?
+            .ok_or_else(|| membership_conflict("The organization's last admin stays an admin", "LAST_ADMIN"))?;
+        RevocationList::revoke_user(config, member.id).await;
+        info!(user_id = %member.id, org_id = %org_id, role = ?role, changed_by = %manager.id, "Member role changed");
+        ActivityFeed::record(
+            conn,
+            org_id,
+            Some(manager.id),
+            FeedEvent::new(
+                FeedKind::MemberRoleChanged,
+                "user",
+                member.id,
+                format!("{} {} is now {:?}", member.first_name, member.last_name, role),
+            )
+            .with_data(json!({ "from": previous, "to": role })),
+        )
+        .await;
+        Ok(member)
+    }
+
+    /// Removes member `user_id` from `org_id`, ending their sessions
+    ///
+    /// Removed users keep their records and can be restored.
+    pub async fn remove(conn: &mut PgConnection, config: &Config, manager: &User, org_id: Uuid, user_id: Uuid) -> Result<User> {
+        let member = Self::find_member(conn, manager, org_id, user_id).await?;
+        Self::check_not_owner(conn, &member, "The owner can't be removed, transfer the ownership first").await?;
+
+        let member = MembershipRepositoryImpl
+            .remove(conn, org_id, member.id)
+            .await?
+            .ok_or_else(|| membership_conflict("The organization's last admin can't be removed", "LAST_ADMIN"))?;
+        RefreshTokenRepositoryImpl.revoke_all_for_user(conn, member.id).await?;
+        RevocationList::revoke_user(config, member.id).await;
+        info!(user_id = %member.id, org_id = %org_id, removed_by = %manager.id, "Member removed");
+        ActivityFeed::record(
+            conn,
+            org_id,
+            Some(manager.id),
+            FeedEvent::new(
+                FeedKind::MemberRemoved,
+                "user",
+                member.id,
+                format!("{} {} was removed", member.first_name, member.last_name),
+            ),
+        )
+        .await;
+        Ok(member)
+    }
+
+    /// Fails unless `viewer`'s organization governs `org_id`
+    async fn governed(conn: &mut PgConnection, viewer: &User, org_id: Uuid) -> Result<()> {
+        let organizations = OrganizationService::new(OrganizationRepositoryImpl);
+        organizations.repository().find_by_id(conn, org_id).await?;
+        if !organizations.governs(conn, viewer.org_id, org_id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Members of other organizations can't be managed",
+                ErrorContext::new(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Member `user_id` of `org_id`, if `manager` may manage them
+    async fn find_member(conn: &mut PgConnection, manager: &User, org_id: Uuid, user_id: Uuid) -> Result<User> {
+        Self::governed(conn, manager, org_id).await?;
+        let member = UserRepositoryImpl.find_by_id(conn, user_id).await?;
+        if member.org_id != org_id {
+            return Err(ApiError::not_found(format!("User with id {} not found", user_id)));
+        }
+        if member.role == Role::Admin && manager.role != Role::Admin {
+            return Err(ApiError::new(ErrorCode::Forbidden, "Only admins manage admins", ErrorContext::new()));
+        }
+        Ok(member)
+    }
+
+    /// Fails if `member` owns their organization
+    async fn check_not_owner(conn: &mut PgConnection, member: &User, message: &str) -> Result<()> {
+        let organization = OrganizationRepositoryImpl.find_by_id(conn, member.org_id).await?;
+        if organization.owner_id == Some(member.id) {
+            return Err(membership_conflict(message, "OWNER"));
+        }
+        Ok(())
+    }
+}
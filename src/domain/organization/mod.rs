@@ -1,5 +1,13 @@
+mod archive;
+mod members;
+mod quota;
 mod service;
+mod settings;
 mod validation;
 
-pub use service::OrganizationService;
+pub use archive::{bundle_filename, bundle_key, ArchiveService, ExportTable, EXPORT_FORMAT_VERSION, EXPORT_TABLES, ORGANIZATION_EXPORT_JOB};
+pub use members::MembershipService;
+pub use quota::{QuotaService, QuotaUsage, Quotas, DEFAULT_TELEMETRY_RETENTION_DAYS, MAX_TELEMETRY_RETENTION_DAYS};
+pub use service::{OrganizationService, OWNERSHIP_TRANSFER_DAYS, OWNERSHIP_TRANSFER_KIND};
+pub use settings::{OrgSettings, OrganizationSettingsService, SettingsChanges, MAX_SAFETY_FORMS, MAX_SAFETY_FORM_LENGTH};
 pub use validation::OrganizationValidator;
\ No newline at end of file
@@ -0,0 +1,148 @@
+use chrono::Utc;
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{auth::User, OrganizationQuota},
+        repositories::{OrganizationRepositoryImpl, QuotaRepository, QuotaRepositoryImpl, Repository},
+    },
+    domain::organization::OrganizationService,
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+
+/// Days telemetry is kept for organizations whose plan doesn't say
+pub const DEFAULT_TELEMETRY_RETENTION_DAYS: u32 = 90;
+
+/// Longest telemetry retention a plan can grant
+pub const MAX_TELEMETRY_RETENTION_DAYS: u32 = 3650;
+
+/// Limits of an organization's plan; counts left `None` are unlimited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Quotas {
+    pub max_users: Option<u32>,
+    pub max_active_blocks: Option<u32>,
+    /// `None` keeps telemetry for `DEFAULT_TELEMETRY_RETENTION_DAYS`
+    pub telemetry_retention_days: Option<u32>,
+}
+
+impl Quotas {
+    fn from_stored(stored: &OrganizationQuota) -> Self {
+        let limit = |value: Option<i32>| value.and_then(|value| u32::try_from(value).ok());
+        Self {
+            max_users: limit(stored.max_users),
+            max_active_blocks: limit(stored.max_active_blocks),
+            telemetry_retention_days: limit(stored.telemetry_retention_days),
+        }
+    }
+
+    /// Days telemetry is kept
+    pub fn retention_days(&self) -> u32 {
+        self.telemetry_retention_days.unwrap_or(DEFAULT_TELEMETRY_RETENTION_DAYS)
+    }
+}
+
+/// An organization's quotas with how much of them it uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaUsage {
+    pub quotas: Quotas,
+    /// Users of the organization, deactivated ones included
+    pub users: i64,
+}
+
+fn invalid(field: &str, message: impl Into<String>) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": field,
+            "code": "OUT_OF_RANGE",
+        })),
+    )
+}
+
+/// Sets organizations' quotas and holds them to those
+///
+/// Operators set quotas through the admin API; creating what a quota
+/// limits fails with `ErrorCode::QuotaExceeded` once it is used up.
+pub struct QuotaService;
+
+impl QuotaService {
+    /// The quotas of `org_id`, unlimited when none were set
+    pub async fn of(conn: &mut PgConnection, org_id: Uuid) -> Result<Quotas> {
+        Ok(QuotaRepositoryImpl
+            .find(conn, org_id)
+            .await?
+            .map(|stored| Quotas::from_stored(&stored))
+            .unwrap_or_default())
+    }
+
+    /// Replaces the quotas of `org_id`
+    pub async fn set(conn: &mut PgConnection, org_id: Uuid, quotas: Quotas) -> Result<Quotas> {
+        let organization = OrganizationRepositoryImpl.find_by_id(conn, org_id).await?;
+        if quotas.max_users == Some(0) {
+            return Err(invalid("max_users", "An organization needs room for at least one user"));
+        }
+        if quotas
+            .telemetry_retention_days
+            .is_some_and(|days| !(1..=MAX_TELEMETRY_RETENTION_DAYS).contains(&days))
+        {
+            return Err(invalid(
+                "telemetry_retention_days",
+                format!("Telemetry retention must be from 1 to {} days", MAX_TELEMETRY_RETENTION_DAYS),
+            ));
+        }
+
+        let now = Utc::now();
+        let column = |value: Option<u32>| value.map(|value| i32::try_from(value).unwrap_or(i32::MAX));
+        let stored = QuotaRepositoryImpl
+            .save(conn, &OrganizationQuota {
+                org_id: organization.id,
+                max_users: column(quotas.max_users),
+                max_active_blocks: column(quotas.max_active_blocks),
+                telemetry_retention_days: column(quotas.telemetry_retention_days),
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+        info!(
+            org_id = %organization.id,
+            max_users = ?quotas.max_users,
+            max_active_blocks = ?quotas.max_active_blocks,
+            telemetry_retention_days = ?quotas.telemetry_retention_days,
+            "Organization quotas updated"
+        );
+        Ok(Quotas::from_stored(&stored))
+    }
+
+    /// The quotas and usage of `org_id` for `viewer`, who must belong to it
+    /// or to an organization above it
+    pub async fn usage(conn: &mut PgConnection, viewer: &User, org_id: Uuid) -> Result<QuotaUsage> {
+        let organizations = OrganizationService::new(OrganizationRepositoryImpl);
+        let organization = organizations.repository().find_by_id(conn, org_id).await?;
+        if !organizations.governs(conn, viewer.org_id, organization.id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Usage of other organizations can't be accessed",
+                ErrorContext::new(),
+            ));
+        }
+        Ok(QuotaUsage {
+            quotas: Self::of(conn, organization.id).await?,
+            users: QuotaRepositoryImpl.count_users(conn, organization.id).await?,
+        })
+    }
+
+    /// Fails unless `org_id` has room for `adding` more users
+    pub async fn check_users(conn: &mut PgConnection, org_id: Uuid, adding: usize) -> Result<()> {
+        let Some(limit) = Self::of(conn, org_id).await?.max_users else {
+            return Ok(());
+        };
+        let users = QuotaRepositoryImpl.count_users(conn, org_id).await?;
+        if users + adding as i64 > i64::from(limit) {
+            return Err(ApiError::quota_exceeded("users", i64::from(limit), users));
+        }
+        Ok(())
+    }
+}
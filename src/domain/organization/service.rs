@@ -2,16 +2,30 @@ use crate::{
     api::utils::PaginationParams,
     api::resources::organization::dto::{CreateOrganizationInput, UpdateOrganizationInput},
     db::{
-        models::Organization,
-        repositories::organization::OrganizationRepository,
+        count::RowCount,
+        hierarchy::MAX_HIERARCHY_DEPTH,
+        models::{auth::{User, UserFilter}, Notification, Organization, OrganizationFilter, OwnershipTransfer},
+        repositories::{
+            auth::{UserRepository, UserRepositoryImpl},
+            organization::OrganizationRepository,
+            NotificationRepositoryImpl, OwnershipTransferRepository, OwnershipTransferRepositoryImpl, Repository,
+        },
     },
-    domain::organization::validation::OrganizationValidator,
-    error::{ApiError, ErrorCode, Result},
+    domain::{notification::NotificationService, organization::validation::OrganizationValidator},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
 };
+use chrono::{Duration, Utc};
 use diesel::PgConnection;
+use serde_json::json;
 use tracing::{error, info};
 use uuid::Uuid;
 
+/// Days the new owner has to accept an ownership transfer
+pub const OWNERSHIP_TRANSFER_DAYS: i64 = 7;
+
+/// Kind of the notifications raised for ownership transfers
+pub const OWNERSHIP_TRANSFER_KIND: &str = "ownership_transfer";
+
 /// Service for managing organizations
 pub struct OrganizationService<R: OrganizationRepository + Send + Sync> {
     repository: R,
@@ -43,13 +57,15 @@ impl<R: OrganizationRepository + Send + Sync> OrganizationService<R> {
         result
     }
 
-    /// Updates an existing organization
+    /// Updates an organization `tenant` governs
     pub async fn update(
         &self,
         conn: &mut PgConnection,
+        tenant: Uuid,
         id: Uuid,
         input: UpdateOrganizationInput,
     ) -> Result<Organization> {
+        self.repository.find_governed(conn, tenant, id).await?;
         OrganizationValidator::validate_update(conn, &self.repository, &input, id).await?;
         
         let org: Organization = (id, input).into();
@@ -65,8 +81,17 @@ impl<R: OrganizationRepository + Send + Sync> OrganizationService<R> {
         result
     }
 
-    /// Deletes an organization
-    pub async fn delete(&self, conn: &mut PgConnection, id: Uuid) -> Result<Organization> {
+    /// Deletes an organization `tenant` governs, which must not have
+    /// divisions or areas left under it
+    pub async fn delete(&self, conn: &mut PgConnection, tenant: Uuid, id: Uuid) -> Result<Organization> {
+        self.repository.find_governed(conn, tenant, id).await?;
+        if self.repository.has_children(conn, id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Conflict,
+                "Remove the organizations under this one first",
+                ErrorContext::new().with_details(json!({ "code": "HAS_CHILDREN" })),
+            ));
+        }
         let result = self.repository.soft_delete(conn, id).await;
         
         if let Ok(org) = &result {
@@ -79,14 +104,234 @@ impl<R: OrganizationRepository + Send + Sync> OrganizationService<R> {
         result
     }
 
-    /// Gets an organization by ID
-    pub async fn get(&self, conn: &mut PgConnection, id: Uuid) -> Result<Organization> {
-        self.repository.find_by_id(conn, id).await
+    /// Brings back a soft-deleted organization `tenant` governs
+    ///
+    /// Its name must still be free among live organizations, and the
+    /// organization it sits under must have been restored first.
+    pub async fn restore(&self, conn: &mut PgConnection, tenant: Uuid, id: Uuid) -> Result<Organization> {
+        let Some(organization) = self.repository.find_deleted(conn, id).await? else {
+            // Answers 404 for organizations that don't exist or belong
+            // to another tenant
+            self.repository.find_governed(conn, tenant, id).await?;
+            return Err(ApiError::new(
+                ErrorCode::Conflict,
+                "The organization is not deleted",
+                ErrorContext::new().with_details(json!({ "code": "NOT_DELETED" })),
+            ));
+        };
+        if !self.repository.subtree_ids_with_removed(conn, tenant).await?.contains(&id) {
+            return Err(ApiError::not_found(format!("Organization with id {} not found", id)));
+        }
+        OrganizationValidator::validate_restore(conn, &self.repository, &organization).await?;
+        if let Some(parent_id) = organization.parent_id {
+            if self.repository.find_deleted(conn, parent_id).await?.is_some() {
+                return Err(ApiError::new(
+                    ErrorCode::Conflict,
+                    "Restore the organization above this one first",
+                    ErrorContext::new().with_details(json!({ "code": "PARENT_DELETED" })),
+                ));
+            }
+        }
+        let result = self.repository.restore(conn, id).await;
+
+        if let Ok(org) = &result {
+            info!(
+                organization_id = %org.id,
+                "Restored organization '{}'", org.name
+            );
+        }
+
+        result
+    }
+
+    /// Whether members of `organization` act on `target`, being the same
+    /// organization or one somewhere under it
+    pub async fn governs(&self, conn: &mut PgConnection, organization: Uuid, target: Uuid) -> Result<bool> {
+        if organization == target {
+            return Ok(true);
+        }
+        Ok(self.repository.ancestor_ids(conn, target).await?.contains(&organization))
+    }
+
+    /// Creates a division or area under `parent_id`, on behalf of a member
+    /// of `organization`, which must govern the parent
+    pub async fn create_child(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        parent_id: Uuid,
+        input: CreateOrganizationInput,
+    ) -> Result<Organization> {
+        let parent = self.repository.find_by_id(conn, parent_id).await?;
+        if !self.governs(conn, organization, parent.id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Organizations can only be created under your own",
+                ErrorContext::new(),
+            ));
+        }
+        if self.repository.ancestor_ids(conn, parent.id).await?.len() + 1 > MAX_HIERARCHY_DEPTH {
+            return Err(ApiError::validation_with_context(
+                format!("Organizations nest at most {} levels deep", MAX_HIERARCHY_DEPTH),
+                ErrorContext::new().with_details(json!({
+                    "field": "parent_id",
+                    "code": "TOO_DEEP",
+                })),
+            ));
+        }
+        OrganizationValidator::validate_create(conn, &self.repository, &input).await?;
+
+        let mut org: Organization = input.into();
+        org.parent_id = Some(parent.id);
+        let org = self.repository.create(conn, &org).await?;
+        info!(
+            organization_id = %org.id,
+            parent_id = %parent.id,
+            "Created organization '{}' under '{}'", org.name, parent.name
+        );
+        Ok(org)
     }
 
-    /// Lists organizations with pagination
-    pub async fn list(&self, conn: &mut PgConnection, pagination: &PaginationParams) -> Result<Vec<Organization>> {
-        self.repository.list(conn, pagination).await
+    /// `root_id` and the organizations under it with their depth below it,
+    /// for a member of `organization`, which must govern `root_id`
+    pub async fn tree(
+        &self,
+        conn: &mut PgConnection,
+        organization: Uuid,
+        root_id: Uuid,
+    ) -> Result<Vec<(Organization, usize)>> {
+        let root = self.repository.find_by_id(conn, root_id).await?;
+        if !self.governs(conn, organization, root.id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Only organizations under your own can be listed",
+                ErrorContext::new(),
+            ));
+        }
+        self.repository.subtree(conn, &root).await
+    }
+
+    /// Offers `org_id` to `new_owner_id` on behalf of its owner; nothing
+    /// changes hands until the new owner accepts
+    pub async fn start_transfer(
+        &self,
+        conn: &mut PgConnection,
+        initiator: &User,
+        org_id: Uuid,
+        new_owner_id: Uuid,
+    ) -> Result<OwnershipTransfer> {
+        let organization = self.repository.find_by_id(conn, org_id).await?;
+        let new_owner = UserRepositoryImpl.find_by_id(conn, new_owner_id).await?;
+        OrganizationValidator::validate_transfer(&organization, initiator, &new_owner)?;
+
+        let now = Utc::now();
+        let transfer = OwnershipTransferRepositoryImpl
+            .create(conn, &OwnershipTransfer {
+                id: Uuid::new_v4(),
+                org_id: organization.id,
+                from_user_id: initiator.id,
+                to_user_id: new_owner.id,
+                expires_at: now + Duration::days(OWNERSHIP_TRANSFER_DAYS),
+                accepted_at: None,
+                created_at: now,
+                updated_at: now,
+                deleted_at: None,
+            })
+            .await?;
+        let notification = Notification::new(new_owner.id, OWNERSHIP_TRANSFER_KIND, format!("{} is being transferred to you", organization.name))
+            .with_org(organization.id)
+            .with_body(format!(
+                "{} {} asked you to take over {}. Accept within {} days to become its owner.",
+                initiator.first_name, initiator.last_name, organization.name, OWNERSHIP_TRANSFER_DAYS
+            ))
+            .with_link(format!("/organizations/{}/transfer-ownership/accept", organization.id))
+            .with_data(json!({ "transfer_id": transfer.id, "from_user_id": initiator.id }));
+        NotificationService::new(NotificationRepositoryImpl).notify(conn, &[notification]).await?;
+
+        info!(
+            organization_id = %organization.id,
+            from_user_id = %initiator.id,
+            to_user_id = %new_owner.id,
+            "Ownership transfer of '{}' started", organization.name
+        );
+        Ok(transfer)
+    }
+
+    /// Accepts the pending transfer of `org_id` to `new_owner`, who becomes
+    /// its owning admin while the previous owner becomes a manager
+    ///
+    /// Fails with a conflict when the organization changed hands since the
+    /// transfer was started.
+    pub async fn accept_transfer(
+        &self,
+        conn: &mut PgConnection,
+        new_owner: &User,
+        org_id: Uuid,
+    ) -> Result<(Organization, OwnershipTransfer)> {
+        let organization = self.repository.find_by_id(conn, org_id).await?;
+        let transfer = OwnershipTransferRepositoryImpl
+            .find_pending(conn, organization.id)
+            .await?
+            .filter(|transfer| transfer.to_user_id == new_owner.id && transfer.expires_at > Utc::now())
+            .ok_or_else(|| ApiError::not_found("No ownership transfer of this organization awaits you"))?;
+
+        let stale = || {
+            ApiError::new(
+                ErrorCode::Conflict,
+                "The organization changed hands since the transfer was started",
+                ErrorContext::new().with_details(json!({ "code": "STALE" })),
+            )
+        };
+        let initiator = UserRepositoryImpl.find_by_ids(conn, &[transfer.from_user_id]).await?.pop().ok_or_else(stale)?;
+        match OrganizationValidator::validate_transfer(&organization, &initiator, new_owner) {
+            Err(e) if e.code == ErrorCode::Forbidden => return Err(stale()),
+            result => result?,
+        }
+        let transfer = OwnershipTransferRepositoryImpl.accept(conn, transfer.id).await?.ok_or_else(stale)?;
+        let organization = self.repository.find_by_id(conn, org_id).await?;
+
+        info!(
+            organization_id = %organization.id,
+            from_user_id = %transfer.from_user_id,
+            to_user_id = %transfer.to_user_id,
+            "Ownership of '{}' transferred", organization.name
+        );
+        Ok((organization, transfer))
+    }
+
+    /// Gets an organization `tenant` governs by ID
+    pub async fn get(&self, conn: &mut PgConnection, tenant: Uuid, id: Uuid) -> Result<Organization> {
+        self.repository.find_governed(conn, tenant, id).await
+    }
+
+    /// Pages through the organizations `tenant` governs matching `filter`,
+    /// with their count
+    pub async fn list(
+        &self,
+        conn: &mut PgConnection,
+        tenant: Uuid,
+        filter: &OrganizationFilter,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<Organization>, RowCount)> {
+        self.repository.search(conn, tenant, filter, pagination).await
+    }
+
+    /// Counts organizations for pagination metadata
+    pub async fn count(&self, conn: &mut PgConnection) -> Result<RowCount> {
+        self.repository.count(conn).await
+    }
+
+    /// Pages through the users of an organization matching `filter`, with
+    /// the total count
+    pub async fn list_users(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        filter: &UserFilter,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<User>, i64)> {
+        self.repository.find_by_id(conn, id).await?;
+        UserRepositoryImpl.search(conn, id, filter, pagination).await
     }
 
     /// Gets an organization by name
@@ -0,0 +1,211 @@
+use chrono::{FixedOffset, Utc};
+use diesel::PgConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{
+            auth::{Role, User},
+            OrganizationSettings,
+        },
+        repositories::{
+            OrganizationRepositoryImpl, OrganizationSettingsRepository, OrganizationSettingsRepositoryImpl, Repository,
+        },
+    },
+    domain::{
+        organization::OrganizationService,
+        user::{parse_timezone, timezone_name, UnitSystem},
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+
+/// Most safety forms an organization can require
+pub const MAX_SAFETY_FORMS: usize = 50;
+
+/// Longest safety form name
+pub const MAX_SAFETY_FORM_LENGTH: usize = 100;
+
+/// How an organization works: the units its data is presented in unless
+/// members say otherwise, when its fiscal year starts, the timezone its
+/// crews work in and the safety forms every job needs
+///
+/// Stored as JSON; values missing from what was stored take their default.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrgSettings {
+    pub default_units: UnitSystem,
+    /// Month the fiscal year starts in, 1 for January
+    pub fiscal_year_start_month: u32,
+    /// `UTC` or an offset from it, such as `-08:00`
+    pub timezone: String,
+    pub required_safety_forms: Vec<String>,
+}
+
+impl Default for OrgSettings {
+    fn default() -> Self {
+        Self {
+            default_units: UnitSystem::default(),
+            fiscal_year_start_month: 1,
+            timezone: "UTC".to_string(),
+            required_safety_forms: Vec::new(),
+        }
+    }
+}
+
+impl OrgSettings {
+    /// The settings saved as `stored`, the defaults if they are no longer
+    /// understood
+    fn from_stored(stored: &OrganizationSettings) -> Self {
+        serde_json::from_value(stored.settings.clone()).unwrap_or_else(|e| {
+            warn!(org_id = %stored.org_id, error = %e, "Unreadable organization settings, using the defaults");
+            Self::default()
+        })
+    }
+
+    /// The operational timezone
+    pub fn timezone_offset(&self) -> FixedOffset {
+        parse_timezone(&self.timezone).unwrap_or_else(|| FixedOffset::east_opt(0).expect("UTC is a valid offset"))
+    }
+}
+
+/// Changes to an organization's settings; values left `None` are kept
+#[derive(Debug, Clone, Default)]
+pub struct SettingsChanges {
+    pub default_units: Option<UnitSystem>,
+    pub fiscal_year_start_month: Option<u32>,
+    pub timezone: Option<String>,
+    pub required_safety_forms: Option<Vec<String>>,
+}
+
+fn invalid(field: &str, code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": field,
+            "code": code,
+        })),
+    )
+}
+
+/// Reads and changes organizations' settings
+///
+/// Members read the settings of their organization and of those under it;
+/// admins change them.
+pub struct OrganizationSettingsService;
+
+impl OrganizationSettingsService {
+    /// The settings of `org_id` for `viewer`
+    pub async fn get(conn: &mut PgConnection, viewer: &User, org_id: Uuid) -> Result<OrgSettings> {
+        Self::check_governs(conn, viewer, org_id).await?;
+        Self::of(conn, org_id).await
+    }
+
+    /// The settings of `org_id`, the defaults when it never saved any
+    pub async fn of(conn: &mut PgConnection, org_id: Uuid) -> Result<OrgSettings> {
+        Ok(OrganizationSettingsRepositoryImpl
+            .find(conn, org_id)
+            .await?
+            .map(|stored| OrgSettings::from_stored(&stored))
+            .unwrap_or_default())
+    }
+
+    /// Changes the settings of `org_id` on behalf of `admin`
+    pub async fn update(conn: &mut PgConnection, admin: &User, org_id: Uuid, changes: SettingsChanges) -> Result<OrgSettings> {
+        if admin.role != Role::Admin {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Only admins can change organization settings",
+                ErrorContext::new(),
+            ));
+        }
+        Self::check_governs(conn, admin, org_id).await?;
+        let existing = OrganizationSettingsRepositoryImpl.find(conn, org_id).await?;
+        let current = existing.as_ref().map(OrgSettings::from_stored).unwrap_or_default();
+
+        let fiscal_year_start_month = match changes.fiscal_year_start_month {
+            Some(month) if !(1..=12).contains(&month) => {
+                return Err(invalid("fiscal_year_start_month", "OUT_OF_RANGE", "Fiscal year start month must be from 1 to 12"));
+            }
+            Some(month) => month,
+            None => current.fiscal_year_start_month,
+        };
+        let timezone = match changes.timezone.as_deref() {
+            Some(timezone) => parse_timezone(timezone)
+                .map(timezone_name)
+                .ok_or_else(|| invalid("timezone", "INVALID_FORMAT", "Timezone must be UTC or an offset such as -08:00"))?,
+            None => current.timezone,
+        };
+        let required_safety_forms = match changes.required_safety_forms {
+            Some(forms) => Self::safety_forms(forms)?,
+            None => current.required_safety_forms,
+        };
+        let settings = OrgSettings {
+            default_units: changes.default_units.unwrap_or(current.default_units),
+            fiscal_year_start_month,
+            timezone,
+            required_safety_forms,
+        };
+
+        let now = Utc::now();
+        let document = serde_json::to_value(&settings).map_err(|e| {
+            ApiError::new(ErrorCode::InternalError, format!("Failed to store settings: {}", e), ErrorContext::new())
+        })?;
+        OrganizationSettingsRepositoryImpl
+            .save(conn, &OrganizationSettings {
+                org_id,
+                settings: document,
+                updated_by: Some(admin.id),
+                created_at: existing.map_or(now, |existing| existing.created_at),
+                updated_at: now,
+            })
+            .await?;
+        info!(org_id = %org_id, updated_by = %admin.id, "Organization settings updated");
+        Ok(settings)
+    }
+
+    /// The required safety forms, trimmed and without repeats
+    fn safety_forms(forms: Vec<String>) -> Result<Vec<String>> {
+        let mut kept: Vec<String> = Vec::with_capacity(forms.len());
+        for form in forms {
+            let form = form.trim();
+            if form.is_empty() {
+                return Err(invalid("required_safety_forms", "REQUIRED", "Safety form names can't be empty"));
+            }
+            if form.chars().count() > MAX_SAFETY_FORM_LENGTH {
+                return Err(invalid(
+                    "required_safety_forms",
+                    "TOO_LONG",
+                    format!("Safety form names must be at most {} characters", MAX_SAFETY_FORM_LENGTH),
+                ));
+            }
+            if !kept.iter().any(|existing| existing.eq_ignore_ascii_case(form)) {
+                kept.push(form.to_string());
+            }
+        }
+        if kept.len() > MAX_SAFETY_FORMS {
+            return Err(invalid(
+                "required_safety_forms",
+                "TOO_MANY",
+                format!("At most {} safety forms can be required", MAX_SAFETY_FORMS),
+            ));
+        }
+        Ok(kept)
+    }
+
+    /// Fails unless `user` belongs to `org_id` or an organization above it
+    async fn check_governs(conn: &mut PgConnection, user: &User, org_id: Uuid) -> Result<()> {
+        let organizations = OrganizationService::new(OrganizationRepositoryImpl);
+        let organization = organizations.repository().find_by_id(conn, org_id).await?;
+        if !organizations.governs(conn, user.org_id, organization.id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Settings of other organizations can't be accessed",
+                ErrorContext::new(),
+            ));
+        }
+        Ok(())
+    }
+}
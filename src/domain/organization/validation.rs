@@ -4,8 +4,11 @@ use validator::Validate as ValidatorValidate;
 
 use crate::{
     api::resources::organization::dto::{CreateOrganizationInput, UpdateOrganizationInput},
-    db::repositories::organization::OrganizationRepository,
-    error::{ApiError, Result, ErrorContext},
+    db::{
+        models::{auth::{Role, User}, Organization},
+        repositories::organization::OrganizationRepository,
+    },
+    error::{ApiError, ErrorCode, Result, ErrorContext},
 };
 
 pub struct OrganizationValidator;
@@ -40,12 +43,66 @@ impl OrganizationValidator {
         Ok(())
     }
 
+    /// Validates bringing back a soft-deleted organization, whose name no
+    /// live organization may have taken meanwhile
+    pub async fn validate_restore<R: OrganizationRepository + Send + Sync>(
+        conn: &mut PgConnection,
+        repo: &R,
+        organization: &Organization,
+    ) -> Result<()> {
+        Self::validate_unique_name(conn, repo, &organization.name, Some(organization.id)).await
+    }
+
+    /// Validates handing `organization` from `initiator` to `new_owner`
+    ///
+    /// The initiator must own the organization, or be one of its admins
+    /// while no owner is recorded. The new owner must be another active,
+    /// verified member of it.
+    pub fn validate_transfer(organization: &Organization, initiator: &User, new_owner: &User) -> Result<()> {
+        let owns = match organization.owner_id {
+            Some(owner_id) => owner_id == initiator.id,
+            None => initiator.org_id == organization.id && initiator.role == Role::Admin,
+        };
+        if !owns {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Only the owner can transfer the organization",
+                ErrorContext::new(),
+            ));
+        }
+
+        let invalid = |code: &str, message: &str| {
+            ApiError::validation_with_context(
+                message,
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "new_owner_id",
+                    "code": code,
+                })),
+            )
+        };
+        if new_owner.id == initiator.id {
+            return Err(invalid("SELF", "The organization already belongs to you"));
+        }
+        if new_owner.org_id != organization.id || new_owner.deleted_at.is_some() || new_owner.anonymized_at.is_some() {
+            return Err(invalid("NOT_A_MEMBER", "The new owner must be a member of the organization"));
+        }
+        if !new_owner.is_active {
+            return Err(invalid("INACTIVE", "The new owner's account is deactivated"));
+        }
+        if !new_owner.email_verified {
+            return Err(invalid("UNVERIFIED", "The new owner must verify their email first"));
+        }
+        Ok(())
+    }
+
     /// Validates struct using the validator crate
     fn validate_struct<T: ValidatorValidate>(input: &T) -> Result<()> {
         if let Err(e) = ValidatorValidate::validate(input) {
             return Err(ApiError::validation_with_context(
                 "Invalid input",
-                ErrorContext::new().with_details(serde_json::json!(e))
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(serde_json::json!(e))
             ));
         }
         Ok(())
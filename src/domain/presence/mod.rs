@@ -0,0 +1,10 @@
+//! Presence of field devices
+//!
+//! Field apps send a heartbeat every minute or so with their app version
+//! and battery level. A device is online while its heartbeats keep coming;
+//! dispatchers see who is online in the field, on which device, and how
+//! much battery it has left.
+
+mod service;
+
+pub use service::{PresenceService, MAX_APP_VERSION_LENGTH, MAX_ONLINE_MINUTES, ONLINE_MINUTES};
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Duration, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::auth::{Device, User},
+        repositories::auth::{DeviceRepository, DeviceRepositoryImpl},
+    },
+    domain::auth::AuthValidator,
+    error::{ApiError, ErrorContext, Result},
+};
+
+/// Minutes since its last heartbeat a device counts as online, by default
+pub const ONLINE_MINUTES: i64 = 5;
+
+/// Longest window a dispatcher may count devices online within
+pub const MAX_ONLINE_MINUTES: i64 = 24 * 60;
+
+/// Longest app version accepted
+pub const MAX_APP_VERSION_LENGTH: usize = 50;
+
+fn invalid_field(field: &str, code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": field,
+            "code": code,
+        })),
+    )
+}
+
+/// Records device heartbeats and lists the devices online
+pub struct PresenceService;
+
+impl PresenceService {
+    /// Records that `device_id` of `user_id` is online, running
+    /// `app_version` with `battery_level` percent left
+    ///
+    /// Devices are registered on their first heartbeat, as on login.
+    pub async fn heartbeat(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        device_id: &str,
+        device_name: Option<&str>,
+        app_version: Option<&str>,
+        battery_level: Option<i16>,
+    ) -> Result<Device> {
+        AuthValidator::validate_device(device_id, device_name)?;
+        let app_version = app_version.map(str::trim).filter(|version| !version.is_empty());
+        if app_version.is_some_and(|version| version.len() > MAX_APP_VERSION_LENGTH) {
+            return Err(invalid_field("app_version", "TOO_LONG", "App version too long"));
+        }
+        if battery_level.is_some_and(|level| !(0..=100).contains(&level)) {
+            return Err(invalid_field("battery_level", "OUT_OF_RANGE", "Battery level must be from 0 to 100"));
+        }
+
+        DeviceRepositoryImpl
+            .heartbeat(conn, user_id, device_id, device_name, app_version, battery_level)
+            .await
+    }
+
+    /// Devices of the organization's active members that sent a heartbeat
+    /// in the `minutes` before `now`, with their user, most recent first
+    pub async fn online(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        now: DateTime<Utc>,
+        minutes: i64,
+    ) -> Result<Vec<(Device, User)>> {
+        if !(1..=MAX_ONLINE_MINUTES).contains(&minutes) {
+            return Err(invalid_field(
+                "minutes",
+                "OUT_OF_RANGE",
+                format!("Minutes must be from 1 to {}", MAX_ONLINE_MINUTES),
+            ));
+        }
+        DeviceRepositoryImpl.online(conn, org_id, now - Duration::minutes(minutes)).await
+    }
+}
@@ -0,0 +1,160 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::db::{
+    models::{auth::User, Notification, SaleContract},
+    schema::{notifications, sale_contracts, users},
+};
+
+/// A row of a dataset, keyed by field name
+pub type Row = Map<String, Value>;
+
+/// Kind of a dataset field, deciding how values compare
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    Number,
+    Boolean,
+    Timestamp,
+    /// Number of cubic metres, presented in the reader's units
+    Volume,
+    /// Number of kilometres, presented in the reader's units
+    Distance,
+}
+
+
+/// Data of an organization a report can read, rolled up from the
+/// organizations under it: its members (`users`, without credentials), the
+/// notifications sent to them (`notifications`) or the contracts its tenders
+/// awarded (`sale_contracts`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ReportDataset {
+    Users,
+    Notifications,
+    SaleContracts,
+}
+
+const USER_FIELDS: &[(&str, FieldKind)] = &[
+    ("id", FieldKind::Text),
+    ("first_name", FieldKind::Text),
+    ("last_name", FieldKind::Text),
+    ("email", FieldKind::Text),
+    ("role", FieldKind::Text),
+    ("email_verified", FieldKind::Boolean),
+    ("created_at", FieldKind::Timestamp),
+];
+
+const NOTIFICATION_FIELDS: &[(&str, FieldKind)] = &[
+    ("id", FieldKind::Text),
+    ("user_id", FieldKind::Text),
+    ("kind", FieldKind::Text),
+    ("title", FieldKind::Text),
+    ("read", FieldKind::Boolean),
+    ("read_at", FieldKind::Timestamp),
+    ("created_at", FieldKind::Timestamp),
+];
+
+const SALE_CONTRACT_FIELDS: &[(&str, FieldKind)] = &[
+    ("id", FieldKind::Text),
+    ("tender_id", FieldKind::Text),
+    ("block_id", FieldKind::Text),
+    ("buyer_name", FieldKind::Text),
+    ("assortment", FieldKind::Text),
+    ("volume", FieldKind::Volume),
+    ("value", FieldKind::Number),
+    ("created_at", FieldKind::Timestamp),
+];
+
+impl ReportDataset {
+    /// Fields rows of the dataset carry
+    pub fn fields(self) -> &'static [(&'static str, FieldKind)] {
+        match self {
+            Self::Users => USER_FIELDS,
+            Self::Notifications => NOTIFICATION_FIELDS,
+            Self::SaleContracts => SALE_CONTRACT_FIELDS,
+        }
+    }
+
+    /// Kind of `name`, `None` when the dataset has no such field
+    pub fn field(self, name: &str) -> Option<FieldKind> {
+        self.fields().iter().find(|(field, _)| *field == name).map(|(_, kind)| *kind)
+    }
+
+    /// Loads up to `limit` rows of the organizations, newest first
+    pub fn load(self, conn: &mut PgConnection, org_ids: &[Uuid], limit: i64) -> QueryResult<Vec<Row>> {
+        match self {
+            Self::Users => Ok(users::table
+                .filter(users::org_id.eq_any(org_ids))
+                .filter(users::deleted_at.is_null())
+                .order_by((users::created_at.desc(), users::id.desc()))
+                .limit(limit)
+                .select(User::as_select())
+                .load(conn)?
+                .into_iter()
+                .map(|user| {
+                    row(json!({
+                        "id": user.id,
+                        "first_name": user.first_name,
+                        "last_name": user.last_name,
+                        "email": user.email,
+                        "role": user.role,
+                        "email_verified": user.email_verified,
+                        "created_at": user.created_at,
+                    }))
+                })
+                .collect()),
+            Self::Notifications => Ok(notifications::table
+                .filter(notifications::org_id.eq_any(org_ids))
+                .order_by((notifications::created_at.desc(), notifications::id.desc()))
+                .limit(limit)
+                .select(Notification::as_select())
+                .load(conn)?
+                .into_iter()
+                .map(|notification| {
+                    row(json!({
+                        "id": notification.id,
+                        "user_id": notification.user_id,
+                        "kind": notification.kind,
+                        "title": notification.title,
+                        "read": notification.read_at.is_some(),
+                        "read_at": notification.read_at,
+                        "created_at": notification.created_at,
+                    }))
+                })
+                .collect()),
+            Self::SaleContracts => Ok(sale_contracts::table
+                .filter(sale_contracts::org_id.eq_any(org_ids))
+                .order_by((sale_contracts::created_at.desc(), sale_contracts::id.desc()))
+                .limit(limit)
+                .select(SaleContract::as_select())
+                .load(conn)?
+                .into_iter()
+                .map(|contract| {
+                    row(json!({
+                        "id": contract.id,
+                        "tender_id": contract.tender_id,
+                        "block_id": contract.block_id,
+                        "buyer_name": contract.buyer_name,
+                        "assortment": contract.assortment,
+                        "volume": contract.volume_m3,
+                        "value": contract.volume_m3 * contract.price_per_m3,
+                        "created_at": contract.created_at,
+                    }))
+                })
+                .collect()),
+        }
+    }
+}
+
+fn row(value: Value) -> Row {
+    match value {
+        Value::Object(row) => row,
+        _ => Row::new(),
+    }
+}
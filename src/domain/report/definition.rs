@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use super::dataset::ReportDataset;
+use crate::{
+    db::models::Report,
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+
+/// What a report reads and how it shapes the rows
+///
+/// Without `group_by`, every matching row is listed with the selected
+/// columns. With it, rows are grouped by those fields and every other
+/// column must be aggregated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ReportDefinition {
+    pub dataset: ReportDataset,
+    /// Values supplied when the report runs, referenced by filters
+    #[serde(default)]
+    pub parameters: Vec<ReportParameter>,
+    #[serde(default)]
+    pub filters: Vec<ReportFilter>,
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    pub columns: Vec<ReportColumn>,
+}
+
+/// A value supplied when the report runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ReportParameter {
+    pub name: String,
+    /// Used when the run does not supply the parameter, which is then
+    /// required when unset
+    #[serde(default)]
+    pub default: Option<serde_json::Value>,
+}
+
+/// A condition rows must meet
+///
+/// Compares `field` with either a fixed `value` or the run's `parameter`;
+/// exactly one of the two is set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ReportFilter {
+    pub field: String,
+    pub op: FilterOp,
+    #[serde(default)]
+    pub value: Option<serde_json::Value>,
+    #[serde(default)]
+    pub parameter: Option<String>,
+}
+
+/// Comparison applied by a filter
+///
+/// `in` expects an array, `contains` a case-insensitive substring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+    In,
+}
+
+/// A column of the report output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ReportColumn {
+    pub field: String,
+    #[serde(default)]
+    pub aggregate: Option<Aggregate>,
+    /// Column heading, derived from the field and aggregate when unset
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Aggregation of a column over each group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum Aggregate {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Count => "count",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+        }
+    }
+}
+
+impl ReportColumn {
+    /// Heading of the column in the output
+    pub fn heading(&self) -> String {
+        match (&self.label, self.aggregate) {
+            (Some(label), _) => label.clone(),
+            (None, Some(aggregate)) => format!("{}_{}", aggregate.name(), self.field),
+            (None, None) => self.field.clone(),
+        }
+    }
+}
+
+fn invalid(message: &str, field: &str, code: &str, value: impl Serialize) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(serde_json::json!({
+            "field": field,
+            "code": code,
+            "value": value
+        })),
+    )
+}
+
+impl ReportDefinition {
+    /// The definition saved with `report`
+    pub fn from_stored(report: &Report) -> Result<Self> {
+        serde_json::from_value(report.definition.clone()).map_err(|e| {
+            tracing::error!(report_id = %report.id, error = %e, "Stored report definition is invalid");
+            ApiError::new(ErrorCode::InternalError, "Stored report definition is invalid", ErrorContext::default())
+        })
+    }
+
+    /// Checks that every field exists in the dataset, every parameter is
+    /// declared and grouped reports aggregate their other columns
+    pub fn validate(&self) -> Result<()> {
+        let known = |field: &str| self.dataset.field(field).is_some();
+
+        if self.columns.is_empty() {
+            return Err(invalid("A report needs at least one column", "columns", "REQUIRED", &self.columns));
+        }
+        for parameter in &self.parameters {
+            if parameter.name.is_empty() || self.parameters.iter().filter(|p| p.name == parameter.name).count() > 1 {
+                return Err(invalid("Parameter names must be unique and not empty", "parameters", "INVALID_PARAMETER", &parameter.name));
+            }
+        }
+        for filter in &self.filters {
+            if !known(&filter.field) {
+                return Err(invalid("Unknown field", "filters", "UNKNOWN_FIELD", &filter.field));
+            }
+            match (&filter.value, &filter.parameter) {
+                (Some(_), None) => {}
+                (None, Some(name)) if self.parameters.iter().any(|p| &p.name == name) => {}
+                (None, Some(name)) => {
+                    return Err(invalid("Filter uses an undeclared parameter", "filters", "UNKNOWN_PARAMETER", name));
+                }
+                _ => {
+                    return Err(invalid("A filter needs either a value or a parameter", "filters", "INVALID_FILTER", &filter.field));
+                }
+            }
+            if filter.op == FilterOp::In && filter.value.as_ref().is_some_and(|value| !value.is_array()) {
+                return Err(invalid("The in operator expects an array", "filters", "INVALID_FILTER", &filter.field));
+            }
+        }
+        for field in &self.group_by {
+            if !known(field) {
+                return Err(invalid("Unknown field", "group_by", "UNKNOWN_FIELD", field));
+            }
+        }
+        let aggregated = self.columns.iter().any(|column| column.aggregate.is_some());
+        for column in &self.columns {
+            if !known(&column.field) {
+                return Err(invalid("Unknown field", "columns", "UNKNOWN_FIELD", &column.field));
+            }
+            let grouped = self.group_by.contains(&column.field);
+            if column.aggregate.is_none() && (aggregated || !self.group_by.is_empty()) && !grouped {
+                return Err(invalid(
+                    "Columns of a grouped report must be grouped or aggregated",
+                    "columns",
+                    "NOT_AGGREGATED",
+                    &column.field,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn definition(value: serde_json::Value) -> ReportDefinition {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_validate_checks_fields_parameters_and_grouping() {
+        let valid = definition(json!({
+            "dataset": "users",
+            "parameters": [{ "name": "since" }],
+            "filters": [{ "field": "created_at", "op": "gte", "parameter": "since" }],
+            "group_by": ["role"],
+            "columns": [{ "field": "role" }, { "field": "id", "aggregate": "count" }]
+        }));
+        assert!(valid.validate().is_ok());
+        assert_eq!(valid.columns[1].heading(), "count_id");
+
+        let mut unknown = valid.clone();
+        unknown.columns.push(ReportColumn { field: "salary".into(), aggregate: Some(Aggregate::Sum), label: None });
+        assert!(unknown.validate().is_err());
+
+        let mut undeclared = valid.clone();
+        undeclared.parameters.clear();
+        assert!(undeclared.validate().is_err());
+
+        let mut ungrouped = valid.clone();
+        ungrouped.columns.push(ReportColumn { field: "email".into(), aggregate: None, label: None });
+        assert!(ungrouped.validate().is_err());
+    }
+}
@@ -0,0 +1,29 @@
+//! Saved reports
+//!
+//! A report definition names a dataset of the organization, filters (with
+//! parameters supplied at run time), grouping and columns. Runs read the
+//! dataset, shape the rows in memory and render them as JSON, CSV or an Excel
+//! workbook. Runs requested by a user present volumes, distances and
+//! timestamps the way their preferences ask.
+//!
+//! A report can also be scheduled: the report delivery job runs it on a
+//! cron schedule and emails the output to a list of recipients, keeping
+//! every run in the report's delivery history.
+
+mod dataset;
+mod definition;
+mod present;
+mod render;
+mod run;
+mod schedule;
+mod service;
+mod workbook;
+
+pub use dataset::{FieldKind, ReportDataset, Row};
+pub use definition::{Aggregate, FilterOp, ReportColumn, ReportDefinition, ReportFilter, ReportParameter};
+pub use present::present;
+pub use render::{to_csv, ReportFormat};
+pub use run::{run, ReportResult};
+pub use schedule::ReportScheduleService;
+pub use service::{ReportService, MAX_REPORT_ROWS};
+pub use workbook::to_xlsx;
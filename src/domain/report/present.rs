@@ -0,0 +1,117 @@
+use chrono::{DateTime, SecondsFormat};
+use serde_json::{Number, Value};
+
+use super::{
+    dataset::FieldKind,
+    definition::{Aggregate, ReportDefinition},
+    run::ReportResult,
+};
+use crate::domain::user::{Preferences, UnitSystem};
+
+/// Decimal places converted measurements are rounded to
+const CONVERTED_DECIMALS: i32 = 3;
+
+/// Kind of the values of each column; counts are plain numbers whatever
+/// they count
+fn column_kinds(definition: &ReportDefinition) -> Vec<FieldKind> {
+    definition
+        .columns
+        .iter()
+        .map(|column| match column.aggregate {
+            Some(Aggregate::Count) => FieldKind::Number,
+            _ => definition.dataset.field(&column.field).unwrap_or(FieldKind::Text),
+        })
+        .collect()
+}
+
+fn measure(value: &Value, convert: impl Fn(f64) -> f64, converted: bool) -> Value {
+    let Some(number) = value.as_f64() else {
+        return value.clone();
+    };
+    let mut number = convert(number);
+    if converted {
+        let scale = 10f64.powi(CONVERTED_DECIMALS);
+        number = (number * scale).round() / scale;
+    }
+    Number::from_f64(number).map(Value::Number).unwrap_or(Value::Null)
+}
+
+fn value(kind: FieldKind, value: &Value, preferences: &Preferences) -> Value {
+    let units = preferences.units;
+    let converted = units != UnitSystem::Metric;
+    match kind {
+        FieldKind::Volume => measure(value, |m3| units.volume(m3), converted),
+        FieldKind::Distance => measure(value, |km| units.distance(km), converted),
+        FieldKind::Timestamp => value
+            .as_str()
+            .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+            .map(|date| {
+                Value::String(date.with_timezone(&preferences.timezone).to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            })
+            .unwrap_or_else(|| value.clone()),
+        FieldKind::Text | FieldKind::Number | FieldKind::Boolean => value.clone(),
+    }
+}
+
+/// Presents a result of `definition` the way `preferences` ask
+///
+/// Volumes and distances are converted to the reader's units, which their
+/// headings name, and timestamps are given at the reader's UTC offset.
+pub fn present(definition: &ReportDefinition, mut result: ReportResult, preferences: &Preferences) -> ReportResult {
+    let kinds = column_kinds(definition);
+    for (heading, kind) in result.columns.iter_mut().zip(&kinds) {
+        match kind {
+            FieldKind::Volume => heading.push_str(&format!(" ({})", preferences.units.volume_unit())),
+            FieldKind::Distance => heading.push_str(&format!(" ({})", preferences.units.distance_unit())),
+            _ => {}
+        }
+    }
+    for row in &mut result.rows {
+        for (cell, kind) in row.iter_mut().zip(&kinds) {
+            *cell = value(*kind, cell, preferences);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::user::parse_timezone;
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[test]
+    fn test_present_converts_measures_and_timestamps() {
+        let definition: ReportDefinition = serde_json::from_value(json!({
+            "dataset": "sale_contracts",
+            "group_by": ["assortment"],
+            "columns": [
+                { "field": "assortment" },
+                { "field": "volume", "aggregate": "sum" },
+                { "field": "volume", "aggregate": "count", "label": "contracts" },
+                { "field": "created_at", "aggregate": "max", "label": "latest" }
+            ]
+        }))
+        .unwrap();
+        let result = || ReportResult {
+            columns: vec!["assortment".into(), "sum_volume".into(), "contracts".into(), "latest".into()],
+            rows: vec![vec![json!("Sawlogs"), json!(10.0), json!(2), json!("2025-01-01T06:00:00Z")]],
+            truncated: false,
+            generated_at: Utc::now(),
+        };
+
+        let metric = present(&definition, result(), &Preferences::default());
+        assert_eq!(metric.columns[1], "sum_volume (m³)");
+        assert_eq!(metric.rows[0], vec![json!("Sawlogs"), json!(10.0), json!(2), json!("2025-01-01T06:00:00Z")]);
+
+        let preferences = Preferences {
+            units: UnitSystem::Imperial,
+            timezone: parse_timezone("-08:00").unwrap(),
+            ..Preferences::default()
+        };
+        let imperial = present(&definition, result(), &preferences);
+        assert_eq!(imperial.columns, vec!["assortment", "sum_volume (ft³)", "contracts", "latest"]);
+        assert_eq!(imperial.rows[0], vec![json!("Sawlogs"), json!(353.147), json!(2), json!("2024-12-31T22:00:00-08:00")]);
+    }
+}
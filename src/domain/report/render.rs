@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use super::{run::ReportResult, workbook::to_xlsx};
+use crate::{error::Result, utils::i18n::Locale};
+
+/// Format a report run is delivered in: the result in the usual response
+/// envelope (`json`), or as an attachment of comma-separated values with a
+/// heading row (`csv`) or a formatted Excel workbook (`xlsx`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+    Xlsx,
+}
+
+impl ReportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+        }
+    }
+
+    /// Parses a stored format, `None` when unknown
+    pub fn parse(value: &str) -> Option<Self> {
+        [Self::Json, Self::Csv, Self::Xlsx].into_iter().find(|format| format.as_str() == value)
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        self.as_str()
+    }
+
+    /// Renders a result as a file of this format, for a reader of `locale`
+    pub fn render(self, result: &ReportResult, locale: Locale) -> Result<Vec<u8>> {
+        match self {
+            Self::Json => Ok(serde_json::to_vec(result).unwrap_or_default()),
+            Self::Csv => Ok(to_csv(result, locale).into_bytes()),
+            Self::Xlsx => to_xlsx(result),
+        }
+    }
+}
+
+/// Separators of CSV written for a locale
+#[derive(Clone, Copy)]
+struct Separators {
+    field: char,
+    decimal: char,
+}
+
+impl Separators {
+    /// Spreadsheets of locales writing decimal commas split fields on
+    /// semicolons
+    fn of(locale: Locale) -> Self {
+        match locale {
+            Locale::En => Self { field: ',', decimal: '.' },
+            Locale::Fr => Self { field: ';', decimal: ',' },
+        }
+    }
+}
+
+/// One CSV cell
+///
+/// Text starting like a formula is prefixed with `'`, so spreadsheets opening
+/// the file show it rather than evaluate it.
+fn cell(value: &Value, separators: Separators) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        Value::String(text) if text.starts_with(['=', '+', '-', '@']) => format!("'{}", text),
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string().replace('.', &separators.decimal.to_string()),
+        value => value.to_string(),
+    };
+    if text.contains([separators.field, '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn line(cells: impl Iterator<Item = String>, separators: Separators) -> String {
+    let mut line = cells.collect::<Vec<_>>().join(&separators.field.to_string());
+    line.push_str("\r\n");
+    line
+}
+
+/// Renders a result as CSV (RFC 4180) for a reader of `locale`
+///
+/// English readers get commas between fields and decimal points; French
+/// readers get semicolons and decimal commas, as their spreadsheets expect.
+pub fn to_csv(result: &ReportResult, locale: Locale) -> String {
+    let separators = Separators::of(locale);
+    let mut csv = line(
+        result.columns.iter().map(|column| cell(&Value::String(column.clone()), separators)),
+        separators,
+    );
+    for row in &result.rows {
+        csv.push_str(&line(row.iter().map(|value| cell(value, separators)), separators));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_csv_quotes_and_neutralizes_cells() {
+        let result = ReportResult {
+            columns: vec!["name".into(), "count".into()],
+            rows: vec![
+                vec![json!("Doe, \"JD\""), json!(3)],
+                vec![json!("=SUM(A1)"), Value::Null],
+            ],
+            truncated: false,
+            generated_at: Utc::now(),
+        };
+        assert_eq!(to_csv(&result, Locale::En), "name,count\r\n\"Doe, \"\"JD\"\"\",3\r\n'=SUM(A1),\r\n");
+    }
+
+    #[test]
+    fn test_to_csv_follows_the_locale() {
+        let result = ReportResult {
+            columns: vec!["assortment".into(), "volume (m³)".into()],
+            rows: vec![vec![json!("Pulp; mixed"), json!(12.5)]],
+            truncated: false,
+            generated_at: Utc::now(),
+        };
+        assert_eq!(to_csv(&result, Locale::En), "assortment,volume (m³)\r\nPulp; mixed,12.5\r\n");
+        assert_eq!(to_csv(&result, Locale::Fr), "assortment;volume (m³)\r\n\"Pulp; mixed\";12,5\r\n");
+    }
+}
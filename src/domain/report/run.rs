@@ -0,0 +1,272 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use super::{
+    dataset::{FieldKind, Row},
+    definition::{Aggregate, FilterOp, ReportDefinition},
+};
+use crate::error::{ApiError, ErrorContext, Result};
+
+/// Output of a report run
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ReportResult {
+    /// Column headings, in the order of each row's values
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// Whether the dataset had more rows than a run reads, so the newest
+    /// rows only were reported
+    pub truncated: bool,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Filters of a definition with their parameters resolved
+struct Condition<'a> {
+    field: &'a str,
+    kind: FieldKind,
+    op: FilterOp,
+    value: Value,
+}
+
+/// Resolves the value of every filter, taking parameters from `supplied` or
+/// their defaults
+fn conditions<'a>(definition: &'a ReportDefinition, supplied: &HashMap<String, Value>) -> Result<Vec<Condition<'a>>> {
+    definition
+        .filters
+        .iter()
+        .map(|filter| {
+            let value = match (&filter.value, &filter.parameter) {
+                (Some(value), _) => value.clone(),
+                (None, Some(name)) => supplied
+                    .get(name)
+                    .cloned()
+                    .or_else(|| {
+                        definition
+                            .parameters
+                            .iter()
+                            .find(|parameter| &parameter.name == name)
+                            .and_then(|parameter| parameter.default.clone())
+                    })
+                    .ok_or_else(|| {
+                        ApiError::validation_with_context(
+                            "Missing report parameter",
+                            ErrorContext::new().with_details(serde_json::json!({
+                                "field": "parameters",
+                                "code": "MISSING_PARAMETER",
+                                "value": name
+                            })),
+                        )
+                    })?,
+                (None, None) => Value::Null,
+            };
+            Ok(Condition {
+                field: &filter.field,
+                kind: definition.dataset.field(&filter.field).unwrap_or(FieldKind::Text),
+                op: filter.op,
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Orders two values of a field, `None` when they do not compare
+fn compare(kind: FieldKind, left: &Value, right: &Value) -> Option<Ordering> {
+    match kind {
+        FieldKind::Number | FieldKind::Volume | FieldKind::Distance => left.as_f64()?.partial_cmp(&right.as_f64()?),
+        FieldKind::Boolean => Some(left.as_bool()?.cmp(&right.as_bool()?)),
+        FieldKind::Timestamp => {
+            let parse = |value: &Value| value.as_str()?.parse::<DateTime<Utc>>().ok();
+            Some(parse(left)?.cmp(&parse(right)?))
+        }
+        FieldKind::Text => Some(left.as_str()?.cmp(right.as_str()?)),
+    }
+}
+
+impl Condition<'_> {
+    fn matches(&self, row: &Row) -> bool {
+        let actual = row.get(self.field).unwrap_or(&Value::Null);
+        let ordering = || compare(self.kind, actual, &self.value);
+        match self.op {
+            FilterOp::Eq => actual == &self.value || ordering() == Some(Ordering::Equal),
+            FilterOp::Ne => !(actual == &self.value || ordering() == Some(Ordering::Equal)),
+            FilterOp::Gt => ordering() == Some(Ordering::Greater),
+            FilterOp::Gte => matches!(ordering(), Some(Ordering::Greater | Ordering::Equal)),
+            FilterOp::Lt => ordering() == Some(Ordering::Less),
+            FilterOp::Lte => matches!(ordering(), Some(Ordering::Less | Ordering::Equal)),
+            FilterOp::Contains => match (actual.as_str(), self.value.as_str()) {
+                (Some(actual), Some(needle)) => actual.to_lowercase().contains(&needle.to_lowercase()),
+                _ => false,
+            },
+            FilterOp::In => self.value.as_array().is_some_and(|values| {
+                values
+                    .iter()
+                    .any(|value| actual == value || compare(self.kind, actual, value) == Some(Ordering::Equal))
+            }),
+        }
+    }
+}
+
+/// Aggregates the values of one field over a group of rows
+fn aggregate(aggregate: Aggregate, kind: FieldKind, rows: &[&Row], field: &str) -> Value {
+    let values = rows.iter().filter_map(|row| row.get(field)).filter(|value| !value.is_null());
+    match aggregate {
+        Aggregate::Count => Value::from(values.count()),
+        Aggregate::Sum | Aggregate::Avg => {
+            let numbers: Vec<f64> = values
+                .filter_map(|value| value.as_f64().or_else(|| value.as_bool().map(|b| b as u8 as f64)))
+                .collect();
+            if numbers.is_empty() {
+                return Value::Null;
+            }
+            let sum: f64 = numbers.iter().sum();
+            let result = if aggregate == Aggregate::Sum { sum } else { sum / numbers.len() as f64 };
+            serde_json::Number::from_f64(result).map(Value::Number).unwrap_or(Value::Null)
+        }
+        Aggregate::Min | Aggregate::Max => {
+            let wanted = if aggregate == Aggregate::Min { Ordering::Less } else { Ordering::Greater };
+            values
+                .fold(None::<&Value>, |best, value| match best {
+                    Some(best) if compare(kind, value, best) != Some(wanted) => Some(best),
+                    _ => Some(value),
+                })
+                .cloned()
+                .unwrap_or(Value::Null)
+        }
+    }
+}
+
+/// Runs `definition` over `rows` loaded from its dataset
+///
+/// `parameters` supplies the values of the definition's parameters.
+pub fn run(
+    definition: &ReportDefinition,
+    rows: Vec<Row>,
+    parameters: &HashMap<String, Value>,
+    truncated: bool,
+) -> Result<ReportResult> {
+    let conditions = conditions(definition, parameters)?;
+    let matching: Vec<&Row> = rows
+        .iter()
+        .filter(|row| conditions.iter().all(|condition| condition.matches(row)))
+        .collect();
+    let kind = |field: &str| definition.dataset.field(field).unwrap_or(FieldKind::Text);
+
+    let aggregated = definition.columns.iter().any(|column| column.aggregate.is_some());
+    let rows = if definition.group_by.is_empty() && !aggregated {
+        matching
+            .iter()
+            .map(|row| {
+                definition
+                    .columns
+                    .iter()
+                    .map(|column| row.get(&column.field).cloned().unwrap_or(Value::Null))
+                    .collect()
+            })
+            .collect()
+    } else {
+        // Groups are keyed by their serialized values, so they come out in
+        // a stable order
+        let mut groups: BTreeMap<String, Vec<&Row>> = BTreeMap::new();
+        for row in matching {
+            let key: Vec<&Value> = definition
+                .group_by
+                .iter()
+                .map(|field| row.get(field).unwrap_or(&Value::Null))
+                .collect();
+            groups.entry(serde_json::to_string(&key).unwrap_or_default()).or_default().push(row);
+        }
+        if groups.is_empty() && definition.group_by.is_empty() {
+            groups.insert(String::new(), Vec::new());
+        }
+        groups
+            .values()
+            .map(|group| {
+                definition
+                    .columns
+                    .iter()
+                    .map(|column| match column.aggregate {
+                        Some(function) => aggregate(function, kind(&column.field), group, &column.field),
+                        None => group
+                            .first()
+                            .and_then(|row| row.get(&column.field))
+                            .cloned()
+                            .unwrap_or(Value::Null),
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+
+    Ok(ReportResult {
+        columns: definition.columns.iter().map(|column| column.heading()).collect(),
+        rows,
+        truncated,
+        generated_at: Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rows() -> Vec<Row> {
+        [
+            json!({ "role": "Operator", "email": "a@example.com", "email_verified": true, "created_at": "2025-03-01T00:00:00Z" }),
+            json!({ "role": "Operator", "email": "b@example.com", "email_verified": false, "created_at": "2025-01-01T00:00:00Z" }),
+            json!({ "role": "Manager", "email": "c@example.com", "email_verified": true, "created_at": "2025-02-01T00:00:00.5Z" }),
+        ]
+        .into_iter()
+        .map(|row| row.as_object().unwrap().clone())
+        .collect()
+    }
+
+    #[test]
+    fn test_run_filters_with_parameters_and_groups() {
+        let definition: ReportDefinition = serde_json::from_value(json!({
+            "dataset": "users",
+            "parameters": [{ "name": "since", "default": "2024-01-01T00:00:00Z" }],
+            "filters": [{ "field": "created_at", "op": "gte", "parameter": "since" }],
+            "group_by": ["role"],
+            "columns": [
+                { "field": "role" },
+                { "field": "email", "aggregate": "count", "label": "members" },
+                { "field": "email_verified", "aggregate": "sum" }
+            ]
+        }))
+        .unwrap();
+
+        let all = run(&definition, rows(), &HashMap::new(), false).unwrap();
+        assert_eq!(all.columns, vec!["role", "members", "sum_email_verified"]);
+        assert_eq!(all.rows, vec![vec![json!("Manager"), json!(1), json!(1.0)], vec![json!("Operator"), json!(2), json!(1.0)]]);
+
+        let parameters = HashMap::from([("since".to_string(), json!("2025-02-01T00:00:00Z"))]);
+        let recent = run(&definition, rows(), &parameters, false).unwrap();
+        assert_eq!(recent.rows, vec![vec![json!("Manager"), json!(1), json!(1.0)], vec![json!("Operator"), json!(1), json!(1.0)]]);
+    }
+
+    #[test]
+    fn test_run_lists_rows_and_requires_parameters() {
+        let definition: ReportDefinition = serde_json::from_value(json!({
+            "dataset": "users",
+            "parameters": [{ "name": "domain" }],
+            "filters": [{ "field": "email", "op": "contains", "parameter": "domain" }, { "field": "role", "op": "in", "value": ["Operator"] }],
+            "columns": [{ "field": "email" }]
+        }))
+        .unwrap();
+
+        assert!(run(&definition, rows(), &HashMap::new(), false).is_err());
+
+        let parameters = HashMap::from([("domain".to_string(), json!("EXAMPLE"))]);
+        let result = run(&definition, rows(), &parameters, false).unwrap();
+        assert_eq!(result.rows, vec![vec![json!("a@example.com")], vec![json!("b@example.com")]]);
+    }
+}
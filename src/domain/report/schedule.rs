@@ -0,0 +1,148 @@
+use chrono::Utc;
+use diesel::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    api::{resources::report::dto::ScheduleReportInput, utils::PaginationParams},
+    db::{
+        count::RowCount,
+        models::{ReportDelivery, ReportSchedule},
+        repositories::{ReportRepository, ReportScheduleRepository},
+    },
+    error::{ApiError, ErrorContext, Result},
+    jobs::scheduler::{next_run, parse_schedule},
+};
+
+/// Service for the email schedules of an organization's reports and their
+/// delivery history
+///
+/// Due schedules are delivered by [`crate::jobs::report_delivery::ReportDeliverer`].
+pub struct ReportScheduleService<R: ReportRepository + Send + Sync, S: ReportScheduleRepository + Send + Sync> {
+    reports: R,
+    schedules: S,
+}
+
+impl<R: ReportRepository + Send + Sync, S: ReportScheduleRepository + Send + Sync> ReportScheduleService<R, S> {
+    pub fn new(reports: R, schedules: S) -> Self {
+        Self { reports, schedules }
+    }
+
+    fn invalid(message: &str, code: &str, value: impl serde::Serialize) -> ApiError {
+        ApiError::validation_with_context(
+            message,
+            ErrorContext::new().with_details(serde_json::json!({
+                "field": "recipients",
+                "code": code,
+                "value": value
+            })),
+        )
+    }
+
+    /// Schedules a report's delivery, replacing its existing schedule
+    ///
+    /// The first delivery is the next time the cron expression fires.
+    pub async fn set(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        report_id: Uuid,
+        created_by: Option<Uuid>,
+        input: ScheduleReportInput,
+    ) -> Result<ReportSchedule> {
+        if let Err(e) = ValidatorValidate::validate(&input) {
+            return Err(ApiError::validation_with_context(
+                "Invalid input",
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(serde_json::json!(e))
+            ));
+        }
+        if let Some(recipient) = input.recipients.iter().find(|recipient| !validator::validate_email(recipient.as_str())) {
+            return Err(Self::invalid("Invalid recipient email address", "INVALID_EMAIL", recipient));
+        }
+        let Some(cron) = parse_schedule(&input.schedule)? else {
+            return Err(ApiError::validation(
+                "A report schedule cannot be off; delete the schedule instead",
+                None,
+            ));
+        };
+
+        let report = self.reports.find(conn, org_id, report_id).await?;
+        let now = Utc::now();
+        let mut recipients = input.recipients;
+        recipients.sort_by_key(|recipient| recipient.to_lowercase());
+        recipients.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+        let schedule = self
+            .schedules
+            .upsert(conn, &ReportSchedule {
+                id: Uuid::new_v4(),
+                org_id,
+                report_id: report.id,
+                schedule: input.schedule.trim().to_string(),
+                recipients,
+                format: input.format.unwrap_or_default().as_str().to_string(),
+                parameters: serde_json::to_value(&input.parameters).unwrap_or_default(),
+                next_run_at: next_run(Some(&cron), now),
+                created_by,
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+        info!(
+            report_id = %report.id,
+            org_id = %org_id,
+            schedule = %schedule.schedule,
+            next_run_at = %schedule.next_run_at,
+            "Scheduled report '{}'", report.name
+        );
+        Ok(schedule)
+    }
+
+    /// Gets the schedule of a report
+    pub async fn get(&self, conn: &mut PgConnection, org_id: Uuid, report_id: Uuid) -> Result<ReportSchedule> {
+        let report = self.reports.find(conn, org_id, report_id).await?;
+        self.schedules
+            .find_for_report(conn, org_id, report.id)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Report with id {} has no schedule", report_id)))
+    }
+
+    /// Stops delivering a report; its delivery history is kept
+    pub async fn remove(&self, conn: &mut PgConnection, org_id: Uuid, report_id: Uuid) -> Result<()> {
+        let report = self.reports.find(conn, org_id, report_id).await?;
+        if !self.schedules.delete_for_report(conn, org_id, report.id).await? {
+            return Err(ApiError::not_found(format!("Report with id {} has no schedule", report_id)));
+        }
+        info!(report_id = %report.id, org_id = %org_id, "Unscheduled report '{}'", report.name);
+        Ok(())
+    }
+
+    /// Lists the deliveries of a report, newest first
+    pub async fn deliveries(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        report_id: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<ReportDelivery>, RowCount)> {
+        let report = self.reports.find(conn, org_id, report_id).await?;
+        let deliveries = self.schedules.list_deliveries(conn, org_id, report.id, pagination).await?;
+        let total = self.schedules.count_deliveries(conn, org_id, report.id).await?;
+        Ok((deliveries, total))
+    }
+
+    /// Gets one delivery of a report
+    pub async fn delivery(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        report_id: Uuid,
+        delivery_id: Uuid,
+    ) -> Result<ReportDelivery> {
+        let report = self.reports.find(conn, org_id, report_id).await?;
+        self.schedules.find_delivery(conn, org_id, report.id, delivery_id).await
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use diesel::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use super::{definition::ReportDefinition, present::present, run::{run, ReportResult}};
+use crate::{
+    api::{resources::report::dto::SaveReportInput, utils::PaginationParams},
+    db::{count::RowCount, hierarchy, models::Report, repositories::ReportRepository},
+    domain::Preferences,
+    error::{ApiError, DatabaseError, ErrorContext, Result},
+};
+
+/// Rows of a dataset a single run reads, newest first
+pub const MAX_REPORT_ROWS: i64 = 100_000;
+
+/// Service for saving and running an organization's reports
+pub struct ReportService<R: ReportRepository + Send + Sync> {
+    repository: R,
+}
+
+impl<R: ReportRepository + Send + Sync> ReportService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    fn validate(input: &SaveReportInput) -> Result<()> {
+        if let Err(e) = ValidatorValidate::validate(input) {
+            return Err(ApiError::validation_with_context(
+                "Invalid input",
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(serde_json::json!(e))
+            ));
+        }
+        input.definition.validate()
+    }
+
+    /// Saves a new report
+    pub async fn create(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        created_by: Option<Uuid>,
+        input: SaveReportInput,
+    ) -> Result<Report> {
+        Self::validate(&input)?;
+        let now = Utc::now();
+        let report = self
+            .repository
+            .create(conn, &Report {
+                id: Uuid::new_v4(),
+                org_id,
+                name: input.name,
+                description: input.description,
+                definition: serde_json::to_value(&input.definition).unwrap_or_default(),
+                created_by,
+                created_at: now,
+                updated_at: now,
+                deleted_at: None,
+            })
+            .await?;
+        info!(report_id = %report.id, org_id = %org_id, "Saved report '{}'", report.name);
+        Ok(report)
+    }
+
+    /// Gets one of the organization's reports
+    pub async fn get(&self, conn: &mut PgConnection, org_id: Uuid, id: Uuid) -> Result<Report> {
+        self.repository.find(conn, org_id, id).await
+    }
+
+    /// Lists the organization's reports by name
+    pub async fn list(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<Report>, RowCount)> {
+        let reports = self.repository.list_for_org(conn, org_id, pagination).await?;
+        let total = self.repository.count_for_org(conn, org_id).await?;
+        Ok((reports, total))
+    }
+
+    /// Replaces the name, description and definition of a report
+    pub async fn update(&self, conn: &mut PgConnection, org_id: Uuid, id: Uuid, input: SaveReportInput) -> Result<Report> {
+        Self::validate(&input)?;
+        let mut report = self.repository.find(conn, org_id, id).await?;
+        report.name = input.name;
+        report.description = input.description;
+        report.definition = serde_json::to_value(&input.definition).unwrap_or_default();
+        let report = self.repository.update(conn, org_id, &report).await?;
+        info!(report_id = %report.id, org_id = %org_id, "Updated report '{}'", report.name);
+        Ok(report)
+    }
+
+    /// Deletes a report
+    pub async fn delete(&self, conn: &mut PgConnection, org_id: Uuid, id: Uuid) -> Result<Report> {
+        let report = self.repository.soft_delete(conn, org_id, id).await?;
+        info!(report_id = %report.id, org_id = %org_id, "Deleted report '{}'", report.name);
+        Ok(report)
+    }
+
+    /// Runs a saved report with the given parameter values, presented the
+    /// way `preferences` ask
+    pub async fn run(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        id: Uuid,
+        parameters: &HashMap<String, serde_json::Value>,
+        preferences: &Preferences,
+    ) -> Result<(Report, ReportResult)> {
+        let report = self.repository.find(conn, org_id, id).await?;
+        let definition = ReportDefinition::from_stored(&report)?;
+        let result = Self::execute(conn, org_id, &definition, parameters, preferences)?;
+        info!(
+            report_id = %report.id,
+            org_id = %org_id,
+            rows = result.rows.len(),
+            truncated = result.truncated,
+            "Ran report '{}'", report.name
+        );
+        Ok((report, result))
+    }
+
+    /// Runs a definition against the data of the organization and of the
+    /// divisions and areas under it
+    pub fn execute(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        definition: &ReportDefinition,
+        parameters: &HashMap<String, serde_json::Value>,
+        preferences: &Preferences,
+    ) -> Result<ReportResult> {
+        let org_ids = hierarchy::subtree_ids(conn, org_id).map_err(|e| ApiError::from(DatabaseError::from(e)))?;
+        let mut rows = definition
+            .dataset
+            .load(conn, &org_ids, MAX_REPORT_ROWS + 1)
+            .map_err(|e| ApiError::from(DatabaseError::from(e)))?;
+        let truncated = rows.len() as i64 > MAX_REPORT_ROWS;
+        rows.truncate(MAX_REPORT_ROWS as usize);
+        Ok(present(definition, run(definition, rows, parameters, truncated)?, preferences))
+    }
+}
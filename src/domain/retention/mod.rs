@@ -0,0 +1,5 @@
+mod policy;
+mod service;
+
+pub use policy::{is_purgeable, PurgeTarget, RetentionPolicy, PURGE_TARGETS};
+pub use service::LegalHoldService;
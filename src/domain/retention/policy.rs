@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    error::{ApiError, Result},
+    utils::Config,
+};
+
+/// A table whose soft-deleted rows are purged once past retention
+#[derive(Debug, Clone, Copy)]
+pub struct PurgeTarget {
+    /// Table name, also used as the legal hold entity type
+    pub entity: &'static str,
+    /// Extra SQL condition a row must satisfy before it can be purged
+    pub guard: Option<&'static str>,
+}
+
+/// Purgeable tables, ordered so that dependent rows are purged before the
+/// rows they reference
+pub const PURGE_TARGETS: &[PurgeTarget] = &[
+    PurgeTarget { entity: "refresh_tokens", guard: None },
+    PurgeTarget { entity: "password_reset_tokens", guard: None },
+    PurgeTarget { entity: "email_verification_tokens", guard: None },
+    PurgeTarget { entity: "magic_link_tokens", guard: None },
+    PurgeTarget { entity: "devices", guard: None },
+    PurgeTarget { entity: "users", guard: None },
+    PurgeTarget {
+        entity: "organizations",
+        guard: Some("NOT EXISTS (SELECT 1 FROM users u WHERE u.org_id = organizations.id)"),
+    },
+    PurgeTarget { entity: "archives", guard: None },
+];
+
+/// Returns true if records of the given entity type are subject to purging
+pub fn is_purgeable(entity: &str) -> bool {
+    PURGE_TARGETS.iter().any(|target| target.entity == entity)
+}
+
+/// How long soft-deleted records are kept before they are purged
+///
+/// A retention of `None` keeps soft-deleted records forever.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    default_days: Option<i64>,
+    overrides: HashMap<String, Option<i64>>,
+}
+
+impl RetentionPolicy {
+    /// Creates a policy that keeps every entity for `default_days`
+    pub fn new(default_days: i64) -> Self {
+        Self {
+            default_days: Some(default_days.max(0)),
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Creates a policy from a default and a list of per-entity overrides
+    ///
+    /// Overrides are comma separated `entity=days` pairs, where `days` may be
+    /// `never` to disable purging, e.g. `users=30,organizations=never`.
+    pub fn parse(default_days: i64, overrides: &str) -> Result<Self> {
+        let mut policy = Self::new(default_days);
+
+        for pair in overrides.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (entity, days) = pair
+                .split_once('=')
+                .map(|(entity, days)| (entity.trim(), days.trim()))
+                .ok_or_else(|| {
+                    ApiError::configuration_error(format!("Invalid retention override '{}', expected entity=days", pair))
+                })?;
+
+            if !is_purgeable(entity) {
+                return Err(ApiError::configuration_error(format!(
+                    "Unknown entity '{}' in retention overrides",
+                    entity
+                )));
+            }
+
+            let days = match days {
+                "never" => None,
+                days => Some(days.parse::<i64>().ok().filter(|days| *days >= 0).ok_or_else(|| {
+                    ApiError::configuration_error(format!("Invalid retention days '{}' for {}", days, entity))
+                })?),
+            };
+            policy.overrides.insert(entity.to_string(), days);
+        }
+
+        Ok(policy)
+    }
+
+    /// Creates the policy configured for this deployment
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Self::parse(config.purge_after_days, &config.purge_retention)
+    }
+
+    /// Returns the default retention in days
+    pub fn default_days(&self) -> Option<i64> {
+        self.default_days
+    }
+
+    /// Returns the retention in days for an entity type
+    pub fn retention_days(&self, entity: &str) -> Option<i64> {
+        self.overrides
+            .get(entity)
+            .copied()
+            .unwrap_or(self.default_days)
+    }
+
+    /// Returns the deletion time before which records of an entity are purged
+    pub fn cutoff(&self, entity: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.retention_days(entity).map(|days| now - Duration::days(days))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_overrides() {
+        let policy = RetentionPolicy::parse(90, "users=30, organizations=never").unwrap();
+        assert_eq!(policy.retention_days("users"), Some(30));
+        assert_eq!(policy.retention_days("organizations"), None);
+        assert_eq!(policy.retention_days("refresh_tokens"), Some(90));
+
+        assert!(RetentionPolicy::parse(90, "").is_ok());
+        assert!(RetentionPolicy::parse(90, "users").is_err());
+        assert!(RetentionPolicy::parse(90, "users=-1").is_err());
+        assert!(RetentionPolicy::parse(90, "unknown=10").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    api::{resources::admin::dto::CreateLegalHoldInput, utils::PaginationParams},
+    db::{
+        count::RowCount,
+        models::LegalHold,
+        repositories::LegalHoldRepository,
+    },
+    domain::retention::policy::is_purgeable,
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+use chrono::Utc;
+use diesel::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+/// Service for placing and releasing legal holds
+pub struct LegalHoldService<R: LegalHoldRepository + Send + Sync> {
+    repository: R,
+}
+
+impl<R: LegalHoldRepository + Send + Sync> LegalHoldService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    pub fn repository(&self) -> &R {
+        &self.repository
+    }
+
+    /// Places a hold on a record, exempting it from purging
+    pub async fn place(
+        &self,
+        conn: &mut PgConnection,
+        input: CreateLegalHoldInput,
+        placed_by: Option<Uuid>,
+    ) -> Result<LegalHold> {
+        if let Err(e) = ValidatorValidate::validate(&input) {
+            return Err(ApiError::validation_with_context(
+                "Invalid input",
+                ErrorContext::new()
+                    .with_message_key("INVALID_INPUT")
+                    .with_details(serde_json::json!(e))
+            ));
+        }
+
+        if !is_purgeable(&input.entity_type) {
+            return Err(ApiError::validation_with_context(
+                "Unknown entity type",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "field": "entity_type",
+                    "code": "UNKNOWN_ENTITY_TYPE",
+                    "value": input.entity_type
+                }))
+            ));
+        }
+
+        if let Some(existing) = self.repository.find_active(conn, &input.entity_type, input.entity_id).await? {
+            return Err(ApiError::new(
+                ErrorCode::Conflict,
+                "Record is already under legal hold",
+                ErrorContext::new().with_details(serde_json::json!({
+                    "legal_hold_id": existing.id
+                }))
+            ));
+        }
+
+        let now = Utc::now();
+        let hold = self.repository.create(conn, &LegalHold {
+            id: Uuid::new_v4(),
+            entity_type: input.entity_type,
+            entity_id: input.entity_id,
+            reason: input.reason,
+            placed_by,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }).await?;
+
+        info!(
+            legal_hold_id = %hold.id,
+            entity_type = %hold.entity_type,
+            entity_id = %hold.entity_id,
+            "Placed legal hold"
+        );
+        Ok(hold)
+    }
+
+    /// Releases a hold, making the record eligible for purging again
+    pub async fn release(&self, conn: &mut PgConnection, id: Uuid) -> Result<LegalHold> {
+        let hold = self.repository.soft_delete(conn, id).await?;
+        info!(
+            legal_hold_id = %hold.id,
+            entity_type = %hold.entity_type,
+            entity_id = %hold.entity_id,
+            "Released legal hold"
+        );
+        Ok(hold)
+    }
+
+    /// Lists active holds, optionally restricted to one entity type
+    pub async fn list(
+        &self,
+        conn: &mut PgConnection,
+        entity_type: Option<&str>,
+        pagination: &PaginationParams,
+    ) -> Result<Vec<LegalHold>> {
+        match entity_type {
+            Some(entity_type) => self.repository.list_by_entity_type(conn, entity_type, pagination).await,
+            None => self.repository.list(conn, pagination).await,
+        }
+    }
+
+    /// Counts active holds, optionally restricted to one entity type
+    pub async fn count(&self, conn: &mut PgConnection, entity_type: Option<&str>) -> Result<RowCount> {
+        self.repository.count(conn, entity_type).await
+    }
+}
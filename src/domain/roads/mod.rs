@@ -0,0 +1,11 @@
+//! Seasonal road restrictions
+//!
+//! Roads on soft ground are closed to hauling during spring breakup, while
+//! thaw weakens the roadbed, and winter roads are only passable while the
+//! ground is frozen. Restrictions are dated per road and season; dispatch
+//! checks a load's route against them and the calendar lists the restricted
+//! periods of the roads serving active blocks.
+
+mod restriction;
+
+pub use restriction::{calendar, dispatch_warnings, CalendarEntry, RestrictionKind, RestrictionWarning, RoadRestriction};
@@ -0,0 +1,168 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Kinds of seasonal road restriction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestrictionKind {
+    /// Closed to hauling between the dates
+    SpringBreakup,
+    /// Open to hauling only between the dates, while the ground is frozen
+    WinterOnly,
+}
+
+/// A dated restriction of one road
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoadRestriction {
+    pub road_id: Uuid,
+    pub kind: RestrictionKind,
+    /// First day of the period, inclusive
+    pub starts_on: NaiveDate,
+    /// Last day of the period, inclusive
+    pub ends_on: NaiveDate,
+}
+
+impl RoadRestriction {
+    fn covers(&self, date: NaiveDate) -> bool {
+        self.starts_on <= date && date <= self.ends_on
+    }
+}
+
+/// A restricted road on a load's route
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestrictionWarning {
+    pub road_id: Uuid,
+    pub kind: RestrictionKind,
+    pub message: String,
+}
+
+/// A period a road is restricted in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CalendarEntry {
+    pub road_id: Uuid,
+    pub kind: RestrictionKind,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// How a road is restricted on a date, if at all
+///
+/// A road with winter-only periods is restricted outside all of them.
+fn restriction_on(restrictions: &[RoadRestriction], road_id: Uuid, date: NaiveDate) -> Option<RestrictionKind> {
+    let mut winter_only = false;
+    let mut winter_open = false;
+    for restriction in restrictions.iter().filter(|restriction| restriction.road_id == road_id) {
+        match restriction.kind {
+            RestrictionKind::SpringBreakup if restriction.covers(date) => return Some(RestrictionKind::SpringBreakup),
+            RestrictionKind::SpringBreakup => {}
+            RestrictionKind::WinterOnly => {
+                winter_only = true;
+                winter_open |= restriction.covers(date);
+            }
+        }
+    }
+    (winter_only && !winter_open).then_some(RestrictionKind::WinterOnly)
+}
+
+/// Warnings for the restricted roads of a load's route on its haul date
+pub fn dispatch_warnings(restrictions: &[RoadRestriction], route: &[Uuid], date: NaiveDate) -> Vec<RestrictionWarning> {
+    let mut warnings = Vec::new();
+    for &road_id in route {
+        if warnings.iter().any(|warning: &RestrictionWarning| warning.road_id == road_id) {
+            continue;
+        }
+        let Some(kind) = restriction_on(restrictions, road_id, date) else {
+            continue;
+        };
+        let message = match kind {
+            RestrictionKind::SpringBreakup => format!("Road {} is closed for spring breakup on {}", road_id, date),
+            RestrictionKind::WinterOnly => format!("Road {} is a winter road and is not open for hauling on {}", road_id, date),
+        };
+        warnings.push(RestrictionWarning { road_id, kind, message });
+    }
+    warnings
+}
+
+/// Periods between `from` and `to`, inclusive, in which the given roads are
+/// restricted, by road and then date
+pub fn calendar(restrictions: &[RoadRestriction], roads: &[Uuid], from: NaiveDate, to: NaiveDate) -> Vec<CalendarEntry> {
+    let mut roads = roads.to_vec();
+    roads.sort();
+    roads.dedup();
+
+    let mut entries: Vec<CalendarEntry> = Vec::new();
+    for road_id in roads {
+        for date in from.iter_days().take_while(|date| *date <= to) {
+            let Some(kind) = restriction_on(restrictions, road_id, date) else {
+                continue;
+            };
+            match entries.last_mut() {
+                Some(entry) if entry.road_id == road_id && entry.kind == kind && entry.to.succ_opt() == Some(date) => {
+                    entry.to = date;
+                }
+                _ => entries.push(CalendarEntry {
+                    road_id,
+                    kind,
+                    from: date,
+                    to: date,
+                }),
+            }
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn restriction(road_id: Uuid, kind: RestrictionKind, starts_on: NaiveDate, ends_on: NaiveDate) -> RoadRestriction {
+        RoadRestriction {
+            road_id,
+            kind,
+            starts_on,
+            ends_on,
+        }
+    }
+
+    #[test]
+    fn test_dispatch_warnings() {
+        let (mainline, winter_road, spur) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let restrictions = [
+            restriction(mainline, RestrictionKind::SpringBreakup, date(3, 20), date(5, 10)),
+            restriction(winter_road, RestrictionKind::WinterOnly, date(1, 1), date(3, 15)),
+        ];
+
+        let warnings = dispatch_warnings(&restrictions, &[spur, mainline, winter_road], date(2, 1));
+        assert!(warnings.is_empty());
+
+        let warnings = dispatch_warnings(&restrictions, &[spur, mainline, winter_road, mainline], date(4, 1));
+        assert_eq!(
+            warnings.iter().map(|warning| (warning.road_id, warning.kind)).collect::<Vec<_>>(),
+            vec![(mainline, RestrictionKind::SpringBreakup), (winter_road, RestrictionKind::WinterOnly)]
+        );
+    }
+
+    #[test]
+    fn test_calendar_merges_days() {
+        let (mainline, winter_road) = (Uuid::new_v4(), Uuid::new_v4());
+        let restrictions = [
+            restriction(mainline, RestrictionKind::SpringBreakup, date(3, 20), date(5, 10)),
+            restriction(winter_road, RestrictionKind::WinterOnly, date(1, 1), date(3, 15)),
+            restriction(winter_road, RestrictionKind::WinterOnly, date(12, 10), date(12, 31)),
+        ];
+
+        let entries = calendar(&restrictions, &[mainline, winter_road], date(3, 1), date(12, 31));
+        let mut expected = vec![
+            CalendarEntry { road_id: mainline, kind: RestrictionKind::SpringBreakup, from: date(3, 20), to: date(5, 10) },
+            CalendarEntry { road_id: winter_road, kind: RestrictionKind::WinterOnly, from: date(3, 16), to: date(12, 9) },
+        ];
+        expected.sort_by_key(|entry| entry.road_id);
+        assert_eq!(entries, expected);
+    }
+}
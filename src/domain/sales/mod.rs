@@ -0,0 +1,12 @@
+//! Timber sales
+//!
+//! A tender (or sealed-bid auction) is prepared as a draft listing the
+//! parcels offered, each an assortment and volume from a harvest block,
+//! then opened for bids until its deadline. Bids are captured as they
+//! arrive and stay sealed until the deadline passes; each parcel is then
+//! awarded to one bid, which becomes a sale contract for delivery tracking.
+//! The tender is awarded once all of its parcels are.
+
+mod service;
+
+pub use service::{TenderDetails, TimberSaleService};
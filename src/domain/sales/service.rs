@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use crate::{
+    api::{
+        resources::sales::dto::{AwardParcelInput, CaptureBidInput, CreateTenderInput},
+        utils::PaginationParams,
+    },
+    db::{
+        count::RowCount,
+        models::{SaleContract, TenderBid, TenderParcel, TenderStatus, TimberTender},
+        repositories::{HarvestBlockRepository, HarvestBlockRepositoryImpl, TimberSaleRepository},
+    },
+    domain::activity::{ActivityKind, ActivityLog, NewActivity},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+
+/// A tender with its parcels and the number of bids each has received
+#[derive(Debug, Clone)]
+pub struct TenderDetails {
+    pub tender: TimberTender,
+    pub parcels: Vec<(TenderParcel, i64)>,
+}
+
+/// Service for timber sale tenders, bids and sale contracts
+pub struct TimberSaleService<R: TimberSaleRepository + Send + Sync> {
+    repository: R,
+}
+
+fn invalid_input(e: validator::ValidationErrors) -> ApiError {
+    ApiError::validation_with_context(
+        "Invalid input",
+        ErrorContext::new()
+            .with_message_key("INVALID_INPUT")
+            .with_details(json!(e))
+    )
+}
+
+fn conflict(message: impl Into<String>) -> ApiError {
+    ApiError::new(ErrorCode::Conflict, message, ErrorContext::new())
+}
+
+fn sealed(tender: &TimberTender) -> ApiError {
+    ApiError::new(
+        ErrorCode::Conflict,
+        format!("Bids are sealed until {}", tender.bid_deadline),
+        ErrorContext::new().with_details(json!({ "bid_deadline": tender.bid_deadline })),
+    )
+}
+
+fn meets_reserve(parcel: &TenderParcel, price_per_m3: f64) -> bool {
+    match parcel.reserve_price {
+        Some(reserve) => price_per_m3 >= reserve,
+        None => true,
+    }
+}
+
+impl<R: TimberSaleRepository + Send + Sync> TimberSaleService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    async fn details(&self, conn: &mut PgConnection, tender: TimberTender) -> Result<TenderDetails> {
+        let parcels = self.repository.parcels(conn, tender.id).await?;
+        let counts: HashMap<Uuid, i64> = self.repository.bid_counts(conn, tender.id).await?.into_iter().collect();
+        Ok(TenderDetails {
+            parcels: parcels
+                .into_iter()
+                .map(|parcel| {
+                    let count = counts.get(&parcel.id).copied().unwrap_or(0);
+                    (parcel, count)
+                })
+                .collect(),
+            tender,
+        })
+    }
+
+    /// An open tender whose deadline has passed, so its parcels can be
+    /// awarded
+    async fn awardable_tender(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        tender_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<TimberTender> {
+        let tender = self.repository.find_tender(conn, org_id, tender_id).await?;
+        if tender.status != TenderStatus::Open.as_str() {
+            return Err(conflict(format!("Tender {} is {}", tender.id, tender.status)));
+        }
+        if tender.bid_deadline > now {
+            return Err(sealed(&tender));
+        }
+        Ok(tender)
+    }
+
+    /// Creates a draft tender with its parcels
+    pub async fn create_tender(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        created_by: Option<Uuid>,
+        input: CreateTenderInput,
+    ) -> Result<TenderDetails> {
+        ValidatorValidate::validate(&input).map_err(invalid_input)?;
+        for parcel in &input.parcels {
+            ValidatorValidate::validate(parcel).map_err(invalid_input)?;
+            if !(parcel.volume_m3.is_finite() && parcel.volume_m3 > 0.0) {
+                return Err(ApiError::validation("Parcel volume must be positive", None));
+            }
+            if parcel.reserve_price.is_some_and(|price| !(price.is_finite() && price >= 0.0)) {
+                return Err(ApiError::validation("Reserve price must not be negative", None));
+            }
+        }
+        let now = Utc::now();
+        if input.bid_deadline <= now {
+            return Err(ApiError::validation("The bid deadline must be in the future", None));
+        }
+        // Parcels can only offer the organization's own blocks
+        for block_id in input.parcels.iter().filter_map(|parcel| parcel.block_id) {
+            HarvestBlockRepositoryImpl.find(conn, org_id, block_id).await?;
+        }
+
+        let tender = TimberTender {
+            id: Uuid::new_v4(),
+            org_id,
+            title: input.title.trim().to_string(),
+            description: input.description,
+            status: TenderStatus::Draft.to_string(),
+            bid_deadline: input.bid_deadline,
+            created_by,
+            created_at: now,
+            updated_at: now,
+        };
+        let parcels = input
+            .parcels
+            .into_iter()
+            .enumerate()
+            .map(|(position, parcel)| TenderParcel {
+                id: Uuid::new_v4(),
+                tender_id: tender.id,
+                position: position as i32,
+                block_id: parcel.block_id,
+                assortment: parcel.assortment.trim().to_string(),
+                volume_m3: parcel.volume_m3,
+                reserve_price: parcel.reserve_price,
+                description: parcel.description,
+                awarded_bid_id: None,
+                created_at: now,
+            })
+            .collect::<Vec<_>>();
+        let (tender, parcels) = self.repository.create_tender(conn, &tender, &parcels).await?;
+        info!(tender_id = %tender.id, org_id = %org_id, parcels = parcels.len(), "Created tender '{}'", tender.title);
+        ActivityLog::record(
+            conn,
+            org_id,
+            created_by,
+            NewActivity::new(ActivityKind::TenderCreated, "tender", tender.id, format!("Created tender {}", tender.title)),
+        )
+        .await;
+        Ok(TenderDetails {
+            tender,
+            parcels: parcels.into_iter().map(|parcel| (parcel, 0)).collect(),
+        })
+    }
+
+    /// Gets a tender with its parcels
+    pub async fn get_tender(&self, conn: &mut PgConnection, org_id: Uuid, tender_id: Uuid) -> Result<TenderDetails> {
+        let tender = self.repository.find_tender(conn, org_id, tender_id).await?;
+        self.details(conn, tender).await
+    }
+
+    /// Lists an organization's tenders, newest first
+    pub async fn list_tenders(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        status: Option<TenderStatus>,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<TimberTender>, RowCount)> {
+        let tenders = self.repository.list_tenders(conn, org_id, status, pagination).await?;
+        let total = self.repository.count_tenders(conn, org_id, status).await?;
+        Ok((tenders, total))
+    }
+
+    /// Opens a draft tender for bids
+    pub async fn open_tender(&self, conn: &mut PgConnection, org_id: Uuid, tender_id: Uuid) -> Result<TimberTender> {
+        let tender = self.repository.find_tender(conn, org_id, tender_id).await?;
+        if tender.bid_deadline <= Utc::now() {
+            return Err(ApiError::validation("The bid deadline has passed", None));
+        }
+        let tender = self
+            .repository
+            .set_status(conn, org_id, tender_id, &[TenderStatus::Draft], TenderStatus::Open)
+            .await?
+            .ok_or_else(|| conflict(format!("Tender {} is {}; only drafts can be opened", tender.id, tender.status)))?;
+        info!(tender_id = %tender.id, org_id = %org_id, "Opened tender for bids until {}", tender.bid_deadline);
+        Ok(tender)
+    }
+
+    /// Cancels a tender that is not awarded yet
+    ///
+    /// Parcels already awarded keep their sale contracts.
+    pub async fn cancel_tender(&self, conn: &mut PgConnection, org_id: Uuid, tender_id: Uuid) -> Result<TimberTender> {
+        let tender = self.repository.find_tender(conn, org_id, tender_id).await?;
+        let tender = self
+            .repository
+            .set_status(conn, org_id, tender_id, &[TenderStatus::Draft, TenderStatus::Open], TenderStatus::Cancelled)
+            .await?
+            .ok_or_else(|| conflict(format!("Tender {} is already {}", tender.id, tender.status)))?;
+        info!(tender_id = %tender.id, org_id = %org_id, "Cancelled tender");
+        Ok(tender)
+    }
+
+    /// Captures a bid received for a parcel of an open tender
+    pub async fn capture_bid(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        tender_id: Uuid,
+        captured_by: Option<Uuid>,
+        input: CaptureBidInput,
+    ) -> Result<TenderBid> {
+        ValidatorValidate::validate(&input).map_err(invalid_input)?;
+        if !(input.price_per_m3.is_finite() && input.price_per_m3 > 0.0) {
+            return Err(ApiError::validation("The bid price must be positive", None));
+        }
+        let tender = self.repository.find_tender(conn, org_id, tender_id).await?;
+        if tender.status != TenderStatus::Open.as_str() {
+            return Err(conflict(format!("Tender {} is {}; bids are only taken while open", tender.id, tender.status)));
+        }
+        let now = Utc::now();
+        let received_at = input.received_at.unwrap_or(now);
+        if received_at > now {
+            return Err(ApiError::validation("A bid cannot be received in the future", None));
+        }
+        if received_at > tender.bid_deadline {
+            return Err(ApiError::validation(
+                format!("The bid was received after the deadline of {}", tender.bid_deadline),
+                None,
+            ));
+        }
+        let parcel = self
+            .repository
+            .parcels(conn, tender.id)
+            .await?
+            .into_iter()
+            .find(|parcel| parcel.id == input.parcel_id)
+            .ok_or_else(|| ApiError::not_found(format!("Parcel with id {} not found", input.parcel_id)))?;
+
+        let bid = self
+            .repository
+            .create_bid(conn, &TenderBid {
+                id: Uuid::new_v4(),
+                tender_id: tender.id,
+                parcel_id: parcel.id,
+                bidder_name: input.bidder_name.trim().to_string(),
+                bidder_email: input.bidder_email,
+                price_per_m3: input.price_per_m3,
+                received_at,
+                captured_by,
+                created_at: now,
+            })
+            .await?;
+        info!(bid_id = %bid.id, tender_id = %tender.id, parcel_id = %parcel.id, "Captured bid");
+        ActivityLog::record(
+            conn,
+            org_id,
+            captured_by,
+            NewActivity::new(
+                ActivityKind::BidCaptured,
+                "tender",
+                tender.id,
+                format!("Captured a bid from {} on tender {}", bid.bidder_name, tender.title),
+            )
+            .with_data(json!({ "bid_id": bid.id, "parcel_id": parcel.id })),
+        )
+        .await;
+        Ok(bid)
+    }
+
+    /// The bids of a tender, by parcel and highest price first, each with
+    /// whether it meets the parcel's reserve
+    ///
+    /// Bids stay sealed until the tender's deadline passes.
+    pub async fn bids(&self, conn: &mut PgConnection, org_id: Uuid, tender_id: Uuid) -> Result<Vec<(TenderBid, bool)>> {
+        let tender = self.repository.find_tender(conn, org_id, tender_id).await?;
+        if tender.bid_deadline > Utc::now() {
+            return Err(sealed(&tender));
+        }
+        let parcels: HashMap<Uuid, TenderParcel> = self
+            .repository
+            .parcels(conn, tender.id)
+            .await?
+            .into_iter()
+            .map(|parcel| (parcel.id, parcel))
+            .collect();
+        Ok(self
+            .repository
+            .list_bids(conn, tender.id)
+            .await?
+            .into_iter()
+            .map(|bid| {
+                let meets = parcels.get(&bid.parcel_id).is_some_and(|parcel| meets_reserve(parcel, bid.price_per_m3));
+                (bid, meets)
+            })
+            .collect())
+    }
+
+    /// Awards a bid's parcel to it, creating the sale contract
+    ///
+    /// The tender is awarded once all of its parcels are.
+    pub async fn award(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        tender_id: Uuid,
+        created_by: Option<Uuid>,
+        input: AwardParcelInput,
+    ) -> Result<SaleContract> {
+        let tender = self.awardable_tender(conn, org_id, tender_id, Utc::now()).await?;
+        let bid = self.repository.find_bid(conn, tender.id, input.bid_id).await?;
+        let parcels = self.repository.parcels(conn, tender.id).await?;
+        let parcel = parcels
+            .iter()
+            .find(|parcel| parcel.id == bid.parcel_id)
+            .ok_or_else(|| ApiError::not_found(format!("Parcel with id {} not found", bid.parcel_id)))?;
+        if !meets_reserve(parcel, bid.price_per_m3) {
+            return Err(ApiError::validation("The bid is below the parcel's reserve price", None));
+        }
+
+        let contract = SaleContract {
+            id: Uuid::new_v4(),
+            org_id,
+            tender_id: tender.id,
+            parcel_id: parcel.id,
+            bid_id: bid.id,
+            buyer_name: bid.bidder_name.clone(),
+            block_id: parcel.block_id,
+            assortment: parcel.assortment.clone(),
+            volume_m3: parcel.volume_m3,
+            price_per_m3: bid.price_per_m3,
+            created_by,
+            created_at: Utc::now(),
+        };
+        let contract = self
+            .repository
+            .award(conn, &contract)
+            .await?
+            .ok_or_else(|| conflict(format!("Parcel {} is already awarded", parcel.id)))?;
+        info!(contract_id = %contract.id, tender_id = %tender.id, parcel_id = %parcel.id, bid_id = %bid.id, "Awarded parcel");
+        ActivityLog::record(
+            conn,
+            org_id,
+            created_by,
+            NewActivity::new(
+                ActivityKind::ParcelAwarded,
+                "sale_contract",
+                contract.id,
+                format!("Awarded a parcel of tender {} to {}", tender.title, contract.buyer_name),
+            )
+            .with_data(json!({ "tender_id": tender.id, "parcel_id": parcel.id })),
+        )
+        .await;
+
+        let unawarded = parcels
+            .iter()
+            .filter(|other| other.id != parcel.id && other.awarded_bid_id.is_none())
+            .count();
+        if unawarded == 0 {
+            self.repository
+                .set_status(conn, org_id, tender.id, &[TenderStatus::Open], TenderStatus::Awarded)
+                .await?;
+            info!(tender_id = %tender.id, org_id = %org_id, "Awarded every parcel of the tender");
+        }
+        Ok(contract)
+    }
+
+    /// Gets a sale contract
+    pub async fn get_contract(&self, conn: &mut PgConnection, org_id: Uuid, contract_id: Uuid) -> Result<SaleContract> {
+        self.repository.find_contract(conn, org_id, contract_id).await
+    }
+
+    /// Lists an organization's sale contracts, newest first
+    pub async fn contracts(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        block_id: Option<Uuid>,
+        pagination: &PaginationParams,
+    ) -> Result<(Vec<SaleContract>, RowCount)> {
+        let contracts = self.repository.list_contracts(conn, org_id, block_id, pagination).await?;
+        let total = self.repository.count_contracts(conn, org_id, block_id).await?;
+        Ok((contracts, total))
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::Serialize;
+use tracing::error;
+use utoipa::ToSchema;
+
+use crate::error::ApiError;
+
+/// Schema of SCIM error responses
+pub const ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+
+/// Content type of SCIM responses
+pub const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+/// An error in the form SCIM clients expect (RFC 7644, section 3.12)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ScimError {
+    pub schemas: Vec<String>,
+    /// HTTP status code, as a string
+    pub status: String,
+    /// `invalidFilter`, `invalidSyntax`, `invalidValue`, `mutability` or
+    /// `uniqueness`, for the errors SCIM defines a type for
+    #[serde(rename = "scimType", skip_serializing_if = "Option::is_none")]
+    pub scim_type: Option<String>,
+    pub detail: String,
+}
+
+impl ScimError {
+    pub fn new(status: StatusCode, scim_type: Option<&str>, detail: impl Into<String>) -> Self {
+        Self {
+            schemas: vec![ERROR_SCHEMA.to_string()],
+            status: status.as_u16().to_string(),
+            scim_type: scim_type.map(str::to_string),
+            detail: detail.into(),
+        }
+    }
+
+    pub fn unauthorized(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, None, detail)
+    }
+
+    pub fn forbidden(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, None, detail)
+    }
+
+    pub fn not_found(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, None, detail)
+    }
+
+    pub fn invalid_filter(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, Some("invalidFilter"), detail)
+    }
+
+    pub fn invalid_syntax(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, Some("invalidSyntax"), detail)
+    }
+
+    pub fn invalid_value(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, Some("invalidValue"), detail)
+    }
+
+    pub fn mutability(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, Some("mutability"), detail)
+    }
+
+    pub fn uniqueness(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, Some("uniqueness"), detail)
+    }
+}
+
+impl fmt::Display for ScimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.status, self.detail)
+    }
+}
+
+impl From<ApiError> for ScimError {
+    fn from(e: ApiError) -> Self {
+        let status = e.status_code();
+        if status.is_server_error() {
+            error!(error_code = %e.code, error_message = %e.message, "SCIM request failed");
+        }
+        let scim_type = match status {
+            StatusCode::CONFLICT => Some("uniqueness"),
+            StatusCode::BAD_REQUEST => Some("invalidValue"),
+            _ => None,
+        };
+        Self::new(status, scim_type, e.message)
+    }
+}
+
+impl ResponseError for ScimError {
+    fn status_code(&self) -> StatusCode {
+        self.status.parse().ok().and_then(|status| StatusCode::from_u16(status).ok()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).content_type(SCIM_CONTENT_TYPE).json(self)
+    }
+}
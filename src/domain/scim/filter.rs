@@ -0,0 +1,72 @@
+use super::ScimError;
+
+/// A filter of the form `attribute eq "value"`, the only form identity
+/// providers use to look up a resource before creating it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub attribute: String,
+    pub value: String,
+}
+
+impl Filter {
+    pub fn parse(filter: &str) -> Result<Self, ScimError> {
+        let unsupported = || ScimError::invalid_filter(format!("Unsupported filter '{}', only 'attribute eq \"value\"' is", filter));
+
+        let (attribute, rest) = filter.trim().split_once(char::is_whitespace).ok_or_else(unsupported)?;
+        let (operator, value) = rest.trim_start().split_once(char::is_whitespace).ok_or_else(unsupported)?;
+        if !operator.eq_ignore_ascii_case("eq") {
+            return Err(unsupported());
+        }
+        let value = value.trim();
+        let value = if value.starts_with('"') {
+            serde_json::from_str::<String>(value).map_err(|_| unsupported())?
+        } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+            value.to_ascii_lowercase()
+        } else {
+            return Err(unsupported());
+        };
+        Ok(Self {
+            attribute: attribute.to_string(),
+            value,
+        })
+    }
+
+    /// Whether the filter is on `attribute`, which SCIM compares
+    /// case-insensitively
+    pub fn is_on(&self, attribute: &str) -> bool {
+        self.attribute.eq_ignore_ascii_case(attribute)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_equality_filters() {
+        assert_eq!(
+            Filter::parse(r#"userName eq "anna@pinecorp.com""#).unwrap(),
+            Filter {
+                attribute: "userName".to_string(),
+                value: "anna@pinecorp.com".to_string(),
+            }
+        );
+        assert_eq!(Filter::parse(r#" displayName  EQ "Manager" "#).unwrap().value, "Manager");
+        assert_eq!(Filter::parse(r#"userName eq "a \"quoted\" name""#).unwrap().value, r#"a "quoted" name"#);
+        assert!(Filter::parse(r#"USERNAME eq "x""#).unwrap().is_on("userName"));
+    }
+
+    #[test]
+    fn test_reject_unsupported_filters() {
+        for filter in [
+            r#"userName sw "anna""#,
+            r#"userName eq "anna" and active eq true"#,
+            "userName eq anna",
+            "userName",
+            "",
+        ] {
+            let error = Filter::parse(filter).unwrap_err();
+            assert_eq!(error.scim_type.as_deref(), Some("invalidFilter"), "{}", filter);
+        }
+    }
+}
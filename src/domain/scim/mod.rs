@@ -0,0 +1,29 @@
+//! SCIM 2.0 user provisioning
+//!
+//! Identity providers such as Entra ID and Okta keep an organization's
+//! users in sync over SCIM (RFC 7643 and 7644), authenticating with a
+//! provisioning token an admin issued for the organization. Only a hash of
+//! each token is stored.
+//!
+//! SCIM users are the organization's users, their `userName` being their
+//! email. Deprovisioning a user, by deleting them or setting `active` to
+//! false, removes them and revokes their refresh tokens; setting `active`
+//! again, or provisioning the same email anew, restores them. SCIM groups
+//! are the `Manager` and `Operator` roles: adding a user to a group gives
+//! them its role, removing them from `Manager` makes them an operator
+//! again. Admins are managed in the application, SCIM can only read them.
+
+mod error;
+mod filter;
+mod schema;
+mod service;
+mod token;
+
+pub use error::{ScimError, ERROR_SCHEMA, SCIM_CONTENT_TYPE};
+pub use filter::Filter;
+pub use schema::{
+    ScimGroup, ScimListResponse, ScimMember, ScimMeta, ScimName, ScimPatchOperation, ScimPatchRequest, ScimUser,
+    ScimUserInput, ScimValue, BASE_PATH, GROUP_SCHEMA, LIST_SCHEMA, PATCH_SCHEMA, USER_SCHEMA,
+};
+pub use service::{ScimService, DEFAULT_COUNT, MAX_COUNT};
+pub use token::generate_token;
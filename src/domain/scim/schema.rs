@@ -0,0 +1,243 @@
+//! SCIM resources and messages (RFC 7643 and 7644)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::db::models::auth::{Role, User};
+
+pub const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const LIST_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const PATCH_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+
+/// Path SCIM resources are served under
+pub const BASE_PATH: &str = "/scim/v2";
+
+/// Components of a user's name
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(default)]
+    pub given_name: Option<String>,
+    #[serde(default)]
+    pub family_name: Option<String>,
+}
+
+/// A value of a multi-valued attribute such as `emails` or `groups`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ScimValue {
+    pub value: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+}
+
+/// Resource metadata
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    /// `User` or `Group`
+    pub resource_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<DateTime<Utc>>,
+    pub location: String,
+}
+
+/// A user of the organization
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    pub schemas: Vec<String>,
+    pub id: Uuid,
+    /// The user's email, which they sign in with
+    pub user_name: String,
+    pub name: ScimName,
+    pub display_name: String,
+    pub emails: Vec<ScimValue>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub phone_numbers: Vec<ScimValue>,
+    /// False once the user has been deprovisioned
+    pub active: bool,
+    /// The user's role, as the group of that name
+    pub groups: Vec<ScimValue>,
+    pub meta: ScimMeta,
+}
+
+impl From<&User> for ScimUser {
+    fn from(user: &User) -> Self {
+        let display_name = format!("{} {}", user.first_name, user.last_name).trim().to_string();
+        let phone_numbers = match user.phone_number.as_str() {
+            "" => Vec::new(),
+            phone_number => vec![ScimValue {
+                value: phone_number.to_string(),
+                kind: Some("work".to_string()),
+                ..Default::default()
+            }],
+        };
+        Self {
+            schemas: vec![USER_SCHEMA.to_string()],
+            id: user.id,
+            user_name: user.email.clone(),
+            name: ScimName {
+                formatted: Some(display_name.clone()),
+                given_name: Some(user.first_name.clone()),
+                family_name: Some(user.last_name.clone()),
+            },
+            display_name,
+            emails: vec![ScimValue {
+                value: user.email.clone(),
+                kind: Some("work".to_string()),
+                primary: Some(true),
+                display: None,
+            }],
+            phone_numbers,
+            active: user.deleted_at.is_none(),
+            groups: vec![ScimValue {
+                value: group_id(user.role),
+                display: Some(group_id(user.role)),
+                ..Default::default()
+            }],
+            meta: ScimMeta {
+                resource_type: "User".to_string(),
+                created: Some(user.created_at),
+                last_modified: Some(user.updated_at),
+                location: format!("{}/Users/{}", BASE_PATH, user.id),
+            },
+        }
+    }
+}
+
+/// A user as a SCIM client creates or replaces it; other attributes are
+/// ignored
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserInput {
+    /// The user's email
+    pub user_name: String,
+    #[serde(default)]
+    pub name: Option<ScimName>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Defaults to true
+    #[serde(default)]
+    pub active: Option<bool>,
+}
+
+/// A member of a group
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ScimMember {
+    pub value: Uuid,
+    /// The member's email
+    pub display: String,
+    #[serde(rename = "$ref")]
+    pub reference: String,
+}
+
+/// A role users can be given through SCIM
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroup {
+    pub schemas: Vec<String>,
+    /// The role, `Manager` or `Operator`
+    pub id: String,
+    pub display_name: String,
+    /// Left out when `excludedAttributes=members`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<ScimMember>>,
+    pub meta: ScimMeta,
+}
+
+impl ScimGroup {
+    pub fn new(role: Role, members: Option<&[User]>) -> Self {
+        let id = group_id(role);
+        Self {
+            schemas: vec![GROUP_SCHEMA.to_string()],
+            members: members.map(|members| {
+                members
+                    .iter()
+                    .map(|user| ScimMember {
+                        value: user.id,
+                        display: user.email.clone(),
+                        reference: format!("{}/Users/{}", BASE_PATH, user.id),
+                    })
+                    .collect()
+            }),
+            meta: ScimMeta {
+                resource_type: "Group".to_string(),
+                created: None,
+                last_modified: None,
+                location: format!("{}/Groups/{}", BASE_PATH, id),
+            },
+            display_name: id.clone(),
+            id,
+        }
+    }
+}
+
+/// The roles exposed as groups; admins are managed in the application
+pub const GROUP_ROLES: [Role; 2] = [Role::Manager, Role::Operator];
+
+/// Id and display name of the group of `role`
+pub fn group_id(role: Role) -> String {
+    format!("{:?}", role)
+}
+
+/// The role exposed as the group `id`
+pub fn group_role(id: &str) -> Option<Role> {
+    GROUP_ROLES.into_iter().find(|role| group_id(*role) == id)
+}
+
+/// A page of resources
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListResponse<T: ToSchema> {
+    pub schemas: Vec<String>,
+    pub total_results: i64,
+    /// 1-based index of the first resource
+    pub start_index: i64,
+    pub items_per_page: i64,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<T>,
+}
+
+impl<T: ToSchema> ScimListResponse<T> {
+    pub fn new(resources: Vec<T>, total_results: i64, start_index: i64) -> Self {
+        Self {
+            schemas: vec![LIST_SCHEMA.to_string()],
+            total_results,
+            start_index,
+            items_per_page: resources.len() as i64,
+            resources,
+        }
+    }
+}
+
+/// A partial update
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ScimPatchRequest {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(rename = "Operations")]
+    pub operations: Vec<ScimPatchOperation>,
+}
+
+/// One change of a partial update
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ScimPatchOperation {
+    /// `add`, `replace` or `remove`, in any case
+    pub op: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub value: Option<serde_json::Value>,
+}
@@ -0,0 +1,531 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use diesel::PgConnection;
+use serde_json::Value;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use super::{
+    schema::{group_id, group_role, ScimGroup, ScimListResponse, ScimName, ScimPatchRequest, ScimUser, ScimUserInput, GROUP_ROLES},
+    token::generate_token,
+    Filter, ScimError,
+};
+use crate::{
+    db::{
+        models::{
+            auth::{Role, User},
+            ScimToken,
+        },
+        repositories::{
+            auth::{RefreshTokenRepository, RefreshTokenRepositoryImpl, UserRepositoryImpl},
+            Repository, ScimRepository, ScimRepositoryImpl,
+        },
+    },
+    domain::{
+        activity::{ActivityFeed, FeedEvent},
+        organization::QuotaService,
+    },
+    error::Result,
+    utils::crypto::hash_token,
+};
+
+/// Resources per page when the client doesn't ask for a count
+pub const DEFAULT_COUNT: i64 = 100;
+
+/// Most resources per page
+pub const MAX_COUNT: i64 = 200;
+
+type ScimResult<T> = std::result::Result<T, ScimError>;
+
+/// The attributes of a user SCIM manages
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Attributes {
+    email: String,
+    first_name: String,
+    last_name: String,
+    active: bool,
+}
+
+impl Attributes {
+    fn of(user: &User) -> Self {
+        Self {
+            email: user.email.clone(),
+            first_name: user.first_name.clone(),
+            last_name: user.last_name.clone(),
+            active: user.deleted_at.is_none(),
+        }
+    }
+
+    fn from_input(input: &ScimUserInput) -> ScimResult<Self> {
+        let email = user_name(&input.user_name)?;
+        let name = input.name.clone().unwrap_or_default();
+        let (first_name, last_name) = match (name.given_name, name.family_name) {
+            (Some(first), Some(last)) => (first, last),
+            (given, family) => {
+                let full = input
+                    .display_name
+                    .clone()
+                    .or(name.formatted)
+                    .unwrap_or_else(|| email.split('@').next().unwrap_or_default().to_string());
+                let (first, last) = match full.trim().split_once(' ') {
+                    Some((first, last)) => (first.to_string(), last.trim().to_string()),
+                    None => (full.trim().to_string(), String::new()),
+                };
+                (given.unwrap_or(first), family.unwrap_or(last))
+            }
+        };
+        Ok(Self {
+            email,
+            first_name,
+            last_name,
+            active: input.active.unwrap_or(true),
+        })
+    }
+
+    /// Applies an `add` or `replace` of the attribute at `path`; attributes
+    /// SCIM doesn't manage here are ignored
+    fn set(&mut self, path: &str, value: &Value) -> ScimResult<()> {
+        match path.to_ascii_lowercase().as_str() {
+            "active" => self.active = boolean(value)?,
+            "username" => self.email = user_name(string(path, value)?)?,
+            "name.givenname" => self.first_name = string(path, value)?.to_string(),
+            "name.familyname" => self.last_name = string(path, value)?.to_string(),
+            "name" => {
+                let name: ScimName = serde_json::from_value(value.clone())
+                    .map_err(|_| ScimError::invalid_value("name must be an object"))?;
+                if let Some(first) = name.given_name {
+                    self.first_name = first;
+                }
+                if let Some(last) = name.family_name {
+                    self.last_name = last;
+                }
+            }
+            _ => debug!(path = %path, "Ignoring SCIM attribute"),
+        }
+        Ok(())
+    }
+}
+
+/// A `userName`, which must be an email address, in canonical form
+fn user_name(value: &str) -> ScimResult<String> {
+    let email = value.trim().to_lowercase();
+    match email.split_once('@') {
+        Some((local, domain)) if !local.is_empty() && domain.contains('.') => Ok(email),
+        _ => Err(ScimError::invalid_value("userName must be an email address")),
+    }
+}
+
+fn string<'a>(path: &str, value: &'a Value) -> ScimResult<&'a str> {
+    value
+        .as_str()
+        .ok_or_else(|| ScimError::invalid_value(format!("{} must be a string", path)))
+}
+
+/// A boolean, which some providers send as `"True"` or `"False"`
+fn boolean(value: &Value) -> ScimResult<bool> {
+    match value {
+        Value::Bool(value) => Ok(*value),
+        Value::String(value) if value.eq_ignore_ascii_case("true") => Ok(true),
+        Value::String(value) if value.eq_ignore_ascii_case("false") => Ok(false),
+        _ => Err(ScimError::invalid_value("active must be a boolean")),
+    }
+}
+
+/// The ids in a list of members, `[{"value": "<id>"}]`
+fn member_ids(value: Option<&Value>) -> ScimResult<Vec<Uuid>> {
+    let invalid = || ScimError::invalid_value("members must be a list of {\"value\": \"<user id>\"}");
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    let members = match value {
+        Value::Array(members) => members.as_slice(),
+        member @ Value::Object(_) => std::slice::from_ref(member),
+        _ => return Err(invalid()),
+    };
+    members
+        .iter()
+        .map(|member| {
+            member
+                .get("value")
+                .and_then(Value::as_str)
+                .and_then(|id| Uuid::parse_str(id).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+/// The member id in a path such as `members[value eq "<id>"]`
+fn member_path_id(path: &str) -> ScimResult<Option<Uuid>> {
+    let Some(filter) = path.strip_prefix("members[").and_then(|rest| rest.strip_suffix(']')) else {
+        return Ok(None);
+    };
+    let filter = Filter::parse(filter)?;
+    if !filter.is_on("value") {
+        return Err(ScimError::invalid_filter("Members can only be selected by value"));
+    }
+    Uuid::parse_str(&filter.value)
+        .map(Some)
+        .map_err(|_| ScimError::invalid_value("Member values are user ids"))
+}
+
+/// Provisioning of an organization's users by its identity provider
+pub struct ScimService;
+
+impl ScimService {
+    /// Issues a provisioning token for an organization, returning it with
+    /// the only copy of its plaintext
+    pub async fn issue_token(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        name: &str,
+        created_by: Uuid,
+    ) -> Result<(ScimToken, String)> {
+        let plaintext = generate_token();
+        let token = ScimRepositoryImpl
+            .create_token(conn, &ScimToken {
+                id: Uuid::new_v4(),
+                org_id,
+                name: name.trim().to_string(),
+                token_hash: hash_token(&plaintext),
+                created_by: Some(created_by),
+                last_used_at: None,
+                created_at: Utc::now(),
+                revoked_at: None,
+            })
+            .await?;
+        info!(token_id = %token.id, org_id = %org_id, "Issued SCIM provisioning token");
+        Ok((token, plaintext))
+    }
+
+    /// The unrevoked token `plaintext`, recording its use
+    pub async fn authenticate(conn: &mut PgConnection, plaintext: &str) -> Result<Option<ScimToken>> {
+        let Some(token) = ScimRepositoryImpl.find_token(conn, &hash_token(plaintext)).await? else {
+            return Ok(None);
+        };
+        ScimRepositoryImpl.touch_token(conn, token.id).await?;
+        Ok(Some(token))
+    }
+
+    /// The unrevoked tokens of an organization, newest first
+    pub async fn list_tokens(conn: &mut PgConnection, org_id: Uuid) -> Result<Vec<ScimToken>> {
+        ScimRepositoryImpl.list_tokens(conn, org_id).await
+    }
+
+    /// Revokes a token, after which its client is refused
+    pub async fn revoke_token(conn: &mut PgConnection, org_id: Uuid, id: Uuid) -> Result<ScimToken> {
+        let token = ScimRepositoryImpl.revoke_token(conn, org_id, id).await?;
+        info!(token_id = %token.id, org_id = %org_id, "Revoked SCIM provisioning token");
+        Ok(token)
+    }
+
+    /// A page of the organization's users, `start_index` being 1-based
+    pub async fn list_users(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        filter: Option<&str>,
+        start_index: i64,
+        count: i64,
+    ) -> ScimResult<ScimListResponse<ScimUser>> {
+        let email = match filter.map(Filter::parse).transpose()? {
+            Some(filter) if filter.is_on("userName") || filter.is_on("emails.value") => Some(filter.value.to_lowercase()),
+            Some(_) => return Err(ScimError::invalid_filter("Users can only be filtered by userName or emails.value")),
+            None => None,
+        };
+        let start_index = start_index.max(1);
+        let (users, total) = ScimRepositoryImpl
+            .list_users(conn, org_id, email.as_deref(), start_index - 1, count.clamp(0, MAX_COUNT))
+            .await?;
+        Ok(ScimListResponse::new(users.iter().map(ScimUser::from).collect(), total, start_index))
+    }
+
+    pub async fn get_user(conn: &mut PgConnection, org_id: Uuid, id: Uuid) -> ScimResult<ScimUser> {
+        Ok(ScimUser::from(&Self::find_user(conn, org_id, id).await?))
+    }
+
+    /// Provisions a user, restoring the organization's removed user with
+    /// the same email if there is one
+    pub async fn create_user(conn: &mut PgConnection, org_id: Uuid, input: &ScimUserInput) -> ScimResult<ScimUser> {
+        let attributes = Attributes::from_input(input)?;
+        if !attributes.active {
+            return Err(ScimError::invalid_value("Users are created active"));
+        }
+
+        let user = match ScimRepositoryImpl.find_user_by_email(conn, &attributes.email).await? {
+            Some(user) if user.org_id == org_id && user.deleted_at.is_some() => Self::apply(conn, user, attributes).await?,
+            Some(_) => return Err(ScimError::uniqueness(format!("A user with userName {} exists", attributes.email))),
+            None => {
+                QuotaService::check_users(conn, org_id, 1).await?;
+                let now = Utc::now();
+                let user = UserRepositoryImpl
+                    .create(conn, &User {
+                        id: Uuid::new_v4(),
+                        first_name: attributes.first_name,
+                        last_name: attributes.last_name,
+                        email: attributes.email,
+                        phone_number: String::new(),
+                        // Unknown to anyone; users sign in through the provider
+                        password: User::hash_password(&Uuid::new_v4().to_string())?,
+                        org_id,
+                        role: Role::Operator,
+                        email_verified: true,
+                        avatar_updated_at: None,
+                        is_active: true,
+                        supervisor_id: None,
+                        anonymized_at: None,
+                        created_at: now,
+                        updated_at: now,
+                        deleted_at: None,
+                    })
+                    .await?;
+                info!(user_id = %user.id, org_id = %org_id, "Provisioned user through SCIM");
+                ActivityFeed::record(conn, org_id, None, FeedEvent::user_joined(&user, "scim")).await;
+                user
+            }
+        };
+        Ok(ScimUser::from(&user))
+    }
+
+    /// Replaces the attributes of a user, deprovisioning them if `active`
+    /// is false
+    pub async fn replace_user(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        id: Uuid,
+        input: &ScimUserInput,
+    ) -> ScimResult<ScimUser> {
+        let user = Self::find_user(conn, org_id, id).await?;
+        let attributes = Attributes::from_input(input)?;
+        Ok(ScimUser::from(&Self::apply(conn, user, attributes).await?))
+    }
+
+    /// Applies a partial update to a user
+    pub async fn patch_user(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        id: Uuid,
+        patch: &ScimPatchRequest,
+    ) -> ScimResult<ScimUser> {
+        let user = Self::find_user(conn, org_id, id).await?;
+        let mut attributes = Attributes::of(&user);
+        for operation in &patch.operations {
+            let op = operation.op.to_ascii_lowercase();
+            match (op.as_str(), operation.path.as_deref(), operation.value.as_ref()) {
+                ("add" | "replace", Some(path), Some(value)) => attributes.set(path, value)?,
+                ("add" | "replace", None, Some(Value::Object(values))) => {
+                    for (path, value) in values {
+                        attributes.set(path, value)?;
+                    }
+                }
+                ("add" | "replace", _, _) => return Err(ScimError::invalid_value(format!("{} needs a value", op))),
+                // Removing required or unmanaged attributes changes nothing
+                ("remove", _, _) => {}
+                _ => return Err(ScimError::invalid_syntax(format!("Unknown patch operation '{}'", operation.op))),
+            }
+        }
+        Ok(ScimUser::from(&Self::apply(conn, user, attributes).await?))
+    }
+
+    /// Deprovisions a user
+    pub async fn delete_user(conn: &mut PgConnection, org_id: Uuid, id: Uuid) -> ScimResult<()> {
+        let user = Self::find_user(conn, org_id, id).await?;
+        if user.deleted_at.is_some() {
+            return Err(ScimError::not_found(format!("User {} not found", id)));
+        }
+        let attributes = Attributes {
+            active: false,
+            ..Attributes::of(&user)
+        };
+        Self::apply(conn, user, attributes).await?;
+        Ok(())
+    }
+
+    /// The groups, optionally without their members
+    pub async fn list_groups(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        filter: Option<&str>,
+        with_members: bool,
+    ) -> ScimResult<ScimListResponse<ScimGroup>> {
+        let roles: Vec<Role> = match filter.map(Filter::parse).transpose()? {
+            Some(filter) if filter.is_on("displayName") => group_role(&filter.value).into_iter().collect(),
+            Some(_) => return Err(ScimError::invalid_filter("Groups can only be filtered by displayName")),
+            None => GROUP_ROLES.to_vec(),
+        };
+        let mut groups = Vec::with_capacity(roles.len());
+        for role in roles {
+            groups.push(Self::group(conn, org_id, role, with_members).await?);
+        }
+        let total = groups.len() as i64;
+        Ok(ScimListResponse::new(groups, total, 1))
+    }
+
+    pub async fn get_group(conn: &mut PgConnection, org_id: Uuid, id: &str, with_members: bool) -> ScimResult<ScimGroup> {
+        let role = group_role(id).ok_or_else(|| ScimError::not_found(format!("Group {} not found", id)))?;
+        Self::group(conn, org_id, role, with_members).await
+    }
+
+    /// Changes the members of a group, which gives them its role; members
+    /// removed from `Manager` become operators again. Returns the users
+    /// whose role changed.
+    pub async fn patch_group(
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        id: &str,
+        patch: &ScimPatchRequest,
+    ) -> ScimResult<Vec<Uuid>> {
+        let role = group_role(id).ok_or_else(|| ScimError::not_found(format!("Group {} not found", id)))?;
+        let mut changed = Vec::new();
+        for operation in &patch.operations {
+            let op = operation.op.to_ascii_lowercase();
+            let path = operation.path.as_deref().map(str::trim);
+            match (op.as_str(), path) {
+                (_, Some(path)) if path.eq_ignore_ascii_case("displayName") => {
+                    Self::keep_display_name(role, operation.value.as_ref())?
+                }
+                ("add", Some("members")) => {
+                    changed.extend(Self::add_members(conn, org_id, role, &member_ids(operation.value.as_ref())?).await?)
+                }
+                ("replace", Some("members")) => {
+                    changed.extend(Self::replace_members(conn, org_id, role, &member_ids(operation.value.as_ref())?).await?)
+                }
+                ("remove", Some("members")) => {
+                    // Without a value, every member is removed
+                    let ids = match operation.value.as_ref() {
+                        Some(value) => member_ids(Some(value))?,
+                        None => Self::members(conn, org_id, role).await?.iter().map(|user| user.id).collect(),
+                    };
+                    changed.extend(Self::remove_members(conn, org_id, role, &ids).await?)
+                }
+                ("remove", Some(path)) if path.starts_with("members[") => {
+                    let id = member_path_id(path)?.into_iter().collect::<Vec<_>>();
+                    changed.extend(Self::remove_members(conn, org_id, role, &id).await?)
+                }
+                ("add" | "replace", None) => {
+                    let Some(Value::Object(values)) = operation.value.as_ref() else {
+                        return Err(ScimError::invalid_value(format!("{} needs a value", op)));
+                    };
+                    for (path, value) in values {
+                        if path.eq_ignore_ascii_case("displayName") {
+                            Self::keep_display_name(role, Some(value))?;
+                        } else if path.eq_ignore_ascii_case("members") {
+                            let ids = member_ids(Some(value))?;
+                            changed.extend(match op.as_str() {
+                                "add" => Self::add_members(conn, org_id, role, &ids).await?,
+                                _ => Self::replace_members(conn, org_id, role, &ids).await?,
+                            });
+                        }
+                    }
+                }
+                ("add" | "replace" | "remove", Some(path)) => {
+                    return Err(ScimError::new(
+                        actix_web::http::StatusCode::BAD_REQUEST,
+                        Some("invalidPath"),
+                        format!("Groups have no attribute '{}' to change", path),
+                    ))
+                }
+                _ => return Err(ScimError::invalid_syntax(format!("Unknown patch operation '{}'", operation.op))),
+            }
+        }
+        Ok(changed)
+    }
+
+    async fn find_user(conn: &mut PgConnection, org_id: Uuid, id: Uuid) -> ScimResult<User> {
+        ScimRepositoryImpl
+            .find_user(conn, org_id, id)
+            .await?
+            .ok_or_else(|| ScimError::not_found(format!("User {} not found", id)))
+    }
+
+    /// Saves changed attributes of a user, deprovisioning them when they
+    /// become inactive and restoring them when they become active again
+    async fn apply(conn: &mut PgConnection, user: User, attributes: Attributes) -> ScimResult<User> {
+        if Attributes::of(&user) == attributes {
+            return Ok(user);
+        }
+        if user.role == Role::Admin {
+            return Err(ScimError::forbidden("Admins are managed in the application"));
+        }
+
+        let mut user = user;
+        if user.deleted_at.is_some() && attributes.active {
+            QuotaService::check_users(conn, user.org_id, 1).await?;
+            user = ScimRepositoryImpl.restore_user(conn, user.org_id, user.id).await?;
+            info!(user_id = %user.id, org_id = %user.org_id, "Restored user through SCIM");
+        }
+        if attributes.email != user.email {
+            if ScimRepositoryImpl.find_user_by_email(conn, &attributes.email).await?.is_some() {
+                return Err(ScimError::uniqueness(format!("A user with userName {} exists", attributes.email)));
+            }
+            user.email = attributes.email;
+            // Vouched for by the identity provider
+            user.email_verified = true;
+        }
+        user.first_name = attributes.first_name;
+        user.last_name = attributes.last_name;
+        user.updated_at = Utc::now();
+        let user = UserRepositoryImpl.update(conn, user.id, &user).await?;
+
+        if attributes.active || user.deleted_at.is_some() {
+            return Ok(user);
+        }
+        let user = UserRepositoryImpl.soft_delete(conn, user.id).await?;
+        RefreshTokenRepositoryImpl.revoke_all_for_user(conn, user.id).await?;
+        info!(user_id = %user.id, org_id = %user.org_id, "Deprovisioned user through SCIM");
+        Ok(user)
+    }
+
+    async fn group(conn: &mut PgConnection, org_id: Uuid, role: Role, with_members: bool) -> ScimResult<ScimGroup> {
+        let members = match with_members {
+            true => Some(Self::members(conn, org_id, role).await?),
+            false => None,
+        };
+        Ok(ScimGroup::new(role, members.as_deref()))
+    }
+
+    async fn members(conn: &mut PgConnection, org_id: Uuid, role: Role) -> ScimResult<Vec<User>> {
+        Ok(ScimRepositoryImpl.list_users_with_role(conn, org_id, role).await?)
+    }
+
+    fn keep_display_name(role: Role, value: Option<&Value>) -> ScimResult<()> {
+        match value.and_then(Value::as_str) {
+            Some(name) if name == group_id(role) => Ok(()),
+            _ => Err(ScimError::mutability("Group names are fixed")),
+        }
+    }
+
+    async fn add_members(conn: &mut PgConnection, org_id: Uuid, role: Role, ids: &[Uuid]) -> ScimResult<Vec<Uuid>> {
+        let changed = ScimRepositoryImpl.set_role(conn, org_id, ids, role).await?;
+        info!(org_id = %org_id, role = ?role, changed = changed.len(), "Added group members through SCIM");
+        Ok(changed)
+    }
+
+    async fn remove_members(conn: &mut PgConnection, org_id: Uuid, role: Role, ids: &[Uuid]) -> ScimResult<Vec<Uuid>> {
+        if role == Role::Operator {
+            // Operator is the role users fall back to
+            return Ok(Vec::new());
+        }
+        let ids: HashSet<&Uuid> = ids.iter().collect();
+        let members: Vec<Uuid> = Self::members(conn, org_id, role)
+            .await?
+            .into_iter()
+            .map(|user| user.id)
+            .filter(|id| ids.contains(id))
+            .collect();
+        let changed = ScimRepositoryImpl.set_role(conn, org_id, &members, Role::Operator).await?;
+        info!(org_id = %org_id, role = ?role, changed = changed.len(), "Removed group members through SCIM");
+        Ok(changed)
+    }
+
+    async fn replace_members(conn: &mut PgConnection, org_id: Uuid, role: Role, ids: &[Uuid]) -> ScimResult<Vec<Uuid>> {
+        let current: Vec<Uuid> = Self::members(conn, org_id, role)
+            .await?
+            .into_iter()
+            .map(|user| user.id)
+            .filter(|id| !ids.contains(id))
+            .collect();
+        let mut changed = Self::remove_members(conn, org_id, role, &current).await?;
+        changed.extend(Self::add_members(conn, org_id, role, ids).await?);
+        Ok(changed)
+    }
+}
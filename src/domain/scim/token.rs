@@ -0,0 +1,11 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// Prefix of provisioning tokens, so a leaked one is recognizable
+const TOKEN_PREFIX: &str = "scim_";
+
+/// A new provisioning token
+pub fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    format!("{}{}", TOKEN_PREFIX, URL_SAFE_NO_PAD.encode(bytes))
+}
+
@@ -0,0 +1,15 @@
+//! Quick search
+//!
+//! Finds records of several kinds by name or number for a command palette.
+//! Each kind is looked up with a substring match, its matches are ranked
+//! by how well the query matches, and the best of each kind are merged
+//! into one list.
+
+mod rank;
+mod service;
+
+pub use rank::{
+    best_score, like_pattern, rank, score, SearchHit, SearchKind, DEFAULT_PER_TYPE, MAX_PER_TYPE, MAX_QUERY_CHARS,
+    MAX_RESULTS, MIN_QUERY_CHARS,
+};
+pub use service::QuickSearchService;
@@ -0,0 +1,192 @@
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Shortest query searched for
+pub const MIN_QUERY_CHARS: usize = 2;
+
+/// Longest query searched for
+pub const MAX_QUERY_CHARS: usize = 100;
+
+/// Hits returned per type unless asked otherwise
+pub const DEFAULT_PER_TYPE: usize = 5;
+
+/// Most hits returned per type
+pub const MAX_PER_TYPE: usize = 10;
+
+/// Most hits returned altogether
+pub const MAX_RESULTS: usize = 20;
+
+/// Kind of record a hit is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, ToSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+    User,
+    Customer,
+    SupplyContract,
+    Tender,
+    Document,
+    Block,
+    Stand,
+}
+
+impl SearchKind {
+    /// Kinds in the order ties are broken in
+    pub const ALL: [SearchKind; 7] = [
+        SearchKind::Block,
+        SearchKind::Stand,
+        SearchKind::SupplyContract,
+        SearchKind::Tender,
+        SearchKind::Customer,
+        SearchKind::User,
+        SearchKind::Document,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchKind::User => "user",
+            SearchKind::Customer => "customer",
+            SearchKind::SupplyContract => "supply_contract",
+            SearchKind::Tender => "tender",
+            SearchKind::Document => "document",
+            SearchKind::Block => "block",
+            SearchKind::Stand => "stand",
+        }
+    }
+
+    /// The kind named `value`, if any
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    /// Whether only managers may find records of the kind, as only they
+    /// can open them
+    pub fn manager_only(&self) -> bool {
+        matches!(self, SearchKind::Customer | SearchKind::SupplyContract | SearchKind::Tender | SearchKind::Block | SearchKind::Stand)
+    }
+}
+
+/// A record matching a quick search
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    pub id: Uuid,
+    /// What the record is shown as
+    pub title: String,
+    /// Secondary line, e.g. a user's email
+    pub subtitle: Option<String>,
+    /// Record the hit is opened under, e.g. a stand's block
+    pub parent_id: Option<Uuid>,
+    /// Relevance; higher ranks first
+    pub score: u32,
+}
+
+/// How well `query` matches `text`, ignoring case: whole text, then the
+/// start of the text, then the start of a word, then anywhere
+pub fn score(query: &str, text: &str) -> Option<u32> {
+    let query = query.to_lowercase();
+    let text = text.to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    if text == query {
+        Some(100)
+    } else if text.starts_with(&query) {
+        Some(75)
+    } else if text
+        .match_indices(&query)
+        .any(|(at, _)| !text[..at].ends_with(|c: char| c.is_alphanumeric()))
+    {
+        Some(50)
+    } else if text.contains(&query) {
+        Some(25)
+    } else {
+        None
+    }
+}
+
+/// The best score of `query` against any of `texts`; the first text is
+/// what the record is known by and wins ties with a point
+pub fn best_score<'a>(query: &str, texts: impl IntoIterator<Item = &'a str>) -> Option<u32> {
+    texts
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, text)| score(query, text).map(|score| if i == 0 { score + 1 } else { score }))
+        .max()
+}
+
+/// Orders hits best first, breaking ties by kind then title, and keeps at
+/// most `limit`
+pub fn rank(mut hits: Vec<SearchHit>, limit: usize) -> Vec<SearchHit> {
+    let position = |kind: SearchKind| SearchKind::ALL.iter().position(|k| *k == kind).unwrap_or(usize::MAX);
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| position(a.kind).cmp(&position(b.kind)))
+            .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+    });
+    hits.truncate(limit);
+    hits
+}
+
+/// `query` as a `LIKE` pattern matching it anywhere, with wildcards in it
+/// escaped
+pub fn like_pattern(query: &str) -> String {
+    let escaped = query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(kind: SearchKind, title: &str, score: u32) -> SearchHit {
+        SearchHit {
+            kind,
+            id: Uuid::nil(),
+            title: title.to_string(),
+            subtitle: None,
+            parent_id: None,
+            score,
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        assert_eq!(score("sc-1042", "SC-1042"), Some(100));
+        assert_eq!(score("sc-10", "SC-1042"), Some(75));
+        assert_eq!(score("mill", "North Mill Ltd"), Some(50));
+        assert_eq!(score("ill", "North Mill Ltd"), Some(25));
+        assert_eq!(score("pulp", "North Mill Ltd"), None);
+    }
+
+    #[test]
+    fn test_best_score_prefers_the_first_text() {
+        assert_eq!(best_score("anna", ["Anna Virtanen", "anna@example.com"]), Some(76));
+        assert_eq!(best_score("example", ["Anna Virtanen", "anna@example.com"]), Some(50));
+        assert_eq!(best_score("pulp", ["Anna Virtanen"]), None);
+    }
+
+    #[test]
+    fn test_rank() {
+        let ranked = rank(
+            vec![
+                hit(SearchKind::User, "b", 50),
+                hit(SearchKind::Customer, "c", 50),
+                hit(SearchKind::Document, "a", 100),
+                hit(SearchKind::User, "a", 50),
+            ],
+            3,
+        );
+        let order = ranked.iter().map(|hit| (hit.kind, hit.title.as_str())).collect::<Vec<_>>();
+        assert_eq!(order, vec![(SearchKind::Document, "a"), (SearchKind::Customer, "c"), (SearchKind::User, "a")]);
+    }
+
+    #[test]
+    fn test_like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("50%_a\\b"), "%50\\%\\_a\\\\b%");
+    }
+}
@@ -0,0 +1,69 @@
+use diesel::PgConnection;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::rank::{
+    best_score, like_pattern, rank, SearchHit, SearchKind, MAX_PER_TYPE, MAX_QUERY_CHARS, MAX_RESULTS, MIN_QUERY_CHARS,
+};
+use crate::{
+    db::repositories::SearchRepository,
+    error::{ApiError, Result},
+};
+
+/// Records fetched per type for ranking, for each hit returned
+const CANDIDATES_PER_HIT: usize = 4;
+
+/// Service for the quick search across record types
+pub struct QuickSearchService<R: SearchRepository + Send + Sync> {
+    repository: R,
+}
+
+impl<R: SearchRepository + Send + Sync> QuickSearchService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// Records of `kinds` matching `query`, best first, with at most
+    /// `per_type` of each kind
+    pub async fn search(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        query: &str,
+        kinds: &[SearchKind],
+        per_type: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let query = query.trim();
+        let length = query.chars().count();
+        if !(MIN_QUERY_CHARS..=MAX_QUERY_CHARS).contains(&length) {
+            return Err(ApiError::validation(
+                format!("Search for {} to {} characters", MIN_QUERY_CHARS, MAX_QUERY_CHARS),
+                Some(json!({ "min": MIN_QUERY_CHARS, "max": MAX_QUERY_CHARS })),
+            ));
+        }
+        if !(1..=MAX_PER_TYPE).contains(&per_type) {
+            return Err(ApiError::validation(
+                format!("Up to {} results per type can be returned", MAX_PER_TYPE),
+                None,
+            ));
+        }
+
+        let pattern = like_pattern(query);
+        let mut hits = Vec::new();
+        for kind in SearchKind::ALL.into_iter().filter(|kind| kinds.contains(kind)) {
+            let rows = self
+                .repository
+                .search(conn, org_id, kind, &pattern, (per_type * CANDIDATES_PER_HIT) as i64)
+                .await?;
+            let matches = rows
+                .into_iter()
+                .filter_map(|(id, title, subtitle, parent_id)| {
+                    let score = best_score(query, [title.as_str()].into_iter().chain(subtitle.as_deref()))?;
+                    Some(SearchHit { kind, id, title, subtitle, parent_id, score })
+                })
+                .collect();
+            hits.extend(rank(matches, per_type));
+        }
+        Ok(rank(hits, MAX_RESULTS))
+    }
+}
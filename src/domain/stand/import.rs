@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use diesel::{prelude::*, result::Error as DieselError, PgConnection, QueryResult};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{service::stand, validation::StandValidator};
+use crate::{
+    api::resources::stand::dto::SaveStandInput,
+    db::{models::SpeciesShare, repositories::upsert_stands, schema::harvest_blocks},
+    domain::import::{ImportField, ImportFieldKind, ImportRecord, ImportTarget},
+};
+
+const FIELDS: &[ImportField] = &[
+    ImportField { name: "license", kind: ImportFieldKind::Text, required: true, aliases: &["licence", "permit"] },
+    ImportField { name: "block_number", kind: ImportFieldKind::Text, required: true, aliases: &["block", "block_no"] },
+    ImportField { name: "stand_number", kind: ImportFieldKind::Text, required: true, aliases: &["stand", "stand_no"] },
+    ImportField { name: "area_ha", kind: ImportFieldKind::Number, required: true, aliases: &["area", "ha"] },
+    ImportField { name: "species", kind: ImportFieldKind::Text, required: true, aliases: &["species_mix"] },
+    ImportField { name: "age_class", kind: ImportFieldKind::Integer, required: true, aliases: &["age"] },
+    ImportField { name: "site_index", kind: ImportFieldKind::Number, required: true, aliases: &["si"] },
+    ImportField {
+        name: "net_merchantable_volume_m3",
+        kind: ImportFieldKind::Number,
+        required: true,
+        aliases: &["volume", "volume_m3"],
+    },
+    ImportField { name: "inventoried_on", kind: ImportFieldKind::Date, required: false, aliases: &["cruised_on"] },
+];
+
+/// Imports stands into the organization's blocks, found by license and
+/// block number
+///
+/// The species mix is written `spruce 70/pine 30`, a lone species taking
+/// the whole stand. As in a bulk upsert, a stand number its block already
+/// has replaces that stand. A row naming a block the organization doesn't
+/// have fails the import.
+pub struct StandImportTarget;
+
+fn text(record: &ImportRecord, field: &str) -> String {
+    record.get(field).and_then(Value::as_str).unwrap_or_default().trim().to_string()
+}
+
+fn number(record: &ImportRecord, field: &str) -> f64 {
+    record.get(field).and_then(Value::as_f64).unwrap_or_default()
+}
+
+/// Reads a species mix such as `spruce 70/pine 30`
+fn species(mix: &str) -> Result<Vec<SpeciesShare>, String> {
+    let shares: Vec<&str> = mix.split('/').map(str::trim).filter(|share| !share.is_empty()).collect();
+    let percent = |share: &str| {
+        let (species, percent) = share.rsplit_once(char::is_whitespace)?;
+        let percent = percent.trim_end_matches('%').replace(',', ".").parse().ok()?;
+        Some(SpeciesShare { species: species.trim().to_string(), percent })
+    };
+    match shares[..] {
+        [species] => Ok(vec![percent(species).unwrap_or_else(|| SpeciesShare { species: species.to_string(), percent: 100.0 })]),
+        _ => shares
+            .into_iter()
+            .map(|share| percent(share).ok_or_else(|| format!("species: '{}' has no percentage", share)))
+            .collect(),
+    }
+}
+
+/// Reads a record as stand input, or the problem with it
+fn input(record: &ImportRecord) -> Result<SaveStandInput, String> {
+    let input = SaveStandInput {
+        stand_number: text(record, "stand_number"),
+        area_ha: number(record, "area_ha"),
+        species: species(&text(record, "species"))?,
+        age_class: record
+            .get("age_class")
+            .and_then(Value::as_i64)
+            .and_then(|age_class| i16::try_from(age_class).ok())
+            .unwrap_or_default(),
+        site_index: number(record, "site_index"),
+        net_merchantable_volume_m3: number(record, "net_merchantable_volume_m3"),
+        inventoried_on: record
+            .get("inventoried_on")
+            .and_then(Value::as_str)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()),
+        windthrow_conditions: None,
+    };
+    StandValidator::validate_save(&input).map_err(|e| e.message)?;
+    Ok(input)
+}
+
+impl ImportTarget for StandImportTarget {
+    fn name(&self) -> &'static str {
+        "stands"
+    }
+
+    fn fields(&self) -> &'static [ImportField] {
+        FIELDS
+    }
+
+    fn check(&self, record: &ImportRecord) -> Vec<String> {
+        input(record).err().into_iter().collect()
+    }
+
+    fn insert(&self, conn: &mut PgConnection, org_id: Uuid, records: &[ImportRecord]) -> QueryResult<usize> {
+        // By block and stand number, a later row for a stand replacing an earlier one
+        let mut stands = BTreeMap::new();
+        // Records reaching here passed `check`
+        for record in records {
+            let Ok(input) = input(record) else { continue };
+            let (license, block_number) = (text(record, "license"), text(record, "block_number"));
+            let block_id = harvest_blocks::table
+                .filter(harvest_blocks::org_id.eq(org_id))
+                .filter(harvest_blocks::license.eq(&license))
+                .filter(harvest_blocks::block_number.eq(&block_number))
+                .select(harvest_blocks::id)
+                .first::<Uuid>(conn)
+                .optional()?
+                .ok_or_else(|| DieselError::QueryBuilderError(format!("no block {} {}", license, block_number).into()))?;
+            let stand = stand(org_id, block_id, input);
+            stands.insert((block_id, stand.stand_number.clone()), stand);
+        }
+        if stands.is_empty() {
+            return Ok(0);
+        }
+        Ok(upsert_stands(conn, &stands.into_values().collect::<Vec<_>>())?.len())
+    }
+}
@@ -0,0 +1,15 @@
+//! Stand inventory
+//!
+//! Stands are recorded under a harvest block one at a time or in bulk from
+//! an inventory system, which sends a block's stands by stand number:
+//! numbers the block doesn't have yet are created and the others replaced.
+//! A block's stand volumes roll up into its inventory volume, split by
+//! species according to each stand's species mix.
+
+mod import;
+mod service;
+mod validation;
+
+pub use import::StandImportTarget;
+pub use service::{SpeciesVolume, StandService, StandVolumeSummary};
+pub use validation::{StandValidator, MAX_AGE_CLASS, MAX_SITE_INDEX};
@@ -0,0 +1,190 @@
+use chrono::Utc;
+use diesel::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+
+use super::validation::StandValidator;
+use crate::{
+    api::resources::stand::dto::{BulkUpsertStandsInput, SaveStandInput},
+    db::{
+        models::{SpeciesShare, Stand, TagSubject},
+        repositories::{HarvestBlockRepository, HarvestBlockRepositoryImpl, StandRepository, TagRepositoryImpl},
+    },
+    domain::{
+        tag::{TagFilter, TagService},
+        windthrow,
+    },
+    error::Result,
+};
+
+/// Net merchantable volume of one species across a block's stands
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeciesVolume {
+    pub species: String,
+    pub volume_m3: f64,
+    /// Percentage of the block's net merchantable volume
+    pub percent: f64,
+}
+
+/// A block's stand volumes rolled up
+#[derive(Debug, Clone)]
+pub struct StandVolumeSummary {
+    pub block_id: Uuid,
+    pub stand_count: i64,
+    pub area_ha: f64,
+    pub net_merchantable_volume_m3: f64,
+    pub volume_per_ha: f64,
+    pub planned_volume_m3: f64,
+    /// Largest volume first
+    pub species: Vec<SpeciesVolume>,
+}
+
+/// Service for the stands of harvest blocks
+pub struct StandService<R: StandRepository + Send + Sync> {
+    repository: R,
+}
+
+/// Builds a stand from its input, scoring its windthrow risk when the
+/// conditions are given
+pub(super) fn stand(org_id: Uuid, block_id: Uuid, input: SaveStandInput) -> Stand {
+    let now = Utc::now();
+    let species: Vec<SpeciesShare> = input
+        .species
+        .into_iter()
+        .map(|share| SpeciesShare {
+            species: share.species.trim().to_string(),
+            percent: share.percent,
+        })
+        .collect();
+    let risk = input.windthrow_conditions.as_ref().map(windthrow::score);
+    Stand {
+        id: Uuid::new_v4(),
+        org_id,
+        block_id,
+        stand_number: input.stand_number.trim().to_string(),
+        area_ha: input.area_ha,
+        species: serde_json::to_value(species).unwrap_or_default(),
+        age_class: input.age_class,
+        site_index: input.site_index,
+        net_merchantable_volume_m3: input.net_merchantable_volume_m3,
+        inventoried_on: input.inventoried_on,
+        windthrow_conditions: input
+            .windthrow_conditions
+            .and_then(|conditions| serde_json::to_value(conditions).ok()),
+        windthrow_score: risk.map(|risk| risk.score),
+        windthrow_class: risk
+            .and_then(|risk| serde_json::to_value(risk.class).ok())
+            .and_then(|class| class.as_str().map(str::to_string)),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+impl<R: StandRepository + Send + Sync> StandService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// Creates a stand in one of the organization's blocks
+    pub async fn create(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid, input: SaveStandInput) -> Result<Stand> {
+        StandValidator::validate_save(&input)?;
+        HarvestBlockRepositoryImpl.find(conn, org_id, block_id).await?;
+        let stand = self.repository.create(conn, &stand(org_id, block_id, input)).await?;
+        info!(stand_id = %stand.id, block_id = %block_id, "Created stand {}", stand.stand_number);
+        Ok(stand)
+    }
+
+    /// Lists a block's stands by stand number, only those with the tags
+    /// of `tags` when given
+    pub async fn list(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid, tags: Option<&TagFilter>) -> Result<Vec<Stand>> {
+        HarvestBlockRepositoryImpl.find(conn, org_id, block_id).await?;
+        let tagged = TagService::new(TagRepositoryImpl).filter(conn, org_id, TagSubject::Stand, tags).await?;
+        let mut stands = self.repository.list(conn, org_id, block_id).await?;
+        if let Some(tagged) = tagged {
+            stands.retain(|stand| tagged.contains(&stand.id));
+        }
+        Ok(stands)
+    }
+
+    /// Gets a stand
+    pub async fn get(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid, stand_id: Uuid) -> Result<Stand> {
+        self.repository.find(conn, org_id, block_id, stand_id).await
+    }
+
+    /// Replaces a stand's details
+    pub async fn update(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        block_id: Uuid,
+        stand_id: Uuid,
+        input: SaveStandInput,
+    ) -> Result<Stand> {
+        StandValidator::validate_save(&input)?;
+        let existing = self.repository.find(conn, org_id, block_id, stand_id).await?;
+        let stand = Stand {
+            id: existing.id,
+            created_at: existing.created_at,
+            ..stand(org_id, block_id, input)
+        };
+        self.repository.update(conn, org_id, &stand).await
+    }
+
+    /// Creates or replaces a block's stands by stand number, returning the
+    /// saved stands with how many were new
+    pub async fn upsert(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        block_id: Uuid,
+        input: BulkUpsertStandsInput,
+    ) -> Result<(Vec<Stand>, i64)> {
+        StandValidator::validate_bulk(&input)?;
+        HarvestBlockRepositoryImpl.find(conn, org_id, block_id).await?;
+        let stands: Vec<Stand> = input.stands.into_iter().map(|input| stand(org_id, block_id, input)).collect();
+        let (stands, created) = self.repository.upsert(conn, org_id, block_id, &stands).await?;
+        info!(block_id = %block_id, org_id = %org_id, stands = stands.len(), created, "Saved stands from inventory");
+        Ok((stands, created as i64))
+    }
+
+    /// Deletes a stand
+    pub async fn delete(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid, stand_id: Uuid) -> Result<()> {
+        self.repository.delete(conn, org_id, block_id, stand_id).await?;
+        info!(stand_id = %stand_id, block_id = %block_id, "Deleted stand");
+        Ok(())
+    }
+
+    /// Rolls a block's stand volumes up, splitting each stand's volume
+    /// between its species by their percentage
+    pub async fn summary(&self, conn: &mut PgConnection, org_id: Uuid, block_id: Uuid) -> Result<StandVolumeSummary> {
+        let block = HarvestBlockRepositoryImpl.find(conn, org_id, block_id).await?;
+        let stands = self.repository.list(conn, org_id, block_id).await?;
+
+        let area_ha: f64 = stands.iter().map(|stand| stand.area_ha).sum();
+        let volume_m3: f64 = stands.iter().map(|stand| stand.net_merchantable_volume_m3).sum();
+        let mut species: Vec<SpeciesVolume> = Vec::new();
+        for stand in &stands {
+            for share in stand.species() {
+                let volume = stand.net_merchantable_volume_m3 * share.percent / 100.0;
+                match species.iter_mut().find(|total| total.species.eq_ignore_ascii_case(&share.species)) {
+                    Some(total) => total.volume_m3 += volume,
+                    None => species.push(SpeciesVolume { species: share.species, volume_m3: volume, percent: 0.0 }),
+                }
+            }
+        }
+        for total in &mut species {
+            total.percent = if volume_m3 > 0.0 { total.volume_m3 / volume_m3 * 100.0 } else { 0.0 };
+        }
+        species.sort_by(|a, b| b.volume_m3.total_cmp(&a.volume_m3).then_with(|| a.species.cmp(&b.species)));
+
+        Ok(StandVolumeSummary {
+            block_id,
+            stand_count: stands.len() as i64,
+            area_ha,
+            net_merchantable_volume_m3: volume_m3,
+            volume_per_ha: if area_ha > 0.0 { volume_m3 / area_ha } else { 0.0 },
+            planned_volume_m3: block.planned_volume_m3,
+            species,
+        })
+    }
+}
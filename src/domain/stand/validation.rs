@@ -0,0 +1,194 @@
+use serde_json::json;
+use std::collections::HashSet;
+
+use crate::{
+    api::resources::stand::dto::{BulkUpsertStandsInput, SaveStandInput},
+    error::{ApiError, ErrorContext, Result},
+};
+use validator::Validate as ValidatorValidate;
+
+/// Oldest age class, stands over 250 years
+pub const MAX_AGE_CLASS: i16 = 9;
+
+/// Highest site index accepted, in metres
+pub const MAX_SITE_INDEX: f64 = 60.0;
+
+/// Tallest mean stand height accepted, in metres
+pub const MAX_MEAN_HEIGHT_M: f64 = 100.0;
+
+/// How far a species mix may add up from 100, for percentages inventory
+/// systems have rounded
+const PERCENT_TOLERANCE: f64 = 0.5;
+
+fn invalid(field: String, code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": field,
+            "code": code,
+        })),
+    )
+}
+
+fn invalid_input(e: validator::ValidationErrors) -> ApiError {
+    ApiError::validation_with_context(
+        "Invalid input",
+        ErrorContext::new()
+            .with_message_key("INVALID_INPUT")
+            .with_details(json!(e))
+    )
+}
+
+pub struct StandValidator;
+
+impl StandValidator {
+    /// Validates input for creating or replacing a stand
+    pub fn validate_save(input: &SaveStandInput) -> Result<()> {
+        Self::validate_stand(input, "")
+    }
+
+    /// Validates the stands of a bulk upsert, naming the failing stand's
+    /// position in the fields reported
+    pub fn validate_bulk(input: &BulkUpsertStandsInput) -> Result<()> {
+        ValidatorValidate::validate(input).map_err(invalid_input)?;
+        let mut numbers = HashSet::new();
+        for (index, stand) in input.stands.iter().enumerate() {
+            let prefix = format!("stands[{}].", index);
+            Self::validate_stand(stand, &prefix)?;
+            if !numbers.insert(stand.stand_number.trim()) {
+                return Err(invalid(
+                    format!("{}stand_number", prefix),
+                    "DUPLICATE",
+                    format!("Stand {} is listed more than once", stand.stand_number.trim()),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_stand(input: &SaveStandInput, prefix: &str) -> Result<()> {
+        ValidatorValidate::validate(input).map_err(invalid_input)?;
+        let field = |name: &str| format!("{}{}", prefix, name);
+        if input.stand_number.trim().is_empty() {
+            return Err(invalid(field("stand_number"), "REQUIRED", "The stand number must not be blank"));
+        }
+        if !(input.area_ha.is_finite() && input.area_ha > 0.0) {
+            return Err(invalid(field("area_ha"), "OUT_OF_RANGE", "The stand area must be positive"));
+        }
+        if !(1..=MAX_AGE_CLASS).contains(&input.age_class) {
+            return Err(invalid(
+                field("age_class"),
+                "OUT_OF_RANGE",
+                format!("The age class must be from 1 to {}", MAX_AGE_CLASS),
+            ));
+        }
+        if !(input.site_index.is_finite() && input.site_index > 0.0 && input.site_index <= MAX_SITE_INDEX) {
+            return Err(invalid(
+                field("site_index"),
+                "OUT_OF_RANGE",
+                format!("The site index must be above 0 and at most {} metres", MAX_SITE_INDEX),
+            ));
+        }
+        if !(input.net_merchantable_volume_m3.is_finite() && input.net_merchantable_volume_m3 >= 0.0) {
+            return Err(invalid(
+                field("net_merchantable_volume_m3"),
+                "OUT_OF_RANGE",
+                "The net merchantable volume must not be negative",
+            ));
+        }
+
+        if let Some(conditions) = &input.windthrow_conditions {
+            if !(conditions.mean_height_m.is_finite() && (0.0..=MAX_MEAN_HEIGHT_M).contains(&conditions.mean_height_m)) {
+                return Err(invalid(
+                    field("windthrow_conditions.mean_height_m"),
+                    "OUT_OF_RANGE",
+                    format!("The mean height must be from 0 to {} metres", MAX_MEAN_HEIGHT_M),
+                ));
+            }
+            if !(conditions.stems_per_ha.is_finite() && conditions.stems_per_ha >= 0.0) {
+                return Err(invalid(
+                    field("windthrow_conditions.stems_per_ha"),
+                    "OUT_OF_RANGE",
+                    "The stocking density must not be negative",
+                ));
+            }
+        }
+
+        let mut species = HashSet::new();
+        for share in &input.species {
+            let name = share.species.trim();
+            if name.is_empty() || name.len() > 50 {
+                return Err(invalid(field("species"), "INVALID_SPECIES", "Species must be named in 1 to 50 characters"));
+            }
+            if !species.insert(name.to_lowercase()) {
+                return Err(invalid(field("species"), "DUPLICATE", format!("Species {} is listed more than once", name)));
+            }
+            if !(share.percent.is_finite() && share.percent > 0.0 && share.percent <= 100.0) {
+                return Err(invalid(field("species"), "OUT_OF_RANGE", "Each species percentage must be above 0 and at most 100"));
+            }
+        }
+        let total: f64 = input.species.iter().map(|share| share.percent).sum();
+        if (total - 100.0).abs() > PERCENT_TOLERANCE {
+            return Err(invalid(
+                field("species"),
+                "NOT_100_PERCENT",
+                format!("The species percentages add up to {} rather than 100", total),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::SpeciesShare;
+
+    fn stand(number: &str) -> SaveStandInput {
+        SaveStandInput {
+            stand_number: number.to_string(),
+            area_ha: 4.2,
+            species: vec![
+                SpeciesShare { species: "spruce".to_string(), percent: 66.7 },
+                SpeciesShare { species: "birch".to_string(), percent: 33.3 },
+            ],
+            age_class: 5,
+            site_index: 24.0,
+            net_merchantable_volume_m3: 1100.0,
+            inventoried_on: None,
+            windthrow_conditions: None,
+        }
+    }
+
+    fn field(error: ApiError) -> serde_json::Value {
+        error.context.details.unwrap()["field"].clone()
+    }
+
+    #[test]
+    fn test_species_mix_adds_up_to_100() {
+        assert!(StandValidator::validate_save(&stand("1")).is_ok());
+
+        let mut short = stand("1");
+        short.species[1].percent = 20.0;
+        let mut repeated = stand("1");
+        repeated.species[1].species = "Spruce".to_string();
+        let mut old = stand("1");
+        old.age_class = 10;
+        for input in [short, repeated, old] {
+            assert!(StandValidator::validate_save(&input).is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_bulk_stands_are_named_by_position() {
+        let mut invalid = stand("2");
+        invalid.site_index = -1.0;
+        let input = BulkUpsertStandsInput { stands: vec![stand("1"), invalid] };
+        let error = StandValidator::validate_bulk(&input).unwrap_err();
+        assert_eq!(field(error), "stands[1].site_index");
+
+        let input = BulkUpsertStandsInput { stands: vec![stand("1"), stand(" 1")] };
+        let error = StandValidator::validate_bulk(&input).unwrap_err();
+        assert_eq!(field(error), "stands[1].stand_number");
+    }
+}
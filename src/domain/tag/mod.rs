@@ -0,0 +1,12 @@
+//! Tags
+//!
+//! Organizations define their own tags, each with a name and a color, and
+//! attach them to stands, blocks, equipment and work orders. List
+//! endpoints of those take a [`TagFilter`] and keep what is tagged with
+//! any or all of its tags.
+
+mod rules;
+mod service;
+
+pub use rules::{normalize_color, TagFilter, MAX_FILTER_TAGS};
+pub use service::TagService;
@@ -0,0 +1,102 @@
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+
+/// Most tags a list can be filtered by at once
+pub const MAX_FILTER_TAGS: usize = 20;
+
+/// A hex color as `#rrggbb`, accepting the `#rgb` shorthand and any case
+pub fn normalize_color(value: &str) -> Option<String> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        6 => Some(format!("#{}", hex.to_ascii_lowercase())),
+        3 => Some(hex.chars().fold(String::from("#"), |mut color, c| {
+            color.push(c.to_ascii_lowercase());
+            color.push(c.to_ascii_lowercase());
+            color
+        })),
+        _ => None,
+    }
+}
+
+/// Tag filter of a list endpoint
+///
+/// Lists take `tags`, a comma separated list of tag ids, and `tag_match`,
+/// `any` (the default) to keep what has any of the tags or `all` to keep
+/// what has every one of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagFilter {
+    pub tag_ids: Vec<Uuid>,
+    pub match_all: bool,
+}
+
+impl TagFilter {
+    /// The filter given by the `tags` and `tag_match` query parameters,
+    /// `None` when no tags are given
+    pub fn parse(tags: Option<&str>, tag_match: Option<&str>) -> Result<Option<Self>> {
+        let match_all = match tag_match.map(str::trim) {
+            None | Some("") | Some("any") => false,
+            Some("all") => true,
+            Some(other) => {
+                return Err(ApiError::validation(
+                    format!("Unknown tag match {}", other),
+                    Some(json!({ "available": ["any", "all"] })),
+                ))
+            }
+        };
+
+        let mut tag_ids = Vec::new();
+        for value in tags.unwrap_or_default().split(',').map(str::trim).filter(|value| !value.is_empty()) {
+            let tag_id = Uuid::parse_str(value)
+                .map_err(|_| ApiError::validation(format!("Invalid tag id {}", value), None))?;
+            if !tag_ids.contains(&tag_id) {
+                tag_ids.push(tag_id);
+            }
+        }
+        if tag_ids.len() > MAX_FILTER_TAGS {
+            return Err(ApiError::validation(
+                format!("At most {} tags can be filtered by", MAX_FILTER_TAGS),
+                None,
+            ));
+        }
+
+        Ok((!tag_ids.is_empty()).then_some(TagFilter { tag_ids, match_all }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_color() {
+        assert_eq!(normalize_color("#2E7D32").as_deref(), Some("#2e7d32"));
+        assert_eq!(normalize_color(" #F0a ").as_deref(), Some("#ff00aa"));
+        assert_eq!(normalize_color("2e7d32"), None);
+        assert_eq!(normalize_color("#2e7d3"), None);
+        assert_eq!(normalize_color("#gggggg"), None);
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+
+        assert_eq!(TagFilter::parse(None, None).unwrap(), None);
+        assert_eq!(TagFilter::parse(Some(" , "), Some("all")).unwrap(), None);
+
+        let filter = TagFilter::parse(Some(&format!("{}, {},{}", a, b, a)), None).unwrap().unwrap();
+        assert_eq!(filter.tag_ids, vec![a, b]);
+        assert!(!filter.match_all);
+
+        let filter = TagFilter::parse(Some(&a.to_string()), Some("all")).unwrap().unwrap();
+        assert!(filter.match_all);
+
+        assert!(TagFilter::parse(Some("stand"), None).is_err());
+        assert!(TagFilter::parse(Some(&a.to_string()), Some("most")).is_err());
+    }
+}
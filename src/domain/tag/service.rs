@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use super::rules::{normalize_color, TagFilter};
+use crate::{
+    api::resources::tag::dto::SaveTagInput,
+    db::{
+        models::{Tag, TagSubject, Tagging},
+        repositories::TagRepository,
+    },
+    error::{ApiError, ErrorContext, Result},
+};
+
+/// Service for an organization's tags and what they are attached to
+pub struct TagService<R: TagRepository + Send + Sync> {
+    repository: R,
+}
+
+fn invalid_input(e: validator::ValidationErrors) -> ApiError {
+    ApiError::validation_with_context(
+        "Invalid input",
+        ErrorContext::new()
+            .with_message_key("INVALID_INPUT")
+            .with_details(json!(e))
+    )
+}
+
+impl<R: TagRepository + Send + Sync> TagService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    fn tag(org_id: Uuid, input: SaveTagInput) -> Result<Tag> {
+        ValidatorValidate::validate(&input).map_err(invalid_input)?;
+        let name = input.name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiError::validation("A tag name is required", None));
+        }
+        let color = normalize_color(&input.color)
+            .ok_or_else(|| ApiError::validation(format!("Invalid color {}, expected e.g. #2e7d32", input.color), None))?;
+        let now = Utc::now();
+        Ok(Tag {
+            id: Uuid::new_v4(),
+            org_id,
+            name,
+            color,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Creates a tag
+    pub async fn create(&self, conn: &mut PgConnection, org_id: Uuid, input: SaveTagInput) -> Result<Tag> {
+        let tag = Self::tag(org_id, input)?;
+        let tag = self.repository.create(conn, &tag).await?;
+        info!(tag_id = %tag.id, org_id = %org_id, "Created tag '{}'", tag.name);
+        Ok(tag)
+    }
+
+    /// Renames or recolors a tag
+    pub async fn update(&self, conn: &mut PgConnection, org_id: Uuid, tag_id: Uuid, input: SaveTagInput) -> Result<Tag> {
+        let existing = self.repository.find(conn, org_id, tag_id).await?;
+        let tag = Tag {
+            id: existing.id,
+            created_at: existing.created_at,
+            ..Self::tag(org_id, input)?
+        };
+        self.repository.update(conn, org_id, &tag).await
+    }
+
+    /// Deletes a tag, detaching it from everything
+    pub async fn delete(&self, conn: &mut PgConnection, org_id: Uuid, tag_id: Uuid) -> Result<()> {
+        self.repository.delete(conn, org_id, tag_id).await?;
+        info!(tag_id = %tag_id, org_id = %org_id, "Deleted tag");
+        Ok(())
+    }
+
+    /// Gets a tag
+    pub async fn get(&self, conn: &mut PgConnection, org_id: Uuid, tag_id: Uuid) -> Result<Tag> {
+        self.repository.find(conn, org_id, tag_id).await
+    }
+
+    /// Lists the organization's tags by name
+    pub async fn list(&self, conn: &mut PgConnection, org_id: Uuid) -> Result<Vec<Tag>> {
+        self.repository.list(conn, org_id).await
+    }
+
+    /// Attaches a tag to a subject; attaching it again changes nothing
+    pub async fn attach(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        tag_id: Uuid,
+        subject: TagSubject,
+        subject_id: Uuid,
+        tagged_by: Option<Uuid>,
+    ) -> Result<Tag> {
+        let tag = self.repository.find(conn, org_id, tag_id).await?;
+        let attached = self
+            .repository
+            .attach(conn, &Tagging {
+                tag_id: tag.id,
+                org_id,
+                subject_type: subject.as_str().to_string(),
+                subject_id,
+                tagged_by,
+                created_at: Utc::now(),
+            })
+            .await?;
+        if attached {
+            info!(tag_id = %tag.id, org_id = %org_id, "Tagged {} {} '{}'", subject, subject_id, tag.name);
+        }
+        Ok(tag)
+    }
+
+    /// Detaches a tag from a subject
+    pub async fn detach(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        tag_id: Uuid,
+        subject: TagSubject,
+        subject_id: Uuid,
+    ) -> Result<()> {
+        if !self.repository.detach(conn, org_id, tag_id, subject, subject_id).await? {
+            return Err(ApiError::not_found(format!("Tag {} is not attached to {} {}", tag_id, subject, subject_id)));
+        }
+        Ok(())
+    }
+
+    /// What a tag is attached to, latest first
+    pub async fn taggings(&self, conn: &mut PgConnection, org_id: Uuid, tag_id: Uuid) -> Result<Vec<Tagging>> {
+        let tag = self.repository.find(conn, org_id, tag_id).await?;
+        self.repository.taggings(conn, org_id, tag.id).await
+    }
+
+    /// The tags of each of `subject_ids` that has any, by name
+    pub async fn subject_tags(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        subject: TagSubject,
+        subject_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<Tag>>> {
+        let mut tags: HashMap<Uuid, Vec<Tag>> = HashMap::new();
+        for (subject_id, tag) in self.repository.subject_tags(conn, org_id, subject, subject_ids).await? {
+            tags.entry(subject_id).or_default().push(tag);
+        }
+        Ok(tags)
+    }
+
+    /// The subjects a list keeps under `filter`, `None` when it keeps
+    /// everything
+    pub async fn filter(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        subject: TagSubject,
+        filter: Option<&TagFilter>,
+    ) -> Result<Option<Vec<Uuid>>> {
+        let Some(filter) = filter else {
+            return Ok(None);
+        };
+        let subject_ids = self
+            .repository
+            .tagged(conn, org_id, subject, &filter.tag_ids, filter.match_all)
+            .await?;
+        Ok(Some(subject_ids))
+    }
+}
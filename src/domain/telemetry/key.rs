@@ -0,0 +1,16 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// Header telemetry uploads carry their API key in
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Prefix of telemetry API keys, so a leaked one is recognizable
+const KEY_PREFIX: &str = "tlm_";
+
+/// A new telemetry API key
+///
+/// Keys are stored and looked up by [`crate::utils::crypto::hash_token`],
+/// like provisioning tokens.
+pub fn generate_key() -> String {
+    let bytes: [u8; 32] = rand::random();
+    format!("{}{}", KEY_PREFIX, URL_SAFE_NO_PAD.encode(bytes))
+}
@@ -0,0 +1,15 @@
+//! Machine telemetry ingestion
+//!
+//! Telematics units upload data points with an API key an admin issued for
+//! the organization. Units buffer points while out of coverage and upload
+//! them later, possibly more than once, so a point replaces any stored for
+//! the same machine and time. A batch is validated as a whole; one invalid
+//! point stores none of them, and the error names it by its position.
+
+mod key;
+mod service;
+mod validation;
+
+pub use key::{generate_key, API_KEY_HEADER};
+pub use service::{IngestOutcome, TelemetryService};
+pub use validation::{TelemetryValidator, MAX_BATCH_POINTS, MAX_CLOCK_SKEW_SECS};
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use diesel::PgConnection;
+use std::collections::HashMap;
+use tracing::info;
+use uuid::Uuid;
+
+use super::{key::generate_key, validation::TelemetryValidator};
+use crate::{
+    api::resources::telemetry::dto::TelemetryPointInput,
+    db::{
+        models::{TelemetryApiKey, TelemetryPoint},
+        repositories::TelemetryRepository,
+    },
+    domain::organization::ArchiveService,
+    error::Result,
+    utils::crypto::hash_token,
+};
+
+/// What became of the points of an upload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IngestOutcome {
+    pub received: usize,
+    pub stored: usize,
+    /// Points repeating an earlier one of the upload
+    pub duplicates: usize,
+}
+
+/// Service for telemetry API keys and the points uploaded with them
+pub struct TelemetryService<R: TelemetryRepository + Send + Sync> {
+    repository: R,
+}
+
+impl<R: TelemetryRepository + Send + Sync> TelemetryService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// Issues a key for the organization, returning it with the plaintext
+    /// key, which is not stored
+    pub async fn issue_key(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        name: &str,
+        created_by: Option<Uuid>,
+    ) -> Result<(TelemetryApiKey, String)> {
+        let plaintext = generate_key();
+        let key = self
+            .repository
+            .create_key(conn, &TelemetryApiKey {
+                id: Uuid::new_v4(),
+                org_id,
+                name: name.trim().to_string(),
+                key_hash: hash_token(&plaintext),
+                created_by,
+                last_used_at: None,
+                created_at: Utc::now(),
+                revoked_at: None,
+            })
+            .await?;
+        info!(key_id = %key.id, org_id = %org_id, "Issued telemetry API key");
+        Ok((key, plaintext))
+    }
+
+    /// The unrevoked key `plaintext`, recording its use
+    pub async fn authenticate(&self, conn: &mut PgConnection, plaintext: &str) -> Result<Option<TelemetryApiKey>> {
+        let Some(key) = self.repository.find_key(conn, &hash_token(plaintext)).await? else {
+            return Ok(None);
+        };
+        self.repository.touch_key(conn, key.id).await?;
+        Ok(Some(key))
+    }
+
+    /// The unrevoked keys of an organization, newest first
+    pub async fn list_keys(&self, conn: &mut PgConnection, org_id: Uuid) -> Result<Vec<TelemetryApiKey>> {
+        self.repository.list_keys(conn, org_id).await
+    }
+
+    /// Revokes a key, after which its uploads are refused
+    pub async fn revoke_key(&self, conn: &mut PgConnection, org_id: Uuid, key_id: Uuid) -> Result<TelemetryApiKey> {
+        let key = self.repository.revoke_key(conn, org_id, key_id).await?;
+        info!(key_id = %key.id, org_id = %org_id, "Revoked telemetry API key");
+        Ok(key)
+    }
+
+    /// Stores the points of an upload received now, keeping the last of
+    /// any that repeat a machine and time
+    ///
+    /// Archived organizations take no more points, like any other write.
+    pub async fn ingest(&self, conn: &mut PgConnection, org_id: Uuid, points: Vec<TelemetryPointInput>) -> Result<IngestOutcome> {
+        ArchiveService::ensure_writable(conn, org_id).await?;
+        let now = Utc::now();
+        TelemetryValidator::validate_batch(&points, now)?;
+
+        let received = points.len();
+        let mut positions: HashMap<(String, DateTime<Utc>), usize> = HashMap::with_capacity(received);
+        let mut unique: Vec<TelemetryPoint> = Vec::with_capacity(received);
+        for point in points {
+            let point = TelemetryPoint {
+                org_id,
+                machine_id: point.machine_id.trim().to_string(),
+                recorded_at: point.timestamp,
+                engine_hours: point.engine_hours,
+                fuel_used_l: point.fuel_used_l,
+                latitude: point.latitude,
+                longitude: point.longitude,
+                received_at: now,
+            };
+            match positions.get(&(point.machine_id.clone(), point.recorded_at)) {
+                Some(&position) => unique[position] = point,
+                None => {
+                    positions.insert((point.machine_id.clone(), point.recorded_at), unique.len());
+                    unique.push(point);
+                }
+            }
+        }
+
+        let stored = self.repository.upsert_points(conn, &unique).await?;
+        info!(org_id = %org_id, received, stored, "Stored machine telemetry");
+        Ok(IngestOutcome {
+            received,
+            stored,
+            duplicates: received - unique.len(),
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+
+use crate::{
+    api::resources::telemetry::dto::TelemetryPointInput,
+    error::{ApiError, ErrorContext, Result},
+};
+
+/// Most points one upload may carry, a day of readings every second
+pub const MAX_BATCH_POINTS: usize = 86_400;
+
+/// How far ahead of the server's clock a machine's clock may run
+pub const MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+fn invalid(field: String, code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": field,
+            "code": code,
+        })),
+    )
+}
+
+pub struct TelemetryValidator;
+
+impl TelemetryValidator {
+    /// Validates the points of an upload received at `now`, naming the
+    /// failing point's position in the fields reported
+    pub fn validate_batch(points: &[TelemetryPointInput], now: DateTime<Utc>) -> Result<()> {
+        if points.is_empty() {
+            return Err(invalid("points".to_string(), "EMPTY", "The upload has no points"));
+        }
+        if points.len() > MAX_BATCH_POINTS {
+            return Err(invalid(
+                "points".to_string(),
+                "TOO_LARGE",
+                format!("An upload can have at most {} points", MAX_BATCH_POINTS),
+            ));
+        }
+        for (index, point) in points.iter().enumerate() {
+            Self::validate_point(point, now, &format!("points[{}].", index))?;
+        }
+        Ok(())
+    }
+
+    /// Validates one point received at `now`, its fields prefixed with
+    /// `prefix` in errors
+    pub fn validate_point(point: &TelemetryPointInput, now: DateTime<Utc>, prefix: &str) -> Result<()> {
+        let field = |name: &str| format!("{}{}", prefix, name);
+        let machine_id = point.machine_id.trim();
+        if machine_id.is_empty() || machine_id.len() > 100 {
+            return Err(invalid(field("machine_id"), "INVALID_LENGTH", "The machine id must be 1 to 100 characters"));
+        }
+        if point.timestamp > now + Duration::seconds(MAX_CLOCK_SKEW_SECS) {
+            return Err(invalid(field("timestamp"), "IN_FUTURE", "The point was recorded in the future"));
+        }
+        let counter = |value: Option<f64>| value.is_none_or(|value| value.is_finite() && value >= 0.0);
+        if !counter(point.engine_hours) {
+            return Err(invalid(field("engine_hours"), "OUT_OF_RANGE", "Engine hours must not be negative"));
+        }
+        if !counter(point.fuel_used_l) {
+            return Err(invalid(field("fuel_used_l"), "OUT_OF_RANGE", "Fuel used must not be negative"));
+        }
+        match (point.latitude, point.longitude) {
+            (None, None) => {}
+            (Some(latitude), Some(longitude)) => {
+                if !(-90.0..=90.0).contains(&latitude) {
+                    return Err(invalid(field("latitude"), "OUT_OF_RANGE", "The latitude must be from -90 to 90"));
+                }
+                if !(-180.0..=180.0).contains(&longitude) {
+                    return Err(invalid(field("longitude"), "OUT_OF_RANGE", "The longitude must be from -180 to 180"));
+                }
+            }
+            (Some(_), None) => {
+                return Err(invalid(field("longitude"), "REQUIRED", "A position needs both a latitude and a longitude"));
+            }
+            (None, Some(_)) => {
+                return Err(invalid(field("latitude"), "REQUIRED", "A position needs both a latitude and a longitude"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point() -> TelemetryPointInput {
+        TelemetryPointInput {
+            machine_id: "H-12".to_string(),
+            timestamp: Utc::now(),
+            engine_hours: Some(10234.5),
+            fuel_used_l: Some(88120.0),
+            latitude: Some(61.2),
+            longitude: Some(25.1),
+        }
+    }
+
+    fn field(error: ApiError) -> serde_json::Value {
+        error.context.details.unwrap()["field"].clone()
+    }
+
+    #[test]
+    fn test_points_are_named_by_position() {
+        let now = Utc::now();
+        assert!(TelemetryValidator::validate_batch(&[point(), point()], now).is_ok());
+
+        let mut half_position = point();
+        half_position.longitude = None;
+        let error = TelemetryValidator::validate_batch(&[point(), half_position], now).unwrap_err();
+        assert_eq!(field(error), "points[1].longitude");
+
+        let mut ahead = point();
+        ahead.timestamp = now + Duration::hours(1);
+        let error = TelemetryValidator::validate_batch(&[ahead], now).unwrap_err();
+        assert_eq!(field(error), "points[0].timestamp");
+
+        assert!(TelemetryValidator::validate_batch(&[], now).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Mean radius of the Earth, in kilometres
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Road distance over straight-line distance on forest roads
+const ROAD_FACTOR: f64 = 1.3;
+
+/// Speed assumed when the pings tell nothing about it
+const DEFAULT_SPEED_KMH: f64 = 50.0;
+
+/// Lowest speed an estimate assumes, so a stop does not push the arrival
+/// out indefinitely
+const MIN_SPEED_KMH: f64 = 15.0;
+
+/// How far back from the latest ping the speed is measured
+const SPEED_WINDOW_MINUTES: i64 = 20;
+
+/// A truck this close to the mill is taken to have arrived
+const ARRIVAL_RADIUS_KM: f64 = 0.5;
+
+/// An estimate is stale when the latest ping is older than this
+const STALE_AFTER_MINUTES: i64 = 15;
+
+/// A point in WGS 84 coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// A position reported by a truck
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GpsPing {
+    pub position: Position,
+    pub recorded_at: DateTime<Utc>,
+    /// Speed reported by the device, if any
+    pub speed_kmh: Option<f64>,
+}
+
+/// Estimated arrival of a truck at its destination
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Eta {
+    /// Latest known position
+    pub position: Position,
+    pub recorded_at: DateTime<Utc>,
+    /// Road distance left to the destination
+    pub remaining_km: f64,
+    /// Speed the estimate assumes
+    pub speed_kmh: f64,
+    pub arrives_at: DateTime<Utc>,
+    /// Whether the truck is at the destination
+    pub arrived: bool,
+    /// Whether the truck has not reported for a while, so the estimate may
+    /// be off
+    pub stale: bool,
+}
+
+/// Great-circle distance between two points
+pub fn distance_km(from: Position, to: Position) -> f64 {
+    let (lat1, lat2) = (from.latitude.to_radians(), to.latitude.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (to.longitude - from.longitude).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// Speed over the pings of the window before the latest one, falling back
+/// to the speed the latest ping reports
+fn speed_kmh(pings: &[GpsPing]) -> Option<f64> {
+    let latest = pings.last()?;
+    let window_start = latest.recorded_at - Duration::minutes(SPEED_WINDOW_MINUTES);
+    let window = &pings[pings.iter().position(|ping| ping.recorded_at >= window_start).unwrap_or(pings.len() - 1)..];
+    let hours = (latest.recorded_at - window[0].recorded_at).num_seconds() as f64 / 3600.0;
+    if hours > 0.0 {
+        let travelled: f64 = window.windows(2).map(|pair| distance_km(pair[0].position, pair[1].position)).sum();
+        Some(travelled / hours)
+    } else {
+        latest.speed_kmh
+    }
+}
+
+/// Estimates when a truck reaches its destination from its pings
+///
+/// Pings may come in any order. `None` when there are none.
+pub fn estimate(pings: &[GpsPing], destination: Position, now: DateTime<Utc>) -> Option<Eta> {
+    let mut pings = pings.to_vec();
+    pings.sort_by_key(|ping| ping.recorded_at);
+    let latest = *pings.last()?;
+
+    let distance = distance_km(latest.position, destination);
+    let arrived = distance <= ARRIVAL_RADIUS_KM;
+    let remaining_km = if arrived { 0.0 } else { distance * ROAD_FACTOR };
+    let speed_kmh = speed_kmh(&pings)
+        .filter(|speed| speed.is_finite())
+        .unwrap_or(DEFAULT_SPEED_KMH)
+        .max(MIN_SPEED_KMH);
+    let travel = Duration::seconds((remaining_km / speed_kmh * 3600.0).round() as i64);
+
+    Some(Eta {
+        position: latest.position,
+        recorded_at: latest.recorded_at,
+        remaining_km,
+        speed_kmh,
+        arrives_at: latest.recorded_at + travel,
+        arrived,
+        stale: !arrived && now - latest.recorded_at > Duration::minutes(STALE_AFTER_MINUTES),
+    })
+}
+
+/// Order to unload trucks in: those already at the mill first, then by
+/// estimated arrival
+pub fn unloading_order(trucks: &[(Uuid, Eta)]) -> Vec<Uuid> {
+    let mut trucks = trucks.to_vec();
+    trucks.sort_by_key(|(_, eta)| (!eta.arrived, eta.arrives_at));
+    trucks.into_iter().map(|(id, _)| id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(latitude: f64) -> Position {
+        Position { latitude, longitude: 25.0 }
+    }
+
+    fn ping(latitude: f64, minutes: i64) -> GpsPing {
+        GpsPing {
+            position: at(latitude),
+            recorded_at: DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minutes),
+            speed_kmh: None,
+        }
+    }
+
+    fn now(minutes: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn test_distance_of_a_degree_of_latitude() {
+        assert!((distance_km(at(60.0), at(61.0)) - 111.19).abs() < 0.01);
+        assert_eq!(distance_km(at(60.0), at(60.0)), 0.0);
+    }
+
+    #[test]
+    fn test_estimate_from_recent_speed() {
+        // 10 km in 10 minutes with 20 km left in a straight line
+        let pings = [ping(60.09, 10), ping(60.0, 0)];
+        let eta = estimate(&pings, at(60.27), now(12)).unwrap();
+        assert!((eta.speed_kmh - 60.0).abs() < 0.1);
+        assert!((eta.remaining_km - 26.0).abs() < 0.1);
+        let travel = (eta.arrives_at - eta.recorded_at).num_seconds();
+        assert!((1555..=1565).contains(&travel), "{}", travel);
+        assert!(!eta.arrived && !eta.stale);
+    }
+
+    #[test]
+    fn test_estimate_falls_back_without_movement() {
+        assert_eq!(estimate(&[], at(60.0), now(0)), None);
+
+        let single = estimate(&[ping(60.0, 0)], at(60.5), now(0)).unwrap();
+        assert_eq!(single.speed_kmh, DEFAULT_SPEED_KMH);
+
+        // Waiting at a crossing
+        let stopped = estimate(&[ping(60.0, 0), ping(60.0, 10)], at(60.5), now(30)).unwrap();
+        assert_eq!(stopped.speed_kmh, MIN_SPEED_KMH);
+        assert!(stopped.stale);
+    }
+
+    #[test]
+    fn test_estimate_at_the_destination() {
+        let eta = estimate(&[ping(60.0, 0), ping(60.27, 30)], at(60.271), now(90)).unwrap();
+        assert!(eta.arrived && !eta.stale);
+        assert_eq!((eta.remaining_km, eta.arrives_at), (0.0, now(30)));
+    }
+
+    #[test]
+    fn test_unloading_order() {
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mill = at(60.27);
+        let near = estimate(&[ping(60.2, 0)], mill, now(0)).unwrap();
+        let far = estimate(&[ping(60.0, 0)], mill, now(0)).unwrap();
+        let arrived = estimate(&[ping(60.27, 5)], mill, now(5)).unwrap();
+
+        assert_eq!(unloading_order(&[(third, far), (second, near), (first, arrived)]), vec![first, second, third]);
+    }
+}
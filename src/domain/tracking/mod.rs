@@ -0,0 +1,10 @@
+//! Truck tracking
+//!
+//! A dispatched load's truck reports its position as GPS pings on the way
+//! to the mill. The latest ping gives the distance left and the pings
+//! before it the speed the truck is making, from which its arrival is
+//! estimated. Mills and dispatchers sequence unloading by these estimates.
+
+mod eta;
+
+pub use eta::{distance_km, estimate, unloading_order, Eta, GpsPing, Position};
@@ -0,0 +1,88 @@
+use diesel::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::auth::User,
+        repositories::{
+            auth::{
+                MagicLinkTokenRepository, MagicLinkTokenRepositoryImpl, RefreshTokenRepository, RefreshTokenRepositoryImpl,
+                UserRepository, UserRepositoryImpl,
+            },
+            Repository,
+        },
+    },
+    domain::auth::{AuthAudit, AuthEventKind, ClientInfo, NewAuthEvent, RevocationList},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::Config,
+};
+
+/// Deactivates and reactivates the users of an organization
+///
+/// Unlike removing a user, deactivating them leaves them a member, with
+/// their records and history attached, until they are reactivated.
+pub struct ActivationService;
+
+impl ActivationService {
+    /// Stops `user_id` from authenticating: their sessions and magic links
+    /// are revoked, their access tokens are rejected from now on and they
+    /// can't log in again until reactivated
+    ///
+    /// Admins can't deactivate themselves, nor users of other organizations.
+    pub async fn deactivate(
+        conn: &mut PgConnection,
+        config: &Config,
+        admin: &User,
+        user_id: Uuid,
+        client: &ClientInfo,
+    ) -> Result<User> {
+        if user_id == admin.id {
+            return Err(ApiError::new(ErrorCode::Forbidden, "You can't deactivate yourself", ErrorContext::new()));
+        }
+        let user = Self::find_member(conn, admin, user_id).await?;
+        if !user.is_active {
+            return Ok(user);
+        }
+
+        let user = UserRepositoryImpl.set_active(conn, user.id, false).await?;
+        RefreshTokenRepositoryImpl.revoke_all_for_user(conn, user.id).await?;
+        MagicLinkTokenRepositoryImpl.revoke_all_for_user(conn, user.id).await?;
+        RevocationList::revoke_user(config, user.id).await;
+
+        AuthAudit::record(conn, client, NewAuthEvent::new(AuthEventKind::Deactivated, Some(user.id))).await;
+        info!(user_id = %user.id, deactivated_by = %admin.id, "User deactivated");
+        Ok(user)
+    }
+
+    /// Lets `user_id` authenticate again
+    ///
+    /// Tokens issued before they were deactivated stay revoked; they log
+    /// in anew.
+    pub async fn reactivate(
+        conn: &mut PgConnection,
+        admin: &User,
+        user_id: Uuid,
+        client: &ClientInfo,
+    ) -> Result<User> {
+        let user = Self::find_member(conn, admin, user_id).await?;
+        if user.is_active {
+            return Ok(user);
+        }
+
+        let user = UserRepositoryImpl.set_active(conn, user.id, true).await?;
+        AuthAudit::record(conn, client, NewAuthEvent::new(AuthEventKind::Reactivated, Some(user.id))).await;
+        info!(user_id = %user.id, reactivated_by = %admin.id, "User reactivated");
+        Ok(user)
+    }
+
+    /// The user `user_id` of the organization of `admin`; users of other
+    /// organizations aren't found
+    async fn find_member(conn: &mut PgConnection, admin: &User, user_id: Uuid) -> Result<User> {
+        let user = UserRepositoryImpl.find_by_id(conn, user_id).await?;
+        if user.org_id != admin.org_id {
+            return Err(ApiError::not_found(format!("User with id {} not found", user_id)));
+        }
+        Ok(user)
+    }
+}
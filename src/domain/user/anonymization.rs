@@ -0,0 +1,73 @@
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        anonymize::anonymize_user,
+        models::auth::User,
+        repositories::auth::{UserRepository, UserRepositoryImpl},
+    },
+    domain::{
+        auth::{AuthAudit, AuthEventKind, ClientInfo, NewAuthEvent, RevocationList},
+        user::AvatarService,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::Config,
+};
+
+/// Forgets departed users of an organization
+///
+/// Anonymizing a user can't be undone. Their name and email become
+/// placeholders and their other personal data is erased, but their id
+/// stays, so blocks they signed off, documents they uploaded and the rest
+/// of what they produced remain attributed to the placeholder.
+pub struct AnonymizationService;
+
+impl AnonymizationService {
+    /// Anonymizes `user_id`, who must have been deactivated or removed
+    /// first
+    ///
+    /// Admins can't anonymize themselves, nor users of other organizations.
+    /// Anonymizing a user again changes nothing.
+    pub async fn anonymize(
+        conn: &mut PgConnection,
+        config: &Config,
+        admin: &User,
+        user_id: Uuid,
+        client: &ClientInfo,
+    ) -> Result<User> {
+        if user_id == admin.id {
+            return Err(ApiError::new(ErrorCode::Forbidden, "You can't anonymize yourself", ErrorContext::new()));
+        }
+        let user = UserRepositoryImpl.find_with_removed(conn, user_id).await?;
+        if user.org_id != admin.org_id {
+            return Err(ApiError::not_found(format!("User with id {} not found", user_id)));
+        }
+        if user.anonymized_at.is_some() {
+            return Ok(user);
+        }
+        if user.is_active && user.deleted_at.is_none() {
+            return Err(ApiError::new(
+                ErrorCode::Conflict,
+                "Deactivate or remove the user before anonymizing them",
+                ErrorContext::new().with_details(json!({ "code": "STILL_ACTIVE" })),
+            ));
+        }
+
+        // Nobody knows this password
+        let password = User::hash_password(&Uuid::new_v4().to_string())?;
+        let anonymized = anonymize_user(conn, user.id, &password).await?;
+        RevocationList::revoke_user(config, user.id).await;
+        if user.avatar_updated_at.is_some() {
+            if let Err(e) = AvatarService::remove(config, user.id).await {
+                warn!(user_id = %user.id, error = %e, "Avatar of anonymized user not deleted");
+            }
+        }
+
+        AuthAudit::record(conn, client, NewAuthEvent::new(AuthEventKind::Anonymized, Some(user.id))).await;
+        info!(user_id = %user.id, anonymized_by = %admin.id, "User anonymized");
+        Ok(anonymized)
+    }
+}
@@ -0,0 +1,147 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use diesel::PgConnection;
+use ring::hmac;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+
+use super::image::{decode_png, ImageError};
+use crate::{
+    db::{
+        models::auth::User,
+        repositories::auth::{UserRepository, UserRepositoryImpl},
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::Config,
+};
+
+/// Edge lengths avatars are stored at, small then large
+pub const AVATAR_SIZES: [u32; 2] = [64, 256];
+
+/// Largest avatar upload accepted
+pub const MAX_AVATAR_BYTES: usize = 5 * 1024 * 1024;
+
+/// Signed links to a user's avatar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvatarLinks {
+    /// The 64 pixel avatar
+    pub small: String,
+    /// The 256 pixel avatar
+    pub large: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Stores avatars and signs links to them
+///
+/// Links carry an HMAC of the user, size and expiry keyed with the JWT
+/// secret, so they can be embedded in `<img>` tags and handed around
+/// without a bearer token.
+pub struct AvatarService;
+
+fn storage_key(user_id: Uuid, size: u32) -> String {
+    format!("avatars/{}/{}.png", user_id, size)
+}
+
+fn signing_key(config: &Config) -> hmac::Key {
+    hmac::Key::new(hmac::HMAC_SHA256, config.jwt_secret.as_bytes())
+}
+
+/// What a link to one avatar size signs
+fn signed_message(user_id: Uuid, size: u32, expires: i64) -> String {
+    format!("avatar:{}:{}:{}", user_id, size, expires)
+}
+
+fn invalid_image(error: ImageError) -> ApiError {
+    ApiError::validation_with_context(
+        error.to_string(),
+        ErrorContext::new().with_details(json!({
+            "field": "avatar",
+            "code": match error {
+                ImageError::TooLarge => "TOO_LARGE",
+                _ => "INVALID_IMAGE",
+            },
+        })),
+    )
+}
+
+impl AvatarService {
+    /// Replaces the avatar of `user_id` with a PNG, stored as a centred
+    /// square at each of [`AVATAR_SIZES`]
+    pub async fn upload(conn: &mut PgConnection, config: &Config, user_id: Uuid, png: Bytes) -> Result<User> {
+        let resized = tokio::task::spawn_blocking(move || {
+            let image = decode_png(&png)?;
+            Ok(AVATAR_SIZES.map(|size| (size, Bytes::from(image.square(size).encode_png()))))
+        })
+        .await
+        .map_err(|e| ApiError::new(ErrorCode::InternalError, format!("Avatar resizing panicked: {}", e), ErrorContext::new()))?
+        .map_err(invalid_image)?;
+
+        for (size, png) in resized {
+            config.storage().put(&storage_key(user_id, size), png).await?;
+        }
+        let user = UserRepositoryImpl.set_avatar_updated_at(conn, user_id, Utc::now()).await?;
+        info!(user_id = %user_id, "Avatar uploaded");
+        Ok(user)
+    }
+
+    /// Deletes the stored avatar of `user_id` at every size, if there is one
+    pub async fn remove(config: &Config, user_id: Uuid) -> Result<()> {
+        for size in AVATAR_SIZES {
+            match config.storage().delete(&storage_key(user_id, size)).await {
+                Err(e) if e.code != ErrorCode::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Links to the avatar of `user`, valid for the storage's
+    /// `signed_url_ttl_secs`; `None` when they have not uploaded one
+    pub fn links(config: &Config, user: &User) -> Option<AvatarLinks> {
+        let uploaded_at = user.avatar_updated_at?;
+        let expires = Utc::now().timestamp() + config.storage.signed_url_ttl_secs as i64;
+        let link = |size: u32| {
+            format!(
+                "{}/v1/avatars/{}/{}?v={}&expires={}&signature={}",
+                config.email.public_url.as_deref().unwrap_or("").trim_end_matches('/'),
+                user.id,
+                size,
+                uploaded_at.timestamp(),
+                expires,
+                URL_SAFE_NO_PAD.encode(hmac::sign(&signing_key(config), signed_message(user.id, size, expires).as_bytes())),
+            )
+        };
+        Some(AvatarLinks {
+            small: link(AVATAR_SIZES[0]),
+            large: link(AVATAR_SIZES[1]),
+            expires_at: DateTime::from_timestamp(expires, 0)?,
+        })
+    }
+
+    /// The stored avatar a signed link points at
+    ///
+    /// Unknown sizes, expired links and bad signatures are all reported as
+    /// not found, so links can't be used to probe which users have avatars.
+    pub async fn file(
+        config: &Config,
+        user_id: Uuid,
+        size: u32,
+        expires: i64,
+        signature: &str,
+    ) -> Result<Bytes> {
+        let not_found = || ApiError::not_found("Avatar not found");
+        if !AVATAR_SIZES.contains(&size) || expires < Utc::now().timestamp() {
+            return Err(not_found());
+        }
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| not_found())?;
+        hmac::verify(&signing_key(config), signed_message(user_id, size, expires).as_bytes(), &signature)
+            .map_err(|_| not_found())?;
+
+        config.storage().get(&storage_key(user_id, size)).await.map_err(|e| match e.code {
+            ErrorCode::NotFound => not_found(),
+            _ => e,
+        })
+    }
+}
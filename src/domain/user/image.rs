@@ -0,0 +1,430 @@
+//! Just enough PNG to turn an uploaded picture into square avatars
+//!
+//! Reads non-interlaced PNGs of every colour type and bit depth, and writes
+//! 8-bit RGBA ones.
+
+use std::io::{Read, Write};
+
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+
+/// The eight bytes every PNG starts with
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Widest or tallest picture decoded
+pub const MAX_DIMENSION: u32 = 4096;
+
+/// An 8-bit RGBA picture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// Rows of `width` RGBA pixels, top to bottom
+    pub pixels: Vec<u8>,
+}
+
+/// Why a file could not be decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageError {
+    NotPng,
+    Malformed(&'static str),
+    Unsupported(&'static str),
+    TooLarge,
+}
+
+impl std::fmt::Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotPng => write!(f, "Image must be a PNG"),
+            Self::Malformed(reason) => write!(f, "Malformed PNG: {}", reason),
+            Self::Unsupported(reason) => write!(f, "Unsupported PNG: {}", reason),
+            Self::TooLarge => write!(f, "Image must be at most {0}×{0} pixels", MAX_DIMENSION),
+        }
+    }
+}
+
+/// The header of a PNG
+struct Header {
+    width: u32,
+    height: u32,
+    depth: u8,
+    color_type: u8,
+}
+
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color_type {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        }
+    }
+
+    /// Bytes between a byte and the one it is filtered against
+    fn filter_distance(&self) -> usize {
+        (self.channels() * self.depth as usize).div_ceil(8)
+    }
+
+    /// Bytes in one scanline, without its filter byte
+    fn stride(&self) -> usize {
+        (self.width as usize * self.channels() * self.depth as usize).div_ceil(8)
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Decodes a PNG into RGBA
+pub fn decode_png(bytes: &[u8]) -> Result<Image, ImageError> {
+    let mut rest = bytes.strip_prefix(&SIGNATURE[..]).ok_or(ImageError::NotPng)?;
+    let mut header = None;
+    let mut palette: Vec<[u8; 4]> = Vec::new();
+    let mut transparent: Option<Vec<u16>> = None;
+    let mut data = Vec::new();
+
+    loop {
+        if rest.len() < 12 {
+            return Err(ImageError::Malformed("truncated chunk"));
+        }
+        let length = read_u32(rest) as usize;
+        if rest.len() - 12 < length {
+            return Err(ImageError::Malformed("truncated chunk"));
+        }
+        let kind = &rest[4..8];
+        let body = &rest[8..8 + length];
+        if crc32fast::hash(&rest[4..8 + length]) != read_u32(&rest[8 + length..]) {
+            return Err(ImageError::Malformed("chunk checksum mismatch"));
+        }
+        rest = &rest[12 + length..];
+
+        match kind {
+            b"IHDR" => {
+                if body.len() != 13 {
+                    return Err(ImageError::Malformed("bad header"));
+                }
+                let parsed = Header {
+                    width: read_u32(body),
+                    height: read_u32(&body[4..]),
+                    depth: body[8],
+                    color_type: body[9],
+                };
+                let valid_depth = match parsed.color_type {
+                    0 => matches!(parsed.depth, 1 | 2 | 4 | 8 | 16),
+                    3 => matches!(parsed.depth, 1 | 2 | 4 | 8),
+                    2 | 4 | 6 => matches!(parsed.depth, 8 | 16),
+                    _ => false,
+                };
+                if !valid_depth || body[10] != 0 || body[11] != 0 {
+                    return Err(ImageError::Malformed("bad header"));
+                }
+                if body[12] != 0 {
+                    return Err(ImageError::Unsupported("interlaced images"));
+                }
+                if parsed.width == 0 || parsed.height == 0 {
+                    return Err(ImageError::Malformed("empty image"));
+                }
+                if parsed.width > MAX_DIMENSION || parsed.height > MAX_DIMENSION {
+                    return Err(ImageError::TooLarge);
+                }
+                header = Some(parsed);
+            }
+            b"PLTE" => {
+                if body.len() % 3 != 0 || body.len() > 256 * 3 {
+                    return Err(ImageError::Malformed("bad palette"));
+                }
+                palette = body.chunks(3).map(|rgb| [rgb[0], rgb[1], rgb[2], 255]).collect();
+            }
+            b"tRNS" => match header.as_ref().map(|header| header.color_type) {
+                Some(3) => {
+                    for (entry, alpha) in palette.iter_mut().zip(body) {
+                        entry[3] = *alpha;
+                    }
+                }
+                Some(0 | 2) => {
+                    transparent = Some(body.chunks_exact(2).map(|sample| u16::from_be_bytes([sample[0], sample[1]])).collect());
+                }
+                _ => {}
+            },
+            b"IDAT" => data.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    let header = header.ok_or(ImageError::Malformed("missing header"))?;
+    if header.color_type == 3 && palette.is_empty() {
+        return Err(ImageError::Malformed("missing palette"));
+    }
+    let stride = header.stride();
+    let expected = (stride + 1) * header.height as usize;
+    let mut raw = Vec::with_capacity(expected);
+    ZlibDecoder::new(data.as_slice())
+        .take(expected as u64)
+        .read_to_end(&mut raw)
+        .map_err(|_| ImageError::Malformed("corrupt image data"))?;
+    if raw.len() < expected {
+        return Err(ImageError::Malformed("truncated image data"));
+    }
+
+    let distance = header.filter_distance();
+    let mut previous = vec![0u8; stride];
+    let mut pixels = Vec::with_capacity(header.width as usize * header.height as usize * 4);
+    for line in raw.chunks_exact_mut(stride + 1) {
+        let (filter, row) = line.split_first_mut().expect("scanlines have a filter byte");
+        unfilter(*filter, row, &previous, distance)?;
+        append_rgba(&header, row, &palette, transparent.as_deref(), &mut pixels);
+        previous.copy_from_slice(row);
+    }
+
+    Ok(Image {
+        width: header.width,
+        height: header.height,
+        pixels,
+    })
+}
+
+/// Reverses the filter a scanline was written with
+fn unfilter(filter: u8, row: &mut [u8], previous: &[u8], distance: usize) -> Result<(), ImageError> {
+    for i in 0..row.len() {
+        let left = if i >= distance { row[i - distance] } else { 0 };
+        let up = previous[i];
+        let up_left = if i >= distance { previous[i - distance] } else { 0 };
+        let predicted = match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((left as u16 + up as u16) / 2) as u8,
+            4 => paeth(left, up, up_left),
+            _ => return Err(ImageError::Malformed("unknown scanline filter")),
+        };
+        row[i] = row[i].wrapping_add(predicted);
+    }
+    Ok(())
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let (to_left, to_up, to_up_left) = (
+        (estimate - left as i16).abs(),
+        (estimate - up as i16).abs(),
+        (estimate - up_left as i16).abs(),
+    );
+    if to_left <= to_up && to_left <= to_up_left {
+        left
+    } else if to_up <= to_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+/// The `index`th sample of a scanline, at its own bit depth
+fn sample(row: &[u8], index: usize, depth: u8) -> u16 {
+    match depth {
+        16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+        8 => row[index] as u16,
+        _ => {
+            let bit = index * depth as usize;
+            let shift = 8 - depth as usize - bit % 8;
+            ((row[bit / 8] >> shift) & ((1u8 << depth) - 1)) as u16
+        }
+    }
+}
+
+/// Scales a sample to eight bits
+fn to_u8(value: u16, depth: u8) -> u8 {
+    match depth {
+        16 => (value >> 8) as u8,
+        8 => value as u8,
+        _ => (value * 255 / ((1 << depth) - 1)) as u8,
+    }
+}
+
+/// Converts a scanline to RGBA pixels
+fn append_rgba(header: &Header, row: &[u8], palette: &[[u8; 4]], transparent: Option<&[u16]>, pixels: &mut Vec<u8>) {
+    let channels = header.channels();
+    let depth = header.depth;
+    for x in 0..header.width as usize {
+        let samples: Vec<u16> = (0..channels).map(|channel| sample(row, x * channels + channel, depth)).collect();
+        let keyed_out = transparent.is_some_and(|key| key == samples.as_slice());
+        let pixel = match header.color_type {
+            3 => palette.get(samples[0] as usize).copied().unwrap_or([0, 0, 0, 255]),
+            0 => {
+                let gray = to_u8(samples[0], depth);
+                [gray, gray, gray, if keyed_out { 0 } else { 255 }]
+            }
+            2 => [
+                to_u8(samples[0], depth),
+                to_u8(samples[1], depth),
+                to_u8(samples[2], depth),
+                if keyed_out { 0 } else { 255 },
+            ],
+            4 => {
+                let gray = to_u8(samples[0], depth);
+                [gray, gray, gray, to_u8(samples[1], depth)]
+            }
+            _ => [
+                to_u8(samples[0], depth),
+                to_u8(samples[1], depth),
+                to_u8(samples[2], depth),
+                to_u8(samples[3], depth),
+            ],
+        };
+        pixels.extend_from_slice(&pixel);
+    }
+}
+
+impl Image {
+    /// The largest centred square of the picture scaled to `size`×`size`
+    ///
+    /// Each pixel averages the ones it covers, weighted by their alpha so
+    /// transparent pixels don't darken the edges.
+    pub fn square(&self, size: u32) -> Image {
+        let side = self.width.min(self.height) as u64;
+        let (left, top) = ((self.width as u64 - side) / 2, (self.height as u64 - side) / 2);
+        let size_u64 = size as u64;
+        let mut pixels = Vec::with_capacity(size as usize * size as usize * 4);
+
+        for y in 0..size_u64 {
+            let (y0, y1) = (y * side / size_u64, ((y + 1) * side / size_u64).max(y * side / size_u64 + 1));
+            for x in 0..size_u64 {
+                let (x0, x1) = (x * side / size_u64, ((x + 1) * side / size_u64).max(x * side / size_u64 + 1));
+                let mut sums = [0u64; 4];
+                let mut count = 0u64;
+                for source_y in (top + y0)..(top + y1) {
+                    for source_x in (left + x0)..(left + x1) {
+                        let offset = ((source_y * self.width as u64 + source_x) * 4) as usize;
+                        let pixel = &self.pixels[offset..offset + 4];
+                        let alpha = pixel[3] as u64;
+                        for channel in 0..3 {
+                            sums[channel] += pixel[channel] as u64 * alpha;
+                        }
+                        sums[3] += alpha;
+                        count += 1;
+                    }
+                }
+                let alpha = sums[3];
+                for sum in &sums[..3] {
+                    pixels.push((sum + alpha / 2).checked_div(alpha).unwrap_or(0) as u8);
+                }
+                pixels.push(((alpha + count / 2) / count) as u8);
+            }
+        }
+
+        Image {
+            width: size,
+            height: size,
+            pixels,
+        }
+    }
+
+    /// Encodes the picture as an 8-bit RGBA PNG
+    pub fn encode_png(&self) -> Vec<u8> {
+        let stride = self.width as usize * 4;
+        let mut raw = Vec::with_capacity((stride + 1) * self.height as usize);
+        for row in self.pixels.chunks_exact(stride) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+        write_png(self.width, self.height, 8, 6, &raw, &[])
+    }
+}
+
+/// Writes a PNG from already filtered scanlines, with `extra` chunks
+/// between its header and data
+fn write_png(width: u32, height: u32, depth: u8, color_type: u8, raw: &[u8], extra: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(raw).expect("writing to a Vec cannot fail");
+    let data = encoder.finish().expect("writing to a Vec cannot fail");
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[depth, color_type, 0, 0, 0]);
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    for (kind, body) in extra {
+        write_chunk(&mut png, kind, body);
+    }
+    write_chunk(&mut png, b"IDAT", &data);
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    png.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(body);
+    let crc = crc32fast::hash(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, pixel: [u8; 4]) -> Image {
+        Image {
+            width,
+            height,
+            pixels: pixel.repeat((width * height) as usize),
+        }
+    }
+
+    #[test]
+    fn test_rgba_round_trip() {
+        let mut image = solid(3, 2, [10, 20, 30, 255]);
+        image.pixels[4..8].copy_from_slice(&[200, 100, 0, 128]);
+        assert_eq!(decode_png(&image.encode_png()), Ok(image));
+    }
+
+    #[test]
+    fn test_decodes_other_colour_types() {
+        // 2×2 palette image at two bits per pixel, with a transparent entry
+        let palette: &[u8] = &[255, 0, 0, 0, 0, 255];
+        let alpha: &[u8] = &[255, 0];
+        let png = write_png(2, 2, 2, 3, &[0, 0b0001_0000, 0, 0b0100_0000], &[(b"PLTE", palette), (b"tRNS", alpha)]);
+        let image = decode_png(&png).unwrap();
+        assert_eq!(image.pixels[..8], [255, 0, 0, 255, 0, 0, 255, 0]);
+        assert_eq!(image.pixels[8..], [0, 0, 255, 0, 255, 0, 0, 255]);
+
+        // 16-bit grey with alpha, the second row Sub-filtered
+        let png = write_png(2, 2, 16, 4, &[0, 0xff, 0xff, 0x80, 0x00, 0, 0, 0, 0, 1, 0x10, 0, 0, 0, 0, 0, 0, 0], &[]);
+        let image = decode_png(&png).unwrap();
+        assert_eq!(image.pixels, [255, 255, 255, 128, 0, 0, 0, 0, 16, 16, 16, 0, 16, 16, 16, 0]);
+    }
+
+    #[test]
+    fn test_rejects_bad_files() {
+        assert_eq!(decode_png(b"GIF89a"), Err(ImageError::NotPng));
+
+        let mut png = solid(1, 1, [0, 0, 0, 255]).encode_png();
+        let last = png.len() - 20;
+        png[last] ^= 0xff;
+        assert!(matches!(decode_png(&png), Err(ImageError::Malformed(_))));
+
+        let png = write_png(MAX_DIMENSION + 1, 1, 8, 0, &[], &[]);
+        assert_eq!(decode_png(&png), Err(ImageError::TooLarge));
+    }
+
+    #[test]
+    fn test_square_crops_the_centre_and_averages() {
+        // A 4×2 image: red, then two grey columns, then blue
+        let mut image = solid(4, 2, [128, 128, 128, 255]);
+        for y in 0..2 {
+            image.pixels[y * 16..y * 16 + 4].copy_from_slice(&[255, 0, 0, 255]);
+            image.pixels[y * 16 + 12..y * 16 + 16].copy_from_slice(&[0, 0, 255, 255]);
+        }
+        assert_eq!(image.square(1), solid(1, 1, [128, 128, 128, 255]));
+        assert_eq!(image.square(4).width, 4);
+
+        // Transparent pixels don't bleed their colour into the average
+        let mut image = solid(2, 2, [0, 0, 0, 0]);
+        image.pixels[..4].copy_from_slice(&[200, 100, 50, 255]);
+        image.pixels[8..12].copy_from_slice(&[200, 100, 50, 255]);
+        assert_eq!(image.square(1), solid(1, 1, [200, 100, 50, 128]));
+    }
+}
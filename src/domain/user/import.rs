@@ -0,0 +1,321 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, Utc};
+use diesel::PgConnection;
+use serde::Serialize;
+use serde_json::json;
+use tracing::info;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::auth::{Role, User},
+        repositories::{
+            auth::{PasswordResetTokenRepository, PasswordResetTokenRepositoryImpl, UserRepository, UserRepositoryImpl},
+            OrganizationRepositoryImpl, Repository,
+        },
+    },
+    domain::{
+        activity::{ActivityFeed, FeedEvent},
+        auth::{link_token, AuthValidator},
+        import::ImportFile,
+        invitation::INVITATION_DAYS,
+        organization::QuotaService,
+    },
+    error::{ApiError, ErrorContext, Result},
+    infrastructure::email::InvitationEmail,
+    jobs::email,
+    utils::{crypto::hash_token, Config},
+};
+
+/// Largest user import accepted
+pub const MAX_USER_IMPORT_BYTES: usize = 1024 * 1024;
+
+/// Most users one import may add
+pub const MAX_USER_IMPORT_ROWS: usize = 1000;
+
+/// What became of each row of a user import
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct UserImportReport {
+    /// Users created and invited
+    pub created: usize,
+    /// Rows rejected
+    pub failed: usize,
+    pub rows: Vec<UserImportRow>,
+}
+
+/// One row of a user import
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct UserImportRow {
+    /// Line of the file, the header being line 1
+    pub line: usize,
+    pub email: String,
+    /// The user created, `null` when the row was rejected
+    pub user_id: Option<Uuid>,
+    /// Why the row was rejected, empty when it was imported
+    pub errors: Vec<UserImportError>,
+}
+
+/// A problem with one field of an imported row
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct UserImportError {
+    pub field: String,
+    /// e.g. `INVALID_FORMAT` or `DUPLICATE`
+    pub code: String,
+    pub message: String,
+}
+
+impl UserImportError {
+    fn new(field: &str, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// The field and code a validation error names
+    fn from_validation(error: ApiError) -> Self {
+        let details = error.context.details.unwrap_or_default();
+        Self {
+            field: details["field"].as_str().unwrap_or("row").to_string(),
+            code: details["code"].as_str().unwrap_or("INVALID").to_string(),
+            message: error.message,
+        }
+    }
+}
+
+/// Columns a user import reads, by their normalized headers
+#[derive(Debug, Clone, Copy)]
+struct Columns {
+    email: usize,
+    first_name: usize,
+    last_name: usize,
+    phone_number: Option<usize>,
+    role: Option<usize>,
+}
+
+fn invalid_file(code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": "file",
+            "code": code,
+        })),
+    )
+}
+
+impl Columns {
+    /// Finds the columns in `headers`, which may be spelled `First name`,
+    /// `first-name` or `first_name`
+    fn find(headers: &[String]) -> Result<Self> {
+        let normalized: Vec<String> = headers
+            .iter()
+            .map(|header| header.trim().to_lowercase().replace([' ', '-'], "_"))
+            .collect();
+        let position = |names: &[&str]| normalized.iter().position(|header| names.contains(&header.as_str()));
+        let required = |column: &str, names: &[&str]| {
+            position(names).ok_or_else(|| invalid_file("MISSING_COLUMN", format!("The file has no {} column", column)))
+        };
+        Ok(Self {
+            email: required("email", &["email", "email_address", "e_mail"])?,
+            first_name: required("first_name", &["first_name", "given_name", "first"])?,
+            last_name: required("last_name", &["last_name", "family_name", "surname", "last"])?,
+            phone_number: position(&["phone_number", "phone", "mobile"]),
+            role: position(&["role"]),
+        })
+    }
+}
+
+/// A row checked and ready to become a user
+struct NewUser {
+    first_name: String,
+    last_name: String,
+    phone_number: String,
+    role: Role,
+}
+
+fn parse_role(role: &str) -> Option<Role> {
+    match role.to_uppercase().as_str() {
+        "" | "OPERATOR" => Some(Role::Operator),
+        "MANAGER" => Some(Role::Manager),
+        "ADMIN" => Some(Role::Admin),
+        _ => None,
+    }
+}
+
+/// Adds users to an organization from a CSV file
+pub struct UserImportService;
+
+impl UserImportService {
+    /// Creates a user for each valid row of `csv` in the organization of
+    /// `inviter` and mails each an invitation to set their password
+    ///
+    /// Rows are checked on their own: a bad email, name, phone number or
+    /// role, or an email or phone number already taken or repeated in the
+    /// file, rejects only that row. Only admins import admins.
+    pub async fn import(conn: &mut PgConnection, config: &Config, inviter: &User, csv: &[u8]) -> Result<UserImportReport> {
+        let file = ImportFile::parse("users.csv", csv)?;
+        if file.rows.len() > MAX_USER_IMPORT_ROWS {
+            return Err(invalid_file(
+                "TOO_MANY_ROWS",
+                format!("An import may add at most {} users", MAX_USER_IMPORT_ROWS),
+            ));
+        }
+        let columns = Columns::find(&file.headers)?;
+        let cell = |row: &[String], column: Option<usize>| column.map(|column| row[column].trim().to_string()).unwrap_or_default();
+
+        let emails: Vec<String> = file.rows.iter().map(|row| cell(row, Some(columns.email)).to_lowercase()).collect();
+        let phone_numbers: Vec<String> = file
+            .rows
+            .iter()
+            .map(|row| cell(row, columns.phone_number))
+            .filter(|phone_number| !phone_number.is_empty())
+            .collect();
+        let taken_emails: HashSet<String> = UserRepositoryImpl.find_taken_emails(conn, &emails).await?.into_iter().collect();
+        let taken_phone_numbers: HashSet<String> =
+            UserRepositoryImpl.find_taken_phone_numbers(conn, &phone_numbers).await?.into_iter().collect();
+
+        let mut seen_emails = HashSet::new();
+        let mut seen_phone_numbers = HashSet::new();
+        let mut checked = Vec::with_capacity(file.rows.len());
+        for (row, email) in file.rows.iter().zip(emails) {
+            let mut errors = Vec::new();
+            let first_name = cell(row, Some(columns.first_name));
+            let last_name = cell(row, Some(columns.last_name));
+            let phone_number = cell(row, columns.phone_number);
+            let role = cell(row, columns.role);
+
+            match AuthValidator::validate_email(&email) {
+                Err(e) => errors.push(UserImportError::from_validation(e)),
+                Ok(()) if taken_emails.contains(&email) => {
+                    errors.push(UserImportError::new("email", "DUPLICATE", "Email already in use"));
+                }
+                Ok(()) if !seen_emails.insert(email.clone()) => {
+                    errors.push(UserImportError::new("email", "DUPLICATE_IN_FILE", "Email appears earlier in the file"));
+                }
+                Ok(()) => {}
+            }
+            for (field, name) in [("first_name", &first_name), ("last_name", &last_name)] {
+                if let Err(e) = AuthValidator::validate_name(field, name) {
+                    errors.push(UserImportError::from_validation(e));
+                }
+            }
+            if !phone_number.is_empty() {
+                match AuthValidator::validate_phone_number(&phone_number) {
+                    Err(e) => errors.push(UserImportError::from_validation(e)),
+                    Ok(()) if taken_phone_numbers.contains(&phone_number) => {
+                        errors.push(UserImportError::new("phone_number", "DUPLICATE", "Phone number already in use"));
+                    }
+                    Ok(()) if !seen_phone_numbers.insert(phone_number.clone()) => {
+                        errors.push(UserImportError::new(
+                            "phone_number",
+                            "DUPLICATE_IN_FILE",
+                            "Phone number appears earlier in the file",
+                        ));
+                    }
+                    Ok(()) => {}
+                }
+            }
+            let role = match parse_role(&role) {
+                Some(Role::Admin) if inviter.role != Role::Admin => {
+                    errors.push(UserImportError::new("role", "FORBIDDEN", "Only admins can import admins"));
+                    None
+                }
+                Some(role) => Some(role),
+                None => {
+                    errors.push(UserImportError::new("role", "UNKNOWN_ROLE", format!("Unknown role {}", role)));
+                    None
+                }
+            };
+
+            let new_user = match (errors.is_empty(), role) {
+                (true, Some(role)) => Some(NewUser {
+                    first_name,
+                    last_name,
+                    phone_number,
+                    role,
+                }),
+                _ => None,
+            };
+            checked.push((email, new_user, errors));
+        }
+
+        let importable = checked.iter().filter(|(_, new_user, _)| new_user.is_some()).count();
+        QuotaService::check_users(conn, inviter.org_id, importable).await?;
+        let organization = OrganizationRepositoryImpl.find_by_id(conn, inviter.org_id).await?;
+        // Nobody knows this password; imported users set their own from the
+        // invitation, so one hash serves the whole file
+        let password = User::hash_password(&Uuid::new_v4().to_string())?;
+        let inviter_name = format!("{} {}", inviter.first_name, inviter.last_name);
+        let mut rows = Vec::with_capacity(checked.len());
+        for (index, (email, new_user, errors)) in checked.into_iter().enumerate() {
+            let user_id = match new_user {
+                Some(new_user) => {
+                    let now = Utc::now();
+                    let user = UserRepositoryImpl
+                        .create(conn, &User {
+                            id: Uuid::new_v4(),
+                            first_name: new_user.first_name,
+                            last_name: new_user.last_name,
+                            email: email.clone(),
+                            phone_number: new_user.phone_number,
+                            password: password.clone(),
+                            role: new_user.role,
+                            email_verified: false,
+                            org_id: inviter.org_id,
+                            avatar_updated_at: None,
+                            is_active: true,
+                            supervisor_id: None,
+                            anonymized_at: None,
+                            created_at: now,
+                            updated_at: now,
+                            deleted_at: None,
+                        })
+                        .await?;
+
+                    let token = link_token();
+                    let reset = PasswordResetTokenRepositoryImpl
+                        .create_with_lifetime(conn, user.id, &hash_token(&token), Duration::days(INVITATION_DAYS))
+                        .await?;
+                    let data = InvitationEmail {
+                        organization: organization.name.clone(),
+                        inviter: inviter_name.clone(),
+                        accept_url: format!("{}/reset-password?token={}", config.email.app_url.trim_end_matches('/'), token),
+                        expires_at: reset.expires_at,
+                    };
+                    let message = config.mailer().compose(conn, Some(user.org_id), &user.email, &data).await?;
+                    email::enqueue(conn, &config.queue, &message).await?;
+                    ActivityFeed::record(conn, user.org_id, Some(inviter.id), FeedEvent::user_joined(&user, "import")).await;
+                    Some(user.id)
+                }
+                None => None,
+            };
+            rows.push(UserImportRow {
+                line: index + 2,
+                email,
+                user_id,
+                errors,
+            });
+        }
+
+        let created = rows.iter().filter(|row| row.user_id.is_some()).count();
+        info!(
+            org_id = %inviter.org_id,
+            imported_by = %inviter.id,
+            created,
+            failed = rows.len() - created,
+            "Users imported"
+        );
+        Ok(UserImportReport {
+            created,
+            failed: rows.len() - created,
+            rows,
+        })
+    }
+}
@@ -0,0 +1,29 @@
+//! Users managing their own accounts
+//!
+//! What a user may change about themselves: their name, phone number and
+//! avatar, and how they want measurements and times presented. Their
+//! email goes through a confirmed change, see
+//! [`AuthService::request_email_change`](super::AuthService::request_email_change),
+//! and their role and organization are for admins to change. Admins also
+//! add users in bulk from a CSV file, see [`UserImportService`], and
+//! deactivate them, see [`ActivationService`], anonymize them once they
+//! have left, see [`AnonymizationService`], and set who they report to, see
+//! [`SupervisorService`].
+
+mod activation;
+mod anonymization;
+mod avatar;
+mod image;
+mod import;
+mod preferences;
+mod profile;
+mod supervisor;
+
+pub use activation::ActivationService;
+pub use anonymization::AnonymizationService;
+pub use avatar::{AvatarLinks, AvatarService, AVATAR_SIZES, MAX_AVATAR_BYTES};
+pub use image::{decode_png, Image, ImageError};
+pub use import::{UserImportError, UserImportReport, UserImportRow, UserImportService, MAX_USER_IMPORT_BYTES, MAX_USER_IMPORT_ROWS};
+pub use preferences::{parse_timezone, timezone_name, PreferenceChanges, PreferenceService, Preferences, UnitSystem};
+pub use profile::ProfileService;
+pub use supervisor::SupervisorService;
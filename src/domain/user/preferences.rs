@@ -0,0 +1,271 @@
+use chrono::{FixedOffset, Utc};
+use diesel::PgConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::info;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::UserPreferences,
+        repositories::{
+            auth::UserRepositoryImpl,
+            PreferenceRepository, PreferenceRepositoryImpl, Repository,
+        },
+    },
+    domain::organization::OrganizationSettingsService,
+    error::{ApiError, ErrorContext, Result},
+    utils::i18n::Locale,
+};
+
+/// Cubic feet in a cubic metre
+const CUBIC_FEET_PER_M3: f64 = 35.314_666_7;
+
+/// Miles in a kilometre
+const MILES_PER_KM: f64 = 0.621_371_192;
+
+/// Units measurements are presented in; they are always stored metric
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum UnitSystem {
+    /// Cubic metres and kilometres
+    #[default]
+    Metric,
+    /// Cubic feet and miles
+    Imperial,
+}
+
+impl UnitSystem {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Metric => "metric",
+            Self::Imperial => "imperial",
+        }
+    }
+
+    /// Parses a stored unit system, `None` when unknown
+    pub fn parse(value: &str) -> Option<Self> {
+        [Self::Metric, Self::Imperial].into_iter().find(|units| units.as_str() == value)
+    }
+
+    /// A volume of `m3` cubic metres in these units
+    pub fn volume(self, m3: f64) -> f64 {
+        match self {
+            Self::Metric => m3,
+            Self::Imperial => m3 * CUBIC_FEET_PER_M3,
+        }
+    }
+
+    pub fn volume_unit(self) -> &'static str {
+        match self {
+            Self::Metric => "m³",
+            Self::Imperial => "ft³",
+        }
+    }
+
+    /// A distance of `km` kilometres in these units
+    pub fn distance(self, km: f64) -> f64 {
+        match self {
+            Self::Metric => km,
+            Self::Imperial => km * MILES_PER_KM,
+        }
+    }
+
+    pub fn distance_unit(self) -> &'static str {
+        match self {
+            Self::Metric => "km",
+            Self::Imperial => "mi",
+        }
+    }
+}
+
+/// Parses a timezone given as `UTC` or an offset from it, such as `-08:00`,
+/// `+0530` or `UTC+01:00`
+pub fn parse_timezone(value: &str) -> Option<FixedOffset> {
+    let value = value.trim();
+    let offset = value
+        .strip_prefix("UTC")
+        .or_else(|| value.strip_prefix("utc"))
+        .unwrap_or(value);
+    if offset.is_empty() || offset == "Z" {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, rest) = match offset.as_bytes().first()? {
+        b'+' => (1, &offset[1..]),
+        b'-' => (-1, &offset[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    if hours.is_empty() || hours.len() > 2 || minutes.len() > 2 {
+        return None;
+    }
+    let (hours, minutes) = (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?);
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// How a timezone is stored and shown: `UTC`, or its offset as `+HH:MM`
+pub fn timezone_name(offset: FixedOffset) -> String {
+    if offset.local_minus_utc() == 0 {
+        "UTC".to_string()
+    } else {
+        offset.to_string()
+    }
+}
+
+/// How a user wants measurements and times presented
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preferences {
+    pub units: UnitSystem,
+    pub timezone: FixedOffset,
+    pub locale: Locale,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            units: UnitSystem::default(),
+            timezone: FixedOffset::east_opt(0).expect("UTC is a valid offset"),
+            locale: Locale::default(),
+        }
+    }
+}
+
+impl Preferences {
+    /// The preferences saved as `stored`, with defaults for values no
+    /// longer understood
+    fn from_stored(stored: &UserPreferences) -> Self {
+        let defaults = Self::default();
+        Self {
+            units: UnitSystem::parse(&stored.units).unwrap_or(defaults.units),
+            timezone: parse_timezone(&stored.timezone).unwrap_or(defaults.timezone),
+            locale: stored.locale.parse().unwrap_or(defaults.locale),
+        }
+    }
+}
+
+/// Changes to a user's preferences; values left `None` are kept
+#[derive(Debug, Clone, Default)]
+pub struct PreferenceChanges {
+    pub units: Option<UnitSystem>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+}
+
+/// Reads and saves the preferences of the authenticated user
+pub struct PreferenceService;
+
+fn invalid(field: &str, code: &str, message: &str) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": field,
+            "code": code,
+        })),
+    )
+}
+
+impl PreferenceService {
+    /// The preferences of `user_id`, those of their organization when they
+    /// never saved any
+    pub async fn get(conn: &mut PgConnection, user_id: Uuid) -> Result<Preferences> {
+        match PreferenceRepositoryImpl.find(conn, user_id).await? {
+            Some(stored) => Ok(Preferences::from_stored(&stored)),
+            None => Self::organization_defaults(conn, user_id).await,
+        }
+    }
+
+    /// The units and timezone the organization of `user_id` works in
+    async fn organization_defaults(conn: &mut PgConnection, user_id: Uuid) -> Result<Preferences> {
+        let user = UserRepositoryImpl.find_by_id(conn, user_id).await?;
+        let settings = OrganizationSettingsService::of(conn, user.org_id).await?;
+        Ok(Preferences {
+            units: settings.default_units,
+            timezone: settings.timezone_offset(),
+            ..Preferences::default()
+        })
+    }
+
+    /// Changes the preferences of `user_id`
+    pub async fn update(conn: &mut PgConnection, user_id: Uuid, changes: PreferenceChanges) -> Result<Preferences> {
+        let existing = PreferenceRepositoryImpl.find(conn, user_id).await?;
+        let current = match &existing {
+            Some(stored) => Preferences::from_stored(stored),
+            None => Self::organization_defaults(conn, user_id).await?,
+        };
+
+        let timezone = match changes.timezone.as_deref() {
+            Some(timezone) => parse_timezone(timezone)
+                .ok_or_else(|| invalid("timezone", "INVALID_FORMAT", "Timezone must be UTC or an offset such as -08:00"))?,
+            None => current.timezone,
+        };
+        let locale = match changes.locale.as_deref() {
+            Some(locale) => locale
+                .trim()
+                .parse::<Locale>()
+                .map_err(|_| invalid("locale", "UNSUPPORTED", "Unsupported locale"))?,
+            None => current.locale,
+        };
+        let preferences = Preferences {
+            units: changes.units.unwrap_or(current.units),
+            timezone,
+            locale,
+        };
+
+        let now = Utc::now();
+        PreferenceRepositoryImpl
+            .save(conn, &UserPreferences {
+                user_id,
+                units: preferences.units.as_str().to_string(),
+                timezone: timezone_name(preferences.timezone),
+                locale: preferences.locale.code().to_string(),
+                created_at: existing.map_or(now, |existing| existing.created_at),
+                updated_at: now,
+            })
+            .await?;
+        info!(
+            user_id = %user_id,
+            units = preferences.units.as_str(),
+            timezone = %timezone_name(preferences.timezone),
+            locale = %preferences.locale,
+            "Preferences updated"
+        );
+        Ok(preferences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timezone() {
+        let offset = |seconds| FixedOffset::east_opt(seconds);
+        assert_eq!(parse_timezone("UTC"), offset(0));
+        assert_eq!(parse_timezone("-08:00"), offset(-8 * 3600));
+        assert_eq!(parse_timezone("+0530"), offset(5 * 3600 + 30 * 60));
+        assert_eq!(parse_timezone("UTC+1"), offset(3600));
+        for invalid in ["America/Vancouver", "+15:00", "+01:60", "08:00", "+"] {
+            assert_eq!(parse_timezone(invalid), None, "{}", invalid);
+        }
+
+        assert_eq!(timezone_name(offset(0).unwrap()), "UTC");
+        assert_eq!(timezone_name(offset(-8 * 3600).unwrap()), "-08:00");
+    }
+
+    #[test]
+    fn test_unit_conversion() {
+        assert_eq!(UnitSystem::Metric.volume(2.0), 2.0);
+        assert!((UnitSystem::Imperial.volume(1.0) - 35.3147).abs() < 1e-4);
+        assert!((UnitSystem::Imperial.distance(100.0) - 62.1371).abs() < 1e-4);
+    }
+}
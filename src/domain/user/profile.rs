@@ -0,0 +1,60 @@
+use diesel::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::auth::{ProfileChanges, User},
+        repositories::{auth::{UserRepository, UserRepositoryImpl}, Repository},
+    },
+    domain::auth::AuthValidator,
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+
+/// Reads and updates the profile of the authenticated user
+pub struct ProfileService;
+
+impl ProfileService {
+    /// The user `user_id`
+    pub async fn get(conn: &mut PgConnection, user_id: Uuid) -> Result<User> {
+        UserRepositoryImpl.find_by_id(conn, user_id).await
+    }
+
+    /// Changes the name or phone number of `user_id`
+    ///
+    /// Values are trimmed. An empty phone number removes it; one another
+    /// user has is a conflict.
+    pub async fn update(conn: &mut PgConnection, user_id: Uuid, changes: ProfileChanges) -> Result<User> {
+        let changes = ProfileChanges {
+            first_name: changes.first_name.map(|name| name.trim().to_string()),
+            last_name: changes.last_name.map(|name| name.trim().to_string()),
+            phone_number: changes.phone_number.map(|phone_number| phone_number.trim().to_string()),
+        };
+        if let Some(first_name) = &changes.first_name {
+            AuthValidator::validate_name("first_name", first_name)?;
+        }
+        if let Some(last_name) = &changes.last_name {
+            AuthValidator::validate_name("last_name", last_name)?;
+        }
+
+        let user_repo = UserRepositoryImpl;
+        if let Some(phone_number) = changes.phone_number.as_deref().filter(|phone_number| !phone_number.is_empty()) {
+            AuthValidator::validate_phone_number(phone_number)?;
+            let holder = user_repo.find_by_phone_number(conn, phone_number).await?;
+            if holder.is_some_and(|holder| holder.id != user_id) {
+                return Err(ApiError::new(
+                    ErrorCode::Conflict,
+                    "Phone number already in use",
+                    ErrorContext::new().with_details(serde_json::json!({ "field": "phone_number" })),
+                ));
+            }
+        }
+
+        if changes.is_empty() {
+            return user_repo.find_by_id(conn, user_id).await;
+        }
+        let user = user_repo.update_profile(conn, user_id, &changes).await?;
+        info!(user_id = %user.id, "Profile updated");
+        Ok(user)
+    }
+}
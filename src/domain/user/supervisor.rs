@@ -0,0 +1,107 @@
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::auth::{Role, User},
+        repositories::{
+            auth::{UserRepository, UserRepositoryImpl},
+            Repository,
+        },
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+
+/// Longest reporting line followed when looking for a loop
+const MAX_REPORTING_DEPTH: usize = 64;
+
+fn invalid_supervisor(code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::validation_with_context(
+        message,
+        ErrorContext::new().with_details(json!({
+            "field": "supervisor_id",
+            "code": code,
+        })),
+    )
+}
+
+/// Who the users of an organization report to
+///
+/// Supervisors are active managers and admins of the same organization. A
+/// user's supervisor is told of what concerns them, such as their
+/// certifications coming up for renewal, instead of every manager.
+pub struct SupervisorService;
+
+impl SupervisorService {
+    /// Makes `user_id` report to `supervisor_id`, or to no one
+    ///
+    /// Users can't report to themselves, nor to someone who reports to
+    /// them, directly or not.
+    pub async fn assign(
+        conn: &mut PgConnection,
+        admin: &User,
+        user_id: Uuid,
+        supervisor_id: Option<Uuid>,
+    ) -> Result<User> {
+        let user = Self::find_member(conn, admin, user_id).await?;
+        if let Some(supervisor_id) = supervisor_id {
+            if supervisor_id == user.id {
+                return Err(invalid_supervisor("SELF", "Users can't report to themselves"));
+            }
+            let supervisor = Self::find_member(conn, admin, supervisor_id)
+                .await
+                .map_err(|_| invalid_supervisor("NOT_FOUND", format!("User with id {} not found", supervisor_id)))?;
+            if supervisor.role < Role::Manager || !supervisor.is_active {
+                return Err(invalid_supervisor(
+                    "NOT_A_SUPERVISOR",
+                    "Supervisors must be active managers or admins",
+                ));
+            }
+            Self::check_no_loop(conn, &user, supervisor).await?;
+        }
+
+        let user = UserRepositoryImpl.set_supervisor(conn, user.id, supervisor_id).await?;
+        info!(user_id = %user.id, supervisor_id = ?supervisor_id, assigned_by = %admin.id, "Supervisor assigned");
+        Ok(user)
+    }
+
+    /// The users reporting directly to `supervisor_id`, a member of the
+    /// organization of `viewer`
+    pub async fn reports(conn: &mut PgConnection, viewer: &User, supervisor_id: Uuid) -> Result<Vec<User>> {
+        let supervisor = Self::find_member(conn, viewer, supervisor_id).await?;
+        UserRepositoryImpl.find_reports(conn, supervisor.id).await
+    }
+
+    /// Fails when `user` is somewhere up the reporting line of `supervisor`
+    async fn check_no_loop(conn: &mut PgConnection, user: &User, supervisor: User) -> Result<()> {
+        let mut current = supervisor;
+        for _ in 0..MAX_REPORTING_DEPTH {
+            let Some(next) = current.supervisor_id else { return Ok(()) };
+            if next == user.id {
+                return Err(invalid_supervisor(
+                    "REPORTING_LOOP",
+                    format!("{} {} already reports to {} {}", current.first_name, current.last_name, user.first_name, user.last_name),
+                ));
+            }
+            current = match UserRepositoryImpl.find_by_id(conn, next).await {
+                Ok(next) => next,
+                // A removed supervisor ends the line
+                Err(e) if e.code == ErrorCode::NotFound => return Ok(()),
+                Err(e) => return Err(e),
+            };
+        }
+        Err(invalid_supervisor("REPORTING_LOOP", "The reporting line is too long"))
+    }
+
+    /// The user `user_id` of the organization of `viewer`; users of other
+    /// organizations aren't found
+    async fn find_member(conn: &mut PgConnection, viewer: &User, user_id: Uuid) -> Result<User> {
+        let user = UserRepositoryImpl.find_by_id(conn, user_id).await?;
+        if user.org_id != viewer.org_id {
+            return Err(ApiError::not_found(format!("User with id {} not found", user_id)));
+        }
+        Ok(user)
+    }
+}
@@ -0,0 +1,11 @@
+//! Saved views
+//!
+//! Users save named filter, sort and column configurations of list
+//! endpoints, share them with their organization and pick, per resource,
+//! the view its list opens with.
+
+mod rules;
+mod service;
+
+pub use rules::{is_identifier, MAX_COLUMNS, MAX_SORT_FIELDS};
+pub use service::SavedViewService;
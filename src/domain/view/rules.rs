@@ -0,0 +1,105 @@
+use serde_json::{json, Map, Value};
+
+use crate::error::{ApiError, Result};
+
+/// Most fields a view can sort by
+pub const MAX_SORT_FIELDS: usize = 10;
+
+/// Most columns a view can show
+pub const MAX_COLUMNS: usize = 100;
+
+/// Whether `value` names a resource or a field: lowercase letters, digits
+/// and underscores, starting with a letter
+pub fn is_identifier(value: &str) -> bool {
+    (1..=50).contains(&value.len())
+        && value.starts_with(|c: char| c.is_ascii_lowercase())
+        && value.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// The filters of a view, an object of query parameters; no filters is an
+/// empty object
+pub fn filters(value: Value) -> Result<Value> {
+    match value {
+        Value::Null => Ok(Value::Object(Map::new())),
+        Value::Object(filters) => Ok(Value::Object(filters)),
+        _ => Err(ApiError::validation("View filters must be an object of query parameters", None)),
+    }
+}
+
+/// The sort of a view, fields with `-` for descending, each at most once
+pub fn sort(fields: Vec<String>) -> Result<Vec<String>> {
+    if fields.len() > MAX_SORT_FIELDS {
+        return Err(ApiError::validation(format!("A view can sort by at most {} fields", MAX_SORT_FIELDS), None));
+    }
+    let mut sorted: Vec<String> = Vec::with_capacity(fields.len());
+    for field in fields {
+        let field = field.trim().to_string();
+        let name = field.strip_prefix('-').unwrap_or(&field);
+        if !is_identifier(name) {
+            return Err(ApiError::validation(format!("Invalid sort field {}", field), None));
+        }
+        if sorted.iter().any(|sorted| sorted.trim_start_matches('-') == name) {
+            return Err(ApiError::validation(format!("The view sorts by {} twice", name), None));
+        }
+        sorted.push(field);
+    }
+    Ok(sorted)
+}
+
+/// The columns of a view, each at most once
+pub fn columns(names: Vec<String>) -> Result<Vec<String>> {
+    if names.len() > MAX_COLUMNS {
+        return Err(ApiError::validation(format!("A view can show at most {} columns", MAX_COLUMNS), None));
+    }
+    let mut columns: Vec<String> = Vec::with_capacity(names.len());
+    for name in names {
+        let name = name.trim().to_string();
+        if !is_identifier(&name) {
+            return Err(ApiError::validation(format!("Invalid column {}", name), None));
+        }
+        if columns.contains(&name) {
+            return Err(ApiError::validation(
+                format!("The view shows {} twice", name),
+                Some(json!({ "column": name })),
+            ));
+        }
+        columns.push(name);
+    }
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert!(is_identifier("supply_contracts"));
+        assert!(is_identifier("volume_m3"));
+        assert!(!is_identifier(""));
+        assert!(!is_identifier("_name"));
+        assert!(!is_identifier("Name"));
+        assert!(!is_identifier("name; drop"));
+    }
+
+    #[test]
+    fn test_filters_are_an_object() {
+        assert_eq!(filters(Value::Null).unwrap(), json!({}));
+        assert_eq!(filters(json!({ "status": "active" })).unwrap(), json!({ "status": "active" }));
+        assert!(filters(json!(["status"])).is_err());
+    }
+
+    #[test]
+    fn test_sort_and_columns() {
+        assert_eq!(sort(strings(&["-updated_at", " name "])).unwrap(), strings(&["-updated_at", "name"]));
+        assert!(sort(strings(&["name", "-name"])).is_err());
+        assert!(sort(strings(&["--name"])).is_err());
+
+        assert_eq!(columns(strings(&["name", "email"])).unwrap(), strings(&["name", "email"]));
+        assert!(columns(strings(&["name", "name"])).is_err());
+    }
+}
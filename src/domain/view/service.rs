@@ -0,0 +1,213 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate as ValidatorValidate;
+
+use super::rules::{columns, filters, is_identifier, sort};
+use crate::{
+    api::resources::view::dto::SaveViewInput,
+    db::{
+        models::{SavedView, SavedViewDefault},
+        repositories::SavedViewRepository,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+};
+
+/// Service for users' saved views and default views
+pub struct SavedViewService<R: SavedViewRepository + Send + Sync> {
+    repository: R,
+}
+
+fn invalid_input(e: validator::ValidationErrors) -> ApiError {
+    ApiError::validation_with_context(
+        "Invalid input",
+        ErrorContext::new()
+            .with_message_key("INVALID_INPUT")
+            .with_details(json!(e))
+    )
+}
+
+fn resource(value: &str) -> Result<&str> {
+    if is_identifier(value) {
+        Ok(value)
+    } else {
+        Err(ApiError::validation(format!("Invalid resource {}", value), None))
+    }
+}
+
+fn not_found(view_id: Uuid) -> ApiError {
+    ApiError::not_found(format!("View with id {} not found", view_id))
+}
+
+impl<R: SavedViewRepository + Send + Sync> SavedViewService<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    fn view(org_id: Uuid, user_id: Uuid, input: SaveViewInput) -> Result<SavedView> {
+        ValidatorValidate::validate(&input).map_err(invalid_input)?;
+        let name = input.name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiError::validation("A view name is required", None));
+        }
+        let now = Utc::now();
+        Ok(SavedView {
+            id: Uuid::new_v4(),
+            org_id,
+            user_id,
+            resource: resource(input.resource.trim())?.to_string(),
+            name,
+            filters: filters(input.filters)?,
+            sort: sort(input.sort)?,
+            columns: columns(input.columns)?,
+            shared: input.shared,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// A view the user owns or that is shared with them
+    async fn visible(&self, conn: &mut PgConnection, org_id: Uuid, user_id: Uuid, view_id: Uuid) -> Result<SavedView> {
+        let view = self.repository.find(conn, org_id, view_id).await?;
+        if view.user_id != user_id && !view.shared {
+            return Err(not_found(view_id));
+        }
+        Ok(view)
+    }
+
+    /// A view the user owns, the only ones they can change
+    async fn owned(&self, conn: &mut PgConnection, org_id: Uuid, user_id: Uuid, view_id: Uuid) -> Result<SavedView> {
+        let view = self.visible(conn, org_id, user_id, view_id).await?;
+        if view.user_id != user_id {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Only the user who saved a view can change it",
+                ErrorContext::new(),
+            ));
+        }
+        Ok(view)
+    }
+
+    /// Saves a view
+    pub async fn create(&self, conn: &mut PgConnection, org_id: Uuid, user_id: Uuid, input: SaveViewInput) -> Result<SavedView> {
+        let view = Self::view(org_id, user_id, input)?;
+        let view = self.repository.create(conn, &view).await?;
+        info!(view_id = %view.id, user_id = %user_id, "Saved {} view '{}'", view.resource, view.name);
+        Ok(view)
+    }
+
+    /// Replaces the configuration of one of the user's views; its resource
+    /// cannot change
+    pub async fn update(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        user_id: Uuid,
+        view_id: Uuid,
+        input: SaveViewInput,
+    ) -> Result<SavedView> {
+        let existing = self.owned(conn, org_id, user_id, view_id).await?;
+        let view = Self::view(org_id, user_id, input)?;
+        if view.resource != existing.resource {
+            return Err(ApiError::validation(
+                "A view's resource cannot be changed",
+                Some(json!({ "resource": existing.resource })),
+            ));
+        }
+        let view = SavedView {
+            id: existing.id,
+            created_at: existing.created_at,
+            ..view
+        };
+        self.repository.update(conn, org_id, &view).await
+    }
+
+    /// Deletes one of the user's views
+    pub async fn delete(&self, conn: &mut PgConnection, org_id: Uuid, user_id: Uuid, view_id: Uuid) -> Result<()> {
+        let view = self.owned(conn, org_id, user_id, view_id).await?;
+        self.repository.delete(conn, org_id, view.id).await?;
+        info!(view_id = %view.id, user_id = %user_id, "Deleted {} view '{}'", view.resource, view.name);
+        Ok(())
+    }
+
+    /// Gets a view the user owns or that is shared with them
+    pub async fn get(&self, conn: &mut PgConnection, org_id: Uuid, user_id: Uuid, view_id: Uuid) -> Result<(SavedView, bool)> {
+        let view = self.visible(conn, org_id, user_id, view_id).await?;
+        let is_default = self
+            .repository
+            .defaults(conn, user_id, Some(&view.resource))
+            .await?
+            .iter()
+            .any(|default| default.view_id == view.id);
+        Ok((view, is_default))
+    }
+
+    /// Lists the views the user owns or that are shared with them, each
+    /// with whether it is their default
+    pub async fn list(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        user_id: Uuid,
+        resource_name: Option<&str>,
+    ) -> Result<Vec<(SavedView, bool)>> {
+        let resource_name = resource_name.map(resource).transpose()?;
+        let views = self.repository.visible(conn, org_id, user_id, resource_name).await?;
+        let defaults = self
+            .repository
+            .defaults(conn, user_id, resource_name)
+            .await?
+            .into_iter()
+            .map(|default| default.view_id)
+            .collect::<HashSet<_>>();
+        Ok(views
+            .into_iter()
+            .map(|view| {
+                let is_default = defaults.contains(&view.id);
+                (view, is_default)
+            })
+            .collect())
+    }
+
+    /// Makes a view the one the user opens its resource's list with
+    pub async fn set_default(&self, conn: &mut PgConnection, org_id: Uuid, user_id: Uuid, view_id: Uuid) -> Result<SavedView> {
+        let view = self.visible(conn, org_id, user_id, view_id).await?;
+        self.repository
+            .set_default(conn, &SavedViewDefault {
+                user_id,
+                resource: view.resource.clone(),
+                view_id: view.id,
+                updated_at: Utc::now(),
+            })
+            .await?;
+        Ok(view)
+    }
+
+    /// The view the user opens a resource's list with, if any
+    pub async fn default_view(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        user_id: Uuid,
+        resource_name: &str,
+    ) -> Result<Option<SavedView>> {
+        let resource_name = resource(resource_name)?;
+        let Some(default) = self.repository.defaults(conn, user_id, Some(resource_name)).await?.into_iter().next() else {
+            return Ok(None);
+        };
+        self.repository.find(conn, org_id, default.view_id).await.map(Some)
+    }
+
+    /// Makes the user's list of a resource open unfiltered again
+    pub async fn clear_default(&self, conn: &mut PgConnection, user_id: Uuid, resource_name: &str) -> Result<()> {
+        let resource_name = resource(resource_name)?;
+        if !self.repository.clear_default(conn, user_id, resource_name).await? {
+            return Err(ApiError::not_found(format!("No default {} view", resource_name)));
+        }
+        Ok(())
+    }
+}
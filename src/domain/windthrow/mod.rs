@@ -0,0 +1,11 @@
+//! Windthrow risk
+//!
+//! Scores how likely a stand is to be blown down in a storm from its
+//! height, stocking density, soil and topographic exposure, and whether a
+//! fresh harvest edge opens it to the wind. Scores range from 0 to 100 and
+//! can weigh stands in harvest scheduling, either to take at-risk stands
+//! first or to avoid cutting next to them.
+
+mod model;
+
+pub use model::{score, Exposure, RiskClass, RiskFactors, SoilRooting, StandConditions, WindthrowRisk};
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// How deeply the soil lets trees root
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SoilRooting {
+    /// Deep, well-drained soils
+    Deep,
+    /// Moderately drained soils or a restricting layer below a metre
+    Moderate,
+    /// Wet, poorly drained soils that keep roots near the surface
+    PoorlyDrained,
+    /// Shallow soils over bedrock or a hardpan
+    Shallow,
+}
+
+impl SoilRooting {
+    fn factor(self) -> f64 {
+        match self {
+            Self::Deep => 0.0,
+            Self::Moderate => 0.35,
+            Self::PoorlyDrained => 0.75,
+            Self::Shallow => 1.0,
+        }
+    }
+}
+
+/// Topographic exposure to prevailing and storm winds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Exposure {
+    /// Valley bottoms and lee slopes
+    Sheltered,
+    /// Mid slopes
+    Moderate,
+    /// Upper slopes and plateaus
+    Exposed,
+    /// Ridges, hilltops and coastal fronts
+    VeryExposed,
+}
+
+impl Exposure {
+    fn factor(self) -> f64 {
+        match self {
+            Self::Sheltered => 0.0,
+            Self::Moderate => 0.35,
+            Self::Exposed => 0.7,
+            Self::VeryExposed => 1.0,
+        }
+    }
+}
+
+/// What the risk of a stand is computed from, as recorded in its inventory
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct StandConditions {
+    /// Mean height of the dominant trees, in metres
+    pub mean_height_m: f64,
+    /// Stocking density, in stems per hectare
+    pub stems_per_ha: f64,
+    pub soil: SoilRooting,
+    pub exposure: Exposure,
+    /// Whether the stand borders an opening cut since it grew up, so its
+    /// trees face wind they are not adapted to
+    #[serde(default)]
+    pub new_edge: bool,
+}
+
+/// Risk classes, by score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskClass {
+    /// Below 25
+    Low,
+    /// 25 to 50
+    Moderate,
+    /// 50 to 75
+    High,
+    /// 75 and above
+    Severe,
+}
+
+/// Each factor's share of a stand's risk, from 0 (none) to 1 (worst)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RiskFactors {
+    pub height: f64,
+    pub density: f64,
+    pub soil: f64,
+    pub exposure: f64,
+}
+
+/// Windthrow risk of a stand
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindthrowRisk {
+    /// From 0 to 100
+    pub score: f64,
+    pub class: RiskClass,
+    pub factors: RiskFactors,
+}
+
+/// Weights of the factors; they sum to one
+const HEIGHT_WEIGHT: f64 = 0.3;
+const DENSITY_WEIGHT: f64 = 0.15;
+const SOIL_WEIGHT: f64 = 0.25;
+const EXPOSURE_WEIGHT: f64 = 0.3;
+
+/// Risk multiplier for stands on a new edge
+const NEW_EDGE_MULTIPLIER: f64 = 1.3;
+
+/// Stands below this height are rarely blown down
+const LOW_HEIGHT_M: f64 = 10.0;
+/// Stands above this height carry the full height risk
+const TALL_HEIGHT_M: f64 = 30.0;
+
+/// Crowded stands above this density grow slender, shallow-rooted trees
+const DENSE_STEMS_PER_HA: f64 = 600.0;
+/// Stands above this density carry the full density risk
+const OVERSTOCKED_STEMS_PER_HA: f64 = 2000.0;
+
+fn ramp(value: f64, low: f64, high: f64) -> f64 {
+    if value.is_nan() {
+        return 0.0;
+    }
+    ((value - low) / (high - low)).clamp(0.0, 1.0)
+}
+
+/// Scores the windthrow risk of a stand
+///
+/// Each factor ramps from 0 to 1 and the weighted sum is scaled to 100;
+/// a new edge multiplies the result, capped at 100.
+pub fn score(conditions: &StandConditions) -> WindthrowRisk {
+    let factors = RiskFactors {
+        height: ramp(conditions.mean_height_m, LOW_HEIGHT_M, TALL_HEIGHT_M),
+        density: ramp(conditions.stems_per_ha, DENSE_STEMS_PER_HA, OVERSTOCKED_STEMS_PER_HA),
+        soil: conditions.soil.factor(),
+        exposure: conditions.exposure.factor(),
+    };
+    let mut score = 100.0
+        * (HEIGHT_WEIGHT * factors.height
+            + DENSITY_WEIGHT * factors.density
+            + SOIL_WEIGHT * factors.soil
+            + EXPOSURE_WEIGHT * factors.exposure);
+    if conditions.new_edge {
+        score = (score * NEW_EDGE_MULTIPLIER).min(100.0);
+    }
+    let score = (score * 10.0).round() / 10.0;
+    let class = match score {
+        score if score < 25.0 => RiskClass::Low,
+        score if score < 50.0 => RiskClass::Moderate,
+        score if score < 75.0 => RiskClass::High,
+        _ => RiskClass::Severe,
+    };
+
+    WindthrowRisk { score, class, factors }
+}
+
+impl WindthrowRisk {
+    /// Term to add to a stand's harvest scheduling objective
+    ///
+    /// A positive weight favours harvesting at-risk stands before storms
+    /// take them; a negative weight penalises them, e.g. to avoid cutting
+    /// blocks that would leave them on a new edge.
+    pub fn scheduling_term(&self, weight: f64) -> f64 {
+        weight * self.score / 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stand(mean_height_m: f64, soil: SoilRooting, exposure: Exposure) -> StandConditions {
+        StandConditions {
+            mean_height_m,
+            stems_per_ha: 500.0,
+            soil,
+            exposure,
+            new_edge: false,
+        }
+    }
+
+    #[test]
+    fn test_score_combines_factors() {
+        let young = score(&stand(8.0, SoilRooting::Deep, Exposure::Sheltered));
+        assert_eq!((young.score, young.class), (0.0, RiskClass::Low));
+
+        let exposed = score(&stand(20.0, SoilRooting::Moderate, Exposure::Exposed));
+        assert_eq!(exposed.factors.height, 0.5);
+        assert_eq!((exposed.score, exposed.class), (44.8, RiskClass::Moderate));
+
+        let ridge = score(&StandConditions {
+            stems_per_ha: 2400.0,
+            ..stand(32.0, SoilRooting::Shallow, Exposure::VeryExposed)
+        });
+        assert_eq!((ridge.score, ridge.class), (100.0, RiskClass::Severe));
+    }
+
+    #[test]
+    fn test_new_edge_raises_risk() {
+        let interior = stand(25.0, SoilRooting::PoorlyDrained, Exposure::Moderate);
+        let edge = StandConditions { new_edge: true, ..interior };
+        assert_eq!(score(&interior).score, 51.7);
+        assert_eq!(score(&edge).score, 67.3);
+        assert_eq!(score(&edge).class, RiskClass::High);
+    }
+
+    #[test]
+    fn test_scheduling_term() {
+        let risk = score(&stand(20.0, SoilRooting::Moderate, Exposure::Exposed));
+        assert!((risk.scheduling_term(2.0) - 0.896).abs() < 1e-9);
+        assert!((risk.scheduling_term(-1.0) + 0.448).abs() < 1e-9);
+    }
+}
@@ -1,6 +1,6 @@
 use super::{ErrorCode, ErrorContext};
-use crate::api::utils::ErrorResponse;
-use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use crate::{api::utils::ErrorResponse, utils::i18n::Locale};
+use actix_web::{http::{header, StatusCode}, HttpResponse, ResponseError};
 use serde::Serialize;
 use std::fmt;
 use std::error::Error as StdError;
@@ -11,7 +11,9 @@ pub struct ApiError {
     pub code: ErrorCode,
     pub message: String,
     #[serde(flatten)]
-    pub context: ErrorContext,
+    pub context: Box<ErrorContext>,
+    #[serde(skip)]
+    retry_after: Option<u64>,
     #[serde(skip)]
     source: Option<Box<dyn StdError + Send + Sync>>,
 }
@@ -22,11 +24,28 @@ impl ApiError {
         Self {
             code,
             message: message.into(),
-            context,
+            context: Box::new(context),
+            retry_after: None,
             source: None,
         }
     }
 
+    /// Overrides the `Retry-After` delay advertised for this error
+    pub fn with_retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = Some(seconds);
+        self
+    }
+
+    /// Returns true if the client may retry the request later
+    pub fn is_retryable(&self) -> bool {
+        self.code.is_retryable()
+    }
+
+    /// Returns the delay in seconds clients should wait before retrying
+    pub fn retry_after(&self) -> Option<u64> {
+        self.retry_after.or_else(|| self.code.default_retry_after())
+    }
+
     pub fn with_source<E>(mut self, error: E) -> Self 
     where 
         E: StdError + Send + Sync + 'static 
@@ -94,6 +113,38 @@ impl ApiError {
         error
     }
 
+    /// Creates a rate limit error telling the client when to retry
+    pub fn rate_limited(message: impl Into<String>, retry_after: u64) -> Self {
+        Self::new(
+            ErrorCode::RateLimitExceeded,
+            message,
+            ErrorContext::default()
+        ).with_retry_after(retry_after)
+    }
+
+    /// Creates an error for a request that would take an organization past
+    /// its `limit` of `quota`
+    pub fn quota_exceeded(quota: &str, limit: i64, usage: i64) -> Self {
+        Self::new(
+            ErrorCode::QuotaExceeded,
+            format!("The organization's plan allows at most {} {}", limit, quota.replace('_', " ")),
+            ErrorContext::new().with_details(serde_json::json!({
+                "quota": quota,
+                "limit": limit,
+                "usage": usage,
+            }))
+        )
+    }
+
+    /// Creates an error for a feature whose `dependency` is down
+    pub fn dependency_unavailable(dependency: &str) -> Self {
+        Self::new(
+            ErrorCode::DependencyUnavailable,
+            format!("Temporarily unavailable, {} is down", dependency),
+            ErrorContext::new().with_details(serde_json::json!({ "dependency": dependency }))
+        )
+    }
+
     /// Creates an unauthorized error
     pub fn unauthorized(message: impl Into<String>) -> Self {
         Self::new(
@@ -104,6 +155,74 @@ impl ApiError {
     }
 }
 
+impl ApiError {
+    /// Builds the error response with the message translated to `locale`
+    ///
+    /// Only validation errors are localized; other errors and messages
+    /// missing from the catalog are returned in English.
+    pub fn localized_response(&self, locale: Locale) -> HttpResponse {
+        let (message, details) = match self.localize(locale) {
+            Some(localized) => localized,
+            None => return self.error_response(),
+        };
+        let mut response = self.build_response(message, details);
+        response.headers_mut().insert(
+            header::CONTENT_LANGUAGE,
+            header::HeaderValue::from_static(locale.code()),
+        );
+        response
+    }
+
+    fn localize(&self, locale: Locale) -> Option<(String, Option<serde_json::Value>)> {
+        if self.code != ErrorCode::ValidationError || locale == Locale::default() {
+            return None;
+        }
+        let key = self.context.message_key()?;
+        let mut details = self.context.details.clone().unwrap_or_default();
+
+        let field_key = details
+            .get("field")
+            .and_then(|field| field.as_str())
+            .map(|field| format!("{}.{}", field, key));
+        let keys: Vec<&str> = field_key.iter().map(String::as_str).chain([key]).collect();
+        let message = locale.translate(&keys, &details)?;
+
+        // Per-field errors reported by the validator crate
+        if let Some(fields) = details.as_object_mut() {
+            for error in fields.values_mut().filter_map(|errors| errors.as_array_mut()).flatten() {
+                let translated = error
+                    .get("code")
+                    .and_then(|code| code.as_str())
+                    .and_then(|code| {
+                        let params = error.get("params").cloned().unwrap_or_default();
+                        locale.translate(&[&format!("validator.{}", code)], &params)
+                    });
+                if let (Some(translated), Some(error)) = (translated, error.as_object_mut()) {
+                    error.insert("message".to_string(), translated.into());
+                }
+            }
+        }
+
+        Some((message, self.context.details.as_ref().map(|_| details)))
+    }
+
+    fn build_response(&self, message: String, details: Option<serde_json::Value>) -> HttpResponse {
+        let error_response = ErrorResponse {
+            code: self.code.to_string(),
+            message,
+            details,
+            retryable: self.is_retryable(),
+            retry_after: self.retry_after(),
+        };
+
+        let mut response = HttpResponse::build(self.status_code());
+        if let Some(retry_after) = error_response.retry_after {
+            response.insert_header((header::RETRY_AFTER, retry_after));
+        }
+        response.json(error_response)
+    }
+}
+
 impl fmt::Display for ApiError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}: {}", self.code, self.message)
@@ -120,7 +239,8 @@ impl ResponseError for ApiError {
     fn error_response(&self) -> HttpResponse {
         // Only log server errors and external service errors
         match self.code {
-            ErrorCode::InternalError | ErrorCode::DatabaseError | ErrorCode::ConfigurationError => {
+            ErrorCode::InternalError | ErrorCode::DatabaseError | ErrorCode::ConfigurationError
+            | ErrorCode::ConnectionPoolError => {
                 error!(
                     error_code = %self.code,
                     error_message = %self.message,
@@ -128,7 +248,7 @@ impl ResponseError for ApiError {
                     "Server error occurred"
                 );
             }
-            ErrorCode::BadGateway | ErrorCode::ServiceUnavailable => {
+            ErrorCode::BadGateway | ErrorCode::ServiceUnavailable | ErrorCode::DependencyUnavailable => {
                 warn!(
                     error_code = %self.code,
                     error_message = %self.message,
@@ -139,27 +259,23 @@ impl ResponseError for ApiError {
             _ => {}
         }
 
-        let error_response = ErrorResponse {
-            code: self.code.to_string(),
-            message: self.message.clone(),
-            details: self.context.details.clone(),
-        };
-
-        HttpResponse::build(self.status_code())
-            .json(error_response)
+        self.build_response(self.message.clone(), self.context.details.clone())
     }
 
     fn status_code(&self) -> StatusCode {
         match self.code {
             ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
-            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::Forbidden | ErrorCode::QuotaExceeded => StatusCode::FORBIDDEN,
             ErrorCode::NotFound => StatusCode::NOT_FOUND,
-            ErrorCode::Conflict => StatusCode::CONFLICT,
+            ErrorCode::Conflict | ErrorCode::LockConflict => StatusCode::CONFLICT,
             ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
             ErrorCode::UnprocessableEntity => StatusCode::UNPROCESSABLE_ENTITY,
             ErrorCode::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::SubscriptionRequired => StatusCode::PAYMENT_REQUIRED,
             ErrorCode::BadGateway => StatusCode::BAD_GATEWAY,
-            ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::ServiceUnavailable | ErrorCode::DependencyUnavailable | ErrorCode::ConnectionPoolError => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
             ErrorCode::RequestTimeout => StatusCode::REQUEST_TIMEOUT,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
@@ -182,3 +298,79 @@ impl From<std::io::Error> for ApiError {
         api_error
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+
+    #[actix_web::test]
+    async fn test_retryable_error_response() {
+        let response = ApiError::rate_limited("Too many requests", 42).error_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "42");
+
+        let body: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap();
+        assert_eq!(body["retryable"], true);
+        assert_eq!(body["retry_after"], 42);
+    }
+
+    #[actix_web::test]
+    async fn test_terminal_error_response() {
+        let response = ApiError::not_found("Missing").error_response();
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
+
+        let body: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap();
+        assert_eq!(body["retryable"], false);
+        assert!(body.get("retry_after").is_none());
+    }
+
+    #[actix_web::test]
+    async fn test_localized_response() {
+        let error = ApiError::validation_with_context(
+            "Password too short",
+            ErrorContext::new().with_details(serde_json::json!({
+                "field": "password",
+                "code": "TOO_SHORT",
+                "min_length": 8
+            }))
+        );
+
+        let response = error.localized_response(Locale::Fr);
+        assert_eq!(response.headers().get(header::CONTENT_LANGUAGE).unwrap(), "fr");
+        let body: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap();
+        assert_eq!(body["message"], "Le mot de passe doit contenir au moins 8 caractères");
+        assert_eq!(body["details"]["code"], "TOO_SHORT");
+
+        let response = error.localized_response(Locale::En);
+        assert!(response.headers().get(header::CONTENT_LANGUAGE).is_none());
+        let body: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap();
+        assert_eq!(body["message"], "Password too short");
+    }
+
+    #[actix_web::test]
+    async fn test_localized_validator_errors() {
+        let error = ApiError::validation_with_context(
+            "Invalid input",
+            ErrorContext::new()
+                .with_message_key("INVALID_INPUT")
+                .with_details(serde_json::json!({
+                    "name": [{ "code": "length", "message": null, "params": { "min": 1, "max": 255, "value": "" } }]
+                }))
+        );
+
+        let response = error.localized_response(Locale::Fr);
+        let body: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap();
+        assert_eq!(body["message"], "Données invalides");
+        assert_eq!(body["details"]["name"][0]["message"], "Doit contenir entre 1 et 255 caractères");
+    }
+
+    #[test]
+    fn test_default_retry_after() {
+        let error = ApiError::new(ErrorCode::ConnectionPoolError, "Pool exhausted", ErrorContext::default());
+        assert_eq!(error.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(error.retry_after(), Some(5));
+        assert!(ApiError::new(ErrorCode::LockConflict, "Deadlock", ErrorContext::default()).is_retryable());
+        assert!(!ApiError::new(ErrorCode::Conflict, "Duplicate", ErrorContext::default()).is_retryable());
+    }
+}
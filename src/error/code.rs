@@ -9,6 +9,11 @@
 //! - Organized into logical categories (auth, validation, infrastructure, etc.)
 //! - Map cleanly to standard HTTP status codes
 //! - Support proper error handling and recovery
+//!
+//! Codes are also classified as retryable or terminal. Retryable codes
+//! describe transient conditions (rate limits, lock contention, pool
+//! exhaustion, upstream outages) where repeating the same request later can
+//! succeed; clients should back off and retry those and give up on the rest.
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -44,6 +49,8 @@ pub enum ErrorCode {
     DatabaseError,
     /// Failed to get connection from pool
     ConnectionPoolError,
+    /// Concurrent transaction held a conflicting lock (deadlock, serialization failure)
+    LockConflict,
     /// Application configuration error
     ConfigurationError,
     /// File system or network I/O error
@@ -52,12 +59,18 @@ pub enum ErrorCode {
     // Rate limiting
     /// Too many requests from client
     RateLimitExceeded,
+    /// The organization's plan doesn't allow more of a resource
+    QuotaExceeded,
+    /// The feature needs an active subscription the organization doesn't have
+    SubscriptionRequired,
     
     // External services
     /// Upstream service returned an error
     BadGateway,
     /// Service temporarily unavailable
     ServiceUnavailable,
+    /// A dependency the feature needs (storage, email, ...) is down
+    DependencyUnavailable,
     /// Request timed out
     RequestTimeout,
     
@@ -66,6 +79,36 @@ pub enum ErrorCode {
     InternalError,
 }
 
+impl ErrorCode {
+    /// Returns true if the same request may succeed when retried later
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::ConnectionPoolError
+                | Self::LockConflict
+                | Self::RateLimitExceeded
+                | Self::BadGateway
+                | Self::ServiceUnavailable
+                | Self::DependencyUnavailable
+                | Self::RequestTimeout
+        )
+    }
+
+    /// Default `Retry-After` delay in seconds for codes that advertise one
+    ///
+    /// Rate limits usually know the exact remaining window and override this
+    /// through `ApiError::with_retry_after`.
+    pub fn default_retry_after(&self) -> Option<u64> {
+        match self {
+            Self::RateLimitExceeded => Some(60),
+            Self::ConnectionPoolError => Some(5),
+            Self::LockConflict => Some(1),
+            Self::DependencyUnavailable => Some(30),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for ErrorCode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Convert enum variant to string, replacing underscores with spaces
@@ -1,7 +1,7 @@
 use crate::error::{ApiError, ErrorCode, ErrorContext};
 use serde::Serialize;
 use std::fmt;
-use diesel::result::Error as DieselError;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 
 #[derive(Debug, Serialize)]
 pub enum DatabaseError {
@@ -10,6 +10,7 @@ pub enum DatabaseError {
     RecordNotFound(String),
     UniqueViolation(String),
     TransactionFailed(String),
+    LockConflict(String),
     PoolError(String),
 }
 
@@ -21,6 +22,7 @@ impl fmt::Display for DatabaseError {
             Self::RecordNotFound(msg) => write!(f, "Record not found: {}", msg),
             Self::UniqueViolation(msg) => write!(f, "Unique constraint violation: {}", msg),
             Self::TransactionFailed(msg) => write!(f, "Transaction failed: {}", msg),
+            Self::LockConflict(msg) => write!(f, "Lock conflict: {}", msg),
             Self::PoolError(msg) => write!(f, "Connection pool error: {}", msg),
         }
     }
@@ -37,6 +39,8 @@ impl From<DatabaseError> for ApiError {
                 (ErrorCode::NotFound, error.to_string()),
             DatabaseError::UniqueViolation(_) => 
                 (ErrorCode::Conflict, error.to_string()),
+            DatabaseError::LockConflict(_) => 
+                (ErrorCode::LockConflict, error.to_string()),
         };
 
         ApiError::new(
@@ -55,6 +59,8 @@ impl From<DieselError> for DatabaseError {
     fn from(error: DieselError) -> Self {
         match error {
             DieselError::NotFound => DatabaseError::RecordNotFound(error.to_string()),
+            DieselError::DatabaseError(DatabaseErrorKind::SerializationFailure, info) => DatabaseError::LockConflict(info.message().to_string()),
+            DieselError::DatabaseError(_, info) if is_lock_conflict(info.message()) => DatabaseError::LockConflict(info.message().to_string()),
             DieselError::DatabaseError(_, info) => DatabaseError::QueryFailed(info.message().to_string()),
             DieselError::RollbackTransaction => DatabaseError::TransactionFailed(error.to_string()),
             DieselError::AlreadyInTransaction => DatabaseError::TransactionFailed("Already in transaction".to_string()),
@@ -62,4 +68,12 @@ impl From<DieselError> for DatabaseError {
             _ => DatabaseError::QueryFailed(error.to_string()),
         }
     }
-} 
\ No newline at end of file
+}
+
+/// Detects lock contention Diesel does not classify, such as deadlocks and
+/// `NOWAIT`/`lock_timeout` failures
+fn is_lock_conflict(message: &str) -> bool {
+    message.starts_with("deadlock detected")
+        || message.starts_with("could not obtain lock")
+        || message.starts_with("canceling statement due to lock timeout")
+}
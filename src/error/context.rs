@@ -1,6 +1,9 @@
 use serde::Serialize;
 use std::collections::HashMap;
 
+/// Metadata key holding the message catalog key of an error
+const MESSAGE_KEY: &str = "message_key";
+
 #[derive(Debug, Default, Serialize)]
 pub struct ErrorContext {
     #[serde(skip_serializing_if = "HashMap::is_empty")]
@@ -27,6 +30,21 @@ impl ErrorContext {
         self
     }
 
+    /// Key the error message in the message catalog
+    ///
+    /// Without an explicit key, the `code` of the details is used.
+    pub fn with_message_key(self, key: impl Into<String>) -> Self {
+        self.with_metadata(MESSAGE_KEY, key)
+    }
+
+    /// Returns the message catalog key of the error, if any
+    pub fn message_key(&self) -> Option<&str> {
+        self.metadata
+            .get(MESSAGE_KEY)
+            .map(String::as_str)
+            .or_else(|| self.details.as_ref()?.get("code")?.as_str())
+    }
+
     /// Check if the error context contains any data
     pub fn is_empty(&self) -> bool {
         self.metadata.is_empty() && self.details.is_none()
@@ -0,0 +1,78 @@
+//! CAPTCHA verification
+//!
+//! hCaptcha and reCAPTCHA verify challenges the same way: the token the
+//! widget produced in the browser is posted with the site's secret, and the
+//! answer says whether it is a freshly solved challenge. Tokens work once.
+
+use std::{fmt, time::Duration};
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use tracing::{error, info};
+
+use crate::{
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::{CaptchaConfig, CaptchaProvider},
+};
+
+const HCAPTCHA_VERIFY_URL: &str = "https://api.hcaptcha.com/siteverify";
+const RECAPTCHA_VERIFY_URL: &str = "https://www.google.com/recaptcha/api/siteverify";
+
+/// Upper bound for a verification request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+#[derive(Debug, Deserialize)]
+struct Verification {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+/// Whether `token` is a solved challenge, answered from `remote_ip` if known
+///
+/// Only call with a provider configured.
+pub async fn verify(config: &CaptchaConfig, token: &str, remote_ip: Option<&str>) -> Result<bool> {
+    let url = match (&config.verify_url, config.provider) {
+        (Some(url), _) => url.as_str(),
+        (None, Some(CaptchaProvider::Hcaptcha)) => HCAPTCHA_VERIFY_URL,
+        (None, Some(CaptchaProvider::Recaptcha)) => RECAPTCHA_VERIFY_URL,
+        (None, None) => return Err(ApiError::configuration_error("auth.captcha.provider is not set")),
+    };
+
+    let mut form = vec![("secret", config.secret.as_deref().unwrap_or_default()), ("response", token)];
+    if let Some(remote_ip) = remote_ip {
+        form.push(("remoteip", remote_ip));
+    }
+    let response = HTTP_CLIENT.post(url).form(&form).send().await.map_err(provider_error)?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(provider_error(status));
+    }
+    let verification: Verification = response.json().await.map_err(provider_error)?;
+
+    if !verification.success {
+        info!(errors = ?verification.error_codes, "CAPTCHA challenge not solved");
+    }
+    Ok(verification.success)
+}
+
+/// Reports a provider that is unreachable or answers unexpectedly
+fn provider_error(reason: impl fmt::Display) -> ApiError {
+    error!(
+        error_code = %ErrorCode::BadGateway,
+        error = %reason,
+        "CAPTCHA verification failed"
+    );
+    ApiError::new(
+        ErrorCode::BadGateway,
+        "CAPTCHA verification is unavailable",
+        ErrorContext::new(),
+    )
+}
@@ -0,0 +1,163 @@
+//! Running several instances behind a load balancer
+//!
+//! Requests may land on any instance, so no instance keeps state that a
+//! later request depends on: sessions are stateless tokens, rate limits are
+//! counted in Redis, scheduled and queued jobs are claimed through leases in
+//! the database and files live in object storage. With `cluster.enabled` the
+//! server refuses to start in production with a backend that keeps state in
+//! one instance (no Redis, `memory://` storage, the `mailbox` transport).
+//!
+//! Changes made through the admin API would otherwise only reach the
+//! instance that served the request. They are broadcast as
+//! [`ClusterEvent`]s over Redis pub/sub and applied by every instance.
+
+use std::time::Duration;
+
+use actix_web::rt::task::JoinHandle;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{
+    domain::auth::PermissionService,
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::RedisClient,
+    jobs::{instance_id, shutdown::Shutdown},
+    utils::{logging, Config},
+};
+
+/// Channel cluster events are published on
+pub const CHANNEL: &str = "cluster:events";
+
+/// Delay before subscribing again after losing the Redis connection
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// A change every instance applies
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ClusterEvent {
+    /// Read the configuration again and apply its `[live]` section
+    ReloadConfig,
+    /// Replace the log filter until the next reload
+    SetLogFilter { filter: String },
+    /// Drop the cached role policy after its permissions changed
+    ReloadPermissions,
+    /// Drop a revoked access token from the token cache
+    ForgetToken { jti: String },
+    /// Drop a user's access tokens from the token cache after their role or
+    /// password changed
+    ForgetUserTokens { user_id: String },
+}
+
+/// An event with the instance that published it
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    origin: String,
+    #[serde(flatten)]
+    event: ClusterEvent,
+}
+
+/// Publishes `event` to the other instances, returning how many received it
+///
+/// Without Redis there are no other instances to tell, and nothing is sent.
+pub async fn broadcast(redis: Option<&RedisClient>, event: ClusterEvent) -> Result<u64> {
+    let Some(redis) = redis else {
+        return Ok(0);
+    };
+    let envelope = Envelope {
+        origin: instance_id(),
+        event,
+    };
+    let message = serde_json::to_string(&envelope).map_err(|e| {
+        ApiError::new(ErrorCode::InternalError, format!("Failed to encode cluster event: {}", e), ErrorContext::new())
+    })?;
+    // Our own subscription counts as a receiver
+    Ok(redis.publish(CHANNEL, message).await?.saturating_sub(1))
+}
+
+/// Applies events published by other instances until shutdown, `None`
+/// without Redis
+pub fn spawn_listener(config: Config, shutdown: Shutdown) -> Option<JoinHandle<()>> {
+    let redis = config.redis()?.clone();
+    Some(actix_web::rt::spawn(async move {
+        let origin = instance_id();
+        loop {
+            match redis.subscribe(CHANNEL).await {
+                Ok(mut pubsub) => {
+                    let mut messages = pubsub.on_message();
+                    loop {
+                        tokio::select! {
+                            message = messages.next() => match message {
+                                Some(message) => match message.get_payload::<String>() {
+                                    Ok(payload) => apply(&config, &origin, &payload),
+                                    Err(e) => warn!(error = %e, "Unreadable cluster event"),
+                                },
+                                None => break,
+                            },
+                            _ = shutdown.triggered() => return,
+                        }
+                    }
+                    warn!("Lost the cluster event subscription, subscribing again");
+                }
+                Err(e) => warn!(error = %e, "Failed to subscribe to cluster events"),
+            }
+            tokio::select! {
+                _ = actix_web::rt::time::sleep(RESUBSCRIBE_DELAY) => {}
+                _ = shutdown.triggered() => return,
+            }
+        }
+    }))
+}
+
+/// Applies an event published by another instance
+fn apply(config: &Config, origin: &str, payload: &str) {
+    let envelope: Envelope = match serde_json::from_str(payload) {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            warn!(error = %e, payload = %payload, "Unknown cluster event");
+            return;
+        }
+    };
+    if envelope.origin == origin {
+        return;
+    }
+
+    let outcome = match &envelope.event {
+        ClusterEvent::ReloadConfig => config.live().reload().map(|changed| format!("{:?}", changed)),
+        ClusterEvent::SetLogFilter { filter } => logging::set_filter(filter),
+        ClusterEvent::ReloadPermissions => {
+            PermissionService::invalidate();
+            Ok("policy dropped".to_string())
+        }
+        ClusterEvent::ForgetToken { jti } => {
+            config.token_cache().forget(jti);
+            Ok("token dropped".to_string())
+        }
+        ClusterEvent::ForgetUserTokens { user_id } => {
+            config.token_cache().forget_user(user_id);
+            Ok("tokens dropped".to_string())
+        }
+    };
+    match outcome {
+        Ok(result) => info!(origin = %envelope.origin, event = ?envelope.event, result = %result, "Applied cluster event"),
+        Err(e) => warn!(origin = %envelope.origin, event = ?envelope.event, error = %e, "Failed to apply cluster event"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_format() {
+        let envelope = Envelope {
+            origin: "api-1:42".to_string(),
+            event: ClusterEvent::SetLogFilter { filter: "debug".to_string() },
+        };
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json, serde_json::json!({ "origin": "api-1:42", "event": "set_log_filter", "filter": "debug" }));
+
+        let parsed: Envelope = serde_json::from_value(serde_json::json!({ "origin": "api-2:7", "event": "reload_config" })).unwrap();
+        assert_eq!(parsed.event, ClusterEvent::ReloadConfig);
+    }
+}
@@ -0,0 +1,96 @@
+//! Last known state of external dependencies
+//!
+//! The readiness checks (run by the probe and every
+//! `health.check_interval_secs` in the background) record each dependency's
+//! status in a [`DependencyMonitor`]. Features that need a dependency ask
+//! the monitor first and fail fast with `DEPENDENCY_UNAVAILABLE` while it is
+//! down, instead of every request waiting for its own timeout.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, Result};
+
+/// Object storage, needed by archives and attachments
+pub const STORAGE: &str = "storage";
+/// Outgoing email, needed by notification mail and invitations
+pub const EMAIL: &str = "email";
+pub const REDIS: &str = "redis";
+/// Event bus domain events are published to
+pub const EVENT_BUS: &str = "event_bus";
+
+/// Outcome of a single dependency check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CheckStatus {
+    /// Dependency answered in time
+    Up,
+    /// Dependency answered, but slower than `health.degraded_latency_ms`
+    Degraded,
+    /// Dependency failed or timed out
+    Down,
+    /// Dependency is not configured for this deployment
+    Skipped,
+}
+
+/// Shared record of dependency statuses, cheap to clone
+#[derive(Debug, Clone, Default)]
+pub struct DependencyMonitor {
+    statuses: Arc<RwLock<HashMap<String, CheckStatus>>>,
+}
+
+impl DependencyMonitor {
+    /// Records the outcome of the latest check of `dependency`
+    pub fn record(&self, dependency: &str, status: CheckStatus) {
+        self.statuses
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(dependency.to_string(), status);
+    }
+
+    /// Status of the latest check, `None` if not checked yet
+    pub fn status(&self, dependency: &str) -> Option<CheckStatus> {
+        self.statuses
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(dependency)
+            .copied()
+    }
+
+    /// Fails if `dependency` was down at its latest check
+    ///
+    /// Degraded and unchecked dependencies are let through.
+    pub fn require(&self, dependency: &str) -> Result<()> {
+        match self.status(dependency) {
+            Some(CheckStatus::Down) => Err(ApiError::dependency_unavailable(dependency)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    #[test]
+    fn test_require_fails_only_when_down() {
+        let monitor = DependencyMonitor::default();
+        assert!(monitor.require(STORAGE).is_ok());
+
+        monitor.record(STORAGE, CheckStatus::Degraded);
+        assert!(monitor.require(STORAGE).is_ok());
+
+        monitor.clone().record(STORAGE, CheckStatus::Down);
+        let error = monitor.require(STORAGE).unwrap_err();
+        assert_eq!(error.code, ErrorCode::DependencyUnavailable);
+        assert_eq!(error.context.details.unwrap()["dependency"], STORAGE);
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::VecDeque;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{EmailMessage, EmailService};
+use crate::error::Result;
+
+/// A message caught by the [`DevMailbox`]
+#[derive(Debug, Clone, Serialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ReceivedEmail {
+    pub id: Uuid,
+    pub received_at: DateTime<Utc>,
+    pub message: EmailMessage,
+}
+
+/// Keeps sent mail in memory instead of delivering it
+///
+/// Only the most recent `capacity` messages are kept.
+#[derive(Debug)]
+pub struct DevMailbox {
+    capacity: usize,
+    messages: Mutex<VecDeque<ReceivedEmail>>,
+}
+
+impl DevMailbox {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            messages: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Messages, newest first, optionally only those sent to `to`
+    pub fn list(&self, to: Option<&str>) -> Vec<ReceivedEmail> {
+        self.messages
+            .lock()
+            .iter()
+            .rev()
+            .filter(|received| to.is_none_or(|to| received.message.to.eq_ignore_ascii_case(to)))
+            .cloned()
+            .collect()
+    }
+
+    /// Removes all messages
+    pub fn clear(&self) {
+        self.messages.lock().clear();
+    }
+}
+
+#[async_trait]
+impl EmailService for DevMailbox {
+    async fn send(&self, message: &EmailMessage) -> Result<()> {
+        let mut messages = self.messages.lock();
+        if messages.len() == self.capacity {
+            messages.pop_front();
+        }
+        messages.push_back(ReceivedEmail {
+            id: Uuid::new_v4(),
+            received_at: Utc::now(),
+            message: message.clone(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(to: &str, subject: &str) -> EmailMessage {
+        EmailMessage {
+            from: "no-reply@example.com".into(),
+            reply_to: None,
+            to: to.into(),
+            subject: subject.into(),
+            html: String::new(),
+            text: String::new(),
+            attachments: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mailbox_keeps_newest_messages() {
+        let mailbox = DevMailbox::new(2);
+        mailbox.send(&message("a@example.com", "1")).await.unwrap();
+        mailbox.send(&message("b@example.com", "2")).await.unwrap();
+        mailbox.send(&message("A@example.com", "3")).await.unwrap();
+
+        let subjects = |list: Vec<ReceivedEmail>| list.into_iter().map(|r| r.message.subject).collect::<Vec<_>>();
+        assert_eq!(subjects(mailbox.list(None)), ["3", "2"]);
+        assert_eq!(subjects(mailbox.list(Some("a@example.com"))), ["3"]);
+        mailbox.clear();
+        assert!(mailbox.list(None).is_empty());
+    }
+}
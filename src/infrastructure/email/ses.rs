@@ -0,0 +1,146 @@
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use aws_credential_types::Credentials;
+use aws_sigv4::{
+    http_request::{sign, SignableBody, SignableRequest, SigningSettings},
+    sign::v4,
+};
+use serde_json::json;
+
+use super::{delivery_error, EmailMessage, EmailService};
+use crate::error::{ApiError, ErrorCode, ErrorContext, Result};
+
+/// Sends mail through the Amazon SES v2 `SendEmail` API
+pub struct SesEmailService {
+    client: reqwest::Client,
+    credentials: Credentials,
+    region: String,
+    /// Base URL of the SES v2 email API
+    endpoint: String,
+}
+
+impl SesEmailService {
+    /// Creates the service with credentials from `AWS_ACCESS_KEY_ID`,
+    /// `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`
+    ///
+    /// The region is `region` if set, then `AWS_REGION`.
+    pub fn from_env(region: Option<&str>) -> Result<Self> {
+        let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let region = region
+            .map(str::to_string)
+            .or_else(|| env("AWS_REGION"))
+            .ok_or_else(|| {
+                ApiError::configuration_error("email.ses_region or AWS_REGION is required for the ses transport")
+            })?;
+        let (Some(access_key), Some(secret_key)) = (env("AWS_ACCESS_KEY_ID"), env("AWS_SECRET_ACCESS_KEY")) else {
+            return Err(ApiError::configuration_error(
+                "AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY are required for the ses transport",
+            ));
+        };
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            credentials: Credentials::new(access_key, secret_key, env("AWS_SESSION_TOKEN"), None, "environment"),
+            endpoint: format!("https://email.{}.amazonaws.com/v2/email", region),
+            region,
+        })
+    }
+}
+
+#[async_trait]
+impl EmailService for SesEmailService {
+    async fn send(&self, message: &EmailMessage) -> Result<()> {
+        let mut request = json!({
+            "FromEmailAddress": message.from,
+            "Destination": { "ToAddresses": [message.to] },
+            "Content": {
+                "Simple": {
+                    "Subject": { "Data": message.subject, "Charset": "UTF-8" },
+                    "Body": {
+                        "Text": { "Data": message.text, "Charset": "UTF-8" },
+                        "Html": { "Data": message.html, "Charset": "UTF-8" }
+                    }
+                }
+            }
+        });
+        if let Some(reply_to) = &message.reply_to {
+            request["ReplyToAddresses"] = json!([reply_to]);
+        }
+        if !message.attachments.is_empty() {
+            request["Content"]["Simple"]["Attachments"] = message
+                .attachments
+                .iter()
+                .map(|attachment| {
+                    json!({
+                        "FileName": attachment.filename,
+                        "ContentType": attachment.content_type,
+                        "ContentDisposition": "ATTACHMENT",
+                        "RawContent": attachment.content
+                    })
+                })
+                .collect();
+        }
+        let body = serde_json::to_vec(&request).map_err(|e| delivery_error("SES", &message.to, e))?;
+
+        let response = self
+            .signed("POST", &format!("{}/outbound-emails", self.endpoint), &body)
+            .map_err(|e| delivery_error("SES", &message.to, e))?
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| delivery_error("SES", &message.to, e))?;
+
+        if response.status().is_success() {
+            return Ok(());
+        }
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        Err(delivery_error("SES", &message.to, format!("{} {}", status, detail)))
+    }
+
+    async fn check(&self) -> Result<()> {
+        let unreachable = |reason: String| {
+            ApiError::new(ErrorCode::BadGateway, format!("SES unreachable: {}", reason), ErrorContext::new())
+        };
+        let response = self
+            .signed("GET", &format!("{}/account", self.endpoint), &[])
+            .map_err(unreachable)?
+            .send()
+            .await
+            .map_err(|e| unreachable(e.to_string()))?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(unreachable(response.status().to_string()))
+        }
+    }
+}
+
+impl SesEmailService {
+    /// Request to `url` signed with SigV4
+    fn signed(&self, method: &str, url: &str, body: &[u8]) -> std::result::Result<reqwest::RequestBuilder, String> {
+        let identity = self.credentials.clone().into();
+        let params = v4::SigningParams::builder()
+            .identity(&identity)
+            .region(&self.region)
+            .name("ses")
+            .time(SystemTime::now())
+            .settings(SigningSettings::default())
+            .build()
+            .map_err(|e| e.to_string())?
+            .into();
+        let headers = if body.is_empty() { None } else { Some(("content-type", "application/json")) };
+        let signable = SignableRequest::new(method, url, headers.into_iter(), SignableBody::Bytes(body))
+            .map_err(|e| e.to_string())?;
+        let (instructions, _) = sign(signable, &params).map_err(|e| e.to_string())?.into_parts();
+
+        let method = reqwest::Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+        let mut request = self.client.request(method, url);
+        for (name, value) in instructions.headers() {
+            request = request.header(name, value);
+        }
+        Ok(request)
+    }
+}
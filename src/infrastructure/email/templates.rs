@@ -0,0 +1,398 @@
+//! Email templates
+//!
+//! Every template has a subject, an HTML and a plain-text part in
+//! `templates/email/`, compiled into the binary. Templates are Handlebars;
+//! the HTML parts share `layout.html.hbs`. Rendering is strict, so a missing
+//! field fails instead of sending a half-empty email.
+
+use chrono::{DateTime, Utc};
+use handlebars::{no_escape, Handlebars};
+use serde::{Serialize, Serializer};
+
+use crate::error::{ApiError, ErrorCode, ErrorContext, Result};
+
+/// Product name shown in every email
+const PRODUCT: &str = "Forestry Optimizer";
+
+/// Templates available for outgoing mail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailTemplate {
+    Invitation,
+    PasswordReset,
+    EmailVerification,
+    EmailChange,
+    EmailChangeNotice,
+    LoginAlert,
+    MagicLink,
+    Alert,
+    Report,
+}
+
+impl EmailTemplate {
+    pub const ALL: [EmailTemplate; 9] = [
+        Self::Invitation,
+        Self::PasswordReset,
+        Self::EmailVerification,
+        Self::EmailChange,
+        Self::EmailChangeNotice,
+        Self::LoginAlert,
+        Self::MagicLink,
+        Self::Alert,
+        Self::Report,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Invitation => "invitation",
+            Self::PasswordReset => "password_reset",
+            Self::EmailVerification => "email_verification",
+            Self::EmailChange => "email_change",
+            Self::EmailChangeNotice => "email_change_notice",
+            Self::LoginAlert => "login_alert",
+            Self::MagicLink => "magic_link",
+            Self::Alert => "alert",
+            Self::Report => "report",
+        }
+    }
+
+    /// Subject, HTML and text sources
+    fn sources(&self) -> [&'static str; 3] {
+        match self {
+            Self::Invitation => [
+                include_str!("../../../templates/email/invitation.subject.hbs"),
+                include_str!("../../../templates/email/invitation.html.hbs"),
+                include_str!("../../../templates/email/invitation.txt.hbs"),
+            ],
+            Self::PasswordReset => [
+                include_str!("../../../templates/email/password_reset.subject.hbs"),
+                include_str!("../../../templates/email/password_reset.html.hbs"),
+                include_str!("../../../templates/email/password_reset.txt.hbs"),
+            ],
+            Self::EmailVerification => [
+                include_str!("../../../templates/email/email_verification.subject.hbs"),
+                include_str!("../../../templates/email/email_verification.html.hbs"),
+                include_str!("../../../templates/email/email_verification.txt.hbs"),
+            ],
+            Self::EmailChange => [
+                include_str!("../../../templates/email/email_change.subject.hbs"),
+                include_str!("../../../templates/email/email_change.html.hbs"),
+                include_str!("../../../templates/email/email_change.txt.hbs"),
+            ],
+            Self::EmailChangeNotice => [
+                include_str!("../../../templates/email/email_change_notice.subject.hbs"),
+                include_str!("../../../templates/email/email_change_notice.html.hbs"),
+                include_str!("../../../templates/email/email_change_notice.txt.hbs"),
+            ],
+            Self::LoginAlert => [
+                include_str!("../../../templates/email/login_alert.subject.hbs"),
+                include_str!("../../../templates/email/login_alert.html.hbs"),
+                include_str!("../../../templates/email/login_alert.txt.hbs"),
+            ],
+            Self::MagicLink => [
+                include_str!("../../../templates/email/magic_link.subject.hbs"),
+                include_str!("../../../templates/email/magic_link.html.hbs"),
+                include_str!("../../../templates/email/magic_link.txt.hbs"),
+            ],
+            Self::Alert => [
+                include_str!("../../../templates/email/alert.subject.hbs"),
+                include_str!("../../../templates/email/alert.html.hbs"),
+                include_str!("../../../templates/email/alert.txt.hbs"),
+            ],
+            Self::Report => [
+                include_str!("../../../templates/email/report.subject.hbs"),
+                include_str!("../../../templates/email/report.html.hbs"),
+                include_str!("../../../templates/email/report.txt.hbs"),
+            ],
+        }
+    }
+}
+
+/// Data rendered into one of the templates
+pub trait TemplateData: Serialize {
+    const TEMPLATE: EmailTemplate;
+}
+
+/// Invitation to join an organization
+#[derive(Debug, Clone, Serialize)]
+pub struct InvitationEmail {
+    pub organization: String,
+    pub inviter: String,
+    pub accept_url: String,
+    #[serde(serialize_with = "human_date")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl TemplateData for InvitationEmail {
+    const TEMPLATE: EmailTemplate = EmailTemplate::Invitation;
+}
+
+/// Link to choose a new password
+#[derive(Debug, Clone, Serialize)]
+pub struct PasswordResetEmail {
+    pub name: String,
+    pub reset_url: String,
+    pub expires_in_minutes: i64,
+}
+
+impl TemplateData for PasswordResetEmail {
+    const TEMPLATE: EmailTemplate = EmailTemplate::PasswordReset;
+}
+
+/// Link to confirm an email address
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailVerificationEmail {
+    pub name: String,
+    pub verify_url: String,
+    pub expires_in_hours: i64,
+}
+
+impl TemplateData for EmailVerificationEmail {
+    const TEMPLATE: EmailTemplate = EmailTemplate::EmailVerification;
+}
+
+/// Link to confirm a new email address, sent to that address
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailChangeEmail {
+    pub name: String,
+    pub new_email: String,
+    pub confirm_url: String,
+    pub expires_in_hours: i64,
+}
+
+impl TemplateData for EmailChangeEmail {
+    const TEMPLATE: EmailTemplate = EmailTemplate::EmailChange;
+}
+
+/// Notice to the current address that a change to another was requested
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailChangeNoticeEmail {
+    pub name: String,
+    pub new_email: String,
+}
+
+impl TemplateData for EmailChangeNoticeEmail {
+    const TEMPLATE: EmailTemplate = EmailTemplate::EmailChangeNotice;
+}
+
+/// Alert about a login from a new location or device
+#[derive(Debug, Clone, Serialize)]
+pub struct LoginAlertEmail {
+    pub name: String,
+    #[serde(serialize_with = "human_date")]
+    pub signed_in_at: DateTime<Utc>,
+    pub location: String,
+    pub device: String,
+    pub ip_address: Option<String>,
+    /// Page that signs the user out everywhere
+    pub secure_url: String,
+    pub expires_in_days: i64,
+}
+
+impl TemplateData for LoginAlertEmail {
+    const TEMPLATE: EmailTemplate = EmailTemplate::LoginAlert;
+}
+
+/// Link that logs the user in without a password
+#[derive(Debug, Clone, Serialize)]
+pub struct MagicLinkEmail {
+    pub name: String,
+    pub login_url: String,
+    pub expires_in_minutes: i64,
+}
+
+impl TemplateData for MagicLinkEmail {
+    const TEMPLATE: EmailTemplate = EmailTemplate::MagicLink;
+}
+
+/// Severity shown in the subject of an alert
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Operational alert, e.g. a failed import or an equipment fault
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertEmail {
+    pub title: String,
+    pub message: String,
+    pub severity: AlertSeverity,
+    /// Page with details, no button is shown when unset
+    pub url: Option<String>,
+}
+
+impl TemplateData for AlertEmail {
+    const TEMPLATE: EmailTemplate = EmailTemplate::Alert;
+}
+
+/// Output of a scheduled report, attached or linked
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportEmail {
+    pub report: String,
+    #[serde(serialize_with = "human_date")]
+    pub generated_at: DateTime<Utc>,
+    pub rows: usize,
+    pub truncated: bool,
+    /// Whether the output is attached rather than linked
+    pub attached: bool,
+    /// Download link of output too large to attach
+    pub url: Option<String>,
+}
+
+impl TemplateData for ReportEmail {
+    const TEMPLATE: EmailTemplate = EmailTemplate::Report;
+}
+
+fn human_date<S: Serializer>(date: &DateTime<Utc>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_str(&date.format("%B %-d, %Y %H:%M UTC"))
+}
+
+/// A rendered template
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+/// Compiled email templates
+#[derive(Debug)]
+pub struct EmailTemplates {
+    /// HTML parts, with values HTML-escaped
+    html: Handlebars<'static>,
+    /// Subjects and text parts, with values inserted verbatim
+    text: Handlebars<'static>,
+}
+
+impl EmailTemplates {
+    /// Compiles all templates, failing on syntax errors
+    pub fn new() -> Result<Self> {
+        let mut html = Handlebars::new();
+        html.set_strict_mode(true);
+        let mut text = Handlebars::new();
+        text.set_strict_mode(true);
+        text.register_escape_fn(no_escape);
+
+        let invalid = |name: &str, e: handlebars::TemplateError| {
+            ApiError::configuration_error(format!("Invalid email template {}: {}", name, e))
+        };
+        html.register_partial("layout", include_str!("../../../templates/email/layout.html.hbs"))
+            .map_err(|e| invalid("layout", e))?;
+        html.register_partial("button", include_str!("../../../templates/email/button.html.hbs"))
+            .map_err(|e| invalid("button", e))?;
+        for template in EmailTemplate::ALL {
+            let name = template.name();
+            let [subject, html_body, text_body] = template.sources();
+            text.register_template_string(&format!("{}.subject", name), subject)
+                .map_err(|e| invalid(name, e))?;
+            html.register_template_string(name, html_body)
+                .map_err(|e| invalid(name, e))?;
+            text.register_template_string(name, text_body)
+                .map_err(|e| invalid(name, e))?;
+        }
+
+        Ok(Self { html, text })
+    }
+
+    /// Renders the template of `data`
+    pub fn render<T: TemplateData>(&self, data: &T) -> Result<RenderedEmail> {
+        let name = T::TEMPLATE.name();
+        let mut context = serde_json::to_value(data).map_err(|e| render_error(name, e))?;
+        context["product"] = PRODUCT.into();
+
+        let subject = self
+            .text
+            .render(&format!("{}.subject", name), &context)
+            .map_err(|e| render_error(name, e))?
+            .trim()
+            .to_string();
+        context["subject"] = subject.clone().into();
+
+        Ok(RenderedEmail {
+            html: self.html.render(name, &context).map_err(|e| render_error(name, e))?,
+            text: self.text.render(name, &context).map_err(|e| render_error(name, e))?,
+            subject,
+        })
+    }
+}
+
+fn render_error(template: &str, e: impl std::fmt::Display) -> ApiError {
+    ApiError::new(
+        ErrorCode::InternalError,
+        format!("Failed to render email template {}: {}", template, e),
+        ErrorContext::new(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render_escapes_html_only() {
+        let templates = EmailTemplates::new().unwrap();
+        let email = templates
+            .render(&InvitationEmail {
+                organization: "Pine & Fir".into(),
+                inviter: "Alex".into(),
+                accept_url: "https://app.example.com/invitations/abc".into(),
+                expires_at: Utc.with_ymd_and_hms(2024, 12, 24, 9, 0, 0).unwrap(),
+            })
+            .unwrap();
+
+        assert_eq!(email.subject, "Alex invited you to join Pine & Fir");
+        assert!(email.html.contains("Pine &amp; Fir"));
+        assert!(email.html.contains("href=\"https://app.example.com/invitations/abc\""));
+        assert!(email.text.contains("join Pine & Fir on Forestry Optimizer"));
+        assert!(email.text.contains("December 24, 2024 09:00 UTC"));
+    }
+
+    #[test]
+    fn test_render_optional_fields() {
+        let templates = EmailTemplates::new().unwrap();
+        let alert = AlertEmail {
+            title: "Import failed".into(),
+            message: "Stand file could not be parsed".into(),
+            severity: AlertSeverity::Warning,
+            url: None,
+        };
+
+        let email = templates.render(&alert).unwrap();
+        assert_eq!(email.subject, "[WARNING] Import failed");
+        assert!(!email.text.contains("View details"));
+
+        let email = templates
+            .render(&AlertEmail { url: Some("https://app.example.com/imports/1".into()), ..alert })
+            .unwrap();
+        assert!(email.text.contains("View details: https://app.example.com/imports/1"));
+    }
+
+    #[test]
+    fn test_render_report_attached_or_linked() {
+        let templates = EmailTemplates::new().unwrap();
+        let report = ReportEmail {
+            report: "Members by role".into(),
+            generated_at: Utc.with_ymd_and_hms(2024, 12, 23, 6, 0, 0).unwrap(),
+            rows: 2,
+            truncated: false,
+            attached: true,
+            url: None,
+        };
+
+        let email = templates.render(&report).unwrap();
+        assert_eq!(email.subject, "Report: Members by role");
+        assert!(email.text.contains("The output is attached to this email."));
+
+        let email = templates
+            .render(&ReportEmail {
+                attached: false,
+                url: Some("https://api.example.com/v1/reports/1/deliveries/2/file".into()),
+                ..report
+            })
+            .unwrap();
+        assert!(email.text.contains("Download report: https://api.example.com/v1/reports/1/deliveries/2/file"));
+    }
+}
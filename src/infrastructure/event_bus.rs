@@ -0,0 +1,194 @@
+//! Event bus client
+//!
+//! Publishes domain events for downstream consumers such as analytics
+//! pipelines, either to NATS subjects or to Kafka topics through a Kafka
+//! REST Proxy. Subjects and topics are named
+//! `<events.subject_prefix>.<event type>.v<version>`, e.g.
+//! `forestry.block.approved.v1`, so a consumer binds to one version of an
+//! event and a breaking change ships under a new name.
+//!
+//! Delivery is at least once: the publisher is fed by the job queue and
+//! retries until the bus accepts an event. Every message carries the event
+//! id (the `Nats-Msg-Id` header on NATS, the record key on Kafka) for
+//! consumers to drop duplicates.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use tokio::sync::OnceCell;
+use tracing::error;
+
+use crate::{
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::{EventTransport, EventsConfig},
+};
+
+/// Content type of JSON records in the Kafka REST Proxy v2 API
+const KAFKA_JSON: &str = "application/vnd.kafka.json.v2+json";
+
+/// Handle to the configured event bus, cheap to clone
+#[derive(Clone)]
+pub struct EventBus {
+    inner: Arc<Inner>,
+}
+
+enum Inner {
+    Nats {
+        url: String,
+        timeout: Duration,
+        client: OnceCell<async_nats::Client>,
+    },
+    Kafka {
+        url: String,
+        http: reqwest::Client,
+    },
+}
+
+impl fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus").field("transport", &self.transport()).finish()
+    }
+}
+
+impl EventBus {
+    /// Creates a client from the `[events]` section, `None` when no
+    /// transport is configured
+    ///
+    /// A NATS connection is opened on first use or by [`EventBus::connect`].
+    pub fn from_config(config: &EventsConfig) -> Result<Option<Self>> {
+        let url = match (config.transport, &config.url) {
+            (EventTransport::None, _) => return Ok(None),
+            (_, Some(url)) => url.trim_end_matches('/').to_string(),
+            (_, None) => return Err(ApiError::configuration_error("events.url is required to publish events")),
+        };
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let inner = match config.transport {
+            EventTransport::Nats => Inner::Nats {
+                url,
+                timeout,
+                client: OnceCell::new(),
+            },
+            EventTransport::Kafka => Inner::Kafka {
+                url,
+                http: reqwest::Client::builder()
+                    .timeout(timeout)
+                    .build()
+                    .map_err(|e| ApiError::configuration_error(format!("Invalid Kafka REST Proxy client: {}", e)))?,
+            },
+            EventTransport::None => unreachable!(),
+        };
+        Ok(Some(Self { inner: Arc::new(inner) }))
+    }
+
+    pub fn transport(&self) -> EventTransport {
+        match *self.inner {
+            Inner::Nats { .. } => EventTransport::Nats,
+            Inner::Kafka { .. } => EventTransport::Kafka,
+        }
+    }
+
+    /// Opens the NATS connection, a no-op for Kafka
+    ///
+    /// The connection is driven by the runtime that opened it, so the
+    /// server calls this once at startup.
+    pub async fn connect(&self) -> Result<()> {
+        match &*self.inner {
+            Inner::Nats { url, timeout, client } => nats(url, *timeout, client).await.map(|_| ()),
+            Inner::Kafka { .. } => Ok(()),
+        }
+    }
+
+    /// Publishes `payload` to `subject`, returning once the bus has it
+    ///
+    /// `key` identifies the message for deduplication and, on Kafka, picks
+    /// the partition.
+    pub async fn publish(&self, subject: &str, key: &str, payload: &serde_json::Value) -> Result<()> {
+        match &*self.inner {
+            Inner::Nats { url, timeout, client } => {
+                let client = nats(url, *timeout, client).await?;
+                let mut headers = async_nats::HeaderMap::new();
+                headers.insert("Nats-Msg-Id", key);
+                client
+                    .publish_with_headers(subject.to_string(), headers, payload.to_string().into())
+                    .await
+                    .map_err(|e| bus_error("publish to NATS", e))?;
+                // Publishing only queues the message, flushing waits for the
+                // server to receive it
+                tokio::time::timeout(*timeout, client.flush())
+                    .await
+                    .map_err(|e| bus_error("flush NATS", e))?
+                    .map_err(|e| bus_error("flush NATS", e))
+            }
+            Inner::Kafka { url, http } => {
+                let records = serde_json::json!({ "records": [{ "key": key, "value": payload }] });
+                let response = http
+                    .post(format!("{}/topics/{}", url, subject))
+                    .header(reqwest::header::CONTENT_TYPE, KAFKA_JSON)
+                    .header(reqwest::header::ACCEPT, "application/vnd.kafka.v2+json")
+                    .body(records.to_string())
+                    .send()
+                    .await
+                    .map_err(|e| bus_error("reach the Kafka REST Proxy", e))?;
+                if response.status().is_success() {
+                    Ok(())
+                } else {
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    Err(bus_error("publish to Kafka", format!("{} {}", status, body)))
+                }
+            }
+        }
+    }
+
+    /// Checks the bus is reachable
+    pub async fn check(&self) -> Result<()> {
+        match &*self.inner {
+            Inner::Nats { url, timeout, client } => {
+                let client = nats(url, *timeout, client).await?;
+                tokio::time::timeout(*timeout, client.flush())
+                    .await
+                    .map_err(|e| bus_error("reach NATS", e))?
+                    .map_err(|e| bus_error("reach NATS", e))
+            }
+            Inner::Kafka { url, http } => {
+                let response = http
+                    .get(format!("{}/topics", url))
+                    .header(reqwest::header::ACCEPT, "application/vnd.kafka.v2+json")
+                    .send()
+                    .await
+                    .map_err(|e| bus_error("reach the Kafka REST Proxy", e))?;
+                if response.status().is_success() {
+                    Ok(())
+                } else {
+                    Err(bus_error("reach the Kafka REST Proxy", response.status()))
+                }
+            }
+        }
+    }
+}
+
+/// The NATS connection, opened if needed
+async fn nats<'a>(url: &str, timeout: Duration, client: &'a OnceCell<async_nats::Client>) -> Result<&'a async_nats::Client> {
+    client
+        .get_or_try_init(|| {
+            async_nats::ConnectOptions::new()
+                .connection_timeout(timeout)
+                .request_timeout(Some(timeout))
+                .connect(url)
+        })
+        .await
+        .map_err(|e| bus_error("connect to NATS", e))
+}
+
+fn bus_error(action: &str, reason: impl fmt::Display) -> ApiError {
+    error!(
+        error_code = %ErrorCode::BadGateway,
+        error = %reason,
+        "Failed to {}",
+        action
+    );
+    ApiError::new(
+        ErrorCode::BadGateway,
+        format!("Failed to {}: {}", action, reason),
+        ErrorContext::new(),
+    )
+}
@@ -0,0 +1,23 @@
+//! Clients for external infrastructure
+//!
+//! This module wraps the services the backend talks to besides the primary
+//! database, such as object storage, outgoing email, Redis, the event bus,
+//! SFTP servers, OpenID Connect providers, CAPTCHA services and Stripe.
+
+pub mod captcha;
+pub mod cluster;
+pub mod dependencies;
+pub mod email;
+pub mod event_bus;
+pub mod mqtt;
+pub mod oidc;
+pub mod redis;
+pub mod sftp;
+pub mod storage;
+pub mod stripe;
+
+pub use dependencies::DependencyMonitor;
+pub use email::Mailer;
+pub use event_bus::EventBus;
+pub use self::redis::RedisClient;
+pub use storage::ObjectStorage;
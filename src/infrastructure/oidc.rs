@@ -0,0 +1,290 @@
+//! OpenID Connect relying party
+//!
+//! Signs users in with the identity providers in `auth.oidc` (Microsoft
+//! Entra ID, Google, ...) through the authorization code flow. Endpoints
+//! come from the issuer's discovery document and ID tokens are checked
+//! against the keys the provider publishes. Both are cached for an hour;
+//! the keys are fetched again early when a token names a key not seen yet,
+//! as happens after the provider rotates them.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize};
+use tracing::{error, warn};
+
+use crate::{
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::OidcProviderConfig,
+};
+
+/// Time discovery documents and keys are reused
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Upper bound for a single request to a provider
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+/// Discovery documents and keys, by issuer
+static CACHE: Lazy<Mutex<HashMap<String, Discovered>>> = Lazy::new(Default::default);
+
+/// Endpoints of a provider, from its discovery document
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Jwk {
+    kid: Option<String>,
+    kty: String,
+    n: Option<String>,
+    e: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Discovered {
+    metadata: ProviderMetadata,
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// Claims of a verified ID token
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IdTokenClaims {
+    /// The provider's id of the account
+    pub sub: String,
+    pub nonce: Option<String>,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+    /// Sign-in name, the email address of most Entra ID accounts
+    pub preferred_username: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub name: Option<String>,
+}
+
+impl IdTokenClaims {
+    /// Email of the account, from `email` or an address-like `preferred_username`
+    pub fn email(&self) -> Option<&str> {
+        self.email
+            .as_deref()
+            .or(self.preferred_username.as_deref().filter(|name| name.contains('@')))
+            .map(str::trim)
+            .filter(|email| !email.is_empty())
+    }
+}
+
+/// One of the providers in `auth.oidc`
+pub struct OidcProvider<'a> {
+    name: &'a str,
+    config: &'a OidcProviderConfig,
+}
+
+impl fmt::Debug for OidcProvider<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OidcProvider").field("name", &self.name).field("issuer", &self.config.issuer).finish()
+    }
+}
+
+impl<'a> OidcProvider<'a> {
+    pub fn new(name: &'a str, config: &'a OidcProviderConfig) -> Self {
+        Self { name, config }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Whether the provider's `email` claim is trusted without `email_verified`
+    pub fn trust_email(&self) -> bool {
+        self.config.trust_email
+    }
+
+    /// URL of the provider's sign-in page, which redirects back to
+    /// `redirect_url` with a code and `state`
+    pub async fn authorization_url(&self, state: &str, nonce: &str) -> Result<String> {
+        let discovered = self.discover(false).await?;
+        reqwest::Url::parse_with_params(
+            &discovered.metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", self.config.redirect_url.as_str()),
+                ("scope", "openid email profile"),
+                ("state", state),
+                ("nonce", nonce),
+            ],
+        )
+        .map(String::from)
+        .map_err(|e| self.provider_error(format!("invalid authorization endpoint: {}", e)))
+    }
+
+    /// Redeems an authorization code for the claims of its ID token, which
+    /// must be signed by the provider, meant for this client and carry `nonce`
+    pub async fn exchange(&self, code: &str, nonce: &str) -> Result<IdTokenClaims> {
+        let discovered = self.discover(false).await?;
+        let response = HTTP_CLIENT
+            .post(&discovered.metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.config.redirect_url.as_str()),
+                ("client_id", self.config.client_id.as_str()),
+                ("client_secret", self.config.client_secret.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| self.provider_error(e))?;
+
+        let status = response.status();
+        let body = response.bytes().await.map_err(|e| self.provider_error(e))?;
+        if status == reqwest::StatusCode::BAD_REQUEST {
+            // Expired, reused or forged codes
+            warn!(provider = %self.name, response = %String::from_utf8_lossy(&body), "Authorization code rejected");
+            return Err(ApiError::validation("The sign-in could not be completed, please try again", None));
+        }
+        if !status.is_success() {
+            return Err(self.provider_error(format!("{} {}", status, String::from_utf8_lossy(&body))));
+        }
+        let tokens: TokenResponse = serde_json::from_slice(&body).map_err(|e| self.provider_error(e))?;
+
+        let claims = self.verify(&tokens.id_token, &discovered).await?;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(self.invalid_token("the nonce does not match"));
+        }
+        Ok(claims)
+    }
+
+    async fn verify(&self, id_token: &str, discovered: &Discovered) -> Result<IdTokenClaims> {
+        let header = decode_header(id_token).map_err(|e| self.invalid_token(e))?;
+        if header.alg != Algorithm::RS256 {
+            return Err(self.invalid_token(format!("unsupported algorithm {:?}", header.alg)));
+        }
+        let key = match find_key(&discovered.keys, header.kid.as_deref()) {
+            Some(key) => key,
+            None => find_key(&self.discover(true).await?.keys, header.kid.as_deref())
+                .ok_or_else(|| self.invalid_token("signed with an unknown key"))?,
+        };
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[&discovered.metadata.issuer]);
+        decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| self.invalid_token(e))
+    }
+
+    /// The provider's endpoints and keys, from the cache unless `refresh`
+    async fn discover(&self, refresh: bool) -> Result<Discovered> {
+        let issuer = self.config.issuer.trim_end_matches('/');
+        if !refresh {
+            let cached = CACHE.lock().get(issuer).filter(|d| d.fetched_at.elapsed() < CACHE_TTL).cloned();
+            if let Some(discovered) = cached {
+                return Ok(discovered);
+            }
+        }
+
+        let metadata: ProviderMetadata = self.fetch(&format!("{}/.well-known/openid-configuration", issuer)).await?;
+        let keys: JwkSet = self.fetch(&metadata.jwks_uri).await?;
+        let discovered = Discovered {
+            metadata,
+            keys,
+            fetched_at: Instant::now(),
+        };
+        CACHE.lock().insert(issuer.to_string(), discovered.clone());
+        Ok(discovered)
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = HTTP_CLIENT.get(url).send().await.map_err(|e| self.provider_error(e))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(self.provider_error(format!("{} from {}", status, url)));
+        }
+        let body = response.bytes().await.map_err(|e| self.provider_error(e))?;
+        serde_json::from_slice(&body).map_err(|e| self.provider_error(format!("invalid response from {}: {}", url, e)))
+    }
+
+    /// Reports a provider that is unreachable or answers unexpectedly
+    fn provider_error(&self, reason: impl fmt::Display) -> ApiError {
+        error!(
+            error_code = %ErrorCode::BadGateway,
+            provider = %self.name,
+            error = %reason,
+            "Identity provider request failed"
+        );
+        ApiError::new(
+            ErrorCode::BadGateway,
+            format!("Identity provider {} is unavailable", self.name),
+            ErrorContext::new(),
+        )
+    }
+
+    /// Reports an ID token that does not verify
+    fn invalid_token(&self, reason: impl fmt::Display) -> ApiError {
+        warn!(provider = %self.name, error = %reason, "Invalid ID token");
+        ApiError::unauthorized(format!("Invalid ID token from {}", self.name))
+    }
+}
+
+/// The RSA key with id `kid`, any RSA key when the token names none
+fn find_key(keys: &JwkSet, kid: Option<&str>) -> Option<DecodingKey> {
+    keys.keys
+        .iter()
+        .filter(|key| key.kty == "RSA")
+        .filter(|key| kid.is_none() || key.kid.as_deref() == kid)
+        .find_map(|key| DecodingKey::from_rsa_components(key.n.as_deref()?, key.e.as_deref()?).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_falls_back_to_preferred_username() {
+        let claims = IdTokenClaims {
+            preferred_username: Some("anna.berg@pinecorp.com".into()),
+            ..Default::default()
+        };
+        assert_eq!(claims.email(), Some("anna.berg@pinecorp.com"));
+
+        let claims = IdTokenClaims {
+            email: Some("anna@pinecorp.com".into()),
+            preferred_username: Some("anna.berg@pinecorp.com".into()),
+            ..Default::default()
+        };
+        assert_eq!(claims.email(), Some("anna@pinecorp.com"));
+
+        let claims = IdTokenClaims {
+            preferred_username: Some("aberg".into()),
+            ..Default::default()
+        };
+        assert_eq!(claims.email(), None);
+    }
+}
@@ -0,0 +1,241 @@
+//! Redis client
+//!
+//! Shared by everything that needs state across instances: rate limits,
+//! caches, revoked sessions and the pub/sub fan-out of server-sent events.
+//!
+//! Requests are spread round-robin over `redis.pool_size` multiplexed
+//! connections, each reconnecting on its own after a failure. Every key and
+//! channel is prefixed with `redis.namespace`, so callers pass bare names
+//! such as `ratelimit:10.0.0.1:4711`.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use ::redis::{
+    aio::{ConnectionManager, ConnectionManagerConfig, PubSub},
+    AsyncCommands, Client, FromRedisValue, RedisError, ToRedisArgs,
+};
+use tokio::sync::OnceCell;
+use tracing::error;
+
+use crate::{
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::RedisConfig,
+};
+
+/// Handle to the configured Redis server, cheap to clone
+#[derive(Clone)]
+pub struct RedisClient {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    client: Client,
+    settings: ConnectionManagerConfig,
+    connections: Vec<OnceCell<ConnectionManager>>,
+    next: AtomicUsize,
+    namespace: String,
+}
+
+impl fmt::Debug for RedisClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisClient")
+            .field("namespace", &self.inner.namespace)
+            .field("pool_size", &self.inner.connections.len())
+            .finish()
+    }
+}
+
+impl RedisClient {
+    /// Creates a client from the `[redis]` section, `None` when no URL is set
+    ///
+    /// Connections are opened on first use or by [`RedisClient::connect`].
+    pub fn from_config(config: &RedisConfig) -> Result<Option<Self>> {
+        let Some(url) = &config.url else {
+            return Ok(None);
+        };
+        let client = Client::open(url.as_str())
+            .map_err(|e| ApiError::configuration_error(format!("Invalid Redis URL: {}", e)))?;
+        let settings = ConnectionManagerConfig::new()
+            .set_connection_timeout(Duration::from_millis(config.connect_timeout_ms))
+            .set_response_timeout(Duration::from_millis(config.response_timeout_ms))
+            .set_number_of_retries(2);
+
+        Ok(Some(Self {
+            inner: Arc::new(Inner {
+                client,
+                settings,
+                connections: (0..config.pool_size.max(1)).map(|_| OnceCell::new()).collect(),
+                next: AtomicUsize::new(0),
+                namespace: config.namespace.clone(),
+            }),
+        }))
+    }
+
+    /// Prefix of all keys and channels
+    pub fn namespace(&self) -> &str {
+        &self.inner.namespace
+    }
+
+    /// Namespaced form of `key`
+    pub fn key(&self, key: &str) -> String {
+        namespaced(&self.inner.namespace, key)
+    }
+
+    /// Opens every pooled connection
+    ///
+    /// Connections are driven by the runtime that opened them, so the
+    /// server calls this once at startup rather than leaving it to whichever
+    /// worker happens to send the first command.
+    pub async fn connect(&self) -> Result<()> {
+        for slot in &self.inner.connections {
+            self.open(slot).await?;
+        }
+        Ok(())
+    }
+
+    /// Next pooled connection, opened if needed
+    pub async fn connection(&self) -> Result<ConnectionManager> {
+        let index = self.inner.next.fetch_add(1, Ordering::Relaxed) % self.inner.connections.len();
+        self.open(&self.inner.connections[index]).await
+    }
+
+    async fn open(&self, slot: &OnceCell<ConnectionManager>) -> Result<ConnectionManager> {
+        slot.get_or_try_init(|| {
+            self.inner
+                .client
+                .get_connection_manager_with_config(self.inner.settings.clone())
+        })
+        .await
+        .cloned()
+        .map_err(|e| redis_error("connect to Redis", e))
+    }
+
+    /// Checks the server answers `PING`
+    pub async fn ping(&self) -> Result<()> {
+        let mut conn = self.connection().await?;
+        ::redis::cmd("PING")
+            .query_async::<String>(&mut conn)
+            .await
+            .map(|_| ())
+            .map_err(|e| redis_error("ping Redis", e))
+    }
+
+    /// Reads a value, `None` if the key does not exist
+    pub async fn get<V: FromRedisValue>(&self, key: &str) -> Result<Option<V>> {
+        let mut conn = self.connection().await?;
+        conn.get(self.key(key))
+            .await
+            .map_err(|e| redis_error("read from Redis", e))
+    }
+
+    /// Stores a value that expires after `ttl`
+    pub async fn set_ex(&self, key: &str, value: impl ToRedisArgs + Send + Sync, ttl: Duration) -> Result<()> {
+        let mut conn = self.connection().await?;
+        conn.set_ex(self.key(key), value, ttl.as_secs().max(1))
+            .await
+            .map_err(|e| redis_error("write to Redis", e))
+    }
+
+    /// Deletes a key, returning whether it existed
+    pub async fn del(&self, key: &str) -> Result<bool> {
+        let mut conn = self.connection().await?;
+        conn.del::<_, u64>(self.key(key))
+            .await
+            .map(|deleted| deleted > 0)
+            .map_err(|e| redis_error("delete from Redis", e))
+    }
+
+    /// Increments a counter, (re)setting its expiry to `ttl`
+    ///
+    /// Both happen in one transaction, so a counter never outlives its
+    /// expiry. Returns the incremented value.
+    pub async fn incr_ex(&self, key: &str, ttl: Duration) -> Result<u64> {
+        let key = self.key(key);
+        let mut conn = self.connection().await?;
+        let (count,): (u64,) = ::redis::pipe()
+            .atomic()
+            .incr(&key, 1)
+            .expire(&key, ttl.as_secs().max(1) as i64)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("increment Redis counter", e))?;
+        Ok(count)
+    }
+
+    /// Publishes a message, returning the number of subscribers reached
+    pub async fn publish(&self, channel: &str, message: impl ToRedisArgs + Send + Sync) -> Result<u64> {
+        let mut conn = self.connection().await?;
+        conn.publish(self.key(channel), message)
+            .await
+            .map_err(|e| redis_error("publish to Redis", e))
+    }
+
+    /// Opens a dedicated connection subscribed to `channel`
+    ///
+    /// Messages report their namespaced channel name.
+    pub async fn subscribe(&self, channel: &str) -> Result<PubSub> {
+        let mut pubsub = self
+            .inner
+            .client
+            .get_async_pubsub()
+            .await
+            .map_err(|e| redis_error("connect to Redis", e))?;
+        pubsub
+            .subscribe(self.key(channel))
+            .await
+            .map_err(|e| redis_error("subscribe to Redis channel", e))?;
+        Ok(pubsub)
+    }
+}
+
+fn namespaced(namespace: &str, key: &str) -> String {
+    if namespace.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}:{}", namespace, key)
+    }
+}
+
+fn redis_error(action: &str, e: RedisError) -> ApiError {
+    error!(
+        error_code = %ErrorCode::ServiceUnavailable,
+        error = %e,
+        "Failed to {}",
+        action
+    );
+    ApiError::new(
+        ErrorCode::ServiceUnavailable,
+        format!("Failed to {}", action),
+        ErrorContext::new(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_are_namespaced() {
+        let config = RedisConfig {
+            url: Some("redis://localhost:6379".to_string()),
+            namespace: "staging".to_string(),
+            ..RedisConfig::default()
+        };
+        let client = RedisClient::from_config(&config).unwrap().unwrap();
+        assert_eq!(client.key("ratelimit:10.0.0.1:42"), "staging:ratelimit:10.0.0.1:42");
+        assert_eq!(namespaced("", "sessions:revoked"), "sessions:revoked");
+    }
+
+    #[test]
+    fn test_unset_url_disables_client() {
+        assert!(RedisClient::from_config(&RedisConfig::default()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,92 @@
+//! SFTP uploads
+//!
+//! Files are written under a temporary `.part` name and renamed once
+//! complete, so a reader polling the directory never picks up a partial
+//! file. The server's host key is checked against a pinned SHA-256
+//! fingerprint, as printed by `ssh-keygen -lf`, before authenticating with
+//! the configured private key.
+
+use std::{
+    io::Write,
+    net::{TcpStream, ToSocketAddrs},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine};
+use ssh2::{HashType, Session};
+
+use crate::error::{ApiError, ErrorCode, ErrorContext, Result};
+
+/// Where and as whom files are uploaded
+#[derive(Debug, Clone)]
+pub struct SftpTarget {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    /// `SHA256:` fingerprint of the server's host key
+    pub host_key_sha256: String,
+}
+
+fn failed(action: &str, target: &SftpTarget, e: impl std::fmt::Display) -> ApiError {
+    ApiError::new(
+        ErrorCode::BadGateway,
+        format!("Failed to {} on {}:{}: {}", action, target.host, target.port, e),
+        ErrorContext::new(),
+    )
+}
+
+/// The fingerprint without its `SHA256:` prefix and base64 padding
+fn normalize_fingerprint(fingerprint: &str) -> &str {
+    fingerprint.trim().trim_start_matches("SHA256:").trim_end_matches('=')
+}
+
+/// Uploads `bytes` to `remote_path`, replacing any file there
+///
+/// Blocks the calling thread; run it off the async executor.
+pub fn upload(target: &SftpTarget, private_key: &Path, timeout: Duration, remote_path: &str, bytes: &[u8]) -> Result<()> {
+    let address = (target.host.as_str(), target.port)
+        .to_socket_addrs()
+        .map_err(|e| failed("resolve host", target, e))?
+        .next()
+        .ok_or_else(|| failed("resolve host", target, "no address"))?;
+    let tcp = TcpStream::connect_timeout(&address, timeout).map_err(|e| failed("connect", target, e))?;
+
+    let mut session = Session::new().map_err(|e| failed("start SSH session", target, e))?;
+    session.set_timeout(timeout.as_millis().min(u32::MAX as u128) as u32);
+    session.set_tcp_stream(tcp);
+    session.handshake().map_err(|e| failed("complete SSH handshake", target, e))?;
+
+    let fingerprint = session
+        .host_key_hash(HashType::Sha256)
+        .map(|hash| STANDARD_NO_PAD.encode(hash))
+        .ok_or_else(|| failed("read host key", target, "no host key"))?;
+    if fingerprint != normalize_fingerprint(&target.host_key_sha256) {
+        return Err(failed("verify host key", target, format!("unexpected fingerprint SHA256:{}", fingerprint)));
+    }
+    session
+        .userauth_pubkey_file(&target.username, None, private_key, None)
+        .map_err(|e| failed("authenticate", target, e))?;
+
+    let sftp = session.sftp().map_err(|e| failed("start SFTP", target, e))?;
+    let final_path = PathBuf::from(remote_path);
+    let part_path = PathBuf::from(format!("{}.part", remote_path));
+    let mut file = sftp.create(&part_path).map_err(|e| failed("create file", target, e))?;
+    file.write_all(bytes).map_err(|e| failed("write file", target, e))?;
+    drop(file);
+    // Rename does not replace an existing file on every server
+    let _ = sftp.unlink(&final_path);
+    sftp.rename(&part_path, &final_path, None).map_err(|e| failed("rename file", target, e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_fingerprint() {
+        assert_eq!(normalize_fingerprint(" SHA256:nThbg6kXUpJWGl7E1IGOCspRomTxdCARLviKw6E5SY8= "), "nThbg6kXUpJWGl7E1IGOCspRomTxdCARLviKw6E5SY8");
+        assert_eq!(normalize_fingerprint("nThbg6kXUpJWGl7E1IGOCspRomTxdCARLviKw6E5SY8"), "nThbg6kXUpJWGl7E1IGOCspRomTxdCARLviKw6E5SY8");
+    }
+}
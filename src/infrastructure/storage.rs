@@ -0,0 +1,128 @@
+//! Object storage
+//!
+//! Thin wrapper around `object_store` that selects a backend from a URL:
+//!
+//! - `s3://bucket/prefix` - Amazon S3 (or compatible), credentials from the
+//!   standard `AWS_*` environment variables
+//! - `file:///var/lib/forestry` - local directory, created on startup
+//! - `memory://` - in-process store, useful for tests
+
+use std::{fmt, path::Path as FsPath, sync::Arc};
+
+use bytes::Bytes;
+use object_store::{
+    aws::AmazonS3Builder, local::LocalFileSystem, memory::InMemory, path::Path, ObjectStore,
+};
+use tracing::error;
+
+use crate::error::{ApiError, ErrorCode, ErrorContext, Result};
+
+/// Handle to the configured object storage backend
+#[derive(Clone)]
+pub struct ObjectStorage {
+    store: Arc<dyn ObjectStore>,
+    url: String,
+}
+
+impl fmt::Debug for ObjectStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObjectStorage").field("url", &self.url).finish()
+    }
+}
+
+impl ObjectStorage {
+    /// Creates a storage handle from a backend URL
+    pub fn from_url(url: &str) -> Result<Self> {
+        let store: Arc<dyn ObjectStore> = if let Some(path) = url.strip_prefix("file://") {
+            std::fs::create_dir_all(path).map_err(|e| {
+                ApiError::configuration_error(format!("Failed to create storage directory {}: {}", path, e))
+            })?;
+            Arc::new(LocalFileSystem::new_with_prefix(FsPath::new(path)).map_err(|e| {
+                ApiError::configuration_error(format!("Invalid storage directory {}: {}", path, e))
+            })?)
+        } else if url.starts_with("s3://") {
+            Arc::new(AmazonS3Builder::from_env().with_url(url).build().map_err(|e| {
+                ApiError::configuration_error(format!("Invalid S3 storage URL {}: {}", url, e))
+            })?)
+        } else if url.starts_with("memory://") {
+            Arc::new(InMemory::new())
+        } else {
+            return Err(ApiError::configuration_error(format!(
+                "Unsupported storage URL: {}",
+                url
+            )));
+        };
+
+        Ok(Self {
+            store,
+            url: url.to_string(),
+        })
+    }
+
+    /// Creates an in-memory storage handle
+    pub fn in_memory() -> Self {
+        Self {
+            store: Arc::new(InMemory::new()),
+            url: "memory://".to_string(),
+        }
+    }
+
+    /// Stores an object under the given key, replacing any existing object
+    pub async fn put(&self, key: &str, body: Bytes) -> Result<()> {
+        self.store
+            .put(&Path::from(key), body.into())
+            .await
+            .map(|_| ())
+            .map_err(|e| storage_error("write", key, e))
+    }
+
+    /// Reads a whole object
+    pub async fn get(&self, key: &str) -> Result<Bytes> {
+        let object = self
+            .store
+            .get(&Path::from(key))
+            .await
+            .map_err(|e| storage_error("read", key, e))?;
+
+        object.bytes().await.map_err(|e| storage_error("read", key, e))
+    }
+
+    /// Checks that the backend answers requests
+    ///
+    /// Looks up a key that normally does not exist; a "not found" answer
+    /// still proves the backend is reachable.
+    pub async fn ping(&self) -> Result<()> {
+        const PROBE_KEY: &str = ".health-check";
+        match self.store.head(&Path::from(PROBE_KEY)).await {
+            Ok(_) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(storage_error("read", PROBE_KEY, e)),
+        }
+    }
+
+    /// Deletes an object
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        self.store
+            .delete(&Path::from(key))
+            .await
+            .map_err(|e| storage_error("delete", key, e))
+    }
+}
+
+fn storage_error(operation: &str, key: &str, e: object_store::Error) -> ApiError {
+    if let object_store::Error::NotFound { .. } = e {
+        return ApiError::not_found(format!("Stored object {} not found", key));
+    }
+
+    error!(
+        error_code = %ErrorCode::IoError,
+        key = %key,
+        error = %e,
+        "Object storage {} failed",
+        operation
+    );
+    ApiError::new(
+        ErrorCode::IoError,
+        format!("Failed to {} stored object", operation),
+        ErrorContext::default(),
+    )
+}
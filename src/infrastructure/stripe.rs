@@ -0,0 +1,106 @@
+//! Stripe API client
+//!
+//! Stripe takes form-encoded requests authenticated with the account's
+//! secret key and answers with JSON. Only what billing needs is covered:
+//! creating Checkout sessions for subscriptions.
+
+use std::{fmt, time::Duration};
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    utils::BillingConfig,
+};
+
+/// Upper bound for a Stripe request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+/// What a Checkout session subscribes an organization to
+#[derive(Debug, Clone)]
+pub struct CheckoutRequest<'a> {
+    pub org_id: Uuid,
+    pub plan: &'a str,
+    pub price: &'a str,
+    /// Stripe customer of an organization that subscribed before
+    pub customer: Option<&'a str>,
+    /// Email prefilled for a new customer
+    pub customer_email: Option<&'a str>,
+    pub success_url: &'a str,
+    pub cancel_url: &'a str,
+}
+
+/// A Checkout session, paid for on the page at `url`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckoutSession {
+    pub id: String,
+    pub url: String,
+}
+
+/// Creates a subscription Checkout session
+///
+/// The organization and plan go into the metadata of both the session and
+/// the subscription it creates, so webhook events of either lead back to
+/// the organization.
+pub async fn create_checkout_session(config: &BillingConfig, request: &CheckoutRequest<'_>) -> Result<CheckoutSession> {
+    let org_id = request.org_id.to_string();
+    let mut form = vec![
+        ("mode", "subscription"),
+        ("line_items[0][price]", request.price),
+        ("line_items[0][quantity]", "1"),
+        ("client_reference_id", org_id.as_str()),
+        ("metadata[org_id]", org_id.as_str()),
+        ("metadata[plan]", request.plan),
+        ("subscription_data[metadata][org_id]", org_id.as_str()),
+        ("subscription_data[metadata][plan]", request.plan),
+        ("success_url", request.success_url),
+        ("cancel_url", request.cancel_url),
+    ];
+    match (request.customer, request.customer_email) {
+        (Some(customer), _) => form.push(("customer", customer)),
+        (None, Some(email)) => form.push(("customer_email", email)),
+        (None, None) => {}
+    }
+
+    let url = format!("{}/v1/checkout/sessions", config.stripe_api_url.trim_end_matches('/'));
+    let response = HTTP_CLIENT
+        .post(url)
+        .bearer_auth(config.stripe_secret_key.as_deref().unwrap_or_default())
+        .form(&form)
+        .send()
+        .await
+        .map_err(provider_error)?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(provider_error(format!("{} {}", status, body)));
+    }
+    let session: CheckoutSession = response.json().await.map_err(provider_error)?;
+
+    info!(org_id = %request.org_id, plan = request.plan, session_id = %session.id, "Stripe Checkout session created");
+    Ok(session)
+}
+
+/// Reports a Stripe API that is unreachable or answers unexpectedly
+fn provider_error(reason: impl fmt::Display) -> ApiError {
+    error!(
+        error_code = %ErrorCode::BadGateway,
+        error = %reason,
+        "Stripe request failed"
+    );
+    ApiError::new(
+        ErrorCode::BadGateway,
+        "Billing is unavailable",
+        ErrorContext::new(),
+    )
+}
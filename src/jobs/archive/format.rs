@@ -0,0 +1,138 @@
+//! Parquet encoding for archived records
+//!
+//! Every dataset is written with the same three-column schema so that any
+//! archive can be read back without knowing the dataset-specific row type:
+//!
+//! ```text
+//! message archive_record {
+//!     REQUIRED BYTE_ARRAY id (UTF8);
+//!     REQUIRED INT64 recorded_at (TIMESTAMP(MICROS,true));
+//!     REQUIRED BYTE_ARRAY payload (JSON);
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::DateTime;
+use parquet::{
+    basic::Compression,
+    data_type::{ByteArray, ByteArrayType, Int64Type},
+    file::{
+        properties::WriterProperties,
+        reader::{FileReader, SerializedFileReader},
+        writer::SerializedFileWriter,
+    },
+    record::RowAccessor,
+    schema::parser::parse_message_type,
+};
+use tracing::error;
+use uuid::Uuid;
+
+use super::ArchiveRecord;
+use crate::error::{ApiError, ErrorCode, ErrorContext, Result};
+
+const SCHEMA: &str = "
+    message archive_record {
+        REQUIRED BYTE_ARRAY id (UTF8);
+        REQUIRED INT64 recorded_at (TIMESTAMP(MICROS,true));
+        REQUIRED BYTE_ARRAY payload (JSON);
+    }
+";
+
+/// Encodes records into a Snappy-compressed Parquet file
+pub fn encode(records: &[ArchiveRecord]) -> Result<Bytes> {
+    write(records).map_err(|e| format_error("encode", e))
+}
+
+/// Decodes records from a Parquet file written by [`encode`]
+pub fn decode(data: Bytes) -> Result<Vec<ArchiveRecord>> {
+    read(data).map_err(|e| format_error("decode", e))
+}
+
+fn write(records: &[ArchiveRecord]) -> std::result::Result<Bytes, Box<dyn std::error::Error>> {
+    let schema = Arc::new(parse_message_type(SCHEMA)?);
+    let properties = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build(),
+    );
+
+    let ids: Vec<ByteArray> = records.iter().map(|r| r.id.to_string().as_str().into()).collect();
+    let timestamps: Vec<i64> = records.iter().map(|r| r.recorded_at.timestamp_micros()).collect();
+    let payloads: Vec<ByteArray> = records
+        .iter()
+        .map(|r| serde_json::to_vec(&r.payload).map(ByteArray::from))
+        .collect::<std::result::Result<_, _>>()?;
+
+    let mut writer = SerializedFileWriter::new(Vec::new(), schema, properties)?;
+    let mut row_group = writer.next_row_group()?;
+
+    let mut column = row_group.next_column()?.ok_or("missing id column")?;
+    column.typed::<ByteArrayType>().write_batch(&ids, None, None)?;
+    column.close()?;
+
+    let mut column = row_group.next_column()?.ok_or("missing recorded_at column")?;
+    column.typed::<Int64Type>().write_batch(&timestamps, None, None)?;
+    column.close()?;
+
+    let mut column = row_group.next_column()?.ok_or("missing payload column")?;
+    column.typed::<ByteArrayType>().write_batch(&payloads, None, None)?;
+    column.close()?;
+
+    row_group.close()?;
+    Ok(Bytes::from(writer.into_inner()?))
+}
+
+fn read(data: Bytes) -> std::result::Result<Vec<ArchiveRecord>, Box<dyn std::error::Error>> {
+    let reader = SerializedFileReader::new(data)?;
+    let mut records = Vec::with_capacity(reader.metadata().file_metadata().num_rows() as usize);
+
+    for row in reader.get_row_iter(None)? {
+        let row = row?;
+        records.push(ArchiveRecord {
+            id: Uuid::parse_str(row.get_string(0)?)?,
+            recorded_at: DateTime::from_timestamp_micros(row.get_timestamp_micros(1)?)
+                .ok_or("recorded_at out of range")?,
+            payload: serde_json::from_str(row.get_string(2)?)?,
+        });
+    }
+
+    Ok(records)
+}
+
+fn format_error(operation: &str, e: Box<dyn std::error::Error>) -> ApiError {
+    error!(
+        error_code = %ErrorCode::InternalError,
+        error = %e,
+        "Failed to {} archive file",
+        operation
+    );
+    ApiError::new(
+        ErrorCode::InternalError,
+        format!("Failed to {} archive file", operation),
+        ErrorContext::default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let records: Vec<ArchiveRecord> = (0..3)
+            .map(|i| ArchiveRecord {
+                id: Uuid::new_v4(),
+                recorded_at: DateTime::from_timestamp_micros(Utc::now().timestamp_micros() - i).unwrap(),
+                payload: serde_json::json!({ "reading": i, "unit": "m3" }),
+            })
+            .collect();
+
+        let encoded = encode(&records).unwrap();
+        assert_eq!(&encoded[..4], b"PAR1");
+        assert_eq!(decode(encoded).unwrap(), records);
+        assert!(decode(Bytes::from_static(b"not parquet")).is_err());
+    }
+}
@@ -0,0 +1,392 @@
+//! Cold storage archival
+//!
+//! The archiver moves records that are past the retention window out of the
+//! hot database. Each pass exports a batch of records to a Parquet file in
+//! object storage, deletes them from their source tables and leaves an
+//! [`Archive`] stub describing the file. Archives can be read back directly
+//! from storage or rehydrated into the hot tables on demand.
+//!
+//! What gets archived is defined by [`ArchiveDataset`] implementations, one
+//! per source table, registered in [`datasets`].
+
+pub mod format;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use diesel::{prelude::*, sql_types::{BigInt, Bool}};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        get_connection,
+        models::Archive,
+        repositories::{ArchiveRepository, ArchiveRepositoryImpl, Repository},
+        schema::archives,
+        DbPool,
+    },
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::ObjectStorage,
+    jobs::{scheduler::ScheduledJob, shutdown::Shutdown},
+    utils::Config,
+};
+
+/// Maximum number of records written to a single archive file
+pub const DEFAULT_BATCH_SIZE: i64 = 50_000;
+
+/// How long rehydrated records are left in the hot database
+pub const DEFAULT_REHYDRATE_GRACE_DAYS: i64 = 30;
+
+/// Advisory lock key ensuring only one instance archives at a time
+const ARCHIVE_LOCK_KEY: i64 = 0x6172_6368_6976_6572;
+
+/// A single archived record
+///
+/// Dataset rows are flattened into a stable id, the timestamp used for
+/// retention and a JSON payload holding the remaining columns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct ArchiveRecord {
+    pub id: Uuid,
+    pub recorded_at: DateTime<Utc>,
+    pub payload: serde_json::Value,
+}
+
+/// A time range excluded from archival
+pub type TimeRange = (DateTime<Utc>, DateTime<Utc>);
+
+/// A source of records that can be moved to cold storage
+///
+/// Methods are synchronous because they run inside the database transaction
+/// that removes the records from (or restores them to) the hot tables.
+pub trait ArchiveDataset: Send + Sync {
+    /// Stable dataset name, used in storage keys and archive stubs
+    fn name(&self) -> &'static str;
+
+    /// Loads up to `limit` records recorded before `cutoff`, oldest first,
+    /// skipping records that fall inside any of the `skip` ranges
+    fn extract(
+        &self,
+        conn: &mut PgConnection,
+        cutoff: DateTime<Utc>,
+        skip: &[TimeRange],
+        limit: i64,
+    ) -> QueryResult<Vec<ArchiveRecord>>;
+
+    /// Deletes the given records from the hot tables
+    fn purge(&self, conn: &mut PgConnection, ids: &[Uuid]) -> QueryResult<usize>;
+
+    /// Inserts previously archived records back into the hot tables
+    fn restore(&self, conn: &mut PgConnection, records: &[ArchiveRecord]) -> QueryResult<usize>;
+}
+
+/// Datasets moved to cold storage by the scheduled archiver
+pub fn datasets() -> Vec<Arc<dyn ArchiveDataset>> {
+    Vec::new()
+}
+
+/// Moves records between the hot database and cold storage
+#[derive(Clone)]
+pub struct Archiver {
+    storage: ObjectStorage,
+    datasets: Vec<Arc<dyn ArchiveDataset>>,
+    retention: Duration,
+    rehydrate_grace: Duration,
+    batch_size: i64,
+    shutdown: Option<Shutdown>,
+}
+
+impl Archiver {
+    /// Creates an archiver for records older than `retention`
+    pub fn new(storage: ObjectStorage, retention: Duration) -> Self {
+        Self {
+            storage,
+            datasets: Vec::new(),
+            retention,
+            rehydrate_grace: Duration::days(DEFAULT_REHYDRATE_GRACE_DAYS),
+            batch_size: DEFAULT_BATCH_SIZE,
+            shutdown: None,
+        }
+    }
+
+    /// Creates an archiver with the configured storage, retention and datasets
+    pub fn from_config(config: &Config) -> Self {
+        let archiver = Self::new(config.storage().clone(), Duration::days(config.archive_after_days));
+        datasets()
+            .into_iter()
+            .fold(archiver, |archiver, dataset| archiver.with_dataset(dataset))
+    }
+
+    /// Registers a dataset
+    pub fn with_dataset(mut self, dataset: Arc<dyn ArchiveDataset>) -> Self {
+        self.datasets.push(dataset);
+        self
+    }
+
+    /// Sets the maximum number of records per archive file
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets how long rehydrated records are exempt from archival
+    pub fn with_rehydrate_grace(mut self, grace: Duration) -> Self {
+        self.rehydrate_grace = grace;
+        self
+    }
+
+    /// Stops passes between batches once `shutdown` is triggered
+    ///
+    /// Every batch is committed on its own, so an interrupted pass resumes
+    /// with the remaining records on the next run.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    fn stopping(&self) -> bool {
+        self.shutdown.as_ref().is_some_and(Shutdown::is_triggered)
+    }
+
+    /// Looks up a registered dataset by name
+    pub fn dataset(&self, name: &str) -> Option<&Arc<dyn ArchiveDataset>> {
+        self.datasets.iter().find(|dataset| dataset.name() == name)
+    }
+
+    /// Archives all eligible records of every registered dataset
+    ///
+    /// Returns the archives created during this pass. When another instance
+    /// already holds the archive lock the pass is skipped.
+    pub async fn run(&self, conn: &mut PgConnection) -> Result<Vec<Archive>> {
+        if !advisory_lock(conn, "pg_try_advisory_lock")? {
+            info!("Archive pass already running on another instance, skipping");
+            return Ok(Vec::new());
+        }
+
+        let result = self.run_locked(conn).await;
+
+        if let Err(e) = advisory_lock(conn, "pg_advisory_unlock") {
+            warn!(error = %e, "Failed to release archive lock");
+        }
+        result
+    }
+
+    async fn run_locked(&self, conn: &mut PgConnection) -> Result<Vec<Archive>> {
+        let now = Utc::now();
+        let cutoff = now - self.retention;
+        let mut created = Vec::new();
+
+        for dataset in &self.datasets {
+            if self.stopping() {
+                info!(archives = created.len(), "Archive pass interrupted by shutdown");
+                break;
+            }
+
+            let skip = ArchiveRepositoryImpl
+                .rehydrated_ranges(conn, dataset.name(), now - self.rehydrate_grace)
+                .await?;
+
+            while let Some(archive) = self.archive_batch(conn, dataset.as_ref(), cutoff, &skip).await? {
+                let full = archive.record_count >= self.batch_size;
+                created.push(archive);
+                if !full || self.stopping() {
+                    break;
+                }
+            }
+        }
+
+        Ok(created)
+    }
+
+    /// Archives a single batch of a dataset, returning `None` when nothing is eligible
+    async fn archive_batch(
+        &self,
+        conn: &mut PgConnection,
+        dataset: &dyn ArchiveDataset,
+        cutoff: DateTime<Utc>,
+        skip: &[TimeRange],
+    ) -> Result<Option<Archive>> {
+        let records = dataset
+            .extract(conn, cutoff, skip, self.batch_size)
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    dataset = %dataset.name(),
+                    error = %e,
+                    "Failed to extract records for archival"
+                );
+                ApiError::database_error("Failed to extract records for archival", None)
+            })?;
+
+        let (Some(range_start), Some(range_end)) = (
+            records.iter().map(|r| r.recorded_at).min(),
+            records.iter().map(|r| r.recorded_at).max(),
+        ) else {
+            return Ok(None);
+        };
+
+        let body = format::encode(&records)?;
+        let now = Utc::now();
+        let archive_id = Uuid::new_v4();
+        let archive = Archive {
+            id: archive_id,
+            dataset: dataset.name().to_string(),
+            storage_key: format!(
+                "archives/{}/{}/{}.parquet",
+                dataset.name(),
+                range_start.format("%Y/%m"),
+                archive_id
+            ),
+            record_count: records.len() as i64,
+            byte_size: body.len() as i64,
+            range_start,
+            range_end,
+            rehydrated_at: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+
+        // Upload first so the hot rows are only removed once the cold copy exists
+        self.storage.put(&archive.storage_key, body).await?;
+
+        let ids: Vec<Uuid> = records.iter().map(|r| r.id).collect();
+        let stored = conn.transaction(|conn| {
+            let purged = dataset.purge(conn, &ids)?;
+            if purged != ids.len() {
+                warn!(
+                    dataset = %dataset.name(),
+                    expected = ids.len(),
+                    purged = purged,
+                    "Archived record count differs from purged row count"
+                );
+            }
+            diesel::insert_into(archives::table)
+                .values(&archive)
+                .get_result::<Archive>(conn)
+        });
+
+        match stored {
+            Ok(archive) => {
+                info!(
+                    archive_id = %archive.id,
+                    dataset = %archive.dataset,
+                    records = archive.record_count,
+                    bytes = archive.byte_size,
+                    "Archived records to cold storage"
+                );
+                Ok(Some(archive))
+            }
+            Err(e) => {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    dataset = %dataset.name(),
+                    error = %e,
+                    "Failed to purge archived records"
+                );
+                if let Err(e) = self.storage.delete(&archive.storage_key).await {
+                    warn!(key = %archive.storage_key, error = %e, "Failed to remove orphaned archive file");
+                }
+                Err(ApiError::database_error("Failed to archive records", None))
+            }
+        }
+    }
+
+    /// Reads the records of an archive straight from cold storage
+    pub async fn read(&self, archive: &Archive) -> Result<Vec<ArchiveRecord>> {
+        let data = self.storage.get(&archive.storage_key).await?;
+        format::decode(data)
+    }
+
+    /// Restores the records of an archive into the hot database
+    ///
+    /// The stub is marked rehydrated and soft deleted, and the cold copy is
+    /// removed. Restored records are exempt from archival for the rehydrate
+    /// grace period.
+    pub async fn rehydrate(&self, conn: &mut PgConnection, archive_id: Uuid) -> Result<Archive> {
+        let archive = ArchiveRepositoryImpl.find_by_id(conn, archive_id).await?;
+        let dataset = self.dataset(&archive.dataset).ok_or_else(|| {
+            ApiError::new(
+                ErrorCode::UnprocessableEntity,
+                format!("Dataset {} cannot be rehydrated", archive.dataset),
+                ErrorContext::default(),
+            )
+        })?;
+
+        let records = self.read(&archive).await?;
+        let now = Utc::now();
+        let rehydrated = conn
+            .transaction(|conn| {
+                dataset.restore(conn, &records)?;
+                diesel::update(archives::table.find(archive.id))
+                    .set((
+                        archives::rehydrated_at.eq(Some(now)),
+                        archives::updated_at.eq(now),
+                        archives::deleted_at.eq(Some(now)),
+                    ))
+                    .get_result::<Archive>(conn)
+            })
+            .map_err(|e| {
+                error!(
+                    error_code = %ErrorCode::DatabaseError,
+                    archive_id = %archive.id,
+                    error = %e,
+                    "Failed to rehydrate archive"
+                );
+                ApiError::database_error("Failed to rehydrate archive", None)
+            })?;
+
+        if let Err(e) = self.storage.delete(&archive.storage_key).await {
+            warn!(key = %archive.storage_key, error = %e, "Failed to remove rehydrated archive file");
+        }
+
+        info!(
+            archive_id = %rehydrated.id,
+            dataset = %rehydrated.dataset,
+            records = records.len(),
+            "Rehydrated archive"
+        );
+        Ok(rehydrated)
+    }
+}
+
+#[derive(QueryableByName)]
+struct LockResult {
+    #[diesel(sql_type = Bool)]
+    acquired: bool,
+}
+
+fn advisory_lock(conn: &mut PgConnection, function: &str) -> Result<bool> {
+    diesel::sql_query(format!("SELECT {}($1) AS acquired", function))
+        .bind::<BigInt, _>(ARCHIVE_LOCK_KEY)
+        .get_result::<LockResult>(conn)
+        .map(|row| row.acquired)
+        .map_err(|e| {
+            error!(error_code = %ErrorCode::DatabaseError, error = %e, "Failed to manage archive lock");
+            ApiError::database_error("Failed to manage archive lock", None)
+        })
+}
+
+#[async_trait(?Send)]
+impl ScheduledJob for Archiver {
+    fn name(&self) -> &'static str {
+        "archiver"
+    }
+
+    async fn run(&self, pool: &DbPool) -> Result<serde_json::Value> {
+        let mut conn = get_connection(pool)?;
+        let archives = Archiver::run(self, &mut conn).await?;
+        if !archives.is_empty() {
+            info!(archives = archives.len(), "Archive pass completed");
+        }
+        Ok(serde_json::json!({
+            "archives": archives.len(),
+            "records": archives.iter().map(|archive| archive.record_count).sum::<i64>(),
+        }))
+    }
+}
@@ -0,0 +1,56 @@
+//! Certification expiry notices
+//!
+//! The [`CertificationExpiryNotifier`] runs as the `certification_expiry`
+//! scheduled job, notifying supervisors in the notification center of the
+//! certifications expiring within
+//! [`EXPIRY_NOTICE_DAYS`](crate::domain::certification::EXPIRY_NOTICE_DAYS).
+//! Each certification is notified once, so the job can run as often as
+//! wanted; renewing a certification, changing its expiry, arms it again.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::json;
+
+use crate::{
+    db::{
+        get_connection,
+        repositories::{CertificationRepositoryImpl, DocumentRepositoryImpl},
+        DbPool,
+    },
+    domain::{certification::CertificationService, document::DocumentService},
+    error::Result,
+    jobs::scheduler::ScheduledJob,
+    utils::Config,
+};
+
+/// Notifies supervisors of coming certification expiries
+pub struct CertificationExpiryNotifier {
+    service: CertificationService<CertificationRepositoryImpl>,
+}
+
+impl CertificationExpiryNotifier {
+    pub fn new(service: CertificationService<CertificationRepositoryImpl>) -> Self {
+        Self { service }
+    }
+
+    /// Creates a notifier with the configured storage
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(CertificationService::new(
+            CertificationRepositoryImpl,
+            DocumentService::new(DocumentRepositoryImpl, config.storage().clone()),
+        ))
+    }
+}
+
+#[async_trait(?Send)]
+impl ScheduledJob for CertificationExpiryNotifier {
+    fn name(&self) -> &'static str {
+        "certification_expiry"
+    }
+
+    async fn run(&self, pool: &DbPool) -> Result<serde_json::Value> {
+        let mut conn = get_connection(pool)?;
+        let notified = self.service.notify_expiring(&mut conn, Utc::now().date_naive()).await?;
+        Ok(json!({ "certifications": notified }))
+    }
+}
@@ -0,0 +1,51 @@
+//! Queued email delivery
+//!
+//! Mail is sent through the durable job queue so a provider outage delays
+//! delivery instead of failing the request that triggered it. While the
+//! health checks report the provider down, deliveries fail fast and are
+//! retried later without waiting for the provider to time out.
+
+use async_trait::async_trait;
+use diesel::PgConnection;
+
+use crate::{
+    db::{models::QueuedJob, DbPool},
+    error::{ApiError, Result},
+    infrastructure::{dependencies, email::EmailMessage, DependencyMonitor, Mailer},
+    jobs::queue::{self, JobHandler},
+    utils::QueueConfig,
+};
+
+/// Kind of queued email jobs
+pub const EMAIL_JOB: &str = "email";
+
+/// Queues a composed message for delivery
+pub async fn enqueue(conn: &mut PgConnection, config: &QueueConfig, message: &EmailMessage) -> Result<QueuedJob> {
+    queue::enqueue(conn, config, EMAIL_JOB, message).await
+}
+
+/// Sends queued email through the [`Mailer`]
+pub struct EmailDelivery {
+    mailer: Mailer,
+    dependencies: DependencyMonitor,
+}
+
+impl EmailDelivery {
+    pub fn new(mailer: Mailer, dependencies: DependencyMonitor) -> Self {
+        Self { mailer, dependencies }
+    }
+}
+
+#[async_trait(?Send)]
+impl JobHandler for EmailDelivery {
+    fn kind(&self) -> &'static str {
+        EMAIL_JOB
+    }
+
+    async fn handle(&self, _pool: &DbPool, payload: &serde_json::Value) -> Result<()> {
+        self.dependencies.require(dependencies::EMAIL)?;
+        let message: EmailMessage = serde_json::from_value(payload.clone())
+            .map_err(|e| ApiError::validation(format!("Invalid email job payload: {}", e), None))?;
+        self.mailer.send(&message).await
+    }
+}
@@ -0,0 +1,145 @@
+//! ERP export delivery
+//!
+//! Exports are written by [`crate::domain::erp::ErpService`], on request or
+//! by the [`ErpExporter`] scheduled job for connections with a schedule,
+//! and delivered by the [`ErpExportDelivery`] queue job through the
+//! connection's connector. A failed delivery is recorded on the export and
+//! retried by the queue until it succeeds or moves to the dead letters.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use diesel::PgConnection;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        get_connection,
+        models::{ErpExport, ErpExportStatus},
+        repositories::{ErpRepository, ErpRepositoryImpl},
+        DbPool,
+    },
+    domain::erp::{connectors, export_filename, ErpService, ExportConnector, ExportFile, ERP_EXPORT_JOB},
+    error::{ApiError, Result},
+    infrastructure::ObjectStorage,
+    jobs::{queue::JobHandler, scheduler::ScheduledJob},
+    utils::{Config, QueueConfig},
+};
+
+#[derive(Deserialize)]
+struct ErpExportJob {
+    export_id: Uuid,
+}
+
+/// Delivers written exports through their connection's connector
+pub struct ErpExportDelivery {
+    storage: ObjectStorage,
+    connectors: Vec<Arc<dyn ExportConnector>>,
+}
+
+impl ErpExportDelivery {
+    pub fn new(storage: ObjectStorage, connectors: Vec<Arc<dyn ExportConnector>>) -> Self {
+        Self { storage, connectors }
+    }
+
+    /// Creates a delivery job with the configured storage and connectors
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.storage().clone(), connectors(&config.erp))
+    }
+
+    /// Delivers an export once, recording the outcome
+    ///
+    /// A delivered export is left alone, so a repeated job does not send
+    /// the file again. A failed attempt is recorded and returned as an
+    /// error so the queue retries it.
+    pub async fn deliver(&self, conn: &mut PgConnection, export_id: Uuid) -> Result<ErpExport> {
+        let repository = ErpRepositoryImpl;
+        let export = repository.find_export_by_id(conn, export_id).await?;
+        if export.status == ErpExportStatus::Delivered.as_str() {
+            return Ok(export);
+        }
+
+        match self.send(conn, &export).await {
+            Ok(remote_ref) => {
+                let export = repository.mark_delivered(conn, export.id, &remote_ref).await?;
+                info!(
+                    export_id = %export.id,
+                    connection_id = %export.connection_id,
+                    remote_ref = %remote_ref,
+                    "Delivered ERP export"
+                );
+                Ok(export)
+            }
+            Err(e) => {
+                warn!(export_id = %export.id, error = %e.message, "ERP export delivery failed");
+                repository.mark_failed(conn, export.id, &e.message).await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn send(&self, conn: &mut PgConnection, export: &ErpExport) -> Result<String> {
+        let connection = ErpRepositoryImpl.find_connection_by_id(conn, export.connection_id).await?;
+        let connector = self
+            .connectors
+            .iter()
+            .find(|connector| connector.kind() == connection.connector)
+            .ok_or_else(|| ApiError::validation(format!("Unknown connector {}", connection.connector), None))?;
+        let file = ExportFile {
+            export_id: export.id,
+            filename: export_filename(export),
+            bytes: self.storage.get(&export.file_key).await?,
+        };
+        connector.deliver(&connection.settings, &file).await
+    }
+}
+
+#[async_trait(?Send)]
+impl JobHandler for ErpExportDelivery {
+    fn kind(&self) -> &'static str {
+        ERP_EXPORT_JOB
+    }
+
+    async fn handle(&self, pool: &DbPool, payload: &serde_json::Value) -> Result<()> {
+        let job: ErpExportJob = serde_json::from_value(payload.clone())
+            .map_err(|e| ApiError::validation(format!("Invalid ERP export job payload: {}", e), None))?;
+        let mut conn = get_connection(pool)?;
+        self.deliver(&mut conn, job.export_id).await.map(|_| ())
+    }
+}
+
+/// Writes the scheduled exports of connections that are due
+pub struct ErpExporter {
+    service: ErpService<ErpRepositoryImpl>,
+    queue: QueueConfig,
+}
+
+impl ErpExporter {
+    pub fn new(service: ErpService<ErpRepositoryImpl>, queue: QueueConfig) -> Self {
+        Self { service, queue }
+    }
+
+    /// Creates an exporter with the configured storage, connectors and queue
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            ErpService::new(ErpRepositoryImpl, config.storage().clone(), connectors(&config.erp)),
+            config.queue.clone(),
+        )
+    }
+}
+
+#[async_trait(?Send)]
+impl ScheduledJob for ErpExporter {
+    fn name(&self) -> &'static str {
+        "erp_export"
+    }
+
+    async fn run(&self, pool: &DbPool) -> Result<serde_json::Value> {
+        let mut conn = get_connection(pool)?;
+        let exports = self.service.export_due(&mut conn, &self.queue).await?;
+        Ok(json!({ "exports": exports.len() }))
+    }
+}
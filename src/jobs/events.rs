@@ -0,0 +1,161 @@
+//! Domain event publication
+//!
+//! Changes other systems care about (a block approved, a load delivered, an
+//! optimization run completed) are recorded as [`DomainEvent`]s in the job
+//! queue, inside the database transaction of the change itself. The queue is
+//! the outbox: an event exists if and only if its change was committed, and
+//! the [`EventPublisher`] keeps retrying until the event bus accepts it.
+//!
+//! Events are versioned. A consumer reads `type` and `version` to pick a
+//! decoder, and each version goes to its own subject or topic (see
+//! [`crate::infrastructure::event_bus`]). Retries can reorder events, so
+//! consumers that care about order sort by `occurred_at`.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    db::{models::QueuedJob, schema::queued_jobs, DbPool},
+    error::{ApiError, ErrorCode, ErrorContext, Result},
+    infrastructure::{dependencies, DependencyMonitor, EventBus},
+    jobs::queue::JobHandler,
+    utils::{Config, EventTransport},
+};
+
+/// Kind of queued event publication jobs
+pub const EVENT_JOB: &str = "domain_event";
+
+/// Name and schema version of an event
+///
+/// Bump the version on any change a consumer could trip over (a field
+/// removed, renamed or given a new meaning); adding a field is not one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventType {
+    pub name: &'static str,
+    pub version: u32,
+}
+
+/// A harvest block was approved for operations
+pub const BLOCK_APPROVED: EventType = EventType { name: "block.approved", version: 1 };
+/// A load was delivered at its destination
+pub const LOAD_DELIVERED: EventType = EventType { name: "load.delivered", version: 1 };
+/// An optimization run finished
+pub const RUN_COMPLETED: EventType = EventType { name: "run.completed", version: 1 };
+
+/// Something that happened, as published to the event bus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainEvent {
+    /// Unique per event, repeated on redelivery
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub version: u32,
+    pub occurred_at: DateTime<Utc>,
+    pub organization_id: Option<Uuid>,
+    /// Event-specific body, shaped by `type` and `version`
+    pub data: serde_json::Value,
+}
+
+impl DomainEvent {
+    pub fn new(event_type: EventType, data: impl Serialize) -> Result<Self> {
+        let data = serde_json::to_value(data).map_err(|e| {
+            ApiError::new(ErrorCode::InternalError, format!("Failed to encode event: {}", e), ErrorContext::new())
+        })?;
+        Ok(Self {
+            id: Uuid::new_v4(),
+            event_type: event_type.name.to_string(),
+            version: event_type.version,
+            occurred_at: Utc::now(),
+            organization_id: None,
+            data,
+        })
+    }
+
+    pub fn with_organization(mut self, organization_id: Uuid) -> Self {
+        self.organization_id = Some(organization_id);
+        self
+    }
+
+    /// Subject or topic the event is published to, e.g.
+    /// `forestry.block.approved.v1`
+    pub fn subject(&self, prefix: &str) -> String {
+        format!("{}.{}.v{}", prefix, self.event_type, self.version)
+    }
+}
+
+/// Records `event` for publication once the surrounding transaction commits
+///
+/// Synchronous so it can run inside `conn.transaction` next to the change
+/// the event describes. Nothing is recorded while no event transport is
+/// configured.
+pub fn record(conn: &mut PgConnection, config: &Config, event: &DomainEvent) -> Result<()> {
+    if config.events.transport == EventTransport::None {
+        return Ok(());
+    }
+    let payload = serde_json::to_value(event).map_err(|e| {
+        ApiError::new(ErrorCode::InternalError, format!("Failed to encode event: {}", e), ErrorContext::new())
+    })?;
+    diesel::insert_into(queued_jobs::table)
+        .values(&QueuedJob::new(EVENT_JOB, payload, config.queue.max_attempts))
+        .execute(conn)
+        .map_err(|e| ApiError::database_error(format!("Failed to record event {}: {}", event.event_type, e), None))?;
+    Ok(())
+}
+
+/// Publishes recorded events to the [`EventBus`]
+pub struct EventPublisher {
+    bus: EventBus,
+    subject_prefix: String,
+    dependencies: DependencyMonitor,
+}
+
+impl EventPublisher {
+    /// Creates a publisher, `None` when events are not published
+    pub fn from_config(config: &Config) -> Option<Self> {
+        Some(Self {
+            bus: config.event_bus()?.clone(),
+            subject_prefix: config.events.subject_prefix.clone(),
+            dependencies: config.dependencies().clone(),
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl JobHandler for EventPublisher {
+    fn kind(&self) -> &'static str {
+        EVENT_JOB
+    }
+
+    async fn handle(&self, _pool: &DbPool, payload: &serde_json::Value) -> Result<()> {
+        self.dependencies.require(dependencies::EVENT_BUS)?;
+        let event: DomainEvent = serde_json::from_value(payload.clone())
+            .map_err(|e| ApiError::validation(format!("Invalid event job payload: {}", e), None))?;
+        self.bus
+            .publish(&event.subject(&self.subject_prefix), &event.id.to_string(), payload)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_format() {
+        let organization_id = Uuid::new_v4();
+        let event = DomainEvent::new(BLOCK_APPROVED, serde_json::json!({ "block_id": "b-17" }))
+            .unwrap()
+            .with_organization(organization_id);
+
+        assert_eq!(event.subject("forestry"), "forestry.block.approved.v1");
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "block.approved");
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["organization_id"], organization_id.to_string());
+        assert_eq!(json["data"]["block_id"], "b-17");
+        assert_eq!(serde_json::from_value::<DomainEvent>(json).unwrap(), event);
+    }
+}
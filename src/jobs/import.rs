@@ -0,0 +1,139 @@
+//! Loading of committed spreadsheet imports
+//!
+//! Committing an import (see [`crate::domain::import`]) queues an
+//! [`IMPORT_JOB`] with its id. The [`ImportProcessor`] converts every row
+//! through the import's column mapping, inserts the valid records in one
+//! transaction and stores the rejected rows as a CSV error report next to
+//! the uploaded file.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use diesel::{Connection, PgConnection};
+use serde::Deserialize;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        get_connection,
+        models::{Import, ImportStatus},
+        repositories::{ImportOutcome, ImportRepository, ImportRepositoryImpl},
+        DbPool,
+    },
+    domain::import::{
+        error_key, error_report, ColumnMapping, ImportFile, ImportTarget, RejectedRow, RowConverter, IMPORT_JOB,
+    },
+    error::{ApiError, Result},
+    infrastructure::ObjectStorage,
+    jobs::queue::JobHandler,
+};
+
+#[derive(Deserialize)]
+struct ImportJob {
+    import_id: Uuid,
+}
+
+/// Loads committed imports into their targets
+pub struct ImportProcessor {
+    storage: ObjectStorage,
+    targets: Vec<Arc<dyn ImportTarget>>,
+}
+
+impl ImportProcessor {
+    pub fn new(storage: ObjectStorage, targets: Vec<Arc<dyn ImportTarget>>) -> Self {
+        Self { storage, targets }
+    }
+
+    /// Loads an import, `None` when it already ended
+    ///
+    /// Errors reading the uploaded file are returned so the job is retried;
+    /// an import that cannot be loaded is recorded as failed instead.
+    pub async fn process(&self, conn: &mut PgConnection, import_id: Uuid) -> Result<Option<Import>> {
+        let repository = ImportRepositoryImpl;
+        if !repository.start(conn, import_id).await? {
+            return Ok(None);
+        }
+        let import = repository.find_by_id(conn, import_id).await?;
+        let bytes = self.storage.get(&import.file_key).await?;
+
+        let outcome = match self.load(conn, &import, &bytes).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                warn!(import_id = %import.id, error = %e.message, "Import failed");
+                ImportOutcome {
+                    status: ImportStatus::Failed,
+                    imported_rows: None,
+                    failed_rows: None,
+                    error_key: None,
+                    error: Some(e.message),
+                }
+            }
+        };
+        let import = repository.finish(conn, import.id, outcome).await?;
+        info!(
+            import_id = %import.id,
+            org_id = %import.org_id,
+            status = %import.status,
+            imported = import.imported_rows.unwrap_or_default(),
+            rejected = import.failed_rows.unwrap_or_default(),
+            "Processed import '{}'", import.filename
+        );
+        Ok(Some(import))
+    }
+
+    async fn load(&self, conn: &mut PgConnection, import: &Import, bytes: &[u8]) -> Result<ImportOutcome> {
+        let target = self
+            .targets
+            .iter()
+            .find(|target| target.name() == import.target)
+            .cloned()
+            .ok_or_else(|| ApiError::validation(format!("Unknown import target {}", import.target), None))?;
+        let mapping: ColumnMapping = serde_json::from_value(import.mapping.clone())
+            .map_err(|e| ApiError::validation(format!("Invalid column mapping: {}", e), None))?;
+        let file = ImportFile::parse(&import.filename, bytes)?;
+        let converter = RowConverter::new(target.clone(), &file.headers, &mapping)?;
+
+        let mut records = Vec::new();
+        let mut rejected = Vec::new();
+        for (index, row) in file.rows.into_iter().enumerate() {
+            match converter.convert(&row) {
+                Ok(record) => records.push(record),
+                Err(errors) => rejected.push(RejectedRow { line: index + 2, errors, cells: row }),
+            }
+        }
+
+        let imported = conn
+            .transaction(|conn| target.insert(conn, import.org_id, &records))
+            .map_err(|e| ApiError::database_error(format!("Failed to insert imported rows: {}", e), None))?;
+        let report_key = if rejected.is_empty() {
+            None
+        } else {
+            let key = error_key(import.org_id, import.id);
+            self.storage.put(&key, Bytes::from(error_report(&file.headers, &rejected)?)).await?;
+            Some(key)
+        };
+        Ok(ImportOutcome {
+            status: ImportStatus::Completed,
+            imported_rows: Some(imported as i32),
+            failed_rows: Some(rejected.len() as i32),
+            error_key: report_key,
+            error: None,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl JobHandler for ImportProcessor {
+    fn kind(&self) -> &'static str {
+        IMPORT_JOB
+    }
+
+    async fn handle(&self, pool: &DbPool, payload: &serde_json::Value) -> Result<()> {
+        let job: ImportJob = serde_json::from_value(payload.clone())
+            .map_err(|e| ApiError::validation(format!("Invalid import job payload: {}", e), None))?;
+        let mut conn = get_connection(pool)?;
+        self.process(&mut conn, job.import_id).await.map(|_| ())
+    }
+}
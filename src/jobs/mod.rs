@@ -0,0 +1,22 @@
+//! Background jobs
+//!
+//! Long-running maintenance work that runs outside the request cycle.
+
+pub mod archive;
+pub mod certification_expiry;
+pub mod email;
+pub mod erp_export;
+pub mod events;
+pub mod import;
+pub mod organization_export;
+pub mod purge;
+pub mod queue;
+pub mod report_delivery;
+pub mod scheduler;
+pub mod shutdown;
+
+/// Identifies this instance in job leases, as `host:pid`
+pub fn instance_id() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "localhost".to_string());
+    format!("{}:{}", host, std::process::id())
+}
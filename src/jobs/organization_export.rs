@@ -0,0 +1,48 @@
+//! Organization export bundles
+//!
+//! Archiving an organization queues an [`OrganizationExporter`] job that
+//! writes the organization's export bundle to object storage through
+//! [`crate::domain::organization::ArchiveService`]. A failed attempt is
+//! recorded on the archival and retried by the queue.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    db::{get_connection, DbPool},
+    domain::organization::{ArchiveService, ORGANIZATION_EXPORT_JOB},
+    error::{ApiError, Result},
+    infrastructure::ObjectStorage,
+    jobs::queue::JobHandler,
+};
+
+#[derive(Deserialize)]
+struct OrganizationExportJob {
+    archive_id: Uuid,
+}
+
+/// Writes the export bundles of archived organizations
+pub struct OrganizationExporter {
+    storage: ObjectStorage,
+}
+
+impl OrganizationExporter {
+    pub fn new(storage: ObjectStorage) -> Self {
+        Self { storage }
+    }
+}
+
+#[async_trait(?Send)]
+impl JobHandler for OrganizationExporter {
+    fn kind(&self) -> &'static str {
+        ORGANIZATION_EXPORT_JOB
+    }
+
+    async fn handle(&self, pool: &DbPool, payload: &serde_json::Value) -> Result<()> {
+        let job: OrganizationExportJob = serde_json::from_value(payload.clone())
+            .map_err(|e| ApiError::validation(format!("Invalid organization export job payload: {}", e), None))?;
+        let mut conn = get_connection(pool)?;
+        ArchiveService::write_bundle(&mut conn, &self.storage, job.archive_id).await.map(|_| ())
+    }
+}
@@ -0,0 +1,169 @@
+//! Soft-delete purging
+//!
+//! Soft-deleted rows are kept for the retention configured per entity type
+//! and then removed for good. Records under an active legal hold are never
+//! purged, no matter how long ago they were deleted.
+//!
+//! Machine telemetry is not soft-deleted; points are removed once older
+//! than the telemetry retention of their organization's plan, unless the
+//! organization is under a legal hold.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, sql_types::{Integer, Text, Timestamptz}};
+use serde::Serialize;
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    db::{get_connection, DbPool},
+    domain::{
+        organization::DEFAULT_TELEMETRY_RETENTION_DAYS,
+        retention::{PurgeTarget, RetentionPolicy, PURGE_TARGETS},
+    },
+    error::Result,
+    jobs::{scheduler::ScheduledJob, shutdown::Shutdown},
+    utils::Config,
+};
+
+/// Entity type reported for purged machine telemetry
+const TELEMETRY_ENTITY: &str = "machine_telemetry";
+
+/// Outcome of purging one entity type
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PurgeReport {
+    pub entity_type: String,
+    pub purged: usize,
+}
+
+/// Permanently removes soft-deleted records past their retention
+#[derive(Debug, Clone)]
+pub struct Purger {
+    policy: RetentionPolicy,
+    shutdown: Option<Shutdown>,
+}
+
+impl Purger {
+    /// Creates a purger enforcing the given policy
+    pub fn new(policy: RetentionPolicy) -> Self {
+        Self { policy, shutdown: None }
+    }
+
+    /// Creates a purger enforcing the configured policy
+    pub fn from_config(config: &Config) -> Result<Self> {
+        RetentionPolicy::from_config(config).map(Self::new)
+    }
+
+    /// Stops passes between entity types once `shutdown` is triggered
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Returns the enforced retention policy
+    pub fn policy(&self) -> &RetentionPolicy {
+        &self.policy
+    }
+
+    /// Purges every entity type once, then expired machine telemetry
+    ///
+    /// Each entity type is purged in its own transaction, so a failure on
+    /// one table is logged and does not stop the others.
+    pub fn run(&self, conn: &mut PgConnection) -> Vec<PurgeReport> {
+        let now = Utc::now();
+        let mut reports = Vec::new();
+
+        for target in PURGE_TARGETS {
+            if self.shutdown.as_ref().is_some_and(Shutdown::is_triggered) {
+                info!("Purge pass interrupted by shutdown");
+                break;
+            }
+
+            let Some(cutoff) = self.policy.cutoff(target.entity, now) else {
+                continue;
+            };
+
+            match conn.transaction(|conn| purge_target(conn, target, cutoff)) {
+                Ok(purged) => {
+                    if purged > 0 {
+                        info!(entity_type = %target.entity, purged = purged, "Purged soft-deleted records");
+                    }
+                    reports.push(PurgeReport {
+                        entity_type: target.entity.to_string(),
+                        purged,
+                    });
+                }
+                Err(e) => error!(entity_type = %target.entity, error = %e, "Failed to purge soft-deleted records"),
+            }
+        }
+
+        if self.shutdown.as_ref().is_some_and(Shutdown::is_triggered) {
+            return reports;
+        }
+        match conn.transaction(|conn| purge_telemetry(conn, now)) {
+            Ok(purged) => {
+                if purged > 0 {
+                    info!(purged = purged, "Purged expired machine telemetry");
+                }
+                reports.push(PurgeReport {
+                    entity_type: TELEMETRY_ENTITY.to_string(),
+                    purged,
+                });
+            }
+            Err(e) => error!(error = %e, "Failed to purge expired machine telemetry"),
+        }
+
+        reports
+    }
+}
+
+/// Removes points recorded longer ago than their organization keeps
+/// telemetry for
+fn purge_telemetry(conn: &mut PgConnection, now: DateTime<Utc>) -> QueryResult<usize> {
+    diesel::sql_query(
+        "DELETE FROM machine_telemetry t \
+         WHERE t.recorded_at < $1 - make_interval(days => COALESCE( \
+             (SELECT q.telemetry_retention_days FROM organization_quotas q WHERE q.org_id = t.org_id), $2)) \
+         AND NOT EXISTS ( \
+             SELECT 1 FROM legal_holds h \
+             WHERE h.entity_type = 'organizations' AND h.entity_id = t.org_id AND h.deleted_at IS NULL \
+         )",
+    )
+    .bind::<Timestamptz, _>(now)
+    .bind::<Integer, _>(DEFAULT_TELEMETRY_RETENTION_DAYS as i32)
+    .execute(conn)
+}
+
+fn purge_target(conn: &mut PgConnection, target: &PurgeTarget, cutoff: DateTime<Utc>) -> QueryResult<usize> {
+    let guard = target
+        .guard
+        .map(|guard| format!(" AND {}", guard))
+        .unwrap_or_default();
+
+    diesel::sql_query(format!(
+        "DELETE FROM {table} \
+         WHERE deleted_at IS NOT NULL AND deleted_at < $1 \
+         AND NOT EXISTS ( \
+             SELECT 1 FROM legal_holds h \
+             WHERE h.entity_type = $2 AND h.entity_id = {table}.id AND h.deleted_at IS NULL \
+         ){guard}",
+        table = target.entity,
+        guard = guard,
+    ))
+    .bind::<Timestamptz, _>(cutoff)
+    .bind::<Text, _>(target.entity)
+    .execute(conn)
+}
+
+#[async_trait(?Send)]
+impl ScheduledJob for Purger {
+    fn name(&self) -> &'static str {
+        "purger"
+    }
+
+    async fn run(&self, pool: &DbPool) -> Result<serde_json::Value> {
+        let mut conn = get_connection(pool)?;
+        let reports = Purger::run(self, &mut conn);
+        Ok(serde_json::to_value(reports).unwrap_or_default())
+    }
+}
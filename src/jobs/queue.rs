@@ -0,0 +1,233 @@
+//! Durable background job queue
+//!
+//! Work that must survive restarts and be retried on failure (imports,
+//! exports, webhook deliveries, optimization runs) is stored in the
+//! `queued_jobs` table with [`enqueue`] and processed by a [`QueueWorker`]
+//! on every instance. Each job kind is handled by one registered
+//! [`JobHandler`].
+//!
+//! A claimed job is hidden from other workers for the visibility timeout,
+//! which the worker keeps extending while the handler runs. If the instance
+//! dies, the job becomes visible again and is picked up elsewhere. Failed
+//! jobs are retried with exponential backoff; after `queue.max_attempts`
+//! they move to `dead_letter_jobs`, where they can be inspected and
+//! requeued through the admin API.
+
+use std::{collections::HashMap, rc::Rc, time::Duration as StdDuration};
+
+use actix_web::rt::task::JoinHandle;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use diesel::PgConnection;
+use serde::Serialize;
+use tracing::{error, info, warn};
+
+use crate::{
+    db::{
+        get_connection,
+        models::QueuedJob,
+        repositories::{JobQueueRepository, JobQueueRepositoryImpl},
+        DbPool,
+    },
+    error::{ApiError, Result},
+    jobs::{instance_id, shutdown::Shutdown},
+    utils::QueueConfig,
+};
+
+/// Processes the jobs of one kind
+#[async_trait(?Send)]
+pub trait JobHandler {
+    /// Kind of the jobs this handler processes, e.g. `stand_import`
+    fn kind(&self) -> &'static str;
+
+    /// Processes one job; an error schedules a retry
+    ///
+    /// Jobs may run more than once (after a retry, or when a worker dies
+    /// after finishing but before recording it), so handlers must be
+    /// idempotent.
+    async fn handle(&self, pool: &DbPool, payload: &serde_json::Value) -> Result<()>;
+}
+
+/// Adds a job of `kind` to the queue
+pub async fn enqueue(
+    conn: &mut PgConnection,
+    config: &QueueConfig,
+    kind: &str,
+    payload: impl Serialize,
+) -> Result<QueuedJob> {
+    let payload = serde_json::to_value(payload)
+        .map_err(|e| ApiError::validation(format!("Invalid job payload: {}", e), None))?;
+    let job = JobQueueRepositoryImpl
+        .enqueue(conn, &QueuedJob::new(kind, payload, config.max_attempts))
+        .await?;
+    info!(job_id = %job.id, kind = %kind, "Job enqueued");
+    Ok(job)
+}
+
+/// Delay before the next attempt of a job that has failed `attempts` times
+pub fn retry_delay(config: &QueueConfig, attempts: i32) -> StdDuration {
+    let doublings = attempts.saturating_sub(1).clamp(0, 31) as u32;
+    let delay = config.retry_base_secs.saturating_mul(1u64 << doublings);
+    StdDuration::from_secs(delay.min(config.retry_max_secs))
+}
+
+/// Claims and processes queued jobs of the registered kinds
+pub struct QueueWorker {
+    pool: DbPool,
+    config: QueueConfig,
+    worker: String,
+    handlers: HashMap<&'static str, Rc<dyn JobHandler>>,
+}
+
+impl QueueWorker {
+    pub fn new(pool: DbPool, config: &QueueConfig) -> Self {
+        Self {
+            pool,
+            config: config.clone(),
+            worker: instance_id(),
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Registers the handler of a job kind
+    pub fn register(&mut self, handler: impl JobHandler + 'static) {
+        self.handlers.insert(handler.kind(), Rc::new(handler));
+    }
+
+    /// Starts polling the queue, `None` when no handler is registered
+    ///
+    /// On shutdown the worker stops claiming jobs and waits for running
+    /// ones. Jobs aborted at the shutdown deadline are retried elsewhere
+    /// once their visibility timeout expires.
+    pub fn spawn(self, shutdown: Shutdown) -> Option<JoinHandle<()>> {
+        if self.handlers.is_empty() {
+            return None;
+        }
+
+        let worker = Rc::new(self);
+        Some(actix_web::rt::spawn(async move {
+            let kinds: Vec<&'static str> = worker.handlers.keys().copied().collect();
+            info!(kinds = ?kinds, concurrency = worker.config.concurrency, "Queue worker started");
+
+            let mut in_flight: Vec<JoinHandle<()>> = Vec::new();
+            let poll_interval = StdDuration::from_millis(worker.config.poll_interval_ms.max(10));
+            loop {
+                in_flight.retain(|job| !job.is_finished());
+                while in_flight.len() < worker.config.concurrency.max(1) && !shutdown.is_triggered() {
+                    match worker.claim(&kinds).await {
+                        Ok(Some(job)) => {
+                            let worker = worker.clone();
+                            in_flight.push(actix_web::rt::spawn(async move { worker.process(job).await }));
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            error!(error = %e, "Failed to claim queued job");
+                            break;
+                        }
+                    }
+                }
+
+                tokio::select! {
+                    _ = actix_web::rt::time::sleep(poll_interval) => {}
+                    _ = shutdown.triggered() => break,
+                }
+            }
+
+            for job in in_flight {
+                let _ = job.await;
+            }
+        }))
+    }
+
+    fn visibility_timeout(&self) -> Duration {
+        Duration::seconds(self.config.visibility_timeout_secs.max(1) as i64)
+    }
+
+    async fn claim(&self, kinds: &[&str]) -> Result<Option<QueuedJob>> {
+        let mut conn = get_connection(&self.pool)?;
+        JobQueueRepositoryImpl
+            .claim(&mut conn, kinds, &self.worker, Utc::now() + self.visibility_timeout())
+            .await
+    }
+
+    /// Runs the handler of a claimed job and records the outcome
+    async fn process(&self, job: QueuedJob) {
+        let Some(handler) = self.handlers.get(job.kind.as_str()) else {
+            return;
+        };
+        let repository = JobQueueRepositoryImpl;
+        let timeout = self.visibility_timeout();
+
+        // Keep the job hidden from other workers while the handler runs
+        let mut heartbeats = actix_web::rt::time::interval((timeout / 3).to_std().unwrap_or(StdDuration::from_secs(60)));
+        heartbeats.tick().await;
+        let handled = handler.handle(&self.pool, &job.payload);
+        tokio::pin!(handled);
+        let result = loop {
+            tokio::select! {
+                result = &mut handled => break result,
+                _ = heartbeats.tick() => {
+                    let extended = match get_connection(&self.pool) {
+                        Ok(mut conn) => repository.extend(&mut conn, job.id, &self.worker, Utc::now() + timeout).await,
+                        Err(e) => Err(e),
+                    };
+                    match extended {
+                        Ok(true) => {}
+                        Ok(false) => warn!(job_id = %job.id, kind = %job.kind, "Queued job was taken over by another worker"),
+                        Err(e) => warn!(job_id = %job.id, error = %e, "Failed to extend queued job visibility timeout"),
+                    }
+                }
+            }
+        };
+
+        let mut conn = match get_connection(&self.pool) {
+            Ok(conn) => conn,
+            Err(e) => {
+                // The job becomes visible again once its timeout expires
+                error!(job_id = %job.id, error = %e, "Failed to record queued job outcome");
+                return;
+            }
+        };
+        let recorded = match result {
+            Ok(()) => {
+                info!(job_id = %job.id, kind = %job.kind, attempt = job.attempts, "Queued job completed");
+                repository.complete(&mut conn, job.id, &self.worker).await
+            }
+            Err(e) if job.is_exhausted() => {
+                error!(job_id = %job.id, kind = %job.kind, attempt = job.attempts, error = %e, "Queued job failed its last attempt, moving to dead letters");
+                repository
+                    .dead_letter(&mut conn, job.clone(), &self.worker, &e.message)
+                    .await
+                    .map(|_| ())
+            }
+            Err(e) => {
+                let delay = retry_delay(&self.config, job.attempts);
+                warn!(job_id = %job.id, kind = %job.kind, attempt = job.attempts, retry_in_secs = delay.as_secs(), error = %e, "Queued job failed, retrying");
+                let retry_at = Utc::now() + Duration::from_std(delay).unwrap_or(Duration::zero());
+                repository.retry(&mut conn, job.id, &self.worker, &e.message, retry_at).await
+            }
+        };
+        if let Err(e) = recorded {
+            error!(job_id = %job.id, error = %e, "Failed to record queued job outcome");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_max() {
+        let config = QueueConfig {
+            retry_base_secs: 10,
+            retry_max_secs: 60,
+            ..QueueConfig::default()
+        };
+        assert_eq!(retry_delay(&config, 1).as_secs(), 10);
+        assert_eq!(retry_delay(&config, 2).as_secs(), 20);
+        assert_eq!(retry_delay(&config, 3).as_secs(), 40);
+        assert_eq!(retry_delay(&config, 4).as_secs(), 60);
+        assert_eq!(retry_delay(&config, 100).as_secs(), 60);
+    }
+}
@@ -0,0 +1,230 @@
+//! Scheduled report delivery
+//!
+//! Every report can have a cron schedule with a list of recipients (see
+//! [`crate::db::models::ReportSchedule`]). The [`ReportDeliverer`] runs as
+//! the `report_delivery` scheduled job, checking for due schedules every
+//! time it runs, so its own schedule bounds how late a delivery can be.
+//!
+//! Each due report is run and rendered once and mailed to every recipient
+//! through the email queue. Output up to [`MAX_ATTACHMENT_BYTES`] is
+//! attached; larger output is kept in object storage and the mail links to
+//! its download endpoint. Every run is recorded as a delivery, including
+//! runs that failed. Recipients are addresses rather than users, so output
+//! is presented with the default preferences: metric units, UTC and English.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::Utc;
+use diesel::PgConnection;
+use serde_json::json;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        get_connection,
+        models::{ReportDelivery, ReportDeliveryStatus, ReportSchedule},
+        repositories::{ReportRepository, ReportRepositoryImpl, ReportScheduleRepository, ReportScheduleRepositoryImpl},
+        DbPool,
+    },
+    domain::{
+        report::{ReportDefinition, ReportFormat, ReportService},
+        Preferences,
+    },
+    error::{ApiError, Result},
+    infrastructure::{
+        email::{EmailAttachment, ReportEmail},
+        Mailer, ObjectStorage,
+    },
+    jobs::{
+        email,
+        scheduler::{next_run, parse_schedule, ScheduledJob},
+        shutdown::Shutdown,
+    },
+    utils::{Config, QueueConfig},
+};
+
+/// Largest output sent as an attachment
+pub const MAX_ATTACHMENT_BYTES: usize = 5 * 1024 * 1024;
+
+/// Schedules delivered per run of the job
+const BATCH_SIZE: i64 = 100;
+
+/// Object storage key of output too large to attach
+pub fn file_key(delivery: &ReportDelivery, format: ReportFormat) -> String {
+    format!("reports/{}/{}/{}.{}", delivery.org_id, delivery.report_id, delivery.id, format.extension())
+}
+
+/// Runs due report schedules and emails the output
+pub struct ReportDeliverer {
+    mailer: Mailer,
+    storage: ObjectStorage,
+    queue: QueueConfig,
+    public_url: Option<String>,
+    shutdown: Option<Shutdown>,
+}
+
+/// Output of a successful run
+struct Delivered {
+    rows: usize,
+    file_key: Option<String>,
+}
+
+impl ReportDeliverer {
+    pub fn new(mailer: Mailer, storage: ObjectStorage, queue: QueueConfig) -> Self {
+        Self {
+            mailer,
+            storage,
+            queue,
+            public_url: None,
+            shutdown: None,
+        }
+    }
+
+    /// Creates a deliverer with the configured mailer, storage and queue
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            public_url: config.email.public_url.clone(),
+            ..Self::new(config.mailer().clone(), config.storage().clone(), config.queue.clone())
+        }
+    }
+
+    /// Sets the public base URL of the API, used to link large output
+    pub fn with_public_url(mut self, public_url: impl Into<String>) -> Self {
+        self.public_url = Some(public_url.into());
+        self
+    }
+
+    /// Stops passes between reports once `shutdown` is triggered
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Delivers every due schedule once
+    ///
+    /// A schedule moves to its next run before it is delivered, so a report
+    /// that keeps failing is retried on its schedule rather than on every
+    /// pass.
+    pub async fn deliver_due(&self, conn: &mut PgConnection) -> Result<Vec<ReportDelivery>> {
+        let repository = ReportScheduleRepositoryImpl;
+        let now = Utc::now();
+        let mut deliveries = Vec::new();
+
+        for schedule in repository.due(conn, now, BATCH_SIZE).await? {
+            if self.shutdown.as_ref().is_some_and(Shutdown::is_triggered) {
+                info!("Report delivery interrupted by shutdown");
+                break;
+            }
+            let cron = parse_schedule(&schedule.schedule).unwrap_or_else(|e| {
+                warn!(report_id = %schedule.report_id, error = %e, "Invalid report schedule");
+                None
+            });
+            repository.set_next_run(conn, schedule.id, next_run(cron.as_ref(), now)).await?;
+            deliveries.push(self.deliver(conn, &schedule).await?);
+        }
+        Ok(deliveries)
+    }
+
+    /// Delivers a report once and records the delivery
+    pub async fn deliver(&self, conn: &mut PgConnection, schedule: &ReportSchedule) -> Result<ReportDelivery> {
+        let mut delivery = ReportDelivery {
+            id: Uuid::new_v4(),
+            org_id: schedule.org_id,
+            report_id: schedule.report_id,
+            recipients: schedule.recipients.clone(),
+            format: schedule.format.clone(),
+            status: ReportDeliveryStatus::Queued.to_string(),
+            row_count: None,
+            file_key: None,
+            error: None,
+            created_at: Utc::now(),
+        };
+        match self.send(conn, schedule, &delivery).await {
+            Ok(delivered) => {
+                delivery.row_count = Some(delivered.rows as i32);
+                delivery.file_key = delivered.file_key;
+            }
+            Err(e) => {
+                warn!(report_id = %schedule.report_id, error = %e.message, "Scheduled report delivery failed");
+                delivery.status = ReportDeliveryStatus::Failed.to_string();
+                delivery.error = Some(e.message);
+            }
+        }
+        ReportScheduleRepositoryImpl.create_delivery(conn, &delivery).await
+    }
+
+    /// Runs the report and queues the mail to every recipient
+    async fn send(&self, conn: &mut PgConnection, schedule: &ReportSchedule, delivery: &ReportDelivery) -> Result<Delivered> {
+        let report = ReportRepositoryImpl.find(conn, schedule.org_id, schedule.report_id).await?;
+        let definition = ReportDefinition::from_stored(&report)?;
+        let parameters: HashMap<String, serde_json::Value> = serde_json::from_value(schedule.parameters.clone())
+            .map_err(|e| ApiError::validation(format!("Invalid schedule parameters: {}", e), None))?;
+        let format = ReportFormat::parse(&schedule.format)
+            .ok_or_else(|| ApiError::validation(format!("Unknown report format {}", schedule.format), None))?;
+
+        let preferences = Preferences::default();
+        let result = ReportService::<ReportRepositoryImpl>::execute(conn, schedule.org_id, &definition, &parameters, &preferences)?;
+        let output = format.render(&result, preferences.locale)?;
+
+        let (attachment, file_key) = if output.len() <= MAX_ATTACHMENT_BYTES {
+            let filename = format!("{}.{}", report.name, format.extension());
+            (Some(EmailAttachment::new(filename, format.content_type(), &output)), None)
+        } else {
+            let key = file_key(delivery, format);
+            self.storage.put(&key, Bytes::from(output)).await?;
+            (None, Some(key))
+        };
+        let url = match (&file_key, &self.public_url) {
+            (Some(_), Some(base)) => Some(format!(
+                "{}/v1/reports/{}/deliveries/{}/file",
+                base.trim_end_matches('/'),
+                report.id,
+                delivery.id
+            )),
+            _ => None,
+        };
+
+        let data = ReportEmail {
+            report: report.name.clone(),
+            generated_at: result.generated_at,
+            rows: result.rows.len(),
+            truncated: result.truncated,
+            attached: attachment.is_some(),
+            url,
+        };
+        for recipient in &schedule.recipients {
+            let mut message = self.mailer.compose(conn, Some(schedule.org_id), recipient, &data).await?;
+            message.attachments.extend(attachment.clone());
+            email::enqueue(conn, &self.queue, &message).await?;
+        }
+        info!(
+            report_id = %report.id,
+            org_id = %report.org_id,
+            recipients = schedule.recipients.len(),
+            rows = result.rows.len(),
+            linked = file_key.is_some(),
+            "Queued scheduled report '{}'", report.name
+        );
+        Ok(Delivered { rows: result.rows.len(), file_key })
+    }
+}
+
+#[async_trait(?Send)]
+impl ScheduledJob for ReportDeliverer {
+    fn name(&self) -> &'static str {
+        "report_delivery"
+    }
+
+    async fn run(&self, pool: &DbPool) -> Result<serde_json::Value> {
+        let mut conn = get_connection(pool)?;
+        let deliveries = self.deliver_due(&mut conn).await?;
+        let failed = deliveries
+            .iter()
+            .filter(|delivery| delivery.status == ReportDeliveryStatus::Failed.as_str())
+            .count();
+        Ok(json!({ "delivered": deliveries.len() - failed, "failed": failed }))
+    }
+}
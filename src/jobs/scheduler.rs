@@ -0,0 +1,291 @@
+//! Cron-style scheduling of recurring jobs
+//!
+//! Jobs implement [`ScheduledJob`] and are registered with the
+//! [`Scheduler`] under a name. Their schedule is a cron expression from
+//! `scheduler.jobs.<name>`, which an admin can override per job in the
+//! database (`scheduled_jobs.schedule`).
+//!
+//! Every instance runs a scheduler, but each occurrence of a job runs on one
+//! instance only: before running a due job an instance takes a lease on its
+//! `scheduled_jobs` row, renews it while the job runs and releases it with
+//! the outcome and next run time. A lease left behind by a crashed instance
+//! expires after `scheduler.lease_secs`.
+
+use std::{
+    collections::HashMap,
+    rc::Rc,
+    str::FromStr,
+    time::{Duration as StdDuration, Instant},
+};
+
+use actix_web::rt::task::JoinHandle;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use cron::Schedule;
+use tracing::{error, info, warn};
+
+use crate::{
+    db::{
+        get_connection,
+        models::{JobRunOutcome, JobRunStatus, ScheduledJobState},
+        repositories::{ScheduledJobRepository, ScheduledJobRepositoryImpl},
+        DbPool,
+    },
+    error::{ApiError, ErrorContext, Result},
+    jobs::{instance_id, shutdown::Shutdown},
+    utils::SchedulerConfig,
+};
+
+/// Schedule value disabling a job
+pub const DISABLED: &str = "off";
+
+/// Next run recorded for jobs that are disabled or never due again
+fn never() -> DateTime<Utc> {
+    Utc::now() + Duration::days(100 * 365)
+}
+
+/// A recurring job run by the [`Scheduler`]
+#[async_trait(?Send)]
+pub trait ScheduledJob {
+    /// Name the job is configured and reported under
+    fn name(&self) -> &'static str;
+
+    /// Runs the job once, returning a summary stored as the run's output
+    async fn run(&self, pool: &DbPool) -> Result<serde_json::Value>;
+}
+
+/// Parses a cron expression, or [`DISABLED`] into `None`
+///
+/// Expressions have six or seven fields:
+/// `sec min hour day-of-month month day-of-week [year]`, evaluated in UTC.
+pub fn parse_schedule(expression: &str) -> Result<Option<Schedule>> {
+    let expression = expression.trim();
+    if expression.eq_ignore_ascii_case(DISABLED) {
+        return Ok(None);
+    }
+    Schedule::from_str(expression).map(Some).map_err(|e| {
+        ApiError::validation_with_context(
+            format!("Invalid cron expression '{}': {}", expression, e),
+            ErrorContext::new().with_details(serde_json::json!({
+                "field": "schedule",
+                "code": "INVALID_FORMAT",
+                "value": expression,
+                "reason": e.to_string()
+            }))
+        )
+    })
+}
+
+/// Next time `schedule` fires after `after`; never when disabled
+pub fn next_run(schedule: Option<&Schedule>, after: DateTime<Utc>) -> DateTime<Utc> {
+    schedule
+        .and_then(|schedule| schedule.after(&after).next())
+        .unwrap_or_else(never)
+}
+
+/// Schedule in effect for a job: the database override if valid, otherwise
+/// the configured one
+pub fn effective_schedule(configured: Option<&Schedule>, state: Option<&ScheduledJobState>) -> Option<Schedule> {
+    let Some(expression) = state.and_then(|state| state.schedule.as_deref()) else {
+        return configured.cloned();
+    };
+    match parse_schedule(expression) {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            warn!(error = %e, "Ignoring invalid schedule override");
+            configured.cloned()
+        }
+    }
+}
+
+struct Registered {
+    job: Rc<dyn ScheduledJob>,
+    schedule: Option<Schedule>,
+}
+
+/// Runs registered jobs on their schedules
+pub struct Scheduler {
+    pool: DbPool,
+    config: SchedulerConfig,
+    instance: String,
+    jobs: Vec<Registered>,
+}
+
+impl Scheduler {
+    pub fn new(pool: DbPool, config: &SchedulerConfig) -> Self {
+        Self {
+            pool,
+            config: config.clone(),
+            instance: instance_id(),
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Adds a job with its configured schedule
+    ///
+    /// Jobs without a configured schedule are skipped; an override set in
+    /// the database still enables jobs configured as [`DISABLED`].
+    pub fn register(&mut self, job: impl ScheduledJob + 'static) {
+        let Some(expression) = self.config.jobs.get(job.name()) else {
+            info!(job = %job.name(), "No schedule configured, job not registered");
+            return;
+        };
+        match parse_schedule(expression) {
+            Ok(schedule) => self.jobs.push(Registered {
+                job: Rc::new(job),
+                schedule,
+            }),
+            Err(e) => error!(job = %job.name(), error = %e, "Invalid schedule, job not registered"),
+        }
+    }
+
+    /// Starts the scheduler loop, `None` when no job is registered
+    ///
+    /// On shutdown the loop stops claiming jobs and waits for running ones,
+    /// which stop at their own checkpoints.
+    pub fn spawn(self, shutdown: Shutdown) -> Option<JoinHandle<()>> {
+        if self.jobs.is_empty() {
+            return None;
+        }
+
+        Some(actix_web::rt::spawn(async move {
+            if let Err(e) = self.register_all().await {
+                error!(error = %e, "Failed to register scheduled jobs");
+            }
+
+            let mut running: HashMap<&'static str, JoinHandle<()>> = HashMap::new();
+            let mut ticker = actix_web::rt::time::interval(StdDuration::from_secs(self.config.poll_interval_secs.max(1)));
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = shutdown.triggered() => break,
+                }
+
+                running.retain(|_, handle| !handle.is_finished());
+                for registered in &self.jobs {
+                    let name = registered.job.name();
+                    if running.contains_key(name) {
+                        continue;
+                    }
+                    match self.try_start(registered).await {
+                        Ok(Some(handle)) => {
+                            running.insert(name, handle);
+                        }
+                        Ok(None) => {}
+                        Err(e) => error!(job = %name, error = %e, "Failed to start scheduled job"),
+                    }
+                }
+            }
+
+            for (_, handle) in running {
+                let _ = handle.await;
+            }
+        }))
+    }
+
+    /// Creates missing job rows and pulls next runs forward to the schedule
+    async fn register_all(&self) -> Result<()> {
+        let repository = ScheduledJobRepositoryImpl;
+        let mut conn = get_connection(&self.pool)?;
+        for registered in &self.jobs {
+            let name = registered.job.name();
+            let state = repository.find_optional(&mut conn, name).await?;
+            let schedule = effective_schedule(registered.schedule.as_ref(), state.as_ref());
+            let state = repository
+                .register(&mut conn, name, next_run(schedule.as_ref(), Utc::now()))
+                .await?;
+            info!(job = %name, next_run_at = %state.next_run_at, "Scheduled job registered");
+        }
+        Ok(())
+    }
+
+    /// Claims the job if it is due and runs it in the background
+    async fn try_start(&self, registered: &Registered) -> Result<Option<JoinHandle<()>>> {
+        let repository = ScheduledJobRepositoryImpl;
+        let name = registered.job.name();
+        let lease = Duration::seconds(self.config.lease_secs as i64);
+
+        let mut conn = get_connection(&self.pool)?;
+        let Some(state) = repository
+            .claim(&mut conn, name, &self.instance, Utc::now() + lease)
+            .await?
+        else {
+            return Ok(None);
+        };
+        drop(conn);
+        let schedule = effective_schedule(registered.schedule.as_ref(), Some(&state));
+
+        let job = registered.job.clone();
+        let pool = self.pool.clone();
+        let instance = self.instance.clone();
+        Ok(Some(actix_web::rt::spawn(async move {
+            info!(job = %name, "Scheduled job started");
+            let started = Instant::now();
+
+            // Renew the lease while the job runs so no other instance takes over
+            let mut renewals = actix_web::rt::time::interval((lease / 3).to_std().unwrap_or(StdDuration::from_secs(60)));
+            renewals.tick().await;
+            let run = job.run(&pool);
+            tokio::pin!(run);
+            let result = loop {
+                tokio::select! {
+                    result = &mut run => break result,
+                    _ = renewals.tick() => {
+                        let renewed = match get_connection(&pool) {
+                            Ok(mut conn) => repository.renew(&mut conn, name, &instance, Utc::now() + lease).await,
+                            Err(e) => Err(e),
+                        };
+                        match renewed {
+                            Ok(true) => {}
+                            Ok(false) => warn!(job = %name, "Lease of scheduled job was lost"),
+                            Err(e) => warn!(job = %name, error = %e, "Failed to renew lease of scheduled job"),
+                        }
+                    }
+                }
+            };
+
+            let outcome = JobRunOutcome {
+                status: if result.is_ok() { JobRunStatus::Succeeded } else { JobRunStatus::Failed },
+                error: result.as_ref().err().map(|e| e.message.clone()),
+                output: result.ok(),
+                duration_ms: started.elapsed().as_millis() as i64,
+            };
+            match &outcome.error {
+                None => info!(job = %name, duration_ms = outcome.duration_ms, "Scheduled job succeeded"),
+                Some(e) => error!(job = %name, duration_ms = outcome.duration_ms, error = %e, "Scheduled job failed"),
+            }
+
+            let completed = match get_connection(&pool) {
+                Ok(mut conn) => {
+                    let next = next_run(schedule.as_ref(), Utc::now());
+                    repository.complete(&mut conn, name, &instance, &outcome, next).await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = completed {
+                error!(job = %name, error = %e, "Failed to record scheduled job run");
+            }
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_schedule() {
+        assert!(parse_schedule("0 0 3 * * *").unwrap().is_some());
+        assert!(parse_schedule("off").unwrap().is_none());
+        assert!(parse_schedule("every day").is_err());
+    }
+
+    #[test]
+    fn test_next_run() {
+        let at = Utc.with_ymd_and_hms(2024, 12, 18, 2, 30, 0).unwrap();
+        let daily = parse_schedule("0 0 3 * * *").unwrap();
+        assert_eq!(next_run(daily.as_ref(), at), Utc.with_ymd_and_hms(2024, 12, 18, 3, 0, 0).unwrap());
+        assert!(next_run(None, at) > at + Duration::days(365));
+    }
+}
@@ -0,0 +1,118 @@
+//! Cooperative shutdown of background jobs
+//!
+//! Jobs receive a [`Shutdown`] signal and check it at their checkpoints,
+//! points where stopping leaves no partial work behind (between archive
+//! batches, between purge targets). Work after the last checkpoint is picked
+//! up again by the next process. On shutdown the server triggers the signal
+//! and waits for every job registered in a [`JobSet`] up to a deadline.
+
+use std::time::Duration;
+
+use actix_web::rt::task::JoinHandle;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// Signal telling background jobs to stop at their next checkpoint
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    sender: std::sync::Arc<watch::Sender<bool>>,
+    receiver: watch::Receiver<bool>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (sender, receiver) = watch::channel(false);
+        Self {
+            sender: std::sync::Arc::new(sender),
+            receiver,
+        }
+    }
+
+    /// Asks every holder of this signal to stop
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Returns true once shutdown has been requested
+    pub fn is_triggered(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    /// Completes once shutdown has been requested
+    pub async fn triggered(&self) {
+        let mut receiver = self.receiver.clone();
+        // The sender lives as long as any clone of this signal
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+}
+
+/// Background jobs awaited during shutdown
+pub struct JobSet {
+    shutdown: Shutdown,
+    jobs: Vec<(&'static str, JoinHandle<()>)>,
+}
+
+impl JobSet {
+    pub fn new(shutdown: Shutdown) -> Self {
+        Self {
+            shutdown,
+            jobs: Vec::new(),
+        }
+    }
+
+    pub fn shutdown(&self) -> &Shutdown {
+        &self.shutdown
+    }
+
+    /// Registers a running job; `None` is accepted for jobs that are disabled
+    pub fn add(&mut self, name: &'static str, job: Option<JoinHandle<()>>) {
+        if let Some(job) = job {
+            self.jobs.push((name, job));
+        }
+    }
+
+    /// Triggers shutdown and waits for all jobs, aborting those still
+    /// running after `deadline`
+    pub async fn drain(self, deadline: Duration) {
+        self.shutdown.trigger();
+
+        let deadline = tokio::time::Instant::now() + deadline;
+        for (name, mut job) in self.jobs {
+            match tokio::time::timeout_at(deadline, &mut job).await {
+                Ok(_) => info!(job = %name, "Background job stopped"),
+                Err(_) => {
+                    warn!(job = %name, "Background job missed the shutdown deadline, aborting");
+                    job.abort();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_drain_stops_jobs() {
+        let mut jobs = JobSet::new(Shutdown::new());
+
+        let shutdown = jobs.shutdown().clone();
+        jobs.add("cooperative", Some(actix_web::rt::spawn(async move {
+            shutdown.triggered().await;
+        })));
+        jobs.add("stuck", Some(actix_web::rt::spawn(std::future::pending())));
+        jobs.add("disabled", None);
+
+        let shutdown = jobs.shutdown().clone();
+        assert!(!shutdown.is_triggered());
+        jobs.drain(Duration::from_millis(50)).await;
+        assert!(shutdown.is_triggered());
+    }
+}
@@ -1,10 +1,19 @@
 pub mod api;
+pub mod cli;
 pub mod utils;
 pub mod db;
 pub mod domain;
 pub mod error;
+pub mod infrastructure;
+pub mod jobs;
 pub mod server;
 
+#[cfg(feature = "bench")]
+pub mod bench;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+
 #[cfg(test)]
 pub mod tests;
 
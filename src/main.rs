@@ -1,24 +1,30 @@
-use rust_server::{Config, server::run};
+use clap::Parser;
+use rust_server::{Config, cli::{self, Cli, Command}, server::run, utils::{logging, sentry}};
 use tracing::info;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let config = Config::load()?;
+    let command = Cli::parse().command;
+    if let Some(Command::ExportOpenapi { out }) = &command {
+        return cli::export_openapi(out.as_ref());
+    }
 
-    // Initialize logging with environment-aware default level
-    let default_log_level = match config.environment {
-        rust_server::utils::environment::Environment::Development |
-        rust_server::utils::environment::Environment::Staging => "debug",
-        rust_server::utils::environment::Environment::Production => "info",
-    };
+    // Logging is not set up yet, so configuration problems go to stderr
+    let config = Config::load().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
 
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG").unwrap_or_else(|_| default_log_level.into())
-        ))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    match command {
+        Some(Command::Seed { profile }) => return cli::seed(&config, profile).await,
+        Some(Command::Anonymize { yes }) => return cli::anonymize(&config, yes).await,
+        _ => {}
+    }
+
+    // Initialize logging with environment-aware default level and format
+    logging::init(&config);
+
+    let _sentry = sentry::init(&config.sentry_dsn, &config.environment);
 
     info!("Starting {} v{} in {} mode", 
         rust_server::NAME,
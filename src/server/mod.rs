@@ -2,40 +2,231 @@
 //! 
 //! This module handles the HTTP server setup, including middleware
 //! configuration and route registration.
+//!
+//! On `SIGTERM` or `SIGINT` the server stops accepting connections, lets
+//! in-flight requests finish and stops background jobs at their next
+//! checkpoint, all within `server.shutdown_timeout_secs`.
+//!
+//! With `tls.cert_path` and `tls.key_path` set the server speaks HTTPS only,
+//! optionally redirecting plain HTTP from `tls.redirect_http_port`.
+
+mod tls;
 
 use crate::{
-    api::{middleware::{RequestId, SecurityHeaders}, resources},
-    utils::Config,
+    api::{
+        middleware::{Cors, ErrorReporter, Localization, Maintenance, RequestId, SecurityHeaders},
+        resources::{self, health::checks},
+    },
+    domain::import,
+    infrastructure::{cluster, mqtt},
+    jobs::{
+        archive::Archiver,
+        certification_expiry::CertificationExpiryNotifier,
+        email::EmailDelivery,
+        erp_export::{ErpExportDelivery, ErpExporter},
+        events::EventPublisher,
+        import::ImportProcessor,
+        organization_export::OrganizationExporter,
+        purge::Purger,
+        queue::QueueWorker,
+        report_delivery::ReportDeliverer,
+        scheduler::Scheduler,
+        shutdown::{JobSet, Shutdown},
+    },
+    utils::{logging, Config},
 };
 use actix_web::{
+    body::MessageBody,
+    dev::{ServiceFactory, ServiceRequest, ServiceResponse},
+    http::KeepAlive,
     middleware::{Logger, NormalizePath},
-    App, HttpServer,
+    web, App, HttpServer,
 };
-use tracing::info;
+use futures_util::future::join;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// The application served by each worker: middleware, shared state and
+/// routes
+pub fn app(
+    config: &Config,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    App::new()
+        // Middleware
+        .wrap(ErrorReporter::new())
+        .wrap(Localization::new())
+        .wrap(Logger::default())
+        .wrap(RequestId::new())
+        .wrap(SecurityHeaders::new())
+        .wrap(NormalizePath::trim())
+        .wrap(Maintenance::new())
+        .wrap(Cors::new())
+        // State
+        .app_data(web::Data::new(config.pool().clone()))
+        .app_data(web::Data::new(config.clone()))
+        // Routes
+        .configure(resources::configure_routes)
+}
 
 pub async fn run() -> std::io::Result<()> {
     // Load config once at startup
     let config = Config::load()?;
     let pool = config.pool().clone();
-    let host = config.host.clone();
-    let port = config.port;
-
-    let server = HttpServer::new(move || {
-        App::new()
-            // Middleware
-            .wrap(Logger::default())
-            .wrap(RequestId::new())
-            .wrap(SecurityHeaders::new())
-            .wrap(NormalizePath::trim())
-            // State
-            .app_data(actix_web::web::Data::new(pool.clone()))
-            .app_data(actix_web::web::Data::new(config.clone()))
-            // Routes
-            .configure(resources::configure_routes)
+    let host = config.server.host.clone();
+    let port = config.server.port;
+
+    let shutdown_timeout = Duration::from_secs(config.server.shutdown_timeout_secs);
+
+    config.live().clone().spawn_reload_on_hangup();
+    if let Some(redis) = config.redis() {
+        // Readiness reports Redis down until it can be reached
+        match redis.connect().await {
+            Ok(()) => info!(namespace = %redis.namespace(), "Connected to Redis"),
+            Err(e) => warn!(error = %e, "Redis unreachable at startup"),
+        }
+    }
+    if let Some(bus) = config.event_bus() {
+        match bus.connect().await {
+            Ok(()) => info!(transport = ?bus.transport(), "Connected to the event bus"),
+            Err(e) => warn!(error = %e, "Event bus unreachable at startup"),
+        }
+    }
+    let mut jobs = JobSet::new(Shutdown::new());
+    if let Some(filter) = &config.live.log_filter {
+        if let Err(e) = logging::set_filter(filter) {
+            warn!(error = %e, "Failed to apply live.log_filter");
+        }
+    }
+    jobs.add("config-watcher", Some(config.live().clone().spawn_watcher(jobs.shutdown().clone())));
+    jobs.add("cluster-events", cluster::spawn_listener(config.clone(), jobs.shutdown().clone()));
+    jobs.add("dependency-monitor", checks::spawn_monitor(config.clone(), pool.clone(), jobs.shutdown().clone()));
+    jobs.add("telemetry-mqtt", mqtt::spawn_bridge(&config, pool.clone(), jobs.shutdown().clone()));
+    let mut scheduler = Scheduler::new(pool.clone(), &config.scheduler);
+    scheduler.register(Archiver::from_config(&config).with_shutdown(jobs.shutdown().clone()));
+    scheduler.register(
+        Purger::from_config(&config)
+            .map_err(|e| std::io::Error::other(e.to_string()))?
+            .with_shutdown(jobs.shutdown().clone()),
+    );
+    scheduler.register(ReportDeliverer::from_config(&config).with_shutdown(jobs.shutdown().clone()));
+    scheduler.register(ErpExporter::from_config(&config));
+    scheduler.register(CertificationExpiryNotifier::from_config(&config));
+    jobs.add("scheduler", scheduler.spawn(jobs.shutdown().clone()));
+    // Email, domain events, imports, exports, organization export bundles, webhook deliveries and
+    // optimization runs register their job handlers here
+    let mut queue = QueueWorker::new(pool.clone(), &config.queue);
+    queue.register(EmailDelivery::new(config.mailer().clone(), config.dependencies().clone()));
+    queue.register(ImportProcessor::new(config.storage().clone(), import::targets()));
+    queue.register(ErpExportDelivery::from_config(&config));
+    queue.register(OrganizationExporter::new(config.storage().clone()));
+    if let Some(publisher) = EventPublisher::from_config(&config) {
+        queue.register(publisher);
+    }
+    jobs.add("queue", queue.spawn(jobs.shutdown().clone()));
+
+    let app_config = config.clone();
+    let http = HttpServer::new(move || app(&app_config))
+        .workers(config.server.effective_workers())
+        .keep_alive(match config.server.keep_alive_secs {
+            0 => KeepAlive::Disabled,
+            secs => KeepAlive::Timeout(Duration::from_secs(secs)),
+        })
+        .client_request_timeout(Duration::from_millis(config.server.client_request_timeout_ms))
+        .backlog(config.server.backlog)
+        .max_connections(config.server.max_connections)
+        .shutdown_timeout(shutdown_timeout.as_secs())
+        .disable_signals();
+
+    info!(
+        workers = config.server.effective_workers(),
+        keep_alive_secs = config.server.keep_alive_secs,
+        client_request_timeout_ms = config.server.client_request_timeout_ms,
+        backlog = config.server.backlog,
+        max_connections = config.server.max_connections,
+        shutdown_timeout_secs = config.server.shutdown_timeout_secs,
+        "HTTP server settings"
+    );
+
+    let mut redirect = None;
+    let server = match config.tls.files() {
+        Some((cert_path, key_path)) => {
+            let certificates = tls::CertificateReloader::load(cert_path, key_path)?;
+            jobs.add("tls-reloader", Some(certificates.clone().spawn(
+                Duration::from_secs(config.tls.reload_interval_secs),
+                jobs.shutdown().clone(),
+            )));
+            let server = http.bind_rustls_0_23((host.clone(), port), certificates.server_config()?)?.run();
+            info!("Server listening on https://{}:{}", host, port);
+
+            if let Some(redirect_port) = config.tls.redirect_http_port {
+                redirect = Some(tls::redirect_server(&host, redirect_port, port)?);
+                info!("Redirecting http://{}:{} to HTTPS", host, redirect_port);
+            }
+            server
+        }
+        None => {
+            let server = http.bind((host.clone(), port))?.run();
+            info!("Server listening on {}:{}", host, port);
+            server
+        }
+    };
+
+    let handles = std::iter::once(server.handle())
+        .chain(redirect.as_ref().map(|r| r.handle()))
+        .collect::<Vec<_>>();
+    let shutdown = jobs.shutdown().clone();
+    actix_web::rt::spawn(async move {
+        shutdown_signal().await;
+        info!(timeout_secs = shutdown_timeout.as_secs(), "Shutting down, draining connections");
+        shutdown.trigger();
+        for handle in handles {
+            handle.stop(true).await;
+        }
+    });
+
+    let (result, redirect_result) = join(server, async move {
+        match redirect {
+            Some(redirect) => redirect.await,
+            None => Ok(()),
+        }
     })
-    .bind((host.clone(), port))?;
+    .await;
+    if let Err(e) = redirect_result {
+        warn!(error = %e, "HTTPS redirect server failed");
+    }
+    jobs.drain(shutdown_timeout).await;
+    info!("Shutdown complete");
+    result
+}
+
+/// Completes on `SIGINT`, or `SIGTERM` on Unix
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = terminate.recv() => {}
+                    _ = tokio::signal::ctrl_c() => {}
+                }
+                return;
+            }
+            Err(e) => warn!(error = %e, "Failed to install SIGTERM handler"),
+        }
+    }
 
-    info!("Server listening on {}:{}", host, port);
-    
-    server.run().await
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        warn!(error = %e, "Failed to listen for SIGINT");
+        std::future::pending::<()>().await;
+    }
 }
\ No newline at end of file
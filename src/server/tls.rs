@@ -0,0 +1,207 @@
+//! TLS termination for deployments without a fronting load balancer
+//!
+//! The certificate is served through [`CertificateReloader`], which checks
+//! the certificate and key files for changes and swaps in the new pair
+//! without dropping connections. A failed reload (e.g. a half-written file)
+//! keeps the previous certificate and is retried at the next check.
+
+use std::{
+    fs::File,
+    io::{self, BufReader},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use actix_web::{
+    http::header, rt::task::JoinHandle, web, App, HttpRequest, HttpResponse, HttpServer,
+};
+use rustls::{
+    crypto::ring,
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+    ServerConfig,
+};
+use tracing::{info, warn};
+
+use crate::jobs::shutdown::Shutdown;
+
+/// Serves the current certificate and reloads it when its files change
+#[derive(Debug)]
+pub struct CertificateReloader {
+    cert_path: String,
+    key_path: String,
+    current: RwLock<Loaded>,
+}
+
+#[derive(Debug)]
+struct Loaded {
+    key: Arc<CertifiedKey>,
+    modified: Option<(SystemTime, SystemTime)>,
+}
+
+impl CertificateReloader {
+    /// Loads the initial certificate, failing if it is unusable
+    pub fn load(cert_path: &str, key_path: &str) -> io::Result<Arc<Self>> {
+        let reloader = Self {
+            cert_path: cert_path.to_string(),
+            key_path: key_path.to_string(),
+            current: RwLock::new(Loaded {
+                modified: modified(cert_path, key_path),
+                key: Arc::new(load_certified_key(cert_path, key_path)?),
+            }),
+        };
+        Ok(Arc::new(reloader))
+    }
+
+    /// Server configuration resolving certificates through this reloader
+    pub fn server_config(self: &Arc<Self>) -> io::Result<ServerConfig> {
+        let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(io::Error::other)?
+            .with_no_client_auth()
+            .with_cert_resolver(self.clone());
+        Ok(config)
+    }
+
+    /// Reloads the certificate if its files changed since the last load
+    ///
+    /// Returns whether a new certificate was installed.
+    pub fn reload_if_changed(&self) -> io::Result<bool> {
+        let modified = modified(&self.cert_path, &self.key_path);
+        if modified.is_some() && modified == self.read().modified {
+            return Ok(false);
+        }
+
+        let key = load_certified_key(&self.cert_path, &self.key_path)?;
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        *current = Loaded {
+            key: Arc::new(key),
+            modified,
+        };
+        Ok(true)
+    }
+
+    /// Checks the files every `interval` until shutdown
+    pub fn spawn(self: Arc<Self>, interval: Duration, shutdown: Shutdown) -> JoinHandle<()> {
+        actix_web::rt::spawn(async move {
+            let mut ticker = actix_web::rt::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = shutdown.triggered() => break,
+                }
+                match self.reload_if_changed() {
+                    Ok(true) => info!(cert_path = %self.cert_path, "TLS certificate reloaded"),
+                    Ok(false) => {}
+                    Err(e) => warn!(
+                        cert_path = %self.cert_path,
+                        error = %e,
+                        "Failed to reload TLS certificate, keeping the current one"
+                    ),
+                }
+            }
+        })
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Loaded> {
+        self.current.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ResolvesServerCert for CertificateReloader {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.read().key.clone())
+    }
+}
+
+/// Modification times of the certificate and key files
+fn modified(cert_path: &str, key_path: &str) -> Option<(SystemTime, SystemTime)> {
+    let mtime = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    Some((mtime(cert_path)?, mtime(key_path)?))
+}
+
+fn load_certified_key(cert_path: &str, key_path: &str) -> io::Result<CertifiedKey> {
+    let invalid = |path: &str, reason: String| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path, reason))
+    };
+
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(cert_path)?))
+        .collect::<io::Result<Vec<_>>>()?;
+    if certs.is_empty() {
+        return Err(invalid(cert_path, "no certificate found".into()));
+    }
+
+    let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(key_path)?))?
+        .ok_or_else(|| invalid(key_path, "no private key found".into()))?;
+    let signing_key = ring::sign::any_supported_type(&key)
+        .map_err(|e| invalid(key_path, e.to_string()))?;
+
+    let certified = CertifiedKey::new(certs, signing_key);
+    certified
+        .keys_match()
+        .map_err(|e| invalid(key_path, e.to_string()))?;
+    Ok(certified)
+}
+
+/// Plain HTTP server answering every request with a redirect to HTTPS
+pub fn redirect_server(host: &str, port: u16, https_port: u16) -> io::Result<actix_web::dev::Server> {
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(https_port))
+            .default_service(web::to(redirect_to_https))
+    })
+    .workers(1)
+    .disable_signals()
+    .bind((host, port))?
+    .run();
+    Ok(server)
+}
+
+async fn redirect_to_https(req: HttpRequest, https_port: web::Data<u16>) -> HttpResponse {
+    let connection = req.connection_info();
+    let path = req.uri().path_and_query().map_or("/", |p| p.as_str());
+    HttpResponse::PermanentRedirect()
+        .insert_header((header::LOCATION, redirect_location(connection.host(), **https_port, path)))
+        .finish()
+}
+
+/// HTTPS URL for `path` on `host`, replacing any port in the host
+fn redirect_location(host: &str, https_port: u16, path: &str) -> String {
+    let hostname = match host.rfind(':') {
+        // Keep the colons of a bracketed IPv6 address without port
+        Some(i) if !host[i..].contains(']') => &host[..i],
+        _ => host,
+    };
+    match https_port {
+        443 => format!("https://{}{}", hostname, path),
+        port => format!("https://{}:{}{}", hostname, port, path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redirect_location() {
+        assert_eq!(redirect_location("example.com", 443, "/api/v1?x=1"), "https://example.com/api/v1?x=1");
+        assert_eq!(redirect_location("example.com:80", 8443, "/"), "https://example.com:8443/");
+        assert_eq!(redirect_location("[::1]:8080", 443, "/"), "https://[::1]/");
+        assert_eq!(redirect_location("[::1]", 8443, "/"), "https://[::1]:8443/");
+    }
+
+    #[test]
+    fn test_load_rejects_missing_certificate() {
+        let dir = std::env::temp_dir().join(format!("tls-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert = dir.join("cert.pem");
+        std::fs::write(&cert, "not a certificate").unwrap();
+        let cert = cert.to_str().unwrap();
+
+        let error = CertificateReloader::load(cert, cert).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(CertificateReloader::load("/nonexistent/cert.pem", cert).is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,54 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    db::models::auth::{Role, User},
+    domain::TokenManager,
+    utils::Config,
+};
+
+/// Signs access tokens for users that need not exist, accepted by the
+/// `Auth` middleware of an app built from the same configuration
+pub struct MockAuthService {
+    config: Config,
+}
+
+impl MockAuthService {
+    pub fn new(config: &Config) -> Self {
+        Self { config: config.clone() }
+    }
+
+    /// Access token of a new user with `role` in a new organization
+    pub fn token(&self, role: Role) -> String {
+        self.token_for(Uuid::new_v4(), Uuid::new_v4(), role)
+    }
+
+    /// Access token of the user `user_id` in the organization `org_id`
+    pub fn token_for(&self, user_id: Uuid, org_id: Uuid, role: Role) -> String {
+        let now = Utc::now();
+        let user = User {
+            id: user_id,
+            first_name: "Mock".to_string(),
+            last_name: "User".to_string(),
+            email: format!("mock-{}@example.com", user_id.simple()),
+            phone_number: String::new(),
+            password: String::new(),
+            org_id,
+            role,
+            email_verified: true,
+            avatar_updated_at: None,
+            is_active: true,
+            supervisor_id: None,
+            anonymized_at: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+        TokenManager::generate_token(&user, &self.config).expect("Failed to sign a mock token")
+    }
+
+    /// `Authorization` header carrying a new token with `role`
+    pub fn header(&self, role: Role) -> (&'static str, String) {
+        ("Authorization", format!("Bearer {}", self.token(role)))
+    }
+}
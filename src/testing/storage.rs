@@ -0,0 +1,40 @@
+use bytes::Bytes;
+
+use crate::infrastructure::ObjectStorage;
+
+/// In-memory object storage that tests can fill and inspect
+///
+/// Clones of [`MockStorageService::storage`] share the same objects, so
+/// what a handler writes through the app's configuration can be read back
+/// here.
+#[derive(Debug, Clone)]
+pub struct MockStorageService {
+    storage: ObjectStorage,
+}
+
+impl Default for MockStorageService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockStorageService {
+    pub fn new() -> Self {
+        Self { storage: ObjectStorage::in_memory() }
+    }
+
+    /// Handle to pass to `Config::with_storage`
+    pub fn storage(&self) -> ObjectStorage {
+        self.storage.clone()
+    }
+
+    /// Stores `body` under `key` as if a handler had written it
+    pub async fn insert(&self, key: &str, body: impl Into<Bytes>) {
+        self.storage.put(key, body.into()).await.expect("Failed to write to in-memory storage");
+    }
+
+    /// The object under `key`, `None` when there is none
+    pub async fn object(&self, key: &str) -> Option<Bytes> {
+        self.storage.get(key).await.ok()
+    }
+}
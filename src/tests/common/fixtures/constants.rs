@@ -1,6 +0,0 @@
-pub const TEST_PASSWORD: &str = "test_password";
-pub const TEST_EMAIL_DOMAIN: &str = "@example.com";
-pub const TEST_PHONE_PREFIX: &str = "123456";
-pub const TEST_ROLES: [&str; 3] = ["Operator", "Manager", "Admin"];
-pub const TEST_ORG_NAME_PREFIX: &str = "Test Organization";
-pub const TEST_USER_NAME_PREFIX: &str = "Test User"; 
\ No newline at end of file
@@ -1,7 +0,0 @@
-pub mod user;
-pub mod organization;
-pub mod constants;
-
-pub use user::*;
-pub use organization::*;
-pub use constants::*; 
\ No newline at end of file
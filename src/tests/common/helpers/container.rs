@@ -0,0 +1,107 @@
+//! Isolated Postgres databases for tests
+//!
+//! The first test that asks for a database starts one Postgres container for
+//! the test binary and migrates a template database in it. Every test then
+//! gets its own `CREATE DATABASE ... TEMPLATE` clone, so tests can commit,
+//! truncate and run in parallel without seeing each other's rows.
+//!
+//! Set `TEST_DATABASE=shared` to run against `DATABASE_URL` instead, for
+//! environments without a reachable Docker daemon such as the compose `app`
+//! service.
+
+use std::sync::Mutex;
+
+use diesel::{connection::SimpleConnection, Connection, PgConnection};
+use diesel_migrations::MigrationHarness;
+use once_cell::sync::Lazy;
+use testcontainers_modules::{
+    postgres::Postgres,
+    testcontainers::{runners::SyncRunner, Container, ImageExt},
+};
+use uuid::Uuid;
+
+use crate::db::migrations::MIGRATIONS;
+
+/// PostGIS build of Postgres, matching the image used by `docker-compose.yml`
+const POSTGRES_IMAGE: &str = "postgis/postgis";
+const POSTGRES_TAG: &str = "14-3.4-alpine";
+const TEMPLATE_DATABASE: &str = "forestry_template";
+
+/// Whether tests share `DATABASE_URL` rather than getting their own database
+pub fn shared_database() -> bool {
+    std::env::var("TEST_DATABASE").is_ok_and(|mode| mode.eq_ignore_ascii_case("shared"))
+}
+
+/// The running container, kept until the test binary exits
+static CONTAINER: Mutex<Option<Container<Postgres>>> = Mutex::new(None);
+
+/// Starts the container and returns the URL of its server
+///
+/// The `SyncRunner` drives the container on a runtime of its own, which
+/// can't be started from within the runtime of a `#[tokio::test]`, so this
+/// runs on a thread of its own.
+fn start_container() -> String {
+    let (container, server_url) = std::thread::spawn(|| {
+        let container = Postgres::default()
+            .with_name(POSTGRES_IMAGE)
+            .with_tag(POSTGRES_TAG)
+            .start()
+            .expect("Failed to start the Postgres test container; is Docker running? Set TEST_DATABASE=shared to use DATABASE_URL");
+        let host = container.get_host().expect("Failed to read the test container host");
+        let port = container
+            .get_host_port_ipv4(5432)
+            .expect("Failed to read the test container port");
+        let server_url = format!("postgres://postgres:postgres@{}:{}", host, port);
+        (container, server_url)
+    })
+    .join()
+    .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+    *CONTAINER.lock().unwrap_or_else(|e| e.into_inner()) = Some(container);
+    server_url
+}
+
+/// Connection to the container's maintenance database, which also
+/// serializes clones: Postgres refuses to copy a template that another
+/// `CREATE DATABASE` is reading
+static SERVER: Lazy<Mutex<(String, PgConnection)>> = Lazy::new(|| {
+    let server_url = start_container();
+
+    let mut admin = PgConnection::establish(&format!("{}/postgres", server_url))
+        .expect("Failed to connect to the test container");
+    admin
+        .batch_execute(&format!("CREATE DATABASE {}", TEMPLATE_DATABASE))
+        .expect("Failed to create the template database");
+
+    // The template must have no open connections by the time it is cloned
+    {
+        let mut template = PgConnection::establish(&format!("{}/{}", server_url, TEMPLATE_DATABASE))
+            .expect("Failed to connect to the template database");
+        template
+            .run_pending_migrations(MIGRATIONS)
+            .expect("Failed to migrate the template database");
+    }
+
+    Mutex::new((server_url, admin))
+});
+
+/// Creates a fresh, migrated database and returns its URL
+///
+/// Databases are not dropped individually; they go away with the container.
+pub fn isolated_database_url() -> String {
+    let mut server = SERVER.lock().unwrap_or_else(|e| e.into_inner());
+    let (server_url, admin) = &mut *server;
+    let name = format!("test_{}", Uuid::new_v4().simple());
+    admin
+        .batch_execute(&format!("CREATE DATABASE {} TEMPLATE {}", name, TEMPLATE_DATABASE))
+        .expect("Failed to clone the template database");
+    format!("{}/{}", server_url, name)
+}
+
+/// Removes the container when the test binary exits; statics are never
+/// dropped otherwise
+#[ctor::dtor]
+fn stop_container() {
+    if let Ok(mut container) = CONTAINER.lock() {
+        container.take();
+    }
+}
@@ -7,6 +7,7 @@ use crate::db::schema::{users, organizations};
 use futures::executor;
 use std::future::Future;
 use std::pin::Pin;
+use super::container::{isolated_database_url, shared_database};
 
 /// Global test configuration
 pub static TEST_CONFIG: Lazy<TestConfig> = Lazy::new(|| {
@@ -28,18 +29,42 @@ impl TestConfig {
     }
 }
 
+impl Default for TestConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Database test utilities
 pub struct TestDb;
 
 impl TestDb {
-    /// Creates a new database connection for testing with retries
+    /// Creates a connection to a database of its own, see [`TestDb::database_url`]
     pub fn conn() -> PgConnection {
+        Self::connect(&Self::database_url())
+    }
+
+    /// URL of a new migrated database cloned for the caller, or the shared
+    /// `DATABASE_URL` when `TEST_DATABASE=shared`
+    ///
+    /// Tests that need several connections to the same rows connect to one
+    /// URL with [`TestDb::connect`].
+    pub fn database_url() -> String {
+        if shared_database() {
+            TEST_CONFIG.database_url.clone()
+        } else {
+            isolated_database_url()
+        }
+    }
+
+    /// Connects to `database_url` with retries
+    pub fn connect(database_url: &str) -> PgConnection {
         let max_retries = 5;
         let mut retry_count = 0;
         let mut last_error = None;
 
         while retry_count < max_retries {
-            match PgConnection::establish(&TEST_CONFIG.database_url) {
+            match PgConnection::establish(database_url) {
                 Ok(conn) => return conn,
                 Err(e) => {
                     last_error = Some(e);
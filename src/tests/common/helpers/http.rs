@@ -0,0 +1,38 @@
+use actix_web::{
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test,
+};
+use serde_json::Value;
+
+use super::TestDb;
+use crate::{db::models::auth::User, domain::TokenManager, utils::Config};
+
+/// Configuration for a server against the test database
+pub fn app_config() -> Config {
+    Config::for_tests(&TestDb::database_url()).expect("Failed to load the test configuration")
+}
+
+/// Authorization header carrying an access token of `user`
+pub fn bearer(user: &User, config: &Config) -> (&'static str, String) {
+    let token = TokenManager::generate_token(user, config).expect("Failed to issue a test token");
+    ("Authorization", format!("Bearer {}", token))
+}
+
+/// Status and body of the response to `request`, including errors from
+/// middleware
+pub async fn send<S, B>(app: &S, request: test::TestRequest) -> (StatusCode, Value)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: actix_web::body::MessageBody,
+{
+    let (status, body) = match test::try_call_service(app, request.to_request()).await {
+        Ok(response) => (response.status(), test::read_body(response).await),
+        Err(error) => {
+            let response = error.error_response();
+            let status = response.status();
+            (status, actix_web::body::to_bytes(response.into_body()).await.unwrap_or_default())
+        }
+    };
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
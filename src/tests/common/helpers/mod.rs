@@ -1,5 +1,8 @@
 pub mod auth;
+pub mod container;
 pub mod database;
+pub mod http;
 
 pub use auth::*;
-pub use database::*; 
\ No newline at end of file
+pub use database::*;
+pub use http::*;
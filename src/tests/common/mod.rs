@@ -34,6 +34,12 @@ impl TestConfig {
     }
 }
 
+impl Default for TestConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Database test utilities
 pub struct TestDb;
 
@@ -75,11 +81,9 @@ pub async fn spawn_app() -> App<
 
 /// Test assertions
 pub mod assertions;
-pub mod fixtures;
 pub mod helpers;
 
 pub use assertions::*;
-pub use fixtures::*;
 pub use helpers::*;
 
 #[cfg(test)]
@@ -90,14 +94,4 @@ mod tests {
     async fn test_config_loading() {
         assert!(!TEST_CONFIG.database_url.is_empty());
     }
-
-    #[tokio::test]
-    async fn test_fake_data_generation() {
-        let user = fake_user();
-        assert!(user.get("email").is_some());
-        assert!(user.get("first_name").is_some());
-        assert!(user.get("last_name").is_some());
-        assert!(user.get("phone_number").is_some());
-        assert!(user.get("password").is_some());
-    }
 } 
\ No newline at end of file
@@ -0,0 +1,9 @@
+//! Contract tests of the HTTP API against its OpenAPI document
+//!
+//! Every operation in `ApiDoc` is called on the in-process application with
+//! payloads generated from the document, and the answers are checked
+//! against the documented statuses and schemas. Handlers and annotations
+//! drifting apart fail here rather than in a generated client.
+
+pub mod payloads;
+mod routes;
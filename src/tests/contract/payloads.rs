@@ -0,0 +1,174 @@
+//! Values generated from, and checked against, schemas of the OpenAPI document
+
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+/// The OpenAPI document as JSON, with helpers to read its schemas
+pub struct Spec {
+    document: Value,
+}
+
+impl Spec {
+    pub fn new(document: Value) -> Self {
+        Self { document }
+    }
+
+    pub fn document(&self) -> &Value {
+        &self.document
+    }
+
+    /// The schema behind a `$ref`, or `schema` itself
+    fn resolve<'a>(&'a self, schema: &'a Value) -> &'a Value {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => {
+                let pointer = reference.trim_start_matches('#');
+                self.resolve(self.document.pointer(pointer).unwrap_or(&Value::Null))
+            }
+            None => schema,
+        }
+    }
+
+    /// A minimal value satisfying `schema`: required properties only, the
+    /// first enum variant, the smallest allowed number and so on
+    pub fn valid_value(&self, schema: &Value) -> Value {
+        let schema = self.resolve(schema);
+        if let Some(variants) = schema.get("enum").and_then(Value::as_array) {
+            return variants.first().cloned().unwrap_or(Value::Null);
+        }
+        if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+            let mut merged = Map::new();
+            for part in parts {
+                if let Value::Object(fields) = self.valid_value(part) {
+                    merged.extend(fields);
+                }
+            }
+            return Value::Object(merged);
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+                let variant = variants.iter().find(|variant| type_of(self.resolve(variant)) != Some("null"));
+                return variant.map(|variant| self.valid_value(variant)).unwrap_or(Value::Null);
+            }
+        }
+
+        match type_of(schema) {
+            Some("object") | None if schema.get("properties").is_some() => self.valid_object(schema),
+            Some("object") => json!({}),
+            Some("array") => match schema.get("minItems").and_then(Value::as_u64) {
+                Some(n) if n > 0 => {
+                    let item = self.valid_value(schema.get("items").unwrap_or(&Value::Null));
+                    Value::Array(vec![item; n as usize])
+                }
+                _ => json!([]),
+            },
+            Some("string") => valid_string(schema),
+            Some("integer") => json!(bounded(schema, 1.0) as i64),
+            Some("number") => json!(bounded(schema, 1.0)),
+            Some("boolean") => json!(false),
+            _ => Value::Null,
+        }
+    }
+
+    fn valid_object(&self, schema: &Value) -> Value {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let required = schema.get("required").and_then(Value::as_array);
+        let mut object = Map::new();
+        for name in required.into_iter().flatten().filter_map(Value::as_str) {
+            let property = properties.and_then(|properties| properties.get(name)).unwrap_or(&Value::Null);
+            object.insert(name.to_string(), self.valid_value(property));
+        }
+        Value::Object(object)
+    }
+
+    /// Why `value` does not match `schema`, empty when it does
+    pub fn violations(&self, schema: &Value, value: &Value) -> Vec<String> {
+        // References point into the document, so validate against a schema
+        // that carries its components along
+        let mut root = schema.clone();
+        if let (Value::Object(root), Some(components)) = (&mut root, self.document.get("components")) {
+            root.insert("components".to_string(), components.clone());
+        }
+        let validator = match jsonschema::draft202012::new(&root) {
+            Ok(validator) => validator,
+            Err(e) => return vec![format!("invalid schema: {}", e)],
+        };
+        validator
+            .iter_errors(value)
+            .map(|error| format!("{} at {}", error, error.instance_path))
+            .collect()
+    }
+}
+
+/// The type of `schema`, ignoring `null` in `["string", "null"]`
+fn type_of(schema: &Value) -> Option<&str> {
+    match schema.get("type")? {
+        Value::String(kind) => Some(kind),
+        Value::Array(kinds) => kinds
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|kind| *kind != "null")
+            .or(Some("null")),
+        _ => None,
+    }
+}
+
+fn bounded(schema: &Value, preferred: f64) -> f64 {
+    let minimum = schema.get("minimum").and_then(Value::as_f64).unwrap_or(f64::MIN);
+    let maximum = schema.get("maximum").and_then(Value::as_f64).unwrap_or(f64::MAX);
+    preferred.clamp(minimum, maximum)
+}
+
+fn valid_string(schema: &Value) -> Value {
+    let unique = Uuid::new_v4();
+    let mut value = match schema.get("format").and_then(Value::as_str) {
+        Some("uuid") => return json!(unique),
+        Some("email") => return json!(format!("contract-{}@example.com", unique.simple())),
+        Some("date-time") => return json!(Utc::now().to_rfc3339()),
+        Some("date") => return json!(Utc::now().date_naive().to_string()),
+        _ => format!("Contract-{}", unique.simple()),
+    };
+    let min_length = schema.get("minLength").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let max_length = schema.get("maxLength").and_then(Value::as_u64).unwrap_or(u64::MAX) as usize;
+    while value.len() < min_length {
+        value.push('x');
+    }
+    value.truncate(max_length);
+    json!(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_value_follows_the_schema() {
+        let spec = Spec::new(json!({
+            "components": { "schemas": {
+                "Role": { "type": "string", "enum": ["Admin", "Operator"] },
+                "Input": {
+                    "type": "object",
+                    "required": ["email", "role", "size", "tags"],
+                    "properties": {
+                        "email": { "type": "string", "format": "email" },
+                        "role": { "$ref": "#/components/schemas/Role" },
+                        "size": { "type": ["integer", "null"], "minimum": 5 },
+                        "tags": { "type": "array", "items": { "type": "string", "maxLength": 4 }, "minItems": 1 },
+                        "note": { "type": "string" }
+                    }
+                }
+            }}
+        }));
+        let schema = json!({ "$ref": "#/components/schemas/Input" });
+
+        let value = spec.valid_value(&schema);
+        assert_eq!(value["role"], "Admin");
+        assert_eq!(value["size"], 5);
+        assert_eq!(value["tags"].as_array().unwrap().len(), 1);
+        assert!(value.get("note").is_none(), "optional properties are left out");
+        assert!(spec.violations(&schema, &value).is_empty());
+
+        let violations = spec.violations(&schema, &json!({ "email": "x", "role": "Guest" }));
+        assert!(!violations.is_empty());
+    }
+}
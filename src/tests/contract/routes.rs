@@ -0,0 +1,196 @@
+use actix_web::{
+    body::{self, MessageBody},
+    dev::{Service, ServiceResponse},
+    http::{Method, StatusCode},
+    test,
+};
+use bytes::Bytes;
+use serde_json::{json, Value};
+use utoipa::OpenApi;
+
+use super::payloads::Spec;
+use crate::{
+    api::resources::docs::openapi::ApiDoc,
+    db::models::auth::Role,
+    domain::TokenManager,
+    server,
+    tests::{common::helpers::app_config, factories::UserFactory, setup},
+};
+
+/// One documented operation
+struct Operation<'a> {
+    method: Method,
+    path: &'a str,
+    spec: &'a Value,
+}
+
+impl Operation<'_> {
+    fn name(&self) -> String {
+        format!("{} {}", self.method, self.path)
+    }
+
+    fn secured(&self) -> bool {
+        self.spec.get("security").and_then(Value::as_array).is_some_and(|s| !s.is_empty())
+    }
+
+    /// Parameters of the operation located `location` ("path" or "query")
+    fn parameters(&self, location: &str) -> Vec<&Value> {
+        self.spec
+            .get("parameters")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|parameter| parameter["in"] == location)
+            .collect()
+    }
+
+    fn request_schema(&self) -> Option<&Value> {
+        self.spec.pointer("/requestBody/content/application~1json/schema")
+    }
+
+    /// The URI with generated path parameters and required query parameters
+    fn uri(&self, spec: &Spec) -> String {
+        let mut uri = self.path.to_string();
+        for parameter in self.parameters("path") {
+            let name = parameter["name"].as_str().unwrap_or_default();
+            uri = uri.replace(&format!("{{{}}}", name), &plain(spec.valid_value(&parameter["schema"])));
+        }
+        let query: Vec<String> = self
+            .parameters("query")
+            .into_iter()
+            .filter(|parameter| parameter["required"] == true)
+            .map(|parameter| {
+                format!("{}={}", parameter["name"].as_str().unwrap_or_default(), plain(spec.valid_value(&parameter["schema"])))
+            })
+            .collect();
+        if !query.is_empty() {
+            uri = format!("{}?{}", uri, query.join("&"));
+        }
+        uri
+    }
+}
+
+/// A generated value as it appears in a path or query string
+fn plain(value: Value) -> String {
+    match value {
+        Value::String(value) => value,
+        value => value.to_string(),
+    }
+}
+
+fn operations(document: &Value) -> Vec<Operation<'_>> {
+    let paths = document["paths"].as_object().expect("the document has no paths");
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        for (method, spec) in item.as_object().into_iter().flatten() {
+            if let Ok(method) = Method::from_bytes(method.to_uppercase().as_bytes()) {
+                operations.push(Operation { method, path, spec });
+            }
+        }
+    }
+    operations
+}
+
+/// Status and body of the response, including errors middleware returns
+/// before a handler runs
+async fn call<S, B>(app: &S, request: test::TestRequest) -> (StatusCode, Bytes)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    match test::try_call_service(app, request.to_request()).await {
+        Ok(response) => {
+            let status = response.status();
+            (status, test::read_body(response).await)
+        }
+        Err(error) => {
+            let response = error.error_response();
+            (response.status(), body::to_bytes(response.into_body()).await.unwrap_or_default())
+        }
+    }
+}
+
+/// Checks `status` and `body` against the responses documented for
+/// `operation`, returning what does not match
+fn check_response(spec: &Spec, operation: &Operation, case: &str, status: StatusCode, body: &[u8]) -> Vec<String> {
+    let name = format!("{} ({})", operation.name(), case);
+    let responses = &operation.spec["responses"];
+    let Some(documented) = responses.get(status.as_str()).or_else(|| responses.get("default")) else {
+        let documented: Vec<&String> = responses.as_object().map(|r| r.keys().collect()).unwrap_or_default();
+        return vec![format!("{}: undocumented status {}, documented {:?}", name, status.as_u16(), documented)];
+    };
+    let Some(schema) = documented.pointer("/content/application~1json/schema") else {
+        return Vec::new();
+    };
+    let body: Value = match serde_json::from_slice(body) {
+        Ok(body) => body,
+        Err(_) => {
+            let body = String::from_utf8_lossy(body);
+            return vec![format!("{}: {} documents a JSON body, got {:?}", name, status.as_u16(), body)];
+        }
+    };
+    spec.violations(schema, &body)
+        .into_iter()
+        .map(|violation| format!("{}: {} body {}", name, status.as_u16(), violation))
+        .collect()
+}
+
+/// Calls every documented operation against the real application with a
+/// valid payload, a malformed payload and without a token, checking that the
+/// status is documented and the body matches the documented schema
+///
+/// Path parameters are fresh ids, so most calls to existing resources answer
+/// 404: this guards the contract, not the behavior of each handler.
+#[actix_rt::test]
+async fn test_routes_honor_the_openapi_contract() {
+    setup();
+    let config = app_config();
+    let admin = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.expect("Failed to create the admin")
+    };
+    // A fresh token per request, as logging out revokes the one it is called with
+    let token = || TokenManager::generate_token(&admin, &config).expect("Failed to sign a token");
+    let app = test::init_service(server::app(&config)).await;
+
+    let spec = Spec::new(serde_json::to_value(ApiDoc::openapi()).unwrap());
+    let operations = operations(spec.document());
+    assert!(operations.len() > 10, "no operations found in the document");
+
+    let mut problems = Vec::new();
+    for operation in &operations {
+        let uri = operation.uri(&spec);
+        let request = || {
+            test::TestRequest::default()
+                .method(operation.method.clone())
+                .uri(&uri)
+        };
+        let authorized = || request().insert_header(("Authorization", format!("Bearer {}", token())));
+
+        let valid = match operation.request_schema() {
+            Some(schema) => authorized().set_json(spec.valid_value(schema)),
+            None => authorized(),
+        };
+        let (status, body) = call(&app, valid).await;
+        problems.extend(check_response(&spec, operation, "valid", status, &body));
+
+        if operation.request_schema().is_some() {
+            let malformed = authorized().set_json(json!(["not", "an", "object"]));
+            let (status, body) = call(&app, malformed).await;
+            if !status.is_client_error() {
+                problems.push(format!("{} (malformed): expected a 4xx status, got {}", operation.name(), status.as_u16()));
+            }
+            problems.extend(check_response(&spec, operation, "malformed", status, &body));
+        }
+
+        if operation.secured() {
+            let (status, body) = call(&app, request()).await;
+            if status != StatusCode::UNAUTHORIZED {
+                problems.push(format!("{} (no token): expected 401, got {}", operation.name(), status.as_u16()));
+            }
+            problems.extend(check_response(&spec, operation, "no token", status, &body));
+        }
+    }
+
+    assert!(problems.is_empty(), "routes drifted from the OpenAPI document:\n{}", problems.join("\n"));
+}
@@ -0,0 +1,90 @@
+use chrono::Utc;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{BlockStatus, Boundary, HarvestBlock, Organization},
+        repositories::{HarvestBlockRepository, HarvestBlockRepositoryImpl},
+    },
+    error::Result,
+};
+
+/// A square boundary with its south-west corner at (`lon`, `lat`)
+pub fn square(lon: f64, lat: f64, size: f64) -> Boundary {
+    Boundary::Polygon {
+        coordinates: vec![vec![[lon, lat], [lon + size, lat], [lon + size, lat + size], [lon, lat + size], [lon, lat]]],
+    }
+}
+
+/// Builds planned harvest blocks of an organization, numbered uniquely
+/// unless told otherwise
+#[derive(Debug, Clone)]
+pub struct HarvestBlockFactory {
+    org_id: Uuid,
+    license: String,
+    block_number: Option<String>,
+    status: BlockStatus,
+    boundary: Boundary,
+}
+
+impl HarvestBlockFactory {
+    pub fn new(organization: &Organization) -> Self {
+        Self {
+            org_id: organization.id,
+            license: "L-100".to_string(),
+            block_number: None,
+            status: BlockStatus::Planned,
+            boundary: square(25.0, 61.0, 0.01),
+        }
+    }
+
+    pub fn license(mut self, license: impl Into<String>) -> Self {
+        self.license = license.into();
+        self
+    }
+
+    pub fn block_number(mut self, block_number: impl Into<String>) -> Self {
+        self.block_number = Some(block_number.into());
+        self
+    }
+
+    /// Stores the block in `status` directly, skipping the sign-off chain
+    pub fn status(mut self, status: BlockStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// The block, not stored
+    pub fn build(&self) -> HarvestBlock {
+        let now = Utc::now();
+        HarvestBlock {
+            id: Uuid::new_v4(),
+            org_id: self.org_id,
+            license: self.license.clone(),
+            block_number: self
+                .block_number
+                .clone()
+                .unwrap_or_else(|| Uuid::new_v4().simple().to_string()[..12].to_string()),
+            area_ha: 12.5,
+            planned_volume_m3: 2400.0,
+            status: self.status.to_string(),
+            boundary: self.boundary.clone(),
+            notes: None,
+            created_by: None,
+            created_at: now,
+            updated_at: now,
+            soil_sensitivity: None,
+        }
+    }
+
+    /// Stores a new block
+    pub async fn create(&self, conn: &mut PgConnection) -> Result<HarvestBlock> {
+        HarvestBlockRepositoryImpl.create(conn, &self.build()).await
+    }
+}
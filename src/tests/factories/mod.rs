@@ -0,0 +1,21 @@
+//! Builders for test records
+//!
+//! Every factory starts from valid, unique defaults, so a test only spells
+//! out the fields it is about:
+//!
+//! ```ignore
+//! let org = OrganizationFactory::new().create(conn).await?;
+//! let manager = UserFactory::new().role(Role::Manager).in_org(&org).create(conn).await?;
+//! let draft = UserFactory::new().email("sam@example.com").build();
+//! ```
+//!
+//! `build` returns the record without touching the database, `create`
+//! inserts it through its repository and returns the stored row.
+
+pub mod harvest_block;
+pub mod organization;
+pub mod user;
+
+pub use harvest_block::HarvestBlockFactory;
+pub use organization::OrganizationFactory;
+pub use user::UserFactory;
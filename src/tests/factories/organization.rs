@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use diesel::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::Organization,
+        repositories::{OrganizationRepositoryImpl, Repository},
+    },
+    error::Result,
+};
+
+const NAME_PREFIX: &str = "Test Organization";
+
+/// Builds organizations, named uniquely unless told otherwise
+#[derive(Debug, Clone, Default)]
+pub struct OrganizationFactory {
+    name: Option<String>,
+    created_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+    parent_id: Option<Uuid>,
+}
+
+impl OrganizationFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the creation time, also used as the update time
+    pub fn created_at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    /// Soft-deletes the organization at `deleted_at`
+    pub fn deleted_at(mut self, deleted_at: DateTime<Utc>) -> Self {
+        self.deleted_at = Some(deleted_at);
+        self
+    }
+
+    /// Makes the organization a division or area of `parent`
+    pub fn child_of(mut self, parent: &Organization) -> Self {
+        self.parent_id = Some(parent.id);
+        self
+    }
+
+    /// The organization, not stored
+    pub fn build(&self) -> Organization {
+        let created_at = self.created_at.unwrap_or_else(Utc::now);
+        Organization {
+            id: Uuid::new_v4(),
+            name: self
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("{} {}", NAME_PREFIX, Uuid::new_v4())),
+            created_at,
+            updated_at: created_at,
+            deleted_at: self.deleted_at,
+            parent_id: self.parent_id,
+            owner_id: None,
+            archived_at: None,
+        }
+    }
+
+    /// Stores a new organization
+    pub async fn create(&self, conn: &mut PgConnection) -> Result<Organization> {
+        OrganizationRepositoryImpl.create(conn, &self.build()).await
+    }
+
+    /// Stores `count` organizations
+    pub async fn create_many(&self, conn: &mut PgConnection, count: usize) -> Result<Vec<Organization>> {
+        let mut organizations = Vec::with_capacity(count);
+        for _ in 0..count {
+            organizations.push(self.create(conn).await?);
+        }
+        Ok(organizations)
+    }
+}
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use diesel::PgConnection;
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+
+use super::OrganizationFactory;
+use crate::{
+    db::{
+        models::{
+            auth::{Role, User},
+            Organization,
+        },
+        repositories::{auth::UserRepositoryImpl, Repository},
+    },
+    error::Result,
+};
+
+const EMAIL_DOMAIN: &str = "@example.com";
+const PHONE_PREFIX: &str = "123456";
+
+/// Hash of [`UserFactory::PASSWORD`], computed once as Argon2 is slow on
+/// purpose
+static PASSWORD_HASH: Lazy<String> =
+    Lazy::new(|| User::hash_password(UserFactory::PASSWORD).expect("Failed to hash the test password"));
+
+/// Builds users with a unique email and phone number, the
+/// [`UserFactory::PASSWORD`] and the operator role unless told otherwise
+#[derive(Debug, Clone, Default)]
+pub struct UserFactory {
+    first_name: Option<String>,
+    last_name: Option<String>,
+    email: Option<String>,
+    password: Option<String>,
+    role: Option<Role>,
+    org_id: Option<Uuid>,
+    email_verified: bool,
+    created_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+    deactivated: bool,
+    supervisor_id: Option<Uuid>,
+}
+
+impl UserFactory {
+    /// Password of the users built without one
+    pub const PASSWORD: &'static str = "test_password";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn first_name(mut self, first_name: impl Into<String>) -> Self {
+        self.first_name = Some(first_name.into());
+        self
+    }
+
+    pub fn last_name(mut self, last_name: impl Into<String>) -> Self {
+        self.last_name = Some(last_name.into());
+        self
+    }
+
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// Sets the plain text password, hashed when the user is built
+    pub fn password(mut self, password: impl Into<String>) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    pub fn role(mut self, role: Role) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    /// Makes the user a member of `organization`; without one, `create`
+    /// stores a new organization for the user
+    pub fn in_org(mut self, organization: &Organization) -> Self {
+        self.org_id = Some(organization.id);
+        self
+    }
+
+    pub fn verified(mut self) -> Self {
+        self.email_verified = true;
+        self
+    }
+
+    /// Sets the creation time, also used as the update time
+    pub fn created_at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    /// Soft-deletes the user at `deleted_at`
+    pub fn deleted_at(mut self, deleted_at: DateTime<Utc>) -> Self {
+        self.deleted_at = Some(deleted_at);
+        self
+    }
+
+    pub fn deactivated(mut self) -> Self {
+        self.deactivated = true;
+        self
+    }
+
+    /// Makes the user report to `supervisor`
+    pub fn reports_to(mut self, supervisor: &User) -> Self {
+        self.supervisor_id = Some(supervisor.id);
+        self
+    }
+
+    /// The user, not stored; without an organization it belongs to a
+    /// random organization id
+    pub fn build(&self) -> User {
+        let unique = Uuid::new_v4();
+        let created_at = self.created_at.unwrap_or_else(Utc::now);
+        let password = match &self.password {
+            Some(password) => User::hash_password(password).expect("Failed to hash the test password"),
+            None => PASSWORD_HASH.clone(),
+        };
+        User {
+            id: Uuid::new_v4(),
+            first_name: self.first_name.clone().unwrap_or_else(|| "Test".to_string()),
+            last_name: self.last_name.clone().unwrap_or_else(|| "User".to_string()),
+            email: self
+                .email
+                .clone()
+                .unwrap_or_else(|| format!("test{}{}", unique, EMAIL_DOMAIN)),
+            phone_number: format!("{}{}", PHONE_PREFIX, unique.simple()),
+            password,
+            org_id: self.org_id.unwrap_or_else(Uuid::new_v4),
+            created_at,
+            updated_at: created_at,
+            deleted_at: self.deleted_at,
+            role: self.role.unwrap_or(Role::Operator),
+            email_verified: self.email_verified,
+            avatar_updated_at: None,
+            is_active: !self.deactivated,
+            supervisor_id: self.supervisor_id,
+            anonymized_at: None,
+        }
+    }
+
+    /// Stores a new user, and an organization for it unless one was given
+    pub async fn create(&self, conn: &mut PgConnection) -> Result<User> {
+        let mut user = self.build();
+        if self.org_id.is_none() {
+            user.org_id = OrganizationFactory::new().create(conn).await?.id;
+        }
+        UserRepositoryImpl.create(conn, &user).await
+    }
+
+    /// Stores `count` users
+    pub async fn create_many(&self, conn: &mut PgConnection, count: usize) -> Result<Vec<User>> {
+        let mut users = Vec::with_capacity(count);
+        for _ in 0..count {
+            users.push(self.create(conn).await?);
+        }
+        Ok(users)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{common::helpers::TestDb, setup};
+
+    #[tokio::test]
+    async fn test_user_factory() -> Result<()> {
+        setup();
+        TestDb::run_test(|conn| {
+            Box::pin(async move {
+                let first = UserFactory::new().build();
+                let second = UserFactory::new().build();
+                assert_ne!(first.email, second.email);
+                assert_ne!(first.phone_number, second.phone_number);
+                assert_eq!(first.role, Role::Operator);
+                assert!(User::verify_password(UserFactory::PASSWORD, &first.password)?);
+
+                // Without an organization, one is created for the user
+                let user = UserFactory::new().role(Role::Manager).create(conn).await?;
+                let org = OrganizationFactory::new().create(conn).await?;
+                let member = UserFactory::new().in_org(&org).verified().create(conn).await?;
+                assert_eq!(user.role, Role::Manager);
+                assert_ne!(user.org_id, org.id);
+                assert_eq!(member.org_id, org.id);
+                assert!(member.email_verified);
+                Ok(())
+            })
+        })
+        .await
+    }
+}
@@ -8,7 +8,7 @@ use crate::tests::common::{TestAuth, assertions::*};
 mod tests {
     use super::*;
     use actix_web::{test, http::StatusCode, web, HttpResponse};
-    use crate::{error::Result, tests::common::fake_organization};
+    use crate::{error::Result, tests::factories::OrganizationFactory};
 
     #[tokio::test]
     #[serial]
@@ -33,7 +33,7 @@ mod tests {
         let admin_token = TestAuth::create_test_token(uuid::Uuid::new_v4(), "admin");
 
         // Test organization creation
-        let org_data = fake_organization();
+        let org_data = serde_json::json!({ "name": OrganizationFactory::new().build().name });
         let req = test::TestRequest::post()
             .uri("/organizations")
             .insert_header(("Authorization", format!("Bearer {}", admin_token)))
@@ -131,7 +131,7 @@ mod tests {
         for _ in 0..3 {
             let req = test::TestRequest::post()
                 .uri("/organizations")
-                .set_json(&fake_organization())
+                .set_json(serde_json::json!({ "name": OrganizationFactory::new().build().name }))
                 .to_request();
             
             let resp = test::call_service(&app, req).await;
@@ -1,7 +1,7 @@
 use actix_web::test;
 use uuid::Uuid;
 use crate::{
-    tests::common::{spawn_app, helpers::TestAuth, fixtures::*},
+    tests::{common::{spawn_app, helpers::TestAuth}, factories::{OrganizationFactory, UserFactory}},
     error::Result,
 };
 
@@ -31,7 +31,7 @@ async fn test_complete_organization_workflow() -> Result<()> {
 
     // Complete workflow test
     let token = TestAuth::create_test_token(Uuid::new_v4(), "admin");
-    let org_data = fake_organization();
+    let org_data = serde_json::json!({ "name": OrganizationFactory::new().build().name });
 
     // 1. Create organization
     let create_resp = test::call_service(&app, 
@@ -47,7 +47,14 @@ async fn test_complete_organization_workflow() -> Result<()> {
     let org_id = create_resp.response().json::<serde_json::Value>().id.to_string();
 
     // 2. Add users to organization
-    let user_data = fake_user();
+    let user = UserFactory::new().build();
+    let user_data = serde_json::json!({
+        "first_name": user.first_name,
+        "last_name": user.last_name,
+        "email": user.email,
+        "phone_number": user.phone_number,
+        "password": UserFactory::PASSWORD,
+    });
     let add_user_resp = test::call_service(&app,
         test::TestRequest::post()
             .uri(&format!("/organizations/{}/users", org_id))
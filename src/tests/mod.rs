@@ -1,4 +1,6 @@
 pub mod common;
+pub mod contract;
+pub mod factories;
 pub mod integration;
 pub mod unit;
 pub mod performance;
@@ -30,6 +32,7 @@ pub fn setup() {
 
 /// Test module organization
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use super::*;
 
@@ -42,6 +45,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_module_organization() {
         // Just verifying module structure exists
         assert!(true);
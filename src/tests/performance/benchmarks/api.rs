@@ -2,7 +2,7 @@ use std::time::Instant;
 use actix_web::test;
 use uuid::Uuid;
 use crate::{
-    tests::common::{spawn_app, helpers::TestAuth, fixtures::*},
+    tests::{common::{spawn_app, helpers::TestAuth}, factories::OrganizationFactory},
     error::Result,
 };
 use super::super::PERFORMANCE_THRESHOLD_MS;
@@ -18,7 +18,7 @@ async fn test_api_response_time() -> Result<()> {
     ).await;
 
     let token = TestAuth::create_test_token(Uuid::new_v4(), "admin");
-    let org_data = fake_organization();
+    let org_data = serde_json::json!({ "name": OrganizationFactory::new().build().name });
 
     let start = Instant::now();
     
@@ -1,9 +1,7 @@
 use std::time::Instant;
-use fake::{Faker, Fake};
-use uuid::Uuid;
-use diesel::{RunQueryDsl, connection::SimpleConnection};
+use diesel::RunQueryDsl;
 use crate::{
-    tests::common::helpers::TestDb,
+    tests::{common::helpers::TestDb, factories::OrganizationFactory},
     db::{
         schema::organizations,
         models::organization::Organization,
@@ -16,26 +14,9 @@ use super::super::{BATCH_SIZE, PERFORMANCE_THRESHOLD_MS};
 async fn test_bulk_insert_performance() {
     let start = Instant::now();
     
-    // Run migrations first
-    let mut conn = TestDb::conn();
-    conn.batch_execute("CREATE TABLE IF NOT EXISTS organizations (
-        id UUID PRIMARY KEY,
-        name VARCHAR NOT NULL,
-        created_at TIMESTAMP NOT NULL,
-        updated_at TIMESTAMP NOT NULL,
-        deleted_at TIMESTAMP
-    )").expect("Failed to create table");
-    
     // Generate test data
-    let organizations: Vec<Organization> = (0..BATCH_SIZE)
-        .map(|_| Organization {
-            id: Uuid::new_v4(),
-            name: Faker.fake(),
-            created_at: chrono::Utc::now(),
-            updated_at: chrono::Utc::now(),
-            deleted_at: None,
-        })
-        .collect();
+    let factory = OrganizationFactory::new();
+    let organizations: Vec<Organization> = (0..BATCH_SIZE).map(|_| factory.build()).collect();
 
     let result = TestDb::run_test(|conn| {
         Box::pin(async move {
@@ -1,9 +1,7 @@
 use std::time::Instant;
-use fake::{Faker, Fake};
-use uuid::Uuid;
-use diesel::{RunQueryDsl, QueryDsl, ExpressionMethods, connection::SimpleConnection};
+use diesel::{RunQueryDsl, QueryDsl, ExpressionMethods};
 use crate::{
-    tests::common::helpers::TestDb,
+    tests::{common::helpers::TestDb, factories::OrganizationFactory},
     db::{
         schema::organizations,
         models::organization::Organization,
@@ -14,49 +12,25 @@ use super::super::{CONCURRENT_USERS, PERFORMANCE_THRESHOLD_MS};
 
 #[tokio::test]
 async fn test_concurrent_reads() {
-    let mut conn = TestDb::conn();
-    conn.batch_execute("CREATE TABLE IF NOT EXISTS organizations (
-        id UUID PRIMARY KEY,
-        name VARCHAR NOT NULL,
-        created_at TIMESTAMP NOT NULL,
-        updated_at TIMESTAMP NOT NULL,
-        deleted_at TIMESTAMP
-    )").expect("Failed to create table");
-
-    let org = Organization {
-        id: Uuid::new_v4(),
-        name: Faker.fake(),
-        created_at: chrono::Utc::now(),
-        updated_at: chrono::Utc::now(),
-        deleted_at: None,
-    };
-
-    // Insert test data
+    // Every reader connects to the same database, so the row is committed
+    let database_url = TestDb::database_url();
+    let org = OrganizationFactory::new()
+        .create(&mut TestDb::connect(&database_url))
+        .await
+        .expect("Failed to insert test data");
     let org_id = org.id;
-    TestDb::run_test(|conn| {
-        Box::pin(async move {
-            diesel::insert_into(organizations::table)
-                .values(&org)
-                .execute(conn)
-                .map_err(|e| ApiError::from(DatabaseError::from(e)))
-        })
-    }).await.expect("Failed to insert test data");
 
     let start = Instant::now();
     let mut handles = vec![];
 
     // Spawn concurrent read operations
     for _ in 0..CONCURRENT_USERS {
-        let org_id = org_id;
-        let handle = tokio::spawn(async move {
-            TestDb::run_test(|conn| {
-                Box::pin(async move {
-                    organizations::table
-                        .filter(organizations::id.eq(org_id))
-                        .first::<Organization>(conn)
-                        .map_err(|e| ApiError::from(DatabaseError::from(e)))
-                })
-            }).await
+        let database_url = database_url.clone();
+        let handle = tokio::task::spawn_blocking(move || {
+            organizations::table
+                .filter(organizations::id.eq(org_id))
+                .first::<Organization>(&mut TestDb::connect(&database_url))
+                .map_err(|e| ApiError::from(DatabaseError::from(e)))
         });
         handles.push(handle);
     }
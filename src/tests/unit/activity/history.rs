@@ -0,0 +1,104 @@
+use actix_web::{http::StatusCode, test};
+use chrono::Utc;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    api::resources::certification::dto::SaveCertificationInput,
+    db::{models::auth::Role, repositories::{CertificationRepositoryImpl, DocumentRepositoryImpl}},
+    domain::{
+        activity::{ActivityKind, NewActivity},
+        certification::CertificationService,
+        document::DocumentService,
+        ActivityLog, TokenManager,
+    },
+    infrastructure::ObjectStorage,
+    server,
+    tests::{
+        common::helpers::{app_config, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_supervisors_review_what_a_user_did() {
+    setup();
+    let config = app_config();
+    let (operator, manager, outsider) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).verified().create(&mut conn).await.unwrap();
+        let manager = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().role(Role::Manager).verified().create(&mut conn).await.unwrap();
+
+        let certifications = CertificationService::new(
+            CertificationRepositoryImpl,
+            DocumentService::new(DocumentRepositoryImpl, ObjectStorage::in_memory()),
+        );
+        certifications
+            .create(&mut conn, organization.id, Some(operator.id), SaveCertificationInput {
+                user_id: operator.id,
+                kind: "first_aid".to_string(),
+                name: None,
+                certificate_number: None,
+                issuer: None,
+                issued_on: "2024-03-01".parse().unwrap(),
+                expires_on: None,
+            })
+            .await
+            .unwrap();
+        let block_id = Uuid::new_v4();
+        ActivityLog::record(
+            &mut conn,
+            organization.id,
+            Some(operator.id),
+            NewActivity::new(ActivityKind::BlockSignedOff, "block", block_id, "Signed off the harvest step of a block")
+                .with_data(json!({ "step": "harvest" })),
+        )
+        .await;
+        // Actions without a user aren't anyone's activity
+        ActivityLog::record(
+            &mut conn,
+            organization.id,
+            None,
+            NewActivity::new(ActivityKind::BlockSignedOff, "block", block_id, "Signed off by the system"),
+        )
+        .await;
+        (operator, manager, outsider)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let get = |uri: String, user: &crate::db::models::auth::User| {
+        let token = TokenManager::generate_token(user, &config).unwrap();
+        test::TestRequest::get().uri(&uri).insert_header(("Authorization", format!("Bearer {}", token)))
+    };
+    let activity = |query: &str| format!("/v1/users/{}/activity{}", operator.id, query);
+    let actions = |body: &Value| -> Vec<String> {
+        body["data"].as_array().unwrap().iter().map(|activity| activity["action"].as_str().unwrap().to_string()).collect()
+    };
+
+    let (status, body) = send(&app, get(activity(""), &manager)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(actions(&body), ["block_signed_off", "certification_recorded"]);
+    assert_eq!(body["data"][0]["subject_type"], "block");
+    assert_eq!(body["data"][0]["data"]["step"], "harvest");
+    assert_eq!(body["data"][1]["summary"], "Recorded First aid");
+    assert_eq!(body["meta"]["total_items"], 2);
+
+    let (_, body) = send(&app, get(activity("?per_page=1&page=2"), &manager)).await;
+    assert_eq!(actions(&body), ["certification_recorded"]);
+    let today = Utc::now().date_naive();
+    let (_, body) = send(&app, get(activity(&format!("?date={}", today)), &manager)).await;
+    assert_eq!(actions(&body).len(), 2);
+    let (_, body) = send(&app, get(activity("?date=2024-03-01"), &manager)).await;
+    assert!(actions(&body).is_empty());
+    assert_eq!(send(&app, get(activity("?date=yesterday"), &manager)).await.0, StatusCode::BAD_REQUEST);
+
+    // Operators see only their own
+    assert_eq!(send(&app, get(activity(""), &operator)).await.0, StatusCode::FORBIDDEN);
+    let (status, body) = send(&app, get("/v1/me/activity".to_string(), &operator)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(actions(&body).len(), 2);
+
+    assert_eq!(send(&app, get(activity(""), &outsider)).await.0, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,97 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+
+use crate::{
+    db::models::auth::{Role, User},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::UserFactory,
+        setup,
+    },
+    utils::Config,
+};
+
+/// Configuration listing a new platform admin, with an admin of another
+/// organization and the platform admin
+async fn admins() -> (Config, User, User) {
+    let mut config = app_config();
+    let (admin, platform_admin) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let platform_admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (admin, platform_admin)
+    };
+    config.auth.platform_admins.push(platform_admin.id);
+    (config, admin, platform_admin)
+}
+
+#[actix_rt::test]
+async fn test_only_platform_admins_change_the_log_filter() {
+    setup();
+    let (config, admin, _) = admins().await;
+    let app = test::init_service(server::app(&config)).await;
+
+    let (status, body) = send(
+        &app,
+        test::TestRequest::put()
+            .uri("/v1/admin/log-filter")
+            .insert_header(bearer(&admin, &config))
+            .set_json(json!({ "filter": "off" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["details"]["code"], "PLATFORM_ADMIN_REQUIRED");
+}
+
+#[actix_rt::test]
+async fn test_only_platform_admins_reschedule_jobs() {
+    setup();
+    let (config, admin, _) = admins().await;
+    let app = test::init_service(server::app(&config)).await;
+
+    let (status, body) = send(
+        &app,
+        test::TestRequest::put()
+            .uri("/v1/admin/jobs/purger/schedule")
+            .insert_header(bearer(&admin, &config))
+            .set_json(json!({ "schedule": "off" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["details"]["code"], "PLATFORM_ADMIN_REQUIRED");
+}
+
+#[actix_rt::test]
+async fn test_only_platform_admins_reach_the_job_queue() {
+    setup();
+    let (config, admin, platform_admin) = admins().await;
+    let app = test::init_service(server::app(&config)).await;
+    let dead_letter = format!("/v1/admin/queue/dead-letters/{}", uuid::Uuid::new_v4());
+
+    for request in [
+        test::TestRequest::get().uri("/v1/admin/queue/jobs"),
+        test::TestRequest::get().uri("/v1/admin/queue/dead-letters"),
+        test::TestRequest::get().uri(&dead_letter),
+        test::TestRequest::post().uri(&format!("{}/requeue", dead_letter)),
+        test::TestRequest::delete().uri(&dead_letter),
+    ] {
+        let (status, body) = send(&app, request.insert_header(bearer(&admin, &config))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["details"]["code"], "PLATFORM_ADMIN_REQUIRED");
+    }
+
+    let (status, _) = send(&app, test::TestRequest::get().uri("/v1/admin/queue/dead-letters").insert_header(bearer(&platform_admin, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[actix_rt::test]
+async fn test_only_platform_admins_reload_the_configuration() {
+    setup();
+    let (config, admin, _) = admins().await;
+    let app = test::init_service(server::app(&config)).await;
+
+    let (status, body) = send(&app, test::TestRequest::post().uri("/v1/admin/config/reload").insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["details"]["code"], "PLATFORM_ADMIN_REQUIRED");
+}
@@ -0,0 +1,121 @@
+use actix_web::{http::StatusCode, test};
+use diesel::prelude::*;
+use serde_json::json;
+
+use crate::{
+    db::{
+        anonymize::{FORGOTTEN_FIRST_NAME, FORGOTTEN_LAST_NAME},
+        models::{
+            auth::{AuthEvent, Role, User},
+            Certification,
+        },
+        repositories::{
+            auth::{RefreshTokenRepository, RefreshTokenRepositoryImpl, SessionLifetime},
+            CertificationRepository, CertificationRepositoryImpl,
+        },
+        schema::{auth_events, refresh_tokens, users},
+    },
+    domain::auth::{AuthAudit, AuthEventKind, ClientInfo, NewAuthEvent},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_departed_users_are_forgotten() {
+    setup();
+    let config = app_config();
+    let (admin, departed, colleague, outsider, certification) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let departed = UserFactory::new()
+            .in_org(&organization)
+            .first_name("Jane")
+            .last_name("Doe")
+            .deactivated()
+            .create(&mut conn)
+            .await
+            .unwrap();
+        let colleague = UserFactory::new().in_org(&organization).create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+
+        RefreshTokenRepositoryImpl.create_for_user(&mut conn, departed.id, SessionLifetime::default()).await.unwrap();
+        let client = ClientInfo::new(Some("203.0.113.7"), Some("Chainsaw/1.0"));
+        AuthAudit::record(&mut conn, &client, NewAuthEvent::new(AuthEventKind::LoginFailed, None).email(&departed.email)).await;
+        let certification = CertificationRepositoryImpl
+            .create(&mut conn, &Certification {
+                id: uuid::Uuid::new_v4(),
+                org_id: organization.id,
+                user_id: departed.id,
+                kind: "faller".to_string(),
+                name: None,
+                certificate_number: Some("F-1234".to_string()),
+                issuer: None,
+                issued_on: "2024-03-01".parse().unwrap(),
+                expires_on: None,
+                expiry_notified_at: None,
+                created_by: Some(admin.id),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        (admin, departed, colleague, outsider, certification)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let anonymize = |id: uuid::Uuid, caller: &User| {
+        test::TestRequest::post()
+            .uri(&format!("/v1/users/{}/anonymize", id))
+            .insert_header(bearer(caller, &config))
+    };
+
+    // Only departed members of the admin's organization
+    let (status, body) = send(&app, anonymize(colleague.id, &admin)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["details"]["code"], "STILL_ACTIVE");
+    assert_eq!(send(&app, anonymize(admin.id, &admin)).await.0, StatusCode::FORBIDDEN);
+    assert_eq!(send(&app, anonymize(departed.id, &outsider)).await.0, StatusCode::NOT_FOUND);
+
+    let (status, body) = send(&app, anonymize(departed.id, &admin)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["first_name"], FORGOTTEN_FIRST_NAME);
+    assert_eq!(body["last_name"], FORGOTTEN_LAST_NAME);
+    assert_eq!(body["phone_number"], "");
+    assert!(body["anonymized_at"].is_string());
+    assert_ne!(body["email"], json!(departed.email));
+
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let forgotten = users::table.find(departed.id).select(User::as_select()).first(&mut conn).unwrap();
+    assert_ne!(forgotten.password, departed.password);
+    let tokens: i64 = refresh_tokens::table
+        .filter(refresh_tokens::user_id.eq(departed.id))
+        .count()
+        .get_result(&mut conn)
+        .unwrap();
+    assert_eq!(tokens, 0);
+    let named: i64 = auth_events::table
+        .filter(auth_events::email.eq(&departed.email))
+        .count()
+        .get_result(&mut conn)
+        .unwrap();
+    assert_eq!(named, 0);
+    let events: Vec<AuthEvent> = auth_events::table
+        .filter(auth_events::user_id.eq(departed.id))
+        .select(AuthEvent::as_select())
+        .load(&mut conn)
+        .unwrap();
+    assert_eq!(events.iter().map(|event| event.event.as_str()).collect::<Vec<_>>(), ["anonymized"]);
+
+    // What they produced is still theirs
+    let kept = CertificationRepositoryImpl.find(&mut conn, certification.org_id, certification.id).await.unwrap();
+    assert_eq!(kept.user_id, departed.id);
+
+    // Once is enough
+    let (status, again) = send(&app, anonymize(departed.id, &admin)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(again["anonymized_at"], body["anonymized_at"]);
+}
@@ -0,0 +1,2 @@
+pub mod rewrite;
+pub mod forget;
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use diesel::{prelude::*, sql_query, sql_types::Text};
+use serde_json::json;
+use uuid::Uuid;
+use crate::{
+    api::resources::report::dto::{SaveReportInput, ScheduleReportInput},
+    db::{
+        anonymize::{anonymize, fake_organization_name, FakeUser, ANONYMIZED_PASSWORD},
+        models::{auth::User, Organization},
+        repositories::{
+            auth::SessionLifetime, RefreshTokenRepository, RefreshTokenRepositoryImpl, ReportRepositoryImpl,
+            ReportScheduleRepositoryImpl,
+        },
+        schema::{
+            change_history, customers, email_change_tokens, organizations, refresh_tokens, sale_contracts,
+            tender_bids, tender_parcels, timber_tenders, user_identities, users,
+        },
+    },
+    domain::report::{ReportScheduleService, ReportService},
+    error::Result,
+    tests::{
+        common::helpers::TestDb,
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[tokio::test]
+async fn test_anonymize_replaces_personal_data_deterministically() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let organization = OrganizationFactory::new().name("Acme Logging").create(conn).await?;
+            let user = UserFactory::new()
+                .first_name("Jane")
+                .last_name("Doe")
+                .email("jane.doe@acme.example")
+                .in_org(&organization)
+                .create(conn)
+                .await?;
+            RefreshTokenRepositoryImpl.create_for_user(conn, user.id, SessionLifetime::default()).await?;
+
+            let report = anonymize(conn).await?;
+            assert!(report.users >= 1);
+            assert!(report.deleted >= 1);
+
+            let anonymized = users::table.find(user.id).select(User::as_select()).first(conn).unwrap();
+            let fake = FakeUser::for_id(user.id);
+            assert_eq!(anonymized.email, fake.email);
+            assert_eq!(anonymized.first_name, fake.first_name);
+            assert_eq!(anonymized.phone_number, fake.phone_number);
+            assert_eq!(anonymized.org_id, organization.id, "references are kept");
+            assert!(User::verify_password(ANONYMIZED_PASSWORD, &anonymized.password)?);
+
+            let renamed = organizations::table.find(organization.id).first::<Organization>(conn).unwrap();
+            assert_eq!(renamed.name, fake_organization_name(organization.id));
+
+            let tokens = refresh_tokens::table
+                .filter(refresh_tokens::user_id.eq(user.id))
+                .count()
+                .get_result::<i64>(conn)
+                .unwrap();
+            assert_eq!(tokens, 0);
+
+            // A second run lands on the same fakes
+            anonymize(conn).await?;
+            let again = users::table.find(user.id).select(User::as_select()).first(conn).unwrap();
+            assert_eq!(again.email, anonymized.email);
+            assert_eq!(again.last_name, anonymized.last_name);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[derive(QueryableByName)]
+struct JsonRow {
+    #[diesel(sql_type = Text)]
+    row: String,
+}
+
+#[tokio::test]
+async fn test_anonymize_leaves_no_personal_value_behind() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let now = Utc::now();
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let user = UserFactory::new().in_org(&organization).create(conn).await?;
+
+            diesel::insert_into(customers::table)
+                .values((
+                    customers::id.eq(Uuid::new_v4()),
+                    customers::org_id.eq(organization.id),
+                    customers::name.eq("Sawmill Ltd"),
+                    customers::contact_name.eq("Karl Kontakt"),
+                    customers::email.eq("karl@sawmill.example"),
+                    customers::phone_number.eq("+15559876543"),
+                    customers::address.eq("12 Mill Road"),
+                    customers::notes.eq("Call Karl after five"),
+                    customers::created_at.eq(now),
+                    customers::updated_at.eq(now),
+                ))
+                .execute(conn)
+                .unwrap();
+
+            let (tender_id, parcel_id, bid_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+            diesel::insert_into(timber_tenders::table)
+                .values((
+                    timber_tenders::id.eq(tender_id),
+                    timber_tenders::org_id.eq(organization.id),
+                    timber_tenders::title.eq("Winter sale"),
+                    timber_tenders::status.eq("awarded"),
+                    timber_tenders::bid_deadline.eq(now),
+                    timber_tenders::created_at.eq(now),
+                    timber_tenders::updated_at.eq(now),
+                ))
+                .execute(conn)
+                .unwrap();
+            diesel::insert_into(tender_parcels::table)
+                .values((
+                    tender_parcels::id.eq(parcel_id),
+                    tender_parcels::tender_id.eq(tender_id),
+                    tender_parcels::position.eq(1),
+                    tender_parcels::assortment.eq("spruce logs"),
+                    tender_parcels::volume_m3.eq(500.0),
+                    tender_parcels::created_at.eq(now),
+                ))
+                .execute(conn)
+                .unwrap();
+            diesel::insert_into(tender_bids::table)
+                .values((
+                    tender_bids::id.eq(bid_id),
+                    tender_bids::tender_id.eq(tender_id),
+                    tender_bids::parcel_id.eq(parcel_id),
+                    tender_bids::bidder_name.eq("Holz Brothers GmbH"),
+                    tender_bids::bidder_email.eq("offers@holz.example"),
+                    tender_bids::price_per_m3.eq(61.5),
+                    tender_bids::received_at.eq(now),
+                    tender_bids::created_at.eq(now),
+                ))
+                .execute(conn)
+                .unwrap();
+            diesel::insert_into(sale_contracts::table)
+                .values((
+                    sale_contracts::id.eq(Uuid::new_v4()),
+                    sale_contracts::org_id.eq(organization.id),
+                    sale_contracts::tender_id.eq(tender_id),
+                    sale_contracts::parcel_id.eq(parcel_id),
+                    sale_contracts::bid_id.eq(bid_id),
+                    sale_contracts::buyer_name.eq("Holz Brothers GmbH"),
+                    sale_contracts::assortment.eq("spruce logs"),
+                    sale_contracts::volume_m3.eq(500.0),
+                    sale_contracts::price_per_m3.eq(61.5),
+                    sale_contracts::created_at.eq(now),
+                ))
+                .execute(conn)
+                .unwrap();
+
+            diesel::insert_into(user_identities::table)
+                .values((
+                    user_identities::provider.eq("oidc"),
+                    user_identities::subject.eq(Uuid::new_v4().to_string()),
+                    user_identities::user_id.eq(user.id),
+                    user_identities::email.eq("jane@idp.example"),
+                    user_identities::created_at.eq(now),
+                    user_identities::last_login_at.eq(now),
+                ))
+                .execute(conn)
+                .unwrap();
+            diesel::insert_into(email_change_tokens::table)
+                .values((
+                    email_change_tokens::id.eq(Uuid::new_v4()),
+                    email_change_tokens::token_hash.eq(Uuid::new_v4().to_string()),
+                    email_change_tokens::user_id.eq(user.id),
+                    email_change_tokens::new_email.eq("jane.new@acme.example"),
+                    email_change_tokens::expires_at.eq(now),
+                    email_change_tokens::created_at.eq(now),
+                    email_change_tokens::updated_at.eq(now),
+                ))
+                .execute(conn)
+                .unwrap();
+
+            let input: SaveReportInput = serde_json::from_value(json!({
+                "name": "Members by role",
+                "definition": {
+                    "dataset": "users",
+                    "group_by": ["role"],
+                    "columns": [{ "field": "role" }, { "field": "id", "aggregate": "count" }]
+                }
+            }))
+            .unwrap();
+            let report = ReportService::new(ReportRepositoryImpl).create(conn, organization.id, None, input).await?;
+            let schedule = ScheduleReportInput {
+                schedule: "0 0 6 * * Mon".into(),
+                recipients: vec!["boss@acme.example".into()],
+                format: None,
+                parameters: HashMap::new(),
+            };
+            ReportScheduleService::new(ReportRepositoryImpl, ReportScheduleRepositoryImpl)
+                .set(conn, organization.id, report.id, None, schedule)
+                .await?;
+
+            diesel::insert_into(change_history::table)
+                .values((
+                    change_history::id.eq(Uuid::new_v4()),
+                    change_history::org_id.eq(organization.id),
+                    change_history::resource.eq("user"),
+                    change_history::record_id.eq(user.id),
+                    change_history::field.eq("email"),
+                    change_history::old_value.eq(json!("jane.old@acme.example")),
+                    change_history::new_value.eq(json!("jane.doe@acme.example")),
+                    change_history::changed_at.eq(now),
+                ))
+                .execute(conn)
+                .unwrap();
+
+            anonymize(conn).await?;
+
+            let personal = [
+                "Karl", "karl@sawmill.example", "+15559876543", "Mill Road", "Holz", "holz.example", "jane@idp.example",
+                "jane.new@acme.example", "boss@acme.example", "jane.old@acme.example", "jane.doe@acme.example",
+            ];
+            let tables = [
+                "customers",
+                "tender_bids",
+                "sale_contracts",
+                "user_identities",
+                "email_change_tokens",
+                "report_schedules",
+                "change_history",
+            ];
+            for table in tables {
+                let rows = sql_query(format!("SELECT row_to_json(t)::text AS row FROM {} t", table))
+                    .load::<JsonRow>(conn)
+                    .unwrap();
+                for JsonRow { row } in rows {
+                    assert!(!personal.iter().any(|value| row.contains(value)), "{} kept personal data: {}", table, row);
+                }
+            }
+
+            // Identities take the fake address of their user
+            let email = user_identities::table
+                .filter(user_identities::user_id.eq(user.id))
+                .select(user_identities::email)
+                .first::<String>(conn)
+                .unwrap();
+            assert_eq!(email, FakeUser::for_id(user.id).email);
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,92 @@
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{auth::Role, SignoffStep},
+        repositories::SignoffRepositoryImpl,
+    },
+    domain::approval::ApprovalService,
+    error::{ErrorCode, Result},
+    tests::{
+        common::helpers::TestDb,
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[tokio::test]
+async fn test_block_is_signed_off_in_order() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = ApprovalService::new(SignoffRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let planner = UserFactory::new().in_org(&organization).create(conn).await?;
+            let forester = UserFactory::new().role(Role::Manager).in_org(&organization).create(conn).await?;
+            let manager = UserFactory::new().role(Role::Manager).in_org(&organization).create(conn).await?;
+            let block_id = Uuid::new_v4();
+            let package = "ab".repeat(32);
+
+            let err = service.ensure_approved(conn, organization.id, block_id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            // Steps are signed in order
+            let err = service
+                .sign(conn, organization.id, block_id, forester.id, SignoffStep::ProfessionalForester, &package)
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            let signed = service
+                .sign(conn, organization.id, block_id, planner.id, SignoffStep::Planner, &package)
+                .await?;
+            assert_eq!(signed.signer_name, format!("{} {}", planner.first_name, planner.last_name));
+            let err = service
+                .sign(conn, organization.id, block_id, planner.id, SignoffStep::Planner, &package)
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            // An operator cannot sign as forester, and nobody signs twice
+            let err = service
+                .sign(conn, organization.id, block_id, planner.id, SignoffStep::ProfessionalForester, &package)
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::Forbidden);
+
+            // Every signer signs the same document
+            let err = service
+                .sign(conn, organization.id, block_id, forester.id, SignoffStep::ProfessionalForester, &"cd".repeat(32))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            service
+                .sign(conn, organization.id, block_id, forester.id, SignoffStep::ProfessionalForester, &package)
+                .await?;
+            let err = service
+                .sign(conn, organization.id, block_id, forester.id, SignoffStep::OperationsManager, &package)
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::Forbidden);
+            service
+                .sign(conn, organization.id, block_id, manager.id, SignoffStep::OperationsManager, &package)
+                .await?;
+
+            let (signoffs, status) = service.status(conn, organization.id, block_id).await?;
+            assert_eq!(signoffs.len(), 3);
+            assert!(status.approved());
+            service.ensure_approved(conn, organization.id, block_id).await?;
+
+            // A revised package starts the chain over
+            let err = service.reset(conn, organization.id, block_id, planner.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Forbidden);
+            service.reset(conn, organization.id, block_id, manager.id).await?;
+            let (_, status) = service.status(conn, organization.id, block_id).await?;
+            assert_eq!(status.next_step, Some(SignoffStep::Planner));
+
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,194 @@
+use std::sync::Arc;
+
+use actix_web::{http::StatusCode, test};
+use chrono::{DateTime, Duration, Utc};
+use diesel::{
+    prelude::*,
+    sql_types::{Double, Timestamptz, Uuid as SqlUuid},
+};
+use uuid::Uuid;
+use crate::{
+    db::{
+        models::auth::Role,
+        repositories::{ArchiveRepositoryImpl, Repository},
+        schema::archives,
+    },
+    error::{ErrorCode, Result},
+    infrastructure::ObjectStorage,
+    jobs::archive::{ArchiveDataset, ArchiveRecord, Archiver, TimeRange},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send, TestDb},
+        factories::UserFactory,
+        setup,
+    },
+};
+
+/// Dataset backed by a temporary table that only lives for the test transaction
+struct ReadingsDataset;
+
+#[derive(QueryableByName)]
+struct Reading {
+    #[diesel(sql_type = SqlUuid)]
+    id: Uuid,
+    #[diesel(sql_type = Timestamptz)]
+    recorded_at: DateTime<Utc>,
+    #[diesel(sql_type = Double)]
+    value: f64,
+}
+
+impl ReadingsDataset {
+    fn create_table(conn: &mut PgConnection) -> QueryResult<usize> {
+        diesel::sql_query(
+            "CREATE TEMP TABLE archive_test_readings (
+                id UUID PRIMARY KEY,
+                recorded_at TIMESTAMPTZ NOT NULL,
+                value DOUBLE PRECISION NOT NULL
+            ) ON COMMIT DROP",
+        )
+        .execute(conn)
+    }
+
+    fn insert(conn: &mut PgConnection, recorded_at: DateTime<Utc>, value: f64) -> QueryResult<usize> {
+        diesel::sql_query("INSERT INTO archive_test_readings (id, recorded_at, value) VALUES ($1, $2, $3)")
+            .bind::<SqlUuid, _>(Uuid::new_v4())
+            .bind::<Timestamptz, _>(recorded_at)
+            .bind::<Double, _>(value)
+            .execute(conn)
+    }
+
+    fn count(conn: &mut PgConnection) -> usize {
+        diesel::sql_query("SELECT id, recorded_at, value FROM archive_test_readings")
+            .load::<Reading>(conn)
+            .unwrap()
+            .len()
+    }
+}
+
+impl ArchiveDataset for ReadingsDataset {
+    fn name(&self) -> &'static str {
+        "test_readings"
+    }
+
+    fn extract(
+        &self,
+        conn: &mut PgConnection,
+        cutoff: DateTime<Utc>,
+        skip: &[TimeRange],
+        limit: i64,
+    ) -> QueryResult<Vec<ArchiveRecord>> {
+        let readings = diesel::sql_query(
+            "SELECT id, recorded_at, value FROM archive_test_readings WHERE recorded_at < $1 ORDER BY recorded_at",
+        )
+        .bind::<Timestamptz, _>(cutoff)
+        .load::<Reading>(conn)?;
+
+        Ok(readings
+            .into_iter()
+            .filter(|r| !skip.iter().any(|(start, end)| r.recorded_at >= *start && r.recorded_at <= *end))
+            .take(limit as usize)
+            .map(|r| ArchiveRecord {
+                id: r.id,
+                recorded_at: r.recorded_at,
+                payload: serde_json::json!({ "value": r.value }),
+            })
+            .collect())
+    }
+
+    fn purge(&self, conn: &mut PgConnection, ids: &[Uuid]) -> QueryResult<usize> {
+        diesel::sql_query("DELETE FROM archive_test_readings WHERE id = ANY($1)")
+            .bind::<diesel::sql_types::Array<SqlUuid>, _>(ids)
+            .execute(conn)
+    }
+
+    fn restore(&self, conn: &mut PgConnection, records: &[ArchiveRecord]) -> QueryResult<usize> {
+        let mut restored = 0;
+        for record in records {
+            restored += diesel::sql_query(
+                "INSERT INTO archive_test_readings (id, recorded_at, value) VALUES ($1, $2, $3)",
+            )
+            .bind::<SqlUuid, _>(record.id)
+            .bind::<Timestamptz, _>(record.recorded_at)
+            .bind::<Double, _>(record.payload["value"].as_f64().unwrap_or_default())
+            .execute(conn)?;
+        }
+        Ok(restored)
+    }
+}
+
+#[tokio::test]
+async fn test_archive_and_rehydrate() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            ReadingsDataset::create_table(conn).unwrap();
+            // Stubs from earlier runs would exempt these readings via their rehydrated ranges
+            diesel::delete(archives::table.filter(archives::dataset.eq("test_readings")))
+                .execute(conn)
+                .unwrap();
+            let now = Utc::now();
+            for days in [120, 110, 100] {
+                ReadingsDataset::insert(conn, now - Duration::days(days), days as f64).unwrap();
+            }
+            ReadingsDataset::insert(conn, now - Duration::days(1), 1.0).unwrap();
+
+            let archiver = Archiver::new(ObjectStorage::in_memory(), Duration::days(90))
+                .with_dataset(Arc::new(ReadingsDataset))
+                .with_batch_size(2);
+
+            // Old readings are moved out in batches, recent ones stay hot
+            let archives = archiver.run(conn).await?;
+            assert_eq!(archives.len(), 2);
+            assert_eq!(archives[0].record_count, 2);
+            assert_eq!(archives[1].record_count, 1);
+            assert!(archives[0].range_end <= archives[1].range_start);
+            assert_eq!(ReadingsDataset::count(conn), 1);
+
+            // The stub points at a readable Parquet file
+            let stub = ArchiveRepositoryImpl.find_by_id(conn, archives[0].id).await?;
+            let records = archiver.read(&stub).await?;
+            assert_eq!(records.len(), 2);
+            assert_eq!(records[0].payload["value"], 120.0);
+
+            // Rehydrating restores the rows and retires the stub
+            let rehydrated = archiver.rehydrate(conn, stub.id).await?;
+            assert!(rehydrated.is_rehydrated());
+            assert_eq!(ReadingsDataset::count(conn), 3);
+            let err = ArchiveRepositoryImpl.find_by_id(conn, stub.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            // Rehydrated rows are not archived again during the grace period
+            let archives = archiver.run(conn).await?;
+            assert!(archives.is_empty());
+            assert_eq!(ReadingsDataset::count(conn), 3);
+
+            Ok(())
+        })
+    }).await
+}
+
+#[actix_rt::test]
+async fn test_archives_are_reached_by_platform_admins() {
+    setup();
+    let mut config = app_config();
+    let (admin, platform_admin) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let platform_admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (admin, platform_admin)
+    };
+    config.auth.platform_admins.push(platform_admin.id);
+    let app = test::init_service(server::app(&config)).await;
+    let list = |as_user| test::TestRequest::get().uri("/v1/admin/archives").insert_header(as_user);
+
+    // Archives hold the records of every organization
+    let (status, body) = send(&app, list(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["details"]["code"], "PLATFORM_ADMIN_REQUIRED");
+    let rehydrate = format!("/v1/admin/archives/{}/rehydrate", Uuid::new_v4());
+    let (status, _) = send(&app, test::TestRequest::post().uri(&rehydrate).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(&app, list(bearer(&platform_admin, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,68 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+
+use crate::{
+    db::models::auth::Role,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_logins_are_recorded_with_the_client() {
+    setup();
+    let config = app_config();
+    let (user, admin, stranger) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let user = UserFactory::new().in_org(&organization).verified().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let stranger = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (user, admin, stranger)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let login = |password: &str| {
+        test::TestRequest::post()
+            .uri("/v1/auth/login")
+            .insert_header(("User-Agent", "FieldApp/2.1"))
+            .set_json(json!({ "email": user.email, "password": password }))
+    };
+
+    let (status, _) = send(&app, login("not-the-password")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = send(&app, login(UserFactory::PASSWORD)).await;
+    assert_eq!(status, StatusCode::OK);
+    let as_user = ("Authorization", format!("Bearer {}", body["access_token"].as_str().unwrap()));
+
+    // Newest first
+    let (status, body) = send(&app, test::TestRequest::get().uri("/v1/me/auth-events").insert_header(as_user.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["meta"]["total_items"], 2);
+    let events = body["data"].as_array().unwrap();
+    assert_eq!(events[0]["event"], "login_succeeded");
+    assert_eq!(events[0]["method"], "password");
+    assert_eq!(events[0]["user_agent"], "FieldApp/2.1");
+    assert_eq!(events[1]["event"], "login_failed");
+    assert_eq!(events[1]["email"], user.email.as_str());
+
+    // Only admins see other users' events
+    let uri = format!("/v1/users/{}/auth-events", user.id);
+    let (status, _) = send(&app, test::TestRequest::get().uri(&uri).insert_header(as_user)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let admin_bearer = bearer(&admin, &config);
+    let (status, body) = send(&app, test::TestRequest::get().uri(&uri).insert_header(admin_bearer.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["meta"]["total_items"], 2);
+
+    // Admins of other organizations don't find the user
+    let (status, _) = send(&app, test::TestRequest::get().uri(&uri).insert_header(bearer(&stranger, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let uri = format!("/v1/users/{}/auth-events", uuid::Uuid::new_v4());
+    let (status, _) = send(&app, test::TestRequest::get().uri(&uri).insert_header(admin_bearer)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
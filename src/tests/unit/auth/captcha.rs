@@ -0,0 +1,117 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+use wiremock::{
+    matchers::{body_string_contains, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use crate::{
+    server,
+    tests::{
+        common::helpers::{app_config, send},
+        factories::UserFactory,
+        setup,
+    },
+    utils::{CaptchaConfig, CaptchaProvider, Config},
+};
+
+/// A provider that accepts the token `solved` and nothing else
+async fn provider() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/siteverify"))
+        .and(body_string_contains("response=solved"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "success": true })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/siteverify"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "success": false, "error-codes": ["invalid-input-response"] })))
+        .with_priority(10)
+        .mount(&server)
+        .await;
+    server
+}
+
+fn config_with_captcha(server: &MockServer) -> Config {
+    let mut config = app_config();
+    config.auth.captcha = CaptchaConfig {
+        provider: Some(CaptchaProvider::Hcaptcha),
+        secret: Some("0x0000000000000000000000000000000000000000".to_string()),
+        verify_url: Some(format!("{}/siteverify", server.uri())),
+        ..CaptchaConfig::default()
+    };
+    config
+}
+
+#[actix_rt::test]
+async fn test_forgot_password_needs_a_solved_captcha() {
+    setup();
+    let server = provider().await;
+    let config = config_with_captcha(&server);
+    let app = test::init_service(server::app(&config)).await;
+    let forgot = |captcha_token: Option<&str>| {
+        test::TestRequest::post()
+            .uri("/v1/auth/forgot-password")
+            .set_json(json!({ "email": "nobody@example.com", "captcha_token": captcha_token }))
+    };
+
+    let (status, body) = send(&app, forgot(None)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "captcha_token");
+    assert_eq!(body["details"]["code"], "REQUIRED");
+
+    let (status, body) = send(&app, forgot(Some("guessed"))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["code"], "INVALID");
+
+    let (status, _) = send(&app, forgot(Some("solved"))).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+}
+
+#[actix_rt::test]
+async fn test_login_needs_a_captcha_after_repeated_failures() {
+    setup();
+    let server = provider().await;
+    let config = config_with_captcha(&server);
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let login = |password: &str, captcha_token: Option<&str>| {
+        test::TestRequest::post().uri("/v1/auth/login").set_json(json!({
+            "email": user.email,
+            "password": password,
+            "captcha_token": captcha_token,
+        }))
+    };
+
+    for _ in 0..config.auth.captcha.login_after_failures {
+        let (status, body) = send(&app, login("not-the-password", None)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["details"]["field"], "password");
+    }
+
+    let (status, body) = send(&app, login(UserFactory::PASSWORD, None)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "captcha_token");
+
+    let (status, body) = send(&app, login(UserFactory::PASSWORD, Some("solved"))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["access_token"].is_string());
+}
+
+#[actix_rt::test]
+async fn test_captcha_is_off_without_a_provider() {
+    setup();
+    let config = app_config();
+    assert!(!config.auth.captcha.enabled());
+    let app = test::init_service(server::app(&config)).await;
+
+    let request = test::TestRequest::post()
+        .uri("/v1/auth/forgot-password")
+        .set_json(json!({ "email": "nobody@example.com" }));
+    let (status, _) = send(&app, request).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+}
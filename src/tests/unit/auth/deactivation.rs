@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+
+use crate::{
+    db::{
+        models::auth::Role,
+        repositories::auth::{RefreshTokenRepository, RefreshTokenRepositoryImpl, SessionLifetime},
+    },
+    domain::TokenManager,
+    server,
+    tests::{
+        common::helpers::{app_config, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_deactivated_users_cannot_authenticate() {
+    setup();
+    let config = app_config();
+    let (admin, user, outsider, refresh_token) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let user = UserFactory::new().in_org(&organization).verified().create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let refresh_token = RefreshTokenRepositoryImpl.create_for_user(&mut conn, user.id, SessionLifetime::default()).await.unwrap();
+        (admin, user, outsider, refresh_token)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let bearer = |token: &str| ("Authorization", format!("Bearer {}", token));
+    let admin_token = TokenManager::generate_token(&admin, &config).unwrap();
+    let token = TokenManager::generate_token(&user, &config).unwrap();
+    let me = |token: &str| test::TestRequest::get().uri("/v1/me").insert_header(bearer(token));
+    let change = |action: &str, id: uuid::Uuid, token: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/v1/users/{}/{}", id, action))
+            .insert_header(bearer(token))
+    };
+    let login = || test::TestRequest::post().uri("/v1/auth/login").set_json(json!({ "email": user.email, "password": UserFactory::PASSWORD }));
+
+    // A token in use, and so cached, is still rejected once revoked
+    assert_eq!(send(&app, me(&token)).await.0, StatusCode::OK);
+    // Revocation covers tokens issued before the second it happens in
+    actix_rt::time::sleep(Duration::from_millis(1100)).await;
+
+    let (status, body) = send(&app, change("deactivate", user.id, &admin_token)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["is_active"], false);
+
+    assert_eq!(send(&app, me(&token)).await.0, StatusCode::UNAUTHORIZED);
+    let refresh = test::TestRequest::post()
+        .uri("/v1/auth/refresh")
+        .set_json(json!({ "refresh_token": refresh_token.token }));
+    assert_eq!(send(&app, refresh).await.0, StatusCode::UNAUTHORIZED);
+    let (status, body) = send(&app, login()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["details"]["code"], "DEACTIVATED");
+
+    // Deactivated users stay members, with their history
+    let (status, body) = send(&app, test::TestRequest::get()
+        .uri(&format!("/v1/users/{}/auth-events", user.id))
+        .insert_header(bearer(&admin_token))).await;
+    assert_eq!(status, StatusCode::OK);
+    let events: Vec<&str> = body["data"].as_array().unwrap().iter().map(|event| event["event"].as_str().unwrap()).collect();
+    assert_eq!(events, ["login_failed", "deactivated"]);
+
+    // Admins can't lock themselves out, nor reach other organizations
+    assert_eq!(send(&app, change("deactivate", admin.id, &admin_token)).await.0, StatusCode::FORBIDDEN);
+    let outsider_token = TokenManager::generate_token(&outsider, &config).unwrap();
+    assert_eq!(send(&app, change("reactivate", user.id, &outsider_token)).await.0, StatusCode::NOT_FOUND);
+    assert_eq!(send(&app, change("reactivate", user.id, &token)).await.0, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = send(&app, change("reactivate", user.id, &admin_token)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["is_active"], true);
+    assert_eq!(send(&app, login()).await.0, StatusCode::OK);
+    // Tokens from before stay revoked
+    assert_eq!(send(&app, me(&token)).await.0, StatusCode::UNAUTHORIZED);
+}
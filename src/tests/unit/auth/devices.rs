@@ -0,0 +1,99 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::{json, Value};
+
+use crate::{
+    server,
+    tests::{
+        common::helpers::{app_config, send},
+        factories::UserFactory,
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_devices_are_registered_at_login_and_revoked() {
+    setup();
+    let config = app_config();
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let login = |device_id: &str, device_name: &str| {
+        test::TestRequest::post().uri("/v1/auth/login").set_json(json!({
+            "email": user.email,
+            "password": UserFactory::PASSWORD,
+            "device_id": device_id,
+            "device_name": device_name,
+        }))
+    };
+    let refresh = |token: &Value| test::TestRequest::post().uri("/v1/auth/refresh").set_json(json!({ "refresh_token": token }));
+
+    let (status, phone) = send(&app, login("phone-1", "Field phone")).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, laptop) = send(&app, login("laptop-1", "Office laptop")).await;
+    let bearer = ("Authorization", format!("Bearer {}", laptop["access_token"].as_str().unwrap()));
+
+    let (status, devices) = send(&app, test::TestRequest::get().uri("/v1/auth/devices").insert_header(bearer.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    let devices = devices["data"].as_array().unwrap().clone();
+    assert_eq!(devices.len(), 2);
+    assert_eq!(devices[0]["device_id"], "laptop-1");
+    assert_eq!(devices[1]["name"], "Field phone");
+
+    // Refreshing on one device leaves the other signed in
+    let (status, phone) = send(&app, refresh(&phone["refresh_token"])).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, laptop) = send(&app, refresh(&laptop["refresh_token"])).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let phone_id = devices[1]["id"].as_str().unwrap();
+    let revoke = |id: &str| {
+        test::TestRequest::delete().uri(&format!("/v1/auth/devices/{}", id)).insert_header(bearer.clone())
+    };
+    assert_eq!(send(&app, revoke(phone_id)).await.0, StatusCode::NO_CONTENT);
+    assert_eq!(send(&app, revoke(phone_id)).await.0, StatusCode::NOT_FOUND);
+
+    assert_eq!(send(&app, refresh(&phone["refresh_token"])).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&app, refresh(&laptop["refresh_token"])).await.0, StatusCode::OK);
+}
+
+#[actix_rt::test]
+async fn test_devices_of_other_users_cannot_be_revoked() {
+    setup();
+    let config = app_config();
+    let (owner, other) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        (
+            UserFactory::new().verified().create(&mut conn).await.unwrap(),
+            UserFactory::new().verified().create(&mut conn).await.unwrap(),
+        )
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let login = |email: &str| {
+        test::TestRequest::post().uri("/v1/auth/login").set_json(json!({
+            "email": email,
+            "password": UserFactory::PASSWORD,
+            "device_id": "shared-id",
+        }))
+    };
+
+    let (_, owner_login) = send(&app, login(&owner.email)).await;
+    let (_, other_login) = send(&app, login(&other.email)).await;
+    let bearer = |login: &Value| ("Authorization", format!("Bearer {}", login["access_token"].as_str().unwrap()));
+
+    let (_, devices) = send(&app, test::TestRequest::get().uri("/v1/auth/devices").insert_header(bearer(&owner_login))).await;
+    let device_id = devices["data"][0]["id"].as_str().unwrap().to_string();
+
+    let revoke = test::TestRequest::delete()
+        .uri(&format!("/v1/auth/devices/{}", device_id))
+        .insert_header(bearer(&other_login));
+    assert_eq!(send(&app, revoke).await.0, StatusCode::NOT_FOUND);
+
+    let blank = test::TestRequest::post().uri("/v1/auth/login").set_json(json!({
+        "email": owner.email,
+        "password": UserFactory::PASSWORD,
+        "device_id": " ",
+    }));
+    assert_eq!(send(&app, blank).await.0, StatusCode::BAD_REQUEST);
+}
@@ -1,15 +1,14 @@
 use chrono::Utc;
-use uuid::Uuid;
 use diesel::prelude::*;
 use tracing::info;
 use crate::{
     db::{
-        models::{auth::{Role, User}, Organization},
-        repositories::{auth::UserRepositoryImpl, OrganizationRepositoryImpl, Repository},
+        models::auth::{Role, User},
+        repositories::{auth::UserRepositoryImpl, Repository},
         schema::{users, organizations},
     },
     error::{Result, ApiError},
-    tests::{common::helpers::TestDb, setup},
+    tests::{common::helpers::TestDb, factories::{OrganizationFactory, UserFactory}, setup},
     api::utils::PaginationParams,
 };
 
@@ -19,34 +18,8 @@ async fn test_user_crud() -> Result<()> {
     TestDb::run_test(|conn| {
         Box::pin(async move {
             // First create an organization since users need an org_id
-            let org = Organization {
-                id: Uuid::new_v4(),
-                name: format!("Test Org {}", Uuid::new_v4()),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-            };
-            
-            let org_repo = OrganizationRepositoryImpl;
-            let created_org = org_repo.create(conn, &org).await?;
-
-            // Create a test user with unique email and phone
-            let uuid = Uuid::new_v4();
-            let password = User::hash_password("test_password")?;
-            let user = User {
-                id: Uuid::new_v4(),
-                first_name: "Test".to_string(),
-                last_name: "User".to_string(),
-                email: format!("test{}@example.com", uuid),
-                phone_number: format!("1234567890{}", uuid.simple()),
-                password,
-                org_id: created_org.id,
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-                role: Role::Operator,
-                email_verified: false,
-            };
+            let created_org = OrganizationFactory::new().create(conn).await?;
+            let user = UserFactory::new().in_org(&created_org).build();
 
             let repo = UserRepositoryImpl;
 
@@ -95,14 +68,7 @@ async fn test_user_pagination() -> Result<()> {
     setup();
     TestDb::run_test(|conn| {
         Box::pin(async move {
-            // Set transaction isolation level to serializable
-            diesel::sql_query("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
-                .execute(conn)
-                .map_err(|e| ApiError::database_error("Failed to set transaction isolation level", Some(serde_json::json!({
-                    "error": e.to_string()
-                }))))?;
-
-            // Clean up any existing data first
+            // Only needed with TEST_DATABASE=shared; isolated databases start empty
             let users_deleted = diesel::delete(users::table)
                 .execute(conn)
                 .map_err(|e| ApiError::database_error("Failed to clean up users", Some(serde_json::json!({
@@ -127,15 +93,7 @@ async fn test_user_pagination() -> Result<()> {
             assert_eq!(initial_user_count, 0, "Should start with 0 users");
 
             // Create an organization for the test users
-            let org = Organization {
-                id: Uuid::new_v4(),
-                name: format!("Test Org {}", Uuid::new_v4()),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-            };
-            let org_repo = OrganizationRepositoryImpl;
-            let created_org = org_repo.create(conn, &org).await?;
+            let created_org = OrganizationFactory::new().create(conn).await?;
 
             // Create multiple test users
             let repo = UserRepositoryImpl;
@@ -144,25 +102,13 @@ async fn test_user_pagination() -> Result<()> {
             // Create exactly 15 users with sequential creation times
             let base_time = Utc::now();
             for i in 0..15 {
-                let uuid = Uuid::new_v4();
                 // Create users with different timestamps, spaced 1 second apart
-                let now = base_time + chrono::Duration::seconds(i);
-                let password = User::hash_password("test_password")?;
-                let user = User {
-                    id: Uuid::new_v4(),
-                    first_name: format!("Test{}", i),
-                    last_name: "User".to_string(),
-                    email: format!("test{}@example.com", uuid),
-                    phone_number: format!("1234567890{}", uuid.simple()),
-                    password,
-                    org_id: created_org.id,
-                    created_at: now,
-                    updated_at: now,
-                    deleted_at: None,
-                    role: Role::Operator,
-                    email_verified: false,
-                };
-                let created_user = repo.create(conn, &user).await?;
+                let created_user = UserFactory::new()
+                    .first_name(format!("Test{}", i))
+                    .in_org(&created_org)
+                    .created_at(base_time + chrono::Duration::seconds(i))
+                    .create(conn)
+                    .await?;
                 info!("Created user {} with email {} at {}", created_user.id, created_user.email, created_user.created_at);
                 created_users.push(created_user);
 
@@ -175,7 +121,7 @@ async fn test_user_pagination() -> Result<()> {
                         "error": e.to_string()
                     }))))?;
                 info!("Current user count after creating user {}: {}", i, current_count);
-                assert_eq!(current_count, i as i64 + 1, "User count should match number of created users");
+                assert_eq!(current_count, i + 1, "User count should match number of created users");
             }
             info!("Created {} test users", created_users.len());
 
@@ -0,0 +1,144 @@
+use actix_web::{http::StatusCode, test};
+use diesel::prelude::*;
+use serde_json::json;
+
+use crate::{
+    db::{
+        models::{auth::{EmailChangeToken, User}, QueuedJob},
+        schema::{auth_events, email_change_tokens, queued_jobs, users},
+    },
+    domain::TokenManager,
+    infrastructure::email::EmailMessage,
+    jobs::email::EMAIL_JOB,
+    server,
+    tests::{common::helpers::{app_config, bearer}, factories::UserFactory, setup},
+    utils::crypto::hash_token,
+};
+use super::magic_link::mailed_token;
+
+/// The emails queued for `to`
+fn queued_for(conn: &mut PgConnection, to: &str) -> Vec<EmailMessage> {
+    queued_jobs::table
+        .filter(queued_jobs::kind.eq(EMAIL_JOB))
+        .load::<QueuedJob>(conn)
+        .unwrap()
+        .into_iter()
+        .filter_map(|job| serde_json::from_value(job.payload).ok())
+        .filter(|message: &EmailMessage| message.to == to)
+        .collect()
+}
+
+#[actix_rt::test]
+async fn test_email_change_is_confirmed_from_the_new_address() {
+    setup();
+    let config = app_config();
+    let (user, other) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        (
+            UserFactory::new().verified().create(&mut conn).await.unwrap(),
+            UserFactory::new().create(&mut conn).await.unwrap(),
+        )
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let access_token = TokenManager::generate_token(&user, &config).unwrap();
+    let new_email = format!("renamed-{}", user.email);
+    let request_change = |email: &str| {
+        test::TestRequest::post()
+            .uri("/v1/me/email-change")
+            .insert_header(("Authorization", format!("Bearer {}", access_token)))
+            .set_json(json!({ "email": email }))
+            .to_request()
+    };
+
+    assert_eq!(test::call_service(&app, request_change(&user.email)).await.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(test::call_service(&app, request_change("not an email")).await.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(test::call_service(&app, request_change(&other.email)).await.status(), StatusCode::CONFLICT);
+    assert_eq!(test::call_service(&app, request_change(&new_email)).await.status(), StatusCode::ACCEPTED);
+
+    let (change, token) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let change = email_change_tokens::table
+            .filter(email_change_tokens::user_id.eq(user.id))
+            .select(EmailChangeToken::as_select())
+            .first(&mut conn)
+            .expect("A confirmation link was sent");
+
+        // The link goes to the new address, a notice to the current one
+        let confirmation = queued_for(&mut conn, &new_email);
+        assert_eq!(confirmation.len(), 1);
+        let token = mailed_token(&mut conn, &new_email);
+        let notice = queued_for(&mut conn, &user.email);
+        assert_eq!(notice.len(), 1);
+        assert!(notice[0].text.contains(&new_email));
+        assert!(!notice[0].text.contains(&token));
+
+        // The current address stays in use until the change is confirmed
+        let unchanged: User = users::table.find(user.id).select(User::as_select()).first(&mut conn).unwrap();
+        assert_eq!(unchanged.email, user.email);
+        (change, token)
+    };
+    assert_eq!(change.new_email, new_email);
+    // Only the hash is stored
+    assert_eq!(change.token_hash, hash_token(&token));
+
+    let confirm = || {
+        test::TestRequest::post()
+            .uri("/v1/auth/confirm-email-change")
+            .set_json(json!({ "token": token }))
+            .to_request()
+    };
+    let response = test::call_service(&app, confirm()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["email"], new_email);
+
+    // Used up
+    assert_eq!(test::call_service(&app, confirm()).await.status(), StatusCode::BAD_REQUEST);
+
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let changed: User = users::table.find(user.id).select(User::as_select()).first(&mut conn).unwrap();
+    assert_eq!(changed.email, new_email);
+    assert!(changed.email_verified);
+    let events: Vec<String> = auth_events::table
+        .filter(auth_events::user_id.eq(user.id))
+        .select(auth_events::event)
+        .load(&mut conn)
+        .unwrap();
+    assert_eq!(events, ["email_changed"]);
+}
+
+#[actix_rt::test]
+async fn test_email_change_fails_when_the_address_is_taken_before_confirming() {
+    setup();
+    let config = app_config();
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let new_email = format!("taken-{}", user.email);
+
+    let request = test::TestRequest::post()
+        .uri("/v1/me/email-change")
+        .insert_header(bearer(&user, &config))
+        .set_json(json!({ "email": new_email }))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::ACCEPTED);
+
+    let token = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().email(new_email.clone()).create(&mut conn).await.unwrap();
+        mailed_token(&mut conn, &new_email)
+    };
+
+    let request = test::TestRequest::post()
+        .uri("/v1/auth/confirm-email-change")
+        .set_json(json!({ "token": token }))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::CONFLICT);
+
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let unchanged: User = users::table.find(user.id).select(User::as_select()).first(&mut conn).unwrap();
+    assert_eq!(unchanged.email, user.email);
+    assert!(!unchanged.email_verified);
+}
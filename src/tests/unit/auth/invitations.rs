@@ -0,0 +1,216 @@
+use actix_web::{http::StatusCode, test};
+use diesel::prelude::*;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{
+            auth::{Role, User},
+            OrgInvitation,
+        },
+        schema::{org_invitations, users},
+    },
+    domain::invitation,
+    server,
+    tests::{common::helpers::{app_config, bearer, send}, factories::{OrganizationFactory, UserFactory}, setup},
+};
+
+fn registration(email: &str, invite_token: Option<&str>) -> test::TestRequest {
+    let phone_number: String = Uuid::new_v4().as_u128().to_string().chars().take(12).collect();
+    test::TestRequest::post().uri("/v1/auth/register").set_json(json!({
+        "first_name": "Ingrid",
+        "last_name": "Larsen",
+        "email": email,
+        "phone_number": phone_number,
+        "password": "s3cure-password",
+        "invite_token": invite_token,
+    }))
+}
+
+#[actix_rt::test]
+async fn test_invited_user_registers_into_the_organization() {
+    setup();
+    let config = app_config();
+    let manager = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Manager).verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let as_manager = bearer(&manager, &config);
+    let email = format!("invitee-{}@example.com", Uuid::new_v4());
+    let invite = |role: &str| {
+        test::TestRequest::post()
+            .uri("/v1/invitations")
+            .insert_header(as_manager.clone())
+            .set_json(json!({ "email": email.to_uppercase(), "role": role }))
+    };
+
+    // Managers can't hand out the admin role
+    let (status, _) = send(&app, invite("Admin")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = send(&app, invite("Operator")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["email"], email.as_str());
+    assert_eq!(body["org_id"], manager.org_id.to_string());
+
+    let token = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let invitation: OrgInvitation = org_invitations::table
+            .find(Uuid::parse_str(body["id"].as_str().unwrap()).unwrap())
+            .select(OrgInvitation::as_select())
+            .first(&mut conn)
+            .unwrap();
+        invitation::issue_token(&invitation, &config).unwrap()
+    };
+
+    let (status, _) = send(&app, registration("someone.else@example.com", Some(&token))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, registration(&email, Some("forged"))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(&app, registration(&email, Some(&token))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["org_id"], manager.org_id.to_string());
+    assert_eq!(body["role"], "Operator");
+
+    let user: User = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        users::table
+            .filter(users::email.eq(&email))
+            .select(User::as_select())
+            .first(&mut conn)
+            .unwrap()
+    };
+    assert!(user.email_verified);
+
+    // Used up
+    let (status, _) = send(&app, registration(&format!("again-{}", email), Some(&token))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_rt::test]
+async fn test_reinviting_revokes_the_earlier_invitation() {
+    setup();
+    let config = app_config();
+    let admin = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let as_admin = bearer(&admin, &config);
+    let email = format!("invitee-{}@example.com", Uuid::new_v4());
+    let invite = || {
+        test::TestRequest::post()
+            .uri("/v1/invitations")
+            .insert_header(as_admin.clone())
+            .set_json(json!({ "email": email, "role": "Admin" }))
+    };
+
+    let (status, _) = send(&app, invite()).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = send(&app, invite()).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let invitations: Vec<OrgInvitation> = org_invitations::table
+        .filter(org_invitations::email.eq(&email))
+        .order_by(org_invitations::created_at.asc())
+        .select(OrgInvitation::as_select())
+        .load(&mut conn)
+        .unwrap();
+    assert_eq!(invitations.len(), 2);
+    assert!(invitations[0].deleted_at.is_some());
+    assert!(invitations[1].deleted_at.is_none());
+
+    let stale = invitation::issue_token(&invitations[0], &config).unwrap();
+    drop(conn);
+    let (status, _) = send(&app, registration(&email, Some(&stale))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_rt::test]
+async fn test_registration_needs_an_organization_or_invitation() {
+    setup();
+    let config = app_config();
+    let app = test::init_service(server::app(&config)).await;
+
+    let (status, body) = send(&app, registration("nobody@example.com", None)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "org_id");
+}
+
+#[actix_rt::test]
+async fn test_pending_invitations_are_listed_resent_and_revoked() {
+    setup();
+    let config = app_config();
+    let (admin, manager) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let manager = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        (admin, manager)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let as_admin = bearer(&admin, &config);
+    let as_manager = bearer(&manager, &config);
+    let email = format!("invitee-{}@example.com", Uuid::new_v4());
+    let invite = |days: i64, role: &str| {
+        test::TestRequest::post()
+            .uri("/v1/invitations")
+            .insert_header(as_admin.clone())
+            .set_json(json!({ "email": email, "role": role, "expires_in_days": days }))
+    };
+
+    let (status, body) = send(&app, invite(31, "Operator")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["code"], "OUT_OF_RANGE");
+    let (status, body) = send(&app, invite(14, "Admin")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let id = body["id"].as_str().unwrap().to_string();
+
+    let (status, body) = send(&app, test::TestRequest::get().uri("/v1/invitations").insert_header(as_manager.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    let listed = body["data"].as_array().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["id"], id.as_str());
+
+    // Managers can't touch invitations of admins
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&format!("/v1/invitations/{}", id)).insert_header(as_manager.clone())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Resending renews an expired invitation
+    {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        diesel::update(org_invitations::table.find(Uuid::parse_str(&id).unwrap()))
+            .set(org_invitations::expires_at.eq(chrono::Utc::now() - chrono::Duration::days(1)))
+            .execute(&mut conn)
+            .unwrap();
+    }
+    let resend = || test::TestRequest::post().uri(&format!("/v1/invitations/{}/resend", id)).insert_header(as_admin.clone());
+    let (status, body) = send(&app, resend()).await;
+    assert_eq!(status, StatusCode::OK);
+    let expires_at: chrono::DateTime<chrono::Utc> = serde_json::from_value(body["expires_at"].clone()).unwrap();
+    assert!(expires_at > chrono::Utc::now() + chrono::Duration::days(6));
+
+    let invitation: OrgInvitation = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        org_invitations::table.find(Uuid::parse_str(&id).unwrap()).select(OrgInvitation::as_select()).first(&mut conn).unwrap()
+    };
+    let token = invitation::issue_token(&invitation, &config).unwrap();
+
+    let revoke = || test::TestRequest::delete().uri(&format!("/v1/invitations/{}", id)).insert_header(as_admin.clone());
+    let (status, _) = send(&app, revoke()).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, revoke()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, resend()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = send(&app, test::TestRequest::get().uri("/v1/invitations").insert_header(as_admin.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"].as_array().unwrap().is_empty());
+    let (status, _) = send(&app, registration(&email, Some(&token))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
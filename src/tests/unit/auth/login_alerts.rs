@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use actix_web::{http::StatusCode, test};
+use diesel::prelude::*;
+use serde_json::{json, Value};
+
+use crate::{
+    db::{
+        models::{auth::Role, Notification, QueuedJob},
+        schema::{auth_events, notifications, queued_jobs},
+    },
+    domain::auth::LOGIN_ALERT_KIND,
+    infrastructure::email::EmailMessage,
+    jobs::email::EMAIL_JOB,
+    server,
+    tests::{
+        common::helpers::{app_config, send},
+        factories::UserFactory,
+        setup,
+    },
+};
+
+/// A password login by `email` from `country` with the user agent `agent`,
+/// on `device_id` if given
+fn login(email: &str, country: &str, agent: &str, device_id: Option<&str>) -> test::TestRequest {
+    test::TestRequest::post()
+        .uri("/v1/auth/login")
+        .insert_header(("CF-IPCountry", country))
+        .insert_header(("User-Agent", agent))
+        .set_json(json!({ "email": email, "password": UserFactory::PASSWORD, "device_id": device_id }))
+}
+
+fn alerts(conn: &mut PgConnection, user_id: uuid::Uuid) -> Vec<Notification> {
+    notifications::table
+        .filter(notifications::user_id.eq(user_id))
+        .filter(notifications::kind.eq(LOGIN_ALERT_KIND))
+        .order(notifications::created_at.asc())
+        .select(Notification::as_select())
+        .load(conn)
+        .unwrap()
+}
+
+#[actix_rt::test]
+async fn test_logins_from_somewhere_new_are_alerted() {
+    setup();
+    let config = app_config();
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+
+    // The first login, and another like it, raise nothing
+    for _ in 0..2 {
+        let (status, _) = send(&app, login(&user.email, "ca", "Field tablet", None)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    assert!(alerts(&mut conn, user.id).is_empty());
+
+    // Another country
+    let (status, _) = send(&app, login(&user.email, "US", "Field tablet", None)).await;
+    assert_eq!(status, StatusCode::OK);
+    let raised = alerts(&mut conn, user.id);
+    assert_eq!(raised.len(), 1);
+    assert_eq!(raised[0].data.as_ref().unwrap()["new_location"], true);
+    assert_eq!(raised[0].data.as_ref().unwrap()["new_device"], false);
+    assert!(raised[0].link.as_deref().unwrap().contains("/secure-account?token="));
+
+    let mails: Vec<EmailMessage> = queued_jobs::table
+        .filter(queued_jobs::kind.eq(EMAIL_JOB))
+        .load::<QueuedJob>(&mut conn)
+        .unwrap()
+        .into_iter()
+        .filter_map(|job| serde_json::from_value(job.payload).ok())
+        .filter(|message: &EmailMessage| message.to == user.email)
+        .collect();
+    assert_eq!(mails.len(), 1);
+    assert!(mails[0].text.contains("Where: US"));
+    assert!(mails[0].text.contains(raised[0].link.as_deref().unwrap()));
+
+    // A country seen before, on a device not seen before
+    let (status, _) = send(&app, login(&user.email, "CA", "Field tablet", Some("truck-7"))).await;
+    assert_eq!(status, StatusCode::OK);
+    let raised = alerts(&mut conn, user.id);
+    assert_eq!(raised.len(), 2);
+    assert_eq!(raised[1].data.as_ref().unwrap()["new_location"], false);
+    assert_eq!(raised[1].data.as_ref().unwrap()["new_device"], true);
+
+    // Logins are recorded where they came from
+    let countries: Vec<Option<String>> = auth_events::table
+        .filter(auth_events::user_id.eq(user.id))
+        .filter(auth_events::event.eq("login_succeeded"))
+        .order(auth_events::created_at.asc())
+        .select(auth_events::country)
+        .load(&mut conn)
+        .unwrap();
+    assert_eq!(countries, [Some("CA".to_string()), Some("CA".to_string()), Some("US".to_string()), Some("CA".to_string())]);
+}
+
+#[actix_rt::test]
+async fn test_this_wasnt_me_signs_out_everywhere() {
+    setup();
+    let config = app_config();
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Manager).verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+
+    let (_, mine) = send(&app, login(&user.email, "CA", "Office", None)).await;
+    let (_, theirs) = send(&app, login(&user.email, "RU", "Office", None)).await;
+    let link = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        alerts(&mut conn, user.id).pop().expect("The login was alerted").link.unwrap()
+    };
+    let token = link.split("token=").nth(1).unwrap().to_string();
+    let tags = |body: &Value| {
+        test::TestRequest::get()
+            .uri("/v1/tags")
+            .insert_header(("Authorization", format!("Bearer {}", body["access_token"].as_str().unwrap())))
+    };
+    assert_eq!(send(&app, tags(&theirs)).await.0, StatusCode::OK);
+
+    // Tokens issued in the same second as the revocation stay valid
+    actix_rt::time::sleep(Duration::from_millis(1100)).await;
+
+    let secure = |token: &str| {
+        test::TestRequest::post().uri("/v1/auth/secure-account").set_json(json!({ "token": token }))
+    };
+    assert_eq!(send(&app, secure("not-a-token")).await.0, StatusCode::BAD_REQUEST);
+    assert_eq!(send(&app, secure(theirs["access_token"].as_str().unwrap())).await.0, StatusCode::BAD_REQUEST);
+    assert_eq!(send(&app, secure(&token)).await.0, StatusCode::OK);
+
+    for session in [&mine, &theirs] {
+        assert_eq!(send(&app, tags(session)).await.0, StatusCode::UNAUTHORIZED);
+        let refresh = test::TestRequest::post()
+            .uri("/v1/auth/refresh")
+            .set_json(json!({ "refresh_token": session["refresh_token"] }));
+        assert_eq!(send(&app, refresh).await.0, StatusCode::UNAUTHORIZED);
+    }
+
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let revoked: i64 = auth_events::table
+        .filter(auth_events::user_id.eq(user.id))
+        .filter(auth_events::event.eq("sessions_revoked"))
+        .count()
+        .get_result(&mut conn)
+        .unwrap();
+    assert_eq!(revoked, 1);
+}
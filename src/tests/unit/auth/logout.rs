@@ -0,0 +1,73 @@
+use actix_web::{
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test,
+};
+
+use crate::{
+    db::{
+        models::auth::Role,
+        repositories::auth::{RefreshTokenRepository, RefreshTokenRepositoryImpl, SessionLifetime},
+    },
+    domain::{auth::RevocationList, TokenManager},
+    server,
+    tests::{common::helpers::app_config, factories::UserFactory, setup},
+    utils::Config,
+};
+
+/// Status of the response to `request`, including errors from middleware
+async fn status<S, B>(app: &S, request: test::TestRequest) -> StatusCode
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+{
+    match test::try_call_service(app, request.to_request()).await {
+        Ok(response) => response.status(),
+        Err(error) => error.error_response().status(),
+    }
+}
+
+#[actix_rt::test]
+async fn test_logout_revokes_both_tokens() {
+    setup();
+    let config = app_config();
+    let (user, refresh_token) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let user = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let refresh_token = RefreshTokenRepositoryImpl.create_for_user(&mut conn, user.id, SessionLifetime::default()).await.unwrap();
+        (user, refresh_token)
+    };
+    let token = TokenManager::generate_token(&user, &config).unwrap();
+    let other = TokenManager::generate_token(&user, &config).unwrap();
+    let app = test::init_service(server::app(&config)).await;
+    let bearer = |token: &str| ("Authorization", format!("Bearer {}", token));
+
+    let logout = test::TestRequest::post()
+        .uri("/v1/auth/logout")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "refresh_token": refresh_token.token }));
+    assert_eq!(status(&app, logout).await, StatusCode::NO_CONTENT);
+
+    // The access token is refused, other sessions of the user are not
+    let tags = |token: &str| test::TestRequest::get().uri("/v1/tags").insert_header(bearer(token));
+    assert_eq!(status(&app, tags(&token)).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(status(&app, tags(&other)).await, StatusCode::OK);
+
+    let refresh = test::TestRequest::post()
+        .uri("/v1/auth/refresh")
+        .set_json(serde_json::json!({ "refresh_token": refresh_token.token }));
+    assert_eq!(status(&app, refresh).await, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+async fn test_revocation_lasts_until_expiry() {
+    setup();
+    let config = Config::offline();
+    let now = chrono::Utc::now().timestamp();
+
+    RevocationList::revoke(&config, "live", now + 60).await;
+    RevocationList::revoke(&config, "expired", now - 1).await;
+
+    assert!(RevocationList::is_revoked(&config, "live").await);
+    assert!(!RevocationList::is_revoked(&config, "expired").await);
+    assert!(!RevocationList::is_revoked(&config, "never-revoked").await);
+}
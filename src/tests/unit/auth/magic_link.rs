@@ -0,0 +1,98 @@
+use actix_web::{http::StatusCode, test};
+use diesel::prelude::*;
+
+use crate::{
+    db::{
+        models::{auth::MagicLinkToken, QueuedJob},
+        schema::{magic_link_tokens, queued_jobs},
+    },
+    infrastructure::email::EmailMessage,
+    jobs::email::EMAIL_JOB,
+    server,
+    tests::{common::helpers::app_config, factories::UserFactory, setup},
+    utils::crypto::hash_token,
+};
+
+/// The token in the last link mailed to `to`
+pub(super) fn mailed_token(conn: &mut PgConnection, to: &str) -> String {
+    let message = queued_jobs::table
+        .filter(queued_jobs::kind.eq(EMAIL_JOB))
+        .order_by(queued_jobs::created_at.desc())
+        .load::<QueuedJob>(conn)
+        .unwrap()
+        .into_iter()
+        .filter_map(|job| serde_json::from_value::<EmailMessage>(job.payload).ok())
+        .find(|message| message.to == to)
+        .expect("A link was mailed");
+    let (_, token) = message.text.split_once("token=").expect("The mail holds a link");
+    token.split_whitespace().next().unwrap().to_string()
+}
+
+#[actix_rt::test]
+async fn test_magic_link_logs_in_on_the_requesting_device() {
+    setup();
+    let config = app_config();
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().create(&mut conn).await.unwrap()
+    };
+    assert!(!user.email_verified);
+    let app = test::init_service(server::app(&config)).await;
+
+    let request = test::TestRequest::post()
+        .uri("/v1/auth/magic-link")
+        .set_json(serde_json::json!({ "email": user.email, "device_id": "phone" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::ACCEPTED);
+
+    let (link, token) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let link: MagicLinkToken = magic_link_tokens::table
+            .filter(magic_link_tokens::user_id.eq(user.id))
+            .select(MagicLinkToken::as_select())
+            .first(&mut conn)
+            .expect("A link was sent");
+        (link, mailed_token(&mut conn, &user.email))
+    };
+    assert_eq!(link.device_id.as_deref(), Some("phone"));
+    // Only the hash is stored
+    assert_eq!(link.token_hash, hash_token(&token));
+
+    let redeem = |device_id: &str| {
+        test::TestRequest::post()
+            .uri("/v1/auth/magic-link/redeem")
+            .set_json(serde_json::json!({ "token": token, "device_id": device_id }))
+            .to_request()
+    };
+    assert_eq!(test::call_service(&app, redeem("laptop")).await.status(), StatusCode::UNAUTHORIZED);
+
+    let response = test::call_service(&app, redeem("phone")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert!(body["access_token"].is_string());
+    assert_eq!(body["user"]["id"], user.id.to_string());
+
+    // Used up
+    assert_eq!(test::call_service(&app, redeem("phone")).await.status(), StatusCode::UNAUTHORIZED);
+
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let verified: bool = crate::db::schema::users::table
+        .find(user.id)
+        .select(crate::db::schema::users::email_verified)
+        .first(&mut conn)
+        .unwrap();
+    assert!(verified);
+}
+
+#[actix_rt::test]
+async fn test_magic_link_for_unknown_email_is_accepted() {
+    setup();
+    let config = app_config();
+    let app = test::init_service(server::app(&config)).await;
+
+    let request = test::TestRequest::post()
+        .uri("/v1/auth/magic-link")
+        .set_json(serde_json::json!({ "email": "nobody@example.com" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::ACCEPTED);
+}
@@ -1,2 +1,21 @@
 pub mod domain;
 pub mod repository;
+pub mod sso;
+pub mod permissions;
+pub mod keys;
+pub mod logout;
+pub mod passwords;
+pub mod magic_link;
+pub mod devices;
+pub mod audit;
+pub mod invitations;
+pub mod captcha;
+pub mod passkeys;
+pub mod roles;
+pub mod token_cache;
+pub mod sessions;
+pub mod email_change;
+pub mod login_alerts;
+pub mod profile;
+pub mod deactivation;
+pub mod supervisors;
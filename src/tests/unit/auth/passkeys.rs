@@ -0,0 +1,282 @@
+use actix_web::{http::StatusCode, test};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ring::{
+    digest::{digest, SHA256},
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING},
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    db::models::auth::Role,
+    server,
+    tests::{common::helpers::{app_config, bearer, send}, factories::UserFactory, setup},
+};
+
+const ORIGIN: &str = "http://localhost:3000";
+
+// Just enough CBOR to play an authenticator
+fn cbor_head(major: u8, value: u64) -> Vec<u8> {
+    match value {
+        0..=23 => vec![major << 5 | value as u8],
+        24..=255 => vec![major << 5 | 24, value as u8],
+        _ => [vec![major << 5 | 25], (value as u16).to_be_bytes().to_vec()].concat(),
+    }
+}
+
+fn cbor_int(value: i64) -> Vec<u8> {
+    if value >= 0 {
+        cbor_head(0, value as u64)
+    } else {
+        cbor_head(1, (-1 - value) as u64)
+    }
+}
+
+fn cbor_bytes(bytes: &[u8]) -> Vec<u8> {
+    [cbor_head(2, bytes.len() as u64), bytes.to_vec()].concat()
+}
+
+fn cbor_text(text: &str) -> Vec<u8> {
+    [cbor_head(3, text.len() as u64), text.as_bytes().to_vec()].concat()
+}
+
+fn cbor_map(entries: Vec<(Vec<u8>, Vec<u8>)>) -> Vec<u8> {
+    let mut map = cbor_head(5, entries.len() as u64);
+    for (key, value) in entries {
+        map.extend(key);
+        map.extend(value);
+    }
+    map
+}
+
+/// A platform authenticator holding one ES256 passkey for localhost
+struct Authenticator {
+    key: EcdsaKeyPair,
+    credential_id: Vec<u8>,
+    sign_count: u32,
+}
+
+impl Authenticator {
+    fn new() -> Self {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        Self {
+            key: EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng).unwrap(),
+            credential_id: Uuid::new_v4().as_bytes().to_vec(),
+            sign_count: 0,
+        }
+    }
+
+    fn credential_id(&self) -> String {
+        URL_SAFE_NO_PAD.encode(&self.credential_id)
+    }
+
+    fn authenticator_data(&self, flags: u8) -> Vec<u8> {
+        [digest(&SHA256, b"localhost").as_ref(), &[flags], &self.sign_count.to_be_bytes()].concat()
+    }
+
+    /// What `navigator.credentials.create()` returns for `challenge`
+    fn create(&self, challenge: &str, origin: &str) -> Value {
+        let point = self.key.public_key().as_ref();
+        let cose_key = cbor_map(vec![
+            (cbor_int(1), cbor_int(2)),
+            (cbor_int(3), cbor_int(-7)),
+            (cbor_int(-1), cbor_int(1)),
+            (cbor_int(-2), cbor_bytes(&point[1..33])),
+            (cbor_int(-3), cbor_bytes(&point[33..])),
+        ]);
+        let auth_data = [
+            self.authenticator_data(0x45),
+            vec![0; 16],
+            (self.credential_id.len() as u16).to_be_bytes().to_vec(),
+            self.credential_id.clone(),
+            cose_key,
+        ]
+        .concat();
+        let attestation_object = cbor_map(vec![
+            (cbor_text("fmt"), cbor_text("none")),
+            (cbor_text("attStmt"), cbor_map(vec![])),
+            (cbor_text("authData"), cbor_bytes(&auth_data)),
+        ]);
+        let client_data = json!({ "type": "webauthn.create", "challenge": challenge, "origin": origin });
+        json!({
+            "id": self.credential_id(),
+            "rawId": self.credential_id(),
+            "type": "public-key",
+            "response": {
+                "clientDataJSON": URL_SAFE_NO_PAD.encode(client_data.to_string()),
+                "attestationObject": URL_SAFE_NO_PAD.encode(attestation_object),
+            },
+        })
+    }
+
+    /// What `navigator.credentials.get()` returns for `challenge`, counting
+    /// the use
+    fn get(&mut self, challenge: &str, user_handle: &str) -> Value {
+        self.sign_count += 1;
+        let auth_data = self.authenticator_data(0x05);
+        let client_data = json!({ "type": "webauthn.get", "challenge": challenge, "origin": ORIGIN }).to_string();
+        let signed = [auth_data.as_slice(), digest(&SHA256, client_data.as_bytes()).as_ref()].concat();
+        let signature = self.key.sign(&SystemRandom::new(), &signed).unwrap();
+        json!({
+            "id": self.credential_id(),
+            "rawId": self.credential_id(),
+            "type": "public-key",
+            "response": {
+                "clientDataJSON": URL_SAFE_NO_PAD.encode(client_data),
+                "authenticatorData": URL_SAFE_NO_PAD.encode(auth_data),
+                "signature": URL_SAFE_NO_PAD.encode(signature),
+                "userHandle": user_handle,
+            },
+        })
+    }
+}
+
+fn login_options() -> test::TestRequest {
+    test::TestRequest::post().uri("/v1/auth/passkeys/login/options")
+}
+
+fn login(challenge_token: &Value, credential: Value) -> test::TestRequest {
+    test::TestRequest::post()
+        .uri("/v1/auth/passkeys/login")
+        .set_json(json!({ "challenge_token": challenge_token, "credential": credential }))
+}
+
+#[actix_rt::test]
+async fn test_register_and_log_in_with_a_passkey() {
+    setup();
+    let config = app_config();
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Operator).verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let as_user = bearer(&user, &config);
+    let mut authenticator = Authenticator::new();
+
+    let options = test::TestRequest::post().uri("/v1/auth/passkeys/options").insert_header(as_user.clone());
+    let (status, body) = send(&app, options).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["options"]["rp"]["id"], "localhost");
+    assert_eq!(body["options"]["user"]["name"], user.email.as_str());
+    assert_eq!(body["options"]["authenticatorSelection"]["userVerification"], "required");
+    let user_handle = body["options"]["user"]["id"].as_str().unwrap().to_string();
+    assert_eq!(URL_SAFE_NO_PAD.decode(&user_handle).unwrap(), user.id.as_bytes());
+
+    let register = |body: &Value, origin: &str| {
+        test::TestRequest::post()
+            .uri("/v1/auth/passkeys")
+            .insert_header(as_user.clone())
+            .set_json(json!({
+                "name": "Office laptop",
+                "challenge_token": body["challenge_token"],
+                "credential": authenticator.create(body["options"]["challenge"].as_str().unwrap(), origin),
+            }))
+    };
+
+    // Pages on other origins can't register passkeys
+    let (status, error) = send(&app, register(&body, "https://phish.example.com")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["details"]["field"], "credential");
+
+    let (_, body) = send(&app, test::TestRequest::post().uri("/v1/auth/passkeys/options").insert_header(as_user.clone())).await;
+    let (status, passkey) = send(&app, register(&body, ORIGIN)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(passkey["name"], "Office laptop");
+
+    // Each challenge answers once
+    let (status, _) = send(&app, register(&body, ORIGIN)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, options) = send(&app, login_options()).await;
+    assert_eq!(options["options"]["rpId"], "localhost");
+    let credential = authenticator.get(options["options"]["challenge"].as_str().unwrap(), &user_handle);
+    let (status, body) = send(&app, login(&options["challenge_token"], credential.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["user"]["id"], user.id.to_string());
+    assert!(body["access_token"].is_string());
+
+    // Replaying the login is refused
+    let (status, _) = send(&app, login(&options["challenge_token"], credential)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let list = test::TestRequest::get().uri("/v1/auth/passkeys").insert_header(as_user.clone());
+    let (status, body) = send(&app, list).await;
+    assert_eq!(status, StatusCode::OK);
+    let passkeys = body["data"].as_array().unwrap();
+    assert_eq!(passkeys.len(), 1);
+    assert!(passkeys[0]["last_used_at"].is_string());
+
+    let events = test::TestRequest::get().uri("/v1/me/auth-events").insert_header(as_user);
+    let (_, body) = send(&app, events).await;
+    assert_eq!(body["data"][0]["event"], "login_failed");
+    assert_eq!(body["data"][1]["event"], "login_succeeded");
+    assert_eq!(body["data"][1]["method"], "passkey");
+    assert_eq!(body["data"][2]["event"], "passkey_added");
+}
+
+#[actix_rt::test]
+async fn test_passkey_login_refuses_forged_cloned_and_removed_passkeys() {
+    setup();
+    let config = app_config();
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let as_user = bearer(&user, &config);
+    let mut authenticator = Authenticator::new();
+    let user_handle = URL_SAFE_NO_PAD.encode(user.id.as_bytes());
+
+    let (_, body) = send(&app, test::TestRequest::post().uri("/v1/auth/passkeys/options").insert_header(as_user.clone())).await;
+    let register = test::TestRequest::post()
+        .uri("/v1/auth/passkeys")
+        .insert_header(as_user.clone())
+        .set_json(json!({
+            "name": "Phone",
+            "challenge_token": body["challenge_token"],
+            "credential": authenticator.create(body["options"]["challenge"].as_str().unwrap(), ORIGIN),
+        }));
+    let (status, passkey) = send(&app, register).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Signed by another key
+    let (_, options) = send(&app, login_options()).await;
+    let mut forger = Authenticator::new();
+    forger.credential_id = authenticator.credential_id.clone();
+    let credential = forger.get(options["options"]["challenge"].as_str().unwrap(), &user_handle);
+    let (status, _) = send(&app, login(&options["challenge_token"], credential)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (_, options) = send(&app, login_options()).await;
+    let credential = authenticator.get(options["options"]["challenge"].as_str().unwrap(), &user_handle);
+    let (status, _) = send(&app, login(&options["challenge_token"], credential)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // A copy of the key whose counter fell behind
+    authenticator.sign_count -= 1;
+    let (_, options) = send(&app, login_options()).await;
+    let credential = authenticator.get(options["options"]["challenge"].as_str().unwrap(), &user_handle);
+    let (status, _) = send(&app, login(&options["challenge_token"], credential)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let uri = format!("/v1/auth/passkeys/{}", passkey["id"].as_str().unwrap());
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&uri).insert_header(as_user.clone())).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&uri).insert_header(as_user.clone())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (_, options) = send(&app, login_options()).await;
+    let credential = authenticator.get(options["options"]["challenge"].as_str().unwrap(), &user_handle);
+    let (status, _) = send(&app, login(&options["challenge_token"], credential)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let events = test::TestRequest::get().uri("/v1/me/auth-events").insert_header(as_user);
+    let (_, body) = send(&app, events).await;
+    let events: Vec<&str> = body["data"].as_array().unwrap().iter().map(|event| event["event"].as_str().unwrap()).collect();
+    assert_eq!(
+        events,
+        ["passkey_removed", "login_failed", "login_succeeded", "login_failed", "passkey_added"]
+    );
+}
@@ -0,0 +1,97 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+use diesel::prelude::*;
+
+use super::magic_link::mailed_token;
+use crate::{
+    db::{
+        models::auth::{EmailVerificationToken, PasswordResetToken, User},
+        repositories::Repository,
+        repositories::auth::UserRepositoryImpl,
+        schema::{email_verification_tokens, password_reset_tokens, users},
+    },
+    domain::{auth::ClientInfo, AuthService},
+    tests::{
+        common::helpers::app_config,
+        factories::UserFactory,
+        setup,
+    },
+    utils::crypto::hash_token,
+};
+
+/// A hash of `password` made with other settings than the defaults
+fn hash_with(password: &str, algorithm: Algorithm, params: Params) -> String {
+    Argon2::new(algorithm, Version::V0x13, params)
+        .hash_password(password.as_bytes(), &SaltString::generate(&mut OsRng))
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_hashes_with_other_settings_need_rehash() {
+    let current = User::hash_password(UserFactory::PASSWORD).unwrap();
+    let weaker = hash_with(UserFactory::PASSWORD, Algorithm::Argon2id, Params::new(8, 1, 1, None).unwrap());
+    let other_variant = hash_with(UserFactory::PASSWORD, Algorithm::Argon2i, Params::default());
+
+    assert!(!User::needs_rehash(&current));
+    assert!(User::needs_rehash(&weaker));
+    assert!(User::needs_rehash(&other_variant));
+    // Any Argon2 hash verifies, whatever its settings
+    assert!(User::verify_password(UserFactory::PASSWORD, &weaker).unwrap());
+    assert!(User::verify_password(UserFactory::PASSWORD, &other_variant).unwrap());
+    assert!(!User::needs_rehash("not a hash"));
+}
+
+#[actix_rt::test]
+async fn test_login_rehashes_outdated_hashes() {
+    setup();
+    let config = app_config();
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let user = UserFactory::new().verified().create(&mut conn).await.unwrap();
+    let weaker = hash_with(UserFactory::PASSWORD, Algorithm::Argon2id, Params::new(8, 1, 1, None).unwrap());
+    diesel::update(users::table.find(user.id))
+        .set(users::password.eq(&weaker))
+        .execute(&mut conn)
+        .unwrap();
+
+    AuthService::login(config.pool(), &user.email, UserFactory::PASSWORD, None, None, &ClientInfo::default(), &config).await.unwrap();
+
+    let stored = UserRepositoryImpl.find_by_id(&mut conn, user.id).await.unwrap().password;
+    assert_ne!(stored, weaker);
+    assert!(!User::needs_rehash(&stored));
+    assert!(User::verify_password(UserFactory::PASSWORD, &stored).unwrap());
+}
+
+#[actix_rt::test]
+async fn test_reset_and_verification_links_are_stored_hashed() {
+    setup();
+    let config = app_config();
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let user = UserFactory::new().create(&mut conn).await.unwrap();
+
+    AuthService::send_verification(config.pool(), &user.email, &config).await.unwrap();
+    let token = mailed_token(&mut conn, &user.email);
+    let stored: EmailVerificationToken = email_verification_tokens::table
+        .filter(email_verification_tokens::user_id.eq(user.id))
+        .select(EmailVerificationToken::as_select())
+        .first(&mut conn)
+        .unwrap();
+    assert_eq!(stored.token_hash, hash_token(&token));
+    // The stored hash doesn't open the link
+    assert!(AuthService::verify_email(config.pool(), &stored.token_hash).await.is_err());
+    assert!(AuthService::verify_email(config.pool(), &token).await.unwrap().email_verified);
+
+    AuthService::forgot_password(config.pool(), &user.email, &config).await.unwrap();
+    let token = mailed_token(&mut conn, &user.email);
+    let stored: PasswordResetToken = password_reset_tokens::table
+        .filter(password_reset_tokens::user_id.eq(user.id))
+        .select(PasswordResetToken::as_select())
+        .first(&mut conn)
+        .unwrap();
+    assert_eq!(stored.token_hash, hash_token(&token));
+    let client = ClientInfo::default();
+    assert!(AuthService::reset_password(config.pool(), &stored.token_hash, "n3w-Passphrase!", &client, &config).await.is_err());
+    AuthService::reset_password(config.pool(), &token, "n3w-Passphrase!", &client, &config).await.unwrap();
+}
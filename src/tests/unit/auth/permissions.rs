@@ -0,0 +1,80 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+
+use crate::{
+    db::models::auth::Role,
+    domain::auth::{Permission, PermissionService},
+    error::{ErrorCode, Result},
+    server,
+    tests::{common::helpers::{app_config, bearer, send, TestDb}, factories::UserFactory, setup},
+};
+
+#[tokio::test]
+async fn test_seeded_role_permissions() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let policy = PermissionService::load(conn).await?;
+
+            // Managers keep what the role-guarded routes allowed
+            assert_eq!(policy.permissions(Role::Manager), Permission::ALL.to_vec());
+            assert!(policy.permissions(Role::Operator).is_empty());
+            assert!(policy.allows(Role::Admin, Permission::ErpWrite));
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_set_role_permissions() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let granted = PermissionService::set_role_permissions(
+                conn,
+                Role::Operator,
+                &[Permission::TagsWrite, Permission::TagsRead, Permission::TagsRead],
+            ).await?;
+            assert_eq!(granted, vec![Permission::TagsRead, Permission::TagsWrite]);
+
+            let policy = PermissionService::load(conn).await?;
+            assert!(policy.allows(Role::Operator, Permission::TagsWrite));
+            assert!(!policy.allows(Role::Operator, Permission::CustomersRead));
+            // Other roles are untouched
+            assert!(policy.allows(Role::Manager, Permission::CustomersRead));
+
+            // Revoking everything leaves the role without permissions
+            let granted = PermissionService::set_role_permissions(conn, Role::Operator, &[]).await?;
+            assert!(granted.is_empty());
+
+            let err = PermissionService::set_role_permissions(conn, Role::Admin, &[]).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            Ok(())
+        })
+    }).await
+}
+
+#[actix_rt::test]
+async fn test_only_platform_admins_change_role_permissions() {
+    setup();
+    let config = app_config();
+    let admin = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+
+    // The built-in roles are shared by every organization
+    let request = test::TestRequest::put()
+        .uri("/v1/admin/roles/Operator/permissions")
+        .insert_header(bearer(&admin, &config))
+        .set_json(json!({ "permissions": ["erp:write"] }));
+    let (status, body) = send(&app, request).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["details"]["code"], "PLATFORM_ADMIN_REQUIRED");
+
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    assert!(PermissionService::load(&mut conn).await.unwrap().permissions(Role::Operator).is_empty());
+}
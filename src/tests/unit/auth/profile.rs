@@ -0,0 +1,180 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    db::models::auth::{Role, User},
+    domain::user::{decode_png, Image},
+    server,
+    tests::{common::helpers::{app_config, bearer}, factories::UserFactory, setup},
+};
+
+/// A phone number no other test uses
+fn unique_phone_number() -> String {
+    format!("+1 {:010}", Uuid::new_v4().as_u128() % 10_000_000_000)
+}
+
+#[actix_rt::test]
+async fn test_users_read_and_edit_their_profile() {
+    setup();
+    let config = app_config();
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Operator).verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let auth = bearer(&user, &config);
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/v1/me").insert_header(auth.clone()).to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["id"], json!(user.id));
+    assert_eq!(body["email"], user.email);
+    assert_eq!(body["role"], "Operator");
+
+    let phone_number = unique_phone_number();
+    let response = test::call_service(&app, test::TestRequest::patch()
+        .uri("/v1/me")
+        .insert_header(auth.clone())
+        .set_json(json!({ "first_name": "  Jane ", "phone_number": phone_number }))
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["first_name"], "Jane");
+    assert_eq!(body["last_name"], user.last_name);
+    assert_eq!(body["phone_number"], phone_number);
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/v1/me").insert_header(auth).to_request()).await;
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["first_name"], "Jane");
+
+    // The middleware turns away requests without a token
+    match test::try_call_service(&app, test::TestRequest::get().uri("/v1/me").to_request()).await {
+        Ok(response) => assert_eq!(response.status(), StatusCode::UNAUTHORIZED),
+        Err(error) => assert_eq!(error.error_response().status(), StatusCode::UNAUTHORIZED),
+    }
+}
+
+#[actix_rt::test]
+async fn test_profile_changes_are_validated() {
+    setup();
+    let config = app_config();
+    let (user, other) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        (
+            UserFactory::new().role(Role::Operator).verified().create(&mut conn).await.unwrap(),
+            UserFactory::new().verified().create(&mut conn).await.unwrap(),
+        )
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let patch = |user: &User, changes: Value| {
+        test::TestRequest::patch()
+            .uri("/v1/me")
+            .insert_header(bearer(user, &config))
+            .set_json(changes)
+            .to_request()
+    };
+
+    let taken = unique_phone_number();
+    let response = test::call_service(&app, patch(&other, json!({ "phone_number": taken }))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = test::call_service(&app, patch(&user, json!({ "phone_number": taken }))).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = test::call_service(&app, patch(&user, json!({ "phone_number": "call me" }))).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["details"]["field"], "phone_number");
+    let response = test::call_service(&app, patch(&user, json!({ "last_name": " " }))).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["details"]["field"], "last_name");
+
+    // Role and organization are for admins to change
+    for changes in [json!({ "role": "ADMIN" }), json!({ "org_id": other.org_id }), json!({ "email": "me@example.com" })] {
+        let response = test::call_service(&app, patch(&user, changes)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+    let response = test::call_service(&app, test::TestRequest::get().uri("/v1/me").insert_header(bearer(&user, &config)).to_request()).await;
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["role"], "Operator");
+    assert_eq!(body["org_id"], json!(user.org_id));
+    assert_eq!(body["phone_number"], user.phone_number);
+}
+
+/// A form posting `file` as its `avatar` field
+fn avatar_form(file: &[u8]) -> (String, Vec<u8>) {
+    let mut body = b"--avatar-boundary\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"me.png\"\r\nContent-Type: image/png\r\n\r\n".to_vec();
+    body.extend_from_slice(file);
+    body.extend_from_slice(b"\r\n--avatar-boundary--\r\n");
+    ("multipart/form-data; boundary=avatar-boundary".to_string(), body)
+}
+
+#[actix_rt::test]
+async fn test_users_upload_an_avatar() {
+    setup();
+    let config = app_config();
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let auth = bearer(&user, &config);
+    let upload = |file: &[u8]| {
+        let (content_type, body) = avatar_form(file);
+        test::TestRequest::put()
+            .uri("/v1/me/avatar")
+            .insert_header(auth.clone())
+            .insert_header(("Content-Type", content_type))
+            .set_payload(body)
+            .to_request()
+    };
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/v1/me").insert_header(auth.clone()).to_request()).await;
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["avatar"], Value::Null);
+
+    let response = test::call_service(&app, upload(b"GIF89a")).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["details"]["field"], "avatar");
+
+    // A 300×200 picture, cropped to its centre
+    let picture = Image {
+        width: 300,
+        height: 200,
+        pixels: [30, 120, 60, 255].repeat(300 * 200),
+    };
+    let response = test::call_service(&app, upload(&picture.encode_png())).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    let small_url = body["avatar"]["small_url"].as_str().unwrap().to_string();
+    let large_url = body["avatar"]["large_url"].as_str().unwrap().to_string();
+    assert!(small_url.starts_with(&format!("/v1/avatars/{}/64?", user.id)), "{}", small_url);
+
+    // Links work without a token
+    for (url, size) in [(&small_url, 64), (&large_url, 256)] {
+        let response = test::call_service(&app, test::TestRequest::get().uri(url).to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get("Content-Type").unwrap(), "image/png");
+        let png = test::read_body(response).await;
+        let image = decode_png(&png).unwrap();
+        assert_eq!((image.width, image.height), (size, size));
+        assert_eq!(image.pixels[..4], [30, 120, 60, 255]);
+    }
+
+    // Changing any part of a link breaks its signature
+    let tampered = [
+        small_url.replace("/64?", "/256?"),
+        small_url.replace(&user.id.to_string(), &Uuid::new_v4().to_string()),
+        small_url.replace("expires=", "expires=9"),
+    ];
+    for url in tampered {
+        let response = test::call_service(&app, test::TestRequest::get().uri(&url).to_request()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", url);
+    }
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/v1/me").insert_header(auth).to_request()).await;
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["avatar"]["small_url"].as_str().unwrap().split('?').next(), small_url.split('?').next());
+}
@@ -1,12 +1,19 @@
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use uuid::Uuid;
 use crate::{
     db::{
-        models::{auth::{Role, User}, Organization},
-        repositories::{auth::UserRepositoryImpl, OrganizationRepositoryImpl, Repository},
+        models::auth::{Role, User},
+        repositories::{
+            auth::{
+                EmailVerificationTokenRepository, EmailVerificationTokenRepositoryImpl, MagicLinkTokenRepository,
+                MagicLinkTokenRepositoryImpl, PasswordResetTokenRepository, PasswordResetTokenRepositoryImpl,
+                UserRepositoryImpl,
+            },
+            Repository,
+        },
     },
     error::Result,
-    tests::{common::helpers::TestDb, setup},
+    tests::{common::helpers::TestDb, factories::{OrganizationFactory, UserFactory}, setup},
     api::utils::PaginationParams,
 };
 use crate::db::repositories::auth::UserRepository;
@@ -19,33 +26,10 @@ async fn test_user_crud() -> Result<()> {
             let repo = UserRepositoryImpl;
 
             // Create an organization first
-            let org = Organization {
-                id: Uuid::new_v4(),
-                name: format!("Test Org {}", Uuid::new_v4()),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-            };
-            let org_repo = OrganizationRepositoryImpl;
-            let created_org = org_repo.create(conn, &org).await?;
+            let created_org = OrganizationFactory::new().create(conn).await?;
 
             // Test Create
-            let uuid = Uuid::new_v4();
-            let password = User::hash_password("test_password")?;
-            let user = User {
-                id: Uuid::new_v4(),
-                email: format!("test{}@example.com", uuid),
-                first_name: "Test".to_string(),
-                last_name: "User".to_string(),
-                phone_number: format!("1234567890{}", uuid.simple()),
-                password,
-                role: Role::Operator,
-                org_id: created_org.id,
-                email_verified: true,
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-            };
+            let user = UserFactory::new().in_org(&created_org).verified().build();
             let created_user = repo.create(conn, &user).await?;
             assert_eq!(created_user.email, user.email);
 
@@ -85,35 +69,12 @@ async fn test_user_queries() -> Result<()> {
             let repo = UserRepositoryImpl;
 
             // Create an organization first
-            let org = Organization {
-                id: Uuid::new_v4(),
-                name: format!("Test Org {}", Uuid::new_v4()),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-            };
-            let org_repo = OrganizationRepositoryImpl;
-            let created_org = org_repo.create(conn, &org).await?;
+            let created_org = OrganizationFactory::new().create(conn).await?;
 
             // Test find by email
-            let email = format!("test{}@example.com", Uuid::new_v4());
-            let user = User {
-                id: Uuid::new_v4(),
-                email: email.clone(),
-                first_name: "Test".to_string(),
-                last_name: "User".to_string(),
-                phone_number: "1234567890".to_string(),
-                password: User::hash_password("test_password")?,
-                role: Role::Operator,
-                org_id: created_org.id,
-                email_verified: true,
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-            };
-            let created_user = repo.create(conn, &user).await?;
-            
-            let found_user = repo.find_by_email(conn, &email).await?;
+            let created_user = UserFactory::new().in_org(&created_org).verified().create(conn).await?;
+
+            let found_user = repo.find_by_email(conn, &created_user.email).await?;
             assert_eq!(found_user.unwrap().id, created_user.id);
 
             // Test find by role
@@ -124,4 +85,130 @@ async fn test_user_queries() -> Result<()> {
             Ok(())
         })
     }).await
-} 
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_user_relation_batch_loading() -> Result<()> {
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let repo = UserRepositoryImpl;
+            let orgs = OrganizationFactory::new().create_many(conn, 2).await?;
+
+            // Two users in the first org, one in the second
+            let mut created = Vec::new();
+            for org in [&orgs[0], &orgs[0], &orgs[1]] {
+                created.push(UserFactory::new().in_org(org).verified().create(conn).await?);
+            }
+
+            let found = repo.find_by_ids(conn, &[created[0].id, created[2].id, Uuid::new_v4()]).await?;
+            assert_eq!(found.len(), 2);
+            assert!(found.iter().all(|user| [created[0].id, created[2].id].contains(&user.id)));
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_password_reset_tokens() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let repo = PasswordResetTokenRepositoryImpl;
+            let user_repo = UserRepositoryImpl;
+
+            let created_org = OrganizationFactory::new().create(conn).await?;
+            let user = UserFactory::new().in_org(&created_org).create(conn).await?;
+
+            // Tokens are single use
+            let token = repo.create_for_user(conn, user.id, "reset").await?;
+            assert!(token.expires_at > Utc::now());
+            assert_eq!(repo.consume(conn, "reset").await?.map(|t| t.id), Some(token.id));
+            assert!(repo.consume(conn, "reset").await?.is_none());
+            assert!(repo.find_by_hash(conn, "reset").await?.is_none());
+
+            // Expired tokens can't be used
+            let mut expired = repo.create_for_user(conn, user.id, "expired").await?;
+            expired.expires_at = Utc::now() - Duration::minutes(1);
+            repo.update(conn, expired.id, &expired).await?;
+            assert!(repo.consume(conn, "expired").await?.is_none());
+
+            // Revoking leaves no token usable
+            repo.create_for_user(conn, user.id, "first").await?;
+            repo.create_for_user(conn, user.id, "second").await?;
+            repo.revoke_all_for_user(conn, user.id).await?;
+            assert!(repo.consume(conn, "first").await?.is_none());
+            assert!(repo.consume(conn, "second").await?.is_none());
+
+            let updated = user_repo.update_password(conn, user.id, "n3w-passphrase").await?;
+            assert!(User::verify_password("n3w-passphrase", &updated.password)?);
+            assert!(updated.updated_at > user.updated_at);
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_email_verification_tokens() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let repo = EmailVerificationTokenRepositoryImpl;
+            let user_repo = UserRepositoryImpl;
+
+            let created_org = OrganizationFactory::new().create(conn).await?;
+            let user = UserFactory::new().in_org(&created_org).create(conn).await?;
+            assert!(!user.email_verified);
+
+            // A new link replaces the previous one
+            repo.create_for_user(conn, user.id, "first").await?;
+            repo.revoke_all_for_user(conn, user.id).await?;
+            let second = repo.create_for_user(conn, user.id, "second").await?;
+            assert!(second.expires_at > Utc::now() + Duration::hours(23));
+            assert!(repo.consume(conn, "first").await?.is_none());
+
+            let consumed = repo.consume(conn, "second").await?.expect("the token is valid");
+            assert_eq!(consumed.user_id, user.id);
+            assert!(repo.consume(conn, "second").await?.is_none());
+
+            let verified = user_repo.mark_email_verified(conn, user.id).await?;
+            assert!(verified.email_verified);
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_magic_link_tokens() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let repo = MagicLinkTokenRepositoryImpl;
+            let created_org = OrganizationFactory::new().create(conn).await?;
+            let user = UserFactory::new().in_org(&created_org).verified().create(conn).await?;
+
+            // Unbound links work from anywhere, once
+            repo.create_for_user(conn, user.id, "unbound", None, Duration::minutes(15)).await?;
+            let consumed = repo.consume(conn, "unbound", Some("phone")).await?.expect("the token is valid");
+            assert_eq!(consumed.user_id, user.id);
+            assert!(repo.consume(conn, "unbound", None).await?.is_none());
+
+            // Bound links only work on their device
+            repo.create_for_user(conn, user.id, "bound", Some("phone"), Duration::minutes(15)).await?;
+            assert!(repo.consume(conn, "bound", Some("laptop")).await?.is_none());
+            assert!(repo.consume(conn, "bound", None).await?.is_none());
+            assert!(repo.consume(conn, "bound", Some("phone")).await?.is_some());
+
+            repo.create_for_user(conn, user.id, "expired", None, Duration::minutes(-1)).await?;
+            assert!(repo.consume(conn, "expired", None).await?.is_none());
+
+            repo.create_for_user(conn, user.id, "revoked", None, Duration::minutes(15)).await?;
+            repo.revoke_all_for_user(conn, user.id).await?;
+            assert!(repo.consume(conn, "revoked", None).await?.is_none());
+
+            Ok(())
+        })
+    }).await
+}
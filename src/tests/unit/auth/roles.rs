@@ -0,0 +1,229 @@
+use actix_web::{
+    http::StatusCode,
+    test, web, App, HttpResponse,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    api::middleware::auth::{Auth, RequireRole},
+    db::models::auth::{Role, User},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+    utils::Config,
+};
+
+/// An admin, a manager and an operator of one organization, and an
+/// operator of another
+async fn users(config: &Config) -> (User, User, User, User) {
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+    let member = |role| UserFactory::new().in_org(&organization).role(role).verified();
+    (
+        member(Role::Admin).create(&mut conn).await.unwrap(),
+        member(Role::Manager).create(&mut conn).await.unwrap(),
+        member(Role::Operator).create(&mut conn).await.unwrap(),
+        UserFactory::new().role(Role::Operator).verified().create(&mut conn).await.unwrap(),
+    )
+}
+
+#[actix_rt::test]
+async fn test_custom_roles_grant_their_permissions() {
+    setup();
+    let config = app_config();
+    let (admin, _, operator, _) = users(&config).await;
+    let app = test::init_service(server::app(&config)).await;
+    let (admin_auth, operator_auth) = (bearer(&admin, &config), bearer(&operator, &config));
+    let reports = || test::TestRequest::get().uri("/v1/reports").insert_header(operator_auth.clone());
+
+    // Operators can't read reports through their built-in role
+    let (status, _) = send(&app, reports()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = send(&app, test::TestRequest::post()
+        .uri("/v1/roles")
+        .insert_header(admin_auth.clone())
+        .set_json(json!({
+            "name": " Dispatcher ",
+            "description": "Plans the day's hauling",
+            "permissions": ["reports:write", "reports:read", "reports:read"],
+        }))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["name"], "Dispatcher");
+    assert_eq!(body["permissions"], json!(["reports:read", "reports:write"]));
+    let role_id = body["id"].as_str().unwrap().to_string();
+    let member = format!("/v1/roles/{}/members/{}", role_id, operator.id);
+
+    let (status, body) = send(&app, test::TestRequest::put().uri(&member).insert_header(admin_auth.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["members"], json!([operator.id]));
+
+    // The assignment applies to the operator's existing token
+    let (status, _) = send(&app, reports()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(&app, test::TestRequest::get().uri("/v1/roles").insert_header(admin_auth.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(body["data"][0]["members"], json!([operator.id]));
+
+    // Taking a permission from the role takes it from its members
+    let (status, body) = send(&app, test::TestRequest::put()
+        .uri(&format!("/v1/roles/{}", role_id))
+        .insert_header(admin_auth.clone())
+        .set_json(json!({ "name": "Dispatcher", "permissions": ["tags:read"] }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["description"], Value::Null);
+    assert_eq!(body["members"], json!([operator.id]));
+    let (status, _) = send(&app, reports()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&member).insert_header(admin_auth.clone())).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&member).insert_header(admin_auth.clone())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, test::TestRequest::delete()
+        .uri(&format!("/v1/roles/{}", role_id))
+        .insert_header(admin_auth.clone())).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, test::TestRequest::get()
+        .uri(&format!("/v1/roles/{}", role_id))
+        .insert_header(admin_auth)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+async fn test_custom_roles_stay_within_their_organization() {
+    setup();
+    let config = app_config();
+    let (admin, manager, operator, outsider) = users(&config).await;
+    let app = test::init_service(server::app(&config)).await;
+    let admin_auth = bearer(&admin, &config);
+    let create = |name: &str| {
+        test::TestRequest::post()
+            .uri("/v1/roles")
+            .insert_header(admin_auth.clone())
+            .set_json(json!({ "name": name, "permissions": ["tags:read"] }))
+    };
+
+    let (status, body) = send(&app, create("Mechanic")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let role_id = body["id"].as_str().unwrap().to_string();
+
+    let (status, _) = send(&app, create("MECHANIC")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, body) = send(&app, create("manager")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["code"], "RESERVED");
+    let (status, body) = send(&app, create(" ")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "name");
+    let (status, _) = send(&app, test::TestRequest::post()
+        .uri("/v1/roles")
+        .insert_header(admin_auth.clone())
+        .set_json(json!({ "name": "Scaler", "permissions": ["blocks:close"] }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Only admins manage roles
+    let (status, _) = send(&app, test::TestRequest::get().uri("/v1/roles").insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Users and roles of other organizations are out of reach
+    let (status, _) = send(&app, test::TestRequest::put()
+        .uri(&format!("/v1/roles/{}/members/{}", role_id, outsider.id))
+        .insert_header(admin_auth.clone())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let outsider_admin = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap()
+    };
+    let (status, _) = send(&app, test::TestRequest::put()
+        .uri(&format!("/v1/roles/{}/members/{}", role_id, operator.id))
+        .insert_header(bearer(&outsider_admin, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, body) = send(&app, test::TestRequest::get().uri("/v1/roles").insert_header(bearer(&outsider_admin, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"].as_array().unwrap().is_empty());
+}
+
+#[actix_rt::test]
+async fn test_require_custom_role() {
+    setup();
+    let config = app_config();
+    let (admin, manager, operator, _) = users(&config).await;
+    let app = test::init_service(server::app(&config)).await;
+    let guarded = test::init_service(
+        App::new()
+            .app_data(web::Data::new(config.pool().clone()))
+            .app_data(web::Data::new(config.clone()))
+            .service(
+                web::resource("/dispatch")
+                    .wrap(RequireRole::custom("Dispatcher"))
+                    .wrap(Auth::new())
+                    .route(web::get().to(HttpResponse::Ok)),
+            ),
+    )
+    .await;
+    let dispatch = |user: &User| test::TestRequest::get().uri("/dispatch").insert_header(bearer(user, &config));
+
+    let (status, body) = send(&app, test::TestRequest::post()
+        .uri("/v1/roles")
+        .insert_header(bearer(&admin, &config))
+        .set_json(json!({ "name": "Dispatcher", "permissions": [] }))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = send(&app, test::TestRequest::put()
+        .uri(&format!("/v1/roles/{}/members/{}", body["id"].as_str().unwrap(), operator.id))
+        .insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(&guarded, dispatch(&operator)).await;
+    assert_eq!(status, StatusCode::OK);
+    // Admins pass; a higher built-in role doesn't stand in for a custom one
+    let (status, _) = send(&guarded, dispatch(&admin)).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&guarded, dispatch(&manager)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[actix_rt::test]
+async fn test_require_role_hierarchy_and_sets() {
+    setup();
+    let config = app_config();
+    let (admin, manager, operator, _) = users(&config).await;
+    let guarded = test::init_service(
+        App::new()
+            .app_data(web::Data::new(config.clone()))
+            .service(
+                web::resource("/managers")
+                    .wrap(RequireRole::new(Role::Manager))
+                    .wrap(Auth::new())
+                    .route(web::get().to(HttpResponse::Ok)),
+            )
+            .service(
+                web::resource("/field")
+                    .wrap(RequireRole::any([Role::Operator, Role::Admin]))
+                    .wrap(Auth::new())
+                    .route(web::get().to(HttpResponse::Ok)),
+            ),
+    )
+    .await;
+    let get = |uri: &str, user: &User| test::TestRequest::get().uri(uri).insert_header(bearer(user, &config));
+
+    // Manager or above
+    for (user, expected) in [(&admin, StatusCode::OK), (&manager, StatusCode::OK), (&operator, StatusCode::FORBIDDEN)] {
+        let (status, _) = send(&guarded, get("/managers", user)).await;
+        assert_eq!(status, expected);
+    }
+    // Only the roles listed
+    for (user, expected) in [(&admin, StatusCode::OK), (&manager, StatusCode::FORBIDDEN), (&operator, StatusCode::OK)] {
+        let (status, _) = send(&guarded, get("/field", user)).await;
+        assert_eq!(status, expected);
+    }
+
+    assert!(Role::Admin > Role::Manager && Role::Manager > Role::Operator);
+    assert_eq!([Role::Manager, Role::Admin, Role::Operator].into_iter().max(), Some(Role::Admin));
+}
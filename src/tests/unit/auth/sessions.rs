@@ -0,0 +1,125 @@
+use actix_web::{http::StatusCode, test};
+use chrono::{Duration, Utc};
+use serde_json::json;
+
+use crate::{
+    db::{
+        models::auth::Role,
+        repositories::{
+            auth::{RefreshTokenRepository, RefreshTokenRepositoryImpl, SessionLifetime},
+            Repository,
+        },
+    },
+    domain::TokenManager,
+    server,
+    tests::{common::helpers::{app_config, send}, factories::UserFactory, setup},
+    utils::{Config, SessionConfig},
+};
+
+fn refresh(token: &str) -> test::TestRequest {
+    test::TestRequest::post()
+        .uri("/v1/auth/refresh")
+        .set_json(json!({ "refresh_token": token }))
+}
+
+/// Test configuration with 15 minute access tokens and sessions that end
+/// after two idle hours or a day
+fn config() -> Config {
+    let mut config = app_config();
+    config.auth.session = SessionConfig {
+        access_token_minutes: 15,
+        idle_timeout_minutes: 120,
+        refresh_token_days: 1,
+    };
+    config
+}
+
+#[actix_rt::test]
+async fn test_access_tokens_last_the_configured_time() {
+    setup();
+    let config = config();
+    let user = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Operator).verified().create(&mut conn).await.unwrap()
+    };
+
+    let token = TokenManager::generate_token(&user, &config).unwrap();
+    let claims = TokenManager::validate_token(&token, &config).unwrap();
+    assert_eq!(claims.exp - claims.iat, 15 * 60);
+}
+
+#[actix_rt::test]
+async fn test_refreshing_slides_the_session() {
+    setup();
+    let config = config();
+    let lifetime = SessionLifetime::from(&config.auth.session);
+    let (started, ending) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let user = UserFactory::new().role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        let started = RefreshTokenRepositoryImpl.create_for_user(&mut conn, user.id, lifetime).await.unwrap();
+        let mut ending = RefreshTokenRepositoryImpl.create_for_device(&mut conn, user.id, Some("tablet"), lifetime).await.unwrap();
+        ending.session_expires_at = Utc::now() + Duration::minutes(30);
+        let ending = RefreshTokenRepositoryImpl.update(&mut conn, ending.id, &ending).await.unwrap();
+        (started, ending)
+    };
+    assert!(started.expires_at < Utc::now() + Duration::minutes(121));
+    assert!(started.session_expires_at > Utc::now() + Duration::hours(23));
+    let app = test::init_service(server::app(&config)).await;
+
+    let (status, body) = send(&app, refresh(&started.token)).await;
+    assert_eq!(status, StatusCode::OK);
+    let renewed = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        RefreshTokenRepositoryImpl
+            .find_by_token(&mut conn, body["refresh_token"].as_str().unwrap())
+            .await
+            .unwrap()
+            .expect("the renewed token is stored")
+    };
+    assert!(renewed.expires_at > Utc::now() + Duration::minutes(119));
+    assert_eq!(renewed.session_expires_at, started.session_expires_at);
+    let (status, _) = send(&app, refresh(&started.token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Refreshing never outlasts the session
+    let (status, body) = send(&app, refresh(&ending.token)).await;
+    assert_eq!(status, StatusCode::OK);
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let renewed = RefreshTokenRepositoryImpl
+        .find_by_token(&mut conn, body["refresh_token"].as_str().unwrap())
+        .await
+        .unwrap()
+        .expect("the renewed token is stored");
+    assert_eq!(renewed.expires_at, ending.session_expires_at);
+    assert_eq!(renewed.device_id.as_deref(), Some("tablet"));
+}
+
+#[actix_rt::test]
+async fn test_idle_sessions_expire() {
+    setup();
+    let config = config();
+    let (idle, ended) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let user = UserFactory::new().role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        // Last refreshed three hours ago, with a longer idle timeout than
+        // the current one
+        let lifetime = SessionLifetime { idle: Duration::hours(8), max: Duration::days(1) };
+        let mut idle = RefreshTokenRepositoryImpl.create_for_user(&mut conn, user.id, lifetime).await.unwrap();
+        idle.created_at = Utc::now() - Duration::hours(3);
+        let idle = RefreshTokenRepositoryImpl.update(&mut conn, idle.id, &idle).await.unwrap();
+        let mut ended = RefreshTokenRepositoryImpl
+            .create_for_device(&mut conn, user.id, Some("phone"), lifetime)
+            .await
+            .unwrap();
+        ended.expires_at = Utc::now() - Duration::minutes(1);
+        ended.session_expires_at = ended.expires_at;
+        let ended = RefreshTokenRepositoryImpl.update(&mut conn, ended.id, &ended).await.unwrap();
+        (idle, ended)
+    };
+    let app = test::init_service(server::app(&config)).await;
+
+    let (status, _) = send(&app, refresh(&idle.token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send(&app, refresh(&ended.token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,180 @@
+use actix_web::{http::StatusCode, test};
+use chrono::Utc;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{auth::{Role, User}, OrganizationSsoDomain},
+        repositories::{
+            auth::{RefreshTokenRepository, RefreshTokenRepositoryImpl, SessionLifetime},
+            SsoRepository, SsoRepositoryImpl,
+        },
+    },
+    domain::auth::sso::resolve_user,
+    error::{ErrorCode, Result},
+    infrastructure::oidc::IdTokenClaims,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send, TestDb},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+    utils::Config,
+};
+
+fn config() -> Config {
+    app_config()
+}
+
+fn claims(sub: &str, email: &str) -> IdTokenClaims {
+    IdTokenClaims {
+        sub: sub.to_string(),
+        email: Some(email.to_string()),
+        email_verified: Some(true),
+        given_name: Some("Anna".to_string()),
+        family_name: Some("Berg".to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_sso_provisions_users_from_claimed_domains() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let org = OrganizationFactory::new().create(conn).await?;
+            let repo = SsoRepositoryImpl;
+
+            // Unclaimed domains are refused
+            let err = resolve_user(conn, "microsoft", false, &claims("sub-1", "anna@pinecorp.com"), &config())
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::Forbidden);
+
+            repo.add_domain(conn, &OrganizationSsoDomain {
+                domain: "pinecorp.com".to_string(),
+                org_id: org.id,
+                role: Role::Manager,
+                created_at: Utc::now(),
+            }).await?;
+
+            let user = resolve_user(conn, "microsoft", false, &claims("sub-1", "Anna@PineCorp.com"), &config()).await?;
+            assert_eq!(user.org_id, org.id);
+            assert_eq!(user.role, Role::Manager);
+            assert_eq!(user.first_name, "Anna");
+            assert!(user.email_verified);
+
+            // The account is linked, so later sign-ons find the user even with a new email
+            let again = resolve_user(conn, "microsoft", false, &claims("sub-1", "anna.berg@pinecorp.com"), &config()).await?;
+            assert_eq!(again.id, user.id);
+            assert!(repo.find_identity(conn, "microsoft", "sub-1").await?.is_some());
+
+            // Another organization can't claim the domain
+            let other = OrganizationFactory::new().create(conn).await?;
+            let err = repo.add_domain(conn, &OrganizationSsoDomain {
+                domain: "pinecorp.com".to_string(),
+                org_id: other.id,
+                role: Role::Operator,
+                created_at: Utc::now(),
+            }).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_sso_links_existing_users_by_email() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let org = OrganizationFactory::new().create(conn).await?;
+            let existing = UserFactory::new().in_org(&org).email("erik@forestops.se").create(conn).await?;
+            assert!(!existing.email_verified);
+
+            // Unverified provider emails can't take over an account
+            let mut unverified = claims("sub-2", "erik@forestops.se");
+            unverified.email_verified = Some(false);
+            let err = resolve_user(conn, "google", true, &unverified, &config()).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Forbidden);
+
+            // trust_email stands in for a missing email_verified claim
+            let mut unstated = claims("sub-2", "erik@forestops.se");
+            unstated.email_verified = None;
+            let user = resolve_user(conn, "google", true, &unstated, &config()).await?;
+            assert_eq!(user.id, existing.id);
+            assert!(user.email_verified);
+            assert_eq!(user.role, existing.role);
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_sso_resets_unverified_accounts_before_linking() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let org = OrganizationFactory::new().create(conn).await?;
+            // Registered by someone who may not own the address
+            let squatted = UserFactory::new().in_org(&org).email("ingrid@forestops.se").create(conn).await?;
+            let refresh_repo = RefreshTokenRepositoryImpl;
+            let session = refresh_repo.create_for_user(conn, squatted.id, SessionLifetime::default()).await?;
+            let verified = UserFactory::new().in_org(&org).email("olle@forestops.se").verified().create(conn).await?;
+
+            let user = resolve_user(conn, "google", false, &claims("sub-3", "ingrid@forestops.se"), &config()).await?;
+            assert_eq!(user.id, squatted.id);
+            assert!(user.email_verified);
+            assert!(!User::verify_password(UserFactory::PASSWORD, &user.password)?);
+            assert!(refresh_repo.find_by_token(conn, &session.token).await?.is_none());
+
+            // Verified accounts keep their password
+            let user = resolve_user(conn, "google", false, &claims("sub-4", "olle@forestops.se"), &config()).await?;
+            assert_eq!(user.id, verified.id);
+            assert!(User::verify_password(UserFactory::PASSWORD, &user.password)?);
+
+            Ok(())
+        })
+    }).await
+}
+
+#[actix_rt::test]
+async fn test_domains_are_claimed_by_platform_admins() {
+    setup();
+    let mut config = app_config();
+    let (admin, stranger, platform_admin) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let stranger = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let platform_admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (admin, stranger, platform_admin)
+    };
+    config.auth.platform_admins.push(platform_admin.id);
+    let app = test::init_service(server::app(&config)).await;
+    let domains_uri = format!("/v1/admin/organizations/{}/sso-domains", stranger.org_id);
+    let domain = format!("{}.example.com", Uuid::new_v4().simple());
+    let claim = |as_user| test::TestRequest::post().uri(&domains_uri).insert_header(as_user).set_json(json!({ "domain": domain }));
+
+    // A claim routes the sign-ons of everyone at the domain
+    let (status, body) = send(&app, claim(bearer(&stranger, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["details"]["code"], "PLATFORM_ADMIN_REQUIRED");
+    let (status, _) = send(&app, claim(bearer(&platform_admin, &config))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Admins of other organizations neither see nor release it
+    let (status, _) = send(&app, test::TestRequest::get().uri(&domains_uri).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let release = format!("{}/{}", domains_uri, domain);
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&release).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = send(&app, test::TestRequest::get().uri(&domains_uri).insert_header(bearer(&stranger, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"][0]["domain"], domain.as_str());
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&release).insert_header(bearer(&stranger, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,74 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    db::models::auth::{Role, User},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_admins_set_reporting_lines() {
+    setup();
+    let config = app_config();
+    let (admin, manager, lead, operator, outsider) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let manager = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let lead = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).verified().create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        (admin, manager, lead, operator, outsider)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let assign = |id: Uuid, supervisor_id: Option<Uuid>, caller: &User| {
+        test::TestRequest::put()
+            .uri(&format!("/v1/users/{}/supervisor", id))
+            .insert_header(bearer(caller, &config))
+            .set_json(json!({ "supervisor_id": supervisor_id }))
+    };
+    let reports = |uri: String, caller: &User| test::TestRequest::get().uri(&uri).insert_header(bearer(caller, &config));
+    let ids = |body: &Value| -> Vec<String> {
+        body["data"].as_array().unwrap().iter().map(|user| user["id"].as_str().unwrap().to_string()).collect()
+    };
+
+    let (status, body) = send(&app, assign(operator.id, Some(lead.id), &admin)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["supervisor_id"], json!(lead.id));
+    assert_eq!(send(&app, assign(lead.id, Some(manager.id), &admin)).await.0, StatusCode::OK);
+
+    let (status, body) = send(&app, reports("/v1/me/reports".to_string(), &lead)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body), [operator.id.to_string()]);
+    let (_, body) = send(&app, reports(format!("/v1/users/{}/reports", manager.id), &admin)).await;
+    assert_eq!(ids(&body), [lead.id.to_string()]);
+    let (_, body) = send(&app, reports("/v1/me/reports".to_string(), &operator)).await;
+    assert!(ids(&body).is_empty());
+
+    // Supervisors are active managers or admins of the organization, and
+    // reporting lines don't loop
+    for (id, supervisor_id, code) in [
+        (manager.id, operator.id, "NOT_A_SUPERVISOR"),
+        (manager.id, lead.id, "REPORTING_LOOP"),
+        (manager.id, manager.id, "SELF"),
+        (operator.id, outsider.id, "NOT_FOUND"),
+    ] {
+        let (status, body) = send(&app, assign(id, Some(supervisor_id), &admin)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["details"]["code"], code);
+    }
+    assert_eq!(send(&app, assign(operator.id, Some(manager.id), &outsider)).await.0, StatusCode::FORBIDDEN);
+    assert_eq!(send(&app, assign(operator.id, Some(manager.id), &manager)).await.0, StatusCode::FORBIDDEN);
+
+    let (status, body) = send(&app, assign(operator.id, None, &admin)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["supervisor_id"], Value::Null);
+    let (_, body) = send(&app, reports("/v1/me/reports".to_string(), &lead)).await;
+    assert!(ids(&body).is_empty());
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use actix_web::{
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test,
+};
+use serde_json::json;
+
+use crate::{
+    db::{
+        models::auth::Role,
+        repositories::auth::{
+            PasswordResetTokenRepository, PasswordResetTokenRepositoryImpl, RefreshTokenRepository,
+            RefreshTokenRepositoryImpl, SessionLifetime,
+        },
+    },
+    domain::{auth::link_token, TokenManager},
+    server,
+    tests::{common::helpers::app_config, factories::UserFactory, setup},
+    utils::crypto::hash_token,
+};
+
+/// Status of the response to `request`, including errors from middleware
+async fn status<S, B>(app: &S, request: test::TestRequest) -> StatusCode
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+{
+    match test::try_call_service(app, request.to_request()).await {
+        Ok(response) => response.status(),
+        Err(error) => error.error_response().status(),
+    }
+}
+
+fn tags(token: &str) -> test::TestRequest {
+    test::TestRequest::get().uri("/v1/tags").insert_header(("Authorization", format!("Bearer {}", token)))
+}
+
+#[actix_rt::test]
+async fn test_logout_drops_a_cached_token() {
+    setup();
+    let config = app_config();
+    let (user, refresh_token) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let user = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let refresh_token = RefreshTokenRepositoryImpl.create_for_user(&mut conn, user.id, SessionLifetime::default()).await.unwrap();
+        (user, refresh_token)
+    };
+    let token = TokenManager::generate_token(&user, &config).unwrap();
+    let app = test::init_service(server::app(&config)).await;
+
+    assert_eq!(status(&app, tags(&token)).await, StatusCode::OK);
+    assert!(config.token_cache().get(&token).is_some());
+
+    let logout = test::TestRequest::post()
+        .uri("/v1/auth/logout")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "refresh_token": refresh_token.token }));
+    assert_eq!(status(&app, logout).await, StatusCode::NO_CONTENT);
+
+    assert!(config.token_cache().get(&token).is_none());
+    assert_eq!(status(&app, tags(&token)).await, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+async fn test_password_reset_revokes_access_tokens() {
+    setup();
+    let config = app_config();
+    let (user, reset) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let user = UserFactory::new().role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let reset = link_token();
+        PasswordResetTokenRepositoryImpl.create_for_user(&mut conn, user.id, &hash_token(&reset)).await.unwrap();
+        (user, reset)
+    };
+    let token = TokenManager::generate_token(&user, &config).unwrap();
+    let app = test::init_service(server::app(&config)).await;
+    assert_eq!(status(&app, tags(&token)).await, StatusCode::OK);
+
+    // Issue times are in whole seconds
+    actix_rt::time::sleep(Duration::from_millis(1100)).await;
+    let reset = test::TestRequest::post()
+        .uri("/v1/auth/reset-password")
+        .set_json(json!({ "token": reset, "password": "n3w-Passphrase!" }));
+    assert_eq!(status(&app, reset).await, StatusCode::OK);
+
+    assert_eq!(status(&app, tags(&token)).await, StatusCode::UNAUTHORIZED);
+    // Tokens issued afterwards work
+    let token = TokenManager::generate_token(&user, &config).unwrap();
+    assert_eq!(status(&app, tags(&token)).await, StatusCode::OK);
+}
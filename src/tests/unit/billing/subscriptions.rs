@@ -0,0 +1,221 @@
+use actix_web::{http::StatusCode, test};
+use chrono::Utc;
+use ring::hmac;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use wiremock::{
+    matchers::{body_string_contains, header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use crate::{
+    db::models::auth::Role,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+    utils::{BillingConfig, Config},
+};
+
+const SECRET_KEY: &str = "sk_test_forestry";
+const WEBHOOK_SECRET: &str = "whsec_forestry";
+
+fn config_with_billing(server: &MockServer) -> Config {
+    let mut config = app_config();
+    config.billing = BillingConfig {
+        stripe_secret_key: Some(SECRET_KEY.to_string()),
+        stripe_webhook_secret: Some(WEBHOOK_SECRET.to_string()),
+        stripe_api_url: server.uri(),
+        prices: [("team".to_string(), "price_team".to_string())].into(),
+    };
+    config
+}
+
+/// A webhook delivery of `event`, signed the way Stripe signs them
+fn webhook(event: &Value, secret: &str) -> test::TestRequest {
+    let payload = event.to_string();
+    let timestamp = Utc::now().timestamp();
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let signature: String = hmac::sign(&key, format!("{}.{}", timestamp, payload).as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    test::TestRequest::post()
+        .uri("/v1/billing/webhook")
+        .insert_header(("Content-Type", "application/json"))
+        .insert_header(("Stripe-Signature", format!("t={},v1={}", timestamp, signature)))
+        .set_payload(payload)
+}
+
+fn subscription_event(kind: &str, org_id: Uuid, subscription: &str, status: &str) -> Value {
+    json!({
+        "id": format!("evt_{}", Uuid::new_v4().simple()),
+        "type": kind,
+        "data": {
+            "object": {
+                "id": subscription,
+                "object": "subscription",
+                "customer": "cus_forestry",
+                "status": status,
+                "current_period_end": Utc::now().timestamp() + 30 * 86400,
+                "cancel_at_period_end": false,
+                "metadata": { "org_id": org_id.to_string(), "plan": "team" },
+                "items": { "data": [{ "price": { "id": "price_team" } }] }
+            }
+        }
+    })
+}
+
+#[actix_rt::test]
+async fn test_subscriptions_follow_stripe_and_gate_premium_features() {
+    setup();
+    let server = MockServer::start().await;
+    let config = config_with_billing(&server);
+    let (organization, admin, operator) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        (organization, admin, operator)
+    };
+    Mock::given(method("POST"))
+        .and(path("/v1/checkout/sessions"))
+        .and(header("Authorization", format!("Bearer {}", SECRET_KEY).as_str()))
+        .and(body_string_contains("price_team"))
+        .and(body_string_contains(format!("client_reference_id={}", organization.id)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "cs_test_forestry",
+            "object": "checkout.session",
+            "url": "https://checkout.stripe.com/c/pay/cs_test_forestry"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let app = test::init_service(server::app(&config)).await;
+    let erp = || test::TestRequest::get().uri("/v1/erp/sources").insert_header(bearer(&admin, &config));
+    let subscription = || test::TestRequest::get().uri("/v1/billing/subscription").insert_header(bearer(&operator, &config));
+    let checkout = |user, plan: &str| {
+        test::TestRequest::post()
+            .uri("/v1/billing/checkout")
+            .insert_header(bearer(user, &config))
+            .set_json(json!({ "plan": plan }))
+    };
+
+    // Premium features wait for a subscription
+    let (status, _) = send(&app, erp()).await;
+    assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+    let (status, body) = send(&app, subscription()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["plan"], "free");
+    assert_eq!(body["status"], Value::Null);
+    assert_eq!(body["premium"], false);
+
+    let (status, body) = send(&app, test::TestRequest::get().uri("/v1/billing/plans").insert_header(bearer(&operator, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    let plans: Vec<(&str, bool)> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|plan| (plan["id"].as_str().unwrap(), plan["available"].as_bool().unwrap()))
+        .collect();
+    assert_eq!(plans, [("free", true), ("team", true), ("enterprise", false)]);
+
+    // Only admins check out, and only paid plans
+    let (status, _) = send(&app, checkout(&operator, "team")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = send(&app, checkout(&admin, "free")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["code"], "NOT_PAID");
+    let (status, body) = send(&app, checkout(&admin, "team")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["id"], "cs_test_forestry");
+    assert_eq!(body["url"], "https://checkout.stripe.com/c/pay/cs_test_forestry");
+
+    // Deliveries need a valid signature
+    let created = subscription_event("customer.subscription.created", organization.id, "sub_forestry", "active");
+    let (status, _) = send(&app, webhook(&created, "whsec_someone_else")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send(&app, test::TestRequest::post().uri("/v1/billing/webhook").set_payload(created.to_string())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let completed = json!({
+        "id": format!("evt_{}", Uuid::new_v4().simple()),
+        "type": "checkout.session.completed",
+        "data": { "object": {
+            "id": "cs_test_forestry",
+            "client_reference_id": organization.id.to_string(),
+            "customer": "cus_forestry",
+            "subscription": "sub_forestry",
+            "metadata": { "org_id": organization.id.to_string(), "plan": "team" }
+        } }
+    });
+    let (status, _) = send(&app, webhook(&completed, WEBHOOK_SECRET)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, body) = send(&app, subscription()).await;
+    assert_eq!(body["plan"], "free");
+    assert_eq!(body["status"], "incomplete");
+
+    // The active subscription opens premium features and the plan's quotas
+    let (status, _) = send(&app, webhook(&created, WEBHOOK_SECRET)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, body) = send(&app, subscription()).await;
+    assert_eq!(body["plan"], "team");
+    assert_eq!(body["status"], "active");
+    assert_eq!(body["premium"], true);
+    let (status, _) = send(&app, erp()).await;
+    assert_eq!(status, StatusCode::OK);
+    let usage_uri = format!("/v1/organizations/{}/usage", organization.id);
+    let (_, body) = send(&app, test::TestRequest::get().uri(&usage_uri).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(body["max_users"], 50);
+
+    let (status, body) = send(&app, checkout(&admin, "team")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["details"]["code"], "ALREADY_SUBSCRIBED");
+
+    // Canceling falls back to the free plan
+    let deleted = subscription_event("customer.subscription.deleted", organization.id, "sub_forestry", "canceled");
+    let (status, _) = send(&app, webhook(&deleted, WEBHOOK_SECRET)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, body) = send(&app, subscription()).await;
+    assert_eq!(body["plan"], "free");
+    assert_eq!(body["subscribed_plan"], "team");
+    assert_eq!(body["status"], "canceled");
+    let (status, _) = send(&app, erp()).await;
+    assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+    let (_, body) = send(&app, test::TestRequest::get().uri(&usage_uri).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(body["max_users"], 5);
+
+    // Redelivered events are applied once
+    let (status, _) = send(&app, webhook(&created, WEBHOOK_SECRET)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, body) = send(&app, subscription()).await;
+    assert_eq!(body["status"], "canceled");
+}
+
+#[actix_rt::test]
+async fn test_billing_disabled_leaves_features_open() {
+    setup();
+    let config = app_config();
+    let admin = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let as_admin = bearer(&admin, &config);
+
+    let (status, _) = send(&app, test::TestRequest::get().uri("/v1/erp/sources").insert_header(as_admin.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = send(
+        &app,
+        test::TestRequest::post()
+            .uri("/v1/billing/checkout")
+            .insert_header(as_admin)
+            .set_json(json!({ "plan": "team" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["details"]["code"], "BILLING_DISABLED");
+}
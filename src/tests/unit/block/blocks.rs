@@ -0,0 +1,363 @@
+use actix_web::{http::StatusCode, test};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use serde_json::json;
+
+use crate::{
+    api::{
+        resources::{
+            block::dto::{ListHarvestBlocksQuery, SaveHarvestBlockInput},
+            sales::dto::{CreateTenderInput, TenderParcelInput},
+            tag::dto::SaveTagInput,
+        },
+        utils::PaginationParams,
+    },
+    db::{
+        models::{auth::Role, BlockStatus, Boundary, SignoffStep, TagSubject},
+        repositories::{HarvestBlockRepositoryImpl, SignoffRepositoryImpl, TagRepositoryImpl, TimberSaleRepositoryImpl},
+        schema::queued_jobs,
+    },
+    domain::{
+        approval::ApprovalService,
+        block::HarvestBlockService,
+        organization::{QuotaService, Quotas},
+        sales::TimberSaleService,
+        tag::TagService,
+    },
+    error::{ErrorCode, Result},
+    jobs::events::{BLOCK_APPROVED, EVENT_JOB},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send, TestDb},
+        factories::{harvest_block::square, HarvestBlockFactory, OrganizationFactory, UserFactory},
+        setup,
+    },
+    utils::EventTransport,
+};
+
+fn input(block_number: &str, boundary: Boundary) -> SaveHarvestBlockInput {
+    SaveHarvestBlockInput {
+        license: " L-200 ".to_string(),
+        block_number: block_number.to_string(),
+        area_ha: 18.4,
+        planned_volume_m3: 3100.0,
+        status: None,
+        boundary,
+        notes: Some("  ".to_string()),
+        soil_sensitivity: None,
+    }
+}
+
+#[tokio::test]
+async fn test_block_is_stored_with_its_boundary() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = HarvestBlockService::new(HarvestBlockRepositoryImpl);
+            let config = app_config();
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let intruder = OrganizationFactory::new().create(conn).await?;
+
+            let block = service.create(conn, &config, organization.id, None, input("12", square(25.0, 61.0, 0.01))).await?;
+            assert_eq!((block.license.as_str(), block.block_number.as_str()), ("L-200", "12"));
+            assert_eq!(block.status, BlockStatus::Planned.as_str());
+            assert_eq!(block.boundary, square(25.0, 61.0, 0.01));
+            assert_eq!(block.notes, None);
+
+            let err = service.create(conn, &config, organization.id, None, input("12", square(26.0, 61.0, 0.01))).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+            // Numbers are unique within an organization's license only
+            service.create(conn, &config, intruder.id, None, input("12", square(25.0, 61.0, 0.01))).await?;
+
+            let mut open = input("13", square(25.0, 61.0, 0.01));
+            open.boundary = Boundary::Polygon { coordinates: vec![vec![[25.0, 61.0], [25.1, 61.0], [25.1, 61.1], [25.0, 61.1]]] };
+            let err = service.create(conn, &config, organization.id, None, open).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+            let mut empty = input("13", square(25.0, 61.0, 0.01));
+            empty.area_ha = 0.0;
+            let err = service.create(conn, &config, organization.id, None, empty).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let mut moved = input("12", square(25.5, 61.5, 0.02));
+            moved.notes = Some("Winter access only".to_string());
+            let updated = service.update(conn, &config, organization.id, block.id, None, moved).await?;
+            assert_eq!(updated.boundary, square(25.5, 61.5, 0.02));
+            assert_eq!(updated.notes.as_deref(), Some("Winter access only"));
+            assert_eq!(updated.created_at, block.created_at);
+
+            // Operability needs the soil sensitivity
+            let err = service.operability(conn, organization.id, block.id, &[10.0]).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            let err = service.get(conn, intruder.id, block.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            service.delete(conn, organization.id, block.id).await?;
+            let err = service.get(conn, organization.id, block.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_blocks_are_listed_by_license_status_and_area() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = HarvestBlockService::new(HarvestBlockRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let north = HarvestBlockFactory::new(&organization)
+                .block_number("2")
+                .boundary(square(25.0, 65.0, 0.01))
+                .create(conn)
+                .await?;
+            let south = HarvestBlockFactory::new(&organization)
+                .block_number("1")
+                .status(BlockStatus::Active)
+                .boundary(square(25.0, 61.0, 0.01))
+                .create(conn)
+                .await?;
+            HarvestBlockFactory::new(&organization).license("L-300").create(conn).await?;
+            let pagination = PaginationParams::new(1, 20);
+
+            let query = ListHarvestBlocksQuery { license: Some("L-100".to_string()), ..Default::default() };
+            let (blocks, total) = service.list(conn, organization.id, &query, &pagination).await?;
+            assert_eq!(blocks.iter().map(|block| block.id).collect::<Vec<_>>(), vec![south.id, north.id]);
+            assert_eq!(total.total, 2);
+
+            let query = ListHarvestBlocksQuery { status: Some("active".to_string()), ..Default::default() };
+            let (blocks, _) = service.list(conn, organization.id, &query, &pagination).await?;
+            assert_eq!(blocks.iter().map(|block| block.id).collect::<Vec<_>>(), vec![south.id]);
+
+            let query = ListHarvestBlocksQuery { bbox: Some("24.9,64.9,25.1,65.1".to_string()), ..Default::default() };
+            let (blocks, total) = service.list(conn, organization.id, &query, &pagination).await?;
+            assert_eq!(blocks.iter().map(|block| block.id).collect::<Vec<_>>(), vec![north.id]);
+            assert_eq!(total.total, 1);
+
+            let tags = TagService::new(TagRepositoryImpl);
+            let steep = tags.create(conn, organization.id, SaveTagInput { name: "Steep".to_string(), color: "#795548".to_string() }).await?;
+            let wet = tags.create(conn, organization.id, SaveTagInput { name: "Wet".to_string(), color: "#1565c0".to_string() }).await?;
+            tags.attach(conn, organization.id, steep.id, TagSubject::Block, north.id, None).await?;
+            tags.attach(conn, organization.id, wet.id, TagSubject::Block, north.id, None).await?;
+            tags.attach(conn, organization.id, wet.id, TagSubject::Block, south.id, None).await?;
+            let tagged = |tag_match: &str| ListHarvestBlocksQuery {
+                tags: Some(format!("{},{}", steep.id, wet.id)),
+                tag_match: Some(tag_match.to_string()),
+                ..Default::default()
+            };
+            let (blocks, total) = service.list(conn, organization.id, &tagged("any"), &pagination).await?;
+            assert_eq!(blocks.iter().map(|block| block.id).collect::<Vec<_>>(), vec![south.id, north.id]);
+            assert_eq!(total.total, 2);
+            let (blocks, total) = service.list(conn, organization.id, &tagged("all"), &pagination).await?;
+            assert_eq!(blocks.iter().map(|block| block.id).collect::<Vec<_>>(), vec![north.id]);
+            assert_eq!(total.total, 1);
+
+            let query = ListHarvestBlocksQuery { status: Some("felled".to_string()), ..Default::default() };
+            let err = service.list(conn, organization.id, &query, &pagination).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_block_moves_status_once_signed_off_and_within_quota() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = HarvestBlockService::new(HarvestBlockRepositoryImpl);
+            let config = app_config();
+            let approvals = ApprovalService::new(SignoffRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let planner = UserFactory::new().in_org(&organization).create(conn).await?;
+            let forester = UserFactory::new().role(Role::Manager).in_org(&organization).create(conn).await?;
+            let manager = UserFactory::new().role(Role::Manager).in_org(&organization).create(conn).await?;
+            let block = HarvestBlockFactory::new(&organization).create(conn).await?;
+            let activate = |status: &str| SaveHarvestBlockInput {
+                status: Some(status.to_string()),
+                ..input(&block.block_number, square(25.0, 61.0, 0.01))
+            };
+
+            let err = service.update(conn, &config, organization.id, block.id, None, activate("active")).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            let package = "ab".repeat(32);
+            approvals.sign(conn, organization.id, block.id, planner.id, SignoffStep::Planner, &package).await?;
+            approvals.sign(conn, organization.id, block.id, forester.id, SignoffStep::ProfessionalForester, &package).await?;
+            approvals.sign(conn, organization.id, block.id, manager.id, SignoffStep::OperationsManager, &package).await?;
+
+            // The plan allows one active block, and another one is active
+            HarvestBlockFactory::new(&organization).status(BlockStatus::Active).create(conn).await?;
+            QuotaService::set(conn, organization.id, Quotas { max_active_blocks: Some(1), ..Default::default() }).await?;
+            let err = service.update(conn, &config, organization.id, block.id, Some(manager.id), activate("active")).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::QuotaExceeded);
+
+            let approved = service.update(conn, &config, organization.id, block.id, Some(manager.id), activate("approved")).await?;
+            assert_eq!(approved.status, BlockStatus::Approved.as_str());
+            QuotaService::set(conn, organization.id, Quotas { max_active_blocks: Some(2), ..Default::default() }).await?;
+            let active = service.update(conn, &config, organization.id, block.id, Some(manager.id), activate("active")).await?;
+            assert_eq!(active.status, BlockStatus::Active.as_str());
+
+            // Other changes keep the status
+            let kept = service.update(conn, &config, organization.id, block.id, None, input(&block.block_number, square(25.0, 61.0, 0.02))).await?;
+            assert_eq!(kept.status, BlockStatus::Active.as_str());
+
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_block_approval_is_recorded_as_an_event() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = HarvestBlockService::new(HarvestBlockRepositoryImpl);
+            let approvals = ApprovalService::new(SignoffRepositoryImpl);
+            let mut config = app_config();
+            config.events.transport = EventTransport::Nats;
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let planner = UserFactory::new().in_org(&organization).create(conn).await?;
+            let forester = UserFactory::new().role(Role::Manager).in_org(&organization).create(conn).await?;
+            let manager = UserFactory::new().role(Role::Manager).in_org(&organization).create(conn).await?;
+            let block = HarvestBlockFactory::new(&organization).create(conn).await?;
+            let save = |status: &str| SaveHarvestBlockInput {
+                status: Some(status.to_string()),
+                ..input(&block.block_number, square(25.0, 61.0, 0.01))
+            };
+            let events = |conn: &mut PgConnection| -> Vec<serde_json::Value> {
+                queued_jobs::table
+                    .filter(queued_jobs::kind.eq(EVENT_JOB))
+                    .select(queued_jobs::payload)
+                    .load::<serde_json::Value>(conn)
+                    .expect("Failed to load the queued events")
+                    .into_iter()
+                    .filter(|event| event["organization_id"] == json!(organization.id))
+                    .collect()
+            };
+
+            let package = "cd".repeat(32);
+            approvals.sign(conn, organization.id, block.id, planner.id, SignoffStep::Planner, &package).await?;
+            approvals.sign(conn, organization.id, block.id, forester.id, SignoffStep::ProfessionalForester, &package).await?;
+            approvals.sign(conn, organization.id, block.id, manager.id, SignoffStep::OperationsManager, &package).await?;
+            service.update(conn, &config, organization.id, block.id, Some(manager.id), save("approved")).await?;
+            let recorded = events(conn);
+            assert_eq!(recorded.len(), 1);
+            assert_eq!(recorded[0]["type"], BLOCK_APPROVED.name);
+            assert_eq!(recorded[0]["data"]["block_id"], json!(block.id));
+            assert_eq!(recorded[0]["data"]["approved_by"], json!(manager.id));
+
+            // Only the move into approved is an event
+            service.update(conn, &config, organization.id, block.id, None, save("approved")).await?;
+            service.update(conn, &config, organization.id, block.id, None, save("active")).await?;
+            assert_eq!(events(conn).len(), 1);
+
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_block_offered_in_a_tender_is_kept() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = HarvestBlockService::new(HarvestBlockRepositoryImpl);
+            let sales = TimberSaleService::new(TimberSaleRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let intruder = OrganizationFactory::new().create(conn).await?;
+            let block = HarvestBlockFactory::new(&organization).create(conn).await?;
+            let tender = || CreateTenderInput {
+                title: "Spring sale".to_string(),
+                description: None,
+                bid_deadline: Utc::now() + Duration::days(7),
+                parcels: vec![TenderParcelInput {
+                    block_id: Some(block.id),
+                    assortment: "pine sawlog".to_string(),
+                    volume_m3: 800.0,
+                    reserve_price: None,
+                    description: None,
+                }],
+            };
+
+            // Only the organization's own blocks can be offered
+            let err = sales.create_tender(conn, intruder.id, None, tender()).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            sales.create_tender(conn, organization.id, None, tender()).await?;
+
+            let err = service.delete(conn, organization.id, block.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+            service.get(conn, organization.id, block.id).await?;
+
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[actix_rt::test]
+async fn test_blocks_are_managed_over_http() {
+    setup();
+    let config = app_config();
+    let (manager, operator) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let manager = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        (manager, operator)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let boundary = json!({
+        "type": "Polygon",
+        "coordinates": [[[25.0, 61.0], [25.01, 61.0], [25.01, 61.01], [25.0, 61.01], [25.0, 61.0]]],
+    });
+    let block = json!({
+        "license": "L-400",
+        "block_number": "7",
+        "area_ha": 9.5,
+        "planned_volume_m3": 1500.0,
+        "boundary": boundary,
+        "soil_sensitivity": "high",
+    });
+
+    let (status, body) = send(&app, test::TestRequest::post().uri("/v1/blocks").insert_header(bearer(&manager, &config)).set_json(&block)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["status"], "planned");
+    assert_eq!(body["boundary"], boundary);
+    let uri = format!("/v1/blocks/{}", body["id"].as_str().unwrap());
+
+    let (status, body) = send(&app, test::TestRequest::get().uri(&uri).insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["block_number"], "7");
+
+    assert_eq!(body["soil_sensitivity"], "high");
+
+    let operability = |rain: &str| test::TestRequest::get().uri(&format!("{}/operability?precipitation_mm={}", uri, rain)).insert_header(bearer(&manager, &config));
+    let (status, body) = send(&app, operability("12,8,4")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "caution");
+    assert_eq!(body["shutdown_mm"], 30.0);
+    let (status, body) = send(&app, operability("20,20")).await;
+    assert_eq!((status, body["status"].as_str()), (StatusCode::OK, Some("shutdown")));
+    let (status, _) = send(&app, operability("-2")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Boundaries are polygons
+    let mut point = block.clone();
+    point["boundary"] = json!({ "type": "Point", "coordinates": [25.0, 61.0] });
+    let (status, _) = send(&app, test::TestRequest::put().uri(&uri).insert_header(bearer(&manager, &config)).set_json(&point)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Operators hold no block permission by default
+    let (status, _) = send(&app, test::TestRequest::get().uri("/v1/blocks").insert_header(bearer(&operator, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&uri).insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
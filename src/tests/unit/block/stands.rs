@@ -0,0 +1,284 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+
+use crate::{
+    api::resources::{
+        stand::dto::{BulkUpsertStandsInput, SaveStandInput},
+        tag::SaveTagInput,
+    },
+    db::{
+        models::{auth::Role, SpeciesShare, TagSubject},
+        repositories::{HarvestBlockRepository, HarvestBlockRepositoryImpl, StandRepositoryImpl, TagRepositoryImpl},
+    },
+    domain::{
+        stand::StandService,
+        tag::{TagFilter, TagService},
+        windthrow::{Exposure, RiskClass, SoilRooting, StandConditions},
+    },
+    error::{ErrorCode, Result},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send, TestDb},
+        factories::{HarvestBlockFactory, OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+fn share(species: &str, percent: f64) -> SpeciesShare {
+    SpeciesShare { species: species.to_string(), percent }
+}
+
+fn input(stand_number: &str, volume_m3: f64, species: Vec<SpeciesShare>) -> SaveStandInput {
+    SaveStandInput {
+        stand_number: stand_number.to_string(),
+        area_ha: 5.0,
+        species,
+        age_class: 6,
+        site_index: 22.5,
+        net_merchantable_volume_m3: volume_m3,
+        inventoried_on: None,
+        windthrow_conditions: None,
+    }
+}
+
+#[tokio::test]
+async fn test_stand_is_kept_under_its_block() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = StandService::new(StandRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let intruder = OrganizationFactory::new().create(conn).await?;
+            let block = HarvestBlockFactory::new(&organization).create(conn).await?;
+            let other = HarvestBlockFactory::new(&organization).create(conn).await?;
+
+            let stand = service.create(conn, organization.id, block.id, input(" 3a ", 900.0, vec![share(" spruce ", 100.0)])).await?;
+            assert_eq!(stand.stand_number, "3a");
+            assert_eq!(stand.species(), vec![share("spruce", 100.0)]);
+
+            let err = service.create(conn, organization.id, block.id, input("3a", 100.0, vec![share("pine", 100.0)])).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+            // Numbers are unique within a block only
+            service.create(conn, organization.id, other.id, input("3a", 100.0, vec![share("pine", 100.0)])).await?;
+
+            let err = service.create(conn, intruder.id, block.id, input("4", 100.0, vec![share("pine", 100.0)])).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            let err = service.get(conn, intruder.id, block.id, stand.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            let err = service.get(conn, organization.id, other.id, stand.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            let updated = service
+                .update(conn, organization.id, block.id, stand.id, input("3a", 950.0, vec![share("spruce", 70.0), share("birch", 30.0)]))
+                .await?;
+            assert_eq!((updated.id, updated.created_at), (stand.id, stand.created_at));
+            assert_eq!(updated.net_merchantable_volume_m3, 950.0);
+            assert_eq!(updated.species().len(), 2);
+
+            // Stands go with their block
+            HarvestBlockRepositoryImpl.delete(conn, organization.id, block.id).await?;
+            let err = service.get(conn, organization.id, block.id, stand.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_inventory_upserts_stands_by_number() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = StandService::new(StandRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let block = HarvestBlockFactory::new(&organization).create(conn).await?;
+
+            let first = BulkUpsertStandsInput {
+                stands: vec![input("2", 400.0, vec![share("pine", 100.0)]), input("1", 600.0, vec![share("spruce", 100.0)])],
+            };
+            let (stands, created) = service.upsert(conn, organization.id, block.id, first).await?;
+            assert_eq!(created, 2);
+            assert_eq!(stands.iter().map(|stand| stand.stand_number.as_str()).collect::<Vec<_>>(), ["1", "2"]);
+
+            let recruised = BulkUpsertStandsInput {
+                stands: vec![input("1", 650.0, vec![share("spruce", 100.0)]), input("3", 200.0, vec![share("birch", 100.0)])],
+            };
+            let (saved, created) = service.upsert(conn, organization.id, block.id, recruised).await?;
+            assert_eq!((saved.len(), created), (2, 1));
+            // Replaced stands keep their id
+            assert_eq!(saved[0].id, stands[0].id);
+            assert_eq!(saved[0].net_merchantable_volume_m3, 650.0);
+
+            // One invalid stand saves none of them
+            let mut invalid = input("5", 100.0, vec![share("pine", 100.0)]);
+            invalid.age_class = 0;
+            let batch = BulkUpsertStandsInput { stands: vec![input("4", 100.0, vec![share("pine", 100.0)]), invalid] };
+            let err = service.upsert(conn, organization.id, block.id, batch).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let stands = service.list(conn, organization.id, block.id, None).await?;
+            assert_eq!(stands.iter().map(|stand| stand.stand_number.as_str()).collect::<Vec<_>>(), ["1", "2", "3"]);
+
+            // Listed by tag
+            let tags = TagService::new(TagRepositoryImpl);
+            let steep = tags.create(conn, organization.id, SaveTagInput { name: "Steep".to_string(), color: "#795548".to_string() }).await?;
+            let wet = tags.create(conn, organization.id, SaveTagInput { name: "Wet".to_string(), color: "#1565c0".to_string() }).await?;
+            tags.attach(conn, organization.id, steep.id, TagSubject::Stand, stands[0].id, None).await?;
+            tags.attach(conn, organization.id, steep.id, TagSubject::Stand, stands[2].id, None).await?;
+            tags.attach(conn, organization.id, wet.id, TagSubject::Stand, stands[2].id, None).await?;
+            let tagged = |tag_match: &str| TagFilter::parse(Some(&format!("{},{}", steep.id, wet.id)), Some(tag_match)).unwrap();
+            let listed = service.list(conn, organization.id, block.id, tagged("any").as_ref()).await?;
+            assert_eq!(listed.iter().map(|stand| stand.stand_number.as_str()).collect::<Vec<_>>(), ["1", "3"]);
+            let listed = service.list(conn, organization.id, block.id, tagged("all").as_ref()).await?;
+            assert_eq!(listed.iter().map(|stand| stand.stand_number.as_str()).collect::<Vec<_>>(), ["3"]);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_inventory_scores_windthrow_risk() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = StandService::new(StandRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let block = HarvestBlockFactory::new(&organization).create(conn).await?;
+            let conditions = StandConditions {
+                mean_height_m: 20.0,
+                stems_per_ha: 500.0,
+                soil: SoilRooting::Moderate,
+                exposure: Exposure::Exposed,
+                new_edge: false,
+            };
+
+            let mut scored = input("1", 600.0, vec![share("spruce", 100.0)]);
+            scored.windthrow_conditions = Some(conditions);
+            let batch = BulkUpsertStandsInput { stands: vec![scored.clone(), input("2", 400.0, vec![share("pine", 100.0)])] };
+            let (stands, _) = service.upsert(conn, organization.id, block.id, batch).await?;
+            assert_eq!(stands[0].windthrow_score, Some(44.8));
+            assert_eq!(stands[0].windthrow_class(), Some(RiskClass::Moderate));
+            assert_eq!(stands[0].windthrow_conditions(), Some(conditions));
+            // Stands without conditions go unscored
+            assert_eq!((stands[1].windthrow_score, stands[1].windthrow_class()), (None, None));
+
+            // A harvest next to the stand rescores it when it is sent again
+            scored.windthrow_conditions = Some(StandConditions { new_edge: true, ..conditions });
+            let (saved, created) = service.upsert(conn, organization.id, block.id, BulkUpsertStandsInput { stands: vec![scored] }).await?;
+            assert_eq!(created, 0);
+            assert!(saved[0].windthrow_score > Some(44.8));
+            assert_eq!(saved[0].windthrow_class(), Some(RiskClass::High));
+
+            let mut invalid = input("3", 100.0, vec![share("pine", 100.0)]);
+            invalid.windthrow_conditions = Some(StandConditions { stems_per_ha: -1.0, ..conditions });
+            let err = service.upsert(conn, organization.id, block.id, BulkUpsertStandsInput { stands: vec![invalid] }).await.unwrap_err();
+            assert_eq!(err.context.details.unwrap()["field"], "stands[0].windthrow_conditions.stems_per_ha");
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_block_volume_rolls_up_by_species() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = StandService::new(StandRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let block = HarvestBlockFactory::new(&organization).create(conn).await?;
+
+            let empty = service.summary(conn, organization.id, block.id).await?;
+            assert_eq!((empty.stand_count, empty.net_merchantable_volume_m3, empty.volume_per_ha), (0, 0.0, 0.0));
+            assert_eq!(empty.planned_volume_m3, block.planned_volume_m3);
+
+            let stands = BulkUpsertStandsInput {
+                stands: vec![
+                    input("1", 1000.0, vec![share("spruce", 60.0), share("pine", 40.0)]),
+                    input("2", 500.0, vec![share("pine", 80.0), share("birch", 20.0)]),
+                ],
+            };
+            service.upsert(conn, organization.id, block.id, stands).await?;
+
+            let summary = service.summary(conn, organization.id, block.id).await?;
+            assert_eq!(summary.stand_count, 2);
+            assert_eq!(summary.area_ha, 10.0);
+            assert_eq!(summary.net_merchantable_volume_m3, 1500.0);
+            assert_eq!(summary.volume_per_ha, 150.0);
+            let species: Vec<(&str, f64)> = summary
+                .species
+                .iter()
+                .map(|volume| (volume.species.as_str(), volume.volume_m3.round()))
+                .collect();
+            assert_eq!(species, [("pine", 800.0), ("spruce", 600.0), ("birch", 100.0)]);
+            assert_eq!(summary.species[1].percent.round(), 40.0);
+
+            let intruder = OrganizationFactory::new().create(conn).await?;
+            let err = service.summary(conn, intruder.id, block.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[actix_rt::test]
+async fn test_stands_are_managed_over_http() {
+    setup();
+    let config = app_config();
+    let (manager, operator, block) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let manager = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        let block = HarvestBlockFactory::new(&organization).create(&mut conn).await.unwrap();
+        (manager, operator, block)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let uri = format!("/v1/blocks/{}/stands", block.id);
+    let stand = |number: &str, volume: f64| {
+        json!({
+            "stand_number": number,
+            "area_ha": 4.0,
+            "species": [{ "species": "spruce", "percent": 100 }],
+            "age_class": 7,
+            "site_index": 19.5,
+            "net_merchantable_volume_m3": volume,
+            "inventoried_on": "2024-09-12",
+        })
+    };
+
+    let bulk = json!({ "stands": [stand("1", 800.0), stand("2", 400.0)] });
+    let (status, body) = send(&app, test::TestRequest::post().uri(&format!("{}/bulk", uri)).insert_header(bearer(&manager, &config)).set_json(&bulk)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!((body["created"].as_i64(), body["updated"].as_i64()), (Some(2), Some(0)));
+    let stand_uri = format!("{}/{}", uri, body["stands"][0]["id"].as_str().unwrap());
+
+    let (status, body) = send(&app, test::TestRequest::get().uri(&uri).insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().map(Vec::len), Some(2));
+
+    let (status, body) = send(&app, test::TestRequest::get().uri(&format!("{}/summary", uri)).insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["net_merchantable_volume_m3"], 1200.0);
+    assert_eq!(body["species"][0]["species"], "spruce");
+
+    let (status, body) = send(&app, test::TestRequest::get().uri(&stand_uri).insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["inventoried_on"], "2024-09-12");
+
+    // Stand numbers can't repeat within a request
+    let bulk = json!({ "stands": [stand("3", 100.0), stand("3", 200.0)] });
+    let (status, body) = send(&app, test::TestRequest::post().uri(&format!("{}/bulk", uri)).insert_header(bearer(&manager, &config)).set_json(&bulk)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "stands[1].stand_number");
+
+    // Operators hold no block permission by default
+    let (status, _) = send(&app, test::TestRequest::get().uri(&uri).insert_header(bearer(&operator, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&stand_uri).insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
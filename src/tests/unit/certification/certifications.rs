@@ -0,0 +1,277 @@
+use actix_web::{http::StatusCode, test};
+use chrono::{Duration, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    api::resources::certification::dto::SaveCertificationInput,
+    db::{
+        models::{auth::Role, Notification},
+        repositories::{CertificationRepositoryImpl, DocumentRepositoryImpl},
+        schema::notifications,
+    },
+    domain::{
+        certification::{CertificationService, EXPIRY_NOTIFICATION_KIND},
+        document::DocumentService,
+    },
+    error::{ErrorCode, Result},
+    infrastructure::ObjectStorage,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, TestDb},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+fn service() -> CertificationService<CertificationRepositoryImpl> {
+    CertificationService::new(
+        CertificationRepositoryImpl,
+        DocumentService::new(DocumentRepositoryImpl, ObjectStorage::in_memory()),
+    )
+}
+
+fn date(value: &str) -> NaiveDate {
+    value.parse().unwrap()
+}
+
+fn input(user_id: Uuid, kind: &str, issued_on: &str, expires_on: Option<&str>) -> SaveCertificationInput {
+    SaveCertificationInput {
+        user_id,
+        kind: kind.to_string(),
+        name: None,
+        certificate_number: None,
+        issuer: None,
+        issued_on: date(issued_on),
+        expires_on: expires_on.map(date),
+    }
+}
+
+/// The expiry notifications `user_id` received
+fn expiry_notifications(conn: &mut PgConnection, user_id: Uuid) -> Vec<Notification> {
+    notifications::table
+        .filter(notifications::user_id.eq(user_id))
+        .filter(notifications::kind.eq(EXPIRY_NOTIFICATION_KIND))
+        .select(Notification::as_select())
+        .load(conn)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_supervisors_are_notified_once_before_expiry() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = service();
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let operator = UserFactory::new().in_org(&organization).first_name("Olga").last_name("Berg").create(conn).await?;
+            let manager = UserFactory::new().in_org(&organization).role(Role::Manager).create(conn).await?;
+            let admin = UserFactory::new().in_org(&organization).role(Role::Admin).create(conn).await?;
+            let away = UserFactory::new().in_org(&organization).role(Role::Manager).deactivated().create(conn).await?;
+            let outsider = UserFactory::new().role(Role::Manager).create(conn).await?;
+
+            // Far from today, so certifications of other tests aren't due
+            let today = date("2090-06-01");
+            let faller = service
+                .create(conn, organization.id, Some(manager.id), input(operator.id, "faller", "2087-06-20", Some("2090-06-20")))
+                .await?;
+            // Later than the notice period, already expired, or not expiring
+            service.create(conn, organization.id, None, input(operator.id, "first_aid", "2088-01-01", Some("2090-08-01"))).await?;
+            service.create(conn, organization.id, None, input(operator.id, "dangerous_goods", "2088-01-01", Some("2090-05-01"))).await?;
+            service.create(conn, organization.id, None, input(manager.id, "first_aid", "2088-01-01", None)).await?;
+
+            assert_eq!(service.notify_expiring(conn, today).await?, 1);
+            for supervisor in [&manager, &admin] {
+                let received = expiry_notifications(conn, supervisor.id);
+                assert_eq!(received.len(), 1);
+                assert_eq!(received[0].title, "Olga Berg's Faller certification expires on 2090-06-20");
+                assert_eq!(received[0].body.as_deref(), Some("Expires in 19 days."));
+                assert_eq!(received[0].data.as_ref().unwrap()["certification_id"], json!(faller.id));
+            }
+            for user in [&operator, &away, &outsider] {
+                assert!(expiry_notifications(conn, user.id).is_empty());
+            }
+
+            // Each certification is notified once
+            assert_eq!(service.notify_expiring(conn, today + Duration::days(1)).await?, 0);
+
+            // until it is renewed
+            let renewed = service
+                .update(conn, organization.id, faller.id, input(operator.id, "faller", "2090-06-10", Some("2090-06-25")))
+                .await?;
+            assert!(renewed.expiry_notified_at.is_none());
+            assert_eq!(service.notify_expiring(conn, today).await?, 1);
+            assert_eq!(expiry_notifications(conn, manager.id).len(), 2);
+
+            let expiring = service.expiring(conn, organization.id, None, today, 30).await?;
+            let kinds: Vec<&str> = expiring.iter().map(|(certification, _)| certification.kind.as_str()).collect();
+            assert_eq!(kinds, ["dangerous_goods", "faller"]);
+            assert_eq!(expiring[1].1.id, operator.id);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_only_the_holders_supervisor_is_notified() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = service();
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let manager = UserFactory::new().in_org(&organization).role(Role::Manager).create(conn).await?;
+            let other_manager = UserFactory::new().in_org(&organization).role(Role::Manager).create(conn).await?;
+            let operator = UserFactory::new().in_org(&organization).reports_to(&manager).create(conn).await?;
+            let away = UserFactory::new().in_org(&organization).role(Role::Manager).deactivated().create(conn).await?;
+            let stranded = UserFactory::new().in_org(&organization).reports_to(&away).create(conn).await?;
+
+            // Far from today, so certifications of other tests aren't due
+            let today = date("2091-06-01");
+            service.create(conn, organization.id, None, input(operator.id, "faller", "2088-06-20", Some("2091-06-20"))).await?;
+            service.create(conn, organization.id, None, input(stranded.id, "faller", "2088-06-20", Some("2091-06-21"))).await?;
+
+            assert_eq!(service.notify_expiring(conn, today).await?, 2);
+            // A deactivated supervisor leaves every manager to be told
+            assert_eq!(expiry_notifications(conn, manager.id).len(), 2);
+            assert_eq!(expiry_notifications(conn, other_manager.id).len(), 1);
+            assert!(expiry_notifications(conn, away.id).is_empty());
+
+            let crew = service.list(conn, organization.id, None, Some(manager.id), None).await?;
+            assert_eq!(crew.len(), 1);
+            assert_eq!(crew[0].user_id, operator.id);
+            let expiring = service.expiring(conn, organization.id, Some(away.id), today, 30).await?;
+            assert_eq!(expiring[0].1.id, stranded.id);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_certifications_are_checked() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = service();
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let operator = UserFactory::new().in_org(&organization).create(conn).await?;
+            let outsider = UserFactory::new().create(conn).await?;
+
+            let err = service.create(conn, organization.id, None, input(operator.id, "chainsaw", "2024-01-01", None)).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+            let err = service.create(conn, organization.id, None, input(operator.id, "other", "2024-01-01", None)).await.unwrap_err();
+            assert_eq!(err.context.details.unwrap()["field"], "name");
+            let err = service
+                .create(conn, organization.id, None, input(operator.id, "faller", "2024-01-01", Some("2023-12-31")))
+                .await
+                .unwrap_err();
+            assert_eq!(err.context.details.unwrap()["code"], "BEFORE_ISSUED");
+            let err = service.create(conn, organization.id, None, input(outsider.id, "faller", "2024-01-01", None)).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            let crane = SaveCertificationInput {
+                name: Some(" Crane operator ".to_string()),
+                ..input(operator.id, "other", "2024-01-01", Some("2029-01-01"))
+            };
+            let crane = service.create(conn, organization.id, None, crane).await?;
+            assert_eq!(crane.title(), "Crane operator");
+            service.create(conn, organization.id, None, input(operator.id, "first_aid", "2024-01-01", Some("2026-01-01"))).await?;
+
+            let listed = service.list(conn, organization.id, Some(operator.id), None, None).await?;
+            assert_eq!(listed.len(), 2);
+            assert_eq!(listed[0].kind, "first_aid");
+            assert_eq!(service.list(conn, organization.id, None, None, Some("other")).await?.len(), 1);
+            assert!(service.list(conn, organization.id, None, None, Some("bogus")).await.is_err());
+            let err = service.expiring(conn, organization.id, None, date("2025-01-01"), 366).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[actix_rt::test]
+async fn test_certifications_api() {
+    setup();
+    let config = app_config();
+    let (manager, operator) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let manager = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).verified().create(&mut conn).await.unwrap();
+        (manager, operator)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let as_manager = bearer(&manager, &config);
+    let today = Utc::now().date_naive();
+
+    let response = test::call_service(&app, test::TestRequest::post()
+        .uri("/v1/certifications")
+        .insert_header(as_manager.clone())
+        .set_json(json!({
+            "user_id": operator.id,
+            "kind": "faller",
+            "certificate_number": "F-1042",
+            "issued_on": today - Duration::days(1000),
+            "expires_on": today + Duration::days(10),
+        }))
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: Value = test::read_body_json(response).await;
+    let id = body["id"].as_str().unwrap().to_string();
+    assert_eq!(body["title"], "Faller certification");
+    assert_eq!(body["created_by"], json!(manager.id));
+
+    // The scanned certificate is attached through the documents API
+    let response = test::call_service(&app, test::TestRequest::post()
+        .uri(&format!("/v1/documents?subject_type=certification&subject_id={}&category=certificate&filename=faller.pdf", id))
+        .insert_header(as_manager.clone())
+        .insert_header(("Content-Type", "application/pdf"))
+        .set_payload("%PDF-1.4")
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = test::call_service(&app, test::TestRequest::get()
+        .uri(&format!("/v1/certifications/{}", id))
+        .insert_header(as_manager.clone())
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["certification"]["certificate_number"], "F-1042");
+    assert_eq!(body["documents"][0]["title"], "faller.pdf");
+
+    let response = test::call_service(&app, test::TestRequest::get()
+        .uri("/v1/certifications/expiring?days=30")
+        .insert_header(as_manager.clone())
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["data"][0]["certification"]["id"], id.as_str());
+    assert_eq!(body["data"][0]["days_left"], 10);
+    assert_eq!(body["data"][0]["email"], operator.email.as_str());
+
+    // Operators don't hold the certifications permissions
+    let operator_bearer = bearer(&operator, &config);
+    let status = match test::try_call_service(&app, test::TestRequest::get()
+        .uri("/v1/certifications")
+        .insert_header(operator_bearer)
+        .to_request()).await {
+        Ok(response) => response.status(),
+        Err(error) => error.error_response().status(),
+    };
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let response = test::call_service(&app, test::TestRequest::delete()
+        .uri(&format!("/v1/certifications/{}", id))
+        .insert_header(as_manager.clone())
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = test::call_service(&app, test::TestRequest::get()
+        .uri(&format!("/v1/documents?subject_type=certification&subject_id={}", id))
+        .insert_header(as_manager)
+        .to_request()).await;
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["data"], json!([]));
+}
@@ -0,0 +1,122 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+
+use crate::{
+    api::resources::crew::dto::{CreateCrewInput, UpdateCrewInput},
+    db::{models::auth::Role, repositories::CrewRepositoryImpl},
+    domain::crew::{CrewRoster, CrewService},
+    error::{ErrorCode, Result},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send, TestDb},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[tokio::test]
+async fn test_crew_is_kept_with_its_members() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = CrewService::new(CrewRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let intruder = OrganizationFactory::new().create(conn).await?;
+            let supervisor = UserFactory::new().in_org(&organization).role(Role::Manager).create(conn).await?;
+            let faller = UserFactory::new().in_org(&organization).last_name("Virtanen").create(conn).await?;
+            let operator = UserFactory::new().in_org(&organization).last_name("Korhonen").create(conn).await?;
+            let outsider = UserFactory::new().in_org(&intruder).create(conn).await?;
+            let input = |name: &str, member_ids| CreateCrewInput {
+                name: name.to_string(),
+                supervisor_id: Some(supervisor.id),
+                default_equipment: vec![" HRV-2 ".to_string(), "FWD-7".to_string()],
+                member_ids,
+            };
+
+            let roster = service.create(conn, organization.id, input(" North crew ", vec![faller.id, operator.id, faller.id])).await?;
+            assert_eq!(roster.crew.name, "North crew");
+            assert_eq!(roster.crew.default_equipment, vec!["HRV-2", "FWD-7"]);
+            let members: Vec<_> = roster.members.iter().map(|(_, user)| user.id).collect();
+            assert_eq!(members, vec![operator.id, faller.id]);
+            let crew_id = roster.crew.id;
+
+            let err = service.create(conn, organization.id, input("North crew", vec![])).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+            // Members come from the crew's organization
+            let err = service.create(conn, organization.id, input("South crew", vec![outsider.id])).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            let err = service.get(conn, intruder.id, crew_id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            // Supervisors and members find their crews
+            service.create(conn, organization.id, CreateCrewInput { supervisor_id: None, ..input("South crew", vec![operator.id]) }).await?;
+            let names = |crews: Vec<CrewRoster>| crews.into_iter().map(|roster| roster.crew.name).collect::<Vec<_>>();
+            assert_eq!(names(service.list(conn, organization.id, None).await?), vec!["North crew", "South crew"]);
+            assert_eq!(names(service.list(conn, organization.id, Some(supervisor.id)).await?), vec!["North crew"]);
+            assert_eq!(names(service.list(conn, organization.id, Some(operator.id)).await?), vec!["North crew", "South crew"]);
+            assert!(service.list(conn, intruder.id, None).await?.is_empty());
+
+            let update = UpdateCrewInput { name: "North crew 2".to_string(), supervisor_id: None, default_equipment: vec![] };
+            let updated = service.update(conn, organization.id, crew_id, update).await?;
+            assert_eq!((updated.crew.supervisor_id, updated.members.len()), (None, 2));
+
+            service.remove_member(conn, organization.id, crew_id, faller.id).await?;
+            let err = service.remove_member(conn, organization.id, crew_id, faller.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            service.add_member(conn, organization.id, crew_id, faller.id).await?;
+            let roster = service.add_member(conn, organization.id, crew_id, faller.id).await?;
+            assert_eq!(roster.members.len(), 2);
+            let err = service.add_member(conn, organization.id, crew_id, outsider.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            service.delete(conn, organization.id, crew_id).await?;
+            let err = service.get(conn, organization.id, crew_id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[actix_rt::test]
+async fn test_crews_are_managed_over_http() {
+    setup();
+    let config = app_config();
+    let (manager, operator) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let manager = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        (manager, operator)
+    };
+    let app = test::init_service(server::app(&config)).await;
+
+    let crew = json!({ "name": "Crew 4", "supervisor_id": manager.id, "default_equipment": ["HRV-2"] });
+    let (status, body) = send(&app, test::TestRequest::post().uri("/v1/crews").insert_header(bearer(&manager, &config)).set_json(&crew)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["members"], json!([]));
+    let crew_uri = format!("/v1/crews/{}", body["id"].as_str().unwrap());
+
+    let member = json!({ "user_id": operator.id });
+    let (status, body) = send(&app, test::TestRequest::post().uri(&format!("{}/members", crew_uri)).insert_header(bearer(&manager, &config)).set_json(&member)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["members"][0]["user_id"], operator.id.to_string());
+
+    let (status, body) = send(&app, test::TestRequest::get().uri(&format!("/v1/crews?user_id={}", operator.id)).insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"][0]["default_equipment"], json!(["HRV-2"]));
+
+    let update = json!({ "name": "Crew 4", "default_equipment": ["HRV-2", "HRV-2"] });
+    let (status, body) = send(&app, test::TestRequest::put().uri(&crew_uri).insert_header(bearer(&manager, &config)).set_json(&update)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "default_equipment[1]");
+
+    // Operators hold no crew permission by default
+    let (status, _) = send(&app, test::TestRequest::get().uri("/v1/crews").insert_header(bearer(&operator, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&format!("{}/members/{}", crew_uri, operator.id)).insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&crew_uri).insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
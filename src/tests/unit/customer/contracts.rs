@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use diesel::{
+    prelude::*,
+    sql_types::{Date, Double, Text, Uuid as SqlUuid},
+};
+use uuid::Uuid;
+
+use crate::{
+    api::resources::customer::dto::{SaveCustomerInput, SaveSupplyContractInput},
+    db::repositories::CustomerRepositoryImpl,
+    domain::customer::{CustomerService, DeliveryLedger},
+    error::{ErrorCode, Result},
+    tests::{common::helpers::TestDb, factories::OrganizationFactory, setup},
+};
+
+/// Ledger backed by a temporary table that only lives for the test transaction
+struct DeliveriesLedger;
+
+#[derive(QueryableByName)]
+struct Delivered {
+    #[diesel(sql_type = Double)]
+    volume_m3: f64,
+}
+
+impl DeliveriesLedger {
+    fn create_table(conn: &mut PgConnection) -> QueryResult<usize> {
+        diesel::sql_query(
+            "CREATE TEMP TABLE customer_test_deliveries (
+                org_id UUID NOT NULL,
+                customer_id UUID NOT NULL,
+                product TEXT NOT NULL,
+                volume_m3 DOUBLE PRECISION NOT NULL,
+                delivered_on DATE NOT NULL
+            ) ON COMMIT DROP",
+        )
+        .execute(conn)
+    }
+
+    fn insert(conn: &mut PgConnection, org_id: Uuid, customer_id: Uuid, product: &str, volume_m3: f64, delivered_on: NaiveDate) {
+        diesel::sql_query(
+            "INSERT INTO customer_test_deliveries (org_id, customer_id, product, volume_m3, delivered_on)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind::<SqlUuid, _>(org_id)
+        .bind::<SqlUuid, _>(customer_id)
+        .bind::<Text, _>(product)
+        .bind::<Double, _>(volume_m3)
+        .bind::<Date, _>(delivered_on)
+        .execute(conn)
+        .unwrap();
+    }
+}
+
+impl DeliveryLedger for DeliveriesLedger {
+    fn delivered(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        customer_id: Uuid,
+        product: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> QueryResult<f64> {
+        diesel::sql_query(
+            "SELECT COALESCE(SUM(volume_m3), 0) AS volume_m3 FROM customer_test_deliveries
+             WHERE org_id = $1 AND customer_id = $2 AND product = $3 AND delivered_on BETWEEN $4 AND $5",
+        )
+        .bind::<SqlUuid, _>(org_id)
+        .bind::<SqlUuid, _>(customer_id)
+        .bind::<Text, _>(product)
+        .bind::<Date, _>(from)
+        .bind::<Date, _>(to)
+        .get_result::<Delivered>(conn)
+        .map(|delivered| delivered.volume_m3)
+    }
+}
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+}
+
+fn customer(name: &str) -> SaveCustomerInput {
+    SaveCustomerInput {
+        name: name.to_string(),
+        contact_name: Some("Anna Berg".to_string()),
+        email: Some("orders@northmill.example".to_string()),
+        phone_number: None,
+        address: None,
+        notes: None,
+    }
+}
+
+fn contract(reference: &str, starts_on: NaiveDate, ends_on: NaiveDate) -> SaveSupplyContractInput {
+    SaveSupplyContractInput {
+        reference: reference.to_string(),
+        product: "spruce sawlog".to_string(),
+        volume_m3: 1000.0,
+        price_per_m3: 62.0,
+        starts_on,
+        ends_on,
+        notes: None,
+    }
+}
+
+#[tokio::test]
+async fn test_commitment_follows_deliveries() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            DeliveriesLedger::create_table(conn).unwrap();
+            let service = CustomerService::with_ledger(CustomerRepositoryImpl, Arc::new(DeliveriesLedger));
+            let organization = OrganizationFactory::new().create(conn).await?;
+
+            let mill = service.create_customer(conn, organization.id, customer("North Mill")).await?;
+            let err = service.create_customer(conn, organization.id, customer("North Mill")).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            let err = service
+                .create_contract(conn, organization.id, mill.id, None, contract("SC-1", date(1, 10), date(1, 1)))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let supply = service
+                .create_contract(conn, organization.id, mill.id, None, contract("SC-1", date(1, 1), date(1, 10)))
+                .await?;
+            let err = service
+                .create_contract(conn, organization.id, mill.id, None, contract("SC-1", date(2, 1), date(2, 10)))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            // Only deliveries of the product within the period count
+            DeliveriesLedger::insert(conn, organization.id, mill.id, "spruce sawlog", 200.0, date(1, 2));
+            DeliveriesLedger::insert(conn, organization.id, mill.id, "pine pulp", 300.0, date(1, 3));
+            DeliveriesLedger::insert(conn, organization.id, mill.id, "spruce sawlog", 500.0, date(1, 20));
+
+            let (_, progress) = service.commitment(conn, organization.id, supply.id, date(1, 4)).await?;
+            assert_eq!(progress.delivered_m3, 200.0);
+            assert_eq!((progress.projected_m3, progress.shortfall_m3), (500.0, 500.0));
+            assert!(progress.at_risk);
+
+            DeliveriesLedger::insert(conn, organization.id, mill.id, "spruce sawlog", 200.0, date(1, 3));
+            let commitments = service.commitments(conn, organization.id, date(1, 4)).await?;
+            assert_eq!(commitments.len(), 1);
+            let (listed, progress) = &commitments[0];
+            assert_eq!(listed.id, supply.id);
+            assert_eq!((progress.delivered_m3, progress.projected_m3), (400.0, 1000.0));
+            assert!(!progress.at_risk);
+
+            assert!(service.commitments(conn, organization.id, date(2, 1)).await?.is_empty());
+
+            // Customers are only deleted once their contracts are
+            let err = service.delete_customer(conn, organization.id, mill.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+            service.delete_contract(conn, organization.id, supply.id).await?;
+            service.delete_customer(conn, organization.id, mill.id).await?;
+
+            Ok(())
+        })
+    })
+    .await
+}
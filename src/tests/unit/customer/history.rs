@@ -0,0 +1,84 @@
+use chrono::NaiveDate;
+use serde_json::json;
+
+use crate::{
+    api::resources::customer::dto::{SaveCustomerInput, SaveSupplyContractInput},
+    db::repositories::CustomerRepositoryImpl,
+    domain::customer::CustomerService,
+    error::{ErrorCode, Result},
+    tests::{
+        common::helpers::TestDb,
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+fn contract(volume_m3: f64, notes: Option<&str>) -> SaveSupplyContractInput {
+    SaveSupplyContractInput {
+        reference: "SC-7".to_string(),
+        product: "pine pulpwood".to_string(),
+        volume_m3,
+        price_per_m3: 31.5,
+        starts_on: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        ends_on: NaiveDate::from_ymd_opt(2025, 6, 30).unwrap(),
+        notes: notes.map(str::to_string),
+    }
+}
+
+#[tokio::test]
+async fn test_updates_record_field_changes() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = CustomerService::new(CustomerRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let planner = UserFactory::new().first_name("Anna").last_name("Berg").in_org(&organization).create(conn).await?;
+            let customer = service
+                .create_customer(conn, organization.id, SaveCustomerInput {
+                    name: "Pulp Co".to_string(),
+                    contact_name: None,
+                    email: None,
+                    phone_number: None,
+                    address: None,
+                    notes: None,
+                })
+                .await?;
+            let supply = service
+                .create_contract(conn, organization.id, customer.id, None, contract(800.0, None))
+                .await?;
+            assert!(service.contract_history(conn, organization.id, supply.id).await?.is_empty());
+
+            service
+                .update_contract(conn, organization.id, supply.id, Some(planner.id), contract(950.0, Some("Storm salvage")))
+                .await?;
+            // Saving the same terms again changes nothing
+            service
+                .update_contract(conn, organization.id, supply.id, None, contract(950.0, Some("Storm salvage")))
+                .await?;
+            service
+                .update_contract(conn, organization.id, supply.id, None, contract(900.0, Some("Storm salvage")))
+                .await?;
+
+            let history = service.contract_history(conn, organization.id, supply.id).await?;
+            let changes = history
+                .iter()
+                .map(|(change, name)| (change.field.as_str(), change.old_value.clone(), change.new_value.clone(), name.clone()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                changes,
+                vec![
+                    ("notes", Some(json!(null)), Some(json!("Storm salvage")), Some("Anna Berg".to_string())),
+                    ("volume_m3", Some(json!(800.0)), Some(json!(950.0)), Some("Anna Berg".to_string())),
+                    ("volume_m3", Some(json!(950.0)), Some(json!(900.0)), None),
+                ]
+            );
+
+            let other = OrganizationFactory::new().create(conn).await?;
+            let err = service.contract_history(conn, other.id, supply.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,2 @@
+pub mod contracts;
+pub mod history;
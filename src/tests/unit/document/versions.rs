@@ -0,0 +1,123 @@
+use bytes::Bytes;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::{models::DocumentSubject, repositories::DocumentRepositoryImpl},
+    domain::document::{DocumentService, DocumentUpload, NewDocument},
+    error::{ErrorCode, Result},
+    infrastructure::ObjectStorage,
+    tests::{
+        common::helpers::TestDb,
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+fn pdf(filename: &str, contents: &'static [u8]) -> DocumentUpload {
+    DocumentUpload {
+        filename: filename.to_string(),
+        content_type: "application/pdf".to_string(),
+        bytes: Bytes::from_static(contents),
+    }
+}
+
+fn new_document(block_id: Uuid, category: &str) -> NewDocument {
+    NewDocument {
+        subject: DocumentSubject::Block,
+        subject_id: block_id,
+        category: category.to_string(),
+        title: None,
+    }
+}
+
+#[tokio::test]
+async fn test_versions_are_kept_and_restored() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = DocumentService::new(DocumentRepositoryImpl, ObjectStorage::in_memory());
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let user = UserFactory::new().in_org(&organization).create(conn).await?;
+            let block_id = Uuid::new_v4();
+
+            let (document, first) = service
+                .upload(conn, organization.id, Some(user.id), new_document(block_id, "Harvest_Plan"), pdf("plan.pdf", b"draft"))
+                .await?;
+            assert_eq!(document.category, "harvest_plan");
+            assert_eq!(document.title, "plan.pdf");
+            assert_eq!(first.version, 1);
+            assert!(document.retain_until.is_none());
+
+            let (document, second) = service
+                .add_version(conn, organization.id, document.id, Some(user.id), pdf("plan-v2.pdf", b"final"))
+                .await?;
+            assert_eq!(second.version, 2);
+            assert_eq!(document.current_version, 2);
+
+            // Restoring the latest version changes nothing
+            let err = service.restore(conn, organization.id, document.id, 2, Some(user.id)).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            let (document, restored) = service.restore(conn, organization.id, document.id, 1, Some(user.id)).await?;
+            assert_eq!(restored.version, 3);
+            assert_eq!(restored.restored_from, Some(1));
+            assert_eq!(restored.filename, "plan.pdf");
+            assert_eq!(document.current_version, 3);
+
+            let (_, versions) = service.get(conn, organization.id, document.id).await?;
+            assert_eq!(versions.iter().map(|version| version.version).collect::<Vec<_>>(), vec![3, 2, 1]);
+
+            let (_, file) = service.file(conn, organization.id, document.id, 3).await?;
+            assert_eq!(file, Bytes::from_static(b"draft"));
+
+            // Documents of other organizations are invisible
+            let other = OrganizationFactory::new().create(conn).await?;
+            let err = service.get(conn, other.id, document.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_checklist_and_retention() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = DocumentService::new(DocumentRepositoryImpl, ObjectStorage::in_memory());
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let block_id = Uuid::new_v4();
+
+            let (permit, _) = service
+                .upload(conn, organization.id, None, new_document(block_id, "harvest_permit"), pdf("permit.pdf", b"permit"))
+                .await?;
+            let (photo, _) = service
+                .upload(conn, organization.id, None, new_document(block_id, "photo"), pdf("landing.pdf", b"photo"))
+                .await?;
+
+            let checklist = service.checklist(conn, organization.id, block_id, "approved").await?;
+            let missing = checklist.iter().filter(|item| !item.present).map(|item| item.category.as_str()).collect::<Vec<_>>();
+            assert_eq!(missing, vec!["harvest_plan", "environmental_assessment"]);
+
+            let err = service.checklist(conn, organization.id, block_id, "felled").await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            // Permits are retained for years, other documents are not
+            let err = service.delete(conn, organization.id, permit.id, Utc::now()).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+            service.delete(conn, organization.id, photo.id, Utc::now()).await?;
+
+            let later = permit.retain_until.unwrap() + Duration::days(1);
+            service.delete(conn, organization.id, permit.id, later).await?;
+
+            let documents = service.list(conn, organization.id, DocumentSubject::Block, block_id).await?;
+            assert!(documents.is_empty());
+
+            Ok(())
+        })
+    })
+    .await
+}
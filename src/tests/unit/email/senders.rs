@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use actix_web::{http::StatusCode, test};
+use chrono::Utc;
+use diesel::prelude::*;
+use serde_json::json;
+use crate::{
+    db::{
+        models::{auth::Role, OrganizationEmailSender},
+        repositories::{EmailSenderRepository, EmailSenderRepositoryImpl},
+        schema::organizations,
+    },
+    error::Result,
+    infrastructure::{
+        email::{DevMailbox, PasswordResetEmail},
+        Mailer,
+    },
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send, TestDb},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[tokio::test]
+async fn test_mail_uses_organization_sender() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let now = Utc::now();
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let mailbox = Arc::new(DevMailbox::new(10));
+            let mailer = Mailer::new(mailbox.clone(), "Forestry <no-reply@example.com>")?;
+            let data = PasswordResetEmail {
+                name: "Sam".into(),
+                reset_url: "https://app.example.com/reset/abc".into(),
+                expires_in_minutes: 30,
+            };
+
+            let message = mailer.compose(conn, Some(organization.id), "sam@example.com", &data).await?;
+            assert_eq!(message.from, "Forestry <no-reply@example.com>");
+            assert!(message.reply_to.is_none());
+
+            let repo = EmailSenderRepositoryImpl;
+            repo.upsert(conn, &OrganizationEmailSender {
+                org_id: organization.id,
+                from_address: "dispatch@pine.example.com".into(),
+                from_name: None,
+                reply_to: None,
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+            let sender = repo
+                .upsert(conn, &OrganizationEmailSender {
+                    org_id: organization.id,
+                    from_address: "dispatch@pine.example.com".into(),
+                    from_name: Some("Pine Dispatch".into()),
+                    reply_to: Some("office@pine.example.com".into()),
+                    created_at: now,
+                    updated_at: now,
+                })
+                .await?;
+            assert_eq!(sender.mailbox(), "Pine Dispatch <dispatch@pine.example.com>");
+
+            let message = mailer.compose(conn, Some(organization.id), "sam@example.com", &data).await?;
+            assert_eq!(message.from, "Pine Dispatch <dispatch@pine.example.com>");
+            assert_eq!(message.reply_to.as_deref(), Some("office@pine.example.com"));
+            assert!(message.text.contains("https://app.example.com/reset/abc"));
+
+            mailer.send(&message).await?;
+            assert_eq!(mailbox.list(Some("sam@example.com"))[0].message, message);
+
+            repo.delete(conn, organization.id).await?;
+            assert!(repo.find(conn, organization.id).await?.is_none());
+            assert!(repo.delete(conn, organization.id).await.is_err());
+
+            diesel::delete(organizations::table.find(organization.id)).execute(conn).unwrap();
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[actix_rt::test]
+async fn test_admins_set_senders_of_their_own_organizations() {
+    setup();
+    let config = app_config();
+    let (admin, stranger) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let stranger = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (admin, stranger)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let sender_uri = format!("/v1/admin/organizations/{}/email-sender", stranger.org_id);
+    let set_sender = |as_user, from_address: &str| {
+        test::TestRequest::put()
+            .uri(&sender_uri)
+            .insert_header(as_user)
+            .set_json(json!({ "from_address": from_address, "from_name": "Pinecorp" }))
+    };
+
+    let (status, _) = send(&app, set_sender(bearer(&stranger, &config), "dispatch@pinecorp.com")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Another organization's sender is not found by the admin
+    let (status, _) = send(&app, set_sender(bearer(&admin, &config), "phish@example.com")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, test::TestRequest::get().uri(&sender_uri).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&sender_uri).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = send(&app, test::TestRequest::get().uri(&sender_uri).insert_header(bearer(&stranger, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["from_address"], "dispatch@pinecorp.com");
+}
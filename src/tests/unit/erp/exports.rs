@@ -0,0 +1,179 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use chrono::{DateTime, Duration, Utc};
+use diesel::{
+    prelude::*,
+    sql_types::{Double, Nullable, Text, Timestamptz, Uuid as SqlUuid},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    api::{resources::erp::dto::SaveErpConnectionInput, utils::PaginationParams},
+    db::{models::ErpExportStatus, repositories::ErpRepositoryImpl},
+    domain::erp::{DownloadConnector, ErpService, ExportColumn, ExportConnector, ExportRecord, ExportSource},
+    error::{ErrorCode, Result},
+    infrastructure::ObjectStorage,
+    jobs::erp_export::ErpExportDelivery,
+    tests::{common::helpers::TestDb, factories::OrganizationFactory, setup},
+    utils::QueueConfig,
+};
+
+/// Source backed by a temporary table that only lives for the test transaction
+struct InvoicesSource;
+
+#[derive(QueryableByName)]
+struct Invoice {
+    #[diesel(sql_type = Text)]
+    number: String,
+    #[diesel(sql_type = Double)]
+    amount: f64,
+}
+
+impl InvoicesSource {
+    fn create_table(conn: &mut PgConnection) -> QueryResult<usize> {
+        diesel::sql_query(
+            "CREATE TEMP TABLE erp_test_invoices (
+                org_id UUID NOT NULL,
+                number TEXT NOT NULL,
+                amount DOUBLE PRECISION NOT NULL,
+                approved_at TIMESTAMPTZ NOT NULL
+            ) ON COMMIT DROP",
+        )
+        .execute(conn)
+    }
+
+    fn insert(conn: &mut PgConnection, org_id: Uuid, number: &str, amount: f64, approved_at: DateTime<Utc>) {
+        diesel::sql_query("INSERT INTO erp_test_invoices (org_id, number, amount, approved_at) VALUES ($1, $2, $3, $4)")
+            .bind::<SqlUuid, _>(org_id)
+            .bind::<Text, _>(number)
+            .bind::<Double, _>(amount)
+            .bind::<Timestamptz, _>(approved_at)
+            .execute(conn)
+            .unwrap();
+    }
+}
+
+impl ExportSource for InvoicesSource {
+    fn name(&self) -> &'static str {
+        "test_invoices"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["number", "amount"]
+    }
+
+    fn records(
+        &self,
+        conn: &mut PgConnection,
+        org_id: Uuid,
+        after: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+    ) -> QueryResult<Vec<ExportRecord>> {
+        let invoices: Vec<Invoice> = diesel::sql_query(
+            "SELECT number, amount FROM erp_test_invoices
+             WHERE org_id = $1 AND ($2 IS NULL OR approved_at > $2) AND approved_at <= $3
+             ORDER BY number",
+        )
+        .bind::<SqlUuid, _>(org_id)
+        .bind::<Nullable<Timestamptz>, _>(after)
+        .bind::<Timestamptz, _>(until)
+        .load(conn)?;
+        Ok(invoices
+            .into_iter()
+            .map(|invoice| {
+                let mut record = ExportRecord::new();
+                record.insert("number".into(), json!(invoice.number));
+                record.insert("amount".into(), json!(invoice.amount));
+                record
+            })
+            .collect())
+    }
+}
+
+fn input(mappings: BTreeMap<String, Vec<ExportColumn>>) -> SaveErpConnectionInput {
+    SaveErpConnectionInput {
+        name: "Ledger".to_string(),
+        connector: "csv_download".to_string(),
+        settings: serde_json::Value::Null,
+        mappings,
+        schedule: None,
+        enabled: None,
+    }
+}
+
+#[tokio::test]
+async fn test_exports_are_incremental_and_delivered() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            InvoicesSource::create_table(conn).unwrap();
+            let storage = ObjectStorage::in_memory();
+            let connectors: Vec<Arc<dyn ExportConnector>> = vec![Arc::new(DownloadConnector)];
+            let service = ErpService::with_sources(
+                ErpRepositoryImpl,
+                storage.clone(),
+                connectors.clone(),
+                vec![Arc::new(InvoicesSource)],
+            );
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let queue = QueueConfig::default();
+
+            let err = service
+                .create_connection(conn, organization.id, None, input(BTreeMap::from([("loads".to_string(), Vec::new())])))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+            let err = service
+                .create_connection(conn, organization.id, None, SaveErpConnectionInput {
+                    settings: json!({ "host": "erp.example.com" }),
+                    ..input(BTreeMap::new())
+                })
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let mapping = BTreeMap::from([("test_invoices".to_string(), vec![
+                ExportColumn { header: "InvoiceNo".into(), field: "number".into() },
+                ExportColumn { header: "Total".into(), field: "amount".into() },
+            ])]);
+            let connection = service.create_connection(conn, organization.id, None, input(mapping.clone())).await?;
+            assert!(connection.next_run_at.is_none());
+            let err = service.create_connection(conn, organization.id, None, input(mapping)).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            InvoicesSource::insert(conn, organization.id, "INV-1", 1250.5, Utc::now() - Duration::hours(2));
+            InvoicesSource::insert(conn, organization.id, "INV-2", 80.0, Utc::now() - Duration::hours(1));
+            let first = service.export(conn, &queue, organization.id, connection.id, "test_invoices").await?;
+            assert_eq!(first.status, ErpExportStatus::Pending.as_str());
+            assert_eq!((first.period_start, first.record_count), (None, 2));
+
+            let delivery = ErpExportDelivery::new(storage, connectors);
+            let delivered = delivery.deliver(conn, first.id).await?;
+            assert_eq!(delivered.status, ErpExportStatus::Delivered.as_str());
+            assert_eq!(delivered.remote_ref, Some(format!("/v1/erp/exports/{}/file", first.id)));
+            assert_eq!(delivery.deliver(conn, first.id).await?.delivered_at, delivered.delivered_at);
+
+            let (_, file) = service.export_file(conn, organization.id, first.id).await?;
+            assert_eq!(String::from_utf8(file.to_vec()).unwrap(), "InvoiceNo,Total\r\nINV-1,1250.5\r\nINV-2,80.0\r\n");
+
+            // Only invoices approved since the previous export are included
+            InvoicesSource::insert(conn, organization.id, "INV-3", 42.0, Utc::now());
+            let second = service.export(conn, &queue, organization.id, connection.id, "test_invoices").await?;
+            assert_eq!((second.period_start, second.record_count), (Some(first.period_end), 1));
+
+            let (exports, total) = service
+                .exports(conn, organization.id, connection.id, &PaginationParams::new(1, 20))
+                .await?;
+            assert_eq!(total.total, 2);
+            assert_eq!(exports.iter().map(|export| export.id).collect::<Vec<_>>(), vec![second.id, first.id]);
+
+            let err = service.export(conn, &queue, organization.id, connection.id, "loads").await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+            let err = service.export_file(conn, Uuid::new_v4(), first.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            Ok(())
+        })
+    })
+    .await
+}
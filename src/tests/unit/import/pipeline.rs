@@ -0,0 +1,226 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use bytes::Bytes;
+use diesel::{
+    prelude::*,
+    sql_types::{Double, Text, Uuid as SqlUuid},
+};
+use serde_json::Value;
+use uuid::Uuid;
+use crate::{
+    db::{
+        models::ImportStatus,
+        repositories::ImportRepositoryImpl,
+        schema::{harvest_blocks, stands},
+    },
+    domain::import::{targets, ImportField, ImportFieldKind, ImportRecord, ImportService, ImportTarget},
+    error::{ErrorCode, Result},
+    infrastructure::ObjectStorage,
+    jobs::import::ImportProcessor,
+    tests::{
+        common::helpers::TestDb,
+        factories::{HarvestBlockFactory, OrganizationFactory},
+        setup,
+    },
+    utils::QueueConfig,
+};
+
+const FIELDS: &[ImportField] = &[
+    ImportField { name: "name", kind: ImportFieldKind::Text, required: true, aliases: &["stand"] },
+    ImportField { name: "area_ha", kind: ImportFieldKind::Number, required: true, aliases: &["ha"] },
+];
+
+/// Target backed by a temporary table that only lives for the test transaction
+struct StandsTarget;
+
+#[derive(QueryableByName)]
+struct Stand {
+    #[diesel(sql_type = Text)]
+    name: String,
+    #[diesel(sql_type = Double)]
+    area_ha: f64,
+}
+
+impl StandsTarget {
+    fn create_table(conn: &mut PgConnection) -> QueryResult<usize> {
+        diesel::sql_query(
+            "CREATE TEMP TABLE import_test_stands (
+                org_id UUID NOT NULL,
+                name TEXT NOT NULL,
+                area_ha DOUBLE PRECISION NOT NULL
+            ) ON COMMIT DROP",
+        )
+        .execute(conn)
+    }
+
+    fn all(conn: &mut PgConnection) -> Vec<Stand> {
+        diesel::sql_query("SELECT name, area_ha FROM import_test_stands ORDER BY name")
+            .load(conn)
+            .unwrap()
+    }
+}
+
+impl ImportTarget for StandsTarget {
+    fn name(&self) -> &'static str {
+        "test_stands"
+    }
+
+    fn fields(&self) -> &'static [ImportField] {
+        FIELDS
+    }
+
+    fn check(&self, record: &ImportRecord) -> Vec<String> {
+        match record.get("area_ha").and_then(Value::as_f64) {
+            Some(area) if area <= 0.0 => vec!["area_ha: must be positive".into()],
+            _ => Vec::new(),
+        }
+    }
+
+    fn insert(&self, conn: &mut PgConnection, org_id: Uuid, records: &[ImportRecord]) -> QueryResult<usize> {
+        let mut inserted = 0;
+        for record in records {
+            inserted += diesel::sql_query("INSERT INTO import_test_stands (org_id, name, area_ha) VALUES ($1, $2, $3)")
+                .bind::<SqlUuid, _>(org_id)
+                .bind::<Text, _>(record["name"].as_str().unwrap_or_default())
+                .bind::<Double, _>(record["area_ha"].as_f64().unwrap_or_default())
+                .execute(conn)?;
+        }
+        Ok(inserted)
+    }
+}
+
+#[tokio::test]
+async fn test_import_is_mapped_previewed_and_loaded() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            StandsTarget::create_table(conn).unwrap();
+            let storage = ObjectStorage::in_memory();
+            let targets: Vec<Arc<dyn ImportTarget>> = vec![Arc::new(StandsTarget)];
+            let service = ImportService::with_targets(ImportRepositoryImpl, storage.clone(), targets.clone());
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let file = Bytes::from("Stand;Size;Owner\nS-1;12,5;North\nS-2;lots;South\nS-3;4;East\n;-1;West\n");
+
+            let err = service.upload(conn, organization.id, None, "roads", "stands.csv", file.clone()).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+            let err = service.upload(conn, organization.id, None, "test_stands", "stands.pdf", file.clone()).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let import = service.upload(conn, organization.id, None, "test_stands", "stands.csv", file).await?;
+            assert_eq!(import.status, ImportStatus::Uploaded.as_str());
+            assert_eq!(import.headers, vec!["Stand", "Size", "Owner"]);
+            assert_eq!(import.total_rows, 4);
+            assert_eq!(import.mapping, serde_json::json!({ "Stand": "name" }));
+
+            // The size column is not detected, so the mapping is incomplete
+            let err = service.commit(conn, &QueueConfig::default(), organization.id, import.id, None).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let mapping = BTreeMap::from([("Stand".to_string(), "name".to_string()), ("Size".to_string(), "area_ha".to_string())]);
+            let preview = service.preview(conn, organization.id, import.id, Some(mapping), Some(2)).await?;
+            assert_eq!((preview.valid_rows, preview.invalid_rows), (2, 2));
+            assert_eq!(preview.rows.len(), 2);
+            assert_eq!(preview.rows[0].line, 2);
+            assert_eq!(preview.rows[1].errors, vec!["Size: 'lots' is not a number"]);
+
+            let queued = service.commit(conn, &QueueConfig::default(), organization.id, import.id, None).await?;
+            assert_eq!(queued.status, ImportStatus::Queued.as_str());
+            let err = service.commit(conn, &QueueConfig::default(), organization.id, import.id, None).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            let processor = ImportProcessor::new(storage, targets);
+            let loaded = processor.process(conn, import.id).await?.unwrap();
+            assert_eq!(loaded.status, ImportStatus::Completed.as_str());
+            assert_eq!((loaded.imported_rows, loaded.failed_rows), (Some(2), Some(2)));
+            assert!(processor.process(conn, import.id).await?.is_none());
+
+            let stands = StandsTarget::all(conn);
+            assert_eq!(stands.iter().map(|stand| (stand.name.as_str(), stand.area_ha)).collect::<Vec<_>>(), vec![
+                ("S-1", 12.5),
+                ("S-3", 4.0),
+            ]);
+
+            let (_, report) = service.error_report(conn, organization.id, import.id).await?;
+            assert_eq!(
+                String::from_utf8(report.to_vec()).unwrap(),
+                "line,errors,Stand,Size,Owner\n\
+                 3,Size: 'lots' is not a number,S-2,lots,South\n\
+                 5,Stand: a value is required,,'-1,West\n"
+            );
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_blocks_and_their_stands_are_imported() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let storage = ObjectStorage::in_memory();
+            let service = ImportService::new(ImportRepositoryImpl, storage.clone());
+            let processor = ImportProcessor::new(storage, targets());
+            let organization = OrganizationFactory::new().create(conn).await?;
+            HarvestBlockFactory::new(&organization).license("L-9").block_number("4").create(conn).await?;
+            let square = r#""{""type"":""Polygon"",""coordinates"":[[[25,61],[25.01,61],[25.01,61.01],[25,61.01],[25,61]]]}""#;
+
+            let blocks = format!(
+                "License;Block;Area;Volume;Boundary\n\
+                 L-9;1;12,5;900;{square}\n\
+                 L-9;2;-3;100;{square}\n\
+                 L-9;3;4;100;a square\n\
+                 L-9;4;4;100;{square}\n"
+            );
+            let import = service.upload(conn, organization.id, None, "blocks", "blocks.csv", Bytes::from(blocks)).await?;
+            assert_eq!(import.mapping.as_object().map(|mapping| mapping.len()), Some(5));
+            service.commit(conn, &QueueConfig::default(), organization.id, import.id, None).await?;
+            let loaded = processor.process(conn, import.id).await?.unwrap();
+            assert_eq!(loaded.status, ImportStatus::Completed.as_str());
+            // The taken block number 4 is skipped
+            assert_eq!((loaded.imported_rows, loaded.failed_rows), (Some(1), Some(2)));
+            let (area, status): (f64, String) = harvest_blocks::table
+                .filter(harvest_blocks::org_id.eq(organization.id))
+                .filter(harvest_blocks::block_number.eq("1"))
+                .select((harvest_blocks::area_ha, harvest_blocks::status))
+                .first(conn)
+                .unwrap();
+            assert_eq!((area, status.as_str()), (12.5, "planned"));
+
+            let stand_file = "License;Block;Stand;Area;Species;Age;SI;Volume;Cruised on\n\
+                              L-9;1;S1;6,5;spruce 70/pine 30;3;24;1200;2024-09-01\n\
+                              L-9;1;S2;2;birch;2;20;300;\n\
+                              L-9;1;S3;2;spruce 60/pine 30;2;20;300;\n\
+                              L-9;1;S2;3;birch;2;20;450;\n";
+            let import = service.upload(conn, organization.id, None, "stands", "stands.csv", Bytes::from(stand_file)).await?;
+            service.commit(conn, &QueueConfig::default(), organization.id, import.id, None).await?;
+            let loaded = processor.process(conn, import.id).await?.unwrap();
+            assert_eq!(loaded.status, ImportStatus::Completed.as_str());
+            assert_eq!(loaded.failed_rows, Some(1));
+            let saved: Vec<(String, f64, Value)> = stands::table
+                .filter(stands::org_id.eq(organization.id))
+                .order_by(stands::stand_number)
+                .select((stands::stand_number, stands::net_merchantable_volume_m3, stands::species))
+                .load(conn)
+                .unwrap();
+            // The later row for S2 replaces the earlier one
+            assert_eq!(saved, vec![
+                (
+                    "S1".to_string(),
+                    1200.0,
+                    serde_json::json!([{ "species": "spruce", "percent": 70.0 }, { "species": "pine", "percent": 30.0 }]),
+                ),
+                ("S2".to_string(), 450.0, serde_json::json!([{ "species": "birch", "percent": 100.0 }])),
+            ]);
+
+            // Stands only go into blocks the organization has
+            let orphan = "License;Block;Stand;Area;Species;Age;SI;Volume\nL-9;8;S1;2;birch;2;20;300\n";
+            let import = service.upload(conn, organization.id, None, "stands", "orphans.csv", Bytes::from(orphan)).await?;
+            service.commit(conn, &QueueConfig::default(), organization.id, import.id, None).await?;
+            let failed = processor.process(conn, import.id).await?.unwrap();
+            assert_eq!(failed.status, ImportStatus::Failed.as_str());
+            Ok(())
+        })
+    })
+    .await
+}
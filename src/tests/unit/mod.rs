@@ -1,2 +1,30 @@
+pub mod activity;
+pub mod admin;
+pub mod anonymize;
+pub mod approval;
+pub mod archive;
 pub mod auth;
-pub mod organization;
\ No newline at end of file
+pub mod billing;
+pub mod block;
+pub mod certification;
+pub mod crew;
+pub mod customer;
+pub mod document;
+pub mod email;
+pub mod erp;
+pub mod import;
+pub mod notification;
+pub mod organization;
+pub mod presence;
+pub mod queue;
+pub mod report;
+pub mod retention;
+pub mod sales;
+pub mod scheduler;
+pub mod scim;
+pub mod search;
+pub mod seed;
+pub mod tag;
+pub mod telemetry;
+pub mod tenant;
+pub mod view;
@@ -0,0 +1,68 @@
+use diesel::prelude::*;
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        models::Notification,
+        repositories::NotificationRepositoryImpl,
+        schema::{organizations, users},
+    },
+    domain::notification::NotificationService,
+    error::Result,
+    tests::{
+        common::helpers::TestDb,
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[tokio::test]
+async fn test_notifications_are_scoped_to_their_recipient() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let org = OrganizationFactory::new().create(conn).await?;
+            let alice = UserFactory::new().first_name("Alice").in_org(&org).create(conn).await?;
+            let bob = UserFactory::new().first_name("Bob").in_org(&org).create(conn).await?;
+            let service = NotificationService::new(NotificationRepositoryImpl);
+            let pagination = PaginationParams::new(1, 10);
+
+            let created = service
+                .notify(conn, &[
+                    Notification::new(alice.id, "import_failed", "Stand import failed")
+                        .with_org(org.id)
+                        .with_link("/imports/1"),
+                    Notification::new(alice.id, "block_approved", "Block 12 approved")
+                        .with_data(serde_json::json!({ "block": 12 })),
+                    Notification::new(bob.id, "block_approved", "Block 12 approved"),
+                ])
+                .await?;
+            assert_eq!(created.len(), 3);
+            assert_eq!(service.unread_count(conn, alice.id).await?, 2);
+
+            let (listed, total) = service.list(conn, alice.id, false, &pagination).await?;
+            assert_eq!(total.total, 2);
+            assert!(listed.iter().all(|n| n.user_id == alice.id));
+
+            // Another user's notification cannot be marked read
+            assert!(service.mark_read(conn, bob.id, created[0].id).await.is_err());
+            let read = service.mark_read(conn, alice.id, created[0].id).await?;
+            assert!(read.is_read());
+            let again = service.mark_read(conn, alice.id, created[0].id).await?;
+            assert_eq!(again.read_at, read.read_at);
+
+            let (unread, _) = service.list(conn, alice.id, true, &pagination).await?;
+            assert_eq!(unread.len(), 1);
+            assert_eq!(unread[0].title, "Block 12 approved");
+
+            assert_eq!(service.mark_all_read(conn, alice.id).await?, 1);
+            assert_eq!(service.unread_count(conn, alice.id).await?, 0);
+            assert_eq!(service.unread_count(conn, bob.id).await?, 1);
+            assert!(service.notify(conn, &[]).await?.is_empty());
+
+            diesel::delete(users::table.filter(users::org_id.eq(org.id))).execute(conn).unwrap();
+            diesel::delete(organizations::table.find(org.id)).execute(conn).unwrap();
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,100 @@
+use actix_web::{http::StatusCode, test};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+
+use crate::{
+    db::models::auth::Role,
+    domain::activity::{ActivityFeed, FeedEvent},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_organization_activity_feed() {
+    setup();
+    let config = app_config();
+    let (organization, child, admin, child_manager, operator, crew) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let child = OrganizationFactory::new().child_of(&organization).create(&mut conn).await.unwrap();
+        let member = |org, role| UserFactory::new().in_org(org).role(role).verified();
+        let admin = member(&organization, Role::Admin).create(&mut conn).await.unwrap();
+        let child_manager = member(&child, Role::Manager).create(&mut conn).await.unwrap();
+        let operator = member(&organization, Role::Operator).create(&mut conn).await.unwrap();
+        let crew = member(&organization, Role::Operator).create(&mut conn).await.unwrap();
+        ActivityFeed::record(&mut conn, organization.id, Some(admin.id), FeedEvent::user_joined(&crew, "invitation")).await;
+        (organization, child, admin, child_manager, operator, crew)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let feed = |user, org: &crate::db::models::Organization, query: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/v1/organizations/{}/activity{}", org.id, query))
+            .insert_header(bearer(user, &config))
+    };
+    let kinds = |body: &Value| -> Vec<String> {
+        body["data"].as_array().unwrap().iter().map(|activity| activity["kind"].as_str().unwrap().to_string()).collect()
+    };
+
+    // Member changes go to the feed
+    let members_uri = format!("/v1/organizations/{}/members", organization.id);
+    let (status, _) = send(
+        &app,
+        test::TestRequest::patch()
+            .uri(&format!("{}/{}", members_uri, crew.id))
+            .insert_header(bearer(&admin, &config))
+            .set_json(json!({ "role": "Manager" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        test::TestRequest::delete().uri(&format!("{}/{}", members_uri, crew.id)).insert_header(bearer(&admin, &config)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    // Managers of the organization or above it only
+    let (status, _) = send(&app, feed(&operator, &organization, "")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, feed(&child_manager, &organization, "")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = send(&app, feed(&child_manager, &child, "")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["meta"]["total_items"], 0);
+
+    // Newest first
+    let (status, body) = send(&app, feed(&admin, &organization, "")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(kinds(&body), ["member_removed", "member_role_changed", "user_joined"]);
+    assert_eq!(body["meta"]["total_items"], 3);
+    let changed = &body["data"][1];
+    assert_eq!(changed["actor_id"], admin.id.to_string());
+    assert_eq!(changed["subject_type"], "user");
+    assert_eq!(changed["subject_id"], crew.id.to_string());
+    assert_eq!(changed["data"], json!({ "from": "Operator", "to": "Manager" }));
+
+    // Filtered, with filtered totals
+    let (_, body) = send(&app, feed(&admin, &organization, "?kind=user_joined,member_removed&per_page=1")).await;
+    assert_eq!(kinds(&body), ["member_removed"]);
+    assert_eq!(body["meta"]["total_items"], 2);
+    let (_, body) = send(&app, feed(&admin, &organization, &format!("?actor_id={}", operator.id))).await;
+    assert_eq!(body["meta"]["total_items"], 0);
+    let (_, body) = send(&app, feed(&admin, &organization, "?subject_type=block")).await;
+    assert_eq!(body["meta"]["total_items"], 0);
+    let tomorrow = (Utc::now() + Duration::days(1)).date_naive();
+    let (_, body) = send(&app, feed(&admin, &organization, &format!("?since={}", tomorrow))).await;
+    assert_eq!(body["meta"]["total_items"], 0);
+    let (_, body) = send(&app, feed(&admin, &organization, &format!("?until={}", tomorrow))).await;
+    assert_eq!(body["meta"]["total_items"], 3);
+
+    let (status, body) = send(&app, feed(&admin, &organization, "?kind=work_order_closed")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "kind");
+    let (status, body) = send(&app, feed(&admin, &organization, &format!("?since={0}&until={0}", tomorrow))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "until");
+}
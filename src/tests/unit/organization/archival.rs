@@ -0,0 +1,116 @@
+use std::io::Read;
+
+use actix_web::{http::StatusCode, test};
+use flate2::read::GzDecoder;
+use serde_json::{json, Value};
+
+use crate::{
+    db::models::auth::Role,
+    jobs::{organization_export::OrganizationExporter, queue::JobHandler},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_archived_organizations_are_read_only_until_unarchived() {
+    setup();
+    let config = app_config();
+    let (organization, child, admin, child_admin, operator) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let child = OrganizationFactory::new().child_of(&organization).create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let child_admin = UserFactory::new().in_org(&child).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        (organization, child, admin, child_admin, operator)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let uri = |org: &crate::db::models::Organization, path: &str| format!("/v1/organizations/{}{}", org.id, path);
+    let change_settings = |user, org| {
+        test::TestRequest::patch()
+            .uri(&uri(org, "/settings"))
+            .insert_header(bearer(user, &config))
+            .set_json(json!({ "timezone": "+02:00" }))
+    };
+    let archive = |user| test::TestRequest::post().uri(&uri(&organization, "/archive")).insert_header(bearer(user, &config));
+    let unarchive = |user, org| test::TestRequest::post().uri(&uri(org, "/unarchive")).insert_header(bearer(user, &config));
+    let export = || test::TestRequest::get().uri(&uri(&organization, "/archive/export")).insert_header(bearer(&admin, &config));
+
+    let (status, body) = send(&app, test::TestRequest::get().uri(&uri(&organization, "/archive")).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "{}", body);
+
+    // Only admins archive
+    let (status, _) = send(&app, archive(&operator)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = send(&app, archive(&admin)).await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{}", body);
+    assert_eq!(body["organization_id"], organization.id.to_string());
+    assert_eq!(body["status"], "pending");
+    let archive_id = body["id"].clone();
+
+    // Writes stop for the organization and those under it, reads go on
+    let (status, body) = send(&app, archive(&admin)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["details"]["code"], "ORGANIZATION_ARCHIVED");
+    for (user, org) in [(&admin, &organization), (&child_admin, &child)] {
+        let (status, body) = send(&app, change_settings(user, org)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["details"]["code"], "ORGANIZATION_ARCHIVED");
+        let (status, body) = send(&app, test::TestRequest::get().uri(&uri(org, "")).insert_header(bearer(user, &config))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["archived_at"].is_string());
+    }
+
+    // The bundle is written by the queue
+    let (status, body) = send(&app, export()).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["details"]["code"], "EXPORT_PENDING");
+    OrganizationExporter::new(config.storage().clone())
+        .handle(config.pool(), &json!({ "archive_id": archive_id }))
+        .await
+        .expect("Failed to write the export bundle");
+    let (status, body) = send(&app, test::TestRequest::get().uri(&uri(&organization, "/archive")).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "completed");
+    assert!(body["byte_size"].as_i64().unwrap() > 0);
+
+    let response = test::call_service(&app, export().to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "application/gzip");
+    let mut bundle = String::new();
+    GzDecoder::new(test::read_body(response).await.as_ref())
+        .read_to_string(&mut bundle)
+        .expect("The bundle is gzipped");
+    let bundle: Value = serde_json::from_str(&bundle).unwrap();
+    assert_eq!(bundle["organization_id"], organization.id.to_string());
+    let organizations: Vec<&str> = bundle["tables"]["organizations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(organizations.len(), 2);
+    assert!(organizations.contains(&child.id.to_string().as_str()));
+    let users = bundle["tables"]["users"].as_array().unwrap();
+    assert_eq!(users.len(), 3);
+    assert!(users.iter().all(|user| user.get("password").is_none() && user["email"].is_string()));
+
+    // Organizations archived together reopen together
+    let (status, body) = send(&app, unarchive(&child_admin, &child)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["details"]["code"], "ARCHIVED_WITH_PARENT");
+    let (status, body) = send(&app, unarchive(&admin, &organization)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(body["unarchived_at"].is_string());
+    for (user, org) in [(&admin, &organization), (&child_admin, &child)] {
+        let (status, body) = send(&app, change_settings(user, org)).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+    let (status, body) = send(&app, unarchive(&admin, &organization)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["details"]["code"], "NOT_ARCHIVED");
+}
@@ -1,17 +1,13 @@
-use chrono::Utc;
 use uuid::Uuid;
 use crate::{
-    db::{
-        models::organization::Organization,
-        repositories::{
-            organization::OrganizationRepositoryImpl,
-            Repository,
-        },
+    db::repositories::{
+        organization::OrganizationRepositoryImpl,
+        Repository,
     },
     domain::organization::OrganizationValidator,
     api::resources::organization::dto::{CreateOrganizationInput, UpdateOrganizationInput},
     error::Result,
-    tests::{common::helpers::TestDb, setup},
+    tests::{common::helpers::TestDb, factories::OrganizationFactory, setup},
 };
 
 #[tokio::test(flavor = "multi_thread")]
@@ -30,14 +26,7 @@ async fn test_organization_validation() -> Result<()> {
             OrganizationValidator::validate_create(conn, &repo, &input).await?;
 
             // Create the organization
-            let org = Organization {
-                id: Uuid::new_v4(),
-                name: input.name.clone(),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-            };
-            let created_org = repo.create(conn, &org).await?;
+            let created_org = OrganizationFactory::new().name(input.name.clone()).create(conn).await?;
 
             // Second creation with same name should fail
             let result = OrganizationValidator::validate_create(conn, &repo, &input).await;
@@ -76,14 +65,7 @@ async fn test_organization_business_rules() -> Result<()> {
             let repo = OrganizationRepositoryImpl;
 
             // Test organization with active users
-            let org = Organization {
-                id: Uuid::new_v4(),
-                name: format!("Test Org {}", Uuid::new_v4()),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-            };
-            let created_org = repo.create(conn, &org).await?;
+            let created_org = OrganizationFactory::new().create(conn).await?;
 
             // Test deletion validation
             let result = repo.soft_delete(conn, created_org.id).await;
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    api::resources::report::dto::SaveReportInput,
+    db::{
+        models::auth::Role,
+        repositories::{OrganizationRepositoryImpl, ReportRepositoryImpl, Repository},
+    },
+    domain::{
+        report::{ReportDefinition, ReportService},
+        Preferences,
+    },
+    error::Result,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send, TestDb},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_divisions_and_areas_nest_under_a_company() {
+    setup();
+    let config = app_config();
+    let (company, company_admin) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let company = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&company).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (company, admin)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let create_child = |parent: &str, name: String, as_user: (&'static str, String)| {
+        test::TestRequest::post()
+            .uri(&format!("/v1/organizations/{}/children", parent))
+            .insert_header(as_user)
+            .set_json(json!({ "name": name }))
+    };
+    let suffix = Uuid::new_v4();
+
+    let (status, division) = send(&app, create_child(&company.id.to_string(), format!("North division {}", suffix), bearer(&company_admin, &config))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(division["parent_id"], company.id.to_string());
+    let division_id = division["id"].as_str().unwrap().to_string();
+    let (status, area) = send(&app, create_child(&division_id, format!("Lakeside area {}", suffix), bearer(&company_admin, &config))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let area_id = area["id"].as_str().unwrap().to_string();
+    let (status, _) = send(&app, create_child(&company.id.to_string(), format!("South division {}", suffix), bearer(&company_admin, &config))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let tree = |root: &str, as_user| test::TestRequest::get().uri(&format!("/v1/organizations/{}/tree", root)).insert_header(as_user);
+    let (status, body) = send(&app, tree(&company.id.to_string(), bearer(&company_admin, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    let nodes: Vec<(&str, u64)> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| (node["name"].as_str().unwrap(), node["depth"].as_u64().unwrap()))
+        .collect();
+    let north = format!("North division {}", suffix);
+    let lakeside = format!("Lakeside area {}", suffix);
+    let south = format!("South division {}", suffix);
+    assert_eq!(nodes, [(company.name.as_str(), 0), (north.as_str(), 1), (lakeside.as_str(), 2), (south.as_str(), 1)]);
+
+    // Members of a division act on what is under it, not above it
+    let (division_manager, area_operator) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let division = OrganizationRepositoryImpl.find_by_id(&mut conn, Uuid::parse_str(&division_id).unwrap()).await.unwrap();
+        let area = OrganizationRepositoryImpl.find_by_id(&mut conn, Uuid::parse_str(&area_id).unwrap()).await.unwrap();
+        let manager = UserFactory::new().in_org(&division).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&area).role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        (manager, operator)
+    };
+    let (status, body) = send(&app, tree(&division_id, bearer(&division_manager, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().unwrap().len(), 2);
+    let (status, _) = send(&app, tree(&company.id.to_string(), bearer(&division_manager, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, create_child(&division_id, format!("Ridge area {}", suffix), bearer(&division_manager, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let users = |org: &str, as_user| test::TestRequest::get().uri(&format!("/v1/organizations/{}/users", org)).insert_header(as_user);
+    let (status, body) = send(&app, users(&area_id, bearer(&company_admin, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"][0]["id"], area_operator.id.to_string());
+    let (status, _) = send(&app, users(&company.id.to_string(), bearer(&area_operator, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Areas go before their division
+    let delete = |org: &str| test::TestRequest::delete().uri(&format!("/v1/organizations/{}", org)).insert_header(bearer(&company_admin, &config));
+    let (status, body) = send(&app, delete(&division_id)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["details"]["code"], "HAS_CHILDREN");
+    let (status, _) = send(&app, delete(&area_id)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, delete(&division_id)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[actix_rt::test]
+async fn test_organizations_of_other_tenants_answer_not_found() {
+    setup();
+    let config = app_config();
+    let (company, division, outsider, admin) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let company = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let division = OrganizationFactory::new().child_of(&company).create(&mut conn).await.unwrap();
+        let outsider = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&company).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (company, division, outsider, admin)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let as_admin = bearer(&admin, &config);
+    let organization = |id: Uuid| format!("/v1/organizations/{}", id);
+
+    let (status, body) = send(&app, test::TestRequest::get().uri(&organization(division.id)).insert_header(as_admin.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], division.id.to_string());
+
+    let (status, _) = send(&app, test::TestRequest::get().uri(&organization(outsider.id)).insert_header(as_admin.clone())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let rename = test::TestRequest::put()
+        .uri(&organization(outsider.id))
+        .insert_header(as_admin.clone())
+        .set_json(json!({ "name": format!("Taken over {}", Uuid::new_v4()) }));
+    let (status, _) = send(&app, rename).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&organization(outsider.id)).insert_header(as_admin.clone())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // The list holds the company and what is under it, nothing else
+    let (status, body) = send(&app, test::TestRequest::get().uri("/v1/organizations?per_page=100").insert_header(as_admin)).await;
+    assert_eq!(status, StatusCode::OK);
+    let mut listed: Vec<&str> = body["data"].as_array().unwrap().iter().map(|org| org["id"].as_str().unwrap()).collect();
+    listed.sort();
+    let mut expected = vec![company.id.to_string(), division.id.to_string()];
+    expected.sort();
+    assert_eq!(listed, expected);
+
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let untouched = OrganizationRepositoryImpl.find_by_id(&mut conn, outsider.id).await.unwrap();
+    assert_eq!(untouched.name, outsider.name);
+}
+
+#[tokio::test]
+async fn test_reports_roll_up_the_organizations_below() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = ReportService::new(ReportRepositoryImpl);
+            let company = OrganizationFactory::new().create(conn).await?;
+            let division = OrganizationFactory::new().child_of(&company).create(conn).await?;
+            let area = OrganizationFactory::new().child_of(&division).create(conn).await?;
+            UserFactory::new().in_org(&company).role(Role::Admin).create(conn).await?;
+            UserFactory::new().in_org(&division).role(Role::Manager).create(conn).await?;
+            UserFactory::new().in_org(&area).role(Role::Operator).create_many(conn, 2).await?;
+
+            let members: SaveReportInput = serde_json::from_value(json!({
+                "name": "Members",
+                "definition": {
+                    "dataset": "users",
+                    "columns": [{ "field": "id", "aggregate": "count", "label": "members" }]
+                }
+            }))
+            .unwrap();
+            let company_report = service.create(conn, company.id, None, members).await?;
+            let (_, result) = service.run(conn, company.id, company_report.id, &HashMap::new(), &Preferences::default()).await?;
+            assert_eq!(result.rows, vec![vec![json!(4)]]);
+
+            let definition = ReportDefinition::from_stored(&company_report)?;
+            let result = ReportService::<ReportRepositoryImpl>::execute(conn, division.id, &definition, &HashMap::new(), &Preferences::default())?;
+            assert_eq!(result.rows, vec![vec![json!(3)]]);
+            Ok(())
+        })
+    })
+    .await
+}
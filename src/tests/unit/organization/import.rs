@@ -0,0 +1,139 @@
+use actix_web::{http::StatusCode, test};
+use diesel::prelude::*;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        models::{auth::{Role, User}, QueuedJob},
+        schema::{queued_jobs, users},
+    },
+    infrastructure::email::EmailMessage,
+    jobs::email::EMAIL_JOB,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+/// The emails queued for `to`
+fn queued_for(conn: &mut PgConnection, to: &str) -> Vec<EmailMessage> {
+    queued_jobs::table
+        .filter(queued_jobs::kind.eq(EMAIL_JOB))
+        .load::<QueuedJob>(conn)
+        .unwrap()
+        .into_iter()
+        .filter_map(|job| serde_json::from_value(job.payload).ok())
+        .filter(|message: &EmailMessage| message.to == to)
+        .collect()
+}
+
+#[actix_rt::test]
+async fn test_users_are_imported_from_csv() {
+    setup();
+    let config = app_config();
+    let (manager, operator, org_id, existing, outsider) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let manager = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        let existing = UserFactory::new().create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (manager, operator, organization.id, existing, outsider)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let import = |user: &User, content_type: &str, body: String| {
+        test::TestRequest::post()
+            .uri(&format!("/v1/organizations/{}/users/import", org_id))
+            .insert_header(bearer(user, &config))
+            .insert_header(("Content-Type", content_type.to_string()))
+            .set_payload(body)
+            .to_request()
+    };
+
+    let unique = Uuid::new_v4().simple().to_string();
+    let (first, second) = (format!("first.{}@example.com", unique), format!("second.{}@example.com", unique));
+    let phone_number = format!("+1 555 {}", &unique.chars().filter(char::is_ascii_digit).chain("0000000".chars()).take(7).collect::<String>());
+    let csv = format!(
+        "Email,First name,Last name,Phone,Role\n\
+         {first},Ada,Lovelace,{phone_number},\n\
+         {},Grace,Hopper,,manager\n\
+         not-an-email,Bad,Row,,\n\
+         {first},Ada,Again,,\n\
+         {},Taken,Email,,\n\
+         boss.{unique}@example.com,Boss,Person,,Admin\n\
+         role.{unique}@example.com,Odd,Role,,Owner\n",
+        second.to_uppercase(),
+        existing.email,
+    );
+    let body = format!(
+        "--XyZ\r\nContent-Disposition: form-data; name=\"file\"; filename=\"crew.csv\"\r\nContent-Type: text/csv\r\n\r\n{}\r\n--XyZ--\r\n",
+        csv
+    );
+    let response = test::call_service(&app, import(&manager, "multipart/form-data; boundary=XyZ", body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["created"], 2);
+    assert_eq!(body["failed"], 5);
+    let rows = body["rows"].as_array().unwrap();
+    assert_eq!(rows.iter().map(|row| row["line"].as_u64().unwrap()).collect::<Vec<_>>(), [2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(rows[1]["email"], second);
+    let errors = |line: usize| {
+        rows[line - 2]["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| (error["field"].as_str().unwrap().to_string(), error["code"].as_str().unwrap().to_string()))
+            .collect::<Vec<_>>()
+    };
+    assert!(errors(2).is_empty() && errors(3).is_empty());
+    for (line, field, code) in [
+        (4, "email", "INVALID_FORMAT"),
+        (5, "email", "DUPLICATE_IN_FILE"),
+        (6, "email", "DUPLICATE"),
+        (7, "role", "FORBIDDEN"),
+        (8, "role", "UNKNOWN_ROLE"),
+    ] {
+        assert_eq!(errors(line), [(field.to_string(), code.to_string())], "line {}", line);
+        assert!(rows[line - 2]["user_id"].is_null(), "line {}", line);
+    }
+
+    // Imported users are unverified members, each invited to set a password
+    let mut conn = config.pool().get().expect("Failed to get a connection");
+    let ada: User = users::table.filter(users::email.eq(&first)).select(User::as_select()).first(&mut conn).unwrap();
+    assert_eq!(rows[0]["user_id"], json!(ada.id));
+    assert_eq!((ada.org_id, ada.role, ada.email_verified, ada.phone_number.as_str()), (org_id, Role::Operator, false, phone_number.as_str()));
+    let grace: User = users::table.filter(users::email.eq(&second)).select(User::as_select()).first(&mut conn).unwrap();
+    assert_eq!(grace.role, Role::Manager);
+    let invitation = queued_for(&mut conn, &first);
+    assert_eq!(invitation.len(), 1);
+    assert_eq!(queued_for(&mut conn, &second).len(), 1);
+    assert!(queued_for(&mut conn, &format!("boss.{}@example.com", unique)).is_empty());
+
+    // Following the invitation sets their password and verifies their email
+    let token = invitation[0].text.split("token=").nth(1).unwrap().split_whitespace().next().unwrap().to_string();
+    let response = test::call_service(&app, test::TestRequest::post()
+        .uri("/v1/auth/reset-password")
+        .set_json(json!({"token": token, "password": "Imported1!Password"}))
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let ada: User = users::table.find(ada.id).select(User::as_select()).first(&mut conn).unwrap();
+    assert!(ada.email_verified);
+
+    // The file needs its required columns
+    let response = test::call_service(&app, import(&manager, "text/csv", "email,first_name\na@example.com,A\n".to_string())).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!((body["details"]["field"].as_str(), body["details"]["code"].as_str()), (Some("file"), Some("MISSING_COLUMN")));
+
+    // Operators and other organizations can't import
+    for user in [&operator, &outsider] {
+        let status = match test::try_call_service(&app, import(user, "text/csv", csv.clone())).await {
+            Ok(response) => response.status(),
+            Err(error) => error.error_response().status(),
+        };
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
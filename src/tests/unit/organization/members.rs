@@ -0,0 +1,107 @@
+use actix_web::{http::StatusCode, test};
+use diesel::prelude::*;
+use serde_json::json;
+
+use crate::{
+    db::{models::auth::Role, schema::organizations},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_members_are_managed_keeping_an_admin() {
+    setup();
+    let config = app_config();
+    let (organization, admin, owner, manager, operator, outsider) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let member = |role| UserFactory::new().in_org(&organization).role(role).verified();
+        let admin = member(Role::Admin).create(&mut conn).await.unwrap();
+        let owner = member(Role::Manager).create(&mut conn).await.unwrap();
+        let manager = member(Role::Manager).create(&mut conn).await.unwrap();
+        let operator = member(Role::Operator).create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (organization, admin, owner, manager, operator, outsider)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let members_uri = format!("/v1/organizations/{}/members", organization.id);
+    let list = |user, query: &str| test::TestRequest::get().uri(&format!("{}{}", members_uri, query)).insert_header(bearer(user, &config));
+    let change_role = |user, member: &crate::db::models::auth::User, role: &str| {
+        test::TestRequest::patch()
+            .uri(&format!("{}/{}", members_uri, member.id))
+            .insert_header(bearer(user, &config))
+            .set_json(json!({ "role": role }))
+    };
+    let remove = |user, member: &crate::db::models::auth::User| {
+        test::TestRequest::delete().uri(&format!("{}/{}", members_uri, member.id)).insert_header(bearer(user, &config))
+    };
+
+    // Managers and admins of the organization only
+    let (status, _) = send(&app, list(&operator, "")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, list(&outsider, "")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = send(&app, list(&manager, "?role=Manager,Operator")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().unwrap().len(), 3);
+    assert_eq!(body["meta"]["total_items"], 3);
+
+    // Only admins deal with admins
+    let (status, _) = send(&app, change_role(&manager, &operator, "Admin")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, change_role(&manager, &admin, "Manager")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, remove(&manager, &admin)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = send(&app, change_role(&manager, &operator, "Manager")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["role"], "Manager");
+    let (status, _) = send(&app, change_role(&admin, &outsider, "Manager")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // The last admin stays
+    let (status, body) = send(&app, change_role(&admin, &admin, "Manager")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["details"]["code"], "LAST_ADMIN");
+    let (status, body) = send(&app, remove(&admin, &admin)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["details"]["code"], "LAST_ADMIN");
+
+    // So does the owner, until they hand the organization over
+    let (status, body) = send(&app, change_role(&admin, &owner, "Admin")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["role"], "Admin");
+    {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        diesel::update(organizations::table.find(organization.id))
+            .set(organizations::owner_id.eq(Some(owner.id)))
+            .execute(&mut conn)
+            .unwrap();
+    }
+    let (status, body) = send(&app, change_role(&admin, &owner, "Operator")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["details"]["code"], "OWNER");
+    let (status, body) = send(&app, remove(&admin, &owner)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["details"]["code"], "OWNER");
+
+    // Removed members can't act any more and are listed apart
+    let (status, _) = send(&app, remove(&admin, &manager)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, list(&manager, "")).await;
+    assert!(matches!(status, StatusCode::UNAUTHORIZED | StatusCode::NOT_FOUND), "{}", status);
+    let (status, _) = send(&app, remove(&admin, &manager)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, body) = send(&app, list(&admin, "?removed=true")).await;
+    let removed: Vec<&str> = body["data"].as_array().unwrap().iter().map(|user| user["id"].as_str().unwrap()).collect();
+    assert_eq!(removed, [manager.id.to_string()]);
+
+    // With another admin, an admin can step down
+    let (status, body) = send(&app, change_role(&admin, &admin, "Manager")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["role"], "Manager");
+}
@@ -1,2 +1,11 @@
+pub mod activity;
+pub mod archival;
 pub mod domain;
+pub mod hierarchy;
+pub mod members;
+pub mod ownership;
+pub mod quotas;
 pub mod repository;
+pub mod settings;
+pub mod import;
+pub mod users;
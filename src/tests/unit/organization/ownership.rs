@@ -0,0 +1,72 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::{json, Value};
+
+use crate::{
+    db::{
+        models::auth::Role,
+        repositories::{auth::UserRepositoryImpl, Repository},
+    },
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_ownership_changes_hands_once_the_new_owner_accepts() {
+    setup();
+    let config = app_config();
+    let (organization, owner, other_admin, manager, unverified, outsider) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let owner = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let other_admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let manager = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let unverified = UserFactory::new().in_org(&organization).role(Role::Operator).create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        (organization, owner, other_admin, manager, unverified, outsider)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let uri = format!("/v1/organizations/{}/transfer-ownership", organization.id);
+    let transfer = |as_user, body: Value| test::TestRequest::post().uri(&uri).insert_header(as_user).set_json(body);
+    let accept_uri = format!("{}/accept", uri);
+    let accept = |as_user| test::TestRequest::post().uri(&accept_uri).insert_header(as_user);
+
+    for (new_owner, code) in [(&owner, "SELF"), (&outsider, "NOT_A_MEMBER"), (&unverified, "UNVERIFIED")] {
+        let (status, body) = send(&app, transfer(bearer(&owner, &config), json!({ "new_owner_id": new_owner.id }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["details"]["field"], "new_owner_id");
+        assert_eq!(body["details"]["code"], code);
+    }
+    let (status, _) = send(&app, transfer(bearer(&manager, &config), json!({ "new_owner_id": owner.id }))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Without a recorded owner any admin starts a transfer, and the latest one wins
+    let (status, _) = send(&app, transfer(bearer(&other_admin, &config), json!({ "new_owner_id": manager.id }))).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let (status, body) = send(&app, transfer(bearer(&owner, &config), json!({ "new_owner_id": manager.id }))).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(body["from_user_id"], owner.id.to_string());
+    assert_eq!(body["to_user_id"], manager.id.to_string());
+    assert_eq!(body["accepted_at"], Value::Null);
+
+    let (status, _) = send(&app, accept(bearer(&other_admin, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, body) = send(&app, accept(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["owner_id"], manager.id.to_string());
+    let (status, _) = send(&app, accept(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        assert_eq!(UserRepositoryImpl.find_by_id(&mut conn, manager.id).await.unwrap().role, Role::Admin);
+        assert_eq!(UserRepositoryImpl.find_by_id(&mut conn, owner.id).await.unwrap().role, Role::Manager);
+    }
+
+    // Once an owner is recorded, other admins can't hand the organization on
+    let (status, _) = send(&app, transfer(bearer(&other_admin, &config), json!({ "new_owner_id": owner.id }))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
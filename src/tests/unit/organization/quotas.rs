@@ -0,0 +1,98 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    db::models::auth::Role,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_quotas_cap_users_and_usage_reports_them() {
+    setup();
+    let mut config = app_config();
+    let (organization, admin, operator, outsider, platform_admin) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let platform_admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (organization, admin, operator, outsider, platform_admin)
+    };
+    config.auth.platform_admins.push(platform_admin.id);
+    let app = test::init_service(server::app(&config)).await;
+    let quotas_uri = format!("/v1/admin/organizations/{}/quotas", organization.id);
+    let set_quotas = |body: Value| test::TestRequest::put().uri(&quotas_uri).insert_header(bearer(&platform_admin, &config)).set_json(body);
+    let get_quotas = |as_user| test::TestRequest::get().uri(&quotas_uri).insert_header(as_user);
+    let usage_uri = format!("/v1/organizations/{}/usage", organization.id);
+    let usage = |as_user| test::TestRequest::get().uri(&usage_uri).insert_header(as_user);
+    let invite = || {
+        test::TestRequest::post()
+            .uri("/v1/invitations")
+            .insert_header(bearer(&admin, &config))
+            .set_json(json!({ "email": format!("invitee-{}@example.com", Uuid::new_v4()), "role": "Operator" }))
+    };
+
+    // Unlimited until a plan says otherwise
+    let (status, body) = send(&app, usage(bearer(&operator, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["users"], 2);
+    assert_eq!(body["max_users"], Value::Null);
+    assert_eq!(body["telemetry_retention_days"], 90);
+
+    // Plans are the platform's to change; admins only read their own
+    let (status, body) = send(&app, test::TestRequest::put().uri(&quotas_uri).insert_header(bearer(&admin, &config)).set_json(json!({}))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["details"]["code"], "PLATFORM_ADMIN_REQUIRED");
+    let (status, _) = send(&app, get_quotas(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, get_quotas(bearer(&outsider, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, get_quotas(bearer(&platform_admin, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    for (input, field) in [
+        (json!({ "max_users": 0 }), "max_users"),
+        (json!({ "telemetry_retention_days": 4000 }), "telemetry_retention_days"),
+    ] {
+        let (status, body) = send(&app, set_quotas(input)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["details"]["field"], field);
+    }
+    let (status, body) = send(&app, set_quotas(json!({ "max_users": 3, "max_active_blocks": 10, "telemetry_retention_days": 30 }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["max_users"], 3);
+
+    let (status, _) = send(&app, invite()).await;
+    assert_eq!(status, StatusCode::CREATED);
+    {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().in_org(&organization).role(Role::Operator).create(&mut conn).await.unwrap();
+    }
+    let (status, body) = send(&app, invite()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "QuotaExceeded");
+    assert_eq!(body["details"], json!({ "quota": "users", "limit": 3, "usage": 3 }));
+
+    let (status, body) = send(&app, usage(bearer(&operator, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["users"], 3);
+    assert_eq!(body["max_users"], 3);
+    assert_eq!(body["max_active_blocks"], 10);
+    assert_eq!(body["telemetry_retention_days"], 30);
+    let (status, _) = send(&app, usage(bearer(&outsider, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Lifting the limit makes room again
+    let (status, body) = send(&app, set_quotas(json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["max_users"], Value::Null);
+    let (status, _) = send(&app, invite()).await;
+    assert_eq!(status, StatusCode::CREATED);
+}
@@ -1,12 +1,18 @@
-use chrono::Utc;
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
 use uuid::Uuid;
 use crate::{
     api::utils::PaginationParams, db::{
-        models::organization::Organization,
+        count,
+        models::{OrganizationFilter, OrganizationSort},
+        schema::organizations,
         repositories::{
             organization::OrganizationRepositoryImpl, OrganizationRepository, Repository
         },
-    }, error::Result, tests::{common::helpers::TestDb, setup}
+    },
+    domain::organization::OrganizationService,
+    error::{ErrorCode, Result},
+    tests::{common::helpers::TestDb, factories::OrganizationFactory, setup},
 };
 
 #[tokio::test]
@@ -17,13 +23,7 @@ async fn test_organization_crud() -> Result<()> {
             let repo = OrganizationRepositoryImpl;
 
             // Test Create
-            let org = Organization {
-                id: Uuid::new_v4(),
-                name: format!("Test Org {}", Uuid::new_v4()),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-            };
+            let org = OrganizationFactory::new().build();
             let created_org = repo.create(conn, &org).await?;
             assert_eq!(created_org.name, org.name);
             assert_eq!(created_org.id, org.id);
@@ -65,20 +65,191 @@ async fn test_organization_queries() -> Result<()> {
             let repo = OrganizationRepositoryImpl;
 
             // Test find by name
-            let name = format!("Test Org {}", Uuid::new_v4());
-            let org = Organization {
-                id: Uuid::new_v4(),
-                name: name.clone(),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                deleted_at: None,
-            };
-            let created_org = repo.create(conn, &org).await?;
-            
-            let found_org = repo.find_by_name(conn, &name).await?;
+            let created_org = OrganizationFactory::new().create(conn).await?;
+
+            let found_org = repo.find_by_name(conn, &created_org.name).await?;
             assert_eq!(found_org.unwrap().id, created_org.id);
 
             Ok(())
         })
     }).await
-} 
\ No newline at end of file
+} 
+#[tokio::test]
+async fn test_organization_batch_loading() -> Result<()> {
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let repo = OrganizationRepositoryImpl;
+
+            let created = OrganizationFactory::new().create_many(conn, 3).await?;
+
+            // Duplicate and unknown ids are tolerated
+            let ids = vec![created[0].id, created[1].id, created[0].id, Uuid::new_v4()];
+            let loaded = repo.find_by_ids(conn, &ids).await?;
+            assert_eq!(loaded.len(), 2);
+            assert!(loaded.iter().all(|org| org.id == created[0].id || org.id == created[1].id));
+
+            // Soft-deleted organizations are not loaded
+            repo.soft_delete(conn, created[2].id).await?;
+            let found = repo.find_by_ids(conn, &[created[2].id]).await?;
+            assert!(found.is_empty());
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_organization_count() -> Result<()> {
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let repo = OrganizationRepositoryImpl;
+            let before = repo.count(conn).await?;
+            assert!(before.exact, "Small tables should be counted exactly");
+
+            OrganizationFactory::new().create(conn).await?;
+
+            let after = repo.count(conn).await?;
+            assert_eq!(after.total, before.total + 1);
+
+            // A zero threshold always switches to the planner estimate
+            let estimated = count::count_with_estimate(
+                conn,
+                organizations::table.filter(organizations::deleted_at.is_null()),
+                0,
+                |_| unreachable!("exact count should not run"),
+            )?;
+            assert!(!estimated.exact);
+            assert!(estimated.total >= 0);
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_organization_search() -> Result<()> {
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let repo = OrganizationRepositoryImpl;
+            let marker = Uuid::new_v4().simple().to_string();
+            let now = Utc::now();
+            let root = OrganizationFactory::new()
+                .name(format!("Root {}", marker))
+                .created_at(now - Duration::days(4))
+                .create(conn)
+                .await?;
+            for (label, age) in [("Birch", 3), ("Alder", 2), ("Cedar", 1)] {
+                OrganizationFactory::new()
+                    .name(format!("{} {}", label, marker))
+                    .child_of(&root)
+                    .created_at(now - Duration::days(age))
+                    .create(conn)
+                    .await?;
+            }
+            OrganizationFactory::new()
+                .name(format!("Gone {}", marker))
+                .child_of(&root)
+                .deleted_at(now)
+                .create(conn)
+                .await?;
+            OrganizationFactory::new()
+                .name(format!("Stray {}", marker))
+                .deleted_at(now)
+                .create(conn)
+                .await?;
+            let names = |found: &[crate::db::models::Organization]| {
+                found.iter().map(|org| org.name.split(' ').next().unwrap().to_string()).collect::<Vec<_>>()
+            };
+
+            // Newest first by default, removed organizations left out
+            let filter = OrganizationFilter { name_contains: Some(marker.to_uppercase()), ..Default::default() };
+            let (found, total) = repo.search(conn, root.id, &filter, &PaginationParams::new(1, 2)).await?;
+            assert_eq!(names(&found), ["Cedar", "Alder"]);
+            assert_eq!(total.total, 4);
+            assert!(total.exact);
+
+            let filter = OrganizationFilter {
+                name_contains: Some(marker.clone()),
+                sort: OrganizationSort::Name,
+                descending: false,
+                ..Default::default()
+            };
+            let (found, _) = repo.search(conn, root.id, &filter, &PaginationParams::new(1, 10)).await?;
+            assert_eq!(names(&found), ["Alder", "Birch", "Cedar", "Root"]);
+
+            // The count follows the filters
+            let filter = OrganizationFilter {
+                name_contains: Some(marker.clone()),
+                created_after: Some(now - Duration::hours(36)),
+                ..Default::default()
+            };
+            let (found, total) = repo.search(conn, root.id, &filter, &PaginationParams::new(1, 10)).await?;
+            assert_eq!(names(&found), ["Cedar"]);
+            assert_eq!(total.total, 1);
+
+            // Admins can list the removed ones too, within their tenant
+            let filter = OrganizationFilter {
+                name_contains: Some(marker.clone()),
+                sort: OrganizationSort::Name,
+                descending: false,
+                include_deleted: true,
+                ..Default::default()
+            };
+            let (found, total) = repo.search(conn, root.id, &filter, &PaginationParams::new(1, 10)).await?;
+            assert_eq!(names(&found), ["Alder", "Birch", "Cedar", "Gone", "Root"]);
+            assert_eq!(total.total, 5);
+
+            let filter = OrganizationFilter { name_contains: Some(format!("{}%", marker)), ..Default::default() };
+            let (found, total) = repo.search(conn, root.id, &filter, &PaginationParams::new(1, 10)).await?;
+            assert!(found.is_empty());
+            assert_eq!(total.total, 0);
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_organization_restore() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = OrganizationService::new(OrganizationRepositoryImpl);
+            let marker = Uuid::new_v4().simple().to_string();
+            let company = OrganizationFactory::new().name(format!("Company {}", marker)).create(conn).await?;
+            let division = OrganizationFactory::new().name(format!("Division {}", marker)).child_of(&company).create(conn).await?;
+
+            let err = service.restore(conn, company.id, company.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+            let err = service.restore(conn, company.id, Uuid::new_v4()).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            service.delete(conn, company.id, division.id).await?;
+            service.delete(conn, company.id, company.id).await?;
+
+            // Other tenants' organizations answer as if they didn't exist
+            let stray = OrganizationFactory::new().name(format!("Stray {}", marker)).deleted_at(Utc::now()).create(conn).await?;
+            let err = service.restore(conn, company.id, stray.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            // The division waits for the company above it
+            let err = service.restore(conn, company.id, division.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            let restored = service.restore(conn, company.id, company.id).await?;
+            assert!(restored.deleted_at.is_none());
+            assert_eq!(service.get(conn, company.id, company.id).await?.name, company.name);
+
+            // A live organization took the division's name meanwhile
+            let taken = OrganizationFactory::new().name(division.name.clone()).create(conn).await?;
+            let err = service.restore(conn, company.id, division.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            service.delete(conn, taken.id, taken.id).await?;
+            let restored = service.restore(conn, company.id, division.id).await?;
+            assert_eq!(restored.parent_id, Some(company.id));
+
+            Ok(())
+        })
+    }).await
+}
@@ -0,0 +1,78 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::{json, Value};
+
+use crate::{
+    db::models::auth::Role,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_admins_change_settings_members_inherit() {
+    setup();
+    let config = app_config();
+    let (organization, admin, operator, outsider) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let operator = UserFactory::new().in_org(&organization).role(Role::Operator).verified().create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (organization, admin, operator, outsider)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let uri = format!("/v1/organizations/{}/settings", organization.id);
+    let get = |as_user| test::TestRequest::get().uri(&uri).insert_header(as_user);
+    let patch = |as_user, body: Value| test::TestRequest::patch().uri(&uri).insert_header(as_user).set_json(body);
+
+    let (status, body) = send(&app, get(bearer(&operator, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["default_units"], "metric");
+    assert_eq!(body["fiscal_year_start_month"], 1);
+    assert_eq!(body["timezone"], "UTC");
+    assert_eq!(body["required_safety_forms"], json!([]));
+
+    let (status, body) = send(&app, patch(bearer(&admin, &config), json!({
+        "default_units": "imperial",
+        "fiscal_year_start_month": 4,
+        "timezone": "-0800",
+        "required_safety_forms": [" Tailgate meeting ", "Hazard assessment", "tailgate meeting"]
+    })))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["timezone"], "-08:00");
+    assert_eq!(body["required_safety_forms"], json!(["Tailgate meeting", "Hazard assessment"]));
+
+    // Left out fields keep their value
+    let (status, body) = send(&app, patch(bearer(&admin, &config), json!({ "fiscal_year_start_month": 7 }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["default_units"], "imperial");
+    assert_eq!(body["fiscal_year_start_month"], 7);
+    assert_eq!(body["required_safety_forms"].as_array().unwrap().len(), 2);
+
+    for (input, field) in [
+        (json!({ "fiscal_year_start_month": 13 }), "fiscal_year_start_month"),
+        (json!({ "timezone": "America/Vancouver" }), "timezone"),
+        (json!({ "required_safety_forms": [""] }), "required_safety_forms"),
+    ] {
+        let (status, body) = send(&app, patch(bearer(&admin, &config), input)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["details"]["field"], field);
+    }
+    let (status, _) = send(&app, patch(bearer(&admin, &config), json!({ "currency": "CAD" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send(&app, patch(bearer(&operator, &config), json!({ "default_units": "metric" }))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, get(bearer(&outsider, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Members who never saved preferences see the organization's units and timezone
+    let (status, body) = send(&app, test::TestRequest::get().uri("/v1/me/preferences").insert_header(bearer(&operator, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["units"], "imperial");
+    assert_eq!(body["timezone"], "-08:00");
+}
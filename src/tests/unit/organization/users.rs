@@ -0,0 +1,94 @@
+use actix_web::{http::StatusCode, test};
+use chrono::{Duration, Utc};
+use serde_json::Value;
+
+use crate::{
+    db::models::auth::Role,
+    domain::TokenManager,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+/// Last names of the users a listing returned, in order
+fn last_names(body: &Value) -> Vec<&str> {
+    body["data"].as_array().unwrap().iter().map(|user| user["last_name"].as_str().unwrap()).collect()
+}
+
+#[actix_rt::test]
+async fn test_organization_users_are_searched_filtered_and_sorted() {
+    setup();
+    let config = app_config();
+    let (admin, org_id, outsider) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let user = |first_name: &str, last_name: &str, role: Role, days_ago: i64| {
+            UserFactory::new()
+                .in_org(&organization)
+                .first_name(first_name)
+                .last_name(last_name)
+                .role(role)
+                .created_at(Utc::now() - Duration::days(days_ago))
+        };
+        let admin = user("Ada", "Admin", Role::Admin, 4).verified().create(&mut conn).await.unwrap();
+        user("Jonathan", "Smith", Role::Manager, 3).create(&mut conn).await.unwrap();
+        user("Jane", "Smithers", Role::Operator, 2).create(&mut conn).await.unwrap();
+        user("Bob", "Jones", Role::Operator, 1).deactivated().create(&mut conn).await.unwrap();
+        user("Gone", "Away", Role::Operator, 0).deleted_at(Utc::now()).create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().verified().create(&mut conn).await.unwrap();
+        (admin, organization.id, outsider)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let token = TokenManager::generate_token(&admin, &config).unwrap();
+    let list = |params: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/v1/organizations/{}/users?{}", org_id, params))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+
+    let response = test::call_service(&app, list("")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(last_names(&body), ["Admin", "Jones", "Smithers", "Smith"]);
+    assert_eq!(body["meta"]["total_items"], 4);
+
+    // Every word has to match; "smi" is a substring, "jonathon" a typo
+    for (params, expected) in [
+        ("query=smi&sort=-name", vec!["Smith", "Smithers"]),
+        ("query=smith", vec!["Smith", "Smithers"]),
+        ("query=jane%20smi", vec!["Smithers"]),
+        ("query=jonathon", vec!["Smith"]),
+        ("query=100%25", vec![]),
+        ("role=operator", vec!["Jones", "Smithers"]),
+        ("role=Admin,Manager&sort=-name", vec!["Smith", "Admin"]),
+        ("sort=role", vec!["Admin", "Smith", "Jones", "Smithers"]),
+        ("sort=-created_at", vec!["Jones", "Smithers", "Smith", "Admin"]),
+        ("active=false", vec!["Jones"]),
+        ("active=true&role=operator", vec!["Smithers"]),
+        ("removed=true", vec!["Away"]),
+        ("sort=created_at&per_page=2&page=2", vec!["Smithers", "Jones"]),
+    ] {
+        let response = test::call_service(&app, list(params)).await;
+        assert_eq!(response.status(), StatusCode::OK, "{}", params);
+        let body: Value = test::read_body_json(response).await;
+        assert_eq!(last_names(&body), expected, "{}", params);
+    }
+
+    for (params, field) in [("role=Owner", "role"), ("sort=password", "sort")] {
+        let response = test::call_service(&app, list(params)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", params);
+        let body: Value = test::read_body_json(response).await;
+        assert_eq!(body["details"]["field"], field);
+    }
+
+    // Only members list an organization's users
+    let response = test::call_service(&app, test::TestRequest::get()
+        .uri(&format!("/v1/organizations/{}/users", org_id))
+        .insert_header(bearer(&outsider, &config))
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
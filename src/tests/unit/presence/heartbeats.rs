@@ -0,0 +1,76 @@
+use actix_web::{http::StatusCode, test};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use serde_json::{json, Value};
+
+use crate::{
+    db::{
+        models::auth::{Role, User},
+        schema::devices,
+    },
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_dispatchers_see_who_is_online() {
+    setup();
+    let config = app_config();
+    let (dispatcher, faller, skidder, outsider) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let dispatcher = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let faller = UserFactory::new().in_org(&organization).verified().create(&mut conn).await.unwrap();
+        let skidder = UserFactory::new().in_org(&organization).verified().create(&mut conn).await.unwrap();
+        let outsider = UserFactory::new().verified().create(&mut conn).await.unwrap();
+        (dispatcher, faller, skidder, outsider)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let heartbeat = |user: &User, body: Value| {
+        test::TestRequest::post().uri("/v1/presence/heartbeat").insert_header(bearer(user, &config)).set_json(body)
+    };
+    let online = |query: &str, user: &User| {
+        test::TestRequest::get().uri(&format!("/v1/presence{}", query)).insert_header(bearer(user, &config))
+    };
+
+    let beat = json!({ "device_id": "tablet-1", "device_name": "Harvester tablet", "app_version": "2.4.1", "battery_level": 82 });
+    assert_eq!(send(&app, heartbeat(&faller, beat)).await.0, StatusCode::NO_CONTENT);
+    assert_eq!(send(&app, heartbeat(&skidder, json!({ "device_id": "phone-7" }))).await.0, StatusCode::NO_CONTENT);
+    assert_eq!(send(&app, heartbeat(&outsider, json!({ "device_id": "phone-9" }))).await.0, StatusCode::NO_CONTENT);
+    // A later heartbeat updates the same device
+    let beat = json!({ "device_id": "tablet-1", "app_version": "2.4.1", "battery_level": 79 });
+    assert_eq!(send(&app, heartbeat(&faller, beat)).await.0, StatusCode::NO_CONTENT);
+
+    // The skidder's phone went quiet ten minutes ago
+    {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        diesel::update(devices::table.filter(devices::user_id.eq(skidder.id)))
+            .set(devices::last_heartbeat_at.eq(Utc::now() - Duration::minutes(10)))
+            .execute(&mut conn)
+            .unwrap();
+    }
+
+    let (status, body) = send(&app, online("", &dispatcher)).await;
+    assert_eq!(status, StatusCode::OK);
+    let devices = body["data"].as_array().unwrap();
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0]["user_id"], json!(faller.id));
+    assert_eq!(devices[0]["device_name"], "Harvester tablet");
+    assert_eq!(devices[0]["app_version"], "2.4.1");
+    assert_eq!(devices[0]["battery_level"], 79);
+
+    let (_, body) = send(&app, online("?minutes=15", &dispatcher)).await;
+    let users: Vec<&str> = body["data"].as_array().unwrap().iter().map(|device| device["user_id"].as_str().unwrap()).collect();
+    assert_eq!(users, [faller.id.to_string(), skidder.id.to_string()]);
+    assert_eq!(send(&app, online("?minutes=0", &dispatcher)).await.0, StatusCode::BAD_REQUEST);
+
+    assert_eq!(send(&app, online("", &faller)).await.0, StatusCode::FORBIDDEN);
+    let (status, body) = send(&app, heartbeat(&faller, json!({ "device_id": "tablet-1", "battery_level": 140 }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["field"], "battery_level");
+    assert_eq!(send(&app, heartbeat(&faller, json!({ "device_id": " " }))).await.0, StatusCode::BAD_REQUEST);
+}
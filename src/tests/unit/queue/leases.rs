@@ -0,0 +1,90 @@
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+use crate::{
+    api::utils::PaginationParams,
+    db::{
+        models::QueuedJob,
+        repositories::{JobQueueRepository, JobQueueRepositoryImpl},
+        schema::{dead_letter_jobs, queued_jobs},
+    },
+    error::Result,
+    tests::{common::helpers::TestDb, setup},
+};
+
+#[tokio::test]
+async fn test_claimed_job_is_hidden_until_its_timeout() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let repo = JobQueueRepositoryImpl;
+            let kind = format!("test-kind-{}", Uuid::new_v4());
+            let kinds = [kind.as_str()];
+            let now = Utc::now();
+
+            let job = repo.enqueue(conn, &QueuedJob::new(&kind, serde_json::json!({ "n": 1 }), 2)).await?;
+            let claimed = repo.claim(conn, &kinds, "a", now + Duration::minutes(5)).await?.unwrap();
+            assert_eq!(claimed.id, job.id);
+            assert_eq!(claimed.attempts, 1);
+            assert!(repo.claim(conn, &kinds, "b", now + Duration::minutes(5)).await?.is_none());
+            assert!(!repo.extend(conn, job.id, "b", now + Duration::minutes(10)).await?);
+            assert!(repo.extend(conn, job.id, "a", now + Duration::minutes(10)).await?);
+
+            // A failed attempt is released for a later retry
+            repo.retry(conn, job.id, "a", "boom", now - Duration::seconds(1)).await?;
+            let retried = repo.claim(conn, &kinds, "b", now - Duration::seconds(1)).await?.unwrap();
+            assert_eq!(retried.attempts, 2);
+            assert_eq!(retried.last_error.as_deref(), Some("boom"));
+            assert!(retried.is_exhausted());
+
+            // An expired visibility timeout lets another worker take over
+            let taken_over = repo.claim(conn, &kinds, "a", now + Duration::minutes(5)).await?.unwrap();
+            assert_eq!(taken_over.locked_by.as_deref(), Some("a"));
+            repo.complete(conn, job.id, "a").await?;
+            assert_eq!(repo.count(conn, Some(&kind)).await?.total, 0);
+
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_exhausted_job_is_dead_lettered_and_requeued() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let repo = JobQueueRepositoryImpl;
+            let kind = format!("test-kind-{}", Uuid::new_v4());
+            let kinds = [kind.as_str()];
+            let now = Utc::now();
+
+            let job = repo.enqueue(conn, &QueuedJob::new(&kind, serde_json::json!({ "n": 2 }), 1)).await?;
+            let claimed = repo.claim(conn, &kinds, "a", now + Duration::minutes(5)).await?.unwrap();
+            assert!(claimed.is_exhausted());
+            let dead = repo.dead_letter(conn, claimed, "a", "gave up").await?;
+            assert_eq!(dead.id, job.id);
+            assert_eq!(dead.last_error.as_deref(), Some("gave up"));
+            assert_eq!(repo.count(conn, Some(&kind)).await?.total, 0);
+
+            let pagination = PaginationParams::new(1, 10);
+            let dead_letters = repo.list_dead_letters(conn, Some(&kind), &pagination).await?;
+            assert_eq!(dead_letters.len(), 1);
+            assert_eq!(repo.count_dead_letters(conn, Some(&kind)).await?.total, 1);
+
+            let requeued = repo.requeue(conn, job.id, 3).await?;
+            assert_eq!(requeued.id, job.id);
+            assert_eq!(requeued.attempts, 0);
+            assert_eq!(requeued.max_attempts, 3);
+            assert_eq!(requeued.payload, serde_json::json!({ "n": 2 }));
+            assert!(repo.find_dead_letter(conn, job.id).await.is_err());
+            assert!(repo.requeue(conn, job.id, 3).await.is_err());
+            assert!(repo.discard(conn, job.id).await.is_err());
+
+            diesel::delete(queued_jobs::table.filter(queued_jobs::kind.eq(&kind))).execute(conn).unwrap();
+            diesel::delete(dead_letter_jobs::table.filter(dead_letter_jobs::kind.eq(&kind))).execute(conn).unwrap();
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,112 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use serde_json::json;
+use crate::{
+    api::{
+        resources::report::dto::{SaveReportInput, ScheduleReportInput},
+        utils::PaginationParams,
+    },
+    db::{
+        models::{auth::Role, QueuedJob, ReportDeliveryStatus},
+        repositories::{ReportRepositoryImpl, ReportScheduleRepository, ReportScheduleRepositoryImpl},
+        schema::queued_jobs,
+    },
+    domain::report::{ReportFormat, ReportScheduleService, ReportService},
+    error::{ErrorCode, Result},
+    infrastructure::{
+        email::{DevMailbox, EmailMessage},
+        Mailer, ObjectStorage,
+    },
+    jobs::{email::EMAIL_JOB, report_delivery::ReportDeliverer},
+    tests::{
+        common::helpers::TestDb,
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+    utils::QueueConfig,
+};
+
+fn weekly(recipients: &[&str]) -> ScheduleReportInput {
+    ScheduleReportInput {
+        schedule: "0 0 6 * * Mon".into(),
+        recipients: recipients.iter().map(|recipient| recipient.to_string()).collect(),
+        format: Some(ReportFormat::Csv),
+        parameters: HashMap::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_due_schedules_are_mailed_and_recorded() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let schedules = ReportScheduleService::new(ReportRepositoryImpl, ReportScheduleRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            UserFactory::new().in_org(&organization).role(Role::Operator).create_many(conn, 2).await?;
+            let input: SaveReportInput = serde_json::from_value(json!({
+                "name": "Members by role",
+                "definition": {
+                    "dataset": "users",
+                    "group_by": ["role"],
+                    "columns": [{ "field": "role" }, { "field": "id", "aggregate": "count" }]
+                }
+            }))
+            .unwrap();
+            let report = ReportService::new(ReportRepositoryImpl).create(conn, organization.id, None, input).await?;
+
+            let err = schedules.set(conn, organization.id, report.id, None, weekly(&["not-an-email"])).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+            let off = ScheduleReportInput { schedule: "off".into(), ..weekly(&["a@example.com"]) };
+            let err = schedules.set(conn, organization.id, report.id, None, off).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let schedule = schedules
+                .set(conn, organization.id, report.id, None, weekly(&["b@example.com", "A@example.com", "a@example.com"]))
+                .await?;
+            assert_eq!(schedule.recipients, vec!["A@example.com", "b@example.com"]);
+            assert!(schedule.next_run_at > Utc::now());
+
+            let deliverer = ReportDeliverer::new(
+                Mailer::new(Arc::new(DevMailbox::new(10)), "no-reply@example.com")?,
+                ObjectStorage::in_memory(),
+                QueueConfig::default(),
+            );
+            assert!(deliverer.deliver_due(conn).await?.is_empty());
+
+            ReportScheduleRepositoryImpl.set_next_run(conn, schedule.id, Utc::now() - Duration::minutes(1)).await?;
+            let deliveries = deliverer.deliver_due(conn).await?;
+            assert_eq!(deliveries.len(), 1);
+            assert_eq!(deliveries[0].status, ReportDeliveryStatus::Queued.as_str());
+            assert_eq!(deliveries[0].row_count, Some(1));
+            assert!(deliveries[0].file_key.is_none());
+            assert!(deliverer.deliver_due(conn).await?.is_empty());
+
+            let messages: Vec<EmailMessage> = queued_jobs::table
+                .filter(queued_jobs::kind.eq(EMAIL_JOB))
+                .load::<QueuedJob>(conn)
+                .unwrap()
+                .into_iter()
+                .filter_map(|job| serde_json::from_value(job.payload).ok())
+                .filter(|message: &EmailMessage| message.subject == "Report: Members by role")
+                .collect();
+            assert_eq!(messages.len(), 2);
+            let attachment = &messages[0].attachments[0];
+            assert_eq!(attachment.filename, "Members by role.csv");
+            assert_eq!(attachment.bytes()?, b"role,count_id\r\nOperator,2\r\n");
+
+            let (history, total) = schedules
+                .deliveries(conn, organization.id, report.id, &PaginationParams::new(1, 20))
+                .await?;
+            assert_eq!(history.len(), 1);
+            assert_eq!(total.total, 1);
+
+            schedules.remove(conn, organization.id, report.id).await?;
+            let err = schedules.get(conn, organization.id, report.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            Ok(())
+        })
+    })
+    .await
+}
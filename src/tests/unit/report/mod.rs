@@ -0,0 +1,3 @@
+pub mod delivery;
+pub mod service;
+pub mod preferences;
@@ -0,0 +1,139 @@
+use actix_web::{http::StatusCode, test};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use serde_json::{json, Value};
+
+use crate::{
+    api::resources::sales::dto::{AwardParcelInput, CaptureBidInput, CreateTenderInput, TenderParcelInput},
+    db::{models::auth::Role, repositories::TimberSaleRepositoryImpl, schema::timber_tenders},
+    domain::sales::TimberSaleService,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[actix_rt::test]
+async fn test_report_runs_follow_the_callers_preferences() {
+    setup();
+    let config = app_config();
+    let manager = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+
+        // A contract for 1,200 m³ of sawlogs
+        let service = TimberSaleService::new(TimberSaleRepositoryImpl);
+        let details = service
+            .create_tender(&mut conn, organization.id, None, CreateTenderInput {
+                title: "Spring sale".to_string(),
+                description: None,
+                bid_deadline: Utc::now() + Duration::hours(1),
+                parcels: vec![TenderParcelInput {
+                    block_id: None,
+                    assortment: "spruce sawlog".to_string(),
+                    volume_m3: 1200.0,
+                    reserve_price: None,
+                    description: None,
+                }],
+            })
+            .await
+            .unwrap();
+        let tender_id = details.tender.id;
+        service.open_tender(&mut conn, organization.id, tender_id).await.unwrap();
+        let bid = service
+            .capture_bid(&mut conn, organization.id, tender_id, None, CaptureBidInput {
+                parcel_id: details.parcels[0].0.id,
+                bidder_name: "North Mill".to_string(),
+                bidder_email: None,
+                price_per_m3: 65.0,
+                received_at: None,
+            })
+            .await
+            .unwrap();
+        diesel::update(timber_tenders::table.find(tender_id))
+            .set(timber_tenders::bid_deadline.eq(Utc::now() - Duration::minutes(1)))
+            .execute(&mut conn)
+            .unwrap();
+        service
+            .award(&mut conn, organization.id, tender_id, None, AwardParcelInput { bid_id: bid.id })
+            .await
+            .unwrap();
+
+        UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let auth = bearer(&manager, &config);
+    let preferences = |changes: Value| {
+        test::TestRequest::patch()
+            .uri("/v1/me/preferences")
+            .insert_header(auth.clone())
+            .set_json(changes)
+            .to_request()
+    };
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/v1/me/preferences").insert_header(auth.clone()).to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["units"], "metric");
+    assert_eq!(body["timezone"], "UTC");
+    assert_eq!(body["locale"], "en");
+
+    for (changes, field) in [
+        (json!({ "timezone": "America/Vancouver" }), "timezone"),
+        (json!({ "locale": "de" }), "locale"),
+    ] {
+        let response = test::call_service(&app, preferences(changes)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: Value = test::read_body_json(response).await;
+        assert_eq!(body["details"]["field"], field);
+    }
+    let response = test::call_service(&app, preferences(json!({ "units": "furlongs" }))).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = test::call_service(&app, test::TestRequest::post()
+        .uri("/v1/reports")
+        .insert_header(auth.clone())
+        .set_json(json!({
+            "name": "Contracts",
+            "definition": {
+                "dataset": "sale_contracts",
+                "columns": [{ "field": "assortment" }, { "field": "volume" }, { "field": "created_at" }]
+            }
+        }))
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: Value = test::read_body_json(response).await;
+    let run_uri = format!("/v1/reports/{}/run", body["id"].as_str().unwrap());
+    let run = |format: &str| {
+        test::TestRequest::post()
+            .uri(&run_uri)
+            .insert_header(auth.clone())
+            .set_json(json!({ "format": format }))
+            .to_request()
+    };
+
+    let response = test::call_service(&app, run("json")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["columns"], json!(["assortment", "volume (m³)", "created_at"]));
+    assert_eq!(body["rows"][0][1], json!(1200.0));
+    assert!(body["rows"][0][2].as_str().unwrap().ends_with('Z'));
+
+    let response = test::call_service(&app, preferences(json!({ "units": "imperial", "timezone": "-08:00", "locale": "FR" }))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!((&body["units"], &body["timezone"], &body["locale"]), (&json!("imperial"), &json!("-08:00"), &json!("fr")));
+
+    let response = test::call_service(&app, run("json")).await;
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["columns"][1], "volume (ft³)");
+    assert_eq!(body["rows"][0][1], json!(42377.6));
+    assert!(body["rows"][0][2].as_str().unwrap().ends_with("-08:00"));
+
+    let response = test::call_service(&app, run("csv")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let csv = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+    assert!(csv.starts_with("assortment;volume (ft³);created_at\r\nspruce sawlog;42377,6;"), "{}", csv);
+}
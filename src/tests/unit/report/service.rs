@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+use crate::{
+    api::{resources::report::dto::SaveReportInput, utils::PaginationParams},
+    db::{models::auth::Role, repositories::ReportRepositoryImpl},
+    domain::{report::ReportService, Preferences},
+    error::{ErrorCode, Result},
+    tests::{
+        common::helpers::TestDb,
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+fn members_by_role() -> SaveReportInput {
+    serde_json::from_value(json!({
+        "name": "Members by role",
+        "definition": {
+            "dataset": "users",
+            "parameters": [{ "name": "verified", "default": true }],
+            "filters": [{ "field": "email_verified", "op": "eq", "parameter": "verified" }],
+            "group_by": ["role"],
+            "columns": [{ "field": "role" }, { "field": "id", "aggregate": "count", "label": "members" }]
+        }
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_reports_are_saved_and_run_per_organization() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = ReportService::new(ReportRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let other = OrganizationFactory::new().create(conn).await?;
+            UserFactory::new().in_org(&organization).role(Role::Operator).verified().create_many(conn, 3).await?;
+            UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(conn).await?;
+            UserFactory::new().in_org(&organization).role(Role::Operator).create(conn).await?;
+            UserFactory::new().in_org(&other).role(Role::Operator).verified().create(conn).await?;
+
+            let report = service.create(conn, organization.id, None, members_by_role()).await?;
+            let (_, result) = service.run(conn, organization.id, report.id, &HashMap::new(), &Preferences::default()).await?;
+            assert_eq!(result.columns, vec!["role", "members"]);
+            assert_eq!(result.rows, vec![vec![json!("Manager"), json!(1)], vec![json!("Operator"), json!(3)]]);
+            assert!(!result.truncated);
+
+            let unverified = HashMap::from([("verified".to_string(), json!(false))]);
+            let (_, result) = service.run(conn, organization.id, report.id, &unverified, &Preferences::default()).await?;
+            assert_eq!(result.rows, vec![vec![json!("Operator"), json!(1)]]);
+
+            let err = service.get(conn, other.id, report.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            let mut invalid = members_by_role();
+            invalid.name = "Unknown field".into();
+            invalid.definition.group_by = vec!["salary".into()];
+            let err = service.create(conn, organization.id, None, invalid).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let (reports, total) = service.list(conn, organization.id, &PaginationParams::new(1, 20)).await?;
+            assert_eq!(reports.len(), 1);
+            assert_eq!(total.total, 1);
+
+            let deleted = service.create(conn, organization.id, None, SaveReportInput { name: "Deleted".into(), ..members_by_role() }).await?;
+            service.delete(conn, organization.id, deleted.id).await?;
+            let err = service.run(conn, organization.id, deleted.id, &HashMap::new(), &Preferences::default()).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            // Last, as the unique violation aborts the test transaction
+            let err = service.create(conn, organization.id, None, members_by_role()).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,188 @@
+use actix_web::{http::StatusCode, test};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use serde_json::json;
+use uuid::Uuid;
+use crate::{
+    api::resources::admin::dto::CreateLegalHoldInput,
+    db::{
+        models::{auth::Role, Organization, TelemetryPoint},
+        repositories::{LegalHoldRepositoryImpl, Repository, TelemetryRepository, TelemetryRepositoryImpl},
+        schema::organizations,
+    },
+    domain::{
+        organization::{QuotaService, Quotas},
+        retention::{LegalHoldService, RetentionPolicy},
+    },
+    error::{ErrorCode, Result},
+    jobs::purge::Purger,
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send, TestDb},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+async fn deleted_organization(conn: &mut PgConnection, days_ago: i64) -> Result<Organization> {
+    let deleted_at = Utc::now() - Duration::days(days_ago);
+    OrganizationFactory::new().created_at(deleted_at).deleted_at(deleted_at).create(conn).await
+}
+
+fn exists(conn: &mut PgConnection, id: Uuid) -> bool {
+    organizations::table
+        .find(id)
+        .count()
+        .get_result::<i64>(conn)
+        .unwrap()
+        == 1
+}
+
+#[tokio::test]
+async fn test_purge_respects_retention_and_legal_hold() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let expired = deleted_organization(conn, 200).await?;
+            let held = deleted_organization(conn, 200).await?;
+            let recent = deleted_organization(conn, 10).await?;
+
+            let service = LegalHoldService::new(LegalHoldRepositoryImpl);
+            let hold = service.place(conn, CreateLegalHoldInput {
+                entity_type: "organizations".to_string(),
+                entity_id: held.id,
+                reason: "Pending incident investigation".to_string(),
+            }, None).await?;
+
+            let reports = Purger::new(RetentionPolicy::parse(90, "")?).run(conn);
+            assert!(reports.iter().any(|r| r.entity_type == "organizations" && r.purged >= 1));
+            assert!(!exists(conn, expired.id));
+            assert!(exists(conn, held.id));
+            assert!(exists(conn, recent.id));
+
+            // Once released, the held record is purged on the next pass
+            service.release(conn, hold.id).await?;
+            Purger::new(RetentionPolicy::parse(90, "")?).run(conn);
+            assert!(!exists(conn, held.id));
+
+            // Entities can opt out of purging entirely
+            Purger::new(RetentionPolicy::parse(0, "organizations=never")?).run(conn);
+            assert!(exists(conn, recent.id));
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_purge_drops_telemetry_past_the_plan_retention() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let standard = OrganizationFactory::new().create(conn).await?;
+            let short = OrganizationFactory::new().create(conn).await?;
+            let held = OrganizationFactory::new().create(conn).await?;
+            QuotaService::set(conn, short.id, Quotas { telemetry_retention_days: Some(30), ..Default::default() }).await?;
+            let now = Utc::now();
+            let mut points = Vec::new();
+            for organization in [&standard, &short, &held] {
+                for days_ago in [10, 60, 120] {
+                    points.push(TelemetryPoint {
+                        org_id: organization.id,
+                        machine_id: "H1".to_string(),
+                        recorded_at: now - Duration::days(days_ago),
+                        engine_hours: Some(days_ago as f64),
+                        fuel_used_l: None,
+                        latitude: None,
+                        longitude: None,
+                        received_at: now,
+                    });
+                }
+            }
+            TelemetryRepositoryImpl.upsert_points(conn, &points).await?;
+            LegalHoldService::new(LegalHoldRepositoryImpl).place(conn, CreateLegalHoldInput {
+                entity_type: "organizations".to_string(),
+                entity_id: held.id,
+                reason: "Machine incident review".to_string(),
+            }, None).await?;
+
+            let reports = Purger::new(RetentionPolicy::parse(0, "organizations=never")?).run(conn);
+            assert!(reports.iter().any(|r| r.entity_type == "machine_telemetry" && r.purged >= 3));
+
+            // 90 days by default, the plan's 30, and everything while held
+            for (organization, expected) in [(&standard, vec![60.0, 10.0]), (&short, vec![10.0]), (&held, vec![120.0, 60.0, 10.0])] {
+                let kept = TelemetryRepositoryImpl
+                    .list_points(conn, organization.id, "H1", now - Duration::days(365), now)
+                    .await?;
+                assert_eq!(kept.iter().filter_map(|p| p.engine_hours).collect::<Vec<_>>(), expected);
+            }
+
+            Ok(())
+        })
+    }).await
+}
+
+#[tokio::test]
+async fn test_legal_hold_validation() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = LegalHoldService::new(LegalHoldRepositoryImpl);
+            let entity_id = Uuid::new_v4();
+            let input = |entity_type: &str| CreateLegalHoldInput {
+                entity_type: entity_type.to_string(),
+                entity_id,
+                reason: "Audit".to_string(),
+            };
+
+            let err = service.place(conn, input("unknown"), None).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let hold = service.place(conn, input("users"), None).await?;
+            let err = service.place(conn, input("users"), None).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            service.release(conn, hold.id).await?;
+            let err = service.release(conn, hold.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            assert!(LegalHoldRepositoryImpl.find_by_id(conn, hold.id).await.is_err());
+
+            Ok(())
+        })
+    }).await
+}
+
+#[actix_rt::test]
+async fn test_legal_holds_are_managed_by_platform_admins() {
+    setup();
+    let mut config = app_config();
+    let (admin, platform_admin) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let platform_admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (admin, platform_admin)
+    };
+    config.auth.platform_admins.push(platform_admin.id);
+    let app = test::init_service(server::app(&config)).await;
+    let place = |as_user| {
+        test::TestRequest::post()
+            .uri("/v1/admin/legal-holds")
+            .insert_header(as_user)
+            .set_json(json!({ "entity_type": "organizations", "entity_id": admin.org_id, "reason": "Litigation" }))
+    };
+
+    // Holds span organizations, so an admin of one neither places nor lifts them
+    let (status, body) = send(&app, place(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["details"]["code"], "PLATFORM_ADMIN_REQUIRED");
+    let (status, hold) = send(&app, place(bearer(&platform_admin, &config))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = send(&app, test::TestRequest::get().uri("/v1/admin/legal-holds").insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let release = format!("/v1/admin/legal-holds/{}", hold["id"].as_str().unwrap());
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&release).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let response = test::call_service(&app, test::TestRequest::delete().uri(&release).insert_header(bearer(&platform_admin, &config)).to_request()).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
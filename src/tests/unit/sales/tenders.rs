@@ -0,0 +1,149 @@
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        resources::sales::dto::{AwardParcelInput, CaptureBidInput, CreateTenderInput, TenderParcelInput},
+        utils::PaginationParams,
+    },
+    db::{
+        models::TenderStatus,
+        repositories::TimberSaleRepositoryImpl,
+        schema::timber_tenders,
+    },
+    domain::sales::TimberSaleService,
+    error::{ErrorCode, Result},
+    tests::{
+        common::helpers::TestDb,
+        factories::{HarvestBlockFactory, OrganizationFactory},
+        setup,
+    },
+};
+
+fn parcel(block_id: Option<Uuid>, assortment: &str, reserve_price: Option<f64>) -> TenderParcelInput {
+    TenderParcelInput {
+        block_id,
+        assortment: assortment.to_string(),
+        volume_m3: 1200.0,
+        reserve_price,
+        description: None,
+    }
+}
+
+fn bid(parcel_id: Uuid, bidder_name: &str, price_per_m3: f64) -> CaptureBidInput {
+    CaptureBidInput {
+        parcel_id,
+        bidder_name: bidder_name.to_string(),
+        bidder_email: None,
+        price_per_m3,
+        received_at: None,
+    }
+}
+
+#[tokio::test]
+async fn test_tender_is_bid_on_and_awarded() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = TimberSaleService::new(TimberSaleRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let block_id = HarvestBlockFactory::new(&organization).create(conn).await?.id;
+
+            let err = service
+                .create_tender(conn, organization.id, None, CreateTenderInput {
+                    title: "Winter sale".to_string(),
+                    description: None,
+                    bid_deadline: Utc::now() - Duration::hours(1),
+                    parcels: vec![parcel(None, "spruce sawlog", None)],
+                })
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let details = service
+                .create_tender(conn, organization.id, None, CreateTenderInput {
+                    title: "Winter sale".to_string(),
+                    description: None,
+                    bid_deadline: Utc::now() + Duration::hours(1),
+                    parcels: vec![parcel(Some(block_id), "spruce sawlog", Some(60.0)), parcel(None, "pine pulp", None)],
+                })
+                .await?;
+            let tender_id = details.tender.id;
+            assert_eq!(details.tender.status, TenderStatus::Draft.as_str());
+            let (sawlog, pulp) = (details.parcels[0].0.id, details.parcels[1].0.id);
+
+            // Drafts take no bids
+            let err = service.capture_bid(conn, organization.id, tender_id, None, bid(sawlog, "North Mill", 65.0)).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            service.open_tender(conn, organization.id, tender_id).await?;
+            let high = service.capture_bid(conn, organization.id, tender_id, None, bid(sawlog, "North Mill", 65.0)).await?;
+            let low = service.capture_bid(conn, organization.id, tender_id, None, bid(sawlog, "River Sawmill", 55.0)).await?;
+            let pulp_bid = service.capture_bid(conn, organization.id, tender_id, None, bid(pulp, "Pulp Co", 30.0)).await?;
+            let err = service.capture_bid(conn, organization.id, tender_id, None, CaptureBidInput {
+                received_at: Some(Utc::now() + Duration::hours(2)),
+                ..bid(pulp, "Late Bidder", 40.0)
+            })
+            .await
+            .unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let details = service.get_tender(conn, organization.id, tender_id).await?;
+            assert_eq!(details.parcels.iter().map(|(_, bids)| *bids).collect::<Vec<_>>(), vec![2, 1]);
+
+            // Bids stay sealed until the deadline
+            let err = service.bids(conn, organization.id, tender_id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+            let err = service
+                .award(conn, organization.id, tender_id, None, AwardParcelInput { bid_id: high.id })
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            diesel::update(timber_tenders::table.find(tender_id))
+                .set(timber_tenders::bid_deadline.eq(Utc::now() - Duration::minutes(1)))
+                .execute(conn)
+                .unwrap();
+            let bids = service.bids(conn, organization.id, tender_id).await?;
+            let sawlog_bids = bids
+                .iter()
+                .filter(|(bid, _)| bid.parcel_id == sawlog)
+                .map(|(bid, meets_reserve)| (bid.id, *meets_reserve))
+                .collect::<Vec<_>>();
+            assert_eq!(sawlog_bids, vec![(high.id, true), (low.id, false)]);
+
+            let err = service
+                .award(conn, organization.id, tender_id, None, AwardParcelInput { bid_id: low.id })
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+            let contract = service
+                .award(conn, organization.id, tender_id, None, AwardParcelInput { bid_id: high.id })
+                .await?;
+            assert_eq!(contract.buyer_name, "North Mill");
+            assert_eq!((contract.block_id, contract.volume_m3, contract.price_per_m3), (Some(block_id), 1200.0, 65.0));
+            let err = service
+                .award(conn, organization.id, tender_id, None, AwardParcelInput { bid_id: high.id })
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            service
+                .award(conn, organization.id, tender_id, None, AwardParcelInput { bid_id: pulp_bid.id })
+                .await?;
+            let details = service.get_tender(conn, organization.id, tender_id).await?;
+            assert_eq!(details.tender.status, TenderStatus::Awarded.as_str());
+
+            let (contracts, total) = service
+                .contracts(conn, organization.id, Some(block_id), &PaginationParams::new(1, 20))
+                .await?;
+            assert_eq!(total.total, 1);
+            assert_eq!(contracts[0].id, contract.id);
+            let err = service.get_contract(conn, Uuid::new_v4(), contract.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,60 @@
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+use crate::{
+    db::{
+        models::{JobRunOutcome, JobRunStatus},
+        repositories::{ScheduledJobRepository, ScheduledJobRepositoryImpl},
+        schema::scheduled_jobs,
+    },
+    error::Result,
+    tests::{common::helpers::TestDb, setup},
+};
+
+#[tokio::test]
+async fn test_only_one_instance_claims_a_due_job() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let repo = ScheduledJobRepositoryImpl;
+            let job = format!("test-job-{}", Uuid::new_v4());
+            let now = Utc::now();
+
+            repo.register(conn, &job, now + Duration::hours(1)).await?;
+            assert!(repo.claim(conn, &job, "a", now + Duration::minutes(5)).await?.is_none());
+
+            // Registering with an earlier run pulls the next run forward
+            repo.register(conn, &job, now - Duration::seconds(1)).await?;
+            let claimed = repo.claim(conn, &job, "a", now + Duration::minutes(5)).await?.unwrap();
+            assert_eq!(claimed.locked_by.as_deref(), Some("a"));
+            assert_eq!(claimed.last_status.as_deref(), Some("running"));
+            assert!(repo.claim(conn, &job, "b", now + Duration::minutes(5)).await?.is_none());
+            assert!(!repo.renew(conn, &job, "b", now + Duration::minutes(10)).await?);
+            assert!(repo.renew(conn, &job, "a", now + Duration::minutes(10)).await?);
+
+            let outcome = JobRunOutcome {
+                status: JobRunStatus::Succeeded,
+                output: Some(serde_json::json!({ "purged": 3 })),
+                error: None,
+                duration_ms: 42,
+            };
+            assert!(repo.complete(conn, &job, "b", &outcome, now + Duration::days(1)).await.is_err());
+            let completed = repo.complete(conn, &job, "a", &outcome, now + Duration::days(1)).await?;
+            assert!(completed.locked_by.is_none());
+            assert_eq!(completed.last_status.as_deref(), Some("succeeded"));
+            assert_eq!(completed.last_duration_ms, Some(42));
+            assert!(repo.claim(conn, &job, "b", now + Duration::minutes(5)).await?.is_none());
+
+            // An expired lease can be taken over
+            repo.set_schedule(conn, &job, Some("0 * * * * *"), now - Duration::seconds(1)).await?;
+            repo.claim(conn, &job, "a", now - Duration::seconds(1)).await?.unwrap();
+            let taken_over = repo.claim(conn, &job, "b", now + Duration::minutes(5)).await?.unwrap();
+            assert_eq!(taken_over.locked_by.as_deref(), Some("b"));
+            assert_eq!(taken_over.schedule.as_deref(), Some("0 * * * * *"));
+
+            diesel::delete(scheduled_jobs::table.find(&job)).execute(conn).unwrap();
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,227 @@
+use actix_web::{
+    dev::{Service, ServiceResponse},
+    http::{header, StatusCode},
+    test,
+};
+use serde_json::json;
+
+use crate::{
+    db::{
+        models::auth::Role,
+        repositories::{auth::UserRepositoryImpl, Repository},
+    },
+    domain::{scim::SCIM_CONTENT_TYPE, TokenManager},
+    server,
+    tests::{common::helpers::{app_config, bearer, send}, factories::UserFactory, setup},
+};
+
+/// Content type of the response to `request`
+async fn content_type<S, B>(app: &S, request: test::TestRequest) -> String
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: actix_web::body::MessageBody,
+{
+    let response = test::call_service(app, request.to_request()).await;
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[actix_rt::test]
+async fn test_provision_promote_and_deprovision_a_user() {
+    setup();
+    let config = app_config();
+    let admin = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap()
+    };
+    let app = test::init_service(server::app(&config)).await;
+
+    let (status, issued) = send(
+        &app,
+        test::TestRequest::post()
+            .uri(&format!("/v1/admin/organizations/{}/scim-tokens", admin.org_id))
+            .insert_header(bearer(&admin, &config))
+            .set_json(json!({ "name": "Entra ID" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let bearer = ("Authorization", format!("Bearer {}", issued["token"].as_str().unwrap()));
+    let scim = |request: test::TestRequest| request.insert_header(bearer.clone());
+
+    let email = format!("scim-{}@pinecorp.com", uuid::Uuid::new_v4().simple());
+    let (status, user) = send(
+        &app,
+        scim(test::TestRequest::post().uri("/scim/v2/Users")).set_json(json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+            "userName": email.to_uppercase(),
+            "name": { "givenName": "Anna", "familyName": "Berg" },
+            "active": true,
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(user["userName"], email);
+    assert_eq!(user["groups"][0]["value"], "Operator");
+    let id = user["id"].as_str().unwrap().to_string();
+    let user_uri = format!("/scim/v2/Users/{}", id);
+    assert_eq!(content_type(&app, scim(test::TestRequest::get().uri(&user_uri))).await, SCIM_CONTENT_TYPE);
+
+    let filter = format!("/scim/v2/Users?filter=userName%20eq%20%22{}%22", email);
+    let (status, page) = send(&app, scim(test::TestRequest::get().uri(&filter))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["totalResults"], 1);
+    assert_eq!(page["Resources"][0]["id"], id.as_str());
+
+    // An access token issued before the promotion carries the old role,
+    // which may not read tags
+    let user_token = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let user = UserRepositoryImpl.find_by_id(&mut conn, id.parse().unwrap()).await.unwrap();
+        TokenManager::generate_token(&user, &config).unwrap()
+    };
+    let tags = || test::TestRequest::get().uri("/v1/tags").insert_header(("Authorization", format!("Bearer {}", user_token)));
+    let refused = test::try_call_service(&app, tags().to_request()).await.err();
+    assert_eq!(refused.map(|e| e.error_response().status()), Some(StatusCode::FORBIDDEN));
+    // Issue times are in whole seconds
+    actix_rt::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    let (status, _) = send(
+        &app,
+        scim(test::TestRequest::patch().uri("/scim/v2/Groups/Manager")).set_json(json!({
+            "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+            "Operations": [{ "op": "Add", "path": "members", "value": [{ "value": id }] }],
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, user) = send(&app, scim(test::TestRequest::get().uri(&user_uri))).await;
+    assert_eq!(user["groups"][0]["value"], "Manager");
+    // and is revoked by it
+    let refused = test::try_call_service(&app, tags().to_request()).await.err();
+    assert_eq!(refused.map(|e| e.error_response().status()), Some(StatusCode::UNAUTHORIZED));
+
+    let (status, user) = send(
+        &app,
+        scim(test::TestRequest::patch().uri(&user_uri)).set_json(json!({
+            "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+            "Operations": [{ "op": "Replace", "path": "active", "value": "False" }],
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["active"], false);
+    let (_, page) = send(&app, scim(test::TestRequest::get().uri(&filter))).await;
+    assert_eq!(page["totalResults"], 0);
+
+    // Provisioning the email again restores the user
+    let (status, user) = send(
+        &app,
+        scim(test::TestRequest::post().uri("/scim/v2/Users")).set_json(json!({ "userName": email })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(user["id"], id.as_str());
+    assert_eq!(user["active"], true);
+}
+
+#[actix_rt::test]
+async fn test_tokens_are_scoped_to_their_organization() {
+    setup();
+    let config = app_config();
+    let (admin, other) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let other = UserFactory::new().verified().create(&mut conn).await.unwrap();
+        (admin, other)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let admin_token = bearer(&admin, &config);
+    let tokens_uri = format!("/v1/admin/organizations/{}/scim-tokens", admin.org_id);
+
+    let (_, issued) = send(
+        &app,
+        test::TestRequest::post().uri(&tokens_uri).insert_header(admin_token.clone()).set_json(json!({ "name": "Okta" })),
+    )
+    .await;
+    let bearer = ("Authorization", format!("Bearer {}", issued["token"].as_str().unwrap()));
+    let get = |uri: String| test::TestRequest::get().uri(&uri).insert_header(bearer.clone());
+
+    // Users of other organizations don't exist, admins are read-only
+    let (status, error) = send(&app, get(format!("/scim/v2/Users/{}", other.id))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error["status"], "404");
+    let (status, _) = send(&app, get(format!("/scim/v2/Users/{}", admin.id))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        test::TestRequest::delete().uri(&format!("/scim/v2/Users/{}", admin.id)).insert_header(bearer.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, error) = send(&app, get("/scim/v2/Users?filter=title%20eq%20%22x%22".to_string())).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["scimType"], "invalidFilter");
+
+    // Revoked tokens are refused
+    let (status, listed) = send(&app, test::TestRequest::get().uri(&tokens_uri).insert_header(admin_token.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(listed["data"][0]["last_used_at"].is_string());
+    let (status, _) = send(
+        &app,
+        test::TestRequest::delete()
+            .uri(&format!("{}/{}", tokens_uri, issued["id"].as_str().unwrap()))
+            .insert_header(admin_token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, get("/scim/v2/Groups".to_string())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(content_type(&app, get("/scim/v2/Groups".to_string())).await, SCIM_CONTENT_TYPE);
+}
+
+#[actix_rt::test]
+async fn test_admins_issue_tokens_for_their_own_organizations_only() {
+    setup();
+    let config = app_config();
+    let (admin, stranger) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let admin = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let stranger = UserFactory::new().role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        (admin, stranger)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let tokens_uri = format!("/v1/admin/organizations/{}/scim-tokens", stranger.org_id);
+
+    let (_, issued) = send(
+        &app,
+        test::TestRequest::post()
+            .uri(&tokens_uri)
+            .insert_header(bearer(&stranger, &config))
+            .set_json(json!({ "name": "Okta" })),
+    )
+    .await;
+
+    // Another organization's tokens don't exist for the admin
+    let (status, _) = send(
+        &app,
+        test::TestRequest::post()
+            .uri(&tokens_uri)
+            .insert_header(bearer(&admin, &config))
+            .set_json(json!({ "name": "Rogue" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, test::TestRequest::get().uri(&tokens_uri).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let revoke = format!("{}/{}", tokens_uri, issued["id"].as_str().unwrap());
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&revoke).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (_, listed) = send(&app, test::TestRequest::get().uri(&tokens_uri).insert_header(bearer(&stranger, &config))).await;
+    assert_eq!(listed["data"].as_array().map(Vec::len), Some(1));
+}
@@ -0,0 +1 @@
+pub mod quick_search;
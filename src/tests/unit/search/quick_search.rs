@@ -0,0 +1,96 @@
+use crate::{
+    api::resources::{customer::dto::SaveCustomerInput, stand::dto::SaveStandInput},
+    db::{
+        models::SpeciesShare,
+        repositories::{CustomerRepositoryImpl, SearchRepositoryImpl, StandRepositoryImpl},
+    },
+    domain::{
+        customer::CustomerService,
+        search::{QuickSearchService, SearchKind},
+        stand::StandService,
+    },
+    error::{ErrorCode, Result},
+    tests::{
+        common::helpers::TestDb,
+        factories::{HarvestBlockFactory, OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+fn customer(name: &str) -> SaveCustomerInput {
+    SaveCustomerInput {
+        name: name.to_string(),
+        contact_name: None,
+        email: None,
+        phone_number: None,
+        address: None,
+        notes: None,
+    }
+}
+
+fn stand(stand_number: &str) -> SaveStandInput {
+    SaveStandInput {
+        stand_number: stand_number.to_string(),
+        area_ha: 5.0,
+        species: vec![SpeciesShare { species: "spruce".to_string(), percent: 100.0 }],
+        age_class: 6,
+        site_index: 22.5,
+        net_merchantable_volume_m3: 900.0,
+        inventoried_on: None,
+        windthrow_conditions: None,
+    }
+}
+
+#[tokio::test]
+async fn test_quick_search_ranks_across_kinds() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = QuickSearchService::new(SearchRepositoryImpl);
+            let customers = CustomerService::new(CustomerRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let other = OrganizationFactory::new().create(conn).await?;
+
+            let miller = UserFactory::new().first_name("Anna").last_name("Miller").in_org(&organization).create(conn).await?;
+            let mill = customers.create_customer(conn, organization.id, customer("Mill")).await?;
+            let north = customers.create_customer(conn, organization.id, customer("North Mill Ltd")).await?;
+            customers.create_customer(conn, other.id, customer("Mill")).await?;
+
+            let hits = service.search(conn, organization.id, "mill", &SearchKind::ALL, 5).await?;
+            let found = hits.iter().map(|hit| (hit.kind, hit.id)).collect::<Vec<_>>();
+            assert_eq!(
+                found,
+                vec![
+                    (SearchKind::Customer, mill.id),
+                    (SearchKind::Customer, north.id),
+                    (SearchKind::User, miller.id),
+                ]
+            );
+
+            let hits = service.search(conn, organization.id, "mill", &[SearchKind::User], 5).await?;
+            assert_eq!(hits.len(), 1);
+
+            // Blocks are found by license and block number, and their stands
+            // under them
+            let block = HarvestBlockFactory::new(&organization).license("A81").block_number("204").create(conn).await?;
+            HarvestBlockFactory::new(&other).license("A81").block_number("204").create(conn).await?;
+            let stand = StandService::new(StandRepositoryImpl).create(conn, organization.id, block.id, stand("7")).await?;
+            let hits = service.search(conn, organization.id, "a81 20", &SearchKind::ALL, 5).await?;
+            assert_eq!(
+                hits.iter().map(|hit| (hit.kind, hit.id, hit.parent_id)).collect::<Vec<_>>(),
+                vec![(SearchKind::Block, block.id, None), (SearchKind::Stand, stand.id, Some(block.id))]
+            );
+            assert_eq!((hits[0].title.as_str(), hits[1].title.as_str()), ("A81 204", "A81 204 7"));
+
+            // Wildcards are searched for literally
+            let hits = service.search(conn, organization.id, "%l", &SearchKind::ALL, 5).await?;
+            assert!(hits.is_empty());
+
+            let err = service.search(conn, organization.id, "m", &SearchKind::ALL, 5).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,51 @@
+use diesel::prelude::*;
+use crate::{
+    db::{
+        schema::{harvest_blocks, notifications, stands, users},
+        seed::{seed, SeedProfile},
+    },
+    error::Result,
+    tests::{common::helpers::TestDb, setup},
+};
+
+#[tokio::test]
+async fn test_demo_seed_is_idempotent() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let first = seed(conn, SeedProfile::Demo).await?;
+            assert_eq!(first.inserted, 1 + first.users + first.notifications + first.blocks + first.stands);
+
+            let seeded_users = users::table
+                .filter(users::org_id.eq(first.organization_id))
+                .count()
+                .get_result::<i64>(conn)
+                .unwrap();
+            let seeded_notifications = notifications::table
+                .filter(notifications::org_id.eq(first.organization_id))
+                .count()
+                .get_result::<i64>(conn)
+                .unwrap();
+            let seeded_blocks = harvest_blocks::table
+                .filter(harvest_blocks::org_id.eq(first.organization_id))
+                .count()
+                .get_result::<i64>(conn)
+                .unwrap();
+            let seeded_stands = stands::table
+                .filter(stands::org_id.eq(first.organization_id))
+                .count()
+                .get_result::<i64>(conn)
+                .unwrap();
+            assert_eq!(seeded_users as usize, first.users);
+            assert_eq!(seeded_blocks as usize, first.blocks);
+            assert_eq!(seeded_stands as usize, first.stands);
+            assert_eq!(seeded_notifications as usize, first.notifications);
+
+            let second = seed(conn, SeedProfile::Demo).await?;
+            assert_eq!(second.organization_id, first.organization_id);
+            assert_eq!(second.inserted, 0);
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,104 @@
+use uuid::Uuid;
+
+use crate::{
+    api::resources::tag::dto::SaveTagInput,
+    db::{models::TagSubject, repositories::TagRepositoryImpl},
+    domain::tag::{TagFilter, TagService},
+    error::{ErrorCode, Result},
+    tests::{
+        common::helpers::TestDb,
+        factories::OrganizationFactory,
+        setup,
+    },
+};
+
+fn input(name: &str, color: &str) -> SaveTagInput {
+    SaveTagInput {
+        name: name.to_string(),
+        color: color.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_tag_names_are_unique_per_organization() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = TagService::new(TagRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let other = OrganizationFactory::new().create(conn).await?;
+
+            let tag = service.create(conn, organization.id, input(" Priority ", "#F00")).await?;
+            assert_eq!(tag.name, "Priority");
+            assert_eq!(tag.color, "#ff0000");
+
+            let err = service.create(conn, organization.id, input("priority", "#00ff00")).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+            let err = service.create(conn, organization.id, input("Steep", "green")).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            // Another organization defines its own tags
+            service.create(conn, other.id, input("Priority", "#00ff00")).await?;
+            let err = service.get(conn, other.id, tag.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            let tag = service.update(conn, organization.id, tag.id, input("Urgent", "#c62828")).await?;
+            assert_eq!(tag.name, "Urgent");
+            assert_eq!(service.list(conn, organization.id).await?.len(), 1);
+
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_subjects_are_filtered_by_tag() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = TagService::new(TagRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let steep = service.create(conn, organization.id, input("Steep", "#6d4c41")).await?;
+            let winter = service.create(conn, organization.id, input("Winter only", "#1565c0")).await?;
+            let (north, south, east) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+            service.attach(conn, organization.id, steep.id, TagSubject::Block, north, None).await?;
+            service.attach(conn, organization.id, winter.id, TagSubject::Block, north, None).await?;
+            service.attach(conn, organization.id, steep.id, TagSubject::Block, south, None).await?;
+            // Attaching twice changes nothing
+            service.attach(conn, organization.id, steep.id, TagSubject::Block, south, None).await?;
+            service.attach(conn, organization.id, winter.id, TagSubject::Stand, east, None).await?;
+
+            let any = TagFilter { tag_ids: vec![steep.id, winter.id], match_all: false };
+            let mut kept = service.filter(conn, organization.id, TagSubject::Block, Some(&any)).await?.unwrap();
+            kept.sort();
+            let mut expected = vec![north, south];
+            expected.sort();
+            assert_eq!(kept, expected);
+
+            let all = TagFilter { match_all: true, ..any };
+            let kept = service.filter(conn, organization.id, TagSubject::Block, Some(&all)).await?.unwrap();
+            assert_eq!(kept, vec![north]);
+
+            assert_eq!(service.filter(conn, organization.id, TagSubject::Block, None).await?, None);
+
+            let tags = service.subject_tags(conn, organization.id, TagSubject::Block, &[north, south]).await?;
+            let names = tags[&north].iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>();
+            assert_eq!(names, vec!["Steep", "Winter only"]);
+            assert_eq!(tags[&south].len(), 1);
+
+            service.detach(conn, organization.id, steep.id, TagSubject::Block, south).await?;
+            let err = service.detach(conn, organization.id, steep.id, TagSubject::Block, south).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            // Deleting a tag detaches it
+            service.delete(conn, organization.id, winter.id).await?;
+            let tags = service.subject_tags(conn, organization.id, TagSubject::Stand, &[east]).await?;
+            assert!(tags.is_empty());
+
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,214 @@
+use actix_web::{http::StatusCode, test};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use diesel::prelude::*;
+use serde_json::json;
+
+use crate::{
+    api::resources::telemetry::dto::TelemetryPointInput,
+    db::{
+        models::auth::Role,
+        repositories::{TelemetryRepository, TelemetryRepositoryImpl},
+        schema::organizations,
+    },
+    domain::telemetry::{IngestOutcome, TelemetryService, API_KEY_HEADER},
+    error::{ErrorCode, Result},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, send, TestDb},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+/// The current time in whole seconds, taken once per test so points meant
+/// to repeat a time do even when a second passes between them
+fn now() -> DateTime<Utc> {
+    Utc::now().duration_trunc(Duration::seconds(1)).unwrap()
+}
+
+fn point(now: DateTime<Utc>, machine_id: &str, seconds_ago: i64, engine_hours: f64) -> TelemetryPointInput {
+    TelemetryPointInput {
+        machine_id: machine_id.to_string(),
+        timestamp: now - Duration::seconds(seconds_ago),
+        engine_hours: Some(engine_hours),
+        fuel_used_l: None,
+        latitude: Some(62.1),
+        longitude: Some(25.7),
+    }
+}
+
+#[tokio::test]
+async fn test_points_are_stored_once_per_machine_and_time() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let now = now();
+            let service = TelemetryService::new(TelemetryRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let other = OrganizationFactory::new().create(conn).await?;
+            let from = Utc::now() - Duration::days(2);
+            let to = Utc::now() + Duration::minutes(1);
+
+            // The last of repeated points is kept
+            let outcome = service
+                .ingest(conn, organization.id, vec![point(now, "H1", 60, 100.0), point(now, "H1", 30, 100.5), point(now, "H1", 60, 100.1)])
+                .await?;
+            assert_eq!(outcome, IngestOutcome { received: 3, stored: 2, duplicates: 1 });
+            let stored = TelemetryRepositoryImpl.list_points(conn, organization.id, "H1", from, to).await?;
+            assert_eq!(stored.iter().map(|p| p.engine_hours).collect::<Vec<_>>(), vec![Some(100.1), Some(100.5)]);
+
+            // Uploading again replaces instead of adding
+            let outcome = service.ingest(conn, organization.id, vec![point(now, "H1", 30, 100.6)]).await?;
+            assert_eq!(outcome, IngestOutcome { received: 1, stored: 1, duplicates: 0 });
+            let stored = TelemetryRepositoryImpl.list_points(conn, organization.id, "H1", from, to).await?;
+            assert_eq!(stored.len(), 2);
+            assert_eq!(stored[1].engine_hours, Some(100.6));
+
+            // A day buffered offline spans several write batches
+            let day: Vec<_> = (1..=12_000).map(|i| point(now, "H2", i * 7, i as f64)).collect();
+            let outcome = service.ingest(conn, organization.id, day).await?;
+            assert_eq!(outcome.stored, 12_000);
+            assert_eq!(TelemetryRepositoryImpl.list_points(conn, organization.id, "H2", from, to).await?.len(), 12_000);
+
+            // Machine identifiers belong to their organization
+            assert!(TelemetryRepositoryImpl.list_points(conn, other.id, "H1", from, to).await?.is_empty());
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_invalid_point_stores_none() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let now = now();
+            let service = TelemetryService::new(TelemetryRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+
+            let mut invalid = point(now, "H1", 10, 5.0);
+            invalid.longitude = None;
+            let err = service.ingest(conn, organization.id, vec![point(now, "H1", 20, 4.0), invalid]).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let err = service.ingest(conn, organization.id, vec![point(now, "H1", -3600, 6.0)]).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            let stored = TelemetryRepositoryImpl
+                .list_points(conn, organization.id, "H1", Utc::now() - Duration::days(1), Utc::now() + Duration::days(1))
+                .await?;
+            assert!(stored.is_empty());
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_archived_organizations_take_no_points() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let now = now();
+            let service = TelemetryService::new(TelemetryRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            diesel::update(organizations::table.find(organization.id))
+                .set(organizations::archived_at.eq(Some(Utc::now())))
+                .execute(conn)
+                .expect("Failed to archive the organization");
+
+            let err = service.ingest(conn, organization.id, vec![point(now, "H1", 10, 5.0)]).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Forbidden);
+            assert_eq!(err.context.details.as_ref().unwrap()["code"], "ORGANIZATION_ARCHIVED");
+
+            let stored = TelemetryRepositoryImpl
+                .list_points(conn, organization.id, "H1", Utc::now() - Duration::days(1), Utc::now() + Duration::days(1))
+                .await?;
+            assert!(stored.is_empty());
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_keys_authenticate_until_revoked() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = TelemetryService::new(TelemetryRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let intruder = OrganizationFactory::new().create(conn).await?;
+
+            let (key, plaintext) = service.issue_key(conn, organization.id, " Harvester 1 ", None).await?;
+            assert_eq!(key.name, "Harvester 1");
+            assert_ne!(key.key_hash, plaintext);
+
+            let found = service.authenticate(conn, &plaintext).await?.expect("key should authenticate");
+            assert_eq!((found.id, found.org_id), (key.id, organization.id));
+            assert!(service.list_keys(conn, organization.id).await?[0].last_used_at.is_some());
+            assert!(service.authenticate(conn, "tlm_unknown").await?.is_none());
+
+            let err = service.revoke_key(conn, intruder.id, key.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            service.revoke_key(conn, organization.id, key.id).await?;
+            assert!(service.authenticate(conn, &plaintext).await?.is_none());
+            assert!(service.list_keys(conn, organization.id).await?.is_empty());
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[actix_rt::test]
+async fn test_machines_upload_with_api_keys() {
+    setup();
+    let config = app_config();
+    let (admin, manager) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let organization = OrganizationFactory::new().create(&mut conn).await.unwrap();
+        let admin = UserFactory::new().in_org(&organization).role(Role::Admin).verified().create(&mut conn).await.unwrap();
+        let manager = UserFactory::new().in_org(&organization).role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        (admin, manager)
+    };
+    let app = test::init_service(server::app(&config)).await;
+    let batch = json!({
+        "points": [
+            { "machine_id": "FWD-7", "timestamp": "2025-02-03T08:00:00Z", "engine_hours": 5120.5, "fuel_used_l": 18.2 },
+            { "machine_id": "FWD-7", "timestamp": "2025-02-03T08:00:00Z", "engine_hours": 5120.6, "fuel_used_l": 18.3 },
+            { "machine_id": "FWD-7", "timestamp": "2025-02-03T08:01:00Z", "latitude": 61.5, "longitude": 23.8 },
+        ]
+    });
+
+    let request = json!({ "name": "Forwarder 7" });
+    let (status, body) = send(&app, test::TestRequest::post().uri("/v1/telemetry/keys").insert_header(bearer(&admin, &config)).set_json(&request)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let key = body["key"].as_str().unwrap().to_string();
+    let key_uri = format!("/v1/telemetry/keys/{}", body["id"].as_str().unwrap());
+
+    let (status, body) = send(&app, test::TestRequest::post().uri("/v1/telemetry/batch").insert_header((API_KEY_HEADER, key.as_str())).set_json(&batch)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        (body["received"].as_i64(), body["stored"].as_i64(), body["duplicates"].as_i64()),
+        (Some(3), Some(2), Some(1))
+    );
+
+    let (status, body) = send(&app, test::TestRequest::get().uri("/v1/telemetry/keys").insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"][0]["name"], "Forwarder 7");
+    assert!(body["data"][0].get("key").is_none());
+
+    // Uploads carry a key, not a session
+    let (status, _) = send(&app, test::TestRequest::post().uri("/v1/telemetry/batch").insert_header(bearer(&admin, &config)).set_json(&batch)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Only admins manage keys
+    let (status, _) = send(&app, test::TestRequest::get().uri("/v1/telemetry/keys").insert_header(bearer(&manager, &config))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(&app, test::TestRequest::delete().uri(&key_uri).insert_header(bearer(&admin, &config))).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, test::TestRequest::post().uri("/v1/telemetry/batch").insert_header((API_KEY_HEADER, key.as_str())).set_json(&batch)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
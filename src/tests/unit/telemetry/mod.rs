@@ -0,0 +1,2 @@
+pub mod ingestion;
+pub mod mqtt;
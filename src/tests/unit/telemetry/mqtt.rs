@@ -0,0 +1,69 @@
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        repositories::{TelemetryRepository, TelemetryRepositoryImpl},
+        schema::organizations,
+    },
+    domain::telemetry::TelemetryService,
+    infrastructure::mqtt::{handle_message, MessageOutcome},
+    tests::{common::helpers::app_config, factories::OrganizationFactory, setup},
+};
+
+#[actix_rt::test]
+async fn test_published_telemetry_is_stored() {
+    setup();
+    let config = app_config();
+    let pool = config.pool().clone();
+    let organization = {
+        let mut conn = pool.get().expect("Failed to get a connection");
+        OrganizationFactory::new().create(&mut conn).await.unwrap()
+    };
+    let service = TelemetryService::new(TelemetryRepositoryImpl);
+    let topic = format!("telemetry/{}", organization.id);
+    let publish = |topic: &str, payload: serde_json::Value| {
+        let (service, pool, topic) = (&service, &pool, topic.to_string());
+        async move { handle_message(service, pool, "telemetry", &topic, payload.to_string().as_bytes()).await }
+    };
+    let point = |machine_id: &str, timestamp: &str| json!({ "machine_id": machine_id, "timestamp": timestamp, "engine_hours": 812.5 });
+
+    // Uploads and single points are both accepted
+    let batch = json!({ "points": [point("HRV-2", "2025-02-03T08:00:00Z"), point("HRV-2", "2025-02-03T08:00:10Z")] });
+    assert_eq!(publish(&topic, batch).await, MessageOutcome::Stored(2));
+    assert_eq!(publish(&topic, point("HRV-2", "2025-02-03T08:00:20Z")).await, MessageOutcome::Stored(1));
+    // Redelivered messages replace what they stored before
+    assert_eq!(publish(&topic, point("HRV-2", "2025-02-03T08:00:20Z")).await, MessageOutcome::Stored(1));
+
+    let stored = {
+        let mut conn = pool.get().expect("Failed to get a connection");
+        TelemetryRepositoryImpl
+            .list_points(&mut conn, organization.id, "HRV-2", Utc::now() - Duration::days(3650), Utc::now())
+            .await
+            .unwrap()
+    };
+    assert_eq!(stored.len(), 3);
+
+    assert_eq!(publish(&format!("telemetry/{}", Uuid::new_v4()), point("HRV-2", "2025-02-03T08:00:30Z")).await, MessageOutcome::Rejected);
+    assert_eq!(publish("telemetry/HRV-2", point("HRV-2", "2025-02-03T08:00:30Z")).await, MessageOutcome::Rejected);
+    assert_eq!(publish(&topic, json!({ "machine": "HRV-2" })).await, MessageOutcome::Rejected);
+    let future = (Utc::now() + Duration::hours(1)).to_rfc3339();
+    assert_eq!(publish(&topic, point("HRV-2", &future)).await, MessageOutcome::Rejected);
+
+    // Archived organizations take no more telemetry, as over HTTP
+    let stored = {
+        let mut conn = pool.get().expect("Failed to get a connection");
+        diesel::update(organizations::table.find(organization.id))
+            .set(organizations::archived_at.eq(Some(Utc::now())))
+            .execute(&mut conn)
+            .expect("Failed to archive the organization");
+        assert_eq!(publish(&topic, point("HRV-2", "2025-02-03T08:00:40Z")).await, MessageOutcome::Rejected);
+        TelemetryRepositoryImpl
+            .list_points(&mut conn, organization.id, "HRV-2", Utc::now() - Duration::days(3650), Utc::now())
+            .await
+            .unwrap()
+    };
+    assert_eq!(stored.len(), 3);
+}
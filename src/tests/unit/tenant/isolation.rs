@@ -0,0 +1,122 @@
+use actix_web::{http::StatusCode, test};
+use diesel::{prelude::*, sql_types::Text};
+use serde_json::{json, Value};
+
+use crate::{
+    api::resources::tag::dto::SaveTagInput,
+    db::{models::auth::Role, repositories::TagRepositoryImpl, tenant::TENANT_TABLES},
+    domain::tag::TagService,
+    error::{ErrorCode, Result},
+    server,
+    tests::{
+        common::helpers::{app_config, bearer, TestDb},
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+#[derive(QueryableByName)]
+struct OrgTable {
+    #[diesel(sql_type = Text)]
+    table_name: String,
+}
+
+fn input(name: &str) -> SaveTagInput {
+    SaveTagInput {
+        name: name.to_string(),
+        color: "#6d4c41".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_every_table_with_an_organization_is_scoped() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let tables: Vec<OrgTable> = diesel::sql_query(
+                "SELECT table_name::text AS table_name FROM information_schema.columns
+                 WHERE table_schema = 'public' AND column_name = 'org_id' AND is_nullable = 'NO'
+                 ORDER BY table_name",
+            )
+            .load(conn)
+            .unwrap();
+
+            let unscoped: Vec<&str> = tables
+                .iter()
+                .map(|table| table.table_name.as_str())
+                .filter(|table| !TENANT_TABLES.contains(table))
+                .collect();
+            assert!(unscoped.is_empty(), "tables missing from tenant_tables!: {:?}", unscoped);
+
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_repositories_ignore_rows_of_other_organizations() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = TagService::new(TagRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let intruder = OrganizationFactory::new().create(conn).await?;
+            let tag = service.create(conn, organization.id, input("Steep")).await?;
+
+            // The id is known, but belongs to another organization
+            let err = service.get(conn, intruder.id, tag.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            let err = service.update(conn, intruder.id, tag.id, input("Flat")).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            let err = service.delete(conn, intruder.id, tag.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            assert!(service.list(conn, intruder.id).await?.is_empty());
+
+            let kept = service.get(conn, organization.id, tag.id).await?;
+            assert_eq!(kept.name, "Steep");
+
+            Ok(())
+        })
+    })
+    .await
+}
+
+#[actix_rt::test]
+async fn test_cross_tenant_requests_find_nothing() {
+    setup();
+    let config = app_config();
+    let (owner, intruder) = {
+        let mut conn = config.pool().get().expect("Failed to get a connection");
+        let owner = UserFactory::new().role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        let intruder = UserFactory::new().role(Role::Manager).verified().create(&mut conn).await.unwrap();
+        (owner, intruder)
+    };
+    let app = test::init_service(server::app(&config)).await;
+
+    let response = test::call_service(&app, test::TestRequest::post()
+        .uri("/v1/tags")
+        .insert_header(bearer(&owner, &config))
+        .set_json(json!({ "name": "Steep", "color": "#6d4c41" }))
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: Value = test::read_body_json(response).await;
+    let uri = format!("/v1/tags/{}", body["id"].as_str().unwrap());
+
+    for request in [
+        test::TestRequest::get().uri(&uri),
+        test::TestRequest::put().uri(&uri).set_json(json!({ "name": "Flat", "color": "#1565c0" })),
+        test::TestRequest::delete().uri(&uri),
+    ] {
+        let response = test::call_service(&app, request.insert_header(bearer(&intruder, &config)).to_request()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    let response = test::call_service(&app, test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(bearer(&owner, &config))
+        .to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["name"], "Steep");
+}
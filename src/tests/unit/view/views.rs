@@ -0,0 +1,75 @@
+use serde_json::json;
+
+use crate::{
+    api::resources::view::dto::SaveViewInput,
+    db::repositories::SavedViewRepositoryImpl,
+    domain::view::SavedViewService,
+    error::{ErrorCode, Result},
+    tests::{
+        common::helpers::TestDb,
+        factories::{OrganizationFactory, UserFactory},
+        setup,
+    },
+};
+
+fn input(name: &str, shared: bool) -> SaveViewInput {
+    SaveViewInput {
+        resource: "customers".to_string(),
+        name: name.to_string(),
+        filters: json!({ "search": "mill" }),
+        sort: vec!["-updated_at".to_string()],
+        columns: vec!["name".to_string(), "email".to_string()],
+        shared,
+    }
+}
+
+#[tokio::test]
+async fn test_views_are_shared_within_the_organization() -> Result<()> {
+    setup();
+    TestDb::run_test(|conn| {
+        Box::pin(async move {
+            let service = SavedViewService::new(SavedViewRepositoryImpl);
+            let organization = OrganizationFactory::new().create(conn).await?;
+            let planner = UserFactory::new().in_org(&organization).create(conn).await?;
+            let dispatcher = UserFactory::new().in_org(&organization).create(conn).await?;
+
+            let private = service.create(conn, organization.id, planner.id, input("Mine", false)).await?;
+            let shared = service.create(conn, organization.id, planner.id, input("Mills", true)).await?;
+            let err = service.create(conn, organization.id, planner.id, input("Mills", false)).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Conflict);
+
+            // Others see shared views only and cannot change them
+            let views = service.list(conn, organization.id, dispatcher.id, Some("customers")).await?;
+            assert_eq!(views.iter().map(|(view, _)| view.id).collect::<Vec<_>>(), vec![shared.id]);
+            let err = service.get(conn, organization.id, dispatcher.id, private.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+            let err = service.delete(conn, organization.id, dispatcher.id, shared.id).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::Forbidden);
+
+            // A shared view can be someone else's default until it is unshared
+            service.set_default(conn, organization.id, dispatcher.id, shared.id).await?;
+            let default = service.default_view(conn, organization.id, dispatcher.id, "customers").await?;
+            assert_eq!(default.map(|view| view.id), Some(shared.id));
+            let (_, is_default) = service.get(conn, organization.id, dispatcher.id, shared.id).await?;
+            assert!(is_default);
+
+            service.set_default(conn, organization.id, planner.id, shared.id).await?;
+            service.update(conn, organization.id, planner.id, shared.id, input("Mills", false)).await?;
+            assert!(service.default_view(conn, organization.id, dispatcher.id, "customers").await?.is_none());
+            assert!(service.default_view(conn, organization.id, planner.id, "customers").await?.is_some());
+
+            let err = service
+                .update(conn, organization.id, planner.id, shared.id, SaveViewInput { resource: "imports".into(), ..input("Mills", false) })
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationError);
+
+            service.clear_default(conn, planner.id, "customers").await?;
+            let err = service.clear_default(conn, planner.id, "customers").await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::NotFound);
+
+            Ok(())
+        })
+    })
+    .await
+}
@@ -0,0 +1,296 @@
+//! Settings that change without a restart
+//!
+//! The `[live]` section holds settings that are safe to change while the
+//! server runs: the log filter, rate limits, CORS origins, maintenance mode
+//! and feature flags. [`LiveConfig`] holds their current values. It is
+//! reloaded when a config file changes (checked every
+//! `live.watch_interval_secs`), on `SIGHUP` and through
+//! `POST /v1/admin/config/reload`. A reload reads the files and environment
+//! again and validates the whole configuration, but only applies `[live]`;
+//! every other change still needs a restart.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use actix_web::rt::task::JoinHandle;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use super::{env, Config};
+use crate::{
+    error::Result,
+    jobs::shutdown::Shutdown,
+    utils::{defaults::*, logging},
+};
+
+/// Settings of the `[live]` section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct LiveSettings {
+    /// Log filter directives, `RUST_LOG` or the environment default when unset
+    pub log_filter: Option<String>,
+    #[serde(default)]
+    pub rate_limit: RateLimitSettings,
+    /// Origins allowed to call the API from a browser, `*` for any
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+    #[serde(default)]
+    pub maintenance: MaintenanceSettings,
+    /// Feature flags by name, unknown flags are off
+    #[serde(default)]
+    pub features: BTreeMap<String, bool>,
+    /// Seconds between checks of the config files for changes, 0 disables
+    #[serde(default = "default_live_watch_interval_secs")]
+    #[ts(type = "number")]
+    pub watch_interval_secs: u64,
+}
+
+impl Default for LiveSettings {
+    fn default() -> Self {
+        Self {
+            log_filter: None,
+            rate_limit: RateLimitSettings::default(),
+            cors_origins: Vec::new(),
+            maintenance: MaintenanceSettings::default(),
+            features: BTreeMap::new(),
+            watch_interval_secs: default_live_watch_interval_secs(),
+        }
+    }
+}
+
+impl LiveSettings {
+    /// Whether the feature flag `name` is on
+    pub fn feature(&self, name: &str) -> bool {
+        self.features.get(name).copied().unwrap_or(false)
+    }
+
+    /// Whether browsers may call the API from `origin`
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.cors_origins.iter().any(|allowed| allowed == "*" || allowed == origin)
+    }
+
+    /// Keys whose values differ from `other`
+    fn changes(&self, other: &Self) -> Vec<String> {
+        let mut changed = Vec::new();
+        let mut compare = |key: &str, differs: bool| {
+            if differs {
+                changed.push(format!("live.{}", key));
+            }
+        };
+        compare("log_filter", self.log_filter != other.log_filter);
+        compare("rate_limit", self.rate_limit != other.rate_limit);
+        compare("cors_origins", self.cors_origins != other.cors_origins);
+        compare("maintenance", self.maintenance != other.maintenance);
+        compare("features", self.features != other.features);
+        compare("watch_interval_secs", self.watch_interval_secs != other.watch_interval_secs);
+        changed
+    }
+}
+
+/// Requests allowed per client and window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct RateLimitSettings {
+    #[serde(default = "default_rate_limit_max_requests")]
+    pub max_requests: u32,
+    #[serde(default = "default_rate_limit_window_secs")]
+    pub window_secs: u32,
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            max_requests: default_rate_limit_max_requests(),
+            window_secs: default_rate_limit_window_secs(),
+        }
+    }
+}
+
+/// Maintenance mode, rejecting API requests except health, auth and admin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export)]
+pub struct MaintenanceSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Message returned with the 503 response
+    #[serde(default = "default_maintenance_message")]
+    pub message: String,
+    /// Seconds clients are told to wait before retrying
+    #[serde(default = "default_maintenance_retry_after_secs")]
+    #[ts(type = "number")]
+    pub retry_after_secs: u64,
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            message: default_maintenance_message(),
+            retry_after_secs: default_maintenance_retry_after_secs(),
+        }
+    }
+}
+
+/// Shared handle to the current live settings, cheap to clone
+#[derive(Debug, Clone)]
+pub struct LiveConfig {
+    current: Arc<RwLock<Arc<LiveSettings>>>,
+    /// Directory and files the configuration was read from
+    dir: PathBuf,
+    files: Vec<PathBuf>,
+}
+
+impl Default for LiveConfig {
+    fn default() -> Self {
+        Self::new(LiveSettings::default(), Path::new(&default_config_dir()), &[])
+    }
+}
+
+impl LiveConfig {
+    pub fn new(settings: LiveSettings, dir: &Path, files: &[PathBuf]) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(settings))),
+            dir: dir.to_path_buf(),
+            files: files.to_vec(),
+        }
+    }
+
+    /// Current settings
+    pub fn get(&self) -> Arc<LiveSettings> {
+        self.current.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Reads the configuration again and applies its `[live]` section
+    ///
+    /// An invalid configuration keeps the current settings. Returns the
+    /// keys that changed.
+    pub fn reload(&self) -> Result<Vec<String>> {
+        let config = Config::from_sources(&self.dir, env())?;
+        Ok(self.apply(config.live))
+    }
+
+    /// Replaces the current settings, returning the keys that changed
+    ///
+    /// The log filter is always re-applied, undoing changes made through
+    /// `PUT /v1/admin/log-filter`.
+    pub fn apply(&self, settings: LiveSettings) -> Vec<String> {
+        let filter = match &settings.log_filter {
+            Some(filter) => logging::set_filter(filter),
+            None => logging::reset_filter(),
+        };
+        if let Err(e) = filter {
+            warn!(error = %e, "Failed to apply live log filter");
+        }
+
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        let changed = current.changes(&settings);
+        *current = Arc::new(settings);
+        changed
+    }
+
+    /// Reloads whenever a config file changes, until shutdown
+    pub fn spawn_watcher(self, shutdown: Shutdown) -> JoinHandle<()> {
+        actix_web::rt::spawn(async move {
+            let mut modified = self.modified();
+            loop {
+                // The interval is itself a live setting; 0 pauses watching
+                let interval = self.get().watch_interval_secs;
+                let sleep = Duration::from_secs(if interval == 0 { default_live_watch_interval_secs() } else { interval });
+                tokio::select! {
+                    _ = actix_web::rt::time::sleep(sleep) => {}
+                    _ = shutdown.triggered() => break,
+                }
+                if interval == 0 {
+                    continue;
+                }
+
+                let latest = self.modified();
+                if latest == modified {
+                    continue;
+                }
+                modified = latest;
+                self.reload_logged("config file changed");
+            }
+        })
+    }
+
+    /// Reloads whenever the process receives `SIGHUP`
+    #[cfg(unix)]
+    pub fn spawn_reload_on_hangup(self) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangups = match signal(SignalKind::hangup()) {
+            Ok(hangups) => hangups,
+            Err(e) => {
+                warn!(error = %e, "Failed to install SIGHUP handler, config reload on SIGHUP disabled");
+                return;
+            }
+        };
+        actix_web::rt::spawn(async move {
+            while hangups.recv().await.is_some() {
+                self.reload_logged("SIGHUP");
+            }
+        });
+    }
+
+    #[cfg(not(unix))]
+    pub fn spawn_reload_on_hangup(self) {}
+
+    fn reload_logged(&self, trigger: &str) {
+        match self.reload() {
+            Ok(changed) => info!(trigger = %trigger, changed = ?changed, "Live configuration reloaded"),
+            Err(e) => warn!(trigger = %trigger, error = %e, "Invalid configuration, keeping the current live settings"),
+        }
+    }
+
+    /// Modification times of the config files, `None` for missing ones
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        self.files
+            .iter()
+            .map(|file| std::fs::metadata(file).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_reports_changed_keys() {
+        let live = LiveConfig::default();
+        let settings = LiveSettings {
+            maintenance: MaintenanceSettings {
+                enabled: true,
+                ..MaintenanceSettings::default()
+            },
+            features: BTreeMap::from([("stand_import".to_string(), true)]),
+            ..LiveSettings::default()
+        };
+
+        assert_eq!(live.apply(settings.clone()), ["live.maintenance", "live.features"]);
+        assert!(live.get().maintenance.enabled);
+        assert!(live.get().feature("stand_import"));
+        assert!(!live.get().feature("unknown"));
+        assert!(live.apply(settings).is_empty());
+    }
+
+    #[test]
+    fn test_allows_origin() {
+        let mut settings = LiveSettings {
+            cors_origins: vec!["https://app.example.com".to_string()],
+            ..LiveSettings::default()
+        };
+        assert!(settings.allows_origin("https://app.example.com"));
+        assert!(!settings.allows_origin("https://evil.example.com"));
+
+        settings.cors_origins.push("*".to_string());
+        assert!(settings.allows_origin("https://evil.example.com"));
+    }
+}
@@ -0,0 +1,563 @@
+//! Application configuration
+//!
+//! Configuration is layered, later sources overriding earlier ones:
+//!
+//! 1. `config/default.{toml,yaml,yml}`
+//! 2. `config/<environment>.{toml,yaml,yml}`, where the environment comes
+//!    from `ENVIRONMENT` or the default file
+//! 3. Environment variables (including `.env`). Top-level keys use their
+//!    upper-case name (`JWT_SECRET`), section keys join section and key with
+//!    a double underscore (`SERVER__PORT`, `DATABASE__POOL_MAX_SIZE`). The
+//!    established `DATABASE_URL`, `HOST`, `PORT`, `STORAGE_URL` and
+//!    `REDIS_URL` variables are still honored.
+//!
+//! The config directory defaults to `./config` and can be moved with
+//! `CONFIG_DIR`. Missing files are skipped.
+
+mod live;
+mod sections;
+mod validation;
+
+pub use sections::{
+    AuthConfig, BillingConfig, CaptchaConfig, CaptchaProvider, ClusterConfig, DatabaseConfig, DocsAuth, DocsConfig, EmailConfig, EmailTransport, ErpConfig, EventTransport, EventsConfig, HealthConfig,
+    JwtAlgorithm, JwtKeyConfig, LoginAlertConfig, MagicLinkConfig, MqttConfig, OidcProviderConfig, OptimizationConfig, PasswordAlgorithm, PasswordHashConfig, QueueConfig, RedisConfig, SchedulerConfig, SessionConfig, ServerConfig, StorageConfig, TlsConfig, WebAuthnConfig,
+};
+pub use live::{LiveConfig, LiveSettings, MaintenanceSettings, RateLimitSettings};
+use validation::InvalidKey;
+
+use super::{defaults::*, environment::Environment, logging::LogFormat};
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+use dotenv::dotenv;
+use figment::{
+    providers::{Env, Format, Toml, Yaml},
+    Figment, Provider,
+};
+use std::path::{Path, PathBuf};
+use tracing::{error, warn};
+use serde::Deserialize;
+use crate::db::{create_connection_pool, models::auth::User};
+use crate::domain::auth::{SigningKeys, TokenCache};
+use crate::error::{ApiError, ErrorCode, ErrorContext, Result};
+use crate::infrastructure::{DependencyMonitor, EventBus, Mailer, ObjectStorage, RedisClient};
+
+/// Flat environment variables predating config sections
+const LEGACY_ENV_KEYS: &[(&str, &str)] = &[
+    ("database_url", "database.url"),
+    ("host", "server.host"),
+    ("port", "server.port"),
+    ("storage_url", "storage.url"),
+    ("redis_url", "redis.url"),
+];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    #[serde(default = "default_environment")]
+    pub environment: Environment,
+    pub sentry_dsn: Option<String>,
+    /// Log output format, defaults to JSON in production
+    pub log_format: Option<LogFormat>,
+    #[serde(skip)]
+    _services: Option<Services>,
+    #[serde(default = "default_jwt_secret")]
+    pub jwt_secret: String,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub tls: TlsConfig,
+    pub database: DatabaseConfig,
+    #[serde(default)]
+    pub redis: RedisConfig,
+    #[serde(default)]
+    pub cluster: ClusterConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub email: EmailConfig,
+    #[serde(default)]
+    pub events: EventsConfig,
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+    #[serde(default)]
+    pub optimization: OptimizationConfig,
+    #[serde(default)]
+    pub erp: ErpConfig,
+    #[serde(default)]
+    pub billing: BillingConfig,
+    #[serde(default)]
+    pub health: HealthConfig,
+    #[serde(default)]
+    pub docs: DocsConfig,
+    #[serde(skip)]
+    dependencies: DependencyMonitor,
+    /// Age in days after which records are moved to cold storage
+    #[serde(default = "default_archive_after_days")]
+    pub archive_after_days: i64,
+    /// Days soft-deleted records are kept before they are purged
+    #[serde(default = "default_purge_after_days")]
+    pub purge_after_days: i64,
+    /// Per-entity retention overrides, e.g. `users=30,organizations=never`
+    #[serde(default)]
+    pub purge_retention: String,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    #[serde(default)]
+    pub queue: QueueConfig,
+    /// Settings applied again on reload, see [`LiveConfig`]
+    #[serde(default)]
+    pub live: LiveSettings,
+    #[serde(skip)]
+    live_config: LiveConfig,
+    #[serde(skip)]
+    signing_keys: SigningKeys,
+    #[serde(skip)]
+    token_cache: TokenCache,
+}
+
+#[derive(Debug, Clone)]
+struct Services {
+    pool: Pool<ConnectionManager<PgConnection>>,
+    storage: ObjectStorage,
+    mailer: Mailer,
+    redis: Option<RedisClient>,
+    event_bus: Option<EventBus>,
+}
+
+impl Config {
+    pub fn load() -> std::io::Result<Self> {
+        if dotenv().is_err() {
+            warn!("No .env file found - using environment variables");
+        }
+        let config = Self::load_from_sources()?.with_services()?;
+        User::configure_password_hashing(&config.auth.password_hash);
+        Ok(config)
+    }
+
+    /// The files in `config/` for the development environment against
+    /// `database_url`, with the dev mailbox as mail transport and a rate
+    /// limit tests do not run into
+    #[cfg(test)]
+    pub(crate) fn for_tests(database_url: &str) -> std::io::Result<Self> {
+        let overrides = Figment::new().merge(env()).merge(figment::providers::Serialized::defaults(serde_json::json!({
+            "environment": "development",
+            "database": { "url": database_url },
+            "email": { "transport": "mailbox" },
+            "live": { "rate_limit": { "max_requests": 100_000 } },
+        })));
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("config");
+        Self::from_sources(&dir, overrides)
+            .map_err(|e| std::io::Error::other(problem_list(&e)))?
+            .with_services()
+    }
+
+    /// The development configuration of `config/` with services that need
+    /// no infrastructure: a pool that only connects when a handler asks for
+    /// a connection, in-memory storage and the dev mailbox
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn offline() -> Self {
+        let overrides = figment::providers::Serialized::defaults(serde_json::json!({
+            "environment": "development",
+            "database": { "url": "postgres://offline.invalid/forestry" },
+            "storage": { "url": "memory://" },
+            "email": { "transport": "mailbox" },
+        }));
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("config");
+        let mut config = Self::from_sources(&dir, overrides).expect("config/ is invalid");
+        let manager = ConnectionManager::<PgConnection>::new(config.database.url.clone());
+        config._services = Some(Services {
+            pool: Pool::builder().min_idle(Some(0)).build_unchecked(manager),
+            storage: ObjectStorage::in_memory(),
+            mailer: Mailer::from_config(&config.email).expect("the dev mailbox needs no setup"),
+            redis: None,
+            event_bus: None,
+        });
+        config
+    }
+
+    /// Replaces the object storage, e.g. with a `MockStorageService`
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn with_storage(mut self, storage: ObjectStorage) -> Self {
+        if let Some(services) = self._services.as_mut() {
+            services.storage = storage;
+        }
+        self
+    }
+
+    /// Replaces the auth section, loading its signing keys
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn with_auth(mut self, auth: AuthConfig) -> Self {
+        self.signing_keys = SigningKeys::from_config(&auth).expect("invalid signing keys");
+        self.token_cache = TokenCache::from_config(&auth);
+        self.auth = auth;
+        self
+    }
+
+    /// Connects the pool and clients described by the configuration
+    fn with_services(mut self) -> std::io::Result<Self> {
+        // Refused in production by validation, tolerated elsewhere so a
+        // cluster can be tried out locally
+        if self.cluster.enabled {
+            for backend in validation::instance_local_backends(&self) {
+                warn!(key = %backend.key, problem = %backend.message, "Instance-local backend in cluster mode");
+            }
+        }
+
+        let services = Services {
+            pool: create_connection_pool(&self.database.url, self.database.pool())
+                .map_err(|e| std::io::Error::other(e.to_string()))?,
+            storage: ObjectStorage::from_url(&self.storage.url)
+                .map_err(|e| std::io::Error::other(e.to_string()))?,
+            mailer: Mailer::from_config(&self.email)
+                .map_err(|e| std::io::Error::other(e.to_string()))?,
+            redis: RedisClient::from_config(&self.redis)
+                .map_err(|e| std::io::Error::other(e.to_string()))?,
+            event_bus: EventBus::from_config(&self.events)
+                .map_err(|e| std::io::Error::other(e.to_string()))?,
+        };
+
+        self._services = Some(services);
+        Ok(self)
+    }
+
+    fn load_from_sources() -> std::io::Result<Self> {
+        let dir = std::env::var("CONFIG_DIR").unwrap_or_else(|_| default_config_dir());
+        Self::from_sources(Path::new(&dir), env()).map_err(|e| {
+            error!("Configuration error: {}", e);
+            std::io::Error::other(problem_list(&e))
+        })
+    }
+
+    /// Reads the config files in `dir` merged with `overrides`
+    fn from_sources(dir: &Path, overrides: impl Provider + Clone) -> Result<Self> {
+        let base = Figment::new()
+            .merge(files(dir, "default"))
+            .merge(overrides.clone());
+        let environment: Environment = base
+            .extract_inner("environment")
+            .unwrap_or_else(|_| default_environment());
+
+        let mut config = Figment::new()
+            .merge(files(dir, "default"))
+            .merge(files(dir, &environment.to_string()))
+            .merge(overrides)
+            .extract::<Self>()
+            .map_err(config_error)?
+            .validated()?;
+        config.signing_keys = SigningKeys::from_config(&config.auth)
+            .map_err(|(key, message)| invalid_keys(vec![InvalidKey { key, message }]))?;
+        config.token_cache = TokenCache::from_config(&config.auth);
+
+        let watched: Vec<PathBuf> = ["default".to_string(), environment.to_string()]
+            .iter()
+            .flat_map(|name| file_paths(dir, name))
+            .collect();
+        config.live_config = LiveConfig::new(config.live.clone(), dir, &watched);
+        Ok(config)
+    }
+
+    /// Checks every key, reporting all problems at once
+    fn validated(self) -> Result<Self> {
+        let problems = validation::validate(&self);
+        if problems.is_empty() {
+            Ok(self)
+        } else {
+            Err(invalid_keys(problems))
+        }
+    }
+
+    /// Last known status of each external dependency
+    pub fn dependencies(&self) -> &DependencyMonitor {
+        &self.dependencies
+    }
+
+    /// Keys access tokens are signed and verified with
+    pub fn signing_keys(&self) -> &SigningKeys {
+        &self.signing_keys
+    }
+
+    /// Access tokens verified recently
+    pub fn token_cache(&self) -> &TokenCache {
+        &self.token_cache
+    }
+
+    /// Live settings, reflecting reloads since startup
+    pub fn live(&self) -> &LiveConfig {
+        &self.live_config
+    }
+
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format
+            .unwrap_or_else(|| LogFormat::default_for(&self.environment))
+    }
+
+    pub fn pool(&self) -> &Pool<ConnectionManager<PgConnection>> {
+        &self
+            ._services
+            .as_ref()
+            .expect("Services not initialized")
+            .pool
+    }
+
+    pub fn storage(&self) -> &ObjectStorage {
+        &self
+            ._services
+            .as_ref()
+            .expect("Services not initialized")
+            .storage
+    }
+
+    pub fn mailer(&self) -> &Mailer {
+        &self
+            ._services
+            .as_ref()
+            .expect("Services not initialized")
+            .mailer
+    }
+
+    /// Redis client, `None` when no Redis is configured
+    pub fn redis(&self) -> Option<&RedisClient> {
+        self._services
+            .as_ref()
+            .and_then(|services| services.redis.as_ref())
+    }
+
+    /// Event bus client, `None` when events are not published
+    pub fn event_bus(&self) -> Option<&EventBus> {
+        self._services
+            .as_ref()
+            .and_then(|services| services.event_bus.as_ref())
+    }
+}
+
+/// TOML and YAML files named `name` in `dir`
+fn files(dir: &Path, name: &str) -> Figment {
+    let [toml, yaml, yml] = file_paths(dir, name);
+    Figment::new()
+        .merge(Toml::file(toml))
+        .merge(Yaml::file(yaml))
+        .merge(Yaml::file(yml))
+}
+
+/// Paths of the files named `name` in `dir`, whether or not they exist
+fn file_paths(dir: &Path, name: &str) -> [PathBuf; 3] {
+    ["toml", "yaml", "yml"].map(|extension| dir.join(format!("{}.{}", name, extension)))
+}
+
+/// Environment variables, `SECTION__KEY` addressing section keys
+fn env() -> Env {
+    Env::raw().map(|key| env_key(key.as_str()).into())
+}
+
+/// Maps an environment variable name to its config key
+fn env_key(name: &str) -> String {
+    let key = name.to_ascii_lowercase();
+    LEGACY_ENV_KEYS
+        .iter()
+        .find(|(legacy, _)| *legacy == key)
+        .map_or_else(|| key.replace("__", "."), |(_, nested)| nested.to_string())
+}
+
+/// Reports keys whose values are malformed or conflict with the rest of
+/// the configuration
+fn invalid_keys(problems: Vec<InvalidKey>) -> ApiError {
+    let summary = problems
+        .iter()
+        .map(|p| format!("{}: {}", p.key, p.message))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let errors: Vec<serde_json::Value> = problems
+        .iter()
+        .map(|p| serde_json::json!({ "key": p.key, "message": p.message }))
+        .collect();
+
+    ApiError::new(
+        ErrorCode::ConfigurationError,
+        format!("Configuration error: {}", summary),
+        ErrorContext::new().with_details(serde_json::json!({ "errors": errors }))
+    )
+}
+
+/// One line per invalid key, for printing before logging is set up
+fn problem_list(error: &ApiError) -> String {
+    let errors = error
+        .context
+        .details
+        .as_ref()
+        .and_then(|details| details["errors"].as_array());
+    let Some(errors) = errors else {
+        return error.message.clone();
+    };
+    errors.iter().fold("Invalid configuration:".to_string(), |mut list, e| {
+        let key = e["key"].as_str().unwrap_or_default();
+        let message = e["message"].as_str().unwrap_or_default();
+        list.push_str(&format!("\n  - {}: {}", key, message));
+        if let Some(source) = e["source"].as_str() {
+            list.push_str(&format!(" (from {})", source));
+        }
+        list
+    })
+}
+
+/// Reports every invalid key with the source that set it
+fn config_error(error: figment::Error) -> ApiError {
+    let errors: Vec<serde_json::Value> = error
+        .into_iter()
+        .map(|e| {
+            serde_json::json!({
+                "key": e.path.join("."),
+                "source": e.metadata.as_ref().map(|m| m.name.to_string()),
+                "message": e.to_string(),
+            })
+        })
+        .collect();
+    let summary = errors
+        .iter()
+        .filter_map(|e| e["message"].as_str())
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    ApiError::new(
+        ErrorCode::ConfigurationError,
+        format!("Configuration error: {}", summary),
+        ErrorContext::new().with_details(serde_json::json!({ "errors": errors }))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::providers::Serialized;
+
+    fn config_dir(files: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("config-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in files {
+            std::fs::write(dir.join(name), contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_layered_sources() {
+        let dir = config_dir(&[
+            ("default.toml", "environment = \"staging\"\n[server]\nport = 9000\n[database]\nurl = \"postgres://default\"\npool_max_size = 20\n"),
+            ("staging.yaml", "server:\n  host: 10.0.0.1\ndatabase:\n  url: postgres://staging\n"),
+        ]);
+        let overrides = Serialized::defaults(serde_json::json!({ "database": { "pool_max_size": 40 } }));
+
+        let config = Config::from_sources(&dir, overrides).unwrap();
+        assert_eq!(config.environment, Environment::Staging);
+        assert_eq!(config.server.port, 9000);
+        assert_eq!(config.server.host, "10.0.0.1");
+        assert_eq!(config.server.keep_alive_secs, default_keep_alive_secs());
+        assert_eq!(config.database.url, "postgres://staging");
+        assert_eq!(config.database.pool_max_size, 40);
+        assert_eq!(config.storage.url, default_storage_url());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_error_names_key() {
+        let dir = config_dir(&[
+            ("default.toml", "[server]\nport = \"eighty\"\n[database]\nurl = \"postgres://default\"\n"),
+        ]);
+
+        let error = Config::from_sources(&dir, Serialized::defaults(serde_json::json!({}))).unwrap_err();
+        assert_eq!(error.code, ErrorCode::ConfigurationError);
+        assert!(error.message.contains("server.port"), "{}", error.message);
+        assert_eq!(error.context.details.as_ref().unwrap()["errors"][0]["key"], "server.port");
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_tls_requires_cert_and_key() {
+        let dir = config_dir(&[
+            ("default.toml", "[tls]\ncert_path = \"cert.pem\"\n[database]\nurl = \"postgres://default\"\n"),
+        ]);
+
+        let error = Config::from_sources(&dir, Serialized::defaults(serde_json::json!({}))).unwrap_err();
+        assert_eq!(error.context.details.as_ref().unwrap()["errors"][0]["key"], "tls");
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_mailbox_transport_only_in_development() {
+        let dir = config_dir(&[
+            ("default.toml", "environment = \"staging\"\n[email]\ntransport = \"mailbox\"\n[database]\nurl = \"postgres://default\"\n"),
+        ]);
+
+        let error = Config::from_sources(&dir, Serialized::defaults(serde_json::json!({}))).unwrap_err();
+        assert_eq!(error.context.details.as_ref().unwrap()["errors"][0]["key"], "email.transport");
+
+        let overrides = Serialized::defaults(serde_json::json!({ "environment": "development" }));
+        let config = Config::from_sources(&dir, overrides).unwrap();
+        assert_eq!(config.email.transport(), EmailTransport::Mailbox);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let dir = config_dir(&[
+            ("default.toml", "environment = \"production\"\n[database]\nurl = \"localhost:5432\"\n[storage]\nurl = \"s3://\"\n[queue]\nmax_attempts = 0\n"),
+        ]);
+
+        let error = Config::from_sources(&dir, Serialized::defaults(serde_json::json!({}))).unwrap_err();
+        let keys: Vec<&str> = error.context.details.as_ref().unwrap()["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|e| e["key"].as_str())
+            .collect();
+        assert_eq!(keys, ["jwt_secret", "database.url", "storage.url", "queue.max_attempts"]);
+        assert!(problem_list(&error).contains("\n  - database.url: must start with one of postgres://"));
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_cluster_mode_refuses_instance_local_backends_in_production() {
+        let files = "environment = \"production\"\njwt_secret = \"a-production-secret-of-at-least-32-bytes\"\n[database]\nurl = \"postgres://localhost/db\"\n[storage]\nurl = \"memory://\"\n";
+        let dir = config_dir(&[("default.toml", files)]);
+        let keys = |cluster: bool, environment: &str| {
+            let overrides = serde_json::json!({ "cluster": { "enabled": cluster }, "environment": environment });
+            Config::from_sources(&dir, Serialized::defaults(overrides)).err().map(|error| {
+                error.context.details.unwrap()["errors"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .filter_map(|e| e["key"].as_str().map(str::to_string))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(keys(true, "production").unwrap(), ["redis.url", "storage.url"]);
+        assert!(keys(false, "production").is_none());
+        assert!(keys(true, "staging").is_none());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_signing_keys_are_checked() {
+        let dir = config_dir(&[
+            ("default.toml", "[auth]\nsigning_key = \"2025-01\"\n[auth.jwt_keys.short]\nalgorithm = \"HS256\"\nsecret = \"too short\"\n[auth.jwt_keys.rsa]\nalgorithm = \"RS256\"\nprivate_key = \"not PEM\"\n[database]\nurl = \"postgres://default\"\n"),
+        ]);
+
+        let error = Config::from_sources(&dir, Serialized::defaults(serde_json::json!({}))).unwrap_err();
+        let keys: Vec<&str> = error.context.details.as_ref().unwrap()["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|e| e["key"].as_str())
+            .collect();
+        assert_eq!(keys, ["auth.jwt_keys.rsa", "auth.jwt_keys.short.secret", "auth.signing_key"]);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_env_key() {
+        assert_eq!(env_key("DATABASE_URL"), "database.url");
+        assert_eq!(env_key("SERVER__PORT"), "server.port");
+        assert_eq!(env_key("JWT_SECRET"), "jwt_secret");
+    }
+}
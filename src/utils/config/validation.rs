@@ -0,0 +1,442 @@
+//! Startup validation of the loaded configuration
+//!
+//! Every check runs, so a misconfigured deployment fails once with the full
+//! list of problems rather than one at a time, or later at first use.
+
+use std::str::FromStr;
+
+use actix_web::http::header::HeaderName;
+
+use super::{Config, DocsAuth, EmailTransport, EventTransport, JwtAlgorithm};
+use crate::{domain::auth::SigningKey, utils::defaults::default_jwt_secret};
+
+/// Shortest accepted `jwt_secret`, in bytes
+const MIN_JWT_SECRET_LEN: usize = 32;
+
+/// Longest a verified access token may be remembered
+const MAX_TOKEN_CACHE_TTL_SECS: u64 = 300;
+
+/// A key whose value is malformed or conflicts with the rest of the
+/// configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidKey {
+    pub key: String,
+    pub message: String,
+}
+
+/// Collects the problems found while checking a configuration
+#[derive(Default)]
+struct Problems(Vec<InvalidKey>);
+
+impl Problems {
+    fn add(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.0.push(InvalidKey {
+            key: key.into(),
+            message: message.into(),
+        });
+    }
+
+    fn check(&mut self, valid: bool, key: &str, message: &str) {
+        if !valid {
+            self.add(key, message);
+        }
+    }
+}
+
+/// Every problem with `config`, empty if it is valid
+pub fn validate(config: &Config) -> Vec<InvalidKey> {
+    let mut problems = Problems::default();
+
+    if let Some(dsn) = config.sentry_dsn.as_deref().filter(|dsn| !dsn.is_empty()) {
+        if let Err(e) = sentry::types::Dsn::from_str(dsn) {
+            problems.add("sentry_dsn", format!("is not a valid DSN: {}", e));
+        }
+    }
+    problems.check(
+        config.jwt_secret.len() >= MIN_JWT_SECRET_LEN,
+        "jwt_secret",
+        &format!("must be at least {} bytes", MIN_JWT_SECRET_LEN),
+    );
+    problems.check(
+        !config.environment.is_production() || config.jwt_secret != default_jwt_secret(),
+        "jwt_secret",
+        "must be changed from the development default in production",
+    );
+
+    if let Some(filter) = &config.live.log_filter {
+        if let Err(e) = tracing_subscriber::EnvFilter::try_new(filter) {
+            problems.add("live.log_filter", format!("is not a valid filter: {}", e));
+        }
+    }
+    problems.check(config.live.rate_limit.max_requests >= 1, "live.rate_limit.max_requests", "must be at least 1");
+    problems.check(config.live.rate_limit.window_secs >= 1, "live.rate_limit.window_secs", "must be at least 1");
+
+    // Server and TLS
+    problems.check(config.server.port != 0, "server.port", "must be between 1 and 65535");
+    problems.check(config.server.workers != Some(0), "server.workers", "must be at least 1");
+    problems.check(
+        config.tls.cert_path.is_some() == config.tls.key_path.is_some(),
+        "tls",
+        "cert_path and key_path must be set together",
+    );
+    if let Some(redirect_port) = config.tls.redirect_http_port {
+        problems.check(config.tls.files().is_some(), "tls.redirect_http_port", "requires cert_path and key_path");
+        problems.check(
+            redirect_port != 0 && redirect_port != config.server.port,
+            "tls.redirect_http_port",
+            "must be between 1 and 65535 and differ from server.port",
+        );
+    }
+
+    // Database and Redis
+    if let Err(message) = check_url(&config.database.url, &["postgres", "postgresql"]) {
+        problems.add("database.url", message);
+    }
+    problems.check(config.database.pool_max_size >= 1, "database.pool_max_size", "must be at least 1");
+    if let Some(min_idle) = config.database.pool_min_idle {
+        problems.check(
+            min_idle <= config.database.pool_max_size,
+            "database.pool_min_idle",
+            "must not exceed database.pool_max_size",
+        );
+    }
+    if let Some(url) = &config.redis.url {
+        if let Err(message) = check_url(url, &["redis", "rediss", "unix", "redis+unix"]) {
+            problems.add("redis.url", message);
+        }
+    }
+    problems.check(config.redis.pool_size >= 1, "redis.pool_size", "must be at least 1");
+
+    // Storage
+    match config.storage.url.split_once("://") {
+        Some(("s3", path)) => problems.check(
+            !path.split('/').next().unwrap_or_default().is_empty(),
+            "storage.url",
+            "must name a bucket, e.g. s3://bucket/prefix",
+        ),
+        Some(("file", path)) => problems.check(!path.is_empty(), "storage.url", "must name a directory"),
+        Some(("memory", _)) => {}
+        _ => problems.add("storage.url", "must be an s3://, file:// or memory:// URL"),
+    }
+    problems.check(config.storage.signed_url_ttl_secs > 0, "storage.signed_url_ttl_secs", "must be positive");
+
+    // Signing keys
+    for (id, key) in &config.auth.jwt_keys {
+        if let Err(message) = SigningKey::from_config(id, key) {
+            problems.add(format!("auth.jwt_keys.{}", id), message);
+        }
+        if key.algorithm == JwtAlgorithm::Hs256 {
+            problems.check(
+                key.secret.as_ref().is_none_or(|secret| secret.len() >= MIN_JWT_SECRET_LEN),
+                &format!("auth.jwt_keys.{}.secret", id),
+                &format!("must be at least {} bytes", MIN_JWT_SECRET_LEN),
+            );
+        }
+    }
+    if let Some(id) = &config.auth.signing_key {
+        problems.check(
+            config.auth.jwt_keys.contains_key(id),
+            "auth.signing_key",
+            "must name a key in auth.jwt_keys",
+        );
+    }
+
+    // Password hashing
+    let hashing = &config.auth.password_hash;
+    if let Err(e) = argon2::Params::new(hashing.memory_kib, hashing.iterations, hashing.parallelism, None) {
+        problems.add("auth.password_hash", format!("is not a valid Argon2 cost: {}", e));
+    }
+
+    // Logging out through another instance applies here once the cache
+    // lets go of the token
+    if config.auth.token_cache_ttl_secs > MAX_TOKEN_CACHE_TTL_SECS {
+        problems.add("auth.token_cache_ttl_secs", format!("must be at most {}", MAX_TOKEN_CACHE_TTL_SECS));
+    }
+
+    // Sessions
+    let session = &config.auth.session;
+    problems.check(session.access_token_minutes > 0, "auth.session.access_token_minutes", "must be positive");
+    problems.check(
+        session.idle_timeout_minutes >= session.access_token_minutes,
+        "auth.session.idle_timeout_minutes",
+        "must be at least auth.session.access_token_minutes, or sessions end before clients need to refresh",
+    );
+    problems.check(session.refresh_token_days > 0, "auth.session.refresh_token_days", "must be positive");
+
+    problems.check(
+        config.auth.magic_link.expiry_minutes > 0,
+        "auth.magic_link.expiry_minutes",
+        "must be positive",
+    );
+
+    // Login alerts
+    let alerts = &config.auth.login_alerts;
+    problems.check(alerts.link_days > 0, "auth.login_alerts.link_days", "must be positive");
+    for (key, header) in [
+        ("auth.login_alerts.country_header", &alerts.country_header),
+        ("auth.login_alerts.asn_header", &alerts.asn_header),
+    ] {
+        problems.check(
+            header.is_empty() || HeaderName::from_bytes(header.as_bytes()).is_ok(),
+            key,
+            "must be a valid header name, or empty",
+        );
+    }
+
+    // CAPTCHA
+    let captcha = &config.auth.captcha;
+    if captcha.enabled() {
+        problems.check(
+            captcha.secret.as_ref().is_some_and(|secret| !secret.is_empty()),
+            "auth.captcha.secret",
+            "is required when a provider is set",
+        );
+    }
+    if let Some(url) = &captcha.verify_url {
+        if let Err(message) = check_url(url, &["http", "https"]) {
+            problems.add("auth.captcha.verify_url", message);
+        }
+    }
+    problems.check(
+        captcha.login_after_failures >= 0,
+        "auth.captcha.login_after_failures",
+        "must not be negative",
+    );
+    problems.check(
+        captcha.failure_window_minutes > 0,
+        "auth.captcha.failure_window_minutes",
+        "must be positive",
+    );
+
+    // Passkeys
+    let webauthn = &config.auth.webauthn;
+    problems.check(!webauthn.rp_id.is_empty(), "auth.webauthn.rp_id", "must not be empty");
+    problems.check(!webauthn.origins.is_empty(), "auth.webauthn.origins", "must list at least one origin");
+    for origin in &webauthn.origins {
+        match check_url(origin, &["http", "https"]) {
+            Ok(()) if !origin_in_rp(origin, &webauthn.rp_id) => {
+                problems.add("auth.webauthn.origins", format!("{} is not on auth.webauthn.rp_id", origin));
+            }
+            Ok(()) => {}
+            Err(message) => problems.add("auth.webauthn.origins", message),
+        }
+    }
+
+    // Single sign-on
+    for (name, provider) in &config.auth.oidc {
+        let key = |field: &str| format!("auth.oidc.{}.{}", name, field);
+        if let Err(message) = check_url(&provider.issuer, &["https"]) {
+            problems.add(key("issuer"), message);
+        }
+        if let Err(message) = check_url(&provider.redirect_url, &["http", "https"]) {
+            problems.add(key("redirect_url"), message);
+        }
+        problems.check(!provider.client_id.is_empty(), &key("client_id"), "must not be empty");
+        problems.check(!provider.client_secret.is_empty(), &key("client_secret"), "must not be empty");
+    }
+
+    // Email
+    if let Err(e) = config.email.from.parse::<lettre::message::Mailbox>() {
+        problems.add("email.from", format!("is not a valid address: {}", e));
+    }
+    match config.email.transport() {
+        EmailTransport::Smtp => match &config.email.smtp_url {
+            None => problems.add("email.smtp_url", "is required for the smtp transport"),
+            Some(url) => {
+                if let Err(message) = check_url(url, &["smtp", "smtps"]) {
+                    problems.add("email.smtp_url", message);
+                }
+            }
+        },
+        EmailTransport::Ses => problems.check(
+            config.email.ses_region.is_some() || env_set("AWS_REGION"),
+            "email.ses_region",
+            "or AWS_REGION is required for the ses transport",
+        ),
+        EmailTransport::Mailbox => problems.check(
+            config.environment.is_development(),
+            "email.transport",
+            "mailbox is only available in development",
+        ),
+        EmailTransport::Log => {}
+    }
+    if let Err(message) = check_url(&config.email.app_url, &["http", "https"]) {
+        problems.add("email.app_url", message);
+    }
+
+    // Domain events
+    let event_schemes: &[&str] = match config.events.transport {
+        EventTransport::None => &[],
+        EventTransport::Nats => &["nats", "tls"],
+        EventTransport::Kafka => &["http", "https"],
+    };
+    if !event_schemes.is_empty() {
+        match &config.events.url {
+            None => problems.add("events.url", "is required to publish events"),
+            Some(url) => {
+                if let Err(message) = check_url(url, event_schemes) {
+                    problems.add("events.url", message);
+                }
+            }
+        }
+    }
+    problems.check(
+        !config.events.subject_prefix.is_empty()
+            && config.events.subject_prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'),
+        "events.subject_prefix",
+        "must be letters, digits, '-', '_' or '.'",
+    );
+    problems.check(config.events.timeout_ms >= 1, "events.timeout_ms", "must be at least 1");
+
+    // Machine telemetry over MQTT
+    let mqtt = &config.mqtt;
+    if let Some(url) = &mqtt.url {
+        if let Err(message) = check_url(url, &["mqtt", "mqtts"]) {
+            problems.add("mqtt.url", message);
+        }
+    }
+    if mqtt.enabled() {
+        problems.check(!mqtt.client_id.trim().is_empty(), "mqtt.client_id", "must not be empty");
+        let segment = |value: &str| !value.is_empty() && !value.contains(['+', '#', '/']);
+        problems.check(
+            !mqtt.topic_prefix.is_empty() && mqtt.topic_prefix.split('/').all(segment),
+            "mqtt.topic_prefix",
+            "must be topic levels separated by '/', without wildcards",
+        );
+        if let Some(group) = &mqtt.shared_group {
+            problems.check(segment(group), "mqtt.shared_group", "must be one topic level, without wildcards");
+        }
+        problems.check(mqtt.keep_alive_secs >= 5, "mqtt.keep_alive_secs", "must be at least 5");
+        problems.check(mqtt.max_backoff_secs >= 1, "mqtt.max_backoff_secs", "must be at least 1");
+    }
+
+    // Billing
+    let billing = &config.billing;
+    if billing.enabled() {
+        problems.check(
+            billing.stripe_webhook_secret.as_ref().is_some_and(|secret| !secret.is_empty()),
+            "billing.stripe_webhook_secret",
+            "is required when billing is enabled",
+        );
+    }
+    if let Err(message) = check_url(&billing.stripe_api_url, &["http", "https"]) {
+        problems.add("billing.stripe_api_url", message);
+    }
+    for plan in billing.prices.keys() {
+        problems.check(
+            crate::domain::billing::Plan::find(plan).is_some_and(|plan| plan.is_paid()),
+            &format!("billing.prices.{}", plan),
+            "is not a paid plan",
+        );
+    }
+
+    // Health checks
+    for (name, url) in &config.health.http_dependencies {
+        if let Err(message) = check_url(url, &["http", "https"]) {
+            problems.add(format!("health.http_dependencies.{}", name), message);
+        }
+    }
+
+    // API documentation
+    if config.docs.auth == DocsAuth::Basic {
+        problems.check(
+            config.docs.username.as_deref().is_some_and(|username| !username.is_empty() && !username.contains(':')),
+            "docs.username",
+            "is required for basic auth and must not contain ':'",
+        );
+        problems.check(
+            config.docs.password.as_deref().is_some_and(|password| !password.is_empty()),
+            "docs.password",
+            "is required for basic auth",
+        );
+    }
+
+    // Cluster mode
+    if config.cluster.enabled && config.environment.is_production() {
+        problems.0.extend(instance_local_backends(config));
+    }
+
+    // Background jobs
+    problems.check(config.queue.max_attempts >= 1, "queue.max_attempts", "must be at least 1");
+    problems.check(config.queue.concurrency >= 1, "queue.concurrency", "must be at least 1");
+    problems.check(
+        config.queue.retry_base_secs <= config.queue.retry_max_secs,
+        "queue.retry_base_secs",
+        "must not exceed queue.retry_max_secs",
+    );
+    for (job, expression) in &config.scheduler.jobs {
+        if let Err(e) = crate::jobs::scheduler::parse_schedule(expression) {
+            problems.add(format!("scheduler.jobs.{}", job), e.message);
+        }
+    }
+
+    problems.0
+}
+
+/// Backends keeping state in a single instance, which other instances of a
+/// cluster would not see
+pub fn instance_local_backends(config: &Config) -> Vec<InvalidKey> {
+    let mut problems = Problems::default();
+    problems.check(
+        config.redis.url.is_some(),
+        "redis.url",
+        "is required in cluster mode, rate limits, revoked tokens and admin changes are shared through Redis",
+    );
+    problems.check(
+        !config.storage.url.starts_with("memory://"),
+        "storage.url",
+        "memory:// keeps files in one instance, use s3:// or a shared file:// directory in cluster mode",
+    );
+    problems.check(
+        config.email.transport() != EmailTransport::Mailbox,
+        "email.transport",
+        "mailbox keeps mail in one instance, use smtp, ses or log in cluster mode",
+    );
+    problems.0
+}
+
+/// Checks `url` has one of `schemes` and something after it
+fn check_url(url: &str, schemes: &[&str]) -> Result<(), String> {
+    match url.split_once("://") {
+        Some((scheme, rest)) if schemes.contains(&scheme) && !rest.is_empty() => Ok(()),
+        _ => {
+            let prefixes: Vec<String> = schemes.iter().map(|scheme| format!("{}://", scheme)).collect();
+            Err(format!("must start with one of {}", prefixes.join(", ")))
+        }
+    }
+}
+
+/// Whether the host of `origin` is `rp_id` or a subdomain of it
+fn origin_in_rp(origin: &str, rp_id: &str) -> bool {
+    let host = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    let host = host.split(['/', ':']).next().unwrap_or_default();
+    host == rp_id || host.strip_suffix(rp_id).is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+fn env_set(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_url() {
+        assert!(check_url("postgres://localhost/db", &["postgres", "postgresql"]).is_ok());
+        assert!(check_url("postgresql://localhost/db", &["postgres", "postgresql"]).is_ok());
+        assert_eq!(
+            check_url("localhost:5432", &["postgres", "postgresql"]).unwrap_err(),
+            "must start with one of postgres://, postgresql://"
+        );
+        assert!(check_url("redis://", &["redis"]).is_err());
+    }
+    #[test]
+    fn test_origin_in_rp() {
+        assert!(origin_in_rp("http://localhost:3000", "localhost"));
+        assert!(origin_in_rp("https://app.example.com", "example.com"));
+        assert!(origin_in_rp("https://example.com/", "example.com"));
+        assert!(!origin_in_rp("https://badexample.com", "example.com"));
+        assert!(!origin_in_rp("https://example.com.evil.io", "example.com"));
+    }
+}
@@ -0,0 +1,16 @@
+//! Hashing of the bearer secrets only a hash of is stored
+//!
+//! Provisioning tokens, telemetry API keys and the tokens of mailed links
+//! are handed out once and stored as their hash, so a leaked database
+//! doesn't leak working credentials.
+
+use ring::digest::{digest, SHA256};
+
+/// The hash a token is stored and looked up by, hex-encoded SHA-256
+pub fn hash_token(token: &str) -> String {
+    digest(&SHA256, token.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
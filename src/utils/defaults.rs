@@ -1,4 +1,5 @@
 use super::environment::Environment;
+use std::collections::BTreeMap;
 
 pub fn default_environment() -> Environment {
     Environment::Development
@@ -12,6 +13,274 @@ pub fn default_port() -> u16 {
     8080
 }
 
+pub fn default_shutdown_timeout_secs() -> u64 {
+    30
+}
+
+pub fn default_keep_alive_secs() -> u64 {
+    5
+}
+
+pub fn default_client_request_timeout_ms() -> u64 {
+    5000
+}
+
+pub fn default_backlog() -> u32 {
+    2048
+}
+
+pub fn default_max_connections() -> usize {
+    25_000
+}
+
+pub fn default_tls_reload_interval_secs() -> u64 {
+    5 * 60
+}
+
 pub fn default_jwt_secret() -> String {
     "your-super-secret-key-for-development".to_string()
 }
+
+/// Argon2's recommended memory cost, 19 MiB
+pub fn default_password_memory_kib() -> u32 {
+    19 * 1024
+}
+
+pub fn default_password_iterations() -> u32 {
+    2
+}
+
+pub fn default_password_parallelism() -> u32 {
+    1
+}
+
+pub fn default_access_token_minutes() -> i64 {
+    60
+}
+
+pub fn default_session_idle_timeout_minutes() -> i64 {
+    7 * 24 * 60
+}
+
+pub fn default_refresh_token_days() -> i64 {
+    30
+}
+
+pub fn default_magic_link_expiry_minutes() -> i64 {
+    15
+}
+
+pub fn default_magic_link_bind_device() -> bool {
+    true
+}
+
+pub fn default_login_alerts_enabled() -> bool {
+    true
+}
+
+pub fn default_login_alerts_country_header() -> String {
+    "CF-IPCountry".to_string()
+}
+
+pub fn default_login_alerts_link_days() -> i64 {
+    7
+}
+
+pub fn default_captcha_login_after_failures() -> i64 {
+    3
+}
+
+pub fn default_captcha_failure_window_minutes() -> i64 {
+    15
+}
+
+pub fn default_token_cache_ttl_secs() -> u64 {
+    30
+}
+
+pub fn default_webauthn_rp_id() -> String {
+    "localhost".to_string()
+}
+
+pub fn default_webauthn_rp_name() -> String {
+    "Forestry Optimizer".to_string()
+}
+
+pub fn default_webauthn_origins() -> Vec<String> {
+    vec!["http://localhost:3000".to_string()]
+}
+
+pub fn default_redis_namespace() -> String {
+    "forestry".to_string()
+}
+
+pub fn default_redis_pool_size() -> usize {
+    4
+}
+
+pub fn default_redis_connect_timeout_ms() -> u64 {
+    1000
+}
+
+pub fn default_redis_response_timeout_ms() -> u64 {
+    500
+}
+
+pub fn default_storage_url() -> String {
+    "file://./data/storage".to_string()
+}
+
+pub fn default_signed_url_ttl_secs() -> u64 {
+    3600
+}
+
+pub fn default_archive_after_days() -> i64 {
+    365
+}
+
+pub fn default_purge_after_days() -> i64 {
+    90
+}
+
+pub fn default_config_dir() -> String {
+    "config".to_string()
+}
+
+pub fn default_pool_max_size() -> u32 {
+    10
+}
+
+pub fn default_pool_min_idle() -> Option<u32> {
+    Some(5)
+}
+
+pub fn default_pool_timeout_secs() -> u64 {
+    30
+}
+
+pub fn default_email_from() -> String {
+    "Forestry Optimizer <no-reply@forestry-optimizer.local>".to_string()
+}
+
+pub fn default_email_mailbox_capacity() -> usize {
+    100
+}
+
+pub fn default_email_app_url() -> String {
+    "http://localhost:3000".to_string()
+}
+
+pub fn default_events_subject_prefix() -> String {
+    "forestry".to_string()
+}
+
+pub fn default_events_timeout_ms() -> u64 {
+    5000
+}
+
+pub fn default_mqtt_client_id() -> String {
+    "forestry-telemetry".to_string()
+}
+
+pub fn default_mqtt_topic_prefix() -> String {
+    "telemetry".to_string()
+}
+
+pub fn default_mqtt_keep_alive_secs() -> u64 {
+    30
+}
+
+pub fn default_mqtt_max_backoff_secs() -> u64 {
+    60
+}
+
+pub fn default_optimization_timeout_secs() -> u64 {
+    5 * 60
+}
+
+pub fn default_optimization_max_concurrent_runs() -> usize {
+    4
+}
+
+pub fn default_erp_timeout_secs() -> u64 {
+    30
+}
+
+pub fn default_stripe_api_url() -> String {
+    "https://api.stripe.com".to_string()
+}
+
+pub fn default_scheduler_poll_interval_secs() -> u64 {
+    30
+}
+
+pub fn default_scheduler_lease_secs() -> u64 {
+    5 * 60
+}
+
+pub fn default_scheduler_jobs() -> BTreeMap<String, String> {
+    BTreeMap::from([
+        // Hourly, on the hour
+        ("archiver".to_string(), "0 0 * * * *".to_string()),
+        // Daily at 06:00 UTC, notifying supervisors of coming expiries
+        ("certification_expiry".to_string(), "0 0 6 * * *".to_string()),
+        // Every minute, starting the ERP exports that are due
+        ("erp_export".to_string(), "0 * * * * *".to_string()),
+        // Daily at 03:00 UTC
+        ("purger".to_string(), "0 0 3 * * *".to_string()),
+        // Every minute, delivering the report schedules that are due
+        ("report_delivery".to_string(), "0 * * * * *".to_string()),
+    ])
+}
+
+pub fn default_queue_concurrency() -> usize {
+    4
+}
+
+pub fn default_queue_poll_interval_ms() -> u64 {
+    1000
+}
+
+pub fn default_queue_visibility_timeout_secs() -> u64 {
+    5 * 60
+}
+
+pub fn default_queue_max_attempts() -> i32 {
+    5
+}
+
+pub fn default_queue_retry_base_secs() -> u64 {
+    10
+}
+
+pub fn default_queue_retry_max_secs() -> u64 {
+    60 * 60
+}
+
+pub fn default_live_watch_interval_secs() -> u64 {
+    10
+}
+
+pub fn default_rate_limit_max_requests() -> u32 {
+    100
+}
+
+pub fn default_rate_limit_window_secs() -> u32 {
+    60
+}
+
+pub fn default_maintenance_message() -> String {
+    "The service is down for maintenance, please try again later".to_string()
+}
+
+pub fn default_maintenance_retry_after_secs() -> u64 {
+    5 * 60
+}
+
+pub fn default_health_degraded_latency_ms() -> u64 {
+    1000
+}
+
+pub fn default_health_check_interval_secs() -> u64 {
+    30
+}
@@ -23,6 +23,10 @@ impl Environment {
     pub fn is_development(&self) -> bool {
         matches!(self, Environment::Development)
     }
+
+    pub fn is_production(&self) -> bool {
+        matches!(self, Environment::Production)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,148 @@
+//! Message catalogs for localized error messages
+//!
+//! Errors are authored in English. Validation errors carry a message key in
+//! their context (the `code` of their details, or an explicit key set with
+//! `ErrorContext::with_message_key`), which is looked up in the catalog of
+//! the locale negotiated from `Accept-Language` when the response is sent.
+//! Catalogs live in `locales/<code>.json` and map keys to templates whose
+//! `{name}` placeholders are filled from the error details.
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use once_cell::sync::Lazy;
+use serde_json::Value;
+
+/// Locales with a message catalog
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// Source language of all messages
+    #[default]
+    En,
+    Fr,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Fr];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Fr => "fr",
+        }
+    }
+
+    /// Picks the preferred supported locale from an `Accept-Language` header
+    pub fn negotiate(accept_language: &str) -> Locale {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // Stable sort keeps header order among equal weights
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges
+            .into_iter()
+            .find_map(|(tag, _)| {
+                let primary = tag.split('-').next().unwrap_or(tag);
+                primary.parse().ok()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Resolves the first key present in this locale's catalog
+    ///
+    /// Returns `None` if no key is translated or a template references a
+    /// parameter missing from `params`, in which case the English message
+    /// should be kept.
+    pub fn translate(&self, keys: &[&str], params: &Value) -> Option<String> {
+        let catalog = CATALOGS.get(self)?;
+        keys.iter()
+            .filter_map(|key| catalog.get(*key))
+            .find_map(|template| interpolate(template, params))
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
+
+static CATALOGS: Lazy<HashMap<Locale, HashMap<String, String>>> = Lazy::new(|| {
+    [(Locale::Fr, include_str!("../../locales/fr.json"))]
+        .into_iter()
+        .map(|(locale, source)| {
+            let catalog = serde_json::from_str(source)
+                .unwrap_or_else(|e| panic!("invalid message catalog for {}: {}", locale, e));
+            (locale, catalog)
+        })
+        .collect()
+});
+
+/// Fills `{name}` placeholders from the matching entries of `params`
+fn interpolate(template: &str, params: &Value) -> Option<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = start + rest[start..].find('}')?;
+        let value = match params.get(&rest[start + 1..end])? {
+            Value::String(value) => value.clone(),
+            Value::Null => return None,
+            value => value.to_string(),
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(Locale::negotiate("fr-CA,fr;q=0.9,en;q=0.8"), Locale::Fr);
+        assert_eq!(Locale::negotiate("de-DE,en;q=0.5,fr;q=0.7"), Locale::Fr);
+        assert_eq!(Locale::negotiate("en-US,fr;q=0.9"), Locale::En);
+        assert_eq!(Locale::negotiate("fr;q=0"), Locale::En);
+        assert_eq!(Locale::negotiate(""), Locale::En);
+    }
+
+    #[test]
+    fn test_translate() {
+        let params = json!({ "field": "password", "min_length": 8 });
+        assert_eq!(
+            Locale::Fr.translate(&["password.TOO_SHORT", "TOO_SHORT"], &params).as_deref(),
+            Some("Le mot de passe doit contenir au moins 8 caractères")
+        );
+        assert_eq!(
+            Locale::Fr.translate(&["unknown", "TOO_SHORT"], &params).as_deref(),
+            Some("Valeur trop courte pour le champ password")
+        );
+        // Missing parameters fall back to the English message
+        assert_eq!(Locale::Fr.translate(&["TOO_SHORT"], &json!({})), None);
+        assert_eq!(Locale::En.translate(&["TOO_SHORT"], &params), None);
+    }
+}
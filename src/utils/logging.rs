@@ -0,0 +1,155 @@
+//! Tracing subscriber setup
+//!
+//! Logs are written to stdout either as human-readable lines or as JSON
+//! records for log aggregation. In both formats every event carries the
+//! fields of its enclosing spans, so events logged while handling a request
+//! include the `request_id`, `org_id` and `user_id` recorded on the request
+//! span (see `api::middleware::RequestId`).
+//!
+//! The `EnvFilter` can be replaced at runtime through the admin API, e.g. to
+//! raise verbosity for one module during an incident without restarting the
+//! server. Reloading the configuration (see `utils::config::LiveConfig`)
+//! restores `live.log_filter`, or the filter the server started with.
+
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use std::fmt;
+use tracing::{info, Level};
+use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry};
+
+use super::{environment::Environment, Config};
+use crate::error::{ApiError, ErrorContext, Result};
+
+/// Handle to the installed filter and the directives it started with
+struct FilterControl {
+    handle: reload::Handle<EnvFilter, Registry>,
+    initial: String,
+}
+
+static FILTER: OnceCell<FilterControl> = OnceCell::new();
+
+/// Output format of log records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines for local development
+    Pretty,
+    /// One JSON object per line
+    Json,
+}
+
+impl LogFormat {
+    /// Format used when `LOG_FORMAT` is not set
+    pub fn default_for(environment: &Environment) -> Self {
+        match environment {
+            Environment::Production => LogFormat::Json,
+            Environment::Development | Environment::Staging => LogFormat::Pretty,
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Pretty => write!(f, "pretty"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Default filter directive when `RUST_LOG` is not set
+fn default_directive(environment: &Environment) -> &'static str {
+    match environment {
+        Environment::Development | Environment::Staging => "debug",
+        Environment::Production => "info",
+    }
+}
+
+/// Installs the global tracing subscriber
+pub fn init(config: &Config) {
+    let initial = std::env::var("RUST_LOG").unwrap_or_else(|_| default_directive(&config.environment).into());
+    let (filter, handle) = reload::Layer::new(EnvFilter::new(&initial));
+    let _ = FILTER.set(FilterControl { handle, initial });
+
+    let json = config.log_format() == LogFormat::Json;
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with((!json).then(tracing_subscriber::fmt::layer))
+        .with(json.then_some(JsonStorageLayer))
+        .with(json.then(|| BunyanFormattingLayer::new(crate::NAME.into(), std::io::stdout)))
+        .with(sentry::integrations::tracing::layer().event_filter(|metadata| {
+            // Log output becomes breadcrumbs; events are captured for 5xx responses and panics
+            match *metadata.level() {
+                Level::ERROR | Level::WARN | Level::INFO => sentry::integrations::tracing::EventFilter::Breadcrumb,
+                _ => sentry::integrations::tracing::EventFilter::Ignore,
+            }
+        }))
+        .init();
+}
+
+/// Returns the directives of the active filter
+pub fn filter() -> Result<String> {
+    control()?
+        .handle
+        .with_current(|filter| filter.to_string())
+        .map_err(|e| ApiError::configuration_error(format!("Log filter unavailable: {}", e)))
+}
+
+/// Replaces the active filter, e.g. with `info,rust_server::domain::optimization=debug`
+pub fn set_filter(directives: &str) -> Result<String> {
+    let parsed = parse_filter(directives)?;
+    control()?
+        .handle
+        .reload(parsed)
+        .map_err(|e| ApiError::configuration_error(format!("Failed to reload log filter: {}", e)))?;
+
+    let active = filter()?;
+    info!(filter = %active, "Log filter changed");
+    Ok(active)
+}
+
+/// Restores the filter the server started with, undoing runtime changes
+pub fn reset_filter() -> Result<String> {
+    let initial = control()?.initial.clone();
+    set_filter(&initial)
+}
+
+fn control() -> Result<&'static FilterControl> {
+    FILTER
+        .get()
+        .ok_or_else(|| ApiError::configuration_error("Logging is not initialized"))
+}
+
+fn parse_filter(directives: &str) -> Result<EnvFilter> {
+    EnvFilter::try_new(directives).map_err(|e| {
+        ApiError::validation_with_context(
+            "Invalid log filter",
+            ErrorContext::new().with_details(serde_json::json!({
+                "field": "filter",
+                "code": "INVALID_FORMAT",
+                "value": directives,
+                "reason": e.to_string()
+            }))
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_log_format() {
+        assert_eq!(LogFormat::default_for(&Environment::Production), LogFormat::Json);
+        assert_eq!(LogFormat::default_for(&Environment::Staging), LogFormat::Pretty);
+        assert_eq!(LogFormat::default_for(&Environment::Development), LogFormat::Pretty);
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        assert!(parse_filter("info,rust_server::domain::optimization=debug").is_ok());
+        assert!(parse_filter("rust_server=loud").is_err());
+    }
+}
@@ -1,6 +1,12 @@
 mod config;
+pub mod crypto;
 mod defaults;
 pub mod environment;
-mod sentry;
+pub mod i18n;
+pub mod logging;
+pub mod sentry;
 
-pub use self::config::Config;
+pub use self::config::{
+    AuthConfig, BillingConfig, CaptchaConfig, CaptchaProvider, Config, DocsAuth, EmailConfig, EmailTransport, ErpConfig, EventTransport, EventsConfig, JwtAlgorithm, JwtKeyConfig,
+    LiveSettings, LoginAlertConfig, MagicLinkConfig, MaintenanceSettings, MqttConfig, OidcProviderConfig, PasswordAlgorithm, PasswordHashConfig, QueueConfig, RateLimitSettings, RedisConfig, SchedulerConfig, SessionConfig, WebAuthnConfig,
+};